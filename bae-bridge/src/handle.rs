@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
@@ -7,6 +8,7 @@ use bae_core::cloud_home::CloudHome;
 use bae_core::config::Config;
 use bae_core::db::Database;
 use bae_core::encryption::EncryptionService;
+use bae_core::error_code::HasErrorCode;
 use bae_core::image_server::{self, ImageServerHandle};
 use bae_core::import::{ImportProgress, ImportService, ImportServiceHandle, ScanEvent};
 use bae_core::keys::{KeyService, UserKeypair};
@@ -14,7 +16,7 @@ use bae_core::library::SharedLibraryManager;
 use bae_core::library_dir::LibraryDir;
 use bae_core::playback::{PlaybackHandle, PlaybackProgress, PlaybackService, PlaybackState};
 use bae_core::sync::bucket::SyncBucketClient;
-use bae_core::sync::cloud_home_bucket::CloudHomeSyncBucket;
+use bae_core::sync::cloud_home_bucket::{image_key, CloudHomeSyncBucket, PLAIN_IMAGE_SUFFIX};
 use bae_core::sync::hlc::Hlc;
 use bae_core::sync::service::SyncService;
 use bae_core::sync::session::SyncSession;
@@ -94,9 +96,10 @@ pub fn discover_libraries() -> Result<Vec<BridgeLibraryInfo>, BridgeError> {
 #[uniffi::export]
 pub fn create_library(name: Option<String>) -> Result<BridgeLibraryInfo, BridgeError> {
     let dev_mode = Config::is_dev_mode();
-    let mut config = Config::create_new_library(dev_mode).map_err(|e| BridgeError::Config {
-        msg: format!("{e}"),
-    })?;
+    let mut config =
+        Config::create_new_library(dev_mode, None).map_err(|e| BridgeError::Config {
+            msg: format!("{e}"),
+        })?;
 
     if let Some(ref n) = name {
         config.library_name = Some(n.clone());
@@ -256,7 +259,7 @@ pub fn restore_from_cloud(
 
         info!("Restored DB ({} bytes)", decrypted_db.len());
 
-        // Download and decrypt images
+        // Download images, decrypting the encrypted ones
         let images_dir = library_dir.images_dir();
         tokio::fs::create_dir_all(&images_dir)
             .await
@@ -275,7 +278,8 @@ pub fn restore_from_cloud(
 
         for key in &image_keys {
             let rel = key.strip_prefix("images/").unwrap_or(key);
-            let target_path = images_dir.join(rel);
+            let plain_rel = rel.strip_suffix(PLAIN_IMAGE_SUFFIX);
+            let target_path = images_dir.join(plain_rel.unwrap_or(rel));
 
             if let Some(parent) = target_path.parent() {
                 tokio::fs::create_dir_all(parent)
@@ -293,12 +297,15 @@ pub fn restore_from_cloud(
                     .map_err(|e| BridgeError::Internal {
                         msg: format!("Failed to download image {key}: {e}"),
                     })?;
-            let decrypted_data =
+            let decrypted_data = if plain_rel.is_some() {
+                encrypted_data
+            } else {
                 encryption_service
                     .decrypt(&encrypted_data)
                     .map_err(|e| BridgeError::Internal {
                         msg: format!("Failed to decrypt image {key}: {e}"),
-                    })?;
+                    })?
+            };
             tokio::fs::write(&target_path, &decrypted_data)
                 .await
                 .map_err(|e| BridgeError::Internal {
@@ -317,6 +324,7 @@ pub fn restore_from_cloud(
             library_id: library_id.clone(),
             library_name: manifest.library_name.clone(),
             encryption_key_fingerprint: Some(fingerprint.clone()),
+            passphrase_kdf: manifest.passphrase_kdf.clone(),
         };
         let manifest_json =
             serde_json::to_string_pretty(&home_manifest).map_err(|e| BridgeError::Internal {
@@ -336,8 +344,12 @@ pub fn restore_from_cloud(
             library_name: manifest.library_name.clone(),
             keys_migrated: true,
             discogs_key_stored: false,
+            fanart_key_stored: false,
             encryption_key_stored: true,
             encryption_key_fingerprint: Some(fingerprint.clone()),
+            key_rotation_in_progress: false,
+            security_key: None,
+            encrypt_database: false,
             torrent_bind_interface: None,
             torrent_listen_port: None,
             torrent_enable_upnp: false,
@@ -353,20 +365,45 @@ pub fn restore_from_cloud(
             server_bind_address: "127.0.0.1".to_string(),
             server_auth_enabled: false,
             server_username: None,
+            server_snapshot_interval_hours: 24,
+            server_snapshot_retention_days: 30,
+            server_sync_interval_secs: 30,
             cloud_provider: None,
             cloud_home_s3_bucket: None,
             cloud_home_s3_region: None,
             cloud_home_s3_endpoint: None,
             cloud_home_s3_key_prefix: None,
+            cloud_home_s3_audio_storage_class: None,
             cloud_home_google_drive_folder_id: None,
             cloud_home_dropbox_folder_path: None,
             cloud_home_onedrive_drive_id: None,
             cloud_home_onedrive_folder_id: None,
             cloud_home_icloud_container_path: None,
+            cloud_home_folder_path: None,
             cloud_home_bae_cloud_url: None,
             cloud_home_bae_cloud_username: None,
             share_base_url: None,
             followed_libraries: vec![],
+            import_ignore_patterns: vec![],
+            import_min_audio_duration_secs: None,
+            import_network_share_url: None,
+            import_network_share_username: None,
+            download_parallelism: None,
+            readahead_mib: None,
+            encryption_chunk_size_kib: None,
+            encrypt_images: true,
+            offload_after_days: None,
+            cloud_prices: None,
+            export_name_template: None,
+            musicbrainz_username: None,
+            musicbrainz_collection_id: None,
+            pause_on_sleep: true,
+            pause_on_screen_lock: false,
+            pause_during_calls: true,
+            duck_for_other_audio: false,
+            auto_upgrade_covers: false,
+            library_sort: vec![],
+            library_filter: Default::default(),
         };
         config
             .save_to_config_yaml()
@@ -494,7 +531,8 @@ pub trait AppEventHandler: Send + Sync {
 ///
 /// Mirrors bae-desktop's SyncHandle but without Dioxus store dependencies.
 /// Holds everything needed to run sync cycles: bucket client, HLC, raw sqlite3
-/// pointer, active sync session, and a user keypair for signing changesets.
+/// pointer, active sync session, and the user and device keypairs for signing
+/// changesets.
 struct BridgeSyncHandle {
     bucket_client: Arc<CloudHomeSyncBucket>,
     hlc: Arc<Hlc>,
@@ -503,6 +541,7 @@ struct BridgeSyncHandle {
     raw_db: *mut libsqlite3_sys::sqlite3,
     session: tokio::sync::Mutex<Option<SyncSession>>,
     user_keypair: UserKeypair,
+    device_keypair: UserKeypair,
 }
 
 // SAFETY: The raw sqlite3 pointer is only used for session extension operations
@@ -1043,7 +1082,7 @@ impl AppHandle {
     pub fn scan_folder(&self, path: String) -> Result<(), BridgeError> {
         self.import_handle
             .enqueue_folder_scan(PathBuf::from(path))
-            .map_err(|e| BridgeError::Import { msg: e })
+            .map_err(|e| BridgeError::Import { msg: e.to_string() })
     }
 
     /// Search MusicBrainz for metadata matching a candidate.
@@ -1338,10 +1377,12 @@ impl AppHandle {
         use bae_core::import::folder_scanner::{collect_release_files, AudioContent};
         use std::path::Path;
 
-        let files =
-            collect_release_files(Path::new(&folder_path)).map_err(|e| BridgeError::Import {
+        let filters = self.import_handle.import_filters();
+        let files = collect_release_files(Path::new(&folder_path), &filters).map_err(|e| {
+            BridgeError::Import {
                 msg: format!("Failed to scan folder: {e}"),
-            })?;
+            }
+        })?;
 
         let audio = match files.audio {
             AudioContent::CueFlacPairs(pairs) => BridgeAudioContent::CueFlacPairs {
@@ -1443,6 +1484,7 @@ impl AppHandle {
                     folder,
                     master_year: 0,
                     managed,
+                    move_to_cloud: false,
                     selected_cover: cover,
                 }
             } else {
@@ -1468,6 +1510,7 @@ impl AppHandle {
                     folder,
                     master_year: 0,
                     managed,
+                    move_to_cloud: false,
                     selected_cover: cover,
                 }
             };
@@ -1475,7 +1518,7 @@ impl AppHandle {
             self.import_handle
                 .send_request(request)
                 .await
-                .map_err(|e| BridgeError::Import { msg: e })?;
+                .map_err(|e| BridgeError::Import { msg: e.to_string() })?;
             Ok(())
         })
     }
@@ -1728,8 +1771,11 @@ impl AppHandle {
 
         // Scan events
         let scan_rx = self.import_handle.subscribe_folder_scan_events();
-        self.runtime
-            .spawn(dispatch_scan_events(scan_rx, handler.clone()));
+        self.runtime.spawn(dispatch_scan_events(
+            scan_rx,
+            self.import_handle.clone(),
+            handler.clone(),
+        ));
 
         // Import progress events
         let import_rx = self.import_handle.subscribe_all_imports();
@@ -1962,12 +2008,24 @@ impl AppHandle {
             self.encryption_service.clone(),
             self.config.library_dir.clone(),
             self.key_service.clone(),
-            auth,
+            bae_core::subsonic::RouterSettings {
+                auth: Arc::new(bae_core::server_auth::ServerAuth::new(
+                    auth,
+                    self.key_service.clone(),
+                )),
+                playback: None,
+                sync: None,
+                reload: None,
+                maintenance: Arc::new(AtomicBool::new(false)),
+            },
+            None,
+            None,
         );
 
         if let Some(ref ch) = self.cloud_home {
             let cloud_state = Arc::new(bae_core::cloud_routes::CloudRouteState {
                 cloud_home: ch.clone(),
+                writes: None,
             });
             let cloud_router = bae_core::cloud_routes::create_cloud_router(cloud_state);
             app = app.merge(cloud_router);
@@ -1988,7 +2046,12 @@ impl AppHandle {
                     return;
                 }
             };
-            if let Err(e) = axum::serve(listener, app).await {
+            if let Err(e) = axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+            {
                 warn!("Subsonic server error: {}", e);
             }
         });
@@ -2117,7 +2180,8 @@ impl AppHandle {
 
             // Cover image key
             let cover_release_id = album.cover_release_id.as_deref().unwrap_or(&release_id);
-            let cover_image_key = find_cover_image_key(&db, cover_release_id).await;
+            let cover_image_key =
+                find_cover_image_key(&db, cloud_home.as_ref(), cover_release_id).await;
             if let Some(ref key) = cover_image_key {
                 manifest_files.push(key.clone());
             }
@@ -2361,6 +2425,7 @@ impl AppHandle {
         config.cloud_home_onedrive_drive_id = None;
         config.cloud_home_onedrive_folder_id = None;
         config.cloud_home_icloud_container_path = None;
+        config.cloud_home_folder_path = None;
         config.cloud_home_bae_cloud_url = None;
         config.cloud_home_bae_cloud_username = None;
 
@@ -2739,6 +2804,7 @@ impl AppHandle {
                     let role_str = match role {
                         bae_core::sync::membership::MemberRole::Owner => "owner".to_string(),
                         bae_core::sync::membership::MemberRole::Member => "member".to_string(),
+                        bae_core::sync::membership::MemberRole::ReadOnly => "read_only".to_string(),
                     };
                     let name = if user_pubkey.as_deref() == Some(&pubkey) {
                         Some("You".to_string())
@@ -2799,6 +2865,7 @@ impl AppHandle {
 
         let core_role = match role.as_str() {
             "owner" => CoreMemberRole::Owner,
+            "read_only" => CoreMemberRole::ReadOnly,
             _ => CoreMemberRole::Member,
         };
 
@@ -2834,6 +2901,8 @@ impl AppHandle {
                     timestamp: sync_handle.hlc.now().to_string(),
                     author_pubkey: String::new(),
                     signature: String::new(),
+                    device_id: None,
+                    device_pubkey: None,
                 };
 
                 sign_membership_entry(&mut founder, &sync_handle.user_keypair);
@@ -3037,6 +3106,9 @@ impl AppHandle {
         self.runtime.block_on(async {
             let db = self.library_manager.get().database();
             let result = run_single_sync_cycle(sync_handle, db, &self.config.library_dir).await?;
+            if result.changesets_applied > 0 {
+                self.library_manager.get().notify_albums_changed();
+            }
 
             // Notify event handler
             if let Some(handler) = self.event_handler.lock().unwrap().as_ref() {
@@ -3099,6 +3171,7 @@ impl AppHandle {
                                 Ok(result) => {
                                     handler.on_sync_status_changed(result.status);
                                     if result.changesets_applied > 0 {
+                                        library_manager.get().notify_albums_changed();
                                         handler.on_library_changed();
                                     }
                                 }
@@ -3164,7 +3237,7 @@ async fn dispatch_playback_events(
             PlaybackProgress::QueueUpdated { tracks } => {
                 handler.on_queue_updated(tracks);
             }
-            PlaybackProgress::PlaybackError { message } => {
+            PlaybackProgress::PlaybackError { message, .. } => {
                 handler.on_error(message);
             }
             // Other events (Seeked, SeekError, TrackCompleted, etc.) don't need
@@ -3177,6 +3250,7 @@ async fn dispatch_playback_events(
 /// Background task that reads folder scan events and forwards candidates to Swift.
 async fn dispatch_scan_events(
     mut rx: tokio::sync::broadcast::Receiver<ScanEvent>,
+    import_handle: ImportServiceHandle,
     handler: Arc<dyn AppEventHandler>,
 ) {
     use bae_core::import::folder_scanner::AudioContent;
@@ -3200,8 +3274,11 @@ async fn dispatch_scan_events(
                     AudioContent::TrackFiles(tracks) => tracks.iter().map(|t| t.size).sum(),
                 };
 
-                let folder_contents =
-                    bae_core::import::detect_folder_contents(candidate.path.clone()).ok();
+                let folder_contents = bae_core::import::detect_folder_contents(
+                    candidate.path.clone(),
+                    &import_handle.import_filters(),
+                )
+                .ok();
                 let metadata = folder_contents.as_ref().map(|fc| &fc.metadata);
 
                 handler.on_scan_result(BridgeImportCandidate {
@@ -3311,8 +3388,13 @@ async fn dispatch_import_events(
             } => {
                 let folder = id_map.lock().unwrap().remove(&iid);
                 if let Some(folder) = folder {
-                    handler
-                        .on_import_progress(folder, BridgeImportStatus::Error { message: error });
+                    handler.on_import_progress(
+                        folder,
+                        BridgeImportStatus::Error {
+                            code: error.code().to_string(),
+                            message: error.to_string(),
+                        },
+                    );
                 }
             }
             _ => {}
@@ -3466,6 +3548,7 @@ async fn write_cover_and_update_db(
         height: None,
         source: source.to_string(),
         source_url,
+        blurhash: bae_core::image_placeholder::blurhash_blocking(bytes.to_vec()).await,
         updated_at: chrono::Utc::now(),
         created_at: chrono::Utc::now(),
     };
@@ -3503,14 +3586,23 @@ fn parse_duration_to_ms(duration: &str) -> Option<u64> {
     }
 }
 
-/// Find the S3 key for a release's cover image.
-async fn find_cover_image_key(db: &Database, release_id: &str) -> Option<String> {
+/// Find the S3 key for a release's cover image, unencrypted if it was
+/// uploaded that way.
+async fn find_cover_image_key(
+    db: &Database,
+    cloud_home: &dyn CloudHome,
+    release_id: &str,
+) -> Option<String> {
     let image = db
         .get_library_image(release_id, &bae_core::db::LibraryImageType::Cover)
         .await
         .ok()??;
-    let hex = image.id.replace('-', "");
-    Some(format!("images/{}/{}/{}", &hex[..2], &hex[2..4], &image.id))
+    let plain_key = image_key(&image.id, false);
+    if cloud_home.exists(&plain_key).await.unwrap_or(false) {
+        Some(plain_key)
+    } else {
+        Some(image_key(&image.id, true))
+    }
 }
 
 /// Initialize the app with a specific library.
@@ -3574,6 +3666,9 @@ pub fn init_app(library_id: String) -> Result<Arc<AppHandle>, BridgeError> {
     } else {
         None
     };
+    // Sync keeps the default format so older devices can read changesets
+    let sync_encryption = encryption_service.clone();
+    let encryption_service = encryption_service.map(|e| config.file_encryption(e));
 
     // Create library manager
     let library_manager =
@@ -3589,6 +3684,12 @@ pub fn init_app(library_id: String) -> Result<Arc<AppHandle>, BridgeError> {
         key_service.clone(),
         config.library_dir.clone(),
     );
+    import_handle.set_import_filters(config.import_filters());
+    match runtime.block_on(import_handle.resume_interrupted_imports()) {
+        Ok(0) => {}
+        Ok(n) => info!("Resumed {} interrupted import(s)", n),
+        Err(e) => warn!("Failed to resume interrupted imports: {}", e),
+    }
 
     // Start playback service (needs an owned LibraryManager clone)
     let playback_handle = PlaybackService::start(
@@ -3619,7 +3720,7 @@ pub fn init_app(library_id: String) -> Result<Arc<AppHandle>, BridgeError> {
 
     // Initialize sync infrastructure if sync is configured and encryption is enabled
     let sync_handle = if config.sync_enabled(&key_service) {
-        if let Some(ref enc) = encryption_service {
+        if let Some(ref enc) = sync_encryption {
             runtime.block_on(create_sync_handle(&config, &key_service, &database, enc))
         } else {
             info!("Sync is configured but encryption is not enabled, skipping sync initialization");
@@ -3750,7 +3851,8 @@ async fn create_sync_handle(
         }
     };
 
-    let bucket_client = CloudHomeSyncBucket::new(Box::new(cloud_home), encryption.clone());
+    let bucket_client = CloudHomeSyncBucket::new(Box::new(cloud_home), encryption.clone())
+        .with_image_encryption(config.encrypt_images);
     let encryption_lock = bucket_client.shared_encryption();
 
     let raw_db = match database.raw_write_handle().await {
@@ -3777,6 +3879,14 @@ async fn create_sync_handle(
         }
     };
 
+    let device_keypair = match key_service.get_or_create_device_keypair() {
+        Ok(kp) => kp,
+        Err(e) => {
+            warn!("Failed to get/create device keypair for sync: {e}");
+            return None;
+        }
+    };
+
     let hlc = Hlc::new(config.device_id.clone());
 
     info!(
@@ -3792,6 +3902,7 @@ async fn create_sync_handle(
         raw_db,
         session: tokio::sync::Mutex::new(Some(session)),
         user_keypair,
+        device_keypair,
     }))
 }
 
@@ -3944,6 +4055,28 @@ async fn run_single_sync_cycle(
             msg: format!("Failed to load sync cursors: {e}"),
         })?;
 
+    // Without the chain we can't tell revoked devices apart, so don't pull.
+    let mut membership_chain = bae_core::sync::invite::load_membership_chain(bucket)
+        .await
+        .map_err(|e| BridgeError::Internal {
+            msg: format!("Failed to load membership chain: {e}"),
+        })?;
+
+    if let Some(chain) = membership_chain.as_mut() {
+        if let Err(e) = bae_core::sync::invite::ensure_device_enrolled(
+            bucket,
+            chain,
+            &sync_handle.user_keypair,
+            device_id,
+            &sync_handle.device_keypair,
+            &hlc.now().to_string(),
+        )
+        .await
+        {
+            warn!("Failed to enroll this device's signing key: {e}");
+        }
+    }
+
     // Take the current session
     let session = match sync_handle.session.lock().await.take() {
         Some(s) => s,
@@ -3971,7 +4104,8 @@ async fn run_single_sync_cycle(
                 &timestamp,
                 "background sync",
                 &sync_handle.user_keypair,
-                None,
+                &sync_handle.device_keypair,
+                membership_chain.as_ref(),
                 library_dir,
             )
             .await
@@ -4094,7 +4228,7 @@ async fn run_single_sync_cycle(
                 )
                 .await
                 {
-                    Ok(()) => {
+                    Ok(_) => {
                         let _ = db
                             .set_sync_state("snapshot_seq", &local_seq.to_string())
                             .await;
@@ -4149,6 +4283,8 @@ fn cloud_provider_to_string(p: &bae_core::config::CloudProvider) -> String {
         bae_core::config::CloudProvider::Dropbox => "dropbox".to_string(),
         bae_core::config::CloudProvider::OneDrive => "onedrive".to_string(),
         bae_core::config::CloudProvider::BaeCloud => "bae_cloud".to_string(),
+        bae_core::config::CloudProvider::Lan => "lan".to_string(),
+        bae_core::config::CloudProvider::Folder => "folder".to_string(),
     }
 }
 
@@ -4157,6 +4293,8 @@ fn cloud_account_display_for(config: &Config, key_service: &KeyService) -> Optio
     match config.cloud_provider.as_ref()? {
         bae_core::config::CloudProvider::BaeCloud => config.cloud_home_bae_cloud_username.clone(),
         bae_core::config::CloudProvider::ICloud => Some("iCloud Drive".to_string()),
+        bae_core::config::CloudProvider::Lan => Some("Local network".to_string()),
+        bae_core::config::CloudProvider::Folder => config.cloud_home_folder_path.clone(),
         bae_core::config::CloudProvider::S3 => config
             .cloud_home_s3_bucket
             .as_ref()
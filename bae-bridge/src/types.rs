@@ -162,9 +162,15 @@ pub struct BridgeCandidateFiles {
 
 #[derive(Debug, Clone, uniffi::Enum)]
 pub enum BridgeImportStatus {
-    Importing { progress_percent: u32 },
+    Importing {
+        progress_percent: u32,
+    },
     Complete,
-    Error { message: String },
+    /// `code` is a stable `ErrorCode` string such as "import.invalid_files"
+    Error {
+        code: String,
+        message: String,
+    },
 }
 
 #[derive(Debug, Clone, uniffi::Record)]
@@ -239,7 +245,7 @@ pub struct BridgeSyncStatus {
 #[derive(Debug, Clone, uniffi::Record)]
 pub struct BridgeMember {
     pub pubkey: String,
-    /// "owner" or "member"
+    /// "owner", "member" or "read_only"
     pub role: String,
     pub added_by: Option<String>,
     pub name: Option<String>,
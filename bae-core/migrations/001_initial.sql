@@ -46,6 +46,8 @@ CREATE TABLE album_artists (
     album_id TEXT NOT NULL,
     artist_id TEXT NOT NULL,
     position INTEGER NOT NULL,
+    -- Text between this artist and the next in the credit (" & ", " feat. ")
+    join_phrase TEXT,
     _updated_at TEXT NOT NULL,
     created_at TEXT NOT NULL,
     FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE,
@@ -53,6 +55,16 @@ CREATE TABLE album_artists (
     UNIQUE(album_id, artist_id)
 );
 
+CREATE TABLE album_versions (
+    id TEXT PRIMARY KEY,
+    album_id TEXT NOT NULL UNIQUE,
+    version_group_id TEXT NOT NULL,
+    label TEXT,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE
+);
+
 CREATE TABLE releases (
     id TEXT PRIMARY KEY,
     album_id TEXT NOT NULL,
@@ -70,6 +82,7 @@ CREATE TABLE releases (
     managed_in_cloud BOOLEAN NOT NULL DEFAULT FALSE,
     unmanaged_path TEXT,
     private BOOLEAN NOT NULL DEFAULT 0,
+    unverified BOOLEAN NOT NULL DEFAULT 0,
     _updated_at TEXT NOT NULL,
     created_at TEXT NOT NULL,
     FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE,
@@ -97,12 +110,164 @@ CREATE TABLE track_artists (
     artist_id TEXT NOT NULL,
     position INTEGER NOT NULL,
     role TEXT,
+    join_phrase TEXT,
     _updated_at TEXT NOT NULL,
     created_at TEXT NOT NULL,
     FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE,
     FOREIGN KEY (artist_id) REFERENCES artists (id) ON DELETE CASCADE
 );
 
+CREATE TABLE track_stats (
+    id TEXT PRIMARY KEY,
+    track_id TEXT NOT NULL UNIQUE,
+    play_count INTEGER NOT NULL DEFAULT 0,
+    rating INTEGER,
+    last_played_at TEXT,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE
+);
+
+-- Lyrics fetched for a track. `content` is the lyrics JSON, encrypted with the
+-- release key when the library is encrypted. A lookup that found nothing is
+-- stored too, so it isn't repeated.
+CREATE TABLE track_lyrics (
+    id TEXT PRIMARY KEY,
+    track_id TEXT NOT NULL UNIQUE,
+    content BLOB NOT NULL,
+    source TEXT NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE
+);
+
+-- The work a track performs, from MusicBrainz. A movement is stored under the
+-- larger work it belongs to, so the album page can group it with the other
+-- movements; a standalone work has no movement.
+CREATE TABLE track_works (
+    id TEXT PRIMARY KEY,
+    track_id TEXT NOT NULL UNIQUE,
+    work_title TEXT NOT NULL,
+    musicbrainz_work_id TEXT,
+    movement_title TEXT,
+    movement_number INTEGER,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE
+);
+
+-- Disc subtitles of box sets and multi-disc releases, from MusicBrainz medium
+-- titles. Discs without a title have no row.
+CREATE TABLE release_discs (
+    id TEXT PRIMARY KEY,
+    release_id TEXT NOT NULL,
+    disc_number INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    UNIQUE(release_id, disc_number),
+    FOREIGN KEY (release_id) REFERENCES releases (id) ON DELETE CASCADE
+);
+
+-- Genres are keyed by their normalized name (lowercase, separators collapsed)
+-- so devices that import the same genre converge on one row.
+CREATE TABLE genres (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE album_genres (
+    id TEXT PRIMARY KEY,
+    album_id TEXT NOT NULL,
+    genre_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE,
+    FOREIGN KEY (genre_id) REFERENCES genres (id) ON DELETE CASCADE,
+    UNIQUE(album_id, genre_id)
+);
+
+-- Tags the user puts on an album ("needs re-rip", "source: vinyl rip"). A tag
+-- without a value is a plain label.
+CREATE TABLE album_custom_tags (
+    id TEXT PRIMARY KEY,
+    album_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT,
+    position INTEGER NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE,
+    UNIQUE(album_id, key)
+);
+
+-- Freeform notes on an album. A row of its own rather than a column on
+-- `albums`, so a note edited on one device and a title fixed on another don't
+-- touch the same row.
+CREATE TABLE album_notes (
+    id TEXT PRIMARY KEY,
+    album_id TEXT NOT NULL UNIQUE,
+    content TEXT NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE
+);
+
+-- Short notes members of a shared library leave on an album for each other.
+-- Unlike `album_notes` there can be many per album, each tagged with the key of
+-- the member who wrote it.
+CREATE TABLE album_comments (
+    id TEXT PRIMARY KEY,
+    album_id TEXT NOT NULL,
+    author_pubkey TEXT NOT NULL,
+    content TEXT NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE
+);
+
+CREATE TABLE playlists (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE playlist_tracks (
+    id TEXT PRIMARY KEY,
+    playlist_id TEXT NOT NULL,
+    track_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE,
+    FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE,
+    UNIQUE(playlist_id, position)
+);
+
+-- Named sets of albums the user keeps ("also on vinyl", "favorites of the
+-- year"). An album can be in any number of collections.
+CREATE TABLE collections (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE collection_albums (
+    id TEXT PRIMARY KEY,
+    collection_id TEXT NOT NULL,
+    album_id TEXT NOT NULL,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (collection_id) REFERENCES collections (id) ON DELETE CASCADE,
+    FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE,
+    UNIQUE(collection_id, album_id)
+);
+
 CREATE TABLE release_files (
     id TEXT PRIMARY KEY,
     release_id TEXT NOT NULL,
@@ -110,11 +275,28 @@ CREATE TABLE release_files (
     file_size INTEGER NOT NULL,
     content_type TEXT NOT NULL,
     encryption_nonce BLOB,
+    -- SHA-256 of the file as imported, before encryption. Lets the duplicate
+    -- finder spot the same audio imported twice.
+    content_sha256 TEXT,
     _updated_at TEXT NOT NULL,
     created_at TEXT NOT NULL,
     FOREIGN KEY (release_id) REFERENCES releases (id) ON DELETE CASCADE
 );
 
+-- Which devices hold a release's files, as last reported by each device.
+-- Each device only writes its own rows; cloud copies are tracked on releases.
+CREATE TABLE release_availability (
+    id TEXT PRIMARY KEY,
+    release_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    has_files BOOLEAN NOT NULL DEFAULT FALSE,
+    cached_files INTEGER NOT NULL DEFAULT 0,
+    _updated_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (release_id) REFERENCES releases (id) ON DELETE CASCADE,
+    UNIQUE(release_id, device_id)
+);
+
 CREATE TABLE audio_formats (
     id TEXT PRIMARY KEY,
     track_id TEXT NOT NULL UNIQUE,
@@ -131,6 +313,8 @@ CREATE TABLE audio_formats (
     seektable_json TEXT NOT NULL,
     audio_data_start INTEGER NOT NULL,
     file_id TEXT REFERENCES release_files(id) ON DELETE SET NULL,
+    cue_in_ms INTEGER,
+    cue_out_ms INTEGER,
     _updated_at TEXT NOT NULL,
     created_at TEXT NOT NULL,
     FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE
@@ -170,6 +354,7 @@ CREATE TABLE library_images (
     height INTEGER,
     source TEXT NOT NULL,
     source_url TEXT,
+    blurhash TEXT,
     _updated_at TEXT NOT NULL,
     created_at TEXT NOT NULL
 );
@@ -181,22 +366,101 @@ CREATE TABLE imports (
     album_title TEXT NOT NULL,
     artist_name TEXT NOT NULL,
     folder_path TEXT NOT NULL,
+    managed BOOLEAN NOT NULL DEFAULT 0,
+    -- Move the stored files to the cloud home once the import completes
+    move_to_cloud BOOLEAN NOT NULL DEFAULT 0,
+    cover_image_path TEXT,
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
     error_message TEXT
 );
 
+-- Per-file progress of a folder import, so an interrupted import can resume
+CREATE TABLE import_files (
+    import_id TEXT NOT NULL REFERENCES imports(id) ON DELETE CASCADE,
+    source_path TEXT NOT NULL,
+    stored BOOLEAN NOT NULL DEFAULT 0,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (import_id, source_path)
+);
+
+-- When each artist was last looked up on the artist image sources, so the
+-- background fetch doesn't ask again for artists the sources don't have
+CREATE TABLE artist_image_lookups (
+    artist_id TEXT PRIMARY KEY,
+    checked_at INTEGER NOT NULL
+);
+
+-- When each release's cover was last looked up for a higher-resolution
+-- version, so the background upgrade job doesn't ask the sources every run
+CREATE TABLE cover_upgrade_lookups (
+    release_id TEXT PRIMARY KEY,
+    checked_at INTEGER NOT NULL
+);
+
+-- Release storage transfers that were started and haven't finished, so they
+-- resume after a restart. target is 'local', 'cloud' or 'eject'. Local,
+-- never synced.
+CREATE TABLE storage_transfers (
+    release_id TEXT PRIMARY KEY,
+    target TEXT NOT NULL,
+    eject_path TEXT,
+    started_at INTEGER NOT NULL
+);
+
+-- When the background storage scrub last read back each release's files, and
+-- how many of the files it read were broken. Local, never synced.
+CREATE TABLE storage_scrubs (
+    release_id TEXT PRIMARY KEY,
+    verified_at INTEGER NOT NULL,
+    broken_files INTEGER NOT NULL
+);
+
+-- What this device sent to and read from the cloud home each calendar month
+-- (YYYY-MM), for estimating what a metered provider charges. Local, never
+-- synced.
+CREATE TABLE cloud_usage (
+    month TEXT PRIMARY KEY,
+    bytes_uploaded INTEGER NOT NULL,
+    bytes_downloaded INTEGER NOT NULL,
+    requests INTEGER NOT NULL
+);
+
+-- Releases this device keeps downloaded in its cache for playing offline.
+-- Local, never synced.
+CREATE TABLE offline_pins (
+    release_id TEXT PRIMARY KEY,
+    pinned_at INTEGER NOT NULL
+);
+
+-- MusicBrainz release IDs this device has added to the linked account's
+-- collection, so releases deleted from the library can be taken out again.
+-- Local, never synced.
+CREATE TABLE musicbrainz_collection_releases (
+    collection_id TEXT NOT NULL,
+    musicbrainz_release_id TEXT NOT NULL,
+    PRIMARY KEY (collection_id, musicbrainz_release_id)
+);
+
 -- Indexes
 CREATE INDEX idx_artists_discogs_id ON artists (discogs_artist_id);
 CREATE INDEX idx_artists_mb_id ON artists (musicbrainz_artist_id);
 CREATE INDEX idx_artists_name ON artists (name COLLATE NOCASE);
 CREATE INDEX idx_album_artists_album_id ON album_artists (album_id);
 CREATE INDEX idx_album_artists_artist_id ON album_artists (artist_id);
+CREATE INDEX idx_album_versions_group_id ON album_versions (version_group_id);
 CREATE INDEX idx_track_artists_track_id ON track_artists (track_id);
 CREATE INDEX idx_track_artists_artist_id ON track_artists (artist_id);
 CREATE INDEX idx_releases_album_id ON releases (album_id);
 CREATE INDEX idx_tracks_release_id ON tracks (release_id);
+CREATE INDEX idx_album_genres_genre_id ON album_genres (genre_id);
+CREATE INDEX idx_albums_year ON albums (year);
+CREATE INDEX idx_album_comments_album_id ON album_comments (album_id);
+CREATE INDEX idx_playlist_tracks_playlist_id ON playlist_tracks (playlist_id);
+CREATE INDEX idx_playlist_tracks_track_id ON playlist_tracks (track_id);
+CREATE INDEX idx_collection_albums_album_id ON collection_albums (album_id);
 CREATE INDEX idx_release_files_release_id ON release_files (release_id);
+CREATE INDEX idx_release_availability_device_id ON release_availability (device_id);
 CREATE INDEX idx_torrents_release_id ON torrents (release_id);
 CREATE INDEX idx_torrents_info_hash ON torrents (info_hash);
 CREATE INDEX idx_torrent_piece_mappings_torrent_id ON torrent_piece_mappings (torrent_id);
@@ -215,6 +479,18 @@ CREATE TABLE sync_state (
     value TEXT NOT NULL
 );
 
+-- Concurrent edits to the same row on two devices, kept until the user picks
+-- the values to keep. Local to this device, never synced.
+CREATE TABLE sync_conflicts (
+    id TEXT PRIMARY KEY,
+    table_name TEXT NOT NULL,
+    row_id TEXT NOT NULL,
+    fields TEXT NOT NULL,
+    kept_remote BOOLEAN NOT NULL,
+    detected_at TEXT NOT NULL,
+    UNIQUE (table_name, row_id)
+);
+
 CREATE TABLE attribution_names (
     pubkey_hex TEXT PRIMARY KEY,
     display_name TEXT NOT NULL
@@ -237,3 +513,24 @@ CREATE INDEX idx_attestations_infohash ON attestations(infohash);
 CREATE INDEX idx_attestations_content_hash ON attestations(content_hash);
 CREATE UNIQUE INDEX idx_attestations_unique ON attestations (mbid, infohash, author_pubkey);
 CREATE INDEX idx_attestations_author_pubkey ON attestations (author_pubkey);
+
+-- Per-client Subsonic credentials. Local to this device, never synced.
+-- Disabled ones are refused at sign-in but kept, so they can be turned back on.
+CREATE TABLE app_passwords (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    password TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    last_used_at TEXT,
+    disabled INTEGER NOT NULL DEFAULT 0
+);
+
+-- Tracks played on this device, newest last. Local, never synced; pruned on insert.
+CREATE TABLE play_history (
+    id TEXT PRIMARY KEY,
+    track_id TEXT NOT NULL,
+    played_at TEXT NOT NULL,
+    FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_play_history_played_at ON play_history (played_at);
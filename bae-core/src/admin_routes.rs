@@ -0,0 +1,459 @@
+//! Server admin API
+//!
+//! Routes under `/admin` let the owner of a headless server manage it from a
+//! browser instead of over SSH: the app passwords clients sign in with (the
+//! server's users), the devices it syncs with, a sync on demand, maintenance
+//! mode, a reload of its settings and a health summary. They need a web UI
+//! session, so they're unavailable while server sign-in is off.
+
+use crate::db::DbAppPassword;
+use crate::library::SharedLibraryManager;
+use crate::server_auth::{error_response, require_session, ServerAuth};
+use crate::server_metrics::server_metrics;
+use crate::subsonic::generate_app_password;
+use crate::sync::cloud_home_bucket::CloudHomeSyncBucket;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+/// Handle on the sync loop, for servers that sync
+pub struct SyncControl {
+    pub device_id: String,
+    /// Asks the sync loop to run a cycle now
+    pub trigger: mpsc::Sender<()>,
+    /// The bucket it syncs through, for health checks
+    pub bucket: Arc<CloudHomeSyncBucket>,
+}
+
+struct AdminState {
+    auth: Arc<ServerAuth>,
+    library_manager: SharedLibraryManager,
+    sync: Option<SyncControl>,
+    /// Set in maintenance mode
+    maintenance: Arc<AtomicBool>,
+    /// Asks the server to reload its settings, where it can
+    reload: Option<mpsc::Sender<()>>,
+    started: Instant,
+}
+
+#[derive(Serialize)]
+struct Health {
+    version: &'static str,
+    uptime_secs: u64,
+    albums: usize,
+    local_bytes: u64,
+    cloud_bytes: u64,
+    sync_enabled: bool,
+    maintenance: bool,
+    /// RFC 3339; None until a sync cycle succeeds after startup
+    last_sync: Option<String>,
+    sync_failures: u64,
+    s3_errors: u64,
+}
+
+#[derive(Serialize)]
+struct User {
+    id: String,
+    name: String,
+    created_at: String,
+    last_used_at: Option<String>,
+    disabled: bool,
+}
+
+/// A new user with its password, which is only ever shown here
+#[derive(Serialize)]
+struct CreatedUser {
+    id: String,
+    name: String,
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct CreateUserRequest {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct MaintenanceRequest {
+    enabled: bool,
+}
+
+#[derive(Serialize)]
+struct Device {
+    device_id: String,
+    /// Last changeset applied from the device, or pushed by this one
+    last_seq: u64,
+    this_device: bool,
+}
+
+/// Admin routes, checked against the web UI sessions in `auth`
+pub fn create_admin_router(
+    auth: Arc<ServerAuth>,
+    library_manager: SharedLibraryManager,
+    sync: Option<SyncControl>,
+    maintenance: Arc<AtomicBool>,
+    reload: Option<mpsc::Sender<()>>,
+) -> Router {
+    let state = Arc::new(AdminState {
+        auth,
+        library_manager,
+        sync,
+        maintenance,
+        reload,
+        started: Instant::now(),
+    });
+    Router::new()
+        .route("/admin/health", get(get_health))
+        .route("/admin/users", get(list_users).post(create_user))
+        .route("/admin/users/:id", delete(delete_user))
+        .route("/admin/users/:id/disable", post(disable_user))
+        .route("/admin/users/:id/enable", post(enable_user))
+        .route("/admin/devices", get(list_devices))
+        .route("/admin/sync", post(force_sync))
+        .route("/admin/maintenance", post(set_maintenance))
+        .route("/admin/reload", post(reload_settings))
+        .with_state(state)
+}
+
+async fn get_health(State(state): State<Arc<AdminState>>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_session(&state.auth, &headers) {
+        return response;
+    }
+
+    let library_manager = state.library_manager.get();
+    let albums = match library_manager.get_albums(&[]).await {
+        Ok(albums) => albums.len(),
+        Err(e) => return internal_error("Failed to count albums", e),
+    };
+    let (local_bytes, cloud_bytes) = match library_manager.get_stored_bytes().await {
+        Ok(bytes) => bytes,
+        Err(e) => return internal_error("Failed to measure storage", e),
+    };
+    let metrics = server_metrics();
+    let last_sync = metrics
+        .last_sync_success()
+        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        .map(|t| t.to_rfc3339());
+
+    Json(Health {
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_secs: state.started.elapsed().as_secs(),
+        albums,
+        local_bytes,
+        cloud_bytes,
+        sync_enabled: state.sync.is_some(),
+        maintenance: state.maintenance.load(Ordering::Relaxed),
+        last_sync,
+        sync_failures: metrics.sync_failures(),
+        s3_errors: metrics.s3_errors(),
+    })
+    .into_response()
+}
+
+async fn list_users(State(state): State<Arc<AdminState>>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_session(&state.auth, &headers) {
+        return response;
+    }
+
+    match state.library_manager.database().get_app_passwords().await {
+        Ok(app_passwords) => {
+            let users: Vec<User> = app_passwords
+                .into_iter()
+                .map(|p| User {
+                    id: p.id,
+                    name: p.name,
+                    created_at: p.created_at.to_rfc3339(),
+                    last_used_at: p.last_used_at.map(|t| t.to_rfc3339()),
+                    disabled: p.disabled,
+                })
+                .collect();
+            Json(users).into_response()
+        }
+        Err(e) => internal_error("Failed to load users", e),
+    }
+}
+
+async fn create_user(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Json(request): Json<CreateUserRequest>,
+) -> Response {
+    if let Err(response) = require_session(&state.auth, &headers) {
+        return response;
+    }
+    let name = request.name.trim();
+    if name.is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "Enter a name", false);
+    }
+
+    let app_password = DbAppPassword {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        password: generate_app_password(),
+        created_at: chrono::Utc::now(),
+        last_used_at: None,
+        disabled: false,
+    };
+    if let Err(e) = state
+        .library_manager
+        .database()
+        .insert_app_password(&app_password)
+        .await
+    {
+        return internal_error("Failed to create user", e);
+    }
+
+    info!("Admin created user {}", app_password.name);
+
+    Json(CreatedUser {
+        id: app_password.id,
+        name: app_password.name,
+        password: app_password.password,
+    })
+    .into_response()
+}
+
+async fn disable_user(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    set_disabled(&state, &headers, &id, true).await
+}
+
+async fn enable_user(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    set_disabled(&state, &headers, &id, false).await
+}
+
+async fn set_disabled(
+    state: &AdminState,
+    headers: &HeaderMap,
+    id: &str,
+    disabled: bool,
+) -> Response {
+    if let Err(response) = require_session(&state.auth, headers) {
+        return response;
+    }
+
+    match state
+        .library_manager
+        .database()
+        .set_app_password_disabled(id, disabled)
+        .await
+    {
+        Ok(()) => {
+            info!(
+                "Admin {} user {}",
+                if disabled { "disabled" } else { "enabled" },
+                id
+            );
+
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => internal_error("Failed to update user", e),
+    }
+}
+
+async fn delete_user(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(response) = require_session(&state.auth, &headers) {
+        return response;
+    }
+
+    match state
+        .library_manager
+        .database()
+        .delete_app_password(&id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => internal_error("Failed to delete user", e),
+    }
+}
+
+async fn list_devices(State(state): State<Arc<AdminState>>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_session(&state.auth, &headers) {
+        return response;
+    }
+    let Some(sync) = &state.sync else {
+        return Json(Vec::<Device>::new()).into_response();
+    };
+
+    let database = state.library_manager.database();
+    let cursors = match database.get_all_sync_cursors().await {
+        Ok(cursors) => cursors,
+        Err(e) => return internal_error("Failed to load devices", e),
+    };
+    let local_seq = match database.get_sync_state("local_seq").await {
+        Ok(seq) => seq.and_then(|v| v.parse().ok()).unwrap_or(0),
+        Err(e) => return internal_error("Failed to load devices", e),
+    };
+
+    let mut devices = vec![Device {
+        device_id: sync.device_id.clone(),
+        last_seq: local_seq,
+        this_device: true,
+    }];
+    let mut others: Vec<Device> = cursors
+        .into_iter()
+        .filter(|(device_id, _)| *device_id != sync.device_id)
+        .map(|(device_id, last_seq)| Device {
+            device_id,
+            last_seq,
+            this_device: false,
+        })
+        .collect();
+    others.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+    devices.extend(others);
+    Json(devices).into_response()
+}
+
+async fn force_sync(State(state): State<Arc<AdminState>>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_session(&state.auth, &headers) {
+        return response;
+    }
+    let Some(sync) = &state.sync else {
+        return error_response(
+            StatusCode::CONFLICT,
+            "Sync isn't set up on this server",
+            false,
+        );
+    };
+    if state.maintenance.load(Ordering::Relaxed) {
+        return error_response(
+            StatusCode::CONFLICT,
+            "Sync is paused while the server is in maintenance mode",
+            false,
+        );
+    }
+
+    // A full channel means a cycle is already queued
+    let _ = sync.trigger.try_send(());
+
+    info!("Admin requested a sync");
+
+    StatusCode::ACCEPTED.into_response()
+}
+
+async fn set_maintenance(
+    State(state): State<Arc<AdminState>>,
+    headers: HeaderMap,
+    Json(request): Json<MaintenanceRequest>,
+) -> Response {
+    if let Err(response) = require_session(&state.auth, &headers) {
+        return response;
+    }
+
+    state.maintenance.store(request.enabled, Ordering::Relaxed);
+    if request.enabled {
+        info!("Admin turned on maintenance mode, pausing sync and cloud writes");
+    } else {
+        // Catch up on what was pushed while paused
+        if let Some(sync) = &state.sync {
+            let _ = sync.trigger.try_send(());
+        }
+
+        info!("Admin turned off maintenance mode");
+    }
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+async fn reload_settings(State(state): State<Arc<AdminState>>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_session(&state.auth, &headers) {
+        return response;
+    }
+    let Some(reload) = &state.reload else {
+        return error_response(
+            StatusCode::CONFLICT,
+            "This server can't reload its settings",
+            false,
+        );
+    };
+
+    // A full channel means a reload is already queued
+    let _ = reload.try_send(());
+
+    info!("Admin requested a settings reload");
+
+    StatusCode::ACCEPTED.into_response()
+}
+
+fn internal_error(message: &str, e: impl std::fmt::Display) -> Response {
+    error!("{message}: {e}");
+
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, message, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloud_home::folder::FolderCloudHome;
+    use crate::encryption::EncryptionService;
+    use crate::keys::KeyService;
+    use crate::library::LibraryManager;
+    use crate::subsonic::SubsonicAuth;
+
+    #[tokio::test]
+    async fn leaving_maintenance_mode_triggers_a_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = crate::test_support::test_database(dir.path()).await;
+        let auth = Arc::new(ServerAuth::new(
+            SubsonicAuth {
+                enabled: true,
+                username: Some("admin".to_string()),
+                password: Some("secret".to_string()),
+            },
+            KeyService::new(true, "test".to_string()),
+        ));
+        let (trigger, mut triggered) = mpsc::channel(1);
+        let bucket = CloudHomeSyncBucket::new(
+            Box::new(FolderCloudHome::new(dir.path().to_path_buf())),
+            EncryptionService::new_with_key(&[7; 32]),
+        );
+        let state = Arc::new(AdminState {
+            auth: auth.clone(),
+            library_manager: SharedLibraryManager::new(LibraryManager::new(database, None)),
+            sync: Some(SyncControl {
+                device_id: "dev1".to_string(),
+                trigger,
+                bucket: Arc::new(bucket),
+            }),
+            maintenance: Arc::new(AtomicBool::new(false)),
+            reload: None,
+            started: Instant::now(),
+        });
+        let set = |enabled| {
+            set_maintenance(
+                State(state.clone()),
+                auth.test_session_headers(),
+                Json(MaintenanceRequest { enabled }),
+            )
+        };
+
+        assert_eq!(set(true).await.status(), StatusCode::NO_CONTENT);
+        assert!(state.maintenance.load(Ordering::Relaxed));
+        assert!(triggered.try_recv().is_err());
+
+        assert_eq!(set(false).await.status(), StatusCode::NO_CONTENT);
+        assert!(!state.maintenance.load(Ordering::Relaxed));
+        assert!(triggered.try_recv().is_ok());
+    }
+}
@@ -1,6 +1,6 @@
 //! Unified audio codec module using FFmpeg.
 //!
-//! Provides decoding (any format to PCM), encoding (PCM to FLAC), and
+//! Provides decoding (any format to PCM), encoding (PCM to FLAC or AAC), and
 //! seektable generation. Uses custom AVIO for in-memory decoding.
 
 use crate::playback::{SharedSparseBuffer, StreamingPcmSink};
@@ -934,6 +934,290 @@ unsafe fn encode_to_flac_avio(
     Ok(result)
 }
 
+/// Encode PCM samples to AAC, in an ADTS stream at `bit_rate` bits per second.
+///
+/// Takes interleaved i32 samples as returned by `decode_audio`. Every ADTS
+/// frame is self-contained, so the stream can be cut at any frame boundary.
+pub fn encode_to_aac(
+    samples: &[i32],
+    sample_rate: u32,
+    channels: u32,
+    bits_per_sample: u32,
+    bit_rate: u32,
+) -> Result<Vec<u8>, String> {
+    unsafe { encode_to_aac_avio(samples, sample_rate, channels, bits_per_sample, bit_rate) }
+}
+
+/// Internal AVIO-based AAC encoding implementation
+unsafe fn encode_to_aac_avio(
+    samples: &[i32],
+    sample_rate: u32,
+    channels: u32,
+    bits_per_sample: u32,
+    bit_rate: u32,
+) -> Result<Vec<u8>, String> {
+    use ffmpeg_sys_next::*;
+
+    let mut write_ctx = Box::new(WriteAvioContext {
+        data: Vec::new(),
+        pos: 0,
+    });
+
+    let avio_buffer_size = 32768;
+    let avio_buffer = av_malloc(avio_buffer_size) as *mut u8;
+    if avio_buffer.is_null() {
+        return Err("Failed to allocate AVIO buffer".to_string());
+    }
+
+    let avio = avio_alloc_context(
+        avio_buffer,
+        avio_buffer_size as c_int,
+        1, // write flag
+        write_ctx.as_mut() as *mut WriteAvioContext as *mut c_void,
+        None, // no read
+        Some(avio_write_callback),
+        Some(avio_write_seek_callback),
+    );
+    if avio.is_null() {
+        av_free(avio_buffer as *mut c_void);
+        return Err("Failed to create AVIO context".to_string());
+    }
+
+    let codec = avcodec_find_encoder(AVCodecID::AV_CODEC_ID_AAC);
+    if codec.is_null() {
+        avio_context_free(&mut (avio as *mut _));
+        return Err("AAC encoder not found".to_string());
+    }
+
+    let codec_ctx = avcodec_alloc_context3(codec);
+    if codec_ctx.is_null() {
+        avio_context_free(&mut (avio as *mut _));
+        return Err("Failed to allocate codec context".to_string());
+    }
+
+    // FFmpeg's native AAC encoder only takes planar float
+    (*codec_ctx).sample_rate = sample_rate as c_int;
+    (*codec_ctx).time_base = AVRational {
+        num: 1,
+        den: sample_rate as c_int,
+    };
+    (*codec_ctx).sample_fmt = AVSampleFormat::AV_SAMPLE_FMT_FLTP;
+    (*codec_ctx).bit_rate = bit_rate as i64;
+
+    let mut ch_layout: AVChannelLayout = std::mem::zeroed();
+    av_channel_layout_default(&mut ch_layout, channels as c_int);
+    (*codec_ctx).ch_layout = ch_layout;
+
+    let ret = avcodec_open2(codec_ctx, codec, ptr::null_mut());
+    if ret < 0 {
+        avcodec_free_context(&mut (codec_ctx as *mut _));
+        avio_context_free(&mut (avio as *mut _));
+        return Err(format!("Failed to open encoder: {}", av_err_str(ret)));
+    }
+
+    let mut fmt_ctx: *mut AVFormatContext = ptr::null_mut();
+    let ret =
+        avformat_alloc_output_context2(&mut fmt_ctx, ptr::null(), c"adts".as_ptr(), ptr::null());
+    if ret < 0 || fmt_ctx.is_null() {
+        avcodec_free_context(&mut (codec_ctx as *mut _));
+        avio_context_free(&mut (avio as *mut _));
+        return Err("Failed to create output context".to_string());
+    }
+
+    (*fmt_ctx).pb = avio;
+    (*fmt_ctx).flags |= AVFMT_FLAG_CUSTOM_IO as c_int;
+
+    let stream = avformat_new_stream(fmt_ctx, ptr::null());
+    if stream.is_null() {
+        avformat_free_context(fmt_ctx);
+        avcodec_free_context(&mut (codec_ctx as *mut _));
+        return Err("Failed to create stream".to_string());
+    }
+
+    let ret = avcodec_parameters_from_context((*stream).codecpar, codec_ctx);
+    if ret < 0 {
+        avformat_free_context(fmt_ctx);
+        avcodec_free_context(&mut (codec_ctx as *mut _));
+        return Err(format!("Failed to copy codec params: {}", av_err_str(ret)));
+    }
+
+    let ret = avformat_write_header(fmt_ctx, ptr::null_mut());
+    if ret < 0 {
+        avformat_free_context(fmt_ctx);
+        avcodec_free_context(&mut (codec_ctx as *mut _));
+        return Err(format!("Failed to write header: {}", av_err_str(ret)));
+    }
+
+    let frame = av_frame_alloc();
+    let packet = av_packet_alloc();
+    if frame.is_null() || packet.is_null() {
+        av_packet_free(&mut (packet as *mut _));
+        av_frame_free(&mut (frame as *mut _));
+        avformat_free_context(fmt_ctx);
+        avcodec_free_context(&mut (codec_ctx as *mut _));
+        return Err("Failed to allocate frame".to_string());
+    }
+
+    let result = write_aac_frames(
+        codec_ctx,
+        fmt_ctx,
+        stream,
+        frame,
+        packet,
+        samples,
+        channels as usize,
+        bits_per_sample,
+    );
+    if result.is_ok() {
+        av_write_trailer(fmt_ctx);
+        avio_flush(avio);
+    }
+
+    // Cleanup (don't free avio - avformat_free_context handles it when CUSTOM_IO is set)
+    av_packet_free(&mut (packet as *mut _));
+    av_frame_free(&mut (frame as *mut _));
+    avcodec_free_context(&mut (codec_ctx as *mut _));
+    avformat_free_context(fmt_ctx);
+    result?;
+
+    let encoded = write_ctx.data[..write_ctx.pos].to_vec();
+    debug!("Encoded {} bytes of AAC data", encoded.len());
+
+    Ok(encoded)
+}
+
+/// Feed interleaved samples to an opened AAC encoder one frame at a time,
+/// then flush it
+#[allow(clippy::too_many_arguments)]
+unsafe fn write_aac_frames(
+    codec_ctx: *mut ffmpeg_sys_next::AVCodecContext,
+    fmt_ctx: *mut ffmpeg_sys_next::AVFormatContext,
+    stream: *mut ffmpeg_sys_next::AVStream,
+    frame: *mut ffmpeg_sys_next::AVFrame,
+    packet: *mut ffmpeg_sys_next::AVPacket,
+    samples: &[i32],
+    channels: usize,
+    bits_per_sample: u32,
+) -> Result<(), String> {
+    use ffmpeg_sys_next::*;
+
+    // `decode_audio` keeps 8- and 16-bit sources in the 16-bit range, and
+    // 32-bit and float sources in the full i32 range
+    let scale = match bits_per_sample {
+        0..=16 => 32768.0,
+        24 => 8388608.0,
+        _ => 2147483648.0,
+    };
+
+    let frame_size = if (*codec_ctx).frame_size > 0 {
+        (*codec_ctx).frame_size as usize
+    } else {
+        1024
+    };
+    (*frame).format = AVSampleFormat::AV_SAMPLE_FMT_FLTP as c_int;
+    (*frame).ch_layout = (*codec_ctx).ch_layout;
+    (*frame).sample_rate = (*codec_ctx).sample_rate;
+    (*frame).nb_samples = frame_size as c_int;
+    let ret = av_frame_get_buffer(frame, 0);
+    if ret < 0 {
+        return Err(format!(
+            "Failed to allocate frame buffer: {}",
+            av_err_str(ret)
+        ));
+    }
+
+    let mut pts: i64 = 0;
+    for chunk in samples.chunks(frame_size * channels) {
+        let ret = av_frame_make_writable(frame);
+        if ret < 0 {
+            return Err(format!(
+                "Failed to make frame writable: {}",
+                av_err_str(ret)
+            ));
+        }
+
+        // Deinterleave into one plane per channel
+        let chunk_frames = chunk.len() / channels;
+        (*frame).nb_samples = chunk_frames as c_int;
+        for ch in 0..channels {
+            let plane = *(*frame).extended_data.add(ch) as *mut f32;
+            for i in 0..chunk_frames {
+                *plane.add(i) = chunk[i * channels + ch] as f32 / scale;
+            }
+        }
+
+        (*frame).pts = pts;
+        pts += chunk_frames as i64;
+        write_aac_packets(codec_ctx, fmt_ctx, stream, frame, packet)?;
+    }
+
+    write_aac_packets(codec_ctx, fmt_ctx, stream, ptr::null(), packet)
+}
+
+/// Send one frame to the encoder (null to flush) and write whatever packets
+/// come out
+unsafe fn write_aac_packets(
+    codec_ctx: *mut ffmpeg_sys_next::AVCodecContext,
+    fmt_ctx: *mut ffmpeg_sys_next::AVFormatContext,
+    stream: *mut ffmpeg_sys_next::AVStream,
+    frame: *const ffmpeg_sys_next::AVFrame,
+    packet: *mut ffmpeg_sys_next::AVPacket,
+) -> Result<(), String> {
+    use ffmpeg_sys_next::*;
+
+    let ret = avcodec_send_frame(codec_ctx, frame);
+    if ret < 0 {
+        return Err(format!("Failed to send frame: {}", av_err_str(ret)));
+    }
+    loop {
+        let ret = avcodec_receive_packet(codec_ctx, packet);
+        if ret == AVERROR(EAGAIN) || ret == AVERROR_EOF {
+            return Ok(());
+        }
+        if ret < 0 {
+            return Err(format!("Failed to receive packet: {}", av_err_str(ret)));
+        }
+
+        (*packet).stream_index = 0;
+        av_packet_rescale_ts(packet, (*codec_ctx).time_base, (*stream).time_base);
+        let ret = av_interleaved_write_frame(fmt_ctx, packet);
+        if ret < 0 {
+            return Err(format!("Failed to write packet: {}", av_err_str(ret)));
+        }
+    }
+}
+
+/// Check a FLAC file's audio against the MD5 in its STREAMINFO block.
+///
+/// Decodes the whole file. Returns None when the encoder left the MD5 unset,
+/// otherwise whether the decoded audio matches it.
+pub fn verify_flac_md5(flac_data: &[u8]) -> Result<Option<bool>, String> {
+    use md5::Digest;
+
+    // fLaC, then the STREAMINFO block header and its 34 bytes
+    if flac_data.len() < 42 || &flac_data[0..4] != b"fLaC" || flac_data[4] & 0x7F != 0 {
+        return Err("Invalid FLAC signature".to_string());
+    }
+    let streaminfo = &flac_data[8..42];
+    let expected = &streaminfo[18..34];
+    if expected.iter().all(|b| *b == 0) {
+        return Ok(None);
+    }
+    let bits_per_sample =
+        ((((streaminfo[12] & 0x01) as u32) << 4) | (streaminfo[13] >> 4) as u32) + 1;
+
+    // The MD5 covers the samples at their own bit depth, little-endian.
+    // FFmpeg hands them back shifted up to its 16 or 32-bit sample format.
+    let decoded = decode_audio(flac_data, None, None)?;
+    let shift = decoded.bits_per_sample.saturating_sub(bits_per_sample);
+    let bytes_per_sample = bits_per_sample.div_ceil(8) as usize;
+    let mut hasher = md5::Md5::new();
+    for sample in &decoded.samples {
+        hasher.update(&(sample >> shift).to_le_bytes()[..bytes_per_sample]);
+    }
+    Ok(Some(hasher.finalize().as_slice() == expected))
+}
+
 /// Build a frame-accurate seektable by scanning FLAC frames.
 ///
 /// This scans the FLAC byte stream for frame sync codes (0xFF 0xF8/0xF9),
@@ -1671,6 +1955,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_verify_flac_md5() {
+        init();
+
+        let samples: Vec<i32> = (0..44100 * 2)
+            .map(|i| ((i as f64 * 0.05).sin() * 20000.0) as i32)
+            .collect();
+        let mut flac_data = encode_to_flac(&samples, 44100, 2, 16).unwrap();
+        assert_eq!(verify_flac_md5(&flac_data), Ok(Some(true)));
+
+        // A damaged checksum no longer matches the audio
+        flac_data[8 + 18] ^= 0xFF;
+        assert_eq!(verify_flac_md5(&flac_data), Ok(Some(false)));
+
+        // An unset checksum can't be checked
+        flac_data[8 + 18..8 + 34].fill(0);
+        assert_eq!(verify_flac_md5(&flac_data), Ok(None));
+    }
+
     /// Test that FLAC encode/decode is lossless - samples should match exactly.
     ///
     /// This catches any sample conversion bugs: wrong byte order, wrong scaling,
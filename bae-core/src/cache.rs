@@ -1,5 +1,5 @@
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::fs;
@@ -31,6 +31,40 @@ impl Default for CacheConfig {
         }
     }
 }
+impl CacheConfig {
+    /// Tracks the server transcoded, kept apart from downloaded files
+    pub fn transcodes() -> Self {
+        let default = CacheConfig::default();
+        CacheConfig {
+            cache_dir: default.cache_dir.join("transcodes"),
+            max_size_bytes: 2 * 1024 * 1024 * 1024,
+            ..default
+        }
+    }
+
+    /// Cover art the server scaled down for clients
+    pub fn thumbnails() -> Self {
+        let default = CacheConfig::default();
+        CacheConfig {
+            cache_dir: default.cache_dir.join("thumbnails"),
+            max_size_bytes: 256 * 1024 * 1024,
+            ..default
+        }
+    }
+}
+/// Cache key for a release file downloaded from cloud storage
+pub fn file_cache_key(file_id: &str) -> String {
+    format!("file:{}", file_id)
+}
+/// Cache key for a track transcoded to `codec` at `bit_rate` kbps
+pub fn transcode_cache_key(track_id: &str, codec: &str, bit_rate: u32) -> String {
+    format!("transcode:{}:{}:{}", track_id, codec, bit_rate)
+}
+/// Cache key for the cover of `release_id` at `size` pixels; `version` is the
+/// cover's update time, so a replaced cover is scaled again
+pub fn thumbnail_cache_key(release_id: &str, version: i64, size: u32) -> String {
+    format!("thumbnail:{}:{}:{}", release_id, version, size)
+}
 /// Metadata about a cached file
 #[derive(Debug, Clone)]
 struct CacheEntry {
@@ -69,6 +103,14 @@ impl CacheManager {
             pinned: Arc::new(RwLock::new(HashSet::new())),
         };
         cache_manager.load_existing_cache().await?;
+        // Workspaces left by an earlier run hold nothing a release points to
+        if let Err(e) = cache_manager
+            .remove_import_workspace(&cache_manager.imports_dir())
+            .await
+        {
+            warn!("Failed to remove old import workspaces: {}", e);
+        }
+
         Ok(cache_manager)
     }
 
@@ -122,6 +164,48 @@ impl CacheManager {
         Ok(())
     }
 
+    /// Whether a key is cached, without counting as an access
+    pub async fn contains(&self, key: &str) -> bool {
+        self.entries.read().await.contains_key(key)
+    }
+
+    /// Size of a cached file, without counting as an access
+    pub async fn cached_size(&self, key: &str) -> Option<u64> {
+        self.entries.read().await.get(key).map(|e| e.size_bytes)
+    }
+
+    /// Create an empty scratch directory for extracting an import.
+    ///
+    /// Workspaces sit under the cache directory but outside the LRU index, so
+    /// eviction never touches them. Releases imported from one are always
+    /// managed, so the workspace is removed once its imports finish; any left
+    /// over from an earlier run are removed on startup.
+    pub async fn create_import_workspace(&self) -> Result<PathBuf, CacheError> {
+        let workspace = self.imports_dir().join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&workspace).await?;
+        Ok(workspace)
+    }
+
+    /// The import workspace containing `path`, if it's inside one.
+    pub fn import_workspace_of(&self, path: &Path) -> Option<PathBuf> {
+        let imports_dir = self.imports_dir();
+        let name = path.strip_prefix(&imports_dir).ok()?.components().next()?;
+        Some(imports_dir.join(name))
+    }
+
+    /// Delete an import workspace and everything extracted into it.
+    pub async fn remove_import_workspace(&self, workspace: &Path) -> Result<(), CacheError> {
+        match fs::remove_dir_all(workspace).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn imports_dir(&self) -> PathBuf {
+        self.config.cache_dir.join("imports")
+    }
+
     /// Load existing cache entries from disk on startup
     async fn load_existing_cache(&self) -> Result<(), CacheError> {
         let mut entries = self.entries.write().await;
@@ -164,25 +248,27 @@ impl CacheManager {
         Ok(())
     }
 
-    /// Ensure there's enough space for a new file, evicting old files if necessary
+    /// Ensure there's enough space for a new file, evicting old files if necessary.
+    /// Pinned files are never evicted, so they can take the cache over its limits.
     async fn ensure_space_available(&self, needed_bytes: u64) -> Result<(), CacheError> {
         let mut entries = self.entries.write().await;
         let mut current_size = self.current_size.write().await;
-        while *current_size + needed_bytes > self.config.max_size_bytes && !entries.is_empty() {
-            self.evict_lru(&mut entries, &mut current_size).await?;
-        }
-        while entries.len() >= self.config.max_files && !entries.is_empty() {
-            self.evict_lru(&mut entries, &mut current_size).await?;
-        }
+        while *current_size + needed_bytes > self.config.max_size_bytes
+            && self.evict_lru(&mut entries, &mut current_size).await?
+        {}
+        while entries.len() >= self.config.max_files
+            && self.evict_lru(&mut entries, &mut current_size).await?
+        {}
         Ok(())
     }
 
-    /// Evict the least recently used entry
+    /// Evict the least recently used entry that isn't pinned. Returns false
+    /// when there's nothing left to evict.
     async fn evict_lru(
         &self,
         entries: &mut HashMap<String, CacheEntry>,
         current_size: &mut u64,
-    ) -> Result<(), CacheError> {
+    ) -> Result<bool, CacheError> {
         let pinned = self.pinned.read().await;
         let lru_key = entries
             .iter()
@@ -201,9 +287,11 @@ impl CacheManager {
                 *current_size = current_size.saturating_sub(entry.size_bytes);
 
                 debug!("Evicted {} ({} bytes)", key, entry.size_bytes);
+
+                return Ok(true);
             }
         }
-        Ok(())
+        Ok(false)
     }
 
     /// Pin a cache entry to prevent it from being evicted
@@ -1,7 +1,7 @@
 //! CUE sheet generation during ripping
 use crate::cd::drive::CdToc;
 use crate::cd::ripper::RipResult;
-use crate::cue_flac::CueSheet;
+use crate::cue_flac::{format_cue_time, CueSheet};
 use std::path::PathBuf;
 /// Generates CUE sheets from CD TOC and rip results
 pub struct CueGenerator;
@@ -17,22 +17,38 @@ impl CueGenerator {
         let mut tracks = Vec::new();
         for (idx, result) in rip_results.iter().enumerate() {
             let track_num = result.track_number;
-            let start_sector = if idx < toc.track_offsets.len() {
-                toc.track_offsets[idx]
-            } else {
-                0
-            };
-            let start_time_ms = (start_sector as u64 * 1000) / 75;
+            // TOC offsets include the 150-sector lead-in; the CUE timeline starts at LBA 0
+            let start_sector = toc
+                .track_offsets
+                .get(idx)
+                .map(|offset| offset.saturating_sub(150))
+                .unwrap_or(0);
+            let pregap_sectors = toc.pregap_sectors(idx).min(start_sector);
+            let start_time_ms = sectors_to_ms(start_sector);
+            let pregap_time_ms =
+                (pregap_sectors > 0).then(|| sectors_to_ms(start_sector - pregap_sectors));
             let end_time_ms = start_time_ms + result.duration_ms;
+            let cd_text = toc.cd_text.as_ref().and_then(|text| text.tracks.get(idx));
             tracks.push(crate::cue_flac::CueTrack {
                 number: track_num as u32,
-                title: format!("Track {}", track_num),
-                performer: Some(performer.to_string()),
+                title: cd_text
+                    .and_then(|t| t.title.clone())
+                    .unwrap_or_else(|| format!("Track {}", track_num)),
+                performer: cd_text
+                    .and_then(|t| t.performer.clone())
+                    .or_else(|| Some(performer.to_string())),
                 start_time_ms,
-                pregap_time_ms: None,
+                pregap_time_ms,
                 end_time_ms: Some(end_time_ms),
             });
         }
+        // A track's audio stops where the next track's pregap begins
+        for idx in 1..tracks.len() {
+            let next_start = tracks[idx].audio_start_ms();
+            if let Some(end) = tracks[idx - 1].end_time_ms.as_mut() {
+                *end = (*end).min(next_start);
+            }
+        }
         CueSheet {
             title: title.to_string(),
             performer: performer.to_string(),
@@ -62,16 +78,102 @@ impl CueGenerator {
                 writeln!(file, "    PERFORMER \"{}\"", performer)?;
             }
             writeln!(file, "    TITLE \"{}\"", track.title)?;
-            let start_ms = track.start_time_ms;
-            let minutes = (start_ms / 60000) % 60;
-            let seconds = ((start_ms / 1000) % 60) as u8;
-            let frames = ((start_ms % 1000) * 75 / 1000) as u8;
+            if let Some(pregap_ms) = track.pregap_time_ms {
+                writeln!(file, "    INDEX 00 {}", format_cue_time(pregap_ms))?;
+            }
             writeln!(
                 file,
-                "    INDEX 01 {:02}:{:02}:{:02}",
-                minutes, seconds, frames
+                "    INDEX 01 {}",
+                format_cue_time(track.start_time_ms)
             )?;
         }
         Ok(())
     }
 }
+fn sectors_to_ms(sectors: u32) -> u64 {
+    (sectors as u64 * 1000) / 75
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cd::drive::{CdText, CdTextTrack};
+    fn rip_result(track_number: u8, duration_ms: u64) -> RipResult {
+        RipResult {
+            track_number,
+            output_path: PathBuf::from(format!("{:02}.flac", track_number)),
+            bytes_written: 0,
+            errors: 0,
+            duration_ms,
+            crc32: 0,
+        }
+    }
+    /// Three tracks: 10s of hidden track one audio, then a 2s pregap before
+    /// track 2, and CD-Text naming track 2 only
+    fn toc() -> CdToc {
+        CdToc {
+            disc_id: "disc".to_string(),
+            first_track: 1,
+            last_track: 3,
+            leadout_track: 170,
+            track_offsets: vec![150 + 750, 150 + 15750, 150 + 23250],
+            pregap_offsets: vec![None, Some(150 + 15600), None],
+            cd_text: Some(CdText {
+                title: Some("Landlocked".to_string()),
+                performer: Some("Glass Harbor".to_string()),
+                tracks: vec![
+                    CdTextTrack::default(),
+                    CdTextTrack {
+                        title: Some("Low Tide".to_string()),
+                        performer: None,
+                    },
+                    CdTextTrack::default(),
+                ],
+            }),
+        }
+    }
+    #[test]
+    fn pregaps_become_index_00_and_trim_the_previous_track() {
+        let results = [
+            rip_result(1, 200_000),
+            rip_result(2, 100_000),
+            rip_result(3, 60_000),
+        ];
+        let sheet = CueGenerator::generate_cue_sheet(
+            &toc(),
+            &results,
+            "a.flac",
+            "Glass Harbor",
+            "Landlocked",
+        );
+        let track1 = &sheet.tracks[0];
+        assert_eq!(track1.start_time_ms, 10_000);
+        assert_eq!(track1.pregap_time_ms, Some(0));
+        // Stops where track 2's pregap begins, not at start + duration
+        assert_eq!(track1.end_time_ms, Some(208_000));
+        let track2 = &sheet.tracks[1];
+        assert_eq!(track2.start_time_ms, 210_000);
+        assert_eq!(track2.pregap_time_ms, Some(208_000));
+        let track3 = &sheet.tracks[2];
+        assert_eq!(track3.pregap_time_ms, None);
+        assert_eq!(track3.end_time_ms, Some(310_000 + 60_000));
+        assert_eq!(format_cue_time(track2.audio_start_ms()), "03:28:00");
+    }
+    #[test]
+    fn cd_text_names_tracks_and_falls_back_per_field() {
+        let results = [
+            rip_result(1, 1_000),
+            rip_result(2, 1_000),
+            rip_result(3, 1_000),
+        ];
+        let sheet = CueGenerator::generate_cue_sheet(
+            &toc(),
+            &results,
+            "a.flac",
+            "Glass Harbor",
+            "Landlocked",
+        );
+        assert_eq!(sheet.tracks[0].title, "Track 1");
+        assert_eq!(sheet.tracks[1].title, "Low Tide");
+        assert_eq!(sheet.tracks[1].performer.as_deref(), Some("Glass Harbor"));
+    }
+}
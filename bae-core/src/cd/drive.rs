@@ -27,6 +27,49 @@ pub struct CdToc {
     pub last_track: u8,
     pub leadout_track: u8,
     pub track_offsets: Vec<u32>,
+    /// Per-track INDEX 00 offsets (same +150 basis as `track_offsets`),
+    /// `None` where the track has no pregap
+    pub pregap_offsets: Vec<Option<u32>>,
+    /// CD-Text stored on the disc, if the drive could read any
+    pub cd_text: Option<CdText>,
+}
+/// Disc and track strings from the disc's CD-Text block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CdText {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    /// One entry per track, in track order
+    pub tracks: Vec<CdTextTrack>,
+}
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CdTextTrack {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+}
+impl CdToc {
+    /// Number of sectors before track 1's INDEX 01.
+    ///
+    /// Non-zero means the disc has a pregap on track 1, which may hold hidden
+    /// track one audio (HTOA) that is only reachable by rewinding from track 1.
+    pub fn htoa_sectors(&self) -> u32 {
+        self.track_offsets
+            .first()
+            .map(|offset| offset.saturating_sub(150))
+            .unwrap_or(0)
+    }
+    /// Pregap length of a track in sectors (0 when there is none)
+    pub fn pregap_sectors(&self, track_index: usize) -> u32 {
+        if track_index == 0 {
+            return self.htoa_sectors();
+        }
+        match (
+            self.pregap_offsets.get(track_index).copied().flatten(),
+            self.track_offsets.get(track_index),
+        ) {
+            (Some(pregap), Some(&start)) => start.saturating_sub(pregap),
+            _ => 0,
+        }
+    }
 }
 impl CdDrive {
     /// Detect available CD drives
@@ -63,12 +106,20 @@ impl CdDrive {
             .last_track_num()
             .map_err(|e| CdDriveError::DiscId(format!("Failed to get last track: {}", e)))?;
         let mut track_offsets = Vec::new();
+        let mut pregap_offsets = Vec::new();
         for track_num in first_track..=last_track {
             let lba = drive.track_start_lba(track_num).map_err(|e| {
                 CdDriveError::DiscId(format!("Failed to get LBA for track {}: {}", track_num, e))
             })?;
             track_offsets.push(lba + 150);
+            pregap_offsets.push(
+                drive
+                    .track_pregap_lba(track_num)
+                    .filter(|&pregap| pregap < lba)
+                    .map(|pregap| pregap + 150),
+            );
         }
+        let cd_text = read_cd_text(&drive, first_track, last_track);
         let leadout_lba = drive
             .leadout_lba()
             .map_err(|e| CdDriveError::DiscId(format!("Failed to get leadout: {}", e)))?;
@@ -85,6 +136,36 @@ impl CdDrive {
             last_track,
             leadout_track,
             track_offsets,
+            pregap_offsets,
+            cd_text,
+        })
+    }
+}
+/// Read disc and per-track CD-Text, or `None` if the disc has none
+fn read_cd_text(
+    drive: &crate::cd::ffi::LibcdioDrive,
+    first_track: u8,
+    last_track: u8,
+) -> Option<CdText> {
+    use libcdio_sys::{cdtext_field_t_CDTEXT_FIELD_PERFORMER, cdtext_field_t_CDTEXT_FIELD_TITLE};
+    let tracks: Vec<CdTextTrack> = (first_track..=last_track)
+        .map(|track_num| CdTextTrack {
+            title: drive.cdtext_field(cdtext_field_t_CDTEXT_FIELD_TITLE, track_num),
+            performer: drive.cdtext_field(cdtext_field_t_CDTEXT_FIELD_PERFORMER, track_num),
         })
+        .collect();
+    let cd_text = CdText {
+        title: drive.cdtext_field(cdtext_field_t_CDTEXT_FIELD_TITLE, 0),
+        performer: drive.cdtext_field(cdtext_field_t_CDTEXT_FIELD_PERFORMER, 0),
+        tracks,
+    };
+    let has_track_text = cd_text
+        .tracks
+        .iter()
+        .any(|t| t.title.is_some() || t.performer.is_some());
+    if cd_text.title.is_none() && cd_text.performer.is_none() && !has_track_text {
+        None
+    } else {
+        Some(cd_text)
     }
 }
@@ -113,6 +113,45 @@ impl LibcdioDrive {
             Ok(lba as u32)
         }
     }
+    /// Get the pregap (INDEX 00) LBA for a track, if the drive reports one
+    pub fn track_pregap_lba(&self, track_num: u8) -> Option<u32> {
+        unsafe {
+            let lba = libcdio_sys::cdio_get_track_pregap_lba(
+                self.device,
+                track_num as libcdio_sys::track_t,
+            );
+            if lba < 0 {
+                None
+            } else {
+                Some(lba as u32)
+            }
+        }
+    }
+    /// Read a CD-Text field for the disc (track 0) or a single track
+    pub fn cdtext_field(
+        &self,
+        field: libcdio_sys::cdtext_field_t,
+        track_num: u8,
+    ) -> Option<String> {
+        unsafe {
+            // Owned by the CdIo_t handle, freed in cdio_destroy
+            let cdtext = libcdio_sys::cdio_get_cdtext(self.device);
+            if cdtext.is_null() {
+                return None;
+            }
+            let value =
+                libcdio_sys::cdtext_get_const(cdtext, field, track_num as libcdio_sys::track_t);
+            if value.is_null() {
+                return None;
+            }
+            let text = CStr::from_ptr(value).to_string_lossy().trim().to_string();
+            if text.is_empty() {
+                None
+            } else {
+                Some(text)
+            }
+        }
+    }
     /// Get the raw device pointer (for advanced operations)
     pub fn device_ptr(&self) -> *mut libcdio_sys::CdIo_t {
         self.device
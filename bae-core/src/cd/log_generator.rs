@@ -91,12 +91,19 @@ impl LogGenerator {
         }
         writeln!(file)?;
         writeln!(file)?;
-        for result in rip_results {
+        for (idx, result) in rip_results.iter().enumerate() {
+            let pregap = toc.pregap_sectors(idx);
             writeln!(file, "Track {}", result.track_number)?;
             writeln!(file)?;
             writeln!(file, "     Filename : {}", result.output_path.display())?;
             writeln!(file)?;
-            writeln!(file, "     Pre-gap length : 00:00:00")?;
+            writeln!(
+                file,
+                "     Pre-gap length : {:02}:{:02}:{:02}",
+                pregap / (75 * 60),
+                (pregap / 75) % 60,
+                pregap % 75,
+            )?;
             writeln!(file)?;
             writeln!(file, "     Track quality : 100.0 %")?;
             writeln!(file, "         Test CRC : {:08X}", result.crc32)?;
@@ -9,6 +9,6 @@ pub mod log_generator;
 pub mod paranoia;
 pub mod ripper;
 pub use cue_generator::CueGenerator;
-pub use drive::CdDrive;
+pub use drive::{CdDrive, CdText, CdTextTrack, CdToc};
 pub use log_generator::LogGenerator;
 pub use ripper::{CdRipper, RipProgress};
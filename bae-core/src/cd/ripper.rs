@@ -81,6 +81,43 @@ impl CdRipper {
         info!("All tracks ripped successfully");
         Ok(results)
     }
+    /// Rip hidden track one audio (HTOA) from track 1's pregap, if any.
+    ///
+    /// Written as `00.flac`. Returns `None` when the disc has no track 1
+    /// pregap or the pregap is digital silence.
+    pub async fn rip_hidden_track(&self) -> Result<Option<RipResult>, RipError> {
+        let num_sectors = self.toc.htoa_sectors();
+        if num_sectors == 0 {
+            return Ok(None);
+        }
+        tracing::info!(
+            "Track 1 has a {} sector pregap, checking for HTOA",
+            num_sectors
+        );
+        let (samples, errors) = self.read_sectors(0, num_sectors, 0, None, 0).await?;
+        if samples.iter().all(|&s| s == 0) {
+            tracing::info!("Track 1 pregap is silent, no hidden track");
+            return Ok(None);
+        }
+        let output_path = self.output_dir.join("00.flac");
+        let sample_rate = 44100u32;
+        let channels = 2u32;
+        let flac_data = self.encode_to_flac(&samples, sample_rate, channels, 16)?;
+        let crc32 = crc32fast::hash(&flac_data);
+        tokio::fs::write(&output_path, &flac_data)
+            .await
+            .map_err(RipError::Io)?;
+        let duration_ms = (samples.len() as u64 * 1000) / (sample_rate as u64 * channels as u64);
+        tracing::info!("Ripped {} ms of hidden track one audio", duration_ms);
+        Ok(Some(RipResult {
+            track_number: 0,
+            output_path,
+            bytes_written: flac_data.len() as u64,
+            errors,
+            duration_ms,
+            crc32,
+        }))
+    }
     /// Rip a single track
     async fn rip_track(
         &self,
@@ -133,7 +170,6 @@ impl CdRipper {
         total_tracks: u8,
     ) -> Result<(Vec<i32>, u32), RipError> {
         use crate::cd::ffi::LibcdioDrive;
-        use crate::cd::paranoia::ParanoiaReader;
         use tracing::info;
         info!(
             "Reading track {} samples from drive {:?}",
@@ -198,6 +234,22 @@ impl CdRipper {
                 track_num, start_lba, end_lba,
             )));
         }
+        self.read_sectors(start_lba, num_sectors, track_num, progress_tx, total_tracks)
+            .await
+    }
+    /// Read raw samples from an LBA range using libcdio-paranoia
+    /// Returns samples and error count
+    async fn read_sectors(
+        &self,
+        start_lba: u32,
+        num_sectors: u32,
+        track_num: u8,
+        progress_tx: Option<&mpsc::UnboundedSender<RipProgress>>,
+        total_tracks: u8,
+    ) -> Result<(Vec<i32>, u32), RipError> {
+        use crate::cd::ffi::LibcdioDrive;
+        use crate::cd::paranoia::ParanoiaReader;
+        use tracing::info;
         let device_path = self.drive.device_path.clone();
         let start_lba_for_read = start_lba;
         let num_sectors_for_read = num_sectors;
@@ -9,7 +9,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
-use super::{CloudHome, CloudHomeError, JoinInfo};
+use super::{CloudHome, CloudHomeError, JoinInfo, UploadSession};
 use crate::keys::KeyService;
 use crate::oauth::{self, OAuthConfig, OAuthTokens};
 
@@ -105,6 +105,39 @@ impl DropboxCloudHome {
         Ok(access_token)
     }
 
+    /// Call an upload session endpoint, returning the response body
+    async fn upload_session_call(
+        &self,
+        endpoint: &str,
+        api_arg: serde_json::Value,
+        data: Vec<u8>,
+        key: &str,
+    ) -> Result<String, CloudHomeError> {
+        let api_arg_str = api_arg.to_string();
+        let resp = self
+            .api_call(|token| {
+                self.client
+                    .post(format!(
+                        "{}/files/upload_session/{}",
+                        CONTENT_BASE, endpoint
+                    ))
+                    .bearer_auth(token)
+                    .header("Dropbox-API-Arg", &api_arg_str)
+                    .header("Content-Type", "application/octet-stream")
+                    .body(data.clone())
+            })
+            .await?;
+
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(CloudHomeError::Storage(format!(
+                "upload session {endpoint} for {key} (HTTP {status}): {body}"
+            )));
+        }
+        Ok(body)
+    }
+
     /// Make an API call with automatic token refresh on 401.
     async fn api_call(
         &self,
@@ -571,6 +604,70 @@ impl CloudHome for DropboxCloudHome {
 
         Ok(())
     }
+
+    // Unfinished sessions expire on their own after a week, so there is no
+    // `abort_upload`
+    async fn begin_upload(
+        &self,
+        key: &str,
+        _size: u64,
+    ) -> Result<Option<UploadSession>, CloudHomeError> {
+        let body = self
+            .upload_session_call(
+                "start",
+                serde_json::json!({ "close": false }),
+                Vec::new(),
+                key,
+            )
+            .await?;
+        let json: serde_json::Value = serde_json::from_str(&body)
+            .map_err(|e| CloudHomeError::Storage(format!("parse upload session: {e}")))?;
+        let id = json["session_id"]
+            .as_str()
+            .ok_or_else(|| CloudHomeError::Storage(format!("start upload {key}: no session ID")))?;
+        Ok(Some(UploadSession {
+            id: id.to_string(),
+            ..Default::default()
+        }))
+    }
+
+    async fn upload_part(
+        &self,
+        key: &str,
+        session: &mut UploadSession,
+        data: Vec<u8>,
+        _size: u64,
+    ) -> Result<(), CloudHomeError> {
+        let len = data.len() as u64;
+        let api_arg = serde_json::json!({
+            "cursor": { "session_id": session.id, "offset": session.uploaded },
+            "close": false,
+        });
+        self.upload_session_call("append_v2", api_arg, data, key)
+            .await?;
+        session.uploaded += len;
+        Ok(())
+    }
+
+    async fn finish_upload(
+        &self,
+        key: &str,
+        session: &UploadSession,
+        size: u64,
+    ) -> Result<(), CloudHomeError> {
+        let api_arg = serde_json::json!({
+            "cursor": { "session_id": session.id, "offset": size },
+            "commit": {
+                "path": self.full_path(key),
+                "mode": { ".tag": "overwrite" },
+                "autorename": false,
+                "mute": true,
+            },
+        });
+        self.upload_session_call("finish", api_arg, Vec::new(), key)
+            .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
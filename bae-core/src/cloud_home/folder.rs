@@ -0,0 +1,242 @@
+//! Folder-backed `CloudHome` implementation.
+//!
+//! Any directory works as the cloud home: a USB drive, a NAS mount, a folder
+//! another tool syncs. Keys map to paths under the folder, in the same layout
+//! every other backend uses, so devices can sync with no network at all by
+//! carrying a drive between them.
+//!
+//! Two things set this apart from iCloud Drive, which is also a directory:
+//! - The folder can go away when its drive is unplugged. Every operation
+//!   checks for it first, so a missing drive is an error rather than an empty
+//!   bucket, and nothing gets written to the bare mount point.
+//! - Writes land in a hidden partial file that is renamed into place, so a
+//!   drive pulled mid-write never leaves a truncated changeset behind.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+
+use super::{CloudHome, CloudHomeError, JoinInfo};
+
+/// Directory-backed cloud home.
+pub struct FolderCloudHome {
+    root: PathBuf,
+}
+
+impl FolderCloudHome {
+    /// Create a cloud home rooted at an existing directory.
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Resolve a key to a full filesystem path, after checking the folder is
+    /// still there.
+    async fn path_for_key(&self, key: &str) -> Result<PathBuf, CloudHomeError> {
+        match tokio::fs::metadata(&self.root).await {
+            Ok(m) if m.is_dir() => Ok(self.root.join(key)),
+            _ => Err(CloudHomeError::Storage(format!(
+                "sync folder {} is not available; is its drive connected?",
+                self.root.display()
+            ))),
+        }
+    }
+}
+
+fn not_found_or_io(key: &str, e: std::io::Error) -> CloudHomeError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        CloudHomeError::NotFound(key.to_string())
+    } else {
+        CloudHomeError::Io(e)
+    }
+}
+
+/// Dotfiles are partial writes, or litter like `.DS_Store` and `._*` that
+/// macOS leaves on FAT-formatted drives. None of them are keys.
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+#[async_trait]
+impl CloudHome for FolderCloudHome {
+    async fn write(&self, key: &str, data: Vec<u8>) -> Result<(), CloudHomeError> {
+        let path = self.path_for_key(key).await?;
+        let parent = path.parent().unwrap_or(&self.root);
+        tokio::fs::create_dir_all(parent).await?;
+
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| CloudHomeError::Storage(format!("invalid key: {key}")))?;
+        let partial = parent.join(format!(".{}.partial", file_name.to_string_lossy()));
+
+        let mut file = tokio::fs::File::create(&partial).await?;
+        file.write_all(&data).await?;
+        file.sync_all().await?;
+        drop(file);
+        tokio::fs::rename(&partial, &path).await?;
+        Ok(())
+    }
+
+    async fn read(&self, key: &str) -> Result<Vec<u8>, CloudHomeError> {
+        let path = self.path_for_key(key).await?;
+        tokio::fs::read(&path)
+            .await
+            .map_err(|e| not_found_or_io(key, e))
+    }
+
+    async fn read_range(&self, key: &str, start: u64, end: u64) -> Result<Vec<u8>, CloudHomeError> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let path = self.path_for_key(key).await?;
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|e| not_found_or_io(key, e))?;
+
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        let mut buf = vec![0u8; end.saturating_sub(start) as usize];
+        file.read_exact(&mut buf).await?;
+        Ok(buf)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, CloudHomeError> {
+        let base = self.path_for_key(prefix).await?;
+        match tokio::fs::metadata(&base).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(CloudHomeError::Io(e)),
+            Ok(m) if !m.is_dir() => return Ok(vec![]),
+            Ok(_) => {}
+        }
+
+        let mut keys = Vec::new();
+        let mut stack = vec![base];
+
+        while let Some(dir) = stack.pop() {
+            let mut entries = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if is_hidden(&path) {
+                    continue;
+                }
+                let file_type = entry.file_type().await?;
+                if file_type.is_dir() {
+                    stack.push(path);
+                } else if file_type.is_file() {
+                    if let Ok(relative) = path.strip_prefix(&self.root) {
+                        keys.push(relative.to_string_lossy().replace('\\', "/"));
+                    }
+                }
+            }
+        }
+
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CloudHomeError> {
+        let path = self.path_for_key(key).await?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(CloudHomeError::Io(e)),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, CloudHomeError> {
+        let path = self.path_for_key(key).await?;
+        match tokio::fs::metadata(&path).await {
+            Ok(m) => Ok(m.is_file()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(CloudHomeError::Io(e)),
+        }
+    }
+
+    async fn grant_access(&self, _member_id: &str) -> Result<JoinInfo, CloudHomeError> {
+        Err(CloudHomeError::Storage(
+            "a sync folder is shared by opening it on the other device".to_string(),
+        ))
+    }
+
+    async fn revoke_access(&self, _member_id: &str) -> Result<(), CloudHomeError> {
+        // Whoever can reach the folder can read it; there's nothing to revoke
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn write_replaces_and_leaves_no_partial_files() {
+        let tmp = TempDir::new().unwrap();
+        let ch = FolderCloudHome::new(tmp.path().to_path_buf());
+
+        ch.write("changes/dev1/1.enc", b"first".to_vec())
+            .await
+            .unwrap();
+        ch.write("changes/dev1/1.enc", b"second".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(ch.read("changes/dev1/1.enc").await.unwrap(), b"second");
+        assert_eq!(
+            ch.read_range("changes/dev1/1.enc", 1, 4).await.unwrap(),
+            b"eco"
+        );
+        let files: Vec<_> = std::fs::read_dir(tmp.path().join("changes/dev1"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec!["1.enc"]);
+    }
+
+    #[tokio::test]
+    async fn list_skips_hidden_files() {
+        let tmp = TempDir::new().unwrap();
+        let ch = FolderCloudHome::new(tmp.path().to_path_buf());
+
+        ch.write("changes/dev1/1.enc", b"a".to_vec()).await.unwrap();
+        ch.write("snapshot.db", b"b".to_vec()).await.unwrap();
+        // A write cut short, and macOS litter
+        std::fs::write(tmp.path().join("changes/dev1/.2.enc.partial"), b"c").unwrap();
+        std::fs::write(tmp.path().join("._snapshot.db"), b"d").unwrap();
+        std::fs::create_dir(tmp.path().join(".Trashes")).unwrap();
+        std::fs::write(tmp.path().join(".Trashes/old.enc"), b"e").unwrap();
+
+        assert_eq!(
+            ch.list("").await.unwrap(),
+            vec!["changes/dev1/1.enc", "snapshot.db"]
+        );
+        assert_eq!(
+            ch.list("changes").await.unwrap(),
+            vec!["changes/dev1/1.enc"]
+        );
+        assert!(ch.list("images").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_folder_is_an_error() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("unplugged");
+        let ch = FolderCloudHome::new(root.clone());
+
+        assert!(matches!(
+            ch.list("changes").await,
+            Err(CloudHomeError::Storage(_))
+        ));
+        assert!(ch.write("snapshot.db", b"a".to_vec()).await.is_err());
+        assert!(!root.exists());
+
+        std::fs::create_dir(&root).unwrap();
+        ch.write("snapshot.db", b"a".to_vec()).await.unwrap();
+        assert!(ch.exists("snapshot.db").await.unwrap());
+        ch.delete("snapshot.db").await.unwrap();
+        assert!(matches!(
+            ch.read("snapshot.db").await,
+            Err(CloudHomeError::NotFound(_))
+        ));
+    }
+}
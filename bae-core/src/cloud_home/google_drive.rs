@@ -8,7 +8,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
-use super::{CloudHome, CloudHomeError, JoinInfo};
+use super::{content_range, CloudHome, CloudHomeError, JoinInfo, UploadSession};
 use crate::keys::KeyService;
 use crate::oauth::{self, OAuthConfig, OAuthTokens};
 
@@ -537,6 +537,123 @@ impl CloudHome for GoogleDriveCloudHome {
 
         Ok(())
     }
+
+    async fn begin_upload(
+        &self,
+        key: &str,
+        size: u64,
+    ) -> Result<Option<UploadSession>, CloudHomeError> {
+        let encoded = Self::encode_key(key);
+
+        // Same update-or-create split as `write`
+        let existing = self.find_file_id(&encoded).await?;
+        let resp = self
+            .api_call(|token| {
+                let request = match &existing {
+                    Some(file_id) => self
+                        .client
+                        .patch(format!(
+                            "{}/files/{}?uploadType=resumable",
+                            UPLOAD_API, file_id
+                        ))
+                        .json(&serde_json::json!({})),
+                    None => self
+                        .client
+                        .post(format!("{}/files?uploadType=resumable", UPLOAD_API))
+                        .json(&serde_json::json!({
+                            "name": encoded,
+                            "parents": [self.folder_id],
+                        })),
+                };
+                request
+                    .bearer_auth(token)
+                    .header("X-Upload-Content-Type", "application/octet-stream")
+                    .header("X-Upload-Content-Length", size)
+            })
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(CloudHomeError::Storage(format!(
+                "start upload {key} (HTTP {status}): {body}"
+            )));
+        }
+
+        let session_url = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| {
+                CloudHomeError::Storage(format!("start upload {key}: no session URL"))
+            })?;
+        Ok(Some(UploadSession {
+            id: session_url.to_string(),
+            ..Default::default()
+        }))
+    }
+
+    async fn upload_part(
+        &self,
+        key: &str,
+        session: &mut UploadSession,
+        data: Vec<u8>,
+        size: u64,
+    ) -> Result<(), CloudHomeError> {
+        let len = data.len() as u64;
+        let range = content_range(session.uploaded, len, size);
+        let resp = self
+            .api_call(|token| {
+                self.client
+                    .put(&session.id)
+                    .bearer_auth(token)
+                    .header("Content-Range", &range)
+                    .body(data.clone())
+            })
+            .await?;
+
+        // 308 means the part arrived and more are expected
+        let status = resp.status();
+        if !status.is_success() && status != reqwest::StatusCode::PERMANENT_REDIRECT {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(CloudHomeError::Storage(format!(
+                "upload part of {key} (HTTP {status}): {body}"
+            )));
+        }
+
+        session.uploaded += len;
+        Ok(())
+    }
+
+    async fn finish_upload(
+        &self,
+        _key: &str,
+        _session: &UploadSession,
+        _size: u64,
+    ) -> Result<(), CloudHomeError> {
+        // The last part commits the file
+        Ok(())
+    }
+
+    async fn abort_upload(&self, key: &str, session: &UploadSession) -> Result<(), CloudHomeError> {
+        let resp = self
+            .api_call(|token| self.client.delete(&session.id).bearer_auth(token))
+            .await?;
+
+        // A cancelled session answers 499
+        let status = resp.status();
+        if !status.is_success()
+            && status.as_u16() != 499
+            && status != reqwest::StatusCode::NOT_FOUND
+        {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(CloudHomeError::Storage(format!(
+                "abort upload {key} (HTTP {status}): {body}"
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
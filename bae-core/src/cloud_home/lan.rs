@@ -0,0 +1,824 @@
+//! LAN `CloudHome`: sync between devices on the same network, with no bucket.
+//!
+//! Every device keeps its own copy of the bucket in its library folder, serves
+//! it over HTTP and advertises itself over mDNS. Each device compares its
+//! peers' listings with its own every few seconds and copies what it's
+//! missing, so a changeset reaches a device that was off when it was written
+//! as long as some device that has it is on.
+//!
+//! - Every key has a version, the time it was last written or deleted. The
+//!   newer version wins and copies keep it, so devices converge. Deletions
+//!   leave a tombstone that spreads the same way.
+//! - Release files under `storage/` stay on the device that stored them and
+//!   are read from it when needed, rather than copied to every device.
+//! - Peers prove they hold the library key: requests and responses carry an
+//!   HMAC under a key derived from it, and the mDNS record names only a tag
+//!   derived from it. Others on the network can't read the bucket or plant
+//!   and delete keys in it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use axum::{
+    extract::{Path as UrlPath, Query, State},
+    http::{HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+use super::{CloudHome, CloudHomeError, JoinInfo};
+use crate::hmac_utils::{hmac_sign, hmac_verify};
+
+const SERVICE_TYPE: &str = "_bae-sync._tcp.local.";
+/// How often peers are checked for new keys, besides when one appears
+const REPLICATE_INTERVAL: Duration = Duration::from_secs(15);
+/// Requests signed longer ago than this are refused, so a captured one can't
+/// be replayed later
+const MAX_REQUEST_AGE_SECS: u64 = 5 * 60;
+/// Keys under this prefix are read from peers on demand rather than copied
+const ON_DEMAND_PREFIX: &str = "storage/";
+const INDEX_FILE: &str = ".index.json";
+const AUTH_HEADER: &str = "x-bae-lan-auth";
+const MAC_HEADER: &str = "x-bae-lan-mac";
+
+/// One node per replica folder, shared by every `LanCloudHome` opened on it
+static NODES: OnceLock<tokio::sync::Mutex<HashMap<PathBuf, Arc<LanNode>>>> = OnceLock::new();
+
+/// A key's latest write or deletion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct KeyVersion {
+    key: String,
+    /// Milliseconds since the Unix epoch
+    version: u64,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    deleted: bool,
+}
+
+struct Peer {
+    url: String,
+    /// Its listing as of the last replication
+    keys: Vec<KeyVersion>,
+}
+
+struct LanNode {
+    root: PathBuf,
+    /// Derived from the library key; only devices with the key have it
+    secret: [u8; 32],
+    device_id: String,
+    /// Versions of every key in the replica, and tombstones of deleted ones
+    index: Mutex<BTreeMap<String, KeyVersion>>,
+    /// Peers by mDNS name
+    peers: RwLock<HashMap<String, Peer>>,
+    /// Wakes replication early when a peer appears
+    wake: Notify,
+    /// Held while a key changes, so versions are compared and set together
+    write_lock: tokio::sync::Mutex<()>,
+    client: reqwest::Client,
+}
+
+/// Cloud home replicated between devices on the local network.
+pub struct LanCloudHome {
+    node: Arc<LanNode>,
+}
+
+impl LanCloudHome {
+    /// Sync the replica at `root` with the devices on the network that hold
+    /// `encryption_key`, starting its server and discovery on first use.
+    pub async fn start(
+        root: PathBuf,
+        device_id: &str,
+        encryption_key: &str,
+    ) -> Result<Self, CloudHomeError> {
+        let secret = derive_secret(encryption_key)?;
+        let mut nodes = NODES
+            .get_or_init(|| tokio::sync::Mutex::new(HashMap::new()))
+            .lock()
+            .await;
+        if let Some(node) = nodes.get(&root).filter(|node| node.secret == secret) {
+            return Ok(Self { node: node.clone() });
+        }
+
+        let node = Arc::new(LanNode::open(root.clone(), device_id, secret).await?);
+        node.clone().start().await?;
+        nodes.insert(root, node.clone());
+        Ok(Self { node })
+    }
+}
+
+/// The key peers sign with, derived from the library encryption key
+fn derive_secret(encryption_key: &str) -> Result<[u8; 32], CloudHomeError> {
+    let key: [u8; 32] = hex::decode(encryption_key)
+        .ok()
+        .and_then(|key| key.try_into().ok())
+        .ok_or_else(|| CloudHomeError::Storage("invalid encryption key".to_string()))?;
+    Ok(hmac_sign(&key, b"bae lan sync"))
+}
+
+/// What peers advertise to find each other without naming the library
+fn discovery_tag(secret: &[u8; 32]) -> String {
+    hex::encode(&hmac_sign(secret, b"discovery tag")[..8])
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Request path of `key`, or of the bytes `start..end` of it
+fn object_path(key: &str, range: Option<(u64, u64)>) -> String {
+    match range {
+        Some((start, end)) => format!("/lan/object/{key}?start={start}&end={end}"),
+        None => format!("/lan/object/{key}"),
+    }
+}
+
+/// What a response's MAC covers: the request it answers and its body
+fn response_message(path: &str, body: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(path.len() + 1 + body.len());
+    message.extend_from_slice(path.as_bytes());
+    message.push(b'\n');
+    message.extend_from_slice(body);
+    message
+}
+
+fn request_auth(secret: &[u8; 32], path: &str, timestamp: u64) -> String {
+    let mac = hmac_sign(secret, format!("{timestamp}\n{path}").as_bytes());
+    format!("{timestamp}:{}", hex::encode(mac))
+}
+
+fn storage_error(e: impl std::fmt::Display) -> CloudHomeError {
+    CloudHomeError::Storage(e.to_string())
+}
+
+fn not_found_or_io(key: &str, e: std::io::Error) -> CloudHomeError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        CloudHomeError::NotFound(key.to_string())
+    } else {
+        CloudHomeError::Io(e)
+    }
+}
+
+/// Write `data` to a hidden partial file and rename it into place, so readers
+/// never see half a file
+async fn write_atomic(path: &Path, data: &[u8]) -> Result<(), CloudHomeError> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| storage_error(format!("invalid path: {}", path.display())))?;
+    tokio::fs::create_dir_all(parent).await?;
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let partial = parent.join(format!(".{file_name}.partial"));
+    let mut file = tokio::fs::File::create(&partial).await?;
+    file.write_all(data).await?;
+    file.sync_all().await?;
+    drop(file);
+    tokio::fs::rename(&partial, path).await?;
+    Ok(())
+}
+
+/// Every file under `root` as (key, modified time in ms), skipping dotfiles
+fn walk(root: &Path) -> std::io::Result<Vec<(String, u64)>> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                stack.push(entry.path());
+            } else if file_type.is_file() {
+                let modified = entry
+                    .metadata()?
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                if let Ok(relative) = entry.path().strip_prefix(root) {
+                    files.push((relative.to_string_lossy().replace('\\', "/"), modified));
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+impl LanNode {
+    /// Open the replica at `root`, creating it if needed. Files written while
+    /// the index wasn't saved get their modified time as their version.
+    async fn open(
+        root: PathBuf,
+        device_id: &str,
+        secret: [u8; 32],
+    ) -> Result<Self, CloudHomeError> {
+        tokio::fs::create_dir_all(&root).await?;
+        let saved: Vec<KeyVersion> = match tokio::fs::read(root.join(INDEX_FILE)).await {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                warn!("LAN sync index is unreadable, rebuilding it: {e}");
+
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        let walk_root = root.clone();
+        let files = tokio::task::spawn_blocking(move || walk(&walk_root))
+            .await
+            .map_err(storage_error)??;
+
+        let saved: HashMap<String, KeyVersion> =
+            saved.into_iter().map(|v| (v.key.clone(), v)).collect();
+        // Tombstones carry over; entries of files that are gone don't
+        let mut index: BTreeMap<String, KeyVersion> = saved
+            .values()
+            .filter(|v| v.deleted)
+            .map(|v| (v.key.clone(), v.clone()))
+            .collect();
+        for (key, modified) in files {
+            let version = match saved.get(&key) {
+                Some(v) if v.deleted => modified.max(v.version + 1),
+                Some(v) => v.version,
+                None => modified,
+            };
+            index.insert(
+                key.clone(),
+                KeyVersion {
+                    key,
+                    version,
+                    deleted: false,
+                },
+            );
+        }
+
+        Ok(Self {
+            root,
+            secret,
+            device_id: device_id.to_string(),
+            index: Mutex::new(index),
+            peers: RwLock::new(HashMap::new()),
+            wake: Notify::new(),
+            write_lock: tokio::sync::Mutex::new(()),
+            client: reqwest::Client::builder()
+                .connect_timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Serve the replica, advertise it and start replicating from peers
+    async fn start(self: Arc<Self>) -> Result<(), CloudHomeError> {
+        let listener = tokio::net::TcpListener::bind(("0.0.0.0", 0)).await?;
+        let port = listener.local_addr()?.port();
+
+        let tag = discovery_tag(&self.secret);
+        let instance = format!("{}-{tag}", self.device_id);
+        let properties = [("tag", tag.as_str()), ("device", self.device_id.as_str())];
+        let service = ServiceInfo::new(
+            SERVICE_TYPE,
+            &instance,
+            &format!("{instance}.local."),
+            "",
+            port,
+            &properties[..],
+        )
+        .map_err(storage_error)?
+        .enable_addr_auto();
+        let mdns = ServiceDaemon::new().map_err(storage_error)?;
+        mdns.register(service).map_err(storage_error)?;
+        let events = mdns.browse(SERVICE_TYPE).map_err(storage_error)?;
+
+        let app = router(self.clone());
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                warn!("LAN sync server stopped: {e}");
+            }
+        });
+        let node = self.clone();
+        tokio::spawn(async move {
+            // The daemon stops when dropped, so this task keeps it
+            let _mdns = mdns;
+            while let Ok(event) = events.recv_async().await {
+                node.on_discovery(event, &tag);
+            }
+        });
+        tokio::spawn(self.replicate());
+
+        info!("LAN sync serving on port {port}");
+        Ok(())
+    }
+
+    fn on_discovery(&self, event: ServiceEvent, tag: &str) {
+        match event {
+            ServiceEvent::ServiceResolved(service) => {
+                let ours = service.get_property_val_str("tag") == Some(tag);
+                let itself =
+                    service.get_property_val_str("device") == Some(self.device_id.as_str());
+                if !ours || itself {
+                    return;
+                }
+                let addresses = service.get_addresses();
+                let Some(ip) = addresses
+                    .iter()
+                    .find(|ip| ip.is_ipv4())
+                    .or_else(|| addresses.iter().next())
+                else {
+                    return;
+                };
+
+                let url = format!("http://{}", SocketAddr::new(*ip, service.get_port()));
+                info!("Found LAN sync peer at {url}");
+                self.peers.write().unwrap().insert(
+                    service.get_fullname().to_string(),
+                    Peer {
+                        url,
+                        keys: Vec::new(),
+                    },
+                );
+                self.wake.notify_one();
+            }
+            ServiceEvent::ServiceRemoved(_, fullname) => {
+                if self.peers.write().unwrap().remove(&fullname).is_some() {
+                    info!("LAN sync peer {fullname} left");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn replicate(self: Arc<Self>) {
+        loop {
+            let peers: Vec<(String, String)> = self
+                .peers
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(name, peer)| (name.clone(), peer.url.clone()))
+                .collect();
+            for (name, url) in peers {
+                match self.pull_from(&url).await {
+                    Ok(keys) => {
+                        if let Some(peer) = self.peers.write().unwrap().get_mut(&name) {
+                            peer.keys = keys;
+                        }
+                    }
+                    Err(e) => debug!("LAN sync with {url} failed: {e}"),
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(REPLICATE_INTERVAL) => {}
+                _ = self.wake.notified() => {}
+            }
+        }
+    }
+
+    /// Copy the keys `url` has newer versions of, returning its listing
+    async fn pull_from(&self, url: &str) -> Result<Vec<KeyVersion>, CloudHomeError> {
+        let listing = self.get(url, "/lan/keys").await?;
+        let remote: Vec<KeyVersion> = serde_json::from_slice(&listing)
+            .map_err(|e| storage_error(format!("invalid listing from {url}: {e}")))?;
+
+        let pulled = self.pull_keys(url, &remote).await;
+        self.save_index().await?;
+        pulled.map(|()| remote)
+    }
+
+    async fn pull_keys(&self, url: &str, remote: &[KeyVersion]) -> Result<(), CloudHomeError> {
+        for version in remote.iter().filter(|v| self.is_newer(v)) {
+            if version.deleted {
+                self.remove(&version.key, Some(version.version)).await?;
+            } else if !version.key.starts_with(ON_DEMAND_PREFIX) {
+                let data = self.get(url, &object_path(&version.key, None)).await?;
+                self.store(&version.key, &data, Some(version.version))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_newer(&self, version: &KeyVersion) -> bool {
+        self.index
+            .lock()
+            .unwrap()
+            .get(&version.key)
+            .is_none_or(|mine| version.version > mine.version)
+    }
+
+    /// GET `path` from the peer at `url`, checking it answered with the
+    /// library key
+    async fn get(&self, url: &str, path: &str) -> Result<Vec<u8>, CloudHomeError> {
+        let resp = self
+            .client
+            .get(format!("{url}{path}"))
+            .header(
+                AUTH_HEADER,
+                request_auth(&self.secret, path, now_ms() / 1000),
+            )
+            .send()
+            .await
+            .map_err(storage_error)?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(CloudHomeError::NotFound(path.to_string()));
+        }
+        if !resp.status().is_success() {
+            return Err(storage_error(format!("{url}{path}: {}", resp.status())));
+        }
+
+        let mac = resp
+            .headers()
+            .get(MAC_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| hex::decode(value).ok())
+            .unwrap_or_default();
+        let body = resp.bytes().await.map_err(storage_error)?.to_vec();
+        if !hmac_verify(&self.secret, &response_message(path, &body), &mac) {
+            return Err(storage_error(format!(
+                "{url} answered without the library key"
+            )));
+        }
+        Ok(body)
+    }
+
+    /// Path of `key` in the replica. Keys come from peers too, so any that
+    /// could point outside it, or at the index, are refused.
+    fn path(&self, key: &str) -> Result<PathBuf, CloudHomeError> {
+        let valid = key
+            .split('/')
+            .all(|part| !part.is_empty() && !part.starts_with('.') && !part.contains('\\'));
+        if valid {
+            Ok(self.root.join(key))
+        } else {
+            Err(storage_error(format!("invalid key: {key}")))
+        }
+    }
+
+    /// Version a change to `key` gets: `version` if it's newer than the one
+    /// in the index, or for local changes (None) one newer than both it and now
+    fn next_version(&self, key: &str, version: Option<u64>) -> Option<u64> {
+        let current = self.index.lock().unwrap().get(key).map(|v| v.version);
+        match version {
+            Some(version) if current.is_some_and(|current| current >= version) => None,
+            Some(version) => Some(version),
+            None => Some(now_ms().max(current.map_or(0, |c| c + 1))),
+        }
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        data: &[u8],
+        version: Option<u64>,
+    ) -> Result<(), CloudHomeError> {
+        let path = self.path(key)?;
+        let _guard = self.write_lock.lock().await;
+        let Some(version) = self.next_version(key, version) else {
+            return Ok(());
+        };
+
+        write_atomic(&path, data).await?;
+        self.index.lock().unwrap().insert(
+            key.to_string(),
+            KeyVersion {
+                key: key.to_string(),
+                version,
+                deleted: false,
+            },
+        );
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str, version: Option<u64>) -> Result<(), CloudHomeError> {
+        let path = self.path(key)?;
+        let _guard = self.write_lock.lock().await;
+        let Some(version) = self.next_version(key, version) else {
+            return Ok(());
+        };
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(CloudHomeError::Io(e)),
+        }
+        self.index.lock().unwrap().insert(
+            key.to_string(),
+            KeyVersion {
+                key: key.to_string(),
+                version,
+                deleted: true,
+            },
+        );
+        Ok(())
+    }
+
+    async fn save_index(&self) -> Result<(), CloudHomeError> {
+        let versions: Vec<KeyVersion> = self.index.lock().unwrap().values().cloned().collect();
+        let data = serde_json::to_vec(&versions).map_err(storage_error)?;
+        write_atomic(&self.root.join(INDEX_FILE), &data).await
+    }
+
+    async fn read_local(&self, key: &str) -> Result<Vec<u8>, CloudHomeError> {
+        let path = self.path(key)?;
+        tokio::fs::read(&path)
+            .await
+            .map_err(|e| not_found_or_io(key, e))
+    }
+
+    async fn read_local_range(
+        &self,
+        key: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u8>, CloudHomeError> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let path = self.path(key)?;
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|e| not_found_or_io(key, e))?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        let mut buf = vec![0u8; end.saturating_sub(start) as usize];
+        file.read_exact(&mut buf).await?;
+        Ok(buf)
+    }
+
+    /// Read `key` from the first peer that has it
+    async fn read_from_peers(
+        &self,
+        key: &str,
+        range: Option<(u64, u64)>,
+    ) -> Result<Vec<u8>, CloudHomeError> {
+        let urls: Vec<String> = self
+            .peers
+            .read()
+            .unwrap()
+            .values()
+            .filter(|peer| peer.keys.iter().any(|v| v.key == key && !v.deleted))
+            .map(|peer| peer.url.clone())
+            .collect();
+        for url in urls {
+            match self.get(&url, &object_path(key, range)).await {
+                Ok(data) => return Ok(data),
+                Err(e) => debug!("Reading {key} from {url} failed: {e}"),
+            }
+        }
+        Err(CloudHomeError::NotFound(key.to_string()))
+    }
+
+    /// The newest version of every key, here or on a peer
+    fn known_versions(&self) -> BTreeMap<String, KeyVersion> {
+        let mut known = self.index.lock().unwrap().clone();
+        for peer in self.peers.read().unwrap().values() {
+            for version in &peer.keys {
+                if known
+                    .get(&version.key)
+                    .is_none_or(|v| version.version > v.version)
+                {
+                    known.insert(version.key.clone(), version.clone());
+                }
+            }
+        }
+        known
+    }
+
+    fn authorized(&self, uri: &Uri, headers: &HeaderMap) -> bool {
+        let Some((timestamp, mac)) = headers
+            .get(AUTH_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(':'))
+        else {
+            return false;
+        };
+        let (Ok(timestamp), Ok(mac)) = (timestamp.parse::<u64>(), hex::decode(mac)) else {
+            return false;
+        };
+        let path = uri.path_and_query().map_or("", |p| p.as_str());
+
+        (now_ms() / 1000).abs_diff(timestamp) <= MAX_REQUEST_AGE_SECS
+            && hmac_verify(
+                &self.secret,
+                format!("{timestamp}\n{path}").as_bytes(),
+                &mac,
+            )
+    }
+
+    fn signed(&self, uri: &Uri, body: Vec<u8>) -> Response {
+        let path = uri.path_and_query().map_or("", |p| p.as_str());
+        let mac = hex::encode(hmac_sign(&self.secret, &response_message(path, &body)));
+        ([(MAC_HEADER, mac)], body).into_response()
+    }
+}
+
+fn router(node: Arc<LanNode>) -> Router {
+    Router::new()
+        .route("/lan/keys", get(serve_keys))
+        .route("/lan/object/*key", get(serve_object))
+        .with_state(node)
+}
+
+#[derive(Deserialize)]
+struct RangeQuery {
+    start: Option<u64>,
+    end: Option<u64>,
+}
+
+async fn serve_keys(State(node): State<Arc<LanNode>>, uri: Uri, headers: HeaderMap) -> Response {
+    if !node.authorized(&uri, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let versions: Vec<KeyVersion> = node.index.lock().unwrap().values().cloned().collect();
+    match serde_json::to_vec(&versions) {
+        Ok(body) => node.signed(&uri, body),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Serves only what this device has, so a read never hops between peers
+async fn serve_object(
+    State(node): State<Arc<LanNode>>,
+    UrlPath(key): UrlPath<String>,
+    Query(range): Query<RangeQuery>,
+    uri: Uri,
+    headers: HeaderMap,
+) -> Response {
+    if !node.authorized(&uri, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let data = match (range.start, range.end) {
+        (Some(start), Some(end)) => node.read_local_range(&key, start, end).await,
+        _ => node.read_local(&key).await,
+    };
+    match data {
+        Ok(body) => node.signed(&uri, body),
+        Err(CloudHomeError::NotFound(_)) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[async_trait]
+impl CloudHome for LanCloudHome {
+    async fn write(&self, key: &str, data: Vec<u8>) -> Result<(), CloudHomeError> {
+        self.node.store(key, &data, None).await?;
+        self.node.save_index().await
+    }
+
+    async fn read(&self, key: &str) -> Result<Vec<u8>, CloudHomeError> {
+        match self.node.read_local(key).await {
+            Err(CloudHomeError::NotFound(_)) => self.node.read_from_peers(key, None).await,
+            result => result,
+        }
+    }
+
+    async fn read_range(&self, key: &str, start: u64, end: u64) -> Result<Vec<u8>, CloudHomeError> {
+        match self.node.read_local_range(key, start, end).await {
+            Err(CloudHomeError::NotFound(_)) => {
+                self.node.read_from_peers(key, Some((start, end))).await
+            }
+            result => result,
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, CloudHomeError> {
+        Ok(self
+            .node
+            .known_versions()
+            .into_values()
+            .filter(|v| !v.deleted && v.key.starts_with(prefix))
+            .map(|v| v.key)
+            .collect())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CloudHomeError> {
+        self.node.remove(key, None).await?;
+        self.node.save_index().await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, CloudHomeError> {
+        Ok(self
+            .node
+            .known_versions()
+            .get(key)
+            .is_some_and(|v| !v.deleted))
+    }
+
+    async fn grant_access(&self, _member_id: &str) -> Result<JoinInfo, CloudHomeError> {
+        Err(CloudHomeError::Storage(
+            "devices on the network sync once they have the library; restore a bundle of it \
+             on the other device"
+                .to_string(),
+        ))
+    }
+
+    async fn revoke_access(&self, _member_id: &str) -> Result<(), CloudHomeError> {
+        // Peers are recognized by the library key, so only rotating it revokes
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn node(dir: &TempDir, device_id: &str, secret: [u8; 32]) -> Arc<LanNode> {
+        Arc::new(
+            LanNode::open(dir.path().to_path_buf(), device_id, secret)
+                .await
+                .unwrap(),
+        )
+    }
+
+    async fn serve(node: Arc<LanNode>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(node)).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn pulls_newer_keys_and_deletions() {
+        let (dir_a, dir_b) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let a = node(&dir_a, "dev-a", [7; 32]).await;
+        let b = node(&dir_b, "dev-b", [7; 32]).await;
+        let url_a = serve(a.clone()).await;
+
+        a.store("changes/dev-a/1.enc", b"one", None).await.unwrap();
+        a.store("snapshot.db.enc", b"older", None).await.unwrap();
+        a.store("storage/ab/cd/file-1", b"audio", None)
+            .await
+            .unwrap();
+        b.store("snapshot.db.enc", b"newer", None).await.unwrap();
+        b.pull_from(&url_a).await.unwrap();
+
+        assert_eq!(b.read_local("changes/dev-a/1.enc").await.unwrap(), b"one");
+        assert_eq!(b.read_local("snapshot.db.enc").await.unwrap(), b"newer");
+        // Release files stay where they were stored and are read from there
+        assert!(matches!(
+            b.read_local("storage/ab/cd/file-1").await,
+            Err(CloudHomeError::NotFound(_))
+        ));
+        let path = object_path("storage/ab/cd/file-1", Some((1, 3)));
+        assert_eq!(b.get(&url_a, &path).await.unwrap(), b"ud");
+
+        a.remove("changes/dev-a/1.enc", None).await.unwrap();
+        b.pull_from(&url_a).await.unwrap();
+        assert!(!dir_b.path().join("changes/dev-a/1.enc").exists());
+
+        // Versions survive a restart through the saved index
+        let reopened = node(&dir_b, "dev-b", [7; 32]).await;
+        let deleted = reopened.index.lock().unwrap()["changes/dev-a/1.enc"].clone();
+        assert!(deleted.deleted);
+        assert!(!reopened.is_newer(&KeyVersion {
+            version: deleted.version - 1,
+            deleted: false,
+            ..deleted
+        }));
+    }
+
+    #[tokio::test]
+    async fn refuses_devices_without_the_library_key() {
+        let (dir_a, dir_b) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let a = node(&dir_a, "dev-a", [7; 32]).await;
+        let stranger = node(&dir_b, "dev-x", [8; 32]).await;
+        let url_a = serve(a.clone()).await;
+        a.store("keys/member.enc", b"wrapped", None).await.unwrap();
+
+        assert!(stranger.pull_from(&url_a).await.is_err());
+        assert!(stranger.index.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn keys_cant_leave_the_replica() {
+        let dir = TempDir::new().unwrap();
+        let node = node(&dir, "dev-a", [7; 32]).await;
+
+        assert_eq!(
+            node.path("changes/dev/1.enc").unwrap(),
+            dir.path().join("changes/dev/1.enc")
+        );
+        for key in [
+            "../etc/passwd",
+            "/etc/passwd",
+            "changes//1.enc",
+            ".index.json",
+            "",
+        ] {
+            assert!(node.path(key).is_err(), "{key}");
+        }
+    }
+}
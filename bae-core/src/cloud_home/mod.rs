@@ -6,10 +6,13 @@
 //! any `dyn CloudHome`.
 
 pub mod dropbox;
+pub mod folder;
 pub mod google_drive;
 pub mod http;
 pub mod icloud;
+pub mod lan;
 pub mod onedrive;
+pub mod probe;
 pub mod s3;
 
 use async_trait::async_trait;
@@ -22,6 +25,9 @@ pub enum CloudHomeError {
     NotFound(String),
     #[error("storage error: {0}")]
     Storage(String),
+    /// The object is archived and being restored; try again later
+    #[error("restoring from archive: {0}")]
+    Restoring(String),
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -53,6 +59,30 @@ pub enum JoinInfo {
     },
 }
 
+/// Size of each part of a resumable upload. At least S3's 5 MiB minimum,
+/// and a multiple of both the 256 KiB Google Drive and the 320 KiB OneDrive
+/// require.
+pub const UPLOAD_PART_SIZE: usize = 10 * 1024 * 1024;
+
+/// An upload sent in parts, so an interrupted one can continue where it
+/// stopped. Serializable so it can outlive the process.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UploadSession {
+    /// The backend's handle for the upload: an S3 upload ID, a Dropbox
+    /// session ID, or a OneDrive or Google Drive upload URL
+    pub id: String,
+    /// Bytes the backend has received
+    pub uploaded: u64,
+    /// Receipts for the parts so far, needed to finish (S3 ETags)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<String>,
+}
+
+/// `Content-Range` header for a part of an upload session
+fn content_range(start: u64, len: u64, size: u64) -> String {
+    format!("bytes {}-{}/{}", start, start + len - 1, size)
+}
+
 /// Low-level cloud storage. Implementations handle a single bucket/container.
 ///
 /// All methods deal in raw bytes. No encryption or path layout logic.
@@ -85,6 +115,57 @@ pub trait CloudHome: Send + Sync {
     /// Revoke a previously granted access. No-op for backends where access
     /// is controlled externally (e.g. S3 with pre-shared credentials).
     async fn revoke_access(&self, member_id: &str) -> Result<(), CloudHomeError>;
+
+    /// Start a resumable upload of `size` bytes to `key`. Backends that can't
+    /// resume an upload return `None`, and the data is written whole instead.
+    async fn begin_upload(
+        &self,
+        _key: &str,
+        _size: u64,
+    ) -> Result<Option<UploadSession>, CloudHomeError> {
+        Ok(None)
+    }
+
+    /// Send the next part of an upload, starting at `session.uploaded`.
+    /// `size` is the size of the whole upload.
+    async fn upload_part(
+        &self,
+        key: &str,
+        _session: &mut UploadSession,
+        _data: Vec<u8>,
+        _size: u64,
+    ) -> Result<(), CloudHomeError> {
+        Err(CloudHomeError::Storage(format!(
+            "resumable upload of {key} is not supported"
+        )))
+    }
+
+    /// Commit an upload once every part is in.
+    async fn finish_upload(
+        &self,
+        key: &str,
+        _session: &UploadSession,
+        _size: u64,
+    ) -> Result<(), CloudHomeError> {
+        Err(CloudHomeError::Storage(format!(
+            "resumable upload of {key} is not supported"
+        )))
+    }
+
+    /// Drop an unfinished upload. Not an error if it's already gone.
+    async fn abort_upload(
+        &self,
+        _key: &str,
+        _session: &UploadSession,
+    ) -> Result<(), CloudHomeError> {
+        Ok(())
+    }
+
+    /// Make `key` readable. Backends with archive storage classes start a
+    /// restore of an archived object and return `Restoring` until it's done.
+    async fn prepare_read(&self, _key: &str) -> Result<(), CloudHomeError> {
+        Ok(())
+    }
 }
 
 /// Extract the OAuth token JSON from cloud home credentials, or return a storage error.
@@ -134,7 +215,8 @@ pub async fn create_cloud_home(
             let key_prefix = config.cloud_home_s3_key_prefix.clone();
             let s3 =
                 s3::S3CloudHome::new(bucket, region, endpoint, access_key, secret_key, key_prefix)
-                    .await?;
+                    .await?
+                    .with_audio_storage_class(config.cloud_home_s3_audio_storage_class.clone());
             Ok(Box::new(s3))
         }
         Some(CloudProvider::GoogleDrive) => {
@@ -164,6 +246,15 @@ pub async fn create_cloud_home(
                 std::path::PathBuf::from(path),
             )))
         }
+        Some(CloudProvider::Folder) => {
+            let path = config
+                .cloud_home_folder_path
+                .as_ref()
+                .ok_or_else(|| CloudHomeError::Storage("sync folder not configured".to_string()))?;
+            Ok(Box::new(folder::FolderCloudHome::new(
+                std::path::PathBuf::from(path),
+            )))
+        }
         Some(CloudProvider::Dropbox) => {
             let folder_path = config
                 .cloud_home_dropbox_folder_path
@@ -207,5 +298,17 @@ pub async fn create_cloud_home(
                 .map_err(|e| CloudHomeError::Storage(format!("keypair: {e}")))?;
             Ok(Box::new(http::HttpCloudHome::new(url, keypair)))
         }
+        Some(CloudProvider::Lan) => {
+            let encryption_key = key_service.get_encryption_key().ok_or_else(|| {
+                CloudHomeError::Storage("encryption key not in keyring".to_string())
+            })?;
+            let lan = lan::LanCloudHome::start(
+                config.library_dir.lan_home_dir(),
+                &config.device_id,
+                &encryption_key,
+            )
+            .await?;
+            Ok(Box::new(lan))
+        }
     }
 }
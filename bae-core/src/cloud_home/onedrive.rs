@@ -9,7 +9,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
-use super::{CloudHome, CloudHomeError, JoinInfo};
+use super::{content_range, CloudHome, CloudHomeError, JoinInfo, UploadSession};
 use crate::keys::KeyService;
 use crate::oauth::{self, OAuthConfig, OAuthTokens};
 
@@ -417,6 +417,100 @@ impl CloudHome for OneDriveCloudHome {
 
         Ok(())
     }
+
+    async fn begin_upload(
+        &self,
+        key: &str,
+        _size: u64,
+    ) -> Result<Option<UploadSession>, CloudHomeError> {
+        let url = format!("{}/createUploadSession", self.item_path_url(key));
+        let body = serde_json::json!({
+            "item": { "@microsoft.graph.conflictBehavior": "replace" },
+        });
+
+        let resp = self
+            .api_call(|token| self.client.post(&url).bearer_auth(token).json(&body))
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(CloudHomeError::Storage(format!(
+                "start upload {key} (HTTP {status}): {body}"
+            )));
+        }
+
+        let json: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| CloudHomeError::Storage(format!("parse upload session: {e}")))?;
+        let upload_url = json["uploadUrl"]
+            .as_str()
+            .ok_or_else(|| CloudHomeError::Storage(format!("start upload {key}: no upload URL")))?;
+        Ok(Some(UploadSession {
+            id: upload_url.to_string(),
+            ..Default::default()
+        }))
+    }
+
+    async fn upload_part(
+        &self,
+        key: &str,
+        session: &mut UploadSession,
+        data: Vec<u8>,
+        size: u64,
+    ) -> Result<(), CloudHomeError> {
+        let len = data.len() as u64;
+        // The upload URL carries its own authorization
+        let resp = self
+            .client
+            .put(&session.id)
+            .header("Content-Range", content_range(session.uploaded, len, size))
+            .body(data)
+            .send()
+            .await
+            .map_err(|e| CloudHomeError::Storage(format!("request failed: {e}")))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(CloudHomeError::Storage(format!(
+                "upload part of {key} (HTTP {status}): {body}"
+            )));
+        }
+
+        session.uploaded += len;
+        Ok(())
+    }
+
+    async fn finish_upload(
+        &self,
+        _key: &str,
+        _session: &UploadSession,
+        _size: u64,
+    ) -> Result<(), CloudHomeError> {
+        // The last part commits the file
+        Ok(())
+    }
+
+    async fn abort_upload(&self, key: &str, session: &UploadSession) -> Result<(), CloudHomeError> {
+        let resp = self
+            .client
+            .delete(&session.id)
+            .send()
+            .await
+            .map_err(|e| CloudHomeError::Storage(format!("request failed: {e}")))?;
+
+        let status = resp.status();
+        if !status.is_success() && status != reqwest::StatusCode::NOT_FOUND {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(CloudHomeError::Storage(format!(
+                "abort upload {key} (HTTP {status}): {body}"
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,143 @@
+//! Connection check for a cloud home.
+//!
+//! Writes a small probe object, reads it back whole and in part, then deletes
+//! it, timing each step. Credentials that can write but not read, a bucket in
+//! the wrong region or an endpoint that ignores range requests show up here,
+//! before the settings are saved, rather than partway through an import.
+
+use std::time::{Duration, Instant};
+
+use super::CloudHome;
+
+/// Size of the probe object. Big enough that a range read has something to
+/// cut from, small enough to cost nothing.
+const PROBE_SIZE: usize = 64 * 1024;
+
+/// One operation of the check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProbeOperation {
+    Write,
+    Read,
+    ReadRange,
+    Delete,
+}
+
+impl ProbeOperation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProbeOperation::Write => "Write",
+            ProbeOperation::Read => "Read",
+            ProbeOperation::ReadRange => "Range read",
+            ProbeOperation::Delete => "Delete",
+        }
+    }
+}
+
+/// How one operation went.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbeStep {
+    pub operation: ProbeOperation,
+    pub latency: Duration,
+    /// Why the operation failed, None if it worked
+    pub error: Option<String>,
+}
+
+/// Run the check against `home`. Stops at the first failure, except that a
+/// probe that was written is always deleted.
+pub async fn check_connection(home: &dyn CloudHome) -> Vec<ProbeStep> {
+    let key = format!("probes/{}", uuid::Uuid::new_v4());
+    let data: Vec<u8> = (0..PROBE_SIZE).map(|i| (i % 251) as u8).collect();
+    let mut steps = Vec::new();
+
+    let started = Instant::now();
+    let written = home.write(&key, data.clone()).await;
+    steps.push(step(
+        ProbeOperation::Write,
+        started,
+        written.map_err(|e| e.to_string()),
+    ));
+    if steps[0].error.is_some() {
+        return steps;
+    }
+
+    let started = Instant::now();
+    let read = home.read(&key).await.map_err(|e| e.to_string());
+    let read = read.and_then(|got| matches(&got, &data));
+    let read_ok = read.is_ok();
+    steps.push(step(ProbeOperation::Read, started, read));
+
+    if read_ok {
+        let (start, end) = (1000, 5000);
+        let started = Instant::now();
+        let range = home.read_range(&key, start, end).await;
+        let range = range
+            .map_err(|e| e.to_string())
+            .and_then(|got| matches(&got, &data[start as usize..end as usize]));
+        steps.push(step(ProbeOperation::ReadRange, started, range));
+    }
+
+    let started = Instant::now();
+    let deleted = home.delete(&key).await.map_err(|e| e.to_string());
+    steps.push(step(ProbeOperation::Delete, started, deleted));
+    steps
+}
+
+fn step(operation: ProbeOperation, started: Instant, result: Result<(), String>) -> ProbeStep {
+    ProbeStep {
+        operation,
+        latency: started.elapsed(),
+        error: result.err(),
+    }
+}
+
+fn matches(got: &[u8], expected: &[u8]) -> Result<(), String> {
+    if got == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "got back {} bytes that differ from the {} written",
+            got.len(),
+            expected.len()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloud_home::folder::FolderCloudHome;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn working_home_passes_and_keeps_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let home = FolderCloudHome::new(tmp.path().to_path_buf());
+
+        let steps = check_connection(&home).await;
+
+        let operations: Vec<_> = steps.iter().map(|s| s.operation).collect();
+        assert_eq!(
+            operations,
+            vec![
+                ProbeOperation::Write,
+                ProbeOperation::Read,
+                ProbeOperation::ReadRange,
+                ProbeOperation::Delete,
+            ]
+        );
+        assert!(steps.iter().all(|s| s.error.is_none()));
+        assert!(home.list("probes/").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_write_stops_the_check() {
+        let tmp = TempDir::new().unwrap();
+        let home = FolderCloudHome::new(tmp.path().join("unplugged"));
+
+        let steps = check_connection(&home).await;
+
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].operation, ProbeOperation::Write);
+        assert!(steps[0].error.as_ref().unwrap().contains("not available"));
+    }
+}
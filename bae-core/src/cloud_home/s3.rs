@@ -2,13 +2,32 @@
 //!
 //! Wraps `aws-sdk-s3` to provide raw storage operations against any
 //! S3-compatible endpoint.
+//!
+//! Release files (keys under `storage/`) can go to a cheaper storage class
+//! than the rest of the cloud home, such as `STANDARD_IA` or `GLACIER_IR`.
+//! Changesets, snapshots and images are read often and stay in `STANDARD`.
+//! Objects in an archive class (`GLACIER`, `DEEP_ARCHIVE`) have to be
+//! restored before they can be read, which takes hours; `prepare_read` starts
+//! the restore and reports `Restoring` until the copy is available.
 
 use async_trait::async_trait;
 use aws_config::{BehaviorVersion, Region};
 use aws_credential_types::Credentials;
+use aws_sdk_s3::types::{
+    CompletedMultipartUpload, CompletedPart, GlacierJobParameters, RestoreRequest, StorageClass,
+    Tier,
+};
 use aws_sdk_s3::Client;
 
-use super::{CloudHome, CloudHomeError, JoinInfo};
+use crate::server_metrics::server_metrics;
+
+use super::{CloudHome, CloudHomeError, JoinInfo, UploadSession};
+
+/// Prefix of the keys holding release files
+const AUDIO_KEY_PREFIX: &str = "storage/";
+
+/// How long a restored copy of an archived object stays readable
+const RESTORE_DAYS: i32 = 7;
 
 /// S3-backed cloud home.
 pub struct S3CloudHome {
@@ -19,6 +38,8 @@ pub struct S3CloudHome {
     access_key: String,
     secret_key: String,
     key_prefix: Option<String>,
+    /// Storage class for release files, None for the bucket default
+    audio_storage_class: Option<StorageClass>,
 }
 
 impl S3CloudHome {
@@ -54,15 +75,51 @@ impl S3CloudHome {
             access_key,
             secret_key,
             key_prefix,
+            audio_storage_class: None,
         })
     }
 
+    /// Store release files in `class` (an S3 storage class name like
+    /// `STANDARD_IA`) instead of the bucket default
+    pub fn with_audio_storage_class(mut self, class: Option<String>) -> Self {
+        self.audio_storage_class = class.as_deref().map(StorageClass::from);
+        self
+    }
+
+    /// Storage class to write `key` with
+    fn storage_class_for(&self, key: &str) -> Option<StorageClass> {
+        storage_class_for(self.audio_storage_class.as_ref(), key)
+    }
+
     /// Prepend the key prefix (if configured) to produce the full S3 object key.
     fn full_key(&self, key: &str) -> String {
         apply_prefix(self.key_prefix.as_deref(), key)
     }
 }
 
+/// Count a failed S3 request in the server metrics
+fn s3_error(operation: &'static str, message: String) -> CloudHomeError {
+    server_metrics().record_s3_error(operation);
+    CloudHomeError::Storage(message)
+}
+
+/// Only release files go to the audio storage class
+fn storage_class_for(audio_class: Option<&StorageClass>, key: &str) -> Option<StorageClass> {
+    audio_class
+        .filter(|_| key.starts_with(AUDIO_KEY_PREFIX))
+        .cloned()
+}
+
+/// Whether objects in `class` must be restored before they can be read
+fn is_archived(class: &StorageClass) -> bool {
+    matches!(class, StorageClass::Glacier | StorageClass::DeepArchive)
+}
+
+/// Whether an object's `x-amz-restore` header says a restored copy is ready
+fn is_restored(restore: &str) -> bool {
+    restore.contains("ongoing-request=\"false\"")
+}
+
 /// Prepend an optional prefix to a key. Trailing slashes on the prefix are normalized.
 fn apply_prefix(prefix: Option<&str>, key: &str) -> String {
     match prefix {
@@ -79,10 +136,11 @@ impl CloudHome for S3CloudHome {
             .put_object()
             .bucket(&self.bucket)
             .key(&full)
+            .set_storage_class(self.storage_class_for(key))
             .body(data.into())
             .send()
             .await
-            .map_err(|e| CloudHomeError::Storage(format!("put {key}: {e}")))?;
+            .map_err(|e| s3_error("PutObject", format!("put {key}: {e}")))?;
         Ok(())
     }
 
@@ -100,7 +158,7 @@ impl CloudHome for S3CloudHome {
                 if msg.contains("NoSuchKey") || msg.contains("not found") || msg.contains("404") {
                     CloudHomeError::NotFound(key.to_string())
                 } else {
-                    CloudHomeError::Storage(format!("get {key}: {e}"))
+                    s3_error("GetObject", format!("get {key}: {e}"))
                 }
             })?;
 
@@ -108,7 +166,7 @@ impl CloudHome for S3CloudHome {
             .body
             .collect()
             .await
-            .map_err(|e| CloudHomeError::Storage(format!("read body for {key}: {e}")))?
+            .map_err(|e| s3_error("GetObject", format!("read body for {key}: {e}")))?
             .into_bytes()
             .to_vec();
 
@@ -131,7 +189,7 @@ impl CloudHome for S3CloudHome {
                 if msg.contains("NoSuchKey") || msg.contains("not found") || msg.contains("404") {
                     CloudHomeError::NotFound(key.to_string())
                 } else {
-                    CloudHomeError::Storage(format!("get range {key}: {e}"))
+                    s3_error("GetObject", format!("get range {key}: {e}"))
                 }
             })?;
 
@@ -139,7 +197,7 @@ impl CloudHome for S3CloudHome {
             .body
             .collect()
             .await
-            .map_err(|e| CloudHomeError::Storage(format!("read range body for {key}: {e}")))?
+            .map_err(|e| s3_error("GetObject", format!("read range body for {key}: {e}")))?
             .into_bytes()
             .to_vec();
 
@@ -170,7 +228,7 @@ impl CloudHome for S3CloudHome {
             let resp = req
                 .send()
                 .await
-                .map_err(|e| CloudHomeError::Storage(format!("list {prefix}: {e}")))?;
+                .map_err(|e| s3_error("ListObjectsV2", format!("list {prefix}: {e}")))?;
 
             for obj in resp.contents() {
                 if let Some(key) = obj.key() {
@@ -200,7 +258,7 @@ impl CloudHome for S3CloudHome {
             .key(&full)
             .send()
             .await
-            .map_err(|e| CloudHomeError::Storage(format!("delete {key}: {e}")))?;
+            .map_err(|e| s3_error("DeleteObject", format!("delete {key}: {e}")))?;
         Ok(())
     }
 
@@ -224,7 +282,7 @@ impl CloudHome for S3CloudHome {
                 {
                     Ok(false)
                 } else {
-                    Err(CloudHomeError::Storage(format!("head {key}: {e}")))
+                    Err(s3_error("HeadObject", format!("head {key}: {e}")))
                 }
             }
         }
@@ -247,6 +305,158 @@ impl CloudHome for S3CloudHome {
         // S3 access is managed externally (IAM/pre-shared credentials).
         Ok(())
     }
+
+    async fn begin_upload(
+        &self,
+        key: &str,
+        _size: u64,
+    ) -> Result<Option<UploadSession>, CloudHomeError> {
+        let resp = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(self.full_key(key))
+            .set_storage_class(self.storage_class_for(key))
+            .send()
+            .await
+            .map_err(|e| s3_error("CreateMultipartUpload", format!("start upload {key}: {e}")))?;
+        let id = resp
+            .upload_id()
+            .ok_or_else(|| CloudHomeError::Storage(format!("start upload {key}: no upload ID")))?;
+        Ok(Some(UploadSession {
+            id: id.to_string(),
+            ..Default::default()
+        }))
+    }
+
+    async fn upload_part(
+        &self,
+        key: &str,
+        session: &mut UploadSession,
+        data: Vec<u8>,
+        _size: u64,
+    ) -> Result<(), CloudHomeError> {
+        let len = data.len() as u64;
+        let resp = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(self.full_key(key))
+            .upload_id(&session.id)
+            .part_number(session.parts.len() as i32 + 1)
+            .body(data.into())
+            .send()
+            .await
+            .map_err(|e| s3_error("UploadPart", format!("upload part of {key}: {e}")))?;
+        let etag = resp
+            .e_tag()
+            .ok_or_else(|| CloudHomeError::Storage(format!("upload part of {key}: no ETag")))?;
+        session.parts.push(etag.to_string());
+        session.uploaded += len;
+        Ok(())
+    }
+
+    async fn finish_upload(
+        &self,
+        key: &str,
+        session: &UploadSession,
+        _size: u64,
+    ) -> Result<(), CloudHomeError> {
+        let parts = session
+            .parts
+            .iter()
+            .enumerate()
+            .map(|(i, etag)| {
+                CompletedPart::builder()
+                    .part_number(i as i32 + 1)
+                    .e_tag(etag)
+                    .build()
+            })
+            .collect();
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(self.full_key(key))
+            .upload_id(&session.id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|e| {
+                s3_error(
+                    "CompleteMultipartUpload",
+                    format!("finish upload {key}: {e}"),
+                )
+            })?;
+        Ok(())
+    }
+
+    async fn abort_upload(&self, key: &str, session: &UploadSession) -> Result<(), CloudHomeError> {
+        let result = self
+            .client
+            .abort_multipart_upload()
+            .bucket(&self.bucket)
+            .key(self.full_key(key))
+            .upload_id(&session.id)
+            .send()
+            .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) if format!("{e:?}").contains("NoSuchUpload") => Ok(()),
+            Err(e) => Err(s3_error(
+                "AbortMultipartUpload",
+                format!("abort upload {key}: {e}"),
+            )),
+        }
+    }
+
+    async fn prepare_read(&self, key: &str) -> Result<(), CloudHomeError> {
+        let full = self.full_key(key);
+        let head = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(&full)
+            .send()
+            .await
+            .map_err(|e| s3_error("HeadObject", format!("head {key}: {e}")))?;
+        if !head.storage_class().is_some_and(is_archived) {
+            return Ok(());
+        }
+        match head.restore() {
+            Some(restore) if is_restored(restore) => return Ok(()),
+            Some(_) => return Err(CloudHomeError::Restoring(key.to_string())),
+            None => {}
+        }
+
+        let job = GlacierJobParameters::builder()
+            .tier(Tier::Standard)
+            .build()
+            .map_err(|e| CloudHomeError::Storage(format!("restore {key}: {e}")))?;
+        let result = self
+            .client
+            .restore_object()
+            .bucket(&self.bucket)
+            .key(&full)
+            .restore_request(
+                RestoreRequest::builder()
+                    .days(RESTORE_DAYS)
+                    .glacier_job_parameters(job)
+                    .build(),
+            )
+            .send()
+            .await;
+        match result {
+            Ok(_) => Err(CloudHomeError::Restoring(key.to_string())),
+            Err(e) if format!("{e:?}").contains("RestoreAlreadyInProgress") => {
+                Err(CloudHomeError::Restoring(key.to_string()))
+            }
+            Err(e) => Err(s3_error("RestoreObject", format!("restore {key}: {e}"))),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -270,4 +480,24 @@ mod tests {
         let key = apply_prefix(Some("libs/abc/"), "heads/dev1.json");
         assert_eq!(key, "libs/abc/heads/dev1.json");
     }
+
+    #[test]
+    fn only_release_files_use_audio_storage_class() {
+        let class = StorageClass::from("GLACIER_IR");
+        assert_eq!(
+            storage_class_for(Some(&class), "storage/ab/cd/abcd1234"),
+            Some(StorageClass::GlacierIr)
+        );
+        assert_eq!(storage_class_for(Some(&class), "images/ab/cd/abcd"), None);
+        assert_eq!(storage_class_for(Some(&class), "changes/dev1/1.enc"), None);
+        assert_eq!(storage_class_for(None, "storage/ab/cd/abcd1234"), None);
+    }
+
+    #[test]
+    fn restore_header_is_parsed() {
+        assert!(is_restored(
+            "ongoing-request=\"false\", expiry-date=\"Fri, 23 Dec 2022 00:00:00 GMT\""
+        ));
+        assert!(!is_restored("ongoing-request=\"true\""));
+    }
 }
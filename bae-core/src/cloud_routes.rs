@@ -1,17 +1,38 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use axum::extract::{Path, Query, State};
-use axum::http::{HeaderMap, StatusCode};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::{HeaderMap, Method, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::routing::get;
-use axum::Router;
+use axum::routing::{get, post};
+use axum::{Json, Router};
 use serde::Deserialize;
-use tracing::warn;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
 
 use crate::cloud_home::{CloudHome, CloudHomeError};
+use crate::keys::verify_signature;
+use crate::sync::bundle_format::{
+    encode_bundle, BundleRequest, MAX_BUNDLE_BYTES, MAX_BUNDLE_FILES,
+};
 
 pub struct CloudRouteState {
     pub cloud_home: Arc<dyn CloudHome>,
+    /// Set when devices may push through this proxy; reads are always open
+    pub writes: Option<CloudWrites>,
+}
+
+/// Lets the owner's other devices write to the cloud home through this
+/// server, the same way they would through bae-proxy.
+pub struct CloudWrites {
+    /// Hex Ed25519 key that writes must be signed with
+    pub owner_pubkey: String,
+    /// Wakes the sync loop when a device finishes a push, so its changesets
+    /// are applied right away instead of on the next timer tick
+    pub on_push: mpsc::Sender<()>,
+    /// Set while the server is in maintenance mode, which turns writes away
+    pub maintenance: Arc<AtomicBool>,
 }
 
 #[derive(Deserialize)]
@@ -27,10 +48,19 @@ struct ShareManifest {
 pub fn create_cloud_router(state: Arc<CloudRouteState>) -> Router {
     Router::new()
         .route("/cloud", get(list_keys))
-        .route("/cloud/*key", get(read_key).head(head_key))
+        .route(
+            "/cloud/*key",
+            get(read_key)
+                .head(head_key)
+                .put(write_key)
+                .delete(delete_key)
+                // Writes are signed, and release files can be large
+                .layer(DefaultBodyLimit::disable()),
+        )
         .route("/share/:share_id/meta", get(share_meta))
         .route("/share/:share_id/manifest", get(share_manifest))
         .route("/share/:share_id/file/*key", get(share_file))
+        .route("/bundle", post(bundle))
         .with_state(state)
 }
 
@@ -41,6 +71,7 @@ fn cloud_error_to_response(err: CloudHomeError) -> Response {
             warn!("Cloud home storage error: {msg}");
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
         }
+        CloudHomeError::Restoring(_) => StatusCode::SERVICE_UNAVAILABLE.into_response(),
         CloudHomeError::Io(err) => {
             warn!("Cloud home I/O error: {err}");
             StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -48,6 +79,85 @@ fn cloud_error_to_response(err: CloudHomeError) -> Response {
     }
 }
 
+/// Maximum allowed clock skew for signed requests (5 minutes).
+const MAX_TIMESTAMP_SKEW_SECS: u64 = 300;
+
+fn unauthorized(msg: &str) -> Response {
+    (StatusCode::UNAUTHORIZED, msg.to_string()).into_response()
+}
+
+/// Check the `X-Bae-*` signature headers of a write, as `HttpCloudHome`
+/// signs them: Ed25519 over "METHOD\nPATH\nTIMESTAMP" by `expected_pubkey_hex`.
+fn verify_signed_request(
+    headers: &HeaderMap,
+    method: &Method,
+    path: &str,
+    expected_pubkey_hex: &str,
+) -> Result<(), Response> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let pubkey_hex =
+        header("x-bae-pubkey").ok_or_else(|| unauthorized("missing X-Bae-Pubkey header"))?;
+    let timestamp_str =
+        header("x-bae-timestamp").ok_or_else(|| unauthorized("missing X-Bae-Timestamp header"))?;
+    let signature_hex =
+        header("x-bae-signature").ok_or_else(|| unauthorized("missing X-Bae-Signature header"))?;
+
+    let timestamp: u64 = timestamp_str
+        .parse()
+        .map_err(|_| unauthorized("invalid timestamp format"))?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    if now.abs_diff(timestamp) > MAX_TIMESTAMP_SKEW_SECS {
+        return Err(unauthorized("timestamp too far from server time"));
+    }
+
+    if pubkey_hex != expected_pubkey_hex {
+        return Err(unauthorized("pubkey does not match this library's owner"));
+    }
+    let public_key: [u8; 32] = hex::decode(pubkey_hex)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| unauthorized("invalid pubkey"))?;
+    let signature: [u8; 64] = hex::decode(signature_hex)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| unauthorized("invalid signature"))?;
+
+    let message = format!("{}\n{}\n{}", method.as_str(), path, timestamp_str);
+    if !verify_signature(&signature, message.as_bytes(), &public_key) {
+        return Err(unauthorized("invalid signature"));
+    }
+    Ok(())
+}
+
+/// The writes config, if the request is a signed write this proxy accepts
+fn authorize_write<'a>(
+    state: &'a CloudRouteState,
+    headers: &HeaderMap,
+    method: &Method,
+    key: &str,
+) -> Result<&'a CloudWrites, Response> {
+    let Some(writes) = &state.writes else {
+        return Err((StatusCode::FORBIDDEN, "this server doesn't accept writes").into_response());
+    };
+    if writes.maintenance.load(Ordering::Relaxed) {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            "this server is in maintenance mode, try again later",
+        )
+            .into_response());
+    }
+    verify_signed_request(
+        headers,
+        method,
+        &format!("/cloud/{key}"),
+        &writes.owner_pubkey,
+    )?;
+    Ok(writes)
+}
+
 /// Parse a `Range: bytes=START-END` header.
 /// Returns (start, end) where both are inclusive, or None if unparseable.
 fn parse_range_header(header: &str) -> Option<(u64, u64)> {
@@ -111,6 +221,48 @@ async fn head_key(State(state): State<Arc<CloudRouteState>>, Path(key): Path<Str
     }
 }
 
+async fn write_key(
+    State(state): State<Arc<CloudRouteState>>,
+    headers: HeaderMap,
+    method: Method,
+    Path(key): Path<String>,
+    body: Bytes,
+) -> Response {
+    let writes = match authorize_write(&state, &headers, &method, &key) {
+        Ok(writes) => writes,
+        Err(response) => return response,
+    };
+
+    if let Err(err) = state.cloud_home.write(&key, body.to_vec()).await {
+        return cloud_error_to_response(err);
+    }
+
+    // A push writes its changesets first and the device head last
+    if key.starts_with("heads/") {
+        info!("Device pushed through the cloud proxy, waking sync");
+
+        // A full channel means a cycle is already queued
+        let _ = writes.on_push.try_send(());
+    }
+    StatusCode::OK.into_response()
+}
+
+async fn delete_key(
+    State(state): State<Arc<CloudRouteState>>,
+    headers: HeaderMap,
+    method: Method,
+    Path(key): Path<String>,
+) -> Response {
+    if let Err(response) = authorize_write(&state, &headers, &method, &key) {
+        return response;
+    }
+
+    match state.cloud_home.delete(&key).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => cloud_error_to_response(err),
+    }
+}
+
 fn cors_headers() -> [(&'static str, &'static str); 1] {
     [("access-control-allow-origin", "*")]
 }
@@ -207,9 +359,46 @@ async fn share_file(
     }
 }
 
+/// Package several files (typically one release's) with a manifest, so a
+/// device can download an album for offline play in one request.
+async fn bundle(
+    State(state): State<Arc<CloudRouteState>>,
+    Json(request): Json<BundleRequest>,
+) -> Response {
+    if request.files.is_empty() || request.files.len() > MAX_BUNDLE_FILES {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("bundle must list 1 to {MAX_BUNDLE_FILES} files"),
+        )
+            .into_response();
+    }
+
+    let mut files = Vec::with_capacity(request.files.len());
+    let mut total = 0u64;
+    for key in request.files {
+        let data = match state.cloud_home.read(&key).await {
+            Ok(data) => data,
+            Err(err) => return cloud_error_to_response(err),
+        };
+        total += data.len() as u64;
+        if total > MAX_BUNDLE_BYTES {
+            return (StatusCode::PAYLOAD_TOO_LARGE, "bundle too large").into_response();
+        }
+        files.push((key, data));
+    }
+
+    (
+        StatusCode::OK,
+        [("content-type", "application/octet-stream")],
+        encode_bundle(&files),
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::keys::UserKeypair;
 
     #[test]
     fn parse_range_valid() {
@@ -226,4 +415,121 @@ mod tests {
         assert_eq!(parse_range_header("bytes="), None);
         assert_eq!(parse_range_header("bytes=100-"), None);
     }
+
+    fn gen_keypair() -> UserKeypair {
+        crate::encryption::ensure_sodium_init();
+        let mut pk = [0u8; crate::sodium_ffi::SIGN_PUBLICKEYBYTES];
+        let mut sk = [0u8; crate::sodium_ffi::SIGN_SECRETKEYBYTES];
+        let ret = unsafe {
+            crate::sodium_ffi::crypto_sign_ed25519_keypair(pk.as_mut_ptr(), sk.as_mut_ptr())
+        };
+        assert_eq!(ret, 0);
+        UserKeypair {
+            signing_key: sk,
+            public_key: pk,
+        }
+    }
+
+    fn signed_headers(
+        keypair: &UserKeypair,
+        method: &str,
+        path: &str,
+        timestamp: u64,
+    ) -> HeaderMap {
+        let message = format!("{method}\n{path}\n{timestamp}");
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-bae-pubkey",
+            hex::encode(keypair.public_key).parse().unwrap(),
+        );
+        headers.insert("x-bae-timestamp", timestamp.to_string().parse().unwrap());
+        headers.insert(
+            "x-bae-signature",
+            hex::encode(keypair.sign(message.as_bytes()))
+                .parse()
+                .unwrap(),
+        );
+        headers
+    }
+
+    fn now() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn signed_write_by_owner_is_accepted() {
+        let owner = gen_keypair();
+        let path = "/cloud/heads/dev1.json.enc";
+        let headers = signed_headers(&owner, "PUT", path, now());
+
+        let owner_hex = hex::encode(owner.public_key);
+        assert!(verify_signed_request(&headers, &Method::PUT, path, &owner_hex).is_ok());
+        // The signature covers the method and path
+        assert!(verify_signed_request(&headers, &Method::DELETE, path, &owner_hex).is_err());
+        assert!(verify_signed_request(
+            &headers,
+            &Method::PUT,
+            "/cloud/heads/dev2.json.enc",
+            &owner_hex
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn write_by_someone_else_or_stale_is_refused() {
+        let owner = gen_keypair();
+        let other = gen_keypair();
+        let path = "/cloud/changes/dev1/1.enc";
+        let owner_hex = hex::encode(owner.public_key);
+
+        let headers = signed_headers(&other, "PUT", path, now());
+        assert!(verify_signed_request(&headers, &Method::PUT, path, &owner_hex).is_err());
+
+        let headers = signed_headers(&owner, "PUT", path, now() - 3600);
+        assert!(verify_signed_request(&headers, &Method::PUT, path, &owner_hex).is_err());
+
+        assert!(verify_signed_request(&HeaderMap::new(), &Method::PUT, path, &owner_hex).is_err());
+    }
+
+    #[tokio::test]
+    async fn writes_are_turned_away_in_maintenance_mode() {
+        let owner = gen_keypair();
+        let dir = tempfile::tempdir().unwrap();
+        let (on_push, mut pushed) = mpsc::channel(1);
+        let maintenance = Arc::new(AtomicBool::new(true));
+        let state = Arc::new(CloudRouteState {
+            cloud_home: Arc::new(crate::cloud_home::folder::FolderCloudHome::new(
+                dir.path().to_path_buf(),
+            )),
+            writes: Some(CloudWrites {
+                owner_pubkey: hex::encode(owner.public_key),
+                on_push,
+                maintenance: maintenance.clone(),
+            }),
+        });
+        let key = "heads/dev1.json.enc";
+        let push = || {
+            write_key(
+                State(state.clone()),
+                signed_headers(&owner, "PUT", &format!("/cloud/{key}"), now()),
+                Method::PUT,
+                Path(key.to_string()),
+                Bytes::from_static(b"head"),
+            )
+        };
+
+        let response = push().await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!dir.path().join(key).exists());
+        assert!(pushed.try_recv().is_err());
+
+        maintenance.store(false, Ordering::Relaxed);
+        let response = push().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(dir.path().join(key).exists());
+        assert!(pushed.try_recv().is_ok());
+    }
 }
@@ -1,11 +1,16 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use aws_config::{BehaviorVersion, Region};
 use aws_credential_types::Credentials;
 use aws_sdk_s3::{primitives::ByteStreamError, Client, Error as S3Error};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 use tracing::{debug, error, info, warn};
+
+use crate::cloud_home::{CloudHome, CloudHomeError, UploadSession, UPLOAD_PART_SIZE};
+use crate::retry::retry_with_backoff;
 #[derive(Error, Debug)]
 pub enum CloudStorageError {
     #[error("S3 error: {0}")]
@@ -20,6 +25,8 @@ pub enum CloudStorageError {
     Config(String),
     #[error("Download error: {0}")]
     Download(String),
+    #[error("Restoring from archive: {0}")]
+    Restoring(String),
 }
 /// S3 configuration for cloud storage
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +76,11 @@ pub trait CloudStorage: Send + Sync {
         end: u64,
     ) -> Result<Vec<u8>, CloudStorageError>;
     async fn delete(&self, storage_location: &str) -> Result<(), CloudStorageError>;
+    /// Make an object readable, returning `Restoring` while an archived
+    /// object is being restored
+    async fn prepare_read(&self, _storage_location: &str) -> Result<(), CloudStorageError> {
+        Ok(())
+    }
 }
 /// Format AWS SDK error for better debugging
 fn format_error_details(err: &dyn std::fmt::Debug) -> String {
@@ -350,19 +362,155 @@ impl CloudStorage for S3CloudStorage {
 /// Used for followed library playback: the follower accesses audio files
 /// through the cloud home (e.g. HttpCloudHome via bae-proxy) using the
 /// CloudStorage interface that the playback system expects.
+///
+/// With upload state, files over `UPLOAD_PART_SIZE` go up in parts on
+/// backends that support it. The session is saved after every part, so
+/// uploading the same bytes again after a failure or a restart continues
+/// from the last part that made it.
 pub struct CloudHomeStorageAdapter {
-    home: Arc<dyn crate::cloud_home::CloudHome>,
+    home: Arc<dyn CloudHome>,
+    upload_state_dir: Option<PathBuf>,
 }
 
+/// An upload in progress, as saved between parts
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedUpload {
+    /// SHA-256 of the bytes being uploaded, so a session is only resumed
+    /// with the data it started with
+    content_hash: String,
+    session: UploadSession,
+}
+
+/// Attempts per part before the upload gives up for now
+const PART_ATTEMPTS: u32 = 3;
+
 impl CloudHomeStorageAdapter {
-    pub fn new(home: Arc<dyn crate::cloud_home::CloudHome>) -> Self {
-        Self { home }
+    pub fn new(home: Arc<dyn CloudHome>) -> Self {
+        Self {
+            home,
+            upload_state_dir: None,
+        }
+    }
+
+    /// Upload large files in parts, keeping their sessions in `dir`
+    pub fn with_upload_state(mut self, dir: PathBuf) -> Self {
+        self.upload_state_dir = Some(dir);
+        self
+    }
+
+    /// Upload `data` in parts. Returns false, having sent nothing, when the
+    /// backend can't resume uploads.
+    async fn upload_in_parts(
+        &self,
+        state_dir: &Path,
+        key: &str,
+        data: &[u8],
+    ) -> Result<bool, CloudHomeError> {
+        let size = data.len() as u64;
+        let content_hash = hex::encode(Sha256::digest(data));
+        let state_path = state_dir.join(format!("{}.json", key.replace('/', "_")));
+
+        let saved = tokio::fs::read(&state_path)
+            .await
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<SavedUpload>(&bytes).ok());
+        let resumed = match saved {
+            Some(saved) if saved.content_hash == content_hash => Some(saved.session),
+            Some(stale) => {
+                let _ = self.home.abort_upload(key, &stale.session).await;
+                None
+            }
+            None => None,
+        };
+
+        if let Some(session) = resumed {
+            info!("Resuming upload of {} at byte {}", key, session.uploaded);
+            match self
+                .send_parts(&state_path, key, data, &content_hash, session.clone())
+                .await
+            {
+                Ok(()) => {
+                    let _ = tokio::fs::remove_file(&state_path).await;
+                    return Ok(true);
+                }
+                // The backend may have expired the session meanwhile
+                Err(e) => {
+                    warn!(
+                        "Couldn't resume the upload of {}, starting over: {}",
+                        key, e
+                    );
+                    let _ = self.home.abort_upload(key, &session).await;
+                }
+            }
+        }
+
+        let Some(session) = self.home.begin_upload(key, size).await? else {
+            return Ok(false);
+        };
+        self.send_parts(&state_path, key, data, &content_hash, session)
+            .await?;
+        let _ = tokio::fs::remove_file(&state_path).await;
+        Ok(true)
+    }
+
+    /// Send the parts of `data` from `session.uploaded` on and commit the
+    /// upload, saving the session to `state_path` after each part
+    async fn send_parts(
+        &self,
+        state_path: &Path,
+        key: &str,
+        data: &[u8],
+        content_hash: &str,
+        mut session: UploadSession,
+    ) -> Result<(), CloudHomeError> {
+        let size = data.len() as u64;
+        if let Some(parent) = state_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        while session.uploaded < size {
+            let start = session.uploaded as usize;
+            let end = (start + UPLOAD_PART_SIZE).min(data.len());
+            let label = format!("Upload of {key} at byte {start}");
+            let sent = retry_with_backoff(PART_ATTEMPTS, &label, || {
+                let mut session = session.clone();
+                async move {
+                    self.home
+                        .upload_part(key, &mut session, data[start..end].to_vec(), size)
+                        .await
+                        .map(|_| session)
+                }
+            })
+            .await?;
+            session = sent;
+
+            let saved = SavedUpload {
+                content_hash: content_hash.to_string(),
+                session: session.clone(),
+            };
+            let json = serde_json::to_vec(&saved)
+                .map_err(|e| CloudHomeError::Storage(format!("save upload session: {e}")))?;
+            tokio::fs::write(state_path, json).await?;
+        }
+
+        self.home.finish_upload(key, &session, size).await
     }
 }
 
 #[async_trait::async_trait]
 impl CloudStorage for CloudHomeStorageAdapter {
     async fn upload(&self, key: &str, data: &[u8]) -> Result<String, CloudStorageError> {
+        if let Some(state_dir) = &self.upload_state_dir {
+            if data.len() > UPLOAD_PART_SIZE
+                && self
+                    .upload_in_parts(state_dir, key, data)
+                    .await
+                    .map_err(|e| CloudStorageError::Download(e.to_string()))?
+            {
+                return Ok(key.to_string());
+            }
+        }
+
         self.home
             .write(key, data.to_vec())
             .await
@@ -395,4 +543,140 @@ impl CloudStorage for CloudHomeStorageAdapter {
             .await
             .map_err(|e| CloudStorageError::Download(e.to_string()))
     }
+
+    async fn prepare_read(&self, key: &str) -> Result<(), CloudStorageError> {
+        self.home.prepare_read(key).await.map_err(|e| match e {
+            CloudHomeError::Restoring(key) => CloudStorageError::Restoring(key),
+            e => CloudStorageError::Download(e.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloud_home::JoinInfo;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Cloud home that takes uploads in parts and can be told to fail them
+    #[derive(Default)]
+    struct PartsHome {
+        objects: Mutex<HashMap<String, Vec<u8>>>,
+        uploads: Mutex<HashMap<String, Vec<u8>>>,
+        /// Offsets of the parts received
+        parts: Mutex<Vec<u64>>,
+        /// Fail every part from this offset on
+        fail_from: Mutex<Option<u64>>,
+    }
+
+    #[async_trait::async_trait]
+    impl CloudHome for PartsHome {
+        async fn write(&self, key: &str, data: Vec<u8>) -> Result<(), CloudHomeError> {
+            self.objects.lock().unwrap().insert(key.to_string(), data);
+            Ok(())
+        }
+        async fn read(&self, key: &str) -> Result<Vec<u8>, CloudHomeError> {
+            self.objects
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .ok_or_else(|| CloudHomeError::NotFound(key.to_string()))
+        }
+        async fn read_range(
+            &self,
+            key: &str,
+            start: u64,
+            end: u64,
+        ) -> Result<Vec<u8>, CloudHomeError> {
+            Ok(self.read(key).await?[start as usize..end as usize].to_vec())
+        }
+        async fn list(&self, _prefix: &str) -> Result<Vec<String>, CloudHomeError> {
+            Ok(vec![])
+        }
+        async fn delete(&self, key: &str) -> Result<(), CloudHomeError> {
+            self.objects.lock().unwrap().remove(key);
+            Ok(())
+        }
+        async fn exists(&self, key: &str) -> Result<bool, CloudHomeError> {
+            Ok(self.objects.lock().unwrap().contains_key(key))
+        }
+        async fn grant_access(&self, _member_id: &str) -> Result<JoinInfo, CloudHomeError> {
+            Err(CloudHomeError::Storage("not shared".to_string()))
+        }
+        async fn revoke_access(&self, _member_id: &str) -> Result<(), CloudHomeError> {
+            Ok(())
+        }
+        async fn begin_upload(
+            &self,
+            _key: &str,
+            _size: u64,
+        ) -> Result<Option<UploadSession>, CloudHomeError> {
+            let id = uuid::Uuid::new_v4().to_string();
+            self.uploads.lock().unwrap().insert(id.clone(), Vec::new());
+            Ok(Some(UploadSession {
+                id,
+                ..Default::default()
+            }))
+        }
+        async fn upload_part(
+            &self,
+            _key: &str,
+            session: &mut UploadSession,
+            data: Vec<u8>,
+            _size: u64,
+        ) -> Result<(), CloudHomeError> {
+            if self
+                .fail_from
+                .lock()
+                .unwrap()
+                .is_some_and(|offset| session.uploaded >= offset)
+            {
+                return Err(CloudHomeError::Storage("connection reset".to_string()));
+            }
+            let mut uploads = self.uploads.lock().unwrap();
+            let upload = uploads
+                .get_mut(&session.id)
+                .ok_or_else(|| CloudHomeError::NotFound(session.id.clone()))?;
+            upload.extend_from_slice(&data);
+            self.parts.lock().unwrap().push(session.uploaded);
+            session.uploaded += data.len() as u64;
+            Ok(())
+        }
+        async fn finish_upload(
+            &self,
+            key: &str,
+            session: &UploadSession,
+            _size: u64,
+        ) -> Result<(), CloudHomeError> {
+            let data = self.uploads.lock().unwrap().remove(&session.id).unwrap();
+            self.objects.lock().unwrap().insert(key.to_string(), data);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn interrupted_upload_resumes_from_the_last_part() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let home = Arc::new(PartsHome::default());
+        let storage = CloudHomeStorageAdapter::new(home.clone())
+            .with_upload_state(temp_dir.path().to_path_buf());
+        let data: Vec<u8> = (0..2 * UPLOAD_PART_SIZE + 100)
+            .map(|i| (i % 251) as u8)
+            .collect();
+
+        *home.fail_from.lock().unwrap() = Some(UPLOAD_PART_SIZE as u64);
+        assert!(storage.upload("storage/ab/cd/f1", &data).await.is_err());
+        assert!(!home.exists("storage/ab/cd/f1").await.unwrap());
+
+        *home.fail_from.lock().unwrap() = None;
+        storage.upload("storage/ab/cd/f1", &data).await.unwrap();
+
+        // The first part went up once, before the failure
+        let part = UPLOAD_PART_SIZE as u64;
+        assert_eq!(*home.parts.lock().unwrap(), vec![0, part, 2 * part]);
+        assert_eq!(home.read("storage/ab/cd/f1").await.unwrap(), data);
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
 }
@@ -1,4 +1,7 @@
-use crate::library_dir::LibraryDir;
+use crate::db::{AlbumFilter, AlbumSortCriterion};
+use crate::encryption::PassphraseKdf;
+use crate::keys::WrappedEncryptionKey;
+use crate::library_dir::{LibraryDir, Manifest};
 use crate::sync::participation::{default_participation, ParticipationMode};
 use rand::prelude::IndexedRandom;
 use serde::{Deserialize, Serialize};
@@ -187,6 +190,10 @@ pub enum CloudProvider {
     Dropbox,
     OneDrive,
     BaeCloud,
+    /// Other devices on the local network, with no bucket at all
+    Lan,
+    /// A directory on a removable drive or network mount
+    Folder,
 }
 
 /// Configuration errors
@@ -204,10 +211,62 @@ fn default_true() -> bool {
     true
 }
 
+/// Whether a setting travels with the library or belongs to one machine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingScope {
+    /// Describes the library itself and follows it to other devices
+    Library,
+    /// Describes this machine (ports, interfaces, keyring hints, local
+    /// paths) and is never taken from another device's config
+    Device,
+}
+
+/// `config.yaml` keys that describe the library. Everything else is
+/// device-scoped, so a newly added setting stays local until it's listed here.
+const LIBRARY_SETTINGS: &[&str] = &[
+    "library_id",
+    "config_version",
+    "library_name",
+    "encryption_key_fingerprint",
+    "cloud_provider",
+    "cloud_home_s3_bucket",
+    "cloud_home_s3_region",
+    "cloud_home_s3_endpoint",
+    "cloud_home_s3_key_prefix",
+    "cloud_home_s3_audio_storage_class",
+    "cloud_home_google_drive_folder_id",
+    "cloud_home_dropbox_folder_path",
+    "cloud_home_onedrive_drive_id",
+    "cloud_home_onedrive_folder_id",
+    "cloud_home_bae_cloud_url",
+    "cloud_home_bae_cloud_username",
+    "share_base_url",
+    "followed_libraries",
+    "import_ignore_patterns",
+    "import_min_audio_duration_secs",
+    "encryption_chunk_size_kib",
+    "encrypt_images",
+    "cloud_prices",
+    "export_name_template",
+];
+
+/// Scope of a `config.yaml` key
+pub fn setting_scope(key: &str) -> SettingScope {
+    if LIBRARY_SETTINGS.contains(&key) {
+        SettingScope::Library
+    } else {
+        SettingScope::Device
+    }
+}
+
 /// YAML config file structure for non-secret settings (per-library)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigYaml {
     pub library_id: String,
+    /// Format version of this file, see `config_migration`. Missing in
+    /// configs written before versioning.
+    #[serde(default)]
+    pub config_version: u32,
     /// Human-readable name for this library
     #[serde(default)]
     pub library_name: Option<String>,
@@ -221,6 +280,9 @@ pub struct ConfigYaml {
     /// Whether a Discogs API key is stored in the keyring (hint flag, avoids keyring read)
     #[serde(default)]
     pub discogs_key_stored: bool,
+    /// Whether a fanart.tv API key is stored in the keyring (hint flag, avoids keyring read)
+    #[serde(default)]
+    pub fanart_key_stored: bool,
     /// Whether an encryption key is stored in the keyring (hint flag, avoids keyring read)
     #[serde(default)]
     pub encryption_key_stored: bool,
@@ -228,6 +290,18 @@ pub struct ConfigYaml {
     /// Used to detect wrong key without attempting decryption.
     #[serde(default)]
     pub encryption_key_fingerprint: Option<String>,
+    /// Set while stored files are being re-encrypted under a new master key.
+    /// The previous key stays in the keyring until this clears.
+    #[serde(default)]
+    pub key_rotation_in_progress: bool,
+    /// The master key wrapped by a FIDO2 security key. When set, the key is
+    /// kept out of the keyring and unlocking needs a tap.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_key: Option<WrappedEncryptionKey>,
+    /// Keep library.db encrypted at rest under a key derived from the master
+    /// key. The file is converted on the next launch after this changes.
+    #[serde(default)]
+    pub encrypt_database: bool,
     pub torrent_bind_interface: Option<String>,
     /// Listening port for incoming torrent connections. None = random port.
     pub torrent_listen_port: Option<u16>,
@@ -266,6 +340,17 @@ pub struct ConfigYaml {
     /// Server username (password stored in keyring)
     #[serde(default)]
     pub server_username: Option<String>,
+    /// Hours between the snapshots a headless server writes to the sync
+    /// bucket (default 24)
+    #[serde(default)]
+    pub server_snapshot_interval_hours: Option<u64>,
+    /// Days a headless server keeps changesets in the sync bucket after one of
+    /// its snapshots covers them (default 30)
+    #[serde(default)]
+    pub server_snapshot_retention_days: Option<u64>,
+    /// Seconds a headless server waits between sync cycles (default 30)
+    #[serde(default)]
+    pub server_sync_interval_secs: Option<u64>,
 
     // Cloud home configuration
     /// Selected cloud provider for the cloud home. None = not configured.
@@ -283,6 +368,10 @@ pub struct ConfigYaml {
     /// S3 key prefix for cloud home (scopes keys under a path, e.g. "libraries/{id}")
     #[serde(default)]
     pub cloud_home_s3_key_prefix: Option<String>,
+    /// S3 storage class for release files, e.g. "STANDARD_IA" or "GLACIER_IR".
+    /// Metadata and images stay in the bucket default. None = bucket default.
+    #[serde(default)]
+    pub cloud_home_s3_audio_storage_class: Option<String>,
     /// Google Drive folder ID for the cloud home
     #[serde(default)]
     pub cloud_home_google_drive_folder_id: Option<String>,
@@ -298,6 +387,9 @@ pub struct ConfigYaml {
     /// iCloud Drive ubiquity container path for cloud home
     #[serde(default)]
     pub cloud_home_icloud_container_path: Option<String>,
+    /// Folder used as the cloud home, e.g. on a USB drive or NAS mount
+    #[serde(default)]
+    pub cloud_home_folder_path: Option<String>,
     /// bae cloud URL for cloud home (e.g. "https://alice.bae.fm")
     #[serde(default)]
     pub cloud_home_bae_cloud_url: Option<String>,
@@ -312,6 +404,103 @@ pub struct ConfigYaml {
     /// Remote servers the user is following (read-only browsing + streaming)
     #[serde(default)]
     pub followed_libraries: Vec<FollowedLibrary>,
+
+    /// File and folder name patterns skipped when scanning imports (e.g. "*.m3u", "@eaDir")
+    #[serde(default)]
+    pub import_ignore_patterns: Vec<String>,
+    /// Audio files shorter than this many seconds are skipped when scanning imports
+    #[serde(default)]
+    pub import_min_audio_duration_secs: Option<u32>,
+    /// SMB or NFS share offered as a folder import source (e.g. "smb://nas.local/Music")
+    #[serde(default)]
+    pub import_network_share_url: Option<String>,
+    /// Username for the import share (password is in the keyring)
+    #[serde(default)]
+    pub import_network_share_username: Option<String>,
+    /// Concurrent range requests per cloud download (default 4)
+    #[serde(default)]
+    pub download_parallelism: Option<usize>,
+    /// How far ahead of playback a cloud stream downloads, in MiB (default 16)
+    #[serde(default)]
+    pub readahead_mib: Option<u32>,
+    /// Plaintext chunk size in KiB for newly encrypted files
+    /// (default 1024 with a cloud provider, 64 otherwise)
+    #[serde(default)]
+    pub encryption_chunk_size_kib: Option<u32>,
+    /// Encrypt cover images and artist photos uploaded to the cloud home
+    /// (default true). Turning it off stores them as plain image files.
+    #[serde(default = "default_true")]
+    pub encrypt_images: bool,
+    /// Move albums kept on this device to the cloud home once they haven't
+    /// been played or changed in this many days (off when unset)
+    #[serde(default)]
+    pub offload_after_days: Option<u32>,
+    /// What the cloud home's provider charges, for the monthly cost estimate
+    /// in settings (no estimate when unset)
+    #[serde(default)]
+    pub cloud_prices: Option<CloudPrices>,
+    /// Path of each track in a full library export, relative to the export
+    /// folder and without the extension. Takes `{artist}`, `{album}`,
+    /// `{year}`, `{disc}`, `{track}` and `{title}`
+    /// (default `{artist}/{album}/{disc}-{track} {title}`).
+    #[serde(default)]
+    pub export_name_template: Option<String>,
+    /// Linked MusicBrainz account (OAuth tokens are in the keyring)
+    #[serde(default)]
+    pub musicbrainz_username: Option<String>,
+    /// MusicBrainz collection kept in sync with the library
+    #[serde(default)]
+    pub musicbrainz_collection_id: Option<String>,
+    /// Pause playback when the computer goes to sleep (default true)
+    #[serde(default = "default_true")]
+    pub pause_on_sleep: bool,
+    /// Pause playback when the screen locks
+    #[serde(default)]
+    pub pause_on_screen_lock: bool,
+    /// Pause playback while the microphone is in use, e.g. during a call (default true)
+    #[serde(default = "default_true")]
+    pub pause_during_calls: bool,
+    /// Lower the volume while another app is playing sound
+    #[serde(default)]
+    pub duck_for_other_audio: bool,
+    /// Replace low-resolution covers as soon as a larger one is found,
+    /// instead of offering it on the album page
+    #[serde(default)]
+    pub auto_upgrade_covers: bool,
+    /// Album order on the library page. Empty means newest first.
+    #[serde(default)]
+    pub library_sort: Vec<AlbumSortCriterion>,
+    /// Filters on the library page's albums
+    #[serde(default)]
+    pub library_filter: AlbumFilter,
+}
+
+impl ConfigYaml {
+    /// Copy of this config with every device setting back at its default,
+    /// for handing the library to another machine.
+    pub fn library_settings_only(&self) -> Result<ConfigYaml, ConfigError> {
+        let mut settings = self.to_mapping()?;
+        settings.retain(|key, _| {
+            key.as_str()
+                .is_some_and(|key| setting_scope(key) == SettingScope::Library)
+        });
+        Self::from_mapping(settings)
+    }
+
+    fn to_mapping(&self) -> Result<serde_yaml::Mapping, ConfigError> {
+        match serde_yaml::to_value(self) {
+            Ok(serde_yaml::Value::Mapping(mapping)) => Ok(mapping),
+            Ok(_) => Err(ConfigError::Serialization(
+                "config did not serialize to a mapping".to_string(),
+            )),
+            Err(e) => Err(ConfigError::Serialization(e.to_string())),
+        }
+    }
+
+    fn from_mapping(mapping: serde_yaml::Mapping) -> Result<ConfigYaml, ConfigError> {
+        serde_yaml::from_value(serde_yaml::Value::Mapping(mapping))
+            .map_err(|e| ConfigError::Serialization(e.to_string()))
+    }
 }
 
 /// A remote library the user is "following" (read-only sync + streaming).
@@ -326,6 +515,23 @@ pub struct FollowedLibrary {
     pub proxy_url: String,
 }
 
+/// A cloud provider's price table, in the currency the bill comes in
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CloudPrices {
+    /// Per GB kept for a month
+    #[serde(default)]
+    pub storage_per_gb_month: f64,
+    /// Per GB read back out of the cloud home
+    #[serde(default)]
+    pub download_per_gb: f64,
+    /// Per GB sent to the cloud home
+    #[serde(default)]
+    pub upload_per_gb: f64,
+    /// Per 1,000 requests of any kind
+    #[serde(default)]
+    pub per_1000_requests: f64,
+}
+
 /// Metadata about a discovered library (for the library switcher UI)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LibraryInfo {
@@ -347,10 +553,18 @@ pub struct Config {
     pub keys_migrated: bool,
     /// Whether a Discogs API key is stored (hint flag, avoids keyring read on settings render)
     pub discogs_key_stored: bool,
+    /// Whether a fanart.tv API key is stored (hint flag, avoids keyring read on settings render)
+    pub fanart_key_stored: bool,
     /// Whether an encryption key is stored (hint flag, avoids keyring read on settings render)
     pub encryption_key_stored: bool,
     /// SHA-256 fingerprint of the encryption key (detects wrong key without decryption)
     pub encryption_key_fingerprint: Option<String>,
+    /// Whether stored files are still being re-encrypted after a key rotation
+    pub key_rotation_in_progress: bool,
+    /// Master key wrapped by a security key, in place of the keyring entry
+    pub security_key: Option<WrappedEncryptionKey>,
+    /// Whether library.db is encrypted at rest on this device
+    pub encrypt_database: bool,
     pub torrent_bind_interface: Option<String>,
     pub torrent_listen_port: Option<u16>,
     pub torrent_enable_upnp: bool,
@@ -369,6 +583,12 @@ pub struct Config {
     pub server_auth_enabled: bool,
     /// Server username (password stored in keyring)
     pub server_username: Option<String>,
+    /// Hours between the snapshots a headless server writes
+    pub server_snapshot_interval_hours: u64,
+    /// Days a headless server keeps changesets its snapshots cover
+    pub server_snapshot_retention_days: u64,
+    /// Seconds between a headless server's sync cycles
+    pub server_sync_interval_secs: u64,
     /// Selected cloud provider for the cloud home. None = not configured.
     pub cloud_provider: Option<CloudProvider>,
     /// S3 bucket name for cloud home
@@ -379,6 +599,8 @@ pub struct Config {
     pub cloud_home_s3_endpoint: Option<String>,
     /// S3 key prefix for cloud home (scopes keys under a path)
     pub cloud_home_s3_key_prefix: Option<String>,
+    /// S3 storage class for release files (None = bucket default)
+    pub cloud_home_s3_audio_storage_class: Option<String>,
     /// Google Drive folder ID for the cloud home
     pub cloud_home_google_drive_folder_id: Option<String>,
     /// Dropbox folder path for the cloud home
@@ -389,6 +611,8 @@ pub struct Config {
     pub cloud_home_onedrive_folder_id: Option<String>,
     /// iCloud Drive ubiquity container path for cloud home
     pub cloud_home_icloud_container_path: Option<String>,
+    /// Folder used as the cloud home, e.g. on a USB drive or NAS mount
+    pub cloud_home_folder_path: Option<String>,
     /// bae cloud URL for cloud home
     pub cloud_home_bae_cloud_url: Option<String>,
     /// bae cloud username (for display in settings)
@@ -397,6 +621,46 @@ pub struct Config {
     pub share_base_url: Option<String>,
     /// Remote servers the user is following
     pub followed_libraries: Vec<FollowedLibrary>,
+    /// File and folder name patterns skipped when scanning imports
+    pub import_ignore_patterns: Vec<String>,
+    /// Audio files shorter than this many seconds are skipped when scanning imports
+    pub import_min_audio_duration_secs: Option<u32>,
+    /// SMB or NFS share offered as a folder import source
+    pub import_network_share_url: Option<String>,
+    /// Username for the import share
+    pub import_network_share_username: Option<String>,
+    /// Concurrent range requests per cloud download
+    pub download_parallelism: Option<usize>,
+    /// MiB a cloud stream downloads ahead of playback
+    pub readahead_mib: Option<u32>,
+    /// Plaintext chunk size in KiB for newly encrypted files
+    pub encryption_chunk_size_kib: Option<u32>,
+    /// Whether images uploaded to the cloud home are encrypted
+    pub encrypt_images: bool,
+    /// Days without a play before an album moves to the cloud home
+    pub offload_after_days: Option<u32>,
+    /// Provider price table for the cloud cost estimate
+    pub cloud_prices: Option<CloudPrices>,
+    /// Track path template for full library exports
+    pub export_name_template: Option<String>,
+    /// Linked MusicBrainz account, None when not linked
+    pub musicbrainz_username: Option<String>,
+    /// MusicBrainz collection kept in sync with the library
+    pub musicbrainz_collection_id: Option<String>,
+    /// Pause playback when the computer goes to sleep
+    pub pause_on_sleep: bool,
+    /// Pause playback when the screen locks
+    pub pause_on_screen_lock: bool,
+    /// Pause playback while the microphone is in use
+    pub pause_during_calls: bool,
+    /// Lower the volume while another app is playing sound
+    pub duck_for_other_audio: bool,
+    /// Replace low-resolution covers without asking
+    pub auto_upgrade_covers: bool,
+    /// Album order on the library page, empty for newest first
+    pub library_sort: Vec<AlbumSortCriterion>,
+    /// Filters on the library page's albums
+    pub library_filter: AlbumFilter,
 }
 
 impl Config {
@@ -441,6 +705,14 @@ impl Config {
             config.discogs_key_stored = true;
         }
 
+        if std::env::var("BAE_FANART_API_KEY")
+            .ok()
+            .filter(|k| !k.is_empty())
+            .is_some()
+        {
+            config.fanart_key_stored = true;
+        }
+
         if let Some(v) = std::env::var("BAE_TORRENT_BIND_INTERFACE")
             .ok()
             .filter(|s| !s.is_empty())
@@ -527,8 +799,19 @@ impl Config {
             )
         });
 
+        Self::load_for_library(library_dir)
+    }
+
+    /// Load the settings of the library in `library_dir`, whether or not it
+    /// is the active one.
+    pub fn load_for_library(library_dir: LibraryDir) -> Self {
         // Read library-specific config — must exist with library_id (first-run flow creates it)
         let config_path = library_dir.config_path();
+        if config_path.exists() {
+            if let Err(e) = crate::config_migration::migrate_config_file(&config_path) {
+                warn!("Failed to migrate {}: {}", config_path.display(), e);
+            }
+        }
         let yaml_config: ConfigYaml =
             serde_yaml::from_str(&std::fs::read_to_string(&config_path).unwrap_or_else(|e| {
                 panic!(
@@ -564,8 +847,12 @@ impl Config {
             library_name: yaml_config.library_name,
             keys_migrated: yaml_config.keys_migrated,
             discogs_key_stored: yaml_config.discogs_key_stored,
+            fanart_key_stored: yaml_config.fanart_key_stored,
             encryption_key_stored: yaml_config.encryption_key_stored,
             encryption_key_fingerprint: yaml_config.encryption_key_fingerprint,
+            key_rotation_in_progress: yaml_config.key_rotation_in_progress,
+            security_key: yaml_config.security_key,
+            encrypt_database: yaml_config.encrypt_database,
             torrent_bind_interface: yaml_config.torrent_bind_interface,
             torrent_listen_port: yaml_config.torrent_listen_port,
             torrent_enable_upnp: yaml_config.torrent_enable_upnp,
@@ -583,20 +870,86 @@ impl Config {
                 .unwrap_or_else(|| "127.0.0.1".to_string()),
             server_auth_enabled: yaml_config.server_auth_enabled,
             server_username: yaml_config.server_username,
+            server_snapshot_interval_hours: yaml_config
+                .server_snapshot_interval_hours
+                .unwrap_or(24),
+            server_snapshot_retention_days: yaml_config
+                .server_snapshot_retention_days
+                .unwrap_or(30),
+            server_sync_interval_secs: yaml_config.server_sync_interval_secs.unwrap_or(30),
             cloud_provider: yaml_config.cloud_provider,
             cloud_home_s3_bucket: yaml_config.cloud_home_s3_bucket,
             cloud_home_s3_region: yaml_config.cloud_home_s3_region,
             cloud_home_s3_endpoint: yaml_config.cloud_home_s3_endpoint,
             cloud_home_s3_key_prefix: yaml_config.cloud_home_s3_key_prefix,
+            cloud_home_s3_audio_storage_class: yaml_config.cloud_home_s3_audio_storage_class,
             cloud_home_google_drive_folder_id: yaml_config.cloud_home_google_drive_folder_id,
             cloud_home_dropbox_folder_path: yaml_config.cloud_home_dropbox_folder_path,
             cloud_home_onedrive_drive_id: yaml_config.cloud_home_onedrive_drive_id,
             cloud_home_onedrive_folder_id: yaml_config.cloud_home_onedrive_folder_id,
             cloud_home_icloud_container_path: yaml_config.cloud_home_icloud_container_path,
+            cloud_home_folder_path: yaml_config.cloud_home_folder_path,
             cloud_home_bae_cloud_url: yaml_config.cloud_home_bae_cloud_url,
             cloud_home_bae_cloud_username: yaml_config.cloud_home_bae_cloud_username,
             share_base_url: yaml_config.share_base_url,
             followed_libraries: yaml_config.followed_libraries,
+            import_ignore_patterns: yaml_config.import_ignore_patterns,
+            import_min_audio_duration_secs: yaml_config.import_min_audio_duration_secs,
+            import_network_share_url: yaml_config.import_network_share_url,
+            import_network_share_username: yaml_config.import_network_share_username,
+            download_parallelism: yaml_config.download_parallelism,
+            readahead_mib: yaml_config.readahead_mib,
+            encryption_chunk_size_kib: yaml_config.encryption_chunk_size_kib,
+            encrypt_images: yaml_config.encrypt_images,
+            offload_after_days: yaml_config.offload_after_days,
+            cloud_prices: yaml_config.cloud_prices,
+            export_name_template: yaml_config.export_name_template,
+            musicbrainz_username: yaml_config.musicbrainz_username,
+            musicbrainz_collection_id: yaml_config.musicbrainz_collection_id,
+            pause_on_sleep: yaml_config.pause_on_sleep,
+            pause_on_screen_lock: yaml_config.pause_on_screen_lock,
+            pause_during_calls: yaml_config.pause_during_calls,
+            duck_for_other_audio: yaml_config.duck_for_other_audio,
+            auto_upgrade_covers: yaml_config.auto_upgrade_covers,
+            library_sort: yaml_config.library_sort,
+            library_filter: yaml_config.library_filter,
+        }
+    }
+
+    /// Plaintext chunk size for newly encrypted files.
+    ///
+    /// Files bound for a cloud provider default to larger chunks so a range
+    /// read costs fewer requests. Existing files keep their chunk size.
+    pub fn encryption_chunk_size(&self) -> usize {
+        match (self.encryption_chunk_size_kib, &self.cloud_provider) {
+            (Some(kib), _) => kib as usize * 1024,
+            (None, Some(_)) => crate::encryption::CLOUD_CHUNK_SIZE,
+            (None, None) => crate::encryption::CHUNK_SIZE,
+        }
+    }
+
+    /// `encryption` set up to write release files with this library's chunk
+    /// size. An unsupported configured size falls back to the default.
+    pub fn file_encryption(
+        &self,
+        encryption: crate::encryption::EncryptionService,
+    ) -> crate::encryption::EncryptionService {
+        let chunk_size = self.encryption_chunk_size();
+        match encryption.clone().with_chunk_size(chunk_size) {
+            Ok(encryption) => encryption,
+            Err(e) => {
+                warn!("Ignoring encryption_chunk_size_kib: {e}");
+
+                encryption
+            }
+        }
+    }
+
+    /// Ignore rules for the import folder scanner
+    pub fn import_filters(&self) -> crate::import::ImportFilters {
+        crate::import::ImportFilters {
+            ignore_patterns: self.import_ignore_patterns.clone(),
+            min_audio_duration_secs: self.import_min_audio_duration_secs,
         }
     }
 
@@ -628,7 +981,9 @@ impl Config {
                     && has_oauth
             }
             Some(CloudProvider::ICloud) => self.cloud_home_icloud_container_path.is_some(),
+            Some(CloudProvider::Folder) => self.cloud_home_folder_path.is_some(),
             Some(CloudProvider::BaeCloud) => self.cloud_home_bae_cloud_url.is_some(),
+            Some(CloudProvider::Lan) => true,
             None => {
                 // Backwards compat: check S3 fields directly (pre-cloud_provider configs)
                 self.cloud_home_s3_bucket.is_some() && self.cloud_home_s3_region.is_some() && has_s3
@@ -642,11 +997,17 @@ impl Config {
 
     /// Save the active library UUID to the global pointer file (~/.bae/active-library).
     pub fn save_active_library(&self) -> Result<(), ConfigError> {
+        Self::set_active_library(&self.library_id)
+    }
+
+    /// Point `~/.bae/active-library` at a library that has no loaded Config
+    /// yet, such as one just restored from a bundle.
+    pub fn set_active_library(library_id: &str) -> Result<(), ConfigError> {
         let bae_dir = dirs::home_dir()
             .expect("Failed to get home directory")
             .join(".bae");
         std::fs::create_dir_all(&bae_dir)?;
-        std::fs::write(bae_dir.join("active-library"), &self.library_id)?;
+        std::fs::write(bae_dir.join("active-library"), library_id)?;
         Ok(())
     }
 
@@ -654,12 +1015,17 @@ impl Config {
         std::fs::create_dir_all(&*self.library_dir)?;
         let yaml = ConfigYaml {
             library_id: self.library_id.clone(),
+            config_version: crate::config_migration::CONFIG_VERSION,
             library_name: self.library_name.clone(),
             device_id: Some(self.device_id.clone()),
             keys_migrated: self.keys_migrated,
             discogs_key_stored: self.discogs_key_stored,
+            fanart_key_stored: self.fanart_key_stored,
             encryption_key_stored: self.encryption_key_stored,
             encryption_key_fingerprint: self.encryption_key_fingerprint.clone(),
+            key_rotation_in_progress: self.key_rotation_in_progress,
+            security_key: self.security_key.clone(),
+            encrypt_database: self.encrypt_database,
             torrent_bind_interface: self.torrent_bind_interface.clone(),
             torrent_listen_port: self.torrent_listen_port,
             torrent_enable_upnp: self.torrent_enable_upnp,
@@ -675,20 +1041,45 @@ impl Config {
             server_bind_address: Some(self.server_bind_address.clone()),
             server_auth_enabled: self.server_auth_enabled,
             server_username: self.server_username.clone(),
+            server_snapshot_interval_hours: Some(self.server_snapshot_interval_hours),
+            server_snapshot_retention_days: Some(self.server_snapshot_retention_days),
+            server_sync_interval_secs: Some(self.server_sync_interval_secs),
             cloud_provider: self.cloud_provider.clone(),
             cloud_home_s3_bucket: self.cloud_home_s3_bucket.clone(),
             cloud_home_s3_region: self.cloud_home_s3_region.clone(),
             cloud_home_s3_endpoint: self.cloud_home_s3_endpoint.clone(),
             cloud_home_s3_key_prefix: self.cloud_home_s3_key_prefix.clone(),
+            cloud_home_s3_audio_storage_class: self.cloud_home_s3_audio_storage_class.clone(),
             cloud_home_google_drive_folder_id: self.cloud_home_google_drive_folder_id.clone(),
             cloud_home_dropbox_folder_path: self.cloud_home_dropbox_folder_path.clone(),
             cloud_home_onedrive_drive_id: self.cloud_home_onedrive_drive_id.clone(),
             cloud_home_onedrive_folder_id: self.cloud_home_onedrive_folder_id.clone(),
             cloud_home_icloud_container_path: self.cloud_home_icloud_container_path.clone(),
+            cloud_home_folder_path: self.cloud_home_folder_path.clone(),
             cloud_home_bae_cloud_url: self.cloud_home_bae_cloud_url.clone(),
             cloud_home_bae_cloud_username: self.cloud_home_bae_cloud_username.clone(),
             share_base_url: self.share_base_url.clone(),
             followed_libraries: self.followed_libraries.clone(),
+            import_ignore_patterns: self.import_ignore_patterns.clone(),
+            import_min_audio_duration_secs: self.import_min_audio_duration_secs,
+            import_network_share_url: self.import_network_share_url.clone(),
+            import_network_share_username: self.import_network_share_username.clone(),
+            download_parallelism: self.download_parallelism,
+            readahead_mib: self.readahead_mib,
+            encryption_chunk_size_kib: self.encryption_chunk_size_kib,
+            encrypt_images: self.encrypt_images,
+            offload_after_days: self.offload_after_days,
+            cloud_prices: self.cloud_prices.clone(),
+            export_name_template: self.export_name_template.clone(),
+            musicbrainz_username: self.musicbrainz_username.clone(),
+            musicbrainz_collection_id: self.musicbrainz_collection_id.clone(),
+            pause_on_sleep: self.pause_on_sleep,
+            pause_on_screen_lock: self.pause_on_screen_lock,
+            pause_during_calls: self.pause_during_calls,
+            duck_for_other_audio: self.duck_for_other_audio,
+            auto_upgrade_covers: self.auto_upgrade_covers,
+            library_sort: self.library_sort.clone(),
+            library_filter: self.library_filter.clone(),
         };
         std::fs::write(
             self.library_dir.config_path(),
@@ -699,8 +1090,14 @@ impl Config {
 
     /// Create a brand-new library: generate ID, create directory, encryption key, and config.yaml.
     ///
+    /// With a passphrase, the key is derived from it instead of generated, and
+    /// manifest.json is written right away to hold the KDF parameters.
+    ///
     /// Returns the Config. Caller should call `save_active_library()` and relaunch separately.
-    pub fn create_new_library(dev_mode: bool) -> Result<Config, ConfigError> {
+    pub fn create_new_library(
+        dev_mode: bool,
+        passphrase: Option<&str>,
+    ) -> Result<Config, ConfigError> {
         let home_dir = dirs::home_dir().expect("Failed to get home directory");
         let bae_dir = home_dir.join(".bae");
         let id = uuid::Uuid::new_v4().to_string();
@@ -718,8 +1115,12 @@ impl Config {
             library_name: Some(generate_library_name()),
             keys_migrated: true,
             discogs_key_stored: false,
+            fanart_key_stored: false,
             encryption_key_stored: true,
             encryption_key_fingerprint: None,
+            key_rotation_in_progress: false,
+            security_key: None,
+            encrypt_database: false,
             torrent_bind_interface: None,
             torrent_listen_port: None,
             torrent_enable_upnp: true,
@@ -735,26 +1136,67 @@ impl Config {
             server_bind_address: "127.0.0.1".to_string(),
             server_auth_enabled: false,
             server_username: None,
+            server_snapshot_interval_hours: 24,
+            server_snapshot_retention_days: 30,
+            server_sync_interval_secs: 30,
             cloud_provider: None,
             cloud_home_s3_bucket: None,
             cloud_home_s3_region: None,
             cloud_home_s3_endpoint: None,
             cloud_home_s3_key_prefix: None,
+            cloud_home_s3_audio_storage_class: None,
             cloud_home_google_drive_folder_id: None,
             cloud_home_dropbox_folder_path: None,
             cloud_home_onedrive_drive_id: None,
             cloud_home_onedrive_folder_id: None,
             cloud_home_icloud_container_path: None,
+            cloud_home_folder_path: None,
             cloud_home_bae_cloud_url: None,
             cloud_home_bae_cloud_username: None,
             share_base_url: None,
             followed_libraries: vec![],
+            import_ignore_patterns: vec![],
+            import_min_audio_duration_secs: None,
+            import_network_share_url: None,
+            import_network_share_username: None,
+            download_parallelism: None,
+            readahead_mib: None,
+            encryption_chunk_size_kib: None,
+            encrypt_images: true,
+            offload_after_days: None,
+            cloud_prices: None,
+            export_name_template: None,
+            musicbrainz_username: None,
+            musicbrainz_collection_id: None,
+            pause_on_sleep: true,
+            pause_on_screen_lock: false,
+            pause_during_calls: true,
+            duck_for_other_audio: false,
+            auto_upgrade_covers: false,
+            library_sort: vec![],
+            library_filter: AlbumFilter::default(),
         };
 
-        match key_service.get_or_create_encryption_key() {
-            Ok(key_hex) => {
+        let created = match passphrase {
+            Some(passphrase) => {
+                let kdf = PassphraseKdf::generate();
+                let key_hex = kdf
+                    .derive_key(passphrase)
+                    .map_err(|e| ConfigError::Config(e.to_string()))?;
+                key_service
+                    .set_encryption_key(&key_hex)
+                    .map(|()| (key_hex, Some(kdf)))
+            }
+            None => key_service
+                .get_or_create_encryption_key()
+                .map(|key_hex| (key_hex, None)),
+        };
+        let mut passphrase_kdf = None;
+        match created {
+            Ok((key_hex, kdf)) => {
                 config.encryption_key_fingerprint =
                     crate::encryption::compute_key_fingerprint(&key_hex);
+                passphrase_kdf = kdf;
             }
             Err(e) => {
                 tracing::error!("Failed to create encryption key: {e}");
@@ -764,6 +1206,18 @@ impl Config {
 
         config.save_to_config_yaml()?;
 
+        if passphrase_kdf.is_some() {
+            let manifest = Manifest {
+                library_id: config.library_id.clone(),
+                library_name: config.library_name.clone(),
+                encryption_key_fingerprint: config.encryption_key_fingerprint.clone(),
+                passphrase_kdf,
+            };
+            let json = serde_json::to_string_pretty(&manifest)
+                .map_err(|e| ConfigError::Serialization(e.to_string()))?;
+            std::fs::write(library_dir.manifest_path(), json)?;
+        }
+
         info!("Created new library at {}", library_dir.display());
         Ok(config)
     }
@@ -902,50 +1356,9 @@ fn read_config_yaml(path: &std::path::Path) -> Option<ConfigYaml> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::make_test_config;
     use tempfile::TempDir;
 
-    fn make_test_config(library_id: &str, library_path: PathBuf) -> Config {
-        Config {
-            library_id: library_id.to_string(),
-            device_id: "test-device-id".to_string(),
-            library_dir: LibraryDir::new(library_path),
-            library_name: None,
-            keys_migrated: true,
-            discogs_key_stored: false,
-            encryption_key_stored: false,
-            encryption_key_fingerprint: None,
-            torrent_bind_interface: None,
-            torrent_listen_port: None,
-            torrent_enable_upnp: true,
-            torrent_enable_natpmp: true,
-            torrent_enable_dht: false,
-            torrent_max_connections: None,
-            torrent_max_connections_per_torrent: None,
-            torrent_max_uploads: None,
-            torrent_max_uploads_per_torrent: None,
-            network_participation: ParticipationMode::Off,
-            server_enabled: true,
-            server_port: 4533,
-            server_bind_address: "127.0.0.1".to_string(),
-            server_auth_enabled: false,
-            server_username: None,
-            cloud_provider: None,
-            cloud_home_s3_bucket: None,
-            cloud_home_s3_region: None,
-            cloud_home_s3_endpoint: None,
-            cloud_home_s3_key_prefix: None,
-            cloud_home_google_drive_folder_id: None,
-            cloud_home_dropbox_folder_path: None,
-            cloud_home_onedrive_drive_id: None,
-            cloud_home_onedrive_folder_id: None,
-            cloud_home_icloud_container_path: None,
-            cloud_home_bae_cloud_url: None,
-            cloud_home_bae_cloud_username: None,
-            share_base_url: None,
-            followed_libraries: vec![],
-        }
-    }
-
     #[test]
     fn config_yaml_requires_library_id() {
         let yaml = "discogs_key_stored: false\n";
@@ -1181,6 +1594,16 @@ mod tests {
         assert!(config.followed_libraries.is_empty());
     }
 
+    #[test]
+    fn system_event_settings_default_when_missing() {
+        let yaml = "library_id: abc-123\n";
+        let config: ConfigYaml = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.pause_on_sleep);
+        assert!(!config.pause_on_screen_lock);
+        assert!(config.pause_during_calls);
+        assert!(!config.duck_for_other_audio);
+    }
+
     #[test]
     fn add_and_remove_followed_library() {
         let tmp = TempDir::new().unwrap();
@@ -1261,4 +1684,48 @@ mod tests {
         assert_eq!(yaml.library_name, Some("New Name".to_string()));
         assert_eq!(yaml.library_id, "lib-1"); // unchanged
     }
+
+    #[test]
+    fn library_settings_only_resets_device_settings() {
+        let yaml: ConfigYaml = serde_yaml::from_str(
+            "library_id: lib-1\nlibrary_name: Low Light\ndevice_id: old-device\n\
+             discogs_key_stored: true\ntorrent_enable_upnp: false\n\
+             server_bind_address: 0.0.0.0\nimport_ignore_patterns: ['*.m3u']\n\
+             cloud_home_icloud_container_path: /Users/someone/Library/Mobile Documents\n",
+        )
+        .unwrap();
+
+        let library = yaml.library_settings_only().unwrap();
+
+        assert_eq!(library.library_id, "lib-1");
+        assert_eq!(library.library_name.as_deref(), Some("Low Light"));
+        assert_eq!(library.import_ignore_patterns, vec!["*.m3u".to_string()]);
+        assert_eq!(library.device_id, None);
+        assert!(!library.discogs_key_stored);
+        assert!(library.torrent_enable_upnp);
+        assert_eq!(library.server_bind_address, None);
+        assert_eq!(library.cloud_home_icloud_container_path, None);
+        assert_eq!(setting_scope("server_port"), SettingScope::Device);
+        assert_eq!(setting_scope("share_base_url"), SettingScope::Library);
+    }
+
+    #[test]
+    fn encryption_chunk_size_defaults_by_cloud_provider() {
+        use crate::encryption::{EncryptionService, CHUNK_SIZE, CLOUD_CHUNK_SIZE};
+
+        let tmp = TempDir::new().unwrap();
+        let mut config = make_test_config("chunk-test", tmp.path().to_path_buf());
+        assert_eq!(config.encryption_chunk_size(), CHUNK_SIZE);
+
+        config.cloud_provider = Some(CloudProvider::BaeCloud);
+        assert_eq!(config.encryption_chunk_size(), CLOUD_CHUNK_SIZE);
+
+        config.encryption_chunk_size_kib = Some(256);
+        assert_eq!(config.encryption_chunk_size(), 256 * 1024);
+
+        // Unsupported sizes fall back to the default
+        config.encryption_chunk_size_kib = Some(100);
+        let encryption = config.file_encryption(EncryptionService::new_with_key(&[1u8; 32]));
+        assert_eq!(encryption.format().chunk_size, CHUNK_SIZE);
+    }
 }
@@ -0,0 +1,285 @@
+//! Versioned migrations for `config.yaml`
+//!
+//! Every library's config.yaml records the `config_version` it was written
+//! at. On load, the migrations newer than that are applied in order to the
+//! raw YAML mapping, and the result must still parse as a `ConfigYaml`
+//! before anything is written. The previous file is kept next to it as
+//! `config.yaml.v{N}.bak`.
+//!
+//! Serde ignores keys `ConfigYaml` doesn't know, and the next save would
+//! quietly lose them, so migrating also drops them explicitly and lists
+//! them in the report.
+
+use crate::config::{ConfigError, ConfigYaml};
+use serde_yaml::{Mapping, Value};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Version of the config written by this build
+pub const CONFIG_VERSION: u32 = 1;
+
+const VERSION_KEY: &str = "config_version";
+
+struct Migration {
+    /// Version the config is at once this has run
+    version: u32,
+    description: &'static str,
+    /// Returns whether the mapping changed
+    apply: fn(&mut Mapping) -> bool,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "Set cloud_provider for an S3 cloud home configured before providers existed",
+    apply: set_s3_cloud_provider,
+}];
+
+/// What migrating a config.yaml changed, for the startup log
+#[derive(Debug, Default, PartialEq)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Migrations that changed something
+    pub applied: Vec<&'static str>,
+    /// Keys no version of the config reads anymore
+    pub dropped: Vec<String>,
+    /// Keys whose values were invalid, put back to their defaults
+    pub reset: Vec<String>,
+    /// Copy of the file from before the migration
+    pub backup_path: Option<PathBuf>,
+}
+
+impl MigrationReport {
+    fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.dropped.is_empty() && self.reset.is_empty()
+    }
+
+    fn log(&self) {
+        info!(
+            "Migrated config.yaml from version {} to {}",
+            self.from_version, self.to_version
+        );
+        for description in &self.applied {
+            info!("  migrated: {}", description);
+        }
+        for key in &self.dropped {
+            info!("  dropped unknown key: {}", key);
+        }
+        for key in &self.reset {
+            warn!("  reset invalid value: {}", key);
+        }
+        if let Some(ref path) = self.backup_path {
+            info!("  previous config kept at {}", path.display());
+        }
+    }
+}
+
+/// Bring the config.yaml at `path` up to `CONFIG_VERSION`, backing up the
+/// original first. A config that's already current is left untouched, and
+/// so is one written by a newer build.
+pub fn migrate_config_file(path: &Path) -> Result<Option<MigrationReport>, ConfigError> {
+    let content = std::fs::read_to_string(path)?;
+    let mut mapping = match serde_yaml::from_str(&content) {
+        Ok(Value::Mapping(mapping)) => mapping,
+        Ok(_) => {
+            return Err(ConfigError::Config(
+                "config.yaml is not a mapping".to_string(),
+            ))
+        }
+        Err(e) => return Err(ConfigError::Serialization(e.to_string())),
+    };
+
+    let Some(mut report) = migrate(&mut mapping)? else {
+        return Ok(None);
+    };
+
+    let backup_path = path.with_file_name(format!("config.yaml.v{}.bak", report.from_version));
+    std::fs::copy(path, &backup_path)?;
+    report.backup_path = Some(backup_path);
+
+    let migrated =
+        serde_yaml::to_string(&mapping).map_err(|e| ConfigError::Serialization(e.to_string()))?;
+    std::fs::write(path, migrated)?;
+
+    if report.is_empty() {
+        info!(
+            "config.yaml is now version {} (nothing to change)",
+            report.to_version
+        );
+    } else {
+        report.log();
+    }
+
+    Ok(Some(report))
+}
+
+/// Migrate a config mapping in place. Returns None when it's already at
+/// (or past) `CONFIG_VERSION`.
+fn migrate(mapping: &mut Mapping) -> Result<Option<MigrationReport>, ConfigError> {
+    let from_version = mapping
+        .get(VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32;
+    if from_version >= CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    let mut report = MigrationReport {
+        from_version,
+        to_version: CONFIG_VERSION,
+        ..Default::default()
+    };
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from_version) {
+        if (migration.apply)(mapping) {
+            report.applied.push(migration.description);
+        }
+    }
+    mapping.insert(VERSION_KEY.into(), CONFIG_VERSION.into());
+
+    report.reset = reset_invalid_values(mapping);
+
+    // Whatever survives a round trip through ConfigYaml is a key it reads
+    let parsed: ConfigYaml = serde_yaml::from_value(Value::Mapping(mapping.clone()))
+        .map_err(|e| ConfigError::Config(format!("migrated config is invalid: {}", e)))?;
+    let known = match serde_yaml::to_value(&parsed) {
+        Ok(Value::Mapping(known)) => known,
+        _ => {
+            return Err(ConfigError::Serialization(
+                "config did not serialize to a mapping".to_string(),
+            ))
+        }
+    };
+    mapping.retain(|key, _| {
+        if known.contains_key(key) {
+            return true;
+        }
+        report
+            .dropped
+            .push(key.as_str().unwrap_or("(non-string key)").to_string());
+        false
+    });
+
+    Ok(Some(report))
+}
+
+/// Remove values the app would refuse or misuse, so their defaults apply
+fn reset_invalid_values(mapping: &mut Mapping) -> Vec<String> {
+    let invalid: Vec<Value> = mapping
+        .iter()
+        .filter(|(key, value)| {
+            !value.is_null() && key.as_str().is_some_and(|key| !is_valid_value(key, value))
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+    invalid
+        .into_iter()
+        .filter_map(|key| {
+            mapping.remove(&key);
+            key.as_str().map(str::to_string)
+        })
+        .collect()
+}
+
+fn is_valid_value(key: &str, value: &Value) -> bool {
+    match key {
+        "download_parallelism" | "readahead_mib" | "offload_after_days" => {
+            value.as_u64().is_some_and(|n| n > 0)
+        }
+        "encryption_chunk_size_kib" => value.as_u64().is_some_and(|kib| {
+            crate::encryption::ContainerFormat::for_chunk_size(kib as usize * 1024).is_ok()
+        }),
+        "cloud_prices" => value.as_mapping().is_some_and(|prices| {
+            prices
+                .values()
+                .all(|price| price.as_f64().is_some_and(|price| price >= 0.0))
+        }),
+        _ => true,
+    }
+}
+
+fn set_s3_cloud_provider(mapping: &mut Mapping) -> bool {
+    let is_set = |key: &str| mapping.get(key).is_some_and(|v| !v.is_null());
+    if is_set("cloud_provider")
+        || !is_set("cloud_home_s3_bucket")
+        || !is_set("cloud_home_s3_region")
+    {
+        return false;
+    }
+    mapping.insert("cloud_provider".into(), "S3".into());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CloudProvider;
+    use tempfile::TempDir;
+
+    fn mapping(yaml: &str) -> Mapping {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn unversioned_s3_config_gets_a_provider() {
+        let mut config = mapping(
+            "library_id: lib\ncloud_home_s3_bucket: tidewater\ncloud_home_s3_region: us-east-1\n",
+        );
+
+        let report = migrate(&mut config).unwrap().unwrap();
+
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.applied.len(), 1);
+        let parsed: ConfigYaml = serde_yaml::from_value(Value::Mapping(config)).unwrap();
+        assert_eq!(parsed.cloud_provider, Some(CloudProvider::S3));
+    }
+
+    #[test]
+    fn unknown_keys_are_dropped_and_invalid_values_reset() {
+        let mut config = mapping(
+            "library_id: lib\ntorrent_seed_ratio: 2.0\ndownload_parallelism: 0\n\
+             encryption_chunk_size_kib: 3\nserver_port: 4533\n",
+        );
+
+        let report = migrate(&mut config).unwrap().unwrap();
+
+        assert_eq!(report.dropped, vec!["torrent_seed_ratio".to_string()]);
+        assert_eq!(
+            report.reset,
+            vec![
+                "download_parallelism".to_string(),
+                "encryption_chunk_size_kib".to_string()
+            ]
+        );
+        assert!(!config.contains_key("torrent_seed_ratio"));
+        assert_eq!(
+            config.get("server_port").and_then(Value::as_u64),
+            Some(4533)
+        );
+    }
+
+    #[test]
+    fn current_config_is_left_alone() {
+        let mut config = mapping("library_id: lib\nconfig_version: 1\nsome_future_key: true\n");
+
+        assert!(migrate(&mut config).unwrap().is_none());
+        assert!(config.contains_key("some_future_key"));
+    }
+
+    #[test]
+    fn migrating_a_file_keeps_a_backup() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.yaml");
+        let original = "library_id: lib\nold_option: 1\n";
+        std::fs::write(&path, original).unwrap();
+
+        let report = migrate_config_file(&path).unwrap().unwrap();
+
+        let backup = report.backup_path.unwrap();
+        assert_eq!(backup, dir.path().join("config.yaml.v0.bak"));
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), original);
+        let migrated: ConfigYaml =
+            serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(migrated.config_version, CONFIG_VERSION);
+        assert!(migrate_config_file(&path).unwrap().is_none());
+    }
+}
@@ -0,0 +1,141 @@
+//! Cover art thumbnails
+//!
+//! `getCoverArt` with a `size` answers with a JPEG scaled down to the
+//! smallest of [`THUMBNAIL_SIZES`] that covers it, so album grids don't
+//! download full-resolution covers. Each one is scaled once and kept on disk,
+//! keyed by the cover's update time so a replaced cover is scaled again.
+
+use crate::cache::{thumbnail_cache_key, CacheManager};
+use crate::db::DbLibraryImage;
+use crate::library_dir::LibraryDir;
+use tracing::{debug, warn};
+
+/// Sizes thumbnails are rendered at, in pixels on the longer side
+pub const THUMBNAIL_SIZES: [u32; 3] = [150, 300, 600];
+
+const JPEG_QUALITY: u8 = 85;
+
+/// The thumbnail size that serves a request for `requested` pixels, None
+/// when only the original is big enough
+pub fn thumbnail_size(requested: u32) -> Option<u32> {
+    THUMBNAIL_SIZES.into_iter().find(|&size| size >= requested)
+}
+
+/// Scale encoded image bytes to fit in `size` pixels and encode them as JPEG.
+/// Images that already fit are only re-encoded.
+///
+/// Returns None if the image can't be decoded.
+pub fn render_thumbnail(bytes: &[u8], size: u32) -> Option<Vec<u8>> {
+    let image = match image::load_from_memory(bytes) {
+        Ok(image) => image,
+        Err(e) => {
+            debug!("Skipping thumbnail, failed to decode image: {}", e);
+
+            return None;
+        }
+    };
+
+    let scaled = if image.width() > size || image.height() > size {
+        image.thumbnail(size, size)
+    } else {
+        image
+    };
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+        .encode_image(&scaled.to_rgb8())
+        .ok()?;
+    Some(jpeg)
+}
+
+/// Thumbnails of the library's covers, rendered on first use
+#[derive(Clone)]
+pub struct CoverThumbnails {
+    cache: CacheManager,
+    library_dir: LibraryDir,
+}
+
+impl CoverThumbnails {
+    pub fn new(cache: CacheManager, library_dir: LibraryDir) -> Self {
+        Self { cache, library_dir }
+    }
+
+    /// The thumbnail of `cover` at `size`, one of [`THUMBNAIL_SIZES`]
+    pub async fn get(&self, cover: &DbLibraryImage, size: u32) -> Option<Vec<u8>> {
+        let key = thumbnail_cache_key(&cover.id, cover.updated_at.timestamp(), size);
+        match self.cache.get(&key).await {
+            Ok(Some(data)) => return Some(data),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to read cached thumbnail {key}: {e}"),
+        }
+
+        let original = tokio::fs::read(self.library_dir.image_path(&cover.id))
+            .await
+            .ok()?;
+        self.render(&key, original, size).await
+    }
+
+    /// Render the thumbnails of `cover` that aren't cached yet
+    pub async fn render_missing(&self, cover: &DbLibraryImage) {
+        let mut missing = Vec::new();
+        for size in THUMBNAIL_SIZES {
+            let key = thumbnail_cache_key(&cover.id, cover.updated_at.timestamp(), size);
+            if !self.cache.contains(&key).await {
+                missing.push((key, size));
+            }
+        }
+        if missing.is_empty() {
+            return;
+        }
+
+        let original = match tokio::fs::read(self.library_dir.image_path(&cover.id)).await {
+            Ok(data) => data,
+            Err(e) => {
+                debug!("Skipping thumbnails of {}, cover unreadable: {e}", cover.id);
+
+                return;
+            }
+        };
+        for (key, size) in missing {
+            self.render(&key, original.clone(), size).await;
+        }
+    }
+
+    async fn render(&self, key: &str, original: Vec<u8>, size: u32) -> Option<Vec<u8>> {
+        let thumbnail = tokio::task::spawn_blocking(move || render_thumbnail(&original, size))
+            .await
+            .ok()
+            .flatten()?;
+        if let Err(e) = self.cache.put(key, &thumbnail).await {
+            warn!("Failed to cache thumbnail {key}: {e}");
+        }
+        Some(thumbnail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_round_up_to_a_thumbnail_size() {
+        assert_eq!(thumbnail_size(64), Some(150));
+        assert_eq!(thumbnail_size(300), Some(300));
+        assert_eq!(thumbnail_size(301), Some(600));
+        assert_eq!(thumbnail_size(1200), None);
+    }
+
+    #[test]
+    fn scales_down_keeping_aspect_ratio() {
+        let mut png = Vec::new();
+        image::RgbImage::new(600, 400)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let thumbnail = image::load_from_memory(&render_thumbnail(&png, 150).unwrap()).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (150, 100));
+
+        let unscaled = image::load_from_memory(&render_thumbnail(&png, 1000).unwrap()).unwrap();
+        assert_eq!(unscaled.width(), 600);
+        assert!(render_thumbnail(b"not an image", 150).is_none());
+    }
+}
@@ -592,6 +592,13 @@ impl CueFlacProcessor {
         Ok((input, total_ms))
     }
 }
+/// Format milliseconds as a CUE MM:SS:FF timestamp (75 frames per second)
+pub fn format_cue_time(ms: u64) -> String {
+    let minutes = ms / 60000;
+    let seconds = (ms / 1000) % 60;
+    let frames = (ms % 1000) * 75 / 1000;
+    format!("{:02}:{:02}:{:02}", minutes, seconds, frames)
+}
 #[cfg(test)]
 mod tests {
     use super::*;
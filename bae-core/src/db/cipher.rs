@@ -0,0 +1,323 @@
+//! Encryption of library.db at rest
+//!
+//! bae links SQLCipher in place of plain SQLite. A connection that never sets
+//! a key reads and writes ordinary SQLite files, so encryption is a per-device
+//! choice: with it on, every connection to library.db sets a key derived from
+//! the library's master key before touching the file.
+//!
+//! Turning it on or off rewrites the file at startup, before anything opens
+//! it: `sqlcipher_export` copies it into a temporary file under the new key
+//! (or none), which is renamed over the original. A master key rotation moves
+//! the file to the new key the same way.
+
+use std::ffi::{c_int, CStr, CString};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use libsqlite3_sys as ffi;
+use tracing::info;
+
+use crate::hmac_utils::hmac_sign;
+
+/// Every plaintext SQLite file starts with this
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CipherError {
+    #[error("Invalid encryption key")]
+    InvalidKey,
+    #[error("The database is encrypted under a different key")]
+    WrongKey,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("SQLite error: {0}")]
+    Sqlite(String),
+}
+
+/// SQLCipher key for library.db
+#[derive(Clone, PartialEq, Eq)]
+pub struct DatabaseKey([u8; 32]);
+
+impl DatabaseKey {
+    /// Derive the database key from the hex master key. It is separate from
+    /// the master key so the two never encrypt under the same key.
+    pub fn derive(master_key_hex: &str) -> Result<Self, CipherError> {
+        let master_key: [u8; 32] = hex::decode(master_key_hex)
+            .ok()
+            .and_then(|key| key.try_into().ok())
+            .ok_or(CipherError::InvalidKey)?;
+        Ok(Self(hmac_sign(&master_key, b"bae library database")))
+    }
+
+    /// Value for `PRAGMA key` and `ATTACH ... KEY`. A raw key in SQLCipher's
+    /// blob syntax skips the passphrase KDF on every connection.
+    pub fn pragma_value(&self) -> String {
+        format!("\"x'{}'\"", hex::encode(self.0))
+    }
+}
+
+/// Whether the database file at `path` is encrypted. Missing and empty files
+/// aren't.
+pub fn is_encrypted(path: &Path) -> std::io::Result<bool> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    file.take(SQLITE_HEADER.len() as u64)
+        .read_to_end(&mut header)?;
+    Ok(!header.is_empty() && header != SQLITE_HEADER)
+}
+
+/// Bring the database at `path` in line with the device's setting before it
+/// is opened: encrypted under `key` if `encrypt` is set, plaintext otherwise.
+/// A file still under `previous`, the master key before a rotation, moves
+/// to `key`.
+pub fn prepare_database(
+    path: &Path,
+    encrypt: bool,
+    key: &DatabaseKey,
+    previous: Option<&DatabaseKey>,
+) -> Result<(), CipherError> {
+    if !is_encrypted(path)? {
+        if encrypt && path.exists() {
+            export(path, None, Some(key))?;
+            info!("Encrypted {}", path.display());
+        }
+        return Ok(());
+    }
+
+    let current = if opens_with(path, key)? {
+        key
+    } else {
+        match previous {
+            Some(previous) if opens_with(path, previous)? => previous,
+            _ => return Err(CipherError::WrongKey),
+        }
+    };
+
+    if !encrypt {
+        export(path, Some(current), None)?;
+        info!("Decrypted {}", path.display());
+    } else if current != key {
+        export(path, Some(current), Some(key))?;
+        info!("Moved {} to the new master key", path.display());
+    }
+    Ok(())
+}
+
+/// Set the key on a raw connection. Has to come before anything else reads
+/// the database.
+///
+/// # Safety
+/// `db` must be a valid, open sqlite3 connection pointer.
+pub unsafe fn apply_key(db: *mut ffi::sqlite3, key: &DatabaseKey) -> Result<(), CipherError> {
+    exec(db, &format!("PRAGMA key = {}", key.pragma_value()))
+}
+
+/// Whether the main database of a raw connection is an encrypted file
+///
+/// # Safety
+/// `db` must be a valid, open sqlite3 connection pointer.
+pub(crate) unsafe fn is_encrypted_connection(db: *mut ffi::sqlite3) -> bool {
+    let filename = ffi::sqlite3_db_filename(db, c"main".as_ptr());
+    if filename.is_null() {
+        return false;
+    }
+    let filename = CStr::from_ptr(filename).to_string_lossy();
+    // In-memory databases have an empty filename
+    !filename.is_empty() && is_encrypted(Path::new(filename.as_ref())).unwrap_or(false)
+}
+
+/// SQL that copies the connection's main database to `target`, encrypted
+/// under `key` or plaintext without one
+pub(crate) fn export_sql(target: &Path, key: Option<&DatabaseKey>) -> String {
+    let target = target.to_string_lossy().replace('\'', "''");
+    let key = key.map_or("''".to_string(), DatabaseKey::pragma_value);
+    format!(
+        "ATTACH DATABASE '{target}' AS exported KEY {key};
+         SELECT sqlcipher_export('exported');
+         DETACH DATABASE exported;"
+    )
+}
+
+/// Rewrite the database at `path` from key `from` to key `to`
+fn export(
+    path: &Path,
+    from: Option<&DatabaseKey>,
+    to: Option<&DatabaseKey>,
+) -> Result<(), CipherError> {
+    let converted = sibling(path, "-converted");
+    let _ = std::fs::remove_file(&converted);
+
+    unsafe {
+        let db = open(path, ffi::SQLITE_OPEN_READWRITE)?;
+        let result = match from {
+            Some(key) => apply_key(db, key),
+            None => Ok(()),
+        }
+        .and_then(|()| exec(db, &export_sql(&converted, to)));
+        // Closing the last connection checkpoints the WAL into the file
+        ffi::sqlite3_close(db);
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&converted);
+            return Err(e);
+        }
+    }
+
+    std::fs::rename(&converted, path)?;
+    // A WAL left behind would be replayed into the new file
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(sibling(path, suffix));
+    }
+    Ok(())
+}
+
+/// Whether `key` opens the encrypted database at `path`
+fn opens_with(path: &Path, key: &DatabaseKey) -> Result<bool, CipherError> {
+    unsafe {
+        let db = open(path, ffi::SQLITE_OPEN_READONLY)?;
+        let result = apply_key(db, key)
+            .and_then(|()| exec(db, "SELECT count(*) FROM sqlite_master"))
+            .is_ok();
+        ffi::sqlite3_close(db);
+        Ok(result)
+    }
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+unsafe fn open(path: &Path, flags: c_int) -> Result<*mut ffi::sqlite3, CipherError> {
+    let c_path = CString::new(path.to_string_lossy().as_bytes())
+        .map_err(|_| CipherError::Sqlite(format!("invalid path: {}", path.display())))?;
+    let mut db: *mut ffi::sqlite3 = ptr::null_mut();
+    let rc = ffi::sqlite3_open_v2(c_path.as_ptr(), &mut db, flags, ptr::null());
+    if rc != ffi::SQLITE_OK {
+        let msg = errmsg(db);
+        ffi::sqlite3_close(db);
+        return Err(CipherError::Sqlite(format!(
+            "failed to open {}: {msg}",
+            path.display()
+        )));
+    }
+    Ok(db)
+}
+
+unsafe fn exec(db: *mut ffi::sqlite3, sql: &str) -> Result<(), CipherError> {
+    let c_sql = CString::new(sql).expect("SQL should not contain null bytes");
+    let rc = ffi::sqlite3_exec(db, c_sql.as_ptr(), None, ptr::null_mut(), ptr::null_mut());
+    if rc != ffi::SQLITE_OK {
+        return Err(CipherError::Sqlite(errmsg(db)));
+    }
+    Ok(())
+}
+
+unsafe fn errmsg(db: *mut ffi::sqlite3) -> String {
+    let err = ffi::sqlite3_errmsg(db);
+    if err.is_null() {
+        "unknown sqlite error".to_string()
+    } else {
+        CStr::from_ptr(err).to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use tempfile::TempDir;
+
+    fn library_with_album(path: &Path) {
+        unsafe {
+            let db = open(path, ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE).unwrap();
+            exec(
+                db,
+                "PRAGMA journal_mode = WAL;
+                 CREATE TABLE albums (title TEXT);
+                 INSERT INTO albums VALUES ('Glass Harbor');",
+            )
+            .unwrap();
+            ffi::sqlite3_close(db);
+        }
+    }
+
+    /// Whether the album is readable with `key`, or with no key
+    fn readable(path: &Path, key: Option<&DatabaseKey>) -> bool {
+        unsafe {
+            let db = open(path, ffi::SQLITE_OPEN_READONLY).unwrap();
+            let result = match key {
+                Some(key) => apply_key(db, key),
+                None => Ok(()),
+            }
+            .and_then(|()| exec(db, "SELECT title FROM albums"));
+            ffi::sqlite3_close(db);
+            result.is_ok()
+        }
+    }
+
+    #[test]
+    fn encrypts_rekeys_and_decrypts_in_place() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.db");
+        library_with_album(&path);
+        assert!(!is_encrypted(&path).unwrap());
+
+        let key = DatabaseKey::derive(&"11".repeat(32)).unwrap();
+        prepare_database(&path, true, &key, None).unwrap();
+        assert!(is_encrypted(&path).unwrap());
+        assert!(!readable(&path, None));
+        assert!(readable(&path, Some(&key)));
+
+        // After a master key rotation the file moves to the new key
+        let rotated = DatabaseKey::derive(&"22".repeat(32)).unwrap();
+        prepare_database(&path, true, &rotated, Some(&key)).unwrap();
+        assert!(!readable(&path, Some(&key)));
+        assert!(readable(&path, Some(&rotated)));
+
+        prepare_database(&path, false, &rotated, None).unwrap();
+        assert!(!is_encrypted(&path).unwrap());
+        assert!(readable(&path, None));
+    }
+
+    #[test]
+    fn an_unknown_key_leaves_the_file_alone() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.db");
+        library_with_album(&path);
+
+        let key = DatabaseKey::derive(&"11".repeat(32)).unwrap();
+        prepare_database(&path, true, &key, None).unwrap();
+
+        let other = DatabaseKey::derive(&"33".repeat(32)).unwrap();
+        assert!(matches!(
+            prepare_database(&path, false, &other, None),
+            Err(CipherError::WrongKey)
+        ));
+        assert!(readable(&path, Some(&key)));
+
+        // Nothing to convert yet
+        let missing = tmp.path().join("missing.db");
+        prepare_database(&missing, true, &key, None).unwrap();
+        assert!(!missing.exists());
+    }
+
+    #[tokio::test]
+    async fn database_opens_an_encrypted_library_with_its_key() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.db");
+        library_with_album(&path);
+        let key = DatabaseKey::derive(&"11".repeat(32)).unwrap();
+        prepare_database(&path, true, &key, None).unwrap();
+
+        let path_str = path.to_str().unwrap();
+        assert!(Database::new(path_str).await.is_err());
+        let database = Database::new_with_key(path_str, Some(&key)).await.unwrap();
+        assert!(database.get_albums(&[]).await.unwrap().is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::content_type::ContentType;
 use crate::db::models::*;
+use crate::db::DatabaseKey;
 use chrono::{DateTime, Utc};
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
 use sqlx::{ConnectOptions, Connection, Row, SqlitePool};
@@ -19,6 +20,7 @@ fn row_to_library_image(row: sqlx::sqlite::SqliteRow) -> DbLibraryImage {
         height: row.get("height"),
         source: row.get("source"),
         source_url: row.get("source_url"),
+        blurhash: row.get("blurhash"),
         updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("_updated_at"))
             .unwrap()
             .with_timezone(&Utc),
@@ -64,9 +66,21 @@ impl Database {
     /// sqlx enables `PRAGMA foreign_keys = ON` by default (since v0.4),
     /// so FK constraints in the schema are enforced on every connection.
     pub async fn new(database_path: &str) -> Result<Self, sqlx::Error> {
-        let opts = SqliteConnectOptions::from_str(&format!("sqlite://{}", database_path))?
+        Self::new_with_key(database_path, None).await
+    }
+
+    /// Like `new`, for a database encrypted at rest under `key`. Every
+    /// connection sets the key before anything else.
+    pub async fn new_with_key(
+        database_path: &str,
+        key: Option<&DatabaseKey>,
+    ) -> Result<Self, sqlx::Error> {
+        let mut opts = SqliteConnectOptions::from_str(&format!("sqlite://{}", database_path))?
             .create_if_missing(true)
             .journal_mode(SqliteJournalMode::Wal);
+        if let Some(key) = key {
+            opts = opts.pragma("key", key.pragma_value());
+        }
 
         info!("Connecting to sqlite://{}", database_path);
 
@@ -197,6 +211,7 @@ impl Database {
             managed_in_cloud: row.get("managed_in_cloud"),
             unmanaged_path: row.get("unmanaged_path"),
             private: row.get("private"),
+            unverified: row.get("unverified"),
             updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("_updated_at"))
                 .unwrap()
                 .with_timezone(&Utc),
@@ -214,6 +229,7 @@ impl Database {
             file_size: row.get("file_size"),
             content_type: ContentType::from_mime(&row.get::<String, _>("content_type")),
             encryption_nonce: row.get("encryption_nonce"),
+            content_sha256: row.get("content_sha256"),
             updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("_updated_at"))
                 .unwrap()
                 .with_timezone(&Utc),
@@ -332,14 +348,15 @@ impl Database {
         let mut conn = self.writer()?.lock().await;
         sqlx::query(
             r#"
-            INSERT INTO album_artists (id, album_id, artist_id, position, _updated_at, created_at)
-            VALUES (?, ?, ?, ?, ?, ?)
+            INSERT INTO album_artists (id, album_id, artist_id, position, join_phrase, _updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&album_artist.id)
         .bind(&album_artist.album_id)
         .bind(&album_artist.artist_id)
         .bind(album_artist.position)
+        .bind(&album_artist.join_phrase)
         .bind(album_artist.updated_at.to_rfc3339())
         .bind(album_artist.created_at.to_rfc3339())
         .execute(&mut *conn)
@@ -354,8 +371,8 @@ impl Database {
         let mut conn = self.writer()?.lock().await;
         sqlx::query(
             r#"
-            INSERT INTO track_artists (id, track_id, artist_id, position, role, _updated_at, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO track_artists (id, track_id, artist_id, position, role, join_phrase, _updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&track_artist.id)
@@ -363,6 +380,7 @@ impl Database {
         .bind(&track_artist.artist_id)
         .bind(track_artist.position)
         .bind(&track_artist.role)
+        .bind(&track_artist.join_phrase)
         .bind(track_artist.updated_at.to_rfc3339())
         .bind(track_artist.created_at.to_rfc3339())
         .execute(&mut *conn)
@@ -387,7 +405,8 @@ impl Database {
         .await?;
         Ok(rows.iter().map(Self::row_to_artist).collect())
     }
-    /// Get artists for a track (ordered by position)
+    /// Get performing artists for a track (ordered by position). Composers
+    /// are credited separately, see `get_composers_for_release`.
     pub async fn get_artists_for_track(
         &self,
         track_id: &str,
@@ -396,15 +415,68 @@ impl Database {
             r#"
             SELECT a.* FROM artists a
             JOIN track_artists ta ON a.id = ta.artist_id
-            WHERE ta.track_id = ?
+            WHERE ta.track_id = ? AND ta.role IS NOT ?
             ORDER BY ta.position
             "#,
         )
         .bind(track_id)
+        .bind(COMPOSER_ROLE)
         .fetch_all(&self.inner.read_pool)
         .await?;
         Ok(rows.iter().map(Self::row_to_artist).collect())
     }
+    /// An album's artist credit in order, each artist with the text that
+    /// joins it to the next
+    pub async fn get_album_artist_credits(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<(DbArtist, Option<String>)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.*, aa.join_phrase FROM artists a
+            JOIN album_artists aa ON a.id = aa.artist_id
+            WHERE aa.album_id = ?
+            ORDER BY aa.position
+            "#,
+        )
+        .bind(album_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| (Self::row_to_artist(row), row.get("join_phrase")))
+            .collect())
+    }
+    /// Performing artist credits on a release's tracks, as (track ID, artist,
+    /// join phrase) in credit order. Composers are left out.
+    pub async fn get_track_artist_credits_for_release(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<(String, DbArtist, Option<String>)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT ta.track_id, ta.join_phrase, a.* FROM track_artists ta
+            JOIN artists a ON a.id = ta.artist_id
+            JOIN tracks t ON t.id = ta.track_id
+            WHERE t.release_id = ? AND ta.role IS NOT ?
+            ORDER BY ta.track_id, ta.position
+            "#,
+        )
+        .bind(release_id)
+        .bind(COMPOSER_ROLE)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("track_id"),
+                    Self::row_to_artist(row),
+                    row.get("join_phrase"),
+                )
+            })
+            .collect())
+    }
     /// Get artist by ID
     pub async fn get_artist_by_id(&self, artist_id: &str) -> Result<Option<DbArtist>, sqlx::Error> {
         let row = sqlx::query("SELECT * FROM artists WHERE id = ?")
@@ -428,7 +500,357 @@ impl Database {
         Ok(rows.iter().map(Self::row_to_artist).collect())
     }
 
-    /// Get albums for an artist (via album_artists join table)
+    /// Artists with albums and an external ID but no artist image, skipping
+    /// those looked up since `checked_since` (unix seconds)
+    pub async fn get_artists_missing_images(
+        &self,
+        checked_since: i64,
+    ) -> Result<Vec<DbArtist>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.* FROM artists a
+            JOIN album_artists aa ON a.id = aa.artist_id
+            LEFT JOIN library_images li ON li.id = a.id AND li.type = 'artist'
+            LEFT JOIN artist_image_lookups l ON l.artist_id = a.id
+            WHERE li.id IS NULL
+              AND (a.discogs_artist_id IS NOT NULL OR a.musicbrainz_artist_id IS NOT NULL)
+              AND (l.checked_at IS NULL OR l.checked_at < ?)
+            GROUP BY a.id
+            ORDER BY COALESCE(a.sort_name, a.name) COLLATE NOCASE
+            "#,
+        )
+        .bind(checked_since)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.iter().map(Self::row_to_artist).collect())
+    }
+
+    /// Record that an artist was just looked up on the artist image sources
+    pub async fn record_artist_image_lookup(&self, artist_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            r#"
+            INSERT INTO artist_image_lookups (artist_id, checked_at)
+            VALUES (?, ?)
+            ON CONFLICT(artist_id) DO UPDATE SET checked_at = excluded.checked_at
+            "#,
+        )
+        .bind(artist_id)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Releases whose cover is smaller than `min_size` on its shorter side,
+    /// or of unknown size, and hasn't been looked up for an upgrade since
+    /// `checked_since`
+    pub async fn get_cover_upgrade_candidates(
+        &self,
+        min_size: i64,
+        checked_since: i64,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT li.id FROM library_images li
+            JOIN releases r ON r.id = li.id
+            LEFT JOIN cover_upgrade_lookups l ON l.release_id = li.id
+            WHERE li.type = 'cover'
+              AND (li.width IS NULL OR li.height IS NULL OR MIN(li.width, li.height) < ?)
+              AND (l.checked_at IS NULL OR l.checked_at < ?)
+            ORDER BY li.created_at
+            "#,
+        )
+        .bind(min_size)
+        .bind(checked_since)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Record that a release's cover was just looked up for an upgrade
+    pub async fn record_cover_upgrade_lookup(&self, release_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            r#"
+            INSERT INTO cover_upgrade_lookups (release_id, checked_at)
+            VALUES (?, ?)
+            ON CONFLICT(release_id) DO UPDATE SET checked_at = excluded.checked_at
+            "#,
+        )
+        .bind(release_id)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Fully imported releases the storage scrub hasn't verified since
+    /// `verified_since`, least recently verified first
+    pub async fn get_storage_scrub_candidates(
+        &self,
+        verified_since: i64,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT r.id FROM releases r
+            LEFT JOIN storage_scrubs s ON s.release_id = r.id
+            WHERE r.import_status = 'complete'
+              AND (s.verified_at IS NULL OR s.verified_at < ?)
+            ORDER BY COALESCE(s.verified_at, 0), r.created_at
+            "#,
+        )
+        .bind(verified_since)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Record that the storage scrub just read back a release's files
+    pub async fn record_storage_scrub(
+        &self,
+        release_id: &str,
+        broken_files: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            r#"
+            INSERT INTO storage_scrubs (release_id, verified_at, broken_files)
+            VALUES (?, ?, ?)
+            ON CONFLICT(release_id) DO UPDATE SET
+                verified_at = excluded.verified_at,
+                broken_files = excluded.broken_files
+            "#,
+        )
+        .bind(release_id)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(broken_files)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// When the storage scrub last verified a release, as a Unix timestamp
+    pub async fn get_last_storage_scrub(&self) -> Result<Option<i64>, sqlx::Error> {
+        let (verified_at,): (Option<i64>,) =
+            sqlx::query_as("SELECT MAX(verified_at) FROM storage_scrubs")
+                .fetch_one(&self.inner.read_pool)
+                .await?;
+        Ok(verified_at)
+    }
+
+    /// Releases kept locally that haven't been played or changed since
+    /// `idle_since` (RFC 3339), least recently played first. Releases with a
+    /// transfer underway are left out.
+    pub async fn get_offload_candidates(
+        &self,
+        idle_since: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT r.id FROM releases r
+            LEFT JOIN (
+                SELECT t.release_id, MAX(s.last_played_at) AS last_played_at
+                FROM tracks t
+                JOIN track_stats s ON s.track_id = t.id
+                GROUP BY t.release_id
+            ) p ON p.release_id = r.id
+            WHERE r.import_status = 'complete'
+              AND r.managed_locally = TRUE
+              AND r._updated_at < ?
+              AND (p.last_played_at IS NULL OR p.last_played_at < ?)
+              AND r.id NOT IN (SELECT release_id FROM storage_transfers)
+            ORDER BY COALESCE(p.last_played_at, r._updated_at)
+            "#,
+        )
+        .bind(idle_since)
+        .bind(idle_since)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Add to this device's cloud home traffic for `month` (`YYYY-MM`)
+    pub async fn record_cloud_usage(
+        &self,
+        month: &str,
+        bytes_uploaded: i64,
+        bytes_downloaded: i64,
+        requests: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            r#"
+            INSERT INTO cloud_usage (month, bytes_uploaded, bytes_downloaded, requests)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(month) DO UPDATE SET
+                bytes_uploaded = bytes_uploaded + excluded.bytes_uploaded,
+                bytes_downloaded = bytes_downloaded + excluded.bytes_downloaded,
+                requests = requests + excluded.requests
+            "#,
+        )
+        .bind(month)
+        .bind(bytes_uploaded)
+        .bind(bytes_downloaded)
+        .bind(requests)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// This device's cloud home traffic in `month` (`YYYY-MM`), zero if none
+    pub async fn get_cloud_usage(&self, month: &str) -> Result<DbCloudUsage, sqlx::Error> {
+        let row: Option<(i64, i64, i64)> = sqlx::query_as(
+            "SELECT bytes_uploaded, bytes_downloaded, requests FROM cloud_usage WHERE month = ?",
+        )
+        .bind(month)
+        .fetch_optional(&self.inner.read_pool)
+        .await?;
+        let (bytes_uploaded, bytes_downloaded, requests) = row.unwrap_or_default();
+        Ok(DbCloudUsage {
+            month: month.to_string(),
+            bytes_uploaded,
+            bytes_downloaded,
+            requests,
+        })
+    }
+
+    /// Size of the files bae manages, as (kept on this device, kept in the
+    /// cloud home). A release kept in both counts toward both.
+    pub async fn get_stored_bytes(&self) -> Result<(i64, i64), sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                COALESCE(SUM(CASE WHEN r.managed_locally THEN f.file_size ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN r.managed_in_cloud THEN f.file_size ELSE 0 END), 0)
+            FROM release_files f
+            JOIN releases r ON r.id = f.release_id
+            "#,
+        )
+        .fetch_one(&self.inner.read_pool)
+        .await
+    }
+
+    /// Keep a release downloaded on this device
+    pub async fn add_offline_pin(&self, release_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("INSERT OR IGNORE INTO offline_pins (release_id, pinned_at) VALUES (?, ?)")
+            .bind(release_id)
+            .bind(Utc::now().timestamp())
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Stop keeping a release downloaded on this device
+    pub async fn remove_offline_pin(&self, release_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM offline_pins WHERE release_id = ?")
+            .bind(release_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Releases this device keeps downloaded, oldest pin first. Pins of
+    /// releases that have since been deleted are left out.
+    pub async fn get_offline_pins(&self) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT p.release_id FROM offline_pins p
+            JOIN releases r ON r.id = p.release_id
+            ORDER BY p.pinned_at
+            "#,
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// MusicBrainz release IDs of albums with at least one fully imported release
+    pub async fn get_imported_musicbrainz_release_ids(&self) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT am.musicbrainz_release_id FROM album_musicbrainz am
+            WHERE EXISTS (
+                SELECT 1 FROM releases r
+                WHERE r.album_id = am.album_id AND r.import_status = ?
+            )
+            "#,
+        )
+        .bind(ImportStatus::Complete.as_str())
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// MusicBrainz release IDs this device has added to a collection
+    pub async fn get_musicbrainz_collection_releases(
+        &self,
+        collection_id: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT musicbrainz_release_id FROM musicbrainz_collection_releases WHERE collection_id = ?",
+        )
+        .bind(collection_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Record releases as added to a collection
+    pub async fn add_musicbrainz_collection_releases(
+        &self,
+        collection_id: &str,
+        release_ids: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        for release_id in release_ids {
+            sqlx::query(
+                "INSERT OR IGNORE INTO musicbrainz_collection_releases (collection_id, musicbrainz_release_id) VALUES (?, ?)",
+            )
+            .bind(collection_id)
+            .bind(release_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Record releases as removed from a collection
+    pub async fn remove_musicbrainz_collection_releases(
+        &self,
+        collection_id: &str,
+        release_ids: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        for release_id in release_ids {
+            sqlx::query(
+                "DELETE FROM musicbrainz_collection_releases WHERE collection_id = ? AND musicbrainz_release_id = ?",
+            )
+            .bind(collection_id)
+            .bind(release_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Forget every collection this device has added releases to
+    pub async fn clear_musicbrainz_collection_releases(&self) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM musicbrainz_collection_releases")
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Get albums for an artist: albums they're credited on, plus albums
+    /// with a track they're credited on, as a featured artist or composer
     pub async fn get_albums_for_artist(
         &self,
         artist_id: &str,
@@ -441,14 +863,21 @@ impl Database {
                 ad.discogs_master_id, ad.discogs_release_id,
                 amb.musicbrainz_release_group_id, amb.musicbrainz_release_id
             FROM albums a
-            JOIN album_artists aa ON a.id = aa.album_id
             LEFT JOIN album_discogs ad ON a.id = ad.album_id
             LEFT JOIN album_musicbrainz amb ON a.id = amb.album_id
-            WHERE aa.artist_id = ?
+            WHERE a.id IN (
+                SELECT album_id FROM album_artists WHERE artist_id = ?
+                UNION
+                SELECT r.album_id FROM track_artists ta
+                JOIN tracks t ON t.id = ta.track_id
+                JOIN releases r ON r.id = t.release_id
+                WHERE ta.artist_id = ?
+            )
             ORDER BY a.year DESC, a.title
             "#,
         )
         .bind(artist_id)
+        .bind(artist_id)
         .fetch_all(&self.inner.read_pool)
         .await?;
         let mut albums = Vec::new();
@@ -488,29 +917,80 @@ impl Database {
         }
         Ok(albums)
     }
-    /// Search across artists, albums, and tracks by name/title
+    /// IDs of albums an artist is credited on only for some tracks, like a
+    /// compilation they appear on, rather than as an album artist
+    pub async fn get_appearance_album_ids(
+        &self,
+        artist_id: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT r.album_id FROM track_artists ta
+            JOIN tracks t ON t.id = ta.track_id
+            JOIN releases r ON r.id = t.release_id
+            WHERE ta.artist_id = ?
+              AND r.album_id NOT IN (SELECT album_id FROM album_artists WHERE artist_id = ?)
+            "#,
+        )
+        .bind(artist_id)
+        .bind(artist_id)
+        .fetch_all(&self.inner.read_pool)
+        .await
+    }
+    /// Search across artists, albums, and tracks by name/title, returning the
+    /// best `limit` of each
     pub async fn search_library(
         &self,
         query: &str,
         limit: usize,
+    ) -> Result<LibrarySearchResults, sqlx::Error> {
+        self.search_library_page(query, &SearchPage::first(limit))
+            .await
+    }
+
+    /// Search across artists, albums, and tracks by name/title. Albums also
+    /// match on their custom tags and notes.
+    ///
+    /// Exact matches rank first, then names starting with the query, then
+    /// names containing it. Within each of those, artists on more albums and
+    /// albums and tracks with more plays come first.
+    pub async fn search_library_page(
+        &self,
+        query: &str,
+        page: &SearchPage,
     ) -> Result<LibrarySearchResults, sqlx::Error> {
         let pattern = format!("%{}%", query);
-        let limit_i64 = limit as i64;
+        let prefix = format!("{}%", query);
 
-        // Search artists by name, with album count
+        // Search artists by name, with the number of albums they're credited
+        // on, on the album itself or on one of its tracks
         let artist_rows = sqlx::query(
             r#"
-            SELECT art.id, art.name, COUNT(DISTINCT aa.album_id) as album_count
+            SELECT art.id, art.name, COUNT(DISTINCT credits.album_id) as album_count
             FROM artists art
-            JOIN album_artists aa ON art.id = aa.artist_id
+            JOIN (
+                SELECT artist_id, album_id FROM album_artists
+                UNION
+                SELECT ta.artist_id, r.album_id FROM track_artists ta
+                JOIN tracks t ON t.id = ta.track_id
+                JOIN releases r ON r.id = t.release_id
+            ) credits ON credits.artist_id = art.id
             WHERE art.name LIKE ?
             GROUP BY art.id
-            ORDER BY album_count DESC, art.name
-            LIMIT ?
+            ORDER BY CASE
+                         WHEN art.name = ? COLLATE NOCASE THEN 0
+                         WHEN art.name LIKE ? THEN 1
+                         ELSE 2
+                     END,
+                     album_count DESC, art.name
+            LIMIT ? OFFSET ?
             "#,
         )
         .bind(&pattern)
-        .bind(limit_i64)
+        .bind(query)
+        .bind(&prefix)
+        .bind(page.artist_count as i64)
+        .bind(page.artist_offset as i64)
         .fetch_all(&self.inner.read_pool)
         .await?;
 
@@ -532,12 +1012,37 @@ impl Database {
             LEFT JOIN album_artists aa ON a.id = aa.album_id AND aa.position = 0
             LEFT JOIN artists art ON aa.artist_id = art.id
             WHERE a.title LIKE ?
-            ORDER BY a.title
-            LIMIT ?
+               OR EXISTS (
+                   SELECT 1 FROM album_custom_tags ct
+                   WHERE ct.album_id = a.id AND (ct.key LIKE ? OR ct.value LIKE ?)
+               )
+               OR EXISTS (
+                   SELECT 1 FROM album_notes n
+                   WHERE n.album_id = a.id AND n.content LIKE ?
+               )
+            ORDER BY CASE
+                         WHEN a.title = ? COLLATE NOCASE THEN 0
+                         WHEN a.title LIKE ? THEN 1
+                         WHEN a.title LIKE ? THEN 2
+                         ELSE 3
+                     END,
+                     (SELECT COALESCE(SUM(ts.play_count), 0) FROM releases r
+                      JOIN tracks t ON t.release_id = r.id
+                      JOIN track_stats ts ON ts.track_id = t.id
+                      WHERE r.album_id = a.id) DESC,
+                     a.title
+            LIMIT ? OFFSET ?
             "#,
         )
         .bind(&pattern)
-        .bind(limit_i64)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(query)
+        .bind(&prefix)
+        .bind(&pattern)
+        .bind(page.album_count as i64)
+        .bind(page.album_offset as i64)
         .fetch_all(&self.inner.read_pool)
         .await?;
 
@@ -552,7 +1057,7 @@ impl Database {
             })
             .collect();
 
-        // Search tracks by title, with album and artist info
+        // Search tracks by title or credited artist, with album and artist info
         let track_rows = sqlx::query(
             r#"
             SELECT t.id, t.title, t.duration_ms, r.album_id,
@@ -563,13 +1068,31 @@ impl Database {
             JOIN albums a ON r.album_id = a.id
             LEFT JOIN album_artists aa ON a.id = aa.album_id AND aa.position = 0
             LEFT JOIN artists art ON aa.artist_id = art.id
+            LEFT JOIN track_stats ts ON ts.track_id = t.id
             WHERE t.title LIKE ?
-            ORDER BY t.title
-            LIMIT ?
+               OR EXISTS (
+                   SELECT 1 FROM track_artists ta
+                   JOIN artists ta_art ON ta_art.id = ta.artist_id
+                   WHERE ta.track_id = t.id AND ta.role IS NOT ? AND ta_art.name LIKE ?
+               )
+            ORDER BY CASE
+                         WHEN t.title = ? COLLATE NOCASE THEN 0
+                         WHEN t.title LIKE ? THEN 1
+                         WHEN t.title LIKE ? THEN 2
+                         ELSE 3
+                     END,
+                     COALESCE(ts.play_count, 0) DESC, t.title
+            LIMIT ? OFFSET ?
             "#,
         )
         .bind(&pattern)
-        .bind(limit_i64)
+        .bind(COMPOSER_ROLE)
+        .bind(&pattern)
+        .bind(query)
+        .bind(&prefix)
+        .bind(&pattern)
+        .bind(page.track_count as i64)
+        .bind(page.track_offset as i64)
         .fetch_all(&self.inner.read_pool)
         .await?;
 
@@ -661,8 +1184,8 @@ impl Database {
                 id, album_id, release_name, year, discogs_release_id,
                 bandcamp_release_id, format, label, catalog_number, country, barcode,
                 import_status, managed_locally, managed_in_cloud, unmanaged_path,
-                _updated_at, created_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                unverified, _updated_at, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&release.id)
@@ -680,6 +1203,7 @@ impl Database {
         .bind(release.managed_locally)
         .bind(release.managed_in_cloud)
         .bind(&release.unmanaged_path)
+        .bind(release.unverified)
         .bind(release.updated_at.to_rfc3339())
         .bind(release.created_at.to_rfc3339())
         .execute(&mut *conn)
@@ -780,8 +1304,8 @@ impl Database {
                 id, album_id, release_name, year, discogs_release_id,
                 bandcamp_release_id, format, label, catalog_number, country, barcode,
                 import_status, managed_locally, managed_in_cloud, unmanaged_path,
-                _updated_at, created_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                unverified, _updated_at, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&release.id)
@@ -799,6 +1323,7 @@ impl Database {
         .bind(release.managed_locally)
         .bind(release.managed_in_cloud)
         .bind(&release.unmanaged_path)
+        .bind(release.unverified)
         .bind(release.updated_at.to_rfc3339())
         .bind(release.created_at.to_rfc3339())
         .execute(&mut *tx)
@@ -879,6 +1404,15 @@ impl Database {
     pub async fn get_albums(
         &self,
         sort: &[AlbumSortCriterion],
+    ) -> Result<Vec<DbAlbum>, sqlx::Error> {
+        self.get_albums_filtered(sort, &AlbumFilter::default())
+            .await
+    }
+    /// Get the albums matching `filter`, sorted like `get_albums`
+    pub async fn get_albums_filtered(
+        &self,
+        sort: &[AlbumSortCriterion],
+        filter: &AlbumFilter,
     ) -> Result<Vec<DbAlbum>, sqlx::Error> {
         let needs_artist_join = sort.iter().any(|c| c.field == AlbumSortField::Artist);
 
@@ -921,12 +1455,72 @@ impl Database {
                         AlbumSortField::DateAdded => {
                             vec![format!("a.created_at {dir}")]
                         }
+                        AlbumSortField::PlayCount => {
+                            vec![format!(
+                                "(SELECT COALESCE(SUM(ts.play_count), 0) FROM releases r \
+                                 JOIN tracks t ON t.release_id = r.id \
+                                 JOIN track_stats ts ON ts.track_id = t.id \
+                                 WHERE r.album_id = a.id) {dir}"
+                            )]
+                        }
                     }
                 })
                 .collect::<Vec<_>>()
                 .join(", ")
         };
 
+        // Binds are applied below in the same order as these conditions
+        let mut conditions: Vec<String> = Vec::new();
+        if filter.genre.is_some() {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM album_genres ag \
+                 WHERE ag.album_id = a.id AND ag.genre_id = ?)"
+                    .to_string(),
+            );
+        }
+        if filter.decade.is_some() {
+            conditions.push("a.year >= ? AND a.year < ?".to_string());
+        }
+        if filter.collection.is_some() {
+            conditions.push(
+                "EXISTS (SELECT 1 FROM collection_albums ca \
+                 WHERE ca.album_id = a.id AND ca.collection_id = ?)"
+                    .to_string(),
+            );
+        }
+        if let Some(storage) = filter.storage {
+            let managed = match storage {
+                AlbumStorage::Local => "r.managed_locally",
+                AlbumStorage::Cloud => "r.managed_in_cloud",
+                AlbumStorage::Unmanaged => "r.unmanaged_path IS NOT NULL",
+            };
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM releases r WHERE r.album_id = a.id AND {managed})"
+            ));
+        }
+        if let Some(quality) = filter.quality {
+            let audio = "SELECT 1 FROM releases r \
+                 JOIN tracks t ON t.release_id = r.id \
+                 JOIN audio_formats af ON af.track_id = t.id \
+                 WHERE r.album_id = a.id";
+            let lossy = format!(
+                "{audio} AND af.content_type NOT IN ('{}', '{}')",
+                ContentType::Flac.as_str(),
+                ContentType::Wav.as_str()
+            );
+            conditions.push(match quality {
+                AudioQuality::Lossless => {
+                    format!("EXISTS ({audio}) AND NOT EXISTS ({lossy})")
+                }
+                AudioQuality::Lossy => format!("EXISTS ({lossy})"),
+            });
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
         let query = format!(
             "SELECT \
                 a.id, a.title, a.year, a.bandcamp_album_id, a.cover_release_id, \
@@ -937,10 +1531,21 @@ impl Database {
             LEFT JOIN album_discogs ad ON a.id = ad.album_id \
             LEFT JOIN album_musicbrainz amb ON a.id = amb.album_id \
             {artist_join} \
+            {where_clause} \
             ORDER BY {order_by}"
         );
 
-        let rows = sqlx::query(&query).fetch_all(&self.inner.read_pool).await?;
+        let mut query = sqlx::query(&query);
+        if let Some(ref genre) = filter.genre {
+            query = query.bind(crate::genres::genre_key(genre));
+        }
+        if let Some(decade) = filter.decade {
+            query = query.bind(decade).bind(decade + 10);
+        }
+        if let Some(ref collection_id) = filter.collection {
+            query = query.bind(collection_id);
+        }
+        let rows = query.fetch_all(&self.inner.read_pool).await?;
         let mut albums = Vec::new();
         for row in rows {
             let discogs_master_id: Option<String> = row.get("discogs_master_id");
@@ -978,6 +1583,58 @@ impl Database {
         }
         Ok(albums)
     }
+    /// First years of the decades the library's albums are from, earliest first
+    pub async fn get_album_decades(&self) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT DISTINCT (year / 10) * 10 AS decade FROM albums \
+             WHERE year IS NOT NULL ORDER BY decade",
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await
+    }
+    /// Every decade the library's albums are from with its album count,
+    /// earliest first
+    pub async fn get_decades(&self) -> Result<Vec<DbDecade>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT (year / 10) * 10 AS decade, COUNT(*) AS album_count FROM albums \
+             WHERE year IS NOT NULL GROUP BY decade ORDER BY decade",
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbDecade {
+                decade: row.get("decade"),
+                album_count: row.get("album_count"),
+            })
+            .collect())
+    }
+    /// IDs of the albums released from `from_year` through `to_year`,
+    /// earliest first
+    pub async fn get_album_ids_for_years(
+        &self,
+        from_year: i32,
+        to_year: i32,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT id FROM albums WHERE year >= ? AND year <= ? \
+             ORDER BY year, title COLLATE NOCASE",
+        )
+        .bind(from_year)
+        .bind(to_year)
+        .fetch_all(&self.inner.read_pool)
+        .await
+    }
+    /// IDs of the most recently added albums, newest first
+    pub async fn get_recently_added_album_ids(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT id FROM albums ORDER BY created_at DESC LIMIT ?")
+            .bind(limit)
+            .fetch_all(&self.inner.read_pool)
+            .await
+    }
     /// Get album by ID
     pub async fn get_album_by_id(&self, album_id: &str) -> Result<Option<DbAlbum>, sqlx::Error> {
         let row = sqlx::query(
@@ -1120,8 +1777,9 @@ impl Database {
         sqlx::query(
             r#"
             INSERT INTO release_files (
-                id, release_id, original_filename, file_size, content_type, encryption_nonce, _updated_at, created_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                id, release_id, original_filename, file_size, content_type, encryption_nonce,
+                content_sha256, _updated_at, created_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&file.id)
@@ -1130,6 +1788,7 @@ impl Database {
         .bind(file.file_size)
         .bind(file.content_type.as_str())
         .bind(&file.encryption_nonce)
+        .bind(&file.content_sha256)
         .bind(file.updated_at.to_rfc3339())
         .bind(file.created_at.to_rfc3339())
         .execute(&mut *conn)
@@ -1215,6 +1874,8 @@ impl Database {
                 seektable_json: row.get("seektable_json"),
                 audio_data_start: row.get("audio_data_start"),
                 file_id: row.get("file_id"),
+                cue_in_ms: row.get("cue_in_ms"),
+                cue_out_ms: row.get("cue_out_ms"),
                 updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("_updated_at"))
                     .unwrap()
                     .with_timezone(&Utc),
@@ -1226,6 +1887,250 @@ impl Database {
             Ok(None)
         }
     }
+    /// Store detected cue points for a track's audio
+    pub async fn set_audio_format_cue_points(
+        &self,
+        track_id: &str,
+        cue_in_ms: i64,
+        cue_out_ms: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "UPDATE audio_formats SET cue_in_ms = ?, cue_out_ms = ?, _updated_at = ? WHERE track_id = ?",
+        )
+        .bind(cue_in_ms)
+        .bind(cue_out_ms)
+        .bind(Utc::now().to_rfc3339())
+        .bind(track_id)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Get the audio attached to each track of a release, in track order
+    pub async fn get_track_audio_sources(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<DbTrackAudioSource>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT af.id, af.track_id, rf.original_filename, t.duration_ms
+            FROM audio_formats af
+            JOIN tracks t ON t.id = af.track_id
+            LEFT JOIN release_files rf ON rf.id = af.file_id
+            WHERE t.release_id = ?
+            ORDER BY t.disc_number, t.track_number
+            "#,
+        )
+        .bind(release_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbTrackAudioSource {
+                audio_format_id: row.get("id"),
+                track_id: row.get("track_id"),
+                original_filename: row.get("original_filename"),
+                duration_ms: row.get("duration_ms"),
+            })
+            .collect())
+    }
+
+    /// Renumber a release's tracks and move audio between them.
+    ///
+    /// Duration and import status describe the audio, so they move with it. A
+    /// track left without audio is marked failed. Callers validate the remaps.
+    pub async fn remap_release_tracks(
+        &self,
+        release_id: &str,
+        remaps: &[TrackRemap],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+
+        let rows: Vec<(String, Option<i64>, ImportStatus)> = sqlx::query_as(
+            r#"
+            SELECT af.id, t.duration_ms, t.import_status
+            FROM audio_formats af
+            JOIN tracks t ON t.id = af.track_id
+            WHERE t.release_id = ?
+            "#,
+        )
+        .bind(release_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let audio: std::collections::HashMap<String, (Option<i64>, ImportStatus)> = rows
+            .into_iter()
+            .map(|(id, duration_ms, status)| (id, (duration_ms, status)))
+            .collect();
+
+        // audio_formats.track_id is unique, so swaps go through a placeholder.
+        // The placeholder isn't a track; defer the FK check to commit.
+        sqlx::query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE audio_formats SET track_id = 'remap:' || id WHERE track_id IN (SELECT id FROM tracks WHERE release_id = ?)",
+        )
+        .bind(release_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let now = Utc::now().to_rfc3339();
+        for remap in remaps {
+            let (duration_ms, import_status) =
+                match remap.audio_format_id.as_ref().and_then(|id| audio.get(id)) {
+                    Some((duration_ms, status)) => (*duration_ms, *status),
+                    None => (None, ImportStatus::Failed),
+                };
+            sqlx::query(
+                "UPDATE tracks SET track_number = ?, duration_ms = ?, import_status = ?, _updated_at = ? WHERE id = ? AND release_id = ?",
+            )
+            .bind(remap.track_number)
+            .bind(duration_ms)
+            .bind(import_status)
+            .bind(&now)
+            .bind(&remap.track_id)
+            .bind(release_id)
+            .execute(&mut *tx)
+            .await?;
+
+            if let Some(ref audio_format_id) = remap.audio_format_id {
+                sqlx::query("UPDATE audio_formats SET track_id = ?, _updated_at = ? WHERE id = ?")
+                    .bind(&remap.track_id)
+                    .bind(&now)
+                    .bind(audio_format_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Update an album's title, year and artist credits.
+    ///
+    /// Artists are matched by name, case-insensitively; names not in the
+    /// library yet become new artists. Credits are only rewritten when the
+    /// artists or their order changed.
+    pub async fn update_album_tags(
+        &self,
+        album_id: &str,
+        title: &str,
+        year: Option<i32>,
+        artist_names: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query("UPDATE albums SET title = ?, year = ?, _updated_at = ? WHERE id = ?")
+            .bind(title)
+            .bind(year)
+            .bind(&now)
+            .bind(album_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut artist_ids: Vec<String> = Vec::new();
+        for name in artist_names {
+            let existing: Option<String> =
+                sqlx::query_scalar("SELECT id FROM artists WHERE name = ? COLLATE NOCASE LIMIT 1")
+                    .bind(name)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            let id = match existing {
+                Some(id) => id,
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    sqlx::query(
+                        "INSERT INTO artists (id, name, _updated_at, created_at) VALUES (?, ?, ?, ?)",
+                    )
+                    .bind(&id)
+                    .bind(name)
+                    .bind(&now)
+                    .bind(&now)
+                    .execute(&mut *tx)
+                    .await?;
+                    id
+                }
+            };
+            if !artist_ids.contains(&id) {
+                artist_ids.push(id);
+            }
+        }
+
+        let current: Vec<String> = sqlx::query_scalar(
+            "SELECT artist_id FROM album_artists WHERE album_id = ? ORDER BY position",
+        )
+        .bind(album_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        if current != artist_ids {
+            sqlx::query("DELETE FROM album_artists WHERE album_id = ?")
+                .bind(album_id)
+                .execute(&mut *tx)
+                .await?;
+            for (position, artist_id) in artist_ids.iter().enumerate() {
+                sqlx::query(
+                    r#"
+                    INSERT INTO album_artists (id, album_id, artist_id, position, _updated_at, created_at)
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(Uuid::new_v4().to_string())
+                .bind(album_id)
+                .bind(artist_id)
+                .bind(position as i32)
+                .bind(&now)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Update the titles and numbering of a release's tracks.
+    ///
+    /// Tracks whose tags are unchanged are left alone so they don't show up
+    /// in the next sync changeset.
+    pub async fn update_track_tags(
+        &self,
+        release_id: &str,
+        updates: &[TrackTagUpdate],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        for update in updates {
+            sqlx::query(
+                r#"
+                UPDATE tracks SET title = ?, disc_number = ?, track_number = ?, _updated_at = ?
+                WHERE id = ? AND release_id = ?
+                  AND (title IS NOT ? OR disc_number IS NOT ? OR track_number IS NOT ?)
+                "#,
+            )
+            .bind(&update.title)
+            .bind(update.disc_number)
+            .bind(update.track_number)
+            .bind(&now)
+            .bind(&update.track_id)
+            .bind(release_id)
+            .bind(&update.title)
+            .bind(update.disc_number)
+            .bind(update.track_number)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
 
     /// Insert multiple files in a single transaction.
     pub async fn batch_insert_files(&self, files: &[DbFile]) -> Result<(), sqlx::Error> {
@@ -1239,8 +2144,9 @@ impl Database {
             sqlx::query(
                 r#"
                 INSERT INTO release_files (
-                    id, release_id, original_filename, file_size, content_type, encryption_nonce, _updated_at, created_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    id, release_id, original_filename, file_size, content_type, encryption_nonce,
+                    content_sha256, _updated_at, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
             )
             .bind(&file.id)
@@ -1249,6 +2155,7 @@ impl Database {
             .bind(file.file_size)
             .bind(file.content_type.as_str())
             .bind(&file.encryption_nonce)
+            .bind(&file.content_sha256)
             .bind(file.updated_at.to_rfc3339())
             .bind(file.created_at.to_rfc3339())
             .execute(&mut *tx)
@@ -1261,6 +2168,8 @@ impl Database {
 
     /// Atomically finalize an import: insert audio formats, mark tracks complete,
     /// mark release complete, and update import status in a single transaction.
+    /// An import marked `move_to_cloud` also gets its storage transfer to the
+    /// cloud home recorded, for the app to run.
     pub async fn finalize_import(
         &self,
         audio_formats: &[DbAudioFormat],
@@ -1325,6 +2234,19 @@ impl Database {
                 .bind(import_id)
                 .execute(&mut *tx)
                 .await?;
+
+            // An import bound for the cloud home leaves a move there to run
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO storage_transfers (release_id, target, eject_path, started_at)
+                SELECT ?, 'cloud', NULL, ? FROM imports WHERE id = ? AND move_to_cloud
+                "#,
+            )
+            .bind(release_id)
+            .bind(now_ts)
+            .bind(import_id)
+            .execute(&mut *tx)
+            .await?;
         }
 
         tx.commit().await?;
@@ -1780,8 +2702,8 @@ impl Database {
         let mut conn = self.writer()?.lock().await;
         sqlx::query(
             r#"
-            INSERT INTO library_images (id, type, content_type, file_size, width, height, source, source_url, _updated_at, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO library_images (id, type, content_type, file_size, width, height, source, source_url, blurhash, _updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(id) DO UPDATE SET
                 type = excluded.type,
                 content_type = excluded.content_type,
@@ -1790,6 +2712,7 @@ impl Database {
                 height = excluded.height,
                 source = excluded.source,
                 source_url = excluded.source_url,
+                blurhash = excluded.blurhash,
                 _updated_at = excluded._updated_at
             "#,
         )
@@ -1801,6 +2724,7 @@ impl Database {
         .bind(image.height)
         .bind(&image.source)
         .bind(&image.source_url)
+        .bind(&image.blurhash)
         .bind(image.updated_at.to_rfc3339())
         .bind(image.created_at.to_rfc3339())
         .execute(&mut *conn)
@@ -1834,6 +2758,26 @@ impl Database {
         Ok(row.map(row_to_library_image))
     }
 
+    /// Get cover blurhashes for all albums that have one, keyed by album ID
+    pub async fn get_album_cover_blurhashes(
+        &self,
+    ) -> Result<std::collections::HashMap<String, String>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, li.blurhash
+            FROM albums a
+            JOIN library_images li ON li.id = a.cover_release_id AND li.type = 'cover'
+            WHERE li.blurhash IS NOT NULL
+            "#,
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get("id"), row.get("blurhash")))
+            .collect())
+    }
+
     /// Delete a library image by ID and type
     pub async fn delete_library_image(
         &self,
@@ -1919,6 +2863,26 @@ impl Database {
         Ok(())
     }
 
+    /// Set where a release's managed files are kept (clears unmanaged_path)
+    pub async fn set_release_storage(
+        &self,
+        release_id: &str,
+        managed_locally: bool,
+        managed_in_cloud: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "UPDATE releases SET managed_locally = ?, managed_in_cloud = ?, unmanaged_path = NULL, _updated_at = ? WHERE id = ?",
+        )
+        .bind(managed_locally)
+        .bind(managed_in_cloud)
+        .bind(Utc::now().to_rfc3339())
+        .bind(release_id)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
     /// Set unmanaged_path on a release (clears managed flags)
     pub async fn set_release_unmanaged(
         &self,
@@ -1953,6 +2917,68 @@ impl Database {
         Ok(())
     }
 
+    /// Clear the encryption nonce on a file record (after ejecting it as plaintext)
+    pub async fn clear_file_encryption_nonce(&self, file_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "UPDATE release_files SET encryption_nonce = NULL, _updated_at = ? WHERE id = ?",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(file_id)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a storage transfer as started, so it can be resumed
+    pub async fn begin_storage_transfer(
+        &self,
+        release_id: &str,
+        target: &str,
+        eject_path: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            r#"
+            INSERT INTO storage_transfers (release_id, target, eject_path, started_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(release_id) DO UPDATE SET
+                target = excluded.target,
+                eject_path = excluded.eject_path,
+                started_at = excluded.started_at
+            "#,
+        )
+        .bind(release_id)
+        .bind(target)
+        .bind(eject_path)
+        .bind(Utc::now().timestamp())
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Storage transfers that were started and never finished, as
+    /// (release_id, target, eject_path), oldest first
+    pub async fn get_storage_transfers(
+        &self,
+    ) -> Result<Vec<(String, String, Option<String>)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT release_id, target, eject_path FROM storage_transfers ORDER BY started_at",
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await
+    }
+
+    /// Forget a storage transfer once it has finished or been cancelled
+    pub async fn finish_storage_transfer(&self, release_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM storage_transfers WHERE release_id = ?")
+            .bind(release_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
     /// Insert a new import operation record
     pub async fn insert_import(&self, import: &DbImport) -> Result<(), sqlx::Error> {
         let mut conn = self.writer()?.lock().await;
@@ -1960,8 +2986,9 @@ impl Database {
             r#"
             INSERT INTO imports (
                 id, status, release_id, album_title, artist_name,
-                folder_path, created_at, updated_at, error_message
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                folder_path, managed, move_to_cloud, cover_image_path, created_at, updated_at,
+                error_message
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&import.id)
@@ -1970,6 +2997,9 @@ impl Database {
         .bind(&import.album_title)
         .bind(&import.artist_name)
         .bind(&import.folder_path)
+        .bind(import.managed)
+        .bind(import.move_to_cloud)
+        .bind(&import.cover_image_path)
         .bind(import.created_at)
         .bind(import.updated_at)
         .bind(&import.error_message)
@@ -1985,6 +3015,18 @@ impl Database {
             .await?;
         Ok(row.map(|row| self.row_to_import(&row)))
     }
+    /// Folder a release was last imported from, if it came from a folder
+    pub async fn get_release_import_folder(
+        &self,
+        release_id: &str,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT folder_path FROM imports WHERE release_id = ? ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(release_id)
+        .fetch_optional(&self.inner.read_pool)
+        .await
+    }
     /// Get all active (non-complete, non-failed) imports
     pub async fn get_active_imports(&self) -> Result<Vec<DbImport>, sqlx::Error> {
         let rows = sqlx::query(
@@ -2069,29 +3111,105 @@ impl Database {
             album_title: row.get("album_title"),
             artist_name: row.get("artist_name"),
             folder_path: row.get("folder_path"),
+            managed: row.get("managed"),
+            move_to_cloud: row.get("move_to_cloud"),
+            cover_image_path: row.get("cover_image_path"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
             error_message: row.get("error_message"),
         }
     }
 
-    // ---- Sync cursors ----
-
-    /// Get the last applied sequence number for a remote device.
-    pub async fn get_sync_cursor(&self, device_id: &str) -> Result<Option<u64>, sqlx::Error> {
-        let row: Option<(i64,)> =
-            sqlx::query_as("SELECT last_seq FROM sync_cursors WHERE device_id = ?")
-                .bind(device_id)
-                .fetch_optional(&self.inner.read_pool)
-                .await?;
-        Ok(row.map(|(seq,)| seq as u64))
+    /// Record the source files of a folder import as not yet stored
+    pub async fn insert_import_files(
+        &self,
+        import_id: &str,
+        source_paths: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().timestamp();
+        for source_path in source_paths {
+            sqlx::query(
+                "INSERT OR IGNORE INTO import_files (import_id, source_path, stored, updated_at) VALUES (?, ?, 0, ?)",
+            )
+            .bind(import_id)
+            .bind(source_path)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
     }
 
-    /// Set (upsert) the last applied sequence number for a remote device.
-    pub async fn set_sync_cursor(&self, device_id: &str, seq: u64) -> Result<(), sqlx::Error> {
-        let mut conn = self.writer()?.lock().await;
-        sqlx::query(
-            "INSERT INTO sync_cursors (device_id, last_seq) VALUES (?, ?)
+    /// Get the per-file state of a folder import
+    pub async fn get_import_files(
+        &self,
+        import_id: &str,
+    ) -> Result<Vec<DbImportFile>, sqlx::Error> {
+        let rows =
+            sqlx::query("SELECT * FROM import_files WHERE import_id = ? ORDER BY source_path")
+                .bind(import_id)
+                .fetch_all(&self.inner.read_pool)
+                .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbImportFile {
+                import_id: row.get("import_id"),
+                source_path: row.get("source_path"),
+                stored: row.get("stored"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect())
+    }
+
+    /// Mark one source file of an import as stored
+    pub async fn mark_import_file_stored(
+        &self,
+        import_id: &str,
+        source_path: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "UPDATE import_files SET stored = 1, updated_at = ? WHERE import_id = ? AND source_path = ?",
+        )
+        .bind(Utc::now().timestamp())
+        .bind(import_id)
+        .bind(source_path)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Remove all file records of a release (used before re-recording an
+    /// interrupted unmanaged import)
+    pub async fn delete_files_for_release(&self, release_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM release_files WHERE release_id = ?")
+            .bind(release_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    // ---- Sync cursors ----
+
+    /// Get the last applied sequence number for a remote device.
+    pub async fn get_sync_cursor(&self, device_id: &str) -> Result<Option<u64>, sqlx::Error> {
+        let row: Option<(i64,)> =
+            sqlx::query_as("SELECT last_seq FROM sync_cursors WHERE device_id = ?")
+                .bind(device_id)
+                .fetch_optional(&self.inner.read_pool)
+                .await?;
+        Ok(row.map(|(seq,)| seq as u64))
+    }
+
+    /// Set (upsert) the last applied sequence number for a remote device.
+    pub async fn set_sync_cursor(&self, device_id: &str, seq: u64) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "INSERT INTO sync_cursors (device_id, last_seq) VALUES (?, ?)
              ON CONFLICT(device_id) DO UPDATE SET last_seq = excluded.last_seq",
         )
         .bind(device_id)
@@ -2137,6 +3255,101 @@ impl Database {
         Ok(())
     }
 
+    // ---- Sync conflicts ----
+
+    /// Record a sync conflict, replacing any earlier one for the same row.
+    pub async fn upsert_sync_conflict(&self, conflict: &DbSyncConflict) -> Result<(), sqlx::Error> {
+        let fields = serde_json::to_string(&conflict.fields).unwrap();
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "INSERT INTO sync_conflicts (id, table_name, row_id, fields, kept_remote, detected_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(table_name, row_id) DO UPDATE SET
+                 fields = excluded.fields,
+                 kept_remote = excluded.kept_remote,
+                 detected_at = excluded.detected_at",
+        )
+        .bind(&conflict.id)
+        .bind(&conflict.table_name)
+        .bind(&conflict.row_id)
+        .bind(fields)
+        .bind(conflict.kept_remote)
+        .bind(conflict.detected_at.to_rfc3339())
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Unresolved sync conflicts, newest first.
+    pub async fn get_sync_conflicts(&self) -> Result<Vec<DbSyncConflict>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM sync_conflicts ORDER BY detected_at DESC")
+            .fetch_all(&self.inner.read_pool)
+            .await?;
+        Ok(rows.iter().map(Self::row_to_sync_conflict).collect())
+    }
+
+    pub async fn get_sync_conflict(&self, id: &str) -> Result<Option<DbSyncConflict>, sqlx::Error> {
+        let row = sqlx::query("SELECT * FROM sync_conflicts WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.inner.read_pool)
+            .await?;
+        Ok(row.as_ref().map(Self::row_to_sync_conflict))
+    }
+
+    /// Write the chosen value of each conflicted column and drop the conflict.
+    /// `values` pairs column names with values; the caller checks the names
+    /// against the conflict's fields.
+    pub async fn resolve_sync_conflict(
+        &self,
+        conflict: &DbSyncConflict,
+        values: &[(String, Option<String>)],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+
+        if !values.is_empty() {
+            let assignments: Vec<String> = values
+                .iter()
+                .map(|(column, _)| format!("{column} = ?"))
+                .collect();
+            let sql = format!(
+                "UPDATE {} SET {}, _updated_at = ? WHERE id = ?",
+                conflict.table_name,
+                assignments.join(", ")
+            );
+            let mut query = sqlx::query(&sql);
+            for (_, value) in values {
+                query = query.bind(value);
+            }
+            query
+                .bind(Utc::now().to_rfc3339())
+                .bind(&conflict.row_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM sync_conflicts WHERE id = ?")
+            .bind(&conflict.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    fn row_to_sync_conflict(row: &sqlx::sqlite::SqliteRow) -> DbSyncConflict {
+        DbSyncConflict {
+            id: row.get("id"),
+            table_name: row.get("table_name"),
+            row_id: row.get("row_id"),
+            fields: serde_json::from_str(&row.get::<String, _>("fields")).unwrap_or_default(),
+            kept_remote: row.get("kept_remote"),
+            detected_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("detected_at"))
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
     // ---- Attribution names ----
 
     /// Load all pubkey -> display name pairs.
@@ -2293,32 +3506,1781 @@ impl Database {
     }
 
     // -------------------------------------------------------------------------
-    // Release privacy
+    // Album versions
     // -------------------------------------------------------------------------
 
-    /// Set the private flag on a release.
-    pub async fn set_release_private(
+    /// Link two albums as versions of the same work.
+    ///
+    /// Joins whichever version group either album already belongs to. If both
+    /// are already in different groups, the groups are merged.
+    pub async fn link_album_versions(
         &self,
-        release_id: &str,
-        private: bool,
+        album_id: &str,
+        other_album_id: &str,
     ) -> Result<(), sqlx::Error> {
+        if album_id == other_album_id {
+            return Err(sqlx::Error::Protocol(
+                "Cannot link an album as a version of itself".to_string(),
+            ));
+        }
+
         let mut conn = self.writer()?.lock().await;
-        sqlx::query("UPDATE releases SET private = ?, _updated_at = ? WHERE id = ?")
-            .bind(private)
+        let mut tx = conn.begin().await?;
+
+        let group_of = "SELECT version_group_id FROM album_versions WHERE album_id = ?";
+        let group: Option<(String,)> = sqlx::query_as(group_of)
+            .bind(album_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let other_group: Option<(String,)> = sqlx::query_as(group_of)
+            .bind(other_album_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let now = Utc::now().to_rfc3339();
+        let version_group_id = match (group, other_group) {
+            (Some((group,)), Some((other_group,))) => {
+                if group != other_group {
+                    sqlx::query(
+                        "UPDATE album_versions SET version_group_id = ?, _updated_at = ? WHERE version_group_id = ?",
+                    )
+                    .bind(&group)
+                    .bind(&now)
+                    .bind(&other_group)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                group
+            }
+            (Some((group,)), None) | (None, Some((group,))) => group,
+            (None, None) => Uuid::new_v4().to_string(),
+        };
+
+        for id in [album_id, other_album_id] {
+            sqlx::query(
+                r#"
+                INSERT INTO album_versions (id, album_id, version_group_id, label, _updated_at, created_at)
+                VALUES (?, ?, ?, NULL, ?, ?)
+                ON CONFLICT(album_id) DO UPDATE SET
+                    version_group_id = excluded.version_group_id,
+                    _updated_at = excluded._updated_at
+                WHERE version_group_id != excluded.version_group_id
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(id)
+            .bind(&version_group_id)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Remove an album from its version group.
+    ///
+    /// A group left with a single member is dissolved.
+    pub async fn unlink_album_version(&self, album_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+
+        let group: Option<(String,)> =
+            sqlx::query_as("SELECT version_group_id FROM album_versions WHERE album_id = ?")
+                .bind(album_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let Some((version_group_id,)) = group else {
+            return Ok(());
+        };
+
+        sqlx::query("DELETE FROM album_versions WHERE album_id = ?")
+            .bind(album_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let (remaining,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM album_versions WHERE version_group_id = ?")
+                .bind(&version_group_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        if remaining < 2 {
+            sqlx::query("DELETE FROM album_versions WHERE version_group_id = ?")
+                .bind(&version_group_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Set the edition label shown in the version picker for a linked album.
+    pub async fn set_album_version_label(
+        &self,
+        album_id: &str,
+        label: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("UPDATE album_versions SET label = ?, _updated_at = ? WHERE album_id = ?")
+            .bind(label)
             .bind(Utc::now().to_rfc3339())
-            .bind(release_id)
+            .bind(album_id)
             .execute(&mut *conn)
             .await?;
         Ok(())
     }
 
-    /// Check whether a release is marked as private.
-    /// Returns false if the release does not exist.
-    pub async fn is_release_private(&self, release_id: &str) -> Result<bool, sqlx::Error> {
-        let row = sqlx::query("SELECT private FROM releases WHERE id = ?")
-            .bind(release_id)
-            .fetch_optional(&self.inner.read_pool)
+    /// Get all versions in the same group as an album, oldest first.
+    ///
+    /// Includes the album itself. Returns an empty list if the album isn't linked.
+    pub async fn get_album_versions(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<DbAlbumVersion>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT v.album_id, v.version_group_id, v.label, a.title, a.year
+            FROM album_versions v
+            JOIN albums a ON a.id = v.album_id
+            WHERE v.version_group_id = (
+                SELECT version_group_id FROM album_versions WHERE album_id = ?
+            )
+            ORDER BY CASE WHEN a.year IS NULL THEN 1 ELSE 0 END, a.year, a.created_at
+            "#,
+        )
+        .bind(album_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbAlbumVersion {
+                album_id: row.get("album_id"),
+                version_group_id: row.get("version_group_id"),
+                label: row.get("label"),
+                title: row.get("title"),
+                year: row.get("year"),
+            })
+            .collect())
+    }
+
+    /// Get the version group of every linked album, keyed by album ID.
+    pub async fn get_album_version_groups(
+        &self,
+    ) -> Result<std::collections::HashMap<String, String>, sqlx::Error> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT album_id, version_group_id FROM album_versions")
+                .fetch_all(&self.inner.read_pool)
+                .await?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// (release group ID, album ID, release ID) for every release of an album
+    /// that shares its MusicBrainz release group with another album, oldest
+    /// release first.
+    pub async fn get_shared_release_group_releases(
+        &self,
+    ) -> Result<Vec<(String, String, String)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT mb.musicbrainz_release_group_id, r.album_id, r.id
+            FROM album_musicbrainz mb
+            JOIN releases r ON r.album_id = mb.album_id
+            WHERE mb.musicbrainz_release_group_id IN (
+                SELECT musicbrainz_release_group_id FROM album_musicbrainz
+                GROUP BY musicbrainz_release_group_id
+                HAVING COUNT(*) > 1
+            )
+            ORDER BY mb.musicbrainz_release_group_id, r.created_at
+            "#,
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await
+    }
+
+    /// (release ID, hash) for every audio file hashed on import, in the order
+    /// the releases were added.
+    pub async fn get_release_audio_checksums(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT f.release_id, f.content_sha256
+            FROM release_files f
+            JOIN releases r ON r.id = f.release_id
+            WHERE f.content_sha256 IS NOT NULL AND f.content_type LIKE 'audio/%'
+            ORDER BY r.created_at
+            "#,
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await
+    }
+
+    // -------------------------------------------------------------------------
+    // Genres
+    // -------------------------------------------------------------------------
+
+    /// Replace an album's genres, in order.
+    ///
+    /// Names are normalized first. A genre that already exists keeps its
+    /// stored name, so a merge or rename isn't undone by the next import.
+    pub async fn set_album_genres(
+        &self,
+        album_id: &str,
+        names: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let genres = crate::genres::normalize_genres(names);
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query("DELETE FROM album_genres WHERE album_id = ?")
+            .bind(album_id)
+            .execute(&mut *tx)
             .await?;
-        Ok(row.map(|r| r.get::<bool, _>("private")).unwrap_or(false))
+
+        for (position, name) in genres.iter().enumerate() {
+            let genre_id = crate::genres::genre_key(name);
+            sqlx::query(
+                r#"
+                INSERT INTO genres (id, name, _updated_at, created_at)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT(id) DO NOTHING
+                "#,
+            )
+            .bind(&genre_id)
+            .bind(name)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO album_genres (id, album_id, genre_id, position, _updated_at, created_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(album_id)
+            .bind(&genre_id)
+            .bind(position as i64)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Get an album's genre names, in order
+    pub async fn get_album_genres(&self, album_id: &str) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT g.name FROM album_genres ag
+            JOIN genres g ON g.id = ag.genre_id
+            WHERE ag.album_id = ?
+            ORDER BY ag.position
+            "#,
+        )
+        .bind(album_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(name,)| name).collect())
+    }
+
+    /// Get the first genre of every album that has one, keyed by album ID.
+    pub async fn get_album_primary_genres(
+        &self,
+    ) -> Result<std::collections::HashMap<String, String>, sqlx::Error> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT ag.album_id, g.name FROM album_genres ag
+            JOIN genres g ON g.id = ag.genre_id
+            WHERE ag.position = (
+                SELECT MIN(position) FROM album_genres WHERE album_id = ag.album_id
+            )
+            "#,
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Get every genre with its album count, alphabetically
+    pub async fn get_genres(&self) -> Result<Vec<DbGenre>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT g.id, g.name, COUNT(ag.album_id) AS album_count
+            FROM genres g
+            LEFT JOIN album_genres ag ON ag.genre_id = g.id
+            GROUP BY g.id
+            ORDER BY g.name COLLATE NOCASE
+            "#,
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbGenre {
+                id: row.get("id"),
+                name: row.get("name"),
+                album_count: row.get("album_count"),
+            })
+            .collect())
+    }
+
+    /// Get the IDs of albums tagged with a genre
+    pub async fn get_album_ids_for_genre(
+        &self,
+        genre_id: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT album_id FROM album_genres WHERE genre_id = ?")
+                .bind(genre_id)
+                .fetch_all(&self.inner.read_pool)
+                .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Fold several genres into one named `into_name`, creating or renaming
+    /// it as needed. Albums keep their genre positions. Returns the kept
+    /// genre's ID.
+    pub async fn merge_genres(
+        &self,
+        genre_ids: &[String],
+        into_name: &str,
+    ) -> Result<String, sqlx::Error> {
+        let into_id = crate::genres::genre_key(into_name);
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query(
+            r#"
+            INSERT INTO genres (id, name, _updated_at, created_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                _updated_at = excluded._updated_at
+            WHERE name != excluded.name
+            "#,
+        )
+        .bind(&into_id)
+        .bind(into_name)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+
+        for genre_id in genre_ids.iter().filter(|id| **id != into_id) {
+            // An album already tagged with the kept genre is skipped here; its
+            // link to the old genre goes with the delete below
+            sqlx::query(
+                "UPDATE OR IGNORE album_genres SET genre_id = ?, _updated_at = ? WHERE genre_id = ?",
+            )
+            .bind(&into_id)
+            .bind(&now)
+            .bind(genre_id)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("DELETE FROM genres WHERE id = ?")
+                .bind(genre_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(into_id)
+    }
+
+    // -------------------------------------------------------------------------
+    // Radio
+    // -------------------------------------------------------------------------
+
+    /// Get imported tracks similar to a seed track, most similar first.
+    ///
+    /// Tracks by the seed's artists rank highest, then tracks by artists who
+    /// share an album with them, with a bonus for albums within five years of
+    /// the seed's. Ties are shuffled. The seed's own album and its linked
+    /// versions are excluded.
+    pub async fn get_radio_candidates(
+        &self,
+        seed_track_id: &str,
+        limit: u32,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            WITH seed AS (
+                SELECT r.album_id, a.year
+                FROM tracks t
+                JOIN releases r ON r.id = t.release_id
+                JOIN albums a ON a.id = r.album_id
+                WHERE t.id = ?
+            ),
+            seed_artists AS (
+                SELECT artist_id FROM album_artists
+                WHERE album_id = (SELECT album_id FROM seed)
+                UNION
+                SELECT artist_id FROM track_artists WHERE track_id = ?
+            ),
+            adjacent_artists AS (
+                SELECT aa.artist_id FROM album_artists aa
+                WHERE aa.album_id IN (
+                    SELECT album_id FROM album_artists
+                    WHERE artist_id IN (SELECT artist_id FROM seed_artists)
+                )
+                AND aa.artist_id NOT IN (SELECT artist_id FROM seed_artists)
+            ),
+            seed_versions AS (
+                SELECT album_id FROM album_versions
+                WHERE version_group_id = (
+                    SELECT version_group_id FROM album_versions
+                    WHERE album_id = (SELECT album_id FROM seed)
+                )
+            )
+            SELECT t.id
+            FROM tracks t
+            JOIN releases r ON r.id = t.release_id
+            JOIN albums a ON a.id = r.album_id
+            WHERE t.import_status = 'complete'
+                AND a.id != (SELECT album_id FROM seed)
+                AND a.id NOT IN (SELECT album_id FROM seed_versions)
+            ORDER BY
+                CASE
+                    WHEN EXISTS (
+                        SELECT 1 FROM album_artists aa
+                        WHERE aa.album_id = a.id
+                        AND aa.artist_id IN (SELECT artist_id FROM seed_artists)
+                    ) THEN 4
+                    WHEN EXISTS (
+                        SELECT 1 FROM album_artists aa
+                        WHERE aa.album_id = a.id
+                        AND aa.artist_id IN (SELECT artist_id FROM adjacent_artists)
+                    ) THEN 2
+                    ELSE 0
+                END
+                + CASE WHEN ABS(a.year - (SELECT year FROM seed)) <= 5 THEN 1 ELSE 0 END
+                DESC,
+                RANDOM()
+            LIMIT ?
+            "#,
+        )
+        .bind(seed_track_id)
+        .bind(seed_track_id)
+        .bind(limit)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    // -------------------------------------------------------------------------
+    // Listening history
+    // -------------------------------------------------------------------------
+
+    /// Merge listening stats into a track's existing stats.
+    ///
+    /// Keeps the higher play count and the later last-played time. An existing
+    /// rating is never overwritten, so merging the same history twice is a no-op.
+    pub async fn merge_track_stats(
+        &self,
+        track_id: &str,
+        play_count: i64,
+        rating: Option<i32>,
+        last_played_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO track_stats (id, track_id, play_count, rating, last_played_at, _updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(track_id) DO UPDATE SET
+                play_count = MAX(play_count, excluded.play_count),
+                rating = COALESCE(rating, excluded.rating),
+                last_played_at = CASE
+                    WHEN last_played_at IS NULL OR excluded.last_played_at > last_played_at
+                    THEN excluded.last_played_at
+                    ELSE last_played_at
+                END,
+                _updated_at = excluded._updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(track_id)
+        .bind(play_count)
+        .bind(rating)
+        .bind(last_played_at.map(|t| t.to_rfc3339()))
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Get a track's listening stats, if it has any.
+    pub async fn get_track_stats(
+        &self,
+        track_id: &str,
+    ) -> Result<Option<DbTrackStats>, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT track_id, play_count, rating, last_played_at FROM track_stats WHERE track_id = ?",
+        )
+        .bind(track_id)
+        .fetch_optional(&self.inner.read_pool)
+        .await?;
+        Ok(row.map(|row| DbTrackStats {
+            track_id: row.get("track_id"),
+            play_count: row.get("play_count"),
+            rating: row.get("rating"),
+            last_played_at: row.get::<Option<String>, _>("last_played_at").map(|t| {
+                DateTime::parse_from_rfc3339(&t)
+                    .unwrap()
+                    .with_timezone(&Utc)
+            }),
+        }))
+    }
+
+    /// Move a track's plays, playlist entries and stats onto another track,
+    /// before the first one is deleted as a duplicate. Play counts add up,
+    /// the later last-played time wins and an existing rating is kept.
+    pub async fn move_track_listening(
+        &self,
+        from_track_id: &str,
+        to_track_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query("UPDATE play_history SET track_id = ? WHERE track_id = ?")
+            .bind(to_track_id)
+            .bind(from_track_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE playlist_tracks SET track_id = ?, _updated_at = ? WHERE track_id = ?")
+            .bind(to_track_id)
+            .bind(&now)
+            .bind(from_track_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO track_stats (id, track_id, play_count, rating, last_played_at, _updated_at, created_at)
+            SELECT ?, ?, play_count, rating, last_played_at, ?, ?
+            FROM track_stats WHERE track_id = ?
+            ON CONFLICT(track_id) DO UPDATE SET
+                play_count = play_count + excluded.play_count,
+                rating = COALESCE(rating, excluded.rating),
+                last_played_at = CASE
+                    WHEN last_played_at IS NULL OR excluded.last_played_at > last_played_at
+                    THEN excluded.last_played_at
+                    ELSE last_played_at
+                END,
+                _updated_at = excluded._updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(to_track_id)
+        .bind(&now)
+        .bind(&now)
+        .bind(from_track_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM track_stats WHERE track_id = ?")
+            .bind(from_track_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Total plays of a release's tracks.
+    pub async fn get_release_play_count(&self, release_id: &str) -> Result<i64, sqlx::Error> {
+        let (count,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(s.play_count), 0)
+            FROM track_stats s
+            JOIN tracks t ON t.id = s.track_id
+            WHERE t.release_id = ?
+            "#,
+        )
+        .bind(release_id)
+        .fetch_one(&self.inner.read_pool)
+        .await?;
+        Ok(count)
+    }
+
+    // -------------------------------------------------------------------------
+    // Custom tags and notes
+    // -------------------------------------------------------------------------
+
+    /// Replace the works and composer credits of a release's tracks.
+    /// `composers` pairs a track ID with an artist ID, in credit order.
+    pub async fn set_release_works(
+        &self,
+        release_id: &str,
+        works: &[DbTrackWork],
+        composers: &[(String, String)],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query(
+            "DELETE FROM track_works WHERE track_id IN (SELECT id FROM tracks WHERE release_id = ?)",
+        )
+        .bind(release_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM track_artists
+            WHERE role = ? AND track_id IN (SELECT id FROM tracks WHERE release_id = ?)
+            "#,
+        )
+        .bind(COMPOSER_ROLE)
+        .bind(release_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for work in works {
+            sqlx::query(
+                r#"
+                INSERT INTO track_works (
+                    id, track_id, work_title, musicbrainz_work_id, movement_title,
+                    movement_number, _updated_at, created_at
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&work.track_id)
+            .bind(&work.work_title)
+            .bind(&work.musicbrainz_work_id)
+            .bind(&work.movement_title)
+            .bind(work.movement_number)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Composers go after any other credits on the track
+        for (track_id, artist_id) in composers {
+            sqlx::query(
+                r#"
+                INSERT INTO track_artists (id, track_id, artist_id, position, role, _updated_at, created_at)
+                VALUES (
+                    ?, ?, ?,
+                    (SELECT COALESCE(MAX(position) + 1, 0) FROM track_artists WHERE track_id = ?),
+                    ?, ?, ?
+                )
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(track_id)
+            .bind(artist_id)
+            .bind(track_id)
+            .bind(COMPOSER_ROLE)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Works performed by a release's tracks; tracks without one are left out
+    pub async fn get_track_works_for_release(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<DbTrackWork>, sqlx::Error> {
+        let rows: Vec<(String, String, Option<String>, Option<String>, Option<i32>)> =
+            sqlx::query_as(
+                r#"
+                SELECT tw.track_id, tw.work_title, tw.musicbrainz_work_id, tw.movement_title,
+                       tw.movement_number
+                FROM track_works tw
+                JOIN tracks t ON t.id = tw.track_id
+                WHERE t.release_id = ?
+                "#,
+            )
+            .bind(release_id)
+            .fetch_all(&self.inner.read_pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(
+                |(track_id, work_title, musicbrainz_work_id, movement_title, movement_number)| {
+                    DbTrackWork {
+                        track_id,
+                        work_title,
+                        musicbrainz_work_id,
+                        movement_title,
+                        movement_number,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// Replace a release's disc titles
+    pub async fn set_release_discs(
+        &self,
+        release_id: &str,
+        discs: &[DbReleaseDisc],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query("DELETE FROM release_discs WHERE release_id = ?")
+            .bind(release_id)
+            .execute(&mut *tx)
+            .await?;
+        for disc in discs {
+            sqlx::query(
+                r#"
+                INSERT INTO release_discs (id, release_id, disc_number, title, _updated_at, created_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(release_id)
+            .bind(disc.disc_number)
+            .bind(&disc.title)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Titled discs of a release, by disc number
+    pub async fn get_release_discs(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<DbReleaseDisc>, sqlx::Error> {
+        let rows: Vec<(i32, String)> = sqlx::query_as(
+            "SELECT disc_number, title FROM release_discs WHERE release_id = ? ORDER BY disc_number",
+        )
+        .bind(release_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(disc_number, title)| DbReleaseDisc {
+                release_id: release_id.to_string(),
+                disc_number,
+                title,
+            })
+            .collect())
+    }
+
+    /// Composers credited on a release's tracks, as (track ID, artist) pairs
+    /// in credit order
+    pub async fn get_composers_for_release(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<(String, DbArtist)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT ta.track_id, a.* FROM track_artists ta
+            JOIN artists a ON a.id = ta.artist_id
+            JOIN tracks t ON t.id = ta.track_id
+            WHERE t.release_id = ? AND ta.role = ?
+            ORDER BY ta.track_id, ta.position
+            "#,
+        )
+        .bind(release_id)
+        .bind(COMPOSER_ROLE)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("track_id"), Self::row_to_artist(row)))
+            .collect())
+    }
+
+    /// Every album with composer credits, as (album ID, composer) pairs
+    pub async fn get_album_composers(&self) -> Result<Vec<(String, DbArtist)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT r.album_id, a.* FROM track_artists ta
+            JOIN artists a ON a.id = ta.artist_id
+            JOIN tracks t ON t.id = ta.track_id
+            JOIN releases r ON r.id = t.release_id
+            WHERE ta.role = ?
+            ORDER BY r.album_id, a.name COLLATE NOCASE
+            "#,
+        )
+        .bind(COMPOSER_ROLE)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("album_id"), Self::row_to_artist(row)))
+            .collect())
+    }
+
+    /// Replace an album's custom tags, in order
+    pub async fn set_album_custom_tags(
+        &self,
+        album_id: &str,
+        tags: &[DbAlbumCustomTag],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query("DELETE FROM album_custom_tags WHERE album_id = ?")
+            .bind(album_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, tag) in tags.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO album_custom_tags (id, album_id, key, value, position, _updated_at, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(album_id)
+            .bind(&tag.key)
+            .bind(&tag.value)
+            .bind(position as i64)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Get an album's custom tags, in order
+    pub async fn get_album_custom_tags(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<DbAlbumCustomTag>, sqlx::Error> {
+        let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+            "SELECT key, value FROM album_custom_tags WHERE album_id = ? ORDER BY position",
+        )
+        .bind(album_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(key, value)| DbAlbumCustomTag { key, value })
+            .collect())
+    }
+
+    /// Store an album's notes, or remove them when `content` is None
+    pub async fn set_album_notes(
+        &self,
+        album_id: &str,
+        content: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let Some(content) = content else {
+            sqlx::query("DELETE FROM album_notes WHERE album_id = ?")
+                .bind(album_id)
+                .execute(&mut *conn)
+                .await?;
+            return Ok(());
+        };
+
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO album_notes (id, album_id, content, _updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(album_id) DO UPDATE SET
+                content = excluded.content,
+                _updated_at = excluded._updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(album_id)
+        .bind(content)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Get an album's notes, if it has any
+    pub async fn get_album_notes(&self, album_id: &str) -> Result<Option<String>, sqlx::Error> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT content FROM album_notes WHERE album_id = ?")
+                .bind(album_id)
+                .fetch_optional(&self.inner.read_pool)
+                .await?;
+        Ok(row.map(|(content,)| content))
+    }
+
+    // -------------------------------------------------------------------------
+    // Comments
+    // -------------------------------------------------------------------------
+
+    /// Add a member's comment to an album
+    pub async fn insert_album_comment(&self, comment: &DbAlbumComment) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let created_at = comment.created_at.to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO album_comments (id, album_id, author_pubkey, content, _updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&comment.id)
+        .bind(&comment.album_id)
+        .bind(&comment.author_pubkey)
+        .bind(&comment.content)
+        .bind(&created_at)
+        .bind(&created_at)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    fn row_to_album_comment(row: &sqlx::sqlite::SqliteRow) -> DbAlbumComment {
+        DbAlbumComment {
+            id: row.get("id"),
+            album_id: row.get("album_id"),
+            author_pubkey: row.get("author_pubkey"),
+            content: row.get("content"),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    /// Get an album's comments, newest first
+    pub async fn get_album_comments(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<DbAlbumComment>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT * FROM album_comments WHERE album_id = ? ORDER BY created_at DESC, id",
+        )
+        .bind(album_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.iter().map(Self::row_to_album_comment).collect())
+    }
+
+    /// Get a single comment by ID
+    pub async fn get_album_comment(
+        &self,
+        comment_id: &str,
+    ) -> Result<Option<DbAlbumComment>, sqlx::Error> {
+        let row = sqlx::query("SELECT * FROM album_comments WHERE id = ?")
+            .bind(comment_id)
+            .fetch_optional(&self.inner.read_pool)
+            .await?;
+        Ok(row.as_ref().map(Self::row_to_album_comment))
+    }
+
+    /// Remove a comment
+    pub async fn delete_album_comment(&self, comment_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM album_comments WHERE id = ?")
+            .bind(comment_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    // Lyrics
+    // -------------------------------------------------------------------------
+
+    /// Store a track's lyrics, replacing any it already has.
+    pub async fn upsert_track_lyrics(&self, lyrics: &DbTrackLyrics) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO track_lyrics (id, track_id, content, source, _updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(track_id) DO UPDATE SET
+                content = excluded.content,
+                source = excluded.source,
+                _updated_at = excluded._updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&lyrics.track_id)
+        .bind(&lyrics.content)
+        .bind(&lyrics.source)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Get a track's stored lyrics, if they've been fetched.
+    pub async fn get_track_lyrics(
+        &self,
+        track_id: &str,
+    ) -> Result<Option<DbTrackLyrics>, sqlx::Error> {
+        let row =
+            sqlx::query("SELECT track_id, content, source FROM track_lyrics WHERE track_id = ?")
+                .bind(track_id)
+                .fetch_optional(&self.inner.read_pool)
+                .await?;
+        Ok(row.map(|row| DbTrackLyrics {
+            track_id: row.get("track_id"),
+            content: row.get("content"),
+            source: row.get("source"),
+        }))
+    }
+
+    /// Every track's stored lyrics, each with the release its track is on.
+    pub async fn get_all_track_lyrics(&self) -> Result<Vec<(String, DbTrackLyrics)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT t.release_id, l.track_id, l.content, l.source
+            FROM track_lyrics l
+            JOIN tracks t ON t.id = l.track_id
+            "#,
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get("release_id"),
+                    DbTrackLyrics {
+                        track_id: row.get("track_id"),
+                        content: row.get("content"),
+                        source: row.get("source"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Find a track by title and, optionally, an artist credited on the track
+    /// or its album. Case-insensitive; the oldest match wins.
+    pub async fn find_track_by_title(
+        &self,
+        title: &str,
+        artist: Option<&str>,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let row: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT t.id FROM tracks t
+            JOIN releases r ON r.id = t.release_id
+            WHERE t.title = ? COLLATE NOCASE
+              AND (
+                ? IS NULL
+                OR EXISTS (
+                    SELECT 1 FROM track_artists ta JOIN artists a ON a.id = ta.artist_id
+                    WHERE ta.track_id = t.id AND a.name = ? COLLATE NOCASE
+                )
+                OR EXISTS (
+                    SELECT 1 FROM album_artists aa JOIN artists a ON a.id = aa.artist_id
+                    WHERE aa.album_id = r.album_id AND a.name = ? COLLATE NOCASE
+                )
+              )
+            ORDER BY t.created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(title)
+        .bind(artist)
+        .bind(artist)
+        .bind(artist)
+        .fetch_optional(&self.inner.read_pool)
+        .await?;
+        Ok(row.map(|(id,)| id))
+    }
+
+    /// Create a playlist, or replace the tracks of the existing playlist with
+    /// the same name. Returns the playlist ID.
+    pub async fn upsert_playlist_by_name(
+        &self,
+        name: &str,
+        track_ids: &[String],
+    ) -> Result<String, sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        let existing: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM playlists WHERE name = ? ORDER BY created_at LIMIT 1")
+                .bind(name)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let playlist_id = match existing {
+            Some((id,)) => {
+                sqlx::query("DELETE FROM playlist_tracks WHERE playlist_id = ?")
+                    .bind(&id)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query("UPDATE playlists SET _updated_at = ? WHERE id = ?")
+                    .bind(&now)
+                    .bind(&id)
+                    .execute(&mut *tx)
+                    .await?;
+                id
+            }
+            None => {
+                let id = Uuid::new_v4().to_string();
+                sqlx::query(
+                    "INSERT INTO playlists (id, name, _updated_at, created_at) VALUES (?, ?, ?, ?)",
+                )
+                .bind(&id)
+                .bind(name)
+                .bind(&now)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+                id
+            }
+        };
+
+        Self::insert_playlist_tracks(&mut tx, &playlist_id, track_ids, 0, &now).await?;
+
+        tx.commit().await?;
+        Ok(playlist_id)
+    }
+
+    /// Insert tracks into a playlist starting at `first_position`
+    async fn insert_playlist_tracks(
+        conn: &mut sqlx::SqliteConnection,
+        playlist_id: &str,
+        track_ids: &[String],
+        first_position: i64,
+        now: &str,
+    ) -> Result<(), sqlx::Error> {
+        for (offset, track_id) in track_ids.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO playlist_tracks (id, playlist_id, track_id, position, _updated_at, created_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(playlist_id)
+            .bind(track_id)
+            .bind(first_position + offset as i64)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Create an empty playlist
+    pub async fn insert_playlist(&self, playlist: &DbPlaylist) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "INSERT INTO playlists (id, name, _updated_at, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&playlist.id)
+        .bind(&playlist.name)
+        .bind(playlist.updated_at.to_rfc3339())
+        .bind(playlist.created_at.to_rfc3339())
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn rename_playlist(&self, playlist_id: &str, name: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("UPDATE playlists SET name = ?, _updated_at = ? WHERE id = ?")
+            .bind(name)
+            .bind(Utc::now().to_rfc3339())
+            .bind(playlist_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete a playlist and its entries
+    pub async fn delete_playlist(&self, playlist_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM playlists WHERE id = ?")
+            .bind(playlist_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Replace a playlist's tracks, e.g. after a removal or reorder
+    pub async fn set_playlist_tracks(
+        &self,
+        playlist_id: &str,
+        track_ids: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        sqlx::query("DELETE FROM playlist_tracks WHERE playlist_id = ?")
+            .bind(playlist_id)
+            .execute(&mut *tx)
+            .await?;
+        Self::insert_playlist_tracks(&mut tx, playlist_id, track_ids, 0, &now).await?;
+        sqlx::query("UPDATE playlists SET _updated_at = ? WHERE id = ?")
+            .bind(&now)
+            .bind(playlist_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Append tracks to the end of a playlist
+    pub async fn append_playlist_tracks(
+        &self,
+        playlist_id: &str,
+        track_ids: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        let (next_position,): (i64,) = sqlx::query_as(
+            "SELECT COALESCE(MAX(position) + 1, 0) FROM playlist_tracks WHERE playlist_id = ?",
+        )
+        .bind(playlist_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        Self::insert_playlist_tracks(&mut tx, playlist_id, track_ids, next_position, &now).await?;
+        sqlx::query("UPDATE playlists SET _updated_at = ? WHERE id = ?")
+            .bind(&now)
+            .bind(playlist_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Get all playlists, alphabetically
+    pub async fn get_playlists(&self) -> Result<Vec<DbPlaylist>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, _updated_at, created_at FROM playlists ORDER BY name COLLATE NOCASE",
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.iter().map(Self::row_to_playlist).collect())
+    }
+
+    pub async fn get_playlist_by_id(
+        &self,
+        playlist_id: &str,
+    ) -> Result<Option<DbPlaylist>, sqlx::Error> {
+        let row =
+            sqlx::query("SELECT id, name, _updated_at, created_at FROM playlists WHERE id = ?")
+                .bind(playlist_id)
+                .fetch_optional(&self.inner.read_pool)
+                .await?;
+        Ok(row.as_ref().map(Self::row_to_playlist))
+    }
+
+    fn row_to_playlist(row: &sqlx::sqlite::SqliteRow) -> DbPlaylist {
+        DbPlaylist {
+            id: row.get("id"),
+            name: row.get("name"),
+            updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("_updated_at"))
+                .unwrap()
+                .with_timezone(&Utc),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    /// Get the track IDs in a playlist, in order
+    pub async fn get_playlist_track_ids(
+        &self,
+        playlist_id: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            "SELECT track_id FROM playlist_tracks WHERE playlist_id = ? ORDER BY position",
+        )
+        .bind(playlist_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Get a playlist's tracks in order, with their album and album artist
+    pub async fn get_playlist_entries(
+        &self,
+        playlist_id: &str,
+    ) -> Result<Vec<DbPlaylistEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT pt.position, t.id, t.title, t.duration_ms, r.album_id,
+                   a.title as album_title, a.cover_release_id,
+                   COALESCE(art.name, 'Unknown Artist') as artist_name
+            FROM playlist_tracks pt
+            JOIN tracks t ON t.id = pt.track_id
+            JOIN releases r ON t.release_id = r.id
+            JOIN albums a ON r.album_id = a.id
+            LEFT JOIN album_artists aa ON a.id = aa.album_id AND aa.position = 0
+            LEFT JOIN artists art ON aa.artist_id = art.id
+            WHERE pt.playlist_id = ?
+            ORDER BY pt.position
+            "#,
+        )
+        .bind(playlist_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbPlaylistEntry {
+                position: row.get("position"),
+                track_id: row.get("id"),
+                title: row.get("title"),
+                duration_ms: row.get("duration_ms"),
+                album_id: row.get("album_id"),
+                album_title: row.get("album_title"),
+                cover_release_id: row.get("cover_release_id"),
+                artist_name: row.get("artist_name"),
+            })
+            .collect())
+    }
+
+    // -------------------------------------------------------------------------
+    // Collections
+    // -------------------------------------------------------------------------
+
+    pub async fn insert_collection(&self, collection: &DbCollection) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "INSERT INTO collections (id, name, _updated_at, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&collection.id)
+        .bind(&collection.name)
+        .bind(collection.updated_at.to_rfc3339())
+        .bind(collection.created_at.to_rfc3339())
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete a collection. Its albums stay in the library.
+    pub async fn delete_collection(&self, collection_id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM collections WHERE id = ?")
+            .bind(collection_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Get all collections, alphabetically
+    pub async fn get_collections(&self) -> Result<Vec<DbCollection>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, _updated_at, created_at FROM collections ORDER BY name COLLATE NOCASE",
+        )
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbCollection {
+                id: row.get("id"),
+                name: row.get("name"),
+                updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("_updated_at"))
+                    .unwrap()
+                    .with_timezone(&Utc),
+                created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+            .collect())
+    }
+
+    /// Add an album to a collection. Does nothing if it's already there.
+    pub async fn add_album_to_collection(
+        &self,
+        collection_id: &str,
+        album_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO collection_albums (id, collection_id, album_id, _updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(collection_id, album_id) DO NOTHING
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(collection_id)
+        .bind(album_id)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_album_from_collection(
+        &self,
+        collection_id: &str,
+        album_id: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM collection_albums WHERE collection_id = ? AND album_id = ?")
+            .bind(collection_id)
+            .bind(album_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Collection IDs of every album in at least one collection, keyed by album ID
+    pub async fn get_album_collection_ids(
+        &self,
+    ) -> Result<std::collections::HashMap<String, Vec<String>>, sqlx::Error> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT album_id, collection_id FROM collection_albums")
+                .fetch_all(&self.inner.read_pool)
+                .await?;
+        let mut collections: std::collections::HashMap<String, Vec<String>> =
+            std::collections::HashMap::new();
+        for (album_id, collection_id) in rows {
+            collections.entry(album_id).or_default().push(collection_id);
+        }
+        Ok(collections)
+    }
+
+    // -------------------------------------------------------------------------
+    // Release availability
+    // -------------------------------------------------------------------------
+
+    /// Get every release, for scans that walk the whole library.
+    pub async fn get_all_releases(&self) -> Result<Vec<DbRelease>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM releases")
+            .fetch_all(&self.inner.read_pool)
+            .await?;
+        Ok(rows.iter().map(Self::row_to_release).collect())
+    }
+
+    /// Replace a device's availability records.
+    ///
+    /// Rows are only rewritten when they change, so reporting the same state
+    /// again produces no sync traffic. Releases missing from `records` are
+    /// removed for this device.
+    pub async fn set_release_availability(
+        &self,
+        device_id: &str,
+        records: &[DbReleaseAvailability],
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        let now = Utc::now().to_rfc3339();
+
+        for record in records {
+            sqlx::query(
+                r#"
+                INSERT INTO release_availability
+                    (id, release_id, device_id, has_files, cached_files, _updated_at, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(release_id, device_id) DO UPDATE SET
+                    has_files = excluded.has_files,
+                    cached_files = excluded.cached_files,
+                    _updated_at = excluded._updated_at
+                WHERE has_files IS NOT excluded.has_files
+                   OR cached_files IS NOT excluded.cached_files
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&record.release_id)
+            .bind(device_id)
+            .bind(record.has_files)
+            .bind(record.cached_files)
+            .bind(&now)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let current: Vec<String> =
+            sqlx::query_scalar("SELECT release_id FROM release_availability WHERE device_id = ?")
+                .bind(device_id)
+                .fetch_all(&mut *tx)
+                .await?;
+        for release_id in current {
+            if !records.iter().any(|r| r.release_id == release_id) {
+                sqlx::query(
+                    "DELETE FROM release_availability WHERE release_id = ? AND device_id = ?",
+                )
+                .bind(&release_id)
+                .bind(device_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Get the devices that hold a release's files.
+    pub async fn get_release_availability(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<DbReleaseAvailability>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT release_id, device_id, has_files, cached_files
+            FROM release_availability
+            WHERE release_id = ?
+            ORDER BY device_id
+            "#,
+        )
+        .bind(release_id)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| DbReleaseAvailability {
+                release_id: row.get("release_id"),
+                device_id: row.get("device_id"),
+                has_files: row.get("has_files"),
+                cached_files: row.get("cached_files"),
+            })
+            .collect())
+    }
+
+    // -------------------------------------------------------------------------
+    // Release privacy
+    // -------------------------------------------------------------------------
+
+    /// Set the private flag on a release.
+    pub async fn set_release_private(
+        &self,
+        release_id: &str,
+        private: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("UPDATE releases SET private = ?, _updated_at = ? WHERE id = ?")
+            .bind(private)
+            .bind(Utc::now().to_rfc3339())
+            .bind(release_id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Check whether a release is marked as private.
+    /// Returns false if the release does not exist.
+    pub async fn is_release_private(&self, release_id: &str) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("SELECT private FROM releases WHERE id = ?")
+            .bind(release_id)
+            .fetch_optional(&self.inner.read_pool)
+            .await?;
+        Ok(row.map(|r| r.get::<bool, _>("private")).unwrap_or(false))
+    }
+
+    // -------------------------------------------------------------------------
+    // App passwords
+    // -------------------------------------------------------------------------
+
+    /// Store a new app password.
+    pub async fn insert_app_password(
+        &self,
+        app_password: &DbAppPassword,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query(
+            "INSERT INTO app_passwords (id, name, password, created_at, last_used_at, disabled)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&app_password.id)
+        .bind(&app_password.name)
+        .bind(&app_password.password)
+        .bind(app_password.created_at.to_rfc3339())
+        .bind(app_password.last_used_at.map(|t| t.to_rfc3339()))
+        .bind(app_password.disabled)
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// All app passwords, oldest first.
+    pub async fn get_app_passwords(&self) -> Result<Vec<DbAppPassword>, sqlx::Error> {
+        let rows = sqlx::query("SELECT * FROM app_passwords ORDER BY created_at")
+            .fetch_all(&self.inner.read_pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbAppPassword {
+                id: row.get("id"),
+                name: row.get("name"),
+                password: row.get("password"),
+                created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                    .unwrap()
+                    .with_timezone(&Utc),
+                last_used_at: row
+                    .get::<Option<String>, _>("last_used_at")
+                    .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                    .map(|t| t.with_timezone(&Utc)),
+                disabled: row.get("disabled"),
+            })
+            .collect())
+    }
+
+    /// Turn an app password off or back on. A disabled one can't sign in.
+    pub async fn set_app_password_disabled(
+        &self,
+        id: &str,
+        disabled: bool,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("UPDATE app_passwords SET disabled = ? WHERE id = ?")
+            .bind(disabled)
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Revoke an app password.
+    pub async fn delete_app_password(&self, id: &str) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("DELETE FROM app_passwords WHERE id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Record that an app password was just used to sign in.
+    pub async fn touch_app_password(
+        &self,
+        id: &str,
+        used_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        sqlx::query("UPDATE app_passwords SET last_used_at = ? WHERE id = ?")
+            .bind(used_at.to_rfc3339())
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    // Play history
+    // -------------------------------------------------------------------------
+
+    /// Record that a track started playing, dropping the oldest entries past `keep`.
+    ///
+    /// Also counts the play in the track's stats, which outlive the trimmed
+    /// history.
+    pub async fn insert_play_history(
+        &self,
+        track_id: &str,
+        played_at: DateTime<Utc>,
+        keep: u32,
+    ) -> Result<(), sqlx::Error> {
+        let mut conn = self.writer()?.lock().await;
+        let mut tx = conn.begin().await?;
+        sqlx::query("INSERT INTO play_history (id, track_id, played_at) VALUES (?, ?, ?)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(track_id)
+            .bind(played_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "DELETE FROM play_history WHERE id NOT IN (SELECT id FROM play_history ORDER BY played_at DESC LIMIT ?)",
+        )
+        .bind(keep)
+        .execute(&mut *tx)
+        .await?;
+        let now = Utc::now().to_rfc3339();
+        sqlx::query(
+            r#"
+            INSERT INTO track_stats (id, track_id, play_count, last_played_at, _updated_at, created_at)
+            VALUES (?, ?, 1, ?, ?, ?)
+            ON CONFLICT(track_id) DO UPDATE SET
+                play_count = play_count + 1,
+                last_played_at = CASE
+                    WHEN last_played_at IS NULL OR excluded.last_played_at > last_played_at
+                    THEN excluded.last_played_at
+                    ELSE last_played_at
+                END,
+                _updated_at = excluded._updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(track_id)
+        .bind(played_at.to_rfc3339())
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Most recent plays, newest first.
+    pub async fn get_play_history(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<DbPlayHistoryEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, track_id, played_at FROM play_history ORDER BY played_at DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.inner.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DbPlayHistoryEntry {
+                id: row.get("id"),
+                track_id: row.get("track_id"),
+                played_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("played_at"))
+                    .unwrap()
+                    .with_timezone(&Utc),
+            })
+            .collect())
+    }
+
+    /// IDs of the albums with the latest plays, ordered by each album's most
+    /// recent play.
+    pub async fn get_recently_played_album_ids(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT r.album_id
+            FROM play_history ph
+            JOIN tracks t ON t.id = ph.track_id
+            JOIN releases r ON r.id = t.release_id
+            GROUP BY r.album_id
+            ORDER BY MAX(ph.played_at) DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.inner.read_pool)
+        .await
     }
 }
@@ -1,4 +1,7 @@
+mod cipher;
 mod client;
 mod models;
+pub use cipher::{apply_key, is_encrypted, prepare_database, CipherError, DatabaseKey};
+pub(crate) use cipher::{export_sql, is_encrypted_connection};
 pub use client::Database;
 pub use models::*;
@@ -70,6 +70,8 @@ pub struct DbAlbumArtist {
     pub artist_id: String,
     /// Order of this artist in multi-artist albums (0-indexed)
     pub position: i32,
+    /// Text between this artist and the next one in the credit, e.g. " & "
+    pub join_phrase: Option<String>,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
@@ -84,11 +86,15 @@ pub struct DbTrackArtist {
     pub artist_id: String,
     /// Order of this artist in multi-artist tracks (0-indexed)
     pub position: i32,
-    /// Role: "main", "featuring", "remixer", etc.
+    /// Role: "main", "featuring", "remixer", [`COMPOSER_ROLE`], etc.
     pub role: Option<String>,
+    /// Text between this artist and the next one in the credit, e.g. " feat. "
+    pub join_phrase: Option<String>,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
+/// Track artist role for the composer of the work a track performs
+pub const COMPOSER_ROLE: &str = "composer";
 /// Discogs release information for an album.
 ///
 /// Not all Discogs releases have a master — master_id is optional.
@@ -182,6 +188,9 @@ pub struct DbRelease {
     /// When true, this release is excluded from discovery network participation
     /// (no DHT announces, no attestation sharing).
     pub private: bool,
+    /// Created from embedded tags without a MusicBrainz or Discogs match.
+    /// Lets the library find releases still waiting to be matched.
+    pub unverified: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -212,6 +221,43 @@ pub struct DbTrack {
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
+/// A track's audio as offered by the track remapping editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbTrackAudioSource {
+    /// ID of the `audio_formats` row
+    pub audio_format_id: String,
+    /// Track the audio is currently attached to
+    pub track_id: String,
+    /// Name of the file the audio comes from (shared by every track of a CUE image)
+    pub original_filename: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+/// Corrected position and audio for one track of a release
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackRemap {
+    pub track_id: String,
+    pub track_number: Option<i32>,
+    /// `audio_formats` row to attach, or `None` to leave the track without audio
+    pub audio_format_id: Option<String>,
+}
+/// Corrected title and position for one track, from the tag editor
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackTagUpdate {
+    pub track_id: String,
+    pub title: String,
+    pub disc_number: Option<i32>,
+    pub track_number: Option<i32>,
+}
+/// Where one device holds a release's files, as that device last reported
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbReleaseAvailability {
+    pub release_id: String,
+    pub device_id: String,
+    /// Every file is on the device, in library storage or the original folder
+    pub has_files: bool,
+    /// Files downloaded from the cloud into the device's cache
+    pub cached_files: i64,
+}
 /// Physical file belonging to a release
 ///
 /// Stores original file information needed to reconstruct file structure for export
@@ -233,10 +279,13 @@ pub struct DbFile {
     pub original_filename: String,
     pub file_size: i64,
     pub content_type: ContentType,
-    /// Encryption nonce (24 bytes) for efficient range decryption.
+    /// Encrypted container prefix for efficient range decryption: the base
+    /// nonce, preceded by the container header for non-legacy files.
     /// Only set when file is encrypted with chunked encryption.
     /// Stored at import time, used during seek to avoid fetching nonce from cloud.
     pub encryption_nonce: Option<Vec<u8>>,
+    /// Hex SHA-256 of the plaintext file, when it was hashed on import
+    pub content_sha256: Option<String>,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
@@ -288,6 +337,12 @@ pub struct DbAudioFormat {
     pub audio_data_start: i64,
     /// FK to DbFile containing this track's audio data.
     pub file_id: Option<String>,
+    /// Where audible content starts, in ms from the start of the decoded track.
+    /// Detected after import; `None` until the track has been analyzed.
+    pub cue_in_ms: Option<i64>,
+    /// Beat-aligned mix-out point, in ms from the start of the decoded track.
+    /// Trailing silence after this point is skipped on automatic transitions.
+    pub cue_out_ms: Option<i64>,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
@@ -315,10 +370,16 @@ impl DbAlbumArtist {
             album_id: album_id.to_string(),
             artist_id: artist_id.to_string(),
             position,
+            join_phrase: None,
             updated_at: now,
             created_at: now,
         }
     }
+
+    pub fn with_join_phrase(mut self, join_phrase: Option<String>) -> Self {
+        self.join_phrase = join_phrase;
+        self
+    }
 }
 impl DbTrackArtist {
     pub fn new(track_id: &str, artist_id: &str, position: i32, role: Option<String>) -> Self {
@@ -329,10 +390,16 @@ impl DbTrackArtist {
             artist_id: artist_id.to_string(),
             position,
             role,
+            join_phrase: None,
             updated_at: now,
             created_at: now,
         }
     }
+
+    pub fn with_join_phrase(mut self, join_phrase: Option<String>) -> Self {
+        self.join_phrase = join_phrase;
+        self
+    }
 }
 impl DbAlbum {
     #[cfg(test)]
@@ -416,8 +483,11 @@ impl DbAlbum {
     }
 }
 
+/// Album artist every compilation is credited to, whatever its source calls it
+pub const VARIOUS_ARTISTS: &str = "Various Artists";
+
 /// Check if an artist name indicates a "Various Artists" compilation
-fn is_various_artists(name: &str) -> bool {
+pub fn is_various_artists(name: &str) -> bool {
     let lower = name.trim().to_lowercase();
     lower == "various" || lower == "various artists"
 }
@@ -443,6 +513,7 @@ impl DbRelease {
             managed_in_cloud: false,
             unmanaged_path: None,
             private: false,
+            unverified: false,
             created_at: now,
             updated_at: now,
         }
@@ -472,6 +543,7 @@ impl DbRelease {
             managed_in_cloud: false,
             unmanaged_path: None,
             private: false,
+            unverified: false,
             created_at: now,
             updated_at: now,
         }
@@ -499,6 +571,7 @@ impl DbRelease {
             managed_in_cloud: false,
             unmanaged_path: None,
             private: false,
+            unverified: false,
             created_at: now,
             updated_at: now,
         }
@@ -566,13 +639,21 @@ impl DbFile {
             file_size,
             content_type,
             encryption_nonce: None,
+            content_sha256: None,
             updated_at: now,
             created_at: now,
         }
     }
 
+    /// Record the hex SHA-256 of the file's plaintext
+    pub fn with_content_sha256(mut self, sha256: String) -> Self {
+        self.content_sha256 = Some(sha256);
+        self
+    }
+
     /// Set the encryption nonce for efficient encrypted range requests.
-    /// The nonce is the first 24 bytes of the encrypted file.
+    /// The prefix is everything before the first chunk: the container
+    /// header, if any, then the 24-byte base nonce.
     pub fn with_encryption_nonce(mut self, nonce: Vec<u8>) -> Self {
         self.encryption_nonce = Some(nonce);
         self
@@ -687,6 +768,8 @@ impl DbAudioFormat {
             seektable_json,
             audio_data_start,
             file_id,
+            cue_in_ms: None,
+            cue_out_ms: None,
             updated_at: now,
             created_at: now,
         }
@@ -807,6 +890,12 @@ pub struct DbImport {
     pub artist_name: String,
     /// Source folder path
     pub folder_path: String,
+    /// Whether files are copied into bae storage (vs referenced in place)
+    pub managed: bool,
+    /// Whether the stored files move on to the cloud home once imported
+    pub move_to_cloud: bool,
+    /// User-selected local cover image, if any
+    pub cover_image_path: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
     /// Error message if status is Failed
@@ -822,12 +911,25 @@ impl DbImport {
             album_title: album_title.to_string(),
             artist_name: artist_name.to_string(),
             folder_path: folder_path.to_string(),
+            managed: false,
+            move_to_cloud: false,
+            cover_image_path: None,
             created_at: now,
             updated_at: now,
             error_message: None,
         }
     }
 }
+/// Storage state of one source file in a folder import
+#[derive(Debug, Clone)]
+pub struct DbImportFile {
+    pub import_id: String,
+    /// Absolute path of the file in the source folder
+    pub source_path: String,
+    /// True once the file has been written to storage (or recorded, for unmanaged imports)
+    pub stored: bool,
+    pub updated_at: i64,
+}
 /// Type discriminator for library images
 #[derive(Debug, Clone, PartialEq)]
 pub enum LibraryImageType {
@@ -872,10 +974,213 @@ pub struct DbLibraryImage {
     pub source: String,
     /// MB: CAA image ID, Discogs: URL, local: "release://{path}"
     pub source_url: Option<String>,
+    /// Blurhash placeholder, shown while the full image loads
+    pub blurhash: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Album Versions
+// ============================================================================
+
+/// An album's membership in a version group.
+///
+/// Different editions of the same work (original, remaster, deluxe) are often
+/// imported as separate albums. Linking them into a version group lets the
+/// library show one entry per work and the album page switch between editions.
+/// Title and year are joined in from the albums table for display.
+#[derive(Debug, Clone)]
+pub struct DbAlbumVersion {
+    pub album_id: String,
+    pub version_group_id: String,
+    /// User-facing edition name (e.g., "Remaster", "Deluxe Edition")
+    pub label: Option<String>,
+    pub title: String,
+    pub year: Option<i32>,
+}
+
+// ============================================================================
+// Genres
+// ============================================================================
+
+/// A genre and how many albums carry it.
+///
+/// `id` is the name's `genres::genre_key`, so spellings that differ only in
+/// case or separators share one row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbGenre {
+    pub id: String,
+    pub name: String,
+    pub album_count: i64,
+}
+
+/// A decade, as its first year, and how many albums are from it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbDecade {
+    pub decade: i32,
+    pub album_count: i64,
+}
+
+// ============================================================================
+// Custom Tags
+// ============================================================================
+
+/// A tag the user put on an album. Keys are unique per album.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbAlbumCustomTag {
+    pub key: String,
+    /// None for a plain label like "needs re-rip"
+    pub value: Option<String>,
+}
+
+// ============================================================================
+// Comments
+// ============================================================================
+
+/// A note a member of a shared library left on an album
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbAlbumComment {
+    pub id: String,
+    pub album_id: String,
+    /// Hex Ed25519 public key of the member who wrote it
+    pub author_pubkey: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Works
+// ============================================================================
+
+/// The work a track performs
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbTrackWork {
+    pub track_id: String,
+    /// The whole work, e.g. "Symphony No. 5 in C minor, Op. 67"
+    pub work_title: String,
+    pub musicbrainz_work_id: Option<String>,
+    /// "I. Allegro con brio", None when the track is the whole work
+    pub movement_title: Option<String>,
+    pub movement_number: Option<i32>,
+}
+
+/// A disc's subtitle, from its MusicBrainz medium title
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbReleaseDisc {
+    pub release_id: String,
+    pub disc_number: i32,
+    /// "Live at Tidewater" for "Disc 2: Live at Tidewater"
+    pub title: String,
+}
+
+// ============================================================================
+// Listening History
+// ============================================================================
+
+/// Per-track listening stats
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbTrackStats {
+    pub track_id: String,
+    pub play_count: i64,
+    /// Star rating from 1 to 5
+    pub rating: Option<i32>,
+    pub last_played_at: Option<DateTime<Utc>>,
+}
+
+/// Stored lyrics for a track
+#[derive(Debug, Clone)]
+pub struct DbTrackLyrics {
+    pub track_id: String,
+    /// Lyrics JSON, encrypted with the release key in encrypted libraries
+    pub content: Vec<u8>,
+    /// Where the lyrics came from, e.g. "lrclib"
+    pub source: String,
+}
+
+/// A named, ordered list of tracks
+#[derive(Debug, Clone)]
+pub struct DbPlaylist {
+    pub id: String,
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DbPlaylist {
+    pub fn new(name: &str) -> Self {
+        let now = Utc::now();
+        DbPlaylist {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            updated_at: now,
+            created_at: now,
+        }
+    }
+}
+
+/// A track in a playlist, with its album and album artist for display
+#[derive(Debug, Clone)]
+pub struct DbPlaylistEntry {
+    pub position: i64,
+    pub track_id: String,
+    pub title: String,
+    pub duration_ms: Option<i64>,
+    pub album_id: String,
+    pub album_title: String,
+    pub cover_release_id: Option<String>,
+    pub artist_name: String,
+}
+
+/// A named set of albums, e.g. the ones also owned on vinyl
+#[derive(Debug, Clone)]
+pub struct DbCollection {
+    pub id: String,
+    pub name: String,
     pub updated_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
 
+impl DbCollection {
+    pub fn new(name: &str) -> Self {
+        let now = Utc::now();
+        DbCollection {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            updated_at: now,
+            created_at: now,
+        }
+    }
+}
+
+// ============================================================================
+// App passwords
+// ============================================================================
+
+/// A generated password that lets one Subsonic client sign in as the server
+/// user, or sign in on its own as an OpenSubsonic API key. Revoking it locks
+/// out that client without touching the others.
+#[derive(Debug, Clone)]
+pub struct DbAppPassword {
+    pub id: String,
+    /// Label chosen by the user, usually the client app or device
+    pub name: String,
+    /// Kept in the clear because Subsonic token auth needs the raw password
+    pub password: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Refused at sign-in until enabled again
+    pub disabled: bool,
+}
+
+/// One play of a track on this device
+#[derive(Debug, Clone)]
+pub struct DbPlayHistoryEntry {
+    pub id: String,
+    pub track_id: String,
+    pub played_at: DateTime<Utc>,
+}
+
 // ============================================================================
 // Attestations
 // ============================================================================
@@ -894,6 +1199,47 @@ pub struct DbAttestation {
     pub created_at: String,
 }
 
+// ============================================================================
+// Sync conflicts
+// ============================================================================
+
+/// A row edited on this device and on another one since they last synced.
+/// Sync keeps the newer edit; this records what the other edit was until the
+/// user picks the values to keep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbSyncConflict {
+    pub id: String,
+    pub table_name: String,
+    pub row_id: String,
+    /// Only the columns the two edits disagree on
+    pub fields: Vec<DbSyncConflictField>,
+    /// Whether sync kept the other device's edit rather than ours
+    pub kept_remote: bool,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// One column of a conflicted row, as each device last wrote it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbSyncConflictField {
+    pub column: String,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+}
+
+// ============================================================================
+// Cloud usage
+// ============================================================================
+
+/// Traffic between this device and the cloud home in one calendar month
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DbCloudUsage {
+    /// `YYYY-MM`
+    pub month: String,
+    pub bytes_uploaded: i64,
+    pub bytes_downloaded: i64,
+    pub requests: i64,
+}
+
 // ============================================================================
 // Library Search Result Types
 // ============================================================================
@@ -906,6 +1252,32 @@ pub struct LibrarySearchResults {
     pub tracks: Vec<TrackSearchResult>,
 }
 
+/// How many results of each kind a library search returns, after skipping
+/// the first `*_offset` of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchPage {
+    pub artist_count: usize,
+    pub artist_offset: usize,
+    pub album_count: usize,
+    pub album_offset: usize,
+    pub track_count: usize,
+    pub track_offset: usize,
+}
+
+impl SearchPage {
+    /// The best `limit` results of each kind
+    pub fn first(limit: usize) -> Self {
+        Self {
+            artist_count: limit,
+            artist_offset: 0,
+            album_count: limit,
+            album_offset: 0,
+            track_count: limit,
+            track_offset: 0,
+        }
+    }
+}
+
 /// Artist search result with album count
 #[derive(Debug, Clone)]
 pub struct ArtistSearchResult {
@@ -936,24 +1308,67 @@ pub struct TrackSearchResult {
 }
 
 /// Field to sort albums by
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AlbumSortField {
     Title,
     Artist,
     Year,
     DateAdded,
+    /// Plays of all the album's tracks, from their track stats
+    PlayCount,
 }
 
 /// Sort direction
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SortDirection {
     Ascending,
     Descending,
 }
 
 /// A single sort criterion (field + direction)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AlbumSortCriterion {
     pub field: AlbumSortField,
     pub direction: SortDirection,
 }
+
+/// Where an album's files are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlbumStorage {
+    /// A release is managed in the library folder on this device
+    Local,
+    /// A release is managed in the cloud home
+    Cloud,
+    /// A release plays from files bae doesn't manage
+    Unmanaged,
+}
+
+/// Whether an album's audio is compressed lossily
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioQuality {
+    /// Every track is FLAC or WAV
+    Lossless,
+    /// At least one track is in another format
+    Lossy,
+}
+
+/// Narrows the albums returned by `get_albums_filtered`. Fields left unset
+/// match every album.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlbumFilter {
+    /// Genre name, in any spelling that normalizes to the genre
+    pub genre: Option<String>,
+    /// First year of the decade, e.g. 1990
+    pub decade: Option<i32>,
+    pub storage: Option<AlbumStorage>,
+    pub quality: Option<AudioQuality>,
+    /// Collection ID
+    pub collection: Option<String>,
+}
+
+impl AlbumFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
@@ -0,0 +1,528 @@
+//! Built-in demo library
+//!
+//! Seeds a fresh library with the fixture albums used by bae-mocks, plus a
+//! short generated tone per track, so the server can be tried against bae-web
+//! without importing music or configuring storage.
+
+use crate::content_type::ContentType;
+use crate::db::{
+    DbAlbum, DbAlbumArtist, DbArtist, DbAudioFormat, DbFile, DbLibraryImage, DbRelease, DbTrack,
+    DbTrackArtist, ImportStatus, LibraryImageType,
+};
+use crate::library::{LibraryError, LibraryManager};
+use crate::library_dir::LibraryDir;
+use axum::extract::Request;
+use axum::http::{Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::info;
+use uuid::Uuid;
+
+/// Fixture albums shared with bae-mocks
+const FIXTURE_JSON: &str = include_str!("../../bae-mocks/fixtures/data.json");
+
+/// Cover art for each fixture album, keyed by `{artist}_{title}.png` slug
+const COVERS: &[(&str, &[u8])] = &[
+    (
+        "apartment-garden_grow-light.png",
+        include_bytes!("../../bae-mocks/public/covers/apartment-garden_grow-light.png"),
+    ),
+    (
+        "apartment-garden_window-sill.png",
+        include_bytes!("../../bae-mocks/public/covers/apartment-garden_window-sill.png"),
+    ),
+    (
+        "copy-machine_collated.png",
+        include_bytes!("../../bae-mocks/public/covers/copy-machine_collated.png"),
+    ),
+    (
+        "glass-harbor_landlocked.png",
+        include_bytes!("../../bae-mocks/public/covers/glass-harbor_landlocked.png"),
+    ),
+    (
+        "glass-harbor_pacific-standard.png",
+        include_bytes!("../../bae-mocks/public/covers/glass-harbor_pacific-standard.png"),
+    ),
+    (
+        "newspaper-weather_back-page.png",
+        include_bytes!("../../bae-mocks/public/covers/newspaper-weather_back-page.png"),
+    ),
+    (
+        "newspaper-weather_tomorrows-forecast.png",
+        include_bytes!("../../bae-mocks/public/covers/newspaper-weather_tomorrows-forecast.png"),
+    ),
+    (
+        "parking-structure_level-4.png",
+        include_bytes!("../../bae-mocks/public/covers/parking-structure_level-4.png"),
+    ),
+    (
+        "stairwell-echo_floors-1-12.png",
+        include_bytes!("../../bae-mocks/public/covers/stairwell-echo_floors-1-12.png"),
+    ),
+    (
+        "the-borrowed-time_interest.png",
+        include_bytes!("../../bae-mocks/public/covers/the-borrowed-time_interest.png"),
+    ),
+    (
+        "the-borrowed-time_seconds.png",
+        include_bytes!("../../bae-mocks/public/covers/the-borrowed-time_seconds.png"),
+    ),
+    (
+        "the-checkout-lane_express.png",
+        include_bytes!("../../bae-mocks/public/covers/the-checkout-lane_express.png"),
+    ),
+    (
+        "the-cold-equations_fuel-weight.png",
+        include_bytes!("../../bae-mocks/public/covers/the-cold-equations_fuel-weight.png"),
+    ),
+    (
+        "the-cold-equations_mission-control.png",
+        include_bytes!("../../bae-mocks/public/covers/the-cold-equations_mission-control.png"),
+    ),
+    (
+        "the-filing-cabinets_alphabetical.png",
+        include_bytes!("../../bae-mocks/public/covers/the-filing-cabinets_alphabetical.png"),
+    ),
+    (
+        "the-last-payphone_dial-tone.png",
+        include_bytes!("../../bae-mocks/public/covers/the-last-payphone_dial-tone.png"),
+    ),
+    (
+        "the-midnight-signal_neon-frequencies.png",
+        include_bytes!("../../bae-mocks/public/covers/the-midnight-signal_neon-frequencies.png"),
+    ),
+    (
+        "the-waiting-room_your-number.png",
+        include_bytes!("../../bae-mocks/public/covers/the-waiting-room_your-number.png"),
+    ),
+    (
+        "velvet-mathematics_proof-by-induction.png",
+        include_bytes!("../../bae-mocks/public/covers/velvet-mathematics_proof-by-induction.png"),
+    ),
+    (
+        "velvet-mathematics_set-theory.png",
+        include_bytes!("../../bae-mocks/public/covers/velvet-mathematics_set-theory.png"),
+    ),
+];
+
+/// Sample audio parameters: mono 16-bit PCM, 8 seconds per track
+const SAMPLE_RATE: u32 = 22_050;
+const BITS_PER_SAMPLE: u16 = 16;
+const SAMPLE_SECONDS: u32 = 8;
+
+/// Size of the canonical WAV header written by `sample_wav`
+const WAV_HEADER_LEN: usize = 44;
+
+/// Subsonic API methods that change server state. Clients send these as GET
+/// requests, so the HTTP method alone doesn't identify a write.
+const SUBSONIC_WRITE_METHODS: &[&str] = &[
+    "star",
+    "unstar",
+    "setRating",
+    "scrobble",
+    "createPlaylist",
+    "updatePlaylist",
+    "deletePlaylist",
+    "savePlayQueue",
+    "createBookmark",
+    "deleteBookmark",
+    "createShare",
+    "updateShare",
+    "deleteShare",
+    "createInternetRadioStation",
+    "updateInternetRadioStation",
+    "deleteInternetRadioStation",
+    "createPodcastChannel",
+    "deletePodcastChannel",
+    "deletePodcastEpisode",
+    "downloadPodcastEpisode",
+    "refreshPodcasts",
+    "jukeboxControl",
+    "startScan",
+    "createUser",
+    "updateUser",
+    "deleteUser",
+    "changePassword",
+    "addChatMessage",
+];
+
+#[derive(Debug, Deserialize)]
+struct FixtureData {
+    albums: Vec<FixtureAlbum>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureAlbum {
+    artist: String,
+    title: String,
+    year: i32,
+    #[serde(default)]
+    tracks: Vec<String>,
+}
+
+/// Middleware that keeps the demo server read-only.
+///
+/// Subsonic write methods get a Subsonic error response; any other request
+/// that isn't a GET, HEAD or OPTIONS is refused with 403.
+pub async fn reject_writes(req: Request, next: Next) -> Response {
+    let subsonic_method = req
+        .uri()
+        .path()
+        .strip_prefix("/rest/")
+        .map(|method| method.strip_suffix(".view").unwrap_or(method));
+    if let Some(method) = subsonic_method {
+        if SUBSONIC_WRITE_METHODS.contains(&method) {
+            return crate::subsonic::error_response(
+                StatusCode::FORBIDDEN,
+                50,
+                "The demo library is read-only",
+            );
+        }
+    }
+
+    if !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return (StatusCode::FORBIDDEN, "The demo library is read-only").into_response();
+    }
+
+    next.run(req).await
+}
+
+/// Populate an empty library with the demo albums.
+///
+/// Releases are unmanaged and point at generated WAV files under
+/// `{library_dir}/demo/{release_id}`, so the Subsonic stream endpoint serves
+/// them straight from disk. Returns the number of albums created.
+pub async fn seed_demo_library(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+) -> Result<usize, LibraryError> {
+    let fixture: FixtureData = serde_json::from_str(FIXTURE_JSON)
+        .map_err(|e| LibraryError::Import(format!("Invalid demo fixture: {}", e)))?;
+
+    let mut artists: HashMap<String, DbArtist> = HashMap::new();
+
+    for (album_index, fixture_album) in fixture.albums.iter().enumerate() {
+        let artist = match artists.get(&fixture_album.artist) {
+            Some(artist) => artist.clone(),
+            None => {
+                let artist = new_artist(&fixture_album.artist);
+                library_manager.insert_artist(&artist).await?;
+                artists.insert(fixture_album.artist.clone(), artist.clone());
+                artist
+            }
+        };
+
+        seed_album(
+            library_manager,
+            library_dir,
+            fixture_album,
+            &artist,
+            album_index,
+        )
+        .await?;
+    }
+
+    info!(
+        "Seeded demo library with {} albums by {} artists",
+        fixture.albums.len(),
+        artists.len()
+    );
+
+    Ok(fixture.albums.len())
+}
+
+async fn seed_album(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    fixture_album: &FixtureAlbum,
+    artist: &DbArtist,
+    album_index: usize,
+) -> Result<(), LibraryError> {
+    let now = Utc::now();
+    let release_id = Uuid::new_v4().to_string();
+    let audio_dir = library_dir.join("demo").join(&release_id);
+    tokio::fs::create_dir_all(&audio_dir).await?;
+
+    let has_cover = write_cover(library_manager, library_dir, fixture_album, &release_id).await?;
+
+    let album = DbAlbum {
+        id: Uuid::new_v4().to_string(),
+        title: fixture_album.title.clone(),
+        year: Some(fixture_album.year),
+        discogs_release: None,
+        musicbrainz_release: None,
+        bandcamp_album_id: None,
+        cover_release_id: has_cover.then(|| release_id.clone()),
+        is_compilation: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let release = DbRelease {
+        id: release_id.clone(),
+        album_id: album.id.clone(),
+        release_name: None,
+        year: Some(fixture_album.year),
+        discogs_release_id: None,
+        bandcamp_release_id: None,
+        format: Some("Digital".to_string()),
+        label: None,
+        catalog_number: None,
+        country: None,
+        barcode: None,
+        import_status: ImportStatus::Queued,
+        managed_locally: false,
+        managed_in_cloud: false,
+        unmanaged_path: Some(audio_dir.to_string_lossy().to_string()),
+        private: false,
+        unverified: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let duration_ms = (SAMPLE_SECONDS * 1000) as i64;
+    let tracks: Vec<DbTrack> = fixture_album
+        .tracks
+        .iter()
+        .enumerate()
+        .map(|(i, title)| DbTrack {
+            id: Uuid::new_v4().to_string(),
+            release_id: release_id.clone(),
+            title: title.clone(),
+            disc_number: Some(1),
+            track_number: Some(i as i32 + 1),
+            duration_ms: Some(duration_ms),
+            discogs_position: None,
+            import_status: ImportStatus::Queued,
+            updated_at: now,
+            created_at: now,
+        })
+        .collect();
+
+    library_manager
+        .insert_album_with_release_and_tracks(&album, &release, &tracks)
+        .await?;
+    library_manager
+        .insert_album_artist(&DbAlbumArtist::new(&album.id, &artist.id, 0))
+        .await?;
+
+    let mut files = Vec::with_capacity(tracks.len());
+    let mut audio_formats = Vec::with_capacity(tracks.len());
+    for (track_index, track) in tracks.iter().enumerate() {
+        library_manager
+            .insert_track_artist(&DbTrackArtist::new(
+                &track.id,
+                &artist.id,
+                0,
+                Some("main".to_string()),
+            ))
+            .await?;
+
+        let wav = sample_wav(album_index, track_index);
+        let filename = format!("{:02}.wav", track_index + 1);
+        tokio::fs::write(audio_dir.join(&filename), &wav).await?;
+
+        let file = DbFile::new(&release_id, &filename, wav.len() as i64, ContentType::Wav);
+        audio_formats.push(
+            DbAudioFormat::new(
+                &track.id,
+                ContentType::Wav,
+                None,
+                false,
+                SAMPLE_RATE as i64,
+                BITS_PER_SAMPLE as i64,
+                "[]".to_string(),
+                WAV_HEADER_LEN as i64,
+            )
+            .with_file_id(&file.id),
+        );
+        files.push(file);
+    }
+
+    library_manager.batch_add_files(&files).await?;
+
+    let track_ids: Vec<&str> = tracks.iter().map(|t| t.id.as_str()).collect();
+    library_manager
+        .finalize_import(&audio_formats, &track_ids, &release_id, None)
+        .await?;
+
+    Ok(())
+}
+
+fn new_artist(name: &str) -> DbArtist {
+    let now = Utc::now();
+    DbArtist {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        sort_name: None,
+        discogs_artist_id: None,
+        bandcamp_artist_id: None,
+        musicbrainz_artist_id: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// Copy the bundled cover for an album into the library image store.
+/// Returns false if the fixture album has no bundled cover.
+async fn write_cover(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    fixture_album: &FixtureAlbum,
+    release_id: &str,
+) -> Result<bool, LibraryError> {
+    let slug = cover_slug(&fixture_album.artist, &fixture_album.title);
+    let Some((_, data)) = COVERS.iter().find(|(name, _)| *name == slug) else {
+        return Ok(false);
+    };
+
+    let image_path = library_dir.image_path(release_id);
+    if let Some(parent) = image_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&image_path, data).await?;
+
+    let now = Utc::now();
+    library_manager
+        .upsert_library_image(&DbLibraryImage {
+            id: release_id.to_string(),
+            image_type: LibraryImageType::Cover,
+            content_type: ContentType::Png,
+            file_size: data.len() as i64,
+            width: None,
+            height: None,
+            source: "local".to_string(),
+            source_url: None,
+            blurhash: crate::image_placeholder::blurhash_blocking(data.to_vec()).await,
+            updated_at: now,
+            created_at: now,
+        })
+        .await?;
+
+    Ok(true)
+}
+
+/// Cover filename used by bae-mocks: `{artist}_{title}.png`, lowercased and hyphenated
+fn cover_slug(artist: &str, title: &str) -> String {
+    let slug = |s: &str| s.to_lowercase().replace(' ', "-").replace('\'', "");
+    format!("{}_{}.png", slug(artist), slug(title))
+}
+
+/// Generate a short WAV file for a demo track.
+///
+/// Each track is a soft two-note figure whose pitch is derived from the album
+/// and track position, so consecutive tracks are audibly different.
+fn sample_wav(album_index: usize, track_index: usize) -> Vec<u8> {
+    let num_samples = SAMPLE_RATE * SAMPLE_SECONDS;
+    let data_len = num_samples * u32::from(BITS_PER_SAMPLE / 8);
+
+    let mut wav = Vec::with_capacity(WAV_HEADER_LEN + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * u32::from(BITS_PER_SAMPLE / 8)).to_le_bytes());
+    wav.extend_from_slice(&(BITS_PER_SAMPLE / 8).to_le_bytes());
+    wav.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+
+    // Semitones above A3, kept within two octaves
+    let root = ((album_index * 5 + track_index * 2) % 24) as f32;
+    let root_hz = 220.0 * 2f32.powf(root / 12.0);
+    let fifth_hz = root_hz * 1.5;
+    let half = num_samples / 2;
+    let fade = SAMPLE_RATE / 10;
+
+    for n in 0..num_samples {
+        let t = n as f32 / SAMPLE_RATE as f32;
+        let hz = if n < half { root_hz } else { fifth_hz };
+        let pos = n % half;
+        let envelope = (pos.min(half - pos) as f32 / fade as f32).min(1.0);
+        let sample = (2.0 * std::f32::consts::PI * hz * t).sin() * envelope * 0.25;
+        wav.extend_from_slice(&((sample * i16::MAX as f32) as i16).to_le_bytes());
+    }
+
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_fixture_album_has_a_cover() {
+        let fixture: FixtureData = serde_json::from_str(FIXTURE_JSON).unwrap();
+        for album in &fixture.albums {
+            let slug = cover_slug(&album.artist, &album.title);
+            assert!(
+                COVERS.iter().any(|(name, _)| *name == slug),
+                "missing cover {}",
+                slug
+            );
+        }
+    }
+
+    async fn serve_read_only() -> String {
+        let app = axum::Router::new()
+            .route("/rest/ping", axum::routing::get(|| async { "ok" }))
+            .route("/rest/star", axum::routing::get(|| async { "ok" }))
+            .route("/cloud/upload", axum::routing::put(|| async { "ok" }))
+            .layer(axum::middleware::from_fn(reject_writes));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+        format!("http://127.0.0.1:{}", port)
+    }
+
+    #[tokio::test]
+    async fn reject_writes_refuses_subsonic_writes() {
+        let base = serve_read_only().await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("{}/rest/star.view?id=1", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["subsonic-response"]["status"], "failed");
+        assert_eq!(body["subsonic-response"]["error"]["code"], 50);
+
+        let response = client
+            .put(format!("{}/cloud/upload", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn reject_writes_allows_reads() {
+        let base = serve_read_only().await;
+        let response = reqwest::get(format!("{}/rest/ping", base)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.text().await.unwrap(), "ok");
+    }
+
+    #[test]
+    fn sample_wav_header_matches_data_length() {
+        let wav = sample_wav(0, 0);
+        let expected_data = (SAMPLE_RATE * SAMPLE_SECONDS * 2) as usize;
+        assert_eq!(wav.len(), WAV_HEADER_LEN + expected_data);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[36..40], b"data");
+        let data_len = u32::from_le_bytes(wav[40..44].try_into().unwrap());
+        assert_eq!(data_len as usize, expected_data);
+    }
+}
@@ -1,8 +1,31 @@
+//! Device link: a QR code that bootstraps a mobile device into a library.
+//!
+//! Each link carries a one-time ticket. The issuing device writes the ticket
+//! to the cloud home under `device-links/{nonce}`; the joining device redeems
+//! it with a signed `POST /device-links/{nonce}/redeem` to the proxy, which
+//! reads and deletes the ticket under a lock and checks its expiry. Only one
+//! device can redeem a ticket, so a screenshot of an old QR code can't enroll
+//! another device through bae.
+//!
+//! After scanning, both devices show a short authentication string: seven
+//! emoji derived from the link's keys. If someone swapped the QR code on the
+//! way, the two sequences differ. Confirming a match records a VerifyDevice
+//! entry in the membership chain.
+
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
 use qrcode::render::svg;
 use qrcode::QrCode;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::cloud_home::CloudHome;
+use crate::encryption;
+
+/// How long a device link can be scanned after it's generated.
+pub const LINK_TTL_MINUTES: i64 = 10;
 
 /// Contains the library encryption key and full Ed25519 secret key.
 /// Treat as highly sensitive -- do not log or expose in debug output.
@@ -12,23 +35,119 @@ struct DeviceLinkPayload {
     encryption_key: String,
     signing_key: String,
     library_id: String,
+    /// Ticket nonce (hex)
+    nonce: String,
+    /// RFC 3339
+    expires_at: String,
+}
+
+/// The one-time ticket behind a device link.
+#[derive(Debug, Clone)]
+pub struct DeviceLinkTicket {
+    pub nonce: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl DeviceLinkTicket {
+    /// A fresh ticket that expires `LINK_TTL_MINUTES` from now.
+    pub fn generate() -> Self {
+        Self {
+            nonce: hex::encode(&encryption::generate_random_key()[..16]),
+            expires_at: Utc::now() + chrono::Duration::minutes(LINK_TTL_MINUTES),
+        }
+    }
+
+    /// Device ID the linked device is known by in the membership chain
+    pub fn device_id(&self) -> String {
+        format!("link-{}", self.nonce)
+    }
+}
+
+/// A decoded, unexpired device link.
+pub struct DeviceLink {
+    pub proxy_url: String,
+    pub encryption_key: Vec<u8>,
+    pub signing_key: Vec<u8>,
+    pub library_id: String,
+    pub nonce: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+const TICKET_PREFIX: &str = "device-links/";
+
+fn ticket_key(nonce: &str) -> String {
+    format!("{TICKET_PREFIX}{nonce}")
+}
+
+/// Store a ticket in the cloud home so the link carrying it can be redeemed,
+/// clearing out expired tickets that were never redeemed. Call before showing
+/// the QR code.
+///
+/// The ticket holds its expiry as Unix seconds, which the proxy checks on
+/// redemption.
+pub async fn issue_ticket(
+    cloud_home: &dyn CloudHome,
+    ticket: &DeviceLinkTicket,
+) -> Result<(), String> {
+    delete_expired_tickets(cloud_home, Utc::now()).await;
+
+    cloud_home
+        .write(
+            &ticket_key(&ticket.nonce),
+            ticket.expires_at.timestamp().to_string().into_bytes(),
+        )
+        .await
+        .map_err(|e| format!("Failed to store device link ticket: {e}"))
+}
+
+/// Delete tickets that expired before `now`. Failures are logged; a
+/// leftover ticket can't be redeemed anyway.
+async fn delete_expired_tickets(cloud_home: &dyn CloudHome, now: DateTime<Utc>) {
+    let keys = match cloud_home.list(TICKET_PREFIX).await {
+        Ok(keys) => keys,
+        Err(e) => {
+            warn!("Failed to list device link tickets: {e}");
+            return;
+        }
+    };
+    for key in keys {
+        let expired = match cloud_home.read(&key).await {
+            Ok(bytes) => String::from_utf8_lossy(&bytes)
+                .parse::<i64>()
+                .ok()
+                .is_none_or(|expires_at| expires_at <= now.timestamp()),
+            Err(e) => {
+                warn!("Failed to read device link ticket {key}: {e}");
+                continue;
+            }
+        };
+        if expired {
+            if let Err(e) = cloud_home.delete(&key).await {
+                warn!("Failed to delete expired device link ticket {key}: {e}");
+            }
+        }
+    }
 }
 
 /// Generate a QR code SVG string containing the device link payload.
 ///
 /// The QR code encodes a JSON object with base64url-encoded keys so a mobile
-/// device can scan it and bootstrap into the same library.
+/// device can scan it and bootstrap into the same library. The ticket must be
+/// issued with `issue_ticket` for the link to work.
 pub fn generate_qr_svg(
     proxy_url: &str,
     encryption_key: &[u8],
     signing_key: &[u8],
     library_id: &str,
+    ticket: &DeviceLinkTicket,
 ) -> Result<String, String> {
     let payload = DeviceLinkPayload {
         proxy_url: proxy_url.to_string(),
         encryption_key: URL_SAFE_NO_PAD.encode(encryption_key),
         signing_key: URL_SAFE_NO_PAD.encode(signing_key),
         library_id: library_id.to_string(),
+        nonce: ticket.nonce.clone(),
+        expires_at: ticket.expires_at.to_rfc3339_opts(SecondsFormat::Secs, true),
     };
 
     let json = serde_json::to_string(&payload).map_err(|e| format!("JSON serialization: {e}"))?;
@@ -44,13 +163,118 @@ pub fn generate_qr_svg(
     Ok(svg)
 }
 
-/// Decode a device link payload from a JSON string.
+/// Emoji for the short authentication string, indexed by 6 bits. The iOS app
+/// carries the same table.
+const SAS_EMOJI: [(&str, &str); 64] = [
+    ("🐶", "Dog"),
+    ("🐱", "Cat"),
+    ("🦁", "Lion"),
+    ("🐎", "Horse"),
+    ("🦄", "Unicorn"),
+    ("🐷", "Pig"),
+    ("🐘", "Elephant"),
+    ("🐰", "Rabbit"),
+    ("🐼", "Panda"),
+    ("🐓", "Rooster"),
+    ("🐧", "Penguin"),
+    ("🐢", "Turtle"),
+    ("🐟", "Fish"),
+    ("🐙", "Octopus"),
+    ("🦋", "Butterfly"),
+    ("🌷", "Flower"),
+    ("🌳", "Tree"),
+    ("🌵", "Cactus"),
+    ("🍄", "Mushroom"),
+    ("🌏", "Globe"),
+    ("🌙", "Moon"),
+    ("☁️", "Cloud"),
+    ("🔥", "Fire"),
+    ("🍌", "Banana"),
+    ("🍎", "Apple"),
+    ("🍓", "Strawberry"),
+    ("🌽", "Corn"),
+    ("🍕", "Pizza"),
+    ("🎂", "Cake"),
+    ("❤️", "Heart"),
+    ("😀", "Smiley"),
+    ("🤖", "Robot"),
+    ("🎩", "Hat"),
+    ("👓", "Glasses"),
+    ("🔧", "Spanner"),
+    ("🎅", "Santa"),
+    ("👍", "Thumbs Up"),
+    ("☂️", "Umbrella"),
+    ("⌛", "Hourglass"),
+    ("⏰", "Clock"),
+    ("🎁", "Gift"),
+    ("💡", "Light Bulb"),
+    ("📕", "Book"),
+    ("✏️", "Pencil"),
+    ("📎", "Paperclip"),
+    ("✂️", "Scissors"),
+    ("🔒", "Lock"),
+    ("🔑", "Key"),
+    ("🔨", "Hammer"),
+    ("☎️", "Telephone"),
+    ("🏁", "Flag"),
+    ("🚂", "Train"),
+    ("🚲", "Bicycle"),
+    ("✈️", "Aeroplane"),
+    ("🚀", "Rocket"),
+    ("🏆", "Trophy"),
+    ("⚽", "Ball"),
+    ("🎸", "Guitar"),
+    ("🎺", "Trumpet"),
+    ("🔔", "Bell"),
+    ("⚓", "Anchor"),
+    ("🎧", "Headphones"),
+    ("📁", "Folder"),
+    ("📌", "Pin"),
+];
+
+/// Short authentication string for a device link, as (emoji, name) pairs.
 ///
-/// Returns (proxy_url, encryption_key_bytes, signing_key_bytes, library_id).
-pub fn decode(json: &str) -> Result<(String, Vec<u8>, Vec<u8>, String), String> {
+/// SHA-256 over the library ID, ticket nonce, encryption key and the public
+/// half of the signing key; the first 42 bits pick seven emoji. The scanning
+/// device computes it from the payload it read, so a substituted QR code
+/// shows a different sequence.
+pub fn short_authentication_string(
+    encryption_key: &[u8],
+    signing_key: &[u8],
+    library_id: &str,
+    nonce: &str,
+) -> Vec<(&'static str, &'static str)> {
+    let public_key = &signing_key[signing_key.len().saturating_sub(32)..];
+    let mut hasher = Sha256::new();
+    hasher.update(b"bae device link sas\0");
+    hasher.update(library_id.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(nonce.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(encryption_key);
+    hasher.update(public_key);
+    let digest = hasher.finalize();
+
+    let bits = digest[..6]
+        .iter()
+        .fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte));
+    (0..7)
+        .map(|i| SAS_EMOJI[((bits >> (42 - 6 * i)) & 0x3f) as usize])
+        .collect()
+}
+
+/// Decode a device link payload from a JSON string, refusing expired links.
+pub fn decode(json: &str) -> Result<DeviceLink, String> {
     let payload: DeviceLinkPayload =
         serde_json::from_str(json).map_err(|e| format!("Invalid device link JSON: {e}"))?;
 
+    let expires_at = DateTime::parse_from_rfc3339(&payload.expires_at)
+        .map_err(|e| format!("Invalid device link expiry: {e}"))?
+        .with_timezone(&Utc);
+    if expires_at <= Utc::now() {
+        return Err("This device link has expired".to_string());
+    }
+
     let encryption_key = URL_SAFE_NO_PAD
         .decode(&payload.encryption_key)
         .map_err(|e| format!("Invalid encryption key encoding: {e}"))?;
@@ -73,17 +297,22 @@ pub fn decode(json: &str) -> Result<(String, Vec<u8>, Vec<u8>, String), String>
         ));
     }
 
-    Ok((
-        payload.proxy_url,
+    Ok(DeviceLink {
+        proxy_url: payload.proxy_url,
         encryption_key,
         signing_key,
-        payload.library_id,
-    ))
+        library_id: payload.library_id,
+        nonce: payload.nonce,
+        expires_at,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cloud_home::folder::FolderCloudHome;
+
+    const FUTURE: &str = "2999-01-01T00:00:00Z";
 
     #[test]
     fn roundtrip_encode_decode() {
@@ -91,8 +320,16 @@ mod tests {
         let signing_key = [0xCD_u8; 64];
         let proxy_url = "https://alice.bae.fm";
         let library_id = "lib-abc-123";
+        let ticket = DeviceLinkTicket::generate();
 
-        let svg = generate_qr_svg(proxy_url, &encryption_key, &signing_key, library_id).unwrap();
+        let svg = generate_qr_svg(
+            proxy_url,
+            &encryption_key,
+            &signing_key,
+            library_id,
+            &ticket,
+        )
+        .unwrap();
 
         // SVG should contain valid markup (starts with XML declaration then <svg>)
         assert!(svg.contains("<svg"));
@@ -104,30 +341,74 @@ mod tests {
             encryption_key: URL_SAFE_NO_PAD.encode(encryption_key),
             signing_key: URL_SAFE_NO_PAD.encode(signing_key),
             library_id: library_id.to_string(),
+            nonce: ticket.nonce.clone(),
+            expires_at: ticket.expires_at.to_rfc3339_opts(SecondsFormat::Secs, true),
         };
         let json = serde_json::to_string(&payload).unwrap();
 
-        let (decoded_url, decoded_enc, decoded_sign, decoded_lib) = decode(&json).unwrap();
-        assert_eq!(decoded_url, proxy_url);
-        assert_eq!(decoded_enc, encryption_key);
-        assert_eq!(decoded_sign, signing_key);
-        assert_eq!(decoded_lib, library_id);
+        let link = decode(&json).unwrap();
+        assert_eq!(link.proxy_url, proxy_url);
+        assert_eq!(link.encryption_key, encryption_key);
+        assert_eq!(link.signing_key, signing_key);
+        assert_eq!(link.library_id, library_id);
+        assert_eq!(link.nonce, ticket.nonce);
+        assert_eq!(link.expires_at.timestamp(), ticket.expires_at.timestamp());
     }
 
     #[test]
     fn svg_output_is_valid() {
-        let svg =
-            generate_qr_svg("https://example.com", &[0x01; 32], &[0x02; 64], "lib-1").unwrap();
+        let svg = generate_qr_svg(
+            "https://example.com",
+            &[0x01; 32],
+            &[0x02; 64],
+            "lib-1",
+            &DeviceLinkTicket::generate(),
+        )
+        .unwrap();
         assert!(svg.contains("<svg"));
         assert!(svg.contains("<rect"));
         assert!(svg.contains("</svg>"));
     }
 
+    #[test]
+    fn sas_depends_on_every_link_field() {
+        let signing_key = [[0x09_u8; 32], [0x02_u8; 32]].concat();
+        let sas = short_authentication_string(&[0x01; 32], &signing_key, "lib-1", "abcd");
+        let names: Vec<&str> = sas.iter().map(|(_, name)| *name).collect();
+        // Shared with the iOS app's test
+        assert_eq!(
+            names,
+            [
+                "Moon",
+                "Flag",
+                "Telephone",
+                "Cactus",
+                "Trophy",
+                "Folder",
+                "Dog"
+            ]
+        );
+
+        assert_ne!(
+            short_authentication_string(&[0x03; 32], &signing_key, "lib-1", "abcd"),
+            sas
+        );
+        assert_ne!(
+            short_authentication_string(&[0x01; 32], &signing_key, "lib-1", "abce"),
+            sas
+        );
+        let other_identity = [[0x09_u8; 32], [0x04_u8; 32]].concat();
+        assert_ne!(
+            short_authentication_string(&[0x01; 32], &other_identity, "lib-1", "abcd"),
+            sas
+        );
+    }
+
     #[test]
     fn decode_invalid_json() {
         let result = decode("not valid json");
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Invalid device link JSON"));
+        assert!(result.err().unwrap().contains("Invalid device link JSON"));
     }
 
     #[test]
@@ -136,32 +417,80 @@ mod tests {
         let short_enc = URL_SAFE_NO_PAD.encode([0xAA_u8; 16]);
         let valid_sign = URL_SAFE_NO_PAD.encode([0xBB_u8; 64]);
         let json = format!(
-            r#"{{"proxy_url":"x","encryption_key":"{short_enc}","signing_key":"{valid_sign}","library_id":"y"}}"#
+            r#"{{"proxy_url":"x","encryption_key":"{short_enc}","signing_key":"{valid_sign}","library_id":"y","nonce":"n","expires_at":"{FUTURE}"}}"#
         );
         let result = decode(&json);
         assert!(result.is_err());
         assert!(result
-            .unwrap_err()
+            .err()
+            .unwrap()
             .contains("Encryption key must be 32 bytes"));
 
         // 32-byte signing key (too short)
         let valid_enc = URL_SAFE_NO_PAD.encode([0xAA_u8; 32]);
         let short_sign = URL_SAFE_NO_PAD.encode([0xBB_u8; 32]);
         let json = format!(
-            r#"{{"proxy_url":"x","encryption_key":"{valid_enc}","signing_key":"{short_sign}","library_id":"y"}}"#
+            r#"{{"proxy_url":"x","encryption_key":"{valid_enc}","signing_key":"{short_sign}","library_id":"y","nonce":"n","expires_at":"{FUTURE}"}}"#
         );
         let result = decode(&json);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Signing key must be 64 bytes"));
+        assert!(result
+            .err()
+            .unwrap()
+            .contains("Signing key must be 64 bytes"));
     }
 
     #[test]
     fn decode_invalid_key_encoding() {
         // Valid JSON but bad base64 in encryption_key
-        let json =
-            r#"{"proxy_url":"x","encryption_key":"!!!","signing_key":"AAAA","library_id":"y"}"#;
-        let result = decode(json);
+        let json = format!(
+            r#"{{"proxy_url":"x","encryption_key":"!!!","signing_key":"AAAA","library_id":"y","nonce":"n","expires_at":"{FUTURE}"}}"#
+        );
+        let result = decode(&json);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Invalid encryption key"));
+        assert!(result.err().unwrap().contains("Invalid encryption key"));
+    }
+
+    #[test]
+    fn decode_refuses_expired_link() {
+        let enc = URL_SAFE_NO_PAD.encode([0xAA_u8; 32]);
+        let sign = URL_SAFE_NO_PAD.encode([0xBB_u8; 64]);
+        let json = format!(
+            r#"{{"proxy_url":"x","encryption_key":"{enc}","signing_key":"{sign}","library_id":"y","nonce":"n","expires_at":"2020-01-01T00:00:00Z"}}"#
+        );
+        let result = decode(&json);
+        assert!(result.err().unwrap().contains("expired"));
+
+        // Links from before tickets existed have no expiry at all
+        let json = format!(
+            r#"{{"proxy_url":"x","encryption_key":"{enc}","signing_key":"{sign}","library_id":"y"}}"#
+        );
+        assert!(decode(&json).is_err());
+    }
+
+    #[tokio::test]
+    async fn issuing_clears_expired_tickets() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let cloud_home = FolderCloudHome::new(tmp.path().to_path_buf());
+        let mut stale = DeviceLinkTicket::generate();
+        stale.expires_at = Utc::now() - chrono::Duration::minutes(1);
+        issue_ticket(&cloud_home, &stale).await.unwrap();
+        cloud_home
+            .write("device-links/garbled", b"soon".to_vec())
+            .await
+            .unwrap();
+
+        let fresh = DeviceLinkTicket::generate();
+        issue_ticket(&cloud_home, &fresh).await.unwrap();
+
+        assert_eq!(
+            cloud_home.list(TICKET_PREFIX).await.unwrap(),
+            vec![ticket_key(&fresh.nonce)]
+        );
+        let stored = cloud_home.read(&ticket_key(&fresh.nonce)).await.unwrap();
+        assert_eq!(
+            stored,
+            fresh.expires_at.timestamp().to_string().into_bytes()
+        );
     }
 }
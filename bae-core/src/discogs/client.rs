@@ -57,6 +57,8 @@ pub struct DiscogsSearchResult {
 struct ArtistCredit {
     id: u64,
     name: String,
+    #[serde(default)]
+    join: String,
 }
 /// Detailed release response from Discogs
 #[derive(Debug, Deserialize)]
@@ -91,6 +93,7 @@ struct TrackResponse {
     position: String,
     title: String,
     duration: Option<String>,
+    artists: Option<Vec<ArtistCredit>>,
 }
 #[derive(Debug, Deserialize)]
 struct LabelResponse {
@@ -211,17 +214,10 @@ impl DiscogsClient {
                     position: t.position,
                     title: t.title,
                     duration: t.duration,
+                    artists: discogs_artists(t.artists.unwrap_or_default()),
                 })
                 .collect();
-            let artists = release
-                .artists
-                .unwrap_or_default()
-                .into_iter()
-                .map(|a| DiscogsArtist {
-                    id: a.id.to_string(),
-                    name: a.name,
-                })
-                .collect();
+            let artists = discogs_artists(release.artists.unwrap_or_default());
             let primary_image = release.images.as_ref().and_then(|images| {
                 images
                     .iter()
@@ -319,3 +315,24 @@ impl DiscogsClient {
         }
     }
 }
+
+fn discogs_artists(credits: Vec<ArtistCredit>) -> Vec<DiscogsArtist> {
+    credits
+        .into_iter()
+        .map(|a| DiscogsArtist {
+            id: a.id.to_string(),
+            join: join_phrase(&a.join),
+            name: a.name,
+        })
+        .collect()
+}
+
+/// Discogs gives joins bare ("&", ",", "Feat."); space them the way they read
+/// between two names
+fn join_phrase(join: &str) -> Option<String> {
+    match join.trim() {
+        "" => None,
+        "," => Some(", ".to_string()),
+        join => Some(format!(" {join} ")),
+    }
+}
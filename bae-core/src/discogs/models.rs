@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 pub struct DiscogsArtist {
     pub id: String,
     pub name: String,
+    /// Text joining this artist to the next in the credit (" & ", ", ")
+    #[serde(default)]
+    pub join: Option<String>,
 }
 /// Represents a Discogs release search result
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -30,4 +33,7 @@ pub struct DiscogsTrack {
     pub position: String,
     pub title: String,
     pub duration: Option<String>,
+    /// Set when the track is credited to different artists than the release
+    #[serde(default)]
+    pub artists: Vec<DiscogsArtist>,
 }
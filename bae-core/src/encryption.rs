@@ -1,16 +1,181 @@
 use crate::sodium_ffi;
 use hkdf::Hkdf;
 use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::ptr;
 use std::sync::Once;
 use thiserror::Error;
 use tracing::info;
 
-/// 64KB plaintext chunks
+/// 64KB plaintext chunks: the default size, and the only one legacy files use
 pub const CHUNK_SIZE: usize = 65536;
 /// Each encrypted chunk: plaintext + 16-byte auth tag
 pub const ENCRYPTED_CHUNK_SIZE: usize = CHUNK_SIZE + sodium_ffi::ABYTES;
+/// Chunk size for files that end up in cloud storage, where every chunk a
+/// range read touches adds to the request count
+pub const CLOUD_CHUNK_SIZE: usize = 1 << 20;
+/// Largest chunk size a container header can declare
+pub const MAX_CHUNK_SIZE: usize = 1 << 24;
+/// Base nonce stored at the start of every encrypted file (after the header, if any)
+pub const NONCE_SIZE: usize = sodium_ffi::NPUBBYTES;
+/// Versioned container header: 6 magic bytes, version, log2(chunk size)
+pub const HEADER_SIZE: usize = 8;
+/// Newest container version this build reads and writes
+pub const CONTAINER_VERSION: u8 = 2;
+
+const CONTAINER_MAGIC: &[u8; 6] = b"baeenc";
+
+/// Layout of an encrypted file: `[header][base_nonce][chunk_0][chunk_1]...`
+///
+/// Version 1 files have no header and always use 64KB chunks. Version 2 adds
+/// a header declaring the chunk size. Writers pick the oldest version that
+/// can express their chunk size, so default-sized files stay readable by
+/// older readers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainerFormat {
+    pub version: u8,
+    /// Plaintext bytes per chunk
+    pub chunk_size: usize,
+}
+
+impl ContainerFormat {
+    /// Headerless format with 64KB chunks
+    pub const LEGACY: ContainerFormat = ContainerFormat {
+        version: 1,
+        chunk_size: CHUNK_SIZE,
+    };
+
+    /// Format a writer should use for `chunk_size`-byte chunks.
+    ///
+    /// Chunk sizes must be powers of two from 64KB to 16MB.
+    pub fn for_chunk_size(chunk_size: usize) -> Result<Self, EncryptionError> {
+        if chunk_size == CHUNK_SIZE {
+            return Ok(Self::LEGACY);
+        }
+        if !chunk_size.is_power_of_two() || !(CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk_size) {
+            return Err(EncryptionError::Encryption(format!(
+                "Unsupported chunk size {}: must be a power of two from {} to {}",
+                chunk_size, CHUNK_SIZE, MAX_CHUNK_SIZE
+            )));
+        }
+        Ok(ContainerFormat {
+            version: CONTAINER_VERSION,
+            chunk_size,
+        })
+    }
+
+    /// Detect the format from the start of an encrypted file, or from the
+    /// prefix stored in the DB.
+    ///
+    /// Data without the container magic is a legacy file. Headers from a
+    /// newer container version are rejected rather than misread.
+    pub fn detect(data: &[u8]) -> Result<Self, EncryptionError> {
+        if data.len() < HEADER_SIZE || !data.starts_with(CONTAINER_MAGIC) {
+            return Ok(Self::LEGACY);
+        }
+        let version = data[6];
+        if version != CONTAINER_VERSION {
+            return Err(EncryptionError::Decryption(format!(
+                "Unsupported container version {} (this build reads up to {})",
+                version, CONTAINER_VERSION
+            )));
+        }
+        let chunk_size = 1usize
+            .checked_shl(data[7] as u32)
+            .filter(|size| (CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(size))
+            .ok_or_else(|| {
+                EncryptionError::Decryption(format!(
+                    "Invalid chunk size in container header: 2^{}",
+                    data[7]
+                ))
+            })?;
+        Ok(ContainerFormat {
+            version,
+            chunk_size,
+        })
+    }
+
+    /// Split a container prefix (header + base nonce) into its format and nonce.
+    pub fn split_prefix(prefix: &[u8]) -> Result<(Self, [u8; NONCE_SIZE]), EncryptionError> {
+        let format = Self::detect(prefix)?;
+        let nonce = prefix
+            .get(format.header_len()..format.prefix_len())
+            .and_then(|nonce| nonce.try_into().ok())
+            .ok_or_else(|| {
+                EncryptionError::Decryption("Ciphertext too short for nonce".to_string())
+            })?;
+        Ok((format, nonce))
+    }
+
+    /// Header bytes written before the nonce (empty for legacy files)
+    pub fn header(&self) -> Vec<u8> {
+        if self.version == 1 {
+            return Vec::new();
+        }
+        let mut header = CONTAINER_MAGIC.to_vec();
+        header.push(self.version);
+        header.push(self.chunk_size.trailing_zeros() as u8);
+        header
+    }
+
+    pub fn header_len(&self) -> usize {
+        if self.version == 1 {
+            0
+        } else {
+            HEADER_SIZE
+        }
+    }
+
+    /// Bytes before the first chunk: header plus base nonce
+    pub fn prefix_len(&self) -> usize {
+        self.header_len() + NONCE_SIZE
+    }
+
+    /// Each encrypted chunk: plaintext + 16-byte auth tag
+    pub fn encrypted_chunk_size(&self) -> usize {
+        self.chunk_size + sodium_ffi::ABYTES
+    }
+
+    /// Calculate the encrypted byte range for a plaintext byte range.
+    ///
+    /// Returns `(chunk_start, chunk_end)` - the byte positions in the encrypted file
+    /// where the needed chunks are located. Does NOT include the prefix.
+    ///
+    /// Use this for efficient range requests: fetch the prefix separately (or from DB),
+    /// then fetch just `chunk_start..chunk_end` from storage.
+    pub fn chunk_range(&self, plaintext_start: u64, plaintext_end: u64) -> (u64, u64) {
+        let chunk_size = self.chunk_size as u64;
+        let encrypted_chunk_size = self.encrypted_chunk_size() as u64;
+        let start_chunk = plaintext_start / chunk_size;
+        let end_chunk = (plaintext_end.saturating_sub(1)) / chunk_size;
+
+        let prefix_len = self.prefix_len() as u64;
+        let chunk_start = prefix_len + start_chunk * encrypted_chunk_size;
+        let chunk_end = prefix_len + (end_chunk + 1) * encrypted_chunk_size;
+
+        (chunk_start, chunk_end)
+    }
+
+    /// Size of the original plaintext for an encrypted file of `encrypted_len` bytes.
+    pub fn plaintext_len(&self, encrypted_len: u64) -> u64 {
+        let chunk_bytes = encrypted_len.saturating_sub(self.prefix_len() as u64);
+        let chunk_count = chunk_bytes.div_ceil(self.encrypted_chunk_size() as u64);
+        chunk_bytes.saturating_sub(chunk_count * sodium_ffi::ABYTES as u64)
+    }
+
+    /// Size of the encrypted file for `plaintext_len` bytes of plaintext.
+    /// Empty plaintext still gets one chunk holding just the auth tag.
+    pub fn encrypted_len(&self, plaintext_len: u64) -> u64 {
+        let chunk_count = plaintext_len.div_ceil(self.chunk_size as u64).max(1);
+        self.prefix_len() as u64 + plaintext_len + chunk_count * sodium_ffi::ABYTES as u64
+    }
+
+    /// Number of chunks in `data_len` bytes of encrypted chunk data
+    fn chunk_count(&self, data_len: usize) -> usize {
+        data_len.div_ceil(self.encrypted_chunk_size())
+    }
+}
 
 static SODIUM_INIT: Once = Once::new();
 
@@ -54,6 +219,80 @@ pub enum EncryptionError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
+
+/// Argon2id parameters for deriving the master key from a passphrase.
+///
+/// Stored in the library manifest, so the same passphrase yields the same key
+/// on any device that has the manifest. None of it is secret.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PassphraseKdf {
+    /// Hex-encoded random salt
+    pub salt: String,
+    pub ops_limit: u64,
+    pub mem_limit: u64,
+}
+
+impl PassphraseKdf {
+    /// Fresh salt with libsodium's moderate limits (about a second and 256 MiB)
+    pub fn generate() -> Self {
+        ensure_sodium_init();
+        let mut salt = [0u8; sodium_ffi::PWHASH_SALTBYTES];
+        unsafe { sodium_ffi::randombytes_buf(salt.as_mut_ptr(), salt.len()) };
+        PassphraseKdf {
+            salt: hex::encode(salt),
+            ops_limit: sodium_ffi::PWHASH_OPSLIMIT_MODERATE,
+            mem_limit: sodium_ffi::PWHASH_MEMLIMIT_MODERATE as u64,
+        }
+    }
+
+    /// Derive the hex-encoded master key, in the form the keyring stores it.
+    /// Slow by design; keep it off the UI thread.
+    pub fn derive_key(&self, passphrase: &str) -> Result<String, EncryptionError> {
+        ensure_sodium_init();
+        let salt = hex::decode(&self.salt)
+            .ok()
+            .filter(|salt| salt.len() == sodium_ffi::PWHASH_SALTBYTES)
+            .ok_or_else(|| EncryptionError::KeyManagement("Invalid passphrase salt".into()))?;
+
+        let mut key = [0u8; 32];
+        let result = unsafe {
+            sodium_ffi::crypto_pwhash(
+                key.as_mut_ptr(),
+                key.len() as u64,
+                passphrase.as_ptr().cast(),
+                passphrase.len() as u64,
+                salt.as_ptr(),
+                self.ops_limit,
+                self.mem_limit as usize,
+                sodium_ffi::PWHASH_ALG_ARGON2ID13,
+            )
+        };
+        if result != 0 {
+            return Err(EncryptionError::KeyManagement(
+                "Passphrase key derivation failed".into(),
+            ));
+        }
+        Ok(hex::encode(key))
+    }
+}
+
+/// Turn what the user typed to unlock a library into a hex master key. A
+/// 64-hex recovery key is taken as is; anything else is a passphrase, which
+/// needs the library's KDF parameters.
+pub fn resolve_unlock_input(
+    input: &str,
+    kdf: Option<&PassphraseKdf>,
+) -> Result<String, EncryptionError> {
+    if compute_key_fingerprint(input).is_some() {
+        return Ok(input.to_string());
+    }
+    match kdf {
+        Some(kdf) => kdf.derive_key(input),
+        None => Err(EncryptionError::KeyManagement(
+            "Invalid key. Must be 64 hex characters (32 bytes).".into(),
+        )),
+    }
+}
 /// Manages encryption keys and provides XChaCha20-Poly1305 encryption/decryption
 ///
 /// This implements the security model described in the README:
@@ -62,11 +301,18 @@ pub enum EncryptionError {
 #[derive(Clone)]
 pub struct EncryptionService {
     key: [u8; 32],
+    /// Key in use before a rotation. Reads fall back to it until every
+    /// stored file has been re-encrypted under `key`.
+    previous_key: Option<[u8; 32]>,
+    /// Container format used for new files. Decryption detects the format
+    /// from the data, so this only affects writes.
+    format: ContainerFormat,
 }
 impl std::fmt::Debug for EncryptionService {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EncryptionService")
             .field("cipher", &"<initialized>")
+            .field("format", &self.format)
             .finish()
     }
 }
@@ -84,12 +330,50 @@ impl EncryptionService {
         let key: [u8; 32] = key_bytes.try_into().map_err(|_| {
             EncryptionError::KeyManagement("Failed to convert key bytes to array".to_string())
         })?;
-        Ok(EncryptionService { key })
+        Ok(Self::from_key(key))
     }
 
     /// Create a new encryption service from a raw 32-byte key.
     pub fn from_key(key: [u8; 32]) -> Self {
-        EncryptionService { key }
+        EncryptionService {
+            key,
+            previous_key: None,
+            format: ContainerFormat::LEGACY,
+        }
+    }
+
+    /// Also decrypt data written under the key this one replaced.
+    pub fn with_previous_key(mut self, key_hex: &str) -> Result<Self, EncryptionError> {
+        self.previous_key = Some(Self::new(key_hex)?.key);
+        Ok(self)
+    }
+
+    /// Whether data written under a previous key can still be read
+    pub fn has_previous_key(&self) -> bool {
+        self.previous_key.is_some()
+    }
+
+    /// The same service without the previous-key fallback, for telling
+    /// re-encrypted data apart from data still under the old key.
+    pub fn current_only(&self) -> Self {
+        EncryptionService {
+            previous_key: None,
+            ..self.clone()
+        }
+    }
+
+    /// Write new files with `chunk_size`-byte plaintext chunks.
+    ///
+    /// Larger chunks mean fewer range requests per read, at the cost of
+    /// fetching more bytes for small seeks.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Result<Self, EncryptionError> {
+        self.format = ContainerFormat::for_chunk_size(chunk_size)?;
+        Ok(self)
+    }
+
+    /// Container format used for new files
+    pub fn format(&self) -> ContainerFormat {
+        self.format
     }
 
     /// SHA-256 fingerprint of the key, first 8 bytes hex-encoded (16 hex chars).
@@ -106,7 +390,7 @@ impl EncryptionService {
             panic!("Invalid key length, expected 32 bytes");
         }
         let key: [u8; 32] = key_bytes.try_into().unwrap();
-        EncryptionService { key }
+        Self::from_key(key)
     }
 
     /// Return the raw 32-byte key.
@@ -115,20 +399,21 @@ impl EncryptionService {
     }
 
     /// Encrypt data using chunked XChaCha20-Poly1305 format.
-    /// Returns: [base_nonce: 24 bytes][ciphertext with auth tags]
+    /// Returns: [header][base_nonce: 24 bytes][ciphertext with auth tags]
     /// For small data (single chunk), this is equivalent to standard AEAD.
     /// For large data, each chunk is independently encrypted for random-access.
     pub fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
         self.encrypt_chunked(plaintext)
     }
 
-    /// Decrypt data in chunked format: [nonce (24 bytes)][ciphertext chunks...]
+    /// Decrypt data in chunked format: [header][nonce (24 bytes)][ciphertext chunks...]
     pub fn decrypt(&self, encrypted_data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
         self.decrypt_chunked(encrypted_data)
     }
 
     /// Encrypt data using chunked XChaCha20-Poly1305 format.
-    /// Returns: [base_nonce: 24 bytes][chunk_0][chunk_1]...
+    /// Returns: [header][base_nonce: 24 bytes][chunk_0][chunk_1]...
+    /// The header is empty for legacy-format files.
     /// Each chunk is independently encrypted, enabling random-access decryption.
     pub fn encrypt_chunked(&self, plaintext: &[u8]) -> Vec<u8> {
         ensure_sodium_init();
@@ -139,7 +424,10 @@ impl EncryptionService {
             sodium_ffi::randombytes_buf(base_nonce.as_mut_ptr(), sodium_ffi::NPUBBYTES);
         }
 
-        let mut output = base_nonce.to_vec();
+        let mut output =
+            Vec::with_capacity(self.format.encrypted_len(plaintext.len() as u64) as usize);
+        output.extend(self.format.header());
+        output.extend(base_nonce);
 
         // Handle empty plaintext - still produce one chunk with just auth tag
         if plaintext.is_empty() {
@@ -165,7 +453,7 @@ impl EncryptionService {
             return output;
         }
 
-        for (i, chunk) in plaintext.chunks(CHUNK_SIZE).enumerate() {
+        for (i, chunk) in plaintext.chunks(self.format.chunk_size).enumerate() {
             let nonce = chunk_nonce(&base_nonce, i as u64);
             let mut ciphertext = vec![0u8; chunk.len() + sodium_ffi::ABYTES];
             let mut ciphertext_len: u64 = 0;
@@ -197,25 +485,19 @@ impl EncryptionService {
         ciphertext: &[u8],
         chunk_index: usize,
     ) -> Result<Vec<u8>, EncryptionError> {
-        ensure_sodium_init();
-
-        if ciphertext.len() < sodium_ffi::NPUBBYTES {
-            return Err(EncryptionError::Decryption(
-                "Ciphertext too short for nonce".to_string(),
-            ));
-        }
-
-        let base_nonce: [u8; sodium_ffi::NPUBBYTES] = ciphertext[..sodium_ffi::NPUBBYTES]
-            .try_into()
-            .map_err(|_| EncryptionError::Decryption("Invalid nonce".to_string()))?;
-
-        let data_start = sodium_ffi::NPUBBYTES;
-        let total_data_len = ciphertext.len() - data_start;
+        let (format, base_nonce) = ContainerFormat::split_prefix(ciphertext)?;
+        self.decrypt_chunk_with_format(format, &base_nonce, ciphertext, chunk_index)
+    }
 
-        // Calculate chunk boundaries
-        let num_full_chunks = total_data_len / ENCRYPTED_CHUNK_SIZE;
-        let has_partial = !total_data_len.is_multiple_of(ENCRYPTED_CHUNK_SIZE);
-        let total_chunks = num_full_chunks + if has_partial { 1 } else { 0 };
+    fn decrypt_chunk_with_format(
+        &self,
+        format: ContainerFormat,
+        base_nonce: &[u8; NONCE_SIZE],
+        ciphertext: &[u8],
+        chunk_index: usize,
+    ) -> Result<Vec<u8>, EncryptionError> {
+        let data = &ciphertext[format.prefix_len()..];
+        let total_chunks = format.chunk_count(data.len());
 
         if chunk_index >= total_chunks {
             return Err(EncryptionError::Decryption(format!(
@@ -224,63 +506,25 @@ impl EncryptionService {
             )));
         }
 
-        let chunk_start = data_start + chunk_index * ENCRYPTED_CHUNK_SIZE;
-        let chunk_end = if chunk_index == total_chunks - 1 && has_partial {
-            ciphertext.len()
-        } else {
-            chunk_start + ENCRYPTED_CHUNK_SIZE
-        };
-
-        let chunk_data = &ciphertext[chunk_start..chunk_end];
-        let nonce = chunk_nonce(&base_nonce, chunk_index as u64);
-
-        let mut plaintext = vec![0u8; chunk_data.len() - sodium_ffi::ABYTES];
-        let mut plaintext_len: u64 = 0;
+        let chunk_start = chunk_index * format.encrypted_chunk_size();
+        let chunk_end = (chunk_start + format.encrypted_chunk_size()).min(data.len());
 
-        let result = unsafe {
-            sodium_ffi::crypto_aead_xchacha20poly1305_ietf_decrypt(
-                plaintext.as_mut_ptr(),
-                &mut plaintext_len,
-                ptr::null_mut(),
-                chunk_data.as_ptr(),
-                chunk_data.len() as u64,
-                ptr::null(),
-                0,
-                nonce.as_ptr(),
-                self.key.as_ptr(),
-            )
-        };
-
-        if result != 0 {
-            return Err(EncryptionError::Decryption(
-                "Authentication failed".to_string(),
-            ));
-        }
-
-        plaintext.truncate(plaintext_len as usize);
-        Ok(plaintext)
+        self.open_chunk(
+            base_nonce,
+            chunk_index as u64,
+            &data[chunk_start..chunk_end],
+        )
+        .map_err(|_| EncryptionError::Decryption("Authentication failed".to_string()))
     }
 
     /// Decrypt all chunks from chunked encrypted data.
     pub fn decrypt_chunked(&self, ciphertext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
-        ensure_sodium_init();
-
-        if ciphertext.len() < sodium_ffi::NPUBBYTES {
-            return Err(EncryptionError::Decryption(
-                "Ciphertext too short for nonce".to_string(),
-            ));
-        }
-
-        let data_start = sodium_ffi::NPUBBYTES;
-        let total_data_len = ciphertext.len() - data_start;
-
-        let num_full_chunks = total_data_len / ENCRYPTED_CHUNK_SIZE;
-        let has_partial = !total_data_len.is_multiple_of(ENCRYPTED_CHUNK_SIZE);
-        let total_chunks = num_full_chunks + if has_partial { 1 } else { 0 };
+        let (format, base_nonce) = ContainerFormat::split_prefix(ciphertext)?;
+        let total_chunks = format.chunk_count(ciphertext.len() - format.prefix_len());
 
         let mut result = Vec::new();
         for i in 0..total_chunks {
-            let chunk = self.decrypt_chunk(ciphertext, i)?;
+            let chunk = self.decrypt_chunk_with_format(format, &base_nonce, ciphertext, i)?;
             result.extend(chunk);
         }
 
@@ -289,8 +533,8 @@ impl EncryptionService {
 
     /// Decrypt a specific plaintext byte range from encrypted data.
     ///
-    /// The ciphertext must start with the nonce (first 24 bytes) but may be
-    /// truncated after the chunks needed for the requested range.
+    /// The ciphertext must start with the container prefix (header and nonce)
+    /// but may be truncated after the chunks needed for the requested range.
     ///
     /// Returns exactly the plaintext bytes from `plaintext_start` to `plaintext_end`.
     pub fn decrypt_range(
@@ -299,8 +543,6 @@ impl EncryptionService {
         plaintext_start: u64,
         plaintext_end: u64,
     ) -> Result<Vec<u8>, EncryptionError> {
-        ensure_sodium_init();
-
         if plaintext_start >= plaintext_end {
             return Err(EncryptionError::Decryption(format!(
                 "Invalid range: start ({}) >= end ({})",
@@ -308,58 +550,52 @@ impl EncryptionService {
             )));
         }
 
-        let start_chunk = plaintext_start / CHUNK_SIZE as u64;
-        let end_chunk = (plaintext_end.saturating_sub(1)) / CHUNK_SIZE as u64;
+        let (format, base_nonce) = ContainerFormat::split_prefix(ciphertext)?;
+        let chunk_size = format.chunk_size as u64;
+        let start_chunk = plaintext_start / chunk_size;
+        let end_chunk = (plaintext_end.saturating_sub(1)) / chunk_size;
 
         let mut plaintext = Vec::new();
         for chunk_idx in start_chunk..=end_chunk {
-            let chunk = self.decrypt_chunk(ciphertext, chunk_idx as usize)?;
+            let chunk = self.decrypt_chunk_with_format(
+                format,
+                &base_nonce,
+                ciphertext,
+                chunk_idx as usize,
+            )?;
             plaintext.extend(chunk);
         }
 
-        // Slice to exact range within the decrypted chunks
-        let offset_in_first_chunk = (plaintext_start % CHUNK_SIZE as u64) as usize;
-        let len = (plaintext_end - plaintext_start) as usize;
-        let end = offset_in_first_chunk + len;
-
-        if end > plaintext.len() {
-            return Err(EncryptionError::Decryption(format!(
-                "Decrypted data too short: need {} bytes, got {}",
-                end,
-                plaintext.len()
-            )));
-        }
-
-        Ok(plaintext[offset_in_first_chunk..end].to_vec())
+        slice_range(&plaintext, format, plaintext_start, plaintext_end)
     }
 
-    /// Decrypt a plaintext byte range using nonce from DB and partial chunk data.
+    /// Decrypt a plaintext byte range using the prefix from DB and partial chunk data.
     ///
     /// This is the efficient method for encrypted range requests:
-    /// - `nonce`: 24-byte nonce stored in DB at import time
-    /// - `encrypted_chunks`: Raw encrypted chunk bytes (NO nonce prefix)
+    /// - `prefix`: container header and nonce stored in DB at import time
+    ///   (24 bytes for legacy files)
+    /// - `encrypted_chunks`: Raw encrypted chunk bytes (NO prefix)
     /// - `first_chunk_index`: Which chunk index the encrypted_chunks starts at
     /// - `plaintext_start`, `plaintext_end`: Absolute byte positions in original file
     ///
-    /// Example: To read plaintext bytes 500,000-600,000:
-    /// 1. Calculate needed chunks: `encrypted_chunk_range(500000, 600000)` → chunks 7-9
+    /// Example: To read plaintext bytes 500,000-600,000 from a legacy file:
+    /// 1. Calculate needed chunks: `format.chunk_range(500000, 600000)` → chunks 7-9
     /// 2. Fetch encrypted bytes from cloud at those positions
-    /// 3. Call `decrypt_range_with_offset(nonce, chunks, 7, 500000, 600000)`
+    /// 3. Call `decrypt_range_with_offset(prefix, chunks, 7, 500000, 600000)`
     pub fn decrypt_range_with_offset(
         &self,
-        nonce: &[u8],
+        prefix: &[u8],
         encrypted_chunks: &[u8],
         first_chunk_index: u64,
         plaintext_start: u64,
         plaintext_end: u64,
     ) -> Result<Vec<u8>, EncryptionError> {
-        ensure_sodium_init();
-
-        if nonce.len() != sodium_ffi::NPUBBYTES {
+        let (format, base_nonce) = ContainerFormat::split_prefix(prefix)?;
+        if prefix.len() != format.prefix_len() {
             return Err(EncryptionError::Decryption(format!(
                 "Invalid nonce length: expected {}, got {}",
-                sodium_ffi::NPUBBYTES,
-                nonce.len()
+                format.prefix_len(),
+                prefix.len()
             )));
         }
 
@@ -370,26 +606,17 @@ impl EncryptionService {
             )));
         }
 
-        let base_nonce: [u8; sodium_ffi::NPUBBYTES] = nonce
-            .try_into()
-            .map_err(|_| EncryptionError::Decryption("Invalid nonce".to_string()))?;
-
-        let start_chunk = plaintext_start / CHUNK_SIZE as u64;
-        let end_chunk = (plaintext_end.saturating_sub(1)) / CHUNK_SIZE as u64;
+        let chunk_size = format.chunk_size as u64;
+        let encrypted_chunk_size = format.encrypted_chunk_size();
+        let start_chunk = plaintext_start / chunk_size;
+        let end_chunk = (plaintext_end.saturating_sub(1)) / chunk_size;
 
         let mut plaintext = Vec::new();
 
         for absolute_chunk_idx in start_chunk..=end_chunk {
             // Convert absolute chunk index to position in encrypted_chunks
             let relative_idx = absolute_chunk_idx - first_chunk_index;
-            let chunk_start = (relative_idx as usize) * ENCRYPTED_CHUNK_SIZE;
-
-            // Handle last chunk which may be smaller
-            let chunk_end = if chunk_start + ENCRYPTED_CHUNK_SIZE > encrypted_chunks.len() {
-                encrypted_chunks.len()
-            } else {
-                chunk_start + ENCRYPTED_CHUNK_SIZE
-            };
+            let chunk_start = (relative_idx as usize) * encrypted_chunk_size;
 
             if chunk_start >= encrypted_chunks.len() {
                 return Err(EncryptionError::Decryption(format!(
@@ -398,51 +625,41 @@ impl EncryptionService {
                 )));
             }
 
-            let chunk_data = &encrypted_chunks[chunk_start..chunk_end];
-            let nonce = chunk_nonce(&base_nonce, absolute_chunk_idx);
-
-            let mut decrypted = vec![0u8; chunk_data.len() - sodium_ffi::ABYTES];
-            let mut decrypted_len: u64 = 0;
+            // Handle last chunk which may be smaller
+            let chunk_end = (chunk_start + encrypted_chunk_size).min(encrypted_chunks.len());
 
-            let result = unsafe {
-                sodium_ffi::crypto_aead_xchacha20poly1305_ietf_decrypt(
-                    decrypted.as_mut_ptr(),
-                    &mut decrypted_len,
-                    ptr::null_mut(),
-                    chunk_data.as_ptr(),
-                    chunk_data.len() as u64,
-                    ptr::null(),
-                    0,
-                    nonce.as_ptr(),
-                    self.key.as_ptr(),
+            let decrypted = self
+                .open_chunk(
+                    &base_nonce,
+                    absolute_chunk_idx,
+                    &encrypted_chunks[chunk_start..chunk_end],
                 )
-            };
-
-            if result != 0 {
-                return Err(EncryptionError::Decryption(format!(
-                    "Authentication failed for chunk {}",
-                    absolute_chunk_idx
-                )));
-            }
-
-            decrypted.truncate(decrypted_len as usize);
+                .map_err(|_| {
+                    EncryptionError::Decryption(format!(
+                        "Authentication failed for chunk {}",
+                        absolute_chunk_idx
+                    ))
+                })?;
             plaintext.extend(decrypted);
         }
 
-        // Slice to exact range within the decrypted chunks
-        let offset_in_first_chunk = (plaintext_start % CHUNK_SIZE as u64) as usize;
-        let len = (plaintext_end - plaintext_start) as usize;
-        let end = offset_in_first_chunk + len;
-
-        if end > plaintext.len() {
-            return Err(EncryptionError::Decryption(format!(
-                "Decrypted data too short: need {} bytes, got {}",
-                end,
-                plaintext.len()
-            )));
-        }
+        slice_range(&plaintext, format, plaintext_start, plaintext_end)
+    }
 
-        Ok(plaintext[offset_in_first_chunk..end].to_vec())
+    /// Authenticate and decrypt one encrypted chunk, with the previous key if
+    /// the current one doesn't open it
+    fn open_chunk(
+        &self,
+        base_nonce: &[u8; NONCE_SIZE],
+        chunk_index: u64,
+        chunk_data: &[u8],
+    ) -> Result<Vec<u8>, ()> {
+        open_chunk_with(&self.key, base_nonce, chunk_index, chunk_data).or_else(|()| {
+            match &self.previous_key {
+                Some(previous) => open_chunk_with(previous, base_nonce, chunk_index, chunk_data),
+                None => Err(()),
+            }
+        })
     }
 
     /// Derive a per-release encryption service.
@@ -450,8 +667,14 @@ impl EncryptionService {
     /// Uses HKDF: master_key + "bae-release-v1:{release_id}" -> 32-byte key.
     /// Deterministic: same master + release_id always gives the same key.
     pub fn derive_release_encryption(&self, release_id: &str) -> EncryptionService {
-        let derived = self.derive_key(&format!("bae-release-v1:{release_id}"));
-        EncryptionService::from_key(derived)
+        let info = format!("bae-release-v1:{release_id}");
+        EncryptionService {
+            key: self.derive_key(&info),
+            previous_key: self
+                .previous_key
+                .map(|previous| Self::from_key(previous).derive_key(&info)),
+            format: self.format,
+        }
     }
 
     /// Derive a 32-byte key using HKDF-SHA256 with the given info label.
@@ -476,6 +699,45 @@ impl EncryptionService {
     }
 }
 
+/// Authenticate and decrypt one encrypted chunk under `key`
+fn open_chunk_with(
+    key: &[u8; 32],
+    base_nonce: &[u8; NONCE_SIZE],
+    chunk_index: u64,
+    chunk_data: &[u8],
+) -> Result<Vec<u8>, ()> {
+    ensure_sodium_init();
+
+    if chunk_data.len() < sodium_ffi::ABYTES {
+        return Err(());
+    }
+
+    let nonce = chunk_nonce(base_nonce, chunk_index);
+    let mut plaintext = vec![0u8; chunk_data.len() - sodium_ffi::ABYTES];
+    let mut plaintext_len: u64 = 0;
+
+    let result = unsafe {
+        sodium_ffi::crypto_aead_xchacha20poly1305_ietf_decrypt(
+            plaintext.as_mut_ptr(),
+            &mut plaintext_len,
+            ptr::null_mut(),
+            chunk_data.as_ptr(),
+            chunk_data.len() as u64,
+            ptr::null(),
+            0,
+            nonce.as_ptr(),
+            key.as_ptr(),
+        )
+    };
+
+    if result != 0 {
+        return Err(());
+    }
+
+    plaintext.truncate(plaintext_len as usize);
+    Ok(plaintext)
+}
+
 /// Derive nonce for chunk i: base_nonce XOR i (little-endian)
 fn chunk_nonce(
     base_nonce: &[u8; sodium_ffi::NPUBBYTES],
@@ -489,21 +751,26 @@ fn chunk_nonce(
     nonce
 }
 
-/// Calculate the encrypted byte range for a plaintext byte range.
-///
-/// Returns `(chunk_start, chunk_end)` - the byte positions in the encrypted file
-/// where the needed chunks are located. Does NOT include the nonce (first 24 bytes).
-///
-/// Use this for efficient range requests: fetch nonce separately (or from DB),
-/// then fetch just `chunk_start..chunk_end` from storage.
-pub fn encrypted_chunk_range(plaintext_start: u64, plaintext_end: u64) -> (u64, u64) {
-    let start_chunk = plaintext_start / CHUNK_SIZE as u64;
-    let end_chunk = (plaintext_end.saturating_sub(1)) / CHUNK_SIZE as u64;
-
-    let chunk_start = sodium_ffi::NPUBBYTES as u64 + start_chunk * ENCRYPTED_CHUNK_SIZE as u64;
-    let chunk_end = sodium_ffi::NPUBBYTES as u64 + (end_chunk + 1) * ENCRYPTED_CHUNK_SIZE as u64;
+/// Slice the decrypted chunks down to the requested plaintext range
+fn slice_range(
+    plaintext: &[u8],
+    format: ContainerFormat,
+    plaintext_start: u64,
+    plaintext_end: u64,
+) -> Result<Vec<u8>, EncryptionError> {
+    let offset_in_first_chunk = (plaintext_start % format.chunk_size as u64) as usize;
+    let len = (plaintext_end - plaintext_start) as usize;
+    let end = offset_in_first_chunk + len;
+
+    if end > plaintext.len() {
+        return Err(EncryptionError::Decryption(format!(
+            "Decrypted data too short: need {} bytes, got {}",
+            end,
+            plaintext.len()
+        )));
+    }
 
-    (chunk_start, chunk_end)
+    Ok(plaintext[offset_in_first_chunk..end].to_vec())
 }
 
 #[cfg(test)]
@@ -803,7 +1070,7 @@ mod tests {
         let chunk5_start = CHUNK_SIZE as u64 * 5;
         let chunk5_end = chunk5_start + 1000;
 
-        let (enc_start, enc_end) = encrypted_chunk_range(chunk5_start, chunk5_end);
+        let (enc_start, enc_end) = ContainerFormat::LEGACY.chunk_range(chunk5_start, chunk5_end);
 
         // Should start at chunk 5's position, not 0
         let expected_start = sodium_ffi::NPUBBYTES as u64 + 5 * ENCRYPTED_CHUNK_SIZE as u64;
@@ -811,7 +1078,7 @@ mod tests {
 
         assert_eq!(
             enc_start, expected_start,
-            "encrypted_chunk_range should return actual chunk start, not 0"
+            "chunk_range should return actual chunk start, not 0"
         );
         assert_eq!(enc_end, expected_end);
     }
@@ -822,7 +1089,7 @@ mod tests {
         let start = CHUNK_SIZE as u64 * 3 + 100;
         let end = CHUNK_SIZE as u64 * 5 + 500;
 
-        let (enc_start, enc_end) = encrypted_chunk_range(start, end);
+        let (enc_start, enc_end) = ContainerFormat::LEGACY.chunk_range(start, end);
 
         let expected_start = sodium_ffi::NPUBBYTES as u64 + 3 * ENCRYPTED_CHUNK_SIZE as u64;
         let expected_end = sodium_ffi::NPUBBYTES as u64 + 6 * ENCRYPTED_CHUNK_SIZE as u64;
@@ -848,7 +1115,8 @@ mod tests {
         let plaintext_end = CHUNK_SIZE as u64 * 7 + 500;
 
         // Get the encrypted chunk range (NOT starting from 0)
-        let (chunk_start, chunk_end) = encrypted_chunk_range(plaintext_start, plaintext_end);
+        let (chunk_start, chunk_end) =
+            ContainerFormat::LEGACY.chunk_range(plaintext_start, plaintext_end);
 
         // Fetch just the needed chunks (simulating range request)
         let chunks_only = &full_ciphertext[chunk_start as usize..chunk_end as usize];
@@ -887,7 +1155,8 @@ mod tests {
         let plaintext_start = CHUNK_SIZE as u64 * 3 + 1000;
         let plaintext_end = CHUNK_SIZE as u64 * 5 + 2000;
 
-        let (chunk_start, chunk_end) = encrypted_chunk_range(plaintext_start, plaintext_end);
+        let (chunk_start, chunk_end) =
+            ContainerFormat::LEGACY.chunk_range(plaintext_start, plaintext_end);
         let chunks_only = &full_ciphertext[chunk_start as usize..chunk_end as usize];
         let first_chunk_index = plaintext_start / CHUNK_SIZE as u64;
 
@@ -981,4 +1250,173 @@ mod tests {
         let wrong_enc = master.derive_release_encryption("rel-999");
         assert!(wrong_enc.decrypt(&encrypted).is_err());
     }
+
+    #[test]
+    fn previous_key_reads_until_rotation_completes() {
+        let old_key = hex::encode([3u8; 32]);
+        let old = EncryptionService::new(&old_key).unwrap();
+        let old_release = old
+            .derive_release_encryption("rel-1")
+            .encrypt(b"Iron Meadow");
+
+        let rotated = EncryptionService::new_with_key(&[4u8; 32])
+            .with_previous_key(&old_key)
+            .unwrap();
+        let release = rotated.derive_release_encryption("rel-1");
+        assert_eq!(release.decrypt(&old_release).unwrap(), b"Iron Meadow");
+        assert!(release.current_only().decrypt(&old_release).is_err());
+
+        // New writes use the new key only
+        let new_release = release.encrypt(b"Iron Meadow");
+        assert!(old
+            .derive_release_encryption("rel-1")
+            .decrypt(&new_release)
+            .is_err());
+        assert_eq!(
+            release.current_only().decrypt(&new_release).unwrap(),
+            b"Iron Meadow"
+        );
+    }
+
+    #[test]
+    fn plaintext_len_matches_encrypted_size() {
+        let service = create_test_service();
+        for len in [
+            0,
+            1,
+            CHUNK_SIZE - 1,
+            CHUNK_SIZE,
+            CHUNK_SIZE + 1,
+            CHUNK_SIZE * 3 + 17,
+        ] {
+            let encrypted = service.encrypt(&vec![7u8; len]);
+            assert_eq!(
+                ContainerFormat::LEGACY.plaintext_len(encrypted.len() as u64),
+                len as u64
+            );
+            assert_eq!(
+                ContainerFormat::LEGACY.encrypted_len(len as u64),
+                encrypted.len() as u64
+            );
+        }
+    }
+
+    #[test]
+    fn default_chunk_size_writes_legacy_format() {
+        let service = create_test_service().with_chunk_size(CHUNK_SIZE).unwrap();
+        assert_eq!(service.format(), ContainerFormat::LEGACY);
+
+        let encrypted = service.encrypt(b"Glass Harbor");
+        assert_eq!(
+            ContainerFormat::detect(&encrypted).unwrap(),
+            ContainerFormat::LEGACY
+        );
+        assert_eq!(service.decrypt(&encrypted).unwrap(), b"Glass Harbor");
+    }
+
+    #[test]
+    fn large_chunks_round_trip_with_header() {
+        let service = create_test_service()
+            .with_chunk_size(CLOUD_CHUNK_SIZE)
+            .unwrap();
+        let plaintext: Vec<u8> = (0..CLOUD_CHUNK_SIZE * 2 + 1000)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let encrypted = service.encrypt(&plaintext);
+
+        let format = ContainerFormat::detect(&encrypted).unwrap();
+        assert_eq!(format.version, CONTAINER_VERSION);
+        assert_eq!(format.chunk_size, CLOUD_CHUNK_SIZE);
+        assert_eq!(&encrypted[..HEADER_SIZE], format.header().as_slice());
+        assert_eq!(
+            format.encrypted_len(plaintext.len() as u64),
+            encrypted.len() as u64
+        );
+        assert_eq!(
+            format.plaintext_len(encrypted.len() as u64),
+            plaintext.len() as u64
+        );
+
+        assert_eq!(service.decrypt(&encrypted).unwrap(), plaintext);
+        // Any service with the same key reads it, whatever its own chunk size
+        assert_eq!(
+            create_test_service().decrypt(&encrypted).unwrap(),
+            plaintext
+        );
+
+        let start = CLOUD_CHUNK_SIZE as u64 - 10;
+        let end = CLOUD_CHUNK_SIZE as u64 + 10;
+        assert_eq!(
+            service.decrypt_range(&encrypted, start, end).unwrap(),
+            &plaintext[start as usize..end as usize]
+        );
+
+        let prefix = &encrypted[..format.prefix_len()];
+        let (chunk_start, chunk_end) = format.chunk_range(start, end);
+        let first_chunk_index =
+            (chunk_start - format.prefix_len() as u64) / format.encrypted_chunk_size() as u64;
+        assert_eq!(first_chunk_index, 0);
+        let chunks = &encrypted[chunk_start as usize..(chunk_end as usize).min(encrypted.len())];
+        assert_eq!(
+            service
+                .decrypt_range_with_offset(prefix, chunks, first_chunk_index, start, end)
+                .unwrap(),
+            &plaintext[start as usize..end as usize]
+        );
+    }
+
+    #[test]
+    fn derived_release_service_keeps_chunk_size() {
+        let master = create_test_service()
+            .with_chunk_size(CLOUD_CHUNK_SIZE)
+            .unwrap();
+        let release = master.derive_release_encryption("rel-1");
+        assert_eq!(release.format(), master.format());
+    }
+
+    #[test]
+    fn rejects_unsupported_chunk_sizes() {
+        for size in [0, 1000, CHUNK_SIZE / 2, CHUNK_SIZE * 3, MAX_CHUNK_SIZE * 2] {
+            assert!(create_test_service().with_chunk_size(size).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_container_version() {
+        let service = create_test_service()
+            .with_chunk_size(CLOUD_CHUNK_SIZE)
+            .unwrap();
+        let mut encrypted = service.encrypt(b"Tidewater");
+        encrypted[6] = CONTAINER_VERSION + 1;
+
+        assert!(ContainerFormat::detect(&encrypted).is_err());
+        assert!(service.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn passphrase_derives_the_same_key_with_the_same_parameters() {
+        // Minimum Argon2id limits keep the test fast
+        let kdf = PassphraseKdf {
+            salt: hex::encode([7u8; sodium_ffi::PWHASH_SALTBYTES]),
+            ops_limit: 1,
+            mem_limit: 8192,
+        };
+        let key = kdf.derive_key("glass harbor at dawn").unwrap();
+        assert!(compute_key_fingerprint(&key).is_some());
+        assert_eq!(kdf.derive_key("glass harbor at dawn").unwrap(), key);
+        assert_ne!(kdf.derive_key("glass harbor at dusk").unwrap(), key);
+
+        let other_salt = PassphraseKdf {
+            salt: hex::encode([8u8; sodium_ffi::PWHASH_SALTBYTES]),
+            ..kdf.clone()
+        };
+        assert_ne!(other_salt.derive_key("glass harbor at dawn").unwrap(), key);
+
+        assert_eq!(
+            resolve_unlock_input("glass harbor at dawn", Some(&kdf)).unwrap(),
+            key
+        );
+        assert_eq!(resolve_unlock_input(&key, None).unwrap(), key);
+        assert!(resolve_unlock_input("glass harbor at dawn", None).is_err());
+    }
 }
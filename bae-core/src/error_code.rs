@@ -0,0 +1,121 @@
+//! Stable error codes for errors surfaced to clients
+//!
+//! Import, playback, storage and sync errors each map onto an `ErrorCode`.
+//! The string form is part of the contract with the desktop app and the
+//! native clients, which use it to pick a recovery action and a localized
+//! message. Never rename a code once it has shipped; add a new one instead.
+//! Clients should fall back to the error's display text for codes they
+//! don't recognize.
+
+/// Category of a failure, stable across releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// Neither a Discogs nor a MusicBrainz release was selected
+    ImportNoRelease,
+    /// Release metadata couldn't be fetched or parsed
+    ImportMetadataUnavailable,
+    /// The selected remote cover art couldn't be downloaded
+    ImportCoverArtUnavailable,
+    /// Source files are missing, unreadable, or don't match the release
+    ImportInvalidFiles,
+    /// Downloading a torrent or ripping a CD failed
+    ImportAcquireFailed,
+    /// The import worker isn't running
+    ImportServiceStopped,
+    /// A track, release or file no longer exists in the library
+    PlaybackNotFound,
+    /// Audio data is corrupt or in an unsupported format
+    PlaybackCorruptAudio,
+    /// No storage location is configured for this release
+    StorageNotConfigured,
+    /// Cloud storage couldn't be reached
+    StorageUnavailable,
+    /// The file is archived in cold storage and being restored; retry later
+    StorageWarmingUp,
+    /// Data couldn't be decrypted, usually a wrong or missing key
+    DecryptionFailed,
+    /// The sync bucket couldn't be reached or an object is missing
+    SyncBucketUnavailable,
+    /// Remote changes require a newer version of bae
+    SyncUpgradeRequired,
+    /// A changeset from another device couldn't be read or applied
+    SyncInvalidChangeset,
+    /// Local database error
+    Database,
+    /// Local file system error
+    Io,
+    /// Anything else; not actionable by the user
+    Internal,
+}
+
+impl ErrorCode {
+    /// Every code, in declaration order
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::ImportNoRelease,
+        ErrorCode::ImportMetadataUnavailable,
+        ErrorCode::ImportCoverArtUnavailable,
+        ErrorCode::ImportInvalidFiles,
+        ErrorCode::ImportAcquireFailed,
+        ErrorCode::ImportServiceStopped,
+        ErrorCode::PlaybackNotFound,
+        ErrorCode::PlaybackCorruptAudio,
+        ErrorCode::StorageNotConfigured,
+        ErrorCode::StorageUnavailable,
+        ErrorCode::StorageWarmingUp,
+        ErrorCode::DecryptionFailed,
+        ErrorCode::SyncBucketUnavailable,
+        ErrorCode::SyncUpgradeRequired,
+        ErrorCode::SyncInvalidChangeset,
+        ErrorCode::Database,
+        ErrorCode::Io,
+        ErrorCode::Internal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::ImportNoRelease => "import.no_release",
+            ErrorCode::ImportMetadataUnavailable => "import.metadata_unavailable",
+            ErrorCode::ImportCoverArtUnavailable => "import.cover_art_unavailable",
+            ErrorCode::ImportInvalidFiles => "import.invalid_files",
+            ErrorCode::ImportAcquireFailed => "import.acquire_failed",
+            ErrorCode::ImportServiceStopped => "import.service_stopped",
+            ErrorCode::PlaybackNotFound => "playback.not_found",
+            ErrorCode::PlaybackCorruptAudio => "playback.corrupt_audio",
+            ErrorCode::StorageNotConfigured => "storage.not_configured",
+            ErrorCode::StorageUnavailable => "storage.unavailable",
+            ErrorCode::StorageWarmingUp => "storage.warming_up",
+            ErrorCode::DecryptionFailed => "decryption_failed",
+            ErrorCode::SyncBucketUnavailable => "sync.bucket_unavailable",
+            ErrorCode::SyncUpgradeRequired => "sync.upgrade_required",
+            ErrorCode::SyncInvalidChangeset => "sync.invalid_changeset",
+            ErrorCode::Database => "database",
+            ErrorCode::Io => "io",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Implemented by error types that clients can act on
+pub trait HasErrorCode {
+    fn code(&self) -> ErrorCode;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique() {
+        let mut seen = HashSet::new();
+        for code in ErrorCode::ALL {
+            assert!(seen.insert(code.as_str()), "duplicate code {}", code);
+        }
+    }
+}
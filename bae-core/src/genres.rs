@@ -0,0 +1,228 @@
+//! Genre normalization
+//!
+//! Imported genres come spelled many ways ("Hip Hop", "hip-hop", "HipHop").
+//! Each genre is stored once under a key that ignores case and separators,
+//! common spellings are rewritten to one canonical name on import, and
+//! related names ("Rap") are offered as merge suggestions in the tag
+//! cleanup tool rather than merged automatically.
+
+use crate::db::DbGenre;
+use std::collections::HashMap;
+
+/// Spellings that always mean the same genre, by compact key
+const ALIASES: &[(&str, &str)] = &[
+    ("hiphop", "Hip-Hop"),
+    ("rnb", "R&B"),
+    ("randb", "R&B"),
+    ("rhythmandblues", "R&B"),
+    ("dnb", "Drum & Bass"),
+    ("drumandbass", "Drum & Bass"),
+    ("drumnbass", "Drum & Bass"),
+    ("synthpop", "Synth-Pop"),
+    ("lofi", "Lo-Fi"),
+    ("postrock", "Post-Rock"),
+    ("postpunk", "Post-Punk"),
+    ("triphop", "Trip-Hop"),
+    ("ost", "Soundtrack"),
+    ("electronica", "Electronic"),
+];
+
+/// Names close enough to suggest merging, but not to merge unasked
+const RELATED: &[(&str, &str)] = &[
+    ("rap", "Hip-Hop"),
+    ("score", "Soundtrack"),
+    ("edm", "Electronic"),
+    ("altrock", "Alternative Rock"),
+];
+
+/// Key a genre is stored under: lowercase, `&` spelled out, and runs of
+/// spaces, hyphens, slashes and underscores collapsed to one space.
+pub fn genre_key(name: &str) -> String {
+    let lowered = name.to_lowercase().replace('&', " and ");
+    lowered
+        .split(|c: char| c.is_whitespace() || matches!(c, '-' | '/' | '_'))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Key with the separators removed too, so "Synth Pop" meets "Synthpop"
+fn compact_key(name: &str) -> String {
+    genre_key(name).replace(' ', "")
+}
+
+/// Clean up one imported genre name. Known spellings become their canonical
+/// name, all-lowercase names are capitalized, and blank names are dropped.
+pub fn normalize_genre(name: &str) -> Option<String> {
+    let cleaned = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if cleaned.is_empty() {
+        return None;
+    }
+    let compact = compact_key(&cleaned);
+    if let Some((_, canonical)) = ALIASES.iter().find(|(alias, _)| *alias == compact) {
+        return Some(canonical.to_string());
+    }
+    if cleaned.chars().any(|c| c.is_uppercase()) {
+        return Some(cleaned);
+    }
+    Some(capitalize_words(&cleaned))
+}
+
+/// Normalize a list of genre names, keeping the first of any that share a key
+pub fn normalize_genres(names: &[String]) -> Vec<String> {
+    let mut seen = Vec::new();
+    let mut genres = Vec::new();
+    for name in names {
+        let Some(genre) = normalize_genre(name) else {
+            continue;
+        };
+        let key = genre_key(&genre);
+        if !seen.contains(&key) {
+            seen.push(key);
+            genres.push(genre);
+        }
+    }
+    genres
+}
+
+fn capitalize_words(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut at_word_start = true;
+    for c in name.chars() {
+        if at_word_start {
+            result.extend(c.to_uppercase());
+        } else {
+            result.push(c);
+        }
+        at_word_start = c == ' ' || c == '-' || c == '/';
+    }
+    result
+}
+
+/// Genres in the library that look like the same genre
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenreMergeSuggestion {
+    /// Name to keep
+    pub into: String,
+    /// IDs of the genres to fold together, including the one kept if it exists
+    pub genre_ids: Vec<String>,
+}
+
+/// Group genres that are spelling variants or related names of each other.
+///
+/// The kept name is the canonical one when the group matches a known name,
+/// otherwise the spelling used on the most albums.
+pub fn suggest_genre_merges(genres: &[DbGenre]) -> Vec<GenreMergeSuggestion> {
+    let mut groups: HashMap<String, (Option<&'static str>, Vec<&DbGenre>)> = HashMap::new();
+    let mut order = Vec::new();
+    for genre in genres {
+        let compact = compact_key(&genre.name);
+        let known = ALIASES
+            .iter()
+            .chain(RELATED)
+            .find(|(alias, _)| *alias == compact)
+            .map(|(_, canonical)| *canonical);
+        let group_key = known.map(compact_key).unwrap_or(compact);
+        let group = groups.entry(group_key.clone()).or_insert_with(|| {
+            order.push(group_key);
+            (None, Vec::new())
+        });
+        group.0 = group.0.or(known);
+        group.1.push(genre);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|key| {
+            let (known, members) = groups.remove(&key)?;
+            if members.len() < 2 {
+                return None;
+            }
+            let into = match known {
+                Some(canonical) => canonical.to_string(),
+                None => members
+                    .iter()
+                    .max_by_key(|g| g.album_count)
+                    .map(|g| g.name.clone())?,
+            };
+            Some(GenreMergeSuggestion {
+                into,
+                genre_ids: members.iter().map(|g| g.id.clone()).collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genre(name: &str, album_count: i64) -> DbGenre {
+        DbGenre {
+            id: genre_key(name),
+            name: name.to_string(),
+            album_count,
+        }
+    }
+
+    #[test]
+    fn key_ignores_case_and_separators() {
+        assert_eq!(genre_key("Hip Hop"), "hip hop");
+        assert_eq!(genre_key("hip-hop"), "hip hop");
+        assert_eq!(genre_key("  Folk / Country "), "folk country");
+        assert_eq!(genre_key("Drum & Bass"), "drum and bass");
+    }
+
+    #[test]
+    fn normalizes_known_spellings_and_capitalizes() {
+        assert_eq!(normalize_genre("hiphop").as_deref(), Some("Hip-Hop"));
+        assert_eq!(normalize_genre("RnB").as_deref(), Some("R&B"));
+        assert_eq!(
+            normalize_genre("drum n bass").as_deref(),
+            Some("Drum & Bass")
+        );
+        assert_eq!(normalize_genre("shoegaze").as_deref(), Some("Shoegaze"));
+        assert_eq!(
+            normalize_genre("post-hardcore").as_deref(),
+            Some("Post-Hardcore")
+        );
+        assert_eq!(normalize_genre("IDM").as_deref(), Some("IDM"));
+        assert_eq!(normalize_genre("   "), None);
+    }
+
+    #[test]
+    fn normalize_genres_drops_duplicates() {
+        let names = vec![
+            "Jazz".to_string(),
+            "Hip Hop".to_string(),
+            "jazz".to_string(),
+            "hip-hop".to_string(),
+        ];
+        assert_eq!(normalize_genres(&names), vec!["Jazz", "Hip-Hop"]);
+    }
+
+    #[test]
+    fn suggests_merging_variants_and_related_names() {
+        let genres = vec![
+            genre("Hip Hop", 4),
+            genre("Rap", 2),
+            genre("Synth Pop", 1),
+            genre("Synthpop", 3),
+            genre("Post Punk", 1),
+            genre("Postpunk", 5),
+            genre("Ambient Techno", 1),
+            genre("AmbientTechno", 6),
+            genre("Jazz", 8),
+        ];
+
+        let suggestions = suggest_genre_merges(&genres);
+
+        assert_eq!(suggestions.len(), 4);
+        assert_eq!(suggestions[0].into, "Hip-Hop");
+        assert_eq!(suggestions[0].genre_ids, vec!["hip hop", "rap"]);
+        assert_eq!(suggestions[1].into, "Synth-Pop");
+        assert_eq!(suggestions[2].into, "Post-Punk");
+        // No known name: the most used spelling wins
+        assert_eq!(suggestions[3].into, "AmbientTechno");
+    }
+}
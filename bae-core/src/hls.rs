@@ -0,0 +1,376 @@
+//! HLS output: a track as segmented AAC plus a media playlist
+//!
+//! Chromecast, smart TVs and Safari play HLS without any client logic of
+//! ours. A track is decoded and encoded once into an ADTS stream, then cut at
+//! AAC frame boundaries into segments of about `SEGMENT_DURATION` seconds, so
+//! consecutive segments play back without gaps. Each segment starts with the
+//! ID3 timestamp tag HLS requires of packed audio.
+//!
+//! Encoded tracks stay in memory while a player fetches their segments and,
+//! on a server, on disk in an LRU cache with a size cap, so streaming the same
+//! album to a phone again doesn't re-encode it.
+
+use crate::audio_codec;
+use crate::cache::CacheManager;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+use tracing::warn;
+
+/// Target segment length, in seconds
+pub const SEGMENT_DURATION: f64 = 6.0;
+/// Bit rate when the client doesn't ask for one, in kbps
+pub const DEFAULT_BIT_RATE: u32 = 192;
+/// Encoded tracks kept in memory for serving their segments
+const CACHED_TRACKS: usize = 4;
+/// Samples per channel in one AAC frame
+const AAC_FRAME_SAMPLES: u64 = 1024;
+/// Sample rates by ADTS sampling frequency index
+const ADTS_SAMPLE_RATES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+/// A run of whole ADTS frames within an encoded track
+#[derive(Debug, Clone, PartialEq)]
+pub struct HlsSegment {
+    /// Byte range in the ADTS stream
+    pub start: usize,
+    pub end: usize,
+    /// First sample (per channel) of the segment, counted from the track start
+    pub start_sample: u64,
+    /// Length in seconds
+    pub duration: f64,
+}
+
+/// A track encoded for HLS
+pub struct HlsTrack {
+    data: Vec<u8>,
+    sample_rate: u32,
+    pub segments: Vec<HlsSegment>,
+}
+
+impl HlsTrack {
+    /// Decode a track's audio and encode it at `bit_rate` kbps. CPU-bound;
+    /// run it on a blocking thread.
+    pub fn encode(audio: &[u8], bit_rate: u32) -> Result<Self, String> {
+        let decoded = audio_codec::decode_audio(audio, None, None)?;
+        let data = audio_codec::encode_to_aac(
+            &decoded.samples,
+            decoded.sample_rate,
+            decoded.channels,
+            decoded.bits_per_sample,
+            bit_rate * 1000,
+        )?;
+        let segments = split_adts(&data, decoded.sample_rate, SEGMENT_DURATION)?;
+
+        Ok(Self {
+            data,
+            sample_rate: decoded.sample_rate,
+            segments,
+        })
+    }
+
+    /// A track from an ADTS stream encoded earlier
+    pub fn from_adts(data: Vec<u8>) -> Result<Self, String> {
+        let index = data
+            .get(2)
+            .map(|b| (b >> 2) & 0x0F)
+            .ok_or("Empty ADTS stream")?;
+        let sample_rate = *ADTS_SAMPLE_RATES
+            .get(index as usize)
+            .ok_or_else(|| format!("Invalid ADTS sample rate index {}", index))?;
+        let segments = split_adts(&data, sample_rate, SEGMENT_DURATION)?;
+
+        Ok(Self {
+            data,
+            sample_rate,
+            segments,
+        })
+    }
+
+    /// The bytes of one segment, timestamp tag first
+    pub fn segment(&self, index: usize) -> Option<Vec<u8>> {
+        let segment = self.segments.get(index)?;
+        let mut bytes = timestamp_tag(segment.start_sample, self.sample_rate);
+        bytes.extend_from_slice(&self.data[segment.start..segment.end]);
+        Some(bytes)
+    }
+}
+
+/// Cut an ADTS stream into segments of at least `target` seconds, the last
+/// one excepted
+pub fn split_adts(data: &[u8], sample_rate: u32, target: f64) -> Result<Vec<HlsSegment>, String> {
+    let target_samples = (target * sample_rate as f64) as u64;
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    let mut sample = 0;
+    let mut segment_pos = 0;
+    let mut segment_sample = 0;
+
+    while pos < data.len() {
+        let header = data
+            .get(pos..pos + 7)
+            .filter(|h| h[0] == 0xFF && h[1] & 0xF0 == 0xF0)
+            .ok_or_else(|| format!("No ADTS frame at byte {}", pos))?;
+        let frame_len = ((header[3] as usize & 0x03) << 11)
+            | ((header[4] as usize) << 3)
+            | (header[5] as usize >> 5);
+        if frame_len < 7 || pos + frame_len > data.len() {
+            return Err(format!("Truncated ADTS frame at byte {}", pos));
+        }
+        let raw_blocks = (header[6] & 0x03) as u64 + 1;
+
+        pos += frame_len;
+        sample += raw_blocks * AAC_FRAME_SAMPLES;
+        if sample - segment_sample >= target_samples || pos == data.len() {
+            segments.push(HlsSegment {
+                start: segment_pos,
+                end: pos,
+                start_sample: segment_sample,
+                duration: (sample - segment_sample) as f64 / sample_rate as f64,
+            });
+            segment_pos = pos;
+            segment_sample = sample;
+        }
+    }
+
+    Ok(segments)
+}
+
+/// The VOD media playlist for a track. `segment_uri` gives each segment's URI
+/// from its index.
+pub fn media_playlist(segments: &[HlsSegment], segment_uri: impl Fn(usize) -> String) -> String {
+    let target_duration = segments
+        .iter()
+        .map(|s| s.duration.ceil() as u64)
+        .max()
+        .unwrap_or(0);
+    let mut playlist = format!(
+        "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-PLAYLIST-TYPE:VOD\n\
+         #EXT-X-TARGETDURATION:{}\n#EXT-X-MEDIA-SEQUENCE:0\n",
+        target_duration
+    );
+    for (index, segment) in segments.iter().enumerate() {
+        playlist.push_str(&format!(
+            "#EXTINF:{:.3},\n{}\n",
+            segment.duration,
+            segment_uri(index)
+        ));
+    }
+    playlist.push_str("#EXT-X-ENDLIST\n");
+    playlist
+}
+
+/// ID3v2.4 tag carrying a segment's start time on the 90 kHz MPEG clock
+fn timestamp_tag(start_sample: u64, sample_rate: u32) -> Vec<u8> {
+    const OWNER: &[u8] = b"com.apple.streaming.transportStreamTimestamp\0";
+    // 33-bit, like an MPEG-TS presentation timestamp
+    let timestamp = (start_sample * 90_000 / sample_rate as u64) & 0x1_FFFF_FFFF;
+    let frame_size = OWNER.len() + 8;
+    let tag_size = 10 + frame_size;
+
+    let mut tag = Vec::with_capacity(10 + tag_size);
+    tag.extend_from_slice(b"ID3\x04\x00\x00");
+    tag.extend_from_slice(&syncsafe(tag_size as u32));
+    tag.extend_from_slice(b"PRIV");
+    tag.extend_from_slice(&syncsafe(frame_size as u32));
+    tag.extend_from_slice(&[0, 0]);
+    tag.extend_from_slice(OWNER);
+    tag.extend_from_slice(&timestamp.to_be_bytes());
+    tag
+}
+
+/// ID3 size: 7 bits per byte, most significant first
+fn syncsafe(n: u32) -> [u8; 4] {
+    [
+        ((n >> 21) & 0x7F) as u8,
+        ((n >> 14) & 0x7F) as u8,
+        ((n >> 7) & 0x7F) as u8,
+        (n & 0x7F) as u8,
+    ]
+}
+
+/// Recently encoded tracks, so a player fetching segments one by one doesn't
+/// cause an encode per segment
+#[derive(Default)]
+pub struct HlsCache {
+    tracks: Mutex<VecDeque<(String, Arc<OnceCell<Arc<HlsTrack>>>)>>,
+    /// Encoded tracks on disk, kept across restarts
+    disk: Option<CacheManager>,
+}
+
+impl HlsCache {
+    /// A cache that also keeps every encoded track in `disk`
+    pub fn with_disk(disk: CacheManager) -> Self {
+        Self {
+            disk: Some(disk),
+            ..Self::default()
+        }
+    }
+
+    /// The track cached under `key`, read from disk or encoded with `encode`
+    /// on first use. Concurrent requests for the same key share one encode.
+    pub async fn get_or_encode<F, Fut>(&self, key: &str, encode: F) -> Result<Arc<HlsTrack>, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<HlsTrack, String>>,
+    {
+        let cell = {
+            let mut tracks = self.tracks.lock().unwrap();
+            match tracks.iter().position(|(k, _)| k == key) {
+                Some(index) => {
+                    let entry = tracks.remove(index).unwrap();
+                    let cell = entry.1.clone();
+                    tracks.push_back(entry);
+                    cell
+                }
+                None => {
+                    let cell = Arc::new(OnceCell::new());
+                    tracks.push_back((key.to_string(), cell.clone()));
+                    if tracks.len() > CACHED_TRACKS {
+                        tracks.pop_front();
+                    }
+                    cell
+                }
+            }
+        };
+        cell.get_or_try_init(|| async move {
+            if let Some(track) = self.read_disk(key).await {
+                return Ok(Arc::new(track));
+            }
+            let track = encode().await?;
+            if let Some(disk) = &self.disk {
+                if let Err(e) = disk.put(key, &track.data).await {
+                    warn!("Failed to cache {}: {}", key, e);
+                }
+            }
+            Ok(Arc::new(track))
+        })
+        .await
+        .cloned()
+    }
+
+    async fn read_disk(&self, key: &str) -> Option<HlsTrack> {
+        let data = self.disk.as_ref()?.get(key).await.ok()??;
+        match HlsTrack::from_adts(data) {
+            Ok(track) => Some(track),
+            Err(e) => {
+                warn!("Ignoring unreadable cached {}: {}", key, e);
+
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An ADTS frame of `len` bytes holding one raw data block
+    fn adts_frame(len: usize) -> Vec<u8> {
+        let mut frame = vec![0u8; len];
+        frame[0] = 0xFF;
+        frame[1] = 0xF1;
+        // AAC LC at 44.1 kHz
+        frame[2] = 0x50;
+        frame[3] = ((len >> 11) & 0x03) as u8;
+        frame[4] = ((len >> 3) & 0xFF) as u8;
+        frame[5] = ((len & 0x07) << 5) as u8;
+        frame
+    }
+
+    #[test]
+    fn splits_at_frame_boundaries() {
+        let data: Vec<u8> = (0..10).flat_map(|_| adts_frame(100)).collect();
+        // 4 frames of 1024 samples make a segment
+        let segments = split_adts(&data, 1024, 4.0).unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!((segments[0].start, segments[0].end), (0, 400));
+        assert_eq!(segments[1].start_sample, 4096);
+        assert_eq!((segments[2].start, segments[2].end), (800, 1000));
+        assert_eq!(segments[2].duration, 2.0);
+
+        assert!(split_adts(&data[..950], 1024, 4.0).is_err());
+        assert!(split_adts(&[0u8; 20], 1024, 4.0).is_err());
+    }
+
+    #[test]
+    fn playlist_lists_every_segment() {
+        let segment = |duration| HlsSegment {
+            start: 0,
+            end: 0,
+            start_sample: 0,
+            duration,
+        };
+        let playlist = media_playlist(&[segment(6.02), segment(1.5)], |i| format!("seg{i}.aac"));
+        assert_eq!(
+            playlist,
+            "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-PLAYLIST-TYPE:VOD\n\
+             #EXT-X-TARGETDURATION:7\n#EXT-X-MEDIA-SEQUENCE:0\n\
+             #EXTINF:6.020,\nseg0.aac\n#EXTINF:1.500,\nseg1.aac\n#EXT-X-ENDLIST\n"
+        );
+    }
+
+    #[test]
+    fn timestamp_tag_is_on_the_mpeg_clock() {
+        let tag = timestamp_tag(44_100, 44_100);
+        assert_eq!(&tag[..3], b"ID3");
+        assert_eq!(tag.len(), 10 + 10 + 45 + 8);
+        assert_eq!(&tag[tag.len() - 8..], &90_000u64.to_be_bytes());
+    }
+
+    #[test]
+    fn encodes_a_track_into_segments() {
+        let sample_rate = 44_100;
+        let samples: Vec<i32> = (0..sample_rate * 10)
+            .flat_map(|i| {
+                let t = i as f64 / sample_rate as f64;
+                let s = (8000.0 * (2.0 * std::f64::consts::PI * 440.0 * t).sin()) as i32;
+                [s, s]
+            })
+            .collect();
+        let flac = audio_codec::encode_to_flac(&samples, sample_rate, 2, 16).unwrap();
+
+        let track = HlsTrack::encode(&flac, DEFAULT_BIT_RATE).unwrap();
+        assert_eq!(track.segments.len(), 2);
+        let total: f64 = track.segments.iter().map(|s| s.duration).sum();
+        assert!((total - 10.0).abs() < 0.2, "total duration {total}");
+        let segment = track.segment(1).unwrap();
+        assert_eq!(&segment[..3], b"ID3");
+        assert!(track.segment(2).is_none());
+    }
+
+    #[tokio::test]
+    async fn encoded_tracks_are_read_back_from_disk() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = crate::cache::CacheConfig {
+            cache_dir: tmp.path().to_path_buf(),
+            max_size_bytes: 1024 * 1024,
+            max_files: 10,
+        };
+        // About 7 seconds of 44.1 kHz frames
+        let data: Vec<u8> = (0..300).flat_map(|_| adts_frame(100)).collect();
+
+        let cache = HlsCache::with_disk(CacheManager::with_config(config.clone()).await.unwrap());
+        let encoded = cache
+            .get_or_encode("transcode:t1:aac:128", || async {
+                HlsTrack::from_adts(data.clone())
+            })
+            .await
+            .unwrap();
+        assert_eq!(encoded.segments.len(), 2);
+
+        // A new cache, as after a restart, finds it without encoding
+        let cache = HlsCache::with_disk(CacheManager::with_config(config).await.unwrap());
+        let cached = cache
+            .get_or_encode("transcode:t1:aac:128", || async {
+                Err("encoded again".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(cached.sample_rate, 44_100);
+        assert_eq!(cached.segments, encoded.segments);
+    }
+}
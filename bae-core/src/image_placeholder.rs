@@ -0,0 +1,71 @@
+//! Blurhash placeholders for library images
+//!
+//! Computed once when an image is stored so clients can paint a blurred
+//! preview before the real image loads.
+
+use tracing::debug;
+
+/// Blurhash component grid (4x3 suits square-ish cover art)
+const COMPONENTS_X: u32 = 4;
+const COMPONENTS_Y: u32 = 3;
+
+/// Images are downscaled to this size before encoding; blurhash only keeps
+/// low-frequency detail, so larger inputs just cost time.
+const SAMPLE_SIZE: u32 = 32;
+
+/// Compute a blurhash for encoded image bytes (JPEG, PNG, etc.).
+///
+/// Returns None if the image can't be decoded.
+pub fn blurhash(bytes: &[u8]) -> Option<String> {
+    let image = match image::load_from_memory(bytes) {
+        Ok(image) => image,
+        Err(e) => {
+            debug!("Skipping blurhash, failed to decode image: {}", e);
+            return None;
+        }
+    };
+
+    let rgba = image.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_rgba8();
+    blurhash::encode(
+        COMPONENTS_X,
+        COMPONENTS_Y,
+        rgba.width(),
+        rgba.height(),
+        rgba.as_raw(),
+    )
+    .ok()
+}
+
+/// `blurhash` on the blocking thread pool, for async callers: decoding a
+/// full-size cover would otherwise stall the runtime.
+pub async fn blurhash_blocking(bytes: Vec<u8>) -> Option<String> {
+    tokio::task::spawn_blocking(move || blurhash(&bytes))
+        .await
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_png() {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(8, 8, image::Rgba([200, 40, 40, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let hash = blurhash(&png).unwrap();
+        // 1 size char + 1 max-AC char + 4 DC chars + 2 per AC component
+        assert_eq!(
+            hash.len(),
+            6 + 2 * (COMPONENTS_X * COMPONENTS_Y - 1) as usize
+        );
+    }
+
+    #[test]
+    fn rejects_non_image_bytes() {
+        assert!(blurhash(b"not an image").is_none());
+    }
+}
@@ -0,0 +1,161 @@
+//! Import from compressed archives
+//!
+//! Bandcamp and label downloads arrive as zips. Archives are extracted into a
+//! workspace under the cache directory and then scanned like any other folder.
+//! Zip is read natively; 7z and rar go through libarchive's `bsdtar`, which
+//! macOS ships as `tar`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+use tracing::info;
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Not a supported archive: {0}")]
+    Unsupported(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("{0} isn't installed; install libarchive to import 7z and rar archives")]
+    BsdtarMissing(&'static str),
+    #[error("Failed to run {0}: {1}")]
+    Command(&'static str, std::io::Error),
+    #[error("Couldn't extract {archive}: {message}")]
+    Extract { archive: String, message: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    SevenZip,
+    Rar,
+}
+
+impl ArchiveFormat {
+    /// File extensions accepted by the archive picker
+    pub const EXTENSIONS: &'static [&'static str] = &["zip", "7z", "rar"];
+
+    /// Detect the format from the file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "zip" => Some(Self::Zip),
+            "7z" => Some(Self::SevenZip),
+            "rar" => Some(Self::Rar),
+            _ => None,
+        }
+    }
+}
+
+/// Extract `archive` into a folder named after it inside `workspace`.
+///
+/// Returns the folder to scan. Blocking; run it off the async runtime.
+pub fn extract(archive: &Path, workspace: &Path) -> Result<PathBuf, ArchiveError> {
+    let format = ArchiveFormat::from_path(archive)
+        .ok_or_else(|| ArchiveError::Unsupported(archive.display().to_string()))?;
+
+    // The scanner names releases after their folder, so keep the archive's name
+    let name = archive
+        .file_stem()
+        .map(|s| s.to_os_string())
+        .unwrap_or_else(|| "archive".into());
+    let dest = workspace.join(name);
+    std::fs::create_dir_all(&dest)?;
+
+    info!("Extracting {} to {}", archive.display(), dest.display());
+
+    match format {
+        // ZipArchive::extract skips entries that would escape `dest`
+        ArchiveFormat::Zip => {
+            let file = std::fs::File::open(archive)?;
+            zip::ZipArchive::new(file)?.extract(&dest)?;
+        }
+        ArchiveFormat::SevenZip | ArchiveFormat::Rar => extract_with_bsdtar(archive, &dest)?,
+    }
+
+    Ok(dest)
+}
+
+/// bsdtar refuses absolute paths and `..` entries unless told otherwise.
+fn extract_with_bsdtar(archive: &Path, dest: &Path) -> Result<(), ArchiveError> {
+    let program = if cfg!(target_os = "macos") {
+        "tar"
+    } else {
+        "bsdtar"
+    };
+    let output = Command::new(program)
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dest)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ArchiveError::BsdtarMissing(program),
+            _ => ArchiveError::Command(program, e),
+        })?;
+    if !output.status.success() {
+        return Err(ArchiveError::Extract {
+            archive: archive.display().to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn detects_format_from_extension() {
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("/downloads/Glass Harbor - Tidewater.ZIP")),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("album.7z")),
+            Some(ArchiveFormat::SevenZip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("album.rar")),
+            Some(ArchiveFormat::Rar)
+        );
+        assert_eq!(ArchiveFormat::from_path(Path::new("album.flac")), None);
+        assert_eq!(ArchiveFormat::from_path(Path::new("album")), None);
+    }
+
+    #[test]
+    fn extracts_zip_into_folder_named_after_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("Glass Harbor - Tidewater.zip");
+        {
+            let mut writer = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file("01 Low Light.flac", options).unwrap();
+            writer.write_all(b"fLaC").unwrap();
+            writer.start_file("Scans/cover.jpg", options).unwrap();
+            writer.write_all(b"jpeg").unwrap();
+            writer.finish().unwrap();
+        }
+
+        let workspace = dir.path().join("workspace");
+        let folder = extract(&archive, &workspace).unwrap();
+
+        assert_eq!(folder, workspace.join("Glass Harbor - Tidewater"));
+        assert_eq!(
+            std::fs::read(folder.join("01 Low Light.flac")).unwrap(),
+            b"fLaC"
+        );
+        assert!(folder.join("Scans/cover.jpg").exists());
+    }
+
+    #[test]
+    fn rejects_unknown_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = extract(&dir.path().join("notes.txt"), dir.path());
+        assert!(matches!(result, Err(ArchiveError::Unsupported(_))));
+    }
+}
@@ -1,10 +1,34 @@
 use crate::content_type::ContentType;
-use crate::db::{DbLibraryImage, LibraryImageType};
+use crate::db::{DbArtist, DbLibraryImage, LibraryImageType};
 use crate::discogs::DiscogsClient;
 use crate::library::LibraryManager;
 use crate::library_dir::LibraryDir;
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
+const FANART_BASE_URL: &str = "https://webservice.fanart.tv/v3/music";
+
+/// How long before an artist the sources had nothing for is looked up again
+pub const LOOKUP_RETRY_AFTER: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Pause between artists in the background fetch. Discogs allows 60
+/// authenticated requests a minute.
+const FETCH_INTERVAL: Duration = Duration::from_millis(1100);
+
+/// Services artist photos can come from. fanart.tv is tried first (by
+/// MusicBrainz ID), then Discogs.
+#[derive(Clone, Default)]
+pub struct ArtistImageSources {
+    pub fanart_api_key: Option<String>,
+    pub discogs: Option<DiscogsClient>,
+}
+
+impl ArtistImageSources {
+    pub fn is_empty(&self) -> bool {
+        self.fanart_api_key.is_none() && self.discogs.is_none()
+    }
+}
+
 /// Fetch and save an artist image from Discogs.
 ///
 /// Skips if the artist already has an image on disk.
@@ -38,7 +62,160 @@ pub async fn fetch_and_save_artist_image(
         }
     };
 
-    // Download the image
+    save_artist_image(
+        artist_id,
+        &image_url,
+        "discogs",
+        library_dir,
+        library_manager,
+    )
+    .await
+}
+
+/// Fetch images for every library artist that doesn't have one yet.
+///
+/// Meant to run in the background: artists are handled one at a time with
+/// a pause in between, and each lookup is recorded so artists the sources
+/// don't have are skipped for `LOOKUP_RETRY_AFTER`. Returns how many images
+/// were saved.
+pub async fn fetch_missing_artist_images(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    sources: &ArtistImageSources,
+) -> usize {
+    if sources.is_empty() {
+        return 0;
+    }
+
+    let checked_since = chrono::Utc::now().timestamp() - LOOKUP_RETRY_AFTER.as_secs() as i64;
+    let artists = match library_manager
+        .get_artists_missing_images(checked_since)
+        .await
+    {
+        Ok(artists) => artists,
+        Err(e) => {
+            warn!("Failed to list artists missing images: {}", e);
+            return 0;
+        }
+    };
+    if artists.is_empty() {
+        return 0;
+    }
+
+    info!("Fetching images for {} artists", artists.len());
+
+    let mut saved = 0;
+    for (index, artist) in artists.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(FETCH_INTERVAL).await;
+        }
+
+        let found = match find_artist_image(artist, sources).await {
+            Ok(found) => found,
+            Err(e) => {
+                // Leave it unrecorded so the next run tries again
+                warn!("Artist image lookup failed for '{}': {}", artist.name, e);
+                continue;
+            }
+        };
+        if let Some((image_url, source)) = found {
+            if save_artist_image(&artist.id, &image_url, source, library_dir, library_manager).await
+            {
+                saved += 1;
+            }
+        }
+        if let Err(e) = library_manager.record_artist_image_lookup(&artist.id).await {
+            warn!("Failed to record artist image lookup: {}", e);
+        }
+    }
+
+    info!("Saved {} of {} artist images", saved, artists.len());
+
+    saved
+}
+
+/// Ask each source in turn for an image URL. Errors only if a source
+/// failed before any source had an image.
+async fn find_artist_image(
+    artist: &DbArtist,
+    sources: &ArtistImageSources,
+) -> Result<Option<(String, &'static str)>, String> {
+    let mut error = None;
+
+    if let (Some(api_key), Some(mbid)) = (&sources.fanart_api_key, &artist.musicbrainz_artist_id) {
+        match get_fanart_artist_image(api_key, mbid).await {
+            Ok(Some(url)) => return Ok(Some((url, "fanart.tv"))),
+            Ok(None) => {}
+            Err(e) => error = Some(format!("fanart.tv: {}", e)),
+        }
+    }
+
+    if let (Some(discogs), Some(discogs_id)) = (&sources.discogs, &artist.discogs_artist_id) {
+        match discogs.get_artist_image(discogs_id).await {
+            Ok(Some(url)) => return Ok(Some((url, "discogs"))),
+            Ok(None) => {}
+            Err(e) => error = Some(format!("Discogs: {}", e)),
+        }
+    }
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(None),
+    }
+}
+
+/// Look up the best-liked artist thumbnail on fanart.tv
+async fn get_fanart_artist_image(
+    api_key: &str,
+    musicbrainz_artist_id: &str,
+) -> Result<Option<String>, reqwest::Error> {
+    let url = format!("{}/{}", FANART_BASE_URL, musicbrainz_artist_id);
+    let response = reqwest::Client::new()
+        .get(&url)
+        .query(&[("api_key", api_key)])
+        .header("User-Agent", "bae/1.0 +https://github.com/hideselfview/bae")
+        .send()
+        .await?;
+
+    // fanart.tv answers 404 for artists it has no images for
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let json: serde_json::Value = response.error_for_status()?.json().await?;
+    Ok(best_fanart_thumb(&json))
+}
+
+/// Pick the artist thumbnail with the most likes
+fn best_fanart_thumb(json: &serde_json::Value) -> Option<String> {
+    json.get("artistthumb")?
+        .as_array()?
+        .iter()
+        .filter_map(|thumb| {
+            let url = thumb.get("url")?.as_str()?;
+            let likes = thumb
+                .get("likes")
+                .and_then(|l| l.as_str())
+                .and_then(|l| l.parse::<u32>().ok())
+                .unwrap_or(0);
+            Some((likes, url))
+        })
+        .max_by_key(|(likes, _)| *likes)
+        .map(|(_, url)| url.to_string())
+}
+
+/// Download an artist image and save it to `images/ab/cd/{artist_id}`,
+/// recording it as a library image from `source`.
+///
+/// Returns true if an image was saved successfully.
+async fn save_artist_image(
+    artist_id: &str,
+    image_url: &str,
+    source: &str,
+    library_dir: &LibraryDir,
+    library_manager: &LibraryManager,
+) -> bool {
+    let dest_path = library_dir.image_path(artist_id);
+
     let client = match reqwest::Client::builder()
         .user_agent("bae/1.0 +https://github.com/hideselfview/bae")
         .build()
@@ -50,7 +227,7 @@ pub async fn fetch_and_save_artist_image(
         }
     };
 
-    let response = match client.get(&image_url).send().await {
+    let response = match client.get(image_url).send().await {
         Ok(r) => r,
         Err(e) => {
             warn!("Failed to download artist image: {}", e);
@@ -79,7 +256,7 @@ pub async fn fetch_and_save_artist_image(
             }
         })
         .unwrap_or_else(|| {
-            let ext = reqwest::Url::parse(&image_url)
+            let ext = reqwest::Url::parse(image_url)
                 .ok()
                 .and_then(|parsed| parsed.path().rsplit('.').next().map(|e| e.to_lowercase()))
                 .unwrap_or_default();
@@ -124,8 +301,9 @@ pub async fn fetch_and_save_artist_image(
         file_size: bytes.len() as i64,
         width: None,
         height: None,
-        source: "discogs".to_string(),
-        source_url: Some(image_url),
+        source: source.to_string(),
+        source_url: Some(image_url.to_string()),
+        blurhash: None,
         updated_at: chrono::Utc::now(),
         created_at: chrono::Utc::now(),
     };
@@ -136,3 +314,30 @@ pub async fn fetch_and_save_artist_image(
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn best_fanart_thumb_prefers_most_liked() {
+        let json = serde_json::json!({
+            "name": "Glass Harbor",
+            "artistthumb": [
+                {"id": "1", "url": "https://assets.fanart.tv/a.jpg", "likes": "2"},
+                {"id": "2", "url": "https://assets.fanart.tv/b.jpg", "likes": "11"},
+                {"id": "3", "url": "https://assets.fanart.tv/c.jpg", "likes": "5"}
+            ]
+        });
+        assert_eq!(
+            best_fanart_thumb(&json).as_deref(),
+            Some("https://assets.fanart.tv/b.jpg")
+        );
+    }
+
+    #[test]
+    fn best_fanart_thumb_without_thumbs_is_none() {
+        let json = serde_json::json!({"name": "Tidewater", "artistbackground": []});
+        assert_eq!(best_fanart_thumb(&json), None);
+    }
+}
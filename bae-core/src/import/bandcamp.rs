@@ -0,0 +1,530 @@
+//! Bandcamp collection importer
+//!
+//! Bandcamp has no public API for fans, so this talks to the same endpoints the
+//! website uses, authenticated with the `identity` cookie from a logged-in
+//! browser session. Purchases are listed from the fan collection, downloaded as
+//! FLAC zips, extracted, and then imported via `ImportRequest::Bandcamp` with
+//! metadata taken from the collection rather than Discogs or MusicBrainz.
+
+use super::archive::{self, ArchiveError};
+use super::ParsedAlbum;
+use crate::db::{DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack, ImportStatus};
+use reqwest::{header, Client};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+use uuid::Uuid;
+
+const BASE_URL: &str = "https://bandcamp.com";
+
+/// Items requested per collection page
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Error, Debug)]
+pub enum BandcampError {
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Not logged in (identity cookie missing or expired)")]
+    NotAuthenticated,
+    #[error("No download link for this purchase")]
+    NoDownload,
+    #[error("FLAC download not offered for this purchase")]
+    NoFlac,
+    #[error("Unexpected response: {0}")]
+    Parse(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+}
+
+/// A purchased album in the user's Bandcamp collection
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandcampAlbum {
+    /// Bandcamp album (tralbum) ID
+    pub item_id: String,
+    pub band_id: String,
+    pub artist: String,
+    pub title: String,
+    pub tracks: Vec<BandcampTrack>,
+    /// Cover art ID, used to build the bcbits.com image URL
+    pub art_id: Option<u64>,
+    /// Download page for this purchase
+    pub redownload_url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandcampTrack {
+    pub title: String,
+    pub track_number: Option<i32>,
+    pub duration_ms: Option<i64>,
+}
+
+impl BandcampAlbum {
+    /// Full-size cover art URL
+    pub fn art_url(&self) -> Option<String> {
+        self.art_id
+            .map(|id| format!("https://f4.bcbits.com/img/a{:010}_10.jpg", id))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionSummaryResponse {
+    fan_id: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionItemsResponse {
+    items: Vec<CollectionItem>,
+    more_available: bool,
+    last_token: Option<String>,
+    #[serde(default)]
+    redownload_urls: HashMap<String, String>,
+    #[serde(default)]
+    tracklists: HashMap<String, Vec<TracklistEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionItem {
+    item_type: String,
+    tralbum_type: String,
+    tralbum_id: u64,
+    band_id: u64,
+    band_name: String,
+    item_title: String,
+    item_art_id: Option<u64>,
+    sale_item_type: Option<String>,
+    sale_item_id: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TracklistEntry {
+    title: String,
+    track_number: Option<i32>,
+    /// Seconds
+    duration: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DownloadPageBlob {
+    digital_items: Vec<DigitalItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DigitalItem {
+    #[serde(default)]
+    downloads: HashMap<String, DownloadFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DownloadFormat {
+    url: String,
+}
+
+#[derive(Clone)]
+pub struct BandcampClient {
+    client: Client,
+    identity: String,
+}
+
+impl BandcampClient {
+    /// Create a client from the value of the `identity` cookie on bandcamp.com
+    pub fn new(identity: String) -> Self {
+        Self {
+            client: Client::new(),
+            identity,
+        }
+    }
+
+    fn cookie(&self) -> String {
+        format!("identity={}", self.identity)
+    }
+
+    /// Resolve the logged-in fan's ID. Fails if the cookie isn't valid.
+    pub async fn fan_id(&self) -> Result<u64, BandcampError> {
+        let response: CollectionSummaryResponse = self
+            .client
+            .get(format!("{}/api/fan/2/collection_summary", BASE_URL))
+            .header(header::COOKIE, self.cookie())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        response.fan_id.ok_or(BandcampError::NotAuthenticated)
+    }
+
+    /// List all purchased albums in the fan's collection.
+    ///
+    /// Single-track purchases are skipped; only full albums are returned.
+    pub async fn collection(&self, fan_id: u64) -> Result<Vec<BandcampAlbum>, BandcampError> {
+        let mut albums = Vec::new();
+        // Tokens are "{timestamp}::{type}::"; starting from now walks back through all purchases
+        let mut token = format!("{}::a::", chrono::Utc::now().timestamp());
+
+        loop {
+            let page: CollectionItemsResponse = self
+                .client
+                .post(format!("{}/api/fancollection/1/collection_items", BASE_URL))
+                .header(header::COOKIE, self.cookie())
+                .json(&serde_json::json!({
+                    "fan_id": fan_id,
+                    "older_than_token": token,
+                    "count": PAGE_SIZE,
+                }))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            let more_available = page.more_available;
+            let last_token = page.last_token.clone();
+            albums.extend(albums_from_page(page));
+
+            match last_token {
+                Some(next) if more_available => token = next,
+                _ => break,
+            }
+        }
+
+        info!("Found {} albums in Bandcamp collection", albums.len());
+        Ok(albums)
+    }
+
+    /// Download an album's FLAC zip and extract it into `dest`.
+    ///
+    /// Returns the folder containing the extracted files, ready for import.
+    pub async fn download_flac(
+        &self,
+        album: &BandcampAlbum,
+        dest: &Path,
+    ) -> Result<PathBuf, BandcampError> {
+        let page_url = album
+            .redownload_url
+            .as_ref()
+            .ok_or(BandcampError::NoDownload)?;
+        let html = self
+            .client
+            .get(page_url)
+            .header(header::COOKIE, self.cookie())
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let flac_url = flac_url_from_download_page(&html)?;
+
+        tokio::fs::create_dir_all(dest).await?;
+        let zip_path = dest.join(format!("{}.zip", album.item_id));
+
+        info!("Downloading '{}' from Bandcamp", album.title);
+
+        let mut response = self
+            .client
+            .get(&flac_url)
+            .header(header::COOKIE, self.cookie())
+            .send()
+            .await?
+            .error_for_status()?;
+        let mut file = tokio::fs::File::create(&zip_path).await?;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        drop(file);
+
+        // Extracts into `dest/{item_id}`, named after the zip
+        let zip_path_for_extract = zip_path.clone();
+        let dest_for_extract = dest.to_path_buf();
+        let folder = tokio::task::spawn_blocking(move || {
+            archive::extract(&zip_path_for_extract, &dest_for_extract)
+        })
+        .await
+        .map_err(|e| BandcampError::Parse(format!("Extraction task failed: {}", e)))??;
+        tokio::fs::remove_file(&zip_path).await?;
+
+        info!("Extracted '{}' to {}", album.title, folder.display());
+        Ok(folder)
+    }
+}
+
+/// Turn a collection page into albums, joining in tracklists and download links
+fn albums_from_page(page: CollectionItemsResponse) -> Vec<BandcampAlbum> {
+    let CollectionItemsResponse {
+        items,
+        mut redownload_urls,
+        mut tracklists,
+        ..
+    } = page;
+
+    items
+        .into_iter()
+        .filter(|item| item.item_type == "album")
+        .map(|item| {
+            let tracks = tracklists
+                .remove(&format!("{}{}", item.tralbum_type, item.tralbum_id))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|t| BandcampTrack {
+                    title: t.title,
+                    track_number: t.track_number,
+                    duration_ms: t.duration.map(|secs| (secs * 1000.0).round() as i64),
+                })
+                .collect();
+            let redownload_url = match (&item.sale_item_type, item.sale_item_id) {
+                (Some(kind), Some(id)) => redownload_urls.remove(&format!("{}{}", kind, id)),
+                _ => None,
+            };
+            BandcampAlbum {
+                item_id: item.tralbum_id.to_string(),
+                band_id: item.band_id.to_string(),
+                artist: item.band_name,
+                title: item.item_title,
+                tracks,
+                art_id: item.item_art_id,
+                redownload_url,
+            }
+        })
+        .collect()
+}
+
+/// Extract the FLAC download URL from the `data-blob` JSON on a download page
+fn flac_url_from_download_page(html: &str) -> Result<String, BandcampError> {
+    let start = html
+        .find("data-blob=\"")
+        .ok_or_else(|| BandcampError::Parse("download page has no data-blob".to_string()))?
+        + "data-blob=\"".len();
+    let len = html[start..]
+        .find('"')
+        .ok_or_else(|| BandcampError::Parse("unterminated data-blob".to_string()))?;
+    let json = unescape_html(&html[start..start + len]);
+
+    let blob: DownloadPageBlob =
+        serde_json::from_str(&json).map_err(|e| BandcampError::Parse(e.to_string()))?;
+    let mut item = blob
+        .digital_items
+        .into_iter()
+        .next()
+        .ok_or(BandcampError::NoDownload)?;
+    item.downloads
+        .remove("flac")
+        .map(|d| d.url)
+        .ok_or(BandcampError::NoFlac)
+}
+
+/// Decode the entities Bandcamp uses when embedding JSON in an attribute
+fn unescape_html(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Convert a Bandcamp album into database models.
+///
+/// `year` usually comes from the downloaded files' tags, since the collection
+/// API doesn't report release dates.
+pub fn parse_bandcamp_album(album: &BandcampAlbum, year: Option<u32>) -> ParsedAlbum {
+    let now = chrono::Utc::now();
+
+    let db_album = DbAlbum {
+        id: Uuid::new_v4().to_string(),
+        title: album.title.clone(),
+        year: year.map(|y| y as i32),
+        discogs_release: None,
+        musicbrainz_release: None,
+        bandcamp_album_id: Some(album.item_id.clone()),
+        cover_release_id: None,
+        is_compilation: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let db_release = DbRelease {
+        id: Uuid::new_v4().to_string(),
+        album_id: db_album.id.clone(),
+        release_name: None,
+        year: year.map(|y| y as i32),
+        discogs_release_id: None,
+        bandcamp_release_id: Some(album.item_id.clone()),
+        format: Some("Digital".to_string()),
+        label: None,
+        catalog_number: None,
+        country: None,
+        barcode: None,
+        import_status: ImportStatus::Queued,
+        managed_locally: false,
+        managed_in_cloud: false,
+        unmanaged_path: None,
+        private: false,
+        unverified: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let artist = DbArtist {
+        id: Uuid::new_v4().to_string(),
+        name: album.artist.clone(),
+        sort_name: Some(album.artist.clone()),
+        discogs_artist_id: None,
+        bandcamp_artist_id: Some(album.band_id.clone()),
+        musicbrainz_artist_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    let album_artist = DbAlbumArtist::new(&db_album.id, &artist.id, 0);
+
+    let tracks = album
+        .tracks
+        .iter()
+        .enumerate()
+        .map(|(index, track)| DbTrack {
+            id: Uuid::new_v4().to_string(),
+            release_id: db_release.id.clone(),
+            title: track.title.clone(),
+            disc_number: None,
+            track_number: Some(track.track_number.unwrap_or(index as i32 + 1)),
+            duration_ms: track.duration_ms,
+            discogs_position: None,
+            import_status: ImportStatus::Queued,
+            updated_at: now,
+            created_at: now,
+        })
+        .collect();
+
+    (
+        db_album,
+        db_release,
+        tracks,
+        vec![artist],
+        vec![album_artist],
+        Vec::new(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLLECTION_PAGE: &str = r#"{
+        "items": [
+            {
+                "item_type": "album",
+                "tralbum_type": "a",
+                "tralbum_id": 111,
+                "band_id": 9,
+                "band_name": "Glass Harbor",
+                "item_title": "Pacific Standard",
+                "item_art_id": 42,
+                "sale_item_type": "p",
+                "sale_item_id": 555
+            },
+            {
+                "item_type": "track",
+                "tralbum_type": "t",
+                "tralbum_id": 222,
+                "band_id": 9,
+                "band_name": "Glass Harbor",
+                "item_title": "Tide Pool",
+                "item_art_id": null,
+                "sale_item_type": "p",
+                "sale_item_id": 556
+            }
+        ],
+        "more_available": false,
+        "last_token": "1700000000::a::",
+        "redownload_urls": {
+            "p555": "https://bandcamp.com/download?from=collection&payment_id=555",
+            "p556": "https://bandcamp.com/download?from=collection&payment_id=556"
+        },
+        "tracklists": {
+            "a111": [
+                { "title": "Coastal", "track_number": 1, "duration": 201.5 },
+                { "title": "Tide Pool", "track_number": 2, "duration": null }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn collection_page_keeps_albums_with_tracks_and_links() {
+        let page: CollectionItemsResponse = serde_json::from_str(COLLECTION_PAGE).unwrap();
+        let albums = albums_from_page(page);
+
+        assert_eq!(albums.len(), 1);
+        let album = &albums[0];
+        assert_eq!(album.item_id, "111");
+        assert_eq!(album.artist, "Glass Harbor");
+        assert_eq!(album.title, "Pacific Standard");
+        assert_eq!(
+            album.redownload_url.as_deref(),
+            Some("https://bandcamp.com/download?from=collection&payment_id=555")
+        );
+        assert_eq!(album.tracks.len(), 2);
+        assert_eq!(album.tracks[0].duration_ms, Some(201_500));
+        assert_eq!(album.tracks[1].duration_ms, None);
+        assert_eq!(
+            album.art_url().as_deref(),
+            Some("https://f4.bcbits.com/img/a0000000042_10.jpg")
+        );
+    }
+
+    #[test]
+    fn download_page_flac_url() {
+        let html = r#"<div id="pagedata" data-blob="{&quot;digital_items&quot;:[{&quot;downloads&quot;:{&quot;mp3-320&quot;:{&quot;url&quot;:&quot;https://popplers5.bandcamp.com/mp3&quot;},&quot;flac&quot;:{&quot;url&quot;:&quot;https://popplers5.bandcamp.com/download/album?enc=flac&amp;id=111&quot;}}}]}"></div>"#;
+        assert_eq!(
+            flac_url_from_download_page(html).unwrap(),
+            "https://popplers5.bandcamp.com/download/album?enc=flac&id=111"
+        );
+    }
+
+    #[test]
+    fn download_page_without_flac() {
+        let html =
+            r#"<div data-blob="{&quot;digital_items&quot;:[{&quot;downloads&quot;:{}}]}"></div>"#;
+        assert!(matches!(
+            flac_url_from_download_page(html),
+            Err(BandcampError::NoFlac)
+        ));
+    }
+
+    #[test]
+    fn parse_album_fills_bandcamp_ids() {
+        let album = BandcampAlbum {
+            item_id: "111".to_string(),
+            band_id: "9".to_string(),
+            artist: "Glass Harbor".to_string(),
+            title: "Pacific Standard".to_string(),
+            tracks: vec![
+                BandcampTrack {
+                    title: "Coastal".to_string(),
+                    track_number: Some(1),
+                    duration_ms: Some(201_500),
+                },
+                BandcampTrack {
+                    title: "Tide Pool".to_string(),
+                    track_number: None,
+                    duration_ms: None,
+                },
+            ],
+            art_id: None,
+            redownload_url: None,
+        };
+
+        let (db_album, db_release, tracks, artists, album_artists, _) =
+            parse_bandcamp_album(&album, Some(2019));
+
+        assert_eq!(db_album.bandcamp_album_id.as_deref(), Some("111"));
+        assert_eq!(db_album.year, Some(2019));
+        assert_eq!(db_release.album_id, db_album.id);
+        assert_eq!(db_release.bandcamp_release_id.as_deref(), Some("111"));
+        assert_eq!(artists[0].bandcamp_artist_id.as_deref(), Some("9"));
+        assert_eq!(album_artists[0].artist_id, artists[0].id);
+        assert_eq!(tracks[1].track_number, Some(2));
+        assert!(tracks.iter().all(|t| t.release_id == db_release.id));
+    }
+}
@@ -108,6 +108,56 @@ pub async fn fetch_cover_art_from_discogs(
     }
 }
 
+/// Fetch cover art URL from the iTunes Search API, matching the album by
+/// artist and title. Returns the largest rendition iTunes offers.
+pub async fn fetch_cover_art_from_itunes(artist: &str, title: &str) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .user_agent("bae/1.0 +https://github.com/hideselfview/bae")
+        .build()
+        .ok()?;
+    let term = format!("{} {}", artist, title);
+    let response = client
+        .get("https://itunes.apple.com/search")
+        .query(&[
+            ("term", term.as_str()),
+            ("entity", "album"),
+            ("limit", "10"),
+        ])
+        .send()
+        .await;
+    match response {
+        Ok(response) if response.status().is_success() => {
+            let json = response.json::<serde_json::Value>().await.ok()?;
+            itunes_artwork_url(&json, artist, title)
+        }
+        Ok(response) => {
+            debug!("iTunes search returned status {}", response.status());
+            None
+        }
+        Err(e) => {
+            debug!("Failed to search iTunes for cover art: {}", e);
+            None
+        }
+    }
+}
+
+/// Artwork of the search result with the same artist and title. iTunes
+/// links a 100px rendition; other sizes are served by changing the size in
+/// the URL, capped at the original's size.
+fn itunes_artwork_url(json: &serde_json::Value, artist: &str, title: &str) -> Option<String> {
+    let same = |a: &str, b: &str| a.trim().eq_ignore_ascii_case(b.trim());
+    json.get("results")?
+        .as_array()?
+        .iter()
+        .find(|result| {
+            let name = |key: &str| result.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+            same(name("artistName"), artist) && same(name("collectionName"), title)
+        })?
+        .get("artworkUrl100")?
+        .as_str()
+        .map(|url| upgrade_to_https(&url.replace("/100x100bb.", "/3000x3000bb.")))
+}
+
 /// Fetch cover art for a MusicBrainz release with fallback to Discogs
 ///
 /// If a `DiscogsRelease` was already fetched (from musicbrainz_parser), its cover image
@@ -196,3 +246,32 @@ pub async fn download_cover_art_bytes(
     info!("Downloaded cover art ({} bytes)", bytes.len());
     Ok((bytes.to_vec(), content_type))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn itunes_artwork_matches_artist_and_title() {
+        let json = serde_json::json!({
+            "resultCount": 2,
+            "results": [
+                {
+                    "artistName": "Glass Harbor",
+                    "collectionName": "Low Light (Deluxe)",
+                    "artworkUrl100": "https://is1-ssl.mzstatic.com/image/thumb/a/100x100bb.jpg"
+                },
+                {
+                    "artistName": "Glass Harbor",
+                    "collectionName": "low light",
+                    "artworkUrl100": "https://is1-ssl.mzstatic.com/image/thumb/b/100x100bb.jpg"
+                }
+            ]
+        });
+        assert_eq!(
+            itunes_artwork_url(&json, "Glass Harbor", "Low Light").as_deref(),
+            Some("https://is1-ssl.mzstatic.com/image/thumb/b/3000x3000bb.jpg")
+        );
+        assert_eq!(itunes_artwork_url(&json, "Tidewater", "Low Light"), None);
+    }
+}
@@ -0,0 +1,286 @@
+use crate::content_type::ContentType;
+use crate::db::{DbLibraryImage, LibraryImageType};
+use crate::discogs::DiscogsClient;
+use crate::import::cover_art::{
+    download_cover_art_bytes, fetch_cover_art_from_archive, fetch_cover_art_from_itunes,
+};
+use crate::library::LibraryManager;
+use crate::library_dir::LibraryDir;
+use crate::network::upgrade_to_https;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Covers smaller than this on their shorter side are looked up again
+pub const LOW_RES_COVER_SIZE: u32 = 600;
+
+/// How long before a cover the sources had nothing better for is looked up again
+pub const LOOKUP_RETRY_AFTER: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Pause between releases in the background scan, to stay well inside the
+/// sources' rate limits
+const FETCH_INTERVAL: Duration = Duration::from_millis(1100);
+
+/// A larger cover found for a release
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverUpgrade {
+    pub album_id: String,
+    pub release_id: String,
+    pub url: String,
+    /// "musicbrainz", "discogs" or "itunes", the same sources the cover
+    /// picker records
+    pub source: String,
+    pub width: u32,
+    pub height: u32,
+    pub current_width: u32,
+    pub current_height: u32,
+}
+
+/// Width and height of an encoded image, read from its header
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// Look for larger versions of the library's low-resolution covers.
+///
+/// Meant to run in the background like the artist image fetch: releases are
+/// handled one at a time with a pause in between, and each lookup is
+/// recorded so releases without anything better are skipped for
+/// `LOOKUP_RETRY_AFTER`. Returns the upgrades found; with `auto_replace`
+/// they've already been saved, otherwise they're for the user to accept.
+pub async fn find_cover_upgrades(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    discogs: Option<&DiscogsClient>,
+    auto_replace: bool,
+) -> Vec<CoverUpgrade> {
+    let checked_since = chrono::Utc::now().timestamp() - LOOKUP_RETRY_AFTER.as_secs() as i64;
+    let release_ids = match library_manager
+        .get_cover_upgrade_candidates(LOW_RES_COVER_SIZE as i64, checked_since)
+        .await
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            warn!("Failed to list covers to upgrade: {}", e);
+            return Vec::new();
+        }
+    };
+    if release_ids.is_empty() {
+        return Vec::new();
+    }
+
+    info!(
+        "Looking for larger covers for {} releases",
+        release_ids.len()
+    );
+
+    let mut upgrades = Vec::new();
+    for (index, release_id) in release_ids.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(FETCH_INTERVAL).await;
+        }
+
+        match find_cover_upgrade(library_manager, library_dir, discogs, release_id).await {
+            Ok(Some((upgrade, bytes, content_type))) => {
+                if !auto_replace {
+                    upgrades.push(upgrade);
+                } else if let Err(e) = save_cover(
+                    library_manager,
+                    library_dir,
+                    &upgrade.release_id,
+                    &bytes,
+                    content_type,
+                    &upgrade.source,
+                    &upgrade.url,
+                )
+                .await
+                {
+                    warn!("Failed to save upgraded cover: {}", e);
+                } else {
+                    upgrades.push(upgrade);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                // Leave it unrecorded so the next run tries again
+                warn!("Cover upgrade lookup failed for {}: {}", release_id, e);
+                continue;
+            }
+        }
+        if let Err(e) = library_manager
+            .record_cover_upgrade_lookup(release_id)
+            .await
+        {
+            warn!("Failed to record cover upgrade lookup: {}", e);
+        }
+    }
+
+    info!(
+        "Found {} cover upgrades for {} releases",
+        upgrades.len(),
+        release_ids.len()
+    );
+
+    upgrades
+}
+
+/// Ask every source for the release's cover and keep the largest, if it's
+/// larger than the current one
+async fn find_cover_upgrade(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    discogs: Option<&DiscogsClient>,
+    release_id: &str,
+) -> Result<Option<(CoverUpgrade, Vec<u8>, ContentType)>, String> {
+    let db = library_manager.database();
+    let release = db
+        .get_release_by_id(release_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("release not found")?;
+    let album = db
+        .get_album_by_id(&release.album_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("album not found")?;
+
+    let current = std::fs::read(library_dir.image_path(release_id))
+        .map_err(|e| format!("failed to read cover: {}", e))?;
+    let Some((current_width, current_height)) = image_dimensions(&current) else {
+        return Err("current cover can't be decoded".to_string());
+    };
+    if current_width.min(current_height) >= LOW_RES_COVER_SIZE {
+        debug!("Cover of {} is already {}px", release_id, current_width);
+        return Ok(None);
+    }
+
+    let mut urls: Vec<(String, &str)> = Vec::new();
+    if let Some(ref mb) = album.musicbrainz_release {
+        if let Some(url) = fetch_cover_art_from_archive(&mb.release_id).await {
+            urls.push((url, "musicbrainz"));
+        }
+    }
+    let discogs_id = release
+        .discogs_release_id
+        .clone()
+        .or(album.discogs_release.as_ref().map(|d| d.release_id.clone()));
+    if let (Some(client), Some(discogs_id)) = (discogs, discogs_id) {
+        match client.get_release(&discogs_id).await {
+            Ok(discogs_release) => {
+                if let Some(url) = discogs_release.cover_image {
+                    urls.push((upgrade_to_https(&url), "discogs"));
+                }
+            }
+            Err(e) => debug!("Discogs release {} unavailable: {}", discogs_id, e),
+        }
+    }
+    let artists = db
+        .get_artists_for_album(&album.id)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(artist) = artists.first() {
+        if let Some(url) = fetch_cover_art_from_itunes(&artist.name, &album.title).await {
+            urls.push((url, "itunes"));
+        }
+    }
+
+    let mut best: Option<(CoverUpgrade, Vec<u8>, ContentType)> = None;
+    for (url, source) in urls {
+        let (bytes, content_type) = match download_cover_art_bytes(&url).await {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                debug!("Skipping {} cover: {}", source, e);
+                continue;
+            }
+        };
+        let Some((width, height)) = image_dimensions(&bytes) else {
+            continue;
+        };
+        let best_size = best
+            .as_ref()
+            .map(|(u, _, _)| u.width.min(u.height))
+            .unwrap_or(current_width.min(current_height));
+        if width.min(height) > best_size {
+            let upgrade = CoverUpgrade {
+                album_id: album.id.clone(),
+                release_id: release_id.to_string(),
+                url,
+                source: source.to_string(),
+                width,
+                height,
+                current_width,
+                current_height,
+            };
+            best = Some((upgrade, bytes, content_type));
+        }
+    }
+    Ok(best)
+}
+
+/// Write a release's cover to `images/ab/cd/{release_id}` and record it, so
+/// sync uploads the new image
+async fn save_cover(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    release_id: &str,
+    bytes: &[u8],
+    content_type: ContentType,
+    source: &str,
+    url: &str,
+) -> Result<(), String> {
+    let cover_path = library_dir.image_path(release_id);
+    if let Some(parent) = cover_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create images dir: {}", e))?;
+    }
+    std::fs::write(&cover_path, bytes).map_err(|e| format!("Failed to write cover: {}", e))?;
+
+    let dimensions = image_dimensions(bytes);
+    let image = DbLibraryImage {
+        id: release_id.to_string(),
+        image_type: LibraryImageType::Cover,
+        content_type,
+        file_size: bytes.len() as i64,
+        width: dimensions.map(|(w, _)| w as i64),
+        height: dimensions.map(|(_, h)| h as i64),
+        source: source.to_string(),
+        source_url: Some(url.to_string()),
+        blurhash: crate::image_placeholder::blurhash(bytes),
+        updated_at: chrono::Utc::now(),
+        created_at: chrono::Utc::now(),
+    };
+    library_manager
+        .upsert_library_image(&image)
+        .await
+        .map_err(|e| format!("Failed to upsert library image: {}", e))?;
+
+    info!(
+        "Replaced cover of {} with {} ({} bytes)",
+        release_id,
+        source,
+        bytes.len()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_dimensions_reads_png_header() {
+        let mut bytes = Vec::new();
+        image::RgbImage::new(640, 480)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        assert_eq!(image_dimensions(&bytes), Some((640, 480)));
+        assert_eq!(image_dimensions(b"not an image"), None);
+    }
+}
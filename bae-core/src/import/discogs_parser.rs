@@ -1,6 +1,9 @@
 use super::ParsedAlbum;
-use crate::db::{DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack};
-use crate::discogs::DiscogsRelease;
+use crate::db::{
+    is_various_artists, DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack, DbTrackArtist,
+    VARIOUS_ARTISTS,
+};
+use crate::discogs::{DiscogsArtist, DiscogsRelease};
 use uuid::Uuid;
 /// Parse Discogs release metadata into database models including artist information.
 ///
@@ -10,7 +13,7 @@ use uuid::Uuid;
 ///
 /// master_year is always provided and used for the album year (not the release year).
 ///
-/// Returns: (album, release, tracks, artists, album_artists)
+/// Returns: (album, release, tracks, artists, album_artists, track_artists)
 pub fn parse_discogs_release(
     release: &DiscogsRelease,
     master_year: u32,
@@ -37,10 +40,17 @@ pub fn parse_discogs_release(
         album_artists.push(album_artist);
     } else {
         for (position, discogs_artist) in release.artists.iter().enumerate() {
+            // Discogs credits compilations to "Various"; naming it like the
+            // other sources do files them all under one artist
+            let name = if is_various_artists(&discogs_artist.name) {
+                VARIOUS_ARTISTS.to_string()
+            } else {
+                discogs_artist.name.clone()
+            };
             let artist = DbArtist {
                 id: Uuid::new_v4().to_string(),
-                name: discogs_artist.name.clone(),
-                sort_name: Some(discogs_artist.name.clone()),
+                name: name.clone(),
+                sort_name: Some(name),
                 discogs_artist_id: Some(discogs_artist.id.clone()),
                 bandcamp_artist_id: None,
                 musicbrainz_artist_id: None,
@@ -48,18 +58,70 @@ pub fn parse_discogs_release(
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
             };
-            let album_artist = DbAlbumArtist::new(&album.id, &artist.id, position as i32);
+            let album_artist = DbAlbumArtist::new(&album.id, &artist.id, position as i32)
+                .with_join_phrase(discogs_artist.join.clone());
             artists.push(artist);
             album_artists.push(album_artist);
         }
     }
     let mut tracks = Vec::new();
+    let mut track_artists = Vec::new();
     for (index, discogs_track) in release.tracklist.iter().enumerate() {
         let disc_number = parse_disc_number_from_position(&discogs_track.position);
         let track = DbTrack::from_discogs_track(discogs_track, &db_release.id, index, disc_number)?;
+        // Discogs only lists a track's artists when they differ from the release's
+        if discogs_track.artists != release.artists {
+            for (position, discogs_artist) in discogs_track.artists.iter().enumerate() {
+                let artist_id = discogs_artist_id(discogs_artist, &mut artists);
+                track_artists.push(
+                    DbTrackArtist::new(&track.id, &artist_id, position as i32, None)
+                        .with_join_phrase(discogs_artist.join.clone()),
+                );
+            }
+        }
         tracks.push(track);
     }
-    Ok((album, db_release, tracks, artists, album_artists))
+    Ok((
+        album,
+        db_release,
+        tracks,
+        artists,
+        album_artists,
+        track_artists,
+    ))
+}
+/// ID of the parsed artist for a Discogs credit, adding it to `artists` if
+/// this release hasn't credited it yet
+fn discogs_artist_id(discogs_artist: &DiscogsArtist, artists: &mut Vec<DbArtist>) -> String {
+    if let Some(existing) = artists
+        .iter()
+        .find(|a| a.discogs_artist_id.as_deref() == Some(discogs_artist.id.as_str()))
+    {
+        return existing.id.clone();
+    }
+    let artist = DbArtist {
+        id: Uuid::new_v4().to_string(),
+        name: discogs_artist.name.clone(),
+        sort_name: Some(discogs_artist.name.clone()),
+        discogs_artist_id: Some(discogs_artist.id.clone()),
+        bandcamp_artist_id: None,
+        musicbrainz_artist_id: None,
+
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    };
+    let id = artist.id.clone();
+    artists.push(artist);
+    id
+}
+/// Genres for an album imported from Discogs: the broad genres, then the styles.
+pub fn discogs_genres(release: &DiscogsRelease) -> Vec<String> {
+    release
+        .genre
+        .iter()
+        .chain(&release.style)
+        .cloned()
+        .collect()
 }
 /// Parse disc number from Discogs position format.
 ///
@@ -0,0 +1,43 @@
+//! Import error type
+use crate::error_code::{ErrorCode, HasErrorCode};
+use thiserror::Error;
+
+/// Why an import couldn't be queued or didn't complete
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ImportError {
+    #[error("Either discogs_release or mb_release must be provided")]
+    NoRelease,
+    /// Fetching or parsing release metadata failed
+    #[error("{0}")]
+    Metadata(String),
+    #[error("Failed to download cover art: {0}")]
+    CoverArt(String),
+    /// Source files are missing, unreadable, or don't match the tracklist
+    #[error("{0}")]
+    Files(String),
+    /// Downloading the torrent or ripping the CD failed
+    #[error("{0}")]
+    Acquire(String),
+    /// Writing files to managed storage failed
+    #[error("{0}")]
+    Storage(String),
+    #[error("{0}")]
+    Database(String),
+    #[error("Import service is not running")]
+    ServiceStopped,
+}
+
+impl HasErrorCode for ImportError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ImportError::NoRelease => ErrorCode::ImportNoRelease,
+            ImportError::Metadata(_) => ErrorCode::ImportMetadataUnavailable,
+            ImportError::CoverArt(_) => ErrorCode::ImportCoverArtUnavailable,
+            ImportError::Files(_) => ErrorCode::ImportInvalidFiles,
+            ImportError::Acquire(_) => ErrorCode::ImportAcquireFailed,
+            ImportError::Storage(_) => ErrorCode::StorageUnavailable,
+            ImportError::Database(_) => ErrorCode::Database,
+            ImportError::ServiceStopped => ErrorCode::ImportServiceStopped,
+        }
+    }
+}
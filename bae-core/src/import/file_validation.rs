@@ -95,6 +95,30 @@ pub fn is_valid_flac(path: &Path) -> io::Result<bool> {
     Ok(true)
 }
 
+/// Read a FLAC file's duration from its STREAMINFO block.
+///
+/// Returns `Ok(None)` if the header is missing or the length is unknown.
+pub fn flac_duration_ms(path: &Path) -> io::Result<Option<u64>> {
+    let mut header = [0u8; 42];
+    let bytes_read = fs::File::open(path)?.read(&mut header)?;
+    if bytes_read < 42 || &header[0..4] != b"fLaC" || header[4] & 0x7F != 0 {
+        return Ok(None);
+    }
+
+    let si = &header[8..42];
+    let sample_rate = ((si[10] as u64) << 12) | ((si[11] as u64) << 4) | ((si[12] as u64) >> 4);
+    let total_samples = (((si[13] & 0x0F) as u64) << 32)
+        | ((si[14] as u64) << 24)
+        | ((si[15] as u64) << 16)
+        | ((si[16] as u64) << 8)
+        | (si[17] as u64);
+
+    if total_samples == 0 || sample_rate == 0 {
+        return Ok(None);
+    }
+    Ok(Some(total_samples * 1000 / sample_rate))
+}
+
 /// Check if an image file has valid magic bytes for its extension.
 ///
 /// Unknown extensions are assumed valid (don't block on formats we don't recognize).
@@ -260,6 +284,20 @@ mod tests {
         assert!(is_valid_flac(file.path()).unwrap());
     }
 
+    #[test]
+    fn test_flac_duration_ms() {
+        let data = make_flac_header(44100, 2, 16, 44100 * 12);
+        let file = write_temp_file("flac", &data);
+        assert_eq!(flac_duration_ms(file.path()).unwrap(), Some(12_000));
+    }
+
+    #[test]
+    fn test_flac_duration_unknown_length() {
+        let data = make_flac_header(44100, 2, 16, 0);
+        let file = write_temp_file("flac", &data);
+        assert_eq!(flac_duration_ms(file.path()).unwrap(), None);
+    }
+
     #[test]
     fn test_valid_jpeg_magic() {
         let data = [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10];
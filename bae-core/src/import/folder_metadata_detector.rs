@@ -1,4 +1,6 @@
 use crate::cue_flac::CueFlacProcessor;
+use crate::import::folder_scanner::ImportFilters;
+use crate::import::tag_parser;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 use tracing::{debug, info, warn};
@@ -131,9 +133,19 @@ fn find_matching_flac_for_cue<'a>(
     }
     None
 }
-/// Read FLAC metadata - currently returns empty, relies on CUE files
-fn read_flac_metadata(_path: &Path) -> (Option<String>, Option<String>, Option<u32>) {
-    (None, None, None)
+/// Read artist, album and year from a FLAC file's Vorbis comments
+fn read_flac_metadata(path: &Path) -> (Option<String>, Option<String>, Option<u32>) {
+    match tag_parser::read_flac_tags(path) {
+        Ok(tags) => (
+            tags.album_artist.or(tags.artist),
+            tags.album,
+            tags.year.and_then(|y| u32::try_from(y).ok()),
+        ),
+        Err(e) => {
+            debug!("No FLAC tags: {}", e);
+            (None, None, None)
+        }
+    }
 }
 /// Get FLAC file duration in seconds using libFLAC
 fn get_flac_duration_seconds(flac_path: &Path) -> Result<f64, MetadataDetectionError> {
@@ -583,12 +595,16 @@ fn parse_folder_name(folder_path: &Path) -> (Option<String>, Option<String>, Vec
 /// Detect folder contents and metadata from a folder containing audio files
 pub fn detect_folder_contents(
     folder_path: PathBuf,
+    filters: &ImportFilters,
 ) -> Result<FolderContents, MetadataDetectionError> {
-    let metadata = detect_metadata(folder_path)?;
+    let metadata = detect_metadata(folder_path, filters)?;
     Ok(FolderContents { metadata })
 }
 /// Detect metadata from a folder containing audio files
-pub fn detect_metadata(folder_path: PathBuf) -> Result<FolderMetadata, MetadataDetectionError> {
+pub fn detect_metadata(
+    folder_path: PathBuf,
+    filters: &ImportFilters,
+) -> Result<FolderMetadata, MetadataDetectionError> {
     use tracing::info;
     info!(
         "📁 Starting metadata detection for folder: {:?}",
@@ -601,7 +617,7 @@ pub fn detect_metadata(folder_path: PathBuf) -> Result<FolderMetadata, MetadataD
     let mut mb_discid: Option<String> = None;
     let mut track_count: Option<u32> = None;
     use crate::import::folder_scanner;
-    let categorized = folder_scanner::collect_release_files(&folder_path)
+    let categorized = folder_scanner::collect_release_files(&folder_path, filters)
         .map_err(|e| MetadataDetectionError::Io(std::io::Error::other(e)))?;
     let (audio_files, cue_files): (Vec<PathBuf>, Vec<PathBuf>) = match &categorized.audio {
         folder_scanner::AudioContent::CueFlacPairs(pairs) => {
@@ -7,12 +7,17 @@
 use super::file_validation;
 use crate::cue_flac::CueFlacProcessor;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::{debug, info, warn};
 const MAX_RECURSION_DEPTH: usize = 10;
 const AUDIO_EXTENSIONS: &[&str] = &["flac"];
 const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "gif", "bmp"];
 const DOCUMENT_EXTENSIONS: &[&str] = &["cue", "log", "txt", "nfo", "m3u", "m3u8"];
+/// Attempts for a directory listing that fails with a transient error
+const READ_DIR_ATTEMPTS: u32 = 3;
+const READ_DIR_RETRY_DELAY: Duration = Duration::from_millis(500);
 /// A file discovered during folder scanning
 #[derive(Debug, Clone)]
 pub struct ScannedFile {
@@ -63,6 +68,65 @@ pub struct CategorizedFiles {
     /// Not included in `artwork`. Any bad file — audio or image — blocks import.
     pub bad_image_count: usize,
 }
+/// User-configured rules for leaving files out of a scan.
+///
+/// Ignored files never appear in `CategorizedFiles`, so they are neither shown
+/// in the import views nor imported.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportFilters {
+    /// Patterns matched against file and folder names, case-insensitively.
+    /// `*` matches any run of characters and `?` matches one character.
+    pub ignore_patterns: Vec<String>,
+    /// Audio files shorter than this are skipped (samples, previews)
+    pub min_audio_duration_secs: Option<u32>,
+}
+impl ImportFilters {
+    /// Check if a file or folder name matches any ignore pattern
+    pub fn is_ignored_name(&self, name: &str) -> bool {
+        self.ignore_patterns
+            .iter()
+            .any(|pattern| glob_matches(pattern.trim(), name))
+    }
+    /// Check if an audio file is shorter than the configured minimum.
+    /// Files whose length can't be read are kept.
+    fn is_too_short(&self, path: &Path) -> bool {
+        let Some(min_secs) = self.min_audio_duration_secs else {
+            return false;
+        };
+        match file_validation::flac_duration_ms(path) {
+            Ok(Some(duration_ms)) => duration_ms < min_secs as u64 * 1000,
+            _ => false,
+        }
+    }
+}
+/// Case-insensitive wildcard match supporting `*` and `?`
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    if pattern.is_empty() {
+        return false;
+    }
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name index it was tried at, for backtracking
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
 /// A detected candidate (leaf directory) in a collection.
 /// Called "candidate" because it hasn't been identified yet.
 #[derive(Debug, Clone)]
@@ -109,6 +173,43 @@ fn is_noise_file(path: &Path) -> bool {
         .map(|name| name == ".DS_Store" || name == "Thumbs.db" || name == "desktop.ini")
         .unwrap_or(false)
 }
+/// List a directory, retrying errors that network shares raise under load or
+/// while reconnecting.
+fn read_dir(dir: &Path) -> Result<fs::ReadDir, String> {
+    let mut attempt = 1;
+    loop {
+        match fs::read_dir(dir) {
+            Ok(entries) => return Ok(entries),
+            Err(e) if attempt < READ_DIR_ATTEMPTS && is_transient(&e) => {
+                warn!(
+                    "Reading {:?} failed (attempt {}/{}): {}",
+                    dir, attempt, READ_DIR_ATTEMPTS, e
+                );
+                std::thread::sleep(READ_DIR_RETRY_DELAY * attempt);
+                attempt += 1;
+            }
+            Err(e) => return Err(format!("Failed to read dir {:?}: {}", dir, e)),
+        }
+    }
+}
+
+fn is_transient(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::TimedOut
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::ResourceBusy
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::HostUnreachable
+            | io::ErrorKind::NetworkDown
+            | io::ErrorKind::NetworkUnreachable
+            | io::ErrorKind::StaleNetworkFileHandle
+    )
+}
+
 /// Check if a directory contains audio files directly (by extension).
 ///
 /// This is used for tree-structure detection (leaf vs collection), not for
@@ -116,7 +217,7 @@ fn is_noise_file(path: &Path) -> bool {
 /// as a candidate — the incompleteness is reported at the candidate level.
 /// Only skips 0-byte files since those are empty placeholders.
 fn has_audio_files(dir: &Path) -> Result<bool, String> {
-    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read dir {:?}: {}", dir, e))?;
+    let entries = read_dir(dir)?;
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_file() && is_audio_file(&path) {
@@ -131,7 +232,7 @@ fn has_audio_files(dir: &Path) -> Result<bool, String> {
 }
 /// Check if any subdirectory contains audio files
 fn has_subdirs_with_audio(dir: &Path) -> Result<bool, String> {
-    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read dir {:?}: {}", dir, e))?;
+    let entries = read_dir(dir)?;
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_dir() && has_audio_files(&path)? {
@@ -142,7 +243,7 @@ fn has_subdirs_with_audio(dir: &Path) -> Result<bool, String> {
 }
 /// Check if any subdirectory has its own subdirectories with audio files
 fn has_nested_audio_dirs(dir: &Path) -> Result<bool, String> {
-    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read dir {:?}: {}", dir, e))?;
+    let entries = read_dir(dir)?;
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_dir() && has_subdirs_with_audio(&path)? {
@@ -183,7 +284,7 @@ const MAX_DISC_FOLDER_NAME_LENGTH: usize = 15;
 /// Check if all audio-containing subdirectories look like disc folders.
 /// Uses a heuristic: disc folders are SHORT and share a common prefix.
 fn subdirs_are_disc_folders(dir: &Path) -> Result<bool, String> {
-    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read dir {:?}: {}", dir, e))?;
+    let entries = read_dir(dir)?;
     let mut subdir_names: Vec<String> = Vec::new();
 
     for entry in entries.flatten() {
@@ -249,6 +350,7 @@ fn is_leaf_directory(dir: &Path) -> Result<bool, String> {
 fn scan_recursive_with_callback<F>(
     dir: &Path,
     depth: usize,
+    filters: &ImportFilters,
     on_candidate: &mut F,
 ) -> Result<(), String>
 where
@@ -268,7 +370,7 @@ where
             .unwrap_or("Unknown")
             .to_string();
         info!("Found candidate leaf: {:?}", dir);
-        let files = collect_release_files(dir)?;
+        let files = collect_release_files(dir, filters)?;
         on_candidate(DetectedCandidate {
             path: dir.to_path_buf(),
             name,
@@ -276,11 +378,15 @@ where
         });
         return Ok(());
     }
-    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read dir {:?}: {}", dir, e))?;
+    let entries = read_dir(dir)?;
     for entry in entries.flatten() {
         let path = entry.path();
-        if path.is_dir() {
-            scan_recursive_with_callback(&path, depth + 1, on_candidate)?;
+        let ignored = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| filters.is_ignored_name(name));
+        if path.is_dir() && !ignored {
+            scan_recursive_with_callback(&path, depth + 1, filters, on_candidate)?;
         }
     }
     Ok(())
@@ -288,21 +394,25 @@ where
 /// Scan a folder for candidates and invoke callback as each is found.
 pub fn scan_for_candidates_with_callback<F>(
     root: PathBuf,
+    filters: &ImportFilters,
     mut on_candidate: F,
 ) -> Result<(), String>
 where
     F: FnMut(DetectedCandidate),
 {
     info!("Scanning for candidates in: {:?}", root);
-    scan_recursive_with_callback(&root, 0, &mut on_candidate)?;
+    scan_recursive_with_callback(&root, 0, filters, &mut on_candidate)?;
     Ok(())
 }
 /// Collect all files from a release directory and categorize them
 ///
 /// This collects files recursively within a single release, preserving relative paths,
 /// and categorizes them into audio (CUE/FLAC pairs or track files), artwork, and documents.
-/// Unrecognized file types are ignored.
-pub fn collect_release_files(release_root: &Path) -> Result<CategorizedFiles, String> {
+/// Unrecognized file types and anything matching `filters` are ignored.
+pub fn collect_release_files(
+    release_root: &Path,
+    filters: &ImportFilters,
+) -> Result<CategorizedFiles, String> {
     let mut all_audio: Vec<ScannedFile> = Vec::new();
     let mut all_cue: Vec<ScannedFile> = Vec::new();
     let mut artwork: Vec<ScannedFile> = Vec::new();
@@ -312,6 +422,7 @@ pub fn collect_release_files(release_root: &Path) -> Result<CategorizedFiles, St
     collect_files_into_vectors(
         release_root,
         release_root,
+        filters,
         &mut all_audio,
         &mut all_cue,
         &mut artwork,
@@ -387,6 +498,7 @@ pub fn collect_release_files(release_root: &Path) -> Result<CategorizedFiles, St
 fn collect_files_into_vectors(
     current_dir: &Path,
     release_root: &Path,
+    filters: &ImportFilters,
     audio: &mut Vec<ScannedFile>,
     cue: &mut Vec<ScannedFile>,
     artwork: &mut Vec<ScannedFile>,
@@ -394,14 +506,13 @@ fn collect_files_into_vectors(
     bad_audio_count: &mut usize,
     bad_image_count: &mut usize,
 ) -> Result<(), String> {
-    let entries = fs::read_dir(current_dir)
-        .map_err(|e| format!("Failed to read dir {:?}: {}", current_dir, e))?;
+    let entries = read_dir(current_dir)?;
     for entry in entries.flatten() {
         let path = entry.path();
 
-        // Skip hidden files and directories (including .bae/)
+        // Skip hidden files and directories (including .bae/) and user-ignored names
         if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            if name.starts_with('.') {
+            if name.starts_with('.') || filters.is_ignored_name(name) {
                 continue;
             }
         }
@@ -421,6 +532,10 @@ fn collect_files_into_vectors(
                     *bad_audio_count += 1;
                     continue;
                 }
+                if filters.is_too_short(&path) {
+                    debug!("Skipping short audio file {:?}", path);
+                    continue;
+                }
             } else if is_image_file(&path)
                 && (size == 0 || !file_validation::is_valid_image(&path).unwrap_or(false))
             {
@@ -452,6 +567,7 @@ fn collect_files_into_vectors(
             collect_files_into_vectors(
                 &path,
                 release_root,
+                filters,
                 audio,
                 cue,
                 artwork,
@@ -479,6 +595,71 @@ mod tests {
         buf
     }
 
+    /// FLAC header declaring `secs` of 8kHz mono 8-bit audio, padded so it passes
+    /// the truncation check
+    fn flac_with_duration(secs: u32) -> Vec<u8> {
+        let total_samples = 8000 * secs;
+        let mut buf = vec![b'f', b'L', b'a', b'C', 0x00, 0x00, 0x00, 34];
+        let mut streaminfo = [0u8; 34];
+        // sample rate 8000 (20 bits), 1 channel, 8 bits per sample
+        streaminfo[10] = 0x01;
+        streaminfo[11] = 0xF4;
+        streaminfo[12] = 0x00;
+        streaminfo[13] = 0x70;
+        streaminfo[14..18].copy_from_slice(&total_samples.to_be_bytes());
+        buf.extend_from_slice(&streaminfo);
+        buf.resize(total_samples as usize / 5, 0);
+        buf
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*.m3u", "Playlist.M3U"));
+        assert!(glob_matches("cover.bak", "cover.bak"));
+        assert!(glob_matches("@eaDir", "@eadir"));
+        assert!(glob_matches("track??.flac", "track01.flac"));
+        assert!(glob_matches("*sample*", "01 - sample clip.flac"));
+        assert!(!glob_matches("*.m3u", "playlist.m3u8"));
+        assert!(!glob_matches("track?.flac", "track01.flac"));
+        assert!(!glob_matches("", "anything"));
+    }
+
+    #[test]
+    fn test_collect_release_files_applies_filters() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+
+        std::fs::write(root.join("01 Track.flac"), flac_with_duration(180)).unwrap();
+        std::fs::write(root.join("02 Sample.flac"), flac_with_duration(10)).unwrap();
+        std::fs::write(root.join("album.m3u"), b"01 Track.flac").unwrap();
+        std::fs::write(root.join("notes.txt"), b"notes").unwrap();
+
+        let thumbs = root.join("@eaDir");
+        std::fs::create_dir(&thumbs).unwrap();
+        std::fs::write(thumbs.join("cover.jpg"), [0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
+
+        let filters = ImportFilters {
+            ignore_patterns: vec!["*.m3u".to_string(), "@eaDir".to_string()],
+            min_audio_duration_secs: Some(30),
+        };
+        let files = collect_release_files(root, &filters).unwrap();
+
+        let AudioContent::TrackFiles(tracks) = &files.audio else {
+            panic!("Expected track files");
+        };
+        let track_paths: Vec<_> = tracks.iter().map(|f| f.relative_path.as_str()).collect();
+        assert_eq!(track_paths, vec!["01 Track.flac"]);
+        assert_eq!(files.bad_audio_count, 0);
+
+        let document_paths: Vec<_> = files
+            .documents
+            .iter()
+            .map(|f| f.relative_path.as_str())
+            .collect();
+        assert_eq!(document_paths, vec!["notes.txt"]);
+        assert!(files.artwork.is_empty());
+    }
+
     #[test]
     fn test_is_audio_file() {
         assert!(is_audio_file(Path::new("track.flac")));
@@ -512,7 +693,7 @@ mod tests {
         std::fs::write(bae_dir.join("cover-mb.jpg"), [0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
         std::fs::write(bae_dir.join("cover-discogs.jpeg"), [0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
 
-        let files = collect_release_files(root).unwrap();
+        let files = collect_release_files(root, &ImportFilters::default()).unwrap();
 
         let audio_paths: Vec<_> = match &files.audio {
             AudioContent::TrackFiles(tracks) => {
@@ -595,7 +776,8 @@ FILE "{flac_filename}" WAVE
         }
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         // We expect 3 separate candidates, one per album
         assert_eq!(
@@ -661,7 +843,8 @@ FILE "{flac_filename}" WAVE
         }
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         // We expect 1 candidate (the multi-disc album as a whole)
         assert_eq!(
@@ -699,7 +882,8 @@ FILE "{flac_filename}" WAVE
         }
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(
             candidates.len(),
@@ -723,7 +907,8 @@ FILE "{flac_filename}" WAVE
         }
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(
             candidates.len(),
@@ -799,7 +984,8 @@ FILE "album.ape" WAVE
         std::fs::write(root.join("cover.jpg"), [0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(
             candidates.len(),
@@ -815,7 +1001,8 @@ FILE "album.ape" WAVE
         std::fs::create_dir(&root).unwrap();
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(candidates.len(), 0, "Empty folder should not be detected");
     }
@@ -834,7 +1021,8 @@ FILE "album.ape" WAVE
         .unwrap();
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(
             candidates.len(),
@@ -856,7 +1044,8 @@ FILE "album.ape" WAVE
         std::fs::write(video_ts.join("VTS_01_1.VOB"), b"fake video").unwrap();
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(
             candidates.len(),
@@ -882,7 +1071,8 @@ FILE "album.ape" WAVE
         }
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(
             candidates.len(),
@@ -905,7 +1095,8 @@ FILE "album.ape" WAVE
         std::fs::write(root.join("cover.jpg"), [0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(
             candidates.len(),
@@ -926,7 +1117,10 @@ FILE "album.ape" WAVE
         std::fs::write(root.join("03 - Track Three.flac"), b"").unwrap();
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root.clone(), |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root.clone(), &ImportFilters::default(), |c| {
+            candidates.push(c)
+        })
+        .unwrap();
 
         assert_eq!(
             candidates.len(),
@@ -965,7 +1159,8 @@ FILE "album.ape" WAVE
         std::fs::write(root.join("inlay.png"), b"").unwrap();
 
         let mut candidates = Vec::new();
-        scan_for_candidates_with_callback(root, |c| candidates.push(c)).unwrap();
+        scan_for_candidates_with_callback(root, &ImportFilters::default(), |c| candidates.push(c))
+            .unwrap();
 
         assert_eq!(candidates.len(), 1);
         assert_eq!(
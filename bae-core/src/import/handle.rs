@@ -1,13 +1,15 @@
-use super::ParsedAlbum;
+use super::{ImportError, ParsedAlbum};
 use crate::cue_flac::CueFlacProcessor;
 #[cfg(feature = "torrent")]
 use crate::db::DbTorrent;
-use crate::db::{Database, DbImport, ImportOperationStatus};
+use crate::db::{Database, DbImport, DbImportFile, ImportOperationStatus, LibraryImageType};
 use crate::discogs::{DiscogsClient, DiscogsRelease};
+use crate::import::bandcamp::{self, BandcampAlbum};
 use crate::import::discogs_parser;
-use crate::import::folder_scanner::DetectedCandidate;
+use crate::import::folder_scanner::{DetectedCandidate, ImportFilters};
 use crate::import::musicbrainz_parser;
 use crate::import::progress::ImportProgressHandle;
+use crate::import::tag_parser;
 use crate::import::track_to_file_mapper::map_tracks_to_files;
 #[cfg(feature = "torrent")]
 use crate::import::types::TorrentSource;
@@ -21,7 +23,7 @@ use crate::library_dir::LibraryDir;
 use crate::musicbrainz::MbRelease;
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, info, warn};
 /// Handle for sending import requests and subscribing to progress updates
@@ -37,6 +39,8 @@ pub struct ImportServiceHandle {
     pub scan_events_tx: broadcast::Sender<ScanEvent>,
     pub key_service: KeyService,
     pub library_dir: LibraryDir,
+    /// Ignore rules applied to folder scans and imports, updated when settings change
+    import_filters: Arc<RwLock<ImportFilters>>,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +52,7 @@ pub enum ScanEvent {
 
 pub struct ScanRequest {
     pub path: std::path::PathBuf,
+    pub filters: ImportFilters,
 }
 
 /// Try to create a DiscogsClient using the KeyService.
@@ -101,13 +106,27 @@ impl ImportServiceHandle {
             scan_events_tx,
             key_service,
             library_dir,
+            import_filters: Arc::new(RwLock::new(ImportFilters::default())),
         }
     }
 
-    pub fn enqueue_folder_scan(&self, path: std::path::PathBuf) -> Result<(), String> {
+    /// Current ignore rules for folder scans and imports
+    pub fn import_filters(&self) -> ImportFilters {
+        self.import_filters.read().unwrap().clone()
+    }
+
+    /// Replace the ignore rules used by subsequent scans and imports
+    pub fn set_import_filters(&self, filters: ImportFilters) {
+        *self.import_filters.write().unwrap() = filters;
+    }
+
+    pub fn enqueue_folder_scan(&self, path: std::path::PathBuf) -> Result<(), ImportError> {
         self.scan_tx
-            .send(ScanRequest { path })
-            .map_err(|_| "Failed to enqueue folder scan".to_string())
+            .send(ScanRequest {
+                path,
+                filters: self.import_filters(),
+            })
+            .map_err(|_| ImportError::ServiceStopped)
     }
 
     pub fn subscribe_folder_scan_events(&self) -> broadcast::Receiver<ScanEvent> {
@@ -121,7 +140,10 @@ impl ImportServiceHandle {
     /// request is sent to the import worker.
     ///
     /// Returns (album_id, release_id) for navigation and progress subscription.
-    pub async fn send_request(&self, request: ImportRequest) -> Result<(String, String), String> {
+    pub async fn send_request(
+        &self,
+        request: ImportRequest,
+    ) -> Result<(String, String), ImportError> {
         match request {
             ImportRequest::Folder {
                 import_id,
@@ -130,6 +152,7 @@ impl ImportServiceHandle {
                 folder,
                 master_year,
                 managed,
+                move_to_cloud,
                 selected_cover,
             } => {
                 self.send_folder_request(
@@ -139,10 +162,31 @@ impl ImportServiceHandle {
                     folder,
                     master_year,
                     managed,
+                    move_to_cloud,
                     selected_cover,
                 )
                 .await
             }
+            ImportRequest::Bandcamp {
+                import_id,
+                album,
+                folder,
+                managed,
+                move_to_cloud,
+            } => {
+                self.send_bandcamp_request(import_id, album, folder, managed, move_to_cloud)
+                    .await
+            }
+            ImportRequest::Tags {
+                import_id,
+                folder,
+                managed,
+                move_to_cloud,
+                selected_cover,
+            } => {
+                self.send_tags_request(import_id, folder, managed, move_to_cloud, selected_cover)
+                    .await
+            }
             #[cfg(feature = "torrent")]
             ImportRequest::Torrent {
                 torrent_source,
@@ -195,12 +239,12 @@ impl ImportServiceHandle {
         folder: std::path::PathBuf,
         master_year: u32,
         managed: bool,
+        move_to_cloud: bool,
         selected_cover: Option<CoverSelection>,
-    ) -> Result<(String, String), String> {
+    ) -> Result<(String, String), ImportError> {
         if discogs_release.is_none() && mb_release.is_none() {
-            return Err("Either discogs_release or mb_release must be provided".to_string());
+            return Err(ImportError::NoRelease);
         }
-        let library_manager = self.library_manager.get();
         let (album_title, artist_name) = if let Some(ref discogs_rel) = discogs_release {
             let artist = discogs_rel
                 .artists
@@ -212,44 +256,180 @@ impl ImportServiceHandle {
         } else if let Some(ref mb_rel) = mb_release {
             (mb_rel.title.clone(), mb_rel.artist.clone())
         } else {
-            return Err("No release provided".to_string());
+            return Err(ImportError::NoRelease);
         };
 
-        let emit_preparing = {
-            let import_id = import_id.clone();
-            let album_title = album_title.clone();
-            let artist_name = artist_name.clone();
-            let progress_tx = self.progress_tx.clone();
-            move |step: PrepareStep| {
-                let _ = progress_tx.send(ImportProgress::Preparing {
-                    import_id: import_id.clone(),
-                    step,
-                    album_title: album_title.clone(),
-                    artist_name: artist_name.clone(),
-                });
-            }
-        };
+        let emit_preparing = self.preparing_emitter(&import_id, &album_title, &artist_name);
         emit_preparing(PrepareStep::ParsingMetadata);
-        let (db_album, db_release, db_tracks, artists, album_artists) = self
+        let parsed = self
             .resolve_metadata(discogs_release.as_ref(), mb_release.as_ref(), master_year)
             .await?;
+        let genres = discogs_release
+            .as_ref()
+            .map(discogs_parser::discogs_genres)
+            .unwrap_or_default();
+
+        self.queue_folder_import(
+            import_id,
+            parsed,
+            genres,
+            &album_title,
+            &artist_name,
+            folder,
+            managed,
+            move_to_cloud,
+            selected_cover,
+            emit_preparing,
+        )
+        .await
+    }
+
+    /// Validate a downloaded Bandcamp purchase and queue it like a folder import.
+    ///
+    /// Metadata comes from the Bandcamp collection; the year is read from the
+    /// downloaded files' tags since the collection API doesn't include it.
+    async fn send_bandcamp_request(
+        &self,
+        import_id: String,
+        album: BandcampAlbum,
+        folder: std::path::PathBuf,
+        managed: bool,
+        move_to_cloud: bool,
+    ) -> Result<(String, String), ImportError> {
+        let emit_preparing = self.preparing_emitter(&import_id, &album.title, &album.artist);
+        emit_preparing(PrepareStep::ParsingMetadata);
+        let year = crate::import::detect_metadata(folder.clone(), &self.import_filters())
+            .ok()
+            .and_then(|m| m.year);
+        let parsed = bandcamp::parse_bandcamp_album(&album, year);
+        let selected_cover = album.art_url().map(CoverSelection::Remote);
+
+        self.queue_folder_import(
+            import_id,
+            parsed,
+            Vec::new(),
+            &album.title,
+            &album.artist,
+            folder,
+            managed,
+            move_to_cloud,
+            selected_cover,
+            emit_preparing,
+        )
+        .await
+    }
+
+    /// Validate a folder using its embedded tags as the release metadata and
+    /// queue it like a folder import.
+    ///
+    /// Only one-file-per-track FLAC folders are supported, since a single-file
+    /// rip has no per-track tags.
+    async fn send_tags_request(
+        &self,
+        import_id: String,
+        folder: std::path::PathBuf,
+        managed: bool,
+        move_to_cloud: bool,
+        selected_cover: Option<CoverSelection>,
+    ) -> Result<(String, String), ImportError> {
+        let files =
+            discover_folder_files(&folder, &self.import_filters()).map_err(ImportError::Files)?;
+        let has_extension = |file: &DiscoveredFile, ext: &str| {
+            file.path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+        };
+        if files.iter().any(|f| has_extension(f, "cue")) {
+            return Err(ImportError::Files(
+                "Importing from tags needs one file per track, not a CUE sheet".to_string(),
+            ));
+        }
+        let audio_files: Vec<std::path::PathBuf> = files
+            .iter()
+            .filter(|f| has_extension(f, "flac"))
+            .map(|f| f.path.clone())
+            .collect();
+        let (parsed, genres) =
+            tag_parser::parse_tagged_files(&audio_files).map_err(ImportError::Metadata)?;
+
+        let album_title = parsed.0.title.clone();
+        let artist_name = parsed.3[0].name.clone();
+        let emit_preparing = self.preparing_emitter(&import_id, &album_title, &artist_name);
+
+        self.queue_folder_import(
+            import_id,
+            parsed,
+            genres,
+            &album_title,
+            &artist_name,
+            folder,
+            managed,
+            move_to_cloud,
+            selected_cover,
+            emit_preparing,
+        )
+        .await
+    }
+
+    /// Build a callback that emits `ImportProgress::Preparing` for one import
+    fn preparing_emitter(
+        &self,
+        import_id: &str,
+        album_title: &str,
+        artist_name: &str,
+    ) -> impl Fn(PrepareStep) {
+        let import_id = import_id.to_string();
+        let album_title = album_title.to_string();
+        let artist_name = artist_name.to_string();
+        let progress_tx = self.progress_tx.clone();
+        move |step: PrepareStep| {
+            let _ = progress_tx.send(ImportProgress::Preparing {
+                import_id: import_id.clone(),
+                step,
+                album_title: album_title.clone(),
+                artist_name: artist_name.clone(),
+            });
+        }
+    }
+
+    /// Shared tail of folder-based imports: map files to tracks, save to the
+    /// database, and hand the validated album to the import worker.
+    async fn queue_folder_import(
+        &self,
+        import_id: String,
+        parsed: ParsedAlbum,
+        genres: Vec<String>,
+        album_title: &str,
+        artist_name: &str,
+        folder: std::path::PathBuf,
+        managed: bool,
+        move_to_cloud: bool,
+        selected_cover: Option<CoverSelection>,
+        emit_preparing: impl Fn(PrepareStep),
+    ) -> Result<(String, String), ImportError> {
+        let library_manager = self.library_manager.get();
+        let (db_album, db_release, db_tracks, artists, album_artists, track_artists) = parsed;
 
         // Download remote cover art bytes early (fail fast on network errors)
         let remote_cover_data = if let Some(CoverSelection::Remote(ref url)) = selected_cover {
             emit_preparing(PrepareStep::DownloadingCoverArt);
             let data = crate::import::cover_art::download_cover_art_bytes(url)
                 .await
-                .map_err(|e| format!("Failed to download cover art: {}", e))?;
+                .map_err(|e| ImportError::CoverArt(e.to_string()))?;
             Some((data, url.clone()))
         } else {
             None
         };
 
         emit_preparing(PrepareStep::DiscoveringFiles);
-        let discovered_files = discover_folder_files(&folder)?;
+        let discovered_files =
+            discover_folder_files(&folder, &self.import_filters()).map_err(ImportError::Files)?;
 
         emit_preparing(PrepareStep::ValidatingTracks);
-        let mapping_result = map_tracks_to_files(&db_tracks, &discovered_files).await?;
+        let mapping_result = map_tracks_to_files(&db_tracks, &discovered_files)
+            .await
+            .map_err(ImportError::Files)?;
         let tracks_to_files = mapping_result.track_files.clone();
         let cue_flac_metadata = mapping_result.cue_flac_metadata.clone();
 
@@ -267,39 +447,66 @@ impl ImportServiceHandle {
         };
 
         emit_preparing(PrepareStep::SavingToDatabase);
-        let db_import = DbImport::new(
+        let mut db_import = DbImport::new(
             &import_id,
-            &album_title,
-            &artist_name,
+            album_title,
+            artist_name,
             folder.to_str().unwrap_or(""),
         );
+        db_import.managed = managed;
+        db_import.move_to_cloud = managed && move_to_cloud;
+        db_import.cover_image_path = cover_image_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string());
         self.database
             .insert_import(&db_import)
             .await
-            .map_err(|e| format!("Failed to create import record: {}", e))?;
-        let artist_id_map = find_or_create_artists(library_manager, &artists).await?;
+            .map_err(|e| ImportError::Database(format!("Failed to create import record: {}", e)))?;
+        let source_paths: Vec<String> = discovered_files
+            .iter()
+            .map(|f| f.path.to_string_lossy().to_string())
+            .collect();
+        self.database
+            .insert_import_files(&import_id, &source_paths)
+            .await
+            .map_err(|e| ImportError::Database(format!("Failed to record import files: {}", e)))?;
+        let artist_id_map = find_or_create_artists(library_manager, &artists)
+            .await
+            .map_err(ImportError::Database)?;
         library_manager
             .insert_album_with_release_and_tracks(&db_album, &db_release, &db_tracks)
             .await
-            .map_err(|e| format!("Database error: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Database error: {}", e)))?;
+        save_album_genres(library_manager, &db_album.id, &genres).await;
+        insert_track_artists(library_manager, &track_artists, &artist_id_map)
+            .await
+            .map_err(ImportError::Database)?;
+        save_release_works(library_manager, &db_album, &db_release.id).await;
         self.database
             .link_import_to_release(&import_id, &db_release.id)
             .await
-            .map_err(|e| format!("Failed to link import to release: {}", e))?;
-        insert_album_artists(library_manager, &album_artists, &artist_id_map).await?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to link import to release: {}", e))
+            })?;
+        insert_album_artists(library_manager, &album_artists, &artist_id_map)
+            .await
+            .map_err(ImportError::Database)?;
         // Write remote cover and create library_images record
         let remote_cover_set = if let Some(((bytes, content_type), url)) = remote_cover_data {
             let image_path = self.library_dir.image_path(&db_release.id);
             if let Some(parent) = image_path.parent() {
-                std::fs::create_dir_all(parent)
-                    .map_err(|e| format!("Failed to create images directory: {}", e))?;
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    ImportError::Storage(format!("Failed to create images directory: {}", e))
+                })?;
             }
             std::fs::write(&image_path, &bytes)
-                .map_err(|e| format!("Failed to write cover: {}", e))?;
+                .map_err(|e| ImportError::Storage(format!("Failed to write cover: {}", e)))?;
 
             info!("Wrote remote cover art to {}", image_path.display());
             let source = if url.contains("musicbrainz") || url.contains("coverartarchive") {
                 "musicbrainz"
+            } else if url.contains("bcbits.com") {
+                "bandcamp"
             } else {
                 "discogs"
             };
@@ -312,18 +519,23 @@ impl ImportServiceHandle {
                 height: None,
                 source: source.to_string(),
                 source_url: Some(url),
+                blurhash: crate::image_placeholder::blurhash_blocking(bytes.clone()).await,
                 updated_at: chrono::Utc::now(),
                 created_at: chrono::Utc::now(),
             };
             library_manager
                 .upsert_library_image(&library_image)
                 .await
-                .map_err(|e| format!("Failed to upsert library image: {}", e))?;
+                .map_err(|e| {
+                    ImportError::Database(format!("Failed to upsert library image: {}", e))
+                })?;
 
             library_manager
                 .set_album_cover_release(&db_album.id, &db_release.id)
                 .await
-                .map_err(|e| format!("Failed to set album cover release: {}", e))?;
+                .map_err(|e| {
+                    ImportError::Database(format!("Failed to set album cover release: {}", e))
+                })?;
 
             true
         } else {
@@ -343,7 +555,9 @@ impl ImportServiceHandle {
         }
 
         emit_preparing(PrepareStep::ExtractingDurations);
-        extract_and_store_durations(library_manager, &tracks_to_files).await?;
+        extract_and_store_durations(library_manager, &tracks_to_files)
+            .await
+            .map_err(ImportError::Database)?;
 
         tracing::info!(
             "Validated and queued album '{}' (release: {}) with {} tracks",
@@ -354,7 +568,7 @@ impl ImportServiceHandle {
         self.database
             .update_import_status(&import_id, ImportOperationStatus::Importing)
             .await
-            .map_err(|e| format!("Failed to update import status: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to update import status: {}", e)))?;
         let album_id = db_album.id.clone();
         let release_id = db_release.id.clone();
         self.requests_tx
@@ -369,9 +583,195 @@ impl ImportServiceHandle {
                 remote_cover_set,
                 import_id,
             })
-            .map_err(|_| "Failed to queue validated album for import".to_string())?;
+            .map_err(|_| ImportError::ServiceStopped)?;
         Ok((album_id, release_id))
     }
+    /// Pick up folder imports left unfinished by a crash or shutdown.
+    ///
+    /// Imports that were already handed to the import worker are queued
+    /// again; files written to storage before the interruption are skipped.
+    /// Imports that can't be resumed (interrupted while preparing, or whose
+    /// source files are gone) are marked failed and their partial release is
+    /// removed. Returns the number of resumed imports.
+    pub async fn resume_interrupted_imports(&self) -> Result<usize, ImportError> {
+        let imports =
+            self.database.get_active_imports().await.map_err(|e| {
+                ImportError::Database(format!("Failed to load active imports: {}", e))
+            })?;
+        let mut resumed = 0;
+        for import in imports {
+            match self.resume_import(&import).await {
+                Ok(()) => {
+                    info!(
+                        "Resumed interrupted import {} ('{}')",
+                        import.id, import.album_title
+                    );
+
+                    resumed += 1;
+                }
+                Err(e) => {
+                    warn!("Cannot resume import {}: {}", import.id, e);
+
+                    self.abandon_import(&import, &e.to_string()).await;
+                }
+            }
+        }
+        Ok(resumed)
+    }
+
+    /// Rebuild the worker command for an interrupted import from the database
+    /// and the per-file state recorded when it was queued.
+    async fn resume_import(&self, import: &DbImport) -> Result<(), ImportError> {
+        if import.status != ImportOperationStatus::Importing {
+            return Err(ImportError::Database(
+                "Interrupted while preparing; import the folder again".to_string(),
+            ));
+        }
+        let release_id = import
+            .release_id
+            .as_deref()
+            .ok_or_else(|| ImportError::Database("Import has no release".to_string()))?;
+        let import_files = self
+            .database
+            .get_import_files(&import.id)
+            .await
+            .map_err(|e| ImportError::Database(format!("Failed to load import files: {}", e)))?;
+        if import_files.is_empty() {
+            return Err(ImportError::Database(
+                "No file state recorded for this import".to_string(),
+            ));
+        }
+
+        let library_manager = self.library_manager.get();
+        let db_release = self
+            .database
+            .get_release_by_id(release_id)
+            .await
+            .map_err(|e| ImportError::Database(format!("Failed to load release: {}", e)))?
+            .ok_or_else(|| ImportError::Database(format!("Release {} not found", release_id)))?;
+        let db_album = library_manager
+            .get_album_by_id(&db_release.album_id)
+            .await
+            .map_err(|e| ImportError::Database(format!("Failed to load album: {}", e)))?
+            .ok_or_else(|| {
+                ImportError::Database(format!("Album {} not found", db_release.album_id))
+            })?;
+        let db_tracks = self
+            .database
+            .get_tracks_for_release(release_id)
+            .await
+            .map_err(|e| ImportError::Database(format!("Failed to load tracks: {}", e)))?;
+
+        let mut discovered_files = Vec::with_capacity(import_files.len());
+        for file in &import_files {
+            let path = std::path::PathBuf::from(&file.source_path);
+            let size = std::fs::metadata(&path)
+                .map_err(|_| {
+                    ImportError::Files(format!("Source file missing: {}", file.source_path))
+                })?
+                .len();
+            discovered_files.push(DiscoveredFile { path, size });
+        }
+        let mapping_result = map_tracks_to_files(&db_tracks, &discovered_files)
+            .await
+            .map_err(ImportError::Files)?;
+
+        self.reconcile_release_files(import, release_id, &import_files)
+            .await?;
+
+        // A remote cover is written during prepare, so an existing cover
+        // image without a local selection means it must not be replaced
+        let cover_image_path = import
+            .cover_image_path
+            .as_ref()
+            .map(std::path::PathBuf::from);
+        let remote_cover_set = cover_image_path.is_none()
+            && library_manager
+                .get_library_image(release_id, &LibraryImageType::Cover)
+                .await
+                .map_err(|e| ImportError::Database(format!("Failed to load cover: {}", e)))?
+                .is_some();
+
+        self.requests_tx
+            .send(ImportCommand::Folder {
+                db_album,
+                db_release,
+                tracks_to_files: mapping_result.track_files,
+                discovered_files,
+                cue_flac_metadata: mapping_result.cue_flac_metadata,
+                managed: import.managed,
+                cover_image_path,
+                remote_cover_set,
+                import_id: import.id.clone(),
+            })
+            .map_err(|_| ImportError::ServiceStopped)
+    }
+
+    /// Bring release_files in line with the recorded per-file state before
+    /// the worker runs again.
+    ///
+    /// A managed file gets its release_files row only after its bytes are
+    /// fully stored, so a row for a file still marked pending means the
+    /// interruption hit between the two writes. Unmanaged imports record all
+    /// files in one batch, so any rows from the earlier attempt are dropped
+    /// and recorded again.
+    async fn reconcile_release_files(
+        &self,
+        import: &DbImport,
+        release_id: &str,
+        import_files: &[DbImportFile],
+    ) -> Result<(), ImportError> {
+        if !import.managed {
+            return self
+                .database
+                .delete_files_for_release(release_id)
+                .await
+                .map_err(|e| {
+                    ImportError::Database(format!("Failed to clear file records: {}", e))
+                });
+        }
+        let recorded: std::collections::HashSet<String> = self
+            .database
+            .get_files_for_release(release_id)
+            .await
+            .map_err(|e| ImportError::Database(format!("Failed to load file records: {}", e)))?
+            .into_iter()
+            .map(|f| f.original_filename)
+            .collect();
+        for file in import_files.iter().filter(|f| !f.stored) {
+            let filename = Path::new(&file.source_path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if recorded.contains(filename) {
+                self.database
+                    .mark_import_file_stored(&import.id, &file.source_path)
+                    .await
+                    .map_err(|e| {
+                        ImportError::Database(format!("Failed to update import file: {}", e))
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark an unresumable import failed and remove its partial release
+    async fn abandon_import(&self, import: &DbImport, reason: &str) {
+        if let Some(release_id) = &import.release_id {
+            if let Err(e) = self
+                .library_manager
+                .get()
+                .delete_release(release_id, &self.library_dir)
+                .await
+            {
+                warn!("Failed to remove partial release {}: {}", release_id, e);
+            }
+        }
+        if let Err(e) = self.database.update_import_error(&import.id, reason).await {
+            warn!("Failed to mark import {} as failed: {}", import.id, e);
+        }
+    }
+
     #[cfg(feature = "torrent")]
     async fn send_torrent_request(
         &self,
@@ -383,11 +783,10 @@ impl ImportServiceHandle {
         torrent_metadata: TorrentImportMetadata,
         managed: bool,
         selected_cover: Option<CoverSelection>,
-    ) -> Result<(String, String), String> {
+    ) -> Result<(String, String), ImportError> {
         if discogs_release.is_none() && mb_release.is_none() {
-            return Err("Either discogs_release or mb_release must be provided".to_string());
+            return Err(ImportError::NoRelease);
         }
-        let library_manager = self.library_manager.get();
         let torrent_source_for_request = torrent_source.clone();
 
         info!(
@@ -396,7 +795,7 @@ impl ImportServiceHandle {
             torrent_metadata.num_pieces,
             torrent_metadata.total_size_bytes
         );
-        let (db_album, db_release, db_tracks, artists, album_artists) = self
+        let (db_album, db_release, db_tracks, artists, album_artists, track_artists) = self
             .resolve_metadata(discogs_release.as_ref(), mb_release.as_ref(), master_year)
             .await?;
         let temp_dir = std::env::temp_dir();
@@ -408,15 +807,35 @@ impl ImportServiceHandle {
                 size: tf.size as u64,
             })
             .collect();
-        let mapping_result = map_tracks_to_files(&db_tracks, &discovered_files).await?;
+        let mapping_result = map_tracks_to_files(&db_tracks, &discovered_files)
+            .await
+            .map_err(ImportError::Files)?;
         let tracks_to_files = mapping_result.track_files.clone();
-        let artist_id_map = find_or_create_artists(library_manager, &artists).await?;
+        let artist_id_map = find_or_create_artists(library_manager, &artists)
+            .await
+            .map_err(ImportError::Database)?;
         library_manager
             .insert_album_with_release_and_tracks(&db_album, &db_release, &db_tracks)
             .await
-            .map_err(|e| format!("Database error: {}", e))?;
-        extract_and_store_durations(library_manager, &tracks_to_files).await?;
-        insert_album_artists(library_manager, &album_artists, &artist_id_map).await?;
+            .map_err(|e| ImportError::Database(format!("Database error: {}", e)))?;
+        if let Some(ref discogs_rel) = discogs_release {
+            save_album_genres(
+                library_manager,
+                &db_album.id,
+                &discogs_parser::discogs_genres(discogs_rel),
+            )
+            .await;
+        }
+        insert_track_artists(library_manager, &track_artists, &artist_id_map)
+            .await
+            .map_err(ImportError::Database)?;
+        save_release_works(library_manager, &db_album, &db_release.id).await;
+        extract_and_store_durations(library_manager, &tracks_to_files)
+            .await
+            .map_err(ImportError::Database)?;
+        insert_album_artists(library_manager, &album_artists, &artist_id_map)
+            .await
+            .map_err(ImportError::Database)?;
 
         // Fetch artist images (best-effort, non-blocking)
         if let Some(ref discogs_client) = get_discogs_client(&self.key_service) {
@@ -442,7 +861,9 @@ impl ImportServiceHandle {
         library_manager
             .insert_torrent(&db_torrent)
             .await
-            .map_err(|e| format!("Failed to save torrent metadata: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to save torrent metadata: {}", e))
+            })?;
 
         tracing::info!(
             "Validated and queued torrent import '{}' (release: {}) with {} tracks",
@@ -463,7 +884,7 @@ impl ImportServiceHandle {
                 managed,
                 selected_cover,
             })
-            .map_err(|_| "Failed to queue validated torrent for import".to_string())?;
+            .map_err(|_| ImportError::ServiceStopped)?;
         Ok((album_id, release_id))
     }
     #[cfg(feature = "cd-rip")]
@@ -475,11 +896,10 @@ impl ImportServiceHandle {
         master_year: u32,
         managed: bool,
         selected_cover: Option<CoverSelection>,
-    ) -> Result<(String, String), String> {
+    ) -> Result<(String, String), ImportError> {
         if discogs_release.is_none() && mb_release.is_none() {
-            return Err("Either discogs_release or mb_release must be provided".to_string());
+            return Err(ImportError::NoRelease);
         }
-        let library_manager = self.library_manager.get();
 
         use crate::cd::CdDrive;
         let drive = CdDrive {
@@ -488,16 +908,32 @@ impl ImportServiceHandle {
         };
         let toc = drive
             .read_toc()
-            .map_err(|e| format!("Failed to read CD TOC: {}", e))?;
-        let (db_album, db_release, db_tracks, artists, album_artists) = self
+            .map_err(|e| ImportError::Acquire(format!("Failed to read CD TOC: {}", e)))?;
+        let (db_album, db_release, db_tracks, artists, album_artists, track_artists) = self
             .resolve_metadata(discogs_release.as_ref(), mb_release.as_ref(), master_year)
             .await?;
-        let artist_id_map = find_or_create_artists(library_manager, &artists).await?;
+        let artist_id_map = find_or_create_artists(library_manager, &artists)
+            .await
+            .map_err(ImportError::Database)?;
         library_manager
             .insert_album_with_release_and_tracks(&db_album, &db_release, &db_tracks)
             .await
-            .map_err(|e| format!("Database error: {}", e))?;
-        insert_album_artists(library_manager, &album_artists, &artist_id_map).await?;
+            .map_err(|e| ImportError::Database(format!("Database error: {}", e)))?;
+        if let Some(ref discogs_rel) = discogs_release {
+            save_album_genres(
+                library_manager,
+                &db_album.id,
+                &discogs_parser::discogs_genres(discogs_rel),
+            )
+            .await;
+        }
+        insert_track_artists(library_manager, &track_artists, &artist_id_map)
+            .await
+            .map_err(ImportError::Database)?;
+        save_release_works(library_manager, &db_album, &db_release.id).await;
+        insert_album_artists(library_manager, &album_artists, &artist_id_map)
+            .await
+            .map_err(ImportError::Database)?;
 
         // Fetch artist images (best-effort, non-blocking)
         if let Some(ref discogs_client) = get_discogs_client(&self.key_service) {
@@ -527,7 +963,7 @@ impl ImportServiceHandle {
                 managed,
                 cover_image_path,
             })
-            .map_err(|_| "Failed to queue validated CD import".to_string())?;
+            .map_err(|_| ImportError::ServiceStopped)?;
         Ok((album_id, release_id))
     }
     /// Resolve metadata from either a Discogs release or a MusicBrainz release.
@@ -541,9 +977,10 @@ impl ImportServiceHandle {
         discogs_release: Option<&DiscogsRelease>,
         mb_release: Option<&MbRelease>,
         master_year: u32,
-    ) -> Result<ParsedAlbum, String> {
+    ) -> Result<ParsedAlbum, ImportError> {
         if let Some(discogs_rel) = discogs_release {
             discogs_parser::parse_discogs_release(discogs_rel, master_year)
+                .map_err(ImportError::Metadata)
         } else if let Some(mb_rel) = mb_release {
             let discogs_client = get_discogs_client(&self.key_service);
             musicbrainz_parser::fetch_and_parse_mb_release(
@@ -552,8 +989,9 @@ impl ImportServiceHandle {
                 discogs_client.as_ref(),
             )
             .await
+            .map_err(ImportError::Metadata)
         } else {
-            Err("No release provided".to_string())
+            Err(ImportError::NoRelease)
         }
     }
 
@@ -717,6 +1155,59 @@ async fn insert_album_artists(
     Ok(())
 }
 
+/// Remap and insert track-artist relationships using the artist_id_map.
+/// Goes in before the release's works so composers are credited last.
+async fn insert_track_artists(
+    library_manager: &LibraryManager,
+    track_artists: &[crate::db::DbTrackArtist],
+    artist_id_map: &HashMap<String, String>,
+) -> Result<(), String> {
+    for track_artist in track_artists {
+        let actual_artist_id = artist_id_map.get(&track_artist.artist_id).ok_or_else(|| {
+            format!(
+                "Artist ID {} not found in artist map",
+                track_artist.artist_id,
+            )
+        })?;
+        let mut updated_track_artist = track_artist.clone();
+        updated_track_artist.artist_id = actual_artist_id.clone();
+        library_manager
+            .insert_track_artist(&updated_track_artist)
+            .await
+            .map_err(|e| format!("Failed to insert track-artist relationship: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Store an imported album's genres. Best-effort: a failure is logged and
+/// the import goes on without them.
+async fn save_album_genres(library_manager: &LibraryManager, album_id: &str, genres: &[String]) {
+    if genres.is_empty() {
+        return;
+    }
+    if let Err(e) = library_manager.set_album_genres(album_id, genres).await {
+        warn!("Failed to save genres for album {}: {}", album_id, e);
+    }
+}
+
+/// Look up classical works and composers, and disc titles, for an album
+/// matched on MusicBrainz. Best-effort like genres; most releases have no
+/// works linked.
+async fn save_release_works(
+    library_manager: &LibraryManager,
+    album: &crate::db::DbAlbum,
+    release_id: &str,
+) {
+    if album.musicbrainz_release.is_none() {
+        return;
+    }
+    match library_manager.fetch_release_works(release_id).await {
+        Ok(0) => {}
+        Ok(count) => info!("Found works for {} tracks of release {}", count, release_id),
+        Err(e) => warn!("Failed to look up works for release {}: {}", release_id, e),
+    }
+}
+
 /// Extract durations from audio files and update database immediately
 pub async fn extract_and_store_durations(
     library_manager: &LibraryManager,
@@ -859,9 +1350,12 @@ async fn fetch_artist_images(
 /// - Collections - recursive tree where leaves are single releases
 ///
 /// Files are sorted by path for consistent ordering across runs.
-fn discover_folder_files(folder: &Path) -> Result<Vec<DiscoveredFile>, String> {
+fn discover_folder_files(
+    folder: &Path,
+    filters: &ImportFilters,
+) -> Result<Vec<DiscoveredFile>, String> {
     use crate::import::folder_scanner::{self, AudioContent};
-    let categorized = folder_scanner::collect_release_files(folder)?;
+    let categorized = folder_scanner::collect_release_files(folder, filters)?;
     let mut files: Vec<DiscoveredFile> = Vec::new();
     match categorized.audio {
         AudioContent::CueFlacPairs(pairs) => {
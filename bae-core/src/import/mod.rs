@@ -1,31 +1,47 @@
+pub mod archive;
 pub mod artist_image;
+pub mod bandcamp;
 pub mod cover_art;
+pub mod cover_upgrade;
 mod discogs_matcher;
 mod discogs_parser;
+mod error;
 mod file_validation;
 mod folder_metadata_detector;
 pub mod folder_scanner;
 mod handle;
 mod musicbrainz_parser;
+pub mod network_share;
 mod progress;
+pub mod server_migration;
 mod service;
+pub mod silence_split;
+pub mod spotify_library;
+mod tag_parser;
 mod track_to_file_mapper;
 mod types;
 
-use crate::db::{DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack};
+use crate::db::{DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack, DbTrackArtist};
 
-/// Result of parsing a release (MusicBrainz or Discogs) into database entities
+/// Result of parsing a release (MusicBrainz or Discogs) into database entities.
+///
+/// Track artists are only listed for tracks credited differently from the
+/// album, e.g. "Glass Harbor feat. Tidewater" on a Glass Harbor album.
 pub type ParsedAlbum = (
     DbAlbum,
     DbRelease,
     Vec<DbTrack>,
     Vec<DbArtist>,
     Vec<DbAlbumArtist>,
+    Vec<DbTrackArtist>,
 );
 
 pub use discogs_matcher::{rank_discogs_matches, rank_mb_matches, MatchCandidate, MatchSource};
+pub use error::ImportError;
 pub use folder_metadata_detector::{detect_folder_contents, detect_metadata, FolderMetadata};
-pub use folder_scanner::{scan_for_candidates_with_callback, CategorizedFiles, DetectedCandidate};
+pub use folder_scanner::{
+    scan_for_candidates_with_callback, CategorizedFiles, DetectedCandidate, ImportFilters,
+};
 pub use handle::{ImportServiceHandle, ScanEvent};
 #[cfg(feature = "torrent")]
 pub use handle::{TorrentFileMetadata, TorrentImportMetadata};
@@ -1,7 +1,7 @@
 use super::ParsedAlbum;
-use crate::db::{DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack};
-use crate::discogs::DiscogsClient;
-use crate::musicbrainz::{lookup_release_by_id, MbReleaseResponse};
+use crate::db::{DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack, DbTrackArtist};
+use crate::discogs::{DiscogsClient, DiscogsRelease};
+use crate::musicbrainz::{lookup_release_by_id, MbArtistCredit, MbReleaseResponse};
 use crate::retry::retry_with_backoff;
 use tracing::{info, warn};
 use uuid::Uuid;
@@ -71,47 +71,13 @@ pub fn map_mb_response_to_db(
     let db_release = DbRelease::from_mb_release(&album.id, &mb_release);
 
     let mut artists = Vec::new();
-    let mut album_artists = Vec::new();
-
-    for (position, credit) in response.artist_credit.iter().enumerate() {
-        if let Some(artist_obj) = &credit.artist {
-            let artist_name = artist_obj
-                .name
-                .as_deref()
-                .unwrap_or("Unknown Artist")
-                .to_string();
-
-            let mb_artist_id = artist_obj.id.clone();
-
-            let sort_name = artist_obj
-                .sort_name
-                .clone()
-                .unwrap_or_else(|| artist_name.clone());
-
-            let discogs_artist_id = discogs_release.as_ref().and_then(|dr| {
-                dr.artists
-                    .iter()
-                    .find(|da| da.name.eq_ignore_ascii_case(&artist_name))
-                    .map(|da| da.id.clone())
-            });
-
-            let artist = DbArtist {
-                id: Uuid::new_v4().to_string(),
-                name: artist_name,
-                sort_name: Some(sort_name),
-                discogs_artist_id,
-                bandcamp_artist_id: None,
-                musicbrainz_artist_id: mb_artist_id,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-            };
-            let album_artist = DbAlbumArtist::new(&album.id, &artist.id, position as i32);
-            artists.push(artist);
-            album_artists.push(album_artist);
-        }
-    }
+    let mut album_credit = credit_artists(
+        &response.artist_credit,
+        discogs_release.as_ref(),
+        &mut artists,
+    );
 
-    if artists.is_empty() {
+    if album_credit.is_empty() {
         let artist_name = mb_release.artist.clone();
         let artist = DbArtist {
             id: Uuid::new_v4().to_string(),
@@ -123,12 +89,21 @@ pub fn map_mb_response_to_db(
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
-        let album_artist = DbAlbumArtist::new(&album.id, &artist.id, 0);
+        album_credit.push((artist.id.clone(), None));
         artists.push(artist);
-        album_artists.push(album_artist);
     }
 
+    let album_artists = album_credit
+        .iter()
+        .enumerate()
+        .map(|(position, (artist_id, join_phrase))| {
+            DbAlbumArtist::new(&album.id, artist_id, position as i32)
+                .with_join_phrase(join_phrase.clone())
+        })
+        .collect();
+
     let mut tracks = Vec::new();
+    let mut track_artists = Vec::new();
     let mut track_index = 0;
 
     for (medium_index, medium) in response.media.iter().enumerate() {
@@ -145,8 +120,19 @@ pub fn map_mb_response_to_db(
             let track_number = position.or(Some(track_index + 1));
 
             let now = chrono::Utc::now();
+            let track_id = Uuid::new_v4().to_string();
+            let track_credit =
+                credit_artists(&track.artist_credit, discogs_release.as_ref(), &mut artists);
+            if !track_credit.is_empty() && track_credit != album_credit {
+                for (position, (artist_id, join_phrase)) in track_credit.into_iter().enumerate() {
+                    track_artists.push(
+                        DbTrackArtist::new(&track_id, &artist_id, position as i32, None)
+                            .with_join_phrase(join_phrase),
+                    );
+                }
+            }
             let db_track = DbTrack {
-                id: Uuid::new_v4().to_string(),
+                id: track_id,
                 release_id: db_release.id.clone(),
                 title,
                 disc_number,
@@ -162,5 +148,122 @@ pub fn map_mb_response_to_db(
         }
     }
 
-    Ok((album, db_release, tracks, artists, album_artists))
+    Ok((
+        album,
+        db_release,
+        tracks,
+        artists,
+        album_artists,
+        track_artists,
+    ))
+}
+
+/// Resolve an artist credit to (artist ID, join phrase) pairs, adding artists
+/// not seen yet on this release to `artists`
+fn credit_artists(
+    credit: &[MbArtistCredit],
+    discogs_release: Option<&DiscogsRelease>,
+    artists: &mut Vec<DbArtist>,
+) -> Vec<(String, Option<String>)> {
+    let mut resolved = Vec::new();
+    for entry in credit {
+        let Some(artist_obj) = &entry.artist else {
+            continue;
+        };
+        let artist_name = artist_obj
+            .name
+            .as_deref()
+            .unwrap_or("Unknown Artist")
+            .to_string();
+        let mb_artist_id = artist_obj.id.clone();
+
+        let existing = artists.iter().find(|a| match &mb_artist_id {
+            Some(id) => a.musicbrainz_artist_id.as_ref() == Some(id),
+            None => a.musicbrainz_artist_id.is_none() && a.name == artist_name,
+        });
+        let artist_id = match existing {
+            Some(artist) => artist.id.clone(),
+            None => {
+                let sort_name = artist_obj
+                    .sort_name
+                    .clone()
+                    .unwrap_or_else(|| artist_name.clone());
+
+                let discogs_artist_id = discogs_release.and_then(|dr| {
+                    dr.artists
+                        .iter()
+                        .find(|da| da.name.eq_ignore_ascii_case(&artist_name))
+                        .map(|da| da.id.clone())
+                });
+
+                let artist = DbArtist {
+                    id: Uuid::new_v4().to_string(),
+                    name: artist_name,
+                    sort_name: Some(sort_name),
+                    discogs_artist_id,
+                    bandcamp_artist_id: None,
+                    musicbrainz_artist_id: mb_artist_id,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                };
+                let id = artist.id.clone();
+                artists.push(artist);
+                id
+            }
+        };
+        let join_phrase = entry.joinphrase.clone().filter(|j| !j.is_empty());
+        resolved.push((artist_id, join_phrase));
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credit(id: &str, name: &str, joinphrase: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "joinphrase": joinphrase,
+            "artist": { "id": id, "name": name, "sort-name": name }
+        })
+    }
+
+    #[test]
+    fn track_credits_differing_from_the_album_are_kept_with_joins() {
+        let response: MbReleaseResponse = serde_json::from_value(serde_json::json!({
+            "id": "mb-release",
+            "title": "Low Light",
+            "artist-credit": [credit("mb-glass", "Glass Harbor", "")],
+            "media": [{ "tracks": [
+                {
+                    "position": 1,
+                    "recording": { "title": "Pacific Standard" },
+                    "artist-credit": [credit("mb-glass", "Glass Harbor", "")]
+                },
+                {
+                    "position": 2,
+                    "recording": { "title": "Undertow" },
+                    "artist-credit": [
+                        credit("mb-glass", "Glass Harbor", " feat. "),
+                        credit("mb-tide", "Tidewater", "")
+                    ]
+                }
+            ] }]
+        }))
+        .unwrap();
+
+        let (_, _, tracks, artists, album_artists, track_artists) =
+            map_mb_response_to_db(&response, 2011, None).unwrap();
+
+        assert_eq!(artists.len(), 2);
+        assert_eq!(album_artists.len(), 1);
+        assert_eq!(album_artists[0].join_phrase, None);
+        assert_eq!(track_artists.len(), 2);
+        assert!(track_artists.iter().all(|ta| ta.track_id == tracks[1].id));
+        assert_eq!(track_artists[0].artist_id, album_artists[0].artist_id);
+        assert_eq!(track_artists[0].join_phrase.as_deref(), Some(" feat. "));
+        assert_eq!(track_artists[1].artist_id, artists[1].id);
+        assert_eq!(track_artists[1].join_phrase, None);
+    }
 }
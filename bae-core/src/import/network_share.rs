@@ -0,0 +1,337 @@
+//! Network share sources for folder imports
+//!
+//! The folder scanner works on local paths, so an `smb://` or `nfs://` share
+//! is resolved to a mount point first: an existing mount of the share is
+//! reused, and on macOS an unmounted share is mounted through the system
+//! (`mount volume`, the same path Finder's "Connect to Server" takes). Other
+//! platforms need the share mounted beforehand, by the file manager or
+//! fstab; both kernel mounts and GNOME's gvfs mounts are found.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::info;
+
+/// How long to wait for a fresh mount to show up in the mount table
+const MOUNT_POLL_ATTEMPTS: u32 = 20;
+const MOUNT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Error, Debug)]
+pub enum NetworkShareError {
+    #[error("Invalid share address: {0}")]
+    InvalidUrl(String),
+    #[error("Couldn't connect to {url}: {message}")]
+    Mount { url: String, message: String },
+    #[error("{0} isn't mounted. Connect to it from your file manager first")]
+    NotMounted(String),
+    #[error("Failed to run {0}: {1}")]
+    Command(&'static str, std::io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareProtocol {
+    Smb,
+    Nfs,
+}
+
+/// A folder on an SMB or NFS server
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkShare {
+    pub protocol: ShareProtocol,
+    pub host: String,
+    /// SMB share name, or the NFS export path (with leading `/`)
+    pub share: String,
+    /// Folder inside the share, without leading `/`. Always empty for NFS,
+    /// where the export and the folder inside it can't be told apart.
+    pub subpath: String,
+    pub username: Option<String>,
+}
+
+impl NetworkShare {
+    /// Parse `smb://[user@]host/share[/path]` or `nfs://host/export/path`.
+    pub fn parse(url: &str) -> Result<Self, NetworkShareError> {
+        let invalid = || NetworkShareError::InvalidUrl(url.to_string());
+        let url = url.trim();
+        let (protocol, rest) = if let Some(rest) = strip_prefix_ignore_case(url, "smb://") {
+            (ShareProtocol::Smb, rest)
+        } else if let Some(rest) = strip_prefix_ignore_case(url, "nfs://") {
+            (ShareProtocol::Nfs, rest)
+        } else {
+            return Err(invalid());
+        };
+
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let (username, host) = match authority.rsplit_once('@') {
+            Some((user, host)) => (Some(decode(user)), host),
+            None => (None, authority),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        let path = decode(path.trim_end_matches('/'));
+
+        let (share, subpath) = match protocol {
+            ShareProtocol::Smb => {
+                let (share, subpath) = path.split_once('/').unwrap_or((path.as_str(), ""));
+                (share.to_string(), subpath.to_string())
+            }
+            ShareProtocol::Nfs => (format!("/{path}"), String::new()),
+        };
+        if share.is_empty() || share == "/" {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            protocol,
+            host: host.to_string(),
+            share,
+            subpath,
+            username: username.filter(|u| !u.is_empty()),
+        })
+    }
+
+    /// The share's address, without credentials
+    pub fn url(&self) -> String {
+        match self.protocol {
+            ShareProtocol::Smb if self.subpath.is_empty() => {
+                format!("smb://{}/{}", self.host, self.share)
+            }
+            ShareProtocol::Smb => format!("smb://{}/{}/{}", self.host, self.share, self.subpath),
+            ShareProtocol::Nfs => format!("nfs://{}{}", self.host, self.share),
+        }
+    }
+
+    /// Local path of the folder, mounting the share first if needed.
+    ///
+    /// Blocks while the OS connects, so call it off the async runtime.
+    pub fn mount(&self, password: Option<&str>) -> Result<PathBuf, NetworkShareError> {
+        if let Some(path) = self.find_mounted()? {
+            return Ok(path);
+        }
+
+        info!("Mounting {}", self.url());
+
+        self.mount_with_os(password)?;
+        for _ in 0..MOUNT_POLL_ATTEMPTS {
+            if let Some(path) = self.find_mounted()? {
+                return Ok(path);
+            }
+            std::thread::sleep(MOUNT_POLL_INTERVAL);
+        }
+        Err(NetworkShareError::Mount {
+            url: self.url(),
+            message: "the share was mounted but its folder didn't appear".to_string(),
+        })
+    }
+
+    /// Local path of the folder if the share is already mounted
+    pub fn find_mounted(&self) -> Result<Option<PathBuf>, NetworkShareError> {
+        let output = Command::new("mount")
+            .output()
+            .map_err(|e| NetworkShareError::Command("mount", e))?;
+        let table = String::from_utf8_lossy(&output.stdout);
+        if let Some(path) = self.find_in_mount_table(&table) {
+            return Ok(Some(path));
+        }
+        Ok(self.find_gvfs_mount())
+    }
+
+    fn find_in_mount_table(&self, table: &str) -> Option<PathBuf> {
+        table
+            .lines()
+            .filter_map(parse_mount_line)
+            .find_map(|(source, target)| {
+                let folder = self.folder_in_mount(&source)?;
+                Some(join_subpath(Path::new(&target), &folder))
+            })
+    }
+
+    /// If `source` (a mount table source) is this share, the folder's path
+    /// relative to the mount point
+    fn folder_in_mount(&self, source: &str) -> Option<String> {
+        match self.protocol {
+            ShareProtocol::Smb => {
+                let rest = decode(source.strip_prefix("//")?);
+                let rest = rest.rsplit_once('@').map_or(rest.as_str(), |(_, r)| r);
+                let (host, share) = rest.split_once('/')?;
+                (host.eq_ignore_ascii_case(&self.host) && share.eq_ignore_ascii_case(&self.share))
+                    .then(|| self.subpath.clone())
+            }
+            ShareProtocol::Nfs => {
+                let (host, export) = source.split_once(':')?;
+                if !host.eq_ignore_ascii_case(&self.host) {
+                    return None;
+                }
+                // The mounted export may be a parent of the requested folder
+                let export = export.trim_end_matches('/');
+                let inner = self.share.strip_prefix(export)?;
+                (inner.is_empty() || inner.starts_with('/'))
+                    .then(|| inner.trim_start_matches('/').to_string())
+            }
+        }
+    }
+
+    /// gvfs mounts all live under one FUSE mount, so they don't show up
+    /// individually in the mount table
+    fn find_gvfs_mount(&self) -> Option<PathBuf> {
+        if self.protocol != ShareProtocol::Smb {
+            return None;
+        }
+        let gvfs = PathBuf::from(std::env::var_os("XDG_RUNTIME_DIR")?).join("gvfs");
+        let server = format!("server={}", self.host.to_lowercase());
+        let share = format!("share={}", self.share.to_lowercase());
+        std::fs::read_dir(&gvfs).ok()?.flatten().find_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            let fields: Vec<&str> = name.strip_prefix("smb-share:")?.split(',').collect();
+            (fields.contains(&server.as_str()) && fields.contains(&share.as_str()))
+                .then(|| join_subpath(&entry.path(), &self.subpath))
+        })
+    }
+
+    #[cfg(target_os = "macos")]
+    fn mount_with_os(&self, password: Option<&str>) -> Result<(), NetworkShareError> {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let volume = match self.protocol {
+            ShareProtocol::Smb => format!("smb://{}/{}", self.host, self.share),
+            ShareProtocol::Nfs => format!("nfs://{}{}", self.host, self.share),
+        };
+        let mut script = format!("mount volume \"{}\"", applescript_escape(&volume));
+        if let Some(username) = &self.username {
+            script.push_str(&format!(
+                " as user name \"{}\"",
+                applescript_escape(username)
+            ));
+        }
+        if let Some(password) = password.filter(|p| !p.is_empty()) {
+            script.push_str(&format!(
+                " with password \"{}\"",
+                applescript_escape(password)
+            ));
+        }
+
+        // The script goes over stdin so the password never shows up in `ps`
+        let mut child = Command::new("osascript")
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| NetworkShareError::Command("osascript", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(script.as_bytes())
+                .map_err(|e| NetworkShareError::Command("osascript", e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| NetworkShareError::Command("osascript", e))?;
+        if !output.status.success() {
+            return Err(NetworkShareError::Mount {
+                url: self.url(),
+                message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn mount_with_os(&self, _password: Option<&str>) -> Result<(), NetworkShareError> {
+        Err(NetworkShareError::NotMounted(self.url()))
+    }
+}
+
+/// Split a `mount` output line into (source, mount point).
+///
+/// macOS prints `source on target (opts)`, Linux `source on target type fs (opts)`.
+fn parse_mount_line(line: &str) -> Option<(String, String)> {
+    let (source, rest) = line.split_once(" on ")?;
+    let end = rest
+        .find(" type ")
+        .or_else(|| rest.rfind(" ("))
+        .unwrap_or(rest.len());
+    Some((source.to_string(), rest[..end].to_string()))
+}
+
+fn join_subpath(mount_point: &Path, subpath: &str) -> PathBuf {
+    if subpath.is_empty() {
+        mount_point.to_path_buf()
+    } else {
+        mount_point.join(subpath)
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
+fn decode(s: &str) -> String {
+    urlencoding::decode(s)
+        .map(|d| d.into_owned())
+        .unwrap_or_else(|_| s.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_smb_url_with_user_and_subfolder() {
+        let share = NetworkShare::parse("smb://alice@nas.local/Music/Rips%20FLAC/").unwrap();
+        assert_eq!(share.protocol, ShareProtocol::Smb);
+        assert_eq!(share.host, "nas.local");
+        assert_eq!(share.share, "Music");
+        assert_eq!(share.subpath, "Rips FLAC");
+        assert_eq!(share.username.as_deref(), Some("alice"));
+        assert_eq!(share.url(), "smb://nas.local/Music/Rips FLAC");
+    }
+
+    #[test]
+    fn parses_nfs_url_as_export_path() {
+        let share = NetworkShare::parse("NFS://nas/volume1/music").unwrap();
+        assert_eq!(share.protocol, ShareProtocol::Nfs);
+        assert_eq!(share.share, "/volume1/music");
+        assert_eq!(share.subpath, "");
+    }
+
+    #[test]
+    fn rejects_unsupported_or_incomplete_urls() {
+        assert!(NetworkShare::parse("/Volumes/Music").is_err());
+        assert!(NetworkShare::parse("afp://nas/Music").is_err());
+        assert!(NetworkShare::parse("smb://nas").is_err());
+        assert!(NetworkShare::parse("smb:///Music").is_err());
+    }
+
+    #[test]
+    fn finds_smb_mount_in_macos_table() {
+        let table = "/dev/disk3s1 on / (apfs, local, journaled)\n\
+            //alice@NAS.local/Music%20Share on /Volumes/Music Share (smbfs, nodev, nosuid, mounted by alice)\n";
+        let share = NetworkShare::parse("smb://nas.local/music share/Glass Harbor").unwrap();
+        assert_eq!(
+            share.find_in_mount_table(table),
+            Some(PathBuf::from("/Volumes/Music Share/Glass Harbor"))
+        );
+    }
+
+    #[test]
+    fn finds_nfs_subfolder_under_mounted_export() {
+        let table = "nas:/volume1/music on /mnt/music type nfs4 (rw,relatime)\n";
+        let share = NetworkShare::parse("nfs://nas/volume1/music/Tidewater").unwrap();
+        assert_eq!(
+            share.find_in_mount_table(table),
+            Some(PathBuf::from("/mnt/music/Tidewater"))
+        );
+
+        let sibling = NetworkShare::parse("nfs://nas/volume1/musicals").unwrap();
+        assert_eq!(sibling.find_in_mount_table(table), None);
+    }
+}
@@ -174,6 +174,7 @@ impl ImportProgressHandle {
 mod tests {
     use super::*;
     use crate::import::types::{ImportPhase, PrepareStep};
+    use crate::import::ImportError;
     #[test]
     fn test_release_filter_matches_release_events() {
         let filter = SubscriptionFilter::Release {
@@ -303,7 +304,7 @@ mod tests {
         },),);
         assert!(filter.matches(&ImportProgress::Failed {
             id: "release-1".to_string(),
-            error: "error".to_string(),
+            error: ImportError::ServiceStopped,
             import_id: Some("import-1".to_string()),
         },),);
         assert!(!filter.matches(&ImportProgress::Progress {
@@ -352,7 +353,7 @@ mod tests {
         },),);
         assert!(filter.matches(&ImportProgress::Failed {
             id: "release-1".to_string(),
-            error: "error".to_string(),
+            error: ImportError::ServiceStopped,
             import_id: Some("import-4".to_string()),
         },),);
         assert!(!filter.matches(&ImportProgress::Started {
@@ -0,0 +1,390 @@
+//! Listening history migration from other music servers
+//!
+//! Pulls play counts, ratings and playlists from a Navidrome or Plex server
+//! and merges them onto the matching albums already in the bae library. The
+//! files themselves aren't transferred; albums are expected to have been
+//! imported separately.
+//!
+//! Albums are matched by MusicBrainz ID when the remote server knows it, and
+//! otherwise by artist and title. Tracks within a matched album are matched
+//! by title, falling back to disc and track number.
+
+pub mod navidrome;
+pub mod plex;
+
+use crate::db::{Database, DbTrack};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use thiserror::Error;
+use tracing::info;
+
+pub use navidrome::NavidromeClient;
+pub use plex::PlexClient;
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Server returned an error: {0}")]
+    Server(String),
+    #[error("Unexpected response: {0}")]
+    Parse(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// Everything worth keeping from a remote server's library
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RemoteLibrary {
+    pub albums: Vec<RemoteAlbum>,
+    pub playlists: Vec<RemotePlaylist>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteAlbum {
+    pub artist: String,
+    pub title: String,
+    pub year: Option<i32>,
+    /// MusicBrainz release or release group ID, if the server knows it
+    pub musicbrainz_id: Option<String>,
+    pub tracks: Vec<RemoteTrack>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteTrack {
+    /// The remote server's ID, used to resolve playlist entries
+    pub remote_id: String,
+    pub title: String,
+    pub disc_number: Option<i32>,
+    pub track_number: Option<i32>,
+    pub play_count: i64,
+    /// Star rating from 1 to 5
+    pub rating: Option<i32>,
+    pub last_played_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemotePlaylist {
+    pub name: String,
+    /// Remote track IDs, in playlist order
+    pub track_ids: Vec<String>,
+}
+
+/// What a merge changed, for reporting back to the user
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationSummary {
+    pub albums_matched: usize,
+    pub albums_unmatched: usize,
+    pub tracks_updated: usize,
+    pub playlists_imported: usize,
+    /// Playlist entries dropped because their track isn't in the library
+    pub playlist_entries_skipped: usize,
+}
+
+/// A library album with the fields used for matching
+pub(crate) struct LocalAlbum {
+    pub(crate) id: String,
+    artist_key: String,
+    title_key: String,
+    year: Option<i32>,
+    musicbrainz_ids: Vec<String>,
+}
+
+/// Load every library album in the shape `match_album` expects
+pub(crate) async fn load_local_albums(database: &Database) -> Result<Vec<LocalAlbum>, sqlx::Error> {
+    let mut local_albums = Vec::new();
+    for album in database.get_albums(&[]).await? {
+        let artists = database.get_artists_for_album(&album.id).await?;
+        let artist_names: Vec<&str> = artists.iter().map(|a| a.name.as_str()).collect();
+        let musicbrainz_ids = album
+            .musicbrainz_release
+            .map(|mb| vec![mb.release_id, mb.release_group_id])
+            .unwrap_or_default();
+        local_albums.push(LocalAlbum {
+            id: album.id,
+            artist_key: normalize(&artist_names.join(" ")),
+            title_key: normalize_title(&album.title),
+            year: album.year,
+            musicbrainz_ids,
+        });
+    }
+    Ok(local_albums)
+}
+
+/// Merge a remote library's listening history into the local database.
+///
+/// Only tracks with something to record (plays or a rating) get stats.
+/// Playlists replace any existing playlist of the same name.
+pub async fn merge_remote_library(
+    database: &Database,
+    library: &RemoteLibrary,
+) -> Result<MigrationSummary, MigrationError> {
+    let local_albums = load_local_albums(database).await?;
+
+    let mut summary = MigrationSummary::default();
+    // Remote track ID -> local track ID, for resolving playlists
+    let mut track_map: HashMap<&str, String> = HashMap::new();
+
+    for remote in &library.albums {
+        let Some(local) = match_album(remote, &local_albums) else {
+            summary.albums_unmatched += 1;
+            continue;
+        };
+        summary.albums_matched += 1;
+
+        let mut local_tracks = Vec::new();
+        for release in database.get_releases_for_album(&local.id).await? {
+            local_tracks.extend(database.get_tracks_for_release(&release.id).await?);
+        }
+
+        for track in &remote.tracks {
+            let Some(local_track) = match_track(track, &local_tracks) else {
+                continue;
+            };
+            track_map.insert(&track.remote_id, local_track.id.clone());
+
+            if track.play_count > 0 || track.rating.is_some() {
+                database
+                    .merge_track_stats(
+                        &local_track.id,
+                        track.play_count,
+                        track.rating,
+                        track.last_played_at,
+                    )
+                    .await?;
+                summary.tracks_updated += 1;
+            }
+        }
+    }
+
+    for playlist in &library.playlists {
+        let track_ids: Vec<String> = playlist
+            .track_ids
+            .iter()
+            .filter_map(|id| track_map.get(id.as_str()).cloned())
+            .collect();
+        summary.playlist_entries_skipped += playlist.track_ids.len() - track_ids.len();
+        if track_ids.is_empty() {
+            continue;
+        }
+        database
+            .upsert_playlist_by_name(&playlist.name, &track_ids)
+            .await?;
+        summary.playlists_imported += 1;
+    }
+
+    info!(
+        "Merged listening history: {} albums matched, {} unmatched, {} tracks updated, {} playlists",
+        summary.albums_matched,
+        summary.albums_unmatched,
+        summary.tracks_updated,
+        summary.playlists_imported
+    );
+
+    Ok(summary)
+}
+
+/// Find the local album a remote album refers to.
+///
+/// A MusicBrainz ID match wins outright. Otherwise artist and title must
+/// match after normalization, with the release year breaking ties.
+pub(crate) fn match_album<'a>(
+    remote: &RemoteAlbum,
+    local: &'a [LocalAlbum],
+) -> Option<&'a LocalAlbum> {
+    if let Some(mbid) = &remote.musicbrainz_id {
+        if let Some(album) = local.iter().find(|a| {
+            a.musicbrainz_ids
+                .iter()
+                .any(|id| id.eq_ignore_ascii_case(mbid))
+        }) {
+            return Some(album);
+        }
+    }
+
+    let artist_key = normalize(&remote.artist);
+    let title_key = normalize_title(&remote.title);
+    let mut candidates = local
+        .iter()
+        .filter(|a| a.artist_key == artist_key && a.title_key == title_key);
+    let first = candidates.next()?;
+    if remote.year.is_none() || first.year == remote.year {
+        return Some(first);
+    }
+    Some(candidates.find(|a| a.year == remote.year).unwrap_or(first))
+}
+
+/// Find the local track a remote track refers to, by title then by position.
+fn match_track<'a>(remote: &RemoteTrack, local: &'a [DbTrack]) -> Option<&'a DbTrack> {
+    let same_disc = |t: &DbTrack| remote.disc_number.unwrap_or(1) == t.disc_number.unwrap_or(1);
+
+    let title_key = normalize_title(&remote.title);
+    local
+        .iter()
+        .find(|t| normalize_title(&t.title) == title_key && same_disc(t))
+        .or_else(|| {
+            local
+                .iter()
+                .find(|t| normalize_title(&t.title) == title_key)
+        })
+        .or_else(|| {
+            remote.track_number.and_then(|number| {
+                local
+                    .iter()
+                    .find(|t| t.track_number == Some(number) && same_disc(t))
+            })
+        })
+}
+
+/// Lowercase alphanumerics only, with a leading "the" dropped
+pub(crate) fn normalize(s: &str) -> String {
+    let key: String = s
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let key = key.split_whitespace().collect::<Vec<_>>().join(" ");
+    key.strip_prefix("the ").unwrap_or(&key).replace(' ', "")
+}
+
+/// Like `normalize`, but ignores bracketed suffixes such as "(Remastered)"
+pub(crate) fn normalize_title(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut depth = 0usize;
+    for c in s.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+    }
+    let key = normalize(&stripped);
+    if key.is_empty() {
+        normalize(s)
+    } else {
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::ImportStatus;
+
+    fn local_album(id: &str, artist: &str, title: &str, year: Option<i32>) -> LocalAlbum {
+        LocalAlbum {
+            id: id.to_string(),
+            artist_key: normalize(artist),
+            title_key: normalize_title(title),
+            year,
+            musicbrainz_ids: vec![],
+        }
+    }
+
+    fn remote_album(artist: &str, title: &str, year: Option<i32>) -> RemoteAlbum {
+        RemoteAlbum {
+            artist: artist.to_string(),
+            title: title.to_string(),
+            year,
+            musicbrainz_id: None,
+            tracks: vec![],
+        }
+    }
+
+    fn remote_track(title: &str, disc: Option<i32>, number: Option<i32>) -> RemoteTrack {
+        RemoteTrack {
+            remote_id: "r1".to_string(),
+            title: title.to_string(),
+            disc_number: disc,
+            track_number: number,
+            play_count: 0,
+            rating: None,
+            last_played_at: None,
+        }
+    }
+
+    fn local_track(id: &str, title: &str, disc: Option<i32>, number: i32) -> DbTrack {
+        let now = Utc::now();
+        DbTrack {
+            id: id.to_string(),
+            release_id: "release".to_string(),
+            title: title.to_string(),
+            disc_number: disc,
+            track_number: Some(number),
+            duration_ms: None,
+            discogs_position: None,
+            import_status: ImportStatus::Complete,
+            updated_at: now,
+            created_at: now,
+        }
+    }
+
+    #[test]
+    fn normalize_ignores_case_punctuation_and_article() {
+        assert_eq!(normalize("The Glass Harbor"), normalize("glass harbor"));
+        assert_eq!(normalize("Tide-Pool!"), "tidepool");
+        assert_eq!(
+            normalize_title("Pacific Standard (Remastered 2011)"),
+            normalize_title("Pacific Standard [Deluxe]")
+        );
+        assert_eq!(normalize_title("(Untitled)"), "untitled");
+    }
+
+    #[test]
+    fn album_matches_by_musicbrainz_id_first() {
+        let mut by_id = local_album("a", "Someone Else", "Different", None);
+        by_id.musicbrainz_ids = vec!["mb-release".to_string()];
+        let local = vec![
+            local_album("b", "Glass Harbor", "Pacific Standard", None),
+            by_id,
+        ];
+
+        let mut remote = remote_album("Glass Harbor", "Pacific Standard", None);
+        remote.musicbrainz_id = Some("MB-RELEASE".to_string());
+
+        assert_eq!(match_album(&remote, &local).unwrap().id, "a");
+    }
+
+    #[test]
+    fn album_matches_by_tags_preferring_same_year() {
+        let local = vec![
+            local_album("orig", "Glass Harbor", "Pacific Standard", Some(2001)),
+            local_album(
+                "remaster",
+                "Glass Harbor",
+                "Pacific Standard (Remaster)",
+                Some(2015),
+            ),
+        ];
+
+        let remote = remote_album("the glass harbor", "Pacific Standard", Some(2015));
+        assert_eq!(match_album(&remote, &local).unwrap().id, "remaster");
+
+        let remote = remote_album("Glass Harbor", "Pacific Standard", None);
+        assert_eq!(match_album(&remote, &local).unwrap().id, "orig");
+
+        let remote = remote_album("Tidewater", "Pacific Standard", None);
+        assert!(match_album(&remote, &local).is_none());
+    }
+
+    #[test]
+    fn track_matches_by_title_then_position() {
+        let local = vec![
+            local_track("t1", "Coastal", Some(1), 1),
+            local_track("t2", "Tide Pool", Some(1), 2),
+            local_track("t3", "Coastal", Some(2), 1),
+        ];
+
+        let matched = match_track(&remote_track("coastal", Some(2), None), &local);
+        assert_eq!(matched.unwrap().id, "t3");
+
+        let matched = match_track(&remote_track("Tide Pool (Live)", None, None), &local);
+        assert_eq!(matched.unwrap().id, "t2");
+
+        let matched = match_track(&remote_track("Track 02", None, Some(2)), &local);
+        assert_eq!(matched.unwrap().id, "t2");
+
+        assert!(match_track(&remote_track("Unknown", None, None), &local).is_none());
+    }
+}
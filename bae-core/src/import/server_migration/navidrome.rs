@@ -0,0 +1,294 @@
+//! Navidrome client, via the Subsonic API
+//!
+//! Works with any Subsonic-compatible server, but MusicBrainz IDs are only
+//! reported by servers implementing the OpenSubsonic extensions (Navidrome does).
+
+use super::{MigrationError, RemoteAlbum, RemoteLibrary, RemotePlaylist, RemoteTrack};
+use crate::subsonic::md5_hex;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tracing::info;
+
+/// Albums requested per `getAlbumList2` page (the API maximum)
+const PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlbumList2 {
+    #[serde(default)]
+    album: Vec<AlbumSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlbumSummary {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlbumWithSongs {
+    name: String,
+    #[serde(default)]
+    artist: String,
+    year: Option<i32>,
+    music_brainz_id: Option<String>,
+    #[serde(default)]
+    song: Vec<Song>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Song {
+    id: String,
+    title: String,
+    track: Option<i32>,
+    disc_number: Option<i32>,
+    #[serde(default)]
+    play_count: i64,
+    user_rating: Option<i32>,
+    /// ISO 8601 timestamp of the last play
+    played: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Playlists {
+    #[serde(default)]
+    playlist: Vec<PlaylistSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaylistSummary {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaylistWithSongs {
+    name: String,
+    #[serde(default)]
+    entry: Vec<PlaylistEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaylistEntry {
+    id: String,
+}
+
+#[derive(Clone)]
+pub struct NavidromeClient {
+    client: Client,
+    base_url: String,
+    username: String,
+    password: String,
+}
+
+impl NavidromeClient {
+    /// Create a client for the server at `base_url` (e.g. "http://nas:4533")
+    pub fn new(base_url: &str, username: String, password: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            username,
+            password,
+        }
+    }
+
+    /// Fetch every album with its per-song stats, plus all playlists.
+    pub async fn fetch_library(&self) -> Result<RemoteLibrary, MigrationError> {
+        let mut album_ids = Vec::new();
+        loop {
+            let offset = album_ids.len().to_string();
+            let page: AlbumList2 = self
+                .call(
+                    "getAlbumList2",
+                    "albumList2",
+                    &[
+                        ("type", "alphabeticalByName"),
+                        ("size", &PAGE_SIZE.to_string()),
+                        ("offset", &offset),
+                    ],
+                )
+                .await?;
+            let count = page.album.len();
+            album_ids.extend(page.album.into_iter().map(|a| a.id));
+            if count < PAGE_SIZE {
+                break;
+            }
+        }
+
+        let mut albums = Vec::with_capacity(album_ids.len());
+        for id in &album_ids {
+            let album: AlbumWithSongs = self.call("getAlbum", "album", &[("id", id)]).await?;
+            albums.push(album.into());
+        }
+
+        let summaries: Playlists = self.call("getPlaylists", "playlists", &[]).await?;
+        let mut playlists = Vec::with_capacity(summaries.playlist.len());
+        for summary in summaries.playlist {
+            let playlist: PlaylistWithSongs = self
+                .call("getPlaylist", "playlist", &[("id", &summary.id)])
+                .await?;
+            playlists.push(playlist.into());
+        }
+
+        info!(
+            "Fetched {} albums and {} playlists from Navidrome",
+            albums.len(),
+            playlists.len()
+        );
+
+        Ok(RemoteLibrary { albums, playlists })
+    }
+
+    /// Call a Subsonic endpoint and extract the named field from the response
+    async fn call<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        field: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T, MigrationError> {
+        let salt = uuid::Uuid::new_v4().simple().to_string();
+        let token = md5_hex(&format!("{}{}", self.password, salt));
+        let body: serde_json::Value = self
+            .client
+            .get(format!("{}/rest/{}", self.base_url, endpoint))
+            .query(&[
+                ("u", self.username.as_str()),
+                ("t", &token),
+                ("s", &salt),
+                ("v", "1.16.1"),
+                ("c", "bae"),
+                ("f", "json"),
+            ])
+            .query(params)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        extract_field(body, field)
+    }
+}
+
+/// Unwrap a `subsonic-response` envelope, surfacing API-level errors
+fn extract_field<T: DeserializeOwned>(
+    mut body: serde_json::Value,
+    field: &str,
+) -> Result<T, MigrationError> {
+    let mut response = body
+        .get_mut("subsonic-response")
+        .map(serde_json::Value::take)
+        .ok_or_else(|| MigrationError::Parse("missing subsonic-response".to_string()))?;
+
+    if response.get("status").and_then(|s| s.as_str()) != Some("ok") {
+        let message = response
+            .pointer("/error/message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("request failed");
+        return Err(MigrationError::Server(message.to_string()));
+    }
+
+    let value = response
+        .get_mut(field)
+        .map(serde_json::Value::take)
+        .ok_or_else(|| MigrationError::Parse(format!("missing {}", field)))?;
+    serde_json::from_value(value).map_err(|e| MigrationError::Parse(e.to_string()))
+}
+
+impl From<AlbumWithSongs> for RemoteAlbum {
+    fn from(album: AlbumWithSongs) -> Self {
+        RemoteAlbum {
+            artist: album.artist,
+            title: album.name,
+            year: album.year,
+            musicbrainz_id: album.music_brainz_id.filter(|id| !id.is_empty()),
+            tracks: album
+                .song
+                .into_iter()
+                .map(|song| RemoteTrack {
+                    remote_id: song.id,
+                    title: song.title,
+                    disc_number: song.disc_number,
+                    track_number: song.track,
+                    play_count: song.play_count,
+                    rating: song.user_rating.filter(|r| (1..=5).contains(r)),
+                    last_played_at: song.played,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<PlaylistWithSongs> for RemotePlaylist {
+    fn from(playlist: PlaylistWithSongs) -> Self {
+        RemotePlaylist {
+            name: playlist.name,
+            track_ids: playlist.entry.into_iter().map(|e| e.id).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn album_response_converts_songs() {
+        let body = serde_json::json!({
+            "subsonic-response": {
+                "status": "ok",
+                "album": {
+                    "id": "al-1",
+                    "name": "Pacific Standard",
+                    "artist": "Glass Harbor",
+                    "year": 2001,
+                    "musicBrainzId": "",
+                    "song": [
+                        {
+                            "id": "so-1",
+                            "title": "Coastal",
+                            "track": 1,
+                            "discNumber": 1,
+                            "playCount": 42,
+                            "userRating": 5,
+                            "played": "2024-03-01T20:15:00Z"
+                        },
+                        { "id": "so-2", "title": "Tide Pool", "track": 2 }
+                    ]
+                }
+            }
+        });
+
+        let album: RemoteAlbum = extract_field::<AlbumWithSongs>(body, "album")
+            .unwrap()
+            .into();
+
+        assert_eq!(album.title, "Pacific Standard");
+        assert_eq!(album.musicbrainz_id, None);
+        assert_eq!(album.tracks[0].play_count, 42);
+        assert_eq!(album.tracks[0].rating, Some(5));
+        assert_eq!(
+            album.tracks[0].last_played_at.unwrap().to_rfc3339(),
+            "2024-03-01T20:15:00+00:00"
+        );
+        assert_eq!(album.tracks[1].play_count, 0);
+        assert_eq!(album.tracks[1].rating, None);
+    }
+
+    #[test]
+    fn failed_response_is_a_server_error() {
+        let body = serde_json::json!({
+            "subsonic-response": {
+                "status": "failed",
+                "error": { "code": 40, "message": "Wrong username or password" }
+            }
+        });
+
+        let result = extract_field::<Playlists>(body, "playlists");
+        assert!(matches!(
+            result,
+            Err(MigrationError::Server(message)) if message == "Wrong username or password"
+        ));
+    }
+}
@@ -0,0 +1,256 @@
+//! Plex Media Server client
+//!
+//! Authenticates with an `X-Plex-Token`. Only music libraries are read, and
+//! smart playlists are skipped since their contents are computed by Plex.
+
+use super::{MigrationError, RemoteAlbum, RemoteLibrary, RemotePlaylist, RemoteTrack};
+use chrono::{DateTime, Utc};
+use reqwest::{header, Client};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Plex metadata type for albums in `/library/sections/{key}/all`
+const TYPE_ALBUM: &str = "9";
+/// Plex metadata type for tracks
+const TYPE_TRACK: &str = "10";
+
+#[derive(Debug, Deserialize)]
+struct Envelope<T> {
+    #[serde(rename = "MediaContainer")]
+    media_container: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sections {
+    #[serde(rename = "Directory", default)]
+    directory: Vec<Section>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Section {
+    key: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataList {
+    #[serde(rename = "Metadata", default)]
+    metadata: Vec<Metadata>,
+}
+
+/// The fields we use from Plex's shared album/track/playlist metadata shape
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    rating_key: String,
+    #[serde(default)]
+    title: String,
+    parent_rating_key: Option<String>,
+    parent_title: Option<String>,
+    year: Option<i32>,
+    index: Option<i32>,
+    parent_index: Option<i32>,
+    #[serde(default)]
+    view_count: i64,
+    /// 0 to 10, in half-star steps
+    user_rating: Option<f64>,
+    /// Unix timestamp
+    last_viewed_at: Option<i64>,
+    #[serde(default)]
+    smart: bool,
+    #[serde(rename = "Guid", default)]
+    guids: Vec<Guid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Guid {
+    id: String,
+}
+
+#[derive(Clone)]
+pub struct PlexClient {
+    client: Client,
+    base_url: String,
+    token: String,
+}
+
+impl PlexClient {
+    /// Create a client for the server at `base_url` (e.g. "http://nas:32400")
+    pub fn new(base_url: &str, token: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    /// Fetch every album and track from all music libraries, plus all
+    /// regular audio playlists.
+    pub async fn fetch_library(&self) -> Result<RemoteLibrary, MigrationError> {
+        let sections: Sections = self.get("/library/sections", &[]).await?;
+
+        let mut albums = Vec::new();
+        for section in sections.directory.iter().filter(|s| s.kind == "artist") {
+            let path = format!("/library/sections/{}/all", section.key);
+            let section_albums: MetadataList = self
+                .get(&path, &[("type", TYPE_ALBUM), ("includeGuids", "1")])
+                .await?;
+            let section_tracks: MetadataList = self.get(&path, &[("type", TYPE_TRACK)]).await?;
+            albums.extend(group_albums(
+                section_albums.metadata,
+                section_tracks.metadata,
+            ));
+        }
+
+        let summaries: MetadataList = self.get("/playlists", &[("playlistType", "audio")]).await?;
+        let mut playlists = Vec::new();
+        for summary in summaries.metadata.into_iter().filter(|p| !p.smart) {
+            let items: MetadataList = self
+                .get(&format!("/playlists/{}/items", summary.rating_key), &[])
+                .await?;
+            playlists.push(RemotePlaylist {
+                name: summary.title,
+                track_ids: items.metadata.into_iter().map(|m| m.rating_key).collect(),
+            });
+        }
+
+        info!(
+            "Fetched {} albums and {} playlists from Plex",
+            albums.len(),
+            playlists.len()
+        );
+
+        Ok(RemoteLibrary { albums, playlists })
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T, MigrationError> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .header("X-Plex-Token", &self.token)
+            .header(header::ACCEPT, "application/json")
+            .query(params)
+            .send()
+            .await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(MigrationError::Server("Invalid Plex token".to_string()));
+        }
+        let envelope: Envelope<T> = response.error_for_status()?.json().await?;
+        Ok(envelope.media_container)
+    }
+}
+
+/// Attach tracks to their albums via `parentRatingKey`
+fn group_albums(albums: Vec<Metadata>, tracks: Vec<Metadata>) -> Vec<RemoteAlbum> {
+    let mut tracks_by_album: HashMap<String, Vec<RemoteTrack>> = HashMap::new();
+    for track in tracks {
+        let Some(album_key) = track.parent_rating_key.clone() else {
+            continue;
+        };
+        tracks_by_album
+            .entry(album_key)
+            .or_default()
+            .push(RemoteTrack {
+                remote_id: track.rating_key,
+                title: track.title,
+                disc_number: track.parent_index,
+                track_number: track.index,
+                play_count: track.view_count,
+                rating: track
+                    .user_rating
+                    .map(|r| (r / 2.0).round() as i32)
+                    .filter(|r| (1..=5).contains(r)),
+                last_played_at: track
+                    .last_viewed_at
+                    .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0)),
+            });
+    }
+
+    albums
+        .into_iter()
+        .map(|album| RemoteAlbum {
+            tracks: tracks_by_album
+                .remove(&album.rating_key)
+                .unwrap_or_default(),
+            artist: album.parent_title.unwrap_or_default(),
+            title: album.title,
+            year: album.year,
+            musicbrainz_id: album
+                .guids
+                .iter()
+                .find_map(|g| g.id.strip_prefix("mbid://"))
+                .map(String::from),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_are_grouped_under_albums() {
+        let albums: Envelope<MetadataList> = serde_json::from_value(serde_json::json!({
+            "MediaContainer": {
+                "Metadata": [{
+                    "ratingKey": "100",
+                    "title": "Pacific Standard",
+                    "parentTitle": "Glass Harbor",
+                    "year": 2001,
+                    "Guid": [
+                        { "id": "mbid://0f4a1c8e-3b7d-4a5e-9c2f-6d8e1b3a5c7d" },
+                        { "id": "plex://album/5d07c" }
+                    ]
+                }]
+            }
+        }))
+        .unwrap();
+        let tracks: Envelope<MetadataList> = serde_json::from_value(serde_json::json!({
+            "MediaContainer": {
+                "Metadata": [
+                    {
+                        "ratingKey": "101",
+                        "parentRatingKey": "100",
+                        "title": "Coastal",
+                        "index": 1,
+                        "parentIndex": 1,
+                        "viewCount": 12,
+                        "userRating": 7.0,
+                        "lastViewedAt": 1700000000
+                    },
+                    { "ratingKey": "102", "parentRatingKey": "100", "title": "Tide Pool" },
+                    { "ratingKey": "201", "parentRatingKey": "200", "title": "Elsewhere" }
+                ]
+            }
+        }))
+        .unwrap();
+
+        let grouped = group_albums(
+            albums.media_container.metadata,
+            tracks.media_container.metadata,
+        );
+
+        assert_eq!(grouped.len(), 1);
+        let album = &grouped[0];
+        assert_eq!(album.artist, "Glass Harbor");
+        assert_eq!(
+            album.musicbrainz_id.as_deref(),
+            Some("0f4a1c8e-3b7d-4a5e-9c2f-6d8e1b3a5c7d")
+        );
+        assert_eq!(album.tracks.len(), 2);
+        assert_eq!(album.tracks[0].play_count, 12);
+        assert_eq!(album.tracks[0].rating, Some(4));
+        assert_eq!(
+            album.tracks[0].last_played_at.unwrap().timestamp(),
+            1_700_000_000
+        );
+        assert_eq!(album.tracks[1].rating, None);
+    }
+}
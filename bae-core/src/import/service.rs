@@ -20,22 +20,22 @@ use crate::import::types::TorrentSource;
 use crate::import::types::{
     CueFlacMetadata, DiscoveredFile, ImportCommand, ImportPhase, ImportProgress, TrackFile,
 };
+use crate::import::ImportError;
 use crate::keys::KeyService;
+use crate::library::duplicates::file_sha256;
 use crate::library::{LibraryManager, SharedLibraryManager};
 use crate::library_dir::LibraryDir;
 use crate::storage::{ReleaseStorage, ReleaseStorageImpl};
 #[cfg(feature = "torrent")]
 use crate::torrent::LazyTorrentManager;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Map from filename to vec of (track_id, start_byte, end_byte) for progress reporting
 type TrackProgressMap = HashMap<String, Vec<(String, i64, i64)>>;
 use std::sync::Arc;
 use tokio::sync::{broadcast, mpsc};
-#[cfg(any(feature = "torrent", feature = "cd-rip"))]
-use tracing::warn;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 /// Calculate track progress percentage based on bytes written.
 ///
@@ -97,10 +97,10 @@ impl ImportService {
         runtime_handle.spawn(async move {
             while let Some(request) = scan_rx.recv().await {
                 let tx = scan_events_tx.clone();
-                let path = request.path;
+                let ScanRequest { path, filters } = request;
 
                 let result = tokio::task::spawn_blocking(move || {
-                    scan_for_candidates_with_callback(path, |candidate| {
+                    scan_for_candidates_with_callback(path, &filters, |candidate| {
                         let _ = tx.send(ScanEvent::Candidate(candidate));
                     })
                 })
@@ -381,6 +381,16 @@ impl ImportService {
             {
                 error!("Failed to mark release as failed: {}", db_err);
             }
+            // Otherwise the import stays active and is resumed on next launch
+            if let Some(import_id) = &import_id_for_error {
+                if let Err(db_err) = self
+                    .database
+                    .update_import_error(import_id, &e.to_string())
+                    .await
+                {
+                    error!("Failed to mark import as failed: {}", db_err);
+                }
+            }
             let _ = self.progress_tx.send(ImportProgress::Failed {
                 id: release_id_for_error,
                 error: e,
@@ -408,7 +418,7 @@ impl ImportService {
     fn analyze_cue_flac(
         cue_flac_metadata: &HashMap<PathBuf, CueFlacMetadata>,
         file_data: Option<&[(String, Vec<u8>, PathBuf)]>,
-    ) -> Result<HashMap<PathBuf, CueFlacAnalysis>, String> {
+    ) -> Result<HashMap<PathBuf, CueFlacAnalysis>, ImportError> {
         use crate::cue_flac::CueFlacProcessor;
 
         let mut result = HashMap::new();
@@ -426,14 +436,21 @@ impl ImportService {
 
             let data = match data {
                 Some(d) => d,
-                None => std::fs::read(flac_path)
-                    .map_err(|e| format!("Failed to read FLAC {:?}: {}", flac_path, e))?,
+                None => std::fs::read(flac_path).map_err(|e| {
+                    ImportError::Files(format!("Failed to read FLAC {:?}: {}", flac_path, e))
+                })?,
             };
 
-            let flac_info = CueFlacProcessor::analyze_flac_data(&data)
-                .map_err(|e| format!("Failed to analyze FLAC {:?}: {}", flac_path, e))?;
-            let flac_headers = CueFlacProcessor::extract_flac_headers_from_data(&data)
-                .map_err(|e| format!("Failed to extract FLAC headers {:?}: {}", flac_path, e))?;
+            let flac_info = CueFlacProcessor::analyze_flac_data(&data).map_err(|e| {
+                ImportError::Files(format!("Failed to analyze FLAC {:?}: {}", flac_path, e))
+            })?;
+            let flac_headers =
+                CueFlacProcessor::extract_flac_headers_from_data(&data).map_err(|e| {
+                    ImportError::Files(format!(
+                        "Failed to extract FLAC headers {:?}: {}",
+                        flac_path, e
+                    ))
+                })?;
             let dense_seektable = CueFlacProcessor::build_dense_seektable(&data, &flac_info);
 
             result.insert(
@@ -460,7 +477,7 @@ impl ImportService {
         tracks_to_files: &[TrackFile],
         file_data: &[(String, Vec<u8>, PathBuf)],
         cue_flac_analysis: &HashMap<PathBuf, CueFlacAnalysis>,
-    ) -> Result<TrackProgressMap, String> {
+    ) -> Result<TrackProgressMap, ImportError> {
         use crate::cue_flac::CueFlacProcessor;
 
         let mut result: TrackProgressMap = HashMap::new();
@@ -473,7 +490,7 @@ impl ImportService {
             let filename = flac_path
                 .file_name()
                 .and_then(|n| n.to_str())
-                .ok_or_else(|| format!("Invalid FLAC path: {:?}", flac_path))?
+                .ok_or_else(|| ImportError::Files(format!("Invalid FLAC path: {:?}", flac_path)))?
                 .to_string();
 
             let flac_tracks: Vec<_> = tracks_to_files
@@ -505,7 +522,9 @@ impl ImportService {
                 .file_path
                 .file_name()
                 .and_then(|n| n.to_str())
-                .ok_or_else(|| format!("Invalid file path: {:?}", track_file.file_path))?
+                .ok_or_else(|| {
+                    ImportError::Files(format!("Invalid file path: {:?}", track_file.file_path))
+                })?
                 .to_string();
 
             if result.contains_key(&filename) {
@@ -538,7 +557,7 @@ impl ImportService {
         cover_image_path: Option<&Path>,
         remote_cover_set: bool,
         import_id: Option<&str>,
-    ) -> Result<(), String> {
+    ) -> Result<(), ImportError> {
         let library_manager = self.library_manager.get();
 
         // Create image records (filesystem operations, done before the DB transaction)
@@ -556,7 +575,7 @@ impl ImportService {
         library_manager
             .finalize_import(&audio_formats, track_ids, release_id, import_id)
             .await
-            .map_err(|e| format!("Failed to finalize import: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to finalize import: {}", e)))?;
 
         // Send progress notifications after successful commit
         for track_id in track_ids {
@@ -591,12 +610,14 @@ impl ImportService {
         cover_image_path: Option<&Path>,
         import_id: &str,
         remote_cover_set: bool,
-    ) -> Result<(), String> {
+    ) -> Result<(), ImportError> {
         let library_manager = self.library_manager.get();
         library_manager
             .mark_release_importing(&db_release.id)
             .await
-            .map_err(|e| format!("Failed to mark release as importing: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to mark release as importing: {}", e))
+            })?;
 
         let _ = self.progress_tx.send(ImportProgress::Started {
             id: db_release.id.clone(),
@@ -607,7 +628,9 @@ impl ImportService {
         self.database
             .set_release_managed_locally(&db_release.id, true)
             .await
-            .map_err(|e| format!("Failed to mark release as managed locally: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to mark release as managed locally: {}", e))
+            })?;
 
         let storage = self.create_storage(&db_release.id);
         let total_files = discovered_files.len();
@@ -623,11 +646,11 @@ impl ImportService {
                 .path
                 .file_name()
                 .and_then(|n| n.to_str())
-                .ok_or_else(|| format!("Invalid filename: {:?}", file.path))?
+                .ok_or_else(|| ImportError::Files(format!("Invalid filename: {:?}", file.path)))?
                 .to_string();
-            let data = tokio::fs::read(&file.path)
-                .await
-                .map_err(|e| format!("Failed to read file {:?}: {}", file.path, e))?;
+            let data = tokio::fs::read(&file.path).await.map_err(|e| {
+                ImportError::Files(format!("Failed to read file {:?}: {}", file.path, e))
+            })?;
             file_data.push((filename, data, file.path.clone()));
         }
 
@@ -643,8 +666,31 @@ impl ImportService {
         let release_total_bytes: usize = file_data.iter().map(|(_, data, _)| data.len()).sum();
         let mut release_bytes_written = 0usize;
 
+        // Files stored before an interruption are already in storage
+        let already_stored: HashSet<String> = self
+            .database
+            .get_import_files(import_id)
+            .await
+            .map_err(|e| ImportError::Database(format!("Failed to load import files: {}", e)))?
+            .into_iter()
+            .filter(|f| f.stored)
+            .map(|f| f.source_path)
+            .collect();
+
         let import_id_owned = import_id.to_string();
-        for (idx, (filename, data, _path)) in file_data.iter().enumerate() {
+        for (idx, (filename, data, path)) in file_data.iter().enumerate() {
+            let source_path = path.to_string_lossy().to_string();
+            if already_stored.contains(&source_path) {
+                release_bytes_written += data.len();
+                info!(
+                    "Skipping file {}/{}: {} (stored before interruption)",
+                    idx + 1,
+                    total_files,
+                    filename
+                );
+                continue;
+            }
+
             let track_infos = file_to_tracks.get(filename).cloned().unwrap_or_default();
             let progress_tx = self.progress_tx.clone();
             let release_id = db_release.id.clone();
@@ -687,7 +733,15 @@ impl ImportService {
                     }),
                 )
                 .await
-                .map_err(|e| format!("Failed to store file {}: {}", filename, e))?;
+                .map_err(|e| {
+                    ImportError::Storage(format!("Failed to store file {}: {}", filename, e))
+                })?;
+            self.database
+                .mark_import_file_stored(import_id, &source_path)
+                .await
+                .map_err(|e| {
+                    ImportError::Database(format!("Failed to update import file: {}", e))
+                })?;
 
             release_bytes_written += file_size;
             info!(
@@ -703,7 +757,7 @@ impl ImportService {
         let files = library_manager
             .get_files_for_release(&db_release.id)
             .await
-            .map_err(|e| format!("Failed to get files: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to get files: {}", e)))?;
         let file_ids: HashMap<String, String> = files
             .into_iter()
             .map(|f| (f.original_filename, f.id))
@@ -752,7 +806,7 @@ impl ImportService {
         library_manager: &LibraryManager,
         cover_image_path: Option<&Path>,
         cover_already_set: bool,
-    ) -> Result<(), String> {
+    ) -> Result<(), ImportError> {
         use crate::db::{DbLibraryImage, LibraryImageType};
 
         if cover_already_set {
@@ -827,6 +881,10 @@ impl ImportService {
         let file_size = std::fs::metadata(&cache_path)
             .map(|m| m.len() as i64)
             .unwrap_or(0);
+        let blurhash = match std::fs::read(&cache_path) {
+            Ok(bytes) => crate::image_placeholder::blurhash_blocking(bytes).await,
+            Err(_) => None,
+        };
 
         info!("Cached cover art to {}", cache_path.display());
 
@@ -839,6 +897,7 @@ impl ImportService {
             height: None,
             source: "local".to_string(),
             source_url: Some(source_url),
+            blurhash,
             updated_at: chrono::Utc::now(),
             created_at: chrono::Utc::now(),
         };
@@ -846,12 +905,14 @@ impl ImportService {
         library_manager
             .upsert_library_image(&db_image)
             .await
-            .map_err(|e| format!("Failed to upsert library image: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to upsert library image: {}", e)))?;
 
         library_manager
             .set_album_cover_release(album_id, release_id)
             .await
-            .map_err(|e| format!("Failed to set album cover release: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to set album cover release: {}", e))
+            })?;
 
         Ok(())
     }
@@ -907,6 +968,7 @@ impl ImportService {
             height: None,
             source: source.to_string(),
             source_url: Some(url.to_string()),
+            blurhash: crate::image_placeholder::blurhash_blocking(bytes.clone()).await,
             updated_at: chrono::Utc::now(),
             created_at: chrono::Utc::now(),
         };
@@ -952,7 +1014,7 @@ impl ImportService {
         cue_flac_analysis: &HashMap<PathBuf, CueFlacAnalysis>,
         file_ids: &HashMap<String, String>,
         preloaded_files: Option<&[(String, Vec<u8>, PathBuf)]>,
-    ) -> Result<Vec<crate::db::DbAudioFormat>, String> {
+    ) -> Result<Vec<crate::db::DbAudioFormat>, ImportError> {
         use crate::cue_flac::CueFlacProcessor;
         use crate::db::DbAudioFormat;
 
@@ -982,11 +1044,11 @@ impl ImportService {
                     .tracks
                     .get(track_idx)
                     .ok_or_else(|| {
-                        format!(
+                        ImportError::Files(format!(
                             "CUE track index {} out of bounds for {}",
                             track_idx,
                             track_file.file_path.display()
-                        )
+                        ))
                     })?;
 
                 let audio_start_ms = cue_track.audio_start_ms();
@@ -1043,8 +1105,9 @@ impl ImportService {
                     })
                     .collect();
 
-                let seektable_json = serde_json::to_string(&track_seektable)
-                    .map_err(|e| format!("Failed to serialize seektable: {}", e))?;
+                let seektable_json = serde_json::to_string(&track_seektable).map_err(|e| {
+                    ImportError::Files(format!("Failed to serialize seektable: {}", e))
+                })?;
 
                 // Look up file_id by filename
                 let filename = track_file
@@ -1074,10 +1137,10 @@ impl ImportService {
             } else {
                 // For regular FLAC files (not CUE), extract headers and seektable for seek support
                 if audio_content_type != ContentType::Flac {
-                    return Err(format!(
+                    return Err(ImportError::Files(format!(
                         "Unsupported audio format '{}' - only FLAC is supported",
                         ext
-                    ));
+                    )));
                 }
 
                 // Use preloaded data if available, otherwise read from disk
@@ -1087,8 +1150,9 @@ impl ImportService {
                 let file_data: &[u8] = if let Some((_, data, _)) = preloaded {
                     data
                 } else {
-                    fallback = std::fs::read(&track_file.file_path)
-                        .map_err(|e| format!("Failed to read FLAC file: {}", e))?;
+                    fallback = std::fs::read(&track_file.file_path).map_err(|e| {
+                        ImportError::Files(format!("Failed to read FLAC file: {}", e))
+                    })?;
                     &fallback
                 };
 
@@ -1097,11 +1161,12 @@ impl ImportService {
                     .map(|h| h.headers);
 
                 let flac_info = CueFlacProcessor::analyze_flac_data(file_data)
-                    .map_err(|e| format!("Failed to analyze FLAC: {}", e))?;
+                    .map_err(|e| ImportError::Files(format!("Failed to analyze FLAC: {}", e)))?;
 
                 let seektable = CueFlacProcessor::build_dense_seektable(file_data, &flac_info);
-                let seektable_json = serde_json::to_string(&seektable.entries)
-                    .map_err(|e| format!("Failed to serialize seektable: {}", e))?;
+                let seektable_json = serde_json::to_string(&seektable.entries).map_err(|e| {
+                    ImportError::Files(format!("Failed to serialize seektable: {}", e))
+                })?;
 
                 // Look up file_id by filename
                 let filename = track_file
@@ -1139,12 +1204,14 @@ impl ImportService {
         cover_image_path: Option<&Path>,
         import_id: &str,
         remote_cover_set: bool,
-    ) -> Result<(), String> {
+    ) -> Result<(), ImportError> {
         let library_manager = self.library_manager.get();
         library_manager
             .mark_release_importing(&db_release.id)
             .await
-            .map_err(|e| format!("Failed to mark release as importing: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to mark release as importing: {}", e))
+            })?;
 
         let _ = self.progress_tx.send(ImportProgress::Started {
             id: db_release.id.clone(),
@@ -1164,14 +1231,19 @@ impl ImportService {
         let unmanaged_root = discovered_files
             .first()
             .and_then(|f| f.path.parent())
-            .ok_or_else(|| "No files to determine unmanaged path".to_string())?;
-        let unmanaged_path_str = unmanaged_root
-            .to_str()
-            .ok_or_else(|| format!("Cannot convert path to string: {:?}", unmanaged_root))?;
+            .ok_or_else(|| {
+                ImportError::Files("No files to determine unmanaged path".to_string())
+            })?;
+        let unmanaged_path_str = unmanaged_root.to_str().ok_or_else(|| {
+            ImportError::Files(format!(
+                "Cannot convert path to string: {:?}",
+                unmanaged_root
+            ))
+        })?;
         self.database
             .set_release_unmanaged(&db_release.id, unmanaged_path_str)
             .await
-            .map_err(|e| format!("Failed to set unmanaged path: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to set unmanaged path: {}", e)))?;
 
         let file_to_tracks: HashMap<String, Vec<String>> = {
             let mut map: HashMap<String, Vec<String>> = HashMap::new();
@@ -1196,18 +1268,18 @@ impl ImportService {
                 .path
                 .strip_prefix(unmanaged_root)
                 .map_err(|_| {
-                    format!(
+                    ImportError::Files(format!(
                         "File {:?} is not under unmanaged root {:?}",
                         file.path, unmanaged_root
-                    )
+                    ))
                 })?
                 .to_str()
-                .ok_or_else(|| format!("Invalid path: {:?}", file.path))?;
+                .ok_or_else(|| ImportError::Files(format!("Invalid path: {:?}", file.path)))?;
             let bare_filename = file
                 .path
                 .file_name()
                 .and_then(|n| n.to_str())
-                .ok_or_else(|| format!("Invalid filename: {:?}", file.path))?;
+                .ok_or_else(|| ImportError::Files(format!("Invalid filename: {:?}", file.path)))?;
             let ext = file
                 .path
                 .extension()
@@ -1215,12 +1287,18 @@ impl ImportService {
                 .unwrap_or("bin")
                 .to_lowercase();
 
-            let db_file = DbFile::new(
+            let mut db_file = DbFile::new(
                 &db_release.id,
                 relative_path,
                 file.size as i64,
                 ContentType::from_extension(&ext),
             );
+            if db_file.content_type.is_audio() {
+                match file_sha256(&file.path).await {
+                    Ok(sha256) => db_file = db_file.with_content_sha256(sha256),
+                    Err(e) => warn!("Failed to hash {:?}: {}", file.path, e),
+                }
+            }
             file_ids.insert(bare_filename.to_string(), db_file.id.clone());
             db_files.push(db_file);
         }
@@ -1228,7 +1306,7 @@ impl ImportService {
         library_manager
             .batch_add_files(&db_files)
             .await
-            .map_err(|e| format!("Failed to add file records: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to add file records: {}", e)))?;
 
         // Send progress for each file
         for (idx, file) in discovered_files.iter().enumerate() {
@@ -1299,12 +1377,14 @@ impl ImportService {
         torrent_source: TorrentSource,
         torrent_metadata: TorrentImportMetadata,
         selected_cover: Option<CoverSelection>,
-    ) -> Result<(), String> {
+    ) -> Result<(), ImportError> {
         let library_manager = self.library_manager.get();
         library_manager
             .mark_release_importing(&db_release.id)
             .await
-            .map_err(|e| format!("Failed to mark release as importing: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to mark release as importing: {}", e))
+            })?;
 
         info!(
             "Starting torrent import with None storage for '{}'",
@@ -1322,18 +1402,17 @@ impl ImportService {
             .get()
             .add_torrent(torrent_source.clone())
             .await
-            .map_err(|e| format!("Failed to add torrent: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to add torrent: {}", e)))?;
 
         torrent_handle
             .wait_for_metadata()
             .await
-            .map_err(|e| format!("Failed to wait for metadata: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to wait for metadata: {}", e)))?;
 
         loop {
-            let progress = torrent_handle
-                .progress()
-                .await
-                .map_err(|e| format!("Failed to check torrent progress: {}", e))?;
+            let progress = torrent_handle.progress().await.map_err(|e| {
+                ImportError::Acquire(format!("Failed to check torrent progress: {}", e))
+            })?;
             let percent = (progress * 100.0) as u8;
             let _ = self.progress_tx.send(ImportProgress::Progress {
                 id: db_release.id.clone(),
@@ -1353,7 +1432,7 @@ impl ImportService {
         let torrent_files = torrent_handle
             .get_file_list()
             .await
-            .map_err(|e| format!("Failed to get torrent file list: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to get torrent file list: {}", e)))?;
 
         let temp_dir = std::env::temp_dir();
         let torrent_save_dir = temp_dir.join(&torrent_metadata.torrent_name);
@@ -1395,7 +1474,9 @@ impl ImportService {
         let torrent_root = discovered_files
             .first()
             .and_then(|f| f.path.parent())
-            .ok_or_else(|| "No files to determine unmanaged path".to_string())?;
+            .ok_or_else(|| {
+                ImportError::Files("No files to determine unmanaged path".to_string())
+            })?;
         if let Some(p) = torrent_root.to_str() {
             let _ = self.database.set_release_unmanaged(&db_release.id, p).await;
         }
@@ -1421,19 +1502,25 @@ impl ImportService {
                 .unwrap_or("bin")
                 .to_lowercase();
 
-            let db_file = DbFile::new(
+            let mut db_file = DbFile::new(
                 &db_release.id,
                 relative_path,
                 file.size as i64,
                 ContentType::from_extension(&ext),
             );
+            if db_file.content_type.is_audio() {
+                match file_sha256(&file.path).await {
+                    Ok(sha256) => db_file = db_file.with_content_sha256(sha256),
+                    Err(e) => warn!("Failed to hash {:?}: {}", file.path, e),
+                }
+            }
             db_files.push(db_file);
         }
 
         library_manager
             .batch_add_files(&db_files)
             .await
-            .map_err(|e| format!("Failed to add file records: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to add file records: {}", e)))?;
 
         for (idx, file) in discovered_files.iter().enumerate() {
             let filename = file.path.file_name().and_then(|n| n.to_str()).unwrap_or("");
@@ -1499,12 +1586,14 @@ impl ImportService {
         torrent_metadata: TorrentImportMetadata,
         seed_after_download: bool,
         selected_cover: Option<CoverSelection>,
-    ) -> Result<(), String> {
+    ) -> Result<(), ImportError> {
         let library_manager = self.library_manager.get();
         library_manager
             .mark_release_importing(&db_release.id)
             .await
-            .map_err(|e| format!("Failed to mark release as importing: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to mark release as importing: {}", e))
+            })?;
 
         info!("Starting torrent import for '{}'", db_album.title);
 
@@ -1519,18 +1608,17 @@ impl ImportService {
             .get()
             .add_torrent(torrent_source.clone())
             .await
-            .map_err(|e| format!("Failed to add torrent: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to add torrent: {}", e)))?;
 
         torrent_handle
             .wait_for_metadata()
             .await
-            .map_err(|e| format!("Failed to wait for metadata: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to wait for metadata: {}", e)))?;
 
         loop {
-            let progress = torrent_handle
-                .progress()
-                .await
-                .map_err(|e| format!("Failed to check torrent progress: {}", e))?;
+            let progress = torrent_handle.progress().await.map_err(|e| {
+                ImportError::Acquire(format!("Failed to check torrent progress: {}", e))
+            })?;
             let percent = (progress * 100.0) as u8;
             let _ = self.progress_tx.send(ImportProgress::Progress {
                 id: db_release.id.clone(),
@@ -1550,7 +1638,7 @@ impl ImportService {
         let torrent_files = torrent_handle
             .get_file_list()
             .await
-            .map_err(|e| format!("Failed to get torrent file list: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to get torrent file list: {}", e)))?;
 
         let temp_dir = std::env::temp_dir();
         let torrent_save_dir = temp_dir.join(&torrent_metadata.torrent_name);
@@ -1578,15 +1666,16 @@ impl ImportService {
 
         // Detect CUE/FLAC
         let file_paths: Vec<PathBuf> = discovered_files.iter().map(|f| f.path.clone()).collect();
-        let cue_flac_pairs =
-            crate::cue_flac::CueFlacProcessor::detect_cue_flac_from_paths(&file_paths)
-                .map_err(|e| format!("Failed to detect CUE/FLAC files: {}", e))?;
+        let cue_flac_pairs = crate::cue_flac::CueFlacProcessor::detect_cue_flac_from_paths(
+            &file_paths,
+        )
+        .map_err(|e| ImportError::Files(format!("Failed to detect CUE/FLAC files: {}", e)))?;
 
         let mut cue_flac_metadata = HashMap::new();
         for pair in cue_flac_pairs {
             let flac_path = pair.flac_path.clone();
             let cue_sheet = crate::cue_flac::CueFlacProcessor::parse_cue_sheet(&pair.cue_path)
-                .map_err(|e| format!("Failed to parse CUE sheet: {}", e))?;
+                .map_err(|e| ImportError::Files(format!("Failed to parse CUE sheet: {}", e)))?;
             let metadata = CueFlacMetadata {
                 cue_sheet,
                 cue_path: pair.cue_path,
@@ -1668,7 +1757,7 @@ impl ImportService {
         drive_path: PathBuf,
         toc: CdToc,
         cover_image_path: Option<&Path>,
-    ) -> Result<(), String> {
+    ) -> Result<(), ImportError> {
         use crate::cd::{CdDrive, CdRipper, CueGenerator, LogGenerator};
         use crate::import::track_to_file_mapper::map_tracks_to_files;
 
@@ -1676,7 +1765,9 @@ impl ImportService {
         library_manager
             .mark_release_importing(&db_release.id)
             .await
-            .map_err(|e| format!("Failed to mark release as importing: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to mark release as importing: {}", e))
+            })?;
 
         info!("Starting CD import for '{}'", db_album.title);
 
@@ -1689,7 +1780,7 @@ impl ImportService {
         let temp_dir = std::env::temp_dir().join(format!("bae_cd_rip_{}", uuid::Uuid::new_v4()));
         tokio::fs::create_dir_all(&temp_dir)
             .await
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to create temp directory: {}", e)))?;
 
         let drive = CdDrive {
             device_path: drive_path.clone(),
@@ -1716,12 +1807,21 @@ impl ImportService {
         let rip_results = ripper
             .rip_all_tracks(Some(rip_progress_tx))
             .await
-            .map_err(|e| format!("Failed to rip CD: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to rip CD: {}", e)))?;
 
         info!("CD ripping completed, {} tracks ripped", rip_results.len());
 
+        let hidden_track = ripper
+            .rip_hidden_track()
+            .await
+            .map_err(|e| ImportError::Acquire(format!("Failed to rip hidden track: {}", e)))?;
+
         // Generate CUE and log files
-        let artist_name = "Unknown Artist".to_string();
+        let artist_name = toc
+            .cd_text
+            .as_ref()
+            .and_then(|text| text.performer.clone())
+            .unwrap_or_else(|| "Unknown Artist".to_string());
         let flac_filename = format!("{}.flac", db_album.title.replace("/", "_"));
 
         let cue_sheet = CueGenerator::generate_cue_sheet(
@@ -1734,18 +1834,18 @@ impl ImportService {
 
         let cue_path = temp_dir.join(format!("{}.cue", db_album.title.replace("/", "_")));
         CueGenerator::write_cue_file(&cue_sheet, &toc.disc_id, &flac_filename, &cue_path)
-            .map_err(|e| format!("Failed to write CUE file: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to write CUE file: {}", e)))?;
 
         let log_path = temp_dir.join(format!("{}.log", db_album.title.replace("/", "_")));
         LogGenerator::write_log_file(&toc, &rip_results, &drive.name, &log_path)
-            .map_err(|e| format!("Failed to write log file: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to write log file: {}", e)))?;
 
         // Build discovered files list
         let mut discovered_files = Vec::new();
         for result in &rip_results {
             let metadata = tokio::fs::metadata(&result.output_path)
                 .await
-                .map_err(|e| format!("Failed to get file size: {}", e))?;
+                .map_err(|e| ImportError::Files(format!("Failed to get file size: {}", e)))?;
             discovered_files.push(DiscoveredFile {
                 path: result.output_path.clone(),
                 size: metadata.len(),
@@ -1754,7 +1854,7 @@ impl ImportService {
 
         let cue_metadata = tokio::fs::metadata(&cue_path)
             .await
-            .map_err(|e| format!("Failed to get CUE file size: {}", e))?;
+            .map_err(|e| ImportError::Files(format!("Failed to get CUE file size: {}", e)))?;
         discovered_files.push(DiscoveredFile {
             path: cue_path.clone(),
             size: cue_metadata.len(),
@@ -1762,7 +1862,7 @@ impl ImportService {
 
         let log_metadata = tokio::fs::metadata(&log_path)
             .await
-            .map_err(|e| format!("Failed to get log file size: {}", e))?;
+            .map_err(|e| ImportError::Files(format!("Failed to get log file size: {}", e)))?;
         discovered_files.push(DiscoveredFile {
             path: log_path.clone(),
             size: log_metadata.len(),
@@ -1771,14 +1871,25 @@ impl ImportService {
         // Map tracks to files
         let mapping_result = map_tracks_to_files(&db_tracks, &discovered_files)
             .await
-            .map_err(|e| format!("Failed to map tracks to files: {}", e))?;
+            .map_err(|e| ImportError::Files(format!("Failed to map tracks to files: {}", e)))?;
 
         let tracks_to_files = mapping_result.track_files.clone();
         let cue_flac_metadata = mapping_result.cue_flac_metadata.clone();
 
+        // Hidden track one audio is kept with the release but not mapped to a track
+        if let Some(hidden) = &hidden_track {
+            let metadata = tokio::fs::metadata(&hidden.output_path)
+                .await
+                .map_err(|e| ImportError::Files(format!("Failed to get file size: {}", e)))?;
+            discovered_files.push(DiscoveredFile {
+                path: hidden.output_path.clone(),
+                size: metadata.len(),
+            });
+        }
+
         crate::import::handle::extract_and_store_durations(library_manager, &tracks_to_files)
             .await
-            .map_err(|e| format!("Failed to extract durations: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to extract durations: {}", e)))?;
 
         // Use the import ID as a placeholder
         let import_id = format!("cd-{}", db_release.id);
@@ -1816,14 +1927,16 @@ impl ImportService {
         drive_path: PathBuf,
         toc: CdToc,
         _cover_image_path: Option<&Path>,
-    ) -> Result<(), String> {
+    ) -> Result<(), ImportError> {
         use crate::cd::{CdDrive, CdRipper};
 
         let library_manager = self.library_manager.get();
         library_manager
             .mark_release_importing(&db_release.id)
             .await
-            .map_err(|e| format!("Failed to mark release as importing: {}", e))?;
+            .map_err(|e| {
+                ImportError::Database(format!("Failed to mark release as importing: {}", e))
+            })?;
 
         info!(
             "Starting CD import with no storage for '{}' ({} tracks)",
@@ -1839,7 +1952,7 @@ impl ImportService {
         let temp_dir = std::env::temp_dir().join(format!("bae_cd_rip_{}", uuid::Uuid::new_v4()));
         tokio::fs::create_dir_all(&temp_dir)
             .await
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to create temp directory: {}", e)))?;
 
         let drive = CdDrive {
             device_path: drive_path.clone(),
@@ -1850,18 +1963,23 @@ impl ImportService {
         let rip_results = ripper
             .rip_all_tracks(None)
             .await
-            .map_err(|e| format!("Failed to rip CD: {}", e))?;
+            .map_err(|e| ImportError::Acquire(format!("Failed to rip CD: {}", e)))?;
 
         info!("CD ripping completed, {} tracks ripped", rip_results.len());
 
+        let hidden_track = ripper
+            .rip_hidden_track()
+            .await
+            .map_err(|e| ImportError::Acquire(format!("Failed to rip hidden track: {}", e)))?;
+
         // Set unmanaged_path to the temp directory
         if let Some(p) = temp_dir.to_str() {
             let _ = self.database.set_release_unmanaged(&db_release.id, p).await;
         }
 
         // Build and batch-insert file records
-        let mut db_files: Vec<DbFile> = Vec::with_capacity(rip_results.len());
-        for result in &rip_results {
+        let mut db_files: Vec<DbFile> = Vec::with_capacity(rip_results.len() + 1);
+        for result in hidden_track.iter().chain(&rip_results) {
             let filename = result
                 .output_path
                 .file_name()
@@ -1879,7 +1997,7 @@ impl ImportService {
         library_manager
             .batch_add_files(&db_files)
             .await
-            .map_err(|e| format!("Failed to add file records: {}", e))?;
+            .map_err(|e| ImportError::Database(format!("Failed to add file records: {}", e)))?;
 
         for (idx, result) in rip_results.iter().enumerate() {
             let filename = result
@@ -0,0 +1,348 @@
+//! Split a single-file recording into tracks at its silences
+//!
+//! Vinyl and tape rips usually arrive as one file per side. The breaks between
+//! songs are quiet but never digitally silent (surface noise, tape hiss), so the
+//! gap threshold sits well above the digital noise floor. The chosen split
+//! points are written out as a CUE sheet next to the recording, and from there
+//! the release imports like any other CUE/FLAC pair.
+
+use crate::cue_flac::{format_cue_time, CueFlacError, CueFlacProcessor};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::info;
+
+/// Length of each loudness measurement window
+const WINDOW_MS: u64 = 50;
+/// Windows quieter than this (dBFS) can be part of a break between tracks
+const GAP_THRESHOLD_DB: f64 = -40.0;
+/// Quiet passages shorter than this are part of the music
+const MIN_GAP_MS: u64 = 1500;
+/// Decoded per pass, so a whole side never sits in memory as PCM
+const DECODE_CHUNK_MS: u64 = 60_000;
+/// Resolution of the waveform overview
+const WAVEFORM_BUCKETS: usize = 400;
+
+#[derive(Error, Debug)]
+pub enum SplitError {
+    #[error("Only FLAC recordings can be split: {0}")]
+    Unsupported(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("FLAC error: {0}")]
+    Flac(#[from] CueFlacError),
+    #[error("Failed to decode recording: {0}")]
+    Decode(String),
+    #[error("{0} already has a CUE sheet")]
+    CueExists(String),
+}
+
+/// A quiet stretch between tracks, in ms from the start of the recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gap {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+impl Gap {
+    pub fn len_ms(&self) -> u64 {
+        self.end_ms - self.start_ms
+    }
+
+    /// Where the split goes: the middle of the gap
+    pub fn midpoint_ms(&self) -> u64 {
+        self.start_ms + self.len_ms() / 2
+    }
+}
+
+/// Loudness profile of a recording
+#[derive(Debug, Clone)]
+pub struct RecordingAnalysis {
+    pub duration_ms: u64,
+    /// Peak level per bucket (0.0 to 1.0), for drawing the waveform
+    pub waveform: Vec<f32>,
+    /// Breaks between tracks, in time order. Lead-in and run-out aren't gaps.
+    pub gaps: Vec<Gap>,
+}
+
+impl RecordingAnalysis {
+    /// Split points for `track_count` tracks, taken from the longest gaps.
+    ///
+    /// With fewer gaps than needed every gap is used and the user adds the
+    /// rest by hand.
+    pub fn split_points(&self, track_count: usize) -> Vec<u64> {
+        let mut gaps = self.gaps.clone();
+        gaps.sort_by_key(|g| std::cmp::Reverse(g.len_ms()));
+        gaps.truncate(track_count.saturating_sub(1));
+        let mut points: Vec<u64> = gaps.iter().map(Gap::midpoint_ms).collect();
+        points.sort_unstable();
+        points
+    }
+}
+
+/// Decode a FLAC recording and find the gaps between its tracks.
+///
+/// Blocking; run it off the async runtime.
+pub fn analyze_recording(path: &Path) -> Result<RecordingAnalysis, SplitError> {
+    let is_flac = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("flac"));
+    if !is_flac {
+        return Err(SplitError::Unsupported(path.display().to_string()));
+    }
+
+    let data = std::fs::read(path)?;
+    let duration_ms = CueFlacProcessor::analyze_flac_data(&data)?.duration_ms();
+
+    info!(
+        "Analyzing {} ({} ms) for track gaps",
+        path.display(),
+        duration_ms
+    );
+
+    let mut rms = Vec::new();
+    let mut peaks = Vec::new();
+    let mut start_ms = 0;
+    while start_ms < duration_ms {
+        let end_ms = (start_ms + DECODE_CHUNK_MS).min(duration_ms);
+        let decoded = crate::audio_codec::decode_audio(&data, Some(start_ms), Some(end_ms))
+            .map_err(SplitError::Decode)?;
+        if decoded.samples.is_empty() {
+            break;
+        }
+        let full_scale = (1u64 << (decoded.bits_per_sample.clamp(8, 32) - 1)) as f64;
+        let frames_per_window = ((decoded.sample_rate as u64 * WINDOW_MS / 1000) as usize).max(1);
+        for window in decoded
+            .samples
+            .chunks(frames_per_window * decoded.channels.max(1) as usize)
+        {
+            let (r, p) = window_level(window, full_scale);
+            rms.push(r);
+            peaks.push(p);
+        }
+        start_ms = end_ms;
+    }
+
+    Ok(RecordingAnalysis {
+        duration_ms,
+        waveform: waveform(&peaks, WAVEFORM_BUCKETS),
+        gaps: find_gaps(&rms),
+    })
+}
+
+/// Write a CUE sheet for `recording` with a track starting at 0 and at each
+/// split point. Returns the path of the new sheet.
+///
+/// The sheet goes next to the recording with the same stem, which is how the
+/// folder scanner pairs CUE sheets with their FLAC.
+pub fn write_cue_sheet(
+    recording: &Path,
+    split_points_ms: &[u64],
+    performer: &str,
+    album: &str,
+    track_titles: &[String],
+) -> Result<PathBuf, SplitError> {
+    let cue_path = recording.with_extension("cue");
+    if cue_path.exists() {
+        return Err(SplitError::CueExists(recording.display().to_string()));
+    }
+    let file_name = recording
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut cue = String::new();
+    cue.push_str(&format!("PERFORMER \"{}\"\n", cue_text(performer)));
+    cue.push_str(&format!("TITLE \"{}\"\n", cue_text(album)));
+    cue.push_str(&format!("FILE \"{}\" WAVE\n", cue_text(&file_name)));
+    let starts = std::iter::once(0).chain(split_points_ms.iter().copied());
+    for (index, start_ms) in starts.enumerate() {
+        let number = index + 1;
+        let title = track_titles
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| format!("Track {}", number));
+        cue.push_str(&format!("  TRACK {:02} AUDIO\n", number));
+        cue.push_str(&format!("    TITLE \"{}\"\n", cue_text(&title)));
+        cue.push_str(&format!("    INDEX 01 {}\n", format_cue_time(start_ms)));
+    }
+    std::fs::write(&cue_path, cue)?;
+
+    info!(
+        "Wrote {} with {} tracks",
+        cue_path.display(),
+        split_points_ms.len() + 1
+    );
+
+    Ok(cue_path)
+}
+
+/// CUE strings are double-quoted with no escape syntax
+fn cue_text(s: &str) -> String {
+    s.replace('"', "'")
+}
+
+/// RMS and peak level of one window, relative to full scale
+fn window_level(window: &[i32], full_scale: f64) -> (f64, f64) {
+    let mut sum = 0.0;
+    let mut peak: f64 = 0.0;
+    for &s in window {
+        let v = s as f64 / full_scale;
+        sum += v * v;
+        peak = peak.max(v.abs());
+    }
+    ((sum / window.len().max(1) as f64).sqrt(), peak)
+}
+
+/// Runs of quiet windows long enough to be breaks between tracks.
+///
+/// Quiet at the very start or end is lead-in or run-out, not a gap.
+fn find_gaps(rms: &[f64]) -> Vec<Gap> {
+    let threshold = 10f64.powf(GAP_THRESHOLD_DB / 20.0);
+    let min_windows = (MIN_GAP_MS / WINDOW_MS) as usize;
+
+    let mut gaps = Vec::new();
+    let mut run_start = None;
+    for (i, &level) in rms.iter().enumerate() {
+        match (level <= threshold, run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(start)) => {
+                if start > 0 && i - start >= min_windows {
+                    gaps.push(Gap {
+                        start_ms: start as u64 * WINDOW_MS,
+                        end_ms: i as u64 * WINDOW_MS,
+                    });
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    gaps
+}
+
+/// Downsample window peaks to `buckets` values, normalized to the loudest
+fn waveform(peaks: &[f64], buckets: usize) -> Vec<f32> {
+    if peaks.is_empty() {
+        return Vec::new();
+    }
+    let per_bucket = peaks.len().div_ceil(buckets);
+    let bucketed: Vec<f64> = peaks
+        .chunks(per_bucket)
+        .map(|c| c.iter().copied().fold(0.0, f64::max))
+        .collect();
+    let loudest = bucketed.iter().copied().fold(0.0, f64::max);
+    if loudest <= f64::EPSILON {
+        return vec![0.0; bucketed.len()];
+    }
+    bucketed.iter().map(|p| (p / loudest) as f32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOUD: f64 = 0.3;
+    const QUIET: f64 = 0.001;
+
+    /// Window levels from (level, duration in ms) spans
+    fn levels(spans: &[(f64, u64)]) -> Vec<f64> {
+        spans
+            .iter()
+            .flat_map(|&(level, ms)| std::iter::repeat_n(level, (ms / WINDOW_MS) as usize))
+            .collect()
+    }
+
+    #[test]
+    fn finds_gaps_between_tracks_but_not_lead_in_or_run_out() {
+        let rms = levels(&[
+            (QUIET, 2000),
+            (LOUD, 60_000),
+            (QUIET, 3000),
+            (LOUD, 90_000),
+            (QUIET, 500),
+            (LOUD, 30_000),
+            (QUIET, 4000),
+        ]);
+
+        let gaps = find_gaps(&rms);
+
+        assert_eq!(
+            gaps,
+            vec![Gap {
+                start_ms: 62_000,
+                end_ms: 65_000
+            }]
+        );
+    }
+
+    #[test]
+    fn split_points_use_the_longest_gaps_in_time_order() {
+        let analysis = RecordingAnalysis {
+            duration_ms: 600_000,
+            waveform: vec![],
+            gaps: vec![
+                Gap {
+                    start_ms: 100_000,
+                    end_ms: 104_000,
+                },
+                Gap {
+                    start_ms: 200_000,
+                    end_ms: 201_600,
+                },
+                Gap {
+                    start_ms: 300_000,
+                    end_ms: 302_000,
+                },
+            ],
+        };
+
+        assert_eq!(analysis.split_points(3), vec![102_000, 301_000]);
+        assert_eq!(analysis.split_points(8), vec![102_000, 200_800, 301_000]);
+        assert_eq!(analysis.split_points(1), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn waveform_is_normalized_to_the_loudest_bucket() {
+        let wave = waveform(&[0.1, 0.2, 0.4, 0.0], 2);
+
+        assert_eq!(wave, vec![0.5, 1.0]);
+    }
+
+    #[test]
+    fn written_cue_sheet_parses_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let recording = dir.path().join("Side A.flac");
+        std::fs::write(&recording, b"fLaC").unwrap();
+        let titles = vec!["Tidewater".to_string(), "Low \"Light\"".to_string()];
+
+        let cue_path = write_cue_sheet(
+            &recording,
+            &[245_000],
+            "Glass Harbor",
+            "Pacific Standard",
+            &titles,
+        )
+        .unwrap();
+
+        assert_eq!(cue_path, dir.path().join("Side A.cue"));
+        let sheet = CueFlacProcessor::parse_cue_sheet(&cue_path).unwrap();
+        assert_eq!(sheet.performer, "Glass Harbor");
+        assert_eq!(sheet.tracks.len(), 2);
+        assert_eq!(sheet.tracks[0].start_time_ms, 0);
+        assert_eq!(sheet.tracks[0].end_time_ms, Some(245_000));
+        assert_eq!(sheet.tracks[1].title, "Low 'Light'");
+        assert_eq!(sheet.tracks[1].start_time_ms, 245_000);
+    }
+
+    #[test]
+    fn refuses_to_overwrite_an_existing_cue_sheet() {
+        let dir = tempfile::tempdir().unwrap();
+        let recording = dir.path().join("Side A.flac");
+        std::fs::write(dir.path().join("Side A.cue"), "").unwrap();
+
+        let result = write_cue_sheet(&recording, &[], "Glass Harbor", "Tidewater", &[]);
+
+        assert!(matches!(result, Err(SplitError::CueExists(_))));
+    }
+}
@@ -0,0 +1,358 @@
+//! Spotify library matching report
+//!
+//! Compares a pasted Spotify playlist or library export against the bae
+//! library and reports which albums are already owned and which are missing.
+//! Only the pasted text is read; nothing is fetched from Spotify.
+//!
+//! Accepted input:
+//! - `YourLibrary.json` or `Playlist1.json` from Spotify's account data export
+//! - CSV from playlist export tools, with "Artist Name(s)" and "Album Name" columns
+//! - Plain "Artist - Album" lines
+
+use crate::db::Database;
+use crate::import::server_migration::{
+    load_local_albums, match_album, normalize, normalize_title, RemoteAlbum,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SpotifyReportError {
+    #[error("Invalid Spotify export: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("CSV has no \"{0}\" column")]
+    MissingColumn(&'static str),
+    #[error(
+        "No albums found. Paste a Spotify data export, a playlist CSV, or \"Artist - Album\" lines"
+    )]
+    Empty,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// An album referenced by the export
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpotifyAlbum {
+    pub artist: String,
+    pub title: String,
+    /// How many export entries (saved tracks, playlist items) point at this album
+    pub entries: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedAlbum {
+    pub album: SpotifyAlbum,
+    /// The matching library album
+    pub album_id: String,
+}
+
+/// Which albums from the export are in the library and which are missing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LibraryReport {
+    pub owned: Vec<OwnedAlbum>,
+    pub missing: Vec<SpotifyAlbum>,
+}
+
+#[derive(Deserialize)]
+struct ExportFile {
+    #[serde(default)]
+    tracks: Vec<LibraryTrack>,
+    #[serde(default)]
+    albums: Vec<LibraryAlbum>,
+    #[serde(default)]
+    playlists: Vec<Playlist>,
+}
+
+#[derive(Deserialize)]
+struct LibraryTrack {
+    artist: String,
+    album: String,
+}
+
+#[derive(Deserialize)]
+struct LibraryAlbum {
+    artist: String,
+    album: String,
+}
+
+#[derive(Deserialize)]
+struct Playlist {
+    #[serde(default)]
+    items: Vec<PlaylistItem>,
+}
+
+#[derive(Deserialize)]
+struct PlaylistItem {
+    /// Null for local files and podcast episodes
+    track: Option<PlaylistTrack>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistTrack {
+    artist_name: String,
+    album_name: String,
+}
+
+/// Parse pasted export text into the albums it references, in first-seen order.
+pub fn parse_export(text: &str) -> Result<Vec<SpotifyAlbum>, SpotifyReportError> {
+    let text = text.trim();
+    let pairs = if text.starts_with('{') {
+        parse_json(text)?
+    } else if text
+        .lines()
+        .next()
+        .is_some_and(|header| header.to_lowercase().contains("album name"))
+    {
+        parse_csv(text)?
+    } else {
+        parse_lines(text)
+    };
+
+    let mut albums: Vec<SpotifyAlbum> = Vec::new();
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+    for (artist, title) in pairs {
+        let (artist, title) = (artist.trim(), title.trim());
+        if artist.is_empty() || title.is_empty() {
+            continue;
+        }
+        let key = (normalize(artist), normalize_title(title));
+        match index.get(&key) {
+            Some(&i) => albums[i].entries += 1,
+            None => {
+                index.insert(key, albums.len());
+                albums.push(SpotifyAlbum {
+                    artist: artist.to_string(),
+                    title: title.to_string(),
+                    entries: 1,
+                });
+            }
+        }
+    }
+
+    if albums.is_empty() {
+        return Err(SpotifyReportError::Empty);
+    }
+    Ok(albums)
+}
+
+/// Match export albums against the library.
+pub async fn build_report(
+    database: &Database,
+    albums: Vec<SpotifyAlbum>,
+) -> Result<LibraryReport, SpotifyReportError> {
+    let local_albums = load_local_albums(database).await?;
+    let mut report = LibraryReport::default();
+    for album in albums {
+        let remote = RemoteAlbum {
+            artist: album.artist.clone(),
+            title: album.title.clone(),
+            year: None,
+            musicbrainz_id: None,
+            tracks: vec![],
+        };
+        match match_album(&remote, &local_albums) {
+            Some(local) => report.owned.push(OwnedAlbum {
+                album,
+                album_id: local.id.clone(),
+            }),
+            None => report.missing.push(album),
+        }
+    }
+    Ok(report)
+}
+
+fn parse_json(text: &str) -> Result<Vec<(String, String)>, SpotifyReportError> {
+    let export: ExportFile = serde_json::from_str(text)?;
+    let albums = export.albums.into_iter().map(|a| (a.artist, a.album));
+    let tracks = export.tracks.into_iter().map(|t| (t.artist, t.album));
+    let playlist_tracks = export
+        .playlists
+        .into_iter()
+        .flat_map(|p| p.items)
+        .filter_map(|item| item.track)
+        .map(|t| (t.artist_name, t.album_name));
+    Ok(albums.chain(tracks).chain(playlist_tracks).collect())
+}
+
+fn parse_csv(text: &str) -> Result<Vec<(String, String)>, SpotifyReportError> {
+    let mut lines = text.lines();
+    let header: Vec<String> = lines
+        .next()
+        .map(split_csv_line)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| header.iter().position(|h| h == name))
+    };
+    let artist_col = column(&["album artist name(s)", "artist name(s)", "artist name"])
+        .ok_or(SpotifyReportError::MissingColumn("Artist Name(s)"))?;
+    let album_col =
+        column(&["album name"]).ok_or(SpotifyReportError::MissingColumn("Album Name"))?;
+
+    Ok(lines
+        .map(split_csv_line)
+        .filter_map(|fields| {
+            let artists = fields.get(artist_col)?;
+            // Multiple artists are comma-separated; the first is the primary one
+            let artist = artists.split(',').next().unwrap_or(artists);
+            Some((artist.to_string(), fields.get(album_col)?.clone()))
+        })
+        .collect())
+}
+
+fn parse_lines(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| line.split_once(" - "))
+        .map(|(artist, title)| (artist.to_string(), title.to_string()))
+        .collect()
+}
+
+/// Split one CSV line, honoring double-quoted fields and `""` escapes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_library_export_and_counts_entries() {
+        let text = r#"{
+            "tracks": [
+                {"artist": "Glass Harbor", "album": "Pacific Standard", "track": "Coastal", "uri": "spotify:track:1"},
+                {"artist": "Glass Harbor", "album": "Pacific Standard", "track": "Tide Pool", "uri": "spotify:track:2"},
+                {"artist": "Tidewater", "album": "Low Light", "track": "Dusk", "uri": "spotify:track:3"}
+            ],
+            "albums": [
+                {"artist": "The Glass Harbor", "album": "Pacific Standard (Remastered)", "uri": "spotify:album:1"}
+            ],
+            "artists": []
+        }"#;
+
+        let albums = parse_export(text).unwrap();
+        assert_eq!(albums.len(), 2);
+        assert_eq!(albums[0].title, "Pacific Standard (Remastered)");
+        assert_eq!(albums[0].entries, 3);
+        assert_eq!(albums[1].artist, "Tidewater");
+        assert_eq!(albums[1].entries, 1);
+    }
+
+    #[test]
+    fn parses_playlist_export_skipping_local_files() {
+        let text = r#"{"playlists": [{"name": "Drive", "items": [
+            {"track": {"trackName": "Coastal", "artistName": "Glass Harbor", "albumName": "Pacific Standard"}},
+            {"track": null, "localTrack": {"uri": "spotify:local:x"}}
+        ]}]}"#;
+
+        let albums = parse_export(text).unwrap();
+        assert_eq!(albums.len(), 1);
+        assert_eq!(albums[0].artist, "Glass Harbor");
+    }
+
+    #[test]
+    fn parses_csv_with_quoted_fields() {
+        let text = "Track URI,Track Name,Artist Name(s),Album Name\n\
+            spotify:track:1,Coastal,\"Glass Harbor,Tidewater\",\"Pacific Standard, Vol. 1\"\n\
+            spotify:track:2,\"Say \"\"Hi\"\"\",Tidewater,Low Light\n";
+
+        let albums = parse_export(text).unwrap();
+        assert_eq!(albums.len(), 2);
+        assert_eq!(albums[0].artist, "Glass Harbor");
+        assert_eq!(albums[0].title, "Pacific Standard, Vol. 1");
+        assert_eq!(albums[1].title, "Low Light");
+    }
+
+    #[test]
+    fn csv_without_artist_column_is_an_error() {
+        let text = "Track Name,Album Name\nCoastal,Pacific Standard\n";
+        assert!(matches!(
+            parse_export(text),
+            Err(SpotifyReportError::MissingColumn(_))
+        ));
+    }
+
+    #[test]
+    fn parses_plain_lines_and_rejects_links() {
+        let albums =
+            parse_export("Glass Harbor - Pacific Standard\nTidewater - Low Light - Live\n")
+                .unwrap();
+        assert_eq!(albums[1].title, "Low Light - Live");
+
+        assert!(matches!(
+            parse_export("https://open.spotify.com/album/abc123"),
+            Err(SpotifyReportError::Empty)
+        ));
+    }
+
+    #[tokio::test]
+    async fn report_splits_owned_and_missing() {
+        use crate::db::{DbAlbum, DbAlbumArtist, DbArtist};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let database = Database::new(db_path.to_str().unwrap()).await.unwrap();
+
+        let now = chrono::Utc::now();
+        let artist = DbArtist {
+            id: "artist-1".to_string(),
+            name: "Glass Harbor".to_string(),
+            sort_name: None,
+            discogs_artist_id: None,
+            bandcamp_artist_id: None,
+            musicbrainz_artist_id: None,
+            created_at: now,
+            updated_at: now,
+        };
+        database.insert_artist(&artist).await.unwrap();
+        let album = DbAlbum {
+            id: "album-1".to_string(),
+            title: "Pacific Standard".to_string(),
+            year: Some(2001),
+            discogs_release: None,
+            musicbrainz_release: None,
+            bandcamp_album_id: None,
+            cover_release_id: None,
+            is_compilation: false,
+            created_at: now,
+            updated_at: now,
+        };
+        database.insert_album(&album).await.unwrap();
+        database
+            .insert_album_artist(&DbAlbumArtist::new(&album.id, &artist.id, 0))
+            .await
+            .unwrap();
+
+        let albums =
+            parse_export("Glass Harbor - Pacific Standard [Deluxe]\nTidewater - Low Light")
+                .unwrap();
+        let report = build_report(&database, albums).await.unwrap();
+
+        assert_eq!(report.owned.len(), 1);
+        assert_eq!(report.owned[0].album_id, "album-1");
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].artist, "Tidewater");
+    }
+}
@@ -0,0 +1,494 @@
+//! Import metadata from embedded FLAC tags
+//!
+//! For releases that aren't on MusicBrainz or Discogs (obscure pressings,
+//! personal recordings). Every file needs a title tag, and the album and
+//! artist tags must agree across files. Files without an album artist whose
+//! artists differ, or that are flagged as a compilation, are credited to
+//! Various Artists. The release is flagged unverified so it can be matched
+//! against a metadata source later.
+use super::ParsedAlbum;
+use crate::db::{
+    is_various_artists, DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack, DbTrackArtist,
+    ImportStatus, VARIOUS_ARTISTS,
+};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// FLAC metadata block type holding Vorbis comments
+const VORBIS_COMMENT_BLOCK: u8 = 4;
+
+/// The tags we use from a file's Vorbis comment block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album_artist: Option<String>,
+    pub album: Option<String>,
+    pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub year: Option<i32>,
+    /// Every GENRE value, in order, with `;`-separated lists split apart
+    pub genres: Vec<String>,
+    /// COMPILATION=1
+    pub compilation: bool,
+}
+
+/// Read Vorbis comments from a FLAC file, skipping over the other metadata
+/// blocks. A file without a comment block has empty tags.
+pub fn read_flac_tags(path: &Path) -> Result<TrackTags, String> {
+    let io_err = |e: std::io::Error| format!("Failed to read tags from {}: {}", path.display(), e);
+    let mut file = BufReader::new(File::open(path).map_err(io_err)?);
+
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic).map_err(io_err)?;
+    if &magic != b"fLaC" {
+        return Err(format!("{} is not a FLAC file", path.display()));
+    }
+
+    loop {
+        let mut header = [0u8; 4];
+        file.read_exact(&mut header).map_err(io_err)?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7F;
+        let block_size = u32::from_be_bytes([0, header[1], header[2], header[3]]);
+
+        if block_type == VORBIS_COMMENT_BLOCK {
+            let mut block = vec![0u8; block_size as usize];
+            file.read_exact(&mut block).map_err(io_err)?;
+            return parse_vorbis_comment(&block)
+                .ok_or_else(|| format!("Malformed tags in {}", path.display()));
+        }
+        if is_last {
+            return Ok(TrackTags::default());
+        }
+        file.seek(SeekFrom::Current(block_size as i64))
+            .map_err(io_err)?;
+    }
+}
+
+/// Build album, release and tracks from the tags of a folder's audio files,
+/// along with the genres tagged on any of them.
+///
+/// Tracks are created in sorted path order, the same order
+/// `map_tracks_to_files` pairs tracks with files in.
+pub fn parse_tagged_files(audio_files: &[PathBuf]) -> Result<(ParsedAlbum, Vec<String>), String> {
+    let mut paths = audio_files.to_vec();
+    paths.sort();
+    if paths.is_empty() {
+        return Err("No FLAC files to read tags from".to_string());
+    }
+    let tags = paths
+        .iter()
+        .map(|path| read_flac_tags(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let genres = tags.iter().flat_map(|t| t.genres.clone()).collect();
+    Ok((build_album(&paths, &tags)?, genres))
+}
+
+fn build_album(paths: &[PathBuf], tags: &[TrackTags]) -> Result<ParsedAlbum, String> {
+    let album_title = common_tag(paths, tags, "album", |t| t.album.as_deref())?;
+    let has_album_artist = tags.iter().any(|t| t.album_artist.is_some());
+    let track_artists_differ = tags
+        .iter()
+        .filter_map(|t| t.artist.as_deref())
+        .collect::<HashSet<_>>()
+        .len()
+        > 1;
+    let tagged_compilation = tags.iter().any(|t| t.compilation);
+    let artist_name = if !has_album_artist && (track_artists_differ || tagged_compilation) {
+        VARIOUS_ARTISTS.to_string()
+    } else {
+        common_tag(paths, tags, "artist", |t| {
+            t.album_artist.as_deref().or(t.artist.as_deref())
+        })?
+    };
+    let year = tags.iter().find_map(|t| t.year);
+    let is_compilation = tagged_compilation
+        || is_various_artists(&artist_name)
+        || tags
+            .iter()
+            .filter_map(|t| t.artist.as_deref())
+            .any(|artist| artist != artist_name);
+    let now = chrono::Utc::now();
+
+    let db_album = DbAlbum {
+        id: Uuid::new_v4().to_string(),
+        title: album_title,
+        year,
+        discogs_release: None,
+        musicbrainz_release: None,
+        bandcamp_album_id: None,
+        cover_release_id: None,
+        is_compilation,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let db_release = DbRelease {
+        id: Uuid::new_v4().to_string(),
+        album_id: db_album.id.clone(),
+        release_name: None,
+        year,
+        discogs_release_id: None,
+        bandcamp_release_id: None,
+        format: None,
+        label: None,
+        catalog_number: None,
+        country: None,
+        barcode: None,
+        import_status: ImportStatus::Queued,
+        managed_locally: false,
+        managed_in_cloud: false,
+        unmanaged_path: None,
+        private: false,
+        unverified: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let artist = DbArtist {
+        id: Uuid::new_v4().to_string(),
+        name: artist_name,
+        sort_name: None,
+        discogs_artist_id: None,
+        bandcamp_artist_id: None,
+        musicbrainz_artist_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    let album_artist = DbAlbumArtist::new(&db_album.id, &artist.id, 0);
+
+    let mut artists = vec![artist];
+    let mut track_artists = Vec::new();
+    let mut tracks = Vec::with_capacity(tags.len());
+    for (index, (path, track_tags)) in paths.iter().zip(tags).enumerate() {
+        let title = track_tags
+            .title
+            .clone()
+            .ok_or_else(|| format!("Missing title tag in {}", file_name(path)))?;
+        let track_id = Uuid::new_v4().to_string();
+        // The artist tag is taken as one credit: how "feat." is written
+        // varies too much between taggers to split it reliably
+        if let Some(name) = track_tags
+            .artist
+            .as_deref()
+            .filter(|name| *name != artists[0].name)
+        {
+            let artist_id = match artists.iter().find(|a| a.name == name) {
+                Some(existing) => existing.id.clone(),
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    artists.push(DbArtist {
+                        id: id.clone(),
+                        name: name.to_string(),
+                        sort_name: None,
+                        discogs_artist_id: None,
+                        bandcamp_artist_id: None,
+                        musicbrainz_artist_id: None,
+                        created_at: now,
+                        updated_at: now,
+                    });
+                    id
+                }
+            };
+            track_artists.push(DbTrackArtist::new(&track_id, &artist_id, 0, None));
+        }
+        tracks.push(DbTrack {
+            id: track_id,
+            release_id: db_release.id.clone(),
+            title,
+            disc_number: track_tags.disc_number,
+            track_number: Some(track_tags.track_number.unwrap_or(index as i32 + 1)),
+            duration_ms: None,
+            discogs_position: None,
+            import_status: ImportStatus::Queued,
+            updated_at: now,
+            created_at: now,
+        });
+    }
+
+    Ok((
+        db_album,
+        db_release,
+        tracks,
+        artists,
+        vec![album_artist],
+        track_artists,
+    ))
+}
+
+/// The value of a tag that every file must carry with the same value
+fn common_tag(
+    paths: &[PathBuf],
+    tags: &[TrackTags],
+    name: &str,
+    get: impl Fn(&TrackTags) -> Option<&str>,
+) -> Result<String, String> {
+    let mut common: Option<&str> = None;
+    for (path, track_tags) in paths.iter().zip(tags) {
+        let value = get(track_tags)
+            .ok_or_else(|| format!("Missing {} tag in {}", name, file_name(path)))?;
+        match common {
+            None => common = Some(value),
+            Some(first) if first != value => {
+                return Err(format!(
+                    "Files have different {} tags: '{}' and '{}'",
+                    name, first, value
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(common.unwrap_or_default().to_string())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Parse a Vorbis comment block: a vendor string followed by `KEY=value`
+/// entries, all length-prefixed with little-endian u32s. Keys are
+/// case-insensitive; the first value wins when a key repeats, except for
+/// GENRE, which collects every value.
+fn parse_vorbis_comment(block: &[u8]) -> Option<TrackTags> {
+    let mut pos = 0;
+    let vendor_len = read_u32_le(block, &mut pos)?;
+    pos = pos.checked_add(vendor_len)?;
+    let count = read_u32_le(block, &mut pos)?;
+
+    let mut tags = TrackTags::default();
+    for _ in 0..count {
+        let len = read_u32_le(block, &mut pos)?;
+        let entry = block.get(pos..pos.checked_add(len)?)?;
+        pos += len;
+
+        let entry = String::from_utf8_lossy(entry);
+        let Some((key, value)) = entry.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        match key.to_ascii_uppercase().as_str() {
+            "TITLE" => set_once(&mut tags.title, value),
+            "ARTIST" => set_once(&mut tags.artist, value),
+            "ALBUMARTIST" | "ALBUM ARTIST" => set_once(&mut tags.album_artist, value),
+            "ALBUM" => set_once(&mut tags.album, value),
+            "TRACKNUMBER" => tags.track_number = tags.track_number.or(parse_number(value)),
+            "DISCNUMBER" => tags.disc_number = tags.disc_number.or(parse_number(value)),
+            "DATE" | "YEAR" => tags.year = tags.year.or(parse_year(value)),
+            "COMPILATION" => tags.compilation |= value == "1",
+            "GENRE" => tags.genres.extend(
+                value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|g| !g.is_empty())
+                    .map(String::from),
+            ),
+            _ => {}
+        }
+    }
+    Some(tags)
+}
+
+fn read_u32_le(data: &[u8], pos: &mut usize) -> Option<usize> {
+    let bytes = data.get(*pos..*pos + 4)?;
+    *pos += 4;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?) as usize)
+}
+
+fn set_once(slot: &mut Option<String>, value: &str) {
+    if slot.is_none() {
+        *slot = Some(value.to_string());
+    }
+}
+
+/// "3" or "3/12"
+fn parse_number(value: &str) -> Option<i32> {
+    value.split('/').next()?.trim().parse().ok()
+}
+
+/// "2001" or "2001-05-14"
+fn parse_year(value: &str) -> Option<i32> {
+    value.get(..4)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vorbis_comment(entries: &[&str]) -> Vec<u8> {
+        let vendor = b"reference libFLAC 1.4.3";
+        let mut block = Vec::new();
+        block.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        block.extend_from_slice(vendor);
+        block.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for entry in entries {
+            block.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+            block.extend_from_slice(entry.as_bytes());
+        }
+        block
+    }
+
+    /// Minimal FLAC header: STREAMINFO followed by the comment block
+    fn flac_with_tags(entries: &[&str]) -> Vec<u8> {
+        let comment = vorbis_comment(entries);
+        let mut data = b"fLaC".to_vec();
+        data.push(0);
+        data.extend_from_slice(&34u32.to_be_bytes()[1..]);
+        data.extend_from_slice(&[0u8; 34]);
+        data.push(0x80 | VORBIS_COMMENT_BLOCK);
+        data.extend_from_slice(&(comment.len() as u32).to_be_bytes()[1..]);
+        data.extend_from_slice(&comment);
+        data
+    }
+
+    fn tags(title: &str, artist: &str, album: &str, track: i32) -> TrackTags {
+        TrackTags {
+            title: Some(title.to_string()),
+            artist: Some(artist.to_string()),
+            album: Some(album.to_string()),
+            track_number: Some(track),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reads_tags_after_other_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("01.flac");
+        std::fs::write(
+            &path,
+            flac_with_tags(&[
+                "title=Coastal",
+                "ARTIST=Glass Harbor",
+                "Album=Pacific Standard",
+                "TRACKNUMBER=1/9",
+                "DISCNUMBER=2",
+                "DATE=2001-05-14",
+                "COMPILATION=1",
+                "GENRE=Dream Pop; Shoegaze",
+                "genre=Ambient",
+                "TITLE=Ignored Duplicate",
+            ]),
+        )
+        .unwrap();
+
+        let tags = read_flac_tags(&path).unwrap();
+        assert_eq!(tags.title.as_deref(), Some("Coastal"));
+        assert_eq!(tags.artist.as_deref(), Some("Glass Harbor"));
+        assert_eq!(tags.album.as_deref(), Some("Pacific Standard"));
+        assert_eq!(tags.track_number, Some(1));
+        assert_eq!(tags.disc_number, Some(2));
+        assert_eq!(tags.year, Some(2001));
+        assert_eq!(tags.genres, vec!["Dream Pop", "Shoegaze", "Ambient"]);
+        assert!(tags.compilation);
+    }
+
+    #[test]
+    fn truncated_comment_block_is_rejected() {
+        let mut block = vorbis_comment(&["TITLE=Coastal"]);
+        block.truncate(block.len() - 3);
+        assert!(parse_vorbis_comment(&block).is_none());
+    }
+
+    #[test]
+    fn builds_unverified_release_in_path_order() {
+        let paths = vec![PathBuf::from("/a/01.flac"), PathBuf::from("/a/02.flac")];
+        let tags = vec![
+            tags("Coastal", "Glass Harbor", "Pacific Standard", 1),
+            tags("Tide Pool", "Glass Harbor", "Pacific Standard", 2),
+        ];
+
+        let (album, release, tracks, artists, album_artists, track_artists) =
+            build_album(&paths, &tags).unwrap();
+
+        assert_eq!(album.title, "Pacific Standard");
+        assert!(!album.is_compilation);
+        assert!(release.unverified);
+        assert_eq!(artists[0].name, "Glass Harbor");
+        assert_eq!(album_artists[0].artist_id, artists[0].id);
+        assert!(track_artists.is_empty());
+        let titles: Vec<_> = tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Coastal", "Tide Pool"]);
+    }
+
+    #[test]
+    fn album_artist_tag_marks_compilation() {
+        let paths = vec![PathBuf::from("/a/01.flac"), PathBuf::from("/a/02.flac")];
+        let mut first = tags("Coastal", "Glass Harbor", "Harbor Sessions", 1);
+        let mut second = tags("Undertow", "Tidewater", "Harbor Sessions", 2);
+        first.album_artist = Some("Various Artists".to_string());
+        second.album_artist = Some("Various Artists".to_string());
+
+        let (album, _, tracks, artists, _, track_artists) =
+            build_album(&paths, &[first, second]).unwrap();
+
+        assert!(album.is_compilation);
+        assert_eq!(artists[0].name, "Various Artists");
+        let credited: Vec<_> = track_artists
+            .iter()
+            .map(|ta| {
+                let artist = artists.iter().find(|a| a.id == ta.artist_id).unwrap();
+                (ta.track_id.as_str(), artist.name.as_str())
+            })
+            .collect();
+        assert_eq!(
+            credited,
+            vec![
+                (tracks[0].id.as_str(), "Glass Harbor"),
+                (tracks[1].id.as_str(), "Tidewater"),
+            ]
+        );
+    }
+
+    #[test]
+    fn different_track_artists_without_album_artist_are_various_artists() {
+        let paths = vec![PathBuf::from("/a/01.flac"), PathBuf::from("/a/02.flac")];
+        let tags = vec![
+            tags("Coastal", "Glass Harbor", "Harbor Sessions", 1),
+            tags("Undertow", "Tidewater", "Harbor Sessions", 2),
+        ];
+
+        let (album, _, _, artists, album_artists, track_artists) =
+            build_album(&paths, &tags).unwrap();
+
+        assert!(album.is_compilation);
+        assert_eq!(artists[0].name, "Various Artists");
+        assert_eq!(album_artists[0].artist_id, artists[0].id);
+        assert_eq!(track_artists.len(), 2);
+    }
+
+    #[test]
+    fn incomplete_or_inconsistent_tags_are_rejected() {
+        let paths = vec![PathBuf::from("/a/01.flac"), PathBuf::from("/a/02.flac")];
+
+        let mut untitled = tags("", "Glass Harbor", "Pacific Standard", 2);
+        untitled.title = None;
+        let err = build_album(
+            &paths,
+            &[
+                tags("Coastal", "Glass Harbor", "Pacific Standard", 1),
+                untitled,
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err, "Missing title tag in 02.flac");
+
+        let err = build_album(
+            &paths,
+            &[
+                tags("Coastal", "Glass Harbor", "Pacific Standard", 1),
+                tags("Undertow", "Tidewater", "Low Tide", 2),
+            ],
+        )
+        .unwrap_err();
+        assert!(err.starts_with("Files have different album tags"));
+    }
+}
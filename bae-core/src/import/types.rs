@@ -30,6 +30,7 @@ use crate::{
     cue_flac::CueSheet,
     db::{DbAlbum, DbRelease},
     discogs::DiscogsRelease,
+    import::{bandcamp::BandcampAlbum, ImportError},
     musicbrainz::MbRelease,
 };
 use std::{collections::HashMap, path::PathBuf};
@@ -56,6 +57,32 @@ pub enum ImportRequest {
         /// Whether to store files in managed local storage.
         /// When false, files stay in place (unmanaged).
         managed: bool,
+        /// Move the managed files on to the cloud home once imported
+        move_to_cloud: bool,
+        /// User-selected cover image.
+        selected_cover: Option<CoverSelection>,
+    },
+    /// A Bandcamp purchase already downloaded and extracted to `folder`
+    Bandcamp {
+        import_id: String,
+        album: BandcampAlbum,
+        folder: PathBuf,
+        /// Whether to store files in managed local storage.
+        /// When false, files stay in the download folder (unmanaged).
+        managed: bool,
+        /// Move the managed files on to the cloud home once imported
+        move_to_cloud: bool,
+    },
+    /// A folder with no MusicBrainz or Discogs match, imported from its
+    /// embedded tags. The release is flagged unverified.
+    Tags {
+        import_id: String,
+        folder: PathBuf,
+        /// Whether to store files in managed local storage.
+        /// When false, files stay in place (unmanaged).
+        managed: bool,
+        /// Move the managed files on to the cloud home once imported
+        move_to_cloud: bool,
         /// User-selected cover image.
         selected_cover: Option<CoverSelection>,
     },
@@ -128,7 +155,7 @@ pub enum ImportProgress {
     },
     Failed {
         id: String,
-        error: String,
+        error: ImportError,
         import_id: Option<String>,
     },
 }
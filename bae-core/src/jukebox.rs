@@ -0,0 +1,320 @@
+//! Jukebox mode: Subsonic clients remote-control this machine's playback
+//!
+//! Subsonic models the jukebox as a playlist with a current index. Here that
+//! playlist is the track now playing followed by the playback queue, so the
+//! current index is 0 whenever something is loaded. Commands go straight to
+//! the `PlaybackHandle`; the status is mirrored from playback progress events.
+
+use crate::playback::{PlaybackHandle, PlaybackProgress, PlaybackState};
+use rand::seq::SliceRandom;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Playback as a Subsonic jukebox client sees it
+#[derive(Debug, Clone, PartialEq)]
+pub struct JukeboxStatus {
+    /// Track loaded in the player, playing or paused
+    pub current: Option<String>,
+    /// Tracks queued after the current one
+    pub queue: Vec<String>,
+    pub playing: bool,
+    pub position: Duration,
+    /// Volume, 0.0 to 1.0
+    pub gain: f32,
+}
+
+impl Default for JukeboxStatus {
+    fn default() -> Self {
+        Self {
+            current: None,
+            queue: Vec::new(),
+            playing: false,
+            position: Duration::ZERO,
+            gain: 1.0,
+        }
+    }
+}
+
+impl JukeboxStatus {
+    /// The jukebox playlist: the current track, then the queue
+    pub fn playlist(&self) -> Vec<String> {
+        self.current
+            .iter()
+            .chain(self.queue.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Index of the current track in `playlist`, -1 when nothing is loaded
+    pub fn current_index(&self) -> i32 {
+        if self.current.is_some() {
+            0
+        } else {
+            -1
+        }
+    }
+
+    fn apply(&mut self, progress: &PlaybackProgress) {
+        match progress {
+            PlaybackProgress::StateChanged { state } => match state {
+                PlaybackState::Playing {
+                    track, position, ..
+                } => {
+                    self.current = Some(track.id.clone());
+                    self.position = *position;
+                    self.playing = true;
+                }
+                PlaybackState::Paused {
+                    track, position, ..
+                } => {
+                    self.current = Some(track.id.clone());
+                    self.position = *position;
+                    self.playing = false;
+                }
+                PlaybackState::Loading { track_id } => {
+                    self.current = Some(track_id.clone());
+                    self.position = Duration::ZERO;
+                }
+                PlaybackState::Stopped => {
+                    self.current = None;
+                    self.position = Duration::ZERO;
+                    self.playing = false;
+                }
+            },
+            PlaybackProgress::PositionUpdate { position, track_id }
+            | PlaybackProgress::Seeked {
+                position, track_id, ..
+            } => {
+                if self.current.as_ref() == Some(track_id) {
+                    self.position = *position;
+                }
+            }
+            PlaybackProgress::QueueUpdated { tracks } => self.queue = tracks.clone(),
+            PlaybackProgress::VolumeChanged { volume } => self.gain = *volume,
+            _ => {}
+        }
+    }
+}
+
+/// A `jukeboxControl` request
+#[derive(Debug, Clone, PartialEq)]
+pub enum JukeboxAction {
+    /// Status plus the playlist's songs
+    Get,
+    Status,
+    /// Replace the playlist without starting it
+    Set(Vec<String>),
+    Start,
+    Stop,
+    /// Jump to a playlist index, `offset` seconds in
+    Skip {
+        index: usize,
+        offset: u64,
+    },
+    Add(Vec<String>),
+    Clear,
+    Remove(usize),
+    Shuffle,
+    SetGain(f32),
+}
+
+impl JukeboxAction {
+    /// Parse the `action` parameter and the parameters that go with it.
+    /// `id` may repeat.
+    pub fn parse(params: &[(String, String)]) -> Result<Self, String> {
+        let value = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        let ids = || -> Vec<String> {
+            params
+                .iter()
+                .filter(|(key, _)| key == "id")
+                .map(|(_, value)| value.clone())
+                .collect()
+        };
+        let index = || -> Result<usize, String> {
+            let index = value("index").ok_or("Required parameter 'index' missing")?;
+            index
+                .parse()
+                .map_err(|_| format!("Invalid index: {}", index))
+        };
+
+        match value("action").ok_or("Required parameter 'action' missing")? {
+            "get" => Ok(Self::Get),
+            "status" => Ok(Self::Status),
+            "set" => Ok(Self::Set(ids())),
+            "start" => Ok(Self::Start),
+            "stop" => Ok(Self::Stop),
+            "skip" => Ok(Self::Skip {
+                index: index()?,
+                offset: value("offset").and_then(|o| o.parse().ok()).unwrap_or(0),
+            }),
+            "add" => Ok(Self::Add(ids())),
+            "clear" => Ok(Self::Clear),
+            "remove" => Ok(Self::Remove(index()?)),
+            "shuffle" => Ok(Self::Shuffle),
+            "setGain" => {
+                let gain = value("gain").ok_or("Required parameter 'gain' missing")?;
+                gain.parse()
+                    .map(Self::SetGain)
+                    .map_err(|_| format!("Invalid gain: {}", gain))
+            }
+            other => Err(format!("Unknown jukebox action: {}", other)),
+        }
+    }
+}
+
+/// Remote control for the local player, shared by the Subsonic router
+pub struct Jukebox {
+    playback: PlaybackHandle,
+    status: Arc<Mutex<JukeboxStatus>>,
+}
+
+impl Jukebox {
+    /// Start mirroring playback. Must be called from within a tokio runtime.
+    pub fn new(playback: PlaybackHandle) -> Arc<Self> {
+        let status = Arc::new(Mutex::new(JukeboxStatus::default()));
+        let mut progress_rx = playback.subscribe_progress();
+        let tracked = status.clone();
+        tokio::spawn(async move {
+            while let Some(progress) = progress_rx.recv().await {
+                tracked.lock().unwrap().apply(&progress);
+            }
+        });
+        // Learn the queue without waiting for it to change
+        playback.get_queue();
+
+        Arc::new(Self { playback, status })
+    }
+
+    pub fn status(&self) -> JukeboxStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Carry out an action. The status catches up once playback reports the
+    /// change.
+    pub fn control(&self, action: &JukeboxAction) {
+        let status = self.status();
+        // Queue position of a playlist index, or None for the current track
+        let queue_index = |index: usize| match status.current {
+            Some(_) => index.checked_sub(1),
+            None => Some(index),
+        };
+
+        match action {
+            JukeboxAction::Get | JukeboxAction::Status => {}
+            JukeboxAction::Set(track_ids) => {
+                self.playback.stop();
+                self.playback.clear_queue();
+                self.playback.add_to_queue(track_ids.clone());
+            }
+            JukeboxAction::Start => {
+                if status.current.is_some() {
+                    self.playback.resume();
+                } else if !status.queue.is_empty() {
+                    self.playback.skip_to(0);
+                }
+            }
+            JukeboxAction::Stop => self.playback.pause(),
+            JukeboxAction::Skip { index, offset } => {
+                let offset = Duration::from_secs(*offset);
+                match queue_index(*index) {
+                    Some(queue_index) => {
+                        self.playback.skip_to(queue_index);
+                        if !offset.is_zero() {
+                            self.playback.seek(offset);
+                        }
+                    }
+                    // Restart the current track
+                    None => self.playback.seek(offset),
+                }
+            }
+            JukeboxAction::Add(track_ids) => self.playback.add_to_queue(track_ids.clone()),
+            JukeboxAction::Clear => {
+                self.playback.stop();
+                self.playback.clear_queue();
+            }
+            JukeboxAction::Remove(index) => match queue_index(*index) {
+                Some(queue_index) => self.playback.remove_from_queue(queue_index),
+                None => self.playback.next(),
+            },
+            JukeboxAction::Shuffle => {
+                let mut queue = status.queue;
+                queue.shuffle(&mut rand::rng());
+                self.playback.clear_queue();
+                self.playback.add_to_queue(queue);
+            }
+            JukeboxAction::SetGain(gain) => self.playback.set_volume(gain.clamp(0.0, 1.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_actions_with_their_parameters() {
+        assert_eq!(
+            JukeboxAction::parse(&params(&[("action", "set"), ("id", "t1"), ("id", "t2")])),
+            Ok(JukeboxAction::Set(vec!["t1".to_string(), "t2".to_string()]))
+        );
+        assert_eq!(
+            JukeboxAction::parse(&params(&[
+                ("action", "skip"),
+                ("index", "2"),
+                ("offset", "30")
+            ])),
+            Ok(JukeboxAction::Skip {
+                index: 2,
+                offset: 30
+            })
+        );
+        assert_eq!(
+            JukeboxAction::parse(&params(&[("action", "setGain"), ("gain", "0.5")])),
+            Ok(JukeboxAction::SetGain(0.5))
+        );
+        assert!(JukeboxAction::parse(&params(&[("action", "remove")])).is_err());
+        assert!(JukeboxAction::parse(&params(&[("action", "dance")])).is_err());
+        assert!(JukeboxAction::parse(&params(&[])).is_err());
+    }
+
+    #[test]
+    fn playlist_is_current_track_then_queue() {
+        let mut status = JukeboxStatus::default();
+        status.apply(&PlaybackProgress::QueueUpdated {
+            tracks: vec!["t2".to_string(), "t3".to_string()],
+        });
+        assert_eq!(status.playlist(), vec!["t2", "t3"]);
+        assert_eq!(status.current_index(), -1);
+
+        status.apply(&PlaybackProgress::StateChanged {
+            state: PlaybackState::Loading {
+                track_id: "t1".to_string(),
+            },
+        });
+        status.apply(&PlaybackProgress::PositionUpdate {
+            position: Duration::from_secs(42),
+            track_id: "t1".to_string(),
+        });
+        assert_eq!(status.playlist(), vec!["t1", "t2", "t3"]);
+        assert_eq!(status.current_index(), 0);
+        assert_eq!(status.position, Duration::from_secs(42));
+
+        status.apply(&PlaybackProgress::StateChanged {
+            state: PlaybackState::Stopped,
+        });
+        assert_eq!(status.current, None);
+        assert!(!status.playing);
+    }
+}
@@ -1,5 +1,7 @@
+use crate::oauth::OAuthTokens;
 use crate::sodium_ffi;
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, RwLock};
 use thiserror::Error;
 use tracing::{info, warn};
 
@@ -186,6 +188,52 @@ pub fn ed25519_to_x25519_public_key(
     curve_pk
 }
 
+/// The encryption master key wrapped by a FIDO2 security key's hmac-secret
+/// output, so it can be kept out of the keyring. All fields are hex.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WrappedEncryptionKey {
+    /// Credential the security key created for bae
+    pub credential_id: String,
+    /// Salt the security key hashes into the wrapping secret
+    pub salt: String,
+    /// Master key encrypted under that secret
+    pub wrapped_key: String,
+}
+
+impl WrappedEncryptionKey {
+    /// Encrypt `key_hex` under the 32-byte hmac-secret output for `salt`
+    pub fn wrap(key_hex: &str, credential_id: &[u8], salt: &[u8; 32], secret: [u8; 32]) -> Self {
+        let wrapped =
+            crate::encryption::EncryptionService::from_key(secret).encrypt(key_hex.as_bytes());
+        WrappedEncryptionKey {
+            credential_id: hex::encode(credential_id),
+            salt: hex::encode(salt),
+            wrapped_key: hex::encode(wrapped),
+        }
+    }
+
+    /// Recover the hex master key from the hmac-secret output
+    pub fn unwrap_key(&self, secret: [u8; 32]) -> Result<String, KeyError> {
+        let wrapped = hex::decode(&self.wrapped_key)
+            .map_err(|e| KeyError::Crypto(format!("wrapped key: {e}")))?;
+        let key = crate::encryption::EncryptionService::from_key(secret)
+            .decrypt(&wrapped)
+            .map_err(|_| KeyError::Crypto("Wrong security key".to_string()))?;
+        String::from_utf8(key).map_err(|e| KeyError::Crypto(format!("wrapped key: {e}")))
+    }
+}
+
+/// Secrets handed to the process instead of stored, such as those in a
+/// server config file. Each one set is read ahead of the keyring or
+/// environment.
+#[derive(Clone, Default)]
+pub struct SecretOverrides {
+    pub encryption_key: Option<String>,
+    pub server_password: Option<String>,
+    pub cloud_home_credentials: Option<CloudHomeCredentials>,
+    pub user_keypair: Option<UserKeypair>,
+}
+
 /// Manages secret keys (Discogs API key, encryption key) with lazy reads.
 ///
 /// In dev mode, reads from environment variables.
@@ -198,6 +246,11 @@ pub fn ed25519_to_x25519_public_key(
 pub struct KeyService {
     dev_mode: bool,
     library_id: String,
+    /// Master key unwrapped by a security key for this process only. Shared
+    /// by every clone.
+    held_encryption_key: Arc<Mutex<Option<String>>>,
+    /// Shared by every clone, so replacing them reaches the whole process
+    overrides: Arc<RwLock<SecretOverrides>>,
 }
 
 impl KeyService {
@@ -205,6 +258,8 @@ impl KeyService {
         Self {
             dev_mode,
             library_id,
+            held_encryption_key: Arc::new(Mutex::new(None)),
+            overrides: Arc::new(RwLock::new(SecretOverrides::default())),
         }
     }
 
@@ -212,6 +267,11 @@ impl KeyService {
         self.dev_mode
     }
 
+    /// Replace the secrets read ahead of the keyring or environment.
+    pub fn set_overrides(&self, overrides: SecretOverrides) {
+        *self.overrides.write().unwrap() = overrides;
+    }
+
     /// Build a namespaced account name for keyring entries.
     fn account(&self, base: &str) -> String {
         format!("{}:{}", base, self.library_id)
@@ -267,12 +327,94 @@ impl KeyService {
         }
     }
 
+    /// Read the fanart.tv API key. Returns None if not configured.
+    ///
+    /// Dev mode: reads `BAE_FANART_API_KEY` env var.
+    /// Prod mode: reads from OS keyring.
+    pub fn get_fanart_key(&self) -> Option<String> {
+        if self.dev_mode {
+            std::env::var("BAE_FANART_API_KEY")
+                .ok()
+                .filter(|k| !k.is_empty())
+        } else {
+            keyring_core::Entry::new("bae", &self.account("fanart_api_key"))
+                .ok()
+                .and_then(|e| e.get_password().ok())
+                .filter(|k| !k.is_empty())
+        }
+    }
+
+    /// Save the fanart.tv API key to the OS keyring.
+    /// Errors in dev mode (use environment variables instead).
+    pub fn set_fanart_key(&self, value: &str) -> Result<(), KeyError> {
+        if self.dev_mode {
+            return Err(KeyError::DevMode);
+        }
+
+        keyring_core::Entry::new("bae", &self.account("fanart_api_key"))?.set_password(value)?;
+        info!("fanart.tv API key saved to keyring");
+        Ok(())
+    }
+
+    /// Read the Bandcamp `identity` session cookie. Returns None if not configured.
+    ///
+    /// Dev mode: reads `BAE_BANDCAMP_IDENTITY` env var.
+    /// Prod mode: reads from OS keyring.
+    pub fn get_bandcamp_identity(&self) -> Option<String> {
+        if self.dev_mode {
+            std::env::var("BAE_BANDCAMP_IDENTITY")
+                .ok()
+                .filter(|k| !k.is_empty())
+        } else {
+            keyring_core::Entry::new("bae", &self.account("bandcamp_identity"))
+                .ok()
+                .and_then(|e| e.get_password().ok())
+                .filter(|k| !k.is_empty())
+        }
+    }
+
+    /// Save the Bandcamp session cookie to the OS keyring.
+    /// Errors in dev mode (use environment variables instead).
+    pub fn set_bandcamp_identity(&self, value: &str) -> Result<(), KeyError> {
+        if self.dev_mode {
+            return Err(KeyError::DevMode);
+        }
+
+        keyring_core::Entry::new("bae", &self.account("bandcamp_identity"))?.set_password(value)?;
+        info!("Bandcamp session saved to keyring");
+        Ok(())
+    }
+
+    /// Delete the Bandcamp session cookie from the OS keyring.
+    /// Errors in dev mode.
+    pub fn delete_bandcamp_identity(&self) -> Result<(), KeyError> {
+        if self.dev_mode {
+            return Err(KeyError::DevMode);
+        }
+
+        match keyring_core::Entry::new("bae", &self.account("bandcamp_identity"))?
+            .delete_credential()
+        {
+            Ok(()) => {
+                info!("Bandcamp session deleted from keyring");
+                Ok(())
+            }
+            Err(keyring_core::Error::NoEntry) => Ok(()),
+            Err(e) => Err(KeyError::Keyring(e)),
+        }
+    }
+
     /// Read the encryption master key. Returns None if not configured.
     ///
     /// Dev mode: reads `BAE_ENCRYPTION_KEY` env var.
     /// Prod mode: reads from OS keyring (may trigger a system prompt on first access).
+    /// Either way, falls back to a key held in memory after a security key unlock.
     pub fn get_encryption_key(&self) -> Option<String> {
-        if self.dev_mode {
+        if let Some(key) = self.overrides.read().unwrap().encryption_key.clone() {
+            return Some(key);
+        }
+
+        let stored = if self.dev_mode {
             std::env::var("BAE_ENCRYPTION_KEY")
                 .ok()
                 .filter(|k| !k.is_empty())
@@ -281,6 +423,34 @@ impl KeyService {
                 .ok()
                 .and_then(|e| e.get_password().ok())
                 .filter(|k| !k.is_empty())
+        };
+        stored.or_else(|| self.held_encryption_key.lock().unwrap().clone())
+    }
+
+    /// Keep the master key in memory for the rest of this process, for a
+    /// library whose key lives wrapped by a security key instead of in the
+    /// keyring.
+    pub fn hold_encryption_key(&self, value: &str) {
+        *self.held_encryption_key.lock().unwrap() = Some(value.to_string());
+    }
+
+    /// Delete the encryption master key from the keyring.
+    ///
+    /// Dev mode: errors (the key comes from the environment).
+    /// Prod mode: deletes from OS keyring. Silently ignores missing entries.
+    pub fn delete_encryption_key(&self) -> Result<(), KeyError> {
+        if self.dev_mode {
+            return Err(KeyError::DevMode);
+        }
+
+        let account = self.account("encryption_master_key");
+        match keyring_core::Entry::new("bae", &account)?.delete_credential() {
+            Ok(()) => {
+                info!("Encryption key deleted from keyring");
+                Ok(())
+            }
+            Err(keyring_core::Error::NoEntry) => Ok(()),
+            Err(e) => Err(KeyError::Keyring(e)),
         }
     }
 
@@ -315,6 +485,57 @@ impl KeyService {
         Ok(())
     }
 
+    /// Read the master key a rotation replaced. Only set while stored files
+    /// are being re-encrypted under the new key.
+    ///
+    /// Dev mode: reads `BAE_PREVIOUS_ENCRYPTION_KEY` env var.
+    /// Prod mode: reads from OS keyring.
+    pub fn get_previous_encryption_key(&self) -> Option<String> {
+        if self.dev_mode {
+            std::env::var("BAE_PREVIOUS_ENCRYPTION_KEY")
+                .ok()
+                .filter(|k| !k.is_empty())
+        } else {
+            keyring_core::Entry::new("bae", &self.account("previous_encryption_master_key"))
+                .ok()
+                .and_then(|e| e.get_password().ok())
+                .filter(|k| !k.is_empty())
+        }
+    }
+
+    /// Save the master key a rotation is replacing.
+    /// Errors in dev mode (use environment variables instead).
+    pub fn set_previous_encryption_key(&self, value: &str) -> Result<(), KeyError> {
+        if self.dev_mode {
+            return Err(KeyError::DevMode);
+        }
+
+        keyring_core::Entry::new("bae", &self.account("previous_encryption_master_key"))?
+            .set_password(value)?;
+        info!("Previous encryption key saved to keyring");
+        Ok(())
+    }
+
+    /// Delete the previous master key once a rotation completes.
+    ///
+    /// Dev mode: no-op.
+    /// Prod mode: deletes from OS keyring. Silently ignores missing entries.
+    pub fn delete_previous_encryption_key(&self) -> Result<(), KeyError> {
+        if self.dev_mode {
+            return Ok(());
+        }
+
+        let account = self.account("previous_encryption_master_key");
+        match keyring_core::Entry::new("bae", &account)?.delete_credential() {
+            Ok(()) => {
+                info!("Previous encryption key deleted from keyring");
+                Ok(())
+            }
+            Err(keyring_core::Error::NoEntry) => Ok(()),
+            Err(e) => Err(KeyError::Keyring(e)),
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Per-storage-profile S3 credentials
     // -------------------------------------------------------------------------
@@ -429,6 +650,16 @@ impl KeyService {
     /// Dev mode: reads `BAE_CLOUD_HOME_CREDENTIALS` env var (JSON).
     /// Prod mode: reads from OS keyring.
     pub fn get_cloud_home_credentials(&self) -> Option<CloudHomeCredentials> {
+        if let Some(creds) = self
+            .overrides
+            .read()
+            .unwrap()
+            .cloud_home_credentials
+            .clone()
+        {
+            return Some(creds);
+        }
+
         let json = if self.dev_mode {
             std::env::var("BAE_CLOUD_HOME_CREDENTIALS")
                 .ok()
@@ -484,6 +715,67 @@ impl KeyService {
         }
     }
 
+    // -------------------------------------------------------------------------
+    // MusicBrainz account (library-scoped, OAuth tokens)
+    // -------------------------------------------------------------------------
+
+    /// Read the linked MusicBrainz account's OAuth tokens. Returns None if not linked.
+    ///
+    /// Dev mode: reads `BAE_MUSICBRAINZ_TOKENS` env var (JSON).
+    /// Prod mode: reads from OS keyring.
+    pub fn get_musicbrainz_tokens(&self) -> Option<OAuthTokens> {
+        let json = if self.dev_mode {
+            std::env::var("BAE_MUSICBRAINZ_TOKENS")
+                .ok()
+                .filter(|k| !k.is_empty())
+        } else {
+            keyring_core::Entry::new("bae", &self.account("musicbrainz_tokens"))
+                .ok()
+                .and_then(|e| e.get_password().ok())
+                .filter(|k| !k.is_empty())
+        };
+
+        json.and_then(|j| serde_json::from_str(&j).ok())
+    }
+
+    /// Save MusicBrainz OAuth tokens.
+    ///
+    /// Dev mode: sets the env var.
+    /// Prod mode: writes to OS keyring.
+    pub fn set_musicbrainz_tokens(&self, tokens: &OAuthTokens) -> Result<(), KeyError> {
+        let json = serde_json::to_string(tokens)
+            .map_err(|e| KeyError::Crypto(format!("serialize tokens: {e}")))?;
+
+        if self.dev_mode {
+            std::env::set_var("BAE_MUSICBRAINZ_TOKENS", &json);
+            return Ok(());
+        }
+
+        keyring_core::Entry::new("bae", &self.account("musicbrainz_tokens"))?
+            .set_password(&json)?;
+        info!("MusicBrainz tokens saved to keyring");
+        Ok(())
+    }
+
+    /// Delete MusicBrainz OAuth tokens. Silently ignores missing entries.
+    pub fn delete_musicbrainz_tokens(&self) -> Result<(), KeyError> {
+        if self.dev_mode {
+            std::env::remove_var("BAE_MUSICBRAINZ_TOKENS");
+            return Ok(());
+        }
+
+        match keyring_core::Entry::new("bae", &self.account("musicbrainz_tokens"))?
+            .delete_credential()
+        {
+            Ok(()) => {
+                info!("MusicBrainz tokens deleted from keyring");
+                Ok(())
+            }
+            Err(keyring_core::Error::NoEntry) => Ok(()),
+            Err(e) => Err(KeyError::Keyring(e)),
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Server password (library-scoped)
     // -------------------------------------------------------------------------
@@ -493,6 +785,10 @@ impl KeyService {
     /// Dev mode: reads `BAE_SERVER_PASSWORD` env var.
     /// Prod mode: reads from OS keyring.
     pub fn get_server_password(&self) -> Option<String> {
+        if let Some(password) = self.overrides.read().unwrap().server_password.clone() {
+            return Some(password);
+        }
+
         if self.dev_mode {
             std::env::var("BAE_SERVER_PASSWORD")
                 .ok()
@@ -544,6 +840,126 @@ impl KeyService {
         }
     }
 
+    // -------------------------------------------------------------------------
+    // Server TOTP secret (library-scoped)
+    // -------------------------------------------------------------------------
+
+    /// Read the server's two-factor secret (base32). Returns None if 2FA is off.
+    ///
+    /// Dev mode: reads `BAE_SERVER_TOTP_SECRET` env var.
+    /// Prod mode: reads from OS keyring.
+    pub fn get_server_totp_secret(&self) -> Option<String> {
+        if self.dev_mode {
+            std::env::var("BAE_SERVER_TOTP_SECRET")
+                .ok()
+                .filter(|k| !k.is_empty())
+        } else {
+            let account = self.account("server_totp_secret");
+            keyring_core::Entry::new("bae", &account)
+                .ok()
+                .and_then(|e| e.get_password().ok())
+                .filter(|k| !k.is_empty())
+        }
+    }
+
+    /// Save the server's two-factor secret (base32).
+    ///
+    /// Dev mode: sets the env var.
+    /// Prod mode: writes to OS keyring.
+    pub fn set_server_totp_secret(&self, secret: &str) -> Result<(), KeyError> {
+        if self.dev_mode {
+            std::env::set_var("BAE_SERVER_TOTP_SECRET", secret);
+            return Ok(());
+        }
+
+        let account = self.account("server_totp_secret");
+        keyring_core::Entry::new("bae", &account)?.set_password(secret)?;
+
+        info!("Server TOTP secret saved to keyring");
+        Ok(())
+    }
+
+    /// Delete the server's two-factor secret, turning 2FA off.
+    ///
+    /// Dev mode: removes env var.
+    /// Prod mode: deletes from OS keyring. Silently ignores missing entries.
+    pub fn delete_server_totp_secret(&self) -> Result<(), KeyError> {
+        if self.dev_mode {
+            std::env::remove_var("BAE_SERVER_TOTP_SECRET");
+            return Ok(());
+        }
+
+        let account = self.account("server_totp_secret");
+        match keyring_core::Entry::new("bae", &account)?.delete_credential() {
+            Ok(()) => {
+                info!("Server TOTP secret deleted from keyring");
+                Ok(())
+            }
+            Err(keyring_core::Error::NoEntry) => Ok(()),
+            Err(e) => Err(KeyError::Keyring(e)),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Import network share password (library-scoped)
+    // -------------------------------------------------------------------------
+
+    /// Read the password for the import network share. Returns None if not set.
+    ///
+    /// Dev mode: reads `BAE_NETWORK_SHARE_PASSWORD` env var.
+    /// Prod mode: reads from OS keyring.
+    pub fn get_network_share_password(&self) -> Option<String> {
+        if self.dev_mode {
+            std::env::var("BAE_NETWORK_SHARE_PASSWORD")
+                .ok()
+                .filter(|k| !k.is_empty())
+        } else {
+            let account = self.account("network_share_password");
+            keyring_core::Entry::new("bae", &account)
+                .ok()
+                .and_then(|e| e.get_password().ok())
+                .filter(|k| !k.is_empty())
+        }
+    }
+
+    /// Save the password for the import network share.
+    ///
+    /// Dev mode: sets the env var.
+    /// Prod mode: writes to OS keyring.
+    pub fn set_network_share_password(&self, password: &str) -> Result<(), KeyError> {
+        if self.dev_mode {
+            std::env::set_var("BAE_NETWORK_SHARE_PASSWORD", password);
+            return Ok(());
+        }
+
+        let account = self.account("network_share_password");
+        keyring_core::Entry::new("bae", &account)?.set_password(password)?;
+
+        info!("Network share password saved to keyring");
+        Ok(())
+    }
+
+    /// Delete the password for the import network share.
+    ///
+    /// Dev mode: removes env var.
+    /// Prod mode: deletes from OS keyring. Silently ignores missing entries.
+    pub fn delete_network_share_password(&self) -> Result<(), KeyError> {
+        if self.dev_mode {
+            std::env::remove_var("BAE_NETWORK_SHARE_PASSWORD");
+            return Ok(());
+        }
+
+        let account = self.account("network_share_password");
+        match keyring_core::Entry::new("bae", &account)?.delete_credential() {
+            Ok(()) => {
+                info!("Network share password deleted from keyring");
+                Ok(())
+            }
+            Err(keyring_core::Error::NoEntry) => Ok(()),
+            Err(e) => Err(KeyError::Keyring(e)),
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Followed library encryption keys (library-scoped, per followed library)
     // -------------------------------------------------------------------------
@@ -623,6 +1039,56 @@ impl KeyService {
         }
     }
 
+    // -------------------------------------------------------------------------
+    // Device signing key (library-scoped)
+    // -------------------------------------------------------------------------
+
+    /// Load this device's changeset signing key, creating a random one if
+    /// none exists. It never leaves the device, so revoking it in the
+    /// membership chain can't be undone by anyone holding the user keypair.
+    ///
+    /// Dev mode: reads `BAE_DEVICE_SIGNING_KEY` env var (hex), generating and
+    /// setting it if missing.
+    /// Prod mode: reads from OS keyring.
+    pub fn get_or_create_device_keypair(&self) -> Result<UserKeypair, KeyError> {
+        let stored = if self.dev_mode {
+            std::env::var("BAE_DEVICE_SIGNING_KEY")
+                .ok()
+                .filter(|k| !k.is_empty())
+        } else {
+            keyring_core::Entry::new("bae", &self.account("device_signing_key"))
+                .ok()
+                .and_then(|e| e.get_password().ok())
+                .filter(|k| !k.is_empty())
+        };
+
+        if let Some(sk_hex) = stored {
+            let signing_key: [u8; sodium_ffi::SIGN_SECRETKEYBYTES] = hex::decode(&sk_hex)
+                .map_err(|e| KeyError::Crypto(format!("Invalid device key hex: {e}")))?
+                .try_into()
+                .map_err(|_| KeyError::Crypto("Device key wrong length".to_string()))?;
+            // libsodium secret keys end with the public key
+            let mut public_key = [0u8; sodium_ffi::SIGN_PUBLICKEYBYTES];
+            public_key.copy_from_slice(&signing_key[sodium_ffi::SIGN_PUBLICKEYBYTES..]);
+            return Ok(UserKeypair {
+                signing_key,
+                public_key,
+            });
+        }
+
+        let kp = UserKeypair::generate();
+        let sk_hex = hex::encode(kp.signing_key);
+        if self.dev_mode {
+            std::env::set_var("BAE_DEVICE_SIGNING_KEY", &sk_hex);
+        } else {
+            keyring_core::Entry::new("bae", &self.account("device_signing_key"))?
+                .set_password(&sk_hex)?;
+        }
+
+        info!("Generated and saved new device signing key");
+        Ok(kp)
+    }
+
     // -------------------------------------------------------------------------
     // Global user keypair (Ed25519 identity, NOT library-scoped)
     // -------------------------------------------------------------------------
@@ -655,6 +1121,10 @@ impl KeyService {
 
     /// Return just the user's Ed25519 public key, or None if no keypair exists.
     pub fn get_user_public_key(&self) -> Option<[u8; sodium_ffi::SIGN_PUBLICKEYBYTES]> {
+        if let Some(kp) = &self.overrides.read().unwrap().user_keypair {
+            return Some(kp.public_key);
+        }
+
         let pk_hex = if self.dev_mode {
             std::env::var("BAE_USER_PUBLIC_KEY")
                 .ok()
@@ -673,6 +1143,10 @@ impl KeyService {
 
     /// Internal: try to load an existing keypair from the keyring.
     fn get_user_keypair_inner(&self) -> Result<Option<UserKeypair>, KeyError> {
+        if let Some(kp) = self.overrides.read().unwrap().user_keypair.clone() {
+            return Ok(Some(kp));
+        }
+
         let (sk_hex, pk_hex) = if self.dev_mode {
             let sk = std::env::var("BAE_USER_SIGNING_KEY")
                 .ok()
@@ -929,4 +1403,44 @@ mod tests {
         std::env::remove_var("BAE_USER_SIGNING_KEY");
         std::env::remove_var("BAE_USER_PUBLIC_KEY");
     }
+
+    #[test]
+    fn device_keypair_is_random_and_kept() {
+        std::env::remove_var("BAE_DEVICE_SIGNING_KEY");
+        let ks = KeyService::new(true, "test-device-key".to_string());
+
+        let kp = ks.get_or_create_device_keypair().unwrap();
+        let again = ks.get_or_create_device_keypair().unwrap();
+        assert_eq!(again.public_key, kp.public_key);
+        assert_eq!(again.signing_key, kp.signing_key);
+
+        let sig = again.sign(b"changeset payload");
+        assert!(verify_signature(&sig, b"changeset payload", &kp.public_key));
+
+        // A fresh device gets a key of its own
+        std::env::remove_var("BAE_DEVICE_SIGNING_KEY");
+        let other = ks.get_or_create_device_keypair().unwrap();
+        assert_ne!(other.public_key, kp.public_key);
+
+        std::env::remove_var("BAE_DEVICE_SIGNING_KEY");
+    }
+
+    #[test]
+    fn wrapped_key_needs_the_same_secret() {
+        let key_hex = hex::encode(crate::encryption::generate_random_key());
+        let wrapped = WrappedEncryptionKey::wrap(&key_hex, b"credential", &[3u8; 32], [9u8; 32]);
+
+        assert_eq!(wrapped.unwrap_key([9u8; 32]).unwrap(), key_hex);
+        assert!(wrapped.unwrap_key([8u8; 32]).is_err());
+    }
+
+    #[test]
+    fn held_key_is_shared_by_clones() {
+        let ks = KeyService::new(true, "held-key-library".to_string());
+        let clone = ks.clone();
+        assert_eq!(ks.get_encryption_key(), None);
+
+        clone.hold_encryption_key(&"ab".repeat(32));
+        assert_eq!(ks.get_encryption_key(), Some("ab".repeat(32)));
+    }
 }
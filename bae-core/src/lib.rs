@@ -1,3 +1,4 @@
+pub mod admin_routes;
 pub mod audio_codec;
 pub mod bae_cloud_api;
 pub mod cache;
@@ -8,32 +9,51 @@ pub mod cloud_routes;
 pub mod cloud_storage;
 #[doc(hidden)]
 pub mod config;
+mod config_migration;
 pub mod content_type;
+pub mod cover_thumbnails;
 pub mod cue_flac;
 pub mod db;
+#[cfg(feature = "demo")]
+pub mod demo;
 pub mod device_link;
 pub mod discogs;
 pub mod encryption;
+pub mod error_code;
 pub mod file_service;
 pub mod follow_code;
+pub mod genres;
+pub mod hls;
 pub mod hmac_utils;
+pub mod image_placeholder;
 pub mod image_server;
 pub mod import;
 pub mod join_code;
+pub mod jukebox;
 pub mod keys;
 pub mod library;
 pub mod library_dir;
+pub mod lyrics;
 pub mod musicbrainz;
 pub mod network;
 pub mod oauth;
+pub mod party;
 pub mod playback;
+pub mod recovery_kit;
+pub mod recovery_shares;
 pub mod retry;
+pub mod server_auth;
+pub mod server_config;
+pub mod server_health;
+pub mod server_metrics;
+pub mod server_warmup;
 pub mod sodium_ffi;
 pub mod storage;
 pub mod subsonic;
 pub mod sync;
-#[cfg(feature = "test-utils")]
+#[cfg(any(test, feature = "test-utils"))]
 pub mod test_support;
 pub mod text_encoding;
 #[cfg(feature = "torrent")]
 pub mod torrent;
+pub mod totp;
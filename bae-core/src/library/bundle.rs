@@ -0,0 +1,380 @@
+//! Portable library bundles
+//!
+//! A bundle is a single zip holding what a one-time move to another machine
+//! needs, without setting up sync: a `VACUUM INTO` copy of the database, the
+//! images directory, the library manifest and config, and optionally the
+//! locally managed audio under `storage/`. Keyring secrets are not included,
+//! so the encryption key has to be entered again on restore.
+
+use crate::config::ConfigYaml;
+use crate::db::Database;
+use crate::library_dir::{LibraryDir, Manifest};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+use thiserror::Error;
+use tracing::info;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// File extension for bundles (the file is a plain zip)
+pub const BUNDLE_EXTENSION: &str = "baebundle";
+const FORMAT_VERSION: u32 = 1;
+const INFO_ENTRY: &str = "bundle.json";
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Not a bae library bundle: {0}")]
+    Invalid(String),
+    #[error("This bundle was made by a newer version of bae (format {0})")]
+    UnsupportedVersion(u32),
+    #[error("Library {0} already exists on this machine")]
+    AlreadyExists(String),
+}
+
+/// Contents of `bundle.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleInfo {
+    pub format_version: u32,
+    /// RFC 3339
+    pub created_at: String,
+    pub includes_audio: bool,
+    pub manifest: Manifest,
+}
+
+/// Write a bundle of the library to `dest`.
+///
+/// The zip is written beside `dest` and renamed into place once complete, so
+/// a failed export never leaves a truncated bundle behind.
+pub async fn export_bundle(
+    database: &Database,
+    library_dir: &LibraryDir,
+    manifest: Manifest,
+    dest: &Path,
+    include_audio: bool,
+) -> Result<BundleInfo, BundleError> {
+    let info = BundleInfo {
+        format_version: FORMAT_VERSION,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        includes_audio: include_audio,
+        manifest,
+    };
+
+    let db_copy = dest.with_extension("db.partial");
+    let _ = tokio::fs::remove_file(&db_copy).await;
+    database.vacuum_into(&db_copy.to_string_lossy()).await?;
+
+    info!(
+        "Exporting library bundle to {} (audio: {})",
+        dest.display(),
+        include_audio
+    );
+
+    let library_dir = library_dir.clone();
+    let dest = dest.to_path_buf();
+    let bundle_info = info.clone();
+    let db_copy_for_write = db_copy.clone();
+    let result = match tokio::task::spawn_blocking(move || {
+        write_bundle(&bundle_info, &library_dir, &db_copy_for_write, &dest)
+    })
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => Err(BundleError::Io(std::io::Error::other(format!(
+            "Bundle task failed: {}",
+            e
+        )))),
+    };
+    let _ = tokio::fs::remove_file(&db_copy).await;
+    result?;
+
+    Ok(info)
+}
+
+fn write_bundle(
+    info: &BundleInfo,
+    library_dir: &LibraryDir,
+    db_copy: &Path,
+    dest: &Path,
+) -> Result<(), BundleError> {
+    let partial = dest.with_extension("partial");
+    let mut zip = ZipWriter::new(std::fs::File::create(&partial)?);
+    let deflated = SimpleFileOptions::default().large_file(true);
+    // Images and audio are already compressed
+    let stored = deflated.compression_method(CompressionMethod::Stored);
+
+    let write = |zip: &mut ZipWriter<std::fs::File>| -> Result<(), BundleError> {
+        zip.start_file(INFO_ENTRY, deflated)?;
+        zip.write_all(&serde_json::to_vec_pretty(info).map_err(std::io::Error::other)?)?;
+
+        zip.start_file("manifest.json", deflated)?;
+        zip.write_all(&serde_json::to_vec_pretty(&info.manifest).map_err(std::io::Error::other)?)?;
+
+        add_file(zip, &library_dir.config_path(), "config.yaml", deflated)?;
+        add_file(zip, db_copy, "library.db", deflated)?;
+        add_dir(zip, &library_dir.images_dir(), "images", stored)?;
+        if info.includes_audio {
+            add_dir(zip, &library_dir.storage_dir(), "storage", stored)?;
+        }
+        Ok(())
+    };
+
+    let result = write(&mut zip).and_then(|()| {
+        zip.finish()?;
+        Ok(())
+    });
+    match result {
+        Ok(()) => {
+            std::fs::rename(&partial, dest)?;
+            Ok(())
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+fn add_file(
+    zip: &mut ZipWriter<std::fs::File>,
+    path: &Path,
+    name: &str,
+    options: SimpleFileOptions,
+) -> Result<(), BundleError> {
+    zip.start_file(name, options)?;
+    std::io::copy(&mut std::fs::File::open(path)?, zip)?;
+    Ok(())
+}
+
+/// Add every file under `dir` as `prefix/...`. A missing directory adds nothing.
+fn add_dir(
+    zip: &mut ZipWriter<std::fs::File>,
+    dir: &Path,
+    prefix: &str,
+    options: SimpleFileOptions,
+) -> Result<(), BundleError> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            add_dir(zip, &entry.path(), &name, options)?;
+        } else {
+            add_file(zip, &entry.path(), &name, options)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read `bundle.json` without extracting anything.
+pub fn read_bundle_info(bundle: &Path) -> Result<BundleInfo, BundleError> {
+    let mut archive = ZipArchive::new(std::fs::File::open(bundle)?)?;
+    let mut entry = archive
+        .by_name(INFO_ENTRY)
+        .map_err(|_| BundleError::Invalid(format!("{} is missing", INFO_ENTRY)))?;
+    let mut json = String::new();
+    entry.read_to_string(&mut json)?;
+    let info: BundleInfo =
+        serde_json::from_str(&json).map_err(|e| BundleError::Invalid(e.to_string()))?;
+    if info.format_version > FORMAT_VERSION {
+        return Err(BundleError::UnsupportedVersion(info.format_version));
+    }
+    Ok(info)
+}
+
+/// Unpack a bundle as a new library under `libraries_dir`.
+///
+/// The restored config keeps the library settings only: the device ID,
+/// Subsonic sign-in and other machine settings are reset, since they (and the
+/// keyring entries some rely on) stay with the old machine.
+/// Blocking; run it off the async runtime.
+pub fn restore_bundle(
+    bundle: &Path,
+    libraries_dir: &Path,
+    encryption_key_stored: bool,
+) -> Result<LibraryDir, BundleError> {
+    let info = read_bundle_info(bundle)?;
+    let library_id = info.manifest.library_id.clone();
+    let target = libraries_dir.join(&library_id);
+    if target.exists() {
+        return Err(BundleError::AlreadyExists(library_id));
+    }
+
+    let staging = libraries_dir.join(format!("{}.restoring", library_id));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging)?;
+
+    let unpack = || -> Result<(), BundleError> {
+        let mut archive = ZipArchive::new(std::fs::File::open(bundle)?)?;
+        archive.extract(&staging)?;
+        std::fs::remove_file(staging.join(INFO_ENTRY))?;
+        let staged = LibraryDir::new(staging.clone());
+        if !staged.db_path().exists() {
+            return Err(BundleError::Invalid("library.db is missing".to_string()));
+        }
+        adopt_config(&staged.config_path(), encryption_key_stored)?;
+        std::fs::rename(&staging, &target)?;
+        Ok(())
+    };
+    if let Err(e) = unpack() {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    info!(
+        "Restored library {} from bundle to {}",
+        library_id,
+        target.display()
+    );
+    Ok(LibraryDir::new(target))
+}
+
+/// Rewrite the bundled config for this machine, keeping only the library
+/// settings. The old machine's device settings (ports, interfaces, keyring
+/// hints) start over at their defaults.
+fn adopt_config(config_path: &Path, encryption_key_stored: bool) -> Result<(), BundleError> {
+    let text = std::fs::read_to_string(config_path)
+        .map_err(|_| BundleError::Invalid("config.yaml is missing".to_string()))?;
+    let bundled: ConfigYaml =
+        serde_yaml::from_str(&text).map_err(|e| BundleError::Invalid(e.to_string()))?;
+    let mut yaml = bundled
+        .library_settings_only()
+        .map_err(|e| BundleError::Invalid(e.to_string()))?;
+    yaml.keys_migrated = true;
+    yaml.encryption_key_stored = encryption_key_stored;
+    std::fs::write(
+        config_path,
+        serde_yaml::to_string(&yaml).map_err(std::io::Error::other)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn library_with_files(root: &Path) -> (Database, LibraryDir, Manifest) {
+        let library_dir = LibraryDir::new(root.join("source"));
+        std::fs::create_dir_all(&*library_dir).unwrap();
+        let database = Database::new(library_dir.db_path().to_str().unwrap())
+            .await
+            .unwrap();
+
+        let cover = library_dir.image_path("0a1b2c3d-0000-0000-0000-000000000000");
+        std::fs::create_dir_all(cover.parent().unwrap()).unwrap();
+        std::fs::write(&cover, b"jpeg").unwrap();
+        let audio = library_dir.storage_file_path("9f8e7d6c-0000-0000-0000-000000000000");
+        std::fs::create_dir_all(audio.parent().unwrap()).unwrap();
+        std::fs::write(&audio, b"flac").unwrap();
+
+        let library_id = "1c0e5a2e-7f3b-4d8a-9c61-2b4f0e8d9a13".to_string();
+        std::fs::write(
+            library_dir.config_path(),
+            format!(
+                "library_id: {library_id}\nlibrary_name: Tidewater\ndevice_id: old-device\n\
+                 server_auth_enabled: true\nserver_username: admin\nserver_port: 4600\n\
+                 torrent_listen_port: 51500\nshare_base_url: https://listen.example.com\n"
+            ),
+        )
+        .unwrap();
+
+        let manifest = Manifest {
+            library_id,
+            library_name: Some("Tidewater".to_string()),
+            encryption_key_fingerprint: None,
+            passphrase_kdf: None,
+        };
+        (database, library_dir, manifest)
+    }
+
+    #[tokio::test]
+    async fn export_and_restore_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let (database, library_dir, manifest) = library_with_files(dir.path()).await;
+        let bundle = dir.path().join(format!("Tidewater.{}", BUNDLE_EXTENSION));
+
+        export_bundle(&database, &library_dir, manifest.clone(), &bundle, true)
+            .await
+            .unwrap();
+
+        let info = read_bundle_info(&bundle).unwrap();
+        assert_eq!(info.manifest.library_id, manifest.library_id);
+        assert!(info.includes_audio);
+
+        let libraries = dir.path().join("libraries");
+        let restored = restore_bundle(&bundle, &libraries, false).unwrap();
+
+        assert_eq!(*restored, libraries.join(&manifest.library_id));
+        assert!(restored.db_path().exists());
+        assert!(restored.manifest_path().exists());
+        assert!(!restored.join(INFO_ENTRY).exists());
+        assert_eq!(
+            std::fs::read(restored.image_path("0a1b2c3d-0000-0000-0000-000000000000")).unwrap(),
+            b"jpeg"
+        );
+        assert_eq!(
+            std::fs::read(restored.storage_file_path("9f8e7d6c-0000-0000-0000-000000000000"))
+                .unwrap(),
+            b"flac"
+        );
+
+        let yaml: ConfigYaml =
+            serde_yaml::from_str(&std::fs::read_to_string(restored.config_path()).unwrap())
+                .unwrap();
+        assert_eq!(yaml.device_id, None);
+        assert!(!yaml.server_auth_enabled);
+        assert_eq!(yaml.server_port, None);
+        assert_eq!(yaml.torrent_listen_port, None);
+        assert_eq!(yaml.library_name.as_deref(), Some("Tidewater"));
+        assert_eq!(
+            yaml.share_base_url.as_deref(),
+            Some("https://listen.example.com")
+        );
+
+        // A second restore would overwrite the library
+        assert!(matches!(
+            restore_bundle(&bundle, &libraries, false),
+            Err(BundleError::AlreadyExists(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn export_without_audio_skips_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let (database, library_dir, manifest) = library_with_files(dir.path()).await;
+        let bundle = dir.path().join(format!("Tidewater.{}", BUNDLE_EXTENSION));
+
+        export_bundle(&database, &library_dir, manifest, &bundle, false)
+            .await
+            .unwrap();
+
+        let archive = ZipArchive::new(std::fs::File::open(&bundle).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert!(names.contains(&"library.db"));
+        assert!(names.iter().any(|n| n.starts_with("images/")));
+        assert!(!names.iter().any(|n| n.starts_with("storage/")));
+    }
+
+    #[test]
+    fn rejects_plain_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("album.zip");
+        let mut zip = ZipWriter::new(std::fs::File::create(&path).unwrap());
+        zip.start_file("01 Low Light.flac", SimpleFileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+
+        assert!(matches!(
+            read_bundle_info(&path),
+            Err(BundleError::Invalid(_))
+        ));
+    }
+}
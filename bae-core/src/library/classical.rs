@@ -0,0 +1,239 @@
+//! Classical works and composers from MusicBrainz
+//!
+//! A classical track's recording performs a work, and that work is usually
+//! one movement of a larger one ("parts" relationship). The composer is
+//! credited on the work: sometimes on each movement, sometimes only on the
+//! whole work, in which case the whole work has to be fetched on its own.
+
+use crate::musicbrainz::{MbArtistRef, MbReleaseResponse, MbWork, MbWorkRelation};
+
+/// Work and composers MusicBrainz has for one track position
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackWorkCredit {
+    pub disc_number: i32,
+    pub track_number: i32,
+    /// MusicBrainz ID of the whole work
+    pub work_id: String,
+    pub work_title: String,
+    /// Set when the track is one movement of the work
+    pub movement_title: Option<String>,
+    pub movement_number: Option<i32>,
+    /// Composers credited on the movement or the whole work
+    pub composers: Vec<ComposerCredit>,
+}
+
+/// A composer as MusicBrainz credits them
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComposerCredit {
+    pub musicbrainz_artist_id: Option<String>,
+    pub name: String,
+    pub sort_name: Option<String>,
+}
+
+/// Works performed by a release's tracks, keyed by disc and track number the
+/// same way tracks are matched when refreshing tags. Tracks without a work
+/// are left out.
+pub fn parse_release_works(response: &MbReleaseResponse) -> Vec<TrackWorkCredit> {
+    let mut credits = Vec::new();
+    for (medium_index, medium) in response.media.iter().enumerate() {
+        for (index, track) in medium.tracks.iter().enumerate() {
+            let Some(work) = track
+                .recording
+                .as_ref()
+                .and_then(|r| performed_work(&r.relations))
+            else {
+                continue;
+            };
+            let track_number = track.position.map(|p| p as i32).unwrap_or(index as i32 + 1);
+            credits.push(credit_for_work(medium_index as i32 + 1, track_number, work));
+        }
+    }
+    credits
+}
+
+/// Whole works whose composers weren't included with their movements
+pub fn works_missing_composers(credits: &[TrackWorkCredit]) -> Vec<String> {
+    let mut work_ids: Vec<String> = credits
+        .iter()
+        .filter(|c| c.composers.is_empty())
+        .map(|c| c.work_id.clone())
+        .collect();
+    work_ids.sort();
+    work_ids.dedup();
+    work_ids
+}
+
+/// Composers credited directly on a work
+pub fn work_composers(work: &MbWork) -> Vec<ComposerCredit> {
+    work.relations
+        .iter()
+        .filter(|rel| rel.relation_type.as_deref() == Some("composer"))
+        .filter_map(|rel| rel.artist.as_ref().and_then(composer_credit))
+        .collect()
+}
+
+fn performed_work(relations: &[MbWorkRelation]) -> Option<&MbWork> {
+    relations
+        .iter()
+        .filter(|rel| rel.relation_type.as_deref() == Some("performance"))
+        .find_map(|rel| rel.work.as_ref())
+}
+
+fn credit_for_work(disc_number: i32, track_number: i32, work: &MbWork) -> TrackWorkCredit {
+    let title = work.title.clone().unwrap_or_default();
+    let composers = work_composers(work);
+
+    // A movement points back at the whole work, with its number
+    let parent = work.relations.iter().find(|rel| {
+        rel.relation_type.as_deref() == Some("parts")
+            && rel.direction.as_deref() == Some("backward")
+            && rel.work.is_some()
+    });
+    let Some((parent_rel, parent)) = parent.and_then(|rel| Some((rel, rel.work.as_ref()?))) else {
+        return TrackWorkCredit {
+            disc_number,
+            track_number,
+            work_id: work.id.clone(),
+            work_title: title,
+            movement_title: None,
+            movement_number: None,
+            composers,
+        };
+    };
+
+    let work_title = parent.title.clone().unwrap_or_default();
+    let composers = if composers.is_empty() {
+        work_composers(parent)
+    } else {
+        composers
+    };
+    TrackWorkCredit {
+        disc_number,
+        track_number,
+        work_id: parent.id.clone(),
+        movement_title: Some(movement_title(&work_title, &title)),
+        movement_number: parent_rel.ordering_key.map(|k| k as i32),
+        work_title,
+        composers,
+    }
+}
+
+/// Movement titles usually repeat the whole work's title:
+/// "Symphony No. 5 in C minor, Op. 67: I. Allegro con brio"
+fn movement_title(work_title: &str, title: &str) -> String {
+    title
+        .strip_prefix(work_title)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .map(str::trim)
+        .filter(|rest| !rest.is_empty())
+        .unwrap_or(title)
+        .to_string()
+}
+
+fn composer_credit(artist: &MbArtistRef) -> Option<ComposerCredit> {
+    let name = artist
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())?;
+    Some(ComposerCredit {
+        musicbrainz_artist_id: artist.id.clone(),
+        name: name.to_string(),
+        sort_name: artist.sort_name.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(json: serde_json::Value) -> MbReleaseResponse {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn composer_rel(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "type": "composer",
+            "direction": "backward",
+            "artist": { "id": format!("mb-{name}"), "name": name, "sort-name": name }
+        })
+    }
+
+    #[test]
+    fn movements_are_grouped_under_the_whole_work() {
+        let whole = serde_json::json!({
+            "id": "work-whole",
+            "title": "Tidewater Suite",
+            "relations": [composer_rel("Glass Harbor")]
+        });
+        let movement = |key: i64, title: &str| {
+            serde_json::json!({
+                "position": key,
+                "recording": {
+                    "title": title,
+                    "relations": [{
+                        "type": "performance",
+                        "work": {
+                            "id": format!("work-{key}"),
+                            "title": format!("Tidewater Suite: {title}"),
+                            "relations": [{
+                                "type": "parts",
+                                "direction": "backward",
+                                "ordering-key": key,
+                                "work": whole.clone()
+                            }]
+                        }
+                    }]
+                }
+            })
+        };
+        let response = release(serde_json::json!({
+            "id": "mb-release",
+            "title": "Low Light",
+            "media": [{ "tracks": [movement(1, "I. Pacific Standard"), movement(2, "II. Low Light")] }]
+        }));
+
+        let credits = parse_release_works(&response);
+
+        assert_eq!(credits.len(), 2);
+        assert_eq!(credits[0].work_id, "work-whole");
+        assert_eq!(credits[0].work_title, "Tidewater Suite");
+        assert_eq!(
+            credits[0].movement_title.as_deref(),
+            Some("I. Pacific Standard")
+        );
+        assert_eq!(credits[1].movement_number, Some(2));
+        assert_eq!(credits[1].composers[0].name, "Glass Harbor");
+        assert!(works_missing_composers(&credits).is_empty());
+    }
+
+    #[test]
+    fn standalone_work_without_composer_is_flagged() {
+        let response = release(serde_json::json!({
+            "id": "mb-release",
+            "title": "Low Light",
+            "media": [{ "tracks": [
+                {
+                    "position": 1,
+                    "recording": {
+                        "title": "Pacific Standard",
+                        "relations": [{
+                            "type": "performance",
+                            "work": { "id": "work-1", "title": "Pacific Standard" }
+                        }]
+                    }
+                },
+                { "position": 2, "recording": { "title": "Interlude" } }
+            ] }]
+        }));
+
+        let credits = parse_release_works(&response);
+
+        assert_eq!(credits.len(), 1);
+        assert_eq!(credits[0].movement_title, None);
+        assert_eq!(
+            works_missing_composers(&credits),
+            vec!["work-1".to_string()]
+        );
+    }
+}
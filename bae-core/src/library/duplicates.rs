@@ -0,0 +1,239 @@
+//! Finding releases that were imported more than once
+//!
+//! Two kinds of duplicate are reported: albums matched to the same
+//! MusicBrainz release group, unless the user already linked them as
+//! versions of each other, and releases whose audio files hash the same.
+//! File hashes are recorded on import, so releases imported before that
+//! only show up by release group.
+
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+/// Why releases were grouped as duplicates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateReason {
+    /// Their albums are matched to the same MusicBrainz release group
+    SameReleaseGroup,
+    /// Every audio file matches byte for byte
+    IdenticalAudio,
+}
+
+/// Releases that look like copies of each other, oldest import first
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub reason: DuplicateReason,
+    pub release_ids: Vec<String>,
+}
+
+/// A release of a duplicate group, with what's needed to choose which to keep
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateRelease {
+    pub release_id: String,
+    pub album_id: String,
+    pub album_title: String,
+    pub artist_name: String,
+    pub release_name: Option<String>,
+    pub year: Option<i32>,
+    pub format: Option<String>,
+    pub track_count: usize,
+    /// Plays of the release's tracks
+    pub play_count: i64,
+    pub managed_locally: bool,
+    pub managed_in_cloud: bool,
+    pub unmanaged: bool,
+}
+
+/// A duplicate group with its releases filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateReleases {
+    pub reason: DuplicateReason,
+    pub releases: Vec<DuplicateRelease>,
+}
+
+/// Hex SHA-256 of a file's bytes
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Hex SHA-256 of a file on disk, read in chunks off the async runtime
+pub async fn file_sha256(path: &Path) -> std::io::Result<String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(hex::encode(hasher.finalize()))
+    })
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+/// Group releases into duplicates.
+///
+/// `release_groups` holds (release group ID, album ID, release ID) for the
+/// albums sharing a release group, oldest release first. `version_groups`
+/// maps album IDs to their version group. `checksums` holds
+/// (release ID, audio file hash) for every hashed audio file.
+pub fn find_duplicate_groups(
+    release_groups: &[(String, String, String)],
+    version_groups: &HashMap<String, String>,
+    checksums: &[(String, String)],
+) -> Vec<DuplicateGroup> {
+    let mut groups = Vec::new();
+
+    // Releases with the same set of audio hashes, in the order they're first seen
+    let mut hashes_by_release: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    let mut release_order: Vec<&str> = Vec::new();
+    for (release_id, sha256) in checksums {
+        let hashes = hashes_by_release
+            .entry(release_id.as_str())
+            .or_insert_with(|| {
+                release_order.push(release_id.as_str());
+                BTreeSet::new()
+            });
+        hashes.insert(sha256.as_str());
+    }
+    let mut by_audio: Vec<(&BTreeSet<&str>, Vec<String>)> = Vec::new();
+    for release_id in release_order {
+        let hashes = &hashes_by_release[release_id];
+        match by_audio.iter_mut().find(|(h, _)| *h == hashes) {
+            Some((_, ids)) => ids.push(release_id.to_string()),
+            None => by_audio.push((hashes, vec![release_id.to_string()])),
+        }
+    }
+    for (_, release_ids) in by_audio {
+        if release_ids.len() > 1 {
+            groups.push(DuplicateGroup {
+                reason: DuplicateReason::IdenticalAudio,
+                release_ids,
+            });
+        }
+    }
+
+    let mut by_release_group: Vec<(&str, Vec<(&str, &str)>)> = Vec::new();
+    for (release_group_id, album_id, release_id) in release_groups {
+        let release = (album_id.as_str(), release_id.as_str());
+        match by_release_group
+            .iter_mut()
+            .find(|(rg, _)| *rg == release_group_id.as_str())
+        {
+            Some((_, releases)) => releases.push(release),
+            None => by_release_group.push((release_group_id.as_str(), vec![release])),
+        }
+    }
+    for (_, releases) in by_release_group {
+        let albums: HashSet<&str> = releases.iter().map(|(album_id, _)| *album_id).collect();
+        if albums.len() < 2 {
+            continue;
+        }
+        // Albums linked as versions are editions the user wants to keep apart
+        let version_group = version_groups.get(releases[0].0);
+        if version_group.is_some()
+            && albums
+                .iter()
+                .all(|album_id| version_groups.get(*album_id) == version_group)
+        {
+            continue;
+        }
+        let release_ids: Vec<String> = releases.iter().map(|(_, id)| id.to_string()).collect();
+        // Identical audio already says everything this group would
+        let ids: HashSet<&String> = release_ids.iter().collect();
+        if groups
+            .iter()
+            .any(|g| g.release_ids.iter().collect::<HashSet<_>>() == ids)
+        {
+            continue;
+        }
+        groups.push(DuplicateGroup {
+            reason: DuplicateReason::SameReleaseGroup,
+            release_ids,
+        });
+    }
+
+    groups
+}
+
+/// Pair each track of a duplicate release with the kept release's track it
+/// stands for: by disc and track number, then by title. Tracks without a
+/// counterpart are left out.
+pub fn match_duplicate_tracks(
+    duplicate: &[crate::db::DbTrack],
+    kept: &[crate::db::DbTrack],
+) -> Vec<(String, String)> {
+    duplicate
+        .iter()
+        .filter_map(|track| {
+            let by_position = track.track_number.and_then(|number| {
+                kept.iter().find(|k| {
+                    k.track_number == Some(number)
+                        && k.disc_number.unwrap_or(1) == track.disc_number.unwrap_or(1)
+                })
+            });
+            let kept_track = by_position.or_else(|| {
+                kept.iter()
+                    .find(|k| k.title.to_lowercase() == track.title.to_lowercase())
+            })?;
+            Some((track.id.clone(), kept_track.id.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triple(rg: &str, album: &str, release: &str) -> (String, String, String) {
+        (rg.to_string(), album.to_string(), release.to_string())
+    }
+
+    fn pair(release: &str, sha256: &str) -> (String, String) {
+        (release.to_string(), sha256.to_string())
+    }
+
+    #[test]
+    fn identical_audio_groups_releases_with_the_same_files() {
+        let checksums = vec![
+            pair("r1", "a"),
+            pair("r1", "b"),
+            pair("r2", "b"),
+            pair("r2", "a"),
+            pair("r3", "a"),
+        ];
+
+        let groups = find_duplicate_groups(&[], &HashMap::new(), &checksums);
+
+        assert_eq!(
+            groups,
+            vec![DuplicateGroup {
+                reason: DuplicateReason::IdenticalAudio,
+                release_ids: vec!["r1".to_string(), "r2".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn release_group_skips_linked_versions_and_identical_audio() {
+        let release_groups = vec![
+            triple("rg-glass", "album-1", "r1"),
+            triple("rg-glass", "album-2", "r2"),
+            triple("rg-tide", "album-3", "r3"),
+            triple("rg-tide", "album-4", "r4"),
+            triple("rg-low", "album-5", "r5"),
+            triple("rg-low", "album-6", "r6"),
+        ];
+        let version_groups = HashMap::from([
+            ("album-3".to_string(), "v1".to_string()),
+            ("album-4".to_string(), "v1".to_string()),
+        ]);
+        let checksums = vec![pair("r5", "x"), pair("r6", "x")];
+
+        let groups = find_duplicate_groups(&release_groups, &version_groups, &checksums);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].reason, DuplicateReason::IdenticalAudio);
+        assert_eq!(groups[0].release_ids, vec!["r5", "r6"]);
+        assert_eq!(groups[1].reason, DuplicateReason::SameReleaseGroup);
+        assert_eq!(groups[1].release_ids, vec!["r1", "r2"]);
+    }
+}
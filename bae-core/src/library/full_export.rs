@@ -0,0 +1,676 @@
+//! Full library export
+//!
+//! The way out of an encrypted library: every release, wherever its files are
+//! kept, written as plain files under one folder with a `manifest.json` that
+//! lists where each track went and which releases couldn't be exported.
+//! Tracks are named by the `export_name_template` setting. A track with its
+//! own file keeps its original bytes; a track cut from a CUE/FLAC image is
+//! decoded to its exact samples and re-encoded. FLAC tracks get their Vorbis
+//! comments rewritten from the library's metadata. Cover art, CUE sheets and
+//! logs go next to the tracks under their original names.
+
+use crate::cloud_storage::CloudStorage;
+use crate::content_type::ContentType;
+use crate::db::{DbAudioFormat, DbFile, DbRelease, DbTrack};
+use crate::encryption::ContainerFormat;
+use crate::library::{LibraryError, LibraryManager};
+use crate::library_dir::LibraryDir;
+use crate::storage::{
+    storage_path, DownloadPriority, ParallelDownloader, DEFAULT_DOWNLOAD_PARALLELISM,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Track path used when `export_name_template` isn't set
+pub const DEFAULT_NAME_TEMPLATE: &str = "{artist}/{album}/{disc}-{track} {title}";
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// FLAC metadata block types
+const STREAMINFO_BLOCK: u8 = 0;
+const VORBIS_COMMENT_BLOCK: u8 = 4;
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportManifest {
+    /// RFC 3339
+    pub created_at: String,
+    pub name_template: String,
+    pub releases: Vec<ExportedRelease>,
+    pub failures: Vec<ExportFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedRelease {
+    pub release_id: String,
+    pub artist: String,
+    pub album: String,
+    pub release_name: Option<String>,
+    pub year: Option<i32>,
+    pub tracks: Vec<ExportedTrack>,
+    /// Other files of the release, relative to the export folder
+    pub other_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedTrack {
+    pub track_id: String,
+    pub title: String,
+    /// Relative to the export folder
+    pub path: String,
+    /// Hex SHA-256 of the written file
+    pub sha256: String,
+    /// Whether the library's tags were written into the file. Only FLAC is
+    /// tagged; other formats keep whatever tags they were imported with.
+    pub tagged: bool,
+}
+
+/// A release that couldn't be exported. The rest of the export goes on.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportFailure {
+    pub release_id: String,
+    pub album: String,
+    pub error: String,
+}
+
+/// Export every release to `target_dir` and write its manifest there.
+/// `storage` reads releases kept in the cloud home; without it they are
+/// listed as failures.
+pub async fn export_library(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    storage: Option<Arc<dyn CloudStorage>>,
+    name_template: &str,
+    target_dir: &Path,
+) -> Result<ExportManifest, LibraryError> {
+    tokio::fs::create_dir_all(target_dir).await?;
+    info!("Exporting the library to {}", target_dir.display());
+
+    let mut manifest = ExportManifest {
+        created_at: chrono::Utc::now().to_rfc3339(),
+        name_template: name_template.to_string(),
+        releases: Vec::new(),
+        failures: Vec::new(),
+    };
+    let mut used_paths = HashSet::new();
+    let exporter = ReleaseExporter {
+        library_manager,
+        library_dir,
+        storage,
+        name_template,
+        target_dir,
+    };
+
+    for release in library_manager.database().get_all_releases().await? {
+        let album = library_manager
+            .get_album_by_id(&release.album_id)
+            .await?
+            .map(|album| album.title)
+            .unwrap_or_default();
+        match exporter.export(&release, &mut used_paths).await {
+            Ok(exported) => manifest.releases.push(exported),
+            Err(error) => {
+                warn!("Failed to export release {}: {}", release.id, error);
+                manifest.failures.push(ExportFailure {
+                    release_id: release.id.clone(),
+                    album,
+                    error,
+                });
+            }
+        }
+    }
+
+    let json = serde_json::to_vec_pretty(&manifest).map_err(std::io::Error::other)?;
+    tokio::fs::write(target_dir.join(MANIFEST_FILE), json).await?;
+
+    info!(
+        "Exported {} releases ({} failed) to {}",
+        manifest.releases.len(),
+        manifest.failures.len(),
+        target_dir.display()
+    );
+    Ok(manifest)
+}
+
+struct ReleaseExporter<'a> {
+    library_manager: &'a LibraryManager,
+    library_dir: &'a LibraryDir,
+    storage: Option<Arc<dyn CloudStorage>>,
+    name_template: &'a str,
+    target_dir: &'a Path,
+}
+
+impl ReleaseExporter<'_> {
+    async fn export(
+        &self,
+        release: &DbRelease,
+        used_paths: &mut HashSet<PathBuf>,
+    ) -> Result<ExportedRelease, String> {
+        let lm = self.library_manager;
+        let db_err = |e: LibraryError| e.to_string();
+
+        let album = lm
+            .get_album_by_id(&release.album_id)
+            .await
+            .map_err(db_err)?
+            .ok_or_else(|| format!("Album {} not found", release.album_id))?;
+        let artist = lm.get_album_artist_name(&album.id).await.map_err(db_err)?;
+        let mut track_artists: HashMap<String, String> = HashMap::new();
+        for (track_id, track_artist, join_phrase) in lm
+            .get_track_artist_credits_for_release(&release.id)
+            .await
+            .map_err(db_err)?
+        {
+            let credit = track_artists.entry(track_id).or_default();
+            credit.push_str(&track_artist.name);
+            credit.push_str(join_phrase.as_deref().unwrap_or(""));
+        }
+        let files = lm
+            .get_files_for_release(&release.id)
+            .await
+            .map_err(db_err)?;
+        let tracks = lm.get_tracks(&release.id).await.map_err(db_err)?;
+
+        let year = release.year.or(album.year);
+        let release_fields = NameFields {
+            artist: artist.clone(),
+            album: album.title.clone(),
+            year: year.map(|y| y.to_string()).unwrap_or_default(),
+            disc: String::new(),
+            track: String::new(),
+            title: String::new(),
+        };
+        // Other files go in the folder the tracks' names put them in
+        let placeholder_track = NameFields {
+            title: "-".to_string(),
+            ..release_fields.clone()
+        };
+        let release_dir = render_name(self.name_template, &placeholder_track)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut exported = ExportedRelease {
+            release_id: release.id.clone(),
+            artist: artist.clone(),
+            album: album.title.clone(),
+            release_name: release.release_name.clone(),
+            year,
+            tracks: Vec::new(),
+            other_files: Vec::new(),
+        };
+
+        // Every track of a CUE/FLAC image comes from the same file
+        let mut decrypted: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut audio_file_ids = HashSet::new();
+        for track in &tracks {
+            let Some(format) = lm
+                .get_audio_format_by_track_id(&track.id)
+                .await
+                .map_err(db_err)?
+            else {
+                continue;
+            };
+            let Some(file) = format
+                .file_id
+                .as_ref()
+                .and_then(|id| files.iter().find(|f| &f.id == id))
+            else {
+                continue;
+            };
+            audio_file_ids.insert(file.id.clone());
+            if !decrypted.contains_key(&file.id) {
+                let data = self.read_file(release, file).await?;
+                decrypted.insert(file.id.clone(), data);
+            }
+
+            let audio = track_audio(&format, &decrypted[&file.id]).await?;
+            let tags = track_tags(
+                track,
+                &album.title,
+                &artist,
+                track_artists.get(&track.id).unwrap_or(&artist),
+                year,
+            );
+            let (audio, tagged) = if format.content_type == ContentType::Flac {
+                match set_flac_tags(&audio, &tags) {
+                    Some(tagged) => (tagged, true),
+                    None => (audio, false),
+                }
+            } else {
+                (audio, false)
+            };
+
+            let fields = NameFields {
+                disc: track.disc_number.unwrap_or(1).to_string(),
+                track: track
+                    .track_number
+                    .map(|n| format!("{:02}", n))
+                    .unwrap_or_default(),
+                title: track.title.clone(),
+                ..release_fields.clone()
+            };
+            let mut name = render_name(self.name_template, &fields).into_os_string();
+            name.push(".");
+            name.push(format.content_type.file_extension());
+            let path = unique_path(PathBuf::from(name), used_paths);
+            self.write(&path, &audio).await?;
+
+            exported.tracks.push(ExportedTrack {
+                track_id: track.id.clone(),
+                title: track.title.clone(),
+                path: path.to_string_lossy().into_owned(),
+                sha256: hex::encode(Sha256::digest(&audio)),
+                tagged,
+            });
+        }
+        drop(decrypted);
+
+        for file in files.iter().filter(|f| !audio_file_ids.contains(&f.id)) {
+            let data = self.read_file(release, file).await?;
+            let path = unique_path(
+                release_dir.join(relative_filename(&file.original_filename)),
+                used_paths,
+            );
+            self.write(&path, &data).await?;
+            exported
+                .other_files
+                .push(path.to_string_lossy().into_owned());
+        }
+
+        Ok(exported)
+    }
+
+    /// A file's plaintext, from wherever the release keeps it
+    async fn read_file(&self, release: &DbRelease, file: &DbFile) -> Result<Vec<u8>, String> {
+        let raw = if release.managed_locally {
+            let path = file.local_storage_path(self.library_dir);
+            tokio::fs::read(&path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        } else if let Some(ref unmanaged_path) = release.unmanaged_path {
+            let path = Path::new(unmanaged_path).join(&file.original_filename);
+            tokio::fs::read(&path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        } else if release.managed_in_cloud {
+            let storage = self.storage.clone().ok_or_else(|| {
+                "Release is kept in the cloud home, which isn't set up on this device".to_string()
+            })?;
+            let stored_len = match file.encryption_nonce {
+                Some(ref prefix) => ContainerFormat::detect(prefix)
+                    .map_err(|e| e.to_string())?
+                    .encrypted_len(file.file_size as u64),
+                None => file.file_size as u64,
+            };
+            let (data, _) = ParallelDownloader::new(storage, DEFAULT_DOWNLOAD_PARALLELISM)
+                .download_range_to_vec(
+                    &storage_path(&file.id),
+                    0,
+                    stored_len,
+                    DownloadPriority::Export,
+                )
+                .await
+                .map_err(|e| format!("Failed to download {}: {}", file.original_filename, e))?;
+            data
+        } else {
+            return Err(format!(
+                "File {} has no readable location",
+                file.original_filename
+            ));
+        };
+
+        crate::file_service::decrypt_if_needed(
+            file,
+            &release.id,
+            self.library_manager.encryption_service(),
+            raw,
+        )
+        .await
+        .map_err(|e| format!("Failed to decrypt {}: {}", file.original_filename, e))
+    }
+
+    async fn write(&self, relative: &Path, data: &[u8]) -> Result<(), String> {
+        let path = self.target_dir.join(relative);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// A track's audio as a standalone file. Tracks of a CUE/FLAC image are cut
+/// from the image at frame boundaries, so they are decoded, trimmed to their
+/// exact samples and encoded again.
+async fn track_audio(format: &DbAudioFormat, file_data: &[u8]) -> Result<Vec<u8>, String> {
+    let (Some(start), Some(end)) = (format.start_byte_offset, format.end_byte_offset) else {
+        return Ok(file_data.to_vec());
+    };
+    let slice = file_data.get(start as usize..end as usize).ok_or_else(|| {
+        format!(
+            "Byte range {}..{} out of bounds for {} byte file",
+            start,
+            end,
+            file_data.len()
+        )
+    })?;
+    let mut data = format.flac_headers.clone().unwrap_or_default();
+    data.extend_from_slice(slice);
+    let skip = format.frame_offset_samples.unwrap_or(0).max(0) as usize;
+    let count = format.exact_sample_count.map(|n| n.max(0) as usize);
+
+    tokio::task::spawn_blocking(move || {
+        let decoded = crate::audio_codec::decode_audio(&data, None, None)?;
+        let channels = decoded.channels as usize;
+        let start = (skip * channels).min(decoded.samples.len());
+        let end = count
+            .map(|n| (start + n * channels).min(decoded.samples.len()))
+            .unwrap_or(decoded.samples.len());
+        crate::audio_codec::encode_to_flac(
+            &decoded.samples[start..end],
+            decoded.sample_rate,
+            decoded.channels,
+            decoded.bits_per_sample,
+        )
+    })
+    .await
+    .map_err(|e| format!("Encoding task failed: {}", e))?
+}
+
+fn track_tags(
+    track: &DbTrack,
+    album: &str,
+    album_artist: &str,
+    artist: &str,
+    year: Option<i32>,
+) -> Vec<(&'static str, String)> {
+    let mut tags = vec![
+        ("TITLE", track.title.clone()),
+        ("ARTIST", artist.to_string()),
+        ("ALBUMARTIST", album_artist.to_string()),
+        ("ALBUM", album.to_string()),
+    ];
+    if let Some(n) = track.track_number {
+        tags.push(("TRACKNUMBER", n.to_string()));
+    }
+    if let Some(n) = track.disc_number {
+        tags.push(("DISCNUMBER", n.to_string()));
+    }
+    if let Some(year) = year {
+        tags.push(("DATE", year.to_string()));
+    }
+    tags
+}
+
+/// Replace a FLAC file's Vorbis comment block with `tags`, placed right after
+/// STREAMINFO. None if the metadata can't be parsed.
+fn set_flac_tags(data: &[u8], tags: &[(&str, String)]) -> Option<Vec<u8>> {
+    if data.get(..4)? != b"fLaC" {
+        return None;
+    }
+    let mut blocks = Vec::new();
+    let mut pos = 4;
+    loop {
+        let header = data.get(pos..pos + 4)?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7F;
+        let size = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        let body = data.get(pos + 4..pos + 4 + size)?;
+        pos += 4 + size;
+        if block_type != VORBIS_COMMENT_BLOCK {
+            blocks.push((block_type, body));
+        }
+        if is_last {
+            break;
+        }
+    }
+    if blocks.first()?.0 != STREAMINFO_BLOCK {
+        return None;
+    }
+
+    let mut comment = Vec::new();
+    let vendor = b"bae";
+    comment.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    comment.extend_from_slice(vendor);
+    comment.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+    for (key, value) in tags {
+        let entry = format!("{}={}", key, value);
+        comment.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+        comment.extend_from_slice(entry.as_bytes());
+    }
+    if comment.len() >= 1 << 24 {
+        return None;
+    }
+    blocks.insert(1, (VORBIS_COMMENT_BLOCK, &comment[..]));
+
+    let mut out = Vec::with_capacity(data.len() + comment.len());
+    out.extend_from_slice(b"fLaC");
+    let last = blocks.len() - 1;
+    for (i, (block_type, body)) in blocks.iter().enumerate() {
+        let flag = if i == last { 0x80 } else { 0 };
+        out.push(flag | block_type);
+        out.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        out.extend_from_slice(body);
+    }
+    out.extend_from_slice(&data[pos..]);
+    Some(out)
+}
+
+/// Values for the placeholders of a name template
+#[derive(Clone)]
+struct NameFields {
+    artist: String,
+    album: String,
+    year: String,
+    disc: String,
+    track: String,
+    title: String,
+}
+
+/// Fill in `{artist}`, `{album}`, `{year}`, `{disc}`, `{track}` and `{title}`.
+/// Each `/`-separated part of the template becomes one path component, with
+/// characters that aren't allowed in file names replaced. Parts that come out
+/// empty are left out.
+fn render_name(template: &str, fields: &NameFields) -> PathBuf {
+    template
+        .split('/')
+        .map(|part| {
+            let rendered = part
+                .replace("{artist}", &fields.artist)
+                .replace("{album}", &fields.album)
+                .replace("{year}", &fields.year)
+                .replace("{disc}", &fields.disc)
+                .replace("{track}", &fields.track)
+                .replace("{title}", &fields.title);
+            sanitize_component(&rendered)
+        })
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn sanitize_component(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows drops trailing dots and spaces
+    cleaned
+        .trim()
+        .trim_end_matches('.')
+        .trim_start_matches('.')
+        .to_string()
+}
+
+/// An original filename as a path inside the release folder, keeping its
+/// subfolders but nothing that could climb out of it
+fn relative_filename(original: &str) -> PathBuf {
+    Path::new(original)
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(sanitize_component(&part.to_string_lossy())),
+            _ => None,
+        })
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// `path`, or `path (2)`, `path (3)`, ... if another file already took it
+fn unique_path(path: PathBuf, used: &mut HashSet<PathBuf>) -> PathBuf {
+    let mut candidate = path.clone();
+    let mut n = 2;
+    while used.contains(&candidate) {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match path.extension() {
+            Some(ext) => format!("{} ({}).{}", stem, n, ext.to_string_lossy()),
+            None => format!("{} ({})", stem, n),
+        };
+        candidate = path.with_file_name(name);
+        n += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, DbAlbum, DbAlbumArtist, DbArtist};
+    use tempfile::TempDir;
+
+    /// "fLaC", an empty-ish STREAMINFO, an old comment block and fake frames
+    fn flac_with_comment(comment: &[u8], frames: &[u8]) -> Vec<u8> {
+        let mut data = b"fLaC".to_vec();
+        data.push(STREAMINFO_BLOCK);
+        data.extend_from_slice(&34u32.to_be_bytes()[1..]);
+        data.extend_from_slice(&[7; 34]);
+        data.push(0x80 | VORBIS_COMMENT_BLOCK);
+        data.extend_from_slice(&(comment.len() as u32).to_be_bytes()[1..]);
+        data.extend_from_slice(comment);
+        data.extend_from_slice(frames);
+        data
+    }
+
+    #[test]
+    fn names_follow_the_template() {
+        let fields = NameFields {
+            artist: "Glass Harbor".to_string(),
+            album: "Low Light: Live".to_string(),
+            year: String::new(),
+            disc: "1".to_string(),
+            track: "03".to_string(),
+            title: "Tidewater/Reprise".to_string(),
+        };
+        assert_eq!(
+            render_name("{artist}/{year}/{album}/{disc}-{track} {title}", &fields),
+            PathBuf::from("Glass Harbor/Low Light_ Live/1-03 Tidewater_Reprise")
+        );
+        assert_eq!(
+            relative_filename("../Scans/./front.jpg"),
+            PathBuf::from("Scans/front.jpg")
+        );
+
+        let mut used = HashSet::new();
+        assert_eq!(
+            unique_path(PathBuf::from("a/cover.jpg"), &mut used),
+            PathBuf::from("a/cover.jpg")
+        );
+        assert_eq!(
+            unique_path(PathBuf::from("a/cover.jpg"), &mut used),
+            PathBuf::from("a/cover (2).jpg")
+        );
+    }
+
+    #[tokio::test]
+    async fn exports_tagged_tracks_and_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = Database::new(temp_dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let manager = LibraryManager::new(database, None);
+        let db = manager.database();
+        let library_dir = LibraryDir::new(temp_dir.path().join("library"));
+
+        let mut album = DbAlbum::new_test("Pacific Standard");
+        album.year = Some(2019);
+        db.insert_album(&album).await.unwrap();
+        let artist = DbArtist::from_discogs_artist("1", "The Lanterns");
+        db.insert_artist(&artist).await.unwrap();
+        db.insert_album_artist(&DbAlbumArtist::new(&album.id, &artist.id, 0))
+            .await
+            .unwrap();
+        let mut release = DbRelease::new_test(&album.id, "release-1");
+        release.managed_locally = true;
+        db.insert_release(&release).await.unwrap();
+
+        let flac = flac_with_comment(b"\x00\x00\x00\x00\x00\x00\x00\x00", &[9; 100]);
+        let file = DbFile::new(&release.id, "01.flac", flac.len() as i64, ContentType::Flac);
+        db.insert_file(&file).await.unwrap();
+        let path = file.local_storage_path(&library_dir);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &flac).unwrap();
+        let cover = DbFile::new(&release.id, "cover.jpg", 3, ContentType::Jpeg);
+        db.insert_file(&cover).await.unwrap();
+        let path = cover.local_storage_path(&library_dir);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"jpg").unwrap();
+
+        let track = DbTrack::new_test(&release.id, "track-1", "Glass Harbor", Some(1));
+        db.insert_track(&track).await.unwrap();
+        let format = DbAudioFormat::new(
+            &track.id,
+            ContentType::Flac,
+            None,
+            false,
+            44100,
+            16,
+            "[]".to_string(),
+            42,
+        )
+        .with_file_id(&file.id);
+        db.insert_audio_format(&format).await.unwrap();
+
+        let target = temp_dir.path().join("export");
+        let manifest = export_library(&manager, &library_dir, None, DEFAULT_NAME_TEMPLATE, &target)
+            .await
+            .unwrap();
+
+        assert!(manifest.failures.is_empty());
+        let exported = &manifest.releases[0];
+        assert_eq!(
+            exported.tracks[0].path,
+            "The Lanterns/Pacific Standard/1-01 Glass Harbor.flac"
+        );
+        assert!(exported.tracks[0].tagged);
+        assert_eq!(
+            exported.other_files,
+            vec!["The Lanterns/Pacific Standard/cover.jpg"]
+        );
+
+        let written = std::fs::read(target.join(&exported.tracks[0].path)).unwrap();
+        assert_eq!(
+            exported.tracks[0].sha256,
+            hex::encode(Sha256::digest(&written))
+        );
+        assert!(written.ends_with(&[9; 100]));
+        let text = String::from_utf8_lossy(&written);
+        for tag in [
+            "TITLE=Glass Harbor",
+            "ALBUMARTIST=The Lanterns",
+            "DATE=2019",
+        ] {
+            assert!(text.contains(tag), "missing {tag}");
+        }
+        assert!(target.join(MANIFEST_FILE).exists());
+    }
+}
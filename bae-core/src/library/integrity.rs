@@ -0,0 +1,423 @@
+//! Library integrity check
+//!
+//! Reads back every file of every release from where the release keeps it:
+//! managed local storage, the cloud home, or the folder of an unmanaged
+//! import. Each file must be there, decrypt (which authenticates it), come
+//! out at its recorded size and, when it was hashed on import, match that
+//! hash. FLAC files also have to match the MD5 of their audio.
+
+use crate::audio_codec::verify_flac_md5;
+use crate::cloud_storage::CloudStorage;
+use crate::content_type::ContentType;
+use crate::db::{DbFile, DbRelease, ImportStatus};
+use crate::file_service::decrypt_if_needed;
+use crate::library::duplicates::sha256_hex;
+use crate::library::{LibraryError, LibraryManager};
+use crate::library_dir::LibraryDir;
+use crate::storage::storage_path;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// FLAC files larger than this skip the MD5 check
+const MAX_FLAC_MD5_CHECK_SIZE: usize = 200 * 1024 * 1024;
+
+/// Where a file was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileLocation {
+    Local,
+    Cloud,
+    Unmanaged,
+}
+
+/// What's wrong with a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileProblem {
+    /// Not where the release keeps it
+    Missing,
+    /// There, but couldn't be read or downloaded
+    Unreadable,
+    /// Failed to decrypt or authenticate
+    Undecryptable,
+    /// Readable, but not the size or content that was imported
+    Corrupt,
+}
+
+/// A file that failed the check
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenFile {
+    pub file_id: String,
+    pub filename: String,
+    pub location: FileLocation,
+    pub problem: FileProblem,
+    pub detail: String,
+}
+
+/// A release with broken files
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenRelease {
+    pub release_id: String,
+    pub release_name: Option<String>,
+    pub file_count: usize,
+    pub broken_files: Vec<BrokenFile>,
+    /// Folder the release was imported from, for importing it again
+    pub import_folder: Option<String>,
+}
+
+/// An album with at least one broken release
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenAlbum {
+    pub album_id: String,
+    pub album_title: String,
+    pub artist_name: String,
+    pub releases: Vec<BrokenRelease>,
+}
+
+/// Outcome of checking the whole library
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    pub releases_checked: usize,
+    pub files_checked: usize,
+    /// Cloud releases not checked because no cloud home is configured
+    pub releases_skipped: usize,
+    pub albums: Vec<BrokenAlbum>,
+}
+
+/// Check every release of the library.
+///
+/// `cloud_storage` is the library's cloud home, if it has one; releases only
+/// kept in the cloud are skipped without it. `on_progress` is called with
+/// (releases done, release count) after each release.
+pub async fn check_library_integrity(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    cloud_storage: Option<Arc<dyn CloudStorage>>,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<IntegrityReport, LibraryError> {
+    let db = library_manager.database();
+    let releases: Vec<DbRelease> = db
+        .get_all_releases()
+        .await?
+        .into_iter()
+        .filter(|r| r.import_status == ImportStatus::Complete)
+        .collect();
+
+    info!("Checking the files of {} releases", releases.len());
+
+    let mut report = IntegrityReport::default();
+    for (index, release) in releases.iter().enumerate() {
+        let locations = release_locations(release, cloud_storage.is_some());
+        if locations.is_empty() {
+            if release.managed_in_cloud {
+                report.releases_skipped += 1;
+            }
+            on_progress(index + 1, releases.len());
+            continue;
+        }
+
+        let files = db.get_files_for_release(&release.id).await?;
+        let mut broken_files = Vec::new();
+        for file in &files {
+            for &location in &locations {
+                if let Some(broken) = check_file(
+                    library_manager,
+                    library_dir,
+                    cloud_storage.as_deref(),
+                    release,
+                    file,
+                    location,
+                )
+                .await
+                {
+                    broken_files.push(broken);
+                }
+            }
+        }
+        report.releases_checked += 1;
+        report.files_checked += files.len();
+
+        if !broken_files.is_empty() {
+            add_broken_release(
+                library_manager,
+                &mut report,
+                release,
+                files.len(),
+                broken_files,
+            )
+            .await?;
+        }
+        on_progress(index + 1, releases.len());
+    }
+
+    info!(
+        "Checked {} files of {} releases, {} albums with broken files",
+        report.files_checked,
+        report.releases_checked,
+        report.albums.len()
+    );
+
+    Ok(report)
+}
+
+/// The places a release's files are kept, and can be checked from here
+pub(crate) fn release_locations(release: &DbRelease, has_cloud: bool) -> Vec<FileLocation> {
+    let mut locations = Vec::new();
+    if release.managed_locally {
+        locations.push(FileLocation::Local);
+    }
+    if release.managed_in_cloud && has_cloud {
+        locations.push(FileLocation::Cloud);
+    }
+    if release.unmanaged_path.is_some() {
+        locations.push(FileLocation::Unmanaged);
+    }
+    locations
+}
+
+/// Read back one copy of a file. None if it's intact.
+pub(crate) async fn check_file(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    cloud_storage: Option<&dyn CloudStorage>,
+    release: &DbRelease,
+    file: &DbFile,
+    location: FileLocation,
+) -> Option<BrokenFile> {
+    let broken = |problem, detail: String| {
+        Some(BrokenFile {
+            file_id: file.id.clone(),
+            filename: file.original_filename.clone(),
+            location,
+            problem,
+            detail,
+        })
+    };
+
+    let raw = match location {
+        FileLocation::Local | FileLocation::Unmanaged => {
+            let path = match location {
+                FileLocation::Local => file.local_storage_path(library_dir),
+                _ => Path::new(release.unmanaged_path.as_deref().unwrap_or_default())
+                    .join(&file.original_filename),
+            };
+            match tokio::fs::read(&path).await {
+                Ok(raw) => raw,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return broken(FileProblem::Missing, path.display().to_string());
+                }
+                Err(e) => return broken(FileProblem::Unreadable, e.to_string()),
+            }
+        }
+        FileLocation::Cloud => {
+            let storage = cloud_storage?;
+            match storage.download(&storage_path(&file.id)).await {
+                Ok(raw) => raw,
+                Err(e) => return broken(FileProblem::Unreadable, e.to_string()),
+            }
+        }
+    };
+
+    let data = match decrypt_if_needed(file, &release.id, library_manager.encryption_service(), raw)
+        .await
+    {
+        Ok(data) => data,
+        Err(e) => return broken(FileProblem::Undecryptable, e.to_string()),
+    };
+
+    if data.len() as i64 != file.file_size {
+        return broken(
+            FileProblem::Corrupt,
+            format!("{} bytes, expected {}", data.len(), file.file_size),
+        );
+    }
+    if let Some(ref expected) = file.content_sha256 {
+        if sha256_hex(&data) != *expected {
+            return broken(
+                FileProblem::Corrupt,
+                "content differs from the import".to_string(),
+            );
+        }
+    }
+    // Decoding holds every sample in memory, so whole-disc images are left
+    // to the checks above
+    if file.content_type == ContentType::Flac && data.len() <= MAX_FLAC_MD5_CHECK_SIZE {
+        if let Ok(Ok(Some(false))) =
+            tokio::task::spawn_blocking(move || verify_flac_md5(&data)).await
+        {
+            return broken(
+                FileProblem::Corrupt,
+                "audio differs from its FLAC MD5".to_string(),
+            );
+        }
+    }
+    None
+}
+
+pub(crate) async fn add_broken_release(
+    library_manager: &LibraryManager,
+    report: &mut IntegrityReport,
+    release: &DbRelease,
+    file_count: usize,
+    broken_files: Vec<BrokenFile>,
+) -> Result<(), LibraryError> {
+    warn!(
+        "Release {} has {} broken files",
+        release.id,
+        broken_files.len()
+    );
+
+    let import_folder = library_manager
+        .database()
+        .get_release_import_folder(&release.id)
+        .await?
+        .or_else(|| release.unmanaged_path.clone());
+    let broken_release = BrokenRelease {
+        release_id: release.id.clone(),
+        release_name: release.release_name.clone(),
+        file_count,
+        broken_files,
+        import_folder,
+    };
+
+    if let Some(album) = report
+        .albums
+        .iter_mut()
+        .find(|a| a.album_id == release.album_id)
+    {
+        album.releases.push(broken_release);
+        return Ok(());
+    }
+    let album_title = library_manager
+        .get_album_by_id(&release.album_id)
+        .await?
+        .map(|a| a.title)
+        .unwrap_or_default();
+    let artist_name = library_manager
+        .get_album_artist_name(&release.album_id)
+        .await?;
+    report.albums.push(BrokenAlbum {
+        album_id: release.album_id.clone(),
+        album_title,
+        artist_name,
+        releases: vec![broken_release],
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::EncryptionService;
+    use crate::test_support::{
+        create_test_album, create_test_release, test_database, MockCloudStorage,
+    };
+    use tempfile::TempDir;
+
+    async fn insert_release(
+        manager: &LibraryManager,
+        configure: impl FnOnce(&mut DbRelease),
+    ) -> DbRelease {
+        let mut album = create_test_album();
+        album.title = "Glass Harbor".to_string();
+        let mut release = create_test_release(&album.id);
+        configure(&mut release);
+        manager.database().insert_album(&album).await.unwrap();
+        manager.database().insert_release(&release).await.unwrap();
+        release
+    }
+
+    #[tokio::test]
+    async fn reports_missing_and_corrupt_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = test_database(temp_dir.path()).await;
+        let encryption = EncryptionService::new_with_key(&[7u8; 32]);
+        let manager = LibraryManager::new(database, Some(encryption.clone()));
+        let library_dir = LibraryDir::new(temp_dir.path().to_path_buf());
+        let cloud = Arc::new(MockCloudStorage::new());
+        let audio = b"Pacific Standard".to_vec();
+
+        // Local: one good encrypted file, one missing, one with the wrong hash
+        let local = insert_release(&manager, |r| r.managed_locally = true).await;
+        let release_enc = encryption.derive_release_encryption(&local.id);
+        for (name, sha256) in [
+            ("01.flac", Some(sha256_hex(&audio))),
+            ("02.flac", None),
+            ("03.flac", Some(sha256_hex(b"Low Light"))),
+        ] {
+            let mut file = DbFile::new(&local.id, name, audio.len() as i64, ContentType::Flac);
+            file.content_sha256 = sha256;
+            file.encryption_nonce = Some(vec![0u8; 24]);
+            if name != "02.flac" {
+                let path = file.local_storage_path(&library_dir);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, release_enc.encrypt_chunked(&audio)).unwrap();
+            }
+            manager.database().insert_file(&file).await.unwrap();
+        }
+
+        // Cloud: the stored bytes were tampered with
+        let cloud_release = insert_release(&manager, |r| r.managed_in_cloud = true).await;
+        let mut file = DbFile::new(
+            &cloud_release.id,
+            "01.flac",
+            audio.len() as i64,
+            ContentType::Flac,
+        );
+        file.encryption_nonce = Some(vec![0u8; 24]);
+        let mut encrypted = encryption
+            .derive_release_encryption(&cloud_release.id)
+            .encrypt_chunked(&audio);
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+        cloud
+            .files
+            .lock()
+            .unwrap()
+            .insert(storage_path(&file.id), encrypted);
+        manager.database().insert_file(&file).await.unwrap();
+
+        // Unmanaged: the folder is intact
+        let folder = temp_dir.path().join("Tidewater");
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("01.flac"), &audio).unwrap();
+        let unmanaged = insert_release(&manager, |r| {
+            r.unmanaged_path = Some(folder.display().to_string())
+        })
+        .await;
+        let file = DbFile::new(
+            &unmanaged.id,
+            "01.flac",
+            audio.len() as i64,
+            ContentType::Flac,
+        );
+        manager.database().insert_file(&file).await.unwrap();
+
+        let mut progress = Vec::new();
+        let report = check_library_integrity(
+            &manager,
+            &library_dir,
+            Some(cloud as Arc<dyn CloudStorage>),
+            |done, total| progress.push((done, total)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.releases_checked, 3);
+        assert_eq!(report.files_checked, 5);
+        assert_eq!(progress.last(), Some(&(3, 3)));
+        assert_eq!(report.albums.len(), 2);
+        let local_files = &report.albums[0].releases[0].broken_files;
+        let problem_of = |name: &str| {
+            local_files
+                .iter()
+                .find(|f| f.filename == name)
+                .map(|f| f.problem)
+        };
+        assert_eq!(local_files.len(), 2);
+        assert_eq!(problem_of("02.flac"), Some(FileProblem::Missing));
+        assert_eq!(problem_of("03.flac"), Some(FileProblem::Corrupt));
+        let cloud_files = &report.albums[1].releases[0].broken_files;
+        assert_eq!(cloud_files[0].location, FileLocation::Cloud);
+        assert_eq!(cloud_files[0].problem, FileProblem::Undecryptable);
+    }
+}
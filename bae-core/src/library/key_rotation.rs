@@ -0,0 +1,555 @@
+//! Master key rotation
+//!
+//! Rotating swaps a new master key into the keyring, then re-encrypts what
+//! was stored under the release keys derived from the old one: release files
+//! kept locally or in the cloud home, and saved lyrics. Until every one of
+//! them is done the old key stays in the keyring and the encryption service
+//! falls back to it, so nothing becomes unreadable part way through.
+//!
+//! Re-encryption runs on the launch after the rotation begins, once every
+//! service has picked up the new key, and picks up where it left off if the
+//! app quits: anything the new key already opens is skipped.
+
+use crate::cloud_storage::{CloudHomeStorageAdapter, CloudStorage};
+use crate::config::{Config, ConfigError};
+use crate::db::{DbFile, DbRelease, DbTrackLyrics, ImportStatus};
+use crate::encryption::{EncryptionError, EncryptionService};
+use crate::keys::{KeyError, KeyService};
+use crate::library::{LibraryError, LibraryManager};
+use crate::library_dir::{LibraryDir, Manifest};
+use crate::storage::storage_path;
+use crate::storage::usage::MeteredCloudStorage;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeyRotationError {
+    #[error("A key rotation is already in progress")]
+    InProgress,
+    #[error("This library has no encryption key")]
+    NoKey,
+    #[error("Keyring error: {0}")]
+    Key(#[from] KeyError),
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+    #[error("Library error: {0}")]
+    Library(#[from] LibraryError),
+}
+
+/// Outcome of re-encrypting the library under the new key
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RotationReport {
+    /// Files and lyrics rewritten under the new key
+    pub reencrypted: usize,
+    /// Cloud releases left alone because no cloud home is configured
+    pub releases_skipped: usize,
+    /// Files and lyrics that couldn't be read, decrypted or written back
+    pub failed: usize,
+}
+
+impl RotationReport {
+    /// Whether nothing is left under the old key, so it can be dropped
+    pub fn is_complete(&self) -> bool {
+        self.releases_skipped == 0 && self.failed == 0
+    }
+}
+
+/// Make `new_key` the library's master key, keeping the current one as the
+/// previous key until `finish_key_rotation`. Returns the new key's
+/// fingerprint.
+///
+/// Services built before this keep using the old key, and anything they
+/// write is re-encrypted on the next launch along with the rest. Only one
+/// rotation runs at a time, since only one previous key is kept.
+pub fn begin_key_rotation(
+    key_service: &KeyService,
+    config: &mut Config,
+    new_key: [u8; 32],
+) -> Result<String, KeyRotationError> {
+    if key_service.get_previous_encryption_key().is_some() {
+        return Err(KeyRotationError::InProgress);
+    }
+    let old_key = key_service
+        .get_encryption_key()
+        .ok_or(KeyRotationError::NoKey)?;
+    let fingerprint = EncryptionService::from_key(new_key).fingerprint();
+
+    key_service.set_previous_encryption_key(&old_key)?;
+    key_service.set_encryption_key(&hex::encode(new_key))?;
+
+    let old_fingerprint = config
+        .encryption_key_fingerprint
+        .replace(fingerprint.clone());
+    config.key_rotation_in_progress = true;
+    if let Err(e) = config.save() {
+        // Without the new fingerprint saved, the next launch would reject the
+        // new key. Put the old one back, and drop the previous key so a later
+        // rotation isn't refused as already in progress.
+        config.encryption_key_fingerprint = old_fingerprint;
+        config.key_rotation_in_progress = false;
+        if let Err(restore) = key_service.set_encryption_key(&old_key) {
+            error!("Failed to restore the encryption key: {}", restore);
+        } else if let Err(cleanup) = key_service.delete_previous_encryption_key() {
+            error!("Failed to delete the previous encryption key: {}", cleanup);
+        }
+        return Err(e.into());
+    }
+
+    if let Err(e) = update_manifest_fingerprint(&config.library_dir, &fingerprint) {
+        warn!("Failed to update the manifest fingerprint: {}", e);
+    }
+
+    info!(
+        "Rotated the encryption key, new fingerprint {}",
+        fingerprint
+    );
+    Ok(fingerprint)
+}
+
+/// Drop the previous key once `reencrypt_library` has left nothing under it.
+pub fn finish_key_rotation(
+    key_service: &KeyService,
+    config: &mut Config,
+) -> Result<(), KeyRotationError> {
+    key_service.delete_previous_encryption_key()?;
+    config.key_rotation_in_progress = false;
+    config.save()?;
+
+    info!("Key rotation complete");
+    Ok(())
+}
+
+/// Carry on with a rotation begun before this launch: re-encrypt the
+/// library, and finish once nothing is left under the previous key. A
+/// rotation that can't finish yet stays in progress for the next launch.
+pub async fn complete_key_rotation(
+    library_manager: &LibraryManager,
+    key_service: &KeyService,
+    config: &mut Config,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<RotationReport, KeyRotationError> {
+    // Without the previous key there's nothing left that could be read
+    let has_previous = library_manager
+        .encryption_service()
+        .is_some_and(|e| e.has_previous_key());
+    if !has_previous {
+        finish_key_rotation(key_service, config)?;
+        return Ok(RotationReport::default());
+    }
+
+    let cloud_storage: Option<Arc<dyn CloudStorage>> = if config.cloud_provider.is_some() {
+        match crate::cloud_home::create_cloud_home(config, key_service).await {
+            Ok(home) => Some(Arc::new(MeteredCloudStorage::new(
+                Arc::new(
+                    CloudHomeStorageAdapter::new(Arc::from(home))
+                        .with_upload_state(config.library_dir.uploads_dir()),
+                ),
+                library_manager.database().clone(),
+            ))),
+            Err(e) => {
+                warn!("Re-encrypting without the cloud home: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let report = reencrypt_library(
+        library_manager,
+        &config.library_dir,
+        cloud_storage,
+        on_progress,
+    )
+    .await?;
+    if report.is_complete() {
+        finish_key_rotation(key_service, config)?;
+    } else {
+        warn!("Keeping the previous encryption key until everything is re-encrypted");
+    }
+    Ok(report)
+}
+
+fn update_manifest_fingerprint(library_dir: &LibraryDir, fingerprint: &str) -> std::io::Result<()> {
+    let path = library_dir.manifest_path();
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut manifest: Manifest = serde_json::from_slice(&std::fs::read(&path)?)?;
+    manifest.encryption_key_fingerprint = Some(fingerprint.to_string());
+    // The new key is random, so a passphrase no longer derives it
+    manifest.passphrase_kdf = None;
+    std::fs::write(&path, serde_json::to_string_pretty(&manifest)?)
+}
+
+/// Re-encrypt every stored file and saved lyrics of the library under the
+/// current key of the library manager's encryption service, which decrypts
+/// with the previous key.
+///
+/// `cloud_storage` is the library's cloud home, if it has one; releases kept
+/// in the cloud are skipped without it. `on_progress` is called with
+/// (releases done, release count) after each release.
+pub async fn reencrypt_library(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    cloud_storage: Option<Arc<dyn CloudStorage>>,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<RotationReport, LibraryError> {
+    let mut report = RotationReport::default();
+    let Some(encryption) = library_manager.encryption_service() else {
+        return Ok(report);
+    };
+    let db = library_manager.database();
+    let releases: Vec<DbRelease> = db
+        .get_all_releases()
+        .await?
+        .into_iter()
+        .filter(|r| r.import_status == ImportStatus::Complete)
+        .collect();
+
+    info!("Re-encrypting the files of {} releases", releases.len());
+
+    for (index, release) in releases.iter().enumerate() {
+        if release.managed_in_cloud && cloud_storage.is_none() {
+            report.releases_skipped += 1;
+        }
+        if release.managed_locally || (release.managed_in_cloud && cloud_storage.is_some()) {
+            let release_enc = encryption.derive_release_encryption(&release.id);
+            for file in db.get_files_for_release(&release.id).await? {
+                if file.encryption_nonce.is_none() {
+                    continue;
+                }
+                if release.managed_locally {
+                    reencrypt_local_file(library_manager, library_dir, &release_enc, &file)
+                        .await
+                        .tally(&mut report, &file);
+                }
+                if let (true, Some(storage)) = (release.managed_in_cloud, &cloud_storage) {
+                    reencrypt_cloud_file(library_manager, storage.as_ref(), &release_enc, &file)
+                        .await
+                        .tally(&mut report, &file);
+                }
+            }
+        }
+        on_progress(index + 1, releases.len());
+    }
+
+    for (release_id, lyrics) in db.get_all_track_lyrics().await? {
+        let release_enc = encryption.derive_release_encryption(&release_id);
+        match reencrypt(&release_enc, lyrics.content).await {
+            Ok(Some(content)) => {
+                db.upsert_track_lyrics(&DbTrackLyrics { content, ..lyrics })
+                    .await?;
+                report.reencrypted += 1;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(
+                    "Failed to re-encrypt lyrics of track {}: {}",
+                    lyrics.track_id, e
+                );
+                report.failed += 1;
+            }
+        }
+    }
+
+    info!(
+        "Re-encrypted {} files and lyrics, {} failed, {} cloud releases skipped",
+        report.reencrypted, report.failed, report.releases_skipped
+    );
+
+    Ok(report)
+}
+
+/// What happened to one stored copy of a file
+enum FileOutcome {
+    Reencrypted,
+    /// Already under the new key, or not there to rewrite
+    Unchanged,
+    Failed(String),
+}
+
+impl FileOutcome {
+    fn tally(self, report: &mut RotationReport, file: &DbFile) {
+        match self {
+            FileOutcome::Reencrypted => report.reencrypted += 1,
+            FileOutcome::Unchanged => {}
+            FileOutcome::Failed(e) => {
+                warn!("Failed to re-encrypt file {}: {}", file.id, e);
+                report.failed += 1;
+            }
+        }
+    }
+}
+
+async fn reencrypt_local_file(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    release_enc: &EncryptionService,
+    file: &DbFile,
+) -> FileOutcome {
+    let path = file.local_storage_path(library_dir);
+    let raw = match tokio::fs::read(&path).await {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return FileOutcome::Unchanged,
+        Err(e) => return FileOutcome::Failed(e.to_string()),
+    };
+    let encrypted = match reencrypt(release_enc, raw).await {
+        Ok(Some(encrypted)) => encrypted,
+        Ok(None) => return FileOutcome::Unchanged,
+        Err(e) => return FileOutcome::Failed(e.to_string()),
+    };
+
+    // Written aside and renamed over, so quitting midway never leaves a file
+    // that neither key opens
+    let partial = path.with_file_name(format!("{}.rotating", file.id));
+    let written = async {
+        tokio::fs::write(&partial, &encrypted).await?;
+        tokio::fs::rename(&partial, &path).await
+    }
+    .await;
+    if let Err(e) = written {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return FileOutcome::Failed(e.to_string());
+    }
+
+    update_nonce(library_manager, release_enc, file, &encrypted).await
+}
+
+async fn reencrypt_cloud_file(
+    library_manager: &LibraryManager,
+    storage: &dyn CloudStorage,
+    release_enc: &EncryptionService,
+    file: &DbFile,
+) -> FileOutcome {
+    let key = storage_path(&file.id);
+    let raw = match storage.download(&key).await {
+        Ok(raw) => raw,
+        Err(e) => return FileOutcome::Failed(e.to_string()),
+    };
+    let encrypted = match reencrypt(release_enc, raw).await {
+        Ok(Some(encrypted)) => encrypted,
+        Ok(None) => return FileOutcome::Unchanged,
+        Err(e) => return FileOutcome::Failed(e.to_string()),
+    };
+    if let Err(e) = storage.upload(&key, &encrypted).await {
+        return FileOutcome::Failed(e.to_string());
+    }
+
+    update_nonce(library_manager, release_enc, file, &encrypted).await
+}
+
+/// Record the new container prefix, so range reads keep working without
+/// fetching the start of the file
+async fn update_nonce(
+    library_manager: &LibraryManager,
+    release_enc: &EncryptionService,
+    file: &DbFile,
+    encrypted: &[u8],
+) -> FileOutcome {
+    let prefix = &encrypted[..release_enc.format().prefix_len().min(encrypted.len())];
+    match library_manager
+        .database()
+        .update_file_encryption_nonce(&file.id, prefix)
+        .await
+    {
+        Ok(()) => FileOutcome::Reencrypted,
+        Err(e) => FileOutcome::Failed(e.to_string()),
+    }
+}
+
+/// Data re-encrypted under the current key, or None if it already is
+async fn reencrypt(
+    release_enc: &EncryptionService,
+    data: Vec<u8>,
+) -> Result<Option<Vec<u8>>, EncryptionError> {
+    let release_enc = release_enc.clone();
+    tokio::task::spawn_blocking(move || {
+        if release_enc.current_only().decrypt(&data).is_ok() {
+            return Ok(None);
+        }
+        let plaintext = release_enc.decrypt(&data)?;
+        Ok(Some(release_enc.encrypt(&plaintext)))
+    })
+    .await
+    .map_err(|e| EncryptionError::Decryption(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content_type::ContentType;
+    use crate::db::DbTrack;
+    use crate::test_support::{
+        create_test_album, create_test_release, make_test_config, test_database, MockCloudStorage,
+    };
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    async fn insert_release(
+        manager: &LibraryManager,
+        configure: impl FnOnce(&mut DbRelease),
+    ) -> DbRelease {
+        let album = create_test_album();
+        let mut release = create_test_release(&album.id);
+        configure(&mut release);
+        manager.database().insert_album(&album).await.unwrap();
+        manager.database().insert_release(&release).await.unwrap();
+        release
+    }
+
+    #[test]
+    fn failed_config_save_leaves_no_rotation_behind() {
+        keyring_core::set_default_store(keyring_core::mock::Store::new().unwrap());
+        let temp_dir = TempDir::new().unwrap();
+        let key_service = KeyService::new(false, Uuid::new_v4().to_string());
+        let old_key = hex::encode([1u8; 32]);
+        key_service.set_encryption_key(&old_key).unwrap();
+
+        // A file where the library folder should be, so config.yaml can't be written
+        let library_path = temp_dir.path().join("library");
+        std::fs::write(&library_path, b"").unwrap();
+        let mut config = make_test_config("test-library", library_path);
+
+        let result = begin_key_rotation(&key_service, &mut config, [2u8; 32]);
+        assert!(matches!(result, Err(KeyRotationError::Config(_))));
+        assert_eq!(key_service.get_encryption_key(), Some(old_key));
+        assert_eq!(key_service.get_previous_encryption_key(), None);
+        assert_eq!(config.encryption_key_fingerprint, None);
+        assert!(!config.key_rotation_in_progress);
+
+        // Nothing is left that would refuse the next attempt as in progress
+        let mut config = make_test_config("test-library", temp_dir.path().join("retry"));
+        begin_key_rotation(&key_service, &mut config, [2u8; 32]).unwrap();
+        assert_eq!(
+            key_service.get_previous_encryption_key(),
+            Some(hex::encode([1u8; 32]))
+        );
+    }
+
+    #[tokio::test]
+    async fn reencrypts_files_and_lyrics_under_the_new_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = test_database(temp_dir.path()).await;
+        let old_key = hex::encode([1u8; 32]);
+        let old = EncryptionService::new(&old_key).unwrap();
+        let encryption = EncryptionService::new_with_key(&[2u8; 32])
+            .with_previous_key(&old_key)
+            .unwrap();
+        let manager = LibraryManager::new(database, Some(encryption.clone()));
+        let library_dir = LibraryDir::new(temp_dir.path().to_path_buf());
+        let cloud = Arc::new(MockCloudStorage::new());
+        let audio = b"The Lanterns".to_vec();
+
+        // A local file and saved lyrics, both under the old key
+        let local = insert_release(&manager, |r| r.managed_locally = true).await;
+        let old_local = old.derive_release_encryption(&local.id);
+        let mut local_file =
+            DbFile::new(&local.id, "01.flac", audio.len() as i64, ContentType::Flac);
+        local_file.encryption_nonce = Some(vec![0u8; 24]);
+        let path = local_file.local_storage_path(&library_dir);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, old_local.encrypt(&audio)).unwrap();
+        manager.database().insert_file(&local_file).await.unwrap();
+
+        let track = DbTrack::new_test(&local.id, "track-1", "Iron Meadow", Some(1));
+        manager.database().insert_track(&track).await.unwrap();
+        manager
+            .database()
+            .upsert_track_lyrics(&DbTrackLyrics {
+                track_id: track.id.clone(),
+                content: old_local.encrypt(b"{}"),
+                source: "lrclib".to_string(),
+            })
+            .await
+            .unwrap();
+
+        // A cloud file under the old key
+        let cloud_release = insert_release(&manager, |r| r.managed_in_cloud = true).await;
+        let mut cloud_file = DbFile::new(
+            &cloud_release.id,
+            "01.flac",
+            audio.len() as i64,
+            ContentType::Flac,
+        );
+        cloud_file.encryption_nonce = Some(vec![0u8; 24]);
+        cloud.files.lock().unwrap().insert(
+            storage_path(&cloud_file.id),
+            old.derive_release_encryption(&cloud_release.id)
+                .encrypt(&audio),
+        );
+        manager.database().insert_file(&cloud_file).await.unwrap();
+
+        let mut progress = Vec::new();
+        let report = reencrypt_library(
+            &manager,
+            &library_dir,
+            Some(cloud.clone() as Arc<dyn CloudStorage>),
+            |done, total| progress.push((done, total)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.reencrypted, 3);
+        assert!(report.is_complete());
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+
+        // Everything opens without the old key
+        let current = encryption.current_only();
+        let new_local = current.derive_release_encryption(&local.id);
+        let stored = std::fs::read(&path).unwrap();
+        assert_eq!(new_local.decrypt(&stored).unwrap(), audio);
+        let files = manager
+            .database()
+            .get_files_for_release(&local.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            files[0].encryption_nonce.as_deref(),
+            Some(&stored[..current.format().prefix_len()])
+        );
+        let lyrics = manager
+            .database()
+            .get_track_lyrics(&track.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(new_local.decrypt(&lyrics.content).unwrap(), b"{}");
+        let stored = cloud.download(&storage_path(&cloud_file.id)).await.unwrap();
+        assert_eq!(
+            current
+                .derive_release_encryption(&cloud_release.id)
+                .decrypt(&stored)
+                .unwrap(),
+            audio
+        );
+
+        // A second run finds nothing left to do
+        let report = reencrypt_library(
+            &manager,
+            &library_dir,
+            Some(cloud as Arc<dyn CloudStorage>),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.reencrypted, 0);
+    }
+
+    #[tokio::test]
+    async fn cloud_releases_without_a_cloud_home_keep_the_rotation_open() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = test_database(temp_dir.path()).await;
+        let encryption = EncryptionService::new_with_key(&[2u8; 32])
+            .with_previous_key(&hex::encode([1u8; 32]))
+            .unwrap();
+        let manager = LibraryManager::new(database, Some(encryption));
+        let library_dir = LibraryDir::new(temp_dir.path().to_path_buf());
+        insert_release(&manager, |r| r.managed_in_cloud = true).await;
+
+        let report = reencrypt_library(&manager, &library_dir, None, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(report.releases_skipped, 1);
+        assert!(!report.is_complete());
+    }
+}
@@ -0,0 +1,169 @@
+//! M3U and M3U8 playlist files
+//!
+//! Extended M3U puts an `#EXTINF:<seconds>,<Artist> - <Title>` line before
+//! each location. Locations written by another player point into that
+//! player's folders, so imported entries are matched by artist and title,
+//! falling back to the title in the file name.
+
+/// One entry of an M3U playlist
+#[derive(Debug, Clone, PartialEq)]
+pub struct M3uEntry {
+    /// Path or URL as written in the file
+    pub location: String,
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub duration_secs: Option<i64>,
+}
+
+/// Parse an M3U or M3U8 playlist. Comments and directives other than
+/// `#EXTINF` are skipped.
+pub fn parse_m3u(text: &str) -> Vec<M3uEntry> {
+    let mut entries = Vec::new();
+    let mut info: Option<(Option<i64>, Option<String>, Option<String>)> = None;
+
+    for line in text.lines() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            info = Some(parse_extinf(extinf));
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let (duration_secs, artist, title) = info.take().unwrap_or_default();
+        entries.push(M3uEntry {
+            location: line.to_string(),
+            artist,
+            title,
+            duration_secs,
+        });
+    }
+
+    entries
+}
+
+/// `-1 tvg-name="x",Artist - Title` -> (duration, artist, title)
+fn parse_extinf(extinf: &str) -> (Option<i64>, Option<String>, Option<String>) {
+    let (duration, display) = extinf.split_once(',').unwrap_or((extinf, ""));
+    let duration_secs = duration
+        .split_whitespace()
+        .next()
+        .and_then(|d| d.parse::<i64>().ok())
+        .filter(|d| *d >= 0);
+
+    let display = display.trim();
+    let (artist, title) = match display.split_once(" - ") {
+        Some((artist, title)) => (Some(artist.trim()), title.trim()),
+        None => (None, display),
+    };
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    (duration_secs, artist.and_then(non_empty), non_empty(title))
+}
+
+/// Write an extended M3U playlist
+pub fn write_m3u(entries: &[M3uEntry]) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for entry in entries {
+        if let Some(ref title) = entry.title {
+            let display = match entry.artist {
+                Some(ref artist) => format!("{} - {}", artist, title),
+                None => title.clone(),
+            };
+            out.push_str(&format!(
+                "#EXTINF:{},{}\n",
+                entry.duration_secs.unwrap_or(-1),
+                display
+            ));
+        }
+        out.push_str(&entry.location);
+        out.push('\n');
+    }
+    out
+}
+
+/// Track title from a location's file name, without the extension or a
+/// leading track number: "Music/03 - Low Light.flac" -> "Low Light"
+pub fn title_from_location(location: &str) -> Option<String> {
+    let file_name = location.rsplit(['/', '\\']).next().unwrap_or(location);
+    let stem = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && ext.len() <= 5 => stem,
+        _ => file_name,
+    };
+    let title = stem
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start_matches([' ', '-', '.', '_'])
+        .trim();
+    // A name that's only a number is the title
+    let title = if title.is_empty() { stem.trim() } else { title };
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_extended_m3u() {
+        let text = "\u{feff}#EXTM3U\n\
+                    #EXTINF:215,Glass Harbor - Pacific Standard\n\
+                    /music/Glass Harbor/01 Pacific Standard.flac\n\
+                    \n\
+                    # a comment\n\
+                    C:\\Music\\02 - Low Light.mp3\n\
+                    #EXTINF:-1 tvg-id=\"x\",Tidewater\n\
+                    http://example.com/stream\n";
+
+        let entries = parse_m3u(text);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].artist.as_deref(), Some("Glass Harbor"));
+        assert_eq!(entries[0].title.as_deref(), Some("Pacific Standard"));
+        assert_eq!(entries[0].duration_secs, Some(215));
+        assert_eq!(entries[1].location, "C:\\Music\\02 - Low Light.mp3");
+        assert_eq!(entries[1].title, None);
+        assert_eq!(entries[2].artist, None);
+        assert_eq!(entries[2].title.as_deref(), Some("Tidewater"));
+        assert_eq!(entries[2].duration_secs, None);
+    }
+
+    #[test]
+    fn written_playlist_parses_back() {
+        let entries = vec![
+            M3uEntry {
+                location: "/music/Pacific Standard.flac".to_string(),
+                artist: Some("Glass Harbor".to_string()),
+                title: Some("Pacific Standard".to_string()),
+                duration_secs: Some(215),
+            },
+            M3uEntry {
+                location: "Low Light.flac".to_string(),
+                artist: None,
+                title: Some("Low Light".to_string()),
+                duration_secs: None,
+            },
+        ];
+
+        let text = write_m3u(&entries);
+
+        assert!(text.starts_with("#EXTM3U\n#EXTINF:215,Glass Harbor - Pacific Standard\n"));
+        assert_eq!(parse_m3u(&text), entries);
+    }
+
+    #[test]
+    fn title_comes_from_the_file_name() {
+        assert_eq!(
+            title_from_location("Music/03 - Low Light.flac").as_deref(),
+            Some("Low Light")
+        );
+        assert_eq!(
+            title_from_location("C:\\Music\\Tidewater.mp3").as_deref(),
+            Some("Tidewater")
+        );
+        assert_eq!(title_from_location("1999.flac").as_deref(), Some("1999"));
+        assert_eq!(title_from_location(""), None);
+    }
+}
@@ -1,18 +1,42 @@
-use crate::cache::CacheManager;
+use crate::cache::{file_cache_key, CacheManager};
 use crate::cloud_storage::CloudStorageError;
 use crate::db::{
-    Database, DbAlbum, DbAlbumArtist, DbArtist, DbAudioFormat, DbFile, DbImport, DbLibraryImage,
-    DbRelease, DbTorrent, DbTrack, DbTrackArtist, ImportOperationStatus, ImportStatus,
-    LibraryImageType, LibrarySearchResults,
+    Database, DbAlbum, DbAlbumArtist, DbAlbumComment, DbAlbumCustomTag, DbAlbumVersion, DbArtist,
+    DbAudioFormat, DbCloudUsage, DbCollection, DbDecade, DbFile, DbGenre, DbImport, DbLibraryImage,
+    DbPlayHistoryEntry, DbPlaylist, DbPlaylistEntry, DbRelease, DbReleaseAvailability,
+    DbReleaseDisc, DbSyncConflict, DbTorrent, DbTrack, DbTrackArtist, DbTrackAudioSource,
+    DbTrackLyrics, DbTrackWork, ImportOperationStatus, ImportStatus, LibraryImageType,
+    LibrarySearchResults, SearchPage, TrackRemap, TrackTagUpdate,
 };
 use crate::encryption::EncryptionService;
+use crate::library::classical::{
+    parse_release_works, work_composers, works_missing_composers, ComposerCredit, TrackWorkCredit,
+};
+use crate::library::duplicates::{
+    find_duplicate_groups, match_duplicate_tracks, DuplicateRelease, DuplicateReleases,
+};
 use crate::library::export::ExportService;
+use crate::library::m3u::{parse_m3u, title_from_location, write_m3u, M3uEntry};
+use crate::library::metadata_refresh::{diff_musicbrainz_release, MetadataChange, MetadataUpdate};
+use crate::library::musicbrainz_collection::{collection_changes, CollectionChanges};
 use crate::library_dir::LibraryDir;
+use crate::lyrics::{Lyrics, LyricsQuery};
 use crate::storage::cleanup::{append_pending_deletions, PendingDeletion};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use thiserror::Error;
 use tokio::sync::broadcast;
-use tracing::warn;
+use tracing::{info, warn};
+
+/// How many plays the history keeps
+pub const PLAY_HISTORY_LEN: u32 = 200;
+
+/// How many albums each "recently ..." section of the library shows
+pub const RECENT_ALBUMS_LEN: u32 = 12;
+
+/// Comments are meant to be short notes, not reviews
+pub const MAX_COMMENT_CHARS: usize = 500;
+
 #[derive(Error, Debug)]
 pub enum LibraryError {
     #[error("Database error: {0}")]
@@ -23,10 +47,44 @@ pub enum LibraryError {
     Import(String),
     #[error("Track mapping error: {0}")]
     TrackMapping(String),
+    #[error("Invalid tags: {0}")]
+    Tags(String),
     #[error("Cloud storage error: {0}")]
     CloudStorage(#[from] CloudStorageError),
     #[error("Encryption error: {0}")]
     Encryption(#[from] crate::encryption::EncryptionError),
+    #[error("Lyrics error: {0}")]
+    Lyrics(String),
+    #[error("MusicBrainz error: {0}")]
+    MusicBrainz(String),
+    #[error("Invalid comment: {0}")]
+    Comment(String),
+    #[error("Playlist error: {0}")]
+    Playlist(String),
+    #[error("Collection error: {0}")]
+    Collection(String),
+    #[error("Duplicate merge error: {0}")]
+    Duplicates(String),
+    #[error("Sync conflict error: {0}")]
+    SyncConflict(String),
+}
+
+/// What importing an M3U playlist found
+#[derive(Debug, Clone)]
+pub struct M3uImport {
+    pub playlist: DbPlaylist,
+    pub matched: usize,
+    /// Entries with no matching track, as "Artist - Title" or the location
+    pub unmatched: Vec<String>,
+}
+
+/// Playlist names are trimmed and can't be empty
+fn playlist_name(name: &str) -> Result<&str, LibraryError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(LibraryError::Playlist("Playlist name is empty".to_string()));
+    }
+    Ok(name)
 }
 
 /// Events emitted by LibraryManager when data changes
@@ -204,561 +262,3440 @@ impl LibraryManager {
     ) -> Result<Vec<DbAlbum>, LibraryError> {
         Ok(self.database.get_albums(sort).await?)
     }
+    /// Get the albums matching `filter`, sorted by the given criteria
+    pub async fn get_albums_filtered(
+        &self,
+        sort: &[crate::db::AlbumSortCriterion],
+        filter: &crate::db::AlbumFilter,
+    ) -> Result<Vec<DbAlbum>, LibraryError> {
+        Ok(self.database.get_albums_filtered(sort, filter).await?)
+    }
+    /// Decades the library's albums are from, as their first years
+    pub async fn get_album_decades(&self) -> Result<Vec<i32>, LibraryError> {
+        Ok(self.database.get_album_decades().await?)
+    }
+    /// Every decade the library's albums are from with its album count
+    pub async fn get_decades(&self) -> Result<Vec<DbDecade>, LibraryError> {
+        Ok(self.database.get_decades().await?)
+    }
+    /// IDs of the albums released from `from_year` through `to_year`
+    pub async fn get_album_ids_for_years(
+        &self,
+        from_year: i32,
+        to_year: i32,
+    ) -> Result<Vec<String>, LibraryError> {
+        Ok(self
+            .database
+            .get_album_ids_for_years(from_year, to_year)
+            .await?)
+    }
     /// Get album by ID
     pub async fn get_album_by_id(&self, album_id: &str) -> Result<Option<DbAlbum>, LibraryError> {
         Ok(self.database.get_album_by_id(album_id).await?)
     }
-    /// Get all releases for a specific album
-    pub async fn get_releases_for_album(
+    /// Link two albums as versions (editions) of the same work
+    pub async fn link_album_versions(
         &self,
         album_id: &str,
-    ) -> Result<Vec<DbRelease>, LibraryError> {
-        Ok(self.database.get_releases_for_album(album_id).await?)
+        other_album_id: &str,
+    ) -> Result<(), LibraryError> {
+        self.database
+            .link_album_versions(album_id, other_album_id)
+            .await?;
+
+        self.notify_albums_changed();
+
+        Ok(())
     }
-    /// Get tracks for a specific release
-    pub async fn get_tracks(&self, release_id: &str) -> Result<Vec<DbTrack>, LibraryError> {
-        Ok(self.database.get_tracks_for_release(release_id).await?)
+    /// Remove an album from its version group
+    pub async fn unlink_album_version(&self, album_id: &str) -> Result<(), LibraryError> {
+        self.database.unlink_album_version(album_id).await?;
+
+        self.notify_albums_changed();
+
+        Ok(())
     }
-    /// Get a single track by ID
-    pub async fn get_track(&self, track_id: &str) -> Result<Option<DbTrack>, LibraryError> {
-        Ok(self.database.get_track_by_id(track_id).await?)
+    /// Set the edition label for a linked album
+    pub async fn set_album_version_label(
+        &self,
+        album_id: &str,
+        label: Option<&str>,
+    ) -> Result<(), LibraryError> {
+        self.database
+            .set_album_version_label(album_id, label)
+            .await?;
+
+        self.notify_albums_changed();
+
+        Ok(())
     }
-    /// Get all files for a specific release
-    ///
-    /// Files belong to releases (not albums or tracks). This includes both:
-    /// - Audio files (linked to tracks via db_track_position)
-    /// - Metadata files (cover art, CUE sheets, etc.)
-    pub async fn get_files_for_release(
+    /// Get all versions linked to an album (including itself)
+    pub async fn get_album_versions(
         &self,
-        release_id: &str,
-    ) -> Result<Vec<DbFile>, LibraryError> {
-        Ok(self.database.get_files_for_release(release_id).await?)
+        album_id: &str,
+    ) -> Result<Vec<DbAlbumVersion>, LibraryError> {
+        Ok(self.database.get_album_versions(album_id).await?)
     }
-    /// Get a specific file by ID
-    ///
-    /// Used during streaming to retrieve the file record after looking up
-    /// the track→file relationship via db_track_position.
-    pub async fn get_file_by_id(&self, file_id: &str) -> Result<Option<DbFile>, LibraryError> {
-        Ok(self.database.get_file_by_id(file_id).await?)
+    /// Get the version group ID for every linked album
+    pub async fn get_album_version_groups(
+        &self,
+    ) -> Result<std::collections::HashMap<String, String>, LibraryError> {
+        Ok(self.database.get_album_version_groups().await?)
     }
-    /// Get audio format for a track
-    pub async fn get_audio_format_by_track_id(
+    /// Replace an album's genres with normalized versions of `names`
+    pub async fn set_album_genres(
         &self,
-        track_id: &str,
-    ) -> Result<Option<DbAudioFormat>, LibraryError> {
-        Ok(self.database.get_audio_format_by_track_id(track_id).await?)
+        album_id: &str,
+        names: &[String],
+    ) -> Result<(), LibraryError> {
+        Ok(self.database.set_album_genres(album_id, names).await?)
     }
-
-    /// Get release ID for a track
-    pub async fn get_release_id_for_track(&self, track_id: &str) -> Result<String, LibraryError> {
-        let track = self
-            .database
-            .get_track_by_id(track_id)
-            .await?
-            .ok_or_else(|| LibraryError::TrackMapping("Track not found".to_string()))?;
-        Ok(track.release_id)
+    /// Get an album's genre names, in order
+    pub async fn get_album_genres(&self, album_id: &str) -> Result<Vec<String>, LibraryError> {
+        Ok(self.database.get_album_genres(album_id).await?)
     }
-    /// Get album ID for a track
-    pub async fn get_album_id_for_track(&self, track_id: &str) -> Result<String, LibraryError> {
-        let track = self
-            .database
-            .get_track_by_id(track_id)
-            .await?
-            .ok_or_else(|| LibraryError::TrackMapping("Track not found".to_string()))?;
-        let album_id = self
-            .database
-            .get_album_id_for_release(&track.release_id)
-            .await?
-            .ok_or_else(|| LibraryError::TrackMapping("Release not found".to_string()))?;
-        Ok(album_id)
+    /// Get the first genre of every album that has one
+    pub async fn get_album_primary_genres(
+        &self,
+    ) -> Result<std::collections::HashMap<String, String>, LibraryError> {
+        Ok(self.database.get_album_primary_genres().await?)
     }
-    /// Get album ID for a release
-    pub async fn get_album_id_for_release(&self, release_id: &str) -> Result<String, LibraryError> {
-        let album_id = self
-            .database
-            .get_album_id_for_release(release_id)
-            .await?
-            .ok_or_else(|| LibraryError::TrackMapping("Release not found".to_string()))?;
-        Ok(album_id)
+    /// Get every genre with its album count
+    pub async fn get_genres(&self) -> Result<Vec<DbGenre>, LibraryError> {
+        Ok(self.database.get_genres().await?)
     }
-    /// Insert an artist
-    pub async fn insert_artist(&self, artist: &DbArtist) -> Result<(), LibraryError> {
-        self.database.insert_artist(artist).await?;
-        Ok(())
+    /// Get the IDs of albums tagged with a genre
+    pub async fn get_album_ids_for_genre(
+        &self,
+        genre_id: &str,
+    ) -> Result<Vec<String>, LibraryError> {
+        Ok(self.database.get_album_ids_for_genre(genre_id).await?)
     }
-    /// Get artist by Discogs ID (for deduplication)
-    pub async fn get_artist_by_discogs_id(
+    /// Genres that look like spelling variants or related names of each other
+    pub async fn get_genre_merge_suggestions(
         &self,
-        discogs_artist_id: &str,
-    ) -> Result<Option<DbArtist>, LibraryError> {
-        Ok(self
-            .database
-            .get_artist_by_discogs_id(discogs_artist_id)
-            .await?)
+    ) -> Result<Vec<crate::genres::GenreMergeSuggestion>, LibraryError> {
+        let genres = self.database.get_genres().await?;
+        Ok(crate::genres::suggest_genre_merges(&genres))
     }
+    /// Fold several genres into one, returning the kept genre's ID
+    pub async fn merge_genres(
+        &self,
+        genre_ids: &[String],
+        into_name: &str,
+    ) -> Result<String, LibraryError> {
+        let into_id = self.database.merge_genres(genre_ids, into_name).await?;
 
-    /// Get artist by MusicBrainz ID (for deduplication)
-    pub async fn get_artist_by_mb_id(&self, mb_id: &str) -> Result<Option<DbArtist>, LibraryError> {
-        Ok(self.database.get_artist_by_mb_id(mb_id).await?)
-    }
+        self.notify_albums_changed();
 
-    /// Get artist by name (case-insensitive, first match)
-    pub async fn get_artist_by_name(&self, name: &str) -> Result<Option<DbArtist>, LibraryError> {
-        Ok(self.database.get_artist_by_name(name).await?)
+        Ok(into_id)
     }
-
-    /// Fill in NULL external IDs on an existing artist (never overwrites)
-    pub async fn update_artist_external_ids(
+    /// Replace an album's custom tags. Keys and values are trimmed, tags with
+    /// an empty key are dropped and an empty value becomes a plain label.
+    pub async fn set_album_custom_tags(
         &self,
-        id: &str,
-        discogs_id: Option<&str>,
-        mb_id: Option<&str>,
-        sort_name: Option<&str>,
+        album_id: &str,
+        tags: &[DbAlbumCustomTag],
     ) -> Result<(), LibraryError> {
+        let mut cleaned: Vec<DbAlbumCustomTag> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let key = tag.key.trim();
+            if key.is_empty() {
+                continue;
+            }
+            if cleaned.iter().any(|t| t.key.eq_ignore_ascii_case(key)) {
+                return Err(LibraryError::Tags(format!("Tag \"{key}\" is used twice")));
+            }
+            cleaned.push(DbAlbumCustomTag {
+                key: key.to_string(),
+                value: tag
+                    .value
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string),
+            });
+        }
         Ok(self
             .database
-            .update_artist_external_ids(id, discogs_id, mb_id, sort_name)
+            .set_album_custom_tags(album_id, &cleaned)
             .await?)
     }
-
-    /// Insert album-artist relationship
-    pub async fn insert_album_artist(
+    /// Get an album's custom tags, in order
+    pub async fn get_album_custom_tags(
         &self,
-        album_artist: &DbAlbumArtist,
-    ) -> Result<(), LibraryError> {
-        self.database.insert_album_artist(album_artist).await?;
-        Ok(())
+        album_id: &str,
+    ) -> Result<Vec<DbAlbumCustomTag>, LibraryError> {
+        Ok(self.database.get_album_custom_tags(album_id).await?)
     }
-    /// Insert track-artist relationship
-    pub async fn insert_track_artist(
+    /// Save an album's notes. Blank notes are removed.
+    pub async fn set_album_notes(&self, album_id: &str, notes: &str) -> Result<(), LibraryError> {
+        let notes = notes.trim();
+        let notes = (!notes.is_empty()).then_some(notes);
+        Ok(self.database.set_album_notes(album_id, notes).await?)
+    }
+    /// Get an album's notes, if it has any
+    pub async fn get_album_notes(&self, album_id: &str) -> Result<Option<String>, LibraryError> {
+        Ok(self.database.get_album_notes(album_id).await?)
+    }
+    /// Leave a comment on an album as the member with `author_pubkey`
+    pub async fn add_album_comment(
         &self,
-        track_artist: &DbTrackArtist,
+        album_id: &str,
+        author_pubkey: &str,
+        content: &str,
+    ) -> Result<DbAlbumComment, LibraryError> {
+        let content = content.trim();
+        if content.is_empty() {
+            return Err(LibraryError::Comment("Comment is empty".to_string()));
+        }
+        if content.chars().count() > MAX_COMMENT_CHARS {
+            return Err(LibraryError::Comment(format!(
+                "Comments are limited to {MAX_COMMENT_CHARS} characters"
+            )));
+        }
+
+        let comment = DbAlbumComment {
+            id: uuid::Uuid::new_v4().to_string(),
+            album_id: album_id.to_string(),
+            author_pubkey: author_pubkey.to_string(),
+            content: content.to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        self.database.insert_album_comment(&comment).await?;
+        Ok(comment)
+    }
+    /// Remove a comment. Members can only remove their own.
+    pub async fn delete_album_comment(
+        &self,
+        comment_id: &str,
+        author_pubkey: &str,
     ) -> Result<(), LibraryError> {
-        self.database.insert_track_artist(track_artist).await?;
-        Ok(())
+        let Some(comment) = self.database.get_album_comment(comment_id).await? else {
+            return Ok(());
+        };
+        if comment.author_pubkey != author_pubkey {
+            return Err(LibraryError::Comment(
+                "Only the member who wrote a comment can remove it".to_string(),
+            ));
+        }
+        Ok(self.database.delete_album_comment(comment_id).await?)
     }
-    /// Get artists for an album
-    pub async fn get_artists_for_album(
+    /// Get an album's comments, newest first
+    pub async fn get_album_comments(
         &self,
         album_id: &str,
-    ) -> Result<Vec<DbArtist>, LibraryError> {
-        Ok(self.database.get_artists_for_album(album_id).await?)
+    ) -> Result<Vec<DbAlbumComment>, LibraryError> {
+        Ok(self.database.get_album_comments(album_id).await?)
     }
-    /// Get artists for a track
-    pub async fn get_artists_for_track(
-        &self,
-        track_id: &str,
-    ) -> Result<Vec<DbArtist>, LibraryError> {
-        Ok(self.database.get_artists_for_track(track_id).await?)
+    /// Get all playlists, alphabetically
+    pub async fn get_playlists(&self) -> Result<Vec<DbPlaylist>, LibraryError> {
+        Ok(self.database.get_playlists().await?)
     }
-    /// Get artist by ID
-    pub async fn get_artist_by_id(
+    pub async fn get_playlist(
         &self,
-        artist_id: &str,
-    ) -> Result<Option<DbArtist>, LibraryError> {
-        Ok(self.database.get_artist_by_id(artist_id).await?)
+        playlist_id: &str,
+    ) -> Result<Option<DbPlaylist>, LibraryError> {
+        Ok(self.database.get_playlist_by_id(playlist_id).await?)
     }
-    /// Search across artists, albums, and tracks
-    pub async fn search_library(
+    /// Get a playlist's tracks in order
+    pub async fn get_playlist_entries(
         &self,
-        query: &str,
-        limit: usize,
-    ) -> Result<LibrarySearchResults, LibraryError> {
-        Ok(self.database.search_library(query, limit).await?)
+        playlist_id: &str,
+    ) -> Result<Vec<DbPlaylistEntry>, LibraryError> {
+        Ok(self.database.get_playlist_entries(playlist_id).await?)
     }
-
-    /// Get all artists that have at least one album
-    pub async fn get_artists_with_albums(&self) -> Result<Vec<DbArtist>, LibraryError> {
-        Ok(self.database.get_artists_with_albums().await?)
+    /// Create an empty playlist
+    pub async fn create_playlist(&self, name: &str) -> Result<DbPlaylist, LibraryError> {
+        let playlist = DbPlaylist::new(playlist_name(name)?);
+        self.database.insert_playlist(&playlist).await?;
+        Ok(playlist)
     }
-
-    /// Get albums for an artist
-    pub async fn get_albums_for_artist(
-        &self,
-        artist_id: &str,
-    ) -> Result<Vec<DbAlbum>, LibraryError> {
-        Ok(self.database.get_albums_for_artist(artist_id).await?)
+    pub async fn rename_playlist(&self, playlist_id: &str, name: &str) -> Result<(), LibraryError> {
+        let name = playlist_name(name)?;
+        Ok(self.database.rename_playlist(playlist_id, name).await?)
     }
-    /// Upsert a library image record
-    pub async fn upsert_library_image(&self, image: &DbLibraryImage) -> Result<(), LibraryError> {
-        self.database.upsert_library_image(image).await?;
-        Ok(())
+    pub async fn delete_playlist(&self, playlist_id: &str) -> Result<(), LibraryError> {
+        Ok(self.database.delete_playlist(playlist_id).await?)
     }
-
-    /// Get a library image by ID and type
-    pub async fn get_library_image(
+    /// Add tracks to the end of a playlist
+    pub async fn add_tracks_to_playlist(
         &self,
-        id: &str,
-        image_type: &LibraryImageType,
-    ) -> Result<Option<DbLibraryImage>, LibraryError> {
-        Ok(self.database.get_library_image(id, image_type).await?)
+        playlist_id: &str,
+        track_ids: &[String],
+    ) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .append_playlist_tracks(playlist_id, track_ids)
+            .await?)
     }
-
-    /// Get a library image by ID (regardless of type)
-    pub async fn get_library_image_by_id(
+    /// Remove the entry at `index`. A track can be in a playlist more than
+    /// once, so entries go by position rather than track ID.
+    pub async fn remove_playlist_entry(
         &self,
-        id: &str,
-    ) -> Result<Option<DbLibraryImage>, LibraryError> {
-        Ok(self.database.get_library_image_by_id(id).await?)
+        playlist_id: &str,
+        index: usize,
+    ) -> Result<(), LibraryError> {
+        let mut track_ids = self.database.get_playlist_track_ids(playlist_id).await?;
+        if index >= track_ids.len() {
+            return Err(LibraryError::Playlist(format!(
+                "No entry {index} in the playlist"
+            )));
+        }
+        track_ids.remove(index);
+        Ok(self
+            .database
+            .set_playlist_tracks(playlist_id, &track_ids)
+            .await?)
     }
-
-    /// Delete a library image by ID and type
-    pub async fn delete_library_image(
+    /// Move the entry at `from` so it ends up at `to`
+    pub async fn move_playlist_entry(
         &self,
-        id: &str,
-        image_type: &LibraryImageType,
+        playlist_id: &str,
+        from: usize,
+        to: usize,
     ) -> Result<(), LibraryError> {
-        self.database.delete_library_image(id, image_type).await?;
-        Ok(())
+        let mut track_ids = self.database.get_playlist_track_ids(playlist_id).await?;
+        if from >= track_ids.len() || to >= track_ids.len() {
+            return Err(LibraryError::Playlist(format!(
+                "Can't move entry {from} to {to} in a playlist of {}",
+                track_ids.len()
+            )));
+        }
+        let track_id = track_ids.remove(from);
+        track_ids.insert(to, track_id);
+        Ok(self
+            .database
+            .set_playlist_tracks(playlist_id, &track_ids)
+            .await?)
     }
+    /// Create a playlist from the text of an M3U or M3U8 file, matching
+    /// entries to library tracks by artist and title
+    pub async fn import_m3u_playlist(
+        &self,
+        name: &str,
+        text: &str,
+    ) -> Result<M3uImport, LibraryError> {
+        let entries = parse_m3u(text);
+        if entries.is_empty() {
+            return Err(LibraryError::Playlist(
+                "The file has no playlist entries".to_string(),
+            ));
+        }
 
-    /// Set an album's cover release (which release provides the cover art)
-    pub async fn set_album_cover_release(
+        let mut track_ids = Vec::new();
+        let mut unmatched = Vec::new();
+        for entry in &entries {
+            let title = entry
+                .title
+                .clone()
+                .or_else(|| title_from_location(&entry.location));
+            let track_id = match title {
+                Some(ref title) => {
+                    self.database
+                        .find_track_by_title(title, entry.artist.as_deref())
+                        .await?
+                }
+                None => None,
+            };
+            match track_id {
+                Some(id) => track_ids.push(id),
+                None => unmatched.push(match (&entry.artist, &entry.title) {
+                    (Some(artist), Some(title)) => format!("{artist} - {title}"),
+                    _ => entry.location.clone(),
+                }),
+            }
+        }
+
+        let playlist = self.create_playlist(name).await?;
+        self.database
+            .set_playlist_tracks(&playlist.id, &track_ids)
+            .await?;
+        Ok(M3uImport {
+            playlist,
+            matched: track_ids.len(),
+            unmatched,
+        })
+    }
+    /// Write a playlist as extended M3U. Tracks from unmanaged releases point
+    /// at their original files; the rest only exist inside bae's storage, so
+    /// they're written by file name for the `#EXTINF` line to identify.
+    pub async fn export_m3u_playlist(&self, playlist_id: &str) -> Result<String, LibraryError> {
+        let mut entries = Vec::new();
+        let mut releases: HashMap<String, Option<DbRelease>> = HashMap::new();
+        for entry in self.database.get_playlist_entries(playlist_id).await? {
+            let file = match self
+                .database
+                .get_audio_format_by_track_id(&entry.track_id)
+                .await?
+                .and_then(|format| format.file_id)
+            {
+                Some(file_id) => self.database.get_file_by_id(&file_id).await?,
+                None => None,
+            };
+            let location = match file {
+                Some(file) => {
+                    if !releases.contains_key(&file.release_id) {
+                        let release = self.database.get_release_by_id(&file.release_id).await?;
+                        releases.insert(file.release_id.clone(), release);
+                    }
+                    match releases[&file.release_id]
+                        .as_ref()
+                        .and_then(|r| r.unmanaged_path.as_deref())
+                    {
+                        Some(dir) => Path::new(dir)
+                            .join(&file.original_filename)
+                            .to_string_lossy()
+                            .into_owned(),
+                        None => file.original_filename,
+                    }
+                }
+                None => entry.title.clone(),
+            };
+            entries.push(M3uEntry {
+                location,
+                artist: Some(entry.artist_name),
+                title: Some(entry.title),
+                duration_secs: entry.duration_ms.map(|ms| ms / 1000),
+            });
+        }
+        Ok(write_m3u(&entries))
+    }
+    /// Get all collections, alphabetically
+    pub async fn get_collections(&self) -> Result<Vec<DbCollection>, LibraryError> {
+        Ok(self.database.get_collections().await?)
+    }
+    /// Create an empty collection. Names are trimmed and can't be empty.
+    pub async fn create_collection(&self, name: &str) -> Result<DbCollection, LibraryError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(LibraryError::Collection(
+                "Collection name is empty".to_string(),
+            ));
+        }
+        let collection = DbCollection::new(name);
+        self.database.insert_collection(&collection).await?;
+        Ok(collection)
+    }
+    /// Delete a collection. Its albums stay in the library.
+    pub async fn delete_collection(&self, collection_id: &str) -> Result<(), LibraryError> {
+        self.database.delete_collection(collection_id).await?;
+
+        self.notify_albums_changed();
+
+        Ok(())
+    }
+    pub async fn add_album_to_collection(
         &self,
+        collection_id: &str,
         album_id: &str,
-        cover_release_id: &str,
     ) -> Result<(), LibraryError> {
         self.database
-            .set_album_cover_release(album_id, cover_release_id)
+            .add_album_to_collection(collection_id, album_id)
             .await?;
 
         self.notify_albums_changed();
 
         Ok(())
     }
+    pub async fn remove_album_from_collection(
+        &self,
+        collection_id: &str,
+        album_id: &str,
+    ) -> Result<(), LibraryError> {
+        self.database
+            .remove_album_from_collection(collection_id, album_id)
+            .await?;
 
-    /// Queue all locally-managed files for a release into the pending deletions manifest.
-    ///
-    /// Only queues files for releases with `managed_locally = true`.
-    /// Unmanaged files and cloud-only files are left untouched.
-    async fn queue_release_files_for_deletion(&self, release_id: &str, library_dir: &LibraryDir) {
-        let release = match self.database.get_release_by_id(release_id).await {
-            Ok(Some(r)) => r,
-            _ => return,
-        };
-
-        if !release.managed_locally {
-            return;
-        }
-
-        let files = match self.get_files_for_release(release_id).await {
-            Ok(files) => files,
-            Err(e) => {
-                warn!("Failed to get files for release {}: {}", release_id, e);
-                return;
-            }
-        };
-
-        let pending: Vec<PendingDeletion> = files
-            .iter()
-            .map(|f| PendingDeletion::Local {
-                path: f.local_storage_path(library_dir).display().to_string(),
-            })
-            .collect();
+        self.notify_albums_changed();
 
-        if !pending.is_empty() {
-            if let Err(e) = append_pending_deletions(library_dir.as_ref(), &pending).await {
-                warn!("Failed to queue deferred deletions: {}", e);
-            }
-        }
+        Ok(())
     }
-
-    /// Delete a release and its associated data
+    /// Collection IDs of every album in at least one collection, keyed by album ID
+    pub async fn get_album_collection_ids(
+        &self,
+    ) -> Result<HashMap<String, Vec<String>>, LibraryError> {
+        Ok(self.database.get_album_collection_ids().await?)
+    }
+    /// Get imported tracks similar to a seed track, most similar first
+    pub async fn get_radio_candidates(
+        &self,
+        seed_track_id: &str,
+        limit: u32,
+    ) -> Result<Vec<String>, LibraryError> {
+        Ok(self
+            .database
+            .get_radio_candidates(seed_track_id, limit)
+            .await?)
+    }
+    /// Get all releases for a specific album
+    pub async fn get_releases_for_album(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<DbRelease>, LibraryError> {
+        Ok(self.database.get_releases_for_album(album_id).await?)
+    }
+    /// Get tracks for a specific release
+    pub async fn get_tracks(&self, release_id: &str) -> Result<Vec<DbTrack>, LibraryError> {
+        Ok(self.database.get_tracks_for_release(release_id).await?)
+    }
+    /// Get a single track by ID
+    pub async fn get_track(&self, track_id: &str) -> Result<Option<DbTrack>, LibraryError> {
+        Ok(self.database.get_track_by_id(track_id).await?)
+    }
+    /// Get all files for a specific release
     ///
-    /// This will:
-    /// 1. Queue files for deferred deletion via the pending deletions manifest
-    /// 2. Delete the release from database (cascades to tracks, files, etc.)
-    /// 3. If this was the last release for the album, also delete the album
+    /// Files belong to releases (not albums or tracks). This includes both:
+    /// - Audio files (linked to tracks via db_track_position)
+    /// - Metadata files (cover art, CUE sheets, etc.)
+    pub async fn get_files_for_release(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<DbFile>, LibraryError> {
+        Ok(self.database.get_files_for_release(release_id).await?)
+    }
+    /// Get a specific file by ID
     ///
-    /// File cleanup happens asynchronously via the cleanup service, which retries
-    /// on failure. This prevents orphaned cloud objects when deletion fails.
-    pub async fn delete_release(
+    /// Used during streaming to retrieve the file record after looking up
+    /// the track→file relationship via db_track_position.
+    pub async fn get_file_by_id(&self, file_id: &str) -> Result<Option<DbFile>, LibraryError> {
+        Ok(self.database.get_file_by_id(file_id).await?)
+    }
+    /// Get audio format for a track
+    pub async fn get_audio_format_by_track_id(
+        &self,
+        track_id: &str,
+    ) -> Result<Option<DbAudioFormat>, LibraryError> {
+        Ok(self.database.get_audio_format_by_track_id(track_id).await?)
+    }
+    /// Store detected cue points for a track
+    pub async fn set_audio_format_cue_points(
+        &self,
+        track_id: &str,
+        cue_in_ms: i64,
+        cue_out_ms: i64,
+    ) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .set_audio_format_cue_points(track_id, cue_in_ms, cue_out_ms)
+            .await?)
+    }
+    /// Get the audio attached to each track of a release
+    pub async fn get_track_audio_sources(
         &self,
         release_id: &str,
-        library_dir: &LibraryDir,
+    ) -> Result<Vec<DbTrackAudioSource>, LibraryError> {
+        Ok(self.database.get_track_audio_sources(release_id).await?)
+    }
+    /// Correct a release's track order and track-to-file assignment.
+    ///
+    /// `remaps` must cover every track of the release exactly once, and every
+    /// piece of imported audio must end up on exactly one track.
+    pub async fn remap_release_tracks(
+        &self,
+        release_id: &str,
+        remaps: &[TrackRemap],
     ) -> Result<(), LibraryError> {
-        let album_id = self.get_album_id_for_release(release_id).await?;
+        let tracks = self.database.get_tracks_for_release(release_id).await?;
+        let sources = self.database.get_track_audio_sources(release_id).await?;
 
-        // Queue files for deferred deletion before removing DB records
-        self.queue_release_files_for_deletion(release_id, library_dir)
-            .await;
+        let track_ids: HashSet<&str> = tracks.iter().map(|t| t.id.as_str()).collect();
+        let mut seen_tracks = HashSet::new();
+        for remap in remaps {
+            if !track_ids.contains(remap.track_id.as_str()) {
+                return Err(LibraryError::TrackMapping(format!(
+                    "Track {} is not part of this release",
+                    remap.track_id
+                )));
+            }
+            if !seen_tracks.insert(remap.track_id.as_str()) {
+                return Err(LibraryError::TrackMapping(format!(
+                    "Track {} is listed more than once",
+                    remap.track_id
+                )));
+            }
+        }
+        if seen_tracks.len() != track_ids.len() {
+            return Err(LibraryError::TrackMapping(
+                "Every track of the release must be included".to_string(),
+            ));
+        }
 
-        self.database.delete_release(release_id).await?;
-        let remaining_releases = self.get_releases_for_album(&album_id).await?;
-        if remaining_releases.is_empty() {
-            self.database.delete_album(&album_id).await?;
+        let source_ids: HashSet<&str> =
+            sources.iter().map(|s| s.audio_format_id.as_str()).collect();
+        let mut assigned = HashSet::new();
+        for id in remaps.iter().filter_map(|r| r.audio_format_id.as_deref()) {
+            if !source_ids.contains(id) {
+                return Err(LibraryError::TrackMapping(format!(
+                    "Audio {} is not part of this release",
+                    id
+                )));
+            }
+            if !assigned.insert(id) {
+                return Err(LibraryError::TrackMapping(
+                    "The same audio can't be assigned to two tracks".to_string(),
+                ));
+            }
+        }
+        if assigned.len() != source_ids.len() {
+            return Err(LibraryError::TrackMapping(
+                "Every audio file must be assigned to a track".to_string(),
+            ));
         }
 
-        // Notify UI that library has changed
+        self.database
+            .remap_release_tracks(release_id, remaps)
+            .await?;
+
         self.notify_albums_changed();
 
         Ok(())
     }
-
-    /// Delete an album and all its associated data
-    ///
-    /// This will:
-    /// 1. Get all releases for the album
-    /// 2. Queue files for deferred deletion via the pending deletions manifest
-    /// 3. Delete the album from database (cascades to releases and all related data)
+    /// Correct an album's title, year and artists.
     ///
-    /// File cleanup happens asynchronously via the cleanup service, which retries
-    /// on failure. This prevents orphaned cloud objects when deletion fails.
-    pub async fn delete_album(
+    /// Written through the synced tables like any other edit, so the
+    /// correction reaches other devices on the next sync.
+    pub async fn update_album_tags(
         &self,
         album_id: &str,
-        library_dir: &LibraryDir,
+        title: &str,
+        year: Option<i32>,
+        artist_names: &[String],
     ) -> Result<(), LibraryError> {
-        let releases = self.get_releases_for_album(album_id).await?;
-        for release in &releases {
-            self.queue_release_files_for_deletion(&release.id, library_dir)
-                .await;
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(LibraryError::Tags("Album title can't be empty".to_string()));
+        }
+        let artist_names: Vec<String> = artist_names
+            .iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        if artist_names.is_empty() {
+            return Err(LibraryError::Tags(
+                "An album needs at least one artist".to_string(),
+            ));
         }
 
-        self.database.delete_album(album_id).await?;
+        self.database
+            .update_album_tags(album_id, title, year, &artist_names)
+            .await?;
 
-        // Notify UI that library has changed
         self.notify_albums_changed();
 
         Ok(())
     }
-    /// Export all files for a release to a directory
-    ///
-    /// Copies files from storage to the target directory.
-    /// Files are written with their original filenames.
-    pub async fn export_release(
+    /// Correct track titles and numbering for a release
+    pub async fn update_track_tags(
         &self,
         release_id: &str,
-        target_dir: &Path,
-        cache: &CacheManager,
-        library_dir: &LibraryDir,
+        updates: &[TrackTagUpdate],
     ) -> Result<(), LibraryError> {
-        ExportService::export_release(
-            release_id,
-            target_dir,
-            self,
-            cache,
-            self.encryption_service.as_ref(),
-            library_dir,
-        )
-        .await
-        .map_err(LibraryError::Import)
+        let tracks = self.database.get_tracks_for_release(release_id).await?;
+        let track_ids: HashSet<&str> = tracks.iter().map(|t| t.id.as_str()).collect();
+
+        let mut trimmed = Vec::with_capacity(updates.len());
+        for update in updates {
+            if !track_ids.contains(update.track_id.as_str()) {
+                return Err(LibraryError::Tags(format!(
+                    "Track {} is not part of this release",
+                    update.track_id
+                )));
+            }
+            let title = update.title.trim();
+            if title.is_empty() {
+                return Err(LibraryError::Tags(
+                    "Track titles can't be empty".to_string(),
+                ));
+            }
+            trimmed.push(TrackTagUpdate {
+                title: title.to_string(),
+                ..update.clone()
+            });
+        }
+
+        self.database
+            .update_track_tags(release_id, &trimmed)
+            .await?;
+
+        self.notify_albums_changed();
+
+        Ok(())
     }
-    /// Export a single track as a FLAC file
-    ///
-    /// For one-file-per-track: extracts the original file.
-    /// For CUE/FLAC: extracts and re-encodes as a standalone FLAC.
-    pub async fn export_track(
+    /// Add a track to the play history and count the play in its stats
+    pub async fn record_play(&self, track_id: &str) -> Result<(), LibraryError> {
+        self.record_play_at(track_id, chrono::Utc::now()).await
+    }
+    /// Record a play at an earlier time, e.g. one scrobbled by a Subsonic
+    /// client. An old play counts, but doesn't move the last played time
+    /// back.
+    pub async fn record_play_at(
         &self,
         track_id: &str,
-        output_path: &Path,
-        cache: &CacheManager,
-        library_dir: &LibraryDir,
+        played_at: chrono::DateTime<chrono::Utc>,
     ) -> Result<(), LibraryError> {
-        ExportService::export_track(
-            track_id,
-            output_path,
-            self,
-            cache,
-            self.encryption_service.as_ref(),
-            library_dir,
-        )
-        .await
-        .map_err(LibraryError::Import)
-    }
-    /// Check if an album already exists by Discogs IDs
-    ///
-    /// Used for duplicate detection before import.
-    /// Returns the existing album if found, None otherwise.
-    pub async fn find_duplicate_by_discogs(
-        &self,
-        master_id: Option<&str>,
-        release_id: Option<&str>,
-    ) -> Result<Option<DbAlbum>, LibraryError> {
         Ok(self
             .database
-            .find_album_by_discogs_ids(master_id, release_id)
+            .insert_play_history(track_id, played_at, PLAY_HISTORY_LEN)
             .await?)
     }
-    /// Check if an album already exists by MusicBrainz IDs
-    ///
-    /// Used for duplicate detection before import.
-    /// Returns the existing album if found, None otherwise.
-    pub async fn find_duplicate_by_musicbrainz(
-        &self,
-        release_id: Option<&str>,
-        release_group_id: Option<&str>,
-    ) -> Result<Option<DbAlbum>, LibraryError> {
+    /// Get the play history, most recent first
+    pub async fn get_play_history(&self) -> Result<Vec<DbPlayHistoryEntry>, LibraryError> {
+        Ok(self.database.get_play_history(PLAY_HISTORY_LEN).await?)
+    }
+    /// Get the most recently added albums, newest first
+    pub async fn get_recently_added_album_ids(&self) -> Result<Vec<String>, LibraryError> {
         Ok(self
             .database
-            .find_album_by_mb_ids(release_id, release_group_id)
+            .get_recently_added_album_ids(RECENT_ALBUMS_LEN)
             .await?)
     }
-    /// Insert a new import operation record
-    pub async fn insert_import(&self, import: &DbImport) -> Result<(), LibraryError> {
-        Ok(self.database.insert_import(import).await?)
+    /// Get the albums played most recently, going by the play history
+    pub async fn get_recently_played_album_ids(&self) -> Result<Vec<String>, LibraryError> {
+        Ok(self
+            .database
+            .get_recently_played_album_ids(RECENT_ALBUMS_LEN)
+            .await?)
     }
-    /// Update the status of an import operation
-    pub async fn update_import_status(
-        &self,
-        id: &str,
-        status: ImportOperationStatus,
-    ) -> Result<(), LibraryError> {
-        Ok(self.database.update_import_status(id, status).await?)
+
+    /// Get a track's stored lyrics. None if they've never been fetched;
+    /// a remembered miss comes back as empty `Lyrics`.
+    pub async fn get_lyrics(&self, track_id: &str) -> Result<Option<Lyrics>, LibraryError> {
+        let Some(stored) = self.database.get_track_lyrics(track_id).await? else {
+            return Ok(None);
+        };
+        let json = match &self.encryption_service {
+            Some(enc) => {
+                let release_id = self.get_release_id_for_track(track_id).await?;
+                enc.derive_release_encryption(&release_id)
+                    .decrypt(&stored.content)?
+            }
+            None => stored.content,
+        };
+        let lyrics = serde_json::from_slice(&json)
+            .map_err(|e| LibraryError::Lyrics(format!("Invalid stored lyrics: {}", e)))?;
+        Ok(Some(lyrics))
     }
-    /// Link an import operation to a release (after release is created)
-    pub async fn link_import_to_release(
+
+    /// Store a track's lyrics, encrypted with its release key when the
+    /// library is encrypted
+    pub async fn save_lyrics(
         &self,
-        import_id: &str,
-        release_id: &str,
+        track_id: &str,
+        lyrics: &Lyrics,
+        source: &str,
     ) -> Result<(), LibraryError> {
+        let json = serde_json::to_vec(lyrics)
+            .map_err(|e| LibraryError::Lyrics(format!("Failed to encode lyrics: {}", e)))?;
+        let content = match &self.encryption_service {
+            Some(enc) => {
+                let release_id = self.get_release_id_for_track(track_id).await?;
+                enc.derive_release_encryption(&release_id).encrypt(&json)
+            }
+            None => json,
+        };
         Ok(self
             .database
-            .link_import_to_release(import_id, release_id)
+            .upsert_track_lyrics(&DbTrackLyrics {
+                track_id: track_id.to_string(),
+                content,
+                source: source.to_string(),
+            })
             .await?)
     }
-    /// Record an error for an import operation
-    pub async fn update_import_error(&self, id: &str, error: &str) -> Result<(), LibraryError> {
-        Ok(self.database.update_import_error(id, error).await?)
-    }
-    /// Get all active (non-complete, non-failed) imports
-    pub async fn get_active_imports(&self) -> Result<Vec<DbImport>, LibraryError> {
-        Ok(self.database.get_active_imports().await?)
-    }
-
-    /// Delete an import record (used by UI to dismiss stuck imports)
-    pub async fn delete_import(&self, id: &str) -> Result<(), LibraryError> {
-        Ok(self.database.delete_import(id).await?)
-    }
-}
+
+    /// Fetch the album's MusicBrainz release again and list where it differs
+    /// from the local tags of the given release
+    pub async fn fetch_musicbrainz_changes(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<MetadataChange>, LibraryError> {
+        let album_id = self.get_album_id_for_release(release_id).await?;
+        let album = self
+            .database
+            .get_album_by_id(&album_id)
+            .await?
+            .ok_or_else(|| LibraryError::Tags("Album not found".to_string()))?;
+        let Some(mb_release) = album.musicbrainz_release.as_ref() else {
+            return Err(LibraryError::MusicBrainz(
+                "This album isn't linked to a MusicBrainz release".to_string(),
+            ));
+        };
+
+        let (_, _, response) = crate::musicbrainz::lookup_release_by_id(&mb_release.release_id)
+            .await
+            .map_err(|e| LibraryError::MusicBrainz(e.to_string()))?;
+        let artists = self.database.get_artists_for_album(&album_id).await?;
+        let tracks = self.database.get_tracks_for_release(release_id).await?;
+        let discs = self.database.get_release_discs(release_id).await?;
+
+        Ok(diff_musicbrainz_release(
+            &album, &artists, &tracks, &discs, &response,
+        ))
+    }
+    /// Apply the MusicBrainz changes the user picked to an album and release.
+    /// Fields without an update keep their current value.
+    pub async fn apply_metadata_updates(
+        &self,
+        release_id: &str,
+        updates: &[MetadataUpdate],
+    ) -> Result<(), LibraryError> {
+        let album_id = self.get_album_id_for_release(release_id).await?;
+        let album = self
+            .database
+            .get_album_by_id(&album_id)
+            .await?
+            .ok_or_else(|| LibraryError::Tags("Album not found".to_string()))?;
+
+        let mut title = album.title;
+        let mut year = album.year;
+        let mut artists: Option<Vec<String>> = None;
+        let mut track_updates = Vec::new();
+        let mut disc_titles = Vec::new();
+        for update in updates {
+            match update {
+                MetadataUpdate::Title(new_title) => title = new_title.clone(),
+                MetadataUpdate::Year(new_year) => year = *new_year,
+                MetadataUpdate::Artists(names) => artists = Some(names.clone()),
+                MetadataUpdate::TrackTitle {
+                    track_id,
+                    disc_number,
+                    track_number,
+                    title,
+                } => track_updates.push(TrackTagUpdate {
+                    track_id: track_id.clone(),
+                    title: title.clone(),
+                    disc_number: *disc_number,
+                    track_number: *track_number,
+                }),
+                MetadataUpdate::DiscTitle { disc_number, title } => {
+                    disc_titles.push((*disc_number, title.clone()))
+                }
+            }
+        }
+
+        // Anything that isn't a track or disc title is an album-level field
+        if track_updates.len() + disc_titles.len() < updates.len() {
+            let artists = match artists {
+                Some(names) => names,
+                None => self
+                    .database
+                    .get_artists_for_album(&album_id)
+                    .await?
+                    .into_iter()
+                    .map(|a| a.name)
+                    .collect(),
+            };
+            self.update_album_tags(&album_id, &title, year, &artists)
+                .await?;
+        }
+        if !track_updates.is_empty() {
+            self.update_track_tags(release_id, &track_updates).await?;
+        }
+        if !disc_titles.is_empty() {
+            // Picked titles replace their disc's; other discs keep theirs
+            let mut titles: Vec<(i32, String)> = self
+                .database
+                .get_release_discs(release_id)
+                .await?
+                .into_iter()
+                .filter(|d| !disc_titles.iter().any(|(n, _)| *n == d.disc_number))
+                .map(|d| (d.disc_number, d.title))
+                .collect();
+            titles.extend(disc_titles);
+            self.set_release_disc_titles(release_id, &titles).await?;
+        }
+
+        Ok(())
+    }
+    /// Record which releases this device holds, so every device can show
+    /// where a release's files live.
+    pub async fn record_release_availability(
+        &self,
+        device_id: &str,
+        library_dir: &LibraryDir,
+        cache: &CacheManager,
+    ) -> Result<(), LibraryError> {
+        let mut records = Vec::new();
+        for release in self.database.get_all_releases().await? {
+            if let Some(record) = self
+                .check_release_availability(&release, device_id, library_dir, cache)
+                .await?
+            {
+                records.push(record);
+            }
+        }
+
+        self.database
+            .set_release_availability(device_id, &records)
+            .await?;
+        Ok(())
+    }
+    /// Get the devices that hold a release's files. Other devices are listed
+    /// as they last reported; this device is checked now.
+    pub async fn get_release_availability(
+        &self,
+        release_id: &str,
+        device_id: &str,
+        library_dir: &LibraryDir,
+        cache: &CacheManager,
+    ) -> Result<Vec<DbReleaseAvailability>, LibraryError> {
+        let release = self
+            .database
+            .get_release_by_id(release_id)
+            .await?
+            .ok_or_else(|| LibraryError::TrackMapping("Release not found".to_string()))?;
+        let mut records: Vec<DbReleaseAvailability> = self
+            .check_release_availability(&release, device_id, library_dir, cache)
+            .await?
+            .into_iter()
+            .collect();
+        records.extend(
+            self.database
+                .get_release_availability(release_id)
+                .await?
+                .into_iter()
+                .filter(|r| r.device_id != device_id),
+        );
+        Ok(records)
+    }
+    /// What this device holds of a release, or None if it holds nothing.
+    ///
+    /// A release counts as held when all of its files are present, either in
+    /// this library's storage or at the original folder of an unmanaged
+    /// import. Files cached from the cloud are counted separately.
+    async fn check_release_availability(
+        &self,
+        release: &DbRelease,
+        device_id: &str,
+        library_dir: &LibraryDir,
+        cache: &CacheManager,
+    ) -> Result<Option<DbReleaseAvailability>, LibraryError> {
+        let files = self.database.get_files_for_release(&release.id).await?;
+        if files.is_empty() {
+            return Ok(None);
+        }
+        let has_files = if release.managed_locally {
+            files
+                .iter()
+                .all(|f| library_dir.storage_file_path(&f.id).exists())
+        } else if let Some(ref folder) = release.unmanaged_path {
+            files
+                .iter()
+                .all(|f| Path::new(folder).join(&f.original_filename).exists())
+        } else {
+            false
+        };
+        let mut cached_files = 0;
+        for file in &files {
+            if cache.contains(&file_cache_key(&file.id)).await {
+                cached_files += 1;
+            }
+        }
+
+        Ok(
+            (has_files || cached_files > 0).then(|| DbReleaseAvailability {
+                release_id: release.id.clone(),
+                device_id: device_id.to_string(),
+                has_files,
+                cached_files,
+            }),
+        )
+    }
+    /// Look up the works and composers of a release's tracks on MusicBrainz
+    /// and store them, along with the disc titles the same lookup returns.
+    /// Returns how many tracks perform a known work.
+    pub async fn fetch_release_works(&self, release_id: &str) -> Result<usize, LibraryError> {
+        let album_id = self.get_album_id_for_release(release_id).await?;
+        let album = self
+            .database
+            .get_album_by_id(&album_id)
+            .await?
+            .ok_or_else(|| LibraryError::Tags("Album not found".to_string()))?;
+        let Some(mb_release) = album.musicbrainz_release.as_ref() else {
+            return Err(LibraryError::MusicBrainz(
+                "This album isn't linked to a MusicBrainz release".to_string(),
+            ));
+        };
+
+        let response = crate::musicbrainz::lookup_release_works(&mb_release.release_id)
+            .await
+            .map_err(|e| LibraryError::MusicBrainz(e.to_string()))?;
+        self.set_release_disc_titles(release_id, &response.disc_titles())
+            .await?;
+        let mut credits = parse_release_works(&response);
+
+        // Composers only credited on the whole work need one more lookup each
+        for work_id in works_missing_composers(&credits) {
+            let composers = match crate::musicbrainz::lookup_work(&work_id).await {
+                Ok(work) => work_composers(&work),
+                Err(e) => {
+                    warn!("Failed to look up composers of work {}: {}", work_id, e);
+                    continue;
+                }
+            };
+            for credit in credits.iter_mut().filter(|c| c.work_id == work_id) {
+                credit.composers = composers.clone();
+            }
+        }
+
+        self.save_release_works(release_id, &credits).await
+    }
+    /// Store works and composer credits for a release's tracks, replacing
+    /// earlier ones. Tracks are matched by disc and track number; returns how
+    /// many matched.
+    pub async fn save_release_works(
+        &self,
+        release_id: &str,
+        credits: &[TrackWorkCredit],
+    ) -> Result<usize, LibraryError> {
+        let tracks = self.database.get_tracks_for_release(release_id).await?;
+        let mut works = Vec::new();
+        let mut composers = Vec::new();
+        let mut composer_ids: HashMap<String, String> = HashMap::new();
+        for credit in credits {
+            let Some(track) = tracks.iter().find(|t| {
+                t.disc_number.unwrap_or(1) == credit.disc_number
+                    && t.track_number == Some(credit.track_number)
+            }) else {
+                continue;
+            };
+            works.push(DbTrackWork {
+                track_id: track.id.clone(),
+                work_title: credit.work_title.clone(),
+                musicbrainz_work_id: Some(credit.work_id.clone()),
+                movement_title: credit.movement_title.clone(),
+                movement_number: credit.movement_number,
+            });
+            for composer in &credit.composers {
+                let artist_id = match composer_ids.get(&composer.name) {
+                    Some(id) => id.clone(),
+                    None => {
+                        let id = self.find_or_create_composer(composer).await?;
+                        composer_ids.insert(composer.name.clone(), id.clone());
+                        id
+                    }
+                };
+                composers.push((track.id.clone(), artist_id));
+            }
+        }
+
+        self.database
+            .set_release_works(release_id, &works, &composers)
+            .await?;
+
+        self.notify_albums_changed();
+
+        Ok(works.len())
+    }
+    /// Existing artist for a composer, by MusicBrainz ID then name, or a new one
+    async fn find_or_create_composer(
+        &self,
+        composer: &ComposerCredit,
+    ) -> Result<String, LibraryError> {
+        if let Some(ref mb_id) = composer.musicbrainz_artist_id {
+            if let Some(artist) = self.database.get_artist_by_mb_id(mb_id).await? {
+                return Ok(artist.id);
+            }
+        }
+        // A name match with a different MusicBrainz ID is a namesake
+        if let Some(artist) = self.database.get_artist_by_name(&composer.name).await? {
+            match (
+                &artist.musicbrainz_artist_id,
+                &composer.musicbrainz_artist_id,
+            ) {
+                (Some(_), Some(_)) => {}
+                (None, Some(mb_id)) => {
+                    self.database
+                        .update_artist_external_ids(
+                            &artist.id,
+                            None,
+                            Some(mb_id),
+                            composer.sort_name.as_deref(),
+                        )
+                        .await?;
+                    return Ok(artist.id);
+                }
+                (_, None) => return Ok(artist.id),
+            }
+        }
+
+        let now = chrono::Utc::now();
+        let artist = DbArtist {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: composer.name.clone(),
+            sort_name: composer.sort_name.clone(),
+            discogs_artist_id: None,
+            bandcamp_artist_id: None,
+            musicbrainz_artist_id: composer.musicbrainz_artist_id.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.database.insert_artist(&artist).await?;
+        Ok(artist.id)
+    }
+    /// Store a release's disc titles as (disc number, title), replacing
+    /// earlier ones
+    pub async fn set_release_disc_titles(
+        &self,
+        release_id: &str,
+        titles: &[(i32, String)],
+    ) -> Result<(), LibraryError> {
+        let discs: Vec<DbReleaseDisc> = titles
+            .iter()
+            .map(|(disc_number, title)| DbReleaseDisc {
+                release_id: release_id.to_string(),
+                disc_number: *disc_number,
+                title: title.clone(),
+            })
+            .collect();
+        self.database.set_release_discs(release_id, &discs).await?;
+
+        self.notify_albums_changed();
+
+        Ok(())
+    }
+    /// Titled discs of a release, by disc number
+    pub async fn get_release_discs(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<DbReleaseDisc>, LibraryError> {
+        Ok(self.database.get_release_discs(release_id).await?)
+    }
+    /// Works performed by a release's tracks
+    pub async fn get_track_works_for_release(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<DbTrackWork>, LibraryError> {
+        Ok(self
+            .database
+            .get_track_works_for_release(release_id)
+            .await?)
+    }
+    /// Composers credited on a release's tracks, as (track ID, artist) pairs
+    pub async fn get_composers_for_release(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<(String, DbArtist)>, LibraryError> {
+        Ok(self.database.get_composers_for_release(release_id).await?)
+    }
+    /// Every album with composer credits, as (album ID, composer) pairs
+    pub async fn get_album_composers(&self) -> Result<Vec<(String, DbArtist)>, LibraryError> {
+        Ok(self.database.get_album_composers().await?)
+    }
+    /// Get a track's lyrics, looking them up on LRCLIB the first time.
+    /// Misses are stored too, so a track without lyrics is only looked up once.
+    pub async fn fetch_lyrics(&self, track_id: &str) -> Result<Lyrics, LibraryError> {
+        if let Some(lyrics) = self.get_lyrics(track_id).await? {
+            return Ok(lyrics);
+        }
+
+        let track = self
+            .database
+            .get_track_by_id(track_id)
+            .await?
+            .ok_or_else(|| LibraryError::TrackMapping("Track not found".to_string()))?;
+        let album_id = self.get_album_id_for_release(&track.release_id).await?;
+        let mut artists = self.database.get_artists_for_track(track_id).await?;
+        if artists.is_empty() {
+            artists = self.database.get_artists_for_album(&album_id).await?;
+        }
+        let Some(artist) = artists.into_iter().next() else {
+            return Ok(Lyrics::default());
+        };
+        let album = self.database.get_album_by_id(&album_id).await?;
+
+        let query = LyricsQuery {
+            artist: artist.name,
+            title: track.title,
+            album: album.map(|a| a.title),
+            duration_ms: track.duration_ms,
+        };
+        let lyrics = crate::lyrics::fetch_lyrics(&query)
+            .await
+            .map_err(|e| LibraryError::Lyrics(e.to_string()))?;
+        self.save_lyrics(track_id, &lyrics, "lrclib").await?;
+        Ok(lyrics)
+    }
+
+    /// Get release ID for a track
+    pub async fn get_release_id_for_track(&self, track_id: &str) -> Result<String, LibraryError> {
+        let track = self
+            .database
+            .get_track_by_id(track_id)
+            .await?
+            .ok_or_else(|| LibraryError::TrackMapping("Track not found".to_string()))?;
+        Ok(track.release_id)
+    }
+    /// Get album ID for a track
+    pub async fn get_album_id_for_track(&self, track_id: &str) -> Result<String, LibraryError> {
+        let track = self
+            .database
+            .get_track_by_id(track_id)
+            .await?
+            .ok_or_else(|| LibraryError::TrackMapping("Track not found".to_string()))?;
+        let album_id = self
+            .database
+            .get_album_id_for_release(&track.release_id)
+            .await?
+            .ok_or_else(|| LibraryError::TrackMapping("Release not found".to_string()))?;
+        Ok(album_id)
+    }
+    /// Get album ID for a release
+    pub async fn get_album_id_for_release(&self, release_id: &str) -> Result<String, LibraryError> {
+        let album_id = self
+            .database
+            .get_album_id_for_release(release_id)
+            .await?
+            .ok_or_else(|| LibraryError::TrackMapping("Release not found".to_string()))?;
+        Ok(album_id)
+    }
+    /// Insert an artist
+    pub async fn insert_artist(&self, artist: &DbArtist) -> Result<(), LibraryError> {
+        self.database.insert_artist(artist).await?;
+        Ok(())
+    }
+    /// Get artist by Discogs ID (for deduplication)
+    pub async fn get_artist_by_discogs_id(
+        &self,
+        discogs_artist_id: &str,
+    ) -> Result<Option<DbArtist>, LibraryError> {
+        Ok(self
+            .database
+            .get_artist_by_discogs_id(discogs_artist_id)
+            .await?)
+    }
+
+    /// Get artist by MusicBrainz ID (for deduplication)
+    pub async fn get_artist_by_mb_id(&self, mb_id: &str) -> Result<Option<DbArtist>, LibraryError> {
+        Ok(self.database.get_artist_by_mb_id(mb_id).await?)
+    }
+
+    /// Get artist by name (case-insensitive, first match)
+    pub async fn get_artist_by_name(&self, name: &str) -> Result<Option<DbArtist>, LibraryError> {
+        Ok(self.database.get_artist_by_name(name).await?)
+    }
+
+    /// Fill in NULL external IDs on an existing artist (never overwrites)
+    pub async fn update_artist_external_ids(
+        &self,
+        id: &str,
+        discogs_id: Option<&str>,
+        mb_id: Option<&str>,
+        sort_name: Option<&str>,
+    ) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .update_artist_external_ids(id, discogs_id, mb_id, sort_name)
+            .await?)
+    }
+
+    /// Insert album-artist relationship
+    pub async fn insert_album_artist(
+        &self,
+        album_artist: &DbAlbumArtist,
+    ) -> Result<(), LibraryError> {
+        self.database.insert_album_artist(album_artist).await?;
+        Ok(())
+    }
+    /// Insert track-artist relationship
+    pub async fn insert_track_artist(
+        &self,
+        track_artist: &DbTrackArtist,
+    ) -> Result<(), LibraryError> {
+        self.database.insert_track_artist(track_artist).await?;
+        Ok(())
+    }
+    /// Get artists for an album
+    pub async fn get_artists_for_album(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<DbArtist>, LibraryError> {
+        Ok(self.database.get_artists_for_album(album_id).await?)
+    }
+    /// An album's artist credit, each artist with its join phrase
+    pub async fn get_album_artist_credits(
+        &self,
+        album_id: &str,
+    ) -> Result<Vec<(DbArtist, Option<String>)>, LibraryError> {
+        Ok(self.database.get_album_artist_credits(album_id).await?)
+    }
+    /// An album's artist credit as one line, e.g. "Glass Harbor & Tidewater"
+    pub async fn get_album_artist_name(&self, album_id: &str) -> Result<String, LibraryError> {
+        Ok(self
+            .database
+            .get_album_artist_credits(album_id)
+            .await?
+            .into_iter()
+            .map(|(artist, join_phrase)| artist.name + join_phrase.as_deref().unwrap_or(""))
+            .collect())
+    }
+    /// Performing artist credits on a release's tracks, as (track ID,
+    /// artist, join phrase)
+    pub async fn get_track_artist_credits_for_release(
+        &self,
+        release_id: &str,
+    ) -> Result<Vec<(String, DbArtist, Option<String>)>, LibraryError> {
+        Ok(self
+            .database
+            .get_track_artist_credits_for_release(release_id)
+            .await?)
+    }
+    /// Get artists for a track
+    pub async fn get_artists_for_track(
+        &self,
+        track_id: &str,
+    ) -> Result<Vec<DbArtist>, LibraryError> {
+        Ok(self.database.get_artists_for_track(track_id).await?)
+    }
+    /// Get artist by ID
+    pub async fn get_artist_by_id(
+        &self,
+        artist_id: &str,
+    ) -> Result<Option<DbArtist>, LibraryError> {
+        Ok(self.database.get_artist_by_id(artist_id).await?)
+    }
+    /// Search across artists, albums, and tracks
+    pub async fn search_library(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<LibrarySearchResults, LibraryError> {
+        Ok(self.database.search_library(query, limit).await?)
+    }
+    /// Search across artists, albums, and tracks, best matches first, one
+    /// page of each
+    pub async fn search_library_page(
+        &self,
+        query: &str,
+        page: &SearchPage,
+    ) -> Result<LibrarySearchResults, LibraryError> {
+        Ok(self.database.search_library_page(query, page).await?)
+    }
+
+    /// Get all artists that have at least one album
+    pub async fn get_artists_with_albums(&self) -> Result<Vec<DbArtist>, LibraryError> {
+        Ok(self.database.get_artists_with_albums().await?)
+    }
+
+    /// Artists still waiting for an artist image, skipping those looked up
+    /// since `checked_since` (unix seconds)
+    pub async fn get_artists_missing_images(
+        &self,
+        checked_since: i64,
+    ) -> Result<Vec<DbArtist>, LibraryError> {
+        Ok(self
+            .database
+            .get_artists_missing_images(checked_since)
+            .await?)
+    }
+
+    /// Record that an artist was just looked up on the artist image sources
+    pub async fn record_artist_image_lookup(&self, artist_id: &str) -> Result<(), LibraryError> {
+        Ok(self.database.record_artist_image_lookup(artist_id).await?)
+    }
+
+    /// Releases whose cover may be worth upgrading, see
+    /// `Database::get_cover_upgrade_candidates`
+    pub async fn get_cover_upgrade_candidates(
+        &self,
+        min_size: i64,
+        checked_since: i64,
+    ) -> Result<Vec<String>, LibraryError> {
+        Ok(self
+            .database
+            .get_cover_upgrade_candidates(min_size, checked_since)
+            .await?)
+    }
+
+    /// Record that a release's cover was just looked up for an upgrade
+    pub async fn record_cover_upgrade_lookup(&self, release_id: &str) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .record_cover_upgrade_lookup(release_id)
+            .await?)
+    }
+
+    /// Releases due for the storage scrub, see
+    /// `Database::get_storage_scrub_candidates`
+    pub async fn get_storage_scrub_candidates(
+        &self,
+        verified_since: i64,
+    ) -> Result<Vec<String>, LibraryError> {
+        Ok(self
+            .database
+            .get_storage_scrub_candidates(verified_since)
+            .await?)
+    }
+
+    /// Record that the storage scrub just read back a release's files
+    pub async fn record_storage_scrub(
+        &self,
+        release_id: &str,
+        broken_files: usize,
+    ) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .record_storage_scrub(release_id, broken_files as i64)
+            .await?)
+    }
+
+    /// When the storage scrub last verified a release, as a Unix timestamp
+    pub async fn get_last_storage_scrub(&self) -> Result<Option<i64>, LibraryError> {
+        Ok(self.database.get_last_storage_scrub().await?)
+    }
+
+    /// Releases due to move to the cloud home, see
+    /// `Database::get_offload_candidates`
+    pub async fn get_offload_candidates(
+        &self,
+        idle_since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<String>, LibraryError> {
+        Ok(self
+            .database
+            .get_offload_candidates(&idle_since.to_rfc3339())
+            .await?)
+    }
+
+    /// This device's cloud home traffic in `month` (`YYYY-MM`)
+    pub async fn get_cloud_usage(&self, month: &str) -> Result<DbCloudUsage, LibraryError> {
+        Ok(self.database.get_cloud_usage(month).await?)
+    }
+
+    /// Bytes kept on this device and in the cloud home, see
+    /// `Database::get_stored_bytes`
+    pub async fn get_stored_bytes(&self) -> Result<(u64, u64), LibraryError> {
+        let (local, cloud) = self.database.get_stored_bytes().await?;
+        Ok((local as u64, cloud as u64))
+    }
+
+    /// Keep a release downloaded on this device
+    pub async fn add_offline_pin(&self, release_id: &str) -> Result<(), LibraryError> {
+        Ok(self.database.add_offline_pin(release_id).await?)
+    }
+
+    /// Stop keeping a release downloaded on this device
+    pub async fn remove_offline_pin(&self, release_id: &str) -> Result<(), LibraryError> {
+        Ok(self.database.remove_offline_pin(release_id).await?)
+    }
+
+    /// Releases this device keeps downloaded
+    pub async fn get_offline_pins(&self) -> Result<Vec<String>, LibraryError> {
+        Ok(self.database.get_offline_pins().await?)
+    }
+
+    /// Releases to add to and remove from a MusicBrainz collection to match
+    /// the library, given what this device has added before
+    pub async fn get_musicbrainz_collection_changes(
+        &self,
+        collection_id: &str,
+    ) -> Result<CollectionChanges, LibraryError> {
+        let library = self.database.get_imported_musicbrainz_release_ids().await?;
+        let added = self
+            .database
+            .get_musicbrainz_collection_releases(collection_id)
+            .await?;
+        Ok(collection_changes(&library, &added))
+    }
+
+    /// Record releases as added to a MusicBrainz collection
+    pub async fn record_musicbrainz_collection_added(
+        &self,
+        collection_id: &str,
+        release_ids: &[String],
+    ) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .add_musicbrainz_collection_releases(collection_id, release_ids)
+            .await?)
+    }
+
+    /// Record releases as removed from a MusicBrainz collection
+    pub async fn record_musicbrainz_collection_removed(
+        &self,
+        collection_id: &str,
+        release_ids: &[String],
+    ) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .remove_musicbrainz_collection_releases(collection_id, release_ids)
+            .await?)
+    }
+
+    /// Forget what was added to MusicBrainz collections, after unlinking
+    pub async fn clear_musicbrainz_collection(&self) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .clear_musicbrainz_collection_releases()
+            .await?)
+    }
+
+    /// Get albums for an artist
+    pub async fn get_albums_for_artist(
+        &self,
+        artist_id: &str,
+    ) -> Result<Vec<DbAlbum>, LibraryError> {
+        Ok(self.database.get_albums_for_artist(artist_id).await?)
+    }
+    /// IDs of the artist's albums they only appear on through track credits
+    pub async fn get_appearance_album_ids(
+        &self,
+        artist_id: &str,
+    ) -> Result<Vec<String>, LibraryError> {
+        Ok(self.database.get_appearance_album_ids(artist_id).await?)
+    }
+    /// Upsert a library image record
+    pub async fn upsert_library_image(&self, image: &DbLibraryImage) -> Result<(), LibraryError> {
+        self.database.upsert_library_image(image).await?;
+        Ok(())
+    }
+
+    /// Get a library image by ID and type
+    pub async fn get_library_image(
+        &self,
+        id: &str,
+        image_type: &LibraryImageType,
+    ) -> Result<Option<DbLibraryImage>, LibraryError> {
+        Ok(self.database.get_library_image(id, image_type).await?)
+    }
+
+    /// Get cover blurhashes keyed by album ID
+    pub async fn get_album_cover_blurhashes(
+        &self,
+    ) -> Result<std::collections::HashMap<String, String>, LibraryError> {
+        Ok(self.database.get_album_cover_blurhashes().await?)
+    }
+
+    /// Get a library image by ID (regardless of type)
+    pub async fn get_library_image_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<DbLibraryImage>, LibraryError> {
+        Ok(self.database.get_library_image_by_id(id).await?)
+    }
+
+    /// Delete a library image by ID and type
+    pub async fn delete_library_image(
+        &self,
+        id: &str,
+        image_type: &LibraryImageType,
+    ) -> Result<(), LibraryError> {
+        self.database.delete_library_image(id, image_type).await?;
+        Ok(())
+    }
+
+    /// Set an album's cover release (which release provides the cover art)
+    pub async fn set_album_cover_release(
+        &self,
+        album_id: &str,
+        cover_release_id: &str,
+    ) -> Result<(), LibraryError> {
+        self.database
+            .set_album_cover_release(album_id, cover_release_id)
+            .await?;
+
+        self.notify_albums_changed();
+
+        Ok(())
+    }
+
+    /// Queue all locally-managed files for a release into the pending deletions manifest.
+    ///
+    /// Only queues files for releases with `managed_locally = true`.
+    /// Unmanaged files and cloud-only files are left untouched.
+    async fn queue_release_files_for_deletion(&self, release_id: &str, library_dir: &LibraryDir) {
+        let release = match self.database.get_release_by_id(release_id).await {
+            Ok(Some(r)) => r,
+            _ => return,
+        };
+
+        if !release.managed_locally {
+            return;
+        }
+
+        let files = match self.get_files_for_release(release_id).await {
+            Ok(files) => files,
+            Err(e) => {
+                warn!("Failed to get files for release {}: {}", release_id, e);
+                return;
+            }
+        };
+
+        let pending: Vec<PendingDeletion> = files
+            .iter()
+            .map(|f| PendingDeletion::Local {
+                path: f.local_storage_path(library_dir).display().to_string(),
+            })
+            .collect();
+
+        if !pending.is_empty() {
+            if let Err(e) = append_pending_deletions(library_dir.as_ref(), &pending).await {
+                warn!("Failed to queue deferred deletions: {}", e);
+            }
+        }
+    }
+
+    /// Delete a release and its associated data
+    ///
+    /// This will:
+    /// 1. Queue files for deferred deletion via the pending deletions manifest
+    /// 2. Delete the release from database (cascades to tracks, files, etc.)
+    /// 3. If this was the last release for the album, also delete the album
+    ///
+    /// File cleanup happens asynchronously via the cleanup service, which retries
+    /// on failure. This prevents orphaned cloud objects when deletion fails.
+    pub async fn delete_release(
+        &self,
+        release_id: &str,
+        library_dir: &LibraryDir,
+    ) -> Result<(), LibraryError> {
+        let album_id = self.get_album_id_for_release(release_id).await?;
+
+        // Queue files for deferred deletion before removing DB records
+        self.queue_release_files_for_deletion(release_id, library_dir)
+            .await;
+
+        self.database.delete_release(release_id).await?;
+        let remaining_releases = self.get_releases_for_album(&album_id).await?;
+        if remaining_releases.is_empty() {
+            self.database.delete_album(&album_id).await?;
+        }
+
+        // Notify UI that library has changed
+        self.notify_albums_changed();
+
+        Ok(())
+    }
+
+    /// Delete an album and all its associated data
+    ///
+    /// This will:
+    /// 1. Get all releases for the album
+    /// 2. Queue files for deferred deletion via the pending deletions manifest
+    /// 3. Delete the album from database (cascades to releases and all related data)
+    ///
+    /// File cleanup happens asynchronously via the cleanup service, which retries
+    /// on failure. This prevents orphaned cloud objects when deletion fails.
+    pub async fn delete_album(
+        &self,
+        album_id: &str,
+        library_dir: &LibraryDir,
+    ) -> Result<(), LibraryError> {
+        let releases = self.get_releases_for_album(album_id).await?;
+        for release in &releases {
+            self.queue_release_files_for_deletion(&release.id, library_dir)
+                .await;
+        }
+
+        self.database.delete_album(album_id).await?;
+
+        // Notify UI that library has changed
+        self.notify_albums_changed();
+
+        Ok(())
+    }
+    /// Find releases that were imported more than once, by shared MusicBrainz
+    /// release group or identical audio files
+    pub async fn find_duplicate_releases(&self) -> Result<Vec<DuplicateReleases>, LibraryError> {
+        let release_groups = self.database.get_shared_release_group_releases().await?;
+        let version_groups = self.database.get_album_version_groups().await?;
+        let checksums = self.database.get_release_audio_checksums().await?;
+
+        let mut duplicates = Vec::new();
+        for group in find_duplicate_groups(&release_groups, &version_groups, &checksums) {
+            let mut releases = Vec::with_capacity(group.release_ids.len());
+            for release_id in &group.release_ids {
+                if let Some(release) = self.duplicate_release(release_id).await? {
+                    releases.push(release);
+                }
+            }
+            if releases.len() > 1 {
+                duplicates.push(DuplicateReleases {
+                    reason: group.reason,
+                    releases,
+                });
+            }
+        }
+        Ok(duplicates)
+    }
+    async fn duplicate_release(
+        &self,
+        release_id: &str,
+    ) -> Result<Option<DuplicateRelease>, LibraryError> {
+        let Some(release) = self.database.get_release_by_id(release_id).await? else {
+            return Ok(None);
+        };
+        let Some(album) = self.database.get_album_by_id(&release.album_id).await? else {
+            return Ok(None);
+        };
+        let artist_name = self.get_album_artist_name(&album.id).await?;
+        let track_count = self
+            .database
+            .get_tracks_for_release(release_id)
+            .await?
+            .len();
+        let play_count = self.database.get_release_play_count(release_id).await?;
+        Ok(Some(DuplicateRelease {
+            release_id: release.id,
+            album_id: album.id,
+            album_title: album.title,
+            artist_name,
+            release_name: release.release_name,
+            year: release.year.or(album.year),
+            format: release.format,
+            track_count,
+            play_count,
+            managed_locally: release.managed_locally,
+            managed_in_cloud: release.managed_in_cloud,
+            unmanaged: release.unmanaged_path.is_some(),
+        }))
+    }
+    /// Keep one release of a duplicate group and delete the others.
+    ///
+    /// Each deleted track's plays, playlist entries and stats move to the
+    /// kept release's matching track first, so no listening history is lost.
+    pub async fn merge_duplicate_releases(
+        &self,
+        keep_release_id: &str,
+        duplicate_release_ids: &[String],
+        library_dir: &LibraryDir,
+    ) -> Result<(), LibraryError> {
+        if duplicate_release_ids.iter().any(|id| id == keep_release_id) {
+            return Err(LibraryError::Duplicates(
+                "The kept release can't also be deleted".to_string(),
+            ));
+        }
+        let kept_tracks = self
+            .database
+            .get_tracks_for_release(keep_release_id)
+            .await?;
+        if kept_tracks.is_empty() {
+            return Err(LibraryError::Duplicates(
+                "The kept release has no tracks".to_string(),
+            ));
+        }
+
+        for release_id in duplicate_release_ids {
+            let tracks = self.database.get_tracks_for_release(release_id).await?;
+            for (from_track_id, to_track_id) in match_duplicate_tracks(&tracks, &kept_tracks) {
+                self.database
+                    .move_track_listening(&from_track_id, &to_track_id)
+                    .await?;
+            }
+            self.delete_release(release_id, library_dir).await?;
+
+            info!(
+                "Merged duplicate release {} into {}",
+                release_id, keep_release_id
+            );
+        }
+        Ok(())
+    }
+    /// Conflicts found while applying other devices' changes, newest first
+    pub async fn get_sync_conflicts(&self) -> Result<Vec<DbSyncConflict>, LibraryError> {
+        Ok(self.database.get_sync_conflicts().await?)
+    }
+    /// Settle a sync conflict with the chosen value of each conflicted column.
+    ///
+    /// The values are written as a new local edit, so the resolution syncs
+    /// to the other devices like any other change.
+    pub async fn resolve_sync_conflict(
+        &self,
+        conflict_id: &str,
+        values: &[(String, Option<String>)],
+    ) -> Result<(), LibraryError> {
+        let conflict = self
+            .database
+            .get_sync_conflict(conflict_id)
+            .await?
+            .ok_or_else(|| LibraryError::SyncConflict(format!("No conflict {}", conflict_id)))?;
+        if let Some((column, _)) = values
+            .iter()
+            .find(|(column, _)| !conflict.fields.iter().any(|f| &f.column == column))
+        {
+            return Err(LibraryError::SyncConflict(format!(
+                "{} is not in conflict",
+                column
+            )));
+        }
+        self.database
+            .resolve_sync_conflict(&conflict, values)
+            .await?;
+
+        info!(
+            "Resolved sync conflict on {} {}",
+            conflict.table_name, conflict.row_id
+        );
+
+        self.notify_albums_changed();
+        Ok(())
+    }
+    /// Export all files for a release to a directory
+    ///
+    /// Copies files from storage to the target directory.
+    /// Files are written with their original filenames.
+    pub async fn export_release(
+        &self,
+        release_id: &str,
+        target_dir: &Path,
+        cache: &CacheManager,
+        library_dir: &LibraryDir,
+    ) -> Result<(), LibraryError> {
+        ExportService::export_release(
+            release_id,
+            target_dir,
+            self,
+            cache,
+            self.encryption_service.as_ref(),
+            library_dir,
+        )
+        .await
+        .map_err(LibraryError::Import)
+    }
+    /// Export a single track as a FLAC file
+    ///
+    /// For one-file-per-track: extracts the original file.
+    /// For CUE/FLAC: extracts and re-encodes as a standalone FLAC.
+    pub async fn export_track(
+        &self,
+        track_id: &str,
+        output_path: &Path,
+        cache: &CacheManager,
+        library_dir: &LibraryDir,
+    ) -> Result<(), LibraryError> {
+        ExportService::export_track(
+            track_id,
+            output_path,
+            self,
+            cache,
+            self.encryption_service.as_ref(),
+            library_dir,
+        )
+        .await
+        .map_err(LibraryError::Import)
+    }
+    /// Check if an album already exists by Discogs IDs
+    ///
+    /// Used for duplicate detection before import.
+    /// Returns the existing album if found, None otherwise.
+    pub async fn find_duplicate_by_discogs(
+        &self,
+        master_id: Option<&str>,
+        release_id: Option<&str>,
+    ) -> Result<Option<DbAlbum>, LibraryError> {
+        Ok(self
+            .database
+            .find_album_by_discogs_ids(master_id, release_id)
+            .await?)
+    }
+    /// Check if an album already exists by MusicBrainz IDs
+    ///
+    /// Used for duplicate detection before import.
+    /// Returns the existing album if found, None otherwise.
+    pub async fn find_duplicate_by_musicbrainz(
+        &self,
+        release_id: Option<&str>,
+        release_group_id: Option<&str>,
+    ) -> Result<Option<DbAlbum>, LibraryError> {
+        Ok(self
+            .database
+            .find_album_by_mb_ids(release_id, release_group_id)
+            .await?)
+    }
+    /// Insert a new import operation record
+    pub async fn insert_import(&self, import: &DbImport) -> Result<(), LibraryError> {
+        Ok(self.database.insert_import(import).await?)
+    }
+    /// Update the status of an import operation
+    pub async fn update_import_status(
+        &self,
+        id: &str,
+        status: ImportOperationStatus,
+    ) -> Result<(), LibraryError> {
+        Ok(self.database.update_import_status(id, status).await?)
+    }
+    /// Link an import operation to a release (after release is created)
+    pub async fn link_import_to_release(
+        &self,
+        import_id: &str,
+        release_id: &str,
+    ) -> Result<(), LibraryError> {
+        Ok(self
+            .database
+            .link_import_to_release(import_id, release_id)
+            .await?)
+    }
+    /// Record an error for an import operation
+    pub async fn update_import_error(&self, id: &str, error: &str) -> Result<(), LibraryError> {
+        Ok(self.database.update_import_error(id, error).await?)
+    }
+    /// Get all active (non-complete, non-failed) imports
+    pub async fn get_active_imports(&self) -> Result<Vec<DbImport>, LibraryError> {
+        Ok(self.database.get_active_imports().await?)
+    }
+
+    /// Delete an import record (used by UI to dismiss stuck imports)
+    pub async fn delete_import(&self, id: &str) -> Result<(), LibraryError> {
+        Ok(self.database.delete_import(id).await?)
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::{DbAlbum, DbRelease, ImportStatus};
+    use crate::content_type::ContentType;
+    use crate::db::{DbRelease, ImportStatus};
+    use crate::test_support::{create_test_album, create_test_release, test_database};
     use chrono::Utc;
     use tempfile::TempDir;
     use uuid::Uuid;
 
-    async fn setup_test_manager() -> (LibraryManager, TempDir, LibraryDir) {
-        let temp_dir = TempDir::new().unwrap();
-        let db_path = temp_dir.path().join("test.db");
-        let database = Database::new(db_path.to_str().unwrap()).await.unwrap();
-        let encryption_service = EncryptionService::new_with_key(&[0u8; 32]);
-        let manager = LibraryManager::new(database, Some(encryption_service));
-        let library_dir = LibraryDir::new(temp_dir.path().to_path_buf());
-        (manager, temp_dir, library_dir)
+    async fn setup_test_manager() -> (LibraryManager, TempDir, LibraryDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let database = test_database(temp_dir.path()).await;
+        let encryption_service = EncryptionService::new_with_key(&[0u8; 32]);
+        let manager = LibraryManager::new(database, Some(encryption_service));
+        let library_dir = LibraryDir::new(temp_dir.path().to_path_buf());
+        (manager, temp_dir, library_dir)
+    }
+
+    #[tokio::test]
+    async fn test_delete_release_with_single_release_deletes_album() {
+        let (manager, _temp_dir, library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+
+        manager
+            .delete_release(&release.id, &library_dir)
+            .await
+            .unwrap();
+
+        let album_result = manager.database.get_album_by_id(&album.id).await.unwrap();
+        assert!(album_result.is_none());
+        let releases = manager
+            .database
+            .get_releases_for_album(&album.id)
+            .await
+            .unwrap();
+        assert!(releases.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_release_with_multiple_releases_preserves_album() {
+        let (manager, _temp_dir, library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release1 = create_test_release(&album.id);
+        let release2 = create_test_release(&album.id);
+
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release1).await.unwrap();
+        manager.database.insert_release(&release2).await.unwrap();
+
+        manager
+            .delete_release(&release1.id, &library_dir)
+            .await
+            .unwrap();
+
+        let album_result = manager.database.get_album_by_id(&album.id).await.unwrap();
+        assert!(album_result.is_some());
+        let releases = manager
+            .database
+            .get_releases_for_album(&album.id)
+            .await
+            .unwrap();
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].id, release2.id);
+    }
+
+    #[tokio::test]
+    async fn test_delete_album_deletes_all_releases() {
+        let (manager, _temp_dir, library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release1 = create_test_release(&album.id);
+        let release2 = create_test_release(&album.id);
+
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release1).await.unwrap();
+        manager.database.insert_release(&release2).await.unwrap();
+
+        manager.delete_album(&album.id, &library_dir).await.unwrap();
+
+        let album_result = manager.database.get_album_by_id(&album.id).await.unwrap();
+        assert!(album_result.is_none());
+        let releases = manager
+            .database
+            .get_releases_for_album(&album.id)
+            .await
+            .unwrap();
+        assert!(releases.is_empty());
+    }
+
+    /// Insert a release whose tracks each have one file, in track order.
+    /// Returns the track IDs and each track's audio format ID.
+    async fn insert_release_with_audio(
+        manager: &LibraryManager,
+        release: &DbRelease,
+        titles: &[&str],
+    ) -> (Vec<String>, Vec<String>) {
+        let mut track_ids = Vec::new();
+        let mut audio_ids = Vec::new();
+        for (i, title) in titles.iter().enumerate() {
+            let track = DbTrack {
+                id: Uuid::new_v4().to_string(),
+                release_id: release.id.clone(),
+                title: title.to_string(),
+                disc_number: Some(1),
+                track_number: Some(i as i32 + 1),
+                duration_ms: Some((i as i64 + 1) * 1000),
+                discogs_position: None,
+                import_status: ImportStatus::Complete,
+                updated_at: Utc::now(),
+                created_at: Utc::now(),
+            };
+            manager.database.insert_track(&track).await.unwrap();
+
+            let file = DbFile::new(
+                &release.id,
+                &format!("{title}.flac"),
+                100,
+                ContentType::Flac,
+            );
+            manager.database.insert_file(&file).await.unwrap();
+            let audio = DbAudioFormat::new(
+                &track.id,
+                ContentType::Flac,
+                None,
+                false,
+                44100,
+                16,
+                "[]".to_string(),
+                0,
+            )
+            .with_file_id(&file.id);
+            manager.database.insert_audio_format(&audio).await.unwrap();
+
+            track_ids.push(track.id);
+            audio_ids.push(audio.id);
+        }
+        (track_ids, audio_ids)
+    }
+
+    #[tokio::test]
+    async fn test_remap_swaps_audio_and_reorders_tracks() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, audio) =
+            insert_release_with_audio(&manager, &release, &["Tidewater", "Low Light"]).await;
+
+        // Files were mapped the wrong way round, and the tracks are in the wrong order
+        manager
+            .remap_release_tracks(
+                &release.id,
+                &[
+                    TrackRemap {
+                        track_id: tracks[1].clone(),
+                        track_number: Some(1),
+                        audio_format_id: Some(audio[0].clone()),
+                    },
+                    TrackRemap {
+                        track_id: tracks[0].clone(),
+                        track_number: Some(2),
+                        audio_format_id: Some(audio[1].clone()),
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+
+        let first = manager.get_track(&tracks[1]).await.unwrap().unwrap();
+        assert_eq!(first.track_number, Some(1));
+        assert_eq!(first.duration_ms, Some(1000));
+        let format = manager
+            .get_audio_format_by_track_id(&tracks[1])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(format.id, audio[0]);
+
+        let second = manager.get_track(&tracks[0]).await.unwrap().unwrap();
+        assert_eq!(second.track_number, Some(2));
+        assert_eq!(second.duration_ms, Some(2000));
+        let format = manager
+            .get_audio_format_by_track_id(&tracks[0])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(format.id, audio[1]);
+    }
+
+    #[tokio::test]
+    async fn test_remap_rejects_orphaned_or_duplicated_audio() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, audio) =
+            insert_release_with_audio(&manager, &release, &["Tidewater", "Low Light"]).await;
+
+        let duplicated = [
+            TrackRemap {
+                track_id: tracks[0].clone(),
+                track_number: Some(1),
+                audio_format_id: Some(audio[0].clone()),
+            },
+            TrackRemap {
+                track_id: tracks[1].clone(),
+                track_number: Some(2),
+                audio_format_id: Some(audio[0].clone()),
+            },
+        ];
+        let result = manager.remap_release_tracks(&release.id, &duplicated).await;
+        assert!(matches!(result, Err(LibraryError::TrackMapping(_))));
+
+        let orphaned = [
+            TrackRemap {
+                track_id: tracks[0].clone(),
+                track_number: Some(1),
+                audio_format_id: Some(audio[0].clone()),
+            },
+            TrackRemap {
+                track_id: tracks[1].clone(),
+                track_number: Some(2),
+                audio_format_id: None,
+            },
+        ];
+        let result = manager.remap_release_tracks(&release.id, &orphaned).await;
+        assert!(matches!(result, Err(LibraryError::TrackMapping(_))));
+
+        // Nothing changed
+        let format = manager
+            .get_audio_format_by_track_id(&tracks[1])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(format.id, audio[1]);
+    }
+
+    #[tokio::test]
+    async fn test_update_album_tags_matches_artists_by_name() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        manager.database.insert_album(&album).await.unwrap();
+        let artist = DbArtist {
+            id: Uuid::new_v4().to_string(),
+            name: "Glass Harbor".to_string(),
+            sort_name: None,
+            discogs_artist_id: None,
+            bandcamp_artist_id: None,
+            musicbrainz_artist_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        manager.database.insert_artist(&artist).await.unwrap();
+
+        manager
+            .update_album_tags(
+                &album.id,
+                " Tidewater ",
+                Some(1998),
+                &["glass harbor".to_string(), "Low Light".to_string()],
+            )
+            .await
+            .unwrap();
+
+        let updated = manager.get_album_by_id(&album.id).await.unwrap().unwrap();
+        assert_eq!(updated.title, "Tidewater");
+        assert_eq!(updated.year, Some(1998));
+        let artists = manager.get_artists_for_album(&album.id).await.unwrap();
+        let names: Vec<&str> = artists.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["Glass Harbor", "Low Light"]);
+        assert_eq!(artists[0].id, artist.id);
+
+        let result = manager
+            .update_album_tags(&album.id, "Tidewater", None, &["  ".to_string()])
+            .await;
+        assert!(matches!(result, Err(LibraryError::Tags(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_track_tags_only_touches_the_release() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+        let other = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        manager.database.insert_release(&other).await.unwrap();
+        let (tracks, _) =
+            insert_release_with_audio(&manager, &release, &["Tidewater", "Low Lihgt"]).await;
+        let (other_tracks, _) =
+            insert_release_with_audio(&manager, &other, &["Pacific Standard"]).await;
+
+        manager
+            .update_track_tags(
+                &release.id,
+                &[TrackTagUpdate {
+                    track_id: tracks[1].clone(),
+                    title: "Low Light".to_string(),
+                    disc_number: Some(2),
+                    track_number: Some(1),
+                }],
+            )
+            .await
+            .unwrap();
+
+        let fixed = manager.get_track(&tracks[1]).await.unwrap().unwrap();
+        assert_eq!(fixed.title, "Low Light");
+        assert_eq!(fixed.disc_number, Some(2));
+        assert_eq!(fixed.track_number, Some(1));
+        let untouched = manager.get_track(&tracks[0]).await.unwrap().unwrap();
+        assert_eq!(untouched.title, "Tidewater");
+
+        let result = manager
+            .update_track_tags(
+                &release.id,
+                &[TrackTagUpdate {
+                    track_id: other_tracks[0].clone(),
+                    title: "Glass Harbor".to_string(),
+                    disc_number: None,
+                    track_number: Some(1),
+                }],
+            )
+            .await;
+        assert!(matches!(result, Err(LibraryError::Tags(_))));
+    }
+
+    #[tokio::test]
+    async fn test_apply_metadata_updates_keeps_unpicked_fields() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, _) =
+            insert_release_with_audio(&manager, &release, &["Tidewater", "Low Lihgt"]).await;
+        manager
+            .update_album_tags(
+                &album.id,
+                "Test Album",
+                Some(2024),
+                &["Glass Harbor".to_string()],
+            )
+            .await
+            .unwrap();
+
+        manager
+            .apply_metadata_updates(
+                &release.id,
+                &[
+                    MetadataUpdate::Year(Some(2009)),
+                    MetadataUpdate::TrackTitle {
+                        track_id: tracks[1].clone(),
+                        disc_number: Some(1),
+                        track_number: Some(2),
+                        title: "Low Light".to_string(),
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+
+        let updated = manager.get_album_by_id(&album.id).await.unwrap().unwrap();
+        assert_eq!(updated.title, "Test Album");
+        assert_eq!(updated.year, Some(2009));
+        let artists = manager.get_artists_for_album(&album.id).await.unwrap();
+        assert_eq!(artists.len(), 1);
+        assert_eq!(artists[0].name, "Glass Harbor");
+        let fixed = manager.get_track(&tracks[1]).await.unwrap().unwrap();
+        assert_eq!(fixed.title, "Low Light");
+        assert_eq!(fixed.track_number, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_apply_disc_title_keeps_other_discs() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        manager
+            .set_release_disc_titles(
+                &release.id,
+                &[(1, "Studio".to_string()), (2, "Live".to_string())],
+            )
+            .await
+            .unwrap();
+
+        manager
+            .apply_metadata_updates(
+                &release.id,
+                &[MetadataUpdate::DiscTitle {
+                    disc_number: 2,
+                    title: "Live at Tidewater".to_string(),
+                }],
+            )
+            .await
+            .unwrap();
+
+        let discs = manager.get_release_discs(&release.id).await.unwrap();
+        let titles: Vec<(i32, &str)> = discs
+            .iter()
+            .map(|d| (d.disc_number, d.title.as_str()))
+            .collect();
+        assert_eq!(titles, vec![(1, "Studio"), (2, "Live at Tidewater")]);
+    }
+
+    #[tokio::test]
+    async fn test_record_release_availability_tracks_local_files() {
+        let (manager, temp_dir, library_dir) = setup_test_manager().await;
+        let cache = CacheManager::with_config(crate::cache::CacheConfig {
+            cache_dir: temp_dir.path().join("cache"),
+            max_size_bytes: 1024 * 1024,
+            max_files: 100,
+        })
+        .await
+        .unwrap();
+        let album = create_test_album();
+        let mut release = create_test_release(&album.id);
+        release.managed_locally = true;
+        let cloud_release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        manager
+            .database
+            .insert_release(&cloud_release)
+            .await
+            .unwrap();
+        let file = DbFile::new(&release.id, "Tidewater.flac", 100, ContentType::Flac);
+        manager.database.insert_file(&file).await.unwrap();
+        let stored = library_dir.storage_file_path(&file.id);
+        std::fs::create_dir_all(stored.parent().unwrap()).unwrap();
+        std::fs::write(&stored, b"audio").unwrap();
+        let cloud_file = DbFile::new(&cloud_release.id, "Low Light.flac", 100, ContentType::Flac);
+        manager.database.insert_file(&cloud_file).await.unwrap();
+        cache
+            .put(&file_cache_key(&cloud_file.id), b"encrypted")
+            .await
+            .unwrap();
+
+        manager
+            .record_release_availability("device-a", &library_dir, &cache)
+            .await
+            .unwrap();
+
+        let local = manager
+            .database
+            .get_release_availability(&release.id)
+            .await
+            .unwrap();
+        assert_eq!(local.len(), 1);
+        assert!(local[0].has_files);
+        assert_eq!(local[0].cached_files, 0);
+        let cloud = manager
+            .database
+            .get_release_availability(&cloud_release.id)
+            .await
+            .unwrap();
+        assert!(!cloud[0].has_files);
+        assert_eq!(cloud[0].cached_files, 1);
+
+        // Once the files are gone the device stops claiming the release
+        std::fs::remove_file(&stored).unwrap();
+        manager
+            .record_release_availability("device-a", &library_dir, &cache)
+            .await
+            .unwrap();
+        assert!(manager
+            .database
+            .get_release_availability(&release.id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Other devices' reports are kept, and this device is checked live
+        manager
+            .database
+            .set_release_availability(
+                "device-b",
+                &[DbReleaseAvailability {
+                    release_id: release.id.clone(),
+                    device_id: "device-b".to_string(),
+                    has_files: true,
+                    cached_files: 0,
+                }],
+            )
+            .await
+            .unwrap();
+        let cloud_everywhere = manager
+            .get_release_availability(&cloud_release.id, "device-a", &library_dir, &cache)
+            .await
+            .unwrap();
+        assert_eq!(cloud_everywhere.len(), 1);
+        assert_eq!(cloud_everywhere[0].device_id, "device-a");
+        let everywhere = manager
+            .get_release_availability(&release.id, "device-a", &library_dir, &cache)
+            .await
+            .unwrap();
+        assert_eq!(everywhere.len(), 1);
+        assert_eq!(everywhere[0].device_id, "device-b");
+    }
+
+    #[tokio::test]
+    async fn test_musicbrainz_collection_changes_follow_library() {
+        let (manager, _temp_dir, library_dir) = setup_test_manager().await;
+        let mut imported = create_test_album();
+        imported.musicbrainz_release = Some(crate::db::MusicBrainzRelease {
+            release_group_id: "rg-1".to_string(),
+            release_id: "mb-1".to_string(),
+        });
+        let imported_release = create_test_release(&imported.id);
+        let mut importing = create_test_album();
+        importing.musicbrainz_release = Some(crate::db::MusicBrainzRelease {
+            release_group_id: "rg-2".to_string(),
+            release_id: "mb-2".to_string(),
+        });
+        let mut importing_release = create_test_release(&importing.id);
+        importing_release.import_status = ImportStatus::Importing;
+        for (album, release) in [
+            (&imported, &imported_release),
+            (&importing, &importing_release),
+        ] {
+            manager.database.insert_album(album).await.unwrap();
+            manager.database.insert_release(release).await.unwrap();
+        }
+
+        // Only fully imported releases are added
+        let changes = manager
+            .get_musicbrainz_collection_changes("collection-1")
+            .await
+            .unwrap();
+        assert_eq!(changes.to_add, vec!["mb-1".to_string()]);
+        assert!(changes.to_remove.is_empty());
+
+        manager
+            .record_musicbrainz_collection_added("collection-1", &changes.to_add)
+            .await
+            .unwrap();
+        assert!(manager
+            .get_musicbrainz_collection_changes("collection-1")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // A different collection starts from scratch
+        assert_eq!(
+            manager
+                .get_musicbrainz_collection_changes("collection-2")
+                .await
+                .unwrap()
+                .to_add,
+            vec!["mb-1".to_string()]
+        );
+
+        manager
+            .delete_release(&imported_release.id, &library_dir)
+            .await
+            .unwrap();
+        let changes = manager
+            .get_musicbrainz_collection_changes("collection-1")
+            .await
+            .unwrap();
+        assert!(changes.to_add.is_empty());
+        assert_eq!(changes.to_remove, vec!["mb-1".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_play_history_keeps_most_recent_plays() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, _) =
+            insert_release_with_audio(&manager, &release, &["Tidewater", "Low Light"]).await;
+
+        let start = Utc::now();
+        for (minutes, track_id) in [(0, &tracks[0]), (1, &tracks[1]), (2, &tracks[0])] {
+            manager
+                .database
+                .insert_play_history(track_id, start + chrono::Duration::minutes(minutes), 2)
+                .await
+                .unwrap();
+        }
+
+        let history = manager.get_play_history().await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].track_id, tracks[0]);
+        assert_eq!(history[1].track_id, tracks[1]);
+        assert!(history[0].played_at > history[1].played_at);
+    }
+
+    #[tokio::test]
+    async fn test_recent_albums_follow_added_and_played_order() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let start = Utc::now();
+        let mut tracks = Vec::new();
+        for (days, title) in [(0, "Glass Harbor"), (1, "Tidewater"), (2, "Low Light")] {
+            let mut album = create_test_album();
+            album.title = title.to_string();
+            album.created_at = start + chrono::Duration::days(days);
+            let release = create_test_release(&album.id);
+            manager.database.insert_album(&album).await.unwrap();
+            manager.database.insert_release(&release).await.unwrap();
+            let (track_ids, _) = insert_release_with_audio(&manager, &release, &[title]).await;
+            tracks.push((album.id, track_ids[0].clone()));
+        }
+
+        let added = manager.get_recently_added_album_ids().await.unwrap();
+        assert_eq!(
+            added,
+            vec![
+                tracks[2].0.clone(),
+                tracks[1].0.clone(),
+                tracks[0].0.clone()
+            ]
+        );
+
+        // Glass Harbor was played first but also most recently
+        for (minutes, index) in [(0, 0), (1, 1), (2, 0)] {
+            manager
+                .database
+                .insert_play_history(
+                    &tracks[index].1,
+                    start + chrono::Duration::minutes(minutes),
+                    PLAY_HISTORY_LEN,
+                )
+                .await
+                .unwrap();
+        }
+
+        let played = manager.get_recently_played_album_ids().await.unwrap();
+        assert_eq!(played, vec![tracks[0].0.clone(), tracks[1].0.clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_scrobbled_plays_count_without_rewinding_last_played() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, _) = insert_release_with_audio(&manager, &release, &["Tidewater"]).await;
+
+        let recent = Utc::now() - chrono::Duration::minutes(5);
+        manager.record_play_at(&tracks[0], recent).await.unwrap();
+        manager
+            .record_play_at(&tracks[0], recent - chrono::Duration::days(3))
+            .await
+            .unwrap();
+
+        let stats = manager
+            .database
+            .get_track_stats(&tracks[0])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.play_count, 2);
+        assert_eq!(
+            stats.last_played_at.map(|t| t.timestamp()),
+            Some(recent.timestamp())
+        );
+        assert_eq!(manager.get_play_history().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_album_filters_and_play_count_sort() {
+        use crate::db::{
+            AlbumFilter, AlbumSortCriterion, AlbumSortField, AlbumStorage, AudioQuality,
+            SortDirection,
+        };
+
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let mut ids = Vec::new();
+        for (title, year, plays) in [
+            ("Glass Harbor", 1994, 0),
+            ("Tidewater", 1997, 5),
+            ("Low Light", 2003, 2),
+        ] {
+            let mut album = create_test_album();
+            album.title = title.to_string();
+            album.year = Some(year);
+            let mut release = create_test_release(&album.id);
+            match title {
+                "Glass Harbor" => release.managed_locally = true,
+                "Tidewater" => release.managed_in_cloud = true,
+                _ => release.unmanaged_path = Some("/music/Low Light".to_string()),
+            }
+            manager.database.insert_album(&album).await.unwrap();
+            manager.database.insert_release(&release).await.unwrap();
+            let (track_ids, _) = insert_release_with_audio(&manager, &release, &[title]).await;
+            for _ in 0..plays {
+                manager.record_play(&track_ids[0]).await.unwrap();
+            }
+            ids.push((album.id, release.id));
+        }
+        manager
+            .set_album_genres(&ids[0].0, &["Shoegaze".to_string()])
+            .await
+            .unwrap();
+
+        // One MP3 makes Tidewater lossy
+        let mp3 = DbTrack {
+            id: Uuid::new_v4().to_string(),
+            release_id: ids[1].1.clone(),
+            title: "Pacific Standard".to_string(),
+            disc_number: Some(1),
+            track_number: Some(2),
+            duration_ms: Some(1000),
+            discogs_position: None,
+            import_status: ImportStatus::Complete,
+            updated_at: Utc::now(),
+            created_at: Utc::now(),
+        };
+        manager.database.insert_track(&mp3).await.unwrap();
+        let audio = DbAudioFormat::new(
+            &mp3.id,
+            ContentType::Mpeg,
+            None,
+            false,
+            44100,
+            16,
+            "[]".to_string(),
+            0,
+        );
+        manager.database.insert_audio_format(&audio).await.unwrap();
+
+        async fn titles(manager: &LibraryManager, filter: AlbumFilter) -> HashSet<String> {
+            let albums = manager.get_albums_filtered(&[], &filter).await.unwrap();
+            albums.into_iter().map(|a| a.title).collect()
+        }
+        let set = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+
+        let in_90s = AlbumFilter {
+            decade: Some(1990),
+            ..Default::default()
+        };
+        assert_eq!(
+            titles(&manager, in_90s).await,
+            set(&["Glass Harbor", "Tidewater"])
+        );
+        let shoegaze = AlbumFilter {
+            genre: Some("shoegaze".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(titles(&manager, shoegaze).await, set(&["Glass Harbor"]));
+        let in_cloud = AlbumFilter {
+            storage: Some(AlbumStorage::Cloud),
+            ..Default::default()
+        };
+        assert_eq!(titles(&manager, in_cloud).await, set(&["Tidewater"]));
+        let unmanaged = AlbumFilter {
+            storage: Some(AlbumStorage::Unmanaged),
+            ..Default::default()
+        };
+        assert_eq!(titles(&manager, unmanaged).await, set(&["Low Light"]));
+        let lossless = AlbumFilter {
+            quality: Some(AudioQuality::Lossless),
+            ..Default::default()
+        };
+        assert_eq!(
+            titles(&manager, lossless).await,
+            set(&["Glass Harbor", "Low Light"])
+        );
+        let lossy_90s = AlbumFilter {
+            decade: Some(1990),
+            quality: Some(AudioQuality::Lossy),
+            ..Default::default()
+        };
+        assert_eq!(titles(&manager, lossy_90s).await, set(&["Tidewater"]));
+
+        let most_played = manager
+            .get_albums(&[AlbumSortCriterion {
+                field: AlbumSortField::PlayCount,
+                direction: SortDirection::Descending,
+            }])
+            .await
+            .unwrap();
+        let order: Vec<_> = most_played.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(order, vec!["Tidewater", "Low Light", "Glass Harbor"]);
+
+        assert_eq!(manager.get_album_decades().await.unwrap(), vec![1990, 2000]);
+        let decades: Vec<_> = manager
+            .get_decades()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|d| (d.decade, d.album_count))
+            .collect();
+        assert_eq!(decades, vec![(1990, 2), (2000, 1)]);
+        assert_eq!(
+            manager.get_album_ids_for_years(1990, 1999).await.unwrap(),
+            vec![ids[0].0.clone(), ids[1].0.clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lyrics_are_stored_encrypted_and_read_back() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, _) = insert_release_with_audio(&manager, &release, &["Tidewater"]).await;
+        assert!(manager.get_lyrics(&tracks[0]).await.unwrap().is_none());
+
+        let lyrics = Lyrics {
+            plain: Some("Low light on the water".to_string()),
+            synced: Some("[00:12.30]Low light on the water".to_string()),
+            instrumental: false,
+        };
+        manager
+            .save_lyrics(&tracks[0], &lyrics, "lrclib")
+            .await
+            .unwrap();
+
+        let stored = manager
+            .database
+            .get_track_lyrics(&tracks[0])
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!stored
+            .content
+            .windows(b"Low light".len())
+            .any(|w| w == b"Low light"));
+        assert_eq!(manager.get_lyrics(&tracks[0]).await.unwrap(), Some(lyrics));
+
+        // A miss replaces the stored lyrics and reads back as empty
+        manager
+            .save_lyrics(&tracks[0], &Lyrics::default(), "lrclib")
+            .await
+            .unwrap();
+        let miss = manager.get_lyrics(&tracks[0]).await.unwrap().unwrap();
+        assert!(miss.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_artists_missing_images_skips_recent_lookups_and_imaged_artists() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        manager.database.insert_album(&album).await.unwrap();
+
+        let with_image = DbArtist::from_discogs_artist("101", "Glass Harbor");
+        let looked_up = DbArtist::from_discogs_artist("102", "Tidewater");
+        let waiting = DbArtist::from_discogs_artist("103", "Low Light");
+        let mut no_ids = DbArtist::from_discogs_artist("104", "Pacific Standard");
+        no_ids.discogs_artist_id = None;
+        for (position, artist) in [&with_image, &looked_up, &waiting, &no_ids]
+            .into_iter()
+            .enumerate()
+        {
+            manager.insert_artist(artist).await.unwrap();
+            manager
+                .insert_album_artist(&DbAlbumArtist::new(&album.id, &artist.id, position as i32))
+                .await
+                .unwrap();
+        }
+        manager
+            .upsert_library_image(&DbLibraryImage {
+                id: with_image.id.clone(),
+                image_type: LibraryImageType::Artist,
+                content_type: ContentType::Jpeg,
+                file_size: 1024,
+                width: None,
+                height: None,
+                source: "discogs".to_string(),
+                source_url: None,
+                blurhash: None,
+                updated_at: Utc::now(),
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+        manager
+            .record_artist_image_lookup(&looked_up.id)
+            .await
+            .unwrap();
+
+        let an_hour_ago = Utc::now().timestamp() - 3600;
+        let missing = manager
+            .get_artists_missing_images(an_hour_ago)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = missing.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec![waiting.id.as_str()]);
+
+        // Lookups older than the cutoff are due again
+        let later = Utc::now().timestamp() + 3600;
+        let missing = manager.get_artists_missing_images(later).await.unwrap();
+        assert_eq!(missing.len(), 2);
     }
 
-    fn create_test_album() -> DbAlbum {
-        DbAlbum {
-            id: Uuid::new_v4().to_string(),
-            title: "Test Album".to_string(),
-            year: Some(2024),
-            discogs_release: None,
-            musicbrainz_release: None,
-            bandcamp_album_id: None,
-            cover_release_id: None,
-            is_compilation: false,
-            created_at: Utc::now(),
+    #[tokio::test]
+    async fn test_small_covers_are_upgrade_candidates_until_looked_up() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        manager.database.insert_album(&album).await.unwrap();
+
+        let cover = |release_id: &str, size: Option<i64>| DbLibraryImage {
+            id: release_id.to_string(),
+            image_type: LibraryImageType::Cover,
+            content_type: ContentType::Jpeg,
+            file_size: 1024,
+            width: size,
+            height: size,
+            source: "local".to_string(),
+            source_url: None,
+            blurhash: None,
             updated_at: Utc::now(),
+            created_at: Utc::now(),
+        };
+        let mut releases = Vec::new();
+        for size in [Some(300), Some(1200), None, Some(250)] {
+            let release = create_test_release(&album.id);
+            manager.database.insert_release(&release).await.unwrap();
+            manager
+                .upsert_library_image(&cover(&release.id, size))
+                .await
+                .unwrap();
+            releases.push(release.id);
         }
+        manager
+            .record_cover_upgrade_lookup(&releases[3])
+            .await
+            .unwrap();
+
+        let an_hour_ago = Utc::now().timestamp() - 3600;
+        let candidates = manager
+            .get_cover_upgrade_candidates(600, an_hour_ago)
+            .await
+            .unwrap();
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.contains(&releases[0]));
+        assert!(candidates.contains(&releases[2]));
+
+        // Lookups older than the cutoff are due again
+        let later = Utc::now().timestamp() + 3600;
+        let candidates = manager
+            .get_cover_upgrade_candidates(600, later)
+            .await
+            .unwrap();
+        assert_eq!(candidates.len(), 3);
     }
 
-    fn create_test_release(album_id: &str) -> DbRelease {
-        DbRelease {
-            id: Uuid::new_v4().to_string(),
-            album_id: album_id.to_string(),
-            release_name: None,
-            year: Some(2024),
-            discogs_release_id: None,
-            bandcamp_release_id: None,
-            format: None,
-            label: None,
-            catalog_number: None,
-            country: None,
-            barcode: None,
-            import_status: ImportStatus::Complete,
-            managed_locally: false,
-            managed_in_cloud: false,
-            unmanaged_path: None,
-            private: false,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+    #[tokio::test]
+    async fn test_genres_are_normalized_and_merged() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let first = create_test_album();
+        let second = create_test_album();
+        manager.database.insert_album(&first).await.unwrap();
+        manager.database.insert_album(&second).await.unwrap();
+
+        manager
+            .set_album_genres(
+                &first.id,
+                &["hip hop".to_string(), "Jazz".to_string(), "Rap".to_string()],
+            )
+            .await
+            .unwrap();
+        manager
+            .set_album_genres(&second.id, &["Rap".to_string(), "jazz".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            manager.get_album_genres(&first.id).await.unwrap(),
+            vec!["Hip-Hop", "Jazz", "Rap"]
+        );
+        let genres = manager.get_genres().await.unwrap();
+        let names: Vec<&str> = genres.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["Hip-Hop", "Jazz", "Rap"]);
+
+        let suggestions = manager.get_genre_merge_suggestions().await.unwrap();
+        assert_eq!(suggestions.len(), 1);
+        manager
+            .merge_genres(&suggestions[0].genre_ids, &suggestions[0].into)
+            .await
+            .unwrap();
+
+        // The first album had both and keeps one link, in its original place
+        assert_eq!(
+            manager.get_album_genres(&first.id).await.unwrap(),
+            vec!["Hip-Hop", "Jazz"]
+        );
+        assert_eq!(
+            manager.get_album_genres(&second.id).await.unwrap(),
+            vec!["Hip-Hop", "Jazz"]
+        );
+        let genres = manager.get_genres().await.unwrap();
+        assert_eq!(genres.len(), 2);
+        assert_eq!(genres[0].album_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_custom_tags_and_notes_are_saved_and_searchable() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        manager.database.insert_album(&album).await.unwrap();
+
+        let tag = |key: &str, value: Option<&str>| DbAlbumCustomTag {
+            key: key.to_string(),
+            value: value.map(str::to_string),
+        };
+        manager
+            .set_album_custom_tags(
+                &album.id,
+                &[
+                    tag(" source ", Some("vinyl rip ")),
+                    tag("", Some("dropped")),
+                    tag("needs re-rip", Some("  ")),
+                ],
+            )
+            .await
+            .unwrap();
+        manager
+            .set_album_notes(&album.id, "  Side B skips at 2:10 \n")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            manager.get_album_custom_tags(&album.id).await.unwrap(),
+            vec![tag("source", Some("vinyl rip")), tag("needs re-rip", None)]
+        );
+        assert_eq!(
+            manager.get_album_notes(&album.id).await.unwrap().as_deref(),
+            Some("Side B skips at 2:10")
+        );
+
+        for query in ["vinyl", "re-rip", "skips"] {
+            let results = manager.search_library(query, 10).await.unwrap();
+            assert_eq!(results.albums.len(), 1, "query {query}");
         }
+
+        let result = manager
+            .set_album_custom_tags(&album.id, &[tag("Source", None), tag("source", None)])
+            .await;
+        assert!(matches!(result, Err(LibraryError::Tags(_))));
+
+        manager.set_album_notes(&album.id, "   ").await.unwrap();
+        assert!(manager.get_album_notes(&album.id).await.unwrap().is_none());
     }
 
     #[tokio::test]
-    async fn test_delete_release_with_single_release_deletes_album() {
-        let (manager, _temp_dir, library_dir) = setup_test_manager().await;
+    async fn test_search_ranks_by_relevance_then_plays_and_pages() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
         let album = create_test_album();
         let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, _) = insert_release_with_audio(
+            &manager,
+            &release,
+            &[
+                "Under Low Light",
+                "Low Light Again",
+                "Low Light",
+                "Low Light Reprise",
+            ],
+        )
+        .await;
+        for _ in 0..4 {
+            manager.record_play(&tracks[3]).await.unwrap();
+        }
+
+        let results = manager.search_library("low light", 10).await.unwrap();
+        let titles: Vec<_> = results.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Low Light",
+                "Low Light Reprise",
+                "Low Light Again",
+                "Under Low Light"
+            ]
+        );
+
+        let page = SearchPage {
+            track_count: 2,
+            track_offset: 1,
+            ..SearchPage::first(10)
+        };
+        let results = manager
+            .search_library_page("low light", &page)
+            .await
+            .unwrap();
+        let titles: Vec<_> = results.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Low Light Reprise", "Low Light Again"]);
+    }
+
+    #[tokio::test]
+    async fn test_album_comments_are_newest_first_and_removable_by_author() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        manager.database.insert_album(&album).await.unwrap();
+
+        let first = manager
+            .add_album_comment(&album.id, "aaaa", " play track 3 loud ")
+            .await
+            .unwrap();
+        let second = manager
+            .add_album_comment(&album.id, "bbbb", "The vinyl rip is better")
+            .await
+            .unwrap();
+        assert_eq!(first.content, "play track 3 loud");
+
+        let comments = manager.get_album_comments(&album.id).await.unwrap();
+        let ids: Vec<&str> = comments.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec![second.id.as_str(), first.id.as_str()]);
+
+        let blank = manager.add_album_comment(&album.id, "aaaa", "  ").await;
+        assert!(matches!(blank, Err(LibraryError::Comment(_))));
+
+        let not_author = manager.delete_album_comment(&second.id, "aaaa").await;
+        assert!(matches!(not_author, Err(LibraryError::Comment(_))));
+        manager
+            .delete_album_comment(&second.id, "bbbb")
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.get_album_comments(&album.id).await.unwrap().len(),
+            1
+        );
+    }
 
+    #[tokio::test]
+    async fn test_playlist_entries_can_be_added_moved_and_removed() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
         manager.database.insert_album(&album).await.unwrap();
         manager.database.insert_release(&release).await.unwrap();
+        let (tracks, _) = insert_release_with_audio(
+            &manager,
+            &release,
+            &["Pacific Standard", "Low Light", "Tidewater"],
+        )
+        .await;
+
+        assert!(matches!(
+            manager.create_playlist("  ").await,
+            Err(LibraryError::Playlist(_))
+        ));
+        let playlist = manager.create_playlist(" Night Drive ").await.unwrap();
+        assert_eq!(playlist.name, "Night Drive");
 
         manager
-            .delete_release(&release.id, &library_dir)
+            .add_tracks_to_playlist(&playlist.id, &tracks[..2])
+            .await
+            .unwrap();
+        manager
+            .add_tracks_to_playlist(&playlist.id, &[tracks[2].clone(), tracks[0].clone()])
+            .await
+            .unwrap();
+        manager
+            .move_playlist_entry(&playlist.id, 2, 0)
+            .await
+            .unwrap();
+        manager
+            .remove_playlist_entry(&playlist.id, 3)
             .await
             .unwrap();
 
-        let album_result = manager.database.get_album_by_id(&album.id).await.unwrap();
-        assert!(album_result.is_none());
-        let releases = manager
-            .database
-            .get_releases_for_album(&album.id)
+        let entries = manager.get_playlist_entries(&playlist.id).await.unwrap();
+        let titles: Vec<&str> = entries.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["Tidewater", "Pacific Standard", "Low Light"]);
+        assert_eq!(entries[0].album_id, album.id);
+
+        manager
+            .rename_playlist(&playlist.id, "Late Night Drive")
             .await
             .unwrap();
-        assert!(releases.is_empty());
+        manager.delete_playlist(&playlist.id).await.unwrap();
+        assert!(manager.get_playlists().await.unwrap().is_empty());
+        assert!(manager
+            .get_playlist_entries(&playlist.id)
+            .await
+            .unwrap()
+            .is_empty());
     }
 
     #[tokio::test]
-    async fn test_delete_release_with_multiple_releases_preserves_album() {
-        let (manager, _temp_dir, library_dir) = setup_test_manager().await;
+    async fn test_collections_hold_albums_and_filter_the_library() {
+        use crate::db::AlbumFilter;
+
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let mut album_ids = Vec::new();
+        for title in ["Glass Harbor", "Tidewater"] {
+            let mut album = create_test_album();
+            album.title = title.to_string();
+            manager.database.insert_album(&album).await.unwrap();
+            album_ids.push(album.id);
+        }
+
+        assert!(matches!(
+            manager.create_collection(" ").await,
+            Err(LibraryError::Collection(_))
+        ));
+        let vinyl = manager.create_collection(" Also on Vinyl ").await.unwrap();
+        assert_eq!(vinyl.name, "Also on Vinyl");
+
+        manager
+            .add_album_to_collection(&vinyl.id, &album_ids[0])
+            .await
+            .unwrap();
+        // Adding twice keeps one entry
+        manager
+            .add_album_to_collection(&vinyl.id, &album_ids[0])
+            .await
+            .unwrap();
+        manager
+            .add_album_to_collection(&vinyl.id, &album_ids[1])
+            .await
+            .unwrap();
+        manager
+            .remove_album_from_collection(&vinyl.id, &album_ids[1])
+            .await
+            .unwrap();
+
+        let on_collection = manager
+            .get_albums_filtered(
+                &[],
+                &AlbumFilter {
+                    collection: Some(vinyl.id.clone()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let titles: Vec<&str> = on_collection.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, vec!["Glass Harbor"]);
+        let collection_ids = manager.get_album_collection_ids().await.unwrap();
+        assert_eq!(collection_ids[&album_ids[0]], vec![vinyl.id.clone()]);
+        assert!(!collection_ids.contains_key(&album_ids[1]));
+
+        manager.delete_collection(&vinyl.id).await.unwrap();
+        assert!(manager.get_collections().await.unwrap().is_empty());
+        assert!(manager.get_album_collection_ids().await.unwrap().is_empty());
+        assert_eq!(manager.get_albums(&[]).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_m3u_playlists_import_by_title_and_export_file_names() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
         let album = create_test_album();
-        let release1 = create_test_release(&album.id);
-        let release2 = create_test_release(&album.id);
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        insert_release_with_audio(&manager, &release, &["Pacific Standard", "Low Light"]).await;
+
+        let text = "#EXTM3U\n\
+                    #EXTINF:1,Unknown Band - Low Light\n\
+                    /elsewhere/Low Light.mp3\n\
+                    /elsewhere/01 - Pacific Standard.mp3\n\
+                    #EXTINF:200,Glass Harbor - Not In The Library\n\
+                    /elsewhere/missing.mp3\n";
+        let imported = manager.import_m3u_playlist("Imported", text).await.unwrap();
+        assert_eq!(imported.matched, 1);
+        assert_eq!(
+            imported.unmatched,
+            vec![
+                "Unknown Band - Low Light".to_string(),
+                "Glass Harbor - Not In The Library".to_string()
+            ]
+        );
+
+        let exported = manager
+            .export_m3u_playlist(&imported.playlist.id)
+            .await
+            .unwrap();
+        let entries = parse_m3u(&exported);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].title.as_deref(), Some("Pacific Standard"));
+        assert_eq!(entries[0].location, "Pacific Standard.flac");
+    }
 
+    #[tokio::test]
+    async fn test_save_release_works_credits_composers_apart_from_performers() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
+        let release = create_test_release(&album.id);
         manager.database.insert_album(&album).await.unwrap();
-        manager.database.insert_release(&release1).await.unwrap();
-        manager.database.insert_release(&release2).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, _) =
+            insert_release_with_audio(&manager, &release, &["Pacific Standard", "Low Light"]).await;
+        let performer = DbArtist {
+            id: "performer".to_string(),
+            name: "Tidewater".to_string(),
+            sort_name: None,
+            discogs_artist_id: None,
+            bandcamp_artist_id: None,
+            musicbrainz_artist_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        manager.insert_artist(&performer).await.unwrap();
+        manager
+            .insert_track_artist(&DbTrackArtist::new(&tracks[0], "performer", 0, None))
+            .await
+            .unwrap();
+
+        let composer = ComposerCredit {
+            musicbrainz_artist_id: Some("mb-glass-harbor".to_string()),
+            name: "Glass Harbor".to_string(),
+            sort_name: None,
+        };
+        let movement = |track_number: i32, title: &str| TrackWorkCredit {
+            disc_number: 1,
+            track_number,
+            work_id: "work-whole".to_string(),
+            work_title: "Tidewater Suite".to_string(),
+            movement_title: Some(title.to_string()),
+            movement_number: Some(track_number),
+            composers: vec![composer.clone()],
+        };
+        let credits = [
+            movement(1, "I. Pacific Standard"),
+            movement(2, "II. Low Light"),
+            movement(7, "VII. Missing"),
+        ];
 
+        let matched = manager
+            .save_release_works(&release.id, &credits)
+            .await
+            .unwrap();
+        // Saving again replaces rather than duplicates
         manager
-            .delete_release(&release1.id, &library_dir)
+            .save_release_works(&release.id, &credits)
             .await
             .unwrap();
 
-        let album_result = manager.database.get_album_by_id(&album.id).await.unwrap();
-        assert!(album_result.is_some());
-        let releases = manager
-            .database
-            .get_releases_for_album(&album.id)
+        assert_eq!(matched, 2);
+        let works = manager
+            .get_track_works_for_release(&release.id)
             .await
             .unwrap();
-        assert_eq!(releases.len(), 1);
-        assert_eq!(releases[0].id, release2.id);
+        assert_eq!(works.len(), 2);
+        assert!(works.iter().all(|w| w.work_title == "Tidewater Suite"));
+        let composers = manager
+            .get_composers_for_release(&release.id)
+            .await
+            .unwrap();
+        assert_eq!(composers.len(), 2);
+        assert!(composers.iter().all(|(_, a)| a.name == "Glass Harbor"));
+        assert_eq!(composers[0].1.id, composers[1].1.id);
+
+        let performers = manager.get_artists_for_track(&tracks[0]).await.unwrap();
+        assert_eq!(performers.len(), 1);
+        assert_eq!(performers[0].name, "Tidewater");
+        let composed = manager
+            .get_albums_for_artist(&composers[0].1.id)
+            .await
+            .unwrap();
+        assert_eq!(composed.len(), 1);
+        assert_eq!(composed[0].id, album.id);
     }
 
     #[tokio::test]
-    async fn test_delete_album_deletes_all_releases() {
-        let (manager, _temp_dir, library_dir) = setup_test_manager().await;
+    async fn test_featured_artists_are_credited_in_order_and_searchable() {
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
         let album = create_test_album();
-        let release1 = create_test_release(&album.id);
-        let release2 = create_test_release(&album.id);
+        let release = create_test_release(&album.id);
+        manager.database.insert_album(&album).await.unwrap();
+        manager.database.insert_release(&release).await.unwrap();
+        let (tracks, _) =
+            insert_release_with_audio(&manager, &release, &["Pacific Standard", "Undertow"]).await;
+        let artist = |id: &str, name: &str| DbArtist {
+            id: id.to_string(),
+            name: name.to_string(),
+            sort_name: None,
+            discogs_artist_id: None,
+            bandcamp_artist_id: None,
+            musicbrainz_artist_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        manager
+            .insert_artist(&artist("glass", "Glass Harbor"))
+            .await
+            .unwrap();
+        manager
+            .insert_artist(&artist("tide", "Tidewater"))
+            .await
+            .unwrap();
+        manager
+            .insert_album_artist(&DbAlbumArtist::new(&album.id, "glass", 0))
+            .await
+            .unwrap();
+        manager
+            .insert_track_artist(
+                &DbTrackArtist::new(&tracks[1], "glass", 0, None)
+                    .with_join_phrase(Some(" feat. ".to_string())),
+            )
+            .await
+            .unwrap();
+        manager
+            .insert_track_artist(&DbTrackArtist::new(&tracks[1], "tide", 1, None))
+            .await
+            .unwrap();
+
+        let credits = manager
+            .get_track_artist_credits_for_release(&release.id)
+            .await
+            .unwrap();
+        let credited: Vec<_> = credits
+            .iter()
+            .map(|(track_id, a, join)| (track_id.as_str(), a.name.as_str(), join.as_deref()))
+            .collect();
+        assert_eq!(
+            credited,
+            vec![
+                (tracks[1].as_str(), "Glass Harbor", Some(" feat. ")),
+                (tracks[1].as_str(), "Tidewater", None),
+            ]
+        );
+
+        let featured_on = manager.get_albums_for_artist("tide").await.unwrap();
+        assert_eq!(featured_on.len(), 1);
+        assert_eq!(featured_on[0].id, album.id);
+        let appearances = manager.get_appearance_album_ids("tide").await.unwrap();
+        assert_eq!(appearances, vec![album.id.clone()]);
+        let appearances = manager.get_appearance_album_ids("glass").await.unwrap();
+        assert!(appearances.is_empty());
+
+        let results = manager.search_library("Tidewater", 10).await.unwrap();
+        assert_eq!(results.artists.len(), 1);
+        assert_eq!(results.artists[0].album_count, 1);
+        assert_eq!(results.tracks.len(), 1);
+        assert_eq!(results.tracks[0].id, tracks[1]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_duplicate_releases_keeps_listening_history() {
+        use crate::library::duplicates::DuplicateReason;
+
+        let (manager, _temp_dir, library_dir) = setup_test_manager().await;
+        let mut releases = Vec::new();
+        for _ in 0..2 {
+            let mut album = create_test_album();
+            album.title = "Glass Harbor".to_string();
+            let release = create_test_release(&album.id);
+            manager.database.insert_album(&album).await.unwrap();
+            manager.database.insert_release(&release).await.unwrap();
+            let (track_ids, _) =
+                insert_release_with_audio(&manager, &release, &["Tidewater", "Low Light"]).await;
+            // Both copies were imported from the same files
+            let file = DbFile::new(&release.id, "Tidewater.flac", 100, ContentType::Flac)
+                .with_content_sha256("ab12".to_string());
+            manager.database.insert_file(&file).await.unwrap();
+            releases.push((release.id, track_ids));
+        }
+        let (kept_id, kept_tracks) = &releases[0];
+        let (duplicate_id, duplicate_tracks) = &releases[1];
+
+        let duplicates = manager.find_duplicate_releases().await.unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].reason, DuplicateReason::IdenticalAudio);
+        assert_eq!(duplicates[0].releases.len(), 2);
+
+        manager
+            .database
+            .merge_track_stats(&kept_tracks[0], 2, None, None)
+            .await
+            .unwrap();
+        manager
+            .database
+            .merge_track_stats(&duplicate_tracks[0], 3, Some(5), None)
+            .await
+            .unwrap();
+        manager.record_play(&duplicate_tracks[1]).await.unwrap();
+        let playlist = manager.create_playlist("Pacific Standard").await.unwrap();
+        manager
+            .add_tracks_to_playlist(&playlist.id, &[duplicate_tracks[0].clone()])
+            .await
+            .unwrap();
 
+        manager
+            .merge_duplicate_releases(kept_id, &[duplicate_id.clone()], &library_dir)
+            .await
+            .unwrap();
+
+        assert!(manager
+            .database
+            .get_release_by_id(duplicate_id)
+            .await
+            .unwrap()
+            .is_none());
+        let stats = manager
+            .database
+            .get_track_stats(&kept_tracks[0])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.play_count, 5);
+        assert_eq!(stats.rating, Some(5));
+        let history = manager.get_play_history().await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].track_id, kept_tracks[1]);
+        let entries = manager.get_playlist_entries(&playlist.id).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].track_id, kept_tracks[0]);
+        assert!(manager.find_duplicate_releases().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_sync_conflict_writes_chosen_values() {
+        use crate::db::DbSyncConflictField;
+
+        let (manager, _temp_dir, _library_dir) = setup_test_manager().await;
+        let album = create_test_album();
         manager.database.insert_album(&album).await.unwrap();
-        manager.database.insert_release(&release1).await.unwrap();
-        manager.database.insert_release(&release2).await.unwrap();
+        let conflict = DbSyncConflict {
+            id: Uuid::new_v4().to_string(),
+            table_name: "albums".to_string(),
+            row_id: album.id.clone(),
+            fields: vec![DbSyncConflictField {
+                column: "title".to_string(),
+                local: Some("Glass Harbor".to_string()),
+                remote: Some("Glass Harbour".to_string()),
+            }],
+            kept_remote: true,
+            detected_at: Utc::now(),
+        };
+        manager
+            .database
+            .upsert_sync_conflict(&conflict)
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.get_sync_conflicts().await.unwrap(),
+            vec![conflict.clone()]
+        );
 
-        manager.delete_album(&album.id, &library_dir).await.unwrap();
+        let not_in_conflict = [("year".to_string(), Some("1999".to_string()))];
+        assert!(manager
+            .resolve_sync_conflict(&conflict.id, &not_in_conflict)
+            .await
+            .is_err());
 
-        let album_result = manager.database.get_album_by_id(&album.id).await.unwrap();
-        assert!(album_result.is_none());
-        let releases = manager
+        let merged = [("title".to_string(), Some("Glass Harbor (Live)".to_string()))];
+        manager
+            .resolve_sync_conflict(&conflict.id, &merged)
+            .await
+            .unwrap();
+        let stored = manager
             .database
-            .get_releases_for_album(&album.id)
+            .get_album_by_id(&album.id)
             .await
+            .unwrap()
             .unwrap();
-        assert!(releases.is_empty());
+        assert_eq!(stored.title, "Glass Harbor (Live)");
+        assert!(manager.get_sync_conflicts().await.unwrap().is_empty());
     }
 }
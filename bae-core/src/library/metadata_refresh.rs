@@ -0,0 +1,389 @@
+//! Refreshing an album's tags from MusicBrainz
+//!
+//! MusicBrainz data keeps improving after an album is imported. The album's
+//! release is fetched again and compared field by field with the local tags;
+//! the user picks which differences to take, and those go through the normal
+//! tag update path so they sync like any other edit.
+
+use crate::db::{DbAlbum, DbArtist, DbReleaseDisc, DbTrack};
+use crate::musicbrainz::MbReleaseResponse;
+use std::collections::HashMap;
+
+/// A new value MusicBrainz has for one field
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataUpdate {
+    Title(String),
+    Year(Option<i32>),
+    /// Album artist names, in credit order
+    Artists(Vec<String>),
+    TrackTitle {
+        track_id: String,
+        disc_number: Option<i32>,
+        track_number: Option<i32>,
+        title: String,
+    },
+    DiscTitle {
+        disc_number: i32,
+        title: String,
+    },
+}
+
+/// A field whose local value differs from MusicBrainz
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataChange {
+    pub update: MetadataUpdate,
+    /// Local value, for display
+    pub current: String,
+    /// MusicBrainz value, for display
+    pub proposed: String,
+}
+
+/// Compare an album and one of its releases' tracks and disc titles with a
+/// MusicBrainz release.
+///
+/// Only fields MusicBrainz has a value for are compared, so a missing date
+/// never clears a local year. Tracks are matched by disc and track number.
+pub fn diff_musicbrainz_release(
+    album: &DbAlbum,
+    artists: &[DbArtist],
+    tracks: &[DbTrack],
+    discs: &[DbReleaseDisc],
+    response: &MbReleaseResponse,
+) -> Vec<MetadataChange> {
+    let mut changes = Vec::new();
+
+    let title = response.title.trim();
+    if !title.is_empty() && title != album.title {
+        changes.push(MetadataChange {
+            update: MetadataUpdate::Title(title.to_string()),
+            current: album.title.clone(),
+            proposed: title.to_string(),
+        });
+    }
+
+    let year = response
+        .release_group
+        .as_ref()
+        .and_then(|rg| rg.first_release_date.as_deref())
+        .or(response.date.as_deref())
+        .and_then(parse_year);
+    if year.is_some() && year != album.year {
+        changes.push(MetadataChange {
+            update: MetadataUpdate::Year(year),
+            current: album.year.map(|y| y.to_string()).unwrap_or_default(),
+            proposed: year.map(|y| y.to_string()).unwrap_or_default(),
+        });
+    }
+
+    let mut credited: Vec<String> = Vec::new();
+    for credit in &response.artist_credit {
+        let name = credit
+            .artist
+            .as_ref()
+            .and_then(|a| a.name.as_deref())
+            .or(credit.name.as_deref())
+            .map(str::trim)
+            .unwrap_or_default();
+        if !name.is_empty() && !credited.iter().any(|c| c.eq_ignore_ascii_case(name)) {
+            credited.push(name.to_string());
+        }
+    }
+    // Artists are matched by name ignoring case when saved, so a case-only
+    // difference can't be applied and isn't offered
+    let same_artists = credited.len() == artists.len()
+        && credited
+            .iter()
+            .zip(artists)
+            .all(|(name, artist)| name.eq_ignore_ascii_case(&artist.name));
+    if !credited.is_empty() && !same_artists {
+        changes.push(MetadataChange {
+            current: artists
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            proposed: credited.join(", "),
+            update: MetadataUpdate::Artists(credited),
+        });
+    }
+
+    let mut mb_titles: HashMap<(i32, i32), String> = HashMap::new();
+    for (medium_index, medium) in response.media.iter().enumerate() {
+        for (index, track) in medium.tracks.iter().enumerate() {
+            let title = track
+                .recording
+                .as_ref()
+                .and_then(|r| r.title.as_deref())
+                .or(track.title.as_deref());
+            let Some(title) = title.map(str::trim).filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            let position = track.position.map(|p| p as i32).unwrap_or(index as i32 + 1);
+            mb_titles.insert((medium_index as i32 + 1, position), title.to_string());
+        }
+    }
+    for track in tracks {
+        let Some(track_number) = track.track_number else {
+            continue;
+        };
+        let key = (track.disc_number.unwrap_or(1), track_number);
+        if let Some(title) = mb_titles.get(&key).filter(|t| **t != track.title) {
+            changes.push(MetadataChange {
+                update: MetadataUpdate::TrackTitle {
+                    track_id: track.id.clone(),
+                    disc_number: track.disc_number,
+                    track_number: track.track_number,
+                    title: title.clone(),
+                },
+                current: track.title.clone(),
+                proposed: title.clone(),
+            });
+        }
+    }
+
+    for (disc_number, title) in response.disc_titles() {
+        if !tracks
+            .iter()
+            .any(|t| t.disc_number.unwrap_or(1) == disc_number)
+        {
+            continue;
+        }
+        let current = discs
+            .iter()
+            .find(|d| d.disc_number == disc_number)
+            .map(|d| d.title.clone())
+            .unwrap_or_default();
+        if current != title {
+            changes.push(MetadataChange {
+                update: MetadataUpdate::DiscTitle {
+                    disc_number,
+                    title: title.clone(),
+                },
+                current,
+                proposed: title,
+            });
+        }
+    }
+
+    changes
+}
+
+/// Year from a MusicBrainz date ("1998", "1998-04" or "1998-04-12")
+fn parse_year(date: &str) -> Option<i32> {
+    date.split('-').next().and_then(|y| y.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::ImportStatus;
+    use crate::musicbrainz::{
+        MbArtistCredit, MbArtistRef, MbMedium, MbRecording, MbReleaseGroupRef, MbTrack,
+    };
+    use chrono::Utc;
+
+    fn album(title: &str, year: Option<i32>) -> DbAlbum {
+        DbAlbum {
+            id: "album-1".to_string(),
+            title: title.to_string(),
+            year,
+            discogs_release: None,
+            musicbrainz_release: None,
+            bandcamp_album_id: None,
+            cover_release_id: None,
+            is_compilation: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn artist(name: &str) -> DbArtist {
+        DbArtist {
+            id: format!("artist-{name}"),
+            name: name.to_string(),
+            sort_name: None,
+            discogs_artist_id: None,
+            bandcamp_artist_id: None,
+            musicbrainz_artist_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn track(id: &str, title: &str, disc_number: Option<i32>, track_number: i32) -> DbTrack {
+        DbTrack {
+            id: id.to_string(),
+            release_id: "release-1".to_string(),
+            title: title.to_string(),
+            disc_number,
+            track_number: Some(track_number),
+            duration_ms: None,
+            discogs_position: None,
+            import_status: ImportStatus::Complete,
+            updated_at: Utc::now(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn mb_track(position: i64, title: &str) -> MbTrack {
+        MbTrack {
+            position: Some(position),
+            number: None,
+            title: None,
+            length: None,
+            recording: Some(MbRecording {
+                title: Some(title.to_string()),
+                relations: vec![],
+            }),
+            artist_credit: vec![],
+        }
+    }
+
+    fn response(artist_names: &[&str], first_release_date: Option<&str>) -> MbReleaseResponse {
+        MbReleaseResponse {
+            id: "mb-release".to_string(),
+            title: "Tidewater".to_string(),
+            date: Some("2011-03-01".to_string()),
+            country: None,
+            barcode: None,
+            artist_credit: artist_names
+                .iter()
+                .map(|name| MbArtistCredit {
+                    name: Some(name.to_string()),
+                    artist: Some(MbArtistRef {
+                        id: None,
+                        name: Some(name.to_string()),
+                        sort_name: None,
+                    }),
+                    joinphrase: None,
+                })
+                .collect(),
+            release_group: Some(MbReleaseGroupRef {
+                id: None,
+                first_release_date: first_release_date.map(str::to_string),
+                secondary_types: vec![],
+                relations: None,
+            }),
+            label_info: vec![],
+            media: vec![
+                MbMedium {
+                    format: None,
+                    title: None,
+                    tracks: vec![mb_track(1, "Low Light"), mb_track(2, "Pacific Standard")],
+                },
+                MbMedium {
+                    format: None,
+                    title: None,
+                    tracks: vec![mb_track(1, "Glass Harbor")],
+                },
+            ],
+            relations: vec![],
+        }
+    }
+
+    #[test]
+    fn unchanged_release_has_no_changes() {
+        let tracks = vec![
+            track("t1", "Low Light", Some(1), 1),
+            track("t2", "Pacific Standard", Some(1), 2),
+            track("t3", "Glass Harbor", Some(2), 1),
+        ];
+        let changes = diff_musicbrainz_release(
+            &album("Tidewater", Some(2009)),
+            &[artist("Glass Harbor")],
+            &tracks,
+            &[],
+            &response(&["glass harbor"], Some("2009")),
+        );
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn finds_title_year_artist_and_track_changes() {
+        let tracks = vec![
+            track("t1", "Low Lihgt", Some(1), 1),
+            track("t2", "Pacific Standard", Some(1), 2),
+            track("t3", "Glass Harbour", Some(2), 1),
+        ];
+        let changes = diff_musicbrainz_release(
+            &album("Tidewater (Remaster)", Some(2011)),
+            &[artist("Glass Harbor")],
+            &tracks,
+            &[],
+            &response(&["Glass Harbor", "Low Light"], Some("2009-05-02")),
+        );
+
+        let updates: Vec<&MetadataUpdate> = changes.iter().map(|c| &c.update).collect();
+        assert_eq!(updates.len(), 5);
+        assert_eq!(updates[0], &MetadataUpdate::Title("Tidewater".to_string()));
+        assert_eq!(updates[1], &MetadataUpdate::Year(Some(2009)));
+        assert_eq!(
+            updates[2],
+            &MetadataUpdate::Artists(vec!["Glass Harbor".to_string(), "Low Light".to_string()])
+        );
+        assert_eq!(changes[2].current, "Glass Harbor");
+        assert_eq!(changes[2].proposed, "Glass Harbor, Low Light");
+        assert!(
+            matches!(updates[3], MetadataUpdate::TrackTitle { track_id, title, .. } if track_id == "t1" && title == "Low Light")
+        );
+        assert!(
+            matches!(updates[4], MetadataUpdate::TrackTitle { track_id, title, .. } if track_id == "t3" && title == "Glass Harbor")
+        );
+    }
+
+    #[test]
+    fn finds_new_disc_title() {
+        let tracks = vec![
+            track("t1", "Low Light", Some(1), 1),
+            track("t2", "Pacific Standard", Some(1), 2),
+            track("t3", "Glass Harbor", Some(2), 1),
+        ];
+        let mut mb = response(&["Glass Harbor"], Some("2009"));
+        mb.media[1].title = Some("Live at Tidewater".to_string());
+        let changes = diff_musicbrainz_release(
+            &album("Tidewater", Some(2009)),
+            &[artist("Glass Harbor")],
+            &tracks,
+            &[],
+            &mb,
+        );
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].update,
+            MetadataUpdate::DiscTitle {
+                disc_number: 2,
+                title: "Live at Tidewater".to_string(),
+            }
+        );
+        assert_eq!(changes[0].current, "");
+
+        let discs = vec![DbReleaseDisc {
+            release_id: "release-1".to_string(),
+            disc_number: 2,
+            title: "Live at Tidewater".to_string(),
+        }];
+        let changes = diff_musicbrainz_release(
+            &album("Tidewater", Some(2009)),
+            &[artist("Glass Harbor")],
+            &tracks,
+            &discs,
+            &mb,
+        );
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn missing_date_keeps_local_year() {
+        let mut mb = response(&["Glass Harbor"], None);
+        mb.date = None;
+        let changes = diff_musicbrainz_release(
+            &album("Tidewater", Some(2009)),
+            &[artist("Glass Harbor")],
+            &[],
+            &[],
+            &mb,
+        );
+        assert!(changes.is_empty());
+    }
+}
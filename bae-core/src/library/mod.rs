@@ -1,5 +1,15 @@
+pub mod bundle;
+pub mod classical;
 pub mod context;
+pub mod duplicates;
 pub mod export;
+pub mod full_export;
+pub mod integrity;
+pub mod key_rotation;
+pub mod m3u;
 pub mod manager;
+pub mod metadata_refresh;
+pub mod musicbrainz_collection;
 pub use context::*;
 pub use manager::*;
+pub use metadata_refresh::{MetadataChange, MetadataUpdate};
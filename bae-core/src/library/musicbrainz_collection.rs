@@ -0,0 +1,217 @@
+//! Keeping a MusicBrainz collection in sync with the library
+//!
+//! The user links a MusicBrainz account and picks one of its release
+//! collections. Albums with a MusicBrainz release are added once they finish
+//! importing and taken out again when they leave the library. Only releases
+//! this device added are ever removed, so whatever was in the collection
+//! before it was linked stays there.
+
+use crate::keys::{KeyError, KeyService};
+use crate::library::{LibraryError, LibraryManager};
+use crate::musicbrainz::{self, MbCollection, MusicBrainzError, COLLECTION_BATCH_SIZE};
+use crate::oauth::{self, OAuthError, OAuthTokens};
+use std::collections::HashSet;
+use std::future::Future;
+use thiserror::Error;
+use tracing::info;
+
+#[derive(Error, Debug)]
+pub enum CollectionSyncError {
+    #[error("No MusicBrainz account linked, or its authorization has expired")]
+    NotLinked,
+    #[error("MusicBrainz authorization failed: {0}")]
+    OAuth(#[from] OAuthError),
+    #[error(transparent)]
+    MusicBrainz(#[from] MusicBrainzError),
+    #[error("Failed to save MusicBrainz tokens: {0}")]
+    Keys(#[from] KeyError),
+    #[error(transparent)]
+    Library(#[from] LibraryError),
+}
+
+/// MusicBrainz release IDs to add to and remove from a collection
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CollectionChanges {
+    pub to_add: Vec<String>,
+    pub to_remove: Vec<String>,
+}
+
+impl CollectionChanges {
+    pub fn is_empty(&self) -> bool {
+        self.to_add.is_empty() && self.to_remove.is_empty()
+    }
+}
+
+/// Compare the library's release IDs with the ones this device already added
+pub fn collection_changes(library: &[String], added: &[String]) -> CollectionChanges {
+    let library: HashSet<&String> = library.iter().collect();
+    let added: HashSet<&String> = added.iter().collect();
+
+    let mut to_add: Vec<String> = library
+        .difference(&added)
+        .map(|id| id.to_string())
+        .collect();
+    let mut to_remove: Vec<String> = added
+        .difference(&library)
+        .map(|id| id.to_string())
+        .collect();
+    to_add.sort();
+    to_remove.sort();
+
+    CollectionChanges { to_add, to_remove }
+}
+
+/// A linked MusicBrainz account. Tokens are refreshed when they expire and
+/// saved back to the keyring.
+pub struct MusicBrainzAccount {
+    key_service: KeyService,
+    tokens: OAuthTokens,
+}
+
+impl MusicBrainzAccount {
+    /// The account linked to this library
+    pub fn load(key_service: &KeyService) -> Result<Self, CollectionSyncError> {
+        let tokens = key_service
+            .get_musicbrainz_tokens()
+            .ok_or(CollectionSyncError::NotLinked)?;
+        Ok(Self {
+            key_service: key_service.clone(),
+            tokens,
+        })
+    }
+
+    /// Authorize bae in the browser and save the tokens. Returns the account
+    /// along with its username.
+    pub async fn link(key_service: &KeyService) -> Result<(Self, String), CollectionSyncError> {
+        let tokens = oauth::authorize(&musicbrainz::oauth_config()).await?;
+        key_service.set_musicbrainz_tokens(&tokens)?;
+
+        let mut account = Self {
+            key_service: key_service.clone(),
+            tokens,
+        };
+        let username = account
+            .call(|token| async move { musicbrainz::fetch_username(&token).await })
+            .await?;
+        Ok((account, username))
+    }
+
+    /// Release collections the account owns
+    pub async fn list_collections(
+        &mut self,
+        username: &str,
+    ) -> Result<Vec<MbCollection>, CollectionSyncError> {
+        self.call(move |token| async move {
+            musicbrainz::list_release_collections(username, &token).await
+        })
+        .await
+    }
+
+    async fn access_token(&mut self) -> Result<String, CollectionSyncError> {
+        match self.tokens.expires_at {
+            Some(expires_at) if chrono::Utc::now().timestamp() >= expires_at - 60 => {
+                self.refresh().await
+            }
+            _ => Ok(self.tokens.access_token.clone()),
+        }
+    }
+
+    async fn refresh(&mut self) -> Result<String, CollectionSyncError> {
+        let refresh_token = self
+            .tokens
+            .refresh_token
+            .as_deref()
+            .ok_or(CollectionSyncError::NotLinked)?;
+        let tokens = oauth::refresh(&musicbrainz::oauth_config(), refresh_token).await?;
+        self.key_service.set_musicbrainz_tokens(&tokens)?;
+
+        info!("Refreshed MusicBrainz OAuth tokens");
+
+        self.tokens = tokens;
+        Ok(self.tokens.access_token.clone())
+    }
+
+    /// Run a request with the current token, refreshing it once if
+    /// MusicBrainz rejects it
+    async fn call<T, F, Fut>(&mut self, request: F) -> Result<T, CollectionSyncError>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, MusicBrainzError>>,
+    {
+        let token = self.access_token().await?;
+        match request(token).await {
+            Err(MusicBrainzError::Unauthorized) => {
+                let token = self.refresh().await?;
+                Ok(request(token).await?)
+            }
+            result => Ok(result?),
+        }
+    }
+}
+
+/// Add newly imported releases to the collection and take deleted ones out.
+///
+/// Each batch is recorded as soon as MusicBrainz accepts it, so an
+/// interrupted sync carries on from there next time.
+pub async fn sync_collection(
+    library_manager: &LibraryManager,
+    account: &mut MusicBrainzAccount,
+    collection_id: &str,
+) -> Result<CollectionChanges, CollectionSyncError> {
+    let changes = library_manager
+        .get_musicbrainz_collection_changes(collection_id)
+        .await?;
+
+    for batch in changes.to_add.chunks(COLLECTION_BATCH_SIZE) {
+        account
+            .call(move |token| async move {
+                musicbrainz::add_releases_to_collection(collection_id, batch, &token).await
+            })
+            .await?;
+        library_manager
+            .record_musicbrainz_collection_added(collection_id, batch)
+            .await?;
+    }
+    for batch in changes.to_remove.chunks(COLLECTION_BATCH_SIZE) {
+        account
+            .call(move |token| async move {
+                musicbrainz::remove_releases_from_collection(collection_id, batch, &token).await
+            })
+            .await?;
+        library_manager
+            .record_musicbrainz_collection_removed(collection_id, batch)
+            .await?;
+    }
+
+    if !changes.is_empty() {
+        info!(
+            "MusicBrainz collection synced: {} added, {} removed",
+            changes.to_add.len(),
+            changes.to_remove.len()
+        );
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn adds_new_releases_and_removes_deleted_ones() {
+        let changes = collection_changes(&ids(&["c", "a", "b"]), &ids(&["b", "d"]));
+        assert_eq!(changes.to_add, ids(&["a", "c"]));
+        assert_eq!(changes.to_remove, ids(&["d"]));
+    }
+
+    #[test]
+    fn in_sync_library_has_no_changes() {
+        let changes = collection_changes(&ids(&["a", "b"]), &ids(&["b", "a"]));
+        assert!(changes.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::encryption::PassphraseKdf;
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
@@ -47,14 +48,31 @@ impl LibraryDir {
             .join(file_id)
     }
 
+    /// This device's copy of the sync bucket when syncing over the local network
+    pub fn lan_home_dir(&self) -> PathBuf {
+        self.path.join("lan-home")
+    }
+
     pub fn manifest_path(&self) -> PathBuf {
         self.path.join("manifest.json")
     }
 
+    /// The library's manifest, if it has one that parses
+    pub fn read_manifest(&self) -> Option<Manifest> {
+        let bytes = std::fs::read(self.manifest_path()).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
     pub fn pending_deletions_path(&self) -> PathBuf {
         self.path.join("pending_deletions.json")
     }
 
+    /// Uploads to the cloud home in progress: their sessions, and the staged
+    /// copies of files being moved there
+    pub fn uploads_dir(&self) -> PathBuf {
+        self.path.join("uploads")
+    }
+
     /// All asset directories that should be synced/created.
     pub fn asset_dirs(&self) -> Vec<PathBuf> {
         vec![self.images_dir(), self.storage_dir()]
@@ -68,6 +86,9 @@ pub struct Manifest {
     pub library_id: String,
     pub library_name: Option<String>,
     pub encryption_key_fingerprint: Option<String>,
+    /// Set when the master key is derived from a passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passphrase_kdf: Option<PassphraseKdf>,
 }
 
 impl Deref for LibraryDir {
@@ -0,0 +1,276 @@
+//! Song lyrics from LRCLIB
+//!
+//! LRCLIB (https://lrclib.net) is an open lyrics database with plain and
+//! time-synced (LRC) lyrics, looked up by artist, title, album and duration.
+//! A lookup that finds nothing still yields a `Lyrics` value, so callers can
+//! remember the miss instead of asking again on every play.
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+const LRCLIB_API: &str = "https://lrclib.net/api";
+
+/// Shared HTTP client for all LRCLIB requests.
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .user_agent("bae/1.0 +https://github.com/bae-fm/bae")
+            .build()
+            .expect("Failed to create HTTP client")
+    })
+}
+
+#[derive(Error, Debug)]
+pub enum LyricsError {
+    #[error("LRCLIB API error: {0}")]
+    Api(String),
+}
+
+/// Lyrics for one track. Both texts are None when the lookup found nothing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lyrics {
+    pub plain: Option<String>,
+    /// LRC text with `[mm:ss.xx]` timestamps on each line
+    pub synced: Option<String>,
+    pub instrumental: bool,
+}
+
+/// One timed line of synced lyrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyricLine {
+    pub time_ms: u64,
+    pub text: String,
+}
+
+impl Lyrics {
+    pub fn is_empty(&self) -> bool {
+        self.plain.is_none() && self.synced.is_none()
+    }
+
+    /// The plain text, or the synced text with its timestamps stripped
+    pub fn plain_text(&self) -> Option<String> {
+        if let Some(plain) = &self.plain {
+            return Some(plain.clone());
+        }
+        let lines = self.synced_lines();
+        (!lines.is_empty()).then(|| {
+            lines
+                .into_iter()
+                .map(|l| l.text)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+
+    /// Timed lines in playback order; empty without synced lyrics
+    pub fn synced_lines(&self) -> Vec<LyricLine> {
+        self.synced.as_deref().map(parse_lrc).unwrap_or_default()
+    }
+}
+
+/// What to look lyrics up by
+#[derive(Debug, Clone)]
+pub struct LyricsQuery {
+    pub artist: String,
+    pub title: String,
+    pub album: Option<String>,
+    pub duration_ms: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LrclibRecord {
+    plain_lyrics: Option<String>,
+    synced_lyrics: Option<String>,
+    #[serde(default)]
+    instrumental: bool,
+}
+
+impl From<LrclibRecord> for Lyrics {
+    fn from(record: LrclibRecord) -> Self {
+        let non_blank = |s: Option<String>| s.filter(|s| !s.trim().is_empty());
+        Lyrics {
+            plain: non_blank(record.plain_lyrics),
+            synced: non_blank(record.synced_lyrics),
+            instrumental: record.instrumental,
+        }
+    }
+}
+
+/// Look a track up on LRCLIB.
+///
+/// With a duration this is an exact signature match; without one it falls
+/// back to a search and takes the first hit.
+pub async fn fetch_lyrics(query: &LyricsQuery) -> Result<Lyrics, LyricsError> {
+    info!("LRCLIB: Looking up '{}' by {}", query.title, query.artist);
+
+    let mut params = vec![
+        ("artist_name", query.artist.clone()),
+        ("track_name", query.title.clone()),
+    ];
+    if let Some(album) = &query.album {
+        params.push(("album_name", album.clone()));
+    }
+    let record = match query.duration_ms {
+        Some(ms) => {
+            params.push(("duration", (ms / 1000).to_string()));
+            request::<LrclibRecord>("get", &params).await?
+        }
+        None => request::<Vec<LrclibRecord>>("search", &params)
+            .await?
+            .and_then(|records| records.into_iter().next()),
+    };
+
+    Ok(record.map(Lyrics::from).unwrap_or_default())
+}
+
+/// GET an LRCLIB endpoint. A 404 means no lyrics, not an error.
+async fn request<T: serde::de::DeserializeOwned>(
+    endpoint: &str,
+    params: &[(&str, String)],
+) -> Result<Option<T>, LyricsError> {
+    let url = format!("{LRCLIB_API}/{endpoint}");
+    debug!("LRCLIB API request: {} {:?}", url, params);
+
+    let response = http_client()
+        .get(&url)
+        .query(params)
+        .send()
+        .await
+        .map_err(|e| LyricsError::Api(format!("HTTP request failed: {}", e)))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+
+        warn!("LRCLIB API error response ({})", status);
+
+        return Err(LyricsError::Api(format!(
+            "LRCLIB API returned status {}",
+            status
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map(Some)
+        .map_err(|e| LyricsError::Api(format!("Failed to parse JSON: {}", e)))
+}
+
+/// Parse LRC text into timed lines, sorted by time.
+///
+/// A line may carry several timestamps (`[00:12.00][01:12.00]chorus`).
+/// Metadata tags like `[ar:Glass Harbor]` and untimed lines are skipped.
+pub fn parse_lrc(text: &str) -> Vec<LyricLine> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut rest = raw.trim();
+        let mut times = Vec::new();
+        while let Some(tag) = rest.strip_prefix('[') {
+            let Some(end) = tag.find(']') else {
+                break;
+            };
+            match parse_timestamp(&tag[..end]) {
+                Some(ms) => times.push(ms),
+                None => break,
+            }
+            rest = &tag[end + 1..];
+        }
+        let text = rest.trim();
+        for time_ms in times {
+            lines.push(LyricLine {
+                time_ms,
+                text: text.to_string(),
+            });
+        }
+    }
+    lines.sort_by_key(|l| l.time_ms);
+    lines
+}
+
+/// `mm:ss`, `mm:ss.xx` or `mm:ss.xxx` in ms
+fn parse_timestamp(tag: &str) -> Option<u64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u64 = minutes.parse().ok()?;
+    let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let whole: u64 = whole.parse().ok()?;
+    let fraction_ms = match fraction.len() {
+        0 => 0,
+        1..=3 => {
+            let digits: u64 = fraction.parse().ok()?;
+            digits * 10u64.pow(3 - fraction.len() as u32)
+        }
+        _ => return None,
+    };
+    Some(minutes * 60_000 + whole * 1000 + fraction_ms)
+}
+
+/// Index of the line being sung at `position_ms`, if any has started
+pub fn current_line(lines: &[LyricLine], position_ms: u64) -> Option<usize> {
+    lines
+        .partition_point(|l| l.time_ms <= position_ms)
+        .checked_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_timed_lines_and_skips_metadata() {
+        let lrc = "[ar:Glass Harbor]\n[ti:Tidewater]\n[00:12.30]Low light on the water\n\n[01:05.5]Pacific standard\n";
+
+        let lines = parse_lrc(lrc);
+
+        assert_eq!(
+            lines,
+            vec![
+                LyricLine {
+                    time_ms: 12_300,
+                    text: "Low light on the water".to_string()
+                },
+                LyricLine {
+                    time_ms: 65_500,
+                    text: "Pacific standard".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn repeated_timestamps_expand_in_time_order() {
+        let lines = parse_lrc("[00:30.000][00:10.000]Chorus\n[00:20.000]Verse");
+
+        let times: Vec<u64> = lines.iter().map(|l| l.time_ms).collect();
+        assert_eq!(times, vec![10_000, 20_000, 30_000]);
+        assert_eq!(lines[0].text, "Chorus");
+        assert_eq!(lines[2].text, "Chorus");
+    }
+
+    #[test]
+    fn current_line_is_the_last_one_started() {
+        let lines = parse_lrc("[00:10.00]One\n[00:20.00]Two");
+
+        assert_eq!(current_line(&lines, 5_000), None);
+        assert_eq!(current_line(&lines, 10_000), Some(0));
+        assert_eq!(current_line(&lines, 25_000), Some(1));
+    }
+
+    #[test]
+    fn plain_text_falls_back_to_synced() {
+        let lyrics = Lyrics {
+            plain: None,
+            synced: Some("[00:01.00]First\n[00:02.00]Second".to_string()),
+            instrumental: false,
+        };
+
+        assert_eq!(lyrics.plain_text().as_deref(), Some("First\nSecond"));
+        assert!(Lyrics::default().plain_text().is_none());
+    }
+}
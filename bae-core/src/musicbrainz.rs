@@ -53,6 +53,9 @@ pub struct MbUrlResource {
 pub struct MbArtistCredit {
     pub name: Option<String>,
     pub artist: Option<MbArtistRef>,
+    /// Text joining this artist to the next in the credit, e.g. " feat. "
+    #[serde(default)]
+    pub joinphrase: Option<String>,
 }
 
 /// Reference to a MusicBrainz artist within an artist-credit
@@ -101,6 +104,33 @@ impl MbReleaseGroupRef {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MbRecording {
     pub title: Option<String>,
+    /// Works this recording performs (only with `recording-level-rels`)
+    #[serde(default)]
+    pub relations: Vec<MbWorkRelation>,
+}
+
+/// A relationship to a work or an artist, from a recording or another work
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MbWorkRelation {
+    /// "performance", "parts", "composer", ...
+    #[serde(rename = "type")]
+    pub relation_type: Option<String>,
+    /// "backward" on a movement's "parts" relation points at the whole work
+    pub direction: Option<String>,
+    /// Movement number on a "parts" relation
+    #[serde(rename = "ordering-key")]
+    pub ordering_key: Option<i64>,
+    pub work: Option<MbWork>,
+    pub artist: Option<MbArtistRef>,
+}
+
+/// A composition, or one movement of one
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MbWork {
+    pub id: String,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub relations: Vec<MbWorkRelation>,
 }
 
 /// A track within a medium
@@ -111,12 +141,16 @@ pub struct MbTrack {
     pub title: Option<String>,
     pub length: Option<u64>,
     pub recording: Option<MbRecording>,
+    #[serde(rename = "artist-credit", default)]
+    pub artist_credit: Vec<MbArtistCredit>,
 }
 
 /// A medium (disc) within a release
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MbMedium {
     pub format: Option<String>,
+    /// Disc subtitle, e.g. "Live at Tidewater"; usually absent
+    pub title: Option<String>,
     #[serde(default)]
     pub tracks: Vec<MbTrack>,
 }
@@ -221,6 +255,19 @@ impl MbReleaseResponse {
     pub fn track_count(&self) -> usize {
         self.media.iter().map(|m| m.tracks.len()).sum()
     }
+
+    /// Titles of the media that have one, as (disc number, title). Disc
+    /// numbers follow media order, like the imported tracks' disc numbers.
+    pub fn disc_titles(&self) -> Vec<(i32, String)> {
+        self.media
+            .iter()
+            .enumerate()
+            .filter_map(|(index, medium)| {
+                let title = medium.title.as_deref()?.trim();
+                (!title.is_empty()).then(|| (index as i32 + 1, title.to_string()))
+            })
+            .collect()
+    }
 }
 
 /// Response from the disc ID lookup endpoint
@@ -439,6 +486,8 @@ pub enum MusicBrainzError {
     Api(String),
     #[error("No release found for DISCID: {0}")]
     NotFound(String),
+    #[error("MusicBrainz rejected the account token")]
+    Unauthorized,
 }
 
 // ============================================================================
@@ -663,6 +712,57 @@ pub async fn lookup_release_by_id(
     Ok((mb_release, external_urls, mb_response))
 }
 
+/// Lookup a release with the works its recordings perform, each work's
+/// composers and the larger work it is a movement of
+pub async fn lookup_release_works(release_id: &str) -> Result<MbReleaseResponse, MusicBrainzError> {
+    info!("MusicBrainz: Looking up works for release '{}'", release_id);
+    let url = format!(
+        "https://musicbrainz.org/ws/2/release/{}?inc=recordings+recording-level-rels+work-rels+work-level-rels+artist-rels",
+        release_id,
+    );
+    fetch_json(&url, release_id).await
+}
+
+/// Lookup a work with its composer and other artist relations
+pub async fn lookup_work(work_id: &str) -> Result<MbWork, MusicBrainzError> {
+    let url = format!(
+        "https://musicbrainz.org/ws/2/work/{}?inc=artist-rels",
+        work_id
+    );
+    fetch_json(&url, work_id).await
+}
+
+async fn fetch_json<T: serde::de::DeserializeOwned>(
+    url: &str,
+    id: &str,
+) -> Result<T, MusicBrainzError> {
+    debug!("MusicBrainz API request: {}", url);
+
+    wait_for_rate_limit().await;
+
+    let response = http_client()
+        .get(url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| MusicBrainzError::Api(format!("HTTP request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        if response.status() == 404 {
+            return Err(MusicBrainzError::NotFound(id.to_string()));
+        }
+        return Err(MusicBrainzError::Api(format!(
+            "MusicBrainz API returned status: {}",
+            response.status()
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| MusicBrainzError::Api(format!("Failed to parse JSON: {}", e)))
+}
+
 // ============================================================================
 // Search
 // ============================================================================
@@ -874,6 +974,183 @@ pub async fn search_releases_with_params(
     Ok(releases)
 }
 
+// ============================================================================
+// Collections (authenticated)
+// ============================================================================
+
+/// Release IDs per collection edit request, keeping the URL well under
+/// MusicBrainz's length limit
+pub const COLLECTION_BATCH_SIZE: usize = 100;
+
+/// A release collection owned by the linked account
+#[derive(Debug, Clone, PartialEq)]
+pub struct MbCollection {
+    pub id: String,
+    pub name: String,
+    pub release_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfoResponse {
+    sub: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionListResponse {
+    #[serde(default)]
+    collections: Vec<CollectionEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionEntry {
+    id: String,
+    name: String,
+    #[serde(rename = "entity-type")]
+    entity_type: String,
+    #[serde(rename = "release-count", default)]
+    release_count: u64,
+}
+
+/// OAuth settings for linking a MusicBrainz account.
+///
+/// bae is registered on musicbrainz.org as an installed application; the
+/// client credentials are supplied at build time.
+pub fn oauth_config() -> crate::oauth::OAuthConfig {
+    crate::oauth::OAuthConfig {
+        client_id: option_env!("BAE_MUSICBRAINZ_CLIENT_ID")
+            .unwrap_or_default()
+            .to_string(),
+        client_secret: option_env!("BAE_MUSICBRAINZ_CLIENT_SECRET").map(str::to_string),
+        auth_url: "https://musicbrainz.org/oauth2/authorize".to_string(),
+        token_url: "https://musicbrainz.org/oauth2/token".to_string(),
+        scopes: vec!["profile".to_string(), "collection".to_string()],
+        redirect_port: 19284,
+        extra_auth_params: vec![("access_type".to_string(), "offline".to_string())],
+    }
+}
+
+/// Send an authenticated request, mapping 401 to `Unauthorized` so the
+/// caller can refresh the token and retry
+async fn send_authenticated(
+    request: reqwest::RequestBuilder,
+    access_token: &str,
+) -> Result<reqwest::Response, MusicBrainzError> {
+    wait_for_rate_limit().await;
+
+    let response = request
+        .bearer_auth(access_token)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| MusicBrainzError::Api(format!("HTTP request failed: {}", e)))?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(MusicBrainzError::Unauthorized);
+    }
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(MusicBrainzError::Api(format!(
+            "MusicBrainz API returned status {}: {}",
+            status, error_text
+        )));
+    }
+    Ok(response)
+}
+
+/// Username of the account an access token belongs to
+pub async fn fetch_username(access_token: &str) -> Result<String, MusicBrainzError> {
+    let response = send_authenticated(
+        http_client().get("https://musicbrainz.org/oauth2/userinfo"),
+        access_token,
+    )
+    .await?;
+    let user: UserInfoResponse = response
+        .json()
+        .await
+        .map_err(|e| MusicBrainzError::Api(format!("Failed to parse JSON: {}", e)))?;
+    Ok(user.sub)
+}
+
+/// Release collections of an account, including private ones
+pub async fn list_release_collections(
+    username: &str,
+    access_token: &str,
+) -> Result<Vec<MbCollection>, MusicBrainzError> {
+    let response = send_authenticated(
+        http_client()
+            .get("https://musicbrainz.org/ws/2/collection")
+            .query(&[("editor", username), ("limit", "100")]),
+        access_token,
+    )
+    .await?;
+    let list: CollectionListResponse = response
+        .json()
+        .await
+        .map_err(|e| MusicBrainzError::Api(format!("Failed to parse JSON: {}", e)))?;
+
+    Ok(list
+        .collections
+        .into_iter()
+        .filter(|c| c.entity_type == "release")
+        .map(|c| MbCollection {
+            id: c.id,
+            name: c.name,
+            release_count: c.release_count,
+        })
+        .collect())
+}
+
+fn collection_releases_url(collection_id: &str, release_ids: &[String]) -> String {
+    format!(
+        "https://musicbrainz.org/ws/2/collection/{}/releases/{}",
+        collection_id,
+        release_ids.join(";"),
+    )
+}
+
+/// Add releases to a collection. At most `COLLECTION_BATCH_SIZE` per call.
+pub async fn add_releases_to_collection(
+    collection_id: &str,
+    release_ids: &[String],
+    access_token: &str,
+) -> Result<(), MusicBrainzError> {
+    info!(
+        "MusicBrainz: Adding {} releases to collection {}",
+        release_ids.len(),
+        collection_id
+    );
+    send_authenticated(
+        http_client()
+            .put(collection_releases_url(collection_id, release_ids))
+            .query(&[("client", "bae-1.0")]),
+        access_token,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Remove releases from a collection. At most `COLLECTION_BATCH_SIZE` per call.
+pub async fn remove_releases_from_collection(
+    collection_id: &str,
+    release_ids: &[String],
+    access_token: &str,
+) -> Result<(), MusicBrainzError> {
+    info!(
+        "MusicBrainz: Removing {} releases from collection {}",
+        release_ids.len(),
+        collection_id
+    );
+    send_authenticated(
+        http_client()
+            .delete(collection_releases_url(collection_id, release_ids))
+            .query(&[("client", "bae-1.0")]),
+        access_token,
+    )
+    .await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -986,6 +1263,7 @@ mod tests {
                     name: Some("Test Artist".to_string()),
                     sort_name: Some("Artist, Test".to_string()),
                 }),
+                joinphrase: None,
             }],
             release_group: Some(MbReleaseGroupRef {
                 id: Some("rg-789".to_string()),
@@ -1001,6 +1279,7 @@ mod tests {
             }],
             media: vec![MbMedium {
                 format: Some("CD".to_string()),
+                title: None,
                 tracks: vec![],
             }],
             relations: vec![],
@@ -1031,6 +1310,7 @@ mod tests {
             media: vec![
                 MbMedium {
                     format: None,
+                    title: None,
                     tracks: vec![
                         MbTrack {
                             position: Some(1),
@@ -1038,6 +1318,7 @@ mod tests {
                             title: Some("Track 1".to_string()),
                             length: None,
                             recording: None,
+                            artist_credit: vec![],
                         },
                         MbTrack {
                             position: Some(2),
@@ -1045,17 +1326,20 @@ mod tests {
                             title: Some("Track 2".to_string()),
                             length: None,
                             recording: None,
+                            artist_credit: vec![],
                         },
                     ],
                 },
                 MbMedium {
                     format: None,
+                    title: None,
                     tracks: vec![MbTrack {
                         position: Some(1),
                         number: None,
                         title: Some("Track 1 Disc 2".to_string()),
                         length: None,
                         recording: None,
+                        artist_credit: vec![],
                     }],
                 },
             ],
@@ -1189,4 +1473,23 @@ mod tests {
         assert!(response.relations.is_empty());
         assert_eq!(response.track_count(), 0);
     }
+
+    #[test]
+    fn test_disc_titles_skips_untitled_media() {
+        let json = r#"{
+            "id": "box-1",
+            "title": "Box Set",
+            "media": [
+                { "position": 1, "format": "CD", "title": "", "tracks": [] },
+                { "position": 2, "format": "CD", "title": "Live at Tidewater", "tracks": [] },
+                { "position": 3, "format": "CD", "tracks": [] }
+            ]
+        }"#;
+
+        let response: MbReleaseResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            response.disc_titles(),
+            vec![(2, "Live at Tidewater".to_string())]
+        );
+    }
 }
@@ -0,0 +1,658 @@
+//! Party mode: guests add tracks to the host's queue
+//!
+//! While a party is running, anyone with the join code can open the guest
+//! page served at `/party` (on the LAN, or through a link the host shares),
+//! search the library, and request tracks. Guests get a throwaway token, not
+//! an account: they can't browse anything but track search results, and
+//! each guest may only make a few requests per window. Requests wait for the
+//! host's approval unless the host turns approval off.
+
+use crate::library::SharedLibraryManager;
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+/// Requests each guest may make per `REQUEST_WINDOW`
+pub const MAX_REQUESTS_PER_GUEST: usize = 5;
+pub const REQUEST_WINDOW: Duration = Duration::from_secs(15 * 60);
+/// Requests waiting for the host at once; more are refused until some are handled
+const MAX_PENDING: usize = 50;
+/// Wrong join codes allowed before joins are refused for `JOIN_LOCKOUT`
+const MAX_FAILED_JOINS: u32 = 10;
+const JOIN_LOCKOUT: Duration = Duration::from_secs(60);
+const SEARCH_LIMIT: usize = 20;
+const GUEST_TOKEN_HEADER: &str = "x-party-token";
+const GUEST_PAGE: &str = include_str!("party_guest.html");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartyError {
+    NotRunning,
+    WrongCode,
+    UnknownGuest,
+    RateLimited,
+    TooManyPending,
+    Throttled,
+}
+
+impl PartyError {
+    fn message(&self) -> &'static str {
+        match self {
+            PartyError::NotRunning => "The party is over",
+            PartyError::WrongCode => "Wrong party code",
+            PartyError::UnknownGuest => "Join the party first",
+            PartyError::RateLimited => "You've requested a lot -- try again in a few minutes",
+            PartyError::TooManyPending => "The host has a backlog of requests, try again soon",
+            PartyError::Throttled => "Too many wrong codes, try again in a minute",
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            PartyError::NotRunning => StatusCode::GONE,
+            PartyError::WrongCode | PartyError::UnknownGuest => StatusCode::UNAUTHORIZED,
+            PartyError::RateLimited | PartyError::TooManyPending | PartyError::Throttled => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+}
+
+/// A guest's track request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartyRequest {
+    pub id: String,
+    pub guest_name: String,
+    pub track_id: String,
+    pub track_title: String,
+    pub artist_name: String,
+}
+
+/// What happened to a request
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestOutcome {
+    /// Waiting for the host
+    Pending,
+    /// Added to the queue
+    Queued,
+}
+
+#[derive(Debug, Clone)]
+pub enum PartyEvent {
+    /// The party started or stopped, or its pending requests changed
+    Changed,
+    /// A track was approved and should be added to the host's queue
+    Queue(String),
+}
+
+/// Snapshot of the running party for the host's UI
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartyStatus {
+    pub code: String,
+    pub require_approval: bool,
+    pub guest_count: usize,
+    pub pending: Vec<PartyRequest>,
+}
+
+struct ActiveParty {
+    code: String,
+    require_approval: bool,
+    /// Guest token -> guest
+    guests: HashMap<String, Guest>,
+    pending: Vec<PartyRequest>,
+    failed_joins: u32,
+    joins_locked_until: Option<Instant>,
+}
+
+struct Guest {
+    name: String,
+    /// When this guest's recent requests were made, oldest first
+    recent_requests: VecDeque<Instant>,
+}
+
+/// The host's party, shared by the guest routes and the host UI
+pub struct PartySession {
+    party: Mutex<Option<ActiveParty>>,
+    event_tx: broadcast::Sender<PartyEvent>,
+}
+
+impl Default for PartySession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartySession {
+    pub fn new() -> Self {
+        let (event_tx, _) = broadcast::channel(32);
+        Self {
+            party: Mutex::new(None),
+            event_tx,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PartyEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Start a party with a fresh join code, ending any party in progress.
+    pub fn start(&self, require_approval: bool) -> String {
+        let code = generate_join_code();
+        *self.party.lock().unwrap() = Some(ActiveParty {
+            code: code.clone(),
+            require_approval,
+            guests: HashMap::new(),
+            pending: Vec::new(),
+            failed_joins: 0,
+            joins_locked_until: None,
+        });
+
+        info!("Party started");
+
+        let _ = self.event_tx.send(PartyEvent::Changed);
+        code
+    }
+
+    /// End the party. Guest tokens and pending requests are dropped.
+    pub fn stop(&self) {
+        if self.party.lock().unwrap().take().is_some() {
+            info!("Party ended");
+
+            let _ = self.event_tx.send(PartyEvent::Changed);
+        }
+    }
+
+    pub fn status(&self) -> Option<PartyStatus> {
+        self.party
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|party| PartyStatus {
+                code: party.code.clone(),
+                require_approval: party.require_approval,
+                guest_count: party.guests.len(),
+                pending: party.pending.clone(),
+            })
+    }
+
+    /// Turn host approval on or off. Turning it off queues everything pending.
+    pub fn set_require_approval(&self, require_approval: bool) {
+        let released = {
+            let mut guard = self.party.lock().unwrap();
+            let Some(party) = guard.as_mut() else {
+                return;
+            };
+            party.require_approval = require_approval;
+            if require_approval {
+                Vec::new()
+            } else {
+                std::mem::take(&mut party.pending)
+            }
+        };
+        for request in released {
+            let _ = self.event_tx.send(PartyEvent::Queue(request.track_id));
+        }
+        let _ = self.event_tx.send(PartyEvent::Changed);
+    }
+
+    /// Admit a guest with the join code, returning their token.
+    pub fn join(&self, code: &str, name: &str) -> Result<String, PartyError> {
+        self.join_at(code, name, Instant::now())
+    }
+
+    fn join_at(&self, code: &str, name: &str, now: Instant) -> Result<String, PartyError> {
+        let mut guard = self.party.lock().unwrap();
+        let party = guard.as_mut().ok_or(PartyError::NotRunning)?;
+        if party.joins_locked_until.is_some_and(|until| until > now) {
+            return Err(PartyError::Throttled);
+        }
+        if !code.trim().eq_ignore_ascii_case(&party.code) {
+            party.failed_joins += 1;
+            if party.failed_joins >= MAX_FAILED_JOINS {
+                party.failed_joins = 0;
+                party.joins_locked_until = Some(now + JOIN_LOCKOUT);
+
+                warn!("Too many wrong party codes, refusing joins for {JOIN_LOCKOUT:?}");
+            }
+            return Err(PartyError::WrongCode);
+        }
+
+        let mut bytes = [0u8; 16];
+        rand::rng().fill(&mut bytes);
+        let token = hex::encode(bytes);
+        let name = match name.trim() {
+            "" => "Guest".to_string(),
+            name => name.chars().take(32).collect(),
+        };
+        party.guests.insert(
+            token.clone(),
+            Guest {
+                name,
+                recent_requests: VecDeque::new(),
+            },
+        );
+        drop(guard);
+
+        let _ = self.event_tx.send(PartyEvent::Changed);
+        Ok(token)
+    }
+
+    fn check_guest(&self, token: &str) -> Result<(), PartyError> {
+        let guard = self.party.lock().unwrap();
+        let party = guard.as_ref().ok_or(PartyError::NotRunning)?;
+        party
+            .guests
+            .contains_key(token)
+            .then_some(())
+            .ok_or(PartyError::UnknownGuest)
+    }
+
+    /// Request a track on behalf of a guest.
+    pub fn request_track(
+        &self,
+        token: &str,
+        track_id: &str,
+        track_title: &str,
+        artist_name: &str,
+    ) -> Result<RequestOutcome, PartyError> {
+        self.request_track_at(token, track_id, track_title, artist_name, Instant::now())
+    }
+
+    fn request_track_at(
+        &self,
+        token: &str,
+        track_id: &str,
+        track_title: &str,
+        artist_name: &str,
+        now: Instant,
+    ) -> Result<RequestOutcome, PartyError> {
+        let mut guard = self.party.lock().unwrap();
+        let party = guard.as_mut().ok_or(PartyError::NotRunning)?;
+        let require_approval = party.require_approval;
+        if require_approval && party.pending.len() >= MAX_PENDING {
+            return Err(PartyError::TooManyPending);
+        }
+        let guest = party
+            .guests
+            .get_mut(token)
+            .ok_or(PartyError::UnknownGuest)?;
+        while guest
+            .recent_requests
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= REQUEST_WINDOW)
+        {
+            guest.recent_requests.pop_front();
+        }
+        if guest.recent_requests.len() >= MAX_REQUESTS_PER_GUEST {
+            return Err(PartyError::RateLimited);
+        }
+        guest.recent_requests.push_back(now);
+
+        if !require_approval {
+            drop(guard);
+            let _ = self.event_tx.send(PartyEvent::Queue(track_id.to_string()));
+            return Ok(RequestOutcome::Queued);
+        }
+
+        let request = PartyRequest {
+            id: uuid::Uuid::new_v4().to_string(),
+            guest_name: guest.name.clone(),
+            track_id: track_id.to_string(),
+            track_title: track_title.to_string(),
+            artist_name: artist_name.to_string(),
+        };
+        party.pending.push(request);
+        drop(guard);
+
+        let _ = self.event_tx.send(PartyEvent::Changed);
+        Ok(RequestOutcome::Pending)
+    }
+
+    /// Approve a pending request, queueing its track.
+    pub fn approve(&self, request_id: &str) {
+        if let Some(request) = self.take_pending(request_id) {
+            let _ = self.event_tx.send(PartyEvent::Queue(request.track_id));
+        }
+    }
+
+    /// Drop a pending request without queueing it.
+    pub fn reject(&self, request_id: &str) {
+        self.take_pending(request_id);
+    }
+
+    fn take_pending(&self, request_id: &str) -> Option<PartyRequest> {
+        let request = {
+            let mut guard = self.party.lock().unwrap();
+            let pending = &mut guard.as_mut()?.pending;
+            let index = pending.iter().position(|r| r.id == request_id)?;
+            pending.remove(index)
+        };
+        let _ = self.event_tx.send(PartyEvent::Changed);
+        Some(request)
+    }
+}
+
+/// Six characters without look-alikes, easy to read out across a room
+fn generate_join_code() -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+    let mut rng = rand::rng();
+    (0..6)
+        .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())] as char)
+        .collect()
+}
+
+/// This machine's address on the local network, for the guest link.
+///
+/// Connecting a UDP socket picks the outbound interface without sending anything.
+pub fn lan_address() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+// =============================================================================
+// Guest routes
+// =============================================================================
+
+#[derive(Clone)]
+struct PartyRouteState {
+    party: Arc<PartySession>,
+    library_manager: SharedLibraryManager,
+}
+
+#[derive(Deserialize)]
+struct JoinRequest {
+    code: String,
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Serialize)]
+struct JoinResponse {
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+}
+
+#[derive(Serialize)]
+struct GuestTrack {
+    id: String,
+    title: String,
+    artist: String,
+    album: String,
+}
+
+#[derive(Deserialize)]
+struct TrackRequest {
+    track_id: String,
+}
+
+#[derive(Serialize)]
+struct TrackRequestResponse {
+    outcome: RequestOutcome,
+}
+
+#[derive(Serialize)]
+struct PartyErrorBody {
+    error: String,
+}
+
+/// Routes for party guests: the guest page, joining, search and requests.
+///
+/// These sit outside the Subsonic auth middleware; everything but the page
+/// and joining needs a guest token from `/party/join`.
+pub fn create_party_router(
+    party: Arc<PartySession>,
+    library_manager: SharedLibraryManager,
+) -> Router {
+    Router::new()
+        .route("/party", get(guest_page))
+        .route("/party/join", post(join))
+        .route("/party/search", get(search))
+        .route("/party/request", post(request_track))
+        .with_state(PartyRouteState {
+            party,
+            library_manager,
+        })
+}
+
+async fn guest_page() -> Html<&'static str> {
+    Html(GUEST_PAGE)
+}
+
+async fn join(State(state): State<PartyRouteState>, Json(request): Json<JoinRequest>) -> Response {
+    match state.party.join(&request.code, &request.name) {
+        Ok(token) => {
+            info!("Guest joined the party");
+
+            Json(JoinResponse { token }).into_response()
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+async fn search(
+    State(state): State<PartyRouteState>,
+    headers: HeaderMap,
+    Query(query): Query<SearchQuery>,
+) -> Response {
+    if let Err(e) = state.party.check_guest(guest_token(&headers)) {
+        return error_response(e);
+    }
+    match state
+        .library_manager
+        .get()
+        .search_library(&query.q, SEARCH_LIMIT)
+        .await
+    {
+        Ok(results) => {
+            let tracks: Vec<GuestTrack> = results
+                .tracks
+                .into_iter()
+                .map(|t| GuestTrack {
+                    id: t.id,
+                    title: t.title,
+                    artist: t.artist_name,
+                    album: t.album_title,
+                })
+                .collect();
+            Json(tracks).into_response()
+        }
+        Err(e) => {
+            warn!("Party search failed: {e}");
+
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+async fn request_track(
+    State(state): State<PartyRouteState>,
+    headers: HeaderMap,
+    Json(request): Json<TrackRequest>,
+) -> Response {
+    let token = guest_token(&headers);
+    if let Err(e) = state.party.check_guest(token) {
+        return error_response(e);
+    }
+
+    let library_manager = state.library_manager.get();
+    let track = match library_manager.get_track(&request.track_id).await {
+        Ok(Some(track)) => track,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            warn!("Failed to look up requested party track: {e}");
+
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let artist_name = library_manager
+        .get_artists_for_track(&track.id)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|a| a.name)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match state
+        .party
+        .request_track(token, &track.id, &track.title, &artist_name)
+    {
+        Ok(outcome) => Json(TrackRequestResponse { outcome }).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+fn guest_token(headers: &HeaderMap) -> &str {
+    headers
+        .get(GUEST_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+}
+
+fn error_response(error: PartyError) -> Response {
+    let body = PartyErrorBody {
+        error: error.message().to_string(),
+    };
+    (error.status(), Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joining_needs_the_running_party_code() {
+        let party = PartySession::new();
+        assert_eq!(party.join("ABC123", "Sam"), Err(PartyError::NotRunning));
+
+        let code = party.start(true);
+        assert_eq!(party.join("nope", "Sam"), Err(PartyError::WrongCode));
+        let token = party.join(&code.to_lowercase(), "Sam").unwrap();
+        assert!(party.check_guest(&token).is_ok());
+
+        party.stop();
+        assert_eq!(party.check_guest(&token), Err(PartyError::NotRunning));
+    }
+
+    #[test]
+    fn wrong_codes_lock_out_joins() {
+        let party = PartySession::new();
+        let code = party.start(true);
+        let now = Instant::now();
+        for _ in 0..MAX_FAILED_JOINS {
+            assert_eq!(
+                party.join_at("wrong", "Sam", now),
+                Err(PartyError::WrongCode)
+            );
+        }
+
+        assert_eq!(party.join_at(&code, "Sam", now), Err(PartyError::Throttled));
+        assert!(party.join_at(&code, "Sam", now + JOIN_LOCKOUT).is_ok());
+    }
+
+    #[test]
+    fn requests_wait_for_approval_and_queue_when_approved() {
+        let party = PartySession::new();
+        let mut events = party.subscribe();
+        let code = party.start(true);
+        let token = party.join(&code, "Sam").unwrap();
+
+        let outcome = party
+            .request_track(&token, "track-1", "Tidewater", "Glass Harbor")
+            .unwrap();
+
+        assert_eq!(outcome, RequestOutcome::Pending);
+        let pending = party.status().unwrap().pending;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].guest_name, "Sam");
+
+        party.approve(&pending[0].id);
+        assert!(party.status().unwrap().pending.is_empty());
+        let mut queued = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let PartyEvent::Queue(track_id) = event {
+                queued.push(track_id);
+            }
+        }
+        assert_eq!(queued, vec!["track-1".to_string()]);
+    }
+
+    #[test]
+    fn rejected_requests_are_not_queued() {
+        let party = PartySession::new();
+        let mut events = party.subscribe();
+        let code = party.start(true);
+        let token = party.join(&code, "Sam").unwrap();
+        party
+            .request_track(&token, "track-1", "Tidewater", "Glass Harbor")
+            .unwrap();
+        let request_id = party.status().unwrap().pending[0].id.clone();
+
+        party.reject(&request_id);
+
+        assert!(party.status().unwrap().pending.is_empty());
+        while let Ok(event) = events.try_recv() {
+            assert!(!matches!(event, PartyEvent::Queue(_)));
+        }
+    }
+
+    #[test]
+    fn guests_are_rate_limited_per_window() {
+        let party = PartySession::new();
+        let code = party.start(false);
+        let token = party.join(&code, "Sam").unwrap();
+        let now = Instant::now();
+        for _ in 0..MAX_REQUESTS_PER_GUEST {
+            assert_eq!(
+                party.request_track_at(&token, "t", "Low Light", "Tidewater", now),
+                Ok(RequestOutcome::Queued)
+            );
+        }
+
+        assert_eq!(
+            party.request_track_at(&token, "t", "Low Light", "Tidewater", now),
+            Err(PartyError::RateLimited)
+        );
+        assert_eq!(
+            party.request_track_at(&token, "t", "Low Light", "Tidewater", now + REQUEST_WINDOW),
+            Ok(RequestOutcome::Queued)
+        );
+    }
+
+    #[test]
+    fn turning_approval_off_queues_pending_requests() {
+        let party = PartySession::new();
+        let mut events = party.subscribe();
+        let code = party.start(true);
+        let token = party.join(&code, "Sam").unwrap();
+        party
+            .request_track(&token, "track-1", "Tidewater", "Glass Harbor")
+            .unwrap();
+
+        party.set_require_approval(false);
+
+        let status = party.status().unwrap();
+        assert!(!status.require_approval);
+        assert!(status.pending.is_empty());
+        let queued = std::iter::from_fn(|| events.try_recv().ok())
+            .filter(|e| matches!(e, PartyEvent::Queue(_)))
+            .count();
+        assert_eq!(queued, 1);
+    }
+}
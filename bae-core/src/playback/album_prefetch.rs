@@ -0,0 +1,231 @@
+//! Prefetch the next album while an album is played front to back
+//!
+//! Followed libraries stream from cloud storage, so the first seconds of
+//! every album pay for a cold fetch. Once the current album is being played
+//! in order, the album most likely to follow is downloaded whole into the
+//! cache: the next album in the queue if there is one, otherwise the same
+//! artist's next album by year. Files are cached as stored (encrypted), under
+//! the same keys export uses.
+
+use crate::cache::{file_cache_key, CacheManager};
+use crate::cloud_storage::CloudStorage;
+use crate::db::{Database, DbAlbum, DbTrack};
+use crate::encryption::ContainerFormat;
+use crate::storage::{storage_path, DownloadPriority, ParallelDownloader};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Whether `current` is the track right after `previous` on the same release
+pub(crate) fn follows_on_album(previous: &DbTrack, current: &DbTrack) -> bool {
+    if previous.release_id != current.release_id {
+        return false;
+    }
+    let (Some(prev_track), Some(track)) = (previous.track_number, current.track_number) else {
+        return false;
+    };
+    let prev_disc = previous.disc_number.unwrap_or(1);
+    let disc = current.disc_number.unwrap_or(1);
+    (disc == prev_disc && track == prev_track + 1) || (disc == prev_disc + 1 && track == 1)
+}
+
+/// The album released after `current`, by year then title. Albums without a
+/// year can't be placed, so they're never picked.
+fn next_album_by_year<'a>(albums: &'a [DbAlbum], current: &DbAlbum) -> Option<&'a DbAlbum> {
+    let year = current.year?;
+    albums
+        .iter()
+        .filter(|a| a.id != current.id)
+        .filter_map(|a| a.year.map(|y| (y, a)))
+        .filter(|(y, a)| (*y, a.title.as_str()) > (year, current.title.as_str()))
+        .min_by(|(ya, a), (yb, b)| (ya, &a.title).cmp(&(yb, &b.title)))
+        .map(|(_, a)| a)
+}
+
+/// Release most likely to be played after `current`'s: the first queued
+/// track from another release, else the same artist's next album
+async fn predict_next_release(
+    db: &Database,
+    current: &DbTrack,
+    queued: &[String],
+) -> Result<Option<String>, sqlx::Error> {
+    for track_id in queued {
+        if let Some(track) = db.get_track_by_id(track_id).await? {
+            if track.release_id != current.release_id {
+                return Ok(Some(track.release_id));
+            }
+        }
+    }
+
+    let Some(release) = db.get_release_by_id(&current.release_id).await? else {
+        return Ok(None);
+    };
+    let Some(album) = db.get_album_by_id(&release.album_id).await? else {
+        return Ok(None);
+    };
+    let Some(artist) = db
+        .get_artists_for_album(&album.id)
+        .await?
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let albums = db.get_albums_for_artist(&artist.id).await?;
+    let Some(next) = next_album_by_year(&albums, &album) else {
+        return Ok(None);
+    };
+
+    let releases = db.get_releases_for_album(&next.id).await?;
+    let release = releases
+        .iter()
+        .find(|r| next.cover_release_id.as_deref() == Some(r.id.as_str()))
+        .or(releases.first());
+    Ok(release.map(|r| r.id.clone()))
+}
+
+/// Download the audio files of the album likely to follow `current` into
+/// the cache, skipping files that are already there
+pub(crate) async fn prefetch_next_album(
+    db: &Database,
+    storage: Arc<dyn CloudStorage>,
+    cache: &CacheManager,
+    parallelism: usize,
+    current: &DbTrack,
+    queued: &[String],
+) {
+    let release_id = match predict_next_release(db, current, queued).await {
+        Ok(Some(id)) => id,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Failed to pick an album to prefetch: {}", e);
+            return;
+        }
+    };
+
+    let tracks = match db.get_tracks_for_release(&release_id).await {
+        Ok(tracks) => tracks,
+        Err(e) => {
+            warn!("Failed to load tracks to prefetch: {}", e);
+            return;
+        }
+    };
+
+    let mut file_ids: Vec<String> = Vec::new();
+    for track in &tracks {
+        if let Ok(Some(format)) = db.get_audio_format_by_track_id(&track.id).await {
+            if let Some(file_id) = format.file_id {
+                if !file_ids.contains(&file_id) {
+                    file_ids.push(file_id);
+                }
+            }
+        }
+    }
+
+    info!(
+        "Prefetching {} files of release {}",
+        file_ids.len(),
+        release_id
+    );
+
+    let downloader = ParallelDownloader::new(storage, parallelism);
+    for file_id in file_ids {
+        let cache_key = file_cache_key(&file_id);
+        if cache.contains(&cache_key).await {
+            continue;
+        }
+        let Ok(Some(file)) = db.get_file_by_id(&file_id).await else {
+            continue;
+        };
+        let prefix = file.encryption_nonce.as_deref().unwrap_or_default();
+        let stored_len = match ContainerFormat::detect(prefix) {
+            Ok(format) => format.encrypted_len(file.file_size as u64),
+            Err(e) => {
+                warn!("Can't prefetch file {}: {}", file_id, e);
+                continue;
+            }
+        };
+
+        match downloader
+            .download_range_to_vec(
+                &storage_path(&file_id),
+                0,
+                stored_len,
+                DownloadPriority::Export,
+            )
+            .await
+        {
+            Ok((data, stats)) => {
+                debug!(
+                    "Prefetched file {} ({} bytes, {:.0} KB/s)",
+                    file_id,
+                    stats.bytes,
+                    stats.bytes_per_sec() / 1024.0
+                );
+
+                if let Err(e) = cache.put(&cache_key, &data).await {
+                    warn!("Failed to cache prefetched file {}: {}", file_id, e);
+                }
+            }
+            Err(e) => {
+                warn!("Failed to prefetch file {}: {}", file_id, e);
+
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(release_id: &str, disc: Option<i32>, number: i32) -> DbTrack {
+        let mut track = DbTrack::new_test(release_id, "t", "Pacific Standard", Some(number));
+        track.disc_number = disc;
+        track
+    }
+
+    fn album(title: &str, year: Option<i32>) -> DbAlbum {
+        let mut album = DbAlbum::new_test(title);
+        album.year = year;
+        album
+    }
+
+    #[test]
+    fn test_in_order_listening_is_detected() {
+        assert!(follows_on_album(
+            &track("r1", None, 3),
+            &track("r1", None, 4)
+        ));
+        assert!(follows_on_album(
+            &track("r1", Some(1), 12),
+            &track("r1", Some(2), 1)
+        ));
+        assert!(!follows_on_album(
+            &track("r1", None, 3),
+            &track("r1", None, 7)
+        ));
+        assert!(!follows_on_album(
+            &track("r1", None, 3),
+            &track("r2", None, 4)
+        ));
+    }
+
+    #[test]
+    fn test_next_album_is_the_following_year() {
+        let current = album("Low Light", Some(2012));
+        let albums = vec![
+            album("Tidewater", Some(2015)),
+            current.clone(),
+            album("Glass Harbor", Some(2009)),
+            album("Pacific Standard", Some(2013)),
+            album("Undated", None),
+        ];
+
+        let next = next_album_by_year(&albums, &current).unwrap();
+        assert_eq!(next.title, "Pacific Standard");
+
+        let latest = albums[0].clone();
+        assert!(next_album_by_year(&albums, &latest).is_none());
+    }
+}
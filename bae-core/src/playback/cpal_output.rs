@@ -1,3 +1,4 @@
+use crate::playback::metrics::PlaybackMetrics;
 use crate::playback::streaming_source::StreamingPcmSource;
 use cpal::traits::{DeviceTrait, HostTrait};
 use cpal::{Device, Stream, StreamConfig};
@@ -5,6 +6,7 @@ use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
 use std::sync::Arc;
 use std::sync::{mpsc, Mutex};
+use std::time::Instant;
 use tracing::{error, info, trace, warn};
 
 /// Audio output state - directly controls what the audio callback does.
@@ -59,11 +61,17 @@ pub struct AudioOutput {
     stream_config: StreamConfig,
     state: Arc<AtomicU8>,
     volume: Arc<AtomicU32>,
+    /// Extra attenuation while ducked, applied on top of the user's volume
+    duck_gain: Arc<AtomicU32>,
+    metrics: Arc<PlaybackMetrics>,
 }
 
+/// Gain applied while ducked for other audio (x10000)
+const DUCKED_GAIN: u32 = 3000;
+
 impl AudioOutput {
-    /// Create a new audio output manager
-    pub fn new() -> Result<Self, AudioError> {
+    /// Create a new audio output manager that records stalls into `metrics`
+    pub fn new(metrics: Arc<PlaybackMetrics>) -> Result<Self, AudioError> {
         let host = cpal::default_host();
         let device = host
             .default_output_device()
@@ -89,6 +97,8 @@ impl AudioOutput {
             stream_config,
             state: Arc::new(AtomicU8::new(AudioState::Stopped as u8)),
             volume: Arc::new(AtomicU32::new(initial_volume)),
+            duck_gain: Arc::new(AtomicU32::new(10000)),
+            metrics,
         })
     }
 
@@ -96,6 +106,9 @@ impl AudioOutput {
     ///
     /// Pulls f32 samples from a `StreamingPcmSource` ring buffer fed by a decoder thread.
     /// Handles buffer underrun with silence.
+    ///
+    /// With `requested_at`, the delay until the first samples are played is
+    /// recorded as the track's time to first audio.
     pub fn create_stream(
         &mut self,
         source: Arc<Mutex<StreamingPcmSource>>,
@@ -103,6 +116,7 @@ impl AudioOutput {
         source_channels: u32,
         position_tx: mpsc::Sender<std::time::Duration>,
         completion_tx: mpsc::Sender<()>,
+        requested_at: Option<Instant>,
     ) -> Result<Stream, AudioError> {
         let output_sample_rate = self.stream_config.sample_rate.0;
         let output_channels = self.stream_config.channels as usize;
@@ -111,12 +125,18 @@ impl AudioOutput {
 
         let state = self.state.clone();
         let volume = self.volume.clone();
+        let duck_gain = self.duck_gain.clone();
+        let metrics = self.metrics.clone();
 
         let mut resample_buffer: Vec<f32> = Vec::new();
         let mut resample_pos = 0usize;
         let mut last_position_update = std::time::Instant::now();
         let position_update_interval = std::time::Duration::from_millis(250);
         let mut completion_sent = false;
+        let mut awaiting_first_audio = requested_at;
+        // Running dry before the first samples is startup, not an underrun
+        let mut has_played = false;
+        let mut starved = false;
 
         let stream = self
             .device
@@ -129,7 +149,9 @@ impl AudioOutput {
                         return;
                     }
 
-                    let vol = volume.load(Ordering::Relaxed) as f32 / 10000.0;
+                    let vol = volume.load(Ordering::Relaxed) as f32 / 10000.0
+                        * duck_gain.load(Ordering::Relaxed) as f32
+                        / 10000.0;
                     let mut output_pos = 0;
 
                     // Try to lock the source (non-blocking in audio callback)
@@ -166,11 +188,22 @@ impl AudioOutput {
                                 } else {
                                     // Buffer underrun - output silence and continue
                                     trace!("Streaming buffer underrun");
+
+                                    if has_played && !starved {
+                                        metrics.record_underrun();
+                                    }
+                                    starved = true;
                                     data[output_pos..].fill(0.0);
                                     return;
                                 }
                             }
 
+                            starved = false;
+                            has_played = true;
+                            if let Some(requested_at) = awaiting_first_audio.take() {
+                                metrics.record_first_audio(requested_at.elapsed());
+                            }
+
                             raw_samples.truncate(read);
                             resample_buffer.clear();
                             resample_pos = 0;
@@ -270,9 +303,15 @@ impl AudioOutput {
         self.volume
             .store((volume.clamp(0.0, 1.0) * 10000.0) as u32, Ordering::Relaxed);
     }
+
+    /// Lower the output below the user's volume, or restore it
+    pub fn set_ducked(&self, ducked: bool) {
+        let gain = if ducked { DUCKED_GAIN } else { 10000 };
+        self.duck_gain.store(gain, Ordering::Relaxed);
+    }
 }
 impl Default for AudioOutput {
     fn default() -> Self {
-        Self::new().expect("Failed to initialize audio output")
+        Self::new(Arc::default()).expect("Failed to initialize audio output")
     }
 }
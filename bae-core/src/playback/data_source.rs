@@ -4,8 +4,11 @@
 //! - Local files (non-storage releases, or storage releases with local backend)
 //! - Cloud storage (storage releases with cloud backend)
 
-use crate::encryption::EncryptionService;
+use crate::encryption::{ContainerFormat, EncryptionService};
 use crate::playback::sparse_buffer::SharedSparseBuffer;
+use crate::storage::{
+    chunk_aligned_part_size, DownloadPriority, DownloadStats, ParallelDownloader, Readahead,
+};
 use std::sync::Arc;
 use tracing::{debug, error, info};
 
@@ -135,6 +138,11 @@ pub struct CloudStorageReader {
     /// When set with start/end byte range, uses chunked decryption
     /// to avoid downloading entire file.
     encryption_nonce: Option<Vec<u8>>,
+    /// Concurrent range requests for byte-range downloads
+    parallelism: usize,
+    /// Bytes a byte-range download may run ahead of the buffer's reader,
+    /// None to download as fast as possible
+    readahead: Option<u64>,
 }
 
 impl CloudStorageReader {
@@ -150,6 +158,8 @@ impl CloudStorageReader {
             encryption_service,
             encrypted,
             encryption_nonce: None,
+            parallelism: crate::storage::DEFAULT_DOWNLOAD_PARALLELISM,
+            readahead: None,
         }
     }
 
@@ -159,6 +169,39 @@ impl CloudStorageReader {
         self.encryption_nonce = nonce;
         self
     }
+
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Keep byte-range downloads within `window` bytes of where the buffer
+    /// is being read, fetching the parts inside the window in parallel.
+    pub fn with_readahead(mut self, window: u64) -> Self {
+        self.readahead = Some(window);
+        self
+    }
+}
+
+/// Readahead that follows the reader of `buffer`. `to_range` maps a
+/// position in the read data (after `headers_len` bytes of headers) to an
+/// offset into the downloaded range.
+fn follow_reader(
+    buffer: &SharedSparseBuffer,
+    window: u64,
+    headers_len: u64,
+    to_range: impl Fn(u64) -> u64 + Send + Sync + 'static,
+) -> Readahead {
+    let buffer = buffer.clone();
+    Readahead {
+        window,
+        position: Box::new(move || {
+            if buffer.is_cancelled() {
+                return None;
+            }
+            Some(to_range(buffer.read_position().saturating_sub(headers_len)))
+        }),
+    }
 }
 
 impl AudioDataReader for CloudStorageReader {
@@ -168,6 +211,13 @@ impl AudioDataReader for CloudStorageReader {
         let encryption_service = self.encryption_service;
         let encrypted = self.encrypted;
         let encryption_nonce = self.encryption_nonce;
+        let readahead = self.readahead;
+        let downloader = ParallelDownloader::new(storage.clone(), self.parallelism);
+        let headers_len = config
+            .flac_headers
+            .as_ref()
+            .map(|h| h.len() as u64)
+            .unwrap_or(0);
 
         tokio::spawn(async move {
             info!(
@@ -184,29 +234,47 @@ impl AudioDataReader for CloudStorageReader {
                 if let (Some(nonce), Some(start), Some(end)) =
                     (&encryption_nonce, config.start_byte, config.end_byte)
                 {
-                    use crate::encryption::encrypted_chunk_range;
-
-                    // Calculate encrypted chunk range for efficient download
-                    let (chunk_start, chunk_end) = encrypted_chunk_range(start, end);
-
-                    info!(
-                        "CloudStorageReader: using efficient range request, plaintext [{}, {}) -> encrypted [{}, {})",
-                        start, end, chunk_start, chunk_end
-                    );
-
-                    download_encrypted_range_to_buffer(
-                        storage,
-                        &config.path,
-                        buffer.clone(),
-                        &encryption_service,
-                        nonce,
-                        start,
-                        end,
-                        chunk_start,
-                        chunk_end,
-                        config.flac_headers.as_deref(),
-                    )
-                    .await
+                    match ContainerFormat::detect(nonce) {
+                        Ok(format) => {
+                            // Calculate encrypted chunk range for efficient download
+                            let (chunk_start, chunk_end) = format.chunk_range(start, end);
+
+                            info!(
+                                "CloudStorageReader: using efficient range request, plaintext [{}, {}) -> encrypted [{}, {}), chunk size {}",
+                                start, end, chunk_start, chunk_end, format.chunk_size
+                            );
+
+                            let mut downloader = downloader.with_part_size(
+                                chunk_aligned_part_size(format.encrypted_chunk_size()),
+                            );
+                            if let Some(window) = readahead {
+                                downloader = downloader.with_readahead(follow_reader(
+                                    &buffer,
+                                    window,
+                                    headers_len,
+                                    move |pos| {
+                                        let (offset, _) =
+                                            format.chunk_range(start + pos, start + pos + 1);
+                                        offset.saturating_sub(chunk_start)
+                                    },
+                                ));
+                            }
+                            download_encrypted_range_to_buffer(
+                                &downloader,
+                                &config.path,
+                                buffer.clone(),
+                                &encryption_service,
+                                nonce,
+                                start,
+                                end,
+                                chunk_start,
+                                chunk_end,
+                                config.flac_headers.as_deref(),
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e.into()),
+                    }
                 } else {
                     // Fall back to full download (initial playback, no nonce available)
                     download_encrypted_to_buffer(
@@ -221,8 +289,17 @@ impl AudioDataReader for CloudStorageReader {
                     .await
                 }
             } else if let (Some(start), Some(end)) = (config.start_byte, config.end_byte) {
+                let downloader = match readahead {
+                    Some(window) => downloader.with_readahead(follow_reader(
+                        &buffer,
+                        window,
+                        headers_len,
+                        |pos| pos,
+                    )),
+                    None => downloader,
+                };
                 download_range_to_buffer(
-                    storage,
+                    &downloader,
                     &config.path,
                     buffer.clone(),
                     start,
@@ -240,9 +317,12 @@ impl AudioDataReader for CloudStorageReader {
                 .await
             };
 
+            // A buffer cancelled midway stops its download, which isn't a failure
             if let Err(e) = result {
-                error!("Cloud download failed: {:?}", e);
-                buffer.cancel();
+                if !buffer.is_cancelled() {
+                    error!("Cloud download failed: {:?}", e);
+                    buffer.cancel();
+                }
             }
         });
     }
@@ -276,29 +356,46 @@ async fn download_full_to_buffer(
 }
 
 async fn download_range_to_buffer(
-    storage: Arc<dyn crate::cloud_storage::CloudStorage>,
+    downloader: &ParallelDownloader,
     path: &str,
     buffer: SharedSparseBuffer,
     start: u64,
     end: u64,
     flac_headers: Option<&[u8]>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let data = storage.download_range(path, start, end - start).await?;
-
-    let mut buffer_pos: u64 = 0;
-
+    let headers_len = flac_headers.map(|h| h.len() as u64).unwrap_or(0);
     if let Some(headers) = flac_headers {
-        buffer.append_at(buffer_pos, headers);
-        buffer_pos += headers.len() as u64;
+        buffer.append_at(0, headers);
     }
 
-    buffer.append_at(buffer_pos, &data);
-    buffer_pos += data.len() as u64;
+    // Parts land in the sparse buffer as they arrive, so playback can
+    // start on the first part while the rest download.
+    let mut data_len: u64 = 0;
+    let stats = downloader
+        .download_range(
+            path,
+            start,
+            end,
+            DownloadPriority::Playback,
+            |offset, data| {
+                let pos = headers_len + (offset - start);
+                buffer.append_at(pos, &data);
+                data_len = data_len.max(offset - start + data.len() as u64);
+                Ok(())
+            },
+        )
+        .await?;
+
+    let buffer_pos = headers_len + data_len;
 
     debug!(
-        "CloudStorageReader: downloaded range {}-{} ({} bytes)",
-        start, end, buffer_pos
+        "CloudStorageReader: downloaded range {}-{} ({} bytes, {})",
+        start,
+        end,
+        buffer_pos,
+        format_stats(&stats)
     );
+
     buffer.set_total_size(buffer_pos);
     buffer.mark_eof();
 
@@ -357,16 +454,18 @@ async fn download_encrypted_to_buffer(
     Ok(())
 }
 
-/// Download encrypted data using range request with nonce from DB.
+/// Download encrypted data using range requests with nonce from DB.
 ///
-/// This is the efficient path for encrypted cloud seeks:
-/// - `nonce`: 24-byte nonce stored in DB at import time
+/// This is the efficient path for encrypted cloud reads:
+/// - `nonce`: container prefix (header + nonce) stored in DB at import time
 /// - `plaintext_start`, `plaintext_end`: Byte range we want in decrypted file
-/// - `chunk_start`, `chunk_end`: Encrypted byte range (from `encrypted_chunk_range`)
+/// - `chunk_start`, `chunk_end`: Encrypted byte range (from `ContainerFormat::chunk_range`)
 ///
-/// Downloads only the needed encrypted chunks, not the entire file.
+/// Downloads only the needed encrypted chunks, not the entire file. Each
+/// downloaded part holds whole chunks, so it is decrypted and buffered as
+/// soon as it arrives.
 pub async fn download_encrypted_range_to_buffer(
-    storage: Arc<dyn crate::cloud_storage::CloudStorage>,
+    downloader: &ParallelDownloader,
     path: &str,
     buffer: SharedSparseBuffer,
     encryption_service: &Option<Arc<EncryptionService>>,
@@ -381,41 +480,63 @@ pub async fn download_encrypted_range_to_buffer(
         .as_ref()
         .ok_or("Cannot play encrypted files: encryption not configured")?;
 
-    use crate::encryption::CHUNK_SIZE;
-
-    // Download only the needed encrypted chunks via range request
-    let encrypted_chunks = storage.download_range(path, chunk_start, chunk_end).await?;
+    use crate::cloud_storage::CloudStorageError;
 
-    // Calculate first chunk index for decrypt_range_with_offset
-    let first_chunk_index = plaintext_start / CHUNK_SIZE as u64;
-
-    // Decrypt using nonce from DB + partial chunks
-    let decrypted = enc
-        .decrypt_range_with_offset(
-            nonce,
-            &encrypted_chunks,
-            first_chunk_index,
-            plaintext_start,
-            plaintext_end,
-        )
-        .map_err(|e| format!("Decryption failed: {}", e))?;
-
-    let mut buffer_pos: u64 = 0;
+    let format = ContainerFormat::detect(nonce)?;
+    let chunk_size = format.chunk_size as u64;
+    let encrypted_chunk_size = format.encrypted_chunk_size();
 
+    let headers_len = flac_headers.map(|h| h.len() as u64).unwrap_or(0);
     if let Some(headers) = flac_headers {
-        buffer.append_at(buffer_pos, headers);
-        buffer_pos += headers.len() as u64;
+        buffer.append_at(0, headers);
     }
 
-    buffer.append_at(buffer_pos, &decrypted);
-    buffer_pos += decrypted.len() as u64;
+    let mut decrypted_len: u64 = 0;
+    let stats = downloader
+        .download_range(
+            path,
+            chunk_start,
+            chunk_end,
+            DownloadPriority::Playback,
+            |offset, encrypted_chunks| {
+                let first_chunk_index =
+                    (offset - format.prefix_len() as u64) / encrypted_chunk_size as u64;
+                let chunk_count = encrypted_chunks.len().div_ceil(encrypted_chunk_size) as u64;
+                let part_start = (first_chunk_index * chunk_size).max(plaintext_start);
+                let part_end = ((first_chunk_index + chunk_count) * chunk_size).min(plaintext_end);
+                if part_start >= part_end {
+                    return Ok(());
+                }
+
+                // Decrypt using nonce from DB + partial chunks
+                let decrypted = enc
+                    .decrypt_range_with_offset(
+                        nonce,
+                        &encrypted_chunks,
+                        first_chunk_index,
+                        part_start,
+                        part_end,
+                    )
+                    .map_err(|e| {
+                        CloudStorageError::Download(format!("Decryption failed: {}", e))
+                    })?;
+
+                let pos = part_start - plaintext_start;
+                buffer.append_at(headers_len + pos, &decrypted);
+                decrypted_len = decrypted_len.max(pos + decrypted.len() as u64);
+                Ok(())
+            },
+        )
+        .await?;
+
+    let buffer_pos = headers_len + decrypted_len;
 
     info!(
-        "CloudStorageReader: range request [{}, {}) -> {} encrypted bytes -> {} decrypted bytes",
+        "CloudStorageReader: range request [{}, {}) -> {} decrypted bytes ({})",
         chunk_start,
         chunk_end,
-        encrypted_chunks.len(),
-        decrypted.len()
+        decrypted_len,
+        format_stats(&stats)
     );
 
     buffer.set_total_size(buffer_pos);
@@ -424,6 +545,15 @@ pub async fn download_encrypted_range_to_buffer(
     Ok(())
 }
 
+fn format_stats(stats: &DownloadStats) -> String {
+    format!(
+        "{} bytes in {} requests, {:.0} KB/s",
+        stats.bytes,
+        stats.requests,
+        stats.bytes_per_sec() / 1024.0
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -615,7 +745,7 @@ mod tests {
     #[tokio::test]
     async fn test_encrypted_seek_uses_range_request() {
         use crate::cloud_storage::{CloudStorage, CloudStorageError};
-        use crate::encryption::{encrypted_chunk_range, EncryptionService, CHUNK_SIZE};
+        use crate::encryption::{ContainerFormat, EncryptionService, CHUNK_SIZE};
         use async_trait::async_trait;
         use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -675,10 +805,11 @@ mod tests {
         let buffer = create_sparse_buffer();
 
         // Calculate chunk range and call the function with nonce
-        let (chunk_start, chunk_end) = encrypted_chunk_range(plaintext_start, plaintext_end);
+        let (chunk_start, chunk_end) =
+            ContainerFormat::LEGACY.chunk_range(plaintext_start, plaintext_end);
 
         super::download_encrypted_range_to_buffer(
-            storage.clone(),
+            &ParallelDownloader::new(storage.clone(), 4),
             "test/file.enc",
             buffer.clone(),
             &encryption_service,
@@ -738,4 +869,123 @@ mod tests {
             "Decrypted data should match original plaintext at seek position"
         );
     }
+
+    #[tokio::test]
+    async fn test_encrypted_range_decrypts_each_part() {
+        use crate::encryption::{
+            ContainerFormat, EncryptionService, CHUNK_SIZE, ENCRYPTED_CHUNK_SIZE,
+        };
+        use crate::storage::LocalFileStorage;
+
+        // 10 chunks plus a short final chunk
+        let plaintext: Vec<u8> = (0..CHUNK_SIZE * 10 + 500)
+            .map(|i| (i % 241) as u8)
+            .collect();
+        let encryption_service = EncryptionService::new_with_key(&[0x17; 32]);
+        let encrypted_data = encryption_service.encrypt(&plaintext);
+        let nonce = encrypted_data[..24].to_vec();
+        let encryption_service = Some(Arc::new(encryption_service));
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&encrypted_data).unwrap();
+        temp_file.flush().unwrap();
+
+        // Three chunks per request, so the range spans several parts
+        let downloader = ParallelDownloader::new(Arc::new(LocalFileStorage), 3)
+            .with_part_size(3 * ENCRYPTED_CHUNK_SIZE as u64);
+
+        let plaintext_start = 1000;
+        let plaintext_end = plaintext.len() as u64;
+        let (chunk_start, chunk_end) =
+            ContainerFormat::LEGACY.chunk_range(plaintext_start, plaintext_end);
+        let buffer = create_sparse_buffer();
+        let headers = b"fLaC-headers".to_vec();
+
+        super::download_encrypted_range_to_buffer(
+            &downloader,
+            temp_file.path().to_str().unwrap(),
+            buffer.clone(),
+            &encryption_service,
+            &nonce,
+            plaintext_start,
+            plaintext_end,
+            chunk_start,
+            chunk_end,
+            Some(&headers),
+        )
+        .await
+        .expect("download should succeed");
+
+        let mut read_buf = vec![0u8; 8192];
+        let mut result = Vec::new();
+        while let Some(n) = buffer.read(&mut read_buf) {
+            if n == 0 {
+                break;
+            }
+            result.extend_from_slice(&read_buf[..n]);
+        }
+
+        let mut expected = headers.clone();
+        expected.extend_from_slice(&plaintext[plaintext_start as usize..]);
+        assert_eq!(result, expected);
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_range_with_large_chunks() {
+        use crate::encryption::{ContainerFormat, EncryptionService, CHUNK_SIZE};
+        use crate::storage::{chunk_aligned_part_size, LocalFileStorage};
+
+        let chunk_size = CHUNK_SIZE * 4;
+        let plaintext: Vec<u8> = (0..chunk_size * 5 + 300).map(|i| (i % 239) as u8).collect();
+        let encryption_service = EncryptionService::new_with_key(&[0x29; 32])
+            .with_chunk_size(chunk_size)
+            .unwrap();
+        let encrypted_data = encryption_service.encrypt(&plaintext);
+        let format = encryption_service.format();
+        let prefix = encrypted_data[..format.prefix_len()].to_vec();
+        let encryption_service = Some(Arc::new(encryption_service));
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(&encrypted_data).unwrap();
+        temp_file.flush().unwrap();
+
+        // Two chunks per request
+        let part_size = chunk_aligned_part_size(format.encrypted_chunk_size());
+        assert_eq!(part_size % format.encrypted_chunk_size() as u64, 0);
+        let downloader = ParallelDownloader::new(Arc::new(LocalFileStorage), 3)
+            .with_part_size(2 * format.encrypted_chunk_size() as u64);
+
+        let plaintext_start = chunk_size as u64 + 17;
+        let plaintext_end = plaintext.len() as u64;
+        let (chunk_start, chunk_end) = ContainerFormat::detect(&prefix)
+            .unwrap()
+            .chunk_range(plaintext_start, plaintext_end);
+        let buffer = create_sparse_buffer();
+
+        super::download_encrypted_range_to_buffer(
+            &downloader,
+            temp_file.path().to_str().unwrap(),
+            buffer.clone(),
+            &encryption_service,
+            &prefix,
+            plaintext_start,
+            plaintext_end,
+            chunk_start,
+            chunk_end,
+            None,
+        )
+        .await
+        .expect("download should succeed");
+
+        let mut read_buf = vec![0u8; 8192];
+        let mut result = Vec::new();
+        while let Some(n) = buffer.read(&mut read_buf) {
+            if n == 0 {
+                break;
+            }
+            result.extend_from_slice(&read_buf[..n]);
+        }
+
+        assert_eq!(result, &plaintext[plaintext_start as usize..]);
+    }
 }
@@ -1,4 +1,5 @@
 //! Playback error types
+use crate::error_code::{ErrorCode, HasErrorCode};
 use thiserror::Error;
 /// Errors that can occur during audio playback operations
 #[derive(Error, Debug)]
@@ -12,6 +13,9 @@ pub enum PlaybackError {
     /// Cloud storage download failed
     #[error("Cloud download failed: {0}")]
     CloudDownload(String),
+    /// The file is archived and being restored
+    #[error("Restoring from archive: {0}")]
+    Restoring(String),
     /// Decryption failed
     #[error("Decryption failed: {0}")]
     Decryption(String),
@@ -48,3 +52,17 @@ impl PlaybackError {
         Self::Io(std::io::Error::other(msg.into()))
     }
 }
+impl HasErrorCode for PlaybackError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            PlaybackError::Database(_) => ErrorCode::Database,
+            PlaybackError::NotFound(..) => ErrorCode::PlaybackNotFound,
+            PlaybackError::CloudDownload(_) => ErrorCode::StorageUnavailable,
+            PlaybackError::Restoring(_) => ErrorCode::StorageWarmingUp,
+            PlaybackError::Decryption(_) => ErrorCode::DecryptionFailed,
+            PlaybackError::InvalidFlac(_) => ErrorCode::PlaybackCorruptAudio,
+            PlaybackError::Io(_) => ErrorCode::Io,
+            PlaybackError::TaskFailed(_) => ErrorCode::Internal,
+        }
+    }
+}
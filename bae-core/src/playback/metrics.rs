@@ -0,0 +1,168 @@
+//! Playback quality-of-service counters
+//!
+//! Stutter reports are hard to reproduce, so the pipeline counts the events
+//! behind them for as long as the playback service runs: stalls in the audio
+//! callback, waits for buffered data after a seek, decode errors, and how long
+//! each track took to make a sound. Everything is a plain atomic so the audio
+//! callback can record without locking.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Counters shared by the playback service, its decoder threads and the audio callback
+#[derive(Debug)]
+pub struct PlaybackMetrics {
+    session_start: Instant,
+    tracks_started: AtomicU64,
+    underruns: AtomicU64,
+    buffering_waits: AtomicU64,
+    buffering_timeouts: AtomicU64,
+    buffering_ms: AtomicU64,
+    decode_errors: AtomicU64,
+    first_audio_count: AtomicU64,
+    first_audio_total_ms: AtomicU64,
+    first_audio_max_ms: AtomicU64,
+    first_audio_last_ms: AtomicU64,
+}
+
+/// Point-in-time copy of the counters
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaybackMetricsSnapshot {
+    /// How long the playback service has been running
+    pub session_duration: Duration,
+    pub tracks_started: u64,
+    /// Times the audio callback ran dry mid-track and played silence
+    pub underruns: u64,
+    /// Times playback waited for a seek buffer to fill
+    pub buffering_waits: u64,
+    /// Seek buffer waits that gave up
+    pub buffering_timeouts: u64,
+    pub total_buffering: Duration,
+    pub decode_errors: u64,
+    /// From the play request to the first samples reaching the output
+    pub last_time_to_first_audio: Option<Duration>,
+    pub avg_time_to_first_audio: Option<Duration>,
+    pub max_time_to_first_audio: Option<Duration>,
+}
+
+impl Default for PlaybackMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlaybackMetrics {
+    pub fn new() -> Self {
+        Self {
+            session_start: Instant::now(),
+            tracks_started: AtomicU64::new(0),
+            underruns: AtomicU64::new(0),
+            buffering_waits: AtomicU64::new(0),
+            buffering_timeouts: AtomicU64::new(0),
+            buffering_ms: AtomicU64::new(0),
+            decode_errors: AtomicU64::new(0),
+            first_audio_count: AtomicU64::new(0),
+            first_audio_total_ms: AtomicU64::new(0),
+            first_audio_max_ms: AtomicU64::new(0),
+            first_audio_last_ms: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record_track_started(&self) {
+        self.tracks_started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_underrun(&self) {
+        self.underruns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_buffering(&self, waited: Duration, timed_out: bool) {
+        self.buffering_waits.fetch_add(1, Ordering::Relaxed);
+        self.buffering_ms
+            .fetch_add(waited.as_millis() as u64, Ordering::Relaxed);
+        if timed_out {
+            self.buffering_timeouts.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_decode_errors(&self, count: u32) {
+        self.decode_errors
+            .fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_first_audio(&self, elapsed: Duration) {
+        let ms = elapsed.as_millis() as u64;
+        self.first_audio_last_ms.store(ms, Ordering::Relaxed);
+        self.first_audio_max_ms.fetch_max(ms, Ordering::Relaxed);
+        self.first_audio_total_ms.fetch_add(ms, Ordering::Relaxed);
+        self.first_audio_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> PlaybackMetricsSnapshot {
+        let first_audio_count = self.first_audio_count.load(Ordering::Relaxed);
+        let first_audio = |ms: u64| (first_audio_count > 0).then_some(Duration::from_millis(ms));
+        let total_ms = self.first_audio_total_ms.load(Ordering::Relaxed);
+
+        PlaybackMetricsSnapshot {
+            session_duration: self.session_start.elapsed(),
+            tracks_started: self.tracks_started.load(Ordering::Relaxed),
+            underruns: self.underruns.load(Ordering::Relaxed),
+            buffering_waits: self.buffering_waits.load(Ordering::Relaxed),
+            buffering_timeouts: self.buffering_timeouts.load(Ordering::Relaxed),
+            total_buffering: Duration::from_millis(self.buffering_ms.load(Ordering::Relaxed)),
+            decode_errors: self.decode_errors.load(Ordering::Relaxed),
+            last_time_to_first_audio: first_audio(self.first_audio_last_ms.load(Ordering::Relaxed)),
+            avg_time_to_first_audio: first_audio(total_ms / first_audio_count.max(1)),
+            max_time_to_first_audio: first_audio(self.first_audio_max_ms.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_to_first_audio_is_unset_until_a_track_plays() {
+        let metrics = PlaybackMetrics::new();
+        metrics.record_track_started();
+
+        let snapshot = metrics.snapshot();
+
+        assert_eq!(snapshot.tracks_started, 1);
+        assert_eq!(snapshot.last_time_to_first_audio, None);
+        assert_eq!(snapshot.avg_time_to_first_audio, None);
+    }
+
+    #[test]
+    fn aggregates_over_the_session() {
+        let metrics = PlaybackMetrics::new();
+        metrics.record_first_audio(Duration::from_millis(120));
+        metrics.record_first_audio(Duration::from_millis(480));
+        metrics.record_first_audio(Duration::from_millis(60));
+        metrics.record_buffering(Duration::from_millis(300), false);
+        metrics.record_buffering(Duration::from_secs(5), true);
+        metrics.record_decode_errors(2);
+        metrics.record_underrun();
+
+        let snapshot = metrics.snapshot();
+
+        assert_eq!(
+            snapshot.last_time_to_first_audio,
+            Some(Duration::from_millis(60))
+        );
+        assert_eq!(
+            snapshot.avg_time_to_first_audio,
+            Some(Duration::from_millis(220))
+        );
+        assert_eq!(
+            snapshot.max_time_to_first_audio,
+            Some(Duration::from_millis(480))
+        );
+        assert_eq!(snapshot.buffering_waits, 2);
+        assert_eq!(snapshot.buffering_timeouts, 1);
+        assert_eq!(snapshot.total_buffering, Duration::from_millis(5300));
+        assert_eq!(snapshot.decode_errors, 2);
+        assert_eq!(snapshot.underruns, 1);
+    }
+}
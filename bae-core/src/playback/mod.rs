@@ -1,18 +1,24 @@
+mod album_prefetch;
 mod cpal_output;
 pub mod data_source;
 mod error;
+pub mod metrics;
 mod pcm_source;
 pub mod progress;
 pub mod service;
 pub mod sparse_buffer;
 pub mod streaming_source;
 pub mod track_loader;
+pub mod transitions;
 
 pub use bae_common::RepeatMode;
 pub use error::PlaybackError;
+pub use metrics::{PlaybackMetrics, PlaybackMetricsSnapshot};
 pub use pcm_source::PcmSource;
 pub use progress::PlaybackProgress;
-pub use service::{FollowedSource, PlaybackHandle, PlaybackService, PlaybackState};
+pub use service::{
+    FollowedSource, LibraryCloudSource, PlaybackHandle, PlaybackService, PlaybackState,
+};
 pub use sparse_buffer::SharedSparseBuffer;
 pub use streaming_source::{create_streaming_pair, StreamingPcmSink, StreamingPcmSource};
 
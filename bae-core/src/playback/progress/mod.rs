@@ -1,4 +1,5 @@
 pub mod handle;
+use crate::error_code::ErrorCode;
 use crate::playback::service::PlaybackState;
 use bae_common::RepeatMode;
 pub use handle::PlaybackProgressHandle;
@@ -41,8 +42,13 @@ pub enum PlaybackProgress {
     RepeatModeChanged {
         mode: RepeatMode,
     },
+    /// Radio auto-continuation toggled
+    RadioChanged {
+        enabled: bool,
+    },
     /// Playback error occurred (e.g. storage offline)
     PlaybackError {
+        code: ErrorCode,
         message: String,
     },
     /// Volume level changed
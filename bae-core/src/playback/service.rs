@@ -20,15 +20,19 @@
 //! 6. State remains unchanged (Playing or Paused) - new stream inherits it
 //! 7. Send `Seeked` progress event
 
-use crate::cloud_storage::CloudStorage;
+use crate::cache::CacheManager;
+use crate::cloud_storage::{CloudStorage, CloudStorageError};
 use crate::db::{Database, DbTrack};
 use crate::encryption::EncryptionService;
+use crate::error_code::HasErrorCode;
 use crate::library::LibraryManager;
+use crate::playback::album_prefetch;
 use crate::playback::cpal_output::AudioOutput;
 use crate::playback::data_source::{
     AudioDataReader, AudioReadConfig, CloudStorageReader, LocalFileReader,
 };
 use crate::playback::error::PlaybackError;
+use crate::playback::metrics::{PlaybackMetrics, PlaybackMetricsSnapshot};
 use crate::playback::progress::{PlaybackProgress, PlaybackProgressHandle};
 use crate::playback::sparse_buffer::{create_sparse_buffer, SharedSparseBuffer};
 use crate::playback::{create_streaming_pair, StreamingPcmSource};
@@ -37,7 +41,7 @@ use cpal::traits::StreamTrait;
 use std::collections::VecDeque;
 use std::sync::{mpsc, Arc, Mutex};
 use tokio::sync::mpsc as tokio_mpsc;
-use tracing::{error, info, trace};
+use tracing::{error, info, trace, warn};
 
 /// Override source for playing tracks from a followed library.
 ///
@@ -47,6 +51,12 @@ pub struct FollowedSource {
     pub database: Database,
     pub cloud_storage: Arc<dyn CloudStorage>,
     pub encryption: EncryptionService,
+    /// Concurrent range requests when streaming a track
+    pub download_parallelism: usize,
+    /// Bytes a stream downloads ahead of playback
+    pub readahead: u64,
+    /// Where the next album is prefetched to, and read back from
+    pub cache: CacheManager,
 }
 
 // FollowedSource contains Database which isn't Debug
@@ -56,6 +66,23 @@ impl std::fmt::Debug for FollowedSource {
     }
 }
 
+/// The library's cloud home, for playing releases that are only kept there.
+pub struct LibraryCloudSource {
+    pub cloud_storage: Arc<dyn CloudStorage>,
+    /// Concurrent range requests when streaming a track
+    pub download_parallelism: usize,
+    /// Bytes a stream downloads ahead of playback
+    pub readahead: u64,
+    /// Where releases kept for offline play are read from before streaming
+    pub cache: CacheManager,
+}
+
+impl std::fmt::Debug for LibraryCloudSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LibraryCloudSource").finish_non_exhaustive()
+    }
+}
+
 /// Playback commands sent to the service
 #[derive(Debug)]
 pub enum PlaybackCommand {
@@ -71,6 +98,8 @@ pub enum PlaybackCommand {
     Previous,
     Seek(std::time::Duration),
     SetVolume(f32),
+    /// Temporarily lower the output without changing the volume setting
+    SetDucked(bool),
     AddToQueue(Vec<String>),
     AddNext(Vec<String>),
     InsertInQueue(Vec<String>, usize),
@@ -82,12 +111,16 @@ pub enum PlaybackCommand {
     ClearQueue,
     GetQueue,
     SetRepeatMode(RepeatMode),
+    /// Keep playing similar tracks from the library once the queue runs out
+    SetRadioEnabled(bool),
     /// Skip to a specific position in the queue (manual action, skip pregap)
     SkipTo(usize),
     /// Set a followed library as the audio source for subsequent Play commands.
     SetFollowedSource(FollowedSource),
     /// Clear the followed source, reverting to the local library.
     ClearFollowedSource,
+    /// Stream the library's cloud-only releases from its cloud home.
+    SetLibraryCloudSource(LibraryCloudSource),
 }
 /// Current playback state
 #[derive(Debug, Clone)]
@@ -121,6 +154,7 @@ pub enum PlaybackState {
 pub struct PlaybackHandle {
     command_tx: tokio_mpsc::UnboundedSender<PlaybackCommand>,
     progress_handle: PlaybackProgressHandle,
+    metrics: Arc<PlaybackMetrics>,
 }
 impl PlaybackHandle {
     pub fn play(&self, track_id: String) {
@@ -150,12 +184,19 @@ impl PlaybackHandle {
     pub fn set_volume(&self, volume: f32) {
         let _ = self.command_tx.send(PlaybackCommand::SetVolume(volume));
     }
+    pub fn set_ducked(&self, ducked: bool) {
+        let _ = self.command_tx.send(PlaybackCommand::SetDucked(ducked));
+    }
     pub async fn get_state(&self) -> PlaybackState {
         PlaybackState::Stopped
     }
     pub fn subscribe_progress(&self) -> tokio_mpsc::UnboundedReceiver<PlaybackProgress> {
         self.progress_handle.subscribe_all()
     }
+    /// Quality-of-service counters since the playback service started
+    pub fn metrics(&self) -> PlaybackMetricsSnapshot {
+        self.metrics.snapshot()
+    }
     pub fn add_to_queue(&self, track_ids: Vec<String>) {
         let _ = self.command_tx.send(PlaybackCommand::AddToQueue(track_ids));
     }
@@ -186,6 +227,11 @@ impl PlaybackHandle {
     pub fn set_repeat_mode(&self, mode: RepeatMode) {
         let _ = self.command_tx.send(PlaybackCommand::SetRepeatMode(mode));
     }
+    pub fn set_radio_enabled(&self, enabled: bool) {
+        let _ = self
+            .command_tx
+            .send(PlaybackCommand::SetRadioEnabled(enabled));
+    }
     pub fn skip_to(&self, index: usize) {
         let _ = self.command_tx.send(PlaybackCommand::SkipTo(index));
     }
@@ -199,6 +245,12 @@ impl PlaybackHandle {
     pub fn clear_followed_source(&self) {
         let _ = self.command_tx.send(PlaybackCommand::ClearFollowedSource);
     }
+    /// Stream the library's cloud-only releases from its cloud home.
+    pub fn set_library_cloud_source(&self, source: LibraryCloudSource) {
+        let _ = self
+            .command_tx
+            .send(PlaybackCommand::SetLibraryCloudSource(source));
+    }
 }
 
 /// Prepared track data for playback.
@@ -223,6 +275,14 @@ struct PreparedTrack {
     pregap_ms: Option<i64>,
     /// Track duration from metadata
     duration: std::time::Duration,
+    /// Where audible content starts, skipped on natural transitions
+    cue_in: Option<std::time::Duration>,
+    /// Mix-out point; reaching it counts as the track completing
+    cue_out: Option<std::time::Duration>,
+    /// Set for local tracks without cue points so they can be analyzed in the background
+    cue_analysis: Option<AudioReadConfig>,
+    /// Track position the decoder was started at (non-zero when leading silence is skipped)
+    decoder_start: std::time::Duration,
     /// True if this track uses local file storage (fast seek via direct file read)
     is_local_storage: bool,
     /// For CUE/FLAC: track's start byte position in original file.
@@ -235,7 +295,7 @@ struct PreparedTrack {
     cloud_storage: Option<Arc<dyn CloudStorage>>,
     /// Whether cloud storage is encrypted
     cloud_encrypted: bool,
-    /// Encryption nonce (24 bytes) for efficient encrypted range requests.
+    /// Encrypted container prefix (header + nonce) for efficient encrypted range requests.
     /// Stored in DB at import time, used during seek to avoid fetching nonce from cloud.
     encryption_nonce: Option<Vec<u8>>,
     /// Per-release encryption service for followed libraries.
@@ -248,6 +308,7 @@ struct PreparedTrack {
 async fn prepare_track(
     library_manager: &LibraryManager,
     library_dir: &crate::library_dir::LibraryDir,
+    cloud_source: Option<&LibraryCloudSource>,
     track_id: &str,
 ) -> Result<PreparedTrack, PlaybackError> {
     let track = library_manager
@@ -280,6 +341,11 @@ async fn prepare_track(
         .map_err(PlaybackError::database)?
         .ok_or_else(|| PlaybackError::not_found("Audio file", file_id))?;
 
+    // Releases kept only in the cloud home stream from there
+    let cloud_source = cloud_source.filter(|_| {
+        release.managed_in_cloud && !release.managed_locally && release.unmanaged_path.is_none()
+    });
+
     // Derive source path from release storage flags
     let source_path = if release.managed_locally {
         audio_file
@@ -291,6 +357,8 @@ async fn prepare_track(
             .join(&audio_file.original_filename)
             .display()
             .to_string()
+    } else if cloud_source.is_some() {
+        crate::storage::storage_path(&audio_file.id)
     } else {
         return Err(PlaybackError::not_found("playable file location", track_id));
     };
@@ -331,15 +399,83 @@ async fn prepare_track(
         end_byte,
     };
 
-    // Create local file reader — all playable files are local (managed or unmanaged).
-    let reader: Box<dyn AudioDataReader> = Box::new(LocalFileReader::new(read_config));
-    let is_local_storage = true;
-    let cloud_storage: Option<Arc<dyn CloudStorage>> = None;
-    let cloud_encrypted = false;
-    let encryption_override: Option<Arc<EncryptionService>> = None;
+    let cue_analysis = if audio_format.cue_out_ms.is_none() && cloud_source.is_none() {
+        Some(read_config.clone())
+    } else {
+        None
+    };
 
-    // Start reading data into buffer
-    reader.start_reading(buffer.clone());
+    let encryption = library_manager
+        .encryption_service()
+        .map(|e| Arc::new(e.derive_release_encryption(&release.id)));
+    // A release kept for offline play is already whole in the cache
+    let cached = match cloud_source {
+        Some(source) => source
+            .cache
+            .get(&crate::cache::file_cache_key(file_id))
+            .await
+            .ok()
+            .flatten(),
+        None => None,
+    };
+    if let Some(stored) = cached {
+        info!("Playing track {} from the cache", track_id);
+
+        let data = match encryption.clone() {
+            Some(enc) => tokio::task::spawn_blocking(move || enc.decrypt(&stored))
+                .await
+                .map_err(PlaybackError::task)?
+                .map_err(PlaybackError::decrypt)?,
+            None => stored,
+        };
+        fill_from_cached_file(
+            &buffer,
+            &data,
+            start_byte.unwrap_or(0),
+            end_byte.unwrap_or(audio_file.file_size as u64),
+            flac_headers.as_deref(),
+        );
+    } else {
+        let reader: Box<dyn AudioDataReader> = match cloud_source {
+            Some(source) => {
+                // An archived file has to be restored before it streams
+                source
+                    .cloud_storage
+                    .prepare_read(&source_path)
+                    .await
+                    .map_err(|e| match e {
+                        CloudStorageError::Restoring(key) => PlaybackError::Restoring(key),
+                        e => PlaybackError::cloud(e),
+                    })?;
+                // An explicit byte range, so the file streams as parallel range requests
+                let read_config = AudioReadConfig {
+                    start_byte: Some(start_byte.unwrap_or(0)),
+                    end_byte: Some(end_byte.unwrap_or(audio_file.file_size as u64)),
+                    ..read_config
+                };
+                Box::new(
+                    CloudStorageReader::new(
+                        read_config,
+                        source.cloud_storage.clone(),
+                        encryption.clone(),
+                        encryption.is_some(),
+                    )
+                    .with_encryption_nonce(audio_file.encryption_nonce.clone())
+                    .with_parallelism(source.download_parallelism)
+                    .with_readahead(source.readahead),
+                )
+            }
+            None => Box::new(LocalFileReader::new(read_config)),
+        };
+
+        // Start reading data into buffer
+        reader.start_reading(buffer.clone());
+    }
+
+    let is_local_storage = cloud_source.is_none();
+    let cloud_storage = cloud_source.map(|s| s.cloud_storage.clone());
+    let cloud_encrypted = cloud_source.is_some() && encryption.is_some();
+    let encryption_override: Option<Arc<EncryptionService>> = None;
 
     // Determine audio_data_start for seek calculations
     let audio_data_start = if needs_headers {
@@ -366,6 +502,10 @@ async fn prepare_track(
         source_path,
         pregap_ms,
         duration,
+        cue_in: cue_duration(audio_format.cue_in_ms),
+        cue_out: cue_duration(audio_format.cue_out_ms),
+        cue_analysis,
+        decoder_start: std::time::Duration::ZERO,
         is_local_storage,
         track_start_byte_offset: start_byte,
         track_end_byte_offset: end_byte,
@@ -376,6 +516,26 @@ async fn prepare_track(
     })
 }
 
+/// Fill `buffer` with a track's bytes out of a whole decrypted file from the
+/// cache, after the FLAC headers the track needs
+fn fill_from_cached_file(
+    buffer: &SharedSparseBuffer,
+    data: &[u8],
+    start_byte: u64,
+    end_byte: u64,
+    flac_headers: Option<&[u8]>,
+) {
+    let end = (end_byte as usize).min(data.len());
+    let start = (start_byte as usize).min(end);
+    let headers_len = flac_headers.map_or(0, |h| h.len() as u64);
+    if let Some(headers) = flac_headers {
+        buffer.append_at(0, headers);
+    }
+    buffer.append_at(headers_len, &data[start..end]);
+    buffer.set_total_size(headers_len + (end - start) as u64);
+    buffer.mark_eof();
+}
+
 /// Prepare a track from a followed library for playback.
 ///
 /// Audio is always fetched from cloud storage (via the followed library's proxy).
@@ -444,24 +604,56 @@ async fn prepare_followed_track(
     let enc = source.encryption.derive_release_encryption(&release.id);
     let encryption_arc = Arc::new(enc);
 
+    // A prefetched file is already whole in the cache
+    let cached = source
+        .cache
+        .get(&crate::cache::file_cache_key(file_id))
+        .await
+        .ok()
+        .flatten();
+
     // Cloud storage path for the audio file
     let storage_key = crate::storage::storage_path(file_id);
 
+    // Always read an explicit byte range so the file is fetched as parallel
+    // range requests instead of one download of the whole object
     let read_config = AudioReadConfig {
         path: storage_key.clone(),
         flac_headers: flac_headers.clone(),
-        start_byte,
-        end_byte,
+        start_byte: Some(start_byte.unwrap_or(0)),
+        end_byte: Some(end_byte.unwrap_or(audio_file.file_size as u64)),
     };
 
-    let reader: Box<dyn AudioDataReader> = Box::new(CloudStorageReader::new(
-        read_config,
-        source.cloud_storage.clone(),
-        Some(encryption_arc.clone()),
-        true,
-    ));
+    if let Some(encrypted) = cached {
+        info!("Playing track {} from the cache", track_id);
 
-    reader.start_reading(buffer.clone());
+        let enc = encryption_arc.clone();
+        let data = tokio::task::spawn_blocking(move || enc.decrypt(&encrypted))
+            .await
+            .map_err(PlaybackError::task)?
+            .map_err(PlaybackError::decrypt)?;
+        fill_from_cached_file(
+            &buffer,
+            &data,
+            read_config.start_byte.unwrap_or(0),
+            read_config.end_byte.unwrap_or(data.len() as u64),
+            flac_headers.as_deref(),
+        );
+    } else {
+        let reader: Box<dyn AudioDataReader> = Box::new(
+            CloudStorageReader::new(
+                read_config,
+                source.cloud_storage.clone(),
+                Some(encryption_arc.clone()),
+                true,
+            )
+            .with_encryption_nonce(audio_file.encryption_nonce.clone())
+            .with_parallelism(source.download_parallelism)
+            .with_readahead(source.readahead),
+        );
+
+        reader.start_reading(buffer.clone());
+    }
 
     let audio_data_start = if needs_headers {
         headers_len
@@ -485,6 +677,10 @@ async fn prepare_followed_track(
         source_path: storage_key,
         pregap_ms,
         duration,
+        cue_in: cue_duration(audio_format.cue_in_ms),
+        cue_out: cue_duration(audio_format.cue_out_ms),
+        cue_analysis: None,
+        decoder_start: std::time::Duration::ZERO,
         is_local_storage: false,
         track_start_byte_offset: start_byte,
         track_end_byte_offset: end_byte,
@@ -495,6 +691,11 @@ async fn prepare_followed_track(
     })
 }
 
+/// How many similar tracks to consider when radio picks the next one
+const RADIO_CANDIDATE_LIMIT: u32 = 50;
+/// How many recently started tracks radio avoids repeating
+const RADIO_HISTORY_LEN: usize = 200;
+
 /// Playback service that manages audio playback
 pub struct PlaybackService {
     library_manager: LibraryManager,
@@ -518,6 +719,17 @@ pub struct PlaybackService {
     next_streaming_source: Option<Arc<Mutex<StreamingPcmSource>>>,
     /// Override source for playing from a followed library.
     followed_source: Option<FollowedSource>,
+    /// Where the library's cloud-only releases stream from
+    library_cloud_source: Option<LibraryCloudSource>,
+    /// Whether to continue with similar tracks when the queue runs out
+    radio_enabled: bool,
+    /// Recently started track IDs, oldest first, so radio avoids repeats
+    play_history: VecDeque<String>,
+    /// Last track started, to tell whether an album is played in order
+    last_started: Option<DbTrack>,
+    /// Release whose successor has already been prefetched
+    prefetched_after: Option<String>,
+    metrics: Arc<PlaybackMetrics>,
 }
 
 impl PlaybackService {
@@ -536,12 +748,16 @@ impl PlaybackService {
     /// The audio output state remains unchanged - caller must explicitly
     /// call `audio_output.set_state(Playing)` to start audio output.
     ///
+    /// `requested_at` is when the user asked for this track, for measuring time
+    /// to first audio. Pass None when the stream won't start playing right away.
+    ///
     /// Returns true if initialization succeeded, false on error.
     async fn init_streaming(
         &mut self,
         source: Arc<Mutex<StreamingPcmSource>>,
         position_offset: std::time::Duration,
         track_id: String,
+        requested_at: Option<std::time::Instant>,
     ) -> bool {
         let (source_sample_rate, source_channels) = {
             let guard = source.lock().unwrap();
@@ -597,6 +813,7 @@ impl PlaybackService {
             source_channels,
             position_tx,
             completion_tx,
+            requested_at,
         ) {
             Ok(stream) => stream,
             Err(e) => {
@@ -621,43 +838,64 @@ impl PlaybackService {
         let position_generation = self.position_generation.clone();
         let gen = position_generation.load(std::sync::atomic::Ordering::SeqCst);
         let streaming_source = Some(source);
+        let cue_out = self.current_prepared.as_ref().and_then(|p| p.cue_out);
+        let metrics = self.metrics.clone();
 
         tokio::spawn(async move {
+            let mut last_pos = position_offset;
             loop {
-                tokio::select! {
+                // Some(report) ends the listener; report is false for a stale completion
+                let finished = tokio::select! {
                     Some(pos) = position_rx_async.recv() => {
-                        if position_generation.load(std::sync::atomic::Ordering::SeqCst) == gen {
-                            // Add offset to convert decoder-relative to track-relative position
-                            let actual_pos = position_offset + pos;
-                            *current_position_shared.lock().unwrap() = Some(actual_pos);
-                            let _ = progress_tx.send(PlaybackProgress::PositionUpdate {
-                                position: actual_pos,
-                                track_id: track_id.clone(),
-                            });
+                        if position_generation.load(std::sync::atomic::Ordering::SeqCst) != gen {
+                            continue;
                         }
+                        // Add offset to convert decoder-relative to track-relative position
+                        let actual_pos = position_offset + pos;
+                        *current_position_shared.lock().unwrap() = Some(actual_pos);
+                        let _ = progress_tx.send(PlaybackProgress::PositionUpdate {
+                            position: actual_pos,
+                            track_id: track_id.clone(),
+                        });
+
+                        // Only trailing silence remains past the mix-out point
+                        let reached_cue_out =
+                            cue_out.is_some_and(|cue_out| last_pos < cue_out && actual_pos >= cue_out);
+                        last_pos = actual_pos;
+                        reached_cue_out.then_some(true)
                     }
                     Some(()) = completion_rx_async.recv() => {
-                        if position_generation.load(std::sync::atomic::Ordering::SeqCst) == gen {
-                            let (error_count, samples_decoded) = streaming_source
-                                .as_ref()
-                                .and_then(|s| s.lock().ok())
-                                .map(|g| (g.decode_error_count(), g.samples_decoded()))
-                                .unwrap_or((0, 0));
-
-                            info!("Track completed: {} ({} decode errors, {} samples)", track_id, error_count, samples_decoded);
-                            let _ = progress_tx.send(PlaybackProgress::TrackCompleted {
-                                track_id: track_id.clone(),
-                            });
-                            let _ = progress_tx.send(PlaybackProgress::DecodeStats {
-                                track_id: track_id.clone(),
-                                error_count,
-                                samples_decoded,
-                            });
-                        }
-                        break;
+                        Some(position_generation.load(std::sync::atomic::Ordering::SeqCst) == gen)
                     }
                     else => break,
+                };
+                let Some(report) = finished else {
+                    continue;
+                };
+
+                if report {
+                    let (error_count, samples_decoded) = streaming_source
+                        .as_ref()
+                        .and_then(|s| s.lock().ok())
+                        .map(|g| (g.decode_error_count(), g.samples_decoded()))
+                        .unwrap_or((0, 0));
+
+                    info!(
+                        "Track completed: {} ({} decode errors, {} samples)",
+                        track_id, error_count, samples_decoded
+                    );
+
+                    metrics.record_decode_errors(error_count);
+                    let _ = progress_tx.send(PlaybackProgress::TrackCompleted {
+                        track_id: track_id.clone(),
+                    });
+                    let _ = progress_tx.send(PlaybackProgress::DecodeStats {
+                        track_id: track_id.clone(),
+                        error_count,
+                        samples_decoded,
+                    });
                 }
+                break;
             }
         });
 
@@ -673,9 +911,11 @@ impl PlaybackService {
         let (command_tx, command_rx) = tokio_mpsc::unbounded_channel();
         let (progress_tx, progress_rx) = tokio_mpsc::unbounded_channel();
         let progress_handle = PlaybackProgressHandle::new(progress_rx, runtime_handle.clone());
+        let metrics = Arc::new(PlaybackMetrics::new());
         let handle = PlaybackHandle {
             command_tx: command_tx.clone(),
             progress_handle: progress_handle.clone(),
+            metrics: metrics.clone(),
         };
         let command_tx_for_completion = command_tx.clone();
         let progress_handle_for_completion = progress_handle.clone();
@@ -694,7 +934,7 @@ impl PlaybackService {
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
             rt.block_on(async move {
-                let audio_output = match AudioOutput::new() {
+                let audio_output = match AudioOutput::new(metrics.clone()) {
                     Ok(output) => output,
                     Err(e) => {
                         error!("Failed to initialize audio output: {:?}", e);
@@ -717,6 +957,12 @@ impl PlaybackService {
                     next_prepared: None,
                     next_streaming_source: None,
                     followed_source: None,
+                    library_cloud_source: None,
+                    radio_enabled: false,
+                    play_history: VecDeque::new(),
+                    last_started: None,
+                    prefetched_after: None,
+                    metrics,
                 };
                 service.run().await;
             });
@@ -839,6 +1085,9 @@ impl PlaybackService {
                                 self.play_track(&next_track, false, true).await;
                                 // preserve paused
                             }
+                            NextTrack::Stop if self.radio_enabled => {
+                                self.continue_with_radio(false, true).await;
+                            }
                             _ => {
                                 info!("No next track available, stopping");
                                 self.emit_queue_update();
@@ -907,6 +1156,9 @@ impl PlaybackService {
                                 self.stop().await;
                             }
                         }
+                        NextTrack::Stop if self.radio_enabled => {
+                            self.continue_with_radio(true, false).await;
+                        }
                         NextTrack::Stop => {
                             info!("No next track available, stopping");
                             self.emit_queue_update();
@@ -991,6 +1243,9 @@ impl PlaybackService {
                         .progress_tx
                         .send(PlaybackProgress::VolumeChanged { volume });
                 }
+                PlaybackCommand::SetDucked(ducked) => {
+                    self.audio_output.set_ducked(ducked);
+                }
                 PlaybackCommand::AddToQueue(track_ids) => {
                     self.playback_queue.add_to_queue(track_ids);
                     self.emit_queue_update();
@@ -1034,6 +1289,14 @@ impl PlaybackService {
                             .send(PlaybackProgress::RepeatModeChanged { mode });
                     }
                 }
+                PlaybackCommand::SetRadioEnabled(enabled) => {
+                    if self.radio_enabled != enabled {
+                        self.radio_enabled = enabled;
+                        let _ = self
+                            .progress_tx
+                            .send(PlaybackProgress::RadioChanged { enabled });
+                    }
+                }
                 PlaybackCommand::SkipTo(index) => {
                     if let Some(track_id) = self.playback_queue.skip_to(index) {
                         info!(
@@ -1055,6 +1318,10 @@ impl PlaybackService {
                     info!("Playback source cleared, using local library");
                     self.followed_source = None;
                 }
+                PlaybackCommand::SetLibraryCloudSource(source) => {
+                    info!("Cloud-only releases will stream from the cloud home");
+                    self.library_cloud_source = Some(source);
+                }
             }
         }
         info!("PlaybackService stopped");
@@ -1073,6 +1340,10 @@ impl PlaybackService {
             track_id, is_natural_transition, preserve_paused
         );
 
+        let requested_at = std::time::Instant::now();
+        self.remember_played(track_id);
+        self.metrics.record_track_started();
+
         let _ = self.progress_tx.send(PlaybackProgress::StateChanged {
             state: PlaybackState::Loading {
                 track_id: track_id.to_string(),
@@ -1083,33 +1354,44 @@ impl PlaybackService {
         let prepared = if let Some(ref followed) = self.followed_source {
             prepare_followed_track(followed, track_id).await
         } else {
-            prepare_track(&self.library_manager, &self.library_dir, track_id).await
+            prepare_track(
+                &self.library_manager,
+                &self.library_dir,
+                self.library_cloud_source.as_ref(),
+                track_id,
+            )
+            .await
         };
-        let prepared = match prepared {
+        let mut prepared = match prepared {
             Ok(p) => p,
             Err(e) => {
                 error!("Failed to prepare track {}: {}", track_id, e);
+
                 self.stop().await;
+                let _ = self.progress_tx.send(PlaybackProgress::PlaybackError {
+                    code: e.code(),
+                    message: e.to_string(),
+                });
                 return;
             }
         };
 
-        // Calculate pregap byte offset if needed (direct selection skips pregap)
-        let pregap_skip_duration = pregap_seek_position(prepared.pregap_ms, is_natural_transition);
-        let pregap_byte_offset: Option<u64> = pregap_skip_duration.and_then(|pregap_duration| {
-            serde_json::from_str::<Vec<crate::audio_codec::SeekEntry>>(&prepared.seektable_json)
-                .ok()
-                .and_then(|entries| {
-                    find_frame_boundary(&entries, pregap_duration, prepared.sample_rate).map(
-                        |(byte_offset, _)| {
-                            info!(
-                                "Pregap skip: will start decoder at byte offset {} for {:?} pregap",
-                                byte_offset, pregap_duration
-                            );
-                            byte_offset
-                        },
-                    )
-                })
+        // Direct selection skips the pregap; natural transitions skip leading silence
+        let start_skip =
+            start_skip_position(prepared.pregap_ms, prepared.cue_in, is_natural_transition);
+        let start_byte_offset: Option<u64> = start_skip.and_then(|skip_duration| {
+            find_frame_boundary_for_seek(
+                skip_duration,
+                prepared.sample_rate,
+                &prepared.seektable_json,
+            )
+            .map(|(byte_offset, _)| {
+                info!(
+                    "Start skip: will start decoder at byte offset {} for {:?}",
+                    byte_offset, skip_duration
+                );
+                byte_offset
+            })
         });
 
         // Create decoder sink/source with track's actual sample rate
@@ -1117,7 +1399,8 @@ impl PlaybackService {
 
         // Spawn decoder thread
         let decoder_buffer = prepared.buffer.clone();
-        let decoder_skip_to = pregap_byte_offset.map(|offset| prepared.audio_data_start + offset);
+        let decoder_skip_to = start_byte_offset.map(|offset| prepared.audio_data_start + offset);
+        let metrics = self.metrics.clone();
         std::thread::spawn(move || {
             if let Some(skip_position) = decoder_skip_to {
                 decoder_buffer.seek(skip_position);
@@ -1125,28 +1408,38 @@ impl PlaybackService {
             if let Err(e) = crate::audio_codec::decode_audio_streaming(decoder_buffer, &mut sink, 0)
             {
                 error!("Streaming decode failed: {}", e);
+
+                metrics.record_decode_errors(1);
             }
         });
 
-        // Position offset: when we skip pregap, decoder positions start at 0 but actual
-        // track position is pregap_ms
-        let position_offset = if pregap_byte_offset.is_some() {
-            std::time::Duration::from_millis(prepared.pregap_ms.unwrap_or(0).max(0) as u64)
-        } else {
-            std::time::Duration::ZERO
-        };
+        // Position offset: when we skip ahead, decoder positions start at 0 but actual
+        // track position is the skipped duration
+        let position_offset = start_skip
+            .filter(|_| start_byte_offset.is_some())
+            .unwrap_or(std::time::Duration::ZERO);
+        prepared.decoder_start = position_offset;
 
         let track = prepared.track.clone();
         let duration = prepared.duration;
         let pregap_ms = prepared.pregap_ms;
 
+        self.spawn_cue_analysis(&prepared);
+        self.prefetch_next_album(&track);
+
         // Store prepared track state
         self.current_prepared = Some(prepared);
 
         // Initialize streaming
         let source = Arc::new(Mutex::new(source));
+        let starts_playing = !preserve_paused || !self.audio_output.is_paused();
         if !self
-            .init_streaming(source, position_offset, track_id.to_string())
+            .init_streaming(
+                source,
+                position_offset,
+                track_id.to_string(),
+                starts_playing.then_some(requested_at),
+            )
             .await
         {
             self.stop().await;
@@ -1195,9 +1488,15 @@ impl PlaybackService {
         let prepared = if let Some(ref followed) = self.followed_source {
             prepare_followed_track(followed, track_id).await
         } else {
-            prepare_track(&self.library_manager, &self.library_dir, track_id).await
+            prepare_track(
+                &self.library_manager,
+                &self.library_dir,
+                self.library_cloud_source.as_ref(),
+                track_id,
+            )
+            .await
         };
-        let prepared = match prepared {
+        let mut prepared = match prepared {
             Ok(p) => p,
             Err(e) => {
                 error!("Failed to preload track {}: {}", track_id, e);
@@ -1205,16 +1504,38 @@ impl PlaybackService {
             }
         };
 
+        // The preloaded decoder serves natural transitions, so it skips leading silence
+        let decoder_skip_to = start_skip_position(prepared.pregap_ms, prepared.cue_in, true)
+            .and_then(|skip_duration| {
+                find_frame_boundary_for_seek(
+                    skip_duration,
+                    prepared.sample_rate,
+                    &prepared.seektable_json,
+                )
+                .map(|(byte_offset, _)| {
+                    prepared.decoder_start = skip_duration;
+                    prepared.audio_data_start + byte_offset
+                })
+            });
+
         // Create decoder sink/source and start decoder eagerly for gapless playback
         let (mut sink, source, _ready) = create_streaming_pair(prepared.sample_rate, 2);
         let decoder_buffer = prepared.buffer.clone();
+        let metrics = self.metrics.clone();
         std::thread::spawn(move || {
+            if let Some(skip_position) = decoder_skip_to {
+                decoder_buffer.seek(skip_position);
+            }
             if let Err(e) = crate::audio_codec::decode_audio_streaming(decoder_buffer, &mut sink, 0)
             {
                 error!("Preload streaming decode failed: {}", e);
+
+                metrics.record_decode_errors(1);
             }
         });
 
+        self.spawn_cue_analysis(&prepared);
+
         let source = Arc::new(Mutex::new(source));
 
         // Store preloaded state
@@ -1304,11 +1625,13 @@ impl PlaybackService {
         let pregap_ms = next_prepared.pregap_ms;
         let track_id = next_prepared.track.id.clone();
 
-        // If we need to skip pregap (direct selection), the preloaded state won't work
-        // because it was set up for auto-advance (starting at byte 0).
-        // Fall back to play_track which handles pregap at decoder start.
-        if !is_natural_transition && pregap_ms.is_some_and(|p| p > 0) {
-            info!("Pregap skip needed for preloaded track - falling back to play_track");
+        // The preloaded state was set up for auto-advance (playing the pregap, skipping
+        // leading silence). Direct selection needs a different start, so fall back to
+        // play_track which handles the skip at decoder start.
+        let needs_restart = pregap_ms.is_some_and(|p| p > 0)
+            || next_prepared.decoder_start > std::time::Duration::ZERO;
+        if !is_natural_transition && needs_restart {
+            info!("Different start needed for preloaded track - falling back to play_track");
             next_prepared.buffer.cancel();
             if let Some(source) = self.next_streaming_source.take() {
                 if let Ok(guard) = source.lock() {
@@ -1320,8 +1643,14 @@ impl PlaybackService {
             return;
         }
 
+        let requested_at = std::time::Instant::now();
+        self.remember_played(&track_id);
+        self.metrics.record_track_started();
+
         let duration = next_prepared.duration;
         let track = next_prepared.track.clone();
+        let start_position = next_prepared.decoder_start;
+        self.prefetch_next_album(&track);
 
         // Cancel current streaming state
         if let Some(source) = self.current_streaming_source.take() {
@@ -1340,12 +1669,15 @@ impl PlaybackService {
             .take()
             .expect("Preloaded track has no streaming source");
 
-        // Natural transition: start at position 0 (INDEX 00, pregap plays)
-        let start_position = std::time::Duration::ZERO;
-
         // Initialize streaming with the preloaded source
+        let starts_playing = !preserve_paused || !self.audio_output.is_paused();
         if !self
-            .init_streaming(source, start_position, track_id.clone())
+            .init_streaming(
+                source,
+                start_position,
+                track_id.clone(),
+                starts_playing.then_some(requested_at),
+            )
             .await
         {
             self.stop().await;
@@ -1497,25 +1829,37 @@ impl PlaybackService {
             // Local files: seek directly in file
             self.create_seek_buffer_for_local(prepared, file_byte)
         } else {
-            // Cloud: start fresh range request at seek position
-            self.create_seek_buffer_for_cloud(prepared, file_byte)
+            // Cloud: reuse what's already downloaded, range request the rest
+            self.create_seek_buffer_for_cloud(prepared, buffer_byte, file_byte)
         };
 
         // Spawn decoder on the seek buffer, skipping sample_offset samples
         // to reach the exact seek position (not just the frame boundary)
         let (mut sink, source, ready_rx) = create_streaming_pair(prepared.sample_rate, 2);
+        let metrics = self.metrics.clone();
         std::thread::spawn(move || {
-            if let Err(e) =
-                crate::audio_codec::decode_audio_streaming(seek_buffer, &mut sink, sample_offset)
-            {
+            if let Err(e) = crate::audio_codec::decode_audio_streaming(
+                seek_buffer.clone(),
+                &mut sink,
+                sample_offset,
+            ) {
                 error!("Seek decode failed: {}", e);
+
+                metrics.record_decode_errors(1);
             }
+            // Nothing reads the seek buffer anymore, so stop its download
+            seek_buffer.cancel();
         });
 
         // Wait for buffer to be ready (50% full or finished)
         // Timeout after 5s to prevent hangs on broken streams
+        let buffering_started = std::time::Instant::now();
         match tokio::time::timeout(std::time::Duration::from_secs(5), ready_rx).await {
-            Ok(Ok(())) => {} // Buffer ready
+            Ok(Ok(())) => {
+                // Buffer ready
+                self.metrics
+                    .record_buffering(buffering_started.elapsed(), false);
+            }
             Ok(Err(_)) => {
                 // Sender dropped without sending - decoder thread crashed
                 error!("Seek decoder failed to signal ready");
@@ -1524,6 +1868,9 @@ impl PlaybackService {
             Err(_) => {
                 // Timeout - something is very wrong
                 error!("Seek buffer ready timeout after 5s");
+
+                self.metrics
+                    .record_buffering(buffering_started.elapsed(), true);
                 return;
             }
         }
@@ -1537,7 +1884,7 @@ impl PlaybackService {
         // Initialize streaming (position offset = seek target, so positions are relative to seek point)
         // State remains unchanged (Playing or Paused) - new stream inherits it
         if !self
-            .init_streaming(source, position, track_id.clone())
+            .init_streaming(source, position, track_id.clone(), None)
             .await
         {
             return;
@@ -1573,22 +1920,43 @@ impl PlaybackService {
         seek_buffer
     }
 
-    /// Create a seek buffer for cloud storage by starting a fresh range request.
-    /// This creates a new CloudStorageReader at target_byte, avoiding the need to
-    /// wait for data to download sequentially.
+    /// Create a seek buffer for cloud storage.
+    ///
+    /// Bytes the track's buffer already holds from `buffer_byte` on are
+    /// copied in, so a seek within the prefetched region plays at once. A
+    /// new CloudStorageReader range requests the rest, from where the copy
+    /// ends, instead of waiting for the data to download sequentially.
     fn create_seek_buffer_for_cloud(
         &self,
         prepared: &PreparedTrack,
+        buffer_byte: u64,
         target_byte: u64,
     ) -> SharedSparseBuffer {
         let seek_buffer = create_sparse_buffer();
 
-        // Create config for new reader starting at target_byte
+        // Whole-file tracks have no end offset, and their buffer is the file
+        let end_byte = prepared.track_end_byte_offset.unwrap_or(prepared.file_size);
+        let mut prefetched = prepared.buffer.copy_from(buffer_byte);
+        prefetched.truncate(end_byte.saturating_sub(target_byte) as usize);
+        let resume_byte = target_byte + prefetched.len() as u64;
+
+        // The prefetched bytes go ahead of the download, after the headers
+        let mut lead = prepared.flac_headers.clone().unwrap_or_default();
+        lead.extend_from_slice(&prefetched);
+
+        if resume_byte >= end_byte {
+            seek_buffer.append_at(0, &lead);
+            seek_buffer.set_total_size(lead.len() as u64);
+            seek_buffer.mark_eof();
+            return seek_buffer;
+        }
+
+        // Create config for new reader starting where the prefetched bytes end
         let config = AudioReadConfig {
             path: prepared.source_path.clone(),
-            flac_headers: prepared.flac_headers.clone(),
-            start_byte: Some(target_byte),
-            end_byte: prepared.track_end_byte_offset,
+            flac_headers: (!lead.is_empty()).then_some(lead),
+            start_byte: Some(resume_byte),
+            end_byte: Some(end_byte),
         };
 
         // Create a new cloud reader at the seek position
@@ -1601,9 +1969,24 @@ impl PlaybackService {
                     .map(|e| Arc::new(e.derive_release_encryption(&prepared.track.release_id)))
             });
 
+            let (parallelism, readahead) = self
+                .followed_source
+                .as_ref()
+                .map(|s| (s.download_parallelism, s.readahead))
+                .or_else(|| {
+                    self.library_cloud_source
+                        .as_ref()
+                        .map(|s| (s.download_parallelism, s.readahead))
+                })
+                .unwrap_or((
+                    crate::storage::DEFAULT_DOWNLOAD_PARALLELISM,
+                    crate::storage::DEFAULT_READAHEAD,
+                ));
             let reader = Box::new(
                 CloudStorageReader::new(config, storage.clone(), enc, prepared.cloud_encrypted)
-                    .with_encryption_nonce(prepared.encryption_nonce.clone()),
+                    .with_encryption_nonce(prepared.encryption_nonce.clone())
+                    .with_parallelism(parallelism)
+                    .with_readahead(readahead),
             );
             reader.start_reading(seek_buffer.clone());
         } else {
@@ -1621,6 +2004,136 @@ impl PlaybackService {
         });
     }
 
+    /// Record a track as started, keeping the history bounded.
+    ///
+    /// Local tracks also go into the persistent play history.
+    fn remember_played(&mut self, track_id: &str) {
+        self.play_history.retain(|id| id != track_id);
+        self.play_history.push_back(track_id.to_string());
+        if self.play_history.len() > RADIO_HISTORY_LEN {
+            self.play_history.pop_front();
+        }
+
+        if self.followed_source.is_some() {
+            return;
+        }
+        let library_manager = self.library_manager.clone();
+        let track_id = track_id.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = library_manager.record_play(&track_id).await {
+                warn!("Failed to record play of track {}: {}", track_id, e);
+            }
+        });
+    }
+
+    /// Once a followed library's album is being played in order, warm the
+    /// cache with the album likely to come next. Local libraries read from
+    /// disk and have nothing to warm.
+    fn prefetch_next_album(&mut self, track: &DbTrack) {
+        let previous = self.last_started.replace(track.clone());
+        let Some(followed) = self.followed_source.as_ref() else {
+            return;
+        };
+        if !previous.is_some_and(|p| album_prefetch::follows_on_album(&p, track)) {
+            return;
+        }
+        if self.prefetched_after.as_deref() == Some(track.release_id.as_str()) {
+            return;
+        }
+        self.prefetched_after = Some(track.release_id.clone());
+
+        let database = followed.database.clone();
+        let cloud_storage = followed.cloud_storage.clone();
+        let cache = followed.cache.clone();
+        let parallelism = followed.download_parallelism;
+        let queued = self.playback_queue.tracks();
+        let track = track.clone();
+        tokio::spawn(async move {
+            album_prefetch::prefetch_next_album(
+                &database,
+                cloud_storage,
+                &cache,
+                parallelism,
+                &track,
+                &queued,
+            )
+            .await;
+        });
+    }
+
+    /// Detect cue points for a track that hasn't been analyzed yet.
+    ///
+    /// Runs in the background; the stored points take effect the next time
+    /// the track is prepared.
+    fn spawn_cue_analysis(&self, prepared: &PreparedTrack) {
+        let Some(config) = prepared.cue_analysis.clone() else {
+            return;
+        };
+        let library_manager = self.library_manager.clone();
+        let track_id = prepared.track.id.clone();
+
+        tokio::spawn(async move {
+            let cues = match crate::playback::transitions::analyze_local_track(config).await {
+                Ok(cues) => cues,
+                Err(e) => {
+                    warn!("Cue analysis failed for track {}: {}", track_id, e);
+                    return;
+                }
+            };
+
+            info!(
+                "Detected cue points for track {}: in {}ms, out {}ms",
+                track_id, cues.cue_in_ms, cues.cue_out_ms
+            );
+
+            if let Err(e) = library_manager
+                .set_audio_format_cue_points(
+                    &track_id,
+                    cues.cue_in_ms as i64,
+                    cues.cue_out_ms as i64,
+                )
+                .await
+            {
+                error!("Failed to store cue points for track {}: {}", track_id, e);
+            }
+        });
+    }
+
+    /// Play a track similar to the current one after the queue has run out,
+    /// or stop if the library has nothing to offer.
+    async fn continue_with_radio(&mut self, is_natural_transition: bool, preserve_paused: bool) {
+        let candidates = match (self.current_track_id(), &self.followed_source) {
+            // Radio draws from the local library only
+            (Some(seed), None) => self
+                .library_manager
+                .get_radio_candidates(seed, RADIO_CANDIDATE_LIMIT)
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to load radio candidates: {}", e);
+                    Vec::new()
+                }),
+            _ => Vec::new(),
+        };
+
+        match pick_radio_track(candidates, &self.play_history) {
+            Some(track_id) => {
+                info!("Queue finished, radio continuing with {}", track_id);
+
+                self.playback_queue.set_current(track_id.clone());
+                self.clear_next_track_state();
+                self.emit_queue_update();
+                self.play_track(&track_id, is_natural_transition, preserve_paused)
+                    .await;
+            }
+            None => {
+                info!("Queue finished and radio found nothing to play, stopping");
+
+                self.emit_queue_update();
+                self.stop().await;
+            }
+        }
+    }
+
     async fn rebuild_queue_for_repeat_album(&mut self) -> Option<(String, VecDeque<String>)> {
         let current_release_id = self
             .current_prepared
@@ -1662,6 +2175,19 @@ impl PlaybackService {
     }
 }
 
+/// Choose the radio track to play next from candidates ordered by similarity.
+///
+/// Prefers the most similar track not played recently; if every candidate was,
+/// falls back to the one played longest ago.
+fn pick_radio_track(candidates: Vec<String>, history: &VecDeque<String>) -> Option<String> {
+    if let Some(fresh) = candidates.iter().find(|id| !history.contains(id)) {
+        return Some(fresh.clone());
+    }
+    candidates
+        .into_iter()
+        .min_by_key(|id| history.iter().position(|played| played == id))
+}
+
 /// Calculate byte offset for seeking based on time position.
 ///
 /// Uses linear interpolation assuming constant bitrate.
@@ -1748,6 +2274,30 @@ fn pregap_seek_position(
     }
 }
 
+/// Determine how far into the track playback should start.
+///
+/// Pregap handling comes first: direct selection skips it, and a natural transition
+/// into a track with a pregap plays it as authored. Otherwise a natural transition
+/// skips the leading silence found by cue analysis.
+fn start_skip_position(
+    pregap_ms: Option<i64>,
+    cue_in: Option<std::time::Duration>,
+    is_natural_transition: bool,
+) -> Option<std::time::Duration> {
+    let has_pregap = pregap_ms.is_some_and(|p| p > 0);
+    if has_pregap || !is_natural_transition {
+        return pregap_seek_position(pregap_ms, is_natural_transition);
+    }
+    cue_in.filter(|c| !c.is_zero())
+}
+
+/// Convert a stored cue point to a playback position
+fn cue_duration(cue_ms: Option<i64>) -> Option<std::time::Duration> {
+    cue_ms
+        .filter(|&ms| ms >= 0)
+        .map(|ms| std::time::Duration::from_millis(ms as u64))
+}
+
 /// Validate a seek position against the decoded audio duration.
 ///
 /// IMPORTANT: This must use `decoded_duration` (actual PCM length including pregap),
@@ -1829,6 +2379,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_natural_transition_skips_leading_silence() {
+        let cue_in = Some(std::time::Duration::from_millis(1500));
+
+        assert_eq!(start_skip_position(None, cue_in, true), cue_in);
+    }
+
+    #[test]
+    fn test_direct_selection_keeps_leading_silence() {
+        let cue_in = Some(std::time::Duration::from_millis(1500));
+
+        assert_eq!(start_skip_position(None, cue_in, false), None);
+    }
+
+    #[test]
+    fn test_pregap_takes_precedence_over_cue_in() {
+        let cue_in = Some(std::time::Duration::from_millis(1500));
+
+        // Natural transition plays the pregap as authored
+        assert_eq!(start_skip_position(Some(3000), cue_in, true), None);
+        // Direct selection skips to INDEX 01
+        assert_eq!(
+            start_skip_position(Some(3000), cue_in, false),
+            Some(std::time::Duration::from_millis(3000))
+        );
+    }
+
     #[test]
     fn test_natural_transition_no_pregap() {
         // When naturally transitioning to a track without pregap,
@@ -2294,4 +2871,27 @@ mod tests {
             track1_end
         );
     }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_radio_prefers_most_similar_unplayed_track() {
+        let history: VecDeque<String> = ids(&["a"]).into();
+        let picked = pick_radio_track(ids(&["a", "b", "c"]), &history);
+        assert_eq!(picked.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_radio_falls_back_to_least_recently_played() {
+        let history: VecDeque<String> = ids(&["b", "c", "a"]).into();
+        let picked = pick_radio_track(ids(&["a", "c", "b"]), &history);
+        assert_eq!(picked.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_radio_with_no_candidates() {
+        assert!(pick_radio_track(Vec::new(), &VecDeque::new()).is_none());
+    }
 }
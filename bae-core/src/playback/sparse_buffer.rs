@@ -175,6 +175,24 @@ impl SparseStreamingBuffer {
         0
     }
 
+    /// Copy of the bytes buffered from `pos` up to the next gap.
+    ///
+    /// Empty if position is not buffered.
+    pub fn copy_from(&self, pos: u64) -> Vec<u8> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .ranges
+            .iter()
+            .find(|r| r.contains(pos))
+            .map(|r| r.data[(pos - r.start) as usize..].to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Current read position.
+    pub fn read_position(&self) -> u64 {
+        self.inner.lock().unwrap().read_pos
+    }
+
     /// Seek to a position.
     ///
     /// Returns true if successful. Does not require position to be buffered
@@ -326,6 +344,17 @@ mod tests {
         assert!(!buffer.is_buffered(10));
     }
 
+    #[test]
+    fn test_copy_from_stops_at_gap() {
+        let buffer = SparseStreamingBuffer::new();
+        buffer.append_at(0, b"0123456789");
+        buffer.append_at(20, b"abcd");
+
+        assert_eq!(buffer.copy_from(6), b"6789");
+        assert_eq!(buffer.copy_from(21), b"bcd");
+        assert!(buffer.copy_from(12).is_empty());
+    }
+
     #[test]
     fn test_multiple_non_contiguous_ranges() {
         let buffer = SparseStreamingBuffer::new();
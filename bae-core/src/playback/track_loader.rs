@@ -3,6 +3,7 @@ use crate::cloud_storage::CloudStorage;
 use crate::encryption::EncryptionService;
 use crate::library::LibraryManager;
 use crate::playback::{PcmSource, PlaybackError};
+use crate::storage::{DownloadPriority, ParallelDownloader, DEFAULT_DOWNLOAD_PARALLELISM};
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
@@ -60,7 +61,7 @@ pub async fn load_track_audio(
         debug!("Downloading from cloud storage");
 
         // Check cache first
-        let cache_key = format!("file:{}", audio_file.id);
+        let cache_key = crate::cache::file_cache_key(&audio_file.id);
         let encrypted_data = match cache.get(&cache_key).await {
             Ok(Some(cached_data)) => {
                 debug!("Cache hit for file: {}", audio_file.id);
@@ -69,11 +70,26 @@ pub async fn load_track_audio(
             Ok(None) | Err(_) => {
                 debug!("Cache miss - downloading file: {}", audio_file.id);
                 let storage_key = crate::storage::storage_path(&audio_file.id);
-                let data = storage
-                    .download(&storage_key)
+                let stored_len = if encryption_service.is_some() {
+                    let prefix = audio_file.encryption_nonce.as_deref().unwrap_or_default();
+                    crate::encryption::ContainerFormat::detect(prefix)
+                        .map_err(PlaybackError::decrypt)?
+                        .encrypted_len(audio_file.file_size as u64)
+                } else {
+                    audio_file.file_size as u64
+                };
+                let (data, stats) = ParallelDownloader::new(storage, DEFAULT_DOWNLOAD_PARALLELISM)
+                    .download_range_to_vec(&storage_key, 0, stored_len, DownloadPriority::Export)
                     .await
                     .map_err(PlaybackError::cloud)?;
 
+                debug!(
+                    "Downloaded {} bytes in {} requests ({:.0} KB/s)",
+                    stats.bytes,
+                    stats.requests,
+                    stats.bytes_per_sec() / 1024.0
+                );
+
                 if let Err(e) = cache.put(&cache_key, &data).await {
                     warn!("Failed to cache file (non-fatal): {}", e);
                 }
@@ -0,0 +1,268 @@
+//! Cue point detection for automatic track transitions.
+//!
+//! Each track gets a cue-in point (where audible content starts) and a cue-out
+//! point (where the next track should take over). Leading and trailing silence
+//! is trimmed, and when the track has a steady pulse the cue-out point is moved
+//! forward to the next beat so the following track enters in time.
+//!
+//! Analysis decodes the whole track, so it runs in the background after a track
+//! is first played and the result is stored on the track's audio format.
+
+use crate::playback::data_source::AudioReadConfig;
+use crate::playback::error::PlaybackError;
+use crate::playback::track_loader::decode_audio_to_pcm;
+
+/// Length of each loudness measurement window
+const WINDOW_MS: u64 = 10;
+/// Windows quieter than this (dBFS) count as silence
+const SILENCE_THRESHOLD_DB: f64 = -48.0;
+/// Silence shorter than this is left alone (it's part of the music)
+const MIN_TRIM_MS: u64 = 500;
+/// Tempo range searched for a beat grid
+const MIN_BPM: f64 = 70.0;
+const MAX_BPM: f64 = 180.0;
+/// Normalized autocorrelation a tempo needs before we trust its beat grid
+const MIN_BEAT_CONFIDENCE: f64 = 0.3;
+
+/// Detected mix points, in ms from the start of the decoded track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CuePoints {
+    pub cue_in_ms: u64,
+    pub cue_out_ms: u64,
+}
+
+/// Read a local track the same way playback does and detect its cue points.
+pub(crate) async fn analyze_local_track(
+    config: AudioReadConfig,
+) -> Result<CuePoints, PlaybackError> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut file = tokio::fs::File::open(&config.path).await?;
+    let start = config.start_byte.unwrap_or(0);
+    if start > 0 {
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+    }
+
+    let mut audio_data = config.flac_headers.unwrap_or_default();
+    match config.end_byte {
+        Some(end) => {
+            let mut range = vec![0u8; end.saturating_sub(start) as usize];
+            file.read_exact(&mut range).await?;
+            audio_data.extend_from_slice(&range);
+        }
+        None => {
+            file.read_to_end(&mut audio_data).await?;
+        }
+    }
+
+    let decoded = decode_audio_to_pcm(&audio_data).await?;
+    drop(audio_data);
+
+    tokio::task::spawn_blocking(move || {
+        detect_cue_points(
+            &decoded.samples,
+            decoded.sample_rate,
+            decoded.channels,
+            decoded.bits_per_sample,
+        )
+    })
+    .await
+    .map_err(PlaybackError::task)
+}
+
+/// Find cue points in interleaved PCM samples.
+///
+/// A track that is silent throughout gets cue points spanning the whole track.
+pub fn detect_cue_points(
+    samples: &[i32],
+    sample_rate: u32,
+    channels: u32,
+    bits_per_sample: u32,
+) -> CuePoints {
+    let channels = channels.max(1) as usize;
+    let total_frames = samples.len() / channels;
+    let total_ms = total_frames as u64 * 1000 / sample_rate.max(1) as u64;
+
+    let rms = window_rms(samples, sample_rate, channels, bits_per_sample);
+    let threshold = 10f64.powf(SILENCE_THRESHOLD_DB / 20.0);
+
+    let first_loud = rms.iter().position(|&r| r > threshold);
+    let last_loud = rms.iter().rposition(|&r| r > threshold);
+    let (Some(first_loud), Some(last_loud)) = (first_loud, last_loud) else {
+        return CuePoints {
+            cue_in_ms: 0,
+            cue_out_ms: total_ms,
+        };
+    };
+
+    let audible_start_ms = first_loud as u64 * WINDOW_MS;
+    let cue_in_ms = if audible_start_ms >= MIN_TRIM_MS {
+        audible_start_ms
+    } else {
+        0
+    };
+
+    let audible_end = last_loud + 1;
+    let audible_end_ms = (audible_end as u64 * WINDOW_MS).min(total_ms);
+    let cue_out_ms = if total_ms - audible_end_ms < MIN_TRIM_MS {
+        total_ms
+    } else if let Some((period, phase)) = beat_grid(&rms[..audible_end]) {
+        let next_beat = if audible_end <= phase {
+            phase
+        } else {
+            phase + (audible_end - phase).div_ceil(period) * period
+        };
+        (next_beat as u64 * WINDOW_MS).min(total_ms)
+    } else {
+        audible_end_ms
+    };
+
+    CuePoints {
+        cue_in_ms,
+        cue_out_ms,
+    }
+}
+
+/// RMS level of each window, relative to full scale
+fn window_rms(
+    samples: &[i32],
+    sample_rate: u32,
+    channels: usize,
+    bits_per_sample: u32,
+) -> Vec<f64> {
+    let frames_per_window = ((sample_rate as u64 * WINDOW_MS / 1000) as usize).max(1);
+    let full_scale = (1u64 << (bits_per_sample.clamp(8, 32) - 1)) as f64;
+
+    samples
+        .chunks(frames_per_window * channels)
+        .map(|window| {
+            let sum: f64 = window
+                .iter()
+                .map(|&s| {
+                    let v = s as f64 / full_scale;
+                    v * v
+                })
+                .sum();
+            (sum / window.len() as f64).sqrt()
+        })
+        .collect()
+}
+
+/// Estimate the beat grid as (period, phase) in windows.
+///
+/// Autocorrelates the onset envelope (rises in loudness) over the supported
+/// tempo range, then picks the phase that lines up with the most onsets.
+/// Returns `None` when no tempo stands out, e.g. for ambient material.
+fn beat_grid(rms: &[f64]) -> Option<(usize, usize)> {
+    let min_lag = (60_000.0 / MAX_BPM / WINDOW_MS as f64).round() as usize;
+    let max_lag = (60_000.0 / MIN_BPM / WINDOW_MS as f64).round() as usize;
+
+    // onsets[i] is the rise into window i + 1
+    let onsets: Vec<f64> = rms.windows(2).map(|w| (w[1] - w[0]).max(0.0)).collect();
+    if onsets.len() < max_lag * 4 {
+        return None;
+    }
+
+    let mean = onsets.iter().sum::<f64>() / onsets.len() as f64;
+    let centered: Vec<f64> = onsets.iter().map(|o| o - mean).collect();
+    let energy: f64 = centered.iter().map(|o| o * o).sum();
+    if energy <= f64::EPSILON {
+        return None;
+    }
+
+    let mut best: Option<(usize, f64)> = None;
+    for lag in min_lag..=max_lag {
+        let score = centered
+            .iter()
+            .zip(&centered[lag..])
+            .map(|(a, b)| a * b)
+            .sum::<f64>()
+            / energy;
+        // Strictly greater keeps the faster tempo when multiples tie
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((lag, score));
+        }
+    }
+    let (period, confidence) = best?;
+    if confidence < MIN_BEAT_CONFIDENCE {
+        return None;
+    }
+
+    let phase = (0..period)
+        .max_by(|&a, &b| {
+            let strength = |p: usize| onsets.iter().skip(p).step_by(period).sum::<f64>();
+            strength(a).total_cmp(&strength(b))
+        })
+        .unwrap_or(0);
+
+    Some((period, phase + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 8000;
+
+    /// Mono 16-bit signal with a short tone burst at each of `starts_ms`
+    fn bursts(total_ms: u64, starts_ms: &[u64], burst_ms: u64) -> Vec<i32> {
+        let mut samples = vec![0i32; (total_ms * RATE as u64 / 1000) as usize];
+        for &start in starts_ms {
+            let from = (start * RATE as u64 / 1000) as usize;
+            let to = ((start + burst_ms) * RATE as u64 / 1000) as usize;
+            for (i, sample) in samples[from..to].iter_mut().enumerate() {
+                *sample = if (i / 4) % 2 == 0 { 12000 } else { -12000 };
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn trims_silence_and_snaps_to_next_beat() {
+        // 120 BPM clicks from 2s to 6.5s, then silence until 10s
+        let starts: Vec<u64> = (0..10).map(|i| 2000 + i * 500).collect();
+        let samples = bursts(10_000, &starts, 50);
+
+        let cues = detect_cue_points(&samples, RATE, 1, 16);
+
+        assert_eq!(cues.cue_in_ms, 2000);
+        assert_eq!(cues.cue_out_ms, 7000);
+    }
+
+    #[test]
+    fn keeps_short_silence() {
+        let mut samples = vec![0i32; (RATE / 5) as usize];
+        samples.extend(bursts(5000, &[0], 5000));
+        samples.extend(vec![0i32; (RATE / 5) as usize]);
+
+        let cues = detect_cue_points(&samples, RATE, 1, 16);
+
+        assert_eq!(cues.cue_in_ms, 0);
+        assert_eq!(cues.cue_out_ms, 5400);
+    }
+
+    #[test]
+    fn without_a_beat_cues_out_at_audible_end() {
+        let samples = bursts(8000, &[1000], 4000);
+
+        let cues = detect_cue_points(&samples, RATE, 1, 16);
+
+        assert_eq!(cues.cue_in_ms, 1000);
+        assert_eq!(cues.cue_out_ms, 5000);
+    }
+
+    #[test]
+    fn silent_track_spans_whole_length() {
+        let samples = vec![0i32; RATE as usize * 2 * 3];
+
+        let cues = detect_cue_points(&samples, RATE, 2, 16);
+
+        assert_eq!(
+            cues,
+            CuePoints {
+                cue_in_ms: 0,
+                cue_out_ms: 3000
+            }
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Printable recovery kit
+//!
+//! A single self-contained HTML page with everything needed to get a library
+//! back on a new device: the recovery key (as text and as a QR code), its
+//! fingerprint, and where the cloud home lives. It is rendered locally with no
+//! external resources, so it can be saved or printed to PDF from a browser
+//! without anything leaving the machine.
+
+use chrono::Utc;
+use qrcode::render::svg;
+use qrcode::QrCode;
+
+use crate::config::{CloudProvider, Config};
+use crate::encryption::compute_key_fingerprint;
+
+/// What goes on the printed page
+pub struct RecoveryKit {
+    pub library_name: Option<String>,
+    pub library_id: String,
+    pub key_hex: String,
+    pub fingerprint: String,
+    /// Cloud home coordinates as (label, value), empty without a cloud home
+    pub cloud_home: Vec<(&'static str, String)>,
+}
+
+impl RecoveryKit {
+    /// Collect the kit for `config`'s library and its recovery key
+    pub fn new(config: &Config, key_hex: &str) -> Result<Self, String> {
+        let fingerprint =
+            compute_key_fingerprint(key_hex).ok_or("The recovery key is not a valid key")?;
+        Ok(Self {
+            library_name: config.library_name.clone(),
+            library_id: config.library_id.clone(),
+            key_hex: key_hex.to_string(),
+            fingerprint,
+            cloud_home: cloud_home_coordinates(config),
+        })
+    }
+
+    /// Render the kit as a standalone HTML page
+    pub fn render_html(&self) -> Result<String, String> {
+        let qr = QrCode::new(self.key_hex.as_bytes())
+            .map_err(|e| format!("QR generation: {e}"))?
+            .render::<svg::Color>()
+            .min_dimensions(200, 200)
+            .quiet_zone(true)
+            .build();
+        // The XML declaration isn't valid inside an HTML body
+        let qr = qr
+            .find("<svg")
+            .map_or(qr.as_str(), |start| &qr[start..])
+            .to_string();
+
+        let title = escape(self.library_name.as_deref().unwrap_or("bae library"));
+        let mut rows = format!(
+            "<tr><th>Library ID</th><td>{}</td></tr>",
+            escape(&self.library_id)
+        );
+        for (label, value) in &self.cloud_home {
+            rows.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>",
+                escape(label),
+                escape(value)
+            ));
+        }
+
+        Ok(format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Recovery kit: {title}</title>
+<style>
+body {{ font-family: -apple-system, "Helvetica Neue", sans-serif; color: #111; max-width: 640px; margin: 40px auto; padding: 0 24px; }}
+h1 {{ font-size: 22px; margin-bottom: 4px; }}
+.muted {{ color: #666; font-size: 13px; }}
+.key {{ font-family: ui-monospace, Menlo, monospace; font-size: 15px; word-break: break-all; border: 1px solid #999; border-radius: 6px; padding: 12px; margin: 8px 0; }}
+.qr svg {{ width: 200px; height: 200px; }}
+table {{ border-collapse: collapse; width: 100%; font-size: 13px; }}
+th {{ text-align: left; padding: 4px 12px 4px 0; color: #444; white-space: nowrap; vertical-align: top; }}
+td {{ font-family: ui-monospace, Menlo, monospace; word-break: break-all; padding: 4px 0; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="muted">bae recovery kit, created {created}</p>
+<h2>Recovery key</h2>
+<div class="key">{key}</div>
+<p class="muted">Fingerprint {fingerprint}. bae shows the same fingerprint when the key is entered.</p>
+<div class="qr">{qr}</div>
+<h2>Library</h2>
+<table>{rows}</table>
+<p class="muted">Anyone with this key can decrypt your library. Store this page somewhere safe, and print a new one if the key is rotated.</p>
+</body>
+</html>
+"#,
+            created = Utc::now().format("%Y-%m-%d"),
+            key = escape(&self.key_hex),
+            fingerprint = escape(&self.fingerprint),
+        ))
+    }
+}
+
+/// Where the library's cloud home is, from the config
+fn cloud_home_coordinates(config: &Config) -> Vec<(&'static str, String)> {
+    let Some(provider) = &config.cloud_provider else {
+        return Vec::new();
+    };
+    let (name, fields): (&'static str, Vec<(&'static str, &Option<String>)>) = match provider {
+        CloudProvider::S3 => (
+            "S3",
+            vec![
+                ("Bucket", &config.cloud_home_s3_bucket),
+                ("Region", &config.cloud_home_s3_region),
+                ("Endpoint", &config.cloud_home_s3_endpoint),
+                ("Key prefix", &config.cloud_home_s3_key_prefix),
+            ],
+        ),
+        CloudProvider::ICloud => (
+            "iCloud Drive",
+            vec![("Container", &config.cloud_home_icloud_container_path)],
+        ),
+        CloudProvider::GoogleDrive => (
+            "Google Drive",
+            vec![("Folder ID", &config.cloud_home_google_drive_folder_id)],
+        ),
+        CloudProvider::Dropbox => (
+            "Dropbox",
+            vec![("Folder", &config.cloud_home_dropbox_folder_path)],
+        ),
+        CloudProvider::OneDrive => (
+            "OneDrive",
+            vec![
+                ("Drive ID", &config.cloud_home_onedrive_drive_id),
+                ("Folder ID", &config.cloud_home_onedrive_folder_id),
+            ],
+        ),
+        CloudProvider::BaeCloud => (
+            "bae cloud",
+            vec![
+                ("URL", &config.cloud_home_bae_cloud_url),
+                ("Username", &config.cloud_home_bae_cloud_username),
+            ],
+        ),
+        CloudProvider::Lan => ("Local network", vec![]),
+        CloudProvider::Folder => ("Folder", vec![("Path", &config.cloud_home_folder_path)]),
+    };
+
+    let mut coordinates = vec![("Cloud home", name.to_string())];
+    coordinates.extend(
+        fields
+            .into_iter()
+            .filter_map(|(label, value)| value.clone().map(|value| (label, value))),
+    );
+    coordinates
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kit_carries_the_key_fingerprint_and_cloud_home() {
+        let key_hex = "ab".repeat(32);
+        let kit = RecoveryKit {
+            library_name: Some("Glass Harbor <home>".to_string()),
+            library_id: "lib-1".to_string(),
+            key_hex: key_hex.clone(),
+            fingerprint: compute_key_fingerprint(&key_hex).unwrap(),
+            cloud_home: vec![
+                ("Cloud home", "S3".to_string()),
+                ("Bucket", "the-lanterns".to_string()),
+            ],
+        };
+        let html = kit.render_html().unwrap();
+
+        assert!(html.contains(&key_hex));
+        assert!(html.contains(&kit.fingerprint));
+        assert!(html.contains("the-lanterns"));
+        assert!(html.contains("Glass Harbor &lt;home&gt;"));
+        assert!(html.contains("<svg"));
+        assert!(!html.contains("<?xml"));
+        // Nothing is fetched when the page opens
+        assert!(!html.contains("<script") && !html.contains("<link") && !html.contains("src="));
+    }
+}
@@ -0,0 +1,290 @@
+//! Shamir secret sharing of the recovery key
+//!
+//! The 32-byte master key is split into `count` shares, any `threshold` of
+//! which rebuild it; fewer reveal nothing about it. Each key byte is the
+//! constant term of its own random polynomial over GF(256), and a share holds
+//! that polynomial evaluated at the share's index.
+//!
+//! A share prints as `bae-{threshold}-{index}-{64 hex}-{4 hex checksum}`, so
+//! it can go on paper and be typed back in. The checksum catches typos
+//! before the shares are combined.
+
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+/// One share of a split recovery key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryShare {
+    /// Shares needed to rebuild the key
+    pub threshold: u8,
+    /// Point the share was evaluated at, from 1
+    pub index: u8,
+    pub data: [u8; 32],
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum ShareError {
+    #[error("a split needs a threshold of at least 2, and at least that many shares")]
+    InvalidThreshold,
+    #[error("not a recovery share")]
+    Malformed,
+    #[error("share {0} has a typo")]
+    Checksum(u8),
+    #[error("need {needed} different shares, got {got}")]
+    NotEnoughShares { needed: u8, got: usize },
+    #[error("these shares are from different splits")]
+    Mismatched,
+}
+
+/// Split `key` into `count` shares, any `threshold` of which rebuild it
+pub fn split_key(
+    key: &[u8; 32],
+    threshold: u8,
+    count: u8,
+) -> Result<Vec<RecoveryShare>, ShareError> {
+    if threshold < 2 || count < threshold {
+        return Err(ShareError::InvalidThreshold);
+    }
+
+    // coefficients[d][i] multiplies x^d in the polynomial for key byte i
+    let mut coefficients = vec![*key];
+    for _ in 1..threshold {
+        coefficients.push(crate::encryption::generate_random_key());
+    }
+
+    Ok((1..=count)
+        .map(|index| {
+            let mut data = [0u8; 32];
+            for (i, byte) in data.iter_mut().enumerate() {
+                // Horner's rule, highest degree first
+                *byte = coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, coefficient| gf_mul(acc, index) ^ coefficient[i]);
+            }
+            RecoveryShare {
+                threshold,
+                index,
+                data,
+            }
+        })
+        .collect())
+}
+
+/// Rebuild the key from at least `threshold` distinct shares of one split
+pub fn combine_shares(shares: &[RecoveryShare]) -> Result<[u8; 32], ShareError> {
+    let first = shares
+        .first()
+        .ok_or(ShareError::NotEnoughShares { needed: 2, got: 0 })?;
+    let threshold = first.threshold;
+
+    let mut distinct: Vec<&RecoveryShare> = Vec::new();
+    for share in shares {
+        if share.threshold != threshold {
+            return Err(ShareError::Mismatched);
+        }
+        match distinct.iter().find(|s| s.index == share.index) {
+            Some(seen) if seen.data != share.data => return Err(ShareError::Mismatched),
+            Some(_) => {}
+            None => distinct.push(share),
+        }
+    }
+    if distinct.len() < threshold as usize {
+        return Err(ShareError::NotEnoughShares {
+            needed: threshold,
+            got: distinct.len(),
+        });
+    }
+    let points = &distinct[..threshold as usize];
+
+    // Lagrange interpolation at x = 0. In GF(256), subtraction is XOR.
+    let mut key = [0u8; 32];
+    for (j, share) in points.iter().enumerate() {
+        let mut basis = 1u8;
+        for (m, other) in points.iter().enumerate() {
+            if m != j {
+                basis = gf_mul(basis, gf_div(other.index, other.index ^ share.index));
+            }
+        }
+        for (byte, y) in key.iter_mut().zip(share.data.iter()) {
+            *byte ^= gf_mul(*y, basis);
+        }
+    }
+    Ok(key)
+}
+
+/// Rebuild the hex master key from shares entered one per line
+pub fn recover_key_hex(text: &str) -> Result<String, ShareError> {
+    let shares = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<RecoveryShare>, _>>()?;
+    combine_shares(&shares).map(hex::encode)
+}
+
+impl RecoveryShare {
+    fn body(&self) -> String {
+        format!(
+            "bae-{}-{}-{}",
+            self.threshold,
+            self.index,
+            hex::encode(self.data)
+        )
+    }
+}
+
+fn checksum(body: &str) -> String {
+    hex::encode(&Sha256::digest(body.as_bytes())[..2])
+}
+
+impl fmt::Display for RecoveryShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let body = self.body();
+        write!(f, "{}-{}", body, checksum(&body))
+    }
+}
+
+impl FromStr for RecoveryShare {
+    type Err = ShareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Tolerate spaces and case changes from copying off paper
+        let s: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+        let parts: Vec<&str> = s.split('-').collect();
+        let [prefix, threshold, index, data, check] = parts[..] else {
+            return Err(ShareError::Malformed);
+        };
+        if prefix != "bae" {
+            return Err(ShareError::Malformed);
+        }
+        let threshold: u8 = threshold.parse().map_err(|_| ShareError::Malformed)?;
+        let index: u8 = index.parse().map_err(|_| ShareError::Malformed)?;
+        let data: [u8; 32] = hex::decode(data)
+            .ok()
+            .and_then(|data| data.try_into().ok())
+            .ok_or(ShareError::Malformed)?;
+        if threshold < 2 || index == 0 {
+            return Err(ShareError::Malformed);
+        }
+
+        let share = RecoveryShare {
+            threshold,
+            index,
+            data,
+        };
+        if checksum(&share.body()) != check {
+            return Err(ShareError::Checksum(index));
+        }
+        Ok(share)
+    }
+}
+
+/// Multiply in GF(256) with the AES polynomial x^8 + x^4 + x^3 + x + 1
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Divide in GF(256). `b` is never 0 here: it's the XOR of two distinct
+/// share indices.
+fn gf_div(a: u8, b: u8) -> u8 {
+    // b^254 is b's inverse, since b^255 = 1
+    let mut inverse = 1;
+    for _ in 0..254 {
+        inverse = gf_mul(inverse, b);
+    }
+    gf_mul(a, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_threshold_of_shares_rebuilds_the_key() {
+        let key = crate::encryption::generate_random_key();
+        let shares = split_key(&key, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        for picked in [[0, 1, 2], [0, 2, 4], [4, 3, 1], [1, 2, 3]] {
+            let subset: Vec<_> = picked.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(combine_shares(&subset).unwrap(), key);
+        }
+        assert_eq!(combine_shares(&shares).unwrap(), key);
+
+        let typed = format!("{}\n\n{}\n{}\n", shares[3], shares[0], shares[2]);
+        assert_eq!(recover_key_hex(&typed).unwrap(), hex::encode(key));
+    }
+
+    #[test]
+    fn too_few_shares_are_rejected() {
+        let key = crate::encryption::generate_random_key();
+        let shares = split_key(&key, 3, 5).unwrap();
+
+        assert_eq!(
+            combine_shares(&shares[..2]),
+            Err(ShareError::NotEnoughShares { needed: 3, got: 2 })
+        );
+        // The same share twice doesn't count twice
+        let repeated = vec![shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert_eq!(
+            combine_shares(&repeated),
+            Err(ShareError::NotEnoughShares { needed: 3, got: 2 })
+        );
+        assert_eq!(split_key(&key, 1, 5), Err(ShareError::InvalidThreshold));
+        assert_eq!(split_key(&key, 4, 3), Err(ShareError::InvalidThreshold));
+    }
+
+    #[test]
+    fn shares_round_trip_through_text() {
+        let share = RecoveryShare {
+            threshold: 2,
+            index: 2,
+            data: [0x5a; 32],
+        };
+        let text = share.to_string();
+        assert!(text.starts_with("bae-2-2-"));
+
+        assert_eq!(text.parse::<RecoveryShare>().unwrap(), share);
+        let spaced = format!(" {} ", text.to_uppercase().replace('-', " - "));
+        assert_eq!(spaced.parse::<RecoveryShare>().unwrap(), share);
+
+        // One wrong hex digit
+        let mut typo = text.into_bytes();
+        typo[10] = if typo[10] == b'0' { b'1' } else { b'0' };
+        assert_eq!(
+            String::from_utf8(typo).unwrap().parse::<RecoveryShare>(),
+            Err(ShareError::Checksum(2))
+        );
+        assert_eq!(
+            "Glass Harbor".parse::<RecoveryShare>(),
+            Err(ShareError::Malformed)
+        );
+    }
+
+    #[test]
+    fn field_arithmetic() {
+        // Known AES field product
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        for b in 1..=255u8 {
+            assert_eq!(gf_mul(gf_div(1, b), b), 1);
+        }
+    }
+}
@@ -0,0 +1,800 @@
+//! Web UI sign-in for the server
+//!
+//! Browsers sign in with the server username and password, plus a TOTP code
+//! once two-factor authentication is enrolled, and get a session cookie that
+//! the Subsonic auth middleware accepts in place of query-string credentials.
+//! Sessions live in memory, so restarting the server signs everyone out, and
+//! so does reloading settings with a changed username or password.
+
+use crate::db::DbAppPassword;
+use crate::keys::KeyService;
+use crate::subsonic::{
+    match_api_key, match_app_password, validate_auth, SubsonicAuth, SubsonicQuery,
+};
+use crate::totp;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+const SESSION_COOKIE: &str = "bae_session";
+const SESSION_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Failed sign-ins allowed from one address before its new attempts are
+/// refused for `LOCKOUT`. Failures older than `LOCKOUT` are forgotten.
+const MAX_FAILED_LOGINS: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(60);
+const TOTP_ISSUER: &str = "bae";
+
+/// Sessions and two-factor state shared by the auth routes and middleware
+pub struct ServerAuth {
+    /// Replaced when the server reloads its settings
+    auth: RwLock<SubsonicAuth>,
+    key_service: KeyService,
+    /// Enrolled secret; `None` when two-factor authentication is off
+    totp_secret: RwLock<Option<Vec<u8>>>,
+    /// Secret handed out by enrollment, waiting for a confirming code
+    pending_secret: Mutex<Option<Vec<u8>>>,
+    /// Session token -> expiry
+    sessions: Mutex<HashMap<String, Instant>>,
+    /// Failed sign-ins per client address
+    throttles: Mutex<HashMap<IpAddr, LoginThrottle>>,
+}
+
+struct LoginThrottle {
+    failures: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+impl LoginThrottle {
+    fn is_stale(&self, now: Instant) -> bool {
+        match self.locked_until {
+            Some(until) => until <= now,
+            None => self.last_failure + LOCKOUT <= now,
+        }
+    }
+}
+
+/// Why an API client's query credentials were refused
+#[derive(Debug, PartialEq)]
+pub enum ApiAuthError {
+    Throttled,
+    /// Refused with a Subsonic error code and message
+    Refused {
+        code: u32,
+        message: &'static str,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+enum LoginError {
+    WrongCredentials,
+    CodeRequired,
+    WrongCode,
+    Throttled,
+}
+
+#[derive(Serialize)]
+struct SessionStatus {
+    auth_enabled: bool,
+    authenticated: bool,
+    totp_enabled: bool,
+}
+
+#[derive(Deserialize)]
+struct LoginRequest {
+    username: String,
+    password: String,
+    #[serde(default)]
+    code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CodeRequest {
+    code: String,
+}
+
+#[derive(Serialize)]
+struct TotpEnrollment {
+    /// Base32 secret for manual entry
+    secret: String,
+    uri: String,
+    qr_svg: String,
+}
+
+#[derive(Serialize)]
+struct AuthErrorBody {
+    error: String,
+    totp_required: bool,
+}
+
+impl ServerAuth {
+    /// Load the enrolled two-factor secret, if any, from the keyring.
+    pub fn new(auth: SubsonicAuth, key_service: KeyService) -> Self {
+        let totp_secret = key_service.get_server_totp_secret().and_then(|encoded| {
+            let secret = totp::base32_decode(&encoded);
+            if secret.is_none() {
+                warn!("Stored server TOTP secret is not valid base32, ignoring it");
+            }
+            secret
+        });
+        Self {
+            auth: RwLock::new(auth),
+            key_service,
+            totp_secret: RwLock::new(totp_secret),
+            pending_secret: Mutex::new(None),
+            sessions: Mutex::new(HashMap::new()),
+            throttles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn subsonic_auth(&self) -> SubsonicAuth {
+        self.auth.read().unwrap().clone()
+    }
+
+    /// Switch to reloaded credentials, signing out web sessions if they changed.
+    pub fn set_subsonic_auth(&self, auth: SubsonicAuth) {
+        let mut current = self.auth.write().unwrap();
+        let changed = current.enabled != auth.enabled
+            || current.username != auth.username
+            || current.password != auth.password;
+        *current = auth;
+        drop(current);
+        if changed {
+            self.sessions.lock().unwrap().clear();
+
+            info!("Server credentials changed, signed out web sessions");
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.auth.read().unwrap().enabled
+    }
+
+    pub fn totp_enabled(&self) -> bool {
+        self.totp_secret.read().unwrap().is_some()
+    }
+
+    /// Whether the request carries a live session cookie.
+    pub fn has_session(&self, headers: &HeaderMap) -> bool {
+        let Some(token) = session_token(headers) else {
+            return false;
+        };
+        let mut sessions = self.sessions.lock().unwrap();
+        match sessions.get(token) {
+            Some(expiry) if *expiry > Instant::now() => true,
+            Some(_) => {
+                sessions.remove(token);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Check credentials and start a session, returning its token.
+    ///
+    /// Failures are counted per client address, so one client guessing
+    /// passwords doesn't lock everyone else out.
+    fn login(
+        &self,
+        client: IpAddr,
+        username: &str,
+        password: &str,
+        code: Option<&str>,
+    ) -> Result<String, LoginError> {
+        let now = Instant::now();
+        if self.is_throttled(client, now) {
+            return Err(LoginError::Throttled);
+        }
+
+        if !self.password_matches(username, password) {
+            self.record_failure(client, now);
+            return Err(LoginError::WrongCredentials);
+        }
+
+        if let Some(secret) = self.totp_secret.read().unwrap().as_deref() {
+            match code {
+                None => return Err(LoginError::CodeRequired),
+                Some(code) if !totp::verify(secret, code) => {
+                    self.record_failure(client, now);
+                    return Err(LoginError::WrongCode);
+                }
+                Some(_) => {}
+            }
+        }
+
+        self.clear_failures(client);
+
+        Ok(self.start_session(now))
+    }
+
+    /// Start a session, returning its token.
+    fn start_session(&self, now: Instant) -> String {
+        let mut bytes = [0u8; 32];
+        rand::rng().fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, expiry| *expiry > now);
+        sessions.insert(token.clone(), now + SESSION_TTL);
+        token
+    }
+
+    /// Headers carrying a new session's cookie, as if the owner had signed in
+    #[cfg(test)]
+    pub(crate) fn test_session_headers(&self) -> HeaderMap {
+        let token = self.start_session(Instant::now());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("{SESSION_COOKIE}={token}").parse().unwrap(),
+        );
+        headers
+    }
+
+    /// Check an API client's query-string credentials, returning the app
+    /// password it signed in with, if any. An app password can stand in for
+    /// the account password or be sent alone as an OpenSubsonic API key.
+    ///
+    /// Once two-factor authentication is enrolled, only app passwords are
+    /// accepted. Failures count toward the same per-address lockout as web
+    /// sign-in; a client without a known address isn't throttled.
+    pub fn check_query_auth<'a>(
+        &self,
+        client: Option<IpAddr>,
+        query: &SubsonicQuery,
+        app_passwords: &'a [DbAppPassword],
+    ) -> Result<Option<&'a DbAppPassword>, ApiAuthError> {
+        let now = Instant::now();
+        if client.is_some_and(|client| self.is_throttled(client, now)) {
+            return Err(ApiAuthError::Throttled);
+        }
+
+        let result = if query.api_key.is_some() {
+            if query.u.is_some() || query.p.is_some() || query.t.is_some() {
+                Err((
+                    43,
+                    "Multiple conflicting authentication mechanisms provided",
+                ))
+            } else {
+                match_api_key(app_passwords, query)
+                    .map(Some)
+                    .ok_or((44, "Invalid API key"))
+            }
+        } else {
+            let auth = self.subsonic_auth();
+            // With two-factor on, the account password is refused without
+            // being looked at, so the answer doesn't reveal whether it was right.
+            match match_app_password(&auth, app_passwords, query) {
+                Some(app_password) => Ok(Some(app_password)),
+                None if self.totp_enabled() => Err((
+                    40,
+                    "Two-factor authentication is on; sign in with an app password instead",
+                )),
+                None => validate_auth(&auth, query)
+                    .map(|()| None)
+                    .map_err(|message| (40, message)),
+            }
+        };
+
+        if let Some(client) = client {
+            match result {
+                Ok(_) => self.clear_failures(client),
+                Err(_) => self.record_failure(client, now),
+            }
+        }
+        result.map_err(|(code, message)| ApiAuthError::Refused { code, message })
+    }
+
+    /// Whether the account username and password match, compared in
+    /// constant time.
+    fn password_matches(&self, username: &str, password: &str) -> bool {
+        let auth = self.auth.read().unwrap();
+        match (&auth.username, &auth.password) {
+            (Some(expected_username), Some(expected_password)) => {
+                constant_time_eq(username.as_bytes(), expected_username.as_bytes())
+                    & constant_time_eq(password.as_bytes(), expected_password.as_bytes())
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `client` is locked out after too many failed sign-ins.
+    fn is_throttled(&self, client: IpAddr, now: Instant) -> bool {
+        let mut throttles = self.throttles.lock().unwrap();
+        throttles.retain(|_, throttle| !throttle.is_stale(now));
+        throttles
+            .get(&client)
+            .and_then(|throttle| throttle.locked_until)
+            .is_some()
+    }
+
+    fn record_failure(&self, client: IpAddr, now: Instant) {
+        let mut throttles = self.throttles.lock().unwrap();
+        let throttle = throttles.entry(client).or_insert(LoginThrottle {
+            failures: 0,
+            last_failure: now,
+            locked_until: None,
+        });
+        throttle.failures += 1;
+        throttle.last_failure = now;
+        if throttle.failures >= MAX_FAILED_LOGINS {
+            throttle.failures = 0;
+            throttle.locked_until = Some(now + LOCKOUT);
+
+            warn!("Too many failed sign-ins from {client}, refusing its attempts for {LOCKOUT:?}");
+        }
+    }
+
+    /// Forget a client's failures after it signs in.
+    fn clear_failures(&self, client: IpAddr) {
+        self.throttles.lock().unwrap().remove(&client);
+    }
+
+    fn logout(&self, headers: &HeaderMap) {
+        if let Some(token) = session_token(headers) {
+            self.sessions.lock().unwrap().remove(token);
+        }
+    }
+
+    fn status(&self, headers: &HeaderMap) -> SessionStatus {
+        SessionStatus {
+            auth_enabled: self.enabled(),
+            authenticated: !self.enabled() || self.has_session(headers),
+            totp_enabled: self.totp_enabled(),
+        }
+    }
+}
+
+/// Compare two byte strings in time that depends only on their lengths.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Routes for the web UI's sign-in and two-factor settings.
+///
+/// These sit outside the Subsonic auth middleware; the settings routes check
+/// the session themselves. Sign-in throttling reads the peer address, so the
+/// router must be served with `into_make_service_with_connect_info`.
+pub fn create_auth_router(auth: Arc<ServerAuth>) -> Router {
+    Router::new()
+        .route("/auth/session", get(get_session))
+        .route("/auth/login", post(login))
+        .route("/auth/logout", post(logout))
+        .route("/auth/totp/enroll", post(enroll_totp))
+        .route("/auth/totp/confirm", post(confirm_totp))
+        .route("/auth/totp/disable", post(disable_totp))
+        .with_state(auth)
+}
+
+async fn get_session(State(auth): State<Arc<ServerAuth>>, headers: HeaderMap) -> Response {
+    Json(auth.status(&headers)).into_response()
+}
+
+async fn login(
+    State(auth): State<Arc<ServerAuth>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<LoginRequest>,
+) -> Response {
+    if !auth.enabled() {
+        return Json(auth.status(&headers)).into_response();
+    }
+
+    match auth.login(
+        peer.ip(),
+        &request.username,
+        &request.password,
+        request.code.as_deref(),
+    ) {
+        Ok(token) => {
+            info!("Web UI signed in");
+
+            let cookie = format!(
+                "{SESSION_COOKIE}={token}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}",
+                SESSION_TTL.as_secs()
+            );
+            let status = SessionStatus {
+                auth_enabled: true,
+                authenticated: true,
+                totp_enabled: auth.totp_enabled(),
+            };
+            ([(header::SET_COOKIE, cookie)], Json(status)).into_response()
+        }
+        Err(LoginError::WrongCredentials) => error_response(
+            StatusCode::UNAUTHORIZED,
+            "Wrong username or password",
+            false,
+        ),
+        Err(LoginError::CodeRequired) => error_response(
+            StatusCode::UNAUTHORIZED,
+            "Enter the code from your authenticator app",
+            true,
+        ),
+        Err(LoginError::WrongCode) => {
+            error_response(StatusCode::UNAUTHORIZED, "Wrong authentication code", true)
+        }
+        Err(LoginError::Throttled) => error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many failed attempts, try again in a minute",
+            false,
+        ),
+    }
+}
+
+async fn logout(State(auth): State<Arc<ServerAuth>>, headers: HeaderMap) -> Response {
+    auth.logout(&headers);
+    let cookie = format!("{SESSION_COOKIE}=; Path=/; HttpOnly; SameSite=Strict; Max-Age=0");
+    (StatusCode::NO_CONTENT, [(header::SET_COOKIE, cookie)]).into_response()
+}
+
+/// Start enrollment: generate a secret and return it with a QR code.
+/// Two-factor stays off until `confirm_totp` sees a valid code.
+async fn enroll_totp(State(auth): State<Arc<ServerAuth>>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_session(&auth, &headers) {
+        return response;
+    }
+    if auth.totp_enabled() {
+        return error_response(
+            StatusCode::CONFLICT,
+            "Two-factor authentication is already on",
+            false,
+        );
+    }
+
+    let secret = totp::generate_secret();
+    let account = auth
+        .subsonic_auth()
+        .username
+        .unwrap_or_else(|| "bae".to_string());
+    let uri = totp::provisioning_uri(&secret, &account, TOTP_ISSUER);
+    let qr_svg = match QrCode::new(uri.as_bytes()) {
+        Ok(code) => code
+            .render::<svg::Color>()
+            .min_dimensions(200, 200)
+            .quiet_zone(true)
+            .build(),
+        Err(e) => {
+            warn!("Failed to render TOTP QR code: {e}");
+            String::new()
+        }
+    };
+    let enrollment = TotpEnrollment {
+        secret: totp::base32_encode(&secret),
+        uri,
+        qr_svg,
+    };
+    *auth.pending_secret.lock().unwrap() = Some(secret);
+    Json(enrollment).into_response()
+}
+
+async fn confirm_totp(
+    State(auth): State<Arc<ServerAuth>>,
+    headers: HeaderMap,
+    Json(request): Json<CodeRequest>,
+) -> Response {
+    if let Err(response) = require_session(&auth, &headers) {
+        return response;
+    }
+
+    let mut pending = auth.pending_secret.lock().unwrap();
+    let Some(secret) = pending.as_deref() else {
+        return error_response(StatusCode::BAD_REQUEST, "Start enrollment first", false);
+    };
+    if !totp::verify(secret, &request.code) {
+        return error_response(StatusCode::BAD_REQUEST, "Wrong authentication code", false);
+    }
+    if let Err(e) = auth
+        .key_service
+        .set_server_totp_secret(&totp::base32_encode(secret))
+    {
+        warn!("Failed to save TOTP secret: {e}");
+        return error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to save the two-factor secret",
+            false,
+        );
+    }
+
+    info!("Two-factor authentication enabled for the server");
+
+    *auth.totp_secret.write().unwrap() = pending.take();
+    StatusCode::NO_CONTENT.into_response()
+}
+
+async fn disable_totp(
+    State(auth): State<Arc<ServerAuth>>,
+    headers: HeaderMap,
+    Json(request): Json<CodeRequest>,
+) -> Response {
+    if let Err(response) = require_session(&auth, &headers) {
+        return response;
+    }
+
+    let mut secret = auth.totp_secret.write().unwrap();
+    let Some(current) = secret.as_deref() else {
+        return StatusCode::NO_CONTENT.into_response();
+    };
+    if !totp::verify(current, &request.code) {
+        return error_response(StatusCode::BAD_REQUEST, "Wrong authentication code", false);
+    }
+    if let Err(e) = auth.key_service.delete_server_totp_secret() {
+        warn!("Failed to delete TOTP secret: {e}");
+        return error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to remove the two-factor secret",
+            false,
+        );
+    }
+
+    info!("Two-factor authentication disabled for the server");
+
+    *secret = None;
+    StatusCode::NO_CONTENT.into_response()
+}
+
+pub(crate) fn require_session(auth: &ServerAuth, headers: &HeaderMap) -> Result<(), Response> {
+    if !auth.enabled() {
+        return Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Server authentication is off",
+            false,
+        ));
+    }
+    if !auth.has_session(headers) {
+        return Err(error_response(
+            StatusCode::UNAUTHORIZED,
+            "Sign in first",
+            false,
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn error_response(status: StatusCode, message: &str, totp_required: bool) -> Response {
+    let body = AuthErrorBody {
+        error: message.to_string(),
+        totp_required,
+    };
+    (status, Json(body)).into_response()
+}
+
+/// Extract the session token from the request's cookies.
+fn session_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| pair.trim().strip_prefix(SESSION_COOKIE)?.strip_prefix('='))
+        .filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_auth(totp_secret: Option<Vec<u8>>) -> ServerAuth {
+        ServerAuth {
+            auth: RwLock::new(SubsonicAuth {
+                enabled: true,
+                username: Some("admin".to_string()),
+                password: Some("secret".to_string()),
+            }),
+            key_service: KeyService::new(true, "test".to_string()),
+            totp_secret: RwLock::new(totp_secret),
+            pending_secret: Mutex::new(None),
+            sessions: Mutex::new(HashMap::new()),
+            throttles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    const CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 1, 20));
+
+    fn cookie_headers(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            format!("theme=dark; {SESSION_COOKIE}={token}")
+                .parse()
+                .unwrap(),
+        );
+        headers
+    }
+
+    fn current_code(secret: &[u8]) -> String {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        totp::code_at(secret, now)
+    }
+
+    #[test]
+    fn password_login_creates_session() {
+        let auth = server_auth(None);
+        let token = auth.login(CLIENT, "admin", "secret", None).unwrap();
+
+        assert!(auth.has_session(&cookie_headers(&token)));
+        assert!(!auth.has_session(&cookie_headers("forged")));
+        assert!(!auth.has_session(&HeaderMap::new()));
+
+        auth.logout(&cookie_headers(&token));
+        assert!(!auth.has_session(&cookie_headers(&token)));
+    }
+
+    #[test]
+    fn enrolled_totp_requires_valid_code() {
+        let secret = totp::generate_secret();
+        let auth = server_auth(Some(secret.clone()));
+
+        assert_eq!(
+            auth.login(CLIENT, "admin", "secret", None),
+            Err(LoginError::CodeRequired)
+        );
+        assert_eq!(
+            auth.login(CLIENT, "admin", "secret", Some("000000x")),
+            Err(LoginError::WrongCode)
+        );
+        assert!(auth
+            .login(CLIENT, "admin", "secret", Some(&current_code(&secret)))
+            .is_ok());
+    }
+
+    #[test]
+    fn wrong_password_is_rejected_before_totp() {
+        let secret = totp::generate_secret();
+        let auth = server_auth(Some(secret.clone()));
+
+        assert_eq!(
+            auth.login(CLIENT, "admin", "wrong", Some(&current_code(&secret))),
+            Err(LoginError::WrongCredentials)
+        );
+    }
+
+    #[test]
+    fn repeated_failures_lock_out_logins() {
+        let auth = server_auth(None);
+        for _ in 0..MAX_FAILED_LOGINS {
+            assert_eq!(
+                auth.login(CLIENT, "admin", "wrong", None),
+                Err(LoginError::WrongCredentials)
+            );
+        }
+
+        assert_eq!(
+            auth.login(CLIENT, "admin", "secret", None),
+            Err(LoginError::Throttled)
+        );
+    }
+
+    #[test]
+    fn lockout_is_per_client() {
+        let auth = server_auth(None);
+        for _ in 0..MAX_FAILED_LOGINS {
+            let _ = auth.login(CLIENT, "admin", "wrong", None);
+        }
+        assert_eq!(
+            auth.login(CLIENT, "admin", "secret", None),
+            Err(LoginError::Throttled)
+        );
+
+        let other = IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 1, 21));
+        assert!(auth.login(other, "admin", "secret", None).is_ok());
+    }
+
+    #[test]
+    fn changed_credentials_sign_out_sessions() {
+        let auth = server_auth(None);
+        let token = auth.login(CLIENT, "admin", "secret", None).unwrap();
+
+        auth.set_subsonic_auth(auth.subsonic_auth());
+        assert!(auth.has_session(&cookie_headers(&token)));
+
+        auth.set_subsonic_auth(SubsonicAuth {
+            enabled: true,
+            username: Some("admin".to_string()),
+            password: Some("rotated".to_string()),
+        });
+        assert!(!auth.has_session(&cookie_headers(&token)));
+        assert_eq!(
+            auth.login(CLIENT, "admin", "secret", None),
+            Err(LoginError::WrongCredentials)
+        );
+        assert!(auth.login(CLIENT, "admin", "rotated", None).is_ok());
+    }
+
+    fn password_query(password: &str) -> SubsonicQuery {
+        SubsonicQuery {
+            u: Some("admin".to_string()),
+            p: Some(password.to_string()),
+            t: None,
+            s: None,
+            api_key: None,
+        }
+    }
+
+    #[test]
+    fn api_failures_share_the_login_lockout() {
+        let auth = server_auth(None);
+        assert!(matches!(
+            auth.check_query_auth(Some(CLIENT), &password_query("secret"), &[]),
+            Ok(None)
+        ));
+        for _ in 0..MAX_FAILED_LOGINS {
+            assert!(matches!(
+                auth.check_query_auth(Some(CLIENT), &password_query("wrong"), &[]),
+                Err(ApiAuthError::Refused { .. })
+            ));
+        }
+
+        assert_eq!(
+            auth.check_query_auth(Some(CLIENT), &password_query("secret"), &[])
+                .unwrap_err(),
+            ApiAuthError::Throttled
+        );
+        assert_eq!(
+            auth.login(CLIENT, "admin", "secret", None),
+            Err(LoginError::Throttled)
+        );
+    }
+
+    #[test]
+    fn totp_refuses_api_passwords_without_checking_them() {
+        let auth = server_auth(Some(totp::generate_secret()));
+
+        assert_eq!(
+            auth.check_query_auth(None, &password_query("secret"), &[])
+                .unwrap_err(),
+            auth.check_query_auth(None, &password_query("wrong"), &[])
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn totp_accepts_app_passwords_and_refuses_the_rest_alike() {
+        let auth = server_auth(Some(totp::generate_secret()));
+        let app_passwords = vec![DbAppPassword {
+            id: "phone".to_string(),
+            name: "phone".to_string(),
+            password: "aaaa-bbbb-cccc-dddd".to_string(),
+            created_at: chrono::Utc::now(),
+            last_used_at: None,
+            disabled: false,
+        }];
+
+        let matched = auth
+            .check_query_auth(None, &password_query("aaaa-bbbb-cccc-dddd"), &app_passwords)
+            .unwrap();
+        assert_eq!(
+            matched.map(|app_password| app_password.id.as_str()),
+            Some("phone")
+        );
+
+        let refused = |password: &str| {
+            auth.check_query_auth(None, &password_query(password), &app_passwords)
+                .unwrap_err()
+        };
+        assert_eq!(refused("secret"), refused("wrong"));
+        assert_eq!(refused("secret"), refused("eeee-ffff-gggg-hhhh"));
+    }
+
+    #[test]
+    fn constant_time_eq_compares_whole_strings() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secrets"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+}
@@ -0,0 +1,398 @@
+//! Server config file
+//!
+//! `bae-desktop --headless --config server.yaml` reads the settings a server
+//! deployment otherwise passes as a dozen `BAE_*` environment variables from
+//! one YAML file:
+//!
+//! ```yaml
+//! library_path: /srv/bae/Tidewater
+//! encryption_key: 64 hex characters
+//! port: 4533
+//! bind: 0.0.0.0
+//! share_base_url: https://listen.example.com
+//! auth:
+//!   username: admin
+//!   password: a long password
+//! s3:
+//!   bucket: tidewater-sync
+//!   region: us-east-1
+//!   endpoint: https://s3.example.com
+//!   access_key: ...
+//!   secret_key: ...
+//! identity:
+//!   signing_key: 128 hex characters
+//!   public_key: 64 hex characters
+//! ```
+//!
+//! Every setting is optional. Settings go over the library's config.yaml and
+//! secrets are handed to `KeyService` ahead of the keyring; neither touches
+//! the process environment. A `BAE_*` variable the environment sets for the
+//! same setting still wins over the file. The library is found through
+//! `~/.bae` unless `library_path` names it.
+
+use crate::config::Config;
+use crate::keys::{CloudHomeCredentials, SecretOverrides, UserKeypair};
+use crate::library_dir::LibraryDir;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ServerConfigError {
+    #[error("Can't read {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{} isn't a valid server config: {source}", .path.display())]
+    Parse {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+    #[error("{} has problems:\n  - {}", .path.display(), .problems.join("\n  - "))]
+    Invalid {
+        path: PathBuf,
+        problems: Vec<String>,
+    },
+}
+
+/// The contents of a server config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerConfigFile {
+    /// Library folder, in place of the active library
+    pub library_path: Option<PathBuf>,
+    /// Master key, as the 64-character hex recovery key
+    pub encryption_key: Option<String>,
+    pub port: Option<u16>,
+    pub bind: Option<String>,
+    /// Base URL of share links
+    pub share_base_url: Option<String>,
+    /// Server sign-in
+    pub auth: Option<AuthSection>,
+    /// S3 cloud home
+    pub s3: Option<S3Section>,
+    /// The user's signing keypair, so the server keeps its identity across
+    /// restarts
+    pub identity: Option<IdentitySection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuthSection {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct S3Section {
+    pub bucket: String,
+    pub region: String,
+    pub endpoint: Option<String>,
+    pub key_prefix: Option<String>,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdentitySection {
+    pub signing_key: String,
+    pub public_key: String,
+}
+
+impl ServerConfigFile {
+    /// Read and validate the file at `path`.
+    pub fn load(path: &Path) -> Result<Self, ServerConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ServerConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let file: Self =
+            serde_yaml::from_str(&contents).map_err(|source| ServerConfigError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        let problems = file.problems();
+        if !problems.is_empty() {
+            return Err(ServerConfigError::Invalid {
+                path: path.to_path_buf(),
+                problems,
+            });
+        }
+
+        Ok(file)
+    }
+
+    /// The library folder to serve, if `BAE_LIBRARY_PATH` or the file names one
+    pub fn library_path(&self) -> Option<PathBuf> {
+        env("BAE_LIBRARY_PATH")
+            .map(PathBuf::from)
+            .or_else(|| self.library_path.clone())
+    }
+
+    /// Load the served library's settings with the file applied over them.
+    ///
+    /// Panics like `Config::load` when the library can't be found.
+    pub fn load_config(&self) -> Config {
+        let mut config = match self.library_path() {
+            Some(path) => Config::load_for_library(LibraryDir::new(path)),
+            None => Config::load(),
+        };
+        self.apply_to(&mut config);
+        config
+    }
+
+    /// Overlay the file's settings on `config`, with environment variables
+    /// that are set taking precedence.
+    pub fn apply_to(&self, config: &mut Config) {
+        if let Some(port) = self.port {
+            config.server_port = port;
+        }
+        if let Some(bind) = &self.bind {
+            config.server_bind_address = bind.clone();
+        }
+        if let Some(url) = env("BAE_SHARE_BASE_URL").or_else(|| self.share_base_url.clone()) {
+            config.share_base_url = Some(url);
+        }
+        if let Some(key) = self.master_key() {
+            // Keep the library's fingerprint so a wrong key is caught at startup
+            config.encryption_key_stored = true;
+            if config.encryption_key_fingerprint.is_none() {
+                config.encryption_key_fingerprint =
+                    crate::encryption::compute_key_fingerprint(&key);
+            }
+        }
+        let username = env("BAE_SERVER_USERNAME")
+            .or_else(|| self.auth.as_ref().map(|auth| auth.username.clone()));
+        if let Some(username) = username {
+            config.server_auth_enabled = true;
+            config.server_username = Some(username);
+        }
+
+        let s3 = self.s3.as_ref();
+        if let Some(bucket) =
+            env("BAE_CLOUD_HOME_S3_BUCKET").or_else(|| s3.map(|s3| s3.bucket.clone()))
+        {
+            config.cloud_home_s3_bucket = Some(bucket);
+        }
+        if let Some(region) =
+            env("BAE_CLOUD_HOME_S3_REGION").or_else(|| s3.map(|s3| s3.region.clone()))
+        {
+            config.cloud_home_s3_region = Some(region);
+        }
+        if let Some(endpoint) =
+            env("BAE_CLOUD_HOME_S3_ENDPOINT").or_else(|| s3.and_then(|s3| s3.endpoint.clone()))
+        {
+            config.cloud_home_s3_endpoint = Some(endpoint);
+        }
+        if let Some(prefix) =
+            env("BAE_CLOUD_HOME_S3_KEY_PREFIX").or_else(|| s3.and_then(|s3| s3.key_prefix.clone()))
+        {
+            config.cloud_home_s3_key_prefix = Some(prefix);
+        }
+    }
+
+    /// The secrets the file holds, for `KeyService::set_overrides`, with
+    /// environment variables that are set taking precedence.
+    pub fn secrets(&self) -> SecretOverrides {
+        let cloud_home_credentials = match env("BAE_CLOUD_HOME_CREDENTIALS") {
+            Some(json) => serde_json::from_str(&json).ok(),
+            None => self.s3.as_ref().and_then(|s3| {
+                let (access_key, secret_key) = (s3.access_key.clone()?, s3.secret_key.clone()?);
+                Some(CloudHomeCredentials::S3 {
+                    access_key,
+                    secret_key,
+                })
+            }),
+        };
+        let user_keypair = match (env("BAE_USER_SIGNING_KEY"), env("BAE_USER_PUBLIC_KEY")) {
+            (Some(signing_key), Some(public_key)) => parse_keypair(&signing_key, &public_key),
+            _ => self
+                .identity
+                .as_ref()
+                .and_then(|identity| parse_keypair(&identity.signing_key, &identity.public_key)),
+        };
+
+        SecretOverrides {
+            encryption_key: self.master_key(),
+            server_password: env("BAE_SERVER_PASSWORD")
+                .or_else(|| self.auth.as_ref().map(|auth| auth.password.clone())),
+            cloud_home_credentials,
+            user_keypair,
+        }
+    }
+
+    fn master_key(&self) -> Option<String> {
+        env("BAE_ENCRYPTION_KEY").or_else(|| self.encryption_key.clone())
+    }
+
+    /// Settings that can't work, each with how to fix it
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if let Some(path) = &self.library_path {
+            if !path.is_dir() {
+                problems.push(format!(
+                    "library_path: {} isn't a folder; point it at the library folder",
+                    path.display()
+                ));
+            }
+        }
+        if let Some(key) = &self.encryption_key {
+            if !is_hex(key, 64) {
+                problems
+                    .push("encryption_key: expected the 64-character hex recovery key".to_string());
+            }
+        }
+        if let Some(bind) = &self.bind {
+            if bind.parse::<std::net::IpAddr>().is_err() {
+                problems.push(format!(
+                    "bind: {bind} isn't an IP address; use 0.0.0.0 to listen everywhere"
+                ));
+            }
+        }
+        if let Some(url) = &self.share_base_url {
+            if !is_http_url(url) {
+                problems.push(format!(
+                    "share_base_url: {url} should start with https:// or http://"
+                ));
+            }
+        }
+        if let Some(auth) = &self.auth {
+            if auth.username.trim().is_empty() {
+                problems.push("auth.username is empty".to_string());
+            }
+            if auth.password.is_empty() {
+                problems.push("auth.password is empty".to_string());
+            }
+        }
+        if let Some(s3) = &self.s3 {
+            if s3.bucket.trim().is_empty() {
+                problems.push("s3.bucket is empty".to_string());
+            }
+            if s3.region.trim().is_empty() {
+                problems.push(
+                    "s3.region is empty; use us-east-1 if the provider doesn't have regions"
+                        .to_string(),
+                );
+            }
+            if let Some(endpoint) = &s3.endpoint {
+                if !is_http_url(endpoint) {
+                    problems.push(format!(
+                        "s3.endpoint: {endpoint} should start with https:// or http://"
+                    ));
+                }
+            }
+            if s3.access_key.is_some() != s3.secret_key.is_some() {
+                problems.push(
+                    "s3: set both access_key and secret_key, or neither to pass them in \
+                     BAE_CLOUD_HOME_CREDENTIALS"
+                        .to_string(),
+                );
+            }
+        }
+        if let Some(identity) = &self.identity {
+            if !is_hex(&identity.signing_key, 128) {
+                problems.push("identity.signing_key: expected 128 hex characters".to_string());
+            }
+            if !is_hex(&identity.public_key, 64) {
+                problems.push("identity.public_key: expected 64 hex characters".to_string());
+            }
+        }
+
+        problems
+    }
+}
+
+/// A variable the environment sets to something other than an empty string
+fn env(var: &str) -> Option<String> {
+    std::env::var(var).ok().filter(|v| !v.is_empty())
+}
+
+fn parse_keypair(signing_key: &str, public_key: &str) -> Option<UserKeypair> {
+    Some(UserKeypair {
+        signing_key: hex::decode(signing_key).ok()?.try_into().ok()?,
+        public_key: hex::decode(public_key).ok()?.try_into().ok()?,
+    })
+}
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_http_url(value: &str) -> bool {
+    value.starts_with("https://") || value.starts_with("http://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlays_settings_on_the_library() {
+        let library = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            library.path().join("config.yaml"),
+            "library_id: lib-1\ndevice_id: dev-1\nserver_port: 4000\n",
+        )
+        .unwrap();
+        let file: ServerConfigFile = serde_yaml::from_str(&format!(
+            "library_path: {}\n\
+             port: 4533\n\
+             auth:\n  username: admin\n  password: secret\n\
+             s3:\n  bucket: tidewater-sync\n  region: us-east-1\n  \
+             access_key: AKIA\n  secret_key: shh\n",
+            library.path().display()
+        ))
+        .unwrap();
+        assert!(file.problems().is_empty());
+
+        let config = file.load_config();
+        assert_eq!(&*config.library_dir, library.path());
+        assert_eq!(config.library_id, "lib-1");
+        assert_eq!(config.server_port, 4533);
+        assert!(config.server_auth_enabled);
+        assert_eq!(config.server_username.as_deref(), Some("admin"));
+        assert_eq!(
+            config.cloud_home_s3_bucket.as_deref(),
+            Some("tidewater-sync")
+        );
+        assert_eq!(config.cloud_home_s3_endpoint, None);
+        assert!(!config.encryption_key_stored);
+
+        let secrets = file.secrets();
+        assert_eq!(secrets.server_password.as_deref(), Some("secret"));
+        assert!(matches!(
+            secrets.cloud_home_credentials,
+            Some(CloudHomeCredentials::S3 { access_key, .. }) if access_key == "AKIA"
+        ));
+        assert!(secrets.encryption_key.is_none());
+        assert!(secrets.user_keypair.is_none());
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let file: ServerConfigFile = serde_yaml::from_str(
+            "bind: everywhere\n\
+             share_base_url: listen.example.com\n\
+             s3:\n  bucket: tidewater-sync\n  region: ''\n  access_key: AKIA\n",
+        )
+        .unwrap();
+
+        let problems = file.problems();
+        assert_eq!(problems.len(), 4, "{problems:?}");
+        assert!(problems[0].starts_with("bind:"));
+        assert!(problems[3].starts_with("s3: set both"));
+    }
+
+    #[test]
+    fn rejects_unknown_settings() {
+        let error = serde_yaml::from_str::<ServerConfigFile>("s3:\n  bukcet: tidewater\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("unknown field `bukcet`"), "{error}");
+    }
+}
@@ -0,0 +1,299 @@
+//! Server health endpoint
+//!
+//! `GET /health` answers 200 `{"status":"ok"}` while the server is up, for
+//! liveness probes. `GET /health?deep=true` also checks what the server needs
+//! to be useful and answers 503 when any check fails, listing each check with
+//! the reason it failed: the database reads, the last sync is recent, the
+//! cloud home answers, the membership chain validates and the cache disk has
+//! room. Like `/metrics` it needs no sign-in, so the reasons describe the
+//! failure and nothing in the library.
+//!
+//! In maintenance mode the status is `maintenance` and still 200, since the
+//! server keeps streaming; the sync and cloud home checks are skipped because
+//! they're expected to fail while sync is paused.
+
+use crate::cache::CacheConfig;
+use crate::library::SharedLibraryManager;
+use crate::server_metrics::server_metrics;
+use crate::sync::cloud_home_bucket::CloudHomeSyncBucket;
+use crate::sync::invite::load_membership_chain;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Sync loops run every 30 seconds by default; this much longer without a successful
+/// cycle means it's stuck or the cloud home is unreachable
+const MAX_SYNC_AGE_SECS: u64 = 10 * 60;
+/// Free space the cache disk needs for downloads and transcodes
+const MIN_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+/// Time allowed for each check that goes over the network
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct HealthState {
+    library_manager: SharedLibraryManager,
+    /// Sync bucket, for servers that sync
+    bucket: Option<Arc<CloudHomeSyncBucket>>,
+    /// Set in maintenance mode
+    maintenance: Arc<AtomicBool>,
+    cache_dir: PathBuf,
+    started: Instant,
+}
+
+#[derive(Deserialize)]
+struct HealthQuery {
+    #[serde(default)]
+    deep: bool,
+}
+
+#[derive(Serialize)]
+struct HealthReport {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checks: Vec<Check>,
+}
+
+/// The outcome of one check
+#[derive(Debug, PartialEq, Serialize)]
+struct Check {
+    name: &'static str,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl Check {
+    fn new(name: &'static str, result: Result<(), String>) -> Self {
+        Check {
+            name,
+            ok: result.is_ok(),
+            reason: result.err(),
+        }
+    }
+}
+
+/// The `/health` route. Sync checks run only when given the sync `bucket`.
+pub fn create_health_router(
+    library_manager: SharedLibraryManager,
+    bucket: Option<Arc<CloudHomeSyncBucket>>,
+    maintenance: Arc<AtomicBool>,
+) -> Router {
+    let state = Arc::new(HealthState {
+        library_manager,
+        bucket,
+        maintenance,
+        cache_dir: CacheConfig::default().cache_dir,
+        started: Instant::now(),
+    });
+    Router::new()
+        .route("/health", get(get_health))
+        .with_state(state)
+}
+
+async fn get_health(
+    State(state): State<Arc<HealthState>>,
+    Query(query): Query<HealthQuery>,
+) -> Response {
+    let checks = if query.deep {
+        run_checks(&state).await
+    } else {
+        Vec::new()
+    };
+    let (status, label) = if !checks.iter().all(|check| check.ok) {
+        (StatusCode::SERVICE_UNAVAILABLE, "unhealthy")
+    } else if state.maintenance.load(Ordering::Relaxed) {
+        (StatusCode::OK, "maintenance")
+    } else {
+        (StatusCode::OK, "ok")
+    };
+
+    (
+        status,
+        Json(HealthReport {
+            status: label,
+            checks,
+        }),
+    )
+        .into_response()
+}
+
+async fn run_checks(state: &HealthState) -> Vec<Check> {
+    let database = state.library_manager.database();
+    let mut checks = vec![Check::new(
+        "database",
+        database
+            .get_sync_state("local_seq")
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Reading the database failed: {e}")),
+    )];
+
+    let bucket = state
+        .bucket
+        .as_ref()
+        .filter(|_| !state.maintenance.load(Ordering::Relaxed));
+    if let Some(bucket) = bucket {
+        let since_sync = server_metrics()
+            .last_sync_success()
+            .map(|t| (chrono::Utc::now().timestamp() - t).max(0) as u64);
+        checks.push(Check::new(
+            "sync",
+            sync_age(since_sync, state.started.elapsed().as_secs()),
+        ));
+
+        checks.push(Check::new(
+            "cloud_home",
+            with_timeout(async {
+                bucket
+                    .cloud_home()
+                    .exists("snapshot.db.enc")
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| format!("The cloud home didn't answer: {e}"))
+            })
+            .await,
+        ));
+
+        checks.push(Check::new(
+            "membership",
+            with_timeout(async {
+                load_membership_chain(bucket.as_ref())
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| format!("The membership chain is invalid: {e}"))
+            })
+            .await,
+        ));
+    }
+
+    checks.push(Check::new(
+        "cache_disk",
+        free_bytes(&state.cache_dir)
+            .map_err(|e| format!("Couldn't read free space of the cache disk: {e}"))
+            .and_then(free_space),
+    ));
+
+    checks
+}
+
+async fn with_timeout(check: impl Future<Output = Result<(), String>>) -> Result<(), String> {
+    tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("No answer within {}s", CHECK_TIMEOUT.as_secs())))
+}
+
+/// Check the seconds since the last successful sync, None if there was none
+/// since startup `uptime` seconds ago
+fn sync_age(since_sync: Option<u64>, uptime: u64) -> Result<(), String> {
+    match since_sync {
+        Some(age) if age > MAX_SYNC_AGE_SECS => Err(format!("Last successful sync was {age}s ago")),
+        None if uptime > MAX_SYNC_AGE_SECS => {
+            Err(format!("No successful sync since startup {uptime}s ago"))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn free_space(free: u64) -> Result<(), String> {
+    if free < MIN_FREE_BYTES {
+        Err(format!(
+            "Only {} MB free on the cache disk",
+            free / (1024 * 1024)
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+// The statvfs field types differ between platforms
+#[allow(clippy::unnecessary_cast)]
+fn free_bytes(path: &Path) -> std::io::Result<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path =
+        std::ffi::CString::new(path.as_os_str().as_bytes()).map_err(std::io::Error::other)?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn free_bytes(_path: &Path) -> std::io::Result<u64> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_sync_fails_after_a_grace_period() {
+        assert!(sync_age(Some(30), 3600).is_ok());
+        assert_eq!(
+            sync_age(Some(3600), 7200),
+            Err("Last successful sync was 3600s ago".to_string())
+        );
+        // Give the first cycle time to run after startup
+        assert!(sync_age(None, 60).is_ok());
+        assert!(sync_age(None, 3600).is_err());
+    }
+
+    #[test]
+    fn low_disk_space_fails() {
+        assert!(free_space(10 * MIN_FREE_BYTES).is_ok());
+        assert_eq!(
+            free_space(100 * 1024 * 1024),
+            Err("Only 100 MB free on the cache disk".to_string())
+        );
+        assert!(free_bytes(&std::env::temp_dir()).unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn maintenance_mode_is_healthy_and_skips_sync_checks() {
+        use crate::cloud_home::folder::FolderCloudHome;
+        use crate::encryption::EncryptionService;
+        use crate::library::LibraryManager;
+
+        let dir = tempfile::tempdir().unwrap();
+        let database = crate::test_support::test_database(dir.path()).await;
+        // A folder that doesn't exist, so the cloud home check would fail
+        let bucket = CloudHomeSyncBucket::new(
+            Box::new(FolderCloudHome::new(dir.path().join("unplugged"))),
+            EncryptionService::new_with_key(&[7; 32]),
+        );
+        let state = Arc::new(HealthState {
+            library_manager: SharedLibraryManager::new(LibraryManager::new(database, None)),
+            bucket: Some(Arc::new(bucket)),
+            maintenance: Arc::new(AtomicBool::new(true)),
+            cache_dir: dir.path().to_path_buf(),
+            started: Instant::now(),
+        });
+
+        let response = get_health(State(state), Query(HealthQuery { deep: true })).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["status"], "maintenance");
+        let checks: Vec<&str> = report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|check| check["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(checks, ["database", "cache_disk"]);
+    }
+}
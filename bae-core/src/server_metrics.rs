@@ -0,0 +1,276 @@
+//! Server metrics
+//!
+//! A self-hosted server exposes `GET /metrics` next to the Subsonic API, in
+//! the Prometheus text format, so it can be scraped and graphed in Grafana.
+//! The counters live in one process-wide `ServerMetrics` and are updated
+//! where the work happens: the Subsonic router times every request, the
+//! headless sync loop records its cycles, pulls count the images they
+//! download and the S3 cloud home counts failed requests. They start from
+//! zero when the process starts.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Upper bounds of the request latency histogram, in seconds
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Requests to one endpoint
+#[derive(Default)]
+struct EndpointStats {
+    /// Responses by HTTP status
+    responses: BTreeMap<u16, u64>,
+    /// Requests at or under each of `LATENCY_BUCKETS`
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    seconds: f64,
+}
+
+/// Counters exported on `/metrics`
+#[derive(Default)]
+pub struct ServerMetrics {
+    endpoints: Mutex<BTreeMap<String, EndpointStats>>,
+    sync_cycles: AtomicU64,
+    sync_failures: AtomicU64,
+    changesets_applied: AtomicU64,
+    /// Unix time of the last successful sync cycle, 0 before the first
+    last_sync_success: AtomicI64,
+    image_downloads: AtomicU64,
+    image_download_failures: AtomicU64,
+    /// Failed S3 requests by operation
+    s3_errors: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+/// The metrics of this process
+pub fn server_metrics() -> &'static ServerMetrics {
+    static METRICS: OnceLock<ServerMetrics> = OnceLock::new();
+    METRICS.get_or_init(ServerMetrics::default)
+}
+
+impl ServerMetrics {
+    /// Count a request to `endpoint` that got `status` after `elapsed`
+    pub fn record_request(&self, endpoint: &str, status: u16, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let stats = endpoints.entry(endpoint.to_string()).or_default();
+        *stats.responses.entry(status).or_default() += 1;
+        for (bucket, le) in stats.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= le {
+                *bucket += 1;
+            }
+        }
+        stats.count += 1;
+        stats.seconds += seconds;
+    }
+
+    /// Count a sync cycle that went through
+    pub fn record_sync_success(&self, changesets_applied: u64) {
+        self.sync_cycles.fetch_add(1, Ordering::Relaxed);
+        self.changesets_applied
+            .fetch_add(changesets_applied, Ordering::Relaxed);
+        self.last_sync_success
+            .store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+    }
+
+    /// Count a sync cycle that failed
+    pub fn record_sync_failure(&self) {
+        self.sync_cycles.fetch_add(1, Ordering::Relaxed);
+        self.sync_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count an image fetched from the cloud home during a pull
+    pub fn record_image_download(&self, ok: bool) {
+        if ok {
+            self.image_downloads.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.image_download_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Count a failed S3 request
+    pub fn record_s3_error(&self, operation: &'static str) {
+        *self.s3_errors.lock().unwrap().entry(operation).or_default() += 1;
+    }
+
+    /// Unix time of the last successful sync cycle
+    pub fn last_sync_success(&self) -> Option<i64> {
+        Some(self.last_sync_success.load(Ordering::Relaxed)).filter(|&t| t > 0)
+    }
+
+    pub fn sync_failures(&self) -> u64 {
+        self.sync_failures.load(Ordering::Relaxed)
+    }
+
+    /// Failed S3 requests of any operation
+    pub fn s3_errors(&self) -> u64 {
+        self.s3_errors.lock().unwrap().values().sum()
+    }
+
+    /// The metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let endpoints = self.endpoints.lock().unwrap();
+        header(
+            &mut out,
+            "bae_http_requests_total",
+            "counter",
+            "Subsonic API requests by endpoint and HTTP status",
+        );
+        for (endpoint, stats) in endpoints.iter() {
+            for (status, count) in &stats.responses {
+                let _ = writeln!(
+                    out,
+                    "bae_http_requests_total{{endpoint=\"{}\",status=\"{status}\"}} {count}",
+                    escape(endpoint)
+                );
+            }
+        }
+        header(
+            &mut out,
+            "bae_http_request_duration_seconds",
+            "histogram",
+            "Subsonic API request latency by endpoint",
+        );
+        for (endpoint, stats) in endpoints.iter() {
+            let endpoint = escape(endpoint);
+            for (count, le) in stats.buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "bae_http_request_duration_seconds_bucket{{endpoint=\"{endpoint}\",le=\"{le}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "bae_http_request_duration_seconds_bucket{{endpoint=\"{endpoint}\",le=\"+Inf\"}} {}",
+                stats.count
+            );
+            let _ = writeln!(
+                out,
+                "bae_http_request_duration_seconds_sum{{endpoint=\"{endpoint}\"}} {}",
+                stats.seconds
+            );
+            let _ = writeln!(
+                out,
+                "bae_http_request_duration_seconds_count{{endpoint=\"{endpoint}\"}} {}",
+                stats.count
+            );
+        }
+        drop(endpoints);
+
+        counter(
+            &mut out,
+            "bae_sync_cycles_total",
+            "Sync cycles run",
+            &self.sync_cycles,
+        );
+        counter(
+            &mut out,
+            "bae_sync_failures_total",
+            "Sync cycles that failed",
+            &self.sync_failures,
+        );
+        counter(
+            &mut out,
+            "bae_sync_changesets_applied_total",
+            "Changesets from other devices applied",
+            &self.changesets_applied,
+        );
+        header(
+            &mut out,
+            "bae_sync_last_success_timestamp_seconds",
+            "gauge",
+            "Unix time of the last successful sync cycle, 0 if there was none",
+        );
+        let _ = writeln!(
+            out,
+            "bae_sync_last_success_timestamp_seconds {}",
+            self.last_sync_success.load(Ordering::Relaxed)
+        );
+        counter(
+            &mut out,
+            "bae_image_downloads_total",
+            "Images downloaded from the cloud home",
+            &self.image_downloads,
+        );
+        counter(
+            &mut out,
+            "bae_image_download_failures_total",
+            "Images that failed to download from the cloud home",
+            &self.image_download_failures,
+        );
+
+        header(
+            &mut out,
+            "bae_s3_errors_total",
+            "counter",
+            "Failed S3 requests by operation",
+        );
+        for (operation, count) in self.s3_errors.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "bae_s3_errors_total{{operation=\"{operation}\"}} {count}"
+            );
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: &AtomicU64) {
+    header(out, name, "counter", help);
+    let _ = writeln!(out, "{name} {}", value.load(Ordering::Relaxed));
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_requests_as_a_histogram() {
+        let metrics = ServerMetrics::default();
+        metrics.record_request("/rest/getAlbum", 200, Duration::from_millis(20));
+        metrics.record_request("/rest/getAlbum", 200, Duration::from_millis(300));
+        metrics.record_request("/rest/stream", 404, Duration::from_millis(2));
+        metrics.record_sync_success(3);
+        metrics.record_sync_failure();
+        metrics.record_s3_error("get");
+
+        let text = metrics.render();
+        assert!(text
+            .contains("bae_http_requests_total{endpoint=\"/rest/getAlbum\",status=\"200\"} 2\n"));
+        assert!(
+            text.contains("bae_http_requests_total{endpoint=\"/rest/stream\",status=\"404\"} 1\n")
+        );
+        assert!(text.contains(
+            "bae_http_request_duration_seconds_bucket{endpoint=\"/rest/getAlbum\",le=\"0.025\"} 1\n"
+        ));
+        assert!(text.contains(
+            "bae_http_request_duration_seconds_bucket{endpoint=\"/rest/getAlbum\",le=\"0.5\"} 2\n"
+        ));
+        assert!(text
+            .contains("bae_http_request_duration_seconds_count{endpoint=\"/rest/getAlbum\"} 2\n"));
+        assert!(text.contains("bae_sync_cycles_total 2\n"));
+        assert!(text.contains("bae_sync_failures_total 1\n"));
+        assert!(text.contains("bae_sync_changesets_applied_total 3\n"));
+        assert!(!text.contains("bae_sync_last_success_timestamp_seconds 0\n"));
+        assert!(text.contains("bae_s3_errors_total{operation=\"get\"} 1\n"));
+    }
+}
@@ -0,0 +1,71 @@
+//! Server warm-up
+//!
+//! A server that just started, or just applied a sync that changed the
+//! library, answers its first search slowly while SQLite pages the tables in,
+//! and its first album grid slowly while every cover is scaled. The warm-up
+//! does both in the background ahead of time: one search that scans every
+//! searchable table, then the thumbnails of every album cover that aren't
+//! cached yet.
+
+use crate::cover_thumbnails::CoverThumbnails;
+use crate::db::LibraryImageType;
+use crate::library::SharedLibraryManager;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Warm the server up each time `requests` receives, until its senders are
+/// dropped. Requests that arrive during a warm-up run it once more after.
+pub async fn run_warmup(
+    mut requests: mpsc::Receiver<()>,
+    library_manager: SharedLibraryManager,
+    thumbnails: Option<CoverThumbnails>,
+) {
+    while requests.recv().await.is_some() {
+        let started = Instant::now();
+        // An empty query matches every row, and ranking them reads them all
+        if let Err(e) = library_manager.get().search_library("", 1).await {
+            warn!("Search warm-up failed: {e}");
+        }
+
+        let mut covers = 0;
+        if let Some(thumbnails) = &thumbnails {
+            covers = warm_thumbnails(&library_manager, thumbnails).await;
+        }
+
+        info!(
+            covers,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Warm-up finished"
+        );
+    }
+}
+
+/// Render missing thumbnails of every album cover, returning how many covers
+/// there are
+async fn warm_thumbnails(
+    library_manager: &SharedLibraryManager,
+    thumbnails: &CoverThumbnails,
+) -> usize {
+    let albums = match library_manager.get().get_albums(&[]).await {
+        Ok(albums) => albums,
+        Err(e) => {
+            warn!("Thumbnail warm-up couldn't load albums: {e}");
+
+            return 0;
+        }
+    };
+
+    let mut covers = 0;
+    for release_id in albums.iter().filter_map(|a| a.cover_release_id.as_deref()) {
+        let cover = library_manager
+            .get()
+            .get_library_image(release_id, &LibraryImageType::Cover)
+            .await;
+        if let Ok(Some(cover)) = cover {
+            thumbnails.render_missing(&cover).await;
+            covers += 1;
+        }
+    }
+    covers
+}
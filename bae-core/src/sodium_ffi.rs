@@ -4,7 +4,7 @@
 //! - macOS: `brew install libsodium`
 //! - Linux: `apt install libsodium-dev`
 
-use libc::{c_int, c_uchar, c_ulonglong};
+use libc::{c_char, c_int, c_uchar, c_ulonglong};
 
 // XChaCha20-Poly1305 AEAD constants
 pub const NPUBBYTES: usize = 24; // nonce size
@@ -20,6 +20,12 @@ pub const CURVE25519_PUBLICKEYBYTES: usize = 32;
 pub const CURVE25519_SECRETKEYBYTES: usize = 32;
 pub const SEALBYTES: usize = 48; // crypto_box_PUBLICKEYBYTES + crypto_box_MACBYTES = 32 + 16
 
+// Argon2id password hashing constants
+pub const PWHASH_ALG_ARGON2ID13: c_int = 2;
+pub const PWHASH_SALTBYTES: usize = 16;
+pub const PWHASH_OPSLIMIT_MODERATE: u64 = 3;
+pub const PWHASH_MEMLIMIT_MODERATE: usize = 268_435_456; // 256 MiB
+
 extern "C" {
     pub fn sodium_init() -> c_int;
 
@@ -98,4 +104,17 @@ extern "C" {
         pk: *const c_uchar,
         sk: *const c_uchar,
     ) -> c_int;
+
+    // --- Argon2id key derivation ---
+
+    pub fn crypto_pwhash(
+        out: *mut c_uchar,
+        outlen: c_ulonglong,
+        passwd: *const c_char,
+        passwdlen: c_ulonglong,
+        salt: *const c_uchar,
+        opslimit: c_ulonglong,
+        memlimit: usize,
+        alg: c_int,
+    ) -> c_int;
 }
@@ -46,6 +46,29 @@ pub async fn append_pending_deletions(
     Ok(())
 }
 
+/// Take paths back out of the manifest before files are written there again,
+/// as when a release moves back into managed local storage
+pub async fn withdraw_pending_deletions(
+    library_path: &Path,
+    paths: &[String],
+) -> Result<(), std::io::Error> {
+    let manifest_path = library_path.join(MANIFEST_FILENAME);
+    let existing = read_manifest(&manifest_path).await;
+    let remaining: Vec<PendingDeletion> = existing
+        .iter()
+        .filter(|deletion| match deletion {
+            PendingDeletion::Local { path } => !paths.contains(path),
+        })
+        .cloned()
+        .collect();
+    if remaining.len() == existing.len() {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string_pretty(&remaining).map_err(std::io::Error::other)?;
+    tokio::fs::write(&manifest_path, json).await
+}
+
 /// Process all pending deletions from the manifest.
 ///
 /// Called on app startup and after a delay post-transfer.
@@ -157,6 +180,35 @@ mod tests {
         assert_eq!(parsed.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_withdraw_keeps_other_deletions() {
+        let temp = TempDir::new().unwrap();
+        let library_path = temp.path();
+
+        append_pending_deletions(
+            library_path,
+            &[
+                PendingDeletion::Local {
+                    path: "/old/a.flac".to_string(),
+                },
+                PendingDeletion::Local {
+                    path: "/old/b.flac".to_string(),
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+        withdraw_pending_deletions(library_path, &["/old/a.flac".to_string()])
+            .await
+            .unwrap();
+
+        let parsed = read_manifest(&library_path.join(MANIFEST_FILENAME)).await;
+        assert_eq!(parsed.len(), 1);
+        let PendingDeletion::Local { path } = &parsed[0];
+        assert_eq!(path, "/old/b.flac");
+    }
+
     #[tokio::test]
     async fn test_process_deletes_local_files() {
         let temp = TempDir::new().unwrap();
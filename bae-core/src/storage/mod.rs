@@ -4,11 +4,18 @@
 //! content-addressed paths under `storage/{ab}/{cd}/{file_id}` with
 //! optional encryption.
 pub mod cleanup;
+pub mod offline;
 mod reader;
+pub mod scrub;
+pub mod tiering;
 mod traits;
 pub mod transfer;
+pub mod usage;
 
-pub use reader::LocalFileStorage;
+pub use reader::{
+    chunk_aligned_part_size, DownloadPriority, DownloadStats, LocalFileStorage, ParallelDownloader,
+    Readahead, DEFAULT_DOWNLOAD_PARALLELISM, DEFAULT_READAHEAD,
+};
 pub use traits::{ReleaseStorage, ReleaseStorageImpl};
 
 /// Hash-based storage path for a file: `storage/{ab}/{cd}/{file_id}`
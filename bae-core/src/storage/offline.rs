@@ -0,0 +1,231 @@
+//! Offline pins
+//!
+//! A release kept only in the cloud home streams from there when it plays, so
+//! it needs the network. Pinning it downloads its files into the cache, as
+//! stored (encrypted), and keeps them from being evicted; playback reads the
+//! cache before going to the cloud home. Pins belong to this device and live
+//! in the local `offline_pins` table. The cache only holds its pins in memory,
+//! so `restore_pins` applies them again at startup.
+
+use crate::cache::{file_cache_key, CacheError, CacheManager};
+use crate::cloud_storage::CloudStorage;
+use crate::db::DbFile;
+use crate::encryption::ContainerFormat;
+use crate::library::{LibraryError, LibraryManager};
+use crate::storage::{storage_path, DownloadPriority, ParallelDownloader};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// How much of a pinned release is in the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfflineStatus {
+    pub downloaded_files: usize,
+    pub total_files: usize,
+    /// Size of the downloaded files as stored
+    pub downloaded_bytes: u64,
+}
+
+/// Keep a release downloaded on this device, downloading what isn't cached yet
+pub async fn pin_release(
+    library_manager: &LibraryManager,
+    storage: Arc<dyn CloudStorage>,
+    cache: &CacheManager,
+    parallelism: usize,
+    release_id: &str,
+) -> Result<(), LibraryError> {
+    library_manager.add_offline_pin(release_id).await?;
+    download_release(library_manager, storage, cache, parallelism, release_id).await
+}
+
+/// Stop keeping a release downloaded. Its files stay cached until evicted.
+pub async fn unpin_release(
+    library_manager: &LibraryManager,
+    cache: &CacheManager,
+    release_id: &str,
+) -> Result<(), LibraryError> {
+    library_manager.remove_offline_pin(release_id).await?;
+    let files = library_manager.get_files_for_release(release_id).await?;
+    cache.unpin_all(&cache_keys(&files)).await;
+    Ok(())
+}
+
+/// Pin the cached files of every pinned release again, downloading any that
+/// were lost. A release that fails to download doesn't stop the others.
+pub async fn restore_pins(
+    library_manager: &LibraryManager,
+    storage: Arc<dyn CloudStorage>,
+    cache: &CacheManager,
+    parallelism: usize,
+) -> Result<(), LibraryError> {
+    for release_id in library_manager.get_offline_pins().await? {
+        if let Err(e) = download_release(
+            library_manager,
+            storage.clone(),
+            cache,
+            parallelism,
+            &release_id,
+        )
+        .await
+        {
+            warn!("Failed to download pinned release {}: {}", release_id, e);
+        }
+    }
+    Ok(())
+}
+
+/// How much of a release is downloaded, or None if it isn't pinned
+pub async fn offline_status(
+    library_manager: &LibraryManager,
+    cache: &CacheManager,
+    release_id: &str,
+) -> Result<Option<OfflineStatus>, LibraryError> {
+    if !library_manager
+        .get_offline_pins()
+        .await?
+        .iter()
+        .any(|id| id == release_id)
+    {
+        return Ok(None);
+    }
+    let files = library_manager.get_files_for_release(release_id).await?;
+    let mut status = OfflineStatus {
+        downloaded_files: 0,
+        total_files: files.len(),
+        downloaded_bytes: 0,
+    };
+    for key in cache_keys(&files) {
+        if let Some(size) = cache.cached_size(&key).await {
+            status.downloaded_files += 1;
+            status.downloaded_bytes += size;
+        }
+    }
+    Ok(Some(status))
+}
+
+/// Pin a release's files in the cache and download the ones it doesn't have
+async fn download_release(
+    library_manager: &LibraryManager,
+    storage: Arc<dyn CloudStorage>,
+    cache: &CacheManager,
+    parallelism: usize,
+    release_id: &str,
+) -> Result<(), LibraryError> {
+    let files = library_manager.get_files_for_release(release_id).await?;
+    let keys = cache_keys(&files);
+    cache.pin_all(&keys).await;
+
+    let encrypted = library_manager.encryption_service().is_some();
+    let downloader = ParallelDownloader::new(storage, parallelism);
+    let mut downloaded = 0;
+    for (file, key) in files.iter().zip(&keys) {
+        if cache.contains(key).await {
+            continue;
+        }
+        let stored_len = if encrypted {
+            let prefix = file.encryption_nonce.as_deref().unwrap_or_default();
+            ContainerFormat::detect(prefix)?.encrypted_len(file.file_size as u64)
+        } else {
+            file.file_size as u64
+        };
+        let (data, _) = downloader
+            .download_range_to_vec(
+                &storage_path(&file.id),
+                0,
+                stored_len,
+                DownloadPriority::Export,
+            )
+            .await?;
+        cache.put(key, &data).await.map_err(|e| match e {
+            CacheError::Io(e) => LibraryError::Io(e),
+        })?;
+        downloaded += 1;
+    }
+
+    if downloaded > 0 {
+        info!(
+            "Downloaded {} files of pinned release {}",
+            downloaded, release_id
+        );
+    }
+    Ok(())
+}
+
+fn cache_keys(files: &[DbFile]) -> Vec<String> {
+    files.iter().map(|f| file_cache_key(&f.id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheConfig;
+    use crate::content_type::ContentType;
+    use crate::db::{Database, DbAlbum, DbRelease};
+    use crate::test_support::MockCloudStorage;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn pinned_release_stays_cached_until_unpinned() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = Database::new(temp_dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let manager = LibraryManager::new(database, None);
+        let db = manager.database();
+
+        let album = DbAlbum::new_test("Tidewater");
+        db.insert_album(&album).await.unwrap();
+        let mut release = DbRelease::new_test(&album.id, "release-1");
+        release.managed_locally = false;
+        release.managed_in_cloud = true;
+        db.insert_release(&release).await.unwrap();
+
+        let storage = Arc::new(MockCloudStorage::new());
+        for (name, size) in [("01.flac", 60), ("02.flac", 40)] {
+            let file = DbFile::new(&release.id, name, size, ContentType::Flac);
+            db.insert_file(&file).await.unwrap();
+            storage
+                .upload(&storage_path(&file.id), &vec![1; size as usize])
+                .await
+                .unwrap();
+        }
+
+        // Room for the whole release, but not for anything next to it
+        let cache = CacheManager::with_config(CacheConfig {
+            cache_dir: temp_dir.path().join("cache"),
+            max_size_bytes: 100,
+            max_files: 10,
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            offline_status(&manager, &cache, &release.id).await.unwrap(),
+            None
+        );
+
+        pin_release(&manager, storage, &cache, 2, &release.id)
+            .await
+            .unwrap();
+        cache.put("file:other", &[0; 10]).await.unwrap();
+        let downloaded = OfflineStatus {
+            downloaded_files: 2,
+            total_files: 2,
+            downloaded_bytes: 100,
+        };
+        assert_eq!(
+            offline_status(&manager, &cache, &release.id).await.unwrap(),
+            Some(downloaded)
+        );
+        assert_eq!(manager.get_offline_pins().await.unwrap(), vec!["release-1"]);
+
+        unpin_release(&manager, &cache, &release.id).await.unwrap();
+        cache.put("file:more", &[0; 90]).await.unwrap();
+        assert_eq!(
+            offline_status(&manager, &cache, &release.id).await.unwrap(),
+            None
+        );
+        let files = manager.get_files_for_release(&release.id).await.unwrap();
+        for key in cache_keys(&files) {
+            assert!(!cache.contains(&key).await);
+        }
+    }
+}
@@ -1,7 +1,207 @@
 //! Storage reader utilities
 use crate::cloud_storage::{CloudStorage, CloudStorageError};
+use crate::encryption::ENCRYPTED_CHUNK_SIZE;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
+/// Concurrent range requests per download when not configured
+pub const DEFAULT_DOWNLOAD_PARALLELISM: usize = 4;
+
+/// Bytes a playback download runs ahead of its reader when not configured
+pub const DEFAULT_READAHEAD: u64 = 16 * 1024 * 1024;
+
+/// How often a download held back by its readahead window checks the reader
+const READAHEAD_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Bytes fetched per range request. A whole number of encrypted chunks, so
+/// every part of an encrypted file can be decrypted on its own.
+pub const DOWNLOAD_PART_SIZE: u64 = 16 * ENCRYPTED_CHUNK_SIZE as u64;
+
+/// Largest whole number of `encrypted_chunk_size`-byte chunks that fits in
+/// a download part, so parts of files with larger chunks stay decryptable.
+pub fn chunk_aligned_part_size(encrypted_chunk_size: usize) -> u64 {
+    let chunk = encrypted_chunk_size as u64;
+    (DOWNLOAD_PART_SIZE / chunk).max(1) * chunk
+}
+
+/// Order in which a download's parts are fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadPriority {
+    /// Fetch the first part alone, then the rest in parallel, delivered in
+    /// order so playback can start before the download finishes.
+    Playback,
+    /// Fetch all parts in parallel, delivered as they complete.
+    Export,
+}
+
+/// Bandwidth used by a single download
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DownloadStats {
+    pub bytes: u64,
+    pub requests: usize,
+    pub elapsed: Duration,
+}
+
+impl DownloadStats {
+    pub fn bytes_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.bytes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Keeps a download a bounded distance ahead of whoever reads it
+pub struct Readahead {
+    /// Bytes past the reader a part may start at
+    pub window: u64,
+    /// How far into the range the reader is, or None once it has stopped
+    pub position: Box<dyn Fn() -> Option<u64> + Send + Sync>,
+}
+
+impl Readahead {
+    /// Wait until the part at `offset` into the range is within the window
+    async fn wait_for(&self, offset: u64) -> Result<(), CloudStorageError> {
+        loop {
+            match (self.position)() {
+                None => {
+                    return Err(CloudStorageError::Download(
+                        "reader stopped before the download finished".to_string(),
+                    ))
+                }
+                Some(position) if offset < position.saturating_add(self.window) => return Ok(()),
+                Some(_) => tokio::time::sleep(READAHEAD_POLL_INTERVAL).await,
+            }
+        }
+    }
+}
+
+/// Downloads a byte range as several concurrent range requests.
+pub struct ParallelDownloader {
+    storage: Arc<dyn CloudStorage>,
+    parallelism: usize,
+    part_size: u64,
+    readahead: Option<Readahead>,
+}
+
+impl ParallelDownloader {
+    pub fn new(storage: Arc<dyn CloudStorage>, parallelism: usize) -> Self {
+        Self {
+            storage,
+            parallelism: parallelism.max(1),
+            part_size: DOWNLOAD_PART_SIZE,
+            readahead: None,
+        }
+    }
+
+    pub fn with_part_size(mut self, part_size: u64) -> Self {
+        self.part_size = part_size.max(1);
+        self
+    }
+
+    /// Hold each part back until the reader is within the readahead window
+    /// of it. Parts inside the window are still fetched in parallel.
+    pub fn with_readahead(mut self, readahead: Readahead) -> Self {
+        self.readahead = Some(readahead);
+        self
+    }
+
+    /// Download `[start, end)`, calling `on_part(offset, bytes)` for each part.
+    pub async fn download_range<F>(
+        &self,
+        path: &str,
+        start: u64,
+        end: u64,
+        priority: DownloadPriority,
+        mut on_part: F,
+    ) -> Result<DownloadStats, CloudStorageError>
+    where
+        F: FnMut(u64, Vec<u8>) -> Result<(), CloudStorageError>,
+    {
+        let started = Instant::now();
+        let mut stats = DownloadStats::default();
+        let mut parts = split_range(start, end, self.part_size);
+
+        if priority == DownloadPriority::Playback && !parts.is_empty() {
+            let (part_start, part_end) = parts.remove(0);
+            let data = self
+                .storage
+                .download_range(path, part_start, part_end)
+                .await?;
+            stats.bytes += data.len() as u64;
+            stats.requests += 1;
+            on_part(part_start, data)?;
+        }
+
+        let fetches = stream::iter(parts).map(|(part_start, part_end)| {
+            let storage = self.storage.clone();
+            let path = path.to_string();
+            let readahead = self.readahead.as_ref();
+            async move {
+                if let Some(readahead) = readahead {
+                    readahead.wait_for(part_start - start).await?;
+                }
+                let data = storage.download_range(&path, part_start, part_end).await?;
+                Ok::<_, CloudStorageError>((part_start, data))
+            }
+        });
+        let mut results: BoxStream<'_, Result<(u64, Vec<u8>), CloudStorageError>> = match priority {
+            DownloadPriority::Playback => fetches.buffered(self.parallelism).boxed(),
+            DownloadPriority::Export => fetches.buffer_unordered(self.parallelism).boxed(),
+        };
+
+        while let Some(result) = results.next().await {
+            let (offset, data) = result?;
+            stats.bytes += data.len() as u64;
+            stats.requests += 1;
+            on_part(offset, data)?;
+        }
+
+        stats.elapsed = started.elapsed();
+        Ok(stats)
+    }
+
+    /// Download `[start, end)` into one contiguous buffer.
+    pub async fn download_range_to_vec(
+        &self,
+        path: &str,
+        start: u64,
+        end: u64,
+        priority: DownloadPriority,
+    ) -> Result<(Vec<u8>, DownloadStats), CloudStorageError> {
+        let mut data = vec![0u8; end.saturating_sub(start) as usize];
+        let mut filled = 0usize;
+        let stats = self
+            .download_range(path, start, end, priority, |offset, part| {
+                let pos = (offset - start) as usize;
+                let len = part.len().min(data.len() - pos);
+                data[pos..pos + len].copy_from_slice(&part[..len]);
+                filled = filled.max(pos + len);
+                Ok(())
+            })
+            .await?;
+        // Storage may return less than requested at the end of the object
+        data.truncate(filled);
+        Ok((data, stats))
+    }
+}
+
+/// Split `[start, end)` into consecutive ranges of at most `part_size` bytes
+fn split_range(start: u64, end: u64, part_size: u64) -> Vec<(u64, u64)> {
+    let mut parts = Vec::new();
+    let mut pos = start;
+    while pos < end {
+        let part_end = (pos + part_size).min(end);
+        parts.push((pos, part_end));
+        pos = part_end;
+    }
+    parts
+}
+
 /// Local file storage that reads files from disk paths.
 pub struct LocalFileStorage;
 
@@ -47,3 +247,130 @@ impl CloudStorage for LocalFileStorage {
             .map_err(CloudStorageError::Io)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Mutex;
+    use tempfile::NamedTempFile;
+
+    fn test_file(len: usize) -> (NamedTempFile, Vec<u8>) {
+        let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&data).unwrap();
+        file.flush().unwrap();
+        (file, data)
+    }
+
+    #[test]
+    fn split_range_covers_range_without_gaps() {
+        assert_eq!(split_range(10, 35, 10), vec![(10, 20), (20, 30), (30, 35)]);
+        assert!(split_range(5, 5, 10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn playback_delivers_parts_in_order() {
+        let (file, _) = test_file(10_000);
+        let path = file.path().to_str().unwrap();
+        let downloader =
+            ParallelDownloader::new(Arc::new(LocalFileStorage), 3).with_part_size(1024);
+
+        let mut offsets = Vec::new();
+        let stats = downloader
+            .download_range(path, 100, 9_000, DownloadPriority::Playback, |offset, _| {
+                offsets.push(offset);
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let mut sorted = offsets.clone();
+        sorted.sort();
+        assert_eq!(offsets, sorted);
+        assert_eq!(offsets[0], 100);
+        assert_eq!(stats.bytes, 8_900);
+        assert_eq!(stats.requests, 9);
+    }
+
+    #[tokio::test]
+    async fn readahead_holds_parts_until_the_reader_catches_up() {
+        let (file, _) = test_file(8_192);
+        let path = file.path().to_str().unwrap().to_string();
+        let position = Arc::new(AtomicU64::new(0));
+        let reader_position = position.clone();
+        let downloader = ParallelDownloader::new(Arc::new(LocalFileStorage), 4)
+            .with_part_size(1024)
+            .with_readahead(Readahead {
+                window: 2048,
+                position: Box::new(move || Some(reader_position.load(Ordering::SeqCst))),
+            });
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let parts = received.clone();
+        let download = tokio::spawn(async move {
+            downloader
+                .download_range(&path, 0, 8_192, DownloadPriority::Playback, |offset, _| {
+                    parts.lock().unwrap().push(offset);
+                    Ok(())
+                })
+                .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(*received.lock().unwrap(), vec![0, 1024]);
+
+        position.store(8_192, Ordering::SeqCst);
+        let stats = download.await.unwrap().unwrap();
+        assert_eq!(stats.requests, 8);
+    }
+
+    #[tokio::test]
+    async fn readahead_stops_when_the_reader_does() {
+        let (file, _) = test_file(4_096);
+        let path = file.path().to_str().unwrap();
+        let downloader = ParallelDownloader::new(Arc::new(LocalFileStorage), 2)
+            .with_part_size(1024)
+            .with_readahead(Readahead {
+                window: 1024,
+                position: Box::new(|| None),
+            });
+
+        let result = downloader
+            .download_range(path, 0, 4_096, DownloadPriority::Playback, |_, _| Ok(()))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn export_reassembles_full_range() {
+        let (file, data) = test_file(50_000);
+        let path = file.path().to_str().unwrap();
+        let downloader =
+            ParallelDownloader::new(Arc::new(LocalFileStorage), 8).with_part_size(4096);
+
+        let (downloaded, stats) = downloader
+            .download_range_to_vec(path, 0, data.len() as u64, DownloadPriority::Export)
+            .await
+            .unwrap();
+
+        assert_eq!(downloaded, data);
+        assert_eq!(stats.bytes, data.len() as u64);
+        assert_eq!(stats.requests, 13);
+    }
+
+    #[tokio::test]
+    async fn download_range_to_vec_truncates_past_end_of_object() {
+        let (file, data) = test_file(1_000);
+        let path = file.path().to_str().unwrap();
+        let downloader = ParallelDownloader::new(Arc::new(LocalFileStorage), 2).with_part_size(300);
+
+        let (downloaded, _) = downloader
+            .download_range_to_vec(path, 500, 2_000, DownloadPriority::Export)
+            .await
+            .unwrap();
+
+        assert_eq!(downloaded, data[500..]);
+    }
+}
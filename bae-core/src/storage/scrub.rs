@@ -0,0 +1,174 @@
+//! Background storage scrub
+//!
+//! A slow, scheduled version of the library integrity check. Each run takes
+//! the releases that haven't been verified in `SCRUB_INTERVAL`, least
+//! recently verified first, and reads back some or all of their files from
+//! every place they're kept. Decrypting a file checks the Poly1305 tag of
+//! each of its chunks, and FLAC files are decoded against the MD5 in their
+//! STREAMINFO. When each release was last verified is kept in the local
+//! `storage_scrubs` table, so the work spreads across runs and app launches.
+
+use crate::cloud_storage::CloudStorage;
+use crate::library::integrity::{
+    add_broken_release, check_file, release_locations, IntegrityReport,
+};
+use crate::library::{LibraryError, LibraryManager};
+use crate::library_dir::LibraryDir;
+use rand::seq::SliceRandom;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+/// How long a verified release goes before it's scrubbed again
+pub const SCRUB_INTERVAL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Which files of a release a scrub reads back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrubSample {
+    All,
+    /// Up to this many files, picked at random
+    Random(usize),
+}
+
+/// Scrub up to `max_releases` releases that are due.
+///
+/// `cloud_storage` is the library's cloud home, if it has one. Releases only
+/// kept in the cloud are left due without it, and counted as skipped.
+pub async fn scrub_storage(
+    library_manager: &LibraryManager,
+    library_dir: &LibraryDir,
+    cloud_storage: Option<Arc<dyn CloudStorage>>,
+    sample: ScrubSample,
+    max_releases: usize,
+) -> Result<IntegrityReport, LibraryError> {
+    let db = library_manager.database();
+    let verified_since = chrono::Utc::now().timestamp() - SCRUB_INTERVAL.as_secs() as i64;
+    let candidates = library_manager
+        .get_storage_scrub_candidates(verified_since)
+        .await?;
+
+    let mut report = IntegrityReport::default();
+    for release_id in candidates {
+        if report.releases_checked >= max_releases {
+            break;
+        }
+        let Some(release) = db.get_release_by_id(&release_id).await? else {
+            continue;
+        };
+        let locations = release_locations(&release, cloud_storage.is_some());
+        if locations.is_empty() {
+            if release.managed_in_cloud {
+                report.releases_skipped += 1;
+            }
+            continue;
+        }
+
+        let files = db.get_files_for_release(&release.id).await?;
+        let file_count = files.len();
+        let mut sampled = files;
+        if let ScrubSample::Random(count) = sample {
+            sampled.shuffle(&mut rand::rng());
+            sampled.truncate(count);
+        }
+
+        let mut broken_files = Vec::new();
+        for file in &sampled {
+            for &location in &locations {
+                if let Some(broken) = check_file(
+                    library_manager,
+                    library_dir,
+                    cloud_storage.as_deref(),
+                    &release,
+                    file,
+                    location,
+                )
+                .await
+                {
+                    broken_files.push(broken);
+                }
+            }
+        }
+        library_manager
+            .record_storage_scrub(&release.id, broken_files.len())
+            .await?;
+        report.releases_checked += 1;
+        report.files_checked += sampled.len();
+
+        if !broken_files.is_empty() {
+            add_broken_release(
+                library_manager,
+                &mut report,
+                &release,
+                file_count,
+                broken_files,
+            )
+            .await?;
+        }
+    }
+
+    if report.releases_checked > 0 {
+        info!(
+            "Scrubbed {} files of {} releases, {} albums with broken files",
+            report.files_checked,
+            report.releases_checked,
+            report.albums.len()
+        );
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content_type::ContentType;
+    use crate::db::DbFile;
+    use crate::library::integrity::FileProblem;
+    use crate::test_support::{create_test_album, create_test_release, test_database};
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn scrubbed_releases_wait_for_the_next_interval() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = test_database(temp_dir.path()).await;
+        let manager = LibraryManager::new(database, None);
+        let library_dir = LibraryDir::new(temp_dir.path().to_path_buf());
+
+        let folder = temp_dir.path().join("The Lanterns");
+        std::fs::create_dir_all(&folder).unwrap();
+        let mut album = create_test_album();
+        album.title = "Pacific Standard".to_string();
+        let mut release = create_test_release(&album.id);
+        release.unmanaged_path = Some(folder.display().to_string());
+        manager.database().insert_album(&album).await.unwrap();
+        manager.database().insert_release(&release).await.unwrap();
+        for name in ["01.flac", "02.flac", "03.flac"] {
+            std::fs::write(folder.join(name), b"Low Light").unwrap();
+            let file = DbFile::new(&release.id, name, 9, ContentType::Flac);
+            manager.database().insert_file(&file).await.unwrap();
+        }
+        std::fs::remove_file(folder.join("02.flac")).unwrap();
+
+        let report = scrub_storage(&manager, &library_dir, None, ScrubSample::All, 10)
+            .await
+            .unwrap();
+        assert_eq!(report.releases_checked, 1);
+        assert_eq!(report.files_checked, 3);
+        let broken = &report.albums[0].releases[0].broken_files;
+        assert_eq!(broken.len(), 1);
+        assert_eq!(broken[0].filename, "02.flac");
+        assert_eq!(broken[0].problem, FileProblem::Missing);
+        assert!(manager.get_last_storage_scrub().await.unwrap().is_some());
+
+        // Verified just now, so not due again
+        let report = scrub_storage(&manager, &library_dir, None, ScrubSample::Random(1), 10)
+            .await
+            .unwrap();
+        assert_eq!(report.releases_checked, 0);
+
+        let later = Utc::now().timestamp() + 1;
+        let due = manager.get_storage_scrub_candidates(later).await.unwrap();
+        assert_eq!(due, vec![release.id.clone()]);
+    }
+}
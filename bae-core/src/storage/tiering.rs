@@ -0,0 +1,97 @@
+//! Tiered storage
+//!
+//! Keeps the albums in rotation on this device and the rest in the cloud
+//! home. With an offload period set, a release kept locally that hasn't been
+//! played or changed in that long moves to the cloud home, and a release only
+//! in the cloud home comes back when one of its tracks plays. Until it's back,
+//! playback streams it from the cloud home as it would any cloud release. Both
+//! moves are ordinary storage transfers (see `transfer`), so nothing is
+//! removed before the new copy verifies and an interrupted move resumes.
+
+use crate::db::DbRelease;
+use crate::library::{LibraryError, LibraryManager};
+
+/// Releases to move to the cloud home: kept on this device and neither
+/// played nor changed in `offload_after_days`, least recently played first
+pub async fn offload_candidates(
+    library_manager: &LibraryManager,
+    offload_after_days: u32,
+) -> Result<Vec<String>, LibraryError> {
+    let idle_since = chrono::Utc::now() - chrono::Duration::days(offload_after_days as i64);
+    library_manager.get_offload_candidates(idle_since).await
+}
+
+/// Whether playing `release` should bring its files back to this device
+pub fn needs_recall(release: &DbRelease) -> bool {
+    release.managed_in_cloud && !release.managed_locally
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{DbTrack, ImportStatus};
+    use crate::test_support::{create_test_album, create_test_release, test_database};
+    use chrono::{Duration, Utc};
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    fn release(album_id: &str, managed_locally: bool, days_ago: i64) -> DbRelease {
+        let mut release = create_test_release(album_id);
+        release.managed_locally = managed_locally;
+        release.managed_in_cloud = !managed_locally;
+        release.created_at = Utc::now() - Duration::days(days_ago);
+        release.updated_at = release.created_at;
+        release
+    }
+
+    fn track(release_id: &str) -> DbTrack {
+        DbTrack {
+            id: Uuid::new_v4().to_string(),
+            release_id: release_id.to_string(),
+            title: "Low Light".to_string(),
+            disc_number: None,
+            track_number: Some(1),
+            duration_ms: None,
+            discogs_position: None,
+            import_status: ImportStatus::Complete,
+            updated_at: Utc::now(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn idle_local_releases_are_offloaded_and_played_ones_stay() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = test_database(temp_dir.path()).await;
+        let manager = LibraryManager::new(database, None);
+        let db = manager.database();
+
+        let album = create_test_album();
+        db.insert_album(&album).await.unwrap();
+
+        let idle = release(&album.id, true, 100);
+        let played = release(&album.id, true, 100);
+        let recent = release(&album.id, true, 2);
+        let in_cloud = release(&album.id, false, 100);
+        for release in [&idle, &played, &recent, &in_cloud] {
+            db.insert_release(release).await.unwrap();
+        }
+        let played_track = track(&played.id);
+        db.insert_track(&played_track).await.unwrap();
+        manager
+            .record_play_at(&played_track.id, Utc::now() - Duration::days(3))
+            .await
+            .unwrap();
+
+        let candidates = offload_candidates(&manager, 30).await.unwrap();
+        assert_eq!(candidates, vec![idle.id.clone()]);
+        assert!(needs_recall(&in_cloud));
+        assert!(!needs_recall(&idle));
+
+        // A release already moving isn't picked again
+        db.begin_storage_transfer(&idle.id, "cloud", None)
+            .await
+            .unwrap();
+        assert!(offload_candidates(&manager, 30).await.unwrap().is_empty());
+    }
+}
@@ -2,6 +2,8 @@
 use crate::content_type::ContentType;
 use crate::db::{Database, DbFile};
 use crate::encryption::EncryptionService;
+use crate::error_code::{ErrorCode, HasErrorCode};
+use crate::library::duplicates::sha256_hex;
 use crate::library_dir::LibraryDir;
 use crate::storage::storage_path;
 use async_trait::async_trait;
@@ -21,6 +23,17 @@ pub enum StorageError {
     Database(String),
 }
 
+impl HasErrorCode for StorageError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            StorageError::Io(_) => ErrorCode::Io,
+            StorageError::NotConfigured => ErrorCode::StorageNotConfigured,
+            StorageError::Cloud(_) => ErrorCode::StorageUnavailable,
+            StorageError::Database(_) => ErrorCode::Database,
+        }
+    }
+}
+
 /// Progress callback type: (bytes_written, total_bytes)
 pub type ProgressCallback = Box<dyn Fn(usize, usize) + Send + Sync>;
 
@@ -83,10 +96,13 @@ impl ReleaseStorageImpl {
 
         let data_to_store = self.encrypt_if_needed(data)?;
 
-        let nonce = if self.encryption.is_some() && data_to_store.len() >= 24 {
-            Some(data_to_store[..24].to_vec())
-        } else {
-            None
+        // Keep the container prefix (header + nonce) so range reads can
+        // decrypt without fetching the start of the file
+        let nonce = match &self.encryption {
+            Some(encryption) if data_to_store.len() >= encryption.format().prefix_len() => {
+                Some(data_to_store[..encryption.format().prefix_len()].to_vec())
+            }
+            _ => None,
         };
 
         let rel_path = storage_path(file_id);
@@ -149,6 +165,9 @@ impl ReleaseStorage for ReleaseStorageImpl {
             data.len() as i64,
             ContentType::from_extension(&ext),
         );
+        if db_file.content_type.is_audio() {
+            db_file = db_file.with_content_sha256(sha256_hex(data));
+        }
 
         let nonce = self.store_bytes(&db_file.id, data, on_progress).await?;
         db_file.encryption_nonce = nonce;
@@ -1,19 +1,37 @@
 //! Transfer service — moves releases between storage modes
 //!
 //! Orchestrates reading files from the source location, writing them to the
-//! destination, updating DB records, and queuing old files for deferred cleanup.
+//! destination, updating DB records, and removing the originals.
+//!
+//! Files go one at a time: each is read from where the release keeps it,
+//! re-encrypted for the destination, written, and read back to verify it
+//! before the next one starts. The release only switches over in the DB once
+//! every file is verified, and the originals are removed after that (local
+//! ones through the deferred cleanup). Until then the release keeps playing
+//! from its old location.
+//!
+//! A running transfer is recorded in the local `storage_transfers` table, so
+//! one interrupted by a quit or an error is picked up again with
+//! `pending_transfers`. Files whose destination copy already verifies are
+//! not written again.
 
-use crate::db::DbFile;
+use crate::cloud_storage::CloudStorage;
+use crate::db::{DbFile, DbRelease};
 use crate::encryption::EncryptionService;
-use crate::library::SharedLibraryManager;
+use crate::file_service::decrypt_if_needed;
+use crate::library::{LibraryManager, SharedLibraryManager};
 use crate::library_dir::LibraryDir;
-use crate::storage::ReleaseStorageImpl;
+use crate::storage::storage_path;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 use super::cleanup::PendingDeletion;
 
+type TransferError = Box<dyn std::error::Error + Send + Sync>;
+
 /// Progress updates emitted during a transfer
 #[derive(Debug, Clone)]
 pub enum TransferProgress {
@@ -32,23 +50,57 @@ pub enum TransferProgress {
     },
     /// Transfer completed
     Complete { release_id: String },
+    /// Transfer cancelled, the release is where it was
+    Cancelled { release_id: String },
     /// Transfer failed
     Failed { release_id: String, error: String },
 }
 
 /// Where to transfer a release
+#[derive(Debug, Clone, PartialEq)]
 pub enum TransferTarget {
     /// Move files into managed local storage
     ManagedLocal,
+    /// Move files into the cloud home (removes them from managed local storage)
+    Cloud,
     /// Eject to a user-chosen local folder (removes from managed storage)
     Eject(PathBuf),
 }
 
+impl TransferTarget {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TransferTarget::ManagedLocal => "local",
+            TransferTarget::Cloud => "cloud",
+            TransferTarget::Eject(_) => "eject",
+        }
+    }
+
+    fn from_db(target: &str, eject_path: Option<String>) -> Option<Self> {
+        match target {
+            "local" => Some(TransferTarget::ManagedLocal),
+            "cloud" => Some(TransferTarget::Cloud),
+            "eject" => eject_path.map(|path| TransferTarget::Eject(PathBuf::from(path))),
+            _ => None,
+        }
+    }
+}
+
+/// Where a file is read from or written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Location {
+    Local,
+    Cloud,
+    Unmanaged,
+}
+
 /// Transfer service that moves releases between storage modes
 pub struct TransferService {
     library_manager: SharedLibraryManager,
     encryption_service: Option<EncryptionService>,
     library_dir: LibraryDir,
+    cloud_storage: Option<Arc<dyn CloudStorage>>,
+    cancel: CancellationToken,
 }
 
 impl TransferService {
@@ -61,9 +113,23 @@ impl TransferService {
             library_manager,
             encryption_service,
             library_dir,
+            cloud_storage: None,
+            cancel: CancellationToken::new(),
         }
     }
 
+    /// Use the library's cloud home, for moving releases into or out of it
+    pub fn with_cloud_storage(mut self, cloud_storage: Arc<dyn CloudStorage>) -> Self {
+        self.cloud_storage = Some(cloud_storage);
+        self
+    }
+
+    /// Stop the transfer before its next file. Files already written to the
+    /// destination are removed and the release stays where it was.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
     /// Transfer a release to a new storage target.
     ///
     /// Returns a receiver for progress updates.
@@ -76,17 +142,19 @@ impl TransferService {
         let library_manager = self.library_manager.clone();
         let encryption_service = self.encryption_service.clone();
         let library_dir = self.library_dir.clone();
+        let cloud_storage = self.cloud_storage.clone();
+        let cancel = self.cancel.clone();
 
         tokio::spawn(async move {
-            let result = do_transfer(
-                &release_id,
-                target,
-                &library_manager,
-                encryption_service.as_ref(),
-                &library_dir,
-                &tx,
-            )
-            .await;
+            let transfer = Transfer {
+                release_id: &release_id,
+                target: &target,
+                library_manager: library_manager.get(),
+                encryption_service: encryption_service.as_ref(),
+                cloud_storage: cloud_storage.as_deref(),
+                library_dir: &library_dir,
+            };
+            let result = transfer.run(&cancel, &tx).await;
 
             if let Err(e) = result {
                 error!("Transfer failed for release {}: {}", release_id, e);
@@ -101,189 +169,425 @@ impl TransferService {
     }
 }
 
-async fn do_transfer(
-    release_id: &str,
-    target: TransferTarget,
-    library_manager: &SharedLibraryManager,
-    encryption_service: Option<&EncryptionService>,
-    library_dir: &LibraryDir,
-    tx: &mpsc::UnboundedSender<TransferProgress>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let mgr = library_manager.get();
-
-    let release = mgr
-        .database()
-        .get_release_by_id(release_id)
-        .await?
-        .ok_or("Release not found")?;
-
-    let old_files = mgr.get_files_for_release(release_id).await?;
-
-    if old_files.is_empty() {
-        return Err("Release has no files".into());
+/// Transfers that were started and haven't finished, to run again
+pub async fn pending_transfers(
+    library_manager: &LibraryManager,
+) -> Result<Vec<(String, TransferTarget)>, sqlx::Error> {
+    let db = library_manager.database();
+    let mut pending = Vec::new();
+    for (release_id, target, eject_path) in db.get_storage_transfers().await? {
+        match TransferTarget::from_db(&target, eject_path) {
+            Some(target) => pending.push((release_id, target)),
+            None => {
+                warn!("Dropping unknown transfer of release {}", release_id);
+
+                db.finish_storage_transfer(&release_id).await?;
+            }
+        }
     }
+    Ok(pending)
+}
 
-    let _ = tx.send(TransferProgress::Started {
-        release_id: release_id.to_string(),
-        total_files: old_files.len(),
-    });
+/// One release on its way to `target`
+struct Transfer<'a> {
+    release_id: &'a str,
+    target: &'a TransferTarget,
+    library_manager: &'a LibraryManager,
+    encryption_service: Option<&'a EncryptionService>,
+    cloud_storage: Option<&'a dyn CloudStorage>,
+    library_dir: &'a LibraryDir,
+}
 
-    info!(
-        "Starting transfer for release {} ({} files)",
-        release_id,
-        old_files.len()
-    );
+impl Transfer<'_> {
+    async fn run(
+        &self,
+        cancel: &CancellationToken,
+        tx: &mpsc::UnboundedSender<TransferProgress>,
+    ) -> Result<(), TransferError> {
+        let db = self.library_manager.database();
+        let release_id = self.release_id;
+
+        let Some(release) = db.get_release_by_id(release_id).await? else {
+            db.finish_storage_transfer(release_id).await?;
+            return Err("Release not found".into());
+        };
+        let files = db.get_files_for_release(release_id).await?;
+        if files.is_empty() {
+            return Err("Release has no files".into());
+        }
+
+        let already_there = match self.target {
+            TransferTarget::ManagedLocal => release.managed_locally,
+            TransferTarget::Cloud => release.managed_in_cloud,
+            TransferTarget::Eject(_) => false,
+        };
+        if *self.target == TransferTarget::Cloud {
+            if self.cloud_storage.is_none() {
+                return Err("No cloud home is configured".into());
+            }
+            if self.encryption_service.is_none() {
+                return Err(
+                    "Files in the cloud home are encrypted, and this library has no encryption key"
+                        .into(),
+                );
+            }
+        }
+        let source = self.source(&release)?;
 
-    // Read all files from source, decrypting if needed
-    let source_encrypted = release.managed_locally && encryption_service.is_some();
+        let eject_path = match self.target {
+            TransferTarget::Eject(dir) => {
+                Some(dir.to_str().ok_or("Cannot convert target dir to string")?)
+            }
+            _ => None,
+        };
+        db.begin_storage_transfer(release_id, self.target.as_str(), eject_path)
+            .await?;
 
-    let mut file_data: Vec<(String, Vec<u8>)> = Vec::with_capacity(old_files.len());
-    for (i, file) in old_files.iter().enumerate() {
-        let _ = tx.send(TransferProgress::FileProgress {
+        let _ = tx.send(TransferProgress::Started {
             release_id: release_id.to_string(),
-            file_index: i,
-            total_files: old_files.len(),
-            filename: file.original_filename.clone(),
-            percent: 0,
+            total_files: files.len(),
         });
 
-        let raw_data = read_file_data(file, &release, library_dir).await?;
+        info!(
+            "Starting transfer for release {} ({} files)",
+            release_id,
+            files.len()
+        );
+
+        if !already_there {
+            // Container prefix of each copy written, None for plaintext
+            let mut nonces: Vec<Option<Vec<u8>>> = Vec::with_capacity(files.len());
+            match self.target {
+                TransferTarget::ManagedLocal => {
+                    // Copies from an earlier move out may still be queued for deletion
+                    let paths: Vec<String> = files
+                        .iter()
+                        .map(|f| f.local_storage_path(self.library_dir).display().to_string())
+                        .collect();
+                    super::cleanup::withdraw_pending_deletions(self.library_dir.as_ref(), &paths)
+                        .await?;
+                }
+                TransferTarget::Eject(dir) => tokio::fs::create_dir_all(dir).await?,
+                TransferTarget::Cloud => {}
+            }
+            for (i, file) in files.iter().enumerate() {
+                if cancel.is_cancelled() {
+                    return self.cancelled(&files, tx).await;
+                }
+                let progress = |percent| {
+                    let _ = tx.send(TransferProgress::FileProgress {
+                        release_id: release_id.to_string(),
+                        file_index: i,
+                        total_files: files.len(),
+                        filename: file.original_filename.clone(),
+                        percent,
+                    });
+                };
+                progress(0);
+                nonces.push(self.copy_file(&release, file, source, &progress).await?);
+                progress(100);
+            }
+            // A cancel during the last file still leaves the release alone
+            if cancel.is_cancelled() {
+                return self.cancelled(&files, tx).await;
+            }
 
-        let data = if source_encrypted {
-            let enc =
-                encryption_service.ok_or("Encryption service required for encrypted source")?;
-            let release_enc = enc.derive_release_encryption(release_id);
-            release_enc.decrypt(&raw_data)?
-        } else {
-            raw_data
-        };
+            // Every file is in place, so the release can switch over
+            for (file, nonce) in files.iter().zip(&nonces) {
+                match nonce {
+                    Some(nonce) => db.update_file_encryption_nonce(&file.id, nonce).await?,
+                    None if file.encryption_nonce.is_some() => {
+                        db.clear_file_encryption_nonce(&file.id).await?
+                    }
+                    None => {}
+                }
+            }
+        }
+        match self.target {
+            TransferTarget::ManagedLocal => db.set_release_storage(release_id, true, false).await?,
+            TransferTarget::Cloud => db.set_release_storage(release_id, false, true).await?,
+            TransferTarget::Eject(_) => {
+                db.set_release_unmanaged(release_id, eject_path.unwrap_or_default())
+                    .await?
+            }
+        }
 
-        file_data.push((file.original_filename.clone(), data));
+        self.remove_originals(&release, &files).await;
+        db.finish_storage_transfer(release_id).await?;
 
-        let _ = tx.send(TransferProgress::FileProgress {
+        info!("Transfer complete for release {}", release_id);
+
+        let _ = tx.send(TransferProgress::Complete {
             release_id: release_id.to_string(),
-            file_index: i,
-            total_files: old_files.len(),
-            filename: file.original_filename.clone(),
-            percent: 50,
         });
-    }
 
-    // Queue old managed-local files for deferred deletion
-    if release.managed_locally {
-        let pending: Vec<PendingDeletion> = old_files
-            .iter()
-            .map(|f| PendingDeletion::Local {
-                path: f.local_storage_path(library_dir).display().to_string(),
-            })
-            .collect();
+        Ok(())
+    }
 
-        if !pending.is_empty() {
-            if let Err(e) =
-                super::cleanup::append_pending_deletions(library_dir.as_ref(), &pending).await
-            {
-                warn!("Failed to queue deferred deletions: {}", e);
+    /// Where to read the files from: any copy that isn't at the destination
+    fn source(&self, release: &DbRelease) -> Result<Location, TransferError> {
+        if release.managed_locally && *self.target != TransferTarget::ManagedLocal {
+            Ok(Location::Local)
+        } else if release.managed_in_cloud && *self.target != TransferTarget::Cloud {
+            if self.cloud_storage.is_none() {
+                return Err("The release is in the cloud home, which isn't configured".into());
             }
+            Ok(Location::Cloud)
+        } else if release.unmanaged_path.is_some() {
+            Ok(Location::Unmanaged)
+        } else if release.managed_locally || release.managed_in_cloud {
+            // Only at the destination already, nothing to copy
+            Ok(Location::Local)
+        } else {
+            Err(format!("Release {} has no readable location", release.id).into())
         }
     }
 
-    // Write files to destination
-    match &target {
-        TransferTarget::ManagedLocal => {
-            let database = std::sync::Arc::new(mgr.database().clone());
-            let release_enc = encryption_service.map(|e| e.derive_release_encryption(release_id));
-            let storage = ReleaseStorageImpl::new_local(library_dir.clone(), release_enc, database);
-
-            for (i, file) in old_files.iter().enumerate() {
-                let data = &file_data[i].1;
-                let total_files = old_files.len();
-                let tx_clone = tx.clone();
-                let rid = release_id.to_string();
-                let fname = file.original_filename.clone();
-
-                let nonce = storage
-                    .store_bytes(
-                        &file.id,
-                        data,
-                        Box::new(move |bytes_written, total_bytes| {
-                            let percent = if total_bytes > 0 {
-                                ((bytes_written as f64 / total_bytes as f64) * 50.0 + 50.0) as u8
-                            } else {
-                                100
-                            };
-                            let _ = tx_clone.send(TransferProgress::FileProgress {
-                                release_id: rid.clone(),
-                                file_index: i,
-                                total_files,
-                                filename: fname.clone(),
-                                percent,
-                            });
-                        }),
-                    )
-                    .await?;
+    /// Encryption of the destination copies, None for plaintext
+    fn destination_encryption(&self) -> Option<EncryptionService> {
+        match self.target {
+            TransferTarget::ManagedLocal | TransferTarget::Cloud => self
+                .encryption_service
+                .map(|e| e.derive_release_encryption(self.release_id)),
+            TransferTarget::Eject(_) => None,
+        }
+    }
 
-                // Update encryption nonce if newly encrypted
-                if let Some(nonce) = nonce {
-                    mgr.database()
-                        .update_file_encryption_nonce(&file.id, &nonce)
-                        .await?;
+    /// Copy one file to the destination and verify it. Returns the container
+    /// prefix of the copy when it's encrypted.
+    async fn copy_file(
+        &self,
+        release: &DbRelease,
+        file: &DbFile,
+        source: Location,
+        progress: &impl Fn(u8),
+    ) -> Result<Option<Vec<u8>>, TransferError> {
+        let raw = self.read(release, file, source).await?;
+        let data = match source {
+            Location::Unmanaged => raw,
+            _ => decrypt_if_needed(file, self.release_id, self.encryption_service, raw).await?,
+        };
+        progress(33);
+
+        let encryption = self.destination_encryption();
+        // An earlier, interrupted run may have copied it already
+        let mut existing = None;
+        if let Some(copy) = self.read_destination(file).await {
+            if decode(encryption.as_ref(), &copy).await?.as_deref() == Some(data.as_slice()) {
+                existing = Some(copy);
+            }
+        }
+        let stored = match existing {
+            Some(existing) => existing,
+            None => {
+                let stored = self.encode(file, encryption.as_ref(), &data).await?;
+                self.write_destination(file, &stored).await?;
+                progress(66);
+
+                let written = self.read_destination(file).await.ok_or_else(|| {
+                    format!("{} is missing after writing it", file.original_filename)
+                })?;
+                if decode(encryption.as_ref(), &written).await?.as_deref() != Some(data.as_slice())
+                {
+                    return Err(format!(
+                        "{} didn't verify after writing it",
+                        file.original_filename
+                    )
+                    .into());
                 }
+                stored
             }
+        };
+        if *self.target == TransferTarget::Cloud {
+            let _ = tokio::fs::remove_file(self.staged_path(file)).await;
+        }
 
-            mgr.database()
-                .set_release_managed_locally(release_id, true)
-                .await?;
+        Ok(encryption.map(|encryption| {
+            stored[..encryption.format().prefix_len().min(stored.len())].to_vec()
+        }))
+    }
+
+    /// The bytes to store for `data`. Encrypted copies bound for the cloud
+    /// home are staged on disk, so a transfer resumed after a restart
+    /// re-sends the same ciphertext and its upload can pick up where it was.
+    async fn encode(
+        &self,
+        file: &DbFile,
+        encryption: Option<&EncryptionService>,
+        data: &[u8],
+    ) -> Result<Vec<u8>, TransferError> {
+        let Some(encryption) = encryption else {
+            return Ok(data.to_vec());
+        };
+        if *self.target != TransferTarget::Cloud {
+            return Ok(encryption.encrypt(data));
         }
-        TransferTarget::Eject(target_dir) => {
-            tokio::fs::create_dir_all(target_dir).await?;
-
-            for (i, file) in old_files.iter().enumerate() {
-                let data = &file_data[i].1;
-                let dest_path = target_dir.join(&file.original_filename);
-                tokio::fs::write(&dest_path, data).await?;
-
-                let _ = tx.send(TransferProgress::FileProgress {
-                    release_id: release_id.to_string(),
-                    file_index: i,
-                    total_files: file_data.len(),
-                    filename: file.original_filename.clone(),
-                    percent: 100,
-                });
+
+        let staged_path = self.staged_path(file);
+        if let Ok(staged) = tokio::fs::read(&staged_path).await {
+            if decode(Some(encryption), &staged).await?.as_deref() == Some(data) {
+                return Ok(staged);
+            }
+        }
+        let stored = encryption.encrypt(data);
+        if let Some(parent) = staged_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&staged_path, &stored).await?;
+        Ok(stored)
+    }
+
+    fn staged_path(&self, file: &DbFile) -> PathBuf {
+        self.library_dir.uploads_dir().join(&file.id)
+    }
+
+    async fn read(
+        &self,
+        release: &DbRelease,
+        file: &DbFile,
+        location: Location,
+    ) -> Result<Vec<u8>, TransferError> {
+        match location {
+            Location::Local => {
+                Ok(tokio::fs::read(file.local_storage_path(self.library_dir)).await?)
+            }
+            Location::Cloud => {
+                let storage = self.cloud_storage.ok_or("No cloud home is configured")?;
+                Ok(storage.download(&storage_path(&file.id)).await?)
+            }
+            Location::Unmanaged => {
+                let dir = release
+                    .unmanaged_path
+                    .as_deref()
+                    .ok_or_else(|| format!("File {} has no readable location", file.id))?;
+                Ok(tokio::fs::read(Path::new(dir).join(&file.original_filename)).await?)
+            }
+        }
+    }
+
+    /// The destination copy of a file, if there is one
+    async fn read_destination(&self, file: &DbFile) -> Option<Vec<u8>> {
+        match self.target {
+            TransferTarget::ManagedLocal => {
+                tokio::fs::read(file.local_storage_path(self.library_dir))
+                    .await
+                    .ok()
             }
+            TransferTarget::Cloud => self
+                .cloud_storage?
+                .download(&storage_path(&file.id))
+                .await
+                .ok(),
+            TransferTarget::Eject(dir) => tokio::fs::read(dir.join(&file.original_filename))
+                .await
+                .ok(),
+        }
+    }
 
-            let unmanaged_path = target_dir
-                .to_str()
-                .ok_or("Cannot convert target dir to string")?;
-            mgr.database()
-                .set_release_unmanaged(release_id, unmanaged_path)
-                .await?;
+    async fn write_destination(&self, file: &DbFile, stored: &[u8]) -> Result<(), TransferError> {
+        match self.target {
+            TransferTarget::ManagedLocal => {
+                // Written aside and renamed over, so a quit midway never
+                // leaves half a file where a resumed run would look
+                let path = file.local_storage_path(self.library_dir);
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                let partial = path.with_file_name(format!("{}.transferring", file.id));
+                tokio::fs::write(&partial, stored).await?;
+                tokio::fs::rename(&partial, &path).await?;
+            }
+            TransferTarget::Cloud => {
+                let storage = self.cloud_storage.ok_or("No cloud home is configured")?;
+                storage.upload(&storage_path(&file.id), stored).await?;
+            }
+            TransferTarget::Eject(dir) => {
+                tokio::fs::write(dir.join(&file.original_filename), stored).await?;
+            }
         }
+        Ok(())
     }
 
-    info!("Transfer complete for release {}", release_id);
+    /// Drop the copies a cancelled transfer made, and forget the transfer
+    async fn cancelled(
+        &self,
+        files: &[DbFile],
+        tx: &mpsc::UnboundedSender<TransferProgress>,
+    ) -> Result<(), TransferError> {
+        for file in files {
+            match self.target {
+                TransferTarget::ManagedLocal => {
+                    let _ = tokio::fs::remove_file(file.local_storage_path(self.library_dir)).await;
+                }
+                TransferTarget::Cloud => {
+                    if let Some(storage) = self.cloud_storage {
+                        let _ = storage.delete(&storage_path(&file.id)).await;
+                    }
+                    let _ = tokio::fs::remove_file(self.staged_path(file)).await;
+                }
+                TransferTarget::Eject(dir) => {
+                    let _ = tokio::fs::remove_file(dir.join(&file.original_filename)).await;
+                }
+            }
+        }
+        self.library_manager
+            .database()
+            .finish_storage_transfer(self.release_id)
+            .await?;
 
-    let _ = tx.send(TransferProgress::Complete {
-        release_id: release_id.to_string(),
-    });
+        info!("Transfer cancelled for release {}", self.release_id);
 
-    Ok(())
-}
+        let _ = tx.send(TransferProgress::Cancelled {
+            release_id: self.release_id.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Remove the copies the release no longer uses. Unmanaged folders are
+    /// the user's and are never touched.
+    async fn remove_originals(&self, release: &DbRelease, files: &[DbFile]) {
+        // Queue old managed-local files for deferred deletion
+        if release.managed_locally && *self.target != TransferTarget::ManagedLocal {
+            let pending: Vec<PendingDeletion> = files
+                .iter()
+                .map(|f| PendingDeletion::Local {
+                    path: f.local_storage_path(self.library_dir).display().to_string(),
+                })
+                .collect();
 
-/// Read file data from its source location based on release storage flags
-async fn read_file_data(
-    file: &DbFile,
-    release: &crate::db::DbRelease,
-    library_dir: &LibraryDir,
-) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
-    if release.managed_locally {
-        // Read from managed local storage path
-        let path = file.local_storage_path(library_dir);
-        Ok(tokio::fs::read(&path).await?)
-    } else if let Some(ref unmanaged_path) = release.unmanaged_path {
-        // Read from unmanaged location
-        let path = Path::new(unmanaged_path).join(&file.original_filename);
-        Ok(tokio::fs::read(&path).await?)
-    } else {
-        Err(format!("File {} has no readable location", file.id).into())
+            if let Err(e) =
+                super::cleanup::append_pending_deletions(self.library_dir.as_ref(), &pending).await
+            {
+                warn!("Failed to queue deferred deletions: {}", e);
+            }
+        }
+
+        if release.managed_in_cloud && *self.target != TransferTarget::Cloud {
+            let Some(storage) = self.cloud_storage else {
+                warn!(
+                    "Leaving the files of release {} in the cloud home, which isn't configured",
+                    release.id
+                );
+
+                return;
+            };
+            for file in files {
+                if let Err(e) = storage.delete(&storage_path(&file.id)).await {
+                    warn!("Failed to delete {} from the cloud home: {}", file.id, e);
+                }
+            }
+        }
     }
 }
+
+/// The plaintext of a stored copy, or None if it doesn't decrypt
+async fn decode(
+    encryption: Option<&EncryptionService>,
+    stored: &[u8],
+) -> Result<Option<Vec<u8>>, TransferError> {
+    let Some(encryption) = encryption.cloned() else {
+        return Ok(Some(stored.to_vec()));
+    };
+    let stored = stored.to_vec();
+    Ok(tokio::task::spawn_blocking(move || encryption.decrypt(&stored).ok()).await?)
+}
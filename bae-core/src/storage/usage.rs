@@ -0,0 +1,167 @@
+//! Cloud home usage
+//!
+//! Metered providers like S3 bill for what's kept, what's transferred and how
+//! many requests are made. `MeteredCloudStorage` counts the release file
+//! traffic that goes through it per calendar month, in the local
+//! `cloud_usage` table. With the size of the releases kept in each place and a
+//! price table from the config, that gives a rough monthly cost. Sync
+//! changesets and images go to the cloud home directly and aren't counted.
+
+use crate::cloud_storage::{CloudStorage, CloudStorageError};
+use crate::config::CloudPrices;
+use crate::db::{Database, DbCloudUsage};
+use crate::library::{LibraryError, LibraryManager};
+use std::sync::Arc;
+use tracing::warn;
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Cloud storage that records its traffic in the library database
+pub struct MeteredCloudStorage {
+    inner: Arc<dyn CloudStorage>,
+    database: Database,
+}
+
+impl MeteredCloudStorage {
+    pub fn new(inner: Arc<dyn CloudStorage>, database: Database) -> Self {
+        Self { inner, database }
+    }
+
+    /// Count one request. Failed requests are billed too, but move no bytes.
+    async fn record(&self, bytes_uploaded: usize, bytes_downloaded: usize) {
+        if let Err(e) = self
+            .database
+            .record_cloud_usage(
+                &current_month(),
+                bytes_uploaded as i64,
+                bytes_downloaded as i64,
+                1,
+            )
+            .await
+        {
+            warn!("Failed to record cloud usage: {}", e);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CloudStorage for MeteredCloudStorage {
+    async fn upload(&self, key: &str, data: &[u8]) -> Result<String, CloudStorageError> {
+        let result = self.inner.upload(key, data).await;
+        let sent = if result.is_ok() { data.len() } else { 0 };
+        self.record(sent, 0).await;
+        result
+    }
+
+    async fn download(&self, storage_location: &str) -> Result<Vec<u8>, CloudStorageError> {
+        let result = self.inner.download(storage_location).await;
+        self.record(0, result.as_ref().map_or(0, Vec::len)).await;
+        result
+    }
+
+    async fn download_range(
+        &self,
+        storage_location: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u8>, CloudStorageError> {
+        let result = self
+            .inner
+            .download_range(storage_location, start, end)
+            .await;
+        self.record(0, result.as_ref().map_or(0, Vec::len)).await;
+        result
+    }
+
+    async fn delete(&self, storage_location: &str) -> Result<(), CloudStorageError> {
+        let result = self.inner.delete(storage_location).await;
+        self.record(0, 0).await;
+        result
+    }
+
+    async fn prepare_read(&self, storage_location: &str) -> Result<(), CloudStorageError> {
+        let result = self.inner.prepare_read(storage_location).await;
+        self.record(0, 0).await;
+        result
+    }
+}
+
+/// What's kept where, and this month's traffic to the cloud home
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageUsage {
+    pub local_bytes: u64,
+    pub cloud_bytes: u64,
+    pub this_month: DbCloudUsage,
+    /// None without a price table
+    pub estimated_monthly_cost: Option<f64>,
+}
+
+/// Collect the storage usage, estimating the cost when `prices` is set
+pub async fn storage_usage(
+    library_manager: &LibraryManager,
+    prices: Option<&CloudPrices>,
+) -> Result<StorageUsage, LibraryError> {
+    let (local_bytes, cloud_bytes) = library_manager.get_stored_bytes().await?;
+    let this_month = library_manager.get_cloud_usage(&current_month()).await?;
+    let estimated_monthly_cost =
+        prices.map(|prices| estimate_monthly_cost(prices, cloud_bytes, &this_month));
+    Ok(StorageUsage {
+        local_bytes,
+        cloud_bytes,
+        this_month,
+        estimated_monthly_cost,
+    })
+}
+
+/// The month's bill so far: a full month of keeping `stored_bytes`, plus the
+/// traffic in `usage`
+pub fn estimate_monthly_cost(prices: &CloudPrices, stored_bytes: u64, usage: &DbCloudUsage) -> f64 {
+    prices.storage_per_gb_month * stored_bytes as f64 / BYTES_PER_GB
+        + prices.upload_per_gb * usage.bytes_uploaded as f64 / BYTES_PER_GB
+        + prices.download_per_gb * usage.bytes_downloaded as f64 / BYTES_PER_GB
+        + prices.per_1000_requests * usage.requests as f64 / 1000.0
+}
+
+fn current_month() -> String {
+    chrono::Utc::now().format("%Y-%m").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockCloudStorage;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn traffic_is_counted_and_priced() {
+        let temp_dir = TempDir::new().unwrap();
+        let database = Database::new(temp_dir.path().join("test.db").to_str().unwrap())
+            .await
+            .unwrap();
+        let manager = LibraryManager::new(database.clone(), None);
+        let storage = MeteredCloudStorage::new(Arc::new(MockCloudStorage::new()), database);
+
+        let location = storage.upload("Low Light", &[0; 1000]).await.unwrap();
+        storage.download_range(&location, 0, 100).await.unwrap();
+        assert!(storage.download("missing").await.is_err());
+
+        let prices = CloudPrices {
+            storage_per_gb_month: 0.02,
+            download_per_gb: 0.09,
+            upload_per_gb: 0.0,
+            per_1000_requests: 5.0,
+        };
+        let usage = storage_usage(&manager, Some(&prices)).await.unwrap();
+        assert_eq!(usage.this_month.bytes_uploaded, 1000);
+        assert_eq!(usage.this_month.bytes_downloaded, 100);
+        assert_eq!(usage.this_month.requests, 3);
+        assert_eq!(usage.cloud_bytes, 0);
+        let cost = usage.estimated_monthly_cost.unwrap();
+        assert!((cost - (0.015 + 0.09 * 100.0 / BYTES_PER_GB)).abs() < 1e-9);
+
+        // A month of keeping 50 GB
+        let stored =
+            estimate_monthly_cost(&prices, 50 * 1024 * 1024 * 1024, &DbCloudUsage::default());
+        assert!((stored - 1.0).abs() < 1e-9);
+    }
+}
@@ -1,19 +1,37 @@
+use crate::admin_routes::{create_admin_router, SyncControl};
+use crate::cache::{transcode_cache_key, CacheManager};
+use crate::cover_thumbnails::{thumbnail_size, CoverThumbnails};
+use crate::db::{DbAppPassword, SearchPage};
+use crate::hls::{HlsCache, HlsTrack};
+use crate::jukebox::{Jukebox, JukeboxAction, JukeboxStatus};
 use crate::library::LibraryError;
-use crate::library::SharedLibraryManager;
+use crate::library::{LibraryEvent, SharedLibraryManager};
 use crate::library_dir::LibraryDir;
+use crate::playback::PlaybackHandle;
+use crate::server_auth::{constant_time_eq, create_auth_router, ApiAuthError, ServerAuth};
+use crate::server_health::create_health_router;
+use crate::server_metrics::server_metrics;
 use axum::{
     body::Body,
-    extract::{Query, Request, State},
-    http::StatusCode,
+    extract::{ConnectInfo, MatchedPath, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
     middleware::{self, Next},
-    response::{IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::get,
     Json, Router,
 };
+use futures::{Stream, StreamExt};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 use tokio_util::io::ReaderStream;
 use tower_http::cors::CorsLayer;
 use tracing::{debug, error, info, warn};
@@ -24,7 +42,14 @@ pub struct SubsonicState {
     pub encryption_service: Option<crate::encryption::EncryptionService>,
     pub library_dir: LibraryDir,
     pub key_service: crate::keys::KeyService,
+    /// Credentials at startup; `ServerAuth` holds the reloaded ones
     pub auth: SubsonicAuth,
+    /// Remote control of local playback; `None` where nothing plays audio
+    pub jukebox: Option<Arc<Jukebox>>,
+    /// Tracks encoded for HLS, in memory and on servers on disk
+    pub hls: Arc<HlsCache>,
+    /// Scaled-down covers; `None` serves every cover full size
+    pub thumbnails: Option<CoverThumbnails>,
 }
 
 /// Subsonic authentication configuration
@@ -50,6 +75,9 @@ pub struct SubsonicQuery {
     /// Salt for token-based auth
     #[serde(default)]
     pub s: Option<String>,
+    /// OpenSubsonic API key, sent instead of a username and password
+    #[serde(default, rename = "apiKey")]
+    pub api_key: Option<String>,
 }
 /// Standard Subsonic API response envelope
 #[derive(Debug, Serialize)]
@@ -100,6 +128,9 @@ pub struct Album {
     pub genre: Option<String>,
     #[serde(rename = "coverArt")]
     pub cover_art: Option<String>,
+    /// Blurhash of the cover art, for painting a placeholder before it loads
+    #[serde(rename = "coverBlurhash", skip_serializing_if = "Option::is_none")]
+    pub cover_blurhash: Option<String>,
 }
 /// Song/track info for browsing
 #[derive(Debug, Serialize)]
@@ -150,36 +181,172 @@ pub struct AlbumListResponse {
 pub struct AlbumList {
     pub album: Vec<Album>,
 }
+/// Genres response
+#[derive(Debug, Serialize)]
+pub struct GenresResponse {
+    pub genres: GenreList,
+}
+#[derive(Debug, Serialize)]
+pub struct GenreList {
+    pub genre: Vec<Genre>,
+}
+#[derive(Debug, Serialize)]
+pub struct Genre {
+    pub value: String,
+    #[serde(rename = "albumCount")]
+    pub album_count: u32,
+}
+/// Playlist info for browsing
+#[derive(Debug, Serialize)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "songCount")]
+    pub song_count: u32,
+    pub duration: u32,
+    pub created: String,
+    pub changed: String,
+}
+/// Playlists response
+#[derive(Debug, Serialize)]
+pub struct PlaylistsResponse {
+    pub playlists: PlaylistList,
+}
+#[derive(Debug, Serialize)]
+pub struct PlaylistList {
+    pub playlist: Vec<Playlist>,
+}
+/// Search response
+#[derive(Debug, Serialize)]
+pub struct SearchResult3Response {
+    #[serde(rename = "searchResult3")]
+    pub search_result3: SearchResult3,
+}
+#[derive(Debug, Serialize)]
+pub struct SearchResult3 {
+    pub artist: Vec<Artist>,
+    pub album: Vec<Album>,
+    pub song: Vec<Song>,
+}
+/// Lyrics response. `value` is empty when the song has no lyrics.
+#[derive(Debug, Serialize)]
+pub struct LyricsResponse {
+    pub lyrics: SongLyrics,
+}
+#[derive(Debug, Serialize)]
+pub struct SongLyrics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub value: String,
+}
+/// Server-wide settings for `create_router`, beyond the library it serves.
+pub struct RouterSettings {
+    /// Sign-in state and credentials, which the server replaces on reload.
+    pub auth: Arc<ServerAuth>,
+    /// Lets Subsonic clients drive this player as a jukebox.
+    pub playback: Option<PlaybackHandle>,
+    /// Lets the admin API report and trigger sync.
+    pub sync: Option<SyncControl>,
+    /// Lets the admin API ask the server to reload its settings.
+    pub reload: Option<mpsc::Sender<()>>,
+    /// Set in the server's maintenance mode, which pauses sync and cloud writes.
+    pub maintenance: Arc<AtomicBool>,
+}
+
 /// Create the Subsonic API router
 pub fn create_router(
     library_manager: SharedLibraryManager,
     encryption_service: Option<crate::encryption::EncryptionService>,
     library_dir: LibraryDir,
     key_service: crate::keys::KeyService,
-    auth: SubsonicAuth,
+    settings: RouterSettings,
+    transcodes: Option<CacheManager>,
+    thumbnails: Option<CoverThumbnails>,
 ) -> Router {
+    let server_auth = settings.auth;
     let state = SubsonicState {
         library_manager,
         encryption_service,
         library_dir,
         key_service,
-        auth: auth.clone(),
+        auth: server_auth.subsonic_auth(),
+        jukebox: settings.playback.map(Jukebox::new),
+        hls: Arc::new(transcodes.map(HlsCache::with_disk).unwrap_or_default()),
+        thumbnails,
     };
-    let auth = Arc::new(auth);
+    let middleware_auth = server_auth.clone();
+    let middleware_library = state.library_manager.clone();
+    let health_router = create_health_router(
+        state.library_manager.clone(),
+        settings.sync.as_ref().map(|sync| sync.bucket.clone()),
+        settings.maintenance.clone(),
+    );
+    let admin_router = create_admin_router(
+        server_auth.clone(),
+        state.library_manager.clone(),
+        settings.sync,
+        settings.maintenance,
+        settings.reload,
+    );
     Router::new()
         .route("/rest/ping", get(ping))
         .route("/rest/getLicense", get(get_license))
+        .route(
+            "/rest/getOpenSubsonicExtensions",
+            get(get_open_subsonic_extensions),
+        )
         .route("/rest/getArtists", get(get_artists))
         .route("/rest/getAlbumList", get(get_album_list))
         .route("/rest/getAlbum", get(get_album))
+        .route("/rest/getGenres", get(get_genres))
         .route("/rest/getCoverArt", get(get_cover_art))
         .route("/rest/stream", get(stream_song))
+        .route("/rest/hls.m3u8", get(get_hls_playlist))
+        .route("/rest/hlsSegment", get(get_hls_segment))
+        .route("/rest/getLyrics", get(get_lyrics))
+        .route("/rest/search3", get(search3))
+        .route("/rest/getPlaylists", get(get_playlists))
+        .route("/rest/getPlaylist", get(get_playlist))
+        .route("/rest/scrobble", get(scrobble))
+        .route("/rest/jukeboxControl", get(jukebox_control))
+        .route("/rest/events", get(library_events))
         .layer(middleware::from_fn(move |req, next| {
-            let auth = auth.clone();
-            auth_middleware(auth, req, next)
+            let auth = middleware_auth.clone();
+            let library_manager = middleware_library.clone();
+            auth_middleware(auth, library_manager, req, next)
         }))
+        .layer(middleware::from_fn(metrics_middleware))
         .layer(CorsLayer::permissive())
         .with_state(state)
+        .merge(create_auth_router(server_auth))
+        .merge(admin_router)
+        .merge(health_router)
+        .route("/metrics", get(get_metrics))
+}
+
+/// Time each API request for `/metrics`, labelled by route so unknown paths
+/// don't each get a series
+async fn metrics_middleware(req: Request, next: Next) -> Response {
+    let endpoint = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", |path| path.as_str())
+        .to_string();
+    let started = std::time::Instant::now();
+    let response = next.run(req).await;
+    server_metrics().record_request(&endpoint, response.status().as_u16(), started.elapsed());
+    response
+}
+
+/// Prometheus scrape endpoint. Unauthenticated, like a health check: it
+/// exposes counts, not library data.
+async fn get_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        server_metrics().render(),
+    )
 }
 /// Compute the MD5 hex digest of a string.
 pub(crate) fn md5_hex(input: &str) -> String {
@@ -218,44 +385,107 @@ pub fn validate_auth(auth: &SubsonicAuth, query: &SubsonicQuery) -> Result<(), &
         None => return Err("Wrong username or password"),
     };
 
-    if username != expected_username {
+    if !constant_time_eq(username.as_bytes(), expected_username.as_bytes()) {
         return Err("Wrong username or password");
     }
 
-    // Token-based auth: client sends t = md5(password + salt), s = salt
+    if password_matches(expected_password, query) {
+        Ok(())
+    } else {
+        Err("Wrong username or password")
+    }
+}
+
+/// Check the query's password or token against `expected_password`.
+///
+/// - Token+salt: `t` = md5(password + salt), `s` = salt
+/// - Password: `p` (plaintext or hex-encoded with "enc:" prefix)
+fn password_matches(expected_password: &str, query: &SubsonicQuery) -> bool {
     if let (Some(token), Some(salt)) = (&query.t, &query.s) {
         let expected_token = md5_hex(&format!("{}{}", expected_password, salt));
-        if token == &expected_token {
-            return Ok(());
-        }
-
-        return Err("Wrong username or password");
+        return constant_time_eq(token.as_bytes(), expected_token.as_bytes());
     }
 
-    // Password-based auth: p = password (optionally hex-encoded with "enc:" prefix)
     if let Some(password) = &query.p {
         let raw_password = if let Some(hex_encoded) = password.strip_prefix("enc:") {
             match hex::decode(hex_encoded) {
                 Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
-                Err(_) => return Err("Wrong username or password"),
+                Err(_) => return false,
             }
         } else {
             password.clone()
         };
+        return constant_time_eq(raw_password.as_bytes(), expected_password.as_bytes());
+    }
 
-        if raw_password == *expected_password {
-            return Ok(());
-        }
+    false
+}
 
-        return Err("Wrong username or password");
+/// Find the app password the query signs in with, if any.
+///
+/// App passwords stand in for the account password, so the username must
+/// still be the server's.
+pub fn match_app_password<'a>(
+    auth: &SubsonicAuth,
+    app_passwords: &'a [DbAppPassword],
+    query: &SubsonicQuery,
+) -> Option<&'a DbAppPassword> {
+    if auth.username.is_none() || query.u != auth.username {
+        return None;
     }
+    app_passwords
+        .iter()
+        .filter(|app_password| !app_password.disabled)
+        .find(|app_password| password_matches(&app_password.password, query))
+}
+
+/// Find the app password the query presents as its API key, if any.
+///
+/// An API key identifies the client on its own, so no username is involved.
+pub fn match_api_key<'a>(
+    app_passwords: &'a [DbAppPassword],
+    query: &SubsonicQuery,
+) -> Option<&'a DbAppPassword> {
+    let api_key = query.api_key.as_deref()?;
+    app_passwords
+        .iter()
+        .filter(|app_password| !app_password.disabled)
+        .find(|app_password| constant_time_eq(app_password.password.as_bytes(), api_key.as_bytes()))
+}
 
-    Err("Wrong username or password")
+/// Generate a new app password: four groups of four lowercase letters and
+/// digits, easy to type into a phone.
+pub fn generate_app_password() -> String {
+    const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+    let mut rng = rand::rng();
+    (0..4)
+        .map(|_| {
+            (0..4)
+                .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())] as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
 }
 
 /// Axum middleware that checks Subsonic authentication on every request.
-async fn auth_middleware(auth: Arc<SubsonicAuth>, req: Request, next: Next) -> Response {
-    if !auth.enabled {
+///
+/// A web UI session cookie or an app password also counts, the latter either
+/// in place of the account password or on its own as an OpenSubsonic API key.
+/// Query credentials go through `ServerAuth::check_query_auth`, which reads
+/// the peer address, so the router must be served with
+/// `into_make_service_with_connect_info`.
+async fn auth_middleware(
+    auth: Arc<ServerAuth>,
+    library_manager: SharedLibraryManager,
+    req: Request,
+    next: Next,
+) -> Response {
+    // Clients ask which extensions are supported before choosing how to sign in
+    if !auth.enabled()
+        || auth.has_session(req.headers())
+        || req.uri().path() == "/rest/getOpenSubsonicExtensions"
+    {
         return next.run(req).await;
     }
 
@@ -264,21 +494,57 @@ async fn auth_middleware(auth: Arc<SubsonicAuth>, req: Request, next: Next) -> R
     let query: SubsonicQuery = match serde_urlencoded::from_str(query_string) {
         Ok(q) => q,
         Err(_) => {
-            return auth_error_response("Missing authentication parameters");
+            return auth_error_response(40, "Missing authentication parameters");
         }
     };
 
-    if let Err(message) = validate_auth(&auth, &query) {
-        return auth_error_response(message);
-    }
+    let database = library_manager.database();
+    let app_passwords = match database.get_app_passwords().await {
+        Ok(app_passwords) => app_passwords,
+        Err(e) => {
+            error!("Failed to load app passwords: {}", e);
+            Vec::new()
+        }
+    };
 
-    next.run(req).await
+    let client = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(peer)| peer.ip());
+    match auth.check_query_auth(client, &query, &app_passwords) {
+        Ok(None) => next.run(req).await,
+        Ok(Some(app_password)) => {
+            // Streaming fires a request per track, so only record the first use
+            // in each interval
+            let now = chrono::Utc::now();
+            let stale = app_password
+                .last_used_at
+                .is_none_or(|at| now - at > chrono::Duration::minutes(1));
+            if stale {
+                if let Err(e) = database.touch_app_password(&app_password.id, now).await {
+                    debug!("Could not record app password use: {}", e);
+                }
+            }
+            next.run(req).await
+        }
+        Err(ApiAuthError::Throttled) => error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            40,
+            "Too many failed attempts, try again in a minute",
+        ),
+        Err(ApiAuthError::Refused { code, message }) => auth_error_response(code, message),
+    }
 }
 
 /// Build a Subsonic error response for authentication failures.
-fn auth_error_response(message: &str) -> Response {
+fn auth_error_response(code: u32, message: &str) -> Response {
+    error_response(StatusCode::UNAUTHORIZED, code, message)
+}
+
+/// Build a Subsonic "failed" response carrying an error code and message.
+pub(crate) fn error_response(status: StatusCode, code: u32, message: &str) -> Response {
     let error = SubsonicError {
-        code: 40,
+        code,
         message: message.to_string(),
     };
     let response = SubsonicResponse {
@@ -288,7 +554,7 @@ fn auth_error_response(message: &str) -> Response {
             data: serde_json::json!({ "error": error }),
         },
     };
-    (StatusCode::UNAUTHORIZED, Json(response)).into_response()
+    (status, Json(response)).into_response()
 }
 
 /// Ping endpoint - basic connectivity test
@@ -298,7 +564,21 @@ async fn ping(Query(_params): Query<SubsonicQuery>) -> impl IntoResponse {
         subsonic_response: SubsonicResponseInner {
             status: "ok".to_string(),
             version: "1.16.1".to_string(),
-            data: serde_json::json!({}),
+            data: serde_json::json!({ "openSubsonic" : true }),
+        },
+    };
+    Json(response)
+}
+/// OpenSubsonic extensions this server supports. Answered without
+/// authentication, per the OpenSubsonic spec.
+async fn get_open_subsonic_extensions() -> impl IntoResponse {
+    let response = SubsonicResponse {
+        subsonic_response: SubsonicResponseInner {
+            status: "ok".to_string(),
+            version: "1.16.1".to_string(),
+            data: serde_json::json!({
+                "openSubsonicExtensions" : [{ "name" : "apiKeyAuthentication", "versions" : [1] }],
+            }),
         },
     };
     Json(response)
@@ -356,10 +636,12 @@ async fn get_artists(
 /// Get album list
 /// params required by Subsonic API spec but not currently validated
 async fn get_album_list(
-    Query(_params): Query<SubsonicQuery>,
+    Query(params): Query<HashMap<String, String>>,
     State(state): State<SubsonicState>,
 ) -> impl IntoResponse {
-    match load_albums(&state.library_manager).await {
+    let list_type = AlbumListType::from_params(&params);
+    let size = params.get("size").and_then(|s| s.parse::<usize>().ok());
+    match load_albums(&state.library_manager, list_type, size).await {
         Ok(album_response) => {
             let response = SubsonicResponse {
                 subsonic_response: SubsonicResponseInner {
@@ -386,6 +668,48 @@ async fn get_album_list(
         }
     }
 }
+/// Get all genres with their album counts
+async fn get_genres(
+    Query(_params): Query<SubsonicQuery>,
+    State(state): State<SubsonicState>,
+) -> impl IntoResponse {
+    match state.library_manager.get().get_genres().await {
+        Ok(genres) => {
+            let genre = genres
+                .into_iter()
+                .filter(|g| g.album_count > 0)
+                .map(|g| Genre {
+                    value: g.name,
+                    album_count: g.album_count as u32,
+                })
+                .collect();
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "ok".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: GenresResponse {
+                        genres: GenreList { genre },
+                    },
+                },
+            };
+            Json(response).into_response()
+        }
+        Err(e) => {
+            let error = SubsonicError {
+                code: 0,
+                message: format!("Failed to load genres: {}", e),
+            };
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "failed".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: serde_json::json!({ "error" : error }),
+                },
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response()
+        }
+    }
+}
 /// Get album with tracks
 async fn get_album(
     Query(params): Query<HashMap<String, String>>,
@@ -435,6 +759,310 @@ async fn get_album(
         }
     }
 }
+/// List playlists
+async fn get_playlists(
+    Query(_params): Query<SubsonicQuery>,
+    State(state): State<SubsonicState>,
+) -> impl IntoResponse {
+    match load_playlists(&state.library_manager).await {
+        Ok(playlist) => {
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "ok".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: PlaylistsResponse {
+                        playlists: PlaylistList { playlist },
+                    },
+                },
+            };
+            Json(response).into_response()
+        }
+        Err(e) => {
+            let error = SubsonicError {
+                code: 0,
+                message: format!("Failed to load playlists: {}", e),
+            };
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "failed".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: serde_json::json!({ "error" : error }),
+                },
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response()
+        }
+    }
+}
+/// Get playlist with tracks
+async fn get_playlist(
+    Query(params): Query<HashMap<String, String>>,
+    State(state): State<SubsonicState>,
+) -> impl IntoResponse {
+    let playlist_id = match params.get("id") {
+        Some(id) => id.clone(),
+        None => {
+            let error = SubsonicError {
+                code: 10,
+                message: "Required parameter 'id' missing".to_string(),
+            };
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "failed".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: serde_json::json!({ "error" : error }),
+                },
+            };
+            return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+        }
+    };
+    match load_playlist_with_songs(&state.library_manager, &playlist_id).await {
+        Ok(playlist_response) => {
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "ok".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: playlist_response,
+                },
+            };
+            Json(response).into_response()
+        }
+        Err(e) => {
+            let error = SubsonicError {
+                code: 70,
+                message: format!("Playlist not found: {}", e),
+            };
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "failed".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: serde_json::json!({ "error" : error }),
+                },
+            };
+            (StatusCode::NOT_FOUND, Json(response)).into_response()
+        }
+    }
+}
+/// Search artists, albums and songs, best matches first
+async fn search3(
+    Query(params): Query<HashMap<String, String>>,
+    State(state): State<SubsonicState>,
+) -> impl IntoResponse {
+    let (query, page) = parse_search(&params);
+    match load_search_results(&state.library_manager, &query, &page).await {
+        Ok(search_response) => {
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "ok".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: search_response,
+                },
+            };
+            Json(response).into_response()
+        }
+        Err(e) => {
+            let error = SubsonicError {
+                code: 0,
+                message: format!("Failed to search: {}", e),
+            };
+            let response = SubsonicResponse {
+                subsonic_response: SubsonicResponseInner {
+                    status: "failed".to_string(),
+                    version: "1.16.1".to_string(),
+                    data: serde_json::json!({ "error" : error }),
+                },
+            };
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response()
+        }
+    }
+}
+/// The search query and which page of each result kind to return. Counts
+/// default to 20. Clients syncing the whole library send an empty query or
+/// `""`, which matches everything.
+fn parse_search(params: &HashMap<String, String>) -> (String, SearchPage) {
+    let number = |name: &str, default: usize| {
+        params
+            .get(name)
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(default)
+    };
+    let query = params.get("query").map(String::as_str).unwrap_or("");
+    let query = if query == "\"\"" { "" } else { query };
+    let page = SearchPage {
+        artist_count: number("artistCount", 20),
+        artist_offset: number("artistOffset", 0),
+        album_count: number("albumCount", 20),
+        album_offset: number("albumOffset", 0),
+        track_count: number("songCount", 20),
+        track_offset: number("songOffset", 0),
+    };
+    (query.to_string(), page)
+}
+/// Record plays submitted by a client into the play history.
+///
+/// `id` and `time` (milliseconds since the epoch) may repeat to submit several
+/// plays at once. `submission=false` is a "now playing" notification and
+/// records nothing.
+async fn scrobble(
+    Query(params): Query<Vec<(String, String)>>,
+    State(state): State<SubsonicState>,
+) -> impl IntoResponse {
+    let plays = match parse_scrobbles(&params, chrono::Utc::now()) {
+        Ok(plays) => plays,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, 10, &message),
+    };
+    for (track_id, played_at) in plays {
+        let manager = state.library_manager.get();
+        let result = match manager.get_track(&track_id).await {
+            Ok(Some(_)) => manager.record_play_at(&track_id, played_at).await,
+            Ok(None) => {
+                return error_response(
+                    StatusCode::NOT_FOUND,
+                    70,
+                    &format!("Song not found: {}", track_id),
+                );
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                0,
+                &format!("Failed to record play: {}", e),
+            );
+        }
+        debug!("Scrobbled track {}", track_id);
+    }
+    let response = SubsonicResponse {
+        subsonic_response: SubsonicResponseInner {
+            status: "ok".to_string(),
+            version: "1.16.1".to_string(),
+            data: serde_json::json!({}),
+        },
+    };
+    Json(response).into_response()
+}
+/// Remote-control playback on this machine
+async fn jukebox_control(
+    Query(params): Query<Vec<(String, String)>>,
+    State(state): State<SubsonicState>,
+) -> impl IntoResponse {
+    let failed = |status: StatusCode, code: u32, message: String| {
+        let error = SubsonicError { code, message };
+        let response = SubsonicResponse {
+            subsonic_response: SubsonicResponseInner {
+                status: "failed".to_string(),
+                version: "1.16.1".to_string(),
+                data: serde_json::json!({ "error" : error }),
+            },
+        };
+        (status, Json(response)).into_response()
+    };
+    let Some(jukebox) = &state.jukebox else {
+        return failed(
+            StatusCode::NOT_IMPLEMENTED,
+            0,
+            "Jukebox is not available on this server".to_string(),
+        );
+    };
+    let action = match JukeboxAction::parse(&params) {
+        Ok(action) => action,
+        Err(message) => return failed(StatusCode::BAD_REQUEST, 10, message),
+    };
+    jukebox.control(&action);
+    let status = jukebox.status();
+    let data = if matches!(action, JukeboxAction::Get) {
+        let mut entry = Vec::new();
+        for track_id in status.playlist() {
+            match load_song(&state.library_manager, &track_id).await {
+                Ok(song) => entry.push(song),
+                Err(e) => warn!("Skipping jukebox track {}: {}", track_id, e),
+            }
+        }
+        let mut playlist = jukebox_status_json(&status);
+        playlist["entry"] = serde_json::json!(entry);
+        serde_json::json!({ "jukeboxPlaylist" : playlist })
+    } else {
+        serde_json::json!({ "jukeboxStatus" : jukebox_status_json(&status) })
+    };
+    let response = SubsonicResponse {
+        subsonic_response: SubsonicResponseInner {
+            status: "ok".to_string(),
+            version: "1.16.1".to_string(),
+            data,
+        },
+    };
+    Json(response).into_response()
+}
+/// Library change notifications as server-sent events. Not part of the
+/// Subsonic API: bae's own clients listen here and refetch what they show,
+/// instead of polling. Fires for local edits and for changesets pulled from
+/// other devices.
+async fn library_events(
+    State(state): State<SubsonicState>,
+) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let rx = state.library_manager.get().subscribe_events();
+    let stream =
+        library_event_names(rx).map(|name| Ok(Event::default().event("library").data(name)));
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+/// Event names for a library event subscription. A lagging subscriber only
+/// learns that something changed, which is all an event says anyway.
+fn library_event_names(
+    rx: tokio::sync::broadcast::Receiver<LibraryEvent>,
+) -> impl Stream<Item = &'static str> {
+    use tokio::sync::broadcast::error::RecvError;
+    futures::stream::unfold(rx, |mut rx| async move {
+        match rx.recv().await {
+            Ok(LibraryEvent::AlbumsChanged) | Err(RecvError::Lagged(_)) => {
+                Some(("albumsChanged", rx))
+            }
+            Err(RecvError::Closed) => None,
+        }
+    })
+}
+fn jukebox_status_json(status: &JukeboxStatus) -> serde_json::Value {
+    serde_json::json!({
+        "currentIndex" : status.current_index(),
+        "playing" : status.playing,
+        "gain" : status.gain,
+        "position" : status.position.as_secs(),
+    })
+}
+/// Pair each scrobbled track ID with when it was played. Plays without a
+/// `time` are taken to have happened at `now`, and times after `now` are
+/// refused; "now playing" notifications yield no plays.
+fn parse_scrobbles(
+    params: &[(String, String)],
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<(String, chrono::DateTime<chrono::Utc>)>, String> {
+    let values = |name: &str| {
+        params
+            .iter()
+            .filter(move |(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let ids: Vec<&str> = values("id").collect();
+    if ids.is_empty() {
+        return Err("Required parameter 'id' missing".to_string());
+    }
+    if values("submission").next() == Some("false") {
+        return Ok(Vec::new());
+    }
+    let times = values("time")
+        .map(|time| {
+            time.parse::<i64>()
+                .ok()
+                .and_then(chrono::DateTime::from_timestamp_millis)
+                .filter(|played_at| *played_at <= now)
+                .ok_or_else(|| format!("Invalid time: {}", time))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids
+        .into_iter()
+        .enumerate()
+        .map(|(i, id)| (id.to_string(), times.get(i).copied().unwrap_or(now)))
+        .collect())
+}
 /// Get cover art for an album
 async fn get_cover_art(
     Query(params): Query<HashMap<String, String>>,
@@ -470,17 +1098,30 @@ async fn get_cover_art(
         }
     };
 
+    let cover = state
+        .library_manager
+        .get()
+        .get_library_image(&release_id, &crate::db::LibraryImageType::Cover)
+        .await
+        .ok()
+        .flatten();
+
+    // Clients pass the size they display the cover at
+    let size = params
+        .get("size")
+        .and_then(|s| s.parse::<u32>().ok())
+        .and_then(thumbnail_size);
+    if let (Some(thumbnails), Some(cover), Some(size)) = (&state.thumbnails, &cover, size) {
+        if let Some(data) = thumbnails.get(cover, size).await {
+            return (StatusCode::OK, [("Content-Type", "image/jpeg")], data).into_response();
+        }
+    }
+
     let image_path = state.library_dir.image_path(&release_id);
 
     match tokio::fs::read(&image_path).await {
         Ok(data) => {
-            let content_type = state
-                .library_manager
-                .get()
-                .get_library_image(&release_id, &crate::db::LibraryImageType::Cover)
-                .await
-                .ok()
-                .flatten()
+            let content_type = cover
                 .map(|img| img.content_type.as_str().to_string())
                 .unwrap_or_else(|| "image/jpeg".to_string());
 
@@ -504,13 +1145,102 @@ enum TrackAudioSource {
         content_type: crate::content_type::ContentType,
         original_filename: String,
     },
-    /// Processed audio data (decrypted, byte-range sliced, headers prepended).
-    /// Already in memory, serve as a single body.
-    Buffered {
-        data: Vec<u8>,
-        content_type: crate::content_type::ContentType,
-        original_filename: String,
-    },
+    /// Local file that needs decryption, byte-range slicing, or header prepend.
+    Processed(ProcessedTrack),
+}
+
+/// A track served as `prefix` followed by plaintext `file_start..file_end` of
+/// its file, reading only the chunks that cover the requested range.
+struct ProcessedTrack {
+    path: PathBuf,
+    /// Per-release key and the file's base nonce, if the file is encrypted
+    decryption: Option<(crate::encryption::EncryptionService, Vec<u8>)>,
+    /// FLAC headers for CUE tracks, empty otherwise
+    prefix: Vec<u8>,
+    file_start: u64,
+    file_end: u64,
+    content_type: crate::content_type::ContentType,
+    original_filename: String,
+}
+
+impl ProcessedTrack {
+    fn len(&self) -> u64 {
+        self.prefix.len() as u64 + (self.file_end - self.file_start)
+    }
+
+    /// Read bytes `start..end` of the served track.
+    async fn read_range(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let prefix_len = self.prefix.len() as u64;
+        let mut data =
+            self.prefix[start.min(prefix_len) as usize..end.min(prefix_len) as usize].to_vec();
+
+        if end > prefix_len {
+            let range_start = self.file_start + start.saturating_sub(prefix_len);
+            let range_end = self.file_start + (end - prefix_len);
+            data.extend(
+                read_file_range(&self.path, self.decryption.as_ref(), range_start, range_end)
+                    .await?,
+            );
+        }
+
+        Ok(data)
+    }
+}
+
+/// Read plaintext bytes `start..end` of a file, decrypting only the chunks
+/// that overlap the range when the file is encrypted.
+async fn read_file_range(
+    path: &std::path::Path,
+    decryption: Option<&(crate::encryption::EncryptionService, Vec<u8>)>,
+    start: u64,
+    end: u64,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    if start >= end {
+        return Ok(Vec::new());
+    }
+
+    let format = match decryption {
+        Some((_, nonce)) => Some(crate::encryption::ContainerFormat::detect(nonce)?),
+        None => None,
+    };
+    let (read_start, read_end) = match format {
+        Some(format) => format.chunk_range(start, end),
+        None => (start, end),
+    };
+
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(std::io::SeekFrom::Start(read_start)).await?;
+    let mut buf = Vec::with_capacity((read_end - read_start) as usize);
+    file.take(read_end - read_start)
+        .read_to_end(&mut buf)
+        .await?;
+
+    match decryption.zip(format) {
+        Some(((release_enc, nonce), format)) => {
+            let first_chunk = start / format.chunk_size as u64;
+            Ok(release_enc
+                .decrypt_range_with_offset(nonce, &buf, first_chunk, start, end)
+                .map_err(|e| format!("Failed to decrypt file: {}", e))?)
+        }
+        None => {
+            if (buf.len() as u64) < end - start {
+                return Err(format!(
+                    "Byte range {}..{} out of bounds for {}",
+                    start,
+                    end,
+                    path.display()
+                )
+                .into());
+            }
+            Ok(buf)
+        }
+    }
 }
 
 /// Pre-fetched DB data needed to stream a track.
@@ -630,16 +1360,145 @@ async fn resolve_track_audio(
 
     // Slow path: need decryption, byte-range slicing, or header prepend
     debug!(
-        "Buffered path: encrypted={}, byte_slicing={}, headers={}",
+        "Processed path: encrypted={}, byte_slicing={}, headers={}",
         is_encrypted, needs_byte_slicing, needs_headers
     );
 
-    let (data, content_type) = buffer_track_audio(state, lookup).await?;
-    Ok(TrackAudioSource::Buffered {
-        data,
-        content_type,
+    let TrackLookup {
+        audio_format,
+        release,
+        audio_file,
+    } = lookup;
+
+    let path = resolve_file_path(&audio_file, &release, &state.library_dir)
+        .ok_or("Cannot determine file path for audio file")?;
+    let file_len = tokio::fs::metadata(&path)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .len();
+
+    let (decryption, plaintext_len) = if is_encrypted {
+        let enc = state
+            .encryption_service
+            .as_ref()
+            .ok_or("Cannot stream encrypted files: encryption not configured")?;
+        let nonce = match audio_file.encryption_nonce {
+            Some(nonce) => nonce,
+            None => read_file_nonce(&path).await?,
+        };
+        let format = crate::encryption::ContainerFormat::detect(&nonce)?;
+        (
+            Some((enc.derive_release_encryption(&release.id), nonce)),
+            format.plaintext_len(file_len),
+        )
+    } else {
+        (None, file_len)
+    };
+
+    let (file_start, file_end) =
+        match (audio_format.start_byte_offset, audio_format.end_byte_offset) {
+            (Some(start), Some(end)) => (start as u64, end as u64),
+            _ => (0, plaintext_len),
+        };
+    if file_start > file_end || file_end > plaintext_len {
+        return Err(format!(
+            "Byte range {}..{} out of bounds for {} byte file",
+            file_start, file_end, plaintext_len
+        )
+        .into());
+    }
+
+    let prefix = if needs_headers {
+        audio_format.flac_headers.unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    Ok(TrackAudioSource::Processed(ProcessedTrack {
+        path,
+        decryption,
+        prefix,
+        file_start,
+        file_end,
+        content_type: audio_format.content_type,
         original_filename,
-    })
+    }))
+}
+
+/// Read the container prefix (header and base nonce) from the start of an
+/// encrypted file.
+async fn read_file_nonce(
+    path: &std::path::Path,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    use crate::encryption::{ContainerFormat, HEADER_SIZE, NONCE_SIZE};
+    use tokio::io::AsyncReadExt;
+
+    // Every encrypted file holds at least a nonce and one auth tag, so this
+    // never reads past the end of a legacy file
+    let mut prefix = vec![0u8; HEADER_SIZE + NONCE_SIZE];
+    let mut file = tokio::fs::File::open(path).await?;
+    file.read_exact(&mut prefix)
+        .await
+        .map_err(|e| format!("Failed to read nonce: {}", e))?;
+    prefix.truncate(ContainerFormat::detect(&prefix)?.prefix_len());
+    Ok(prefix)
+}
+
+/// Outcome of interpreting a `Range` request header against a body length.
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    /// No usable range: serve the whole body
+    Full,
+    /// Serve bytes `start..end`
+    Partial { start: u64, end: u64 },
+    /// Range starts past the end of the body
+    Unsatisfiable,
+}
+
+/// Parse a single-range `bytes=` header (RFC 9110). Multi-range and malformed
+/// headers are ignored, which the spec permits.
+fn parse_byte_range(value: Option<&str>, total_len: u64) -> ByteRange {
+    let Some(spec) = value.and_then(|v| v.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    let (first, last) = (first.trim(), last.trim());
+
+    if first.is_empty() {
+        // Suffix range: the final N bytes
+        return match last.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if total_len == 0 => ByteRange::Unsatisfiable,
+            Ok(suffix) => ByteRange::Partial {
+                start: total_len.saturating_sub(suffix),
+                end: total_len,
+            },
+            Err(_) => ByteRange::Full,
+        };
+    }
+
+    let Ok(start) = first.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    let end = if last.is_empty() {
+        total_len
+    } else {
+        match last.parse::<u64>() {
+            Ok(last) if last >= start => last.saturating_add(1).min(total_len),
+            _ => return ByteRange::Full,
+        }
+    };
+
+    if start >= total_len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial { start, end }
 }
 
 /// Sanitize a filename for use in Content-Disposition headers.
@@ -649,9 +1508,13 @@ fn sanitize_content_disposition_filename(name: &str) -> String {
 }
 
 /// Stream a song - read and decrypt audio file from storage.
+///
+/// Single `Range: bytes=` requests get a 206 with just the requested bytes,
+/// so clients can seek without downloading the whole track.
 async fn stream_song(
     Query(params): Query<HashMap<String, String>>,
     State(state): State<SubsonicState>,
+    headers: HeaderMap,
 ) -> Response {
     let song_id = match params.get("id") {
         Some(id) => id.clone(),
@@ -660,86 +1523,294 @@ async fn stream_song(
         }
     };
 
-    let is_download = params.get("download").map(|v| v == "true").unwrap_or(false);
+    let is_download = params.get("download").map(|v| v == "true").unwrap_or(false);
+
+    info!("Streaming request for song ID: {}", song_id);
+
+    let source = match resolve_track_audio(&state, &song_id).await {
+        Ok(source) => source,
+        Err(e) => {
+            error!("Streaming error for song {}: {}", song_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Streaming error: {}", e),
+            )
+                .into_response();
+        }
+    };
+
+    let (total_len, content_type, original_filename) = match &source {
+        TrackAudioSource::DirectFile {
+            path,
+            content_type,
+            original_filename,
+        } => match tokio::fs::metadata(path).await {
+            Ok(meta) => (meta.len(), content_type.clone(), original_filename.clone()),
+            Err(e) => {
+                error!("Failed to open file {:?}: {}", path, e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to open file: {}", e),
+                )
+                    .into_response();
+            }
+        },
+        TrackAudioSource::Processed(track) => (
+            track.len(),
+            track.content_type.clone(),
+            track.original_filename.clone(),
+        ),
+    };
+
+    let range = parse_byte_range(
+        headers
+            .get(header::RANGE)
+            .and_then(|value| value.to_str().ok()),
+        total_len,
+    );
+    let (start, end) = match range {
+        ByteRange::Full => (0, total_len),
+        ByteRange::Partial { start, end } => (start, end),
+        ByteRange::Unsatisfiable => {
+            return Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header("Content-Range", format!("bytes */{}", total_len))
+                .body(Body::empty())
+                .unwrap();
+        }
+    };
+
+    let body = match &source {
+        TrackAudioSource::DirectFile { path, .. } => open_file_range(path, start, end)
+            .await
+            .map(|reader| Body::from_stream(ReaderStream::new(reader))),
+        TrackAudioSource::Processed(track) => track.read_range(start, end).await.map(Body::from),
+    };
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => {
+            error!("Streaming error for song {}: {}", song_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Streaming error: {}", e),
+            )
+                .into_response();
+        }
+    };
+
+    let mut builder = Response::builder()
+        .header("Content-Type", content_type.as_str())
+        .header("Content-Length", (end - start).to_string())
+        .header("Accept-Ranges", "bytes");
+
+    builder = if range == ByteRange::Full {
+        builder.status(StatusCode::OK)
+    } else {
+        debug!("Serving bytes {}-{}/{}", start, end - 1, total_len);
 
-    info!("Streaming request for song ID: {}", song_id);
+        builder.status(StatusCode::PARTIAL_CONTENT).header(
+            "Content-Range",
+            format!("bytes {}-{}/{}", start, end - 1, total_len),
+        )
+    };
 
-    match resolve_track_audio(&state, &song_id).await {
-        Ok(TrackAudioSource::DirectFile {
-            path,
-            content_type,
-            original_filename,
-        }) => match tokio::fs::File::open(&path).await {
-            Ok(file) => {
-                let metadata = file.metadata().await;
-                let stream = ReaderStream::new(file);
-                let body = Body::from_stream(stream);
-
-                let mut builder = Response::builder()
-                    .status(StatusCode::OK)
-                    .header("Content-Type", content_type.as_str())
-                    .header("Accept-Ranges", "bytes");
-
-                if let Ok(meta) = metadata {
-                    builder = builder.header("Content-Length", meta.len().to_string());
-                }
+    if is_download {
+        builder = builder.header(
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"{}\"",
+                sanitize_content_disposition_filename(&original_filename),
+            ),
+        );
+    }
 
-                if is_download {
-                    builder = builder.header(
-                        "Content-Disposition",
-                        format!(
-                            "attachment; filename=\"{}\"",
-                            sanitize_content_disposition_filename(&original_filename),
-                        ),
-                    );
-                }
+    builder.body(body).unwrap()
+}
 
-                builder.body(body).unwrap()
-            }
-            Err(e) => {
-                error!("Failed to open file {:?}: {}", path, e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to open file: {}", e),
-                )
-                    .into_response()
-            }
-        },
-        Ok(TrackAudioSource::Buffered {
-            data,
-            content_type,
-            original_filename,
-        }) => {
-            let content_length = data.len().to_string();
-
-            let mut builder = Response::builder()
-                .status(StatusCode::OK)
-                .header("Content-Type", content_type.as_str())
-                .header("Content-Length", content_length)
-                .header("Accept-Ranges", "bytes");
-
-            if is_download {
-                builder = builder.header(
-                    "Content-Disposition",
-                    format!(
-                        "attachment; filename=\"{}\"",
-                        sanitize_content_disposition_filename(&original_filename),
-                    ),
-                );
-            }
+/// Open a file positioned at `start`, limited to `end - start` bytes.
+async fn open_file_range(
+    path: &std::path::Path,
+    start: u64,
+    end: u64,
+) -> Result<tokio::io::Take<tokio::fs::File>, Box<dyn std::error::Error + Send + Sync>> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(std::io::SeekFrom::Start(start)).await?;
+    Ok(file.take(end - start))
+}
+/// HLS playlist for a song, for players that only take HLS (Chromecast,
+/// smart TVs, Safari)
+async fn get_hls_playlist(
+    Query(params): Query<Vec<(String, String)>>,
+    State(state): State<SubsonicState>,
+) -> Response {
+    let (song_id, bit_rate) = match parse_hls_params(&params) {
+        Ok(parsed) => parsed,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response(),
+    };
 
-            builder.body(Body::from(data)).unwrap()
+    info!("HLS playlist request for song ID: {}", song_id);
+
+    match load_hls_track(&state, &song_id, bit_rate).await {
+        Ok(track) => {
+            let playlist = crate::hls::media_playlist(&track.segments, |index| {
+                hls_segment_uri(&params, index)
+            });
+            Response::builder()
+                .header("Content-Type", "application/vnd.apple.mpegurl")
+                .body(Body::from(playlist))
+                .unwrap()
         }
         Err(e) => {
-            error!("Streaming error for song {}: {}", song_id, e);
+            error!("HLS error for song {}: {}", song_id, e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Streaming error: {}", e),
+                format!("HLS error: {}", e),
             )
                 .into_response()
         }
     }
 }
+/// One AAC segment of a song's HLS stream
+async fn get_hls_segment(
+    Query(params): Query<Vec<(String, String)>>,
+    State(state): State<SubsonicState>,
+) -> Response {
+    let (song_id, bit_rate) = match parse_hls_params(&params) {
+        Ok(parsed) => parsed,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response(),
+    };
+    let Some(index) = params
+        .iter()
+        .find(|(key, _)| key == "index")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+    else {
+        return (StatusCode::BAD_REQUEST, "Missing segment index").into_response();
+    };
+
+    let track = match load_hls_track(&state, &song_id, bit_rate).await {
+        Ok(track) => track,
+        Err(e) => {
+            error!("HLS error for song {}: {}", song_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("HLS error: {}", e),
+            )
+                .into_response();
+        }
+    };
+    match track.segment(index) {
+        Some(segment) => Response::builder()
+            .header("Content-Type", "audio/aac")
+            .header("Content-Length", segment.len().to_string())
+            .body(Body::from(segment))
+            .unwrap(),
+        None => (StatusCode::NOT_FOUND, "Segment not found").into_response(),
+    }
+}
+/// The song ID and bit rate (kbps) of an HLS request. `bitRate` may list
+/// several rates; only the first is used.
+fn parse_hls_params(params: &[(String, String)]) -> Result<(String, u32), String> {
+    let value = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let song_id = value("id").ok_or("Missing song ID")?;
+    let bit_rate = value("bitRate")
+        .and_then(|rates| rates.split(',').next())
+        .and_then(|rate| rate.trim().parse::<u32>().ok())
+        .filter(|&rate| rate > 0)
+        .map(|rate| rate.clamp(32, 320))
+        .unwrap_or(crate::hls::DEFAULT_BIT_RATE);
+    Ok((song_id.to_string(), bit_rate))
+}
+/// A segment's URI relative to the playlist. It repeats the playlist
+/// request's parameters, so the segment request authenticates the same way.
+fn hls_segment_uri(params: &[(String, String)], index: usize) -> String {
+    let index = index.to_string();
+    let query: Vec<(&str, &str)> = params
+        .iter()
+        .filter(|(key, _)| key != "index")
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .chain([("index", index.as_str())])
+        .collect();
+    format!(
+        "hlsSegment?{}",
+        serde_urlencoded::to_string(&query).unwrap_or_default()
+    )
+}
+/// Encode a song for HLS at `bit_rate` kbps, or take it from the cache. The
+/// audio is read and decrypted as for `stream` before it's re-encoded.
+async fn load_hls_track(
+    state: &SubsonicState,
+    song_id: &str,
+    bit_rate: u32,
+) -> Result<Arc<HlsTrack>, String> {
+    let key = transcode_cache_key(song_id, "aac", bit_rate);
+    state
+        .hls
+        .get_or_encode(&key, || async {
+            let (audio, _) = stream_track_audio(state, song_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            tokio::task::spawn_blocking(move || HlsTrack::encode(&audio, bit_rate))
+                .await
+                .map_err(|e| e.to_string())?
+        })
+        .await
+}
+/// Get lyrics for a song by artist and title, fetching them on first request
+async fn get_lyrics(
+    Query(params): Query<HashMap<String, String>>,
+    State(state): State<SubsonicState>,
+) -> impl IntoResponse {
+    let artist = params.get("artist").cloned();
+    let title = params.get("title").cloned();
+    let value = match &title {
+        Some(title) => load_lyrics_text(&state.library_manager, artist.as_deref(), title)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to load lyrics for '{}': {}", title, e);
+
+                String::new()
+            }),
+        None => String::new(),
+    };
+    let response = SubsonicResponse {
+        subsonic_response: SubsonicResponseInner {
+            status: "ok".to_string(),
+            version: "1.16.1".to_string(),
+            data: LyricsResponse {
+                lyrics: SongLyrics {
+                    artist,
+                    title,
+                    value,
+                },
+            },
+        },
+    };
+    Json(response).into_response()
+}
+/// Plain lyrics for the library track matching title and artist, or empty
+async fn load_lyrics_text(
+    library_manager: &SharedLibraryManager,
+    artist: Option<&str>,
+    title: &str,
+) -> Result<String, LibraryError> {
+    let manager = library_manager.get();
+    let Some(track_id) = manager
+        .database()
+        .find_track_by_title(title, artist)
+        .await?
+    else {
+        return Ok(String::new());
+    };
+    let lyrics = manager.fetch_lyrics(&track_id).await?;
+    Ok(lyrics.plain_text().unwrap_or_default())
+}
 /// Load artists from database and group by first letter
 async fn load_artists(
     library_manager: &SharedLibraryManager,
@@ -786,10 +1857,92 @@ async fn load_artists(
     })
 }
 /// Load albums from database
+/// Which albums `getAlbumList` returns
+#[derive(Debug, PartialEq)]
+enum AlbumListType<'a> {
+    All,
+    ByGenre(&'a str),
+    /// Released between the two years, inclusive. Newest first when `from`
+    /// is after `to`, per the Subsonic spec.
+    ByYear {
+        from: i32,
+        to: i32,
+    },
+    /// Most recently added first
+    Newest,
+    /// Most recently played first
+    Recent,
+}
+impl<'a> AlbumListType<'a> {
+    /// `byGenre`, `byYear`, `newest` and `recent` narrow the list; other list
+    /// types, and narrowing types missing their parameters, return every album
+    fn from_params(params: &'a HashMap<String, String>) -> Self {
+        let year_param = |name: &str| params.get(name).and_then(|y| y.parse::<i32>().ok());
+        match params.get("type").map(String::as_str) {
+            Some("byGenre") => params
+                .get("genre")
+                .map(String::as_str)
+                .map(AlbumListType::ByGenre)
+                .unwrap_or(AlbumListType::All),
+            Some("byYear") => match (year_param("fromYear"), year_param("toYear")) {
+                (Some(from), Some(to)) => AlbumListType::ByYear { from, to },
+                _ => AlbumListType::All,
+            },
+            Some("newest") => AlbumListType::Newest,
+            Some("recent") => AlbumListType::Recent,
+            _ => AlbumListType::All,
+        }
+    }
+}
 async fn load_albums(
     library_manager: &SharedLibraryManager,
+    list_type: AlbumListType<'_>,
+    size: Option<usize>,
 ) -> Result<AlbumListResponse, LibraryError> {
-    let db_albums = library_manager.get().get_albums(&[]).await?;
+    let mut db_albums = library_manager.get().get_albums(&[]).await?;
+    let ordered_ids = match list_type {
+        AlbumListType::All => None,
+        AlbumListType::ByGenre(genre) => {
+            let genre_id = crate::genres::genre_key(genre);
+            let album_ids = library_manager
+                .get()
+                .get_album_ids_for_genre(&genre_id)
+                .await?;
+            db_albums.retain(|a| album_ids.contains(&a.id));
+            None
+        }
+        AlbumListType::ByYear { from, to } => {
+            let mut album_ids = library_manager
+                .get()
+                .get_album_ids_for_years(from.min(to), from.max(to))
+                .await?;
+            if from > to {
+                album_ids.reverse();
+            }
+            Some(album_ids)
+        }
+        AlbumListType::Newest => Some(library_manager.get().get_recently_added_album_ids().await?),
+        AlbumListType::Recent => Some(
+            library_manager
+                .get()
+                .get_recently_played_album_ids()
+                .await?,
+        ),
+    };
+    if let Some(ids) = ordered_ids {
+        db_albums = ids
+            .iter()
+            .filter_map(|id| {
+                let index = db_albums.iter().position(|a| &a.id == id)?;
+                Some(db_albums.swap_remove(index))
+            })
+            .collect();
+    }
+    if let Some(size) = size {
+        db_albums.truncate(size);
+    }
+    let mut cover_blurhashes = library_manager.get().get_album_cover_blurhashes().await?;
+    let mut genres = library_manager.get().get_album_primary_genres().await?;
     let mut albums = Vec::new();
     for db_album in db_albums {
         let tracks = library_manager.get().get_tracks(&db_album.id).await?;
@@ -820,7 +1973,8 @@ async fn load_albums(
             song_count: tracks.len() as u32,
             duration: 0,
             year: db_album.year,
-            genre: None,
+            genre: genres.remove(&db_album.id),
+            cover_blurhash: cover_blurhashes.remove(&db_album.id),
             cover_art,
         });
     }
@@ -839,6 +1993,12 @@ async fn load_album_with_songs(
         .find(|a| a.id == album_id)
         .ok_or_else(|| LibraryError::Import("Album not found".to_string()))?;
     let tracks = library_manager.get().get_tracks(album_id).await?;
+    let genre = library_manager
+        .get()
+        .get_album_genres(album_id)
+        .await?
+        .into_iter()
+        .next();
     let album_artists = library_manager
         .get()
         .get_artists_for_album(&db_album.id)
@@ -889,7 +2049,7 @@ async fn load_album_with_songs(
             artist_id: format!("artist_{}", track_artist_name.replace(' ', "_")),
             track: track.track_number,
             year: db_album.year,
-            genre: None,
+            genre: genre.clone(),
             cover_art: song_cover_art,
             size: None,
             content_type: track_content_type.as_str().to_string(),
@@ -914,16 +2074,184 @@ async fn load_album_with_songs(
         song_count: songs.len() as u32,
         duration: songs.iter().map(|s| s.duration.unwrap_or(0) as u32).sum(),
         year: db_album.year,
-        genre: None,
+        genre,
         cover_art: album_cover_art,
+        cover_blurhash: None,
     };
     Ok(serde_json::json!(
         { "album" : { "id" : album.id, "name" : album.name, "artist" : album.artist,
         "artistId" : album.artist_id, "songCount" : album.song_count, "duration" :
-        album.duration, "year" : album.year, "coverArt" : album.cover_art, "song" :
-        songs } }
+        album.duration, "year" : album.year, "genre" : album.genre, "coverArt" :
+        album.cover_art, "song" : songs } }
     ))
 }
+async fn load_playlists(
+    library_manager: &SharedLibraryManager,
+) -> Result<Vec<Playlist>, LibraryError> {
+    let mut playlists = Vec::new();
+    for playlist in library_manager.get().get_playlists().await? {
+        let entries = library_manager
+            .get()
+            .get_playlist_entries(&playlist.id)
+            .await?;
+        playlists.push(Playlist {
+            id: playlist.id,
+            name: playlist.name,
+            song_count: entries.len() as u32,
+            duration: entries
+                .iter()
+                .map(|e| (e.duration_ms.unwrap_or(0) / 1000) as u32)
+                .sum(),
+            created: playlist.created_at.to_rfc3339(),
+            changed: playlist.updated_at.to_rfc3339(),
+        });
+    }
+    Ok(playlists)
+}
+async fn load_playlist_with_songs(
+    library_manager: &SharedLibraryManager,
+    playlist_id: &str,
+) -> Result<serde_json::Value, LibraryError> {
+    let playlist = library_manager
+        .get()
+        .get_playlist(playlist_id)
+        .await?
+        .ok_or_else(|| LibraryError::Playlist("Playlist not found".to_string()))?;
+    let entries = library_manager
+        .get()
+        .get_playlist_entries(playlist_id)
+        .await?;
+    let mut songs = Vec::new();
+    for entry in entries {
+        let track_content_type = library_manager
+            .get()
+            .get_audio_format_by_track_id(&entry.track_id)
+            .await?
+            .map(|af| af.content_type)
+            .unwrap_or(crate::content_type::ContentType::Flac);
+
+        songs.push(Song {
+            id: entry.track_id,
+            title: entry.title,
+            album: entry.album_title.clone(),
+            artist: entry.artist_name.clone(),
+            album_id: entry.album_id.clone(),
+            artist_id: format!("artist_{}", entry.artist_name.replace(' ', "_")),
+            track: None,
+            year: None,
+            genre: None,
+            cover_art: entry
+                .cover_release_id
+                .as_ref()
+                .map(|_| entry.album_id.clone()),
+            size: None,
+            content_type: track_content_type.as_str().to_string(),
+            suffix: track_content_type.file_extension().to_string(),
+            duration: entry.duration_ms.map(|ms| (ms / 1000) as i32),
+            bit_rate: None,
+            path: format!("{}/{}", entry.artist_name, entry.album_title),
+        });
+    }
+    let duration: u32 = songs.iter().map(|s| s.duration.unwrap_or(0) as u32).sum();
+    Ok(serde_json::json!(
+        { "playlist" : { "id" : playlist.id, "name" : playlist.name, "songCount" :
+        songs.len(), "duration" : duration, "created" : playlist.created_at.to_rfc3339(),
+        "changed" : playlist.updated_at.to_rfc3339(), "entry" : songs } }
+    ))
+}
+/// Run a library search and shape the results for `search3`
+async fn load_search_results(
+    library_manager: &SharedLibraryManager,
+    query: &str,
+    page: &SearchPage,
+) -> Result<SearchResult3Response, LibraryError> {
+    let results = library_manager
+        .get()
+        .search_library_page(query, page)
+        .await?;
+    let artist = results
+        .artists
+        .into_iter()
+        .map(|a| Artist {
+            id: format!("artist_{}", a.name.replace(' ', "_")),
+            name: a.name,
+            album_count: a.album_count as u32,
+        })
+        .collect();
+    let album = results
+        .albums
+        .into_iter()
+        .map(|a| Album {
+            artist_id: format!("artist_{}", a.artist_name.replace(' ', "_")),
+            cover_art: a.cover_release_id.as_ref().map(|_| a.id.clone()),
+            id: a.id,
+            name: a.title,
+            artist: a.artist_name,
+            song_count: 0,
+            duration: 0,
+            year: a.year,
+            genre: None,
+            cover_blurhash: None,
+        })
+        .collect();
+    let mut song = Vec::new();
+    for track in results.tracks {
+        song.push(load_song(library_manager, &track.id).await?);
+    }
+    Ok(SearchResult3Response {
+        search_result3: SearchResult3 {
+            artist,
+            album,
+            song,
+        },
+    })
+}
+/// Load one track as a song, with its album and album artist
+async fn load_song(
+    library_manager: &SharedLibraryManager,
+    track_id: &str,
+) -> Result<Song, LibraryError> {
+    let manager = library_manager.get();
+    let track = manager
+        .get_track(track_id)
+        .await?
+        .ok_or_else(|| LibraryError::TrackMapping("Track not found".to_string()))?;
+    let album_id = manager.get_album_id_for_track(track_id).await?;
+    let album = manager
+        .get_album_by_id(&album_id)
+        .await?
+        .ok_or_else(|| LibraryError::TrackMapping("Album not found".to_string()))?;
+    let artist_name = manager
+        .get_artists_for_album(&album_id)
+        .await?
+        .first()
+        .map(|a| a.name.clone())
+        .unwrap_or_else(|| "Unknown Artist".to_string());
+    let content_type = manager
+        .get_audio_format_by_track_id(track_id)
+        .await?
+        .map(|af| af.content_type)
+        .unwrap_or(crate::content_type::ContentType::Flac);
+
+    Ok(Song {
+        id: track.id,
+        title: track.title,
+        album: album.title.clone(),
+        artist: artist_name.clone(),
+        album_id: album.id.clone(),
+        artist_id: format!("artist_{}", artist_name.replace(' ', "_")),
+        track: track.track_number,
+        year: album.year,
+        genre: None,
+        cover_art: album.cover_release_id.as_ref().map(|_| album.id.clone()),
+        size: None,
+        content_type: content_type.as_str().to_string(),
+        suffix: content_type.file_extension().to_string(),
+        duration: track.duration_ms.map(|ms| (ms / 1000) as i32),
+        bit_rate: None,
+        path: format!("{}/{}", artist_name, album.title),
+    })
+}
 /// Stream track audio - read file and decrypt if needed.
 /// Returns audio data and its content type.
 pub async fn stream_track_audio(
@@ -1025,6 +2353,24 @@ async fn buffer_track_audio(
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn library_events_stream_until_the_library_closes() {
+        let (tx, rx) = tokio::sync::broadcast::channel(1);
+        let mut names = Box::pin(library_event_names(rx));
+
+        tx.send(LibraryEvent::AlbumsChanged).unwrap();
+        assert_eq!(names.next().await, Some("albumsChanged"));
+
+        // Overflow the channel: the subscriber still hears about it
+        tx.send(LibraryEvent::AlbumsChanged).unwrap();
+        tx.send(LibraryEvent::AlbumsChanged).unwrap();
+        assert_eq!(names.next().await, Some("albumsChanged"));
+        assert_eq!(names.next().await, Some("albumsChanged"));
+
+        drop(tx);
+        assert_eq!(names.next().await, None);
+    }
+
     fn auth_enabled(username: &str, password: &str) -> SubsonicAuth {
         SubsonicAuth {
             enabled: true,
@@ -1049,6 +2395,7 @@ mod tests {
             p: None,
             t: None,
             s: None,
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_ok());
     }
@@ -1061,6 +2408,7 @@ mod tests {
             p: Some("secret123".to_string()),
             t: None,
             s: None,
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_ok());
     }
@@ -1073,6 +2421,7 @@ mod tests {
             p: Some("wrong".to_string()),
             t: None,
             s: None,
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_err());
     }
@@ -1087,6 +2436,7 @@ mod tests {
             p: Some(format!("enc:{}", hex_password)),
             t: None,
             s: None,
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_ok());
     }
@@ -1101,6 +2451,7 @@ mod tests {
             p: None,
             t: Some(token),
             s: Some(salt.to_string()),
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_ok());
     }
@@ -1113,6 +2464,7 @@ mod tests {
             p: None,
             t: Some("badtoken".to_string()),
             s: Some("somesalt".to_string()),
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_err());
     }
@@ -1125,6 +2477,7 @@ mod tests {
             p: Some("secret123".to_string()),
             t: None,
             s: None,
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_err());
     }
@@ -1137,6 +2490,7 @@ mod tests {
             p: None,
             t: None,
             s: None,
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_err());
     }
@@ -1149,6 +2503,7 @@ mod tests {
             p: None,
             t: None,
             s: None,
+            api_key: None,
         };
         assert!(validate_auth(&auth, &query).is_err());
     }
@@ -1158,4 +2513,337 @@ mod tests {
         // Known MD5 hash for "password"
         assert_eq!(md5_hex("password"), "5f4dcc3b5aa765d61d8327deb882cf99");
     }
+
+    #[test]
+    fn byte_range_bounded() {
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-99"), 1000),
+            ByteRange::Partial { start: 0, end: 100 }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=900-1999"), 1000),
+            ByteRange::Partial {
+                start: 900,
+                end: 1000
+            }
+        );
+    }
+
+    #[test]
+    fn byte_range_open_ended_and_suffix() {
+        assert_eq!(
+            parse_byte_range(Some("bytes=500-"), 1000),
+            ByteRange::Partial {
+                start: 500,
+                end: 1000
+            }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-200"), 1000),
+            ByteRange::Partial {
+                start: 800,
+                end: 1000
+            }
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-5000"), 1000),
+            ByteRange::Partial {
+                start: 0,
+                end: 1000
+            }
+        );
+    }
+
+    #[test]
+    fn byte_range_unsatisfiable() {
+        assert_eq!(
+            parse_byte_range(Some("bytes=1000-"), 1000),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            parse_byte_range(Some("bytes=-0"), 1000),
+            ByteRange::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn byte_range_ignores_unsupported_headers() {
+        assert_eq!(parse_byte_range(None, 1000), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("items=0-1"), 1000), ByteRange::Full);
+        assert_eq!(
+            parse_byte_range(Some("bytes=0-1,5-6"), 1000),
+            ByteRange::Full
+        );
+        assert_eq!(parse_byte_range(Some("bytes=50-10"), 1000), ByteRange::Full);
+        assert_eq!(parse_byte_range(Some("bytes=abc"), 1000), ByteRange::Full);
+    }
+
+    #[test]
+    fn album_list_type_reads_genre_and_year_params() {
+        let params = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        let by_genre = params(&[("type", "byGenre"), ("genre", "Shoegaze")]);
+        assert_eq!(
+            AlbumListType::from_params(&by_genre),
+            AlbumListType::ByGenre("Shoegaze")
+        );
+        let by_year = params(&[("type", "byYear"), ("fromYear", "1999"), ("toYear", "1990")]);
+        assert_eq!(
+            AlbumListType::from_params(&by_year),
+            AlbumListType::ByYear {
+                from: 1999,
+                to: 1990
+            }
+        );
+        let missing_year = params(&[("type", "byYear"), ("fromYear", "1990")]);
+        assert_eq!(
+            AlbumListType::from_params(&missing_year),
+            AlbumListType::All
+        );
+        let bad_year = params(&[
+            ("type", "byYear"),
+            ("fromYear", "1990"),
+            ("toYear", "later"),
+        ]);
+        assert_eq!(AlbumListType::from_params(&bad_year), AlbumListType::All);
+        assert_eq!(
+            AlbumListType::from_params(&params(&[("type", "random")])),
+            AlbumListType::All
+        );
+    }
+
+    fn query(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn scrobbles_pair_ids_with_times() {
+        let now = chrono::DateTime::from_timestamp_millis(1_700_000_900_000).unwrap();
+        let plays = parse_scrobbles(
+            &query(&[
+                ("id", "t1"),
+                ("id", "t2"),
+                ("time", "1700000000000"),
+                ("submission", "true"),
+            ]),
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            plays,
+            vec![
+                (
+                    "t1".to_string(),
+                    chrono::DateTime::from_timestamp_millis(1_700_000_000_000).unwrap()
+                ),
+                ("t2".to_string(), now),
+            ]
+        );
+    }
+
+    #[test]
+    fn now_playing_scrobbles_record_nothing() {
+        let plays = parse_scrobbles(
+            &query(&[("id", "t1"), ("submission", "false")]),
+            chrono::Utc::now(),
+        )
+        .unwrap();
+        assert!(plays.is_empty());
+    }
+
+    #[test]
+    fn scrobbles_reject_missing_id_and_bad_time() {
+        assert!(parse_scrobbles(&query(&[("time", "1")]), chrono::Utc::now()).is_err());
+        assert!(parse_scrobbles(
+            &query(&[("id", "t1"), ("time", "yesterday")]),
+            chrono::Utc::now()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn scrobbles_reject_times_in_the_future() {
+        let now = chrono::DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        assert!(parse_scrobbles(&query(&[("id", "t1"), ("time", "1700000000001")]), now).is_err());
+        assert!(parse_scrobbles(&query(&[("id", "t1"), ("time", "1700000000000")]), now).is_ok());
+    }
+
+    #[test]
+    fn search_reads_counts_and_offsets() {
+        let params: HashMap<String, String> = query(&[
+            ("query", "low light"),
+            ("artistCount", "0"),
+            ("albumOffset", "40"),
+            ("songCount", "50"),
+            ("songOffset", "junk"),
+        ])
+        .into_iter()
+        .collect();
+        let (search, page) = parse_search(&params);
+        assert_eq!(search, "low light");
+        assert_eq!(
+            page,
+            SearchPage {
+                artist_count: 0,
+                artist_offset: 0,
+                album_count: 20,
+                album_offset: 40,
+                track_count: 50,
+                track_offset: 0,
+            }
+        );
+
+        let params: HashMap<String, String> = query(&[("query", "\"\"")]).into_iter().collect();
+        assert_eq!(
+            parse_search(&params),
+            (String::new(), SearchPage::first(20))
+        );
+    }
+
+    #[test]
+    fn hls_segments_carry_the_playlist_parameters() {
+        let params = query(&[
+            ("id", "t1"),
+            ("u", "admin"),
+            ("t", "a1b2"),
+            ("s", "salt&pepper"),
+            ("bitRate", "128,256"),
+        ]);
+        assert_eq!(parse_hls_params(&params), Ok(("t1".to_string(), 128)));
+        assert_eq!(
+            hls_segment_uri(&params, 3),
+            "hlsSegment?id=t1&u=admin&t=a1b2&s=salt%26pepper&bitRate=128%2C256&index=3"
+        );
+        assert_eq!(
+            parse_hls_params(&query(&[("id", "t1"), ("bitRate", "9000")])),
+            Ok(("t1".to_string(), 320))
+        );
+        assert!(parse_hls_params(&query(&[("bitRate", "128")])).is_err());
+    }
+
+    fn app_password(id: &str, password: &str) -> DbAppPassword {
+        DbAppPassword {
+            id: id.to_string(),
+            name: id.to_string(),
+            password: password.to_string(),
+            created_at: chrono::Utc::now(),
+            last_used_at: None,
+            disabled: false,
+        }
+    }
+
+    #[test]
+    fn app_password_matches_token_and_plaintext() {
+        let auth = auth_enabled("admin", "secret123");
+        let app_passwords = vec![
+            app_password("phone", "aaaa-bbbb-cccc-dddd"),
+            app_password("car", "eeee-ffff-gggg-hhhh"),
+        ];
+
+        let token_query = SubsonicQuery {
+            u: Some("admin".to_string()),
+            p: None,
+            t: Some(md5_hex("eeee-ffff-gggg-hhhhsalt")),
+            s: Some("salt".to_string()),
+            api_key: None,
+        };
+        let matched = match_app_password(&auth, &app_passwords, &token_query).unwrap();
+        assert_eq!(matched.id, "car");
+
+        let plain_query = SubsonicQuery {
+            u: Some("admin".to_string()),
+            p: Some("aaaa-bbbb-cccc-dddd".to_string()),
+            t: None,
+            s: None,
+            api_key: None,
+        };
+        let matched = match_app_password(&auth, &app_passwords, &plain_query).unwrap();
+        assert_eq!(matched.id, "phone");
+    }
+
+    #[test]
+    fn disabled_app_password_is_refused() {
+        let auth = auth_enabled("admin", "secret123");
+        let mut phone = app_password("phone", "aaaa-bbbb-cccc-dddd");
+        phone.disabled = true;
+        let app_passwords = vec![phone];
+
+        let query = SubsonicQuery {
+            u: Some("admin".to_string()),
+            p: Some("aaaa-bbbb-cccc-dddd".to_string()),
+            t: None,
+            s: None,
+            api_key: None,
+        };
+        assert!(match_app_password(&auth, &app_passwords, &query).is_none());
+
+        let api_key_query = SubsonicQuery {
+            u: None,
+            p: None,
+            t: None,
+            s: None,
+            api_key: Some("aaaa-bbbb-cccc-dddd".to_string()),
+        };
+        assert!(match_api_key(&app_passwords, &api_key_query).is_none());
+    }
+
+    #[test]
+    fn app_password_requires_server_username() {
+        let auth = auth_enabled("admin", "secret123");
+        let app_passwords = vec![app_password("phone", "aaaa-bbbb-cccc-dddd")];
+        let query = SubsonicQuery {
+            u: Some("someone".to_string()),
+            p: Some("aaaa-bbbb-cccc-dddd".to_string()),
+            t: None,
+            s: None,
+            api_key: None,
+        };
+        assert!(match_app_password(&auth, &app_passwords, &query).is_none());
+
+        // The account password is not an app password
+        let query = SubsonicQuery {
+            u: Some("admin".to_string()),
+            p: Some("secret123".to_string()),
+            t: None,
+            s: None,
+            api_key: None,
+        };
+        assert!(match_app_password(&auth, &app_passwords, &query).is_none());
+    }
+
+    #[test]
+    fn api_key_matches_app_password_without_username() {
+        let app_passwords = vec![
+            app_password("phone", "aaaa-bbbb-cccc-dddd"),
+            app_password("car", "eeee-ffff-gggg-hhhh"),
+        ];
+        let query = |api_key: Option<&str>| SubsonicQuery {
+            u: None,
+            p: None,
+            t: None,
+            s: None,
+            api_key: api_key.map(str::to_string),
+        };
+
+        let matched = match_api_key(&app_passwords, &query(Some("eeee-ffff-gggg-hhhh"))).unwrap();
+        assert_eq!(matched.id, "car");
+        assert!(match_api_key(&app_passwords, &query(Some("secret123"))).is_none());
+        assert!(match_api_key(&app_passwords, &query(None)).is_none());
+    }
+
+    #[test]
+    fn generated_app_passwords_are_grouped_and_unique() {
+        let first = generate_app_password();
+        assert_eq!(first.len(), 19);
+        assert_eq!(first.split('-').count(), 4);
+        assert_ne!(first, generate_app_password());
+    }
 }
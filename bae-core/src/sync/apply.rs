@@ -11,15 +11,19 @@
 /// For `release_files` DATA conflicts where incoming wins, the local
 /// `encryption_nonce` value is restored after applying because that column
 /// is device-specific.
+///
+/// For DATA conflicts where local wins, columns only the remote side edited
+/// are written after applying, so both devices end up with both edits.
 use std::collections::HashMap;
 use std::ffi::{c_char, c_int, CStr, CString};
 use std::ptr;
 
 use libsqlite3_sys as ffi;
 
-use super::conflict::{lww_conflict_handler, ConflictTracker, TableSchema};
+use super::conflict::{lww_conflict_handler, ConflictTracker, FieldMerge, TableSchema};
 use super::session::SyncError;
-use super::session_ext::{apply_changeset_with_context, Changeset};
+use super::session_ext::{apply_changeset_with_context, Changeset, ColumnValue};
+use crate::db::DbSyncConflict;
 
 /// Result of applying a changeset.
 pub struct ApplyResult {
@@ -27,6 +31,8 @@ pub struct ApplyResult {
     /// want to retry this changeset after applying other changesets that
     /// contain the missing parent rows.
     pub had_fk_violations: bool,
+    /// Concurrent edits the user may want to review.
+    pub conflicts: Vec<DbSyncConflict>,
 }
 
 /// Snapshot of device-specific columns for a single release_files row.
@@ -67,8 +73,14 @@ pub unsafe fn apply_changeset_lww(
         }
     }
 
+    // Merge in remote-only column edits on rows where local won.
+    for merge in &tracker.merges {
+        apply_field_merge(db, merge);
+    }
+
     Ok(ApplyResult {
         had_fk_violations: tracker.had_constraint_conflict,
+        conflicts: tracker.conflicts,
     })
 }
 
@@ -173,3 +185,79 @@ unsafe fn restore_device_local_columns(
 
     ffi::sqlite3_finalize(stmt);
 }
+
+/// Write the remote-only column edits on a row where the local edit won.
+unsafe fn apply_field_merge(db: *mut ffi::sqlite3, merge: &FieldMerge) {
+    let assignments: Vec<String> = merge
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| format!("\"{name}\" = ?{}", i + 1))
+        .collect();
+    let conditions: Vec<String> = merge
+        .key
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| format!("\"{name}\" = ?{}", merge.columns.len() + i + 1))
+        .collect();
+    let sql = format!(
+        "UPDATE \"{}\" SET {} WHERE {}",
+        merge.table,
+        assignments.join(", "),
+        conditions.join(" AND ")
+    );
+    let c_sql = CString::new(sql).unwrap();
+    let mut stmt: *mut ffi::sqlite3_stmt = ptr::null_mut();
+    let rc = ffi::sqlite3_prepare_v2(db, c_sql.as_ptr(), -1, &mut stmt, ptr::null_mut());
+    assert_eq!(
+        rc,
+        ffi::SQLITE_OK as c_int,
+        "prepare apply_field_merge failed"
+    );
+
+    let values = merge.columns.iter().chain(&merge.key).map(|(_, v)| v);
+    for (i, value) in values.enumerate() {
+        bind_column_value(stmt, i as c_int + 1, value);
+    }
+
+    let step = ffi::sqlite3_step(stmt);
+    assert_eq!(
+        step,
+        ffi::SQLITE_DONE as c_int,
+        "apply_field_merge step failed"
+    );
+
+    ffi::sqlite3_finalize(stmt);
+}
+
+unsafe fn bind_column_value(stmt: *mut ffi::sqlite3_stmt, index: c_int, value: &ColumnValue) {
+    match value {
+        ColumnValue::Null => {
+            ffi::sqlite3_bind_null(stmt, index);
+        }
+        ColumnValue::Integer(n) => {
+            ffi::sqlite3_bind_int64(stmt, index, *n);
+        }
+        ColumnValue::Real(f) => {
+            ffi::sqlite3_bind_double(stmt, index, *f);
+        }
+        ColumnValue::Text(text) => {
+            ffi::sqlite3_bind_text(
+                stmt,
+                index,
+                text.as_ptr() as *const c_char,
+                text.len() as c_int,
+                ffi::SQLITE_TRANSIENT(),
+            );
+        }
+        ColumnValue::Blob(bytes) => {
+            ffi::sqlite3_bind_blob(
+                stmt,
+                index,
+                bytes.as_ptr() as *const _,
+                bytes.len() as c_int,
+                ffi::SQLITE_TRANSIENT(),
+            );
+        }
+    }
+}
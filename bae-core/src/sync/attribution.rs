@@ -101,6 +101,8 @@ mod tests {
             timestamp: timestamp.to_string(),
             author_pubkey: pk_hex,
             signature: String::new(),
+            device_id: None,
+            device_pubkey: None,
         };
         sign_membership_entry(&mut entry, kp);
         entry
@@ -120,6 +122,8 @@ mod tests {
             timestamp: timestamp.to_string(),
             author_pubkey: pubkey_hex(author),
             signature: String::new(),
+            device_id: None,
+            device_pubkey: None,
         };
         sign_membership_entry(&mut entry, author);
         entry
@@ -13,6 +13,7 @@
 ///
 /// All data is encrypted before upload and decrypted after download.
 /// The trait is async and mockable for testing.
+use crate::error_code::{ErrorCode, HasErrorCode};
 use async_trait::async_trait;
 
 /// Per-device head: the latest sequence number for a device.
@@ -40,6 +41,15 @@ pub enum BucketError {
     Decryption(String),
 }
 
+impl HasErrorCode for BucketError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            BucketError::S3(_) | BucketError::NotFound(_) => ErrorCode::SyncBucketUnavailable,
+            BucketError::Decryption(_) => ErrorCode::DecryptionFailed,
+        }
+    }
+}
+
 impl From<crate::cloud_home::CloudHomeError> for BucketError {
     fn from(e: crate::cloud_home::CloudHomeError) -> Self {
         match e {
@@ -60,11 +70,12 @@ pub trait SyncBucketClient: Send + Sync {
     /// Fetch a single changeset by device_id and seq.
     ///
     /// Returns the **decrypted** envelope bytes from `changes/{device_id}/{seq}.enc`.
-    /// Implementations must handle downloading the encrypted blob and decrypting
-    /// it before returning. Callers receive plaintext ready for `envelope::unpack()`.
+    /// Implementations must handle downloading the encrypted blob, decrypting
+    /// and decompressing it before returning. Callers receive plaintext ready for `envelope::unpack()`.
     async fn get_changeset(&self, device_id: &str, seq: u64) -> Result<Vec<u8>, BucketError>;
 
-    /// Upload a changeset blob (plaintext — the implementation encrypts it).
+    /// Upload a changeset blob (plaintext — the implementation compresses and
+    /// encrypts it).
     /// Writes to `changes/{device_id}/{seq}.enc`.
     async fn put_changeset(
         &self,
@@ -109,7 +120,7 @@ pub trait SyncBucketClient: Send + Sync {
         release_id: Option<&str>,
     ) -> Result<Vec<u8>, BucketError>;
 
-    /// Upload an encrypted snapshot.
+    /// Upload an encrypted snapshot (compressed by `create_snapshot`).
     /// Writes to `snapshot.db.enc` (overwrites any previous snapshot).
     async fn put_snapshot(&self, data: Vec<u8>) -> Result<(), BucketError>;
 
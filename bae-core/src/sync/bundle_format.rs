@@ -0,0 +1,114 @@
+use crate::encryption::ContainerFormat;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Most files one bundle request may ask for.
+pub const MAX_BUNDLE_FILES: usize = 500;
+
+/// Most bytes one bundle may carry. Larger releases are fetched in several bundles.
+pub const MAX_BUNDLE_BYTES: u64 = 1 << 30;
+
+/// Body of `POST /bundle`: the storage keys to package, usually every file
+/// of one release. Keys come from the client's copy of the library DB; the
+/// server can't tell which files belong to a release.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleRequest {
+    pub files: Vec<String>,
+}
+
+/// Describes the files packed into a bundle, in order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BundleManifest {
+    pub files: Vec<BundleEntry>,
+}
+
+/// One file in a bundle. `offset` counts from the start of the data section.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BundleEntry {
+    pub key: String,
+    pub offset: u64,
+    pub size: u64,
+    /// Hex SHA-256 of the encrypted bytes
+    pub sha256: String,
+    /// Encrypted chunks in the file, from its container header
+    pub chunk_count: u64,
+}
+
+/// Pack encrypted files into one bundle body:
+/// `[u32 BE manifest length][manifest JSON][file 0][file 1]...`
+pub fn encode_bundle(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut offset = 0u64;
+    let entries = files
+        .iter()
+        .map(|(key, data)| {
+            let entry = BundleEntry {
+                key: key.clone(),
+                offset,
+                size: data.len() as u64,
+                sha256: hex::encode(Sha256::digest(data)),
+                chunk_count: chunk_count(data),
+            };
+            offset += data.len() as u64;
+            entry
+        })
+        .collect();
+    let manifest =
+        serde_json::to_vec(&BundleManifest { files: entries }).expect("bundle manifest serializes");
+
+    let mut body = Vec::with_capacity(4 + manifest.len() + offset as usize);
+    body.extend_from_slice(&(manifest.len() as u32).to_be_bytes());
+    body.extend_from_slice(&manifest);
+    for (_, data) in files {
+        body.extend_from_slice(data);
+    }
+    body
+}
+
+/// Number of encrypted chunks after the container prefix. Zero if the data
+/// is too short to be an encrypted file.
+pub fn chunk_count(data: &[u8]) -> u64 {
+    let Ok(format) = ContainerFormat::detect(data) else {
+        return 0;
+    };
+    let body_len = data.len().saturating_sub(format.prefix_len());
+    body_len.div_ceil(format.encrypted_chunk_size()) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::EncryptionService;
+
+    #[test]
+    fn bundle_layout_matches_manifest() {
+        let enc = EncryptionService::new_with_key(&[7u8; 32]);
+        let first = enc.encrypt(&vec![1u8; 100_000]);
+        let second = enc.encrypt(b"Tidewater");
+        let files = vec![
+            ("storage/ab/cd/file-1".to_string(), first.clone()),
+            ("storage/ab/cd/file-2".to_string(), second.clone()),
+        ];
+
+        let body = encode_bundle(&files);
+
+        let manifest_len = u32::from_be_bytes(body[..4].try_into().unwrap()) as usize;
+        let manifest: BundleManifest = serde_json::from_slice(&body[4..4 + manifest_len]).unwrap();
+        let data = &body[4 + manifest_len..];
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(manifest.files[0].chunk_count, 2);
+        assert_eq!(manifest.files[1].chunk_count, 1);
+        for (entry, (key, bytes)) in manifest.files.iter().zip(&files) {
+            assert_eq!(&entry.key, key);
+            let start = entry.offset as usize;
+            let packed = &data[start..start + entry.size as usize];
+            assert_eq!(packed, bytes.as_slice());
+            assert_eq!(entry.sha256, hex::encode(Sha256::digest(bytes)));
+        }
+    }
+
+    #[test]
+    fn chunk_count_of_short_data_is_zero() {
+        assert_eq!(chunk_count(&[]), 0);
+        assert_eq!(chunk_count(&[0u8; 10]), 0);
+    }
+}
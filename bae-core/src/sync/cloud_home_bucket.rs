@@ -1,15 +1,18 @@
 //! `SyncBucketClient` implementation backed by any `CloudHome`.
 //!
 //! Handles the cloud home path layout (where keys, heads, images, etc. live)
-//! and encryption/decryption. The underlying `CloudHome` only deals in raw
-//! bytes and flat keys.
+//! and encryption/decryption. Changesets are also compressed before they're
+//! encrypted. The underlying `CloudHome` only deals in raw bytes and flat
+//! keys.
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 
 use super::bucket::{BucketError, DeviceHead, SyncBucketClient};
-use crate::cloud_home::CloudHome;
+use super::compression;
+use crate::cloud_home::{CloudHome, CloudHomeError};
 use crate::encryption::EncryptionService;
 
 /// Serialized form of a device head stored in `heads/{device_id}.json.enc`.
@@ -29,11 +32,23 @@ struct MinSchemaVersionJson {
     min_schema_version: u32,
 }
 
+/// Marks an image stored in the cloud home without encryption.
+pub const PLAIN_IMAGE_SUFFIX: &str = ".plain";
+
+/// Image key from ID: `images/{ab}/{cd}/{id}`, with `PLAIN_IMAGE_SUFFIX`
+/// when the image is stored unencrypted.
+pub fn image_key(id: &str, encrypted: bool) -> String {
+    let hex = id.replace('-', "");
+    let suffix = if encrypted { "" } else { PLAIN_IMAGE_SUFFIX };
+    format!("images/{}/{}/{id}{suffix}", &hex[..2], &hex[2..4])
+}
+
 /// `SyncBucketClient` that delegates raw I/O to a `CloudHome` and handles
 /// the path layout and encryption layer.
 pub struct CloudHomeSyncBucket {
     home: Box<dyn CloudHome>,
     encryption: Arc<RwLock<EncryptionService>>,
+    encrypt_images: AtomicBool,
 }
 
 impl CloudHomeSyncBucket {
@@ -41,9 +56,23 @@ impl CloudHomeSyncBucket {
         CloudHomeSyncBucket {
             home,
             encryption: Arc::new(RwLock::new(encryption)),
+            encrypt_images: AtomicBool::new(true),
         }
     }
 
+    /// Whether images this device uploads are encrypted (the default).
+    /// Downloads read either kind, whatever the uploading device chose.
+    pub fn with_image_encryption(self, encrypt_images: bool) -> Self {
+        self.set_image_encryption(encrypt_images);
+        self
+    }
+
+    /// Change the image policy of a running bucket. Images already in the
+    /// cloud home keep theirs until they're uploaded again.
+    pub fn set_image_encryption(&self, encrypt_images: bool) {
+        self.encrypt_images.store(encrypt_images, Ordering::Relaxed);
+    }
+
     /// Return a shared reference to the encryption lock for external use
     /// (e.g., SyncHandle can share the same instance for snapshot creation).
     pub fn shared_encryption(&self) -> Arc<RwLock<EncryptionService>> {
@@ -60,17 +89,17 @@ impl CloudHomeSyncBucket {
         self.encryption.read().unwrap()
     }
 
-    /// Image key from ID: `images/{ab}/{cd}/{id}`.
-    fn image_key(id: &str) -> String {
-        let hex = id.replace('-', "");
-        format!("images/{}/{}/{id}", &hex[..2], &hex[2..4])
+    /// Whether an image has been uploaded to the cloud home, encrypted or not.
+    pub async fn has_image(&self, id: &str) -> Result<bool, BucketError> {
+        Ok(self.home.exists(&image_key(id, true)).await?
+            || self.home.exists(&image_key(id, false)).await?)
     }
 
     /// List all image keys in the cloud home.
     ///
     /// Separate from `SyncBucketClient` because only bae-server needs to
     /// enumerate all images for bulk download. Returns keys like
-    /// `images/ab/cd/{id}`.
+    /// `images/ab/cd/{id}`, or `images/ab/cd/{id}.plain` for unencrypted ones.
     pub async fn list_image_keys(&self) -> Result<Vec<String>, BucketError> {
         self.home.list("images/").await.map_err(BucketError::from)
     }
@@ -112,9 +141,13 @@ impl SyncBucketClient for CloudHomeSyncBucket {
     async fn get_changeset(&self, device_id: &str, seq: u64) -> Result<Vec<u8>, BucketError> {
         let key = format!("changes/{device_id}/{seq}.enc");
         let encrypted = self.home.read(&key).await?;
-        self.enc()
+        let decrypted = self
+            .enc()
             .decrypt(&encrypted)
-            .map_err(|e| BucketError::Decryption(format!("changeset {device_id}/{seq}: {e}")))
+            .map_err(|e| BucketError::Decryption(format!("changeset {device_id}/{seq}: {e}")))?;
+        compression::decompress(decrypted).map_err(|e| {
+            BucketError::Decryption(format!("decompress changeset {device_id}/{seq}: {e}"))
+        })
     }
 
     async fn put_changeset(
@@ -124,7 +157,9 @@ impl SyncBucketClient for CloudHomeSyncBucket {
         data: Vec<u8>,
     ) -> Result<(), BucketError> {
         let key = format!("changes/{device_id}/{seq}.enc");
-        let encrypted = self.enc().encrypt(&data);
+        let compressed = compression::compress(&data)
+            .map_err(|e| BucketError::S3(format!("compress changeset: {e}")))?;
+        let encrypted = self.enc().encrypt(&compressed);
         self.home.write(&key, encrypted).await?;
         Ok(())
     }
@@ -155,13 +190,21 @@ impl SyncBucketClient for CloudHomeSyncBucket {
         release_id: Option<&str>,
         data: Vec<u8>,
     ) -> Result<(), BucketError> {
-        let key = Self::image_key(id);
+        if !self.encrypt_images.load(Ordering::Relaxed) {
+            self.home.write(&image_key(id, false), data).await?;
+            // An encrypted copy from before the policy changed would be
+            // read first
+            self.home.delete(&image_key(id, true)).await?;
+            return Ok(());
+        }
+
         let enc = match release_id {
             Some(rid) => self.enc().derive_release_encryption(rid),
             None => self.enc().clone(),
         };
         let encrypted = enc.encrypt(&data);
-        self.home.write(&key, encrypted).await?;
+        self.home.write(&image_key(id, true), encrypted).await?;
+        self.home.delete(&image_key(id, false)).await?;
         Ok(())
     }
 
@@ -170,8 +213,14 @@ impl SyncBucketClient for CloudHomeSyncBucket {
         id: &str,
         release_id: Option<&str>,
     ) -> Result<Vec<u8>, BucketError> {
-        let key = Self::image_key(id);
-        let encrypted = self.home.read(&key).await?;
+        let encrypted = match self.home.read(&image_key(id, true)).await {
+            Ok(encrypted) => encrypted,
+            // Uploaded by a device with image encryption turned off
+            Err(CloudHomeError::NotFound(_)) => {
+                return Ok(self.home.read(&image_key(id, false)).await?);
+            }
+            Err(e) => return Err(e.into()),
+        };
         let enc = match release_id {
             Some(rid) => self.enc().derive_release_encryption(rid),
             None => self.enc().clone(),
@@ -330,3 +379,51 @@ impl SyncBucketClient for CloudHomeSyncBucket {
             .map_err(|e| BucketError::Decryption(format!("snapshot_meta: {e}")))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloud_home::folder::FolderCloudHome;
+    use tempfile::TempDir;
+
+    fn bucket(root: &std::path::Path, encrypt_images: bool) -> CloudHomeSyncBucket {
+        let home = FolderCloudHome::new(root.to_path_buf());
+        let encryption = EncryptionService::new_with_key(&[7u8; 32]);
+        CloudHomeSyncBucket::new(Box::new(home), encryption).with_image_encryption(encrypt_images)
+    }
+
+    #[tokio::test]
+    async fn images_follow_the_uploaders_policy() {
+        let tmp = TempDir::new().unwrap();
+        let encrypting = bucket(tmp.path(), true);
+        let plain = bucket(tmp.path(), false);
+        let id = "ab12cd34-0000-0000-0000-000000000000";
+
+        plain
+            .upload_image(id, Some("rel-1"), b"cover".to_vec())
+            .await
+            .unwrap();
+        let stored = tmp.path().join(image_key(id, false));
+        assert_eq!(std::fs::read(&stored).unwrap(), b"cover");
+        assert_eq!(
+            encrypting.download_image(id, Some("rel-1")).await.unwrap(),
+            b"cover"
+        );
+
+        // Encrypting it again replaces the plaintext copy
+        encrypting
+            .upload_image(id, Some("rel-1"), b"cover".to_vec())
+            .await
+            .unwrap();
+        assert!(!stored.exists());
+        assert_ne!(
+            std::fs::read(tmp.path().join(image_key(id, true))).unwrap(),
+            b"cover"
+        );
+        assert_eq!(
+            plain.download_image(id, Some("rel-1")).await.unwrap(),
+            b"cover"
+        );
+        assert!(plain.has_image(id).await.unwrap());
+    }
+}
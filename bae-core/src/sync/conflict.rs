@@ -1,7 +1,12 @@
 /// Production conflict handler for changeset application.
 ///
-/// Uses row-level Last-Writer-Wins (LWW) based on the `_updated_at` column,
-/// which contains HLC timestamps that sort lexicographically = causally.
+/// Uses Last-Writer-Wins (LWW) based on the `_updated_at` column, which
+/// contains HLC timestamps that sort lexicographically = causally, merged
+/// field by field. An incoming UPDATE carries only the columns it changed,
+/// along with their values before the change. A column the local row still
+/// has at that old value was only edited remotely and takes the incoming
+/// value whichever side is newer; only columns both sides edited are decided
+/// by `_updated_at`.
 ///
 /// The `_updated_at` column index is looked up dynamically from the schema
 /// (via `TableSchema`) so adding columns to the end of a table is safe.
@@ -9,19 +14,40 @@
 /// For `release_files`, the `encryption_nonce` column is device-specific.
 /// When incoming wins a DATA conflict on that table, the row ID is recorded
 /// so the caller can restore the local value afterward.
+///
+/// DATA conflicts on tables the user edits by hand (see `SURFACED_TABLES`) are
+/// also recorded column by column, so the UI can offer to undo the choice
+/// LWW made.
 use std::collections::HashMap;
 use std::ffi::{c_char, c_int, CStr, CString};
 use std::ptr;
 
+use chrono::Utc;
 use libsqlite3_sys as ffi;
 use tracing::warn;
+use uuid::Uuid;
+
+use super::session_ext::{ColumnValue, ConflictAction, ConflictContext, ConflictType};
+use crate::db::{DbSyncConflict, DbSyncConflictField};
+
+/// Tables whose DATA conflicts are recorded for the user to review.
+pub const SURFACED_TABLES: &[&str] = &["albums"];
 
-use super::session_ext::{ConflictAction, ConflictContext, ConflictType};
+/// Columns that hold a different value on every device and never sync over
+/// a local value.
+pub const DEVICE_LOCAL_COLUMNS: &[(&str, &str)] = &[("release_files", "encryption_nonce")];
+
+/// Bookkeeping columns left out of recorded conflicts.
+const UNSURFACED_COLUMNS: &[&str] = &["id", "_updated_at", "created_at"];
 
 /// Column indices for a synced table, looked up from `PRAGMA table_info`.
 pub struct TableColumns {
     /// Index of the `_updated_at` column.
     pub updated_at: usize,
+    /// Column names in table order.
+    pub names: Vec<String>,
+    /// Indices of the primary key columns, in key order.
+    pub primary_key: Vec<usize>,
 }
 
 /// Schema info for all synced tables: maps table name to column indices.
@@ -49,6 +75,8 @@ impl TableSchema {
             );
 
             let mut updated_at = None;
+            let mut names = Vec::new();
+            let mut primary_key = Vec::new();
 
             while ffi::sqlite3_step(stmt) == ffi::SQLITE_ROW as c_int {
                 let col_index = ffi::sqlite3_column_int(stmt, 0) as usize;
@@ -63,6 +91,11 @@ impl TableSchema {
                 if name == "_updated_at" {
                     updated_at = Some(col_index);
                 }
+                let pk_position = ffi::sqlite3_column_int(stmt, 5);
+                if pk_position > 0 {
+                    primary_key.push((pk_position, col_index));
+                }
+                names.push(name.to_string());
             }
 
             ffi::sqlite3_finalize(stmt);
@@ -71,7 +104,17 @@ impl TableSchema {
                 panic!("synced table {table} has no _updated_at column");
             });
 
-            tables.insert(table.to_string(), TableColumns { updated_at });
+            primary_key.sort();
+            let primary_key = primary_key.into_iter().map(|(_, i)| i).collect();
+
+            tables.insert(
+                table.to_string(),
+                TableColumns {
+                    updated_at,
+                    names,
+                    primary_key,
+                },
+            );
         }
 
         TableSchema { tables }
@@ -94,6 +137,20 @@ pub struct ConflictTracker {
     /// Row IDs in `release_files` where incoming won a DATA conflict and
     /// device-specific columns need to be restored afterward.
     pub release_file_restore_ids: Vec<String>,
+    /// DATA conflicts on `SURFACED_TABLES`, whichever side won.
+    pub conflicts: Vec<DbSyncConflict>,
+    /// Remote-only column edits on rows where local won a DATA conflict.
+    /// The handler omits the change, so the caller writes these afterward.
+    pub merges: Vec<FieldMerge>,
+}
+
+/// Incoming column values to write onto a row whose newer local edit won.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldMerge {
+    pub table: String,
+    /// Primary key columns and their values.
+    pub key: Vec<(String, ColumnValue)>,
+    pub columns: Vec<(String, ColumnValue)>,
 }
 
 impl ConflictTracker {
@@ -105,9 +162,12 @@ impl ConflictTracker {
 /// The production conflict handler for `apply_changeset_with_context`.
 ///
 /// Rules:
-/// - **DATA** (same row, both sides edited): compare `_updated_at`. Newer wins.
-///   For `release_files`, records the row ID so device-specific columns can
-///   be restored by the caller.
+/// - **DATA** (same row, both sides edited): compare `_updated_at`. If
+///   incoming is newer, REPLACE, which writes only the columns it carries.
+///   Otherwise OMIT, and record the columns only the remote side edited as a
+///   `FieldMerge` for the caller to write. For `release_files`, records the
+///   row ID so device-specific columns can be restored by the caller. For
+///   `SURFACED_TABLES`, records both sides of each column both edited.
 /// - **NOTFOUND** (row deleted locally, incoming UPDATE): OMIT (delete wins).
 /// - **CONFLICT** (row exists, incoming INSERT): compare `_updated_at`. Newer wins.
 /// - **CONSTRAINT** (FK violation): OMIT and track for retry.
@@ -125,6 +185,16 @@ pub fn lww_conflict_handler(
 
             let incoming = ctx.new_value(cols.updated_at);
             let local = ctx.conflict_value(cols.updated_at);
+            let incoming_wins = matches!(
+                (incoming.as_deref(), local.as_deref()),
+                (Some(inc), Some(loc)) if inc > loc
+            );
+
+            if SURFACED_TABLES.contains(&table) {
+                if let Some(conflict) = record_conflict(ctx, table, cols, incoming_wins) {
+                    tracker.conflicts.push(conflict);
+                }
+            }
 
             match (incoming.as_deref(), local.as_deref()) {
                 (Some(inc), Some(loc)) if inc > loc => {
@@ -138,7 +208,12 @@ pub fn lww_conflict_handler(
 
                     ConflictAction::Replace
                 }
-                (Some(_), Some(_)) => ConflictAction::Omit,
+                (Some(_), Some(_)) => {
+                    if let Some(merge) = remote_only_edits(ctx, table, cols) {
+                        tracker.merges.push(merge);
+                    }
+                    ConflictAction::Omit
+                }
                 _ => {
                     warn!(
                         table,
@@ -185,3 +260,87 @@ pub fn lww_conflict_handler(
         }
     }
 }
+
+/// True if the local row still holds the value the incoming UPDATE changed
+/// column `i` from, so only the remote side edited it.
+fn only_remote_edited(ctx: &ConflictContext, i: usize) -> bool {
+    match (ctx.old_column_value(i), ctx.conflict_column_value(i)) {
+        (Some(old), Some(local)) => old == local,
+        _ => false,
+    }
+}
+
+/// Incoming values for the columns only the remote side edited, or None if
+/// there are none.
+fn remote_only_edits(
+    ctx: &ConflictContext,
+    table: &str,
+    cols: &TableColumns,
+) -> Option<FieldMerge> {
+    let columns: Vec<(String, ColumnValue)> = cols
+        .names
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != cols.updated_at && !cols.primary_key.contains(i))
+        .filter(|(_, name)| !DEVICE_LOCAL_COLUMNS.contains(&(table, name.as_str())))
+        .filter(|(i, _)| only_remote_edited(ctx, *i))
+        .filter_map(|(i, name)| Some((name.clone(), ctx.new_column_value(i)?)))
+        .collect();
+    if columns.is_empty() {
+        return None;
+    }
+
+    let key = cols
+        .primary_key
+        .iter()
+        .map(|&i| Some((cols.names[i].clone(), ctx.conflict_column_value(i)?)))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(FieldMerge {
+        table: table.to_string(),
+        key,
+        columns,
+    })
+}
+
+/// Both sides of each column both edits changed, or None when they only
+/// overlap in bookkeeping columns.
+///
+/// Columns the incoming UPDATE didn't touch have no new value and are
+/// skipped, as are columns only the remote side edited: those merge cleanly.
+fn record_conflict(
+    ctx: &ConflictContext,
+    table: &str,
+    cols: &TableColumns,
+    incoming_wins: bool,
+) -> Option<DbSyncConflict> {
+    let row_id = ctx.conflict_value(0)?;
+    let fields: Vec<DbSyncConflictField> = cols
+        .names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| !UNSURFACED_COLUMNS.contains(&name.as_str()))
+        .filter(|(i, _)| !only_remote_edited(ctx, *i))
+        .filter_map(|(i, name)| {
+            let remote = ctx.new_value(i)?;
+            let local = ctx.conflict_value(i);
+            (local.as_deref() != Some(remote.as_str())).then(|| DbSyncConflictField {
+                column: name.clone(),
+                local,
+                remote: Some(remote),
+            })
+        })
+        .collect();
+    if fields.is_empty() {
+        return None;
+    }
+
+    Some(DbSyncConflict {
+        id: Uuid::new_v4().to_string(),
+        table_name: table.to_string(),
+        row_id,
+        fields,
+        kept_remote: incoming_wins,
+        detected_at: Utc::now(),
+    })
+}
@@ -4,6 +4,12 @@
 ///
 /// The envelope carries enough context to understand the changeset without
 /// unpacking the binary portion (schema version, author, description).
+///
+/// Changesets are signed with the pushing device's own key rather than the
+/// author's identity key. The envelope carries a certificate, signed by the
+/// identity, binding that device key to the device ID. Libraries with a
+/// membership chain also enroll each device key there, so a single device
+/// can be revoked without touching the rest.
 use serde::{Deserialize, Serialize};
 
 use crate::keys::{self, UserKeypair};
@@ -21,17 +27,48 @@ pub struct ChangesetEnvelope {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub author_pubkey: Option<String>,
     /// Hex-encoded detached Ed25519 signature over the changeset bytes. None for unsigned.
+    /// Made with the device key when `device_pubkey` is set, otherwise
+    /// with the author's key.
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub signature: Option<String>,
+    /// Hex-encoded Ed25519 public key of the device key. None for
+    /// changesets signed directly by the author's key.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub device_pubkey: Option<String>,
+    /// Hex-encoded signature by the author's key over `device_certificate_bytes`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub device_certificate: Option<String>,
 }
 
-/// Sign a changeset envelope with the user's Ed25519 keypair.
+/// What an author signs to vouch for a device key.
+pub fn device_certificate_bytes(device_id: &str, device_pubkey: &[u8]) -> Vec<u8> {
+    let mut bytes = b"bae device certificate\0".to_vec();
+    bytes.extend_from_slice(device_id.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(device_pubkey);
+    bytes
+}
+
+/// Sign a changeset envelope with the key of the device `env.device_id`.
 ///
-/// Sets `author_pubkey` to the hex-encoded public key and `signature` to the
-/// hex-encoded detached signature over the raw changeset bytes.
-pub fn sign_envelope(env: &mut ChangesetEnvelope, keypair: &UserKeypair, changeset_bytes: &[u8]) {
-    let sig = keypair.sign(changeset_bytes);
+/// Sets `author_pubkey` to the user's public key, `device_pubkey` and
+/// `device_certificate` to the device key and the user's signature over it,
+/// and `signature` to the device key's detached signature over the raw
+/// changeset bytes.
+pub fn sign_envelope(
+    env: &mut ChangesetEnvelope,
+    keypair: &UserKeypair,
+    device_keypair: &UserKeypair,
+    changeset_bytes: &[u8],
+) {
+    let certificate = keypair.sign(&device_certificate_bytes(
+        &env.device_id,
+        &device_keypair.public_key,
+    ));
+    let sig = device_keypair.sign(changeset_bytes);
     env.author_pubkey = Some(hex::encode(keypair.public_key));
+    env.device_pubkey = Some(hex::encode(device_keypair.public_key));
+    env.device_certificate = Some(hex::encode(certificate));
     env.signature = Some(hex::encode(sig));
 }
 
@@ -40,9 +77,14 @@ pub fn sign_envelope(env: &mut ChangesetEnvelope, keypair: &UserKeypair, changes
 /// Returns true if:
 /// - No signature is present (unsigned changesets are accepted for now).
 /// - A valid signature is present that matches the author's public key.
+/// - A valid signature is present that matches the device key, and the
+///   author certified that key for this envelope's device ID.
+///
+/// Whether the device key is enrolled and unrevoked is up to the membership
+/// chain, checked on pull.
 ///
 /// Returns false if a signature is present but invalid (wrong key, tampered data,
-/// or malformed hex).
+/// a device key certified for another device, or malformed hex).
 pub fn verify_changeset_signature(env: &ChangesetEnvelope, changeset_bytes: &[u8]) -> bool {
     match (&env.author_pubkey, &env.signature) {
         (None, None) => return true, // Unsigned envelope -- OK for now.
@@ -53,21 +95,32 @@ pub fn verify_changeset_signature(env: &ChangesetEnvelope, changeset_bytes: &[u8
         unreachable!()
     };
 
-    let Ok(pk_bytes) = hex::decode(pk_hex) else {
-        return false;
-    };
-    let Ok(sig_bytes) = hex::decode(sig_hex) else {
+    let (Some(author_pk), Some(sig)) = (decode_pubkey(pk_hex), decode_signature(sig_hex)) else {
         return false;
     };
 
-    let Ok(pk): Result<[u8; sodium_ffi::SIGN_PUBLICKEYBYTES], _> = pk_bytes.try_into() else {
-        return false;
-    };
-    let Ok(sig): Result<[u8; sodium_ffi::SIGN_BYTES], _> = sig_bytes.try_into() else {
-        return false;
-    };
+    match (&env.device_pubkey, &env.device_certificate) {
+        (None, None) => keys::verify_signature(&sig, changeset_bytes, &author_pk),
+        (Some(device_hex), Some(cert_hex)) => {
+            let (Some(device_pk), Some(cert)) =
+                (decode_pubkey(device_hex), decode_signature(cert_hex))
+            else {
+                return false;
+            };
+            let certificate = device_certificate_bytes(&env.device_id, &device_pk);
+            keys::verify_signature(&cert, &certificate, &author_pk)
+                && keys::verify_signature(&sig, changeset_bytes, &device_pk)
+        }
+        _ => false,
+    }
+}
+
+fn decode_pubkey(pk_hex: &str) -> Option<[u8; sodium_ffi::SIGN_PUBLICKEYBYTES]> {
+    hex::decode(pk_hex).ok()?.try_into().ok()
+}
 
-    keys::verify_signature(&sig, changeset_bytes, &pk)
+fn decode_signature(sig_hex: &str) -> Option<[u8; sodium_ffi::SIGN_BYTES]> {
+    hex::decode(sig_hex).ok()?.try_into().ok()
 }
 
 /// Pack an envelope and changeset into the wire format.
@@ -113,6 +166,8 @@ mod tests {
             changeset_size: 4096,
             author_pubkey: None,
             signature: None,
+            device_pubkey: None,
+            device_certificate: None,
         }
     }
 
@@ -206,12 +261,13 @@ mod tests {
 
         let ks = KeyService::new(true, "test-signing".to_string());
         let keypair = ks.get_or_create_user_keypair().unwrap();
+        let device_keypair = UserKeypair::generate();
 
         let changeset_bytes = b"some changeset payload";
 
         // sign_envelope produces a valid signature.
         let mut env = test_envelope();
-        sign_envelope(&mut env, &keypair, changeset_bytes);
+        sign_envelope(&mut env, &keypair, &device_keypair, changeset_bytes);
 
         assert!(env.author_pubkey.is_some());
         assert!(env.signature.is_some());
@@ -245,6 +301,31 @@ mod tests {
         bad_pk_env.author_pubkey = Some(hex::encode([0u8; 16])); // 16 bytes, not 32
         assert!(!verify_changeset_signature(&bad_pk_env, changeset_bytes));
 
+        // The changeset is signed by the device key, not the identity.
+        assert_eq!(
+            env.device_pubkey.as_deref(),
+            Some(hex::encode(device_keypair.public_key).as_str())
+        );
+
+        // A device key certified for one device can't sign for another.
+        let mut moved_env = env.clone();
+        moved_env.device_id = "dev-other".into();
+        assert!(!verify_changeset_signature(&moved_env, changeset_bytes));
+
+        // A certificate without its device key fails.
+        let mut uncertified_env = env.clone();
+        uncertified_env.device_pubkey = None;
+        assert!(!verify_changeset_signature(
+            &uncertified_env,
+            changeset_bytes
+        ));
+
+        // Envelopes signed by the author's key directly still verify.
+        let mut legacy_env = test_envelope();
+        legacy_env.author_pubkey = Some(hex::encode(keypair.public_key));
+        legacy_env.signature = Some(hex::encode(keypair.sign(changeset_bytes)));
+        assert!(verify_changeset_signature(&legacy_env, changeset_bytes));
+
         // Clean up
         std::env::remove_var("BAE_USER_SIGNING_KEY");
         std::env::remove_var("BAE_USER_PUBLIC_KEY");
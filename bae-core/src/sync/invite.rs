@@ -3,12 +3,17 @@
 /// `create_invitation()` is called by the library owner to invite a new member.
 /// `accept_invitation()` is called by the invitee to unwrap the library key.
 /// `revoke_member()` is called by the library owner to remove a member and rotate the key.
+/// `pending_devices()` lists the caller's devices waiting to be approved.
+/// `revoke_device()` retires one of the caller's devices, with no key rotation.
+/// `load_membership_chain()` is called before each sync so pulls can check authors.
+/// `verify_device()` records that a linked device's short authentication string matched.
 use crate::cloud_home::{CloudHome, CloudHomeError, JoinInfo};
 use crate::encryption;
 use crate::keys::{self, KeyError, UserKeypair};
 use crate::sodium_ffi;
 
-use super::bucket::{BucketError, SyncBucketClient};
+use super::bucket::{BucketError, DeviceHead, SyncBucketClient};
+use super::envelope::{self, verify_changeset_signature};
 use super::membership::{
     sign_membership_entry, MemberRole, MembershipAction, MembershipChain, MembershipEntry,
     MembershipError,
@@ -74,6 +79,27 @@ async fn upload_membership_entry(
     Ok(())
 }
 
+/// Download and validate the membership chain. None if the library has no
+/// chain yet.
+pub async fn load_membership_chain(
+    bucket: &dyn SyncBucketClient,
+) -> Result<Option<MembershipChain>, InviteError> {
+    let entry_keys = bucket.list_membership_entries().await?;
+    if entry_keys.is_empty() {
+        return Ok(None);
+    }
+
+    let mut entries = Vec::with_capacity(entry_keys.len());
+    for (author, seq) in &entry_keys {
+        let data = bucket.get_membership_entry(author, *seq).await?;
+        let entry: MembershipEntry = serde_json::from_slice(&data)
+            .map_err(|e| InviteError::Crypto(format!("parse membership entry: {e}")))?;
+        entries.push(entry);
+    }
+
+    Ok(Some(MembershipChain::from_entries(entries)?))
+}
+
 /// Create an invitation for a new member.
 ///
 /// This grants access on the cloud home, wraps the library encryption key
@@ -107,6 +133,8 @@ pub async fn create_invitation(
         timestamp: timestamp.to_string(),
         author_pubkey: String::new(),
         signature: String::new(),
+        device_id: None,
+        device_pubkey: None,
     };
     sign_membership_entry(&mut entry, owner_keypair);
 
@@ -194,6 +222,8 @@ pub async fn revoke_member(
         timestamp: timestamp.to_string(),
         author_pubkey: String::new(),
         signature: String::new(),
+        device_id: None,
+        device_pubkey: None,
     };
     sign_membership_entry(&mut entry, owner_keypair);
 
@@ -221,6 +251,207 @@ pub async fn revoke_member(
     Ok(new_key)
 }
 
+/// Enroll `device_pubkey` as the signing key of the member's device
+/// `device_id`.
+///
+/// `author` signs the AddDevice entry: the member's identity for their first
+/// device, one of their enrolled devices after that.
+pub async fn enroll_device(
+    bucket: &dyn SyncBucketClient,
+    chain: &mut MembershipChain,
+    user_pubkey: &str,
+    author: &UserKeypair,
+    device_id: &str,
+    device_pubkey: &str,
+    timestamp: &str,
+) -> Result<(), InviteError> {
+    let mut entry = MembershipEntry {
+        action: MembershipAction::AddDevice,
+        user_pubkey: user_pubkey.to_string(),
+        role: MemberRole::Member, // role field is not meaningful for AddDevice, but required
+        timestamp: timestamp.to_string(),
+        author_pubkey: String::new(),
+        signature: String::new(),
+        device_id: Some(device_id.to_string()),
+        device_pubkey: Some(device_pubkey.to_string()),
+    };
+    sign_membership_entry(&mut entry, author);
+
+    // Validate against the local chain BEFORE any bucket writes.
+    chain.add_entry(entry.clone())?;
+
+    let author_pubkey_hex = hex::encode(author.public_key);
+    upload_membership_entry(bucket, &entry, &author_pubkey_hex).await?;
+
+    Ok(())
+}
+
+/// Enroll this device's key under our identity unless it already is.
+///
+/// Only the member's first device enrolls itself. Later devices are left
+/// alone: they show up in `pending_devices` until an enrolled one approves
+/// them with `enroll_device`.
+pub async fn ensure_device_enrolled(
+    bucket: &dyn SyncBucketClient,
+    chain: &mut MembershipChain,
+    keypair: &UserKeypair,
+    device_id: &str,
+    device_keypair: &UserKeypair,
+    timestamp: &str,
+) -> Result<(), InviteError> {
+    let user_pubkey = hex::encode(keypair.public_key);
+    if chain.device(&user_pubkey, device_id).is_some() || chain.has_devices(&user_pubkey) {
+        return Ok(());
+    }
+
+    enroll_device(
+        bucket,
+        chain,
+        &user_pubkey,
+        keypair,
+        device_id,
+        &hex::encode(device_keypair.public_key),
+        timestamp,
+    )
+    .await
+}
+
+/// One of our devices signing changesets with a key the chain hasn't
+/// enrolled yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingDevice {
+    pub device_id: String,
+    pub device_pubkey: String,
+}
+
+/// Find the member's devices waiting for an enrolled device to approve them.
+///
+/// Reads the latest changeset of each device in `heads` the chain doesn't
+/// know, and keeps those signed with a device key the member's identity
+/// certified for that device ID. A device that hasn't pushed anything yet
+/// doesn't show up until it does.
+pub async fn pending_devices(
+    bucket: &dyn SyncBucketClient,
+    chain: &MembershipChain,
+    user_pubkey: &str,
+    heads: &[DeviceHead],
+    our_device_id: &str,
+) -> Vec<PendingDevice> {
+    let mut pending = Vec::new();
+    for head in heads {
+        if head.device_id == our_device_id
+            || head.seq == 0
+            || chain.device(user_pubkey, &head.device_id).is_some()
+        {
+            continue;
+        }
+
+        let Ok(bytes) = bucket.get_changeset(&head.device_id, head.seq).await else {
+            continue;
+        };
+        let Some((env, changeset_bytes)) = envelope::unpack(&bytes) else {
+            continue;
+        };
+        if env.device_id != head.device_id
+            || env.author_pubkey.as_deref() != Some(user_pubkey)
+            || !verify_changeset_signature(&env, &changeset_bytes)
+        {
+            continue;
+        }
+        if let Some(device_pubkey) = env.device_pubkey {
+            pending.push(PendingDevice {
+                device_id: head.device_id.clone(),
+                device_pubkey,
+            });
+        }
+    }
+    pending
+}
+
+/// Revoke a device, enrolled or not.
+///
+/// An ID the chain doesn't know is taken to be one of ours. Our own devices
+/// are revoked with this device's key, which must be enrolled; another
+/// member's need `keypair` to be an owner's identity. Peers skip every
+/// changeset the revoked device signs. The library key is not rotated and no
+/// other device needs anything new; the revoked device can still read the
+/// library with the key it already has.
+pub async fn revoke_device(
+    bucket: &dyn SyncBucketClient,
+    chain: &mut MembershipChain,
+    keypair: &UserKeypair,
+    device_keypair: &UserKeypair,
+    device_id: &str,
+    timestamp: &str,
+) -> Result<(), InviteError> {
+    let own_pubkey = hex::encode(keypair.public_key);
+    let user_pubkey = chain
+        .devices()
+        .into_iter()
+        .find(|d| d.device_id == device_id)
+        .map(|d| d.user_pubkey)
+        .unwrap_or_else(|| own_pubkey.clone());
+    let author = if user_pubkey == own_pubkey {
+        device_keypair
+    } else {
+        keypair
+    };
+
+    let mut entry = MembershipEntry {
+        action: MembershipAction::RevokeDevice,
+        user_pubkey,
+        role: MemberRole::Member, // role field is not meaningful for RevokeDevice, but required
+        timestamp: timestamp.to_string(),
+        author_pubkey: String::new(),
+        signature: String::new(),
+        device_id: Some(device_id.to_string()),
+        device_pubkey: None,
+    };
+    sign_membership_entry(&mut entry, author);
+
+    // Validate against the local chain BEFORE any bucket writes.
+    chain.add_entry(entry.clone())?;
+
+    let author_pubkey_hex = hex::encode(author.public_key);
+    upload_membership_entry(bucket, &entry, &author_pubkey_hex).await?;
+
+    Ok(())
+}
+
+/// Record that one of the caller's devices was verified.
+///
+/// Appends a VerifyDevice entry naming `device_id`, signed with this device's
+/// key, which must be enrolled, once the member has seen the same short
+/// authentication string on both ends of a device link.
+pub async fn verify_device(
+    bucket: &dyn SyncBucketClient,
+    chain: &mut MembershipChain,
+    keypair: &UserKeypair,
+    device_keypair: &UserKeypair,
+    device_id: &str,
+    timestamp: &str,
+) -> Result<(), InviteError> {
+    let mut entry = MembershipEntry {
+        action: MembershipAction::VerifyDevice,
+        user_pubkey: hex::encode(keypair.public_key),
+        role: MemberRole::Member, // role field is not meaningful for VerifyDevice, but required
+        timestamp: timestamp.to_string(),
+        author_pubkey: String::new(),
+        signature: String::new(),
+        device_id: Some(device_id.to_string()),
+        device_pubkey: None,
+    };
+    sign_membership_entry(&mut entry, device_keypair);
+
+    // Validate against the local chain BEFORE any bucket writes.
+    chain.add_entry(entry.clone())?;
+
+    let author_pubkey_hex = hex::encode(device_keypair.public_key);
+    upload_membership_entry(bucket, &entry, &author_pubkey_hex).await?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,6 +531,8 @@ mod tests {
             timestamp: "0000000001000-0000-dev1".to_string(),
             author_pubkey: pk_hex,
             signature: String::new(),
+            device_id: None,
+            device_pubkey: None,
         };
         sign_membership_entry(&mut entry, owner);
 
@@ -706,4 +939,260 @@ mod tests {
 
         assert!(matches!(result, Err(InviteError::Membership(_))));
     }
+
+    #[tokio::test]
+    async fn load_membership_chain_reads_bucket_entries() {
+        let owner = gen_keypair();
+        let reader = gen_keypair();
+
+        let bucket = MockBucket::new();
+        assert!(load_membership_chain(&bucket).await.unwrap().is_none());
+
+        let mut chain = bootstrap_chain(&owner);
+        let founder = serde_json::to_vec(&chain.entries()[0]).unwrap();
+        bucket
+            .put_membership_entry(&pubkey_hex(&owner), 1, founder)
+            .await
+            .unwrap();
+        create_invitation(
+            &bucket,
+            &MockCloudHome,
+            &mut chain,
+            &owner,
+            &pubkey_hex(&reader),
+            MemberRole::ReadOnly,
+            &[42u8; 32],
+            "0000000002000-0000-dev1",
+        )
+        .await
+        .unwrap();
+
+        let loaded = load_membership_chain(&bucket).await.unwrap().unwrap();
+        assert_eq!(loaded.entries().len(), 2);
+        assert_eq!(
+            loaded.role_at(&pubkey_hex(&reader), "0000000003000-0000-dev1"),
+            Some(MemberRole::ReadOnly)
+        );
+    }
+
+    #[tokio::test]
+    async fn member_revokes_own_device_without_rotating() {
+        let owner = gen_keypair();
+        let member = gen_keypair();
+        let laptop = gen_keypair();
+        let desktop = gen_keypair();
+        let encryption_key: [u8; 32] = [9u8; 32];
+
+        let bucket = MockBucket::new();
+        let mut chain = bootstrap_chain(&owner);
+        let founder = serde_json::to_vec(&chain.entries()[0]).unwrap();
+        bucket
+            .put_membership_entry(&pubkey_hex(&owner), 1, founder)
+            .await
+            .unwrap();
+
+        create_invitation(
+            &bucket,
+            &MockCloudHome,
+            &mut chain,
+            &owner,
+            &pubkey_hex(&member),
+            MemberRole::Member,
+            &encryption_key,
+            "0000000002000-0000-dev1",
+        )
+        .await
+        .unwrap();
+
+        // The laptop enrolls itself, then the desktop.
+        ensure_device_enrolled(
+            &bucket,
+            &mut chain,
+            &member,
+            "dev-laptop",
+            &laptop,
+            "0000000002100-0000-dev1",
+        )
+        .await
+        .unwrap();
+        enroll_device(
+            &bucket,
+            &mut chain,
+            &pubkey_hex(&member),
+            &laptop,
+            "dev-desktop",
+            &pubkey_hex(&desktop),
+            "0000000002200-0000-dev1",
+        )
+        .await
+        .unwrap();
+
+        // The desktop revokes the laptop.
+        revoke_device(
+            &bucket,
+            &mut chain,
+            &member,
+            &desktop,
+            "dev-laptop",
+            "0000000003000-0000-dev1",
+        )
+        .await
+        .unwrap();
+
+        // Peers see the revocation once they reload the chain.
+        let mut loaded = load_membership_chain(&bucket).await.unwrap().unwrap();
+        let member_pk = pubkey_hex(&member);
+        assert!(loaded.device(&member_pk, "dev-laptop").unwrap().revoked);
+        assert!(!loaded.device(&member_pk, "dev-desktop").unwrap().revoked);
+        assert_eq!(loaded.current_members().len(), 2);
+
+        // The laptop still holds the identity, but can't enroll a fresh key
+        // with it.
+        let result = enroll_device(
+            &bucket,
+            &mut loaded,
+            &member_pk,
+            &member,
+            "dev-phone",
+            &pubkey_hex(&gen_keypair()),
+            "0000000004000-0000-dev1",
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(InviteError::Membership(
+                MembershipError::DeviceNotAuthorized(_)
+            ))
+        ));
+
+        // The member's wrapped key is untouched.
+        assert_eq!(
+            accept_invitation(&bucket, &member).await.unwrap(),
+            encryption_key
+        );
+    }
+
+    #[tokio::test]
+    async fn enrolled_device_approves_a_pending_one() {
+        let owner = gen_keypair();
+        let laptop = gen_keypair();
+        let phone = gen_keypair();
+
+        let bucket = MockBucket::new();
+        let mut chain = bootstrap_chain(&owner);
+        let founder = serde_json::to_vec(&chain.entries()[0]).unwrap();
+        bucket
+            .put_membership_entry(&pubkey_hex(&owner), 1, founder)
+            .await
+            .unwrap();
+        let owner_pk = pubkey_hex(&owner);
+
+        ensure_device_enrolled(
+            &bucket,
+            &mut chain,
+            &owner,
+            "dev-laptop",
+            &laptop,
+            "0000000002000-0000-dev1",
+        )
+        .await
+        .unwrap();
+
+        // The phone can't enroll itself once the laptop has, so it syncs
+        // with a key nobody has approved.
+        ensure_device_enrolled(
+            &bucket,
+            &mut chain,
+            &owner,
+            "dev-phone",
+            &phone,
+            "0000000003000-0000-dev2",
+        )
+        .await
+        .unwrap();
+        assert!(chain.device(&owner_pk, "dev-phone").is_none());
+        bucket.store_device_signed_changeset(
+            "dev-phone",
+            1,
+            b"phone edits",
+            1,
+            "0000000003100-0000-dev2",
+            &owner,
+            &phone,
+        );
+
+        let heads = bucket.list_heads().await.unwrap();
+        let pending = pending_devices(&bucket, &chain, &owner_pk, &heads, "dev-laptop").await;
+        assert_eq!(
+            pending,
+            vec![PendingDevice {
+                device_id: "dev-phone".to_string(),
+                device_pubkey: pubkey_hex(&phone),
+            }]
+        );
+
+        // The laptop approves it.
+        enroll_device(
+            &bucket,
+            &mut chain,
+            &owner_pk,
+            &laptop,
+            "dev-phone",
+            &pending[0].device_pubkey,
+            "0000000004000-0000-dev1",
+        )
+        .await
+        .unwrap();
+
+        let loaded = load_membership_chain(&bucket).await.unwrap().unwrap();
+        assert_eq!(
+            loaded.device(&owner_pk, "dev-phone").unwrap().device_pubkey,
+            Some(pubkey_hex(&phone))
+        );
+        assert!(
+            pending_devices(&bucket, &loaded, &owner_pk, &heads, "dev-laptop")
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn verified_device_is_recorded_in_the_chain() {
+        let owner = gen_keypair();
+        let laptop = gen_keypair();
+        let bucket = MockBucket::new();
+        let mut chain = bootstrap_chain(&owner);
+        let founder = serde_json::to_vec(&chain.entries()[0]).unwrap();
+        bucket
+            .put_membership_entry(&pubkey_hex(&owner), 1, founder)
+            .await
+            .unwrap();
+        ensure_device_enrolled(
+            &bucket,
+            &mut chain,
+            &owner,
+            "dev-laptop",
+            &laptop,
+            "0000000002000-0000-dev1",
+        )
+        .await
+        .unwrap();
+
+        verify_device(
+            &bucket,
+            &mut chain,
+            &owner,
+            &laptop,
+            "link-phone",
+            "0000000003000-0000-dev1",
+        )
+        .await
+        .unwrap();
+
+        let loaded = load_membership_chain(&bucket).await.unwrap().unwrap();
+        let owner_pk = pubkey_hex(&owner);
+        assert!(loaded.is_device_verified(&owner_pk, "link-phone"));
+        assert!(loaded.device(&owner_pk, "link-phone").is_none());
+        assert_eq!(loaded.current_members().len(), 1);
+    }
 }
@@ -10,6 +10,18 @@
 ///
 /// Each entry records an Add or Remove action, signed by a current owner.
 /// The first entry must be a self-signed Add with role Owner.
+///
+/// An AddDevice entry enrolls the signing key a device of a member generated
+/// for itself. The member's identity signs their first one; later ones must
+/// be signed by a device already enrolled, so a revoked device can't enroll a
+/// replacement with the identity it holds. A RevokeDevice entry retires one
+/// device ID, enrolled or not, signed by another of the member's devices or
+/// by another owner. Neither touches membership or the library key. Peers
+/// reject everything a revoked device signs, whatever timestamp it claims.
+///
+/// A VerifyDevice entry records that the member compared the short
+/// authentication string of a device link on both devices and it matched.
+/// It is authorized like RevokeDevice and has no effect on sync.
 use serde::{Deserialize, Serialize};
 
 use crate::keys::{self, UserKeypair};
@@ -19,12 +31,27 @@ use crate::sodium_ffi;
 pub enum MembershipAction {
     Add,
     Remove,
+    AddDevice,
+    RevokeDevice,
+    VerifyDevice,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum MemberRole {
+    /// Writes changesets and manages membership.
     Owner,
+    /// Writes changesets.
     Member,
+    /// Pulls changesets and streams audio. Changesets it pushes are rejected
+    /// on pull.
+    ReadOnly,
+}
+
+impl MemberRole {
+    /// Whether changesets authored with this role are applied by peers.
+    pub fn can_write(&self) -> bool {
+        !matches!(self, MemberRole::ReadOnly)
+    }
 }
 
 /// A single membership entry in the chain.
@@ -36,6 +63,22 @@ pub struct MembershipEntry {
     pub timestamp: String,
     pub author_pubkey: String,
     pub signature: String,
+    /// The device an AddDevice, RevokeDevice or VerifyDevice entry is about.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    /// The enrolled device key (hex). Only set on AddDevice entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_pubkey: Option<String>,
+}
+
+/// A device key enrolled in the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnrolledDevice {
+    pub user_pubkey: String,
+    pub device_id: String,
+    /// None for a device revoked before it enrolled.
+    pub device_pubkey: Option<String>,
+    pub revoked: bool,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -48,19 +91,33 @@ pub enum MembershipError {
     NotAnOwner(usize),
     #[error("chain is empty")]
     EmptyChain,
+    #[error("entry at index {0} names no device")]
+    MissingDevice(usize),
+    #[error("entry at index {0} enrolls a device that is already enrolled")]
+    DeviceExists(usize),
+    #[error("entry at index {0}: author may not enroll, revoke or verify this device")]
+    DeviceNotAuthorized(usize),
 }
 
 /// Deterministic serialization of the signed fields (everything except signature).
 pub fn canonical_bytes(entry: &MembershipEntry) -> Vec<u8> {
     // Use serde_json::json! with explicit field ordering for determinism.
     // JSON object keys from json! macro are sorted alphabetically by serde_json.
-    let canonical = serde_json::json!({
+    let mut canonical = serde_json::json!({
         "action": entry.action,
         "author_pubkey": entry.author_pubkey,
         "role": entry.role,
         "timestamp": entry.timestamp,
         "user_pubkey": entry.user_pubkey,
     });
+    // Left out when absent so entries signed before devices had their own
+    // keys still verify.
+    if let Some(ref device_id) = entry.device_id {
+        canonical["device_id"] = serde_json::json!(device_id);
+    }
+    if let Some(ref device_pubkey) = entry.device_pubkey {
+        canonical["device_pubkey"] = serde_json::json!(device_pubkey);
+    }
     serde_json::to_vec(&canonical).expect("canonical serialization cannot fail")
 }
 
@@ -94,6 +151,134 @@ pub fn verify_membership_entry(entry: &MembershipEntry) -> bool {
     keys::verify_signature(&sig, &bytes, &pk)
 }
 
+/// Members and devices as of some point in the chain.
+#[derive(Default)]
+struct ChainState {
+    members: Vec<(String, MemberRole)>,
+    devices: Vec<EnrolledDevice>,
+}
+
+impl ChainState {
+    fn is_owner(&self, pubkey: &str) -> bool {
+        self.members
+            .iter()
+            .any(|(pk, role)| pk == pubkey && *role == MemberRole::Owner)
+    }
+
+    fn device(&self, user_pubkey: &str, device_id: &str) -> Option<&EnrolledDevice> {
+        self.devices
+            .iter()
+            .find(|d| d.user_pubkey == user_pubkey && d.device_id == device_id)
+    }
+
+    /// Whether the member has enrolled a device key, revoked or not.
+    fn has_devices(&self, user_pubkey: &str) -> bool {
+        self.devices
+            .iter()
+            .any(|d| d.user_pubkey == user_pubkey && d.device_pubkey.is_some())
+    }
+
+    /// Whether `pubkey` belongs to one of the member's unrevoked devices.
+    fn is_active_device(&self, user_pubkey: &str, pubkey: &str) -> bool {
+        self.devices.iter().any(|d| {
+            d.user_pubkey == user_pubkey && d.device_pubkey.as_deref() == Some(pubkey) && !d.revoked
+        })
+    }
+
+    /// Check that the entry at `index` may follow this state.
+    fn check(&self, index: usize, entry: &MembershipEntry) -> Result<(), MembershipError> {
+        match entry.action {
+            MembershipAction::Add | MembershipAction::Remove => {
+                if !self.is_owner(&entry.author_pubkey) {
+                    return Err(MembershipError::NotAnOwner(index));
+                }
+            }
+            MembershipAction::AddDevice => {
+                let (Some(device_id), Some(_)) = (&entry.device_id, &entry.device_pubkey) else {
+                    return Err(MembershipError::MissingDevice(index));
+                };
+                let is_member = self.members.iter().any(|(pk, _)| pk == &entry.user_pubkey);
+                let authorized = if self.has_devices(&entry.user_pubkey) {
+                    self.is_active_device(&entry.user_pubkey, &entry.author_pubkey)
+                } else {
+                    entry.author_pubkey == entry.user_pubkey
+                };
+                if !is_member || !authorized {
+                    return Err(MembershipError::DeviceNotAuthorized(index));
+                }
+                if self.device(&entry.user_pubkey, device_id).is_some() {
+                    return Err(MembershipError::DeviceExists(index));
+                }
+            }
+            MembershipAction::RevokeDevice | MembershipAction::VerifyDevice => {
+                if entry.device_id.is_none() {
+                    return Err(MembershipError::MissingDevice(index));
+                }
+                // The member's own identity can't revoke: a device being
+                // revoked holds it too.
+                let is_member = self.members.iter().any(|(pk, _)| pk == &entry.user_pubkey);
+                let by_other_owner =
+                    self.is_owner(&entry.author_pubkey) && entry.author_pubkey != entry.user_pubkey;
+                if !is_member
+                    || !(by_other_owner
+                        || self.is_active_device(&entry.user_pubkey, &entry.author_pubkey))
+                {
+                    return Err(MembershipError::DeviceNotAuthorized(index));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply an entry that passed `check`.
+    fn apply(&mut self, entry: &MembershipEntry) {
+        match entry.action {
+            MembershipAction::Add => {
+                // Remove any existing entry for this pubkey (role change).
+                self.members.retain(|(pk, _)| pk != &entry.user_pubkey);
+                self.members
+                    .push((entry.user_pubkey.clone(), entry.role.clone()));
+            }
+            MembershipAction::Remove => {
+                self.members.retain(|(pk, _)| pk != &entry.user_pubkey);
+                self.devices.retain(|d| d.user_pubkey != entry.user_pubkey);
+            }
+            MembershipAction::AddDevice => {
+                if let (Some(device_id), Some(device_pubkey)) =
+                    (&entry.device_id, &entry.device_pubkey)
+                {
+                    self.devices.push(EnrolledDevice {
+                        user_pubkey: entry.user_pubkey.clone(),
+                        device_id: device_id.clone(),
+                        device_pubkey: Some(device_pubkey.clone()),
+                        revoked: false,
+                    });
+                }
+            }
+            MembershipAction::RevokeDevice => {
+                let Some(ref device_id) = entry.device_id else {
+                    return;
+                };
+                match self
+                    .devices
+                    .iter_mut()
+                    .find(|d| d.user_pubkey == entry.user_pubkey && &d.device_id == device_id)
+                {
+                    Some(device) => device.revoked = true,
+                    // Revoked before it enrolled: the ID stays taken.
+                    None => self.devices.push(EnrolledDevice {
+                        user_pubkey: entry.user_pubkey.clone(),
+                        device_id: device_id.clone(),
+                        device_pubkey: None,
+                        revoked: true,
+                    }),
+                }
+            }
+            MembershipAction::VerifyDevice => {}
+        }
+    }
+}
+
 /// An append-only membership chain.
 ///
 /// Entries are sorted by timestamp (HLC string comparison gives causal order).
@@ -127,7 +312,12 @@ impl MembershipChain {
     /// Rules:
     /// 1. First entry must be Add with role Owner, self-signed.
     /// 2. Every entry must have a valid signature.
-    /// 3. Every entry's author must be a current Owner at that point.
+    /// 3. Every Add or Remove entry's author must be a current Owner at that
+    ///    point.
+    /// 4. AddDevice entries are signed by the member's identity for their
+    ///    first device, and by one of their unrevoked devices after that.
+    /// 5. RevokeDevice and VerifyDevice entries name a device and are signed
+    ///    by another owner or one of the member's unrevoked devices.
     pub fn validate(&self) -> Result<(), MembershipError> {
         if self.entries.is_empty() {
             return Err(MembershipError::EmptyChain);
@@ -145,82 +335,92 @@ impl MembershipChain {
             return Err(MembershipError::InvalidSignature(0));
         }
 
-        // Track active members as we walk the chain.
-        let mut active: Vec<(String, MemberRole)> = vec![];
-        active.push((first.user_pubkey.clone(), first.role.clone()));
+        // Track members and devices as we walk the chain.
+        let mut state = ChainState::default();
+        state.apply(first);
 
         for (i, entry) in self.entries.iter().enumerate().skip(1) {
             if !verify_membership_entry(entry) {
                 return Err(MembershipError::InvalidSignature(i));
             }
-
-            // Author must be an active owner.
-            let is_owner = active
-                .iter()
-                .any(|(pk, role)| pk == &entry.author_pubkey && *role == MemberRole::Owner);
-
-            if !is_owner {
-                return Err(MembershipError::NotAnOwner(i));
-            }
-
-            match entry.action {
-                MembershipAction::Add => {
-                    // Remove any existing entry for this pubkey (role change).
-                    active.retain(|(pk, _)| pk != &entry.user_pubkey);
-                    active.push((entry.user_pubkey.clone(), entry.role.clone()));
-                }
-                MembershipAction::Remove => {
-                    active.retain(|(pk, _)| pk != &entry.user_pubkey);
-                }
-            }
+            state.check(i, entry)?;
+            state.apply(entry);
         }
 
         Ok(())
     }
 
     /// Check if a pubkey was an active member at the given timestamp.
-    ///
-    /// Replays entries up to and including the given timestamp.
     pub fn is_member_at(&self, pubkey: &str, timestamp: &str) -> bool {
-        let mut active: Vec<String> = Vec::new();
+        self.role_at(pubkey, timestamp).is_some()
+    }
+
+    /// Role a pubkey held at the given timestamp, or None if it wasn't a
+    /// member then.
+    ///
+    /// Replays entries up to and including the given timestamp. A later Add
+    /// for the same pubkey replaces its role.
+    pub fn role_at(&self, pubkey: &str, timestamp: &str) -> Option<MemberRole> {
+        let mut role = None;
 
         for entry in &self.entries {
             if entry.timestamp.as_str() > timestamp {
                 break;
             }
-
-            match entry.action {
-                MembershipAction::Add => {
-                    if !active.contains(&entry.user_pubkey) {
-                        active.push(entry.user_pubkey.clone());
-                    }
-                }
-                MembershipAction::Remove => {
-                    active.retain(|pk| pk != &entry.user_pubkey);
-                }
+            if entry.user_pubkey != pubkey {
+                continue;
             }
+
+            role = match entry.action {
+                MembershipAction::Add => Some(entry.role.clone()),
+                MembershipAction::Remove => None,
+                MembershipAction::AddDevice
+                | MembershipAction::RevokeDevice
+                | MembershipAction::VerifyDevice => continue,
+            };
         }
 
-        active.contains(&pubkey.to_string())
+        role
+    }
+
+    /// The device `device_id` of a member, if it was ever enrolled or revoked.
+    pub fn device(&self, user_pubkey: &str, device_id: &str) -> Option<EnrolledDevice> {
+        self.state().device(user_pubkey, device_id).cloned()
+    }
+
+    /// Whether a member has enrolled a device key, revoked or not. Their
+    /// changesets must then be signed by an enrolled device.
+    pub fn has_devices(&self, user_pubkey: &str) -> bool {
+        self.state().has_devices(user_pubkey)
+    }
+
+    /// Whether a member's device was verified with a short authentication
+    /// string.
+    pub fn is_device_verified(&self, user_pubkey: &str, device_id: &str) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.action == MembershipAction::VerifyDevice
+                && entry.user_pubkey == user_pubkey
+                && entry.device_id.as_deref() == Some(device_id)
+        })
+    }
+
+    /// Every device of the current members, revoked ones included.
+    pub fn devices(&self) -> Vec<EnrolledDevice> {
+        self.state().devices
     }
 
     /// Return current active members with their roles.
     pub fn current_members(&self) -> Vec<(String, MemberRole)> {
-        let mut active: Vec<(String, MemberRole)> = Vec::new();
+        self.state().members
+    }
 
+    /// Replay the whole chain, which has already been validated.
+    fn state(&self) -> ChainState {
+        let mut state = ChainState::default();
         for entry in &self.entries {
-            match entry.action {
-                MembershipAction::Add => {
-                    active.retain(|(pk, _)| pk != &entry.user_pubkey);
-                    active.push((entry.user_pubkey.clone(), entry.role.clone()));
-                }
-                MembershipAction::Remove => {
-                    active.retain(|(pk, _)| pk != &entry.user_pubkey);
-                }
-            }
+            state.apply(entry);
         }
-
-        active
+        state
     }
 
     /// Validate and append an entry to the chain.
@@ -246,15 +446,7 @@ impl MembershipChain {
             return Err(MembershipError::InvalidSignature(self.entries.len()));
         }
 
-        // Author must be an active owner.
-        let members = self.current_members();
-        let is_owner = members
-            .iter()
-            .any(|(pk, role)| pk == &entry.author_pubkey && *role == MemberRole::Owner);
-
-        if !is_owner {
-            return Err(MembershipError::NotAnOwner(self.entries.len()));
-        }
+        self.state().check(self.entries.len(), &entry)?;
 
         self.entries.push(entry);
         Ok(())
@@ -294,6 +486,8 @@ mod tests {
             timestamp: timestamp.to_string(),
             author_pubkey: pk_hex,
             signature: String::new(),
+            device_id: None,
+            device_pubkey: None,
         };
         sign_membership_entry(&mut entry, kp);
         entry
@@ -314,6 +508,8 @@ mod tests {
             timestamp: timestamp.to_string(),
             author_pubkey: pubkey_hex(author),
             signature: String::new(),
+            device_id: None,
+            device_pubkey: None,
         };
         sign_membership_entry(&mut entry, author);
         entry
@@ -435,6 +631,45 @@ mod tests {
         assert!(!chain.is_member_at(&pubkey_hex(&member), "0000000004000-0000-dev1"));
     }
 
+    #[test]
+    fn role_at_follows_role_changes() {
+        let owner = gen_keypair();
+        let member = gen_keypair();
+
+        let chain = MembershipChain::from_entries(vec![
+            founder_entry(&owner, "0000000001000-0000-dev1"),
+            make_entry(
+                &owner,
+                MembershipAction::Add,
+                &member,
+                MemberRole::ReadOnly,
+                "0000000002000-0000-dev1",
+            ),
+            make_entry(
+                &owner,
+                MembershipAction::Add,
+                &member,
+                MemberRole::Member,
+                "0000000004000-0000-dev1",
+            ),
+        ])
+        .unwrap();
+
+        let pk = pubkey_hex(&member);
+        assert_eq!(chain.role_at(&pk, "0000000001500-0000-dev1"), None);
+        assert_eq!(
+            chain.role_at(&pk, "0000000003000-0000-dev1"),
+            Some(MemberRole::ReadOnly)
+        );
+        assert_eq!(
+            chain.role_at(&pk, "0000000004000-0000-dev1"),
+            Some(MemberRole::Member)
+        );
+        assert!(chain.is_member_at(&pk, "0000000003000-0000-dev1"));
+        assert!(!MemberRole::ReadOnly.can_write());
+        assert!(MemberRole::Member.can_write());
+    }
+
     #[test]
     fn current_members_returns_active() {
         let owner = gen_keypair();
@@ -547,6 +782,276 @@ mod tests {
         assert!(matches!(result, Err(MembershipError::NotAnOwner(_))));
     }
 
+    /// Create a signed entry where `author` enrolls `device` as one of
+    /// `subject`'s devices.
+    fn add_device_entry(
+        author: &UserKeypair,
+        subject: &UserKeypair,
+        device_id: &str,
+        device: &UserKeypair,
+        timestamp: &str,
+    ) -> MembershipEntry {
+        let mut entry = MembershipEntry {
+            action: MembershipAction::AddDevice,
+            user_pubkey: pubkey_hex(subject),
+            role: MemberRole::Member,
+            timestamp: timestamp.to_string(),
+            author_pubkey: pubkey_hex(author),
+            signature: String::new(),
+            device_id: Some(device_id.to_string()),
+            device_pubkey: Some(pubkey_hex(device)),
+        };
+        sign_membership_entry(&mut entry, author);
+        entry
+    }
+
+    /// Create a signed entry where `author` revokes one of `subject`'s devices.
+    fn revoke_device_entry(
+        author: &UserKeypair,
+        subject: &UserKeypair,
+        device_id: &str,
+        timestamp: &str,
+    ) -> MembershipEntry {
+        let mut entry = MembershipEntry {
+            action: MembershipAction::RevokeDevice,
+            user_pubkey: pubkey_hex(subject),
+            role: MemberRole::Member,
+            timestamp: timestamp.to_string(),
+            author_pubkey: pubkey_hex(author),
+            signature: String::new(),
+            device_id: Some(device_id.to_string()),
+            device_pubkey: None,
+        };
+        sign_membership_entry(&mut entry, author);
+        entry
+    }
+
+    #[test]
+    fn identity_enrolls_only_the_first_device() {
+        let owner = gen_keypair();
+        let laptop = gen_keypair();
+        let desktop = gen_keypair();
+
+        let mut chain = MembershipChain::from_entries(vec![
+            founder_entry(&owner, "0000000001000-0000-dev1"),
+            add_device_entry(&owner, &owner, "laptop", &laptop, "0000000002000-0000-dev1"),
+        ])
+        .unwrap();
+        assert!(chain.has_devices(&pubkey_hex(&owner)));
+
+        // Whoever holds the identity can't enroll more devices with it.
+        let result = chain.add_entry(add_device_entry(
+            &owner,
+            &owner,
+            "desktop",
+            &desktop,
+            "0000000003000-0000-dev1",
+        ));
+        assert!(matches!(
+            result,
+            Err(MembershipError::DeviceNotAuthorized(_))
+        ));
+
+        // An enrolled device can.
+        chain
+            .add_entry(add_device_entry(
+                &laptop,
+                &owner,
+                "desktop",
+                &desktop,
+                "0000000003000-0000-dev1",
+            ))
+            .unwrap();
+        let enrolled = chain.device(&pubkey_hex(&owner), "desktop").unwrap();
+        assert_eq!(enrolled.device_pubkey, Some(pubkey_hex(&desktop)));
+        assert!(!enrolled.revoked);
+
+        // Device ids can't be re-enrolled with another key.
+        let result = chain.add_entry(add_device_entry(
+            &desktop,
+            &owner,
+            "laptop",
+            &gen_keypair(),
+            "0000000004000-0000-dev1",
+        ));
+        assert!(matches!(result, Err(MembershipError::DeviceExists(_))));
+    }
+
+    #[test]
+    fn revoked_device_cannot_enroll_a_replacement() {
+        let owner = gen_keypair();
+        let laptop = gen_keypair();
+        let desktop = gen_keypair();
+        let replacement = gen_keypair();
+
+        let mut chain = MembershipChain::from_entries(vec![
+            founder_entry(&owner, "0000000001000-0000-dev1"),
+            add_device_entry(&owner, &owner, "laptop", &laptop, "0000000002000-0000-dev1"),
+            add_device_entry(
+                &laptop,
+                &owner,
+                "desktop",
+                &desktop,
+                "0000000003000-0000-dev1",
+            ),
+            revoke_device_entry(&desktop, &owner, "laptop", "0000000004000-0000-dev1"),
+        ])
+        .unwrap();
+
+        for author in [&laptop, &owner] {
+            let result = chain.add_entry(add_device_entry(
+                author,
+                &owner,
+                "phone",
+                &replacement,
+                "0000000005000-0000-dev1",
+            ));
+            assert!(matches!(
+                result,
+                Err(MembershipError::DeviceNotAuthorized(_))
+            ));
+
+            // Nor revoke the devices that are left, even with an owner's
+            // identity.
+            let result = chain.add_entry(revoke_device_entry(
+                author,
+                &owner,
+                "desktop",
+                "0000000005000-0000-dev1",
+            ));
+            assert!(matches!(
+                result,
+                Err(MembershipError::DeviceNotAuthorized(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn revoke_device_leaves_membership_alone() {
+        let owner = gen_keypair();
+        let member = gen_keypair();
+        let laptop = gen_keypair();
+        let desktop = gen_keypair();
+
+        let chain = MembershipChain::from_entries(vec![
+            founder_entry(&owner, "0000000001000-0000-dev1"),
+            make_entry(
+                &owner,
+                MembershipAction::Add,
+                &member,
+                MemberRole::Member,
+                "0000000002000-0000-dev1",
+            ),
+            add_device_entry(
+                &member,
+                &member,
+                "laptop",
+                &laptop,
+                "0000000002100-0000-dev1",
+            ),
+            add_device_entry(
+                &laptop,
+                &member,
+                "desktop",
+                &desktop,
+                "0000000002200-0000-dev1",
+            ),
+            revoke_device_entry(&desktop, &member, "laptop", "0000000003000-0000-dev1"),
+        ])
+        .unwrap();
+
+        let member_pk = pubkey_hex(&member);
+        assert!(chain.device(&member_pk, "laptop").unwrap().revoked);
+        assert!(!chain.device(&member_pk, "desktop").unwrap().revoked);
+
+        assert_eq!(
+            chain.role_at(&member_pk, "0000000004000-0000-dev1"),
+            Some(MemberRole::Member)
+        );
+        assert_eq!(chain.current_members().len(), 2);
+    }
+
+    #[test]
+    fn only_owners_revoke_other_members_devices() {
+        let owner = gen_keypair();
+        let m1 = gen_keypair();
+        let m2 = gen_keypair();
+        let m1_laptop = gen_keypair();
+        let m2_laptop = gen_keypair();
+
+        let mut chain = MembershipChain::from_entries(vec![
+            founder_entry(&owner, "0000000001000-0000-dev1"),
+            make_entry(
+                &owner,
+                MembershipAction::Add,
+                &m1,
+                MemberRole::Member,
+                "0000000002000-0000-dev1",
+            ),
+            make_entry(
+                &owner,
+                MembershipAction::Add,
+                &m2,
+                MemberRole::Member,
+                "0000000003000-0000-dev1",
+            ),
+            add_device_entry(&m1, &m1, "laptop", &m1_laptop, "0000000003100-0000-dev1"),
+            add_device_entry(&m2, &m2, "laptop", &m2_laptop, "0000000003200-0000-dev1"),
+        ])
+        .unwrap();
+
+        // Neither another member's device nor the member's own identity can
+        // revoke a device.
+        for author in [&m1_laptop, &m2] {
+            let result = chain.add_entry(revoke_device_entry(
+                author,
+                &m2,
+                "laptop",
+                "0000000004000-0000-dev1",
+            ));
+            assert!(matches!(
+                result,
+                Err(MembershipError::DeviceNotAuthorized(_))
+            ));
+        }
+
+        // A device can be revoked before it ever enrolls; its ID stays
+        // taken.
+        chain
+            .add_entry(revoke_device_entry(
+                &owner,
+                &m2,
+                "phone",
+                "0000000004000-0000-dev1",
+            ))
+            .unwrap();
+        let result = chain.add_entry(add_device_entry(
+            &m2_laptop,
+            &m2,
+            "phone",
+            &gen_keypair(),
+            "0000000004100-0000-dev1",
+        ));
+        assert!(matches!(result, Err(MembershipError::DeviceExists(_))));
+
+        chain
+            .add_entry(revoke_device_entry(
+                &owner,
+                &m2,
+                "laptop",
+                "0000000004000-0000-dev1",
+            ))
+            .unwrap();
+
+        let mut unnamed = revoke_device_entry(&m1_laptop, &m1, "laptop", "0000000005000-0000-dev1");
+        unnamed.device_id = None;
+        sign_membership_entry(&mut unnamed, &m1_laptop);
+        assert!(matches!(
+            chain.add_entry(unnamed),
+            Err(MembershipError::MissingDevice(_))
+        ));
+    }
+
     #[test]
     fn first_entry_not_self_signed_owner_add_fails() {
         let kp1 = gen_keypair();
@@ -579,6 +1084,8 @@ mod tests {
             timestamp: "0000000001000-0000-dev1".to_string(),
             author_pubkey: pk_hex,
             signature: String::new(),
+            device_id: None,
+            device_pubkey: None,
         };
         sign_membership_entry(&mut entry, &kp);
 
@@ -685,6 +1192,8 @@ mod tests {
             timestamp: "0000000001000-0000-dev1".to_string(),
             author_pubkey: pubkey_hex(&kp),
             signature: "does-not-matter".to_string(),
+            device_id: None,
+            device_pubkey: None,
         };
 
         let b1 = canonical_bytes(&entry);
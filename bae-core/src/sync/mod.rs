@@ -3,8 +3,10 @@ pub mod attestation;
 pub mod attestation_cache;
 pub mod attribution;
 pub mod bucket;
+pub mod bundle_format;
 pub mod changeset_scanner;
 pub mod cloud_home_bucket;
+pub mod compression;
 pub mod conflict;
 pub mod envelope;
 #[cfg(feature = "torrent")]
@@ -28,3 +30,4 @@ pub mod status;
 mod test_helpers;
 #[cfg(test)]
 mod tests;
+pub mod verify;
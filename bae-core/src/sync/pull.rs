@@ -21,7 +21,10 @@ use super::envelope::{self, verify_changeset_signature};
 use super::membership::MembershipChain;
 use super::push::SCHEMA_VERSION;
 use super::session_ext::Changeset;
+use crate::db::DbSyncConflict;
+use crate::error_code::{ErrorCode, HasErrorCode};
 use crate::library_dir::LibraryDir;
+use crate::server_metrics::server_metrics;
 
 /// Summary of a pull operation.
 #[derive(Debug)]
@@ -35,6 +38,9 @@ pub struct PullResult {
     /// All device heads fetched during this pull (including our own).
     /// Used by the sync status UI to show other devices' activity.
     pub remote_heads: Vec<DeviceHead>,
+    /// Concurrent edits resolved by LWW that the user may want to revisit.
+    /// The caller persists these.
+    pub conflicts: Vec<DbSyncConflict>,
 }
 
 /// A changeset that had FK violations on first apply and needs retry.
@@ -87,6 +93,7 @@ pub async unsafe fn pull_changes(
         devices_pulled: 0,
         skipped_schema: 0,
         remote_heads: heads.clone(),
+        conflicts: Vec::new(),
     };
     let mut deferred: Vec<DeferredChangeset> = Vec::new();
 
@@ -169,18 +176,84 @@ pub async unsafe fn pull_changes(
             }
 
             // Membership validation: if a chain exists, verify the author
-            // was a member at the time the changeset was created.
+            // was a member allowed to write at the time the changeset was
+            // created.
             if let Some(chain) = membership_chain {
                 if let Some(ref pk) = env.author_pubkey {
-                    if !chain.is_member_at(pk, &env.timestamp) {
-                        warn!(
-                            device_id = %head.device_id,
-                            seq,
-                            author = %pk,
-                            "changeset author not a member at timestamp, skipping"
-                        );
-                        updated_cursors.insert(head.device_id.clone(), seq);
-                        continue;
+                    match chain.role_at(pk, &env.timestamp) {
+                        None => {
+                            warn!(
+                                device_id = %head.device_id,
+                                seq,
+                                author = %pk,
+                                "changeset author not a member at timestamp, skipping"
+                            );
+                            updated_cursors.insert(head.device_id.clone(), seq);
+                            continue;
+                        }
+                        Some(role) if !role.can_write() => {
+                            warn!(
+                                device_id = %head.device_id,
+                                seq,
+                                author = %pk,
+                                "changeset author is a read-only member, skipping"
+                            );
+                            updated_cursors.insert(head.device_id.clone(), seq);
+                            continue;
+                        }
+                        Some(_) => {}
+                    }
+                }
+
+                // Device keys: the key must be the one the chain enrolled for
+                // the certified device ID, and not revoked. Timestamps are
+                // the sender's to pick, so revocation ignores them. Once a
+                // member has enrolled a device, changesets signed with their
+                // identity alone are no longer accepted.
+                if let Some(ref pk) = env.author_pubkey {
+                    match (&env.device_pubkey, chain.device(pk, &env.device_id)) {
+                        (_, Some(device)) if device.revoked => {
+                            warn!(
+                                device_id = %head.device_id,
+                                seq,
+                                "changeset from a revoked device, skipping"
+                            );
+                            updated_cursors.insert(head.device_id.clone(), seq);
+                            continue;
+                        }
+                        (Some(device_pk), Some(device)) => {
+                            if device.device_pubkey.as_ref() != Some(device_pk) {
+                                warn!(
+                                    device_id = %head.device_id,
+                                    seq,
+                                    "changeset signed by a device key the chain doesn't know, skipping"
+                                );
+                                updated_cursors.insert(head.device_id.clone(), seq);
+                                continue;
+                            }
+                        }
+                        (Some(_), None) => {
+                            // Not enrolled yet: the AddDevice entry may still
+                            // be on its way. Retry on the next pull.
+                            warn!(
+                                device_id = %head.device_id,
+                                seq,
+                                "changeset signed by a device not enrolled yet, waiting"
+                            );
+                            break;
+                        }
+                        (None, _) => {
+                            if chain.has_devices(pk) {
+                                warn!(
+                                    device_id = %head.device_id,
+                                    seq,
+                                    author = %pk,
+                                    "changeset signed with the identity key of a member with devices, skipping"
+                                );
+                                updated_cursors.insert(head.device_id.clone(), seq);
+                                continue;
+                            }
+                        }
                     }
                 }
 
@@ -207,6 +280,7 @@ pub async unsafe fn pull_changes(
 
             let cs = Changeset::from_bytes(&changeset_bytes);
             let apply_result = apply_changeset_lww(db, &cs).map_err(PullError::Apply)?;
+            result.conflicts.extend(apply_result.conflicts);
 
             // Download any images referenced by this changeset.
             download_changeset_images(&changeset_bytes, bucket, library_dir).await;
@@ -286,7 +360,9 @@ async fn download_changeset_images(
             None
         };
 
-        match bucket.download_image(&image.id, release_id).await {
+        let downloaded = bucket.download_image(&image.id, release_id).await;
+        server_metrics().record_image_download(downloaded.is_ok());
+        match downloaded {
             Ok(bytes) => {
                 if let Some(parent) = image_path.parent() {
                     if let Err(e) = std::fs::create_dir_all(parent) {
@@ -337,3 +413,13 @@ impl std::fmt::Display for PullError {
 }
 
 impl std::error::Error for PullError {}
+
+impl HasErrorCode for PullError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            PullError::Bucket(e) => e.code(),
+            PullError::InvalidEnvelope | PullError::Apply(_) => ErrorCode::SyncInvalidChangeset,
+            PullError::SchemaVersionTooOld { .. } => ErrorCode::SyncUpgradeRequired,
+        }
+    }
+}
@@ -517,6 +517,7 @@ async fn sync_cycle_push_then_pull() {
         // Device 1: write some data, create outgoing.
         let ks = KeyService::new(true, "test-sync-cycle".to_string());
         let keypair = ks.get_or_create_user_keypair().unwrap();
+        let device_keypair = crate::keys::UserKeypair::generate();
 
         let svc1 = SyncService::new("dev-1".into());
         let session1 = SyncSession::start(db1).expect("start session");
@@ -535,6 +536,7 @@ async fn sync_cycle_push_then_pull() {
                 "2026-02-10T00:00:00Z",
                 "Imported Kind of Blue",
                 &keypair,
+                &device_keypair,
                 None,
                 &lib_dir,
             )
@@ -639,6 +641,7 @@ async fn sync_cycle_no_local_changes_returns_none() {
         let bucket = MockBucket::new();
         let ks = KeyService::new(true, "test-sync-no-changes".to_string());
         let keypair = ks.get_or_create_user_keypair().unwrap();
+        let device_keypair = crate::keys::UserKeypair::generate();
 
         let svc = SyncService::new("dev-local".into());
         let session = SyncSession::start(db).expect("start");
@@ -654,6 +657,7 @@ async fn sync_cycle_no_local_changes_returns_none() {
                 "2026-02-10T00:00:00Z",
                 "",
                 &keypair,
+                &device_keypair,
                 None,
                 &lib_dir,
             )
@@ -677,6 +681,7 @@ async fn sync_service_outgoing_has_correct_envelope() {
         let bucket = MockBucket::new();
         let ks = KeyService::new(true, "test-sync-envelope".to_string());
         let keypair = ks.get_or_create_user_keypair().unwrap();
+        let device_keypair = crate::keys::UserKeypair::generate();
 
         let svc = SyncService::new("dev-local".into());
         let session = SyncSession::start(db).expect("start");
@@ -696,6 +701,7 @@ async fn sync_service_outgoing_has_correct_envelope() {
                 "2026-02-10T12:00:00Z",
                 "Added Test artist",
                 &keypair,
+                &device_keypair,
                 None,
                 &lib_dir,
             )
@@ -760,6 +766,8 @@ fn build_chain(
         timestamp: "0000000001000-0000-dev1".to_string(),
         author_pubkey: pk_hex,
         signature: String::new(),
+        device_id: None,
+        device_pubkey: None,
     };
     sign_membership_entry(&mut founder, owner);
 
@@ -775,6 +783,8 @@ fn build_chain(
             timestamp: ts,
             author_pubkey: pubkey_hex(owner),
             signature: String::new(),
+            device_id: None,
+            device_pubkey: None,
         };
         sign_membership_entry(&mut entry, owner);
         chain.add_entry(entry).unwrap();
@@ -783,6 +793,29 @@ fn build_chain(
     chain
 }
 
+/// Enroll `device` as `member`'s device `device_id`, signed by `author`.
+fn enroll_device(
+    chain: &mut MembershipChain,
+    author: &crate::keys::UserKeypair,
+    member: &crate::keys::UserKeypair,
+    device_id: &str,
+    device: &crate::keys::UserKeypair,
+    timestamp: &str,
+) {
+    let mut entry = MembershipEntry {
+        action: MembershipAction::AddDevice,
+        user_pubkey: pubkey_hex(member),
+        role: MemberRole::Member,
+        timestamp: timestamp.to_string(),
+        author_pubkey: String::new(),
+        signature: String::new(),
+        device_id: Some(device_id.to_string()),
+        device_pubkey: Some(pubkey_hex(device)),
+    };
+    sign_membership_entry(&mut entry, author);
+    chain.add_entry(entry).unwrap();
+}
+
 #[tokio::test]
 async fn pull_rejects_changeset_from_non_member() {
     unsafe {
@@ -874,6 +907,50 @@ async fn pull_accepts_changeset_from_valid_member() {
     }
 }
 
+#[tokio::test]
+async fn pull_rejects_changeset_from_read_only_member() {
+    unsafe {
+        let db = open_memory_db();
+        create_synced_schema(db);
+        let (_tmp, lib_dir) = test_library_dir();
+
+        let owner = gen_keypair();
+        let listener = gen_keypair();
+        let chain = build_chain(&owner, &[(&listener, MemberRole::ReadOnly)]);
+
+        let remote_db = open_memory_db();
+        create_synced_schema(remote_db);
+        let cs = capture_changeset(
+            remote_db,
+            &["artists"],
+            &["INSERT INTO artists (id, name, _updated_at, created_at) VALUES ('a1', 'Read Only', '0000000005000-0000-dev-r', '2026-01-01')"],
+        );
+
+        let bucket = MockBucket::new();
+        bucket.store_signed_changeset(
+            "dev-listener",
+            1,
+            &cs,
+            SCHEMA_VERSION,
+            "0000000005000-0000-dev-r",
+            &listener,
+        );
+
+        let cursors = HashMap::new();
+        let (updated, result) =
+            pull::pull_changes(db, &bucket, "dev-local", &cursors, Some(&chain), &lib_dir)
+                .await
+                .expect("pull");
+
+        assert_eq!(result.changesets_applied, 0);
+        assert!(!row_exists(db, "SELECT 1 FROM artists WHERE id = 'a1'"));
+        assert_eq!(updated.get("dev-listener"), Some(&1));
+
+        ffi::sqlite3_close(db);
+        ffi::sqlite3_close(remote_db);
+    }
+}
+
 #[tokio::test]
 async fn pull_rejects_unsigned_changeset_after_chain_creation() {
     unsafe {
@@ -963,3 +1040,197 @@ async fn pull_accepts_unsigned_changeset_before_chain_creation() {
         ffi::sqlite3_close(remote_db);
     }
 }
+
+#[tokio::test]
+async fn pull_rejects_changeset_from_revoked_device() {
+    unsafe {
+        let db = open_memory_db();
+        create_synced_schema(db);
+        let (_tmp, lib_dir) = test_library_dir();
+
+        let owner = gen_keypair();
+        let member = gen_keypair();
+        let laptop = gen_keypair();
+        let desktop = gen_keypair();
+        let mut chain = build_chain(&owner, &[(&member, MemberRole::Member)]);
+        enroll_device(
+            &mut chain,
+            &member,
+            &member,
+            "dev-laptop",
+            &laptop,
+            "0000000002100-0000-dev1",
+        );
+        enroll_device(
+            &mut chain,
+            &laptop,
+            &member,
+            "dev-desktop",
+            &desktop,
+            "0000000002200-0000-dev1",
+        );
+
+        // The member retires their lost laptop; their desktop keeps syncing.
+        let mut revoke = MembershipEntry {
+            action: MembershipAction::RevokeDevice,
+            user_pubkey: pubkey_hex(&member),
+            role: MemberRole::Member,
+            timestamp: "0000000003000-0000-dev1".to_string(),
+            author_pubkey: String::new(),
+            signature: String::new(),
+            device_id: Some("dev-laptop".to_string()),
+            device_pubkey: None,
+        };
+        sign_membership_entry(&mut revoke, &desktop);
+        chain.add_entry(revoke).unwrap();
+
+        let remote_db = open_memory_db();
+        create_synced_schema(remote_db);
+        let laptop_cs = capture_changeset(
+            remote_db,
+            &["artists"],
+            &["INSERT INTO artists (id, name, _updated_at, created_at) VALUES ('a1', 'Glass Harbor', '0000000002500-0000-dev-r', '2026-01-01')"],
+        );
+        let desktop_cs = capture_changeset(
+            remote_db,
+            &["artists"],
+            &["INSERT INTO artists (id, name, _updated_at, created_at) VALUES ('a2', 'The Lanterns', '0000000005000-0000-dev-r', '2026-01-01')"],
+        );
+
+        let bucket = MockBucket::new();
+        // Backdated to before the revocation: still rejected.
+        bucket.store_device_signed_changeset(
+            "dev-laptop",
+            1,
+            &laptop_cs,
+            SCHEMA_VERSION,
+            "0000000002500-0000-dev-r",
+            &member,
+            &laptop,
+        );
+        bucket.store_device_signed_changeset(
+            "dev-desktop",
+            1,
+            &desktop_cs,
+            SCHEMA_VERSION,
+            "0000000005000-0000-dev-r",
+            &member,
+            &desktop,
+        );
+
+        let cursors = HashMap::new();
+        let (updated, result) =
+            pull::pull_changes(db, &bucket, "dev-local", &cursors, Some(&chain), &lib_dir)
+                .await
+                .expect("pull");
+
+        assert_eq!(result.changesets_applied, 1);
+        assert!(!row_exists(db, "SELECT 1 FROM artists WHERE id = 'a1'"));
+        assert!(row_exists(db, "SELECT 1 FROM artists WHERE id = 'a2'"));
+        assert_eq!(updated.get("dev-laptop"), Some(&1));
+        assert_eq!(updated.get("dev-desktop"), Some(&1));
+
+        ffi::sqlite3_close(db);
+        ffi::sqlite3_close(remote_db);
+    }
+}
+
+#[tokio::test]
+async fn pull_rejects_identity_signed_changeset_once_member_has_devices() {
+    unsafe {
+        let db = open_memory_db();
+        create_synced_schema(db);
+        let (_tmp, lib_dir) = test_library_dir();
+
+        let owner = gen_keypair();
+        let member = gen_keypair();
+        let mut chain = build_chain(&owner, &[(&member, MemberRole::Member)]);
+        enroll_device(
+            &mut chain,
+            &member,
+            &member,
+            "dev-laptop",
+            &gen_keypair(),
+            "0000000002100-0000-dev1",
+        );
+
+        let remote_db = open_memory_db();
+        create_synced_schema(remote_db);
+        let cs = capture_changeset(
+            remote_db,
+            &["artists"],
+            &["INSERT INTO artists (id, name, _updated_at, created_at) VALUES ('a1', 'No Device', '0000000005000-0000-dev-r', '2026-01-01')"],
+        );
+
+        let bucket = MockBucket::new();
+        bucket.store_signed_changeset(
+            "dev-laptop",
+            1,
+            &cs,
+            SCHEMA_VERSION,
+            "0000000005000-0000-dev-r",
+            &member,
+        );
+
+        let cursors = HashMap::new();
+        let (updated, result) =
+            pull::pull_changes(db, &bucket, "dev-local", &cursors, Some(&chain), &lib_dir)
+                .await
+                .expect("pull");
+
+        assert_eq!(result.changesets_applied, 0);
+        assert!(!row_exists(db, "SELECT 1 FROM artists WHERE id = 'a1'"));
+        assert_eq!(updated.get("dev-laptop"), Some(&1));
+
+        ffi::sqlite3_close(db);
+        ffi::sqlite3_close(remote_db);
+    }
+}
+
+#[tokio::test]
+async fn pull_waits_for_unenrolled_device() {
+    unsafe {
+        let db = open_memory_db();
+        create_synced_schema(db);
+        let (_tmp, lib_dir) = test_library_dir();
+
+        let owner = gen_keypair();
+        let member = gen_keypair();
+        let chain = build_chain(&owner, &[(&member, MemberRole::Member)]);
+
+        let remote_db = open_memory_db();
+        create_synced_schema(remote_db);
+        let cs = capture_changeset(
+            remote_db,
+            &["artists"],
+            &["INSERT INTO artists (id, name, _updated_at, created_at) VALUES ('a1', 'Fresh Key', '0000000005000-0000-dev-r', '2026-01-01')"],
+        );
+
+        // A key for a device ID the chain has never seen, e.g. one a revoked
+        // device certified with the identity it still holds.
+        let bucket = MockBucket::new();
+        bucket.store_device_signed_changeset(
+            "dev-new",
+            1,
+            &cs,
+            SCHEMA_VERSION,
+            "0000000005000-0000-dev-r",
+            &member,
+            &gen_keypair(),
+        );
+
+        let cursors = HashMap::new();
+        let (updated, result) =
+            pull::pull_changes(db, &bucket, "dev-local", &cursors, Some(&chain), &lib_dir)
+                .await
+                .expect("pull");
+
+        assert_eq!(result.changesets_applied, 0);
+        assert!(!row_exists(db, "SELECT 1 FROM artists WHERE id = 'a1'"));
+        // Not skipped for good: the enrollment may still arrive.
+        assert_eq!(updated.get("dev-new"), None);
+
+        ffi::sqlite3_close(db);
+        ffi::sqlite3_close(remote_db);
+    }
+}
@@ -15,6 +15,7 @@ use std::collections::HashMap;
 
 use tracing::{info, warn};
 
+use crate::error_code::{ErrorCode, HasErrorCode};
 use crate::keys::UserKeypair;
 use crate::library_dir::LibraryDir;
 
@@ -75,6 +76,7 @@ impl SyncService {
         timestamp: &str,
         message: &str,
         keypair: &UserKeypair,
+        device_keypair: &UserKeypair,
         membership_chain: Option<&MembershipChain>,
         library_dir: &LibraryDir,
     ) -> Result<SyncResult, SyncCycleError> {
@@ -102,8 +104,10 @@ impl SyncService {
                 changeset_size: cs.len(),
                 author_pubkey: None,
                 signature: None,
+                device_pubkey: None,
+                device_certificate: None,
             };
-            sign_envelope(&mut env, keypair, cs.as_bytes());
+            sign_envelope(&mut env, keypair, device_keypair, cs.as_bytes());
             let packed = envelope::pack(&env, cs.as_bytes());
             OutgoingChangeset {
                 packed,
@@ -203,3 +207,14 @@ impl std::fmt::Display for SyncCycleError {
 }
 
 impl std::error::Error for SyncCycleError {}
+
+impl HasErrorCode for SyncCycleError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            SyncCycleError::Session(_) => ErrorCode::Database,
+            SyncCycleError::Pull(e) => e.code(),
+            SyncCycleError::ImageScan(_) => ErrorCode::Io,
+            SyncCycleError::ImageUpload(_) => ErrorCode::SyncBucketUnavailable,
+        }
+    }
+}
@@ -1,10 +1,10 @@
 /// Production session management for sync.
 ///
 /// `SyncSession` wraps the low-level FFI `Session` and attaches exactly the
-/// 11 synced tables. It provides a clean start/changeset/end lifecycle.
+/// tables in `SYNCED_TABLES`. It provides a clean start/changeset/end lifecycle.
 use super::session_ext::{Changeset, Session};
 
-/// The 11 tables that participate in changeset sync.
+/// The tables that participate in changeset sync.
 /// Device-specific tables (torrents, torrent_piece_mappings, imports)
 /// are NOT attached.
 pub const SYNCED_TABLES: &[&str] = &[
@@ -13,10 +13,25 @@ pub const SYNCED_TABLES: &[&str] = &[
     "album_discogs",
     "album_musicbrainz",
     "album_artists",
+    "album_versions",
     "releases",
     "tracks",
     "track_artists",
+    "track_stats",
+    "track_lyrics",
+    "track_works",
+    "release_discs",
+    "genres",
+    "album_genres",
+    "album_custom_tags",
+    "album_notes",
+    "album_comments",
+    "playlists",
+    "playlist_tracks",
+    "collections",
+    "collection_albums",
     "release_files",
+    "release_availability",
     "audio_formats",
     "library_images",
 ];
@@ -94,6 +94,16 @@ impl ConflictType {
     }
 }
 
+/// A column value with its SQLite type preserved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
 /// Context available to a conflict handler during changeset application.
 ///
 /// Wraps the raw `sqlite3_changeset_iter` to provide safe access to the
@@ -166,6 +176,62 @@ impl ConflictContext {
             value_to_string(val)
         }
     }
+
+    /// Typed "new" value for a column. Unlike `new_value`, distinguishes a
+    /// column the change doesn't carry (None) from one set to NULL.
+    pub fn new_column_value(&self, col: usize) -> Option<ColumnValue> {
+        unsafe {
+            let mut val: *mut ffi::sqlite3_value = ptr::null_mut();
+            let rc = ffi::sqlite3changeset_new(self.iter, col as c_int, &mut val);
+            if rc != ffi::SQLITE_OK as c_int || val.is_null() {
+                return None;
+            }
+            Some(column_value(val))
+        }
+    }
+
+    /// Typed "old" value for a column, or None if the change doesn't carry it.
+    pub fn old_column_value(&self, col: usize) -> Option<ColumnValue> {
+        unsafe {
+            let mut val: *mut ffi::sqlite3_value = ptr::null_mut();
+            let rc = ffi::sqlite3changeset_old(self.iter, col as c_int, &mut val);
+            if rc != ffi::SQLITE_OK as c_int || val.is_null() {
+                return None;
+            }
+            Some(column_value(val))
+        }
+    }
+
+    /// Typed "conflict" value for a column (the current local value).
+    pub fn conflict_column_value(&self, col: usize) -> Option<ColumnValue> {
+        unsafe {
+            let mut val: *mut ffi::sqlite3_value = ptr::null_mut();
+            let rc = ffi::sqlite3changeset_conflict(self.iter, col as c_int, &mut val);
+            if rc != ffi::SQLITE_OK as c_int || val.is_null() {
+                return None;
+            }
+            Some(column_value(val))
+        }
+    }
+}
+
+/// Copy a sqlite3_value out with its type.
+unsafe fn column_value(val: *mut ffi::sqlite3_value) -> ColumnValue {
+    match ffi::sqlite3_value_type(val) {
+        ffi::SQLITE_INTEGER => ColumnValue::Integer(ffi::sqlite3_value_int64(val)),
+        ffi::SQLITE_FLOAT => ColumnValue::Real(ffi::sqlite3_value_double(val)),
+        ffi::SQLITE_TEXT => ColumnValue::Text(value_to_string(val).unwrap_or_default()),
+        ffi::SQLITE_BLOB => {
+            let blob = ffi::sqlite3_value_blob(val);
+            let len = ffi::sqlite3_value_bytes(val) as usize;
+            if blob.is_null() || len == 0 {
+                ColumnValue::Blob(Vec::new())
+            } else {
+                ColumnValue::Blob(std::slice::from_raw_parts(blob as *const u8, len).to_vec())
+            }
+        }
+        _ => ColumnValue::Null,
+    }
 }
 
 /// Extract a text string from a sqlite3_value, or None if NULL.
@@ -1,12 +1,16 @@
 /// Snapshots and garbage collection for the sync system.
 ///
 /// Periodically, a device creates a full snapshot of the database via
-/// `VACUUM INTO`, encrypts it, and uploads as `snapshot.db.enc`. This
+/// `VACUUM INTO`, compresses and encrypts it, and uploads as `snapshot.db.enc`. This
 /// allows new devices to bootstrap without replaying the entire changeset
 /// history, and enables GC of old changesets.
 ///
 /// Snapshot creation policy: after every N changesets (default 100) or
-/// T hours (default 24) since the last snapshot.
+/// T hours (default 24) since the last snapshot. A headless server, which
+/// mostly applies other devices' changesets, counts those too and snapshots
+/// on its own interval (`server_snapshot_due`). It also keeps the changesets
+/// its snapshots cover for a retention window before collecting them
+/// (`take_expired_snapshot`).
 use std::collections::HashMap;
 use std::ffi::CString;
 use std::path::Path;
@@ -16,6 +20,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
 use super::bucket::{BucketError, SyncBucketClient};
+use super::compression;
 use crate::encryption::EncryptionService;
 
 /// Default: create a snapshot after this many changesets since the last one.
@@ -58,7 +63,7 @@ pub struct BootstrapResult {
 /// Create a snapshot of the database as encrypted bytes.
 ///
 /// Uses `VACUUM INTO` to create a clean copy of the database at a temp path,
-/// reads the bytes, encrypts, and returns the encrypted blob.
+/// reads the bytes, compresses and encrypts them, and returns the encrypted blob.
 ///
 /// # Safety
 /// `db` must be a valid, open sqlite3 connection pointer.
@@ -75,8 +80,14 @@ pub unsafe fn create_snapshot(
     // Remove any leftover snapshot file from a previous failed attempt.
     let _ = std::fs::remove_file(&snapshot_path);
 
-    // VACUUM INTO creates a clean, defragmented copy of the database.
-    let sql = format!("VACUUM INTO '{}'", path_str.replace('\'', "''"));
+    // VACUUM INTO creates a clean, defragmented copy of the database. A
+    // database encrypted at rest is exported as plaintext instead: the
+    // snapshot is encrypted below, and other devices may not encrypt theirs.
+    let sql = if crate::db::is_encrypted_connection(db) {
+        crate::db::export_sql(&snapshot_path, None)
+    } else {
+        format!("VACUUM INTO '{}'", path_str.replace('\'', "''"))
+    };
     let c_sql = CString::new(sql).expect("SQL should not contain null bytes");
     let rc = ffi::sqlite3_exec(
         db,
@@ -96,14 +107,16 @@ pub unsafe fn create_snapshot(
         return Err(SnapshotError::VacuumFailed(msg));
     }
 
-    // Read the snapshot file and encrypt.
+    // Read the snapshot file, compress and encrypt.
     let plaintext = std::fs::read(&snapshot_path)?;
     let _ = std::fs::remove_file(&snapshot_path);
 
-    let encrypted = encryption.encrypt(&plaintext);
+    let compressed = compression::compress(&plaintext)?;
+    let encrypted = encryption.encrypt(&compressed);
 
     info!(
         plaintext_size = plaintext.len(),
+        compressed_size = compressed.len(),
         encrypted_size = encrypted.len(),
         "created snapshot"
     );
@@ -115,13 +128,14 @@ pub unsafe fn create_snapshot(
 ///
 /// Also uploads per-device cursor metadata (`snapshot_meta.json.enc`) so that
 /// bootstrapping devices know where each device was at snapshot time, and GC
-/// can safely delete only changesets covered by the snapshot.
+/// can safely delete only changesets covered by the snapshot. Returns that
+/// metadata.
 pub async fn push_snapshot(
     bucket: &dyn SyncBucketClient,
     encrypted_snapshot: Vec<u8>,
     device_id: &str,
     current_seq: u64,
-) -> Result<(), SnapshotError> {
+) -> Result<SnapshotMeta, SnapshotError> {
     let size = encrypted_snapshot.len();
     let timestamp = chrono::Utc::now().to_rfc3339();
 
@@ -156,7 +170,7 @@ pub async fn push_snapshot(
         "pushed snapshot to sync bucket"
     );
 
-    Ok(())
+    Ok(meta)
 }
 
 /// Check whether it's time to create a new snapshot.
@@ -190,6 +204,50 @@ pub fn should_create_snapshot(
     false
 }
 
+/// Check whether a headless server should write a new snapshot.
+///
+/// `changesets_since_snapshot` counts every device's changesets since the
+/// server's last snapshot, not just its own. Due once `interval_hours` have
+/// passed, or sooner if the changeset threshold (100) is reached, but only if
+/// anything changed. A server that never wrote one snapshots as soon as there
+/// is history to cover.
+pub fn server_snapshot_due(
+    changesets_since_snapshot: u64,
+    hours_since_snapshot: Option<u64>,
+    interval_hours: u64,
+) -> bool {
+    if changesets_since_snapshot == 0 {
+        return false;
+    }
+    match hours_since_snapshot {
+        None => true,
+        Some(hours) => {
+            hours >= interval_hours || changesets_since_snapshot >= SNAPSHOT_CHANGESET_THRESHOLD
+        }
+    }
+}
+
+/// Take the server snapshots that have aged out of the retention window.
+///
+/// `history` lists the server's snapshots oldest first. Removes every one
+/// created more than `retention_days` before `now`, except the latest, and
+/// returns the newest removed: changesets up to its cursors can go
+/// (`garbage_collect_through`), since every later snapshot covers them too.
+/// None while the others are all within the window.
+pub fn take_expired_snapshot(
+    history: &mut Vec<SnapshotMeta>,
+    retention_days: u64,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<SnapshotMeta> {
+    let cutoff = now - chrono::Duration::days(retention_days as i64);
+    let older = history.len().saturating_sub(1);
+    let expired = history[..older].iter().rposition(|meta| {
+        chrono::DateTime::parse_from_rfc3339(&meta.created_at)
+            .is_ok_and(|created| created <= cutoff)
+    })?;
+    history.drain(..=expired).last()
+}
+
 /// Delete changesets that are superseded by a snapshot.
 ///
 /// Reads snapshot metadata to get per-device cursors at snapshot time.
@@ -217,13 +275,22 @@ pub async fn garbage_collect(bucket: &dyn SyncBucketClient) -> Result<GcResult,
     let meta: SnapshotMeta = serde_json::from_slice(&meta_json)
         .map_err(|e| SnapshotError::Io(std::io::Error::other(e)))?;
 
+    garbage_collect_through(bucket, &meta.cursors).await
+}
+
+/// Delete each device's changesets up to its seq in `cursors`, the cursors
+/// of a snapshot. Devices not in `cursors` are left alone.
+pub async fn garbage_collect_through(
+    bucket: &dyn SyncBucketClient,
+    cursors: &HashMap<String, u64>,
+) -> Result<GcResult, SnapshotError> {
     let heads = bucket.list_heads().await?;
     let mut deleted = 0u64;
     let mut errors = 0u64;
 
     for head in &heads {
         // Only GC changesets up to what the snapshot covers for THIS device.
-        let safe_seq = match meta.cursors.get(&head.device_id) {
+        let safe_seq = match cursors.get(&head.device_id) {
             Some(&seq) => seq,
             None => continue, // Device appeared after snapshot -- don't touch.
         };
@@ -291,10 +358,11 @@ pub async fn bootstrap_from_snapshot(
     // Download encrypted snapshot.
     let encrypted = bucket.get_snapshot().await?;
 
-    // Decrypt.
-    let plaintext = encryption
+    // Decrypt, then decompress (snapshots from before compression pass through).
+    let decrypted = encryption
         .decrypt(&encrypted)
         .map_err(|e| SnapshotError::Decryption(e.to_string()))?;
+    let plaintext = compression::decompress(decrypted)?;
 
     // Write to target path.
     if let Some(parent) = target_path.parent() {
@@ -594,6 +662,45 @@ mod tests {
         assert!(!should_create_snapshot(50, Some(50), Some(24)));
     }
 
+    // ---- server snapshot policy tests ----
+
+    #[test]
+    fn server_snapshots_on_its_interval_when_anything_changed() {
+        // First snapshot as soon as there's history.
+        assert!(server_snapshot_due(1, None, 24));
+        assert!(!server_snapshot_due(0, None, 24));
+
+        assert!(!server_snapshot_due(10, Some(5), 24));
+        assert!(server_snapshot_due(10, Some(24), 24));
+        assert!(server_snapshot_due(100, Some(1), 24));
+        assert!(!server_snapshot_due(0, Some(72), 24));
+    }
+
+    #[test]
+    fn expired_snapshots_are_taken_newest_first() {
+        let meta = |created_at: &str, seq: u64| SnapshotMeta {
+            cursors: HashMap::from([("dev-a".to_string(), seq)]),
+            created_at: created_at.to_string(),
+        };
+        let mut history = vec![
+            meta("2026-01-01T00:00:00Z", 10),
+            meta("2026-01-20T00:00:00Z", 20),
+            meta("2026-02-25T00:00:00Z", 30),
+        ];
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let expired = take_expired_snapshot(&mut history, 30, now).unwrap();
+        assert_eq!(expired.cursors.get("dev-a"), Some(&20));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].cursors.get("dev-a"), Some(&30));
+
+        // The latest snapshot is kept whatever its age
+        assert!(take_expired_snapshot(&mut history, 0, now).is_none());
+        assert_eq!(history.len(), 1);
+    }
+
     // ---- create_snapshot tests ----
 
     #[test]
@@ -617,8 +724,9 @@ mod tests {
             // Should be non-empty encrypted bytes.
             assert!(!encrypted.is_empty());
 
-            // Should be decryptable.
-            let plaintext = enc.decrypt(&encrypted).expect("decrypt should succeed");
+            // Should be decryptable, then decompressible.
+            let compressed = enc.decrypt(&encrypted).expect("decrypt should succeed");
+            let plaintext = compression::decompress(compressed).expect("decompress");
             assert!(!plaintext.is_empty());
 
             // The plaintext should be a valid SQLite database (starts with "SQLite format 3\0").
@@ -652,7 +760,8 @@ mod tests {
             let enc = test_encryption();
 
             let encrypted = create_snapshot(db, temp.path(), &enc).expect("snapshot");
-            let plaintext = enc.decrypt(&encrypted).expect("decrypt");
+            let compressed = enc.decrypt(&encrypted).expect("decrypt");
+            let plaintext = compression::decompress(compressed).expect("decompress");
 
             // Write to file and open to verify contents.
             let db_path = temp.path().join("verify.db");
@@ -970,7 +1079,8 @@ mod tests {
 
             // --- Path A: bootstrap from snapshot + apply cs2 ---
 
-            let snapshot_plain = enc.decrypt(&snapshot_encrypted).unwrap();
+            let snapshot_plain =
+                compression::decompress(enc.decrypt(&snapshot_encrypted).unwrap()).unwrap();
             let path_a = temp.path().join("path_a.db");
             std::fs::write(&path_a, &snapshot_plain).unwrap();
 
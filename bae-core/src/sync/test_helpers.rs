@@ -132,6 +132,7 @@ pub unsafe fn create_synced_schema(db: *mut ffi::sqlite3) {
             album_id TEXT NOT NULL,
             artist_id TEXT NOT NULL,
             position INTEGER NOT NULL,
+            join_phrase TEXT,
             _updated_at TEXT NOT NULL,
             created_at TEXT NOT NULL,
             FOREIGN KEY (album_id) REFERENCES albums (id) ON DELETE CASCADE,
@@ -186,6 +187,7 @@ pub unsafe fn create_synced_schema(db: *mut ffi::sqlite3) {
             artist_id TEXT NOT NULL,
             position INTEGER NOT NULL,
             role TEXT,
+            join_phrase TEXT,
             _updated_at TEXT NOT NULL,
             created_at TEXT NOT NULL,
             FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE,
@@ -201,6 +203,7 @@ pub unsafe fn create_synced_schema(db: *mut ffi::sqlite3) {
             file_size INTEGER NOT NULL,
             content_type TEXT NOT NULL,
             encryption_nonce BLOB,
+            content_sha256 TEXT,
             _updated_at TEXT NOT NULL,
             created_at TEXT NOT NULL,
             FOREIGN KEY (release_id) REFERENCES releases (id) ON DELETE CASCADE
@@ -224,6 +227,8 @@ pub unsafe fn create_synced_schema(db: *mut ffi::sqlite3) {
             seektable_json TEXT NOT NULL,
             audio_data_start INTEGER NOT NULL,
             file_id TEXT REFERENCES release_files(id),
+            cue_in_ms INTEGER,
+            cue_out_ms INTEGER,
             _updated_at TEXT NOT NULL,
             created_at TEXT NOT NULL,
             FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE
@@ -240,10 +245,34 @@ pub unsafe fn create_synced_schema(db: *mut ffi::sqlite3) {
             height INTEGER,
             source TEXT NOT NULL,
             source_url TEXT,
+            blurhash TEXT,
             _updated_at TEXT NOT NULL,
             created_at TEXT NOT NULL
         )",
     );
+    exec(
+        db,
+        "CREATE TABLE playlists (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            _updated_at TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+    );
+    exec(
+        db,
+        "CREATE TABLE playlist_tracks (
+            id TEXT PRIMARY KEY,
+            playlist_id TEXT NOT NULL,
+            track_id TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            _updated_at TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (playlist_id) REFERENCES playlists (id) ON DELETE CASCADE,
+            FOREIGN KEY (track_id) REFERENCES tracks (id) ON DELETE CASCADE,
+            UNIQUE(playlist_id, position)
+        )",
+    );
 }
 
 /// In-memory mock of SyncBucketClient for tests.
@@ -283,6 +312,8 @@ impl MockBucket {
             changeset_size: changeset_bytes.len(),
             author_pubkey: None,
             signature: None,
+            device_pubkey: None,
+            device_certificate: None,
         };
         let packed = envelope::pack(&env, changeset_bytes);
 
@@ -294,7 +325,8 @@ impl MockBucket {
             .insert(device_id.to_string(), seq);
     }
 
-    /// Store a signed changeset (with author_pubkey and signature).
+    /// Store a changeset signed directly with the author's identity key, as
+    /// before devices had their own keys.
     pub fn store_signed_changeset(
         &self,
         device_id: &str,
@@ -303,6 +335,39 @@ impl MockBucket {
         schema_version: u32,
         timestamp: &str,
         keypair: &UserKeypair,
+    ) {
+        let env = ChangesetEnvelope {
+            device_id: device_id.to_string(),
+            seq,
+            schema_version,
+            message: String::new(),
+            timestamp: timestamp.to_string(),
+            changeset_size: changeset_bytes.len(),
+            author_pubkey: Some(hex::encode(keypair.public_key)),
+            signature: Some(hex::encode(keypair.sign(changeset_bytes))),
+            device_pubkey: None,
+            device_certificate: None,
+        };
+        let packed = envelope::pack(&env, changeset_bytes);
+
+        let key = format!("changes/{device_id}/{seq}");
+        self.objects.lock().unwrap().insert(key, packed);
+        self.heads
+            .lock()
+            .unwrap()
+            .insert(device_id.to_string(), seq);
+    }
+
+    /// Store a changeset signed with a device key, the way push does.
+    pub fn store_device_signed_changeset(
+        &self,
+        device_id: &str,
+        seq: u64,
+        changeset_bytes: &[u8],
+        schema_version: u32,
+        timestamp: &str,
+        keypair: &UserKeypair,
+        device_keypair: &UserKeypair,
     ) {
         let mut env = ChangesetEnvelope {
             device_id: device_id.to_string(),
@@ -313,8 +378,10 @@ impl MockBucket {
             changeset_size: changeset_bytes.len(),
             author_pubkey: None,
             signature: None,
+            device_pubkey: None,
+            device_certificate: None,
         };
-        sign_envelope(&mut env, keypair, changeset_bytes);
+        sign_envelope(&mut env, keypair, device_keypair, changeset_bytes);
         let packed = envelope::pack(&env, changeset_bytes);
 
         let key = format!("changes/{device_id}/{seq}");
@@ -343,6 +410,8 @@ impl MockBucket {
             changeset_size: changeset_bytes.len(),
             author_pubkey: None,
             signature: None,
+            device_pubkey: None,
+            device_certificate: None,
         };
         let packed = envelope::pack(&env, changeset_bytes);
 
@@ -181,6 +181,195 @@ fn lww_older_updated_at_loses_data_conflict() {
     }
 }
 
+// ---- Album DATA conflicts are recorded for review ----
+
+#[test]
+fn album_data_conflict_records_both_sides_of_changed_columns() {
+    unsafe {
+        let db1 = open_memory_db();
+        create_synced_schema(db1);
+        exec(
+            db1,
+            "INSERT INTO albums (id, title, year, _updated_at, created_at) VALUES ('al1', 'Glass Harbor', 2019, '0000000001000-0000-dev1', '2026-01-01')",
+        );
+
+        let session = Session::new(db1).expect("session");
+        session.attach(Some("albums")).expect("attach");
+        exec(
+            db1,
+            "UPDATE albums SET title = 'Glass Harbour', _updated_at = '0000000003000-0000-dev1' WHERE id = 'al1'",
+        );
+        let cs = session.changeset().expect("changeset");
+        drop(session);
+
+        // Device 2 renamed the same album earlier and also fixed the year
+        let db2 = open_memory_db();
+        create_synced_schema(db2);
+        exec(
+            db2,
+            "INSERT INTO albums (id, title, year, _updated_at, created_at) VALUES ('al1', 'Glass Harbor (Remaster)', 2020, '0000000002000-0000-dev2', '2026-01-01')",
+        );
+
+        let result = apply_changeset_lww(db2, &cs).expect("apply");
+
+        assert_eq!(result.conflicts.len(), 1);
+        let conflict = &result.conflicts[0];
+        assert_eq!(conflict.table_name, "albums");
+        assert_eq!(conflict.row_id, "al1");
+        assert!(conflict.kept_remote);
+        // The incoming UPDATE only touched the title, so the year isn't in
+        // conflict
+        assert_eq!(
+            conflict.fields,
+            vec![crate::db::DbSyncConflictField {
+                column: "title".to_string(),
+                local: Some("Glass Harbor (Remaster)".to_string()),
+                remote: Some("Glass Harbour".to_string()),
+            }]
+        );
+
+        ffi::sqlite3_close(db1);
+        ffi::sqlite3_close(db2);
+    }
+}
+
+#[test]
+fn artist_data_conflict_is_not_recorded() {
+    unsafe {
+        let db1 = open_memory_db();
+        create_synced_schema(db1);
+        exec(
+            db1,
+            "INSERT INTO artists (id, name, _updated_at, created_at) VALUES ('a1', 'Original', '0000000001000-0000-dev1', '2026-01-01')",
+        );
+
+        let session = Session::new(db1).expect("session");
+        session.attach(Some("artists")).expect("attach");
+        exec(
+            db1,
+            "UPDATE artists SET name = 'From Dev1', _updated_at = '0000000003000-0000-dev1' WHERE id = 'a1'",
+        );
+        let cs = session.changeset().expect("changeset");
+        drop(session);
+
+        let db2 = open_memory_db();
+        create_synced_schema(db2);
+        exec(
+            db2,
+            "INSERT INTO artists (id, name, _updated_at, created_at) VALUES ('a1', 'From Dev2', '0000000002000-0000-dev2', '2026-01-01')",
+        );
+
+        let result = apply_changeset_lww(db2, &cs).expect("apply");
+        assert!(result.conflicts.is_empty());
+
+        ffi::sqlite3_close(db1);
+        ffi::sqlite3_close(db2);
+    }
+}
+
+// ---- Field-level merge: remote-only column edits survive a local win ----
+
+#[test]
+fn local_win_keeps_remote_edits_to_other_columns() {
+    unsafe {
+        let base = "INSERT INTO albums (id, title, year, _updated_at, created_at) VALUES ('al1', 'Glass Harbor', 2019, '0000000001000-0000-dev1', '2026-01-01')";
+
+        let db1 = open_memory_db();
+        create_synced_schema(db1);
+        exec(db1, base);
+
+        let session = Session::new(db1).expect("session");
+        session.attach(Some("albums")).expect("attach");
+        exec(
+            db1,
+            "UPDATE albums SET title = 'Glass Harbour', year = 2018, _updated_at = '0000000002000-0000-dev1' WHERE id = 'al1'",
+        );
+        let cs = session.changeset().expect("changeset");
+        drop(session);
+
+        // Device 2 renamed the album later, but never touched the year
+        let db2 = open_memory_db();
+        create_synced_schema(db2);
+        exec(db2, base);
+        exec(
+            db2,
+            "UPDATE albums SET title = 'Glass Harbor (Remaster)', _updated_at = '0000000003000-0000-dev2' WHERE id = 'al1'",
+        );
+
+        let result = apply_changeset_lww(db2, &cs).expect("apply");
+
+        // The title was edited on both sides and the newer local edit wins;
+        // the year was only edited remotely and comes through
+        assert_eq!(
+            query_text(db2, "SELECT title FROM albums WHERE id = 'al1'"),
+            "Glass Harbor (Remaster)"
+        );
+        assert_eq!(
+            query_int(db2, "SELECT year FROM albums WHERE id = 'al1'"),
+            2018
+        );
+        assert_eq!(
+            query_text(db2, "SELECT _updated_at FROM albums WHERE id = 'al1'"),
+            "0000000003000-0000-dev2"
+        );
+
+        // Only the title is up for review
+        assert_eq!(result.conflicts.len(), 1);
+        assert!(!result.conflicts[0].kept_remote);
+        let columns: Vec<&str> = result.conflicts[0]
+            .fields
+            .iter()
+            .map(|f| f.column.as_str())
+            .collect();
+        assert_eq!(columns, vec!["title"]);
+
+        ffi::sqlite3_close(db1);
+        ffi::sqlite3_close(db2);
+    }
+}
+
+#[test]
+fn concurrent_edits_to_different_columns_converge() {
+    unsafe {
+        let base = "INSERT INTO artists (id, name, sort_name, _updated_at, created_at) VALUES ('a1', 'The Lanterns', NULL, '0000000001000-0000-dev1', '2026-01-01')";
+
+        let db1 = open_memory_db();
+        let db2 = open_memory_db();
+        let mut changesets = Vec::new();
+        for (db, edit) in [
+            (
+                db1,
+                "UPDATE artists SET name = 'Lanterns', _updated_at = '0000000002000-0000-dev1' WHERE id = 'a1'",
+            ),
+            (
+                db2,
+                "UPDATE artists SET sort_name = 'Lanterns, The', _updated_at = '0000000003000-0000-dev2' WHERE id = 'a1'",
+            ),
+        ] {
+            create_synced_schema(db);
+            exec(db, base);
+            let session = Session::new(db).expect("session");
+            session.attach(Some("artists")).expect("attach");
+            exec(db, edit);
+            changesets.push(session.changeset().expect("changeset"));
+        }
+
+        apply_changeset_lww(db1, &changesets[1]).expect("apply on dev1");
+        apply_changeset_lww(db2, &changesets[0]).expect("apply on dev2");
+
+        let row =
+            "SELECT name || '|' || sort_name || '|' || _updated_at FROM artists WHERE id = 'a1'";
+        assert_eq!(
+            query_text(db1, row),
+            "Lanterns|Lanterns, The|0000000003000-0000-dev2"
+        );
+        assert_eq!(query_text(db2, row), query_text(db1, row));
+
+        ffi::sqlite3_close(db1);
+        ffi::sqlite3_close(db2);
+    }
+}
+
 // ---- Delete wins over edit (NOTFOUND) ----
 
 #[test]
@@ -426,6 +615,72 @@ fn insert_conflict_newer_wins() {
     }
 }
 
+// ---- Playlists: rewriting entries at the same positions syncs ----
+
+/// Reordering a playlist deletes its entries and inserts new ones at the
+/// same positions, so applying depends on the deletes landing before the
+/// inserts hit the UNIQUE(playlist_id, position) constraint.
+#[test]
+fn playlist_reorder_applies_on_other_device() {
+    unsafe {
+        let db1 = open_memory_db();
+        create_synced_schema(db1);
+        let db2 = open_memory_db();
+        create_synced_schema(db2);
+
+        let session = SyncSession::start(db1).expect("start session");
+        exec(
+            db1,
+            "INSERT INTO albums (id, title, _updated_at, created_at) VALUES ('al1', 'Low Light', '0000000001000-0000-dev1', '2026-01-01')",
+        );
+        exec(
+            db1,
+            "INSERT INTO releases (id, album_id, import_status, _updated_at, created_at) VALUES ('r1', 'al1', 'complete', '0000000001000-0000-dev1', '2026-01-01')",
+        );
+        for (id, title) in [("t1", "Pacific Standard"), ("t2", "Tidewater")] {
+            exec(
+                db1,
+                &format!("INSERT INTO tracks (id, release_id, title, _updated_at, created_at) VALUES ('{id}', 'r1', '{title}', '0000000001000-0000-dev1', '2026-01-01')"),
+            );
+        }
+        exec(
+            db1,
+            "INSERT INTO playlists (id, name, _updated_at, created_at) VALUES ('p1', 'Night Drive', '0000000001000-0000-dev1', '2026-01-01')",
+        );
+        exec(
+            db1,
+            "INSERT INTO playlist_tracks (id, playlist_id, track_id, position, _updated_at, created_at) VALUES ('e1', 'p1', 't1', 0, '0000000001000-0000-dev1', '2026-01-01'), ('e2', 'p1', 't2', 1, '0000000001000-0000-dev1', '2026-01-01')",
+        );
+        let created = session.changeset().expect("changeset").expect("changes");
+        drop(session);
+        apply_changeset_lww(db2, &created).expect("apply created");
+
+        let session = SyncSession::start(db1).expect("start session");
+        exec(db1, "DELETE FROM playlist_tracks WHERE playlist_id = 'p1'");
+        exec(
+            db1,
+            "INSERT INTO playlist_tracks (id, playlist_id, track_id, position, _updated_at, created_at) VALUES ('e3', 'p1', 't2', 0, '0000000002000-0000-dev1', '2026-01-01'), ('e4', 'p1', 't1', 1, '0000000002000-0000-dev1', '2026-01-01')",
+        );
+        let reordered = session.changeset().expect("changeset").expect("changes");
+        drop(session);
+        apply_changeset_lww(db2, &reordered).expect("apply reorder");
+
+        let first = query_text(
+            db2,
+            "SELECT track_id FROM playlist_tracks WHERE playlist_id = 'p1' AND position = 0",
+        );
+        assert_eq!(first, "t2");
+        let count = query_int(
+            db2,
+            "SELECT COUNT(*) FROM playlist_tracks WHERE playlist_id = 'p1'",
+        );
+        assert_eq!(count, 2);
+
+        ffi::sqlite3_close(db1);
+        ffi::sqlite3_close(db2);
+    }
+}
+
 // ---- Changeset from_bytes roundtrip ----
 
 #[test]
@@ -603,16 +858,31 @@ fn release_files_data_conflict_local_wins_no_restore_needed() {
 
 #[test]
 fn synced_tables_constant_has_correct_count() {
-    assert_eq!(SYNCED_TABLES.len(), 11);
+    assert_eq!(SYNCED_TABLES.len(), 26);
     assert!(SYNCED_TABLES.contains(&"artists"));
     assert!(SYNCED_TABLES.contains(&"albums"));
     assert!(SYNCED_TABLES.contains(&"album_discogs"));
     assert!(SYNCED_TABLES.contains(&"album_musicbrainz"));
     assert!(SYNCED_TABLES.contains(&"album_artists"));
+    assert!(SYNCED_TABLES.contains(&"album_versions"));
     assert!(SYNCED_TABLES.contains(&"releases"));
     assert!(SYNCED_TABLES.contains(&"tracks"));
     assert!(SYNCED_TABLES.contains(&"track_artists"));
+    assert!(SYNCED_TABLES.contains(&"track_stats"));
+    assert!(SYNCED_TABLES.contains(&"track_lyrics"));
+    assert!(SYNCED_TABLES.contains(&"track_works"));
+    assert!(SYNCED_TABLES.contains(&"release_discs"));
+    assert!(SYNCED_TABLES.contains(&"genres"));
+    assert!(SYNCED_TABLES.contains(&"album_genres"));
+    assert!(SYNCED_TABLES.contains(&"album_custom_tags"));
+    assert!(SYNCED_TABLES.contains(&"album_notes"));
+    assert!(SYNCED_TABLES.contains(&"album_comments"));
+    assert!(SYNCED_TABLES.contains(&"playlists"));
+    assert!(SYNCED_TABLES.contains(&"playlist_tracks"));
+    assert!(SYNCED_TABLES.contains(&"collections"));
+    assert!(SYNCED_TABLES.contains(&"collection_albums"));
     assert!(SYNCED_TABLES.contains(&"release_files"));
+    assert!(SYNCED_TABLES.contains(&"release_availability"));
     assert!(SYNCED_TABLES.contains(&"audio_formats"));
     assert!(SYNCED_TABLES.contains(&"library_images"));
 
@@ -620,4 +890,7 @@ fn synced_tables_constant_has_correct_count() {
     assert!(!SYNCED_TABLES.contains(&"torrents"));
     assert!(!SYNCED_TABLES.contains(&"torrent_piece_mappings"));
     assert!(!SYNCED_TABLES.contains(&"imports"));
+    assert!(!SYNCED_TABLES.contains(&"import_files"));
+    assert!(!SYNCED_TABLES.contains(&"artist_image_lookups"));
+    assert!(!SYNCED_TABLES.contains(&"cover_upgrade_lookups"));
 }
@@ -0,0 +1,460 @@
+/// Sync verification: replay the bucket into a scratch database and diff it
+/// against the live library.
+///
+/// The replay starts from the latest snapshot (when there is one) and applies
+/// every changeset after it from every device, this one included, exactly as
+/// a freshly joined device would. If sync is healthy, the result matches the
+/// live database row for row. Anything else is reported per table and row:
+/// rows only one side has, and rows whose columns differ.
+///
+/// Edits on this device that haven't been pushed yet show up as divergence,
+/// so run it right after a sync for a meaningful answer.
+use std::collections::BTreeMap;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::path::Path;
+use std::ptr;
+
+use libsqlite3_sys as ffi;
+use tracing::info;
+
+use super::bucket::{BucketError, SyncBucketClient};
+use super::conflict::DEVICE_LOCAL_COLUMNS;
+use super::pull::{pull_changes, PullError};
+use super::session::SYNCED_TABLES;
+use super::snapshot::{bootstrap_from_snapshot, SnapshotError};
+use crate::db::{apply_key, Database, DatabaseKey};
+use crate::encryption::EncryptionService;
+use crate::library_dir::LibraryDir;
+
+/// Device ID passed to the pull so no device's changesets are skipped.
+const REPLAY_DEVICE_ID: &str = "sync-verify";
+
+/// Error type for sync verification.
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+    #[error("snapshot error: {0}")]
+    Snapshot(#[from] SnapshotError),
+    #[error("pull error: {0}")]
+    Pull(#[from] PullError),
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Outcome of a verification run.
+#[derive(Debug)]
+pub struct VerifyReport {
+    /// Changesets applied on top of the snapshot.
+    pub changesets_replayed: u64,
+    /// Tables where the replay and the live database disagree.
+    pub tables: Vec<TableDivergence>,
+}
+
+impl VerifyReport {
+    /// True if the replay matches the live database.
+    pub fn is_clean(&self) -> bool {
+        self.tables.is_empty()
+    }
+}
+
+/// Divergence within one synced table. Rows are identified by their quoted
+/// primary key values, e.g. `'album-1'`.
+#[derive(Debug, Default, PartialEq)]
+pub struct TableDivergence {
+    pub table: String,
+    /// Rows in the live database that the replay doesn't have.
+    pub only_in_live: Vec<String>,
+    /// Rows the replay produced that the live database doesn't have.
+    pub only_in_replay: Vec<String>,
+    /// Rows present on both sides with different values.
+    pub differing: Vec<RowDivergence>,
+}
+
+impl TableDivergence {
+    fn is_empty(&self) -> bool {
+        self.only_in_live.is_empty() && self.only_in_replay.is_empty() && self.differing.is_empty()
+    }
+}
+
+/// A row whose values differ between the live database and the replay.
+#[derive(Debug, PartialEq)]
+pub struct RowDivergence {
+    pub key: String,
+    /// Names of the columns that differ.
+    pub columns: Vec<String>,
+}
+
+/// Replay the bucket into `scratch_dir` and diff the result against the live
+/// database in `library_dir`.
+///
+/// The live database is opened read-only, with `key` if it is encrypted at
+/// rest. Images referenced by replayed
+/// changesets are fetched into `library_dir` only if missing there, the same
+/// as a regular pull would. The scratch database is removed afterwards.
+pub async fn verify(
+    bucket: &dyn SyncBucketClient,
+    encryption: &EncryptionService,
+    library_dir: &LibraryDir,
+    key: Option<&DatabaseKey>,
+    scratch_dir: &Path,
+) -> Result<VerifyReport, VerifyError> {
+    std::fs::create_dir_all(scratch_dir)?;
+    let scratch_path = scratch_dir.join("verify.db");
+    remove_db_files(&scratch_path);
+
+    let result = replay_and_diff(bucket, encryption, library_dir, key, &scratch_path).await;
+    remove_db_files(&scratch_path);
+    result
+}
+
+async fn replay_and_diff(
+    bucket: &dyn SyncBucketClient,
+    encryption: &EncryptionService,
+    library_dir: &LibraryDir,
+    key: Option<&DatabaseKey>,
+    scratch_path: &Path,
+) -> Result<VerifyReport, VerifyError> {
+    let cursors = match bootstrap_from_snapshot(bucket, encryption, scratch_path).await {
+        Ok(result) => result.cursors,
+        // No snapshot yet: replay every changeset from the start.
+        Err(SnapshotError::Bucket(BucketError::NotFound(_))) => Default::default(),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Bring the schema up to date. For a snapshot from this version this is
+    // a no-op; without a snapshot it creates the empty schema.
+    let scratch_str = path_str(scratch_path)?;
+    Database::new(scratch_str)
+        .await
+        .map_err(|e| VerifyError::Database(e.to_string()))?;
+
+    unsafe {
+        let replay = open_db(scratch_path, ffi::SQLITE_OPEN_READWRITE)?;
+        let pulled = pull_changes(
+            replay,
+            bucket,
+            REPLAY_DEVICE_ID,
+            &cursors,
+            None,
+            library_dir,
+        )
+        .await;
+        let (_, pull_result) = match pulled {
+            Ok(pulled) => pulled,
+            Err(e) => {
+                ffi::sqlite3_close(replay);
+                return Err(e.into());
+            }
+        };
+
+        let live = match open_db(&library_dir.db_path(), ffi::SQLITE_OPEN_READONLY) {
+            Ok(live) => live,
+            Err(e) => {
+                ffi::sqlite3_close(replay);
+                return Err(e);
+            }
+        };
+        if let Some(key) = key {
+            if let Err(e) = apply_key(live, key) {
+                ffi::sqlite3_close(live);
+                ffi::sqlite3_close(replay);
+                return Err(VerifyError::Database(e.to_string()));
+            }
+        }
+        let tables = diff_tables(live, replay);
+        ffi::sqlite3_close(live);
+        ffi::sqlite3_close(replay);
+
+        let report = VerifyReport {
+            changesets_replayed: pull_result.changesets_applied,
+            tables: tables?,
+        };
+
+        info!(
+            changesets = report.changesets_replayed,
+            divergent_tables = report.tables.len(),
+            "sync verification finished"
+        );
+
+        Ok(report)
+    }
+}
+
+/// Compare every synced table between two databases with the same schema.
+///
+/// # Safety
+/// `live` and `replay` must be valid, open sqlite3 connection pointers.
+pub unsafe fn diff_tables(
+    live: *mut ffi::sqlite3,
+    replay: *mut ffi::sqlite3,
+) -> Result<Vec<TableDivergence>, VerifyError> {
+    let mut tables = Vec::new();
+
+    for &table in SYNCED_TABLES {
+        let (columns, key_columns) = table_columns(live, table)?;
+        // Partial schemas (tests) may lack some synced tables.
+        if key_columns.is_empty() {
+            continue;
+        }
+
+        let live_rows = read_rows(live, table, &columns, &key_columns)?;
+        let mut replay_rows = read_rows(replay, table, &columns, &key_columns)?;
+
+        let mut divergence = TableDivergence {
+            table: table.to_string(),
+            ..Default::default()
+        };
+
+        for (key, live_values) in live_rows {
+            match replay_rows.remove(&key) {
+                None => divergence.only_in_live.push(key),
+                Some(replay_values) => {
+                    let differing: Vec<String> = columns
+                        .iter()
+                        .zip(live_values.iter().zip(&replay_values))
+                        .filter(|(_, (a, b))| a != b)
+                        .map(|(column, _)| column.clone())
+                        .collect();
+                    if !differing.is_empty() {
+                        divergence.differing.push(RowDivergence {
+                            key,
+                            columns: differing,
+                        });
+                    }
+                }
+            }
+        }
+        divergence.only_in_replay = replay_rows.into_keys().collect();
+
+        if !divergence.is_empty() {
+            tables.push(divergence);
+        }
+    }
+
+    Ok(tables)
+}
+
+/// Compared columns of a table, and the primary key columns in key order.
+unsafe fn table_columns(
+    db: *mut ffi::sqlite3,
+    table: &str,
+) -> Result<(Vec<String>, Vec<String>), VerifyError> {
+    let stmt = prepare(db, &format!("PRAGMA table_info({table})"))?;
+
+    let mut columns = Vec::new();
+    let mut key_columns = Vec::new();
+
+    while ffi::sqlite3_step(stmt) == ffi::SQLITE_ROW as c_int {
+        let name = column_text(stmt, 1).unwrap_or_default();
+        let pk_position = ffi::sqlite3_column_int(stmt, 5);
+
+        if pk_position > 0 {
+            key_columns.push((pk_position, name.clone()));
+        }
+        if !DEVICE_LOCAL_COLUMNS.contains(&(table, name.as_str())) {
+            columns.push(name);
+        }
+    }
+    ffi::sqlite3_finalize(stmt);
+
+    key_columns.sort();
+    Ok((columns, key_columns.into_iter().map(|(_, n)| n).collect()))
+}
+
+/// Read a table as quoted primary key -> quoted column values.
+unsafe fn read_rows(
+    db: *mut ffi::sqlite3,
+    table: &str,
+    columns: &[String],
+    key_columns: &[String],
+) -> Result<BTreeMap<String, Vec<String>>, VerifyError> {
+    let key_expr = key_columns
+        .iter()
+        .map(|c| format!("quote(\"{c}\")"))
+        .collect::<Vec<_>>()
+        .join(" || ',' || ");
+    let value_exprs = columns
+        .iter()
+        .map(|c| format!("quote(\"{c}\")"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let stmt = prepare(
+        db,
+        &format!("SELECT {key_expr}, {value_exprs} FROM \"{table}\""),
+    )?;
+
+    let mut rows = BTreeMap::new();
+    while ffi::sqlite3_step(stmt) == ffi::SQLITE_ROW as c_int {
+        let key = column_text(stmt, 0).unwrap_or_default();
+        let values = (0..columns.len())
+            .map(|i| column_text(stmt, i as c_int + 1).unwrap_or_default())
+            .collect();
+        rows.insert(key, values);
+    }
+    ffi::sqlite3_finalize(stmt);
+
+    Ok(rows)
+}
+
+unsafe fn prepare(db: *mut ffi::sqlite3, sql: &str) -> Result<*mut ffi::sqlite3_stmt, VerifyError> {
+    let c_sql = CString::new(sql).unwrap();
+    let mut stmt: *mut ffi::sqlite3_stmt = ptr::null_mut();
+    let rc = ffi::sqlite3_prepare_v2(db, c_sql.as_ptr(), -1, &mut stmt, ptr::null_mut());
+    if rc != ffi::SQLITE_OK as c_int {
+        return Err(VerifyError::Database(format!("{}: {sql}", errmsg(db))));
+    }
+    Ok(stmt)
+}
+
+unsafe fn column_text(stmt: *mut ffi::sqlite3_stmt, index: c_int) -> Option<String> {
+    let ptr = ffi::sqlite3_column_text(stmt, index);
+    if ptr.is_null() {
+        return None;
+    }
+    Some(
+        CStr::from_ptr(ptr as *const c_char)
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+unsafe fn errmsg(db: *mut ffi::sqlite3) -> String {
+    let err = ffi::sqlite3_errmsg(db);
+    if err.is_null() {
+        "unknown sqlite error".to_string()
+    } else {
+        CStr::from_ptr(err).to_string_lossy().into_owned()
+    }
+}
+
+unsafe fn open_db(path: &Path, flags: c_int) -> Result<*mut ffi::sqlite3, VerifyError> {
+    let c_path = CString::new(path_str(path)?).unwrap();
+    let mut db: *mut ffi::sqlite3 = ptr::null_mut();
+    let rc = ffi::sqlite3_open_v2(c_path.as_ptr(), &mut db, flags, ptr::null());
+    if rc != ffi::SQLITE_OK {
+        let msg = errmsg(db);
+        ffi::sqlite3_close(db);
+        return Err(VerifyError::Database(format!(
+            "failed to open {}: {msg}",
+            path.display()
+        )));
+    }
+    Ok(db)
+}
+
+fn path_str(path: &Path) -> Result<&str, VerifyError> {
+    path.to_str()
+        .ok_or_else(|| VerifyError::Database(format!("invalid path: {}", path.display())))
+}
+
+/// Remove a database file along with its WAL and shared-memory files.
+fn remove_db_files(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    for suffix in ["-wal", "-shm"] {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        let _ = std::fs::remove_file(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::test_helpers::*;
+
+    unsafe fn two_dbs() -> (*mut ffi::sqlite3, *mut ffi::sqlite3) {
+        let live = open_memory_db();
+        let replay = open_memory_db();
+        create_synced_schema(live);
+        create_synced_schema(replay);
+        for db in [live, replay] {
+            exec(
+                db,
+                "INSERT INTO albums (id, title, year, _updated_at, created_at)
+                 VALUES ('a1', 'Glass Harbor', 2001, 't1', 't0')",
+            );
+        }
+        (live, replay)
+    }
+
+    #[test]
+    fn identical_databases_are_clean() {
+        unsafe {
+            let (live, replay) = two_dbs();
+            assert!(diff_tables(live, replay).unwrap().is_empty());
+            ffi::sqlite3_close(live);
+            ffi::sqlite3_close(replay);
+        }
+    }
+
+    #[test]
+    fn reports_missing_extra_and_differing_rows() {
+        unsafe {
+            let (live, replay) = two_dbs();
+            exec(
+                live,
+                "UPDATE albums SET title = 'Iron Meadow' WHERE id = 'a1'",
+            );
+            exec(
+                live,
+                "INSERT INTO artists (id, name, _updated_at, created_at)
+                 VALUES ('r1', 'The Lanterns', 't1', 't0')",
+            );
+            exec(
+                replay,
+                "INSERT INTO artists (id, name, _updated_at, created_at)
+                 VALUES ('r2', 'Velvet Static', 't1', 't0')",
+            );
+
+            let tables = diff_tables(live, replay).unwrap();
+            assert_eq!(
+                tables,
+                vec![
+                    TableDivergence {
+                        table: "artists".to_string(),
+                        only_in_live: vec!["'r1'".to_string()],
+                        only_in_replay: vec!["'r2'".to_string()],
+                        differing: vec![],
+                    },
+                    TableDivergence {
+                        table: "albums".to_string(),
+                        only_in_live: vec![],
+                        only_in_replay: vec![],
+                        differing: vec![RowDivergence {
+                            key: "'a1'".to_string(),
+                            columns: vec!["title".to_string()],
+                        }],
+                    },
+                ]
+            );
+            ffi::sqlite3_close(live);
+            ffi::sqlite3_close(replay);
+        }
+    }
+
+    #[test]
+    fn device_local_columns_are_not_compared() {
+        unsafe {
+            let (live, replay) = two_dbs();
+            for (db, nonce) in [(live, "X'AABB'"), (replay, "X'CCDD'")] {
+                exec(
+                    db,
+                    "INSERT INTO releases (id, album_id, _updated_at, created_at)
+                     VALUES ('r1', 'a1', 't1', 't0')",
+                );
+                exec(
+                    db,
+                    &format!(
+                        "INSERT INTO release_files (id, release_id, original_filename, file_size, content_type, encryption_nonce, _updated_at, created_at)
+                         VALUES ('f1', 'r1', 'track01.flac', 50000, 'audio/flac', {nonce}, 't1', 't0')"
+                    ),
+                );
+            }
+
+            assert!(diff_tables(live, replay).unwrap().is_empty());
+            ffi::sqlite3_close(live);
+            ffi::sqlite3_close(replay);
+        }
+    }
+}
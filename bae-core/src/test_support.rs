@@ -1,11 +1,19 @@
 use crate::cloud_storage::{CloudStorage, CloudStorageError};
+use crate::config::Config;
+use crate::db::{AlbumFilter, Database, DbAlbum, DbRelease, ImportStatus};
+use crate::library_dir::LibraryDir;
+use crate::sync::participation::ParticipationMode;
+use chrono::Utc;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use uuid::Uuid;
 
 /// Mock cloud storage for testing.
-/// Stores files in memory instead of uploading to S3.
+/// Stores files in memory instead of uploading to S3, keyed by the upload key,
+/// which is also the storage location it returns.
 pub struct MockCloudStorage {
-    /// Public for test assertions
+    /// Public for test assertions and for seeding files
     pub files: Mutex<HashMap<String, Vec<u8>>>,
 }
 
@@ -28,17 +36,11 @@ impl MockCloudStorage {
 #[async_trait::async_trait]
 impl CloudStorage for MockCloudStorage {
     async fn upload(&self, key: &str, data: &[u8]) -> Result<String, CloudStorageError> {
-        let location = format!(
-            "s3://test-bucket/files/{}/{}/{}",
-            &key[0..2],
-            &key[2..4],
-            key,
-        );
         self.files
             .lock()
             .unwrap()
-            .insert(location.clone(), data.to_vec());
-        Ok(location)
+            .insert(key.to_string(), data.to_vec());
+        Ok(key.to_string())
     }
 
     async fn download(&self, storage_location: &str) -> Result<Vec<u8>, CloudStorageError> {
@@ -89,3 +91,123 @@ impl CloudStorage for MockCloudStorage {
         Ok(())
     }
 }
+
+/// Open a fresh database in `dir`
+pub async fn test_database(dir: &Path) -> Database {
+    Database::new(dir.join("test.db").to_str().unwrap())
+        .await
+        .unwrap()
+}
+
+/// An album with only the required fields set
+pub fn create_test_album() -> DbAlbum {
+    DbAlbum {
+        id: Uuid::new_v4().to_string(),
+        title: "Test Album".to_string(),
+        year: Some(2024),
+        discogs_release: None,
+        musicbrainz_release: None,
+        bandcamp_album_id: None,
+        cover_release_id: None,
+        is_compilation: false,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// A completed release of `album_id` with no storage configured
+pub fn create_test_release(album_id: &str) -> DbRelease {
+    DbRelease {
+        id: Uuid::new_v4().to_string(),
+        album_id: album_id.to_string(),
+        release_name: None,
+        year: Some(2024),
+        discogs_release_id: None,
+        bandcamp_release_id: None,
+        format: None,
+        label: None,
+        catalog_number: None,
+        country: None,
+        barcode: None,
+        import_status: ImportStatus::Complete,
+        managed_locally: false,
+        managed_in_cloud: false,
+        unmanaged_path: None,
+        private: false,
+        unverified: false,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// A config for a library at `library_path` with every setting at its default
+pub fn make_test_config(library_id: &str, library_path: PathBuf) -> Config {
+    Config {
+        library_id: library_id.to_string(),
+        device_id: "test-device-id".to_string(),
+        library_dir: LibraryDir::new(library_path),
+        library_name: None,
+        keys_migrated: true,
+        discogs_key_stored: false,
+        fanart_key_stored: false,
+        encryption_key_stored: false,
+        encryption_key_fingerprint: None,
+        key_rotation_in_progress: false,
+        security_key: None,
+        encrypt_database: false,
+        torrent_bind_interface: None,
+        torrent_listen_port: None,
+        torrent_enable_upnp: true,
+        torrent_enable_natpmp: true,
+        torrent_enable_dht: false,
+        torrent_max_connections: None,
+        torrent_max_connections_per_torrent: None,
+        torrent_max_uploads: None,
+        torrent_max_uploads_per_torrent: None,
+        network_participation: ParticipationMode::Off,
+        server_enabled: true,
+        server_port: 4533,
+        server_bind_address: "127.0.0.1".to_string(),
+        server_auth_enabled: false,
+        server_username: None,
+        server_snapshot_interval_hours: 24,
+        server_snapshot_retention_days: 30,
+        server_sync_interval_secs: 30,
+        cloud_provider: None,
+        cloud_home_s3_bucket: None,
+        cloud_home_s3_region: None,
+        cloud_home_s3_endpoint: None,
+        cloud_home_s3_key_prefix: None,
+        cloud_home_s3_audio_storage_class: None,
+        cloud_home_google_drive_folder_id: None,
+        cloud_home_dropbox_folder_path: None,
+        cloud_home_onedrive_drive_id: None,
+        cloud_home_onedrive_folder_id: None,
+        cloud_home_icloud_container_path: None,
+        cloud_home_folder_path: None,
+        cloud_home_bae_cloud_url: None,
+        cloud_home_bae_cloud_username: None,
+        share_base_url: None,
+        followed_libraries: vec![],
+        import_ignore_patterns: vec![],
+        import_min_audio_duration_secs: None,
+        import_network_share_url: None,
+        import_network_share_username: None,
+        download_parallelism: None,
+        readahead_mib: None,
+        encryption_chunk_size_kib: None,
+        encrypt_images: true,
+        offload_after_days: None,
+        cloud_prices: None,
+        export_name_template: None,
+        musicbrainz_username: None,
+        musicbrainz_collection_id: None,
+        pause_on_sleep: true,
+        pause_on_screen_lock: false,
+        pause_during_calls: true,
+        duck_for_other_audio: false,
+        auto_upgrade_covers: false,
+        library_sort: vec![],
+        library_filter: AlbumFilter::default(),
+    }
+}
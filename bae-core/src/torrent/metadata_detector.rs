@@ -2,7 +2,7 @@
 //!
 //! This module provides functionality to quickly download and analyze CUE/log files
 //! from torrents for automatic release matching, separate from the main import flow.
-use crate::import::{detect_metadata, FolderMetadata};
+use crate::import::{detect_metadata, FolderMetadata, ImportFilters};
 use crate::torrent::client::{FilePriority, TorrentHandle};
 use crate::torrent::progress::TorrentProgress;
 use std::path::PathBuf;
@@ -141,7 +141,8 @@ pub async fn detect_metadata_from_torrent_file(
     let torrent_name = handle.name().await?;
     let save_dir = temp_path.join(&torrent_name);
     if save_dir.exists() {
-        match detect_metadata(save_dir.clone()) {
+        // Torrent contents are fixed; filters only apply to folder imports
+        match detect_metadata(save_dir.clone(), &ImportFilters::default()) {
             Ok(metadata) => {
                 info!("Successfully detected metadata from torrent CUE/log files");
                 Ok(Some(metadata))
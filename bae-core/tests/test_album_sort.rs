@@ -53,6 +53,7 @@ fn make_album_artist(album_id: &str, artist_id: &str, position: i32) -> DbAlbumA
         album_id: album_id.to_string(),
         artist_id: artist_id.to_string(),
         position,
+        join_phrase: None,
         created_at: now,
         updated_at: now,
     }
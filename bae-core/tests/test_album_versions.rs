@@ -0,0 +1,193 @@
+#![cfg(feature = "test-utils")]
+mod support;
+use bae_core::db::{Database, DbAlbum};
+use chrono::{Duration, Utc};
+use tempfile::TempDir;
+use uuid::Uuid;
+
+async fn setup_db() -> (Database, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let database = Database::new(db_path.to_str().unwrap())
+        .await
+        .expect("Failed to create database");
+    (database, temp_dir)
+}
+
+fn make_album(title: &str, year: Option<i32>, created_offset_hours: i64) -> DbAlbum {
+    let now = Utc::now();
+    DbAlbum {
+        id: Uuid::new_v4().to_string(),
+        title: title.to_string(),
+        year,
+        discogs_release: None,
+        musicbrainz_release: None,
+        bandcamp_album_id: None,
+        cover_release_id: None,
+        is_compilation: false,
+        created_at: now + Duration::hours(created_offset_hours),
+        updated_at: now,
+    }
+}
+
+#[tokio::test]
+async fn test_unlinked_album_has_no_versions() {
+    let (db, _dir) = setup_db().await;
+
+    let album = make_album("Album Title", Some(2000), 0);
+    db.insert_album(&album).await.unwrap();
+
+    assert!(db.get_album_versions(&album.id).await.unwrap().is_empty());
+    assert!(db.get_album_version_groups().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_link_two_albums_ordered_by_year() {
+    let (db, _dir) = setup_db().await;
+
+    let remaster = make_album("Album Title (Remaster)", Some(2015), 0);
+    let original = make_album("Album Title", Some(1990), 1);
+    db.insert_album(&remaster).await.unwrap();
+    db.insert_album(&original).await.unwrap();
+
+    db.link_album_versions(&remaster.id, &original.id)
+        .await
+        .unwrap();
+
+    let versions = db.get_album_versions(&remaster.id).await.unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0].album_id, original.id);
+    assert_eq!(versions[1].album_id, remaster.id);
+    assert_eq!(versions[0].version_group_id, versions[1].version_group_id);
+
+    // Same group is visible from either side
+    let from_original = db.get_album_versions(&original.id).await.unwrap();
+    assert_eq!(from_original.len(), 2);
+}
+
+#[tokio::test]
+async fn test_link_album_to_itself_is_rejected() {
+    let (db, _dir) = setup_db().await;
+
+    let album = make_album("Album Title", Some(2000), 0);
+    db.insert_album(&album).await.unwrap();
+
+    assert!(db.link_album_versions(&album.id, &album.id).await.is_err());
+    assert!(db.get_album_versions(&album.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_linking_joins_existing_group() {
+    let (db, _dir) = setup_db().await;
+
+    let a = make_album("Album Title", Some(1990), 0);
+    let b = make_album("Album Title (Remaster)", Some(2010), 1);
+    let c = make_album("Album Title (Deluxe)", Some(2020), 2);
+    for album in [&a, &b, &c] {
+        db.insert_album(album).await.unwrap();
+    }
+
+    db.link_album_versions(&a.id, &b.id).await.unwrap();
+    db.link_album_versions(&c.id, &b.id).await.unwrap();
+
+    let versions = db.get_album_versions(&c.id).await.unwrap();
+    assert_eq!(versions.len(), 3);
+
+    let groups = db.get_album_version_groups().await.unwrap();
+    assert_eq!(groups[&a.id], groups[&c.id]);
+}
+
+#[tokio::test]
+async fn test_linking_merges_two_groups() {
+    let (db, _dir) = setup_db().await;
+
+    let a = make_album("A", Some(1990), 0);
+    let b = make_album("B", Some(1995), 1);
+    let c = make_album("C", Some(2000), 2);
+    let d = make_album("D", Some(2005), 3);
+    for album in [&a, &b, &c, &d] {
+        db.insert_album(album).await.unwrap();
+    }
+
+    db.link_album_versions(&a.id, &b.id).await.unwrap();
+    db.link_album_versions(&c.id, &d.id).await.unwrap();
+    db.link_album_versions(&b.id, &d.id).await.unwrap();
+
+    let groups = db.get_album_version_groups().await.unwrap();
+    assert_eq!(groups.len(), 4);
+    assert!(groups.values().all(|g| *g == groups[&a.id]));
+}
+
+#[tokio::test]
+async fn test_unlink_dissolves_two_member_group() {
+    let (db, _dir) = setup_db().await;
+
+    let a = make_album("Album Title", Some(1990), 0);
+    let b = make_album("Album Title (Remaster)", Some(2010), 1);
+    db.insert_album(&a).await.unwrap();
+    db.insert_album(&b).await.unwrap();
+
+    db.link_album_versions(&a.id, &b.id).await.unwrap();
+    db.unlink_album_version(&b.id).await.unwrap();
+
+    assert!(db.get_album_versions(&a.id).await.unwrap().is_empty());
+    assert!(db.get_album_version_groups().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_unlink_keeps_remaining_group() {
+    let (db, _dir) = setup_db().await;
+
+    let a = make_album("A", Some(1990), 0);
+    let b = make_album("B", Some(2000), 1);
+    let c = make_album("C", Some(2010), 2);
+    for album in [&a, &b, &c] {
+        db.insert_album(album).await.unwrap();
+    }
+
+    db.link_album_versions(&a.id, &b.id).await.unwrap();
+    db.link_album_versions(&a.id, &c.id).await.unwrap();
+    db.unlink_album_version(&c.id).await.unwrap();
+
+    let versions = db.get_album_versions(&a.id).await.unwrap();
+    assert_eq!(versions.len(), 2);
+    assert!(db.get_album_versions(&c.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_version_label() {
+    let (db, _dir) = setup_db().await;
+
+    let a = make_album("Album Title", Some(1990), 0);
+    let b = make_album("Album Title", Some(2010), 1);
+    db.insert_album(&a).await.unwrap();
+    db.insert_album(&b).await.unwrap();
+
+    db.link_album_versions(&a.id, &b.id).await.unwrap();
+    db.set_album_version_label(&b.id, Some("Remaster"))
+        .await
+        .unwrap();
+
+    let versions = db.get_album_versions(&a.id).await.unwrap();
+    assert_eq!(versions[0].label, None);
+    assert_eq!(versions[1].label.as_deref(), Some("Remaster"));
+}
+
+#[tokio::test]
+async fn test_deleting_album_removes_its_version_link() {
+    let (db, _dir) = setup_db().await;
+
+    let a = make_album("A", Some(1990), 0);
+    let b = make_album("B", Some(2000), 1);
+    let c = make_album("C", Some(2010), 2);
+    for album in [&a, &b, &c] {
+        db.insert_album(album).await.unwrap();
+    }
+
+    db.link_album_versions(&a.id, &b.id).await.unwrap();
+    db.link_album_versions(&a.id, &c.id).await.unwrap();
+    db.delete_album(&c.id).await.unwrap();
+
+    let versions = db.get_album_versions(&a.id).await.unwrap();
+    assert_eq!(versions.len(), 2);
+}
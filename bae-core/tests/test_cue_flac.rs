@@ -63,6 +63,7 @@ async fn test_cue_flac_records_track_positions() {
             folder: album_dir,
             master_year: 2024,
             managed: false,
+            move_to_cloud: false,
             selected_cover: None,
         })
         .await
@@ -213,6 +214,7 @@ async fn test_cue_flac_playback_uses_track_positions() {
             folder: album_dir,
             master_year: 2024,
             managed: false,
+            move_to_cloud: false,
             selected_cover: None,
         })
         .await
@@ -368,6 +370,7 @@ async fn test_cue_flac_decoded_duration_matches_cue_timing() {
             folder: album_dir,
             master_year: 2024,
             managed: false,
+            move_to_cloud: false,
             selected_cover: None,
         })
         .await
@@ -508,6 +511,7 @@ async fn test_cue_flac_byte_ranges_have_no_gaps() {
             folder: album_dir,
             master_year: 2024,
             managed: false,
+            move_to_cloud: false,
             selected_cover: None,
         })
         .await
@@ -662,6 +666,7 @@ async fn test_cue_flac_builds_dense_seektable() {
             folder: album_dir.clone(),
             master_year: 2024,
             managed: false,
+            move_to_cloud: false,
             selected_cover: None,
         })
         .await
@@ -1438,6 +1443,7 @@ async fn test_subsonic_stream_cue_flac_track2_correct_range() {
             folder: album_dir.clone(),
             master_year: 2024,
             managed: false,
+            move_to_cloud: false,
             selected_cover: None,
         })
         .await
@@ -1499,6 +1505,8 @@ async fn test_subsonic_stream_cue_flac_track2_correct_range() {
             username: None,
             password: None,
         },
+        jukebox: None,
+        hls: Default::default(),
     };
 
     let (audio_data, content_type) = stream_track_audio(&state, &track2.id)
@@ -1640,16 +1648,19 @@ fn create_test_discogs_release() -> DiscogsRelease {
                 position: "1".to_string(),
                 title: "Track One (440Hz)".to_string(),
                 duration: Some("0:10".to_string()),
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "2".to_string(),
                 title: "Track Two (880Hz)".to_string(),
                 duration: Some("0:10".to_string()),
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "3".to_string(),
                 title: "Track Three (660Hz)".to_string(),
                 duration: Some("0:10".to_string()),
+                artists: vec![],
             },
         ],
         master_id: Some("test-master".to_string()),
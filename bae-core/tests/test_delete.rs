@@ -54,6 +54,7 @@ fn create_test_release(album_id: &str) -> DbRelease {
         managed_in_cloud: false,
         unmanaged_path: None,
         private: false,
+        unverified: false,
         created_at: Utc::now(),
         updated_at: Utc::now(),
     }
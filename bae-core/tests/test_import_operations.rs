@@ -15,6 +15,7 @@
 //! - Stuck imports (preparing with no release_id)
 //! - Clearing/dismissing imports from the UI
 //! - App restart loading active imports from DB
+//! - Per-file state that lets an interrupted import resume
 
 use bae_core::db::{Database, DbImport, ImportOperationStatus};
 use tempfile::TempDir;
@@ -327,3 +328,119 @@ async fn test_active_imports_ordered_by_created_at_desc() {
     assert_eq!(active[1].id, "second");
     assert_eq!(active[2].id, "first");
 }
+
+/// Test that managed flag and cover path survive a round trip, so an
+/// interrupted import can be rebuilt after restart.
+#[tokio::test]
+async fn test_import_resume_fields_persisted() {
+    tracing_init();
+    let (db, _temp) = create_test_db().await;
+
+    let mut import = DbImport::new(
+        "resume-fields",
+        "Low Tide",
+        "Harbor Lights",
+        "/music/low-tide",
+    );
+    import.managed = true;
+    import.cover_image_path = Some("/music/low-tide/cover.jpg".to_string());
+    db.insert_import(&import).await.unwrap();
+
+    let retrieved = db.get_import("resume-fields").await.unwrap().unwrap();
+    assert!(retrieved.managed);
+    assert_eq!(
+        retrieved.cover_image_path.as_deref(),
+        Some("/music/low-tide/cover.jpg")
+    );
+}
+
+/// Test per-file import state: files start pending and are marked stored
+/// one at a time as they are written.
+#[tokio::test]
+async fn test_import_files_track_stored_state() {
+    tracing_init();
+    let (db, _temp) = create_test_db().await;
+
+    let import = DbImport::new("file-state", "Low Tide", "Harbor Lights", "/music/low-tide");
+    db.insert_import(&import).await.unwrap();
+    let paths = vec![
+        "/music/low-tide/01.flac".to_string(),
+        "/music/low-tide/02.flac".to_string(),
+    ];
+    db.insert_import_files("file-state", &paths).await.unwrap();
+
+    let files = db.get_import_files("file-state").await.unwrap();
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|f| !f.stored));
+
+    db.mark_import_file_stored("file-state", "/music/low-tide/01.flac")
+        .await
+        .unwrap();
+
+    let files = db.get_import_files("file-state").await.unwrap();
+    let stored: Vec<&str> = files
+        .iter()
+        .filter(|f| f.stored)
+        .map(|f| f.source_path.as_str())
+        .collect();
+    assert_eq!(stored, vec!["/music/low-tide/01.flac"]);
+
+    // Recording the same files again keeps existing state
+    db.insert_import_files("file-state", &paths).await.unwrap();
+    let files = db.get_import_files("file-state").await.unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files.iter().filter(|f| f.stored).count(), 1);
+}
+
+/// Test that dismissing an import also removes its per-file state.
+#[tokio::test]
+async fn test_delete_import_removes_import_files() {
+    tracing_init();
+    let (db, _temp) = create_test_db().await;
+
+    let import = DbImport::new(
+        "file-cascade",
+        "Low Tide",
+        "Harbor Lights",
+        "/music/low-tide",
+    );
+    db.insert_import(&import).await.unwrap();
+    db.insert_import_files("file-cascade", &["/music/low-tide/01.flac".to_string()])
+        .await
+        .unwrap();
+
+    db.delete_import("file-cascade").await.unwrap();
+
+    assert!(db
+        .get_import_files("file-cascade")
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// Test that finishing an import kept in the cloud home queues the move there,
+/// and that other imports queue nothing.
+#[tokio::test]
+async fn test_finalize_import_queues_cloud_move() {
+    tracing_init();
+    let (db, _temp) = create_test_db().await;
+
+    let mut import = DbImport::new("to-cloud", "Low Tide", "Harbor Lights", "/music/low-tide");
+    import.managed = true;
+    import.move_to_cloud = true;
+    db.insert_import(&import).await.unwrap();
+    let local = DbImport::new("local", "Tidewater", "Glass Harbor", "/music/tidewater");
+    db.insert_import(&local).await.unwrap();
+
+    db.finalize_import(&[], &[], "release-cloud", Some("to-cloud"))
+        .await
+        .unwrap();
+    db.finalize_import(&[], &[], "release-local", Some("local"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        db.get_storage_transfers().await.unwrap(),
+        vec![("release-cloud".to_string(), "cloud".to_string(), None)]
+    );
+}
@@ -64,6 +64,7 @@ impl PlaybackTestFixture {
                 folder: album_dir.clone(),
                 master_year,
                 managed: false,
+                move_to_cloud: false,
                 selected_cover: None,
             })
             .await?;
@@ -195,17 +196,20 @@ fn create_test_album() -> DiscogsRelease {
         artists: vec![DiscogsArtist {
             name: "Test Artist".to_string(),
             id: "test-artist-1".to_string(),
+            join: None,
         }],
         tracklist: vec![
             DiscogsTrack {
                 position: "1".to_string(),
                 title: "Test Track 1".to_string(),
                 duration: Some("0:10".to_string()),
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "2".to_string(),
                 title: "Test Track 2".to_string(),
                 duration: Some("0:10".to_string()),
+                artists: vec![],
             },
         ],
         master_id: Some("test-master-123".to_string()),
@@ -297,22 +301,26 @@ fn create_cue_flac_test_album() -> DiscogsRelease {
         artists: vec![DiscogsArtist {
             name: "Test Artist".to_string(),
             id: "test-artist-1".to_string(),
+            join: None,
         }],
         tracklist: vec![
             DiscogsTrack {
                 position: "1".to_string(),
                 title: "Track One (Silence)".to_string(),
                 duration: Some("0:10".to_string()),
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "2".to_string(),
                 title: "Track Two (White Noise)".to_string(),
                 duration: Some("0:10".to_string()),
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "3".to_string(),
                 title: "Track Three (Brown Noise)".to_string(),
                 duration: Some("0:10".to_string()),
+                artists: vec![],
             },
         ],
         master_id: Some("test-master-cue-flac".to_string()),
@@ -378,6 +386,7 @@ impl CueFlacTestFixture {
                 folder: album_dir.clone(),
                 master_year,
                 managed: false, // No storage - direct local playback
+                move_to_cloud: false,
                 selected_cover: None,
             })
             .await?;
@@ -1986,11 +1995,13 @@ impl HighSampleRateTestFixture {
             artists: vec![DiscogsArtist {
                 name: "Test Artist".to_string(),
                 id: "test-artist-1".to_string(),
+                join: None,
             }],
             tracklist: vec![DiscogsTrack {
                 position: "1".to_string(),
                 title: "96kHz Track".to_string(),
                 duration: Some("0:03".to_string()),
+                artists: vec![],
             }],
             master_id: Some("test-master-96khz".to_string()),
             is_compilation: false,
@@ -2014,6 +2025,7 @@ impl HighSampleRateTestFixture {
                 folder: album_dir.clone(),
                 master_year: 2024,
                 managed: false, // Local playback
+                move_to_cloud: false,
                 selected_cover: None,
             })
             .await?;
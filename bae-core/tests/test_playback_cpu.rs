@@ -113,22 +113,26 @@ fn create_cue_flac_test_album() -> DiscogsRelease {
         artists: vec![DiscogsArtist {
             name: "Test Artist".to_string(),
             id: "test-artist-1".to_string(),
+            join: None,
         }],
         tracklist: vec![
             DiscogsTrack {
                 position: "1".to_string(),
                 title: "Track One".to_string(),
                 duration: Some("1:40".to_string()), // 100 seconds
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "2".to_string(),
                 title: "Track Two".to_string(),
                 duration: Some("1:40".to_string()), // 100 seconds
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "3".to_string(),
                 title: "Track Three".to_string(),
                 duration: Some("1:40".to_string()), // 100 seconds
+                artists: vec![],
             },
         ],
         master_id: Some("test-master".to_string()),
@@ -194,6 +198,7 @@ impl CueFlacTestFixture {
                 folder: album_dir.clone(),
                 master_year,
                 managed: false,
+                move_to_cloud: false,
                 selected_cover: None,
             })
             .await?;
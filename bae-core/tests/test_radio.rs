@@ -0,0 +1,237 @@
+#![cfg(feature = "test-utils")]
+mod support;
+use bae_core::db::{Database, DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack, ImportStatus};
+use chrono::Utc;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+async fn setup_db() -> (Database, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let database = Database::new(db_path.to_str().unwrap())
+        .await
+        .expect("Failed to create database");
+    (database, temp_dir)
+}
+
+async fn insert_artist(db: &Database, name: &str) -> String {
+    let now = Utc::now();
+    let artist = DbArtist {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        sort_name: None,
+        discogs_artist_id: None,
+        bandcamp_artist_id: None,
+        musicbrainz_artist_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    db.insert_artist(&artist).await.unwrap();
+    artist.id
+}
+
+/// Insert an album with one release and one track per status, returning the track IDs.
+async fn insert_album(
+    db: &Database,
+    title: &str,
+    year: Option<i32>,
+    artist_ids: &[&str],
+    track_statuses: &[ImportStatus],
+) -> (String, Vec<String>) {
+    let now = Utc::now();
+    let album = DbAlbum {
+        id: Uuid::new_v4().to_string(),
+        title: title.to_string(),
+        year,
+        discogs_release: None,
+        musicbrainz_release: None,
+        bandcamp_album_id: None,
+        cover_release_id: None,
+        is_compilation: false,
+        created_at: now,
+        updated_at: now,
+    };
+    db.insert_album(&album).await.unwrap();
+
+    for (position, artist_id) in artist_ids.iter().enumerate() {
+        db.insert_album_artist(&DbAlbumArtist {
+            id: Uuid::new_v4().to_string(),
+            album_id: album.id.clone(),
+            artist_id: artist_id.to_string(),
+            position: position as i32,
+            join_phrase: None,
+            created_at: now,
+            updated_at: now,
+        })
+        .await
+        .unwrap();
+    }
+
+    let release = DbRelease {
+        id: Uuid::new_v4().to_string(),
+        album_id: album.id.clone(),
+        release_name: None,
+        year,
+        discogs_release_id: None,
+        bandcamp_release_id: None,
+        format: None,
+        label: None,
+        catalog_number: None,
+        country: None,
+        barcode: None,
+        import_status: ImportStatus::Complete,
+        managed_locally: false,
+        managed_in_cloud: false,
+        unmanaged_path: None,
+        private: false,
+        unverified: false,
+        created_at: now,
+        updated_at: now,
+    };
+    db.insert_release(&release).await.unwrap();
+
+    let mut track_ids = Vec::new();
+    for (index, status) in track_statuses.iter().enumerate() {
+        let track = DbTrack {
+            id: Uuid::new_v4().to_string(),
+            release_id: release.id.clone(),
+            title: format!("{} {}", title, index + 1),
+            disc_number: None,
+            track_number: Some(index as i32 + 1),
+            duration_ms: None,
+            discogs_position: None,
+            import_status: *status,
+            updated_at: now,
+            created_at: now,
+        };
+        db.insert_track(&track).await.unwrap();
+        track_ids.push(track.id);
+    }
+
+    (album.id, track_ids)
+}
+
+#[tokio::test]
+async fn test_radio_ranks_by_artist_then_adjacency_then_era() {
+    let (db, _dir) = setup_db().await;
+
+    let glass = insert_artist(&db, "Glass Harbor").await;
+    let tide = insert_artist(&db, "Tidewater").await;
+    let other = insert_artist(&db, "Lantern Field").await;
+
+    let (_, seed) = insert_album(
+        &db,
+        "Seed",
+        Some(2000),
+        &[&glass],
+        &[ImportStatus::Complete, ImportStatus::Complete],
+    )
+    .await;
+    let (_, same_artist) = insert_album(
+        &db,
+        "Same",
+        Some(1980),
+        &[&glass],
+        &[ImportStatus::Complete],
+    )
+    .await;
+    let (_, collab) = insert_album(
+        &db,
+        "Collab",
+        Some(1960),
+        &[&glass, &tide],
+        &[ImportStatus::Complete],
+    )
+    .await;
+    let (_, adjacent) = insert_album(
+        &db,
+        "Adjacent",
+        Some(1970),
+        &[&tide],
+        &[ImportStatus::Complete],
+    )
+    .await;
+    let (_, same_era) =
+        insert_album(&db, "Era", Some(2003), &[&other], &[ImportStatus::Complete]).await;
+    let (_, unrelated) =
+        insert_album(&db, "Far", Some(1950), &[&other], &[ImportStatus::Complete]).await;
+
+    let candidates = db.get_radio_candidates(&seed[0], 10).await.unwrap();
+
+    // Seed album tracks are never offered
+    assert_eq!(candidates.len(), 5);
+    assert!(!candidates.contains(&seed[1]));
+
+    // Both albums by the seed's artist come first, in either order
+    let mut top = candidates[..2].to_vec();
+    top.sort();
+    let mut expected = vec![same_artist[0].clone(), collab[0].clone()];
+    expected.sort();
+    assert_eq!(top, expected);
+
+    assert_eq!(candidates[2], adjacent[0]);
+    assert_eq!(candidates[3], same_era[0]);
+    assert_eq!(candidates[4], unrelated[0]);
+}
+
+#[tokio::test]
+async fn test_radio_skips_unimported_tracks_and_linked_versions() {
+    let (db, _dir) = setup_db().await;
+
+    let artist = insert_artist(&db, "Glass Harbor").await;
+
+    let (seed_album, seed) = insert_album(
+        &db,
+        "Seed",
+        Some(2000),
+        &[&artist],
+        &[ImportStatus::Complete],
+    )
+    .await;
+    let (remaster, _) = insert_album(
+        &db,
+        "Seed (Remaster)",
+        Some(2015),
+        &[&artist],
+        &[ImportStatus::Complete],
+    )
+    .await;
+    let (_, pending) = insert_album(
+        &db,
+        "Pending",
+        Some(2001),
+        &[&artist],
+        &[ImportStatus::Queued, ImportStatus::Complete],
+    )
+    .await;
+
+    db.link_album_versions(&seed_album, &remaster)
+        .await
+        .unwrap();
+
+    let candidates = db.get_radio_candidates(&seed[0], 10).await.unwrap();
+    assert_eq!(candidates, vec![pending[1].clone()]);
+}
+
+#[tokio::test]
+async fn test_radio_respects_limit() {
+    let (db, _dir) = setup_db().await;
+
+    let artist = insert_artist(&db, "Glass Harbor").await;
+    let (_, seed) = insert_album(&db, "Seed", None, &[&artist], &[ImportStatus::Complete]).await;
+    insert_album(
+        &db,
+        "Other",
+        None,
+        &[&artist],
+        &[
+            ImportStatus::Complete,
+            ImportStatus::Complete,
+            ImportStatus::Complete,
+        ],
+    )
+    .await;
+
+    let candidates = db.get_radio_candidates(&seed[0], 2).await.unwrap();
+    assert_eq!(candidates.len(), 2);
+}
@@ -0,0 +1,254 @@
+#![cfg(feature = "test-utils")]
+mod support;
+use bae_core::db::{
+    Database, DbAlbum, DbAlbumArtist, DbArtist, DbRelease, DbTrack, ImportStatus,
+    MusicBrainzRelease,
+};
+use bae_core::import::server_migration::{
+    merge_remote_library, MigrationSummary, RemoteAlbum, RemoteLibrary, RemotePlaylist, RemoteTrack,
+};
+use chrono::{TimeZone, Utc};
+use tempfile::TempDir;
+use uuid::Uuid;
+
+async fn setup_db() -> (Database, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("test.db");
+    let database = Database::new(db_path.to_str().unwrap())
+        .await
+        .expect("Failed to create database");
+    (database, temp_dir)
+}
+
+/// Insert an album by one artist with one release, returning the track IDs in order.
+async fn insert_album(
+    db: &Database,
+    artist_name: &str,
+    title: &str,
+    musicbrainz_release: Option<MusicBrainzRelease>,
+    track_titles: &[&str],
+) -> Vec<String> {
+    let now = Utc::now();
+    let artist = DbArtist {
+        id: Uuid::new_v4().to_string(),
+        name: artist_name.to_string(),
+        sort_name: None,
+        discogs_artist_id: None,
+        bandcamp_artist_id: None,
+        musicbrainz_artist_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    db.insert_artist(&artist).await.unwrap();
+
+    let album = DbAlbum {
+        id: Uuid::new_v4().to_string(),
+        title: title.to_string(),
+        year: Some(2001),
+        discogs_release: None,
+        musicbrainz_release,
+        bandcamp_album_id: None,
+        cover_release_id: None,
+        is_compilation: false,
+        created_at: now,
+        updated_at: now,
+    };
+    db.insert_album(&album).await.unwrap();
+    db.insert_album_artist(&DbAlbumArtist::new(&album.id, &artist.id, 0))
+        .await
+        .unwrap();
+
+    let release = DbRelease {
+        id: Uuid::new_v4().to_string(),
+        album_id: album.id.clone(),
+        release_name: None,
+        year: Some(2001),
+        discogs_release_id: None,
+        bandcamp_release_id: None,
+        format: None,
+        label: None,
+        catalog_number: None,
+        country: None,
+        barcode: None,
+        import_status: ImportStatus::Complete,
+        managed_locally: false,
+        managed_in_cloud: false,
+        unmanaged_path: None,
+        private: false,
+        unverified: false,
+        created_at: now,
+        updated_at: now,
+    };
+    db.insert_release(&release).await.unwrap();
+
+    let mut track_ids = Vec::new();
+    for (index, track_title) in track_titles.iter().enumerate() {
+        let track = DbTrack {
+            id: Uuid::new_v4().to_string(),
+            release_id: release.id.clone(),
+            title: track_title.to_string(),
+            disc_number: Some(1),
+            track_number: Some(index as i32 + 1),
+            duration_ms: None,
+            discogs_position: None,
+            import_status: ImportStatus::Complete,
+            updated_at: now,
+            created_at: now,
+        };
+        db.insert_track(&track).await.unwrap();
+        track_ids.push(track.id);
+    }
+    track_ids
+}
+
+fn remote_track(remote_id: &str, title: &str, play_count: i64, rating: Option<i32>) -> RemoteTrack {
+    RemoteTrack {
+        remote_id: remote_id.to_string(),
+        title: title.to_string(),
+        disc_number: Some(1),
+        track_number: None,
+        play_count,
+        rating,
+        last_played_at: Some(Utc.with_ymd_and_hms(2024, 3, 1, 20, 0, 0).unwrap()),
+    }
+}
+
+fn remote_library() -> RemoteLibrary {
+    RemoteLibrary {
+        albums: vec![
+            RemoteAlbum {
+                artist: "Unrelated Name".to_string(),
+                title: "Unrelated Title".to_string(),
+                year: None,
+                musicbrainz_id: Some("mb-release-1".to_string()),
+                tracks: vec![remote_track("r1", "Coastal", 12, Some(5))],
+            },
+            RemoteAlbum {
+                artist: "The Tidewater".to_string(),
+                title: "Low Tide (Remastered)".to_string(),
+                year: None,
+                musicbrainz_id: None,
+                tracks: vec![
+                    remote_track("r2", "Undertow", 3, None),
+                    remote_track("r3", "Slack Water", 0, None),
+                ],
+            },
+            RemoteAlbum {
+                artist: "Lantern Field".to_string(),
+                title: "Not Imported".to_string(),
+                year: None,
+                musicbrainz_id: None,
+                tracks: vec![remote_track("r4", "Missing", 7, None)],
+            },
+        ],
+        playlists: vec![
+            RemotePlaylist {
+                name: "Evening".to_string(),
+                track_ids: vec!["r3".to_string(), "r4".to_string(), "r1".to_string()],
+            },
+            RemotePlaylist {
+                name: "Only Missing".to_string(),
+                track_ids: vec!["r4".to_string()],
+            },
+        ],
+    }
+}
+
+#[tokio::test]
+async fn test_merge_matches_by_mbid_and_tags() {
+    let (db, _dir) = setup_db().await;
+
+    let by_mbid = insert_album(
+        &db,
+        "Glass Harbor",
+        "Pacific Standard",
+        Some(MusicBrainzRelease {
+            release_group_id: "mb-group-1".to_string(),
+            release_id: "mb-release-1".to_string(),
+        }),
+        &["Coastal", "Tide Pool"],
+    )
+    .await;
+    let by_tags = insert_album(
+        &db,
+        "Tidewater",
+        "Low Tide",
+        None,
+        &["Undertow", "Slack Water"],
+    )
+    .await;
+
+    let summary = merge_remote_library(&db, &remote_library()).await.unwrap();
+
+    assert_eq!(
+        summary,
+        MigrationSummary {
+            albums_matched: 2,
+            albums_unmatched: 1,
+            tracks_updated: 2,
+            playlists_imported: 1,
+            playlist_entries_skipped: 2,
+        }
+    );
+
+    let stats = db.get_track_stats(&by_mbid[0]).await.unwrap().unwrap();
+    assert_eq!(stats.play_count, 12);
+    assert_eq!(stats.rating, Some(5));
+    assert_eq!(
+        stats.last_played_at,
+        Some(Utc.with_ymd_and_hms(2024, 3, 1, 20, 0, 0).unwrap())
+    );
+    assert!(db.get_track_stats(&by_mbid[1]).await.unwrap().is_none());
+
+    let stats = db.get_track_stats(&by_tags[0]).await.unwrap().unwrap();
+    assert_eq!(stats.play_count, 3);
+    assert_eq!(stats.rating, None);
+    // Never played and unrated, so nothing to record
+    assert!(db.get_track_stats(&by_tags[1]).await.unwrap().is_none());
+
+    let playlists = db.get_playlists().await.unwrap();
+    assert_eq!(playlists.len(), 1);
+    assert_eq!(playlists[0].name, "Evening");
+    assert_eq!(
+        db.get_playlist_track_ids(&playlists[0].id).await.unwrap(),
+        vec![by_tags[1].clone(), by_mbid[0].clone()]
+    );
+}
+
+#[tokio::test]
+async fn test_merge_twice_keeps_existing_stats() {
+    let (db, _dir) = setup_db().await;
+
+    let tracks = insert_album(
+        &db,
+        "Glass Harbor",
+        "Pacific Standard",
+        Some(MusicBrainzRelease {
+            release_group_id: "mb-group-1".to_string(),
+            release_id: "mb-release-1".to_string(),
+        }),
+        &["Coastal"],
+    )
+    .await;
+    db.merge_track_stats(&tracks[0], 20, Some(3), None)
+        .await
+        .unwrap();
+
+    let library = remote_library();
+    merge_remote_library(&db, &library).await.unwrap();
+    merge_remote_library(&db, &library).await.unwrap();
+
+    // Higher local play count and existing rating are kept; last played is filled in
+    let stats = db.get_track_stats(&tracks[0]).await.unwrap().unwrap();
+    assert_eq!(stats.play_count, 20);
+    assert_eq!(stats.rating, Some(3));
+    assert!(stats.last_played_at.is_some());
+
+    // Re-importing a playlist replaces it rather than duplicating
+    let playlists = db.get_playlists().await.unwrap();
+    assert_eq!(playlists.len(), 1);
+    assert_eq!(
+        db.get_playlist_track_ids(&playlists[0].id).await.unwrap(),
+        vec![tracks[0].clone()]
+    );
+}
@@ -94,6 +94,7 @@ async fn test_unmanaged_import() {
             folder: album_dir.clone(),
             master_year: 2024,
             managed: false,
+            move_to_cloud: false,
             selected_cover: None,
         })
         .await
@@ -246,6 +247,7 @@ async fn test_unmanaged_delete_preserves_files() {
             folder: album_dir.clone(),
             master_year: 2024,
             managed: false,
+            move_to_cloud: false,
             selected_cover: None,
         })
         .await
@@ -376,6 +378,7 @@ async fn run_managed_test() {
             folder: album_dir.clone(),
             master_year,
             managed: true,
+            move_to_cloud: false,
             selected_cover: Some(CoverSelection::Local(selected_cover.clone())),
             import_id: uuid::Uuid::new_v4().to_string(),
         })
@@ -604,16 +607,19 @@ fn create_test_discogs_release() -> DiscogsRelease {
                 position: "1".to_string(),
                 title: "Track One".to_string(),
                 duration: Some("3:00".to_string()),
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "2".to_string(),
                 title: "Track Two".to_string(),
                 duration: Some("4:00".to_string()),
+                artists: vec![],
             },
             DiscogsTrack {
                 position: "3".to_string(),
                 title: "Track Three".to_string(),
                 duration: Some("2:30".to_string()),
+                artists: vec![],
             },
         ],
         master_id: Some("test-master-storage".to_string()),
@@ -731,6 +737,7 @@ async fn run_real_album_test(album_dir: PathBuf) {
             folder: album_dir.clone(),
             master_year: 1981,
             managed: true,
+            move_to_cloud: false,
             selected_cover: None,
             import_id: uuid::Uuid::new_v4().to_string(),
         })
@@ -6,6 +6,8 @@
 //! - Managed local → eject (export to folder)
 //! - Transfer preserves audio_format.file_id
 //! - Empty release transfer fails gracefully
+//! - Managed local → cloud home, and back
+//! - Cancelled transfer leaves the release where it was
 
 mod support;
 
@@ -15,8 +17,10 @@ use bae_core::library::LibraryManager;
 use bae_core::library_dir::LibraryDir;
 use bae_core::storage::cleanup::PendingDeletion;
 use bae_core::storage::transfer::{TransferProgress, TransferService, TransferTarget};
+use bae_core::test_support::MockCloudStorage;
 use chrono::Utc;
 use std::path::Path;
+use std::sync::Arc;
 use tempfile::TempDir;
 use uuid::Uuid;
 
@@ -72,6 +76,7 @@ async fn create_album_and_release(db: &Database, unmanaged_path: Option<&str>) -
         managed_in_cloud: false,
         unmanaged_path: unmanaged_path.map(|s| s.to_string()),
         private: false,
+        unverified: false,
         created_at: now,
         updated_at: now,
     };
@@ -150,7 +155,9 @@ async fn collect_progress(
     while let Some(event) = rx.recv().await {
         let is_terminal = matches!(
             event,
-            TransferProgress::Complete { .. } | TransferProgress::Failed { .. }
+            TransferProgress::Complete { .. }
+                | TransferProgress::Cancelled { .. }
+                | TransferProgress::Failed { .. }
         );
         events.push(event);
         if is_terminal {
@@ -436,3 +443,113 @@ async fn test_transfer_empty_release_fails() {
         "Transfer with no files should fail"
     );
 }
+
+/// Move managed local files into the cloud home, then back again.
+/// Verifies: cloud copies are encrypted and decrypt to the originals, local
+/// copies are queued for deletion, and moving back removes the cloud copies.
+#[tokio::test]
+async fn test_move_between_local_and_cloud() {
+    tracing_init();
+
+    let temp = TempDir::new().unwrap();
+    let library_path = temp.path().join("library");
+    tokio::fs::create_dir_all(&library_path).await.unwrap();
+
+    let (db, mgr) = setup_db(&temp).await;
+    let (_album_id, release_id) = create_album_and_release(&db, None).await;
+    let library_dir = LibraryDir::new(library_path.clone());
+    let original_files = create_managed_local_files(&db, &mgr, &release_id, &library_dir).await;
+
+    let encryption = support::test_encryption_service().unwrap();
+    let cloud = Arc::new(MockCloudStorage::new());
+    let shared_mgr = bae_core::library::SharedLibraryManager::new(mgr);
+    let service = TransferService::new(
+        shared_mgr.clone(),
+        Some(encryption.clone()),
+        library_dir.clone(),
+    )
+    .with_cloud_storage(cloud.clone());
+
+    let events =
+        collect_progress(service.transfer(release_id.clone(), TransferTarget::Cloud)).await;
+    assert!(matches!(
+        events.last(),
+        Some(TransferProgress::Complete { .. })
+    ));
+
+    let release = db.get_release_by_id(&release_id).await.unwrap().unwrap();
+    assert!(release.managed_in_cloud);
+    assert!(!release.managed_locally);
+    let release_enc = encryption.derive_release_encryption(&release_id);
+    for file in db.get_files_for_release(&release_id).await.unwrap() {
+        let stored = cloud
+            .download(&bae_core::storage::storage_path(&file.id))
+            .await
+            .unwrap();
+        let (_, data) = original_files
+            .iter()
+            .find(|(name, _)| *name == file.original_filename)
+            .unwrap();
+        assert_ne!(&stored, data, "Cloud copies should be encrypted");
+        assert_eq!(&release_enc.decrypt(&stored).unwrap(), data);
+        assert!(file.encryption_nonce.is_some());
+    }
+    assert_eq!(
+        read_pending_deletions(&library_path).await.len(),
+        original_files.len()
+    );
+    assert!(db.get_storage_transfers().await.unwrap().is_empty());
+
+    // And back to this device
+    let events =
+        collect_progress(service.transfer(release_id.clone(), TransferTarget::ManagedLocal)).await;
+    assert!(matches!(
+        events.last(),
+        Some(TransferProgress::Complete { .. })
+    ));
+    let release = db.get_release_by_id(&release_id).await.unwrap().unwrap();
+    assert!(release.managed_locally);
+    assert!(!release.managed_in_cloud);
+    assert!(cloud.files.lock().unwrap().is_empty());
+    // The local copies written back aren't deleted by the earlier move's cleanup
+    assert!(read_pending_deletions(&library_path).await.is_empty());
+}
+
+/// A cancelled transfer removes what it copied and leaves the release alone.
+#[tokio::test]
+async fn test_cancelled_transfer_leaves_release_in_place() {
+    tracing_init();
+
+    let temp = TempDir::new().unwrap();
+    let source_dir = temp.path().join("source");
+    let library_path = temp.path().join("library");
+    tokio::fs::create_dir_all(&source_dir).await.unwrap();
+    tokio::fs::create_dir_all(&library_path).await.unwrap();
+
+    let (db, mgr) = setup_db(&temp).await;
+    let (_album_id, release_id) =
+        create_album_and_release(&db, Some(source_dir.to_str().unwrap())).await;
+    create_unmanaged_files(&mgr, &release_id, &source_dir).await;
+
+    let library_dir = LibraryDir::new(library_path.clone());
+    let shared_mgr = bae_core::library::SharedLibraryManager::new(mgr);
+    let service = TransferService::new(shared_mgr.clone(), None, library_dir.clone());
+    service.cancel();
+    let events =
+        collect_progress(service.transfer(release_id.clone(), TransferTarget::ManagedLocal)).await;
+
+    assert!(matches!(
+        events.last(),
+        Some(TransferProgress::Cancelled { .. })
+    ));
+    let release = db.get_release_by_id(&release_id).await.unwrap().unwrap();
+    assert!(!release.managed_locally);
+    assert_eq!(
+        release.unmanaged_path.as_deref(),
+        Some(source_dir.to_str().unwrap())
+    );
+    for file in db.get_files_for_release(&release_id).await.unwrap() {
+        assert!(!file.local_storage_path(&library_dir).exists());
+    }
+    assert!(db.get_storage_transfers().await.unwrap().is_empty());
+}
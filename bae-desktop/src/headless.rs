@@ -1,15 +1,30 @@
 use bae_core::config::Config;
+use bae_core::db::Database;
 use bae_core::encryption::EncryptionService;
 use bae_core::image_server::ImageServerHandle;
-use bae_core::import::ImportServiceHandle;
+use bae_core::import::folder_scanner::{self, ImportFilters};
+use bae_core::import::{ImportProgress, ImportRequest, ImportServiceHandle};
 use bae_core::keys::{KeyService, UserKeypair};
-use bae_core::library::SharedLibraryManager;
+use bae_core::library::key_rotation::complete_key_rotation;
+use bae_core::library::{LibraryManager, SharedLibraryManager};
 use bae_core::library_dir::LibraryDir;
 use bae_core::playback::PlaybackHandle;
+use bae_core::server_auth::ServerAuth;
+use bae_core::server_config::ServerConfigFile;
+use bae_core::server_metrics::server_metrics;
+use bae_core::subsonic::{RouterSettings, SubsonicAuth};
 use bae_core::sync::bucket::SyncBucketClient;
 use bae_core::sync::hlc::Timestamp;
 use bae_core::sync::service::SyncService;
 use bae_core::sync::session::SyncSession;
+use bae_core::sync::snapshot::{
+    garbage_collect_through, server_snapshot_due, take_expired_snapshot, SnapshotMeta,
+};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
 use tracing::{error, info, warn};
 
 use crate::ui::app_context::SyncHandle;
@@ -19,9 +34,11 @@ use crate::ui::app_service::{
 
 /// Run bae in headless mode (no GUI).
 ///
-/// Starts the Subsonic server (spawned), runs the sync loop on the main task
-/// (because it holds a raw sqlite3 pointer that is not Send), and waits for
-/// SIGTERM / Ctrl-C.
+/// Imports `ingest_dir` first if one was given, then starts the Subsonic
+/// server (spawned), runs the sync loop on the main task (because it holds a
+/// raw sqlite3 pointer that is not Send), and waits for SIGTERM / Ctrl-C.
+/// Settings are reloaded, along with `config_file` if the server was started
+/// with one, on SIGHUP or when the admin API asks.
 pub fn run(
     runtime: tokio::runtime::Runtime,
     config: Config,
@@ -29,24 +46,77 @@ pub fn run(
     encryption_service: Option<EncryptionService>,
     key_service: KeyService,
     sync_handle: Option<SyncHandle>,
+    maintenance: Arc<AtomicBool>,
     image_server: ImageServerHandle,
     user_keypair: Option<UserKeypair>,
-    _import_handle: ImportServiceHandle,
-    _playback_handle: PlaybackHandle,
+    import_handle: ImportServiceHandle,
+    playback_handle: PlaybackHandle,
     cloud_home: Option<std::sync::Arc<dyn bae_core::cloud_home::CloudHome>>,
+    ingest_dir: Option<PathBuf>,
+    config_file: Option<PathBuf>,
 ) {
     runtime.block_on(async {
-        let auth = crate::build_subsonic_auth(&config, &key_service);
+        if let Some(dir) = ingest_dir {
+            ingest_folder_tree(&import_handle, &library_manager, &dir).await;
+        }
+
+        if config.key_rotation_in_progress {
+            let library_manager = library_manager.clone();
+            let key_service = key_service.clone();
+            let mut config = config.clone();
+            tokio::spawn(async move {
+                if let Err(e) = complete_key_rotation(
+                    library_manager.get(),
+                    &key_service,
+                    &mut config,
+                    |_, _| {},
+                )
+                .await
+                {
+                    error!("Failed to complete key rotation: {e}");
+                }
+            });
+        }
+
+        let server_auth = Arc::new(ServerAuth::new(
+            crate::build_subsonic_auth(&config, &key_service),
+            key_service.clone(),
+        ));
+        let (reload_tx, reload_rx) = mpsc::channel(1);
+        let (config_tx, config_rx) = watch::channel(config.clone());
+        // Warm up now, then again after each sync that changes the library
+        let (warmup_tx, warmup_rx) = mpsc::channel(1);
+        let _ = warmup_tx.try_send(());
+        tokio::spawn(reload_on_request(
+            reload_rx,
+            config_file,
+            config_tx,
+            server_auth.clone(),
+            key_service.clone(),
+        ));
+
+        let settings = crate::ServerSettings {
+            port: config.server_port,
+            bind_address: config.server_bind_address.clone(),
+            router: RouterSettings {
+                auth: server_auth,
+                playback: Some(playback_handle),
+                sync: sync_handle.as_ref().map(|sync| sync.control()),
+                reload: Some(reload_tx),
+                maintenance,
+            },
+            read_only: false,
+            cloud_home,
+            party: None,
+            warmup: Some(warmup_rx),
+        };
 
         tokio::spawn(crate::start_subsonic_server(
             library_manager.clone(),
             encryption_service,
-            config.server_port,
-            config.server_bind_address.clone(),
             config.library_dir.clone(),
             key_service,
-            auth,
-            cloud_home,
+            settings,
         ));
 
         info!("bae headless server running");
@@ -55,6 +125,10 @@ pub fn run(
             "  Subsonic: http://{}:{}",
             config.server_bind_address, config.server_port
         );
+        info!(
+            "  Metrics: http://{}:{}/metrics",
+            config.server_bind_address, config.server_port
+        );
         info!(
             "  Image server: http://{}:{}",
             image_server.host, image_server.port
@@ -70,7 +144,8 @@ pub fn run(
                         &sync,
                         &keypair,
                         &library_manager,
-                        &config.library_dir,
+                        config_rx,
+                        &warmup_tx,
                     ) => {}
                     _ = wait_for_shutdown_signal() => {}
                 }
@@ -84,13 +159,269 @@ pub fn run(
     });
 }
 
-/// Headless sync loop -- same logic as app_service.rs but without Store updates.
+/// Reload settings from config.yaml, with `config_file` over them if given,
+/// on SIGHUP or when `reload_rx` gets a request.
+///
+/// The new server credentials apply to the next request and the sync loop
+/// picks up its interval and snapshot settings from `config_tx`. Open
+/// connections, streams included, carry on. The port and bind address only
+/// change on restart.
+async fn reload_on_request(
+    mut reload_rx: mpsc::Receiver<()>,
+    config_file: Option<PathBuf>,
+    config_tx: watch::Sender<Config>,
+    server_auth: Arc<ServerAuth>,
+    key_service: KeyService,
+) {
+    #[cfg(unix)]
+    let mut sighup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .expect("failed to register SIGHUP handler");
+
+    loop {
+        #[cfg(unix)]
+        let requested = tokio::select! {
+            msg = reload_rx.recv() => msg.is_some(),
+            _ = sighup.recv() => true,
+        };
+        #[cfg(not(unix))]
+        let requested = reload_rx.recv().await.is_some();
+        if !requested {
+            break;
+        }
+
+        let file = match &config_file {
+            Some(path) => match ServerConfigFile::load(path) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    error!("Kept the old settings: {e}");
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        // Loading panics when the home directory is gone; keep the old settings
+        let loaded = tokio::task::spawn_blocking(move || match file {
+            Some(file) => (file.load_config(), Some(file.secrets())),
+            None => (Config::load(), None),
+        })
+        .await;
+        let (config, secrets) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                error!("Failed to reload settings: {e}");
+                continue;
+            }
+        };
+        if let Some(secrets) = secrets {
+            key_service.set_overrides(secrets);
+        }
+
+        server_auth.set_subsonic_auth(crate::build_subsonic_auth(&config, &key_service));
+        config_tx.send_replace(config);
+
+        info!("Reloaded settings");
+    }
+}
+
+/// Import every album found under `dir` from its embedded tags.
+///
+/// Meant for seeding a new server library from an existing music folder, so
+/// it does nothing once the library has albums; running it again after a
+/// restart would import everything twice. Files stay where they are. Albums
+/// are imported one at a time and a failure is logged and skipped.
+async fn ingest_folder_tree(
+    import_handle: &ImportServiceHandle,
+    library_manager: &SharedLibraryManager,
+    dir: &Path,
+) {
+    match library_manager.get().get_albums(&[]).await {
+        Ok(albums) if !albums.is_empty() => {
+            warn!(
+                "Library already has {} albums, skipping import of {}",
+                albums.len(),
+                dir.display()
+            );
+            return;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("Failed to check library before import: {e}");
+            return;
+        }
+    }
+
+    let root = dir.to_path_buf();
+    let filters = import_handle.import_filters();
+    let scan = tokio::task::spawn_blocking(move || find_album_folders(root, &filters)).await;
+    let folders = match scan {
+        Ok(Ok(folders)) => folders,
+        Ok(Err(e)) => {
+            error!("Failed to scan {}: {e}", dir.display());
+            return;
+        }
+        Err(e) => {
+            error!("Folder scan task failed: {e}");
+            return;
+        }
+    };
+
+    info!("Importing {} albums from {}", folders.len(), dir.display());
+
+    let mut imported = 0;
+    for (index, folder) in folders.iter().enumerate() {
+        info!("[{}/{}] {}", index + 1, folders.len(), folder.display());
+
+        match import_tagged_folder(import_handle, folder).await {
+            Ok(()) => imported += 1,
+            Err(e) => warn!("Skipped {}: {e}", folder.display()),
+        }
+    }
+
+    info!(
+        "Imported {imported} of {} albums from {}",
+        folders.len(),
+        dir.display()
+    );
+}
+
+/// Album folders under `root`, in path order so an interrupted ingest is
+/// easy to follow in the log.
+fn find_album_folders(root: PathBuf, filters: &ImportFilters) -> Result<Vec<PathBuf>, String> {
+    let mut folders = Vec::new();
+    folder_scanner::scan_for_candidates_with_callback(root, filters, |candidate| {
+        folders.push(candidate.path)
+    })?;
+    folders.sort();
+    Ok(folders)
+}
+
+/// Queue a tag import for one folder and wait for it to finish
+async fn import_tagged_folder(
+    import_handle: &ImportServiceHandle,
+    folder: &Path,
+) -> Result<(), String> {
+    let import_id = uuid::Uuid::new_v4().to_string();
+    // Subscribe before queueing so no events are missed
+    let mut progress = import_handle.subscribe_import(import_id.clone());
+
+    let (_, release_id) = import_handle
+        .send_request(ImportRequest::Tags {
+            import_id,
+            folder: folder.to_path_buf(),
+            managed: false,
+            move_to_cloud: false,
+            selected_cover: None,
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    while let Some(event) = progress.recv().await {
+        match event {
+            ImportProgress::Complete {
+                id,
+                release_id: None,
+                ..
+            } if id == release_id => return Ok(()),
+            ImportProgress::Failed { error, .. } => return Err(error.to_string()),
+            _ => {}
+        }
+    }
+
+    Err("import service stopped".to_string())
+}
+
+/// Run a read-only demo server (no GUI, no config, no keyring).
+///
+/// Seeds a throwaway library in a temp directory with the built-in demo
+/// albums and serves it over Subsonic with auth disabled. The library is
+/// deleted on shutdown.
+pub fn run_demo(port: u16, bind_address: String) {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+
+    runtime.block_on(async {
+        let library_dir = LibraryDir::new(
+            std::env::temp_dir().join(format!("bae-demo-{}", uuid::Uuid::new_v4())),
+        );
+        if let Err(e) = std::fs::create_dir_all(&*library_dir) {
+            error!("Failed to create demo library directory: {e}");
+            return;
+        }
+
+        let database = match Database::new(library_dir.db_path().to_str().unwrap()).await {
+            Ok(db) => db,
+            Err(e) => {
+                error!("Failed to create demo database: {e}");
+                return;
+            }
+        };
+        let library_manager = LibraryManager::new(database, None);
+        if let Err(e) = bae_core::demo::seed_demo_library(&library_manager, &library_dir).await {
+            error!("Failed to seed demo library: {e}");
+            return;
+        }
+
+        let key_service = KeyService::new(true, "demo".to_string());
+        let settings = crate::ServerSettings {
+            port,
+            bind_address: bind_address.clone(),
+            router: RouterSettings {
+                auth: Arc::new(ServerAuth::new(
+                    SubsonicAuth {
+                        enabled: false,
+                        username: None,
+                        password: None,
+                    },
+                    key_service.clone(),
+                )),
+                playback: None,
+                sync: None,
+                reload: None,
+                maintenance: Arc::new(AtomicBool::new(false)),
+            },
+            read_only: true,
+            cloud_home: None,
+            party: None,
+            warmup: None,
+        };
+
+        tokio::spawn(crate::start_subsonic_server(
+            SharedLibraryManager::new(library_manager),
+            None,
+            library_dir.clone(),
+            key_service,
+            settings,
+        ));
+
+        info!("bae demo server running at http://{bind_address}:{port}");
+
+        wait_for_shutdown_signal().await;
+
+        info!("Shutting down, removing demo library");
+
+        if let Err(e) = std::fs::remove_dir_all(&*library_dir) {
+            warn!("Failed to remove demo library: {e}");
+        }
+    });
+}
+
+/// Headless sync loop -- same logic as app_service.rs but without Store updates,
+/// except that it waits `server_sync_interval_secs` between cycles and
+/// snapshots follow the server policy: they count every device's changesets,
+/// come every `server_snapshot_interval_hours`, and changesets they cover are
+/// collected once `server_snapshot_retention_days` have passed. These settings
+/// are read from `config` each cycle, so reloads apply without a restart.
+/// Cycles that apply remote changes send on `warmup` so the search tables and
+/// thumbnails of new covers are ready before anyone asks.
 async fn run_headless_sync_loop(
     sync_handle: &SyncHandle,
     user_keypair: &UserKeypair,
     library_manager: &SharedLibraryManager,
-    library_dir: &LibraryDir,
+    mut config: watch::Receiver<Config>,
+    warmup: &mpsc::Sender<()>,
 ) {
+    let library_dir = config.borrow().library_dir.clone();
+    let library_dir = &library_dir;
     let db = library_manager.get().database();
     let device_id = &sync_handle.device_id;
     let bucket: &dyn SyncBucketClient = &*sync_handle.bucket_client;
@@ -121,6 +452,15 @@ async fn run_headless_sync_loop(
         .and_then(|v| chrono::DateTime::parse_from_rfc3339(&v).ok())
         .map(|dt| dt.with_timezone(&chrono::Utc));
 
+    // Snapshots this server wrote whose changesets are still kept, oldest first
+    let mut snapshot_history: Vec<SnapshotMeta> = db
+        .get_sync_state("snapshot_history")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default();
+
     let mut staged_seq: Option<u64> = db
         .get_sync_state("staged_seq")
         .await
@@ -140,6 +480,13 @@ async fn run_headless_sync_loop(
     tokio::time::sleep(std::time::Duration::from_secs(3)).await;
 
     loop {
+        if !sync_handle
+            .wait_out_maintenance(&mut trigger_rx, sync_interval(&config))
+            .await
+        {
+            break;
+        }
+
         // Retry staged changeset from a previous failed push
         if let Some(seq) = staged_seq {
             if let Some(staged_data) = read_staged_changeset(library_dir) {
@@ -168,7 +515,7 @@ async fn run_headless_sync_loop(
                     Err(e) => {
                         warn!("Staged changeset push failed: {e}");
                         tokio::select! {
-                            _ = tokio::time::sleep(std::time::Duration::from_secs(30)) => {}
+                            _ = tokio::time::sleep(sync_interval(&config)) => {}
                             msg = trigger_rx.recv() => { if msg.is_none() { break; } }
                         }
                         continue;
@@ -186,13 +533,43 @@ async fn run_headless_sync_loop(
             Err(e) => {
                 warn!("Failed to load sync cursors: {e}");
                 tokio::select! {
-                    _ = tokio::time::sleep(std::time::Duration::from_secs(30)) => {}
+                    _ = tokio::time::sleep(sync_interval(&config)) => {}
                     msg = trigger_rx.recv() => { if msg.is_none() { break; } }
                 }
                 continue;
             }
         };
 
+        // Without the chain we can't tell revoked devices apart, so wait
+        // for the next round instead of pulling.
+        let mut membership_chain = match bae_core::sync::invite::load_membership_chain(bucket).await
+        {
+            Ok(chain) => chain,
+            Err(e) => {
+                warn!("Failed to load membership chain: {e}");
+                tokio::select! {
+                    _ = tokio::time::sleep(sync_interval(&config)) => {}
+                    msg = trigger_rx.recv() => { if msg.is_none() { break; } }
+                }
+                continue;
+            }
+        };
+
+        if let Some(chain) = membership_chain.as_mut() {
+            if let Err(e) = bae_core::sync::invite::ensure_device_enrolled(
+                bucket,
+                chain,
+                user_keypair,
+                device_id,
+                &sync_handle.device_keypair,
+                &hlc.now().to_string(),
+            )
+            .await
+            {
+                warn!("Failed to enroll this device's signing key: {e}");
+            }
+        }
+
         // Take session
         let session = match sync_handle.session.lock().await.take() {
             Some(s) => s,
@@ -221,12 +598,18 @@ async fn run_headless_sync_loop(
                     &timestamp,
                     "headless sync",
                     user_keypair,
-                    None,
+                    &sync_handle.device_keypair,
+                    membership_chain.as_ref(),
                     library_dir,
                 )
                 .await
         };
 
+        match &sync_result {
+            Ok(result) => server_metrics().record_sync_success(result.pull.changesets_applied),
+            Err(_) => server_metrics().record_sync_failure(),
+        }
+
         match sync_result {
             Ok(result) => {
                 // Push outgoing changeset
@@ -313,20 +696,41 @@ async fn run_headless_sync_loop(
                         applied = result.pull.changesets_applied,
                         "Applied remote changes"
                     );
+
+                    library_manager.get().notify_albums_changed();
+                    let _ = warmup.try_send(());
                 }
 
-                // Check snapshot policy
+                // Check snapshot policy, counting changesets from every device
                 let hours_since = last_snapshot_time.map(|t| {
                     chrono::Utc::now()
                         .signed_duration_since(t)
                         .num_hours()
                         .max(0) as u64
                 });
+                let library_seq = local_seq
+                    + result
+                        .pull
+                        .remote_heads
+                        .iter()
+                        .filter(|h| h.device_id != *device_id)
+                        .map(|h| h.seq)
+                        .sum::<u64>();
+                let (interval_hours, retention_days) = {
+                    let config = config.borrow();
+                    (
+                        config.server_snapshot_interval_hours,
+                        config.server_snapshot_retention_days,
+                    )
+                };
+                let snapshotted_seq = snapshot_history
+                    .last()
+                    .map_or(0, |meta| meta.cursors.values().sum::<u64>());
 
-                if bae_core::sync::snapshot::should_create_snapshot(
-                    local_seq,
-                    snapshot_seq,
+                if server_snapshot_due(
+                    library_seq.saturating_sub(snapshotted_seq),
                     hours_since,
+                    interval_hours,
                 ) {
                     info!("Creating snapshot");
 
@@ -349,7 +753,7 @@ async fn run_headless_sync_loop(
                             )
                             .await
                             {
-                                Ok(()) => {
+                                Ok(meta) => {
                                     snapshot_seq = Some(local_seq);
                                     last_snapshot_time = Some(chrono::Utc::now());
                                     let _ = db
@@ -363,6 +767,26 @@ async fn run_headless_sync_loop(
                                         .await;
 
                                     info!(local_seq, "Snapshot created and pushed");
+
+                                    snapshot_history.push(meta);
+                                    if let Some(expired) = take_expired_snapshot(
+                                        &mut snapshot_history,
+                                        retention_days,
+                                        chrono::Utc::now(),
+                                    ) {
+                                        match garbage_collect_through(bucket, &expired.cursors)
+                                            .await
+                                        {
+                                            Ok(gc) => info!(
+                                                deleted = gc.deleted,
+                                                "Collected changesets past retention"
+                                            ),
+                                            Err(e) => warn!("Failed to collect changesets: {e}"),
+                                        }
+                                    }
+                                    if let Ok(json) = serde_json::to_string(&snapshot_history) {
+                                        let _ = db.set_sync_state("snapshot_history", &json).await;
+                                    }
                                 }
                                 Err(e) => warn!("Failed to push snapshot: {e}"),
                             }
@@ -380,9 +804,10 @@ async fn run_headless_sync_loop(
             }
         }
 
-        // Wait for next cycle
+        // Wait for next cycle, starting over when the interval is reloaded
         tokio::select! {
-            _ = tokio::time::sleep(std::time::Duration::from_secs(30)) => {}
+            _ = tokio::time::sleep(sync_interval(&config)) => {}
+            Ok(()) = config.changed() => {}
             msg = trigger_rx.recv() => {
                 if msg.is_none() {
                     info!("Sync trigger channel closed, stopping sync loop");
@@ -393,6 +818,11 @@ async fn run_headless_sync_loop(
     }
 }
 
+fn sync_interval(config: &watch::Receiver<Config>) -> Duration {
+    // Zero would sync in a tight loop
+    Duration::from_secs(config.borrow().server_sync_interval_secs.max(1))
+}
+
 async fn wait_for_shutdown_signal() {
     use tokio::signal;
 
@@ -413,3 +843,44 @@ async fn wait_for_shutdown_signal() {
         ctrl_c.await.ok();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A FLAC header with an empty STREAMINFO block, enough for the scanner
+    fn fake_flac() -> Vec<u8> {
+        let mut buf = vec![b'f', b'L', b'a', b'C', 0x00, 0x00, 0x00, 34];
+        buf.extend_from_slice(&[0u8; 34]);
+        buf
+    }
+
+    #[test]
+    fn finds_nested_album_folders_in_path_order() {
+        let root = std::env::temp_dir().join(format!("bae-ingest-{}", uuid::Uuid::new_v4()));
+        for album in [
+            "Tidewater/Second",
+            "Glass Harbor/Landlocked",
+            "@eaDir/thumbs",
+        ] {
+            let folder = root.join(album);
+            std::fs::create_dir_all(&folder).unwrap();
+            std::fs::write(folder.join("01 Track.flac"), fake_flac()).unwrap();
+        }
+        let filters = ImportFilters {
+            ignore_patterns: vec!["@eaDir".to_string()],
+            min_audio_duration_secs: None,
+        };
+
+        let folders = find_album_folders(root.clone(), &filters);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            folders.unwrap(),
+            vec![
+                root.join("Glass Harbor/Landlocked"),
+                root.join("Tidewater/Second"),
+            ]
+        );
+    }
+}
@@ -1,8 +1,8 @@
-use bae_core::db::Database;
+use bae_core::db::{is_encrypted, prepare_database, Database, DatabaseKey};
 use bae_core::image_server;
 use bae_core::keys::KeyService;
 use bae_core::library::SharedLibraryManager;
-use bae_core::subsonic::create_router;
+use bae_core::subsonic::{create_router, RouterSettings};
 use bae_core::{audio_codec, cache, config, encryption, import, playback};
 #[cfg(feature = "torrent")]
 use bae_core::{network, torrent};
@@ -14,6 +14,11 @@ use tracing::{error, info};
 mod crash_report;
 mod headless;
 mod media_controls;
+mod security_key;
+#[cfg(target_os = "macos")]
+mod spotlight;
+#[cfg(target_os = "macos")]
+mod system_events;
 mod ui;
 mod updater;
 
@@ -26,6 +31,35 @@ struct Cli {
     #[arg(long)]
     headless: bool,
 
+    /// Serve a read-only demo library with sample audio (no setup required)
+    #[arg(long)]
+    demo: bool,
+
+    /// Import every album under this folder from its tags before serving.
+    /// Only runs while the library is empty.
+    #[arg(long, value_name = "PATH", requires = "headless")]
+    ingest_dir: Option<std::path::PathBuf>,
+
+    /// Read server settings from this YAML file. Environment variables still
+    /// override it.
+    #[arg(long, value_name = "PATH", requires = "headless")]
+    config: Option<std::path::PathBuf>,
+
+    /// Start in maintenance mode: streaming works, but sync and writes
+    /// through the cloud proxy wait until it's turned off on the admin page
+    #[arg(long, requires = "headless")]
+    maintenance: bool,
+
+    /// Replay the sync bucket into a scratch database, report where it
+    /// differs from this library, and exit
+    #[arg(long, conflicts_with_all = ["headless", "demo"])]
+    verify_sync: bool,
+
+    /// Decrypt every release into plain, tagged files under this folder,
+    /// with a manifest.json listing them, and exit
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["headless", "demo", "verify_sync"])]
+    export_library: Option<std::path::PathBuf>,
+
     /// Override Subsonic server port
     #[arg(long)]
     port: Option<u16>,
@@ -48,16 +82,145 @@ async fn create_cache_manager() -> cache::CacheManager {
     cache_manager
 }
 
-/// Initialize database
-async fn create_database(config: &config::Config) -> Database {
+/// Initialize database. With the master key at hand, library.db is first
+/// encrypted or decrypted to match this device's setting. Returns the key
+/// the database was opened with.
+async fn create_database(
+    config: &config::Config,
+    key_service: &KeyService,
+) -> (Database, Option<DatabaseKey>) {
     std::fs::create_dir_all(&*config.library_dir).expect("Failed to create library directory");
     let db_path = config.library_dir.db_path();
+
+    let derive = |key: Option<String>| key.and_then(|key| DatabaseKey::derive(&key).ok());
+    let key = if config.encryption_key_stored {
+        derive(key_service.get_encryption_key())
+    } else {
+        None
+    };
+    if let Some(ref key) = key {
+        // Until a key rotation finishes, the file may still be under the old key
+        let previous = if config.key_rotation_in_progress {
+            derive(key_service.get_previous_encryption_key())
+        } else {
+            None
+        };
+        if let Err(e) = prepare_database(&db_path, config.encrypt_database, key, previous.as_ref())
+        {
+            error!("Failed to convert the database: {e}");
+        }
+    }
+    // Whatever the setting, an encrypted file only opens with its key
+    let key = key.filter(|_| is_encrypted(&db_path).unwrap_or(false));
+
     info!("Opening database at {}", db_path.display());
-    let database = Database::new(db_path.to_str().unwrap())
+    let database = Database::new_with_key(db_path.to_str().unwrap(), key.as_ref())
         .await
         .expect("Failed to create database");
     info!("Database created");
-    database
+    (database, key)
+}
+
+/// Replay the sync bucket and print where it differs from the library.
+/// Returns the process exit code: 0 when they match.
+async fn verify_sync(
+    sync: &ui::app_context::SyncHandle,
+    encryption: &encryption::EncryptionService,
+    library_dir: &bae_core::library_dir::LibraryDir,
+    database_key: Option<&DatabaseKey>,
+) -> i32 {
+    let scratch_dir = std::env::temp_dir().join("bae-verify-sync");
+    let report = match bae_core::sync::verify::verify(
+        sync.bucket_client.as_ref(),
+        encryption,
+        library_dir,
+        database_key,
+        &scratch_dir,
+    )
+    .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: sync verification failed: {e}");
+            return 1;
+        }
+    };
+
+    println!("Replayed {} changesets", report.changesets_replayed);
+    if report.is_clean() {
+        println!("Library matches the sync bucket");
+        return 0;
+    }
+
+    for table in &report.tables {
+        println!("{}:", table.table);
+        for key in &table.only_in_live {
+            println!("  {key}: only in this library");
+        }
+        for key in &table.only_in_replay {
+            println!("  {key}: only in the sync bucket");
+        }
+        for row in &table.differing {
+            println!("  {}: differs in {}", row.key, row.columns.join(", "));
+        }
+    }
+    println!("Changes not yet pushed from this device also show up here; sync first and run again to rule them out.");
+    1
+}
+
+/// Export every release as plain files and print what couldn't be exported.
+/// Returns the process exit code: 0 when every release was exported.
+async fn export_library(
+    config: &config::Config,
+    library_manager: &bae_core::library::LibraryManager,
+    cloud_home: Option<std::sync::Arc<dyn bae_core::cloud_home::CloudHome>>,
+    target: &std::path::Path,
+) -> i32 {
+    let storage = cloud_home.map(|home| {
+        std::sync::Arc::new(bae_core::storage::usage::MeteredCloudStorage::new(
+            std::sync::Arc::new(bae_core::cloud_storage::CloudHomeStorageAdapter::new(home)),
+            library_manager.database().clone(),
+        )) as std::sync::Arc<dyn bae_core::cloud_storage::CloudStorage>
+    });
+    let template = config
+        .export_name_template
+        .as_deref()
+        .unwrap_or(bae_core::library::full_export::DEFAULT_NAME_TEMPLATE);
+    let manifest = match bae_core::library::full_export::export_library(
+        library_manager,
+        &config.library_dir,
+        storage,
+        template,
+        target,
+    )
+    .await
+    {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("Error: export failed: {e}");
+            return 1;
+        }
+    };
+
+    let tracks: usize = manifest.releases.iter().map(|r| r.tracks.len()).sum();
+    println!(
+        "Exported {} releases ({} tracks) to {}",
+        manifest.releases.len(),
+        tracks,
+        target.display()
+    );
+    if manifest.failures.is_empty() {
+        return 0;
+    }
+
+    println!("Not exported:");
+    for failure in &manifest.failures {
+        println!(
+            "  {} ({}): {}",
+            failure.album, failure.release_id, failure.error
+        );
+    }
+    1
 }
 
 /// Initialize library manager with all dependencies
@@ -115,10 +278,32 @@ fn main() {
     config::init_keyring();
     configure_logging();
 
+    if let Some(ref dir) = cli.ingest_dir {
+        if !dir.is_dir() {
+            eprintln!("Error: --ingest-dir {} is not a folder", dir.display());
+            std::process::exit(1);
+        }
+    }
+
+    let server_config = cli.config.as_ref().map(|path| {
+        bae_core::server_config::ServerConfigFile::load(path).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        })
+    });
+
+    if cli.demo {
+        headless::run_demo(
+            cli.port.unwrap_or(4533),
+            cli.bind.unwrap_or_else(|| "127.0.0.1".to_string()),
+        );
+        return;
+    }
+
     // Detect first run BEFORE Config::load() (which creates the pointer file)
     if is_first_run() {
-        if cli.headless {
-            eprintln!("Error: no library found. Run bae without --headless to create one.");
+        if cli.headless || cli.verify_sync || cli.export_library.is_some() {
+            eprintln!("Error: no library found. Run bae with no options to create one.");
             std::process::exit(1);
         }
         info!("First run detected — launching welcome screen");
@@ -130,7 +315,10 @@ fn main() {
         return;
     }
 
-    let mut config = config::Config::load();
+    let mut config = match &server_config {
+        Some(file) => file.load_config(),
+        None => config::Config::load(),
+    };
     crash_report::check_for_crash_report();
 
     // Initialize FFmpeg for audio processing
@@ -141,10 +329,13 @@ fn main() {
 
     info!("Building dependencies...");
     let cache_manager = runtime_handle.block_on(create_cache_manager());
-    let database = runtime_handle.block_on(create_database(&config));
 
     let dev_mode = config::Config::is_dev_mode();
     let key_service = KeyService::new(dev_mode, config.library_id.clone());
+    if let Some(file) = &server_config {
+        key_service.set_overrides(file.secrets());
+    }
+    security_key::take_unlocked_key(&key_service);
 
     // One-time migration from global keyring entries to per-library namespaced entries
     if !config.keys_migrated {
@@ -175,20 +366,34 @@ fn main() {
     if config.encryption_key_stored {
         if let Some(ref fp) = config.encryption_key_fingerprint {
             if key_service.get_encryption_key().is_none() {
-                if cli.headless {
-                    eprintln!(
-                        "Error: encryption key missing from keyring. \
-                         Run bae without --headless to unlock."
+                let passphrase_kdf = config
+                    .library_dir
+                    .read_manifest()
+                    .and_then(|m| m.passphrase_kdf);
+                if cli.headless || cli.verify_sync || cli.export_library.is_some() {
+                    match config.security_key {
+                        Some(ref wrapped) => unlock_with_security_key(&key_service, fp, wrapped),
+                        None => {
+                            unlock_with_passphrase_env(&key_service, fp, passphrase_kdf.as_ref())
+                        }
+                    }
+                } else {
+                    info!("Encryption key missing from keyring — launching unlock screen");
+                    ui::components::unlock::launch_unlock(
+                        key_service,
+                        fp.clone(),
+                        passphrase_kdf,
+                        config.security_key.clone(),
                     );
-                    std::process::exit(1);
+                    return;
                 }
-                info!("Encryption key missing from keyring — launching unlock screen");
-                ui::components::unlock::launch_unlock(key_service, fp.clone());
-                return;
             }
         }
     }
 
+    // Opened past the unlock screen: an encrypted library.db needs the key
+    let (database, database_key) = runtime_handle.block_on(create_database(&config, &key_service));
+
     // Create encryption service only if hint flag says a key is stored (avoids keyring prompt)
     let encryption_service = if config.encryption_key_stored {
         key_service.get_encryption_key().and_then(|key| {
@@ -218,12 +423,43 @@ fn main() {
     } else {
         None
     };
+    // Until a key rotation has re-encrypted everything, data under the
+    // previous key stays readable
+    let encryption_service = match encryption_service {
+        Some(service) if config.key_rotation_in_progress => {
+            match key_service.get_previous_encryption_key() {
+                Some(previous) => match service.clone().with_previous_key(&previous) {
+                    Ok(service) => Some(service),
+                    Err(e) => {
+                        error!("Invalid previous encryption key: {e}");
+                        Some(service)
+                    }
+                },
+                None => Some(service),
+            }
+        }
+        service => service,
+    };
+    // Sync keeps the default format so changesets stay readable by devices
+    // running older versions; release files get the library's chunk size
+    let sync_encryption = encryption_service.clone();
+    let encryption_service = encryption_service.map(|e| config.file_encryption(e));
     let library_manager = create_library_manager(database.clone(), encryption_service.clone());
 
+    // Set in the server's maintenance mode, which pauses sync and writes
+    // through the cloud proxy
+    let maintenance = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(cli.maintenance));
+
     // Initialize sync infrastructure if sync is configured and encryption is enabled
     let sync_handle = if config.sync_enabled(&key_service) {
-        if let Some(ref enc) = encryption_service {
-            runtime_handle.block_on(create_sync_handle(&config, &key_service, &database, enc))
+        if let Some(ref enc) = sync_encryption {
+            runtime_handle.block_on(create_sync_handle(
+                &config,
+                &key_service,
+                &database,
+                enc,
+                maintenance.clone(),
+            ))
         } else {
             info!(
                 "Sync is configured but encryption is not enabled — skipping sync initialization"
@@ -234,6 +470,22 @@ fn main() {
         None
     };
 
+    if cli.verify_sync {
+        let code = match (&sync_handle, &sync_encryption) {
+            (Some(sync), Some(enc)) => runtime_handle.block_on(verify_sync(
+                sync,
+                enc,
+                &config.library_dir,
+                database_key.as_ref(),
+            )),
+            _ => {
+                eprintln!("Error: sync is not set up for this library.");
+                1
+            }
+        };
+        std::process::exit(code);
+    }
+
     // Create a CloudHome for proxy routes (followers, share links).
     // Only when a cloud provider is explicitly configured.
     let cloud_home_for_proxy: Option<std::sync::Arc<dyn bae_core::cloud_home::CloudHome>> =
@@ -252,6 +504,16 @@ fn main() {
             None
         };
 
+    if let Some(ref target) = cli.export_library {
+        let code = runtime_handle.block_on(export_library(
+            &config,
+            library_manager.get(),
+            cloud_home_for_proxy.clone(),
+            target,
+        ));
+        std::process::exit(code);
+    }
+
     // Ensure manifest.json exists (idempotent, runs every startup)
     ensure_manifest(&config, encryption_service.as_ref());
 
@@ -280,6 +542,12 @@ fn main() {
         key_service.clone(),
         config.library_dir.clone(),
     );
+    import_handle.set_import_filters(config.import_filters());
+    match runtime_handle.block_on(import_handle.resume_interrupted_imports()) {
+        Ok(0) => {}
+        Ok(n) => info!("Resumed {} interrupted import(s)", n),
+        Err(e) => error!("Failed to resume interrupted imports: {}", e),
+    }
 
     let playback_handle = playback::PlaybackService::start(
         library_manager.get().clone(),
@@ -305,6 +573,10 @@ fn main() {
         }
         config.server_enabled = true;
 
+        if cli.maintenance {
+            info!("Starting in maintenance mode, sync and cloud writes are paused");
+        }
+
         headless::run(
             runtime,
             config,
@@ -312,11 +584,14 @@ fn main() {
             encryption_service,
             key_service,
             sync_handle,
+            maintenance,
             image_server,
             user_keypair,
             import_handle,
             playback_handle,
             cloud_home_for_proxy,
+            cli.ingest_dir,
+            cli.config,
         );
         return;
     }
@@ -339,6 +614,16 @@ fn main() {
     };
     let _keep_alive = media_controls;
 
+    #[cfg(target_os = "macos")]
+    spotlight::setup_spotlight(
+        playback_handle.clone(),
+        library_manager.clone(),
+        runtime_handle.clone(),
+    );
+
+    #[cfg(target_os = "macos")]
+    system_events::setup_system_events(playback_handle.clone(), &config, runtime_handle.clone());
+
     // Initialize navigation + playback + URL channels (must be before menu/handler setup)
     ui::shortcuts::init_nav_channel();
     ui::shortcuts::init_url_channel();
@@ -357,28 +642,40 @@ fn main() {
         }
     }
 
+    let party = std::sync::Arc::new(bae_core::party::PartySession::new());
+
     if config.server_enabled {
         let subsonic_library = library_manager.clone();
         let subsonic_encryption = encryption_service.clone();
-        let subsonic_port = config.server_port;
-        let subsonic_bind_address = config.server_bind_address.clone();
         let subsonic_library_dir = config.library_dir.clone();
         let subsonic_key_service = key_service.clone();
 
-        let subsonic_auth = build_subsonic_auth(&config, &key_service);
-
-        let subsonic_cloud_home = cloud_home_for_proxy.clone();
+        let subsonic_settings = ServerSettings {
+            port: config.server_port,
+            bind_address: config.server_bind_address.clone(),
+            router: RouterSettings {
+                auth: std::sync::Arc::new(bae_core::server_auth::ServerAuth::new(
+                    build_subsonic_auth(&config, &key_service),
+                    key_service.clone(),
+                )),
+                playback: Some(playback_handle.clone()),
+                sync: sync_handle.as_ref().map(|sync| sync.control()),
+                reload: None,
+                maintenance: maintenance.clone(),
+            },
+            read_only: false,
+            cloud_home: cloud_home_for_proxy.clone(),
+            party: Some(party.clone()),
+            warmup: None,
+        };
 
         runtime_handle.spawn(async move {
             start_subsonic_server(
                 subsonic_library,
                 subsonic_encryption,
-                subsonic_port,
-                subsonic_bind_address,
                 subsonic_library_dir,
                 subsonic_key_service,
-                subsonic_auth,
-                subsonic_cloud_home,
+                subsonic_settings,
             )
             .await
         });
@@ -396,6 +693,7 @@ fn main() {
         image_server,
         user_keypair,
         sync_handle,
+        party,
     };
 
     // Initialize auto-updater (checks for updates on launch)
@@ -406,6 +704,68 @@ fn main() {
     info!("UI quit");
 }
 
+/// Without a window to unlock in, wait for a tap on the security key and
+/// hold the unwrapped key for this run, or exit.
+fn unlock_with_security_key(
+    key_service: &KeyService,
+    expected_fingerprint: &str,
+    wrapped: &bae_core::keys::WrappedEncryptionKey,
+) {
+    eprintln!("Touch your security key to unlock the library...");
+    let key_hex = match security_key::unlock(wrapped) {
+        Ok(key_hex) => key_hex,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    if encryption::compute_key_fingerprint(&key_hex).as_deref() != Some(expected_fingerprint) {
+        eprintln!("Error: the security key unwrapped a key for a different library.");
+        std::process::exit(1);
+    }
+    key_service.hold_encryption_key(&key_hex);
+
+    info!("Encryption key unlocked with security key");
+}
+
+/// Without a window to unlock in, derive the missing key from
+/// `BAE_LIBRARY_PASSPHRASE` and store it, or exit.
+fn unlock_with_passphrase_env(
+    key_service: &KeyService,
+    expected_fingerprint: &str,
+    passphrase_kdf: Option<&encryption::PassphraseKdf>,
+) {
+    let passphrase = std::env::var("BAE_LIBRARY_PASSPHRASE")
+        .ok()
+        .filter(|p| !p.is_empty());
+    let (Some(passphrase), Some(kdf)) = (passphrase, passphrase_kdf) else {
+        eprintln!(
+            "Error: encryption key missing from keyring. \
+             Run bae with no options to unlock, or set BAE_LIBRARY_PASSPHRASE \
+             if the library uses a passphrase."
+        );
+        std::process::exit(1);
+    };
+
+    let key_hex = match kdf.derive_key(&passphrase) {
+        Ok(key_hex) => key_hex,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+    if encryption::compute_key_fingerprint(&key_hex).as_deref() != Some(expected_fingerprint) {
+        eprintln!("Error: BAE_LIBRARY_PASSPHRASE does not unlock this library.");
+        std::process::exit(1);
+    }
+    if let Err(e) = key_service.set_encryption_key(&key_hex) {
+        eprintln!("Error: failed to save the encryption key to the keyring: {e}");
+        std::process::exit(1);
+    }
+
+    info!("Encryption key derived from passphrase and saved to keyring");
+}
+
 /// Ensure a home storage profile exists and manifest.json is present at the library root.
 ///
 /// On first launch after library creation, no home profile exists yet. This creates one
@@ -424,6 +784,7 @@ fn ensure_manifest(
         library_id: config.library_id.clone(),
         library_name: config.library_name.clone(),
         encryption_key_fingerprint: encryption_service.map(|e| e.fingerprint()),
+        passphrase_kdf: None,
     };
 
     match serde_json::to_string_pretty(&manifest) {
@@ -459,36 +820,109 @@ pub(crate) fn build_subsonic_auth(
     }
 }
 
-/// Start the Subsonic API server, optionally with cloud home proxy routes.
+/// How the Subsonic server is exposed.
+pub(crate) struct ServerSettings {
+    pub port: u16,
+    pub bind_address: String,
+    pub router: RouterSettings,
+    /// Reject every request that would change the library.
+    pub read_only: bool,
+    /// Serve the cloud home proxy routes (/cloud/*, /share/*) for this cloud home.
+    pub cloud_home: Option<std::sync::Arc<dyn bae_core::cloud_home::CloudHome>>,
+    /// Serve the party mode routes for this session.
+    pub party: Option<std::sync::Arc<bae_core::party::PartySession>>,
+    /// Warm up search and cover thumbnails on each request sent here.
+    pub warmup: Option<tokio::sync::mpsc::Receiver<()>>,
+}
+
+/// Start the Subsonic API server, optionally with cloud home proxy and party routes.
+///
+/// With a playback handle in the router settings, Subsonic clients can also
+/// use it as a jukebox; with a sync handle, the admin API can trigger syncs
+/// and the cloud proxy accepts pushes from the owner's devices, except in
+/// maintenance mode; with a reload trigger, it can ask the server to reload
+/// its settings.
 pub(crate) async fn start_subsonic_server(
     library_manager: SharedLibraryManager,
     encryption_service: Option<encryption::EncryptionService>,
-    port: u16,
-    bind_address: String,
     library_dir: bae_core::library_dir::LibraryDir,
     key_service: bae_core::keys::KeyService,
-    auth: bae_core::subsonic::SubsonicAuth,
-    cloud_home: Option<std::sync::Arc<dyn bae_core::cloud_home::CloudHome>>,
+    settings: ServerSettings,
 ) {
     info!("Starting Subsonic API server...");
+    let party_router = settings
+        .party
+        .map(|party| bae_core::party::create_party_router(party, library_manager.clone()));
+    // The owner's other devices can push through the cloud proxy, which
+    // wakes the sync loop so their changes land within seconds
+    let cloud_writes = settings
+        .router
+        .sync
+        .as_ref()
+        .zip(key_service.get_user_public_key())
+        .map(|(sync, pubkey)| bae_core::cloud_routes::CloudWrites {
+            owner_pubkey: hex::encode(pubkey),
+            on_push: sync.trigger.clone(),
+            maintenance: settings.router.maintenance.clone(),
+        });
+    let transcodes = match cache::CacheManager::with_config(cache::CacheConfig::transcodes()).await
+    {
+        Ok(transcodes) => Some(transcodes),
+        Err(e) => {
+            warn!("Transcode cache unavailable, HLS tracks will be re-encoded: {e}");
+
+            None
+        }
+    };
+    let thumbnails = match cache::CacheManager::with_config(cache::CacheConfig::thumbnails()).await
+    {
+        Ok(cache) => Some(bae_core::cover_thumbnails::CoverThumbnails::new(
+            cache,
+            library_dir.clone(),
+        )),
+        Err(e) => {
+            warn!("Thumbnail cache unavailable, covers will be served full size: {e}");
+
+            None
+        }
+    };
+    if let Some(requests) = settings.warmup {
+        tokio::spawn(bae_core::server_warmup::run_warmup(
+            requests,
+            library_manager.clone(),
+            thumbnails.clone(),
+        ));
+    }
     let mut app = create_router(
         library_manager,
         encryption_service,
         library_dir,
         key_service,
-        auth,
+        settings.router,
+        transcodes,
+        thumbnails,
     );
 
-    if let Some(ch) = cloud_home {
-        let cloud_state =
-            std::sync::Arc::new(bae_core::cloud_routes::CloudRouteState { cloud_home: ch });
+    if let Some(ch) = settings.cloud_home {
+        let cloud_state = std::sync::Arc::new(bae_core::cloud_routes::CloudRouteState {
+            cloud_home: ch,
+            writes: cloud_writes,
+        });
         let cloud_router = bae_core::cloud_routes::create_cloud_router(cloud_state);
         app = app.merge(cloud_router);
 
         info!("Cloud home proxy routes enabled (/cloud/*, /share/*)");
     }
 
-    let addr = format!("{}:{}", bind_address, port);
+    if let Some(party_router) = party_router {
+        app = app.merge(party_router);
+    }
+
+    if settings.read_only {
+        app = app.layer(axum::middleware::from_fn(bae_core::demo::reject_writes));
+    }
+
+    let addr = format!("{}:{}", settings.bind_address, settings.port);
     let listener = match tokio::net::TcpListener::bind(&addr).await {
         Ok(listener) => {
             info!("Subsonic API server listening on http://{}", addr);
@@ -499,7 +933,12 @@ pub(crate) async fn start_subsonic_server(
             return;
         }
     };
-    if let Err(e) = axum::serve(listener, app).await {
+    if let Err(e) = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    {
         error!("Subsonic server error: {}", e);
     }
 }
@@ -513,6 +952,7 @@ async fn create_sync_handle(
     key_service: &KeyService,
     database: &Database,
     encryption: &encryption::EncryptionService,
+    maintenance: std::sync::Arc<std::sync::atomic::AtomicBool>,
 ) -> Option<ui::app_context::SyncHandle> {
     use bae_core::cloud_home::s3::S3CloudHome;
     use bae_core::sync::cloud_home_bucket::CloudHomeSyncBucket;
@@ -549,7 +989,8 @@ async fn create_sync_handle(
         }
     };
 
-    let bucket_client = CloudHomeSyncBucket::new(Box::new(cloud_home), encryption.clone());
+    let bucket_client = CloudHomeSyncBucket::new(Box::new(cloud_home), encryption.clone())
+        .with_image_encryption(config.encrypt_images);
 
     let raw_db = match database.raw_write_handle().await {
         Ok(ptr) => ptr,
@@ -568,6 +1009,14 @@ async fn create_sync_handle(
         }
     };
 
+    let device_keypair = match key_service.get_or_create_device_keypair() {
+        Ok(kp) => kp,
+        Err(e) => {
+            error!("Failed to load/create device keypair for sync: {e}");
+            return None;
+        }
+    };
+
     let hlc = Hlc::new(config.device_id.clone());
 
     // Channel for manual sync trigger (Phase 5d). Capacity of 1 is sufficient
@@ -583,10 +1032,12 @@ async fn create_sync_handle(
         bucket_client,
         hlc,
         config.device_id.clone(),
+        device_keypair,
         raw_db,
         session,
         sync_trigger_tx,
         sync_trigger_rx,
+        maintenance,
     ))
 }
 
@@ -0,0 +1,108 @@
+//! Unlocking with a FIDO2 security key
+//!
+//! A security key that supports the hmac-secret extension (YubiKey 5, SoloKey
+//! and most FIDO2 keys) hashes a salt with a secret that never leaves the
+//! device. bae wraps the library's master key under that hash and keeps only
+//! the wrapped copy in config.yaml, so unlocking needs the key present and a
+//! tap. The unwrapped key lives in memory for one run of the app.
+
+use bae_core::keys::{KeyService, WrappedEncryptionKey};
+use ctap_hid_fido2::fidokey::get_assertion::get_assertion_params::Extension as AssertionExtension;
+use ctap_hid_fido2::fidokey::make_credential::make_credential_params::Extension as CredentialExtension;
+use ctap_hid_fido2::fidokey::{GetAssertionArgsBuilder, MakeCredentialArgsBuilder};
+use ctap_hid_fido2::{verifier, Cfg, FidoKeyHid, FidoKeyHidFactory};
+use tracing::info;
+
+/// Relying party the credential is scoped to
+const RP_ID: &str = "bae.fm";
+
+/// Carries the unwrapped key across the re-exec after an unlock. Read and
+/// cleared first thing on startup.
+const UNLOCKED_KEY_ENV: &str = "BAE_UNLOCKED_ENCRYPTION_KEY";
+
+/// Create a credential on the security key and wrap `key_hex` with it.
+/// Blocks until the key has been touched twice.
+pub fn enroll(key_hex: &str) -> Result<WrappedEncryptionKey, String> {
+    let device = open_device()?;
+    let challenge = verifier::create_challenge();
+    let args = MakeCredentialArgsBuilder::new(RP_ID, &challenge)
+        .extensions(&[CredentialExtension::HmacSecret(Some(true))])
+        .without_pin_and_uv()
+        .build();
+    let attestation = device
+        .make_credential_with_args(&args)
+        .map_err(|e| format!("The security key didn't create a credential: {e}"))?;
+    let credential_id = attestation.credential_descriptor.id;
+
+    let salt = bae_core::encryption::generate_random_key();
+    let secret = hmac_secret(&device, &credential_id, salt)?;
+
+    info!("Wrapped the encryption key with a security key");
+    Ok(WrappedEncryptionKey::wrap(
+        key_hex,
+        &credential_id,
+        &salt,
+        secret,
+    ))
+}
+
+/// Unwrap the master key. Blocks until the key is touched.
+pub fn unlock(wrapped: &WrappedEncryptionKey) -> Result<String, String> {
+    let credential_id =
+        hex::decode(&wrapped.credential_id).map_err(|e| format!("Invalid credential: {e}"))?;
+    let salt: [u8; 32] = hex::decode(&wrapped.salt)
+        .ok()
+        .and_then(|salt| salt.try_into().ok())
+        .ok_or("Invalid security key salt")?;
+
+    let device = open_device()?;
+    let secret = hmac_secret(&device, &credential_id, salt)?;
+    wrapped.unwrap_key(secret).map_err(|e| e.to_string())
+}
+
+/// Re-exec with the unwrapped key handed to the new process, which picks it
+/// up with `take_unlocked_key`
+pub fn relaunch_unlocked(key_hex: &str) {
+    std::env::set_var(UNLOCKED_KEY_ENV, key_hex);
+    crate::ui::components::welcome::relaunch();
+}
+
+/// Hold a key handed over by `relaunch_unlocked`, and drop it from the
+/// environment so child processes don't inherit it
+pub fn take_unlocked_key(key_service: &KeyService) {
+    if let Ok(key_hex) = std::env::var(UNLOCKED_KEY_ENV) {
+        std::env::remove_var(UNLOCKED_KEY_ENV);
+        key_service.hold_encryption_key(&key_hex);
+    }
+}
+
+fn open_device() -> Result<FidoKeyHid, String> {
+    FidoKeyHidFactory::create(&Cfg::init())
+        .map_err(|e| format!("No security key found. Plug it in and try again. ({e})"))
+}
+
+/// The security key's hmac-secret output for `salt` under the credential
+fn hmac_secret(
+    device: &FidoKeyHid,
+    credential_id: &[u8],
+    salt: [u8; 32],
+) -> Result<[u8; 32], String> {
+    let challenge = verifier::create_challenge();
+    let args = GetAssertionArgsBuilder::new(RP_ID, &challenge)
+        .credential_id(credential_id)
+        .extensions(&[AssertionExtension::HmacSecret(Some(salt))])
+        .without_pin_and_uv()
+        .build();
+    let assertions = device
+        .get_assertion_with_args(&args)
+        .map_err(|e| format!("The security key didn't respond: {e}"))?;
+
+    assertions
+        .iter()
+        .flat_map(|assertion| assertion.extensions.iter())
+        .find_map(|extension| match extension {
+            AssertionExtension::HmacSecret(Some(secret)) => Some(*secret),
+            _ => None,
+        })
+        .ok_or_else(|| "This security key doesn't support hmac-secret".to_string())
+}
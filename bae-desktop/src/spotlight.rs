@@ -0,0 +1,290 @@
+//! macOS Spotlight integration
+//!
+//! Donates library albums and the now-playing track to CoreSpotlight so they
+//! show up in system search. Each item's unique identifier is its `bae://`
+//! deep link, so opening a result goes through the regular URL handling.
+
+use bae_core::db::DbTrack;
+use bae_core::library::{LibraryEvent, SharedLibraryManager};
+use bae_core::playback::{PlaybackHandle, PlaybackProgress, PlaybackState};
+use cocoa::base::{id, nil, BOOL, NO, YES};
+use cocoa::foundation::{NSArray, NSAutoreleasePool, NSString};
+use dispatch::Queue;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+#[link(name = "CoreSpotlight", kind = "framework")]
+extern "C" {}
+
+const ALBUM_DOMAIN: &str = "fm.bae.album";
+const TRACK_DOMAIN: &str = "fm.bae.track";
+
+/// `CSSearchableItemActionType`: activity type for an opened Spotlight result
+const SEARCHABLE_ITEM_ACTION_TYPE: &str = "com.apple.corespotlightitem";
+/// `CSSearchableItemActivityIdentifier`: userInfo key holding the item's identifier
+const SEARCHABLE_ITEM_ACTIVITY_IDENTIFIER: &str = "kCSSearchableItemActivityIdentifier";
+
+/// One entry in the Spotlight index
+struct SpotlightItem {
+    /// `bae://` deep link, also used as the unique identifier
+    url: String,
+    domain: &'static str,
+    title: String,
+    artist: Option<String>,
+    album: Option<String>,
+    duration: Option<Duration>,
+}
+
+/// Start donating library and now-playing metadata to Spotlight
+pub fn setup_spotlight(
+    playback_handle: PlaybackHandle,
+    library_manager: SharedLibraryManager,
+    runtime_handle: tokio::runtime::Handle,
+) {
+    register_activity_handler();
+
+    let library_manager_for_albums = library_manager.clone();
+    runtime_handle.spawn(async move {
+        let library_manager = library_manager_for_albums;
+        let mut events = library_manager.get().subscribe_events();
+        donate_albums(&library_manager).await;
+        loop {
+            match events.recv().await {
+                Ok(LibraryEvent::AlbumsChanged) => {
+                    // Imports fire bursts of events; reindex once per burst
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    while events.try_recv().is_ok() {}
+                    donate_albums(&library_manager).await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+            }
+        }
+    });
+
+    runtime_handle.spawn(async move {
+        let mut progress_rx = playback_handle.subscribe_progress();
+        let mut last_track_id: Option<String> = None;
+        while let Some(progress) = progress_rx.recv().await {
+            if let PlaybackProgress::StateChanged {
+                state:
+                    PlaybackState::Playing {
+                        track, duration, ..
+                    },
+            } = progress
+            {
+                if last_track_id.as_deref() == Some(track.id.as_str()) {
+                    continue;
+                }
+                last_track_id = Some(track.id.clone());
+                donate_track(&library_manager, &track, duration).await;
+            }
+        }
+    });
+
+    info!("Spotlight donation started");
+}
+
+/// Replace the album entries in the index with the current library
+async fn donate_albums(library_manager: &SharedLibraryManager) {
+    let albums = match library_manager.get().get_albums(&[]).await {
+        Ok(albums) => albums,
+        Err(e) => {
+            error!("Failed to load albums for Spotlight: {}", e);
+            return;
+        }
+    };
+
+    let mut items = Vec::with_capacity(albums.len());
+    for album in albums {
+        let artist = match library_manager.get().get_artists_for_album(&album.id).await {
+            Ok(artists) => join_artist_names(artists.iter().map(|a| a.name.as_str())),
+            Err(e) => {
+                warn!("Failed to load artists for album {}: {}", album.id, e);
+                None
+            }
+        };
+        items.push(SpotlightItem {
+            url: format!("bae://album/{}", album.id),
+            domain: ALBUM_DOMAIN,
+            title: album.title.clone(),
+            artist,
+            album: Some(album.title),
+            duration: None,
+        });
+    }
+
+    info!("Donating {} albums to Spotlight", items.len());
+
+    replace_domain(ALBUM_DOMAIN, items);
+}
+
+/// Add the now-playing track to the index
+async fn donate_track(
+    library_manager: &SharedLibraryManager,
+    track: &DbTrack,
+    duration: Option<Duration>,
+) {
+    let library = library_manager.get();
+    let artist = library
+        .get_artists_for_track(&track.id)
+        .await
+        .ok()
+        .and_then(|artists| join_artist_names(artists.iter().map(|a| a.name.as_str())));
+    let album = match library.get_album_id_for_release(&track.release_id).await {
+        Ok(album_id) => library
+            .get_album_by_id(&album_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|a| a.title),
+        Err(_) => None,
+    };
+
+    index_items(vec![SpotlightItem {
+        url: format!("bae://track/{}", track.id),
+        domain: TRACK_DOMAIN,
+        title: track.title.clone(),
+        artist,
+        album,
+        duration,
+    }]);
+}
+
+fn join_artist_names<'a>(names: impl Iterator<Item = &'a str>) -> Option<String> {
+    let joined = names.collect::<Vec<_>>().join(", ");
+    (!joined.is_empty()).then_some(joined)
+}
+
+/// Drop everything in `domain`, then index `items` in its place
+fn replace_domain(domain: &'static str, items: Vec<SpotlightItem>) {
+    Queue::main().exec_async(move || unsafe {
+        let index: id = msg_send![class!(CSSearchableIndex), defaultSearchableIndex];
+        let domains = NSArray::arrayWithObjects(nil, &[ns_string(domain)]);
+        let _: () = msg_send![
+            index,
+            deleteSearchableItemsWithDomainIdentifiers: domains
+            completionHandler: nil
+        ];
+    });
+    index_items(items);
+}
+
+fn index_items(items: Vec<SpotlightItem>) {
+    if items.is_empty() {
+        return;
+    }
+    Queue::main().exec_async(move || unsafe {
+        let searchable_items: Vec<id> = items.iter().map(|item| searchable_item(item)).collect();
+        let array = NSArray::arrayWithObjects(nil, &searchable_items);
+        let index: id = msg_send![class!(CSSearchableIndex), defaultSearchableIndex];
+        let _: () = msg_send![index, indexSearchableItems: array completionHandler: nil];
+    });
+}
+
+/// Build an autoreleased `CSSearchableItem`. Must run on the main thread.
+unsafe fn searchable_item(item: &SpotlightItem) -> id {
+    let attributes: id = msg_send![class!(CSSearchableItemAttributeSet), alloc];
+    let attributes: id = msg_send![attributes, initWithItemContentType: ns_string("public.audio")];
+    let _: () = msg_send![attributes, setTitle: ns_string(&item.title)];
+    if let Some(ref artist) = item.artist {
+        let _: () = msg_send![attributes, setArtist: ns_string(artist)];
+        let _: () = msg_send![attributes, setContentDescription: ns_string(artist)];
+    }
+    if let Some(ref album) = item.album {
+        let _: () = msg_send![attributes, setAlbum: ns_string(album)];
+    }
+    if let Some(duration) = item.duration {
+        let seconds: id = msg_send![class!(NSNumber), numberWithDouble: duration.as_secs_f64()];
+        let _: () = msg_send![attributes, setDuration: seconds];
+    }
+
+    let searchable: id = msg_send![class!(CSSearchableItem), alloc];
+    let searchable: id = msg_send![
+        searchable,
+        initWithUniqueIdentifier: ns_string(&item.url)
+        domainIdentifier: ns_string(item.domain)
+        attributeSet: attributes
+    ];
+    let _: () = msg_send![attributes, release];
+    msg_send![searchable, autorelease]
+}
+
+/// Handle `application:continueUserActivity:restorationHandler:` on the app delegate.
+///
+/// The method is added to Dioxus's delegate class at runtime rather than
+/// replacing the delegate, the same approach the URL handler takes.
+fn register_activity_handler() {
+    Queue::main().exec_async(|| unsafe {
+        extern "C" fn continue_user_activity(
+            _this: &Object,
+            _sel: Sel,
+            _application: id,
+            activity: id,
+            _restoration_handler: id,
+        ) -> BOOL {
+            unsafe {
+                let activity_type: id = msg_send![activity, activityType];
+                if rust_string(activity_type).as_deref() != Some(SEARCHABLE_ITEM_ACTION_TYPE) {
+                    return NO;
+                }
+                let user_info: id = msg_send![activity, userInfo];
+                if user_info == nil {
+                    return NO;
+                }
+                let identifier: id = msg_send![
+                    user_info,
+                    objectForKey: ns_string(SEARCHABLE_ITEM_ACTIVITY_IDENTIFIER)
+                ];
+                let Some(url) = rust_string(identifier) else {
+                    return NO;
+                };
+
+                info!("Opened Spotlight result: {url}");
+
+                crate::ui::shortcuts::send_url(url);
+                YES
+            }
+        }
+
+        let app: id = msg_send![class!(NSApplication), sharedApplication];
+        let delegate: id = msg_send![app, delegate];
+        if delegate == nil {
+            warn!("No app delegate, Spotlight results won't open bae");
+            return;
+        }
+        let delegate_class: *const Class = msg_send![delegate, class];
+        let added = objc::runtime::class_addMethod(
+            delegate_class as *mut Class,
+            sel!(application:continueUserActivity:restorationHandler:),
+            std::mem::transmute::<
+                extern "C" fn(&Object, Sel, id, id, id) -> BOOL,
+                objc::runtime::Imp,
+            >(continue_user_activity),
+            b"c@:@@@\0".as_ptr() as *const std::os::raw::c_char,
+        );
+        if added == NO {
+            warn!("App delegate already handles user activities, Spotlight handler not added");
+        }
+    });
+}
+
+unsafe fn ns_string(s: &str) -> id {
+    NSString::alloc(nil).init_str(s).autorelease()
+}
+
+unsafe fn rust_string(ns: id) -> Option<String> {
+    if ns == nil {
+        return None;
+    }
+    let bytes: *const std::os::raw::c_char = msg_send![ns, UTF8String];
+    if bytes.is_null() {
+        return None;
+    }
+    std::ffi::CStr::from_ptr(bytes)
+        .to_str()
+        .ok()
+        .map(str::to_string)
+}
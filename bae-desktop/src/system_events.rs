@@ -0,0 +1,329 @@
+//! Pause or duck playback on macOS system events
+//!
+//! Sleep and screen lock arrive as workspace and distributed notifications.
+//! Calls and other apps' sound have no notification, so CoreAudio's process
+//! list is polled instead: a call shows up as another process recording from
+//! a microphone, and notification sounds as another process playing output.
+//! The process list needs macOS 14.2 or later; on older systems only sleep
+//! and screen lock are handled.
+
+use bae_core::config::Config;
+use bae_core::playback::{PlaybackHandle, PlaybackProgress, PlaybackState};
+use cocoa::base::{id, nil};
+use cocoa::foundation::NSString;
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing::info;
+
+static PAUSE_ON_SLEEP: AtomicBool = AtomicBool::new(true);
+static PAUSE_ON_SCREEN_LOCK: AtomicBool = AtomicBool::new(false);
+static PAUSE_DURING_CALLS: AtomicBool = AtomicBool::new(true);
+static DUCK_FOR_OTHER_AUDIO: AtomicBool = AtomicBool::new(false);
+
+static PLAYER: OnceLock<Player> = OnceLock::new();
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Polls of silence before the volume comes back up, so a run of short
+/// sounds doesn't make it pump
+const UNDUCK_AFTER_POLLS: u32 = 4;
+
+/// Playback handle plus the last state it reported, reachable from the
+/// Objective-C notification callbacks
+struct Player {
+    playback: PlaybackHandle,
+    state: Mutex<PlaybackState>,
+}
+
+impl Player {
+    fn is_playing(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), PlaybackState::Playing { .. })
+    }
+
+    fn is_paused(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), PlaybackState::Paused { .. })
+    }
+
+    /// Pause if something is playing; returns whether it paused
+    fn pause_if_playing(&self, reason: &str) -> bool {
+        if !self.is_playing() {
+            return false;
+        }
+
+        info!("Pausing playback: {reason}");
+
+        self.playback.pause();
+        true
+    }
+}
+
+/// Copy the system event toggles from `config`; takes effect immediately
+pub fn apply_config(config: &Config) {
+    PAUSE_ON_SLEEP.store(config.pause_on_sleep, Ordering::Relaxed);
+    PAUSE_ON_SCREEN_LOCK.store(config.pause_on_screen_lock, Ordering::Relaxed);
+    PAUSE_DURING_CALLS.store(config.pause_during_calls, Ordering::Relaxed);
+    DUCK_FOR_OTHER_AUDIO.store(config.duck_for_other_audio, Ordering::Relaxed);
+}
+
+/// Start watching for sleep, screen lock, calls and other apps' audio.
+///
+/// Must be called on the main thread so the notification observers are
+/// attached to its run loop.
+pub fn setup_system_events(
+    playback_handle: PlaybackHandle,
+    config: &Config,
+    runtime_handle: tokio::runtime::Handle,
+) {
+    apply_config(config);
+
+    let player = PLAYER.get_or_init(|| Player {
+        playback: playback_handle.clone(),
+        state: Mutex::new(PlaybackState::Stopped),
+    });
+
+    runtime_handle.spawn(async move {
+        let mut progress_rx = playback_handle.subscribe_progress();
+        while let Some(progress) = progress_rx.recv().await {
+            if let PlaybackProgress::StateChanged { state } = progress {
+                *player.state.lock().unwrap() = state;
+            }
+        }
+    });
+
+    register_observers();
+    std::thread::spawn(move || watch_audio_activity(player));
+}
+
+// --- Sleep and screen lock ---
+
+unsafe fn observer_class() -> &'static Class {
+    if let Some(class) = Class::get("BaeSystemEventObserver") {
+        return class;
+    }
+
+    let superclass = class!(NSObject);
+    let mut decl = ClassDecl::new("BaeSystemEventObserver", superclass).unwrap();
+
+    extern "C" fn will_sleep(_this: &Object, _cmd: Sel, _notification: id) {
+        if let Some(player) = PLAYER.get() {
+            if PAUSE_ON_SLEEP.load(Ordering::Relaxed) {
+                player.pause_if_playing("system is going to sleep");
+            }
+        }
+    }
+
+    extern "C" fn screen_locked(_this: &Object, _cmd: Sel, _notification: id) {
+        if let Some(player) = PLAYER.get() {
+            if PAUSE_ON_SCREEN_LOCK.load(Ordering::Relaxed) {
+                player.pause_if_playing("screen locked");
+            }
+        }
+    }
+
+    decl.add_method(
+        sel!(willSleep:),
+        will_sleep as extern "C" fn(&Object, Sel, id),
+    );
+    decl.add_method(
+        sel!(screenLocked:),
+        screen_locked as extern "C" fn(&Object, Sel, id),
+    );
+    decl.register()
+}
+
+fn register_observers() {
+    unsafe {
+        // Never released: observes for the lifetime of the app
+        let observer: id = msg_send![observer_class(), new];
+
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let workspace_center: id = msg_send![workspace, notificationCenter];
+        let _: () = msg_send![workspace_center,
+            addObserver: observer
+            selector: sel!(willSleep:)
+            name: NSString::alloc(nil).init_str("NSWorkspaceWillSleepNotification")
+            object: nil];
+
+        let distributed_center: id =
+            msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+        let _: () = msg_send![distributed_center,
+            addObserver: observer
+            selector: sel!(screenLocked:)
+            name: NSString::alloc(nil).init_str("com.apple.screenIsLocked")
+            object: nil];
+    }
+
+    info!("Watching for system sleep and screen lock");
+}
+
+// --- Calls and other apps' audio ---
+
+fn watch_audio_activity(player: &Player) {
+    let own_pid = std::process::id() as i32;
+    if other_process_activity(own_pid).is_none() {
+        info!("CoreAudio process list unavailable; not watching for calls or other audio");
+        return;
+    }
+
+    let mut in_call = false;
+    let mut paused_for_call = false;
+    let mut ducked = false;
+    let mut quiet_polls = 0;
+
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+        let Some(activity) = other_process_activity(own_pid) else {
+            continue;
+        };
+
+        if activity.recording && !in_call {
+            in_call = true;
+            if PAUSE_DURING_CALLS.load(Ordering::Relaxed) {
+                paused_for_call = player.pause_if_playing("microphone in use");
+            }
+        } else if !activity.recording && in_call {
+            in_call = false;
+            // Only pick up where the call interrupted; a pause or stop
+            // during the call stays put
+            if paused_for_call && player.is_paused() {
+                info!("Microphone released, resuming playback");
+
+                player.playback.resume();
+            }
+            paused_for_call = false;
+        }
+
+        quiet_polls = if activity.playing { 0 } else { quiet_polls + 1 };
+        let should_duck = DUCK_FOR_OTHER_AUDIO.load(Ordering::Relaxed)
+            && (activity.playing || (ducked && quiet_polls < UNDUCK_AFTER_POLLS));
+        if should_duck != ducked {
+            ducked = should_duck;
+            player.playback.set_ducked(ducked);
+        }
+    }
+}
+
+/// What processes other than bae are doing with audio right now
+struct AudioActivity {
+    recording: bool,
+    playing: bool,
+}
+
+#[repr(C)]
+struct AudioObjectPropertyAddress {
+    selector: u32,
+    scope: u32,
+    element: u32,
+}
+
+#[link(name = "CoreAudio", kind = "framework")]
+extern "C" {
+    fn AudioObjectGetPropertyDataSize(
+        object_id: u32,
+        address: *const AudioObjectPropertyAddress,
+        qualifier_size: u32,
+        qualifier: *const c_void,
+        out_size: *mut u32,
+    ) -> i32;
+    fn AudioObjectGetPropertyData(
+        object_id: u32,
+        address: *const AudioObjectPropertyAddress,
+        qualifier_size: u32,
+        qualifier: *const c_void,
+        io_size: *mut u32,
+        out_data: *mut c_void,
+    ) -> i32;
+}
+
+const fn four_cc(code: &[u8; 4]) -> u32 {
+    u32::from_be_bytes(*code)
+}
+
+/// `kAudioObjectSystemObject`
+const SYSTEM_OBJECT: u32 = 1;
+/// `kAudioObjectPropertyScopeGlobal`
+const SCOPE_GLOBAL: u32 = four_cc(b"glob");
+/// `kAudioObjectPropertyElementMain`
+const ELEMENT_MAIN: u32 = 0;
+/// `kAudioHardwarePropertyProcessObjectList`
+const PROCESS_OBJECT_LIST: u32 = four_cc(b"prs#");
+/// `kAudioProcessPropertyPID`
+const PROCESS_PID: u32 = four_cc(b"ppid");
+/// `kAudioProcessPropertyIsRunningInput`
+const PROCESS_IS_RUNNING_INPUT: u32 = four_cc(b"piri");
+/// `kAudioProcessPropertyIsRunningOutput`
+const PROCESS_IS_RUNNING_OUTPUT: u32 = four_cc(b"piro");
+
+fn address(selector: u32) -> AudioObjectPropertyAddress {
+    AudioObjectPropertyAddress {
+        selector,
+        scope: SCOPE_GLOBAL,
+        element: ELEMENT_MAIN,
+    }
+}
+
+/// Read a fixed-size property, None if the object doesn't have it
+fn get_property<T: Copy + Default>(object_id: u32, selector: u32) -> Option<T> {
+    let mut value = T::default();
+    let mut size = std::mem::size_of::<T>() as u32;
+    let status = unsafe {
+        AudioObjectGetPropertyData(
+            object_id,
+            &address(selector),
+            0,
+            std::ptr::null(),
+            &mut size,
+            &mut value as *mut T as *mut c_void,
+        )
+    };
+    (status == 0).then_some(value)
+}
+
+fn process_objects() -> Option<Vec<u32>> {
+    let address = address(PROCESS_OBJECT_LIST);
+    let mut size = 0u32;
+    let status = unsafe {
+        AudioObjectGetPropertyDataSize(SYSTEM_OBJECT, &address, 0, std::ptr::null(), &mut size)
+    };
+    if status != 0 {
+        return None;
+    }
+
+    let mut objects = vec![0u32; size as usize / std::mem::size_of::<u32>()];
+    let status = unsafe {
+        AudioObjectGetPropertyData(
+            SYSTEM_OBJECT,
+            &address,
+            0,
+            std::ptr::null(),
+            &mut size,
+            objects.as_mut_ptr() as *mut c_void,
+        )
+    };
+    if status != 0 {
+        return None;
+    }
+    objects.truncate(size as usize / std::mem::size_of::<u32>());
+    Some(objects)
+}
+
+fn other_process_activity(own_pid: i32) -> Option<AudioActivity> {
+    let mut activity = AudioActivity {
+        recording: false,
+        playing: false,
+    };
+    for object_id in process_objects()? {
+        if get_property::<i32>(object_id, PROCESS_PID) == Some(own_pid) {
+            continue;
+        }
+        activity.recording |= get_property::<u32>(object_id, PROCESS_IS_RUNNING_INPUT)
+            .is_some_and(|running| running != 0);
+        activity.playing |= get_property::<u32>(object_id, PROCESS_IS_RUNNING_OUTPUT)
+            .is_some_and(|running| running != 0);
+    }
+    Some(activity)
+}
@@ -22,6 +22,14 @@ pub enum Route {
     AlbumDetail { album_id: String, release_id: String },
     #[route("/artist/:artist_id")]
     ArtistDetail { artist_id: String },
+    #[route("/browse")]
+    Browse {},
+    #[route("/browse/genre?:genre")]
+    GenreAlbums { genre: String },
+    #[route("/browse/decade/:decade")]
+    DecadeAlbums { decade: i32 },
+    #[route("/playlists")]
+    Playlists {},
     #[route("/import")]
     ImportWorkflowManager {},
     #[route("/settings")]
@@ -68,6 +76,7 @@ pub fn launch_app(context: super::app_context::AppContext) {
         image_server: context.image_server.clone(),
         user_keypair: context.user_keypair.clone(),
         sync_handle: context.sync_handle.clone(),
+        party: context.party.clone(),
     };
     #[cfg(not(feature = "torrent"))]
     let services = super::app_context::AppServices {
@@ -80,6 +89,7 @@ pub fn launch_app(context: super::app_context::AppContext) {
         image_server: context.image_server.clone(),
         user_keypair: context.user_keypair.clone(),
         sync_handle: context.sync_handle.clone(),
+        party: context.party.clone(),
     };
 
     LaunchBuilder::desktop()
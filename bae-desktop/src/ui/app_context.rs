@@ -4,8 +4,11 @@
 //! This file contains the `AppServices` struct for passing backend service handles
 //! from main.rs through the launch boundary.
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
+use bae_core::admin_routes::SyncControl;
 use bae_core::cache;
 use bae_core::config;
 use bae_core::encryption::EncryptionService;
@@ -13,6 +16,7 @@ use bae_core::image_server::ImageServerHandle;
 use bae_core::import;
 use bae_core::keys::{KeyService, UserKeypair};
 use bae_core::library::SharedLibraryManager;
+use bae_core::party::PartySession;
 use bae_core::playback;
 use bae_core::sync::cloud_home_bucket::CloudHomeSyncBucket;
 use bae_core::sync::hlc::Hlc;
@@ -36,6 +40,9 @@ pub struct SyncHandle {
     pub hlc: Arc<Hlc>,
     /// Device ID for this device (used in push, cursors, and SyncService)
     pub device_id: String,
+    /// This device's own key for signing changesets, enrolled in the
+    /// membership chain
+    pub device_keypair: UserKeypair,
     /// Shared encryption service (same instance as bucket_client's).
     /// Wrapped in Arc<RwLock> so key rotation can update it atomically.
     pub encryption: Arc<RwLock<EncryptionService>>,
@@ -50,6 +57,8 @@ pub struct SyncHandle {
     /// Channel receiver for the sync trigger. The sync loop (Phase 5c) takes
     /// this once via `take_trigger_rx()`.
     sync_trigger_rx: Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::Receiver<()>>>>,
+    /// Set in the server's maintenance mode, which pauses the sync loop
+    pub maintenance: Arc<AtomicBool>,
 }
 
 // SAFETY: The raw sqlite3 pointer is only used for session extension operations
@@ -63,10 +72,12 @@ impl SyncHandle {
         bucket_client: CloudHomeSyncBucket,
         hlc: Hlc,
         device_id: String,
+        device_keypair: UserKeypair,
         raw_db: *mut libsqlite3_sys::sqlite3,
         session: SyncSession,
         sync_trigger: tokio::sync::mpsc::Sender<()>,
         sync_trigger_rx: tokio::sync::mpsc::Receiver<()>,
+        maintenance: Arc<AtomicBool>,
     ) -> Self {
         // Share the encryption lock with the bucket client so key rotation
         // is visible to both the sync loop and the S3 operations.
@@ -75,11 +86,13 @@ impl SyncHandle {
             bucket_client: Arc::new(bucket_client),
             hlc: Arc::new(hlc),
             device_id,
+            device_keypair,
             encryption,
             raw_db,
             session: Arc::new(tokio::sync::Mutex::new(Some(session))),
             sync_trigger,
             sync_trigger_rx: Arc::new(tokio::sync::Mutex::new(Some(sync_trigger_rx))),
+            maintenance,
         }
     }
 
@@ -88,12 +101,38 @@ impl SyncHandle {
         self.raw_db
     }
 
+    /// Device ID and sync trigger for the server's admin routes
+    pub fn control(&self) -> SyncControl {
+        SyncControl {
+            device_id: self.device_id.clone(),
+            trigger: self.sync_trigger.clone(),
+            bucket: self.bucket_client.clone(),
+        }
+    }
+
     /// Take the sync trigger receiver. Returns `None` if already taken.
     /// Called once by the sync loop (Phase 5c) to own the receive end.
     pub async fn take_trigger_rx(&self) -> Option<tokio::sync::mpsc::Receiver<()>> {
         self.sync_trigger_rx.lock().await.take()
     }
 
+    /// Hold the sync loop while maintenance mode is on, checking again every
+    /// `interval` or on a trigger, which turning it off also sends. Returns
+    /// false once the trigger channel closes.
+    pub async fn wait_out_maintenance(
+        &self,
+        trigger_rx: &mut tokio::sync::mpsc::Receiver<()>,
+        interval: Duration,
+    ) -> bool {
+        while self.maintenance.load(Ordering::Relaxed) {
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                msg = trigger_rx.recv() => { if msg.is_none() { return false; } }
+            }
+        }
+        true
+    }
+
     /// Replace the encryption key (used after member revocation / key rotation).
     /// This updates the shared lock so both the sync loop and the bucket client
     /// see the new key immediately.
@@ -131,6 +170,8 @@ pub struct AppServices {
     pub user_keypair: Option<UserKeypair>,
     /// Sync infrastructure handle (present when sync is configured and encryption is enabled)
     pub sync_handle: Option<SyncHandle>,
+    /// Party mode session, shared with the server's guest routes
+    pub party: Arc<PartySession>,
 }
 
 #[derive(Clone)]
@@ -146,4 +187,5 @@ pub struct AppContext {
     pub image_server: ImageServerHandle,
     pub user_keypair: Option<UserKeypair>,
     pub sync_handle: Option<SyncHandle>,
+    pub party: Arc<PartySession>,
 }
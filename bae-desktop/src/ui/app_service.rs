@@ -11,33 +11,70 @@
 //! - Call action methods like `app.play_album()`
 
 use crate::ui::display_types::{
-    album_from_db_ref, artist_from_db_ref, file_from_db_ref, release_from_db_ref, track_from_db_ref,
+    album_comment_from_db, album_from_db_ref, album_version_from_db_ref, artist_credit_from_db,
+    artist_from_db_ref, composers_by_album_from_db, cover_upgrade_offer_from_core,
+    custom_tag_from_db, custom_tag_to_db, device_availability_from_db, file_from_db_ref,
+    metadata_change_from_core, metadata_update_to_core, playlist_entry_from_db, playlist_from_db,
+    release_from_db_ref, track_credits_from_db, track_from_db_ref, track_mapping_from_db,
+    track_works_from_db,
 };
 use crate::ui::import_helpers::consume_scan_events;
 use bae_core::cache;
 use bae_core::config;
 use bae_core::db::ImportStatus;
 use bae_core::image_server::ImageServerHandle;
+use bae_core::import::artist_image::{fetch_missing_artist_images, ArtistImageSources};
+use bae_core::import::cover_upgrade::{find_cover_upgrades, image_dimensions};
 use bae_core::import::{self, ImportProgress};
 use bae_core::keys::{KeyService, UserKeypair};
-use bae_core::library::{LibraryEvent, SharedLibraryManager};
+use bae_core::library::key_rotation::{begin_key_rotation, complete_key_rotation};
+use bae_core::library::musicbrainz_collection::{sync_collection, MusicBrainzAccount};
+use bae_core::library::{LibraryEvent, LibraryManager, SharedLibraryManager};
+use bae_core::party::{PartyEvent, PartySession, PartyStatus};
 use bae_core::playback::{self, PlaybackProgress};
+use bae_core::storage::offline;
+use bae_core::storage::scrub::{scrub_storage, ScrubSample};
+use bae_core::storage::tiering::{needs_recall, offload_candidates};
+use bae_core::storage::transfer::{
+    pending_transfers, TransferProgress, TransferService, TransferTarget,
+};
+use bae_core::storage::usage::MeteredCloudStorage;
 #[cfg(feature = "torrent")]
 use bae_core::torrent;
 use bae_ui::display_types::{
-    Album, Artist, File, LibrarySortField, QueueItem, Release, SortCriterion, SortDirection, Track,
-    TrackImportState,
+    Album, AlbumVersion, Artist, BrowseDecade, BrowseGenre, Collection, CreditedArtist, CustomTag,
+    File, LibraryFilter, LibraryQualityFilter, LibrarySortField, LibraryStorageFilter, OfflinePin,
+    PlayHistoryItem, QueueItem, Release, ReleaseAvailability, SortCriterion, SortDirection, Track,
+    TrackImportState, TrackWork,
 };
 use bae_ui::stores::{
     ActiveImport, ActiveImportsUiStateStoreExt, AlbumDetailStateStoreExt, AppState,
-    AppStateStoreExt, ArtistDetailStateStoreExt, ConfigStateStoreExt, DeviceActivityInfo,
-    ImportOperationStatus, LibrarySortStateStoreExt, LibraryStateStoreExt, Member, MemberRole,
-    PlaybackStatus, PlaybackUiStateStoreExt, PrepareStep, SyncStateStoreExt, UiStateStoreExt,
+    AppStateStoreExt, ArtistDetailStateStoreExt, BrowseStateStoreExt, ConfigStateStoreExt,
+    DeviceActivityInfo, ImportOperationStatus, LibrarySortStateStoreExt, LibraryStateStoreExt,
+    Member, MemberRole, PartyInfo, PartyRequestInfo, PartyStateStoreExt, PlaybackStatus,
+    PlaybackUiStateStoreExt, PlaylistsStateStoreExt, PrepareStep, SyncConflictFieldInfo,
+    SyncConflictInfo, SyncStateStoreExt, UiStateStoreExt,
 };
 use dioxus::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use super::app_context::{AppServices, SyncHandle};
+use super::components::settings::integrity::report_albums;
+
+/// Releases the storage scrub reads back per run, and files per release
+const SCRUB_RELEASES_PER_RUN: usize = 25;
+const SCRUB_FILES_PER_RELEASE: usize = 3;
+
+/// Time between storage scrub runs
+const SCRUB_RUN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
+
+/// Releases moved to the cloud home per offload run
+const OFFLOAD_RELEASES_PER_RUN: usize = 5;
+
+/// Time between offload runs
+const OFFLOAD_RUN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
 
 /// Main application service that encapsulates state and backend coordination.
 ///
@@ -69,6 +106,10 @@ pub struct AppService {
     pub user_keypair: Option<UserKeypair>,
     /// Sync infrastructure handle (present when sync is configured and encryption is enabled).
     pub sync_handle: Option<SyncHandle>,
+    /// Party mode session, shared with the server's guest routes
+    pub party: Arc<PartySession>,
+    /// Running storage transfers by release ID, for cancelling them
+    pub transfers: Arc<std::sync::Mutex<HashMap<String, Arc<TransferService>>>>,
 }
 
 impl AppService {
@@ -88,6 +129,8 @@ impl AppService {
                 image_server: services.image_server.clone(),
                 user_keypair: services.user_keypair.clone(),
                 sync_handle: services.sync_handle.clone(),
+                party: services.party.clone(),
+                transfers: Arc::default(),
             }
         }
         #[cfg(not(feature = "torrent"))]
@@ -103,6 +146,8 @@ impl AppService {
                 image_server: services.image_server.clone(),
                 user_keypair: services.user_keypair.clone(),
                 sync_handle: services.sync_handle.clone(),
+                party: services.party.clone(),
+                transfers: Arc::default(),
             }
         }
     }
@@ -118,9 +163,19 @@ impl AppService {
         self.subscribe_library_events();
         self.subscribe_folder_scan_events();
         self.subscribe_sync_events();
-        self.subscribe_url_events();
+        self.subscribe_party_events();
         self.load_initial_data();
         self.process_pending_deletions();
+        self.resume_key_rotation();
+        self.resume_storage_transfers();
+        self.stream_cloud_releases();
+        self.restore_offline_pins();
+        self.record_release_availability();
+        self.fetch_missing_artist_images();
+        self.find_cover_upgrades();
+        self.scrub_storage();
+        self.tier_storage();
+        self.sync_musicbrainz_collection();
     }
 
     // =========================================================================
@@ -201,6 +256,9 @@ impl AppService {
                             pb.position_ms = position_ms;
                             pb.duration_ms = duration_ms;
                             pb.pregap_ms = pregap_ms;
+                            if status == PlaybackStatus::Playing {
+                                pb.warming_up = None;
+                            }
                         }
 
                         // Load album and artist info for current track
@@ -296,7 +354,18 @@ impl AppService {
                             state.playback().status().set(PlaybackStatus::Playing);
                         }
                     }
-                    PlaybackProgress::PlaybackError { message } => {
+                    // Stays up until something plays; the restore takes hours
+                    PlaybackProgress::PlaybackError {
+                        code: bae_core::error_code::ErrorCode::StorageWarmingUp,
+                        ..
+                    } => {
+                        state.playback().warming_up().set(Some(
+                            "This track is archived in cold storage. It's being restored and \
+                             can be played in a few hours."
+                                .to_string(),
+                        ));
+                    }
+                    PlaybackProgress::PlaybackError { message, .. } => {
                         state.playback().playback_error().set(Some(message.clone()));
                         // Clear error after 5 seconds
                         let state = state;
@@ -309,31 +378,10 @@ impl AppService {
                         // Load track/album details for queue items before writing store
                         let mut queue_items = Vec::new();
                         for track_id in &tracks {
-                            if let Ok(Some(track)) = library_manager.get().get_track(track_id).await
+                            if let Some(item) =
+                                load_queue_item(&library_manager, &imgs, track_id).await
                             {
-                                let (album_title, cover_url) = if let Ok(album_id) =
-                                    library_manager.get().get_album_id_for_track(track_id).await
-                                {
-                                    if let Ok(Some(album)) =
-                                        library_manager.get().get_album_by_id(&album_id).await
-                                    {
-                                        let cover = album
-                                            .cover_release_id
-                                            .as_ref()
-                                            .map(|rid| imgs.image_url(rid));
-                                        (album.title, cover)
-                                    } else {
-                                        ("Unknown Album".to_string(), None)
-                                    }
-                                } else {
-                                    ("Unknown Album".to_string(), None)
-                                };
-
-                                queue_items.push(QueueItem {
-                                    track: track_from_db_ref(&track),
-                                    album_title,
-                                    cover_url,
-                                });
+                                queue_items.push(item);
                             }
                         }
 
@@ -353,6 +401,9 @@ impl AppService {
                         #[cfg(target_os = "macos")]
                         crate::ui::window_activation::set_playback_repeat_mode(mode);
                     }
+                    PlaybackProgress::RadioChanged { enabled } => {
+                        state.playback().radio_enabled().set(enabled);
+                    }
                     _ => {}
                 }
             }
@@ -389,19 +440,30 @@ impl AppService {
 
     /// Subscribe to import progress and update Store
     fn subscribe_import_progress(&self) {
-        let state = self.state;
-        let import_handle = self.import_handle.clone();
+        let app = self.clone();
 
         spawn(async move {
-            let mut progress_rx = import_handle.subscribe_all_imports();
+            let mut progress_rx = app.import_handle.subscribe_all_imports();
             while let Some(event) = progress_rx.recv().await {
-                handle_import_progress(&state, event);
+                // An import kept in the cloud home leaves a move there to run
+                let release_imported = matches!(
+                    event,
+                    ImportProgress::Complete {
+                        release_id: None,
+                        ..
+                    }
+                );
+                handle_import_progress(&app.state, event);
+                if release_imported {
+                    app.resume_storage_transfers();
+                }
             }
         });
     }
 
     /// Subscribe to library events and reload when albums change
     fn subscribe_library_events(&self) {
+        let app = self.clone();
         let state = self.state;
         let library_manager = self.library_manager.clone();
         let imgs = self.image_server.clone();
@@ -412,6 +474,8 @@ impl AppService {
                 match event {
                     LibraryEvent::AlbumsChanged => {
                         load_library(&state, &library_manager, &imgs).await;
+                        load_playlists(&state, &library_manager, &imgs).await;
+                        app.sync_musicbrainz_collection();
                     }
                 }
             }
@@ -428,6 +492,38 @@ impl AppService {
         });
     }
 
+    /// Subscribe to party mode events: queue approved guest requests and
+    /// keep the host's view of the party current
+    fn subscribe_party_events(&self) {
+        let state = self.state;
+        let party = self.party.clone();
+        let playback_handle = self.playback_handle.clone();
+
+        spawn(async move {
+            let mut rx = party.subscribe();
+            loop {
+                match rx.recv().await {
+                    Ok(PartyEvent::Changed) => {
+                        state
+                            .party()
+                            .active()
+                            .set(party.status().map(party_info_from_status));
+                    }
+                    Ok(PartyEvent::Queue(track_id)) => {
+                        playback_handle.add_to_queue(vec![track_id]);
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                        state
+                            .party()
+                            .active()
+                            .set(party.status().map(party_info_from_status));
+                    }
+                }
+            }
+        });
+    }
+
     /// Start the background sync loop if sync is configured.
     ///
     /// Runs periodic sync cycles: push local changes, pull remote changes,
@@ -488,30 +584,13 @@ impl AppService {
         });
     }
 
-    /// Subscribe to incoming `bae://` URLs from Apple Events or CLI arguments.
-    fn subscribe_url_events(&self) {
-        let mut rx = crate::ui::shortcuts::subscribe_url();
-
-        // Drain any URL that arrived before this subscriber existed (cold launch)
-        if let Some(url) = crate::ui::shortcuts::take_buffered_url() {
-            tracing::info!("URL received in app (buffered): {url}");
-            // TODO: Parse bae://share/{token} and trigger import
-        }
-
-        spawn(async move {
-            while let Ok(url) = rx.recv().await {
-                tracing::info!("URL received in app: {url}");
-                // TODO: Parse bae://share/{token} and trigger import
-            }
-        });
-    }
-
     /// Load initial data from database
     fn load_initial_data(&self) {
         self.state.playback().volume().set(1.0);
         self.load_config();
         self.load_active_imports();
         self.load_library();
+        self.load_playlists();
     }
 
     /// Process any pending file deletions from previous transfers
@@ -523,6 +602,384 @@ impl AppService {
         });
     }
 
+    /// Re-encrypt stored files under the new master key, when a rotation began
+    /// before this launch
+    fn resume_key_rotation(&self) {
+        if !self.config.key_rotation_in_progress {
+            return;
+        }
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let key_service = self.key_service.clone();
+        let mut config = self.config.clone();
+
+        state.config().key_rotation_progress().set(Some((0, 0)));
+        spawn(async move {
+            let result = complete_key_rotation(
+                library_manager.get(),
+                &key_service,
+                &mut config,
+                |done, total| {
+                    state
+                        .config()
+                        .key_rotation_progress()
+                        .set(Some((done, total)))
+                },
+            )
+            .await;
+            if let Err(e) = result {
+                tracing::error!("Failed to complete key rotation: {}", e);
+            }
+            state.config().key_rotation_progress().set(None);
+        });
+    }
+
+    /// Report which releases this device holds, so other devices can show it
+    fn record_release_availability(&self) {
+        let library_manager = self.library_manager.clone();
+        let library_dir = self.config.library_dir.clone();
+        let device_id = self.config.device_id.clone();
+        let cache = self.cache.clone();
+
+        spawn(async move {
+            if let Err(e) = library_manager
+                .get()
+                .record_release_availability(&device_id, &library_dir, &cache)
+                .await
+            {
+                tracing::warn!("Failed to record release availability: {}", e);
+            }
+        });
+    }
+
+    /// Bring the linked MusicBrainz collection in line with the library, in
+    /// the background. Called at startup and whenever albums change; a call
+    /// made while a sync is running makes it go round once more.
+    pub fn sync_musicbrainz_collection(&self) {
+        static RUNNING: AtomicBool = AtomicBool::new(false);
+        static AGAIN: AtomicBool = AtomicBool::new(false);
+
+        let Some(collection_id) = self
+            .state
+            .config()
+            .musicbrainz_collection_id()
+            .peek()
+            .clone()
+        else {
+            return;
+        };
+        if RUNNING.swap(true, Ordering::SeqCst) {
+            AGAIN.store(true, Ordering::SeqCst);
+            return;
+        }
+
+        let library_manager = self.library_manager.clone();
+        let key_service = self.key_service.clone();
+
+        spawn(async move {
+            loop {
+                AGAIN.store(false, Ordering::SeqCst);
+                let result = async {
+                    let mut account = MusicBrainzAccount::load(&key_service)?;
+                    sync_collection(library_manager.get(), &mut account, &collection_id).await
+                }
+                .await;
+                if let Err(e) = result {
+                    tracing::warn!("MusicBrainz collection sync failed: {}", e);
+
+                    break;
+                }
+                if !AGAIN.load(Ordering::SeqCst) {
+                    break;
+                }
+            }
+            RUNNING.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// Look up photos for artists that don't have one, in the background.
+    /// Called at startup and again when an image source key is saved.
+    pub fn fetch_missing_artist_images(&self) {
+        static RUNNING: AtomicBool = AtomicBool::new(false);
+
+        let config_store = self.state.config();
+        let sources = ArtistImageSources {
+            fanart_api_key: if *config_store.fanart_key_stored().peek() {
+                self.key_service.get_fanart_key()
+            } else {
+                None
+            },
+            discogs: if *config_store.discogs_key_stored().peek() {
+                self.key_service
+                    .get_discogs_key()
+                    .map(bae_core::discogs::DiscogsClient::new)
+            } else {
+                None
+            },
+        };
+        if sources.is_empty() || RUNNING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let library_manager = self.library_manager.clone();
+        let library_dir = self.config.library_dir.clone();
+
+        spawn(async move {
+            let saved =
+                fetch_missing_artist_images(library_manager.get(), &library_dir, &sources).await;
+            RUNNING.store(false, Ordering::SeqCst);
+            if saved > 0 {
+                library_manager.get().notify_albums_changed();
+            }
+        });
+    }
+
+    /// Look for larger versions of low-resolution covers in the background.
+    ///
+    /// Depending on the setting, they replace the covers right away or are
+    /// offered on the album page.
+    pub fn find_cover_upgrades(&self) {
+        static RUNNING: AtomicBool = AtomicBool::new(false);
+
+        if RUNNING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let library_dir = self.config.library_dir.clone();
+        let auto_replace = *state.config().auto_upgrade_covers().peek();
+        let discogs = if *state.config().discogs_key_stored().peek() {
+            self.key_service
+                .get_discogs_key()
+                .map(bae_core::discogs::DiscogsClient::new)
+        } else {
+            None
+        };
+
+        spawn(async move {
+            let upgrades = find_cover_upgrades(
+                library_manager.get(),
+                &library_dir,
+                discogs.as_ref(),
+                auto_replace,
+            )
+            .await;
+            RUNNING.store(false, Ordering::SeqCst);
+            if auto_replace {
+                if !upgrades.is_empty() {
+                    library_manager.get().notify_albums_changed();
+                }
+                return;
+            }
+            let mut offers = state.library().cover_upgrades();
+            let mut offers = offers.write();
+            for upgrade in &upgrades {
+                offers.insert(
+                    upgrade.release_id.clone(),
+                    cover_upgrade_offer_from_core(upgrade),
+                );
+            }
+        });
+    }
+
+    /// Read back a sample of the library's stored files every few hours, and
+    /// raise an alert when one turns out to be broken. See
+    /// `bae_core::storage::scrub`.
+    pub fn scrub_storage(&self) {
+        static RUNNING: AtomicBool = AtomicBool::new(false);
+
+        if RUNNING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let config = self.config.clone();
+        let key_service = self.key_service.clone();
+
+        spawn(async move {
+            loop {
+                // Cloud releases are read back from the cloud home, when there is one
+                let cloud_storage =
+                    library_cloud_storage(&config, &key_service, library_manager.get()).await;
+                let result = scrub_storage(
+                    library_manager.get(),
+                    &config.library_dir,
+                    cloud_storage,
+                    ScrubSample::Random(SCRUB_FILES_PER_RELEASE),
+                    SCRUB_RELEASES_PER_RUN,
+                )
+                .await;
+                match result {
+                    Ok(report) => {
+                        let found = report_albums(&report);
+                        let mut lib_lens = state.library();
+                        let mut lib = lib_lens.write();
+                        if !found.is_empty() {
+                            lib.scrub_alert = true;
+                        }
+                        for album in found {
+                            lib.scrub_broken_albums
+                                .retain(|a| a.album_id != album.album_id);
+                            lib.scrub_broken_albums.push(album);
+                        }
+                    }
+                    Err(e) => tracing::error!("Storage scrub failed: {}", e),
+                }
+                if let Ok(last) = library_manager.get().get_last_storage_scrub().await {
+                    state.library().last_storage_scrub().set(last);
+                }
+
+                tokio::time::sleep(SCRUB_RUN_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Let playback stream releases that are only kept in the cloud home
+    fn stream_cloud_releases(&self) {
+        let playback_handle = self.playback_handle.clone();
+        let library_manager = self.library_manager.clone();
+        let config = self.config.clone();
+        let key_service = self.key_service.clone();
+        let cache = self.cache.clone();
+
+        spawn(async move {
+            let Some(cloud_storage) =
+                library_cloud_storage(&config, &key_service, library_manager.get()).await
+            else {
+                return;
+            };
+            let (download_parallelism, readahead) = streaming_settings(&config);
+            playback_handle.set_library_cloud_source(playback::LibraryCloudSource {
+                cloud_storage,
+                download_parallelism,
+                readahead,
+                cache,
+            });
+        });
+    }
+
+    /// Keep the files of releases kept downloaded from being evicted, and
+    /// download any the cache lost. See `bae_core::storage::offline`.
+    fn restore_offline_pins(&self) {
+        let library_manager = self.library_manager.clone();
+        let config = self.config.clone();
+        let key_service = self.key_service.clone();
+        let cache = self.cache.clone();
+
+        spawn(async move {
+            let Some(cloud_storage) =
+                library_cloud_storage(&config, &key_service, library_manager.get()).await
+            else {
+                return;
+            };
+            let (download_parallelism, _) = streaming_settings(&config);
+            if let Err(e) = offline::restore_pins(
+                library_manager.get(),
+                cloud_storage,
+                &cache,
+                download_parallelism,
+            )
+            .await
+            {
+                tracing::warn!("Failed to restore offline pins: {}", e);
+            }
+        });
+    }
+
+    /// Move albums this device hasn't played in a while to the cloud home, and
+    /// bring them back when they play. Does nothing until an offload period is
+    /// set. See `bae_core::storage::tiering`.
+    fn tier_storage(&self) {
+        static RUNNING: AtomicBool = AtomicBool::new(false);
+
+        if RUNNING.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let app = self.clone();
+        spawn(async move {
+            loop {
+                app.offload_idle_releases().await;
+                tokio::time::sleep(OFFLOAD_RUN_INTERVAL).await;
+            }
+        });
+
+        let app = self.clone();
+        spawn(async move {
+            let mut progress_rx = app.playback_handle.subscribe_progress();
+            let mut last_release_id: Option<String> = None;
+            while let Some(progress) = progress_rx.recv().await {
+                let PlaybackProgress::StateChanged {
+                    state: playback::PlaybackState::Playing { track, .. },
+                } = progress
+                else {
+                    continue;
+                };
+                if last_release_id.as_deref() == Some(track.release_id.as_str()) {
+                    continue;
+                }
+                last_release_id = Some(track.release_id.clone());
+                if app.state.config().offload_after_days().peek().is_none() {
+                    continue;
+                }
+
+                let release = app
+                    .library_manager
+                    .get()
+                    .database()
+                    .get_release_by_id(&track.release_id)
+                    .await;
+                match release {
+                    Ok(Some(release)) if needs_recall(&release) => {
+                        tracing::info!("Bringing release {} back to this device", release.id);
+
+                        app.run_release_transfer(release.id, TransferTarget::ManagedLocal);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to load played release: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Start moving the longest-idle releases to the cloud home
+    async fn offload_idle_releases(&self) {
+        let Some(days) = *self.state.config().offload_after_days().peek() else {
+            return;
+        };
+        // Wait for earlier moves to finish before starting more
+        if !self.transfers.lock().unwrap().is_empty() {
+            return;
+        }
+        if library_cloud_storage(&self.config, &self.key_service, self.library_manager.get())
+            .await
+            .is_none()
+        {
+            return;
+        }
+
+        let candidates = match offload_candidates(self.library_manager.get(), days).await {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                tracing::warn!("Failed to find releases to offload: {}", e);
+
+                return;
+            }
+        };
+        let playing = self.state.playback().current_release_id().peek().clone();
+        for release_id in candidates
+            .into_iter()
+            .filter(|id| playing.as_ref() != Some(id))
+            .take(OFFLOAD_RELEASES_PER_RUN)
+        {
+            tracing::info!("Offloading release {} to the cloud home", release_id);
+
+            self.run_release_transfer(release_id, TransferTarget::Cloud);
+        }
+    }
+
     /// Load config into Store
     fn load_config(&self) {
         // Populate user identity in sync store
@@ -532,6 +989,13 @@ impl AppService {
             .map(|kp| hex::encode(kp.public_key));
         self.state.sync().user_pubkey().set(user_pubkey);
 
+        if !self.config.library_sort.is_empty() {
+            let criteria = core_sort_to_ui(&self.config.library_sort);
+            self.state.ui().library_sort().sort_criteria().set(criteria);
+        }
+        let filter = core_filter_to_ui(&self.config.library_filter);
+        self.state.ui().library_sort().filter().set(filter);
+
         self.sync_config_to_store(&self.config);
     }
 
@@ -544,6 +1008,7 @@ impl AppService {
             let mut config_lens = self.state.config();
             let mut cs = config_lens.write();
             cs.discogs_key_stored = config.discogs_key_stored;
+            cs.fanart_key_stored = config.fanart_key_stored;
             cs.encryption_key_stored = config.encryption_key_stored;
             cs.encryption_key_fingerprint = config.encryption_key_fingerprint.clone();
             cs.server_enabled = config.server_enabled;
@@ -559,6 +1024,19 @@ impl AppService {
             cs.torrent_max_uploads = config.torrent_max_uploads;
             cs.torrent_max_uploads_per_torrent = config.torrent_max_uploads_per_torrent;
             cs.share_base_url = config.share_base_url.clone();
+            cs.import_ignore_patterns = config.import_ignore_patterns.clone();
+            cs.import_min_audio_duration_secs = config.import_min_audio_duration_secs;
+            cs.import_network_share_url = config.import_network_share_url.clone();
+            cs.import_network_share_username = config.import_network_share_username.clone();
+            cs.musicbrainz_username = config.musicbrainz_username.clone();
+            cs.musicbrainz_collection_id = config.musicbrainz_collection_id.clone();
+            cs.pause_on_sleep = config.pause_on_sleep;
+            cs.pause_on_screen_lock = config.pause_on_screen_lock;
+            cs.pause_during_calls = config.pause_during_calls;
+            cs.duck_for_other_audio = config.duck_for_other_audio;
+            cs.auto_upgrade_covers = config.auto_upgrade_covers;
+            cs.encrypt_images = config.encrypt_images;
+            cs.offload_after_days = config.offload_after_days;
             cs.cloud_provider = config.cloud_provider.as_ref().map(|p| match p {
                 bae_core::config::CloudProvider::S3 => bae_ui::stores::config::CloudProvider::S3,
                 bae_core::config::CloudProvider::ICloud => {
@@ -576,6 +1054,10 @@ impl AppService {
                 bae_core::config::CloudProvider::BaeCloud => {
                     bae_ui::stores::config::CloudProvider::BaeCloud
                 }
+                bae_core::config::CloudProvider::Lan => bae_ui::stores::config::CloudProvider::Lan,
+                bae_core::config::CloudProvider::Folder => {
+                    bae_ui::stores::config::CloudProvider::Folder
+                }
             });
             cs.cloud_account_display = if matches!(
                 config.cloud_provider,
@@ -587,6 +1069,16 @@ impl AppService {
                 Some(bae_core::config::CloudProvider::BaeCloud)
             ) {
                 config.cloud_home_bae_cloud_username.clone()
+            } else if matches!(
+                config.cloud_provider,
+                Some(bae_core::config::CloudProvider::Lan)
+            ) {
+                Some("Local network".to_string())
+            } else if matches!(
+                config.cloud_provider,
+                Some(bae_core::config::CloudProvider::Folder)
+            ) {
+                config.cloud_home_folder_path.clone()
             } else {
                 cs.cloud_account_display.clone()
             };
@@ -670,25 +1162,112 @@ impl AppService {
         });
     }
 
-    /// Load albums from a followed library's local DB into the library state.
-    ///
-    /// If the followed library hasn't been synced yet (no local DB), triggers an
-    /// initial sync from the remote snapshot first.
-    pub fn load_followed_library(&self, followed_id: &str) {
-        let state = self.state;
-        let key_service = self.key_service.clone();
-        let imgs = self.image_server.clone();
-
-        // Read connection details from the Store (not the stale boot config)
-        let followed = {
-            let followed_libs = state.read().config.followed_libraries.clone();
-            followed_libs.into_iter().find(|f| f.id == followed_id)
-        };
+    /// Save the library page's sort order and filters to config.yaml, so
+    /// they're restored on the next launch
+    pub fn save_library_sort(&self) {
+        let library_sort = self.state.ui().library_sort().peek().clone();
+        self.save_config(move |config| {
+            config.library_sort = ui_sort_to_core(&library_sort.sort_criteria);
+            config.library_filter = ui_filter_to_core(&library_sort.filter);
+        });
+    }
 
-        let Some(followed) = followed else {
-            state
-                .library()
-                .error()
+    /// Add an album to a collection, or take it out if it's already there
+    pub fn toggle_album_collection(&self, album_id: &str, collection_id: &str) {
+        let library_manager = self.library_manager.clone();
+        let album_id = album_id.to_string();
+        let collection_id = collection_id.to_string();
+        let in_collection = self
+            .state
+            .library()
+            .collection_ids_by_album()
+            .peek()
+            .get(&album_id)
+            .is_some_and(|ids| ids.contains(&collection_id));
+
+        self.edit_collection("update collection", async move {
+            let lm = library_manager.get();
+            if in_collection {
+                lm.remove_album_from_collection(&collection_id, &album_id)
+                    .await
+            } else {
+                lm.add_album_to_collection(&collection_id, &album_id).await
+            }
+        });
+    }
+
+    /// Create a collection holding one album
+    pub fn create_collection(&self, name: &str, album_id: &str) {
+        let library_manager = self.library_manager.clone();
+        let name = name.to_string();
+        let album_id = album_id.to_string();
+
+        self.edit_collection("create collection", async move {
+            let lm = library_manager.get();
+            let collection = lm.create_collection(&name).await?;
+            lm.add_album_to_collection(&collection.id, &album_id).await
+        });
+    }
+
+    /// Delete a collection, dropping it from the library filter if selected
+    pub fn delete_collection(&self, collection_id: &str) {
+        let library_manager = self.library_manager.clone();
+        let collection_id = collection_id.to_string();
+
+        let mut filter = self.state.ui().library_sort().filter();
+        if filter.peek().collection.as_ref() == Some(&collection_id) {
+            filter.write().collection = None;
+            self.save_library_sort();
+        }
+
+        self.edit_collection("delete collection", async move {
+            library_manager
+                .get()
+                .delete_collection(&collection_id)
+                .await
+        });
+    }
+
+    /// Run a collection change. The library reloads on the resulting
+    /// `AlbumsChanged` event, so this only reports failures and syncs.
+    fn edit_collection(
+        &self,
+        action: &'static str,
+        edit: impl std::future::Future<Output = Result<(), bae_core::library::LibraryError>> + 'static,
+    ) {
+        let sync_handle = self.sync_handle.clone();
+
+        spawn(async move {
+            if let Err(e) = edit.await {
+                tracing::error!("Failed to {}: {}", action, e);
+
+                return;
+            }
+            if let Some(sh) = sync_handle {
+                let _ = sh.sync_trigger.try_send(());
+            }
+        });
+    }
+
+    /// Load albums from a followed library's local DB into the library state.
+    ///
+    /// If the followed library hasn't been synced yet (no local DB), triggers an
+    /// initial sync from the remote snapshot first.
+    pub fn load_followed_library(&self, followed_id: &str) {
+        let state = self.state;
+        let key_service = self.key_service.clone();
+        let imgs = self.image_server.clone();
+
+        // Read connection details from the Store (not the stale boot config)
+        let followed = {
+            let followed_libs = state.read().config.followed_libraries.clone();
+            followed_libs.into_iter().find(|f| f.id == followed_id)
+        };
+
+        let Some(followed) = followed else {
+            state
+                .library()
+                .error()
                 .set(Some("Followed library not found".to_string()));
             return;
         };
@@ -705,6 +1284,8 @@ impl AppService {
 
         let followed_id = followed.id.clone();
         let playback_handle = self.playback_handle.clone();
+        let cache = self.cache.clone();
+        let (download_parallelism, readahead) = streaming_settings(&self.config);
 
         spawn(async move {
             load_followed_library_from_db(
@@ -714,6 +1295,9 @@ impl AppService {
                 &encryption_key,
                 &imgs,
                 &playback_handle,
+                download_parallelism,
+                readahead,
+                cache,
             )
             .await;
         });
@@ -783,6 +1367,8 @@ impl AppService {
         active_source: &bae_ui::stores::config::LibrarySource,
     ) {
         let state = self.state;
+        state.album_detail().offline_pin().set(None);
+        state.album_detail().offline_error().set(None);
 
         match active_source {
             bae_ui::stores::config::LibrarySource::Followed(ref followed_id) => {
@@ -800,6 +1386,7 @@ impl AppService {
                 let album_id = album_id.to_string();
                 let release_id = release_id.map(|s| s.to_string());
                 let imgs = self.image_server.clone();
+                let cache = self.cache.clone();
 
                 spawn(async move {
                     load_album_detail(
@@ -810,6 +1397,7 @@ impl AppService {
                         &imgs,
                     )
                     .await;
+                    load_offline_pin(&state, &library_manager, &cache).await;
                 });
             }
         }
@@ -874,6 +1462,7 @@ impl AppService {
                 tracing::error!("Failed to change cover: {}", e);
                 return;
             }
+            state.library().cover_upgrades().write().remove(&release_id);
 
             // Reload album detail to reflect updated cover
             load_album_detail(
@@ -901,153 +1490,204 @@ impl AppService {
 
     /// Transfer a release to managed local storage
     pub fn transfer_release_to_managed(&self, release_id: &str) {
+        self.run_release_transfer(release_id.to_string(), TransferTarget::ManagedLocal);
+    }
+
+    /// Move a release into the cloud home
+    pub fn move_release_to_cloud(&self, release_id: &str) {
+        self.run_release_transfer(release_id.to_string(), TransferTarget::Cloud);
+    }
+
+    /// Eject a release from managed storage to a local folder
+    pub fn eject_release_storage(&self, release_id: &str) {
+        let app = self.clone();
+        let release_id = release_id.to_string();
+
+        spawn(async move {
+            // Show folder picker
+            let folder_handle = match rfd::AsyncFileDialog::new()
+                .set_title("Select Eject Directory")
+                .pick_folder()
+                .await
+            {
+                Some(handle) => handle,
+                None => return, // User cancelled
+            };
+            let target_dir = folder_handle.path().to_path_buf();
+            app.run_release_transfer(release_id, TransferTarget::Eject(target_dir));
+        });
+    }
+
+    /// Keep a release downloaded so it plays without a network, or stop
+    pub fn set_release_kept_downloaded(&self, release_id: &str, keep: bool) {
         let state = self.state;
         let library_manager = self.library_manager.clone();
         let config = self.config.clone();
+        let key_service = self.key_service.clone();
+        let cache = self.cache.clone();
         let release_id = release_id.to_string();
-        let imgs = self.image_server.clone();
+
+        state.album_detail().offline_error().set(None);
+        let pin = keep.then(|| OfflinePin {
+            downloaded_files: 0,
+            total_files: state.album_detail().files().peek().len(),
+            downloaded_bytes: 0,
+        });
+        state.album_detail().offline_pin().set(pin);
 
         spawn(async move {
-            let encryption_service = library_manager.get().encryption_service().cloned();
-            let library_dir = config.library_dir.clone();
+            let lm = library_manager.get();
+            let result = if keep {
+                match library_cloud_storage(&config, &key_service, lm).await {
+                    Some(cloud_storage) => {
+                        let (download_parallelism, _) = streaming_settings(&config);
+                        offline::pin_release(
+                            lm,
+                            cloud_storage,
+                            &cache,
+                            download_parallelism,
+                            &release_id,
+                        )
+                        .await
+                        .map_err(|e| e.to_string())
+                    }
+                    None => Err("No cloud home to download from".to_string()),
+                }
+            } else {
+                offline::unpin_release(lm, &cache, &release_id)
+                    .await
+                    .map_err(|e| e.to_string())
+            };
 
-            let transfer_service = bae_core::storage::transfer::TransferService::new(
-                library_manager.clone(),
-                encryption_service,
-                library_dir.clone(),
-            );
+            if state.album_detail().selected_release_id().peek().as_deref()
+                != Some(release_id.as_str())
+            {
+                return;
+            }
+            if let Err(e) = result {
+                tracing::error!("Failed to update offline pin of {}: {}", release_id, e);
 
-            let mut rx = transfer_service.transfer(
-                release_id.clone(),
-                bae_core::storage::transfer::TransferTarget::ManagedLocal,
-            );
+                let action = if keep {
+                    "download the release"
+                } else {
+                    "stop keeping the release downloaded"
+                };
+                state
+                    .album_detail()
+                    .offline_error()
+                    .set(Some(format!("Couldn't {action}: {e}")));
+            }
+            load_offline_pin(&state, &library_manager, &cache).await;
+        });
+    }
 
-            while let Some(progress) = rx.recv().await {
-                match progress {
-                    bae_core::storage::transfer::TransferProgress::Started { .. } => {
-                        state.album_detail().transfer_error().set(None);
-                        state.album_detail().transfer_progress().set(Some(
-                            bae_ui::stores::album_detail::TransferProgressState {
-                                file_index: 0,
-                                total_files: 0,
-                                filename: String::new(),
-                                percent: 0,
-                            },
-                        ));
-                    }
-                    bae_core::storage::transfer::TransferProgress::FileProgress {
-                        file_index,
-                        total_files,
-                        filename,
-                        percent,
-                        ..
-                    } => {
-                        state.album_detail().transfer_progress().set(Some(
-                            bae_ui::stores::album_detail::TransferProgressState {
-                                file_index,
-                                total_files,
-                                filename,
-                                percent,
-                            },
-                        ));
-                    }
-                    bae_core::storage::transfer::TransferProgress::Complete { .. } => {
-                        state.album_detail().transfer_progress().set(None);
+    /// Stop a running transfer. The release stays where it was.
+    pub fn cancel_release_transfer(&self, release_id: &str) {
+        if let Some(service) = self.transfers.lock().unwrap().get(release_id) {
+            service.cancel();
+        }
+    }
 
-                        // Reload album detail to reflect new storage state
-                        let album_id = state
-                            .album_detail()
-                            .album()
-                            .read()
-                            .as_ref()
-                            .map(|a| a.id.clone());
-                        if let Some(album_id) = album_id {
-                            load_album_detail(
-                                &state,
-                                &library_manager,
-                                &album_id,
-                                Some(&release_id),
-                                &imgs,
-                            )
-                            .await;
-                        }
+    /// Pick up transfers a quit or an error interrupted
+    fn resume_storage_transfers(&self) {
+        let app = self.clone();
 
-                        // Schedule deferred cleanup of old files
-                        bae_core::storage::cleanup::schedule_cleanup(&library_dir);
-                    }
-                    bae_core::storage::transfer::TransferProgress::Failed { error, .. } => {
-                        state.album_detail().transfer_progress().set(None);
-                        state.album_detail().transfer_error().set(Some(error));
+        spawn(async move {
+            match pending_transfers(app.library_manager.get()).await {
+                Ok(pending) => {
+                    for (release_id, target) in pending {
+                        tracing::info!("Resuming transfer of release {}", release_id);
+
+                        app.run_release_transfer(release_id, target);
                     }
                 }
+                Err(e) => tracing::warn!("Failed to load unfinished transfers: {}", e),
             }
         });
     }
 
-    /// Eject a release from managed storage to a local folder
-    pub fn eject_release_storage(&self, release_id: &str) {
+    /// Run a transfer, showing its progress on the album page while the
+    /// release is selected there
+    fn run_release_transfer(&self, release_id: String, target: TransferTarget) {
+        if self.transfers.lock().unwrap().contains_key(&release_id) {
+            return;
+        }
+
         let state = self.state;
         let library_manager = self.library_manager.clone();
         let config = self.config.clone();
-        let release_id = release_id.to_string();
+        let key_service = self.key_service.clone();
+        let transfers = self.transfers.clone();
         let imgs = self.image_server.clone();
 
         spawn(async move {
-            // Show folder picker
-            let folder_handle = match rfd::AsyncFileDialog::new()
-                .set_title("Select Eject Directory")
-                .pick_folder()
-                .await
-            {
-                Some(handle) => handle,
-                None => return, // User cancelled
-            };
-            let target_dir = folder_handle.path().to_path_buf();
-
             let encryption_service = library_manager.get().encryption_service().cloned();
             let library_dir = config.library_dir.clone();
 
-            let transfer_service = bae_core::storage::transfer::TransferService::new(
+            let mut transfer_service = TransferService::new(
                 library_manager.clone(),
                 encryption_service,
                 library_dir.clone(),
             );
-
-            let mut rx = transfer_service.transfer(
-                release_id.clone(),
-                bae_core::storage::transfer::TransferTarget::Eject(target_dir),
-            );
+            if let Some(cloud_storage) =
+                library_cloud_storage(&config, &key_service, library_manager.get()).await
+            {
+                transfer_service = transfer_service.with_cloud_storage(cloud_storage);
+            }
+            let transfer_service = Arc::new(transfer_service);
+            transfers
+                .lock()
+                .unwrap()
+                .insert(release_id.clone(), transfer_service.clone());
+
+            let mut rx = transfer_service.transfer(release_id.clone(), target);
+            let is_selected = move || {
+                state.album_detail().selected_release_id().peek().as_deref()
+                    == Some(release_id.as_str())
+            };
 
             while let Some(progress) = rx.recv().await {
                 match progress {
-                    bae_core::storage::transfer::TransferProgress::Started { .. } => {
-                        state.album_detail().transfer_error().set(None);
-                        state.album_detail().transfer_progress().set(Some(
-                            bae_ui::stores::album_detail::TransferProgressState {
-                                file_index: 0,
-                                total_files: 0,
-                                filename: String::new(),
-                                percent: 0,
-                            },
-                        ));
+                    TransferProgress::Started { .. } => {
+                        if is_selected() {
+                            state.album_detail().transfer_error().set(None);
+                            state.album_detail().transfer_progress().set(Some(
+                                bae_ui::stores::album_detail::TransferProgressState {
+                                    file_index: 0,
+                                    total_files: 0,
+                                    filename: String::new(),
+                                    percent: 0,
+                                },
+                            ));
+                        }
                     }
-                    bae_core::storage::transfer::TransferProgress::FileProgress {
+                    TransferProgress::FileProgress {
                         file_index,
                         total_files,
                         filename,
                         percent,
                         ..
                     } => {
-                        state.album_detail().transfer_progress().set(Some(
-                            bae_ui::stores::album_detail::TransferProgressState {
-                                file_index,
-                                total_files,
-                                filename,
-                                percent,
-                            },
-                        ));
+                        if is_selected() {
+                            state.album_detail().transfer_progress().set(Some(
+                                bae_ui::stores::album_detail::TransferProgressState {
+                                    file_index,
+                                    total_files,
+                                    filename,
+                                    percent,
+                                },
+                            ));
+                        }
                     }
-                    bae_core::storage::transfer::TransferProgress::Complete { .. } => {
+                    TransferProgress::Complete { release_id }
+                    | TransferProgress::Cancelled { release_id } => {
+                        transfers.lock().unwrap().remove(&release_id);
+
+                        // Schedule deferred cleanup of old files
+                        bae_core::storage::cleanup::schedule_cleanup(&library_dir);
+                        if !is_selected() {
+                            continue;
+                        }
                         state.album_detail().transfer_progress().set(None);
 
                         // Reload album detail to reflect new storage state
@@ -1067,13 +1707,13 @@ impl AppService {
                             )
                             .await;
                         }
-
-                        // Schedule deferred cleanup of old files
-                        bae_core::storage::cleanup::schedule_cleanup(&library_dir);
                     }
-                    bae_core::storage::transfer::TransferProgress::Failed { error, .. } => {
-                        state.album_detail().transfer_progress().set(None);
-                        state.album_detail().transfer_error().set(Some(error));
+                    TransferProgress::Failed { release_id, error } => {
+                        transfers.lock().unwrap().remove(&release_id);
+                        if is_selected() {
+                            state.album_detail().transfer_progress().set(None);
+                            state.album_detail().transfer_error().set(Some(error));
+                        }
                     }
                 }
             }
@@ -1114,80 +1754,944 @@ impl AppService {
         });
     }
 
-    // =========================================================================
-    // Artist Detail Methods
-    // =========================================================================
-
-    /// Load artist detail data into Store (called when navigating to artist page)
-    pub fn load_artist_detail(&self, artist_id: &str) {
+    /// Link another album as a version of the given album
+    pub fn link_album_version(&self, album_id: &str, other_album_id: &str) {
         let state = self.state;
         let library_manager = self.library_manager.clone();
-        let artist_id = artist_id.to_string();
         let imgs = self.image_server.clone();
+        let album_id = album_id.to_string();
+        let other_album_id = other_album_id.to_string();
 
         spawn(async move {
-            load_artist_detail(&state, &library_manager, &artist_id, &imgs).await;
+            if let Err(e) = library_manager
+                .get()
+                .link_album_versions(&album_id, &other_album_id)
+                .await
+            {
+                tracing::error!("Failed to link album versions: {}", e);
+                return;
+            }
+            refresh_album_versions(&state, &library_manager, &album_id, &imgs).await;
         });
     }
 
-    // =========================================================================
-    // Config Methods
-    // =========================================================================
-
-    /// Update config and save to disk
-    pub fn save_config(&self, updater: impl FnOnce(&mut config::Config)) {
-        // Clone current config, apply update, save to disk, update Store
-        let mut new_config = self.config.clone();
-        updater(&mut new_config);
-
-        // Save to disk
-        if let Err(e) = new_config.save() {
-            tracing::error!("Failed to save config: {}", e);
-            return;
-        }
-
-        self.sync_config_to_store(&new_config);
-    }
-
-    // =========================================================================
-    // Sync Config Methods
-    // =========================================================================
+    /// Remove an album from its version group
+    pub fn unlink_album_version(&self, album_id: &str) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+        let album_id = album_id.to_string();
 
-    pub fn trigger_sync(&self) {
-        if let Some(ref sh) = self.sync_handle {
-            let _ = sh.sync_trigger.try_send(());
-        }
+        spawn(async move {
+            if let Err(e) = library_manager.get().unlink_album_version(&album_id).await {
+                tracing::error!("Failed to unlink album version: {}", e);
+                return;
+            }
+            refresh_album_versions(&state, &library_manager, &album_id, &imgs).await;
+        });
     }
 
-    /// Download membership entries from the sync bucket, build the membership
-    /// chain, and update the store with the current members list.
-    pub fn load_membership(&self) {
-        let Some(sync_handle) = self.sync_handle.clone() else {
-            return;
-        };
-
-        let user_pubkey = self
-            .user_keypair
-            .as_ref()
-            .map(|kp| hex::encode(kp.public_key));
+    /// Set or clear the label that names an album's version
+    pub fn set_album_version_label(&self, album_id: &str, label: Option<String>) {
         let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+        let album_id = album_id.to_string();
 
         spawn(async move {
-            let bucket: &dyn bae_core::sync::bucket::SyncBucketClient = &*sync_handle.bucket_client;
-
-            match load_membership_from_bucket(bucket, user_pubkey.as_deref()).await {
-                Ok(members) => {
-                    state.sync().members().set(members);
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to load membership: {e}");
-                }
+            if let Err(e) = library_manager
+                .get()
+                .set_album_version_label(&album_id, label.as_deref())
+                .await
+            {
+                tracing::error!("Failed to set album version label: {}", e);
+                return;
             }
+            refresh_album_versions(&state, &library_manager, &album_id, &imgs).await;
         });
     }
 
-    /// Invite a new member to the shared library.
-    ///
+    /// Load a release's tracks and audio for the track mapping editor
+    pub fn load_track_mapping(&self, release_id: &str) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let release_id = release_id.to_string();
+
+        state.album_detail().track_mapping().set(None);
+        state.album_detail().track_mapping_error().set(None);
+
+        spawn(async move {
+            let lm = library_manager.get();
+            let result = async {
+                let tracks = lm.get_tracks(&release_id).await?;
+                let sources = lm.get_track_audio_sources(&release_id).await?;
+                Ok::<_, bae_core::library::LibraryError>(track_mapping_from_db(
+                    &release_id,
+                    &tracks,
+                    &sources,
+                ))
+            }
+            .await;
+            match result {
+                Ok(mapping) => state.album_detail().track_mapping().set(Some(mapping)),
+                Err(e) => {
+                    tracing::error!("Failed to load track mapping: {}", e);
+
+                    state
+                        .album_detail()
+                        .track_mapping_error()
+                        .set(Some(format!("Couldn't load tracks: {e}")));
+                }
+            }
+        });
+    }
+
+    /// Save a corrected track order and track-to-file assignment, then refresh the tracklist
+    pub fn save_track_mapping(&self, mapping: bae_ui::display_types::TrackMapping) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+
+        state.album_detail().track_mapping_error().set(None);
+
+        spawn(async move {
+            let remaps: Vec<bae_core::db::TrackRemap> = mapping
+                .entries
+                .iter()
+                .map(|e| bae_core::db::TrackRemap {
+                    track_id: e.track_id.clone(),
+                    track_number: e.track_number,
+                    audio_format_id: e.source_id.clone(),
+                })
+                .collect();
+            if let Err(e) = library_manager
+                .get()
+                .remap_release_tracks(&mapping.release_id, &remaps)
+                .await
+            {
+                tracing::error!("Failed to save track mapping: {}", e);
+
+                state
+                    .album_detail()
+                    .track_mapping_error()
+                    .set(Some(format!("Couldn't save track mapping: {e}")));
+                return;
+            }
+
+            let album_id = state
+                .album_detail()
+                .album()
+                .read()
+                .as_ref()
+                .map(|a| a.id.clone());
+            let Some(album_id) = album_id else { return };
+            match fetch_album_detail(
+                &library_manager,
+                &album_id,
+                Some(&mapping.release_id),
+                &imgs,
+            )
+            .await
+            {
+                Ok(data) => {
+                    let mut detail_lens = state.album_detail();
+                    let mut detail = detail_lens.write();
+                    detail.tracks = data.tracks;
+                    detail.track_count = data.track_count;
+                    detail.track_ids = data.track_ids;
+                    detail.track_disc_info = data.track_disc_info;
+                    detail.disc_titles = data.disc_titles;
+                    detail.track_works = data.track_works;
+                    detail.track_credits = data.track_credits;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to reload tracks: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Save tag edits for an album and its selected release, then refresh the view
+    pub fn save_album_tags(&self, edit: bae_ui::display_types::AlbumTagEdit) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+
+        state.album_detail().tags_error().set(None);
+
+        spawn(async move {
+            let lm = library_manager.get();
+            let updates: Vec<bae_core::db::TrackTagUpdate> = edit
+                .tracks
+                .iter()
+                .map(|t| bae_core::db::TrackTagUpdate {
+                    track_id: t.track_id.clone(),
+                    title: t.title.clone(),
+                    disc_number: t.disc_number,
+                    track_number: t.track_number,
+                })
+                .collect();
+            let result = async {
+                lm.update_album_tags(&edit.album_id, &edit.title, edit.year, &edit.artists)
+                    .await?;
+                lm.update_track_tags(&edit.release_id, &updates).await
+            }
+            .await;
+            if let Err(e) = result {
+                tracing::error!("Failed to save tags: {}", e);
+
+                state
+                    .album_detail()
+                    .tags_error()
+                    .set(Some(format!("Couldn't save tags: {e}")));
+                return;
+            }
+
+            match fetch_album_detail(
+                &library_manager,
+                &edit.album_id,
+                Some(&edit.release_id),
+                &imgs,
+            )
+            .await
+            {
+                Ok(data) => {
+                    let mut detail_lens = state.album_detail();
+                    let mut detail = detail_lens.write();
+                    detail.album = data.album;
+                    detail.artists = data.artists;
+                    detail.artist_credit = data.artist_credit;
+                    detail.versions = data.versions;
+                    detail.tracks = data.tracks;
+                    detail.track_count = data.track_count;
+                    detail.track_ids = data.track_ids;
+                    detail.track_disc_info = data.track_disc_info;
+                    detail.disc_titles = data.disc_titles;
+                    detail.track_works = data.track_works;
+                    detail.track_credits = data.track_credits;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to reload album: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Save an album's custom tags and notes
+    pub fn save_album_notes(&self, edit: bae_ui::display_types::AlbumNotesEdit) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+
+        state.album_detail().tags_error().set(None);
+
+        spawn(async move {
+            let lm = library_manager.get();
+            let tags: Vec<bae_core::db::DbAlbumCustomTag> =
+                edit.tags.iter().map(custom_tag_to_db).collect();
+            let result = async {
+                lm.set_album_custom_tags(&edit.album_id, &tags).await?;
+                lm.set_album_notes(&edit.album_id, &edit.notes).await?;
+                let tags = lm.get_album_custom_tags(&edit.album_id).await?;
+                let notes = lm.get_album_notes(&edit.album_id).await?;
+                Ok::<_, bae_core::library::LibraryError>((tags, notes))
+            }
+            .await;
+            match result {
+                Ok((tags, notes)) => {
+                    state
+                        .album_detail()
+                        .custom_tags()
+                        .set(tags.iter().map(custom_tag_from_db).collect());
+                    state.album_detail().notes().set(notes);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to save album notes: {}", e);
+
+                    state
+                        .album_detail()
+                        .tags_error()
+                        .set(Some(format!("Couldn't save tags and notes: {e}")));
+                }
+            }
+        });
+    }
+
+    /// Leave a comment on an album as the current user and push it to the
+    /// other members
+    pub fn add_album_comment(&self, album_id: &str, content: &str) {
+        let Some(user_pubkey) = self
+            .user_keypair
+            .as_ref()
+            .map(|kp| hex::encode(kp.public_key))
+        else {
+            return;
+        };
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let sync_handle = self.sync_handle.clone();
+        let album_id = album_id.to_string();
+        let content = content.to_string();
+
+        state.album_detail().comment_error().set(None);
+
+        spawn(async move {
+            let lm = library_manager.get();
+            if let Err(e) = lm
+                .add_album_comment(&album_id, &user_pubkey, &content)
+                .await
+            {
+                tracing::error!("Failed to add comment: {}", e);
+
+                state
+                    .album_detail()
+                    .comment_error()
+                    .set(Some(format!("Couldn't post comment: {e}")));
+                return;
+            }
+            reload_album_comments(&state, &library_manager, &album_id).await;
+            if let Some(sh) = sync_handle {
+                let _ = sh.sync_trigger.try_send(());
+            }
+        });
+    }
+
+    /// Remove one of the current user's comments
+    pub fn remove_album_comment(&self, album_id: &str, comment_id: &str) {
+        let Some(user_pubkey) = self
+            .user_keypair
+            .as_ref()
+            .map(|kp| hex::encode(kp.public_key))
+        else {
+            return;
+        };
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let sync_handle = self.sync_handle.clone();
+        let album_id = album_id.to_string();
+        let comment_id = comment_id.to_string();
+
+        state.album_detail().comment_error().set(None);
+
+        spawn(async move {
+            let lm = library_manager.get();
+            if let Err(e) = lm.delete_album_comment(&comment_id, &user_pubkey).await {
+                tracing::error!("Failed to remove comment: {}", e);
+
+                state
+                    .album_detail()
+                    .comment_error()
+                    .set(Some(format!("Couldn't remove comment: {e}")));
+                return;
+            }
+            reload_album_comments(&state, &library_manager, &album_id).await;
+            if let Some(sh) = sync_handle {
+                let _ = sh.sync_trigger.try_send(());
+            }
+        });
+    }
+
+    /// Load where a release's files live, for the storage modal
+    pub fn load_release_availability(&self, release_id: &str) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let library_dir = self.config.library_dir.clone();
+        let device_id = self.config.device_id.clone();
+        let cache = self.cache.clone();
+        let sync_handle = self.sync_handle.clone();
+        let release_id = release_id.to_string();
+
+        state.album_detail().availability().set(None);
+
+        spawn(async move {
+            let lm = library_manager.get();
+            let result = async {
+                let release = lm
+                    .database()
+                    .get_release_by_id(&release_id)
+                    .await?
+                    .ok_or_else(|| "Release not found".to_string())?;
+                let file_count = lm.get_files_for_release(&release_id).await?.len();
+                let devices = lm
+                    .get_release_availability(&release_id, &device_id, &library_dir, &cache)
+                    .await?;
+
+                // Cover images are stored under the release ID
+                let has_cover = library_dir.image_path(&release_id).exists();
+                let cover_synced = match sync_handle {
+                    Some(ref sh) if has_cover => {
+                        Some(sh.bucket_client.has_image(&release_id).await?)
+                    }
+                    _ => None,
+                };
+
+                Ok::<_, Box<dyn std::error::Error>>(ReleaseAvailability {
+                    release_id: release_id.clone(),
+                    file_count,
+                    in_cloud: release.managed_in_cloud,
+                    cover_synced,
+                    devices: devices
+                        .iter()
+                        .map(|d| device_availability_from_db(d, &device_id))
+                        .collect(),
+                })
+            }
+            .await;
+
+            match result {
+                Ok(availability) => state.album_detail().availability().set(Some(availability)),
+                Err(e) => {
+                    tracing::error!("Failed to load release availability: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Compare a release's tags with its MusicBrainz release for the refresh review
+    pub fn load_musicbrainz_changes(&self, release_id: &str) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let release_id = release_id.to_string();
+
+        state.album_detail().musicbrainz_changes().set(None);
+        state.album_detail().musicbrainz_error().set(None);
+
+        spawn(async move {
+            match library_manager
+                .get()
+                .fetch_musicbrainz_changes(&release_id)
+                .await
+            {
+                Ok(changes) => state.album_detail().musicbrainz_changes().set(Some(
+                    changes.iter().map(metadata_change_from_core).collect(),
+                )),
+                Err(e) => {
+                    tracing::error!("Failed to check MusicBrainz: {}", e);
+
+                    state
+                        .album_detail()
+                        .musicbrainz_error()
+                        .set(Some(format!("Couldn't check MusicBrainz: {e}")));
+                }
+            }
+        });
+    }
+
+    /// Apply the reviewed MusicBrainz changes to a release, then refresh the view
+    pub fn apply_musicbrainz_changes(
+        &self,
+        release_id: &str,
+        updates: Vec<bae_ui::display_types::MetadataUpdate>,
+    ) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+        let release_id = release_id.to_string();
+
+        state.album_detail().tags_error().set(None);
+
+        spawn(async move {
+            let lm = library_manager.get();
+            let updates: Vec<_> = updates.iter().map(metadata_update_to_core).collect();
+            let result = async {
+                lm.apply_metadata_updates(&release_id, &updates).await?;
+                lm.get_album_id_for_release(&release_id).await
+            }
+            .await;
+            let album_id = match result {
+                Ok(album_id) => album_id,
+                Err(e) => {
+                    tracing::error!("Failed to apply MusicBrainz changes: {}", e);
+
+                    state
+                        .album_detail()
+                        .tags_error()
+                        .set(Some(format!("Couldn't save tags: {e}")));
+                    return;
+                }
+            };
+
+            match fetch_album_detail(&library_manager, &album_id, Some(&release_id), &imgs).await {
+                Ok(data) => {
+                    let mut detail_lens = state.album_detail();
+                    let mut detail = detail_lens.write();
+                    detail.album = data.album;
+                    detail.artists = data.artists;
+                    detail.artist_credit = data.artist_credit;
+                    detail.versions = data.versions;
+                    detail.tracks = data.tracks;
+                    detail.track_count = data.track_count;
+                    detail.track_ids = data.track_ids;
+                    detail.track_disc_info = data.track_disc_info;
+                    detail.disc_titles = data.disc_titles;
+                    detail.track_works = data.track_works;
+                    detail.track_credits = data.track_credits;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to reload album: {}", e);
+                }
+            }
+        });
+    }
+
+    // =========================================================================
+    // Play History Methods
+    // =========================================================================
+
+    /// Load recently played tracks into the queue sidebar's History tab
+    pub fn load_play_history(&self) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+
+        spawn(async move {
+            let entries = match library_manager.get().get_play_history().await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    tracing::error!("Failed to load play history: {}", e);
+
+                    return;
+                }
+            };
+
+            let mut history = Vec::new();
+            for entry in entries {
+                if let Some(item) = load_queue_item(&library_manager, &imgs, &entry.track_id).await
+                {
+                    history.push(PlayHistoryItem {
+                        id: entry.id,
+                        item,
+                        played_at: entry.played_at.to_rfc3339(),
+                    });
+                }
+            }
+
+            state.playback().history().set(history);
+        });
+    }
+
+    /// Refresh the "Recently Played" and "Recently Added" shelves. Plays
+    /// don't change the albums, so this runs whenever the library is shown.
+    pub fn load_recent_albums(&self) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+
+        spawn(async move {
+            load_recent_albums(&state, &library_manager).await;
+        });
+    }
+
+    // =========================================================================
+    // Playlist Methods
+    // =========================================================================
+
+    /// Load the playlist list, and the selected playlist's tracks
+    pub fn load_playlists(&self) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+
+        spawn(async move {
+            load_playlists(&state, &library_manager, &imgs).await;
+        });
+    }
+
+    pub fn select_playlist(&self, playlist_id: &str) {
+        self.state
+            .playlists()
+            .selected_id()
+            .set(Some(playlist_id.to_string()));
+        self.state.playlists().import_summary().set(None);
+        self.load_playlists();
+    }
+
+    pub fn create_playlist(&self, name: &str) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+        let sync_handle = self.sync_handle.clone();
+        let name = name.to_string();
+
+        spawn(async move {
+            match library_manager.get().create_playlist(&name).await {
+                Ok(playlist) => {
+                    state.playlists().selected_id().set(Some(playlist.id));
+                    state.playlists().import_summary().set(None);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to create playlist: {}", e);
+
+                    state.playlists().error().set(Some(e.to_string()));
+                    return;
+                }
+            }
+            load_playlists(&state, &library_manager, &imgs).await;
+            if let Some(sh) = sync_handle {
+                let _ = sh.sync_trigger.try_send(());
+            }
+        });
+    }
+
+    pub fn rename_playlist(&self, playlist_id: &str, name: &str) {
+        let library_manager = self.library_manager.clone();
+        let playlist_id = playlist_id.to_string();
+        let name = name.to_string();
+
+        self.edit_playlist("rename playlist", async move {
+            library_manager
+                .get()
+                .rename_playlist(&playlist_id, &name)
+                .await
+        });
+    }
+
+    pub fn delete_playlist(&self, playlist_id: &str) {
+        let library_manager = self.library_manager.clone();
+        let playlist_id = playlist_id.to_string();
+
+        self.state.playlists().selected_id().set(None);
+        self.edit_playlist("delete playlist", async move {
+            library_manager.get().delete_playlist(&playlist_id).await
+        });
+    }
+
+    pub fn add_track_to_playlist(&self, playlist_id: &str, track_id: &str) {
+        let library_manager = self.library_manager.clone();
+        let playlist_id = playlist_id.to_string();
+        let track_ids = vec![track_id.to_string()];
+
+        self.edit_playlist("add to playlist", async move {
+            library_manager
+                .get()
+                .add_tracks_to_playlist(&playlist_id, &track_ids)
+                .await
+        });
+    }
+
+    /// Remove the entry at `index` from the selected playlist
+    pub fn remove_playlist_entry(&self, index: usize) {
+        let Some(playlist_id) = self.state.playlists().selected_id().read().clone() else {
+            return;
+        };
+        let library_manager = self.library_manager.clone();
+
+        self.edit_playlist("remove from playlist", async move {
+            library_manager
+                .get()
+                .remove_playlist_entry(&playlist_id, index)
+                .await
+        });
+    }
+
+    /// Move an entry of the selected playlist
+    pub fn move_playlist_entry(&self, from: usize, to: usize) {
+        let Some(playlist_id) = self.state.playlists().selected_id().read().clone() else {
+            return;
+        };
+        let library_manager = self.library_manager.clone();
+
+        self.edit_playlist("reorder playlist", async move {
+            library_manager
+                .get()
+                .move_playlist_entry(&playlist_id, from, to)
+                .await
+        });
+    }
+
+    /// Run a playlist edit, then reload the playlists and push the change
+    fn edit_playlist(
+        &self,
+        action: &'static str,
+        edit: impl std::future::Future<Output = Result<(), bae_core::library::LibraryError>> + 'static,
+    ) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+        let sync_handle = self.sync_handle.clone();
+
+        spawn(async move {
+            if let Err(e) = edit.await {
+                tracing::error!("Failed to {}: {}", action, e);
+
+                state
+                    .playlists()
+                    .error()
+                    .set(Some(format!("Couldn't {action}: {e}")));
+                return;
+            }
+            load_playlists(&state, &library_manager, &imgs).await;
+            if let Some(sh) = sync_handle {
+                let _ = sh.sync_trigger.try_send(());
+            }
+        });
+    }
+
+    /// Pick an M3U or M3U8 file and create a playlist from the library
+    /// tracks it lists
+    pub fn import_m3u_playlist(&self) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+        let sync_handle = self.sync_handle.clone();
+
+        spawn(async move {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_title("Import Playlist")
+                .add_filter("M3U playlist", &["m3u", "m3u8"])
+                .pick_file()
+                .await
+            else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let bytes = match tokio::fs::read(&path).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    state
+                        .playlists()
+                        .error()
+                        .set(Some(format!("Couldn't read {}: {e}", path.display())));
+                    return;
+                }
+            };
+            // .m3u8 is UTF-8 by definition; plain .m3u is often a legacy encoding
+            let text = bae_core::text_encoding::decode_text(&bytes).text;
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Imported Playlist".to_string());
+
+            match library_manager
+                .get()
+                .import_m3u_playlist(&name, &text)
+                .await
+            {
+                Ok(import) => {
+                    let total = import.matched + import.unmatched.len();
+
+                    tracing::info!(
+                        "Imported playlist {} ({} of {} tracks matched)",
+                        import.playlist.name,
+                        import.matched,
+                        total
+                    );
+
+                    state
+                        .playlists()
+                        .selected_id()
+                        .set(Some(import.playlist.id));
+                    state.playlists().import_summary().set(Some(format!(
+                        "Matched {} of {} tracks from {}",
+                        import.matched,
+                        total,
+                        path.display()
+                    )));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to import playlist: {}", e);
+
+                    state
+                        .playlists()
+                        .error()
+                        .set(Some(format!("Couldn't import playlist: {e}")));
+                    return;
+                }
+            }
+            load_playlists(&state, &library_manager, &imgs).await;
+            if let Some(sh) = sync_handle {
+                let _ = sh.sync_trigger.try_send(());
+            }
+        });
+    }
+
+    /// Write a playlist to an M3U8 file the user picks
+    pub fn export_m3u_playlist(&self, playlist_id: &str) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let playlist_id = playlist_id.to_string();
+
+        spawn(async move {
+            let lm = library_manager.get();
+            let name = match lm.get_playlist(&playlist_id).await {
+                Ok(Some(playlist)) => playlist.name,
+                _ => return,
+            };
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_title("Export Playlist")
+                .set_file_name(format!("{}.m3u8", name))
+                .add_filter("M3U playlist", &["m3u8", "m3u"])
+                .save_file()
+                .await
+            else {
+                return;
+            };
+
+            let result = match lm.export_m3u_playlist(&playlist_id).await {
+                Ok(text) => tokio::fs::write(file.path(), text)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(e) = result {
+                tracing::error!("Failed to export playlist: {}", e);
+
+                state
+                    .playlists()
+                    .error()
+                    .set(Some(format!("Couldn't export playlist: {e}")));
+            }
+        });
+    }
+
+    // =========================================================================
+    // Artist Detail Methods
+    // =========================================================================
+
+    /// Load artist detail data into Store (called when navigating to artist page)
+    pub fn load_artist_detail(&self, artist_id: &str) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let artist_id = artist_id.to_string();
+        let imgs = self.image_server.clone();
+
+        spawn(async move {
+            load_artist_detail(&state, &library_manager, &artist_id, &imgs).await;
+        });
+    }
+
+    // =========================================================================
+    // Browse Methods
+    // =========================================================================
+
+    /// Load the genres and decades for the browse page into Store
+    pub fn load_browse_index(&self) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+
+        spawn(async move {
+            load_browse_index(&state, &library_manager).await;
+        });
+    }
+
+    /// Load the albums matching `filter` into the browse Store, in the
+    /// library's sort order
+    pub fn load_browse_albums(&self, filter: LibraryFilter) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+        let imgs = self.image_server.clone();
+
+        spawn(async move {
+            load_browse_albums(&state, &library_manager, &filter, &imgs).await;
+        });
+    }
+
+    // =========================================================================
+    // Config Methods
+    // =========================================================================
+
+    /// Update config and save to disk
+    pub fn save_config(&self, updater: impl FnOnce(&mut config::Config)) {
+        // Clone current config, apply update, save to disk, update Store
+        let mut new_config = self.config.clone();
+        updater(&mut new_config);
+
+        // Save to disk
+        if let Err(e) = new_config.save() {
+            tracing::error!("Failed to save config: {}", e);
+            return;
+        }
+
+        self.import_handle
+            .set_import_filters(new_config.import_filters());
+        if let Some(ref sh) = self.sync_handle {
+            sh.bucket_client
+                .set_image_encryption(new_config.encrypt_images);
+        }
+        #[cfg(target_os = "macos")]
+        crate::system_events::apply_config(&new_config);
+        self.sync_config_to_store(&new_config);
+    }
+
+    // =========================================================================
+    // Sync Config Methods
+    // =========================================================================
+
+    pub fn trigger_sync(&self) {
+        if let Some(ref sh) = self.sync_handle {
+            let _ = sh.sync_trigger.try_send(());
+        }
+    }
+
+    /// Download membership entries from the sync bucket, build the membership
+    /// chain, and update the store with the current members list.
+    pub fn load_membership(&self) {
+        let Some(sync_handle) = self.sync_handle.clone() else {
+            return;
+        };
+
+        let user_pubkey = self
+            .user_keypair
+            .as_ref()
+            .map(|kp| hex::encode(kp.public_key));
+        let state = self.state;
+
+        spawn(async move {
+            let bucket: &dyn bae_core::sync::bucket::SyncBucketClient = &*sync_handle.bucket_client;
+
+            match load_membership_from_bucket(bucket, user_pubkey.as_deref()).await {
+                Ok(members) => {
+                    state.sync().members().set(members);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to load membership: {e}");
+                }
+            }
+        });
+    }
+
+    /// Load the conflicts sync found into the store.
+    pub fn load_sync_conflicts(&self) {
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+
+        spawn(async move {
+            load_sync_conflicts(&state, &library_manager).await;
+        });
+    }
+
+    /// Write the values picked for a sync conflict, then sync them to the
+    /// other devices.
+    pub fn resolve_sync_conflict(
+        &self,
+        conflict_id: String,
+        values: Vec<(String, Option<String>)>,
+    ) {
+        let app = self.clone();
+        let state = self.state;
+        let library_manager = self.library_manager.clone();
+
+        spawn(async move {
+            match library_manager
+                .get()
+                .resolve_sync_conflict(&conflict_id, &values)
+                .await
+            {
+                Ok(()) => {
+                    load_sync_conflicts(&state, &library_manager).await;
+                    app.trigger_sync();
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to resolve sync conflict {conflict_id}: {e}");
+                    state
+                        .sync()
+                        .error()
+                        .set(Some(format!("Failed to resolve conflict: {e}")));
+                }
+            }
+        });
+    }
+
+    /// Invite a new member to the shared library.
+    ///
     /// If no membership chain exists yet, bootstraps the founder entry first.
     /// Creates the invitation (membership entry + wrapped key), uploads to the
     /// bucket, and refreshes the member list in the store.
@@ -1257,41 +2761,14 @@ impl AppService {
                     .try_into()
                     .map_err(|_| "Encryption key wrong length".to_string())?;
 
-                // Download existing membership entries.
-                let entry_keys = bucket
-                    .list_membership_entries()
-                    .await
-                    .map_err(|e| format!("Failed to list membership entries: {e}"))?;
-
-                let mut chain = if entry_keys.is_empty() {
-                    // No membership chain yet -- bootstrap with a founder entry.
-                    let mut founder = MembershipEntry {
-                        action: MembershipAction::Add,
-                        user_pubkey: user_pubkey_hex.clone(),
-                        role: CoreMemberRole::Owner,
-                        timestamp: hlc.now().to_string(),
-                        author_pubkey: String::new(),
-                        signature: String::new(),
-                    };
-
-                    sign_membership_entry(&mut founder, &keypair);
-
-                    let mut chain = MembershipChain::new();
-                    chain
-                        .add_entry(founder.clone())
-                        .map_err(|e| format!("Failed to create founder entry: {e}"))?;
-
-                    // Upload the founder entry to the bucket.
-                    let founder_bytes = serde_json::to_vec(&founder)
-                        .map_err(|e| format!("Failed to serialize founder entry: {e}"))?;
-                    bucket
-                        .put_membership_entry(&user_pubkey_hex, 1, founder_bytes)
-                        .await
-                        .map_err(|e| format!("Failed to upload founder entry: {e}"))?;
-
-                    tracing::info!("Bootstrapped membership chain with founder entry");
+                // Download existing membership entries.
+                let entry_keys = bucket
+                    .list_membership_entries()
+                    .await
+                    .map_err(|e| format!("Failed to list membership entries: {e}"))?;
 
-                    chain
+                let mut chain = if entry_keys.is_empty() {
+                    bootstrap_membership_chain(bucket, &keypair, &hlc).await?
                 } else {
                     // Build chain from existing entries.
                     let mut raw_entries = Vec::new();
@@ -1318,6 +2795,7 @@ impl AppService {
                 let core_role = match role {
                     MemberRole::Owner => CoreMemberRole::Owner,
                     MemberRole::Member => CoreMemberRole::Member,
+                    MemberRole::ReadOnly => CoreMemberRole::ReadOnly,
                 };
 
                 // Create the invitation (validates chain, wraps key, uploads).
@@ -1358,57 +2836,363 @@ impl AppService {
                     };
                     let code_string = bae_core::join_code::encode(&invite_code);
 
-                    let invitee_display = if invitee_pubkey_hex.len() > 16 {
-                        format!(
-                            "{}...{}",
-                            &invitee_pubkey_hex[..8],
-                            &invitee_pubkey_hex[invitee_pubkey_hex.len() - 8..]
-                        )
-                    } else {
-                        invitee_pubkey_hex.clone()
-                    };
+                    let invitee_display = if invitee_pubkey_hex.len() > 16 {
+                        format!(
+                            "{}...{}",
+                            &invitee_pubkey_hex[..8],
+                            &invitee_pubkey_hex[invitee_pubkey_hex.len() - 8..]
+                        )
+                    } else {
+                        invitee_pubkey_hex.clone()
+                    };
+
+                    {
+                        let mut sync_lens = state.sync();
+                        let mut ss = sync_lens.write();
+                        ss.invite_status = Some(bae_ui::stores::InviteStatus::Success);
+                        ss.share_info = Some(bae_ui::stores::ShareInfo {
+                            invite_code: code_string,
+                            invitee_display,
+                        });
+                    }
+
+                    // Reload the member list (after await, separate write).
+                    match load_membership_from_bucket(bucket, Some(&user_pubkey_hex)).await {
+                        Ok(members) => state.sync().members().set(members),
+                        Err(e) => tracing::warn!("Failed to reload membership after invite: {e}"),
+                    }
+                }
+                Err(e) => {
+                    state
+                        .sync()
+                        .invite_status()
+                        .set(Some(bae_ui::stores::InviteStatus::Error(e)));
+                }
+            }
+        });
+    }
+
+    /// Remove a member from the shared library.
+    ///
+    /// Downloads the membership chain, calls `revoke_member()` (which creates a
+    /// Remove entry, generates a new encryption key, re-wraps for remaining
+    /// members, and deletes the revoked member's wrapped key), begins a key
+    /// rotation so stored files are re-encrypted on the next launch, updates
+    /// the shared EncryptionService, and reloads the member list.
+    ///
+    /// Progress and errors are written to `state.sync().removing_member()` and
+    /// `state.sync().remove_member_error()`.
+    pub fn remove_member(&self, revokee_pubkey: String) {
+        let Some(sync_handle) = self.sync_handle.clone() else {
+            self.state
+                .sync()
+                .remove_member_error()
+                .set(Some("Sync is not configured".to_string()));
+            return;
+        };
+
+        let Some(ref user_keypair) = self.user_keypair else {
+            self.state
+                .sync()
+                .remove_member_error()
+                .set(Some("No user keypair available".to_string()));
+            return;
+        };
+
+        let state = self.state;
+        let keypair = user_keypair.clone();
+        let user_pubkey_hex = hex::encode(keypair.public_key);
+        let hlc = sync_handle.hlc.clone();
+        let key_service = self.key_service.clone();
+        let config = self.config.clone();
+
+        // Removing a member rotates the key into the keyring, around a
+        // security key that would then unwrap the old one
+        if config.security_key.is_some() {
+            state.sync().remove_member_error().set(Some(
+                "Remove the security key in Library settings before removing a member.".to_string(),
+            ));
+            return;
+        }
+
+        // Files still under the key before the last rotation would be lost
+        if key_service.get_previous_encryption_key().is_some() {
+            state.sync().remove_member_error().set(Some(
+                "The library is still being re-encrypted under its last key. Try again once that finishes.".to_string(),
+            ));
+            return;
+        }
+
+        state.sync().removing_member().set(true);
+        state.sync().remove_member_error().set(None);
+
+        spawn(async move {
+            let bucket: &dyn SyncBucketClient = &*sync_handle.bucket_client;
+
+            let result: Result<(), String> = async {
+                // Download existing membership entries and build the chain.
+                let entry_keys = bucket
+                    .list_membership_entries()
+                    .await
+                    .map_err(|e| format!("Failed to list membership entries: {e}"))?;
+
+                if entry_keys.is_empty() {
+                    return Err("No membership chain exists".to_string());
+                }
+
+                let mut raw_entries = Vec::new();
+                for (author, seq) in &entry_keys {
+                    let data = bucket
+                        .get_membership_entry(author, *seq)
+                        .await
+                        .map_err(|e| {
+                            format!("Failed to get membership entry {author}/{seq}: {e}")
+                        })?;
+                    let entry: MembershipEntry = serde_json::from_slice(&data).map_err(|e| {
+                        format!("Failed to parse membership entry {author}/{seq}: {e}")
+                    })?;
+                    raw_entries.push(entry);
+                }
+
+                let mut chain = MembershipChain::from_entries(raw_entries)
+                    .map_err(|e| format!("Invalid membership chain: {e}"))?;
+
+                // Revoke the member (creates Remove entry, rotates key, re-wraps).
+                let cloud_home = sync_handle.bucket_client.cloud_home();
+                let revoke_ts = hlc.now().to_string();
+                let new_key = bae_core::sync::invite::revoke_member(
+                    bucket,
+                    cloud_home,
+                    &mut chain,
+                    &keypair,
+                    &revokee_pubkey,
+                    &revoke_ts,
+                )
+                .await
+                .map_err(|e| format!("Failed to revoke member: {e}"))?;
+
+                // Persist the new key and fingerprint, keeping the old key
+                // until stored files are re-encrypted on the next launch.
+                let mut updated_config = config.clone();
+                begin_key_rotation(&key_service, &mut updated_config, new_key)
+                    .map_err(|e| format!("Failed to persist new encryption key: {e}"))?;
+
+                // Update the shared encryption service (visible to sync loop + bucket client).
+                sync_handle.update_encryption_key(new_key);
+
+                tracing::info!(
+                    "Revoked member {}... and rotated encryption key",
+                    &revokee_pubkey[..revokee_pubkey.len().min(16)]
+                );
+
+                Ok(())
+            }
+            .await;
+
+            match result {
+                Ok(()) => {
+                    // Reload the member list.
+                    let bucket: &dyn SyncBucketClient = &*sync_handle.bucket_client;
+
+                    match load_membership_from_bucket(bucket, Some(&user_pubkey_hex)).await {
+                        Ok(members) => state.sync().members().set(members),
+                        Err(e) => {
+                            tracing::warn!("Failed to reload membership after revocation: {e}")
+                        }
+                    }
+                }
+                Err(e) => {
+                    state.sync().remove_member_error().set(Some(e));
+                }
+            }
+
+            state.sync().removing_member().set(false);
+        });
+    }
+
+    /// Revoke one of this user's other devices.
+    ///
+    /// Appends a RevokeDevice entry for `device_id` signed with this device's
+    /// key, founding the membership chain and enrolling this device first if
+    /// needed. Nothing is re-keyed: other devices skip the revoked device's
+    /// changesets once they reload the chain on their next sync.
+    pub fn revoke_device(&self, device_id: String) {
+        let Some(sync_handle) = self.sync_handle.clone() else {
+            self.state
+                .sync()
+                .error()
+                .set(Some("Sync is not configured".to_string()));
+            return;
+        };
+
+        let Some(ref user_keypair) = self.user_keypair else {
+            self.state
+                .sync()
+                .error()
+                .set(Some("No user keypair available".to_string()));
+            return;
+        };
+
+        let state = self.state;
+        let keypair = user_keypair.clone();
+        let hlc = sync_handle.hlc.clone();
+
+        spawn(async move {
+            let bucket: &dyn SyncBucketClient = &*sync_handle.bucket_client;
+
+            let result: Result<(), String> = async {
+                let mut chain = match bae_core::sync::invite::load_membership_chain(bucket)
+                    .await
+                    .map_err(|e| format!("Failed to load membership chain: {e}"))?
+                {
+                    Some(chain) => chain,
+                    None => bootstrap_membership_chain(bucket, &keypair, &hlc).await?,
+                };
+
+                bae_core::sync::invite::ensure_device_enrolled(
+                    bucket,
+                    &mut chain,
+                    &keypair,
+                    &sync_handle.device_id,
+                    &sync_handle.device_keypair,
+                    &hlc.now().to_string(),
+                )
+                .await
+                .map_err(|e| format!("Failed to enroll this device: {e}"))?;
+
+                let revoke_ts = hlc.now().to_string();
+                bae_core::sync::invite::revoke_device(
+                    bucket,
+                    &mut chain,
+                    &keypair,
+                    &sync_handle.device_keypair,
+                    &device_id,
+                    &revoke_ts,
+                )
+                .await
+                .map_err(|e| format!("Failed to revoke device: {e}"))
+            }
+            .await;
+
+            match result {
+                Ok(()) => {
+                    tracing::info!("Revoked device {device_id}");
+
+                    let mut sync_lens = state.sync();
+                    let mut ss = sync_lens.write();
+                    for device in ss.other_devices.iter_mut() {
+                        if device.device_id == device_id {
+                            device.revoked = true;
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("{e}");
+
+                    state.sync().error().set(Some(e));
+                }
+            }
+        });
+    }
+
+    /// Approve one of this user's other devices.
+    ///
+    /// Looks the device up among those waiting to be approved and appends an
+    /// AddDevice entry for its key, signed with this device's key. Other
+    /// devices start applying its changesets once they reload the chain.
+    pub fn approve_device(&self, device_id: String) {
+        let Some(sync_handle) = self.sync_handle.clone() else {
+            self.state
+                .sync()
+                .error()
+                .set(Some("Sync is not configured".to_string()));
+            return;
+        };
+
+        let Some(ref user_keypair) = self.user_keypair else {
+            self.state
+                .sync()
+                .error()
+                .set(Some("No user keypair available".to_string()));
+            return;
+        };
+
+        let state = self.state;
+        let keypair = user_keypair.clone();
+        let hlc = sync_handle.hlc.clone();
+
+        spawn(async move {
+            let bucket: &dyn SyncBucketClient = &*sync_handle.bucket_client;
+
+            let result: Result<(), String> = async {
+                let mut chain = bae_core::sync::invite::load_membership_chain(bucket)
+                    .await
+                    .map_err(|e| format!("Failed to load membership chain: {e}"))?
+                    .ok_or_else(|| "This library has no membership chain".to_string())?;
+
+                let user_pubkey = hex::encode(keypair.public_key);
+                let heads = bucket
+                    .list_heads()
+                    .await
+                    .map_err(|e| format!("Failed to list devices: {e}"))?;
+                let pending = bae_core::sync::invite::pending_devices(
+                    bucket,
+                    &chain,
+                    &user_pubkey,
+                    &heads,
+                    &sync_handle.device_id,
+                )
+                .await
+                .into_iter()
+                .find(|d| d.device_id == device_id)
+                .ok_or_else(|| format!("Device {device_id} is not waiting for approval"))?;
+
+                bae_core::sync::invite::enroll_device(
+                    bucket,
+                    &mut chain,
+                    &user_pubkey,
+                    &sync_handle.device_keypair,
+                    &pending.device_id,
+                    &pending.device_pubkey,
+                    &hlc.now().to_string(),
+                )
+                .await
+                .map_err(|e| format!("Failed to approve device: {e}"))
+            }
+            .await;
 
-                    {
-                        let mut sync_lens = state.sync();
-                        let mut ss = sync_lens.write();
-                        ss.invite_status = Some(bae_ui::stores::InviteStatus::Success);
-                        ss.share_info = Some(bae_ui::stores::ShareInfo {
-                            invite_code: code_string,
-                            invitee_display,
-                        });
-                    }
+            match result {
+                Ok(()) => {
+                    tracing::info!("Approved device {device_id}");
 
-                    // Reload the member list (after await, separate write).
-                    match load_membership_from_bucket(bucket, Some(&user_pubkey_hex)).await {
-                        Ok(members) => state.sync().members().set(members),
-                        Err(e) => tracing::warn!("Failed to reload membership after invite: {e}"),
+                    let mut sync_lens = state.sync();
+                    let mut ss = sync_lens.write();
+                    for device in ss.other_devices.iter_mut() {
+                        if device.device_id == device_id {
+                            device.pending = false;
+                        }
                     }
                 }
                 Err(e) => {
-                    state
-                        .sync()
-                        .invite_status()
-                        .set(Some(bae_ui::stores::InviteStatus::Error(e)));
+                    tracing::warn!("{e}");
+
+                    state.sync().error().set(Some(e));
                 }
             }
         });
     }
 
-    /// Remove a member from the shared library.
+    /// Record that a linked device showed the same short authentication
+    /// string as this one.
     ///
-    /// Downloads the membership chain, calls `revoke_member()` (which creates a
-    /// Remove entry, generates a new encryption key, re-wraps for remaining
-    /// members, and deletes the revoked member's wrapped key), persists the new
-    /// key to keyring, updates the shared EncryptionService, and reloads the
-    /// member list.
-    ///
-    /// Progress and errors are written to `state.sync().removing_member()` and
-    /// `state.sync().remove_member_error()`.
-    pub fn remove_member(&self, revokee_pubkey: String) {
+    /// Appends a VerifyDevice entry for the link's device ID, signed with
+    /// this device's key, founding the membership chain and enrolling this
+    /// device first if needed.
+    pub fn verify_device(&self, device_id: String) {
         let Some(sync_handle) = self.sync_handle.clone() else {
             self.state
                 .sync()
-                .remove_member_error()
+                .error()
                 .set(Some("Sync is not configured".to_string()));
             return;
         };
@@ -1416,113 +3200,62 @@ impl AppService {
         let Some(ref user_keypair) = self.user_keypair else {
             self.state
                 .sync()
-                .remove_member_error()
+                .error()
                 .set(Some("No user keypair available".to_string()));
             return;
         };
 
         let state = self.state;
         let keypair = user_keypair.clone();
-        let user_pubkey_hex = hex::encode(keypair.public_key);
         let hlc = sync_handle.hlc.clone();
-        let key_service = self.key_service.clone();
-        let config = self.config.clone();
-
-        state.sync().removing_member().set(true);
-        state.sync().remove_member_error().set(None);
 
         spawn(async move {
             let bucket: &dyn SyncBucketClient = &*sync_handle.bucket_client;
 
             let result: Result<(), String> = async {
-                // Download existing membership entries and build the chain.
-                let entry_keys = bucket
-                    .list_membership_entries()
+                let mut chain = match bae_core::sync::invite::load_membership_chain(bucket)
                     .await
-                    .map_err(|e| format!("Failed to list membership entries: {e}"))?;
-
-                if entry_keys.is_empty() {
-                    return Err("No membership chain exists".to_string());
-                }
-
-                let mut raw_entries = Vec::new();
-                for (author, seq) in &entry_keys {
-                    let data = bucket
-                        .get_membership_entry(author, *seq)
-                        .await
-                        .map_err(|e| {
-                            format!("Failed to get membership entry {author}/{seq}: {e}")
-                        })?;
-                    let entry: MembershipEntry = serde_json::from_slice(&data).map_err(|e| {
-                        format!("Failed to parse membership entry {author}/{seq}: {e}")
-                    })?;
-                    raw_entries.push(entry);
-                }
-
-                let mut chain = MembershipChain::from_entries(raw_entries)
-                    .map_err(|e| format!("Invalid membership chain: {e}"))?;
+                    .map_err(|e| format!("Failed to load membership chain: {e}"))?
+                {
+                    Some(chain) => chain,
+                    None => bootstrap_membership_chain(bucket, &keypair, &hlc).await?,
+                };
 
-                // Revoke the member (creates Remove entry, rotates key, re-wraps).
-                let cloud_home = sync_handle.bucket_client.cloud_home();
-                let revoke_ts = hlc.now().to_string();
-                let new_key = bae_core::sync::invite::revoke_member(
+                bae_core::sync::invite::ensure_device_enrolled(
                     bucket,
-                    cloud_home,
                     &mut chain,
                     &keypair,
-                    &revokee_pubkey,
-                    &revoke_ts,
+                    &sync_handle.device_id,
+                    &sync_handle.device_keypair,
+                    &hlc.now().to_string(),
                 )
                 .await
-                .map_err(|e| format!("Failed to revoke member: {e}"))?;
-
-                // Persist the new encryption key to keyring.
-                let new_key_hex = hex::encode(new_key);
-                key_service
-                    .set_encryption_key(&new_key_hex)
-                    .map_err(|e| format!("Failed to persist new encryption key: {e}"))?;
-
-                // Update the shared encryption service (visible to sync loop + bucket client).
-                sync_handle.update_encryption_key(new_key);
-
-                // Update config fingerprint and persist so startup won't reject the new key.
-                let new_fingerprint = {
-                    let enc = sync_handle.encryption.read().unwrap();
-                    enc.fingerprint()
-                };
-                let mut updated_config = config.clone();
-                updated_config.encryption_key_fingerprint = Some(new_fingerprint);
-                if let Err(e) = updated_config.save_to_config_yaml() {
-                    tracing::error!("Failed to save config after key rotation: {e}");
-                }
-
-                tracing::info!(
-                    "Revoked member {}... and rotated encryption key",
-                    &revokee_pubkey[..revokee_pubkey.len().min(16)]
-                );
+                .map_err(|e| format!("Failed to enroll this device: {e}"))?;
 
-                Ok(())
+                let verify_ts = hlc.now().to_string();
+                bae_core::sync::invite::verify_device(
+                    bucket,
+                    &mut chain,
+                    &keypair,
+                    &sync_handle.device_keypair,
+                    &device_id,
+                    &verify_ts,
+                )
+                .await
+                .map_err(|e| format!("Failed to record device verification: {e}"))
             }
             .await;
 
             match result {
                 Ok(()) => {
-                    // Reload the member list.
-                    let bucket: &dyn SyncBucketClient = &*sync_handle.bucket_client;
-
-                    match load_membership_from_bucket(bucket, Some(&user_pubkey_hex)).await {
-                        Ok(members) => state.sync().members().set(members),
-                        Err(e) => {
-                            tracing::warn!("Failed to reload membership after revocation: {e}")
-                        }
-                    }
+                    tracing::info!("Verified linked device {device_id}");
                 }
                 Err(e) => {
-                    state.sync().remove_member_error().set(Some(e));
+                    tracing::warn!("{e}");
+
+                    state.sync().error().set(Some(e));
                 }
             }
-
-            state.sync().removing_member().set(false);
         });
     }
 
@@ -1572,6 +3305,59 @@ impl AppService {
         Ok(())
     }
 
+    /// Try S3 settings before they're saved: write, read back, range-read and
+    /// delete a probe object, and put each step's latency or error in the store.
+    pub fn check_s3_connection(&self, config_data: bae_ui::SyncBucketConfig) {
+        let state = self.state;
+        let key_prefix = self.config.cloud_home_s3_key_prefix.clone();
+
+        state.sync().checking_connection().set(true);
+        state.sync().connection_check().set(None);
+
+        spawn(async move {
+            let endpoint = if config_data.endpoint.is_empty() {
+                None
+            } else {
+                Some(config_data.endpoint)
+            };
+            let steps = match bae_core::cloud_home::s3::S3CloudHome::new(
+                config_data.bucket,
+                config_data.region,
+                endpoint,
+                config_data.access_key,
+                config_data.secret_key,
+                key_prefix,
+            )
+            .await
+            {
+                Ok(home) => bae_core::cloud_home::probe::check_connection(&home)
+                    .await
+                    .into_iter()
+                    .map(|step| bae_ui::stores::ConnectionCheckStep {
+                        label: step.operation.label().to_string(),
+                        latency_ms: step.latency.as_millis() as u64,
+                        error: step.error,
+                    })
+                    .collect(),
+                Err(e) => vec![bae_ui::stores::ConnectionCheckStep {
+                    label: "Connect".to_string(),
+                    latency_ms: 0,
+                    error: Some(e.to_string()),
+                }],
+            };
+
+            if let Some(failed) = steps.iter().find(|s| s.error.is_some()) {
+                tracing::warn!(
+                    "S3 connection check failed at {}: {}",
+                    failed.label,
+                    failed.error.as_deref().unwrap_or_default()
+                );
+            }
+            state.sync().connection_check().set(Some(steps));
+            state.sync().checking_connection().set(false);
+        });
+    }
+
     /// Update the selected cloud provider in the store (does not persist until sign-in/save).
     pub fn select_cloud_provider(&self, provider: bae_ui::stores::config::CloudProvider) {
         self.state.config().cloud_provider().set(Some(provider));
@@ -1863,6 +3649,82 @@ impl AppService {
         }
     }
 
+    /// Sync with other devices on the local network instead of a cloud home.
+    pub fn use_lan_sync(&self) {
+        let state = self.state;
+        let mut config = self.config.clone();
+        let key_service = self.key_service.clone();
+
+        config.cloud_provider = Some(config::CloudProvider::Lan);
+        if let Err(e) = config.save() {
+            tracing::error!("Failed to save local network sync config: {e}");
+            state
+                .sync()
+                .sign_in_error()
+                .set(Some(format!("Failed to save config: {e}")));
+            return;
+        }
+
+        {
+            let mut config_lens = state.config();
+            let mut cs = config_lens.write();
+            cs.cloud_provider = Some(bae_ui::stores::config::CloudProvider::Lan);
+            cs.cloud_account_display = Some("Local network".to_string());
+        }
+        {
+            let mut sync_lens = state.sync();
+            let mut ss = sync_lens.write();
+            ss.cloud_home_configured = config.sync_enabled(&key_service);
+            ss.sign_in_error = None;
+        }
+    }
+
+    /// Ask for a folder, such as one on a USB drive or NAS mount, and use it
+    /// as the cloud home.
+    pub fn choose_sync_folder(&self) {
+        let state = self.state;
+        let mut config = self.config.clone();
+        let key_service = self.key_service.clone();
+
+        spawn(async move {
+            let Some(folder) = rfd::AsyncFileDialog::new()
+                .set_title("Choose a sync folder")
+                .pick_folder()
+                .await
+            else {
+                return;
+            };
+            let path = folder.path().to_string_lossy().to_string();
+
+            config.cloud_provider = Some(config::CloudProvider::Folder);
+            config.cloud_home_folder_path = Some(path.clone());
+
+            if let Err(e) = config.save() {
+                tracing::error!("Failed to save sync folder config: {e}");
+                state
+                    .sync()
+                    .sign_in_error()
+                    .set(Some(format!("Failed to save config: {e}")));
+                return;
+            }
+
+            tracing::info!("Using {path} as the sync folder");
+
+            {
+                let mut config_lens = state.config();
+                let mut cs = config_lens.write();
+                cs.cloud_provider = Some(bae_ui::stores::config::CloudProvider::Folder);
+                cs.cloud_account_display = Some(path);
+            }
+            {
+                let mut sync_lens = state.sync();
+                let mut ss = sync_lens.write();
+                ss.cloud_home_configured = config.sync_enabled(&key_service);
+                ss.sign_in_error = None;
+            }
+        });
+    }
+
     /// Disconnect the current cloud provider. Clears tokens and config.
     pub fn disconnect_cloud_provider(&self) {
         let state = self.state;
@@ -1895,6 +3757,7 @@ impl AppService {
         new_config.cloud_home_onedrive_drive_id = None;
         new_config.cloud_home_onedrive_folder_id = None;
         new_config.cloud_home_icloud_container_path = None;
+        new_config.cloud_home_folder_path = None;
         new_config.cloud_home_bae_cloud_url = None;
         new_config.cloud_home_bae_cloud_username = None;
 
@@ -2095,6 +3958,7 @@ fn ui_sort_to_core(criteria: &[SortCriterion]) -> Vec<bae_core::db::AlbumSortCri
                 LibrarySortField::Artist => bae_core::db::AlbumSortField::Artist,
                 LibrarySortField::Year => bae_core::db::AlbumSortField::Year,
                 LibrarySortField::DateAdded => bae_core::db::AlbumSortField::DateAdded,
+                LibrarySortField::PlayCount => bae_core::db::AlbumSortField::PlayCount,
             },
             direction: match c.direction {
                 SortDirection::Ascending => bae_core::db::SortDirection::Ascending,
@@ -2104,6 +3968,95 @@ fn ui_sort_to_core(criteria: &[SortCriterion]) -> Vec<bae_core::db::AlbumSortCri
         .collect()
 }
 
+/// Convert saved bae-core sort criteria back to bae-ui sort criteria
+fn core_sort_to_ui(criteria: &[bae_core::db::AlbumSortCriterion]) -> Vec<SortCriterion> {
+    criteria
+        .iter()
+        .map(|c| SortCriterion {
+            field: match c.field {
+                bae_core::db::AlbumSortField::Title => LibrarySortField::Title,
+                bae_core::db::AlbumSortField::Artist => LibrarySortField::Artist,
+                bae_core::db::AlbumSortField::Year => LibrarySortField::Year,
+                bae_core::db::AlbumSortField::DateAdded => LibrarySortField::DateAdded,
+                bae_core::db::AlbumSortField::PlayCount => LibrarySortField::PlayCount,
+            },
+            direction: match c.direction {
+                bae_core::db::SortDirection::Ascending => SortDirection::Ascending,
+                bae_core::db::SortDirection::Descending => SortDirection::Descending,
+            },
+        })
+        .collect()
+}
+
+/// Convert the bae-ui library filter to a bae-core album filter
+fn ui_filter_to_core(filter: &LibraryFilter) -> bae_core::db::AlbumFilter {
+    bae_core::db::AlbumFilter {
+        genre: filter.genre.clone(),
+        decade: filter.decade,
+        collection: filter.collection.clone(),
+        storage: filter.storage.map(|s| match s {
+            LibraryStorageFilter::Local => bae_core::db::AlbumStorage::Local,
+            LibraryStorageFilter::Cloud => bae_core::db::AlbumStorage::Cloud,
+            LibraryStorageFilter::Unmanaged => bae_core::db::AlbumStorage::Unmanaged,
+        }),
+        quality: filter.quality.map(|q| match q {
+            LibraryQualityFilter::Lossless => bae_core::db::AudioQuality::Lossless,
+            LibraryQualityFilter::Lossy => bae_core::db::AudioQuality::Lossy,
+        }),
+    }
+}
+
+/// Convert a saved bae-core album filter back to the bae-ui library filter
+fn core_filter_to_ui(filter: &bae_core::db::AlbumFilter) -> LibraryFilter {
+    LibraryFilter {
+        genre: filter.genre.clone(),
+        decade: filter.decade,
+        collection: filter.collection.clone(),
+        storage: filter.storage.map(|s| match s {
+            bae_core::db::AlbumStorage::Local => LibraryStorageFilter::Local,
+            bae_core::db::AlbumStorage::Cloud => LibraryStorageFilter::Cloud,
+            bae_core::db::AlbumStorage::Unmanaged => LibraryStorageFilter::Unmanaged,
+        }),
+        quality: filter.quality.map(|q| match q {
+            bae_core::db::AudioQuality::Lossless => LibraryQualityFilter::Lossless,
+            bae_core::db::AudioQuality::Lossy => LibraryQualityFilter::Lossy,
+        }),
+    }
+}
+
+/// Collapse albums linked as versions into one entry per version group.
+///
+/// The first album of each group in `albums` (i.e. per the current sort order)
+/// stands in for the whole group. Returns the remaining albums along with the
+/// group size keyed by each representative's album ID.
+fn collapse_album_versions(
+    albums: Vec<bae_core::db::DbAlbum>,
+    groups: &HashMap<String, String>,
+) -> (Vec<bae_core::db::DbAlbum>, HashMap<String, usize>) {
+    let mut group_sizes: HashMap<&str, usize> = HashMap::new();
+    for group in groups.values() {
+        *group_sizes.entry(group.as_str()).or_default() += 1;
+    }
+
+    let mut seen_groups = HashSet::new();
+    let mut version_counts = HashMap::new();
+    let albums = albums
+        .into_iter()
+        .filter(|album| match groups.get(&album.id) {
+            Some(group) => {
+                if !seen_groups.insert(group.as_str()) {
+                    return false;
+                }
+                version_counts.insert(album.id.clone(), group_sizes[group.as_str()]);
+                true
+            }
+            None => true,
+        })
+        .collect();
+
+    (albums, version_counts)
+}
+
 /// Load library albums and artists into the Store
 async fn load_library(
     state: &Store<AppState>,
@@ -2115,9 +4068,26 @@ async fn load_library(
 
     let ui_criteria = state.ui().library_sort().sort_criteria().read().clone();
     let sort = ui_sort_to_core(&ui_criteria);
+    let filter = ui_filter_to_core(&state.ui().library_sort().filter().read());
 
-    match library_manager.get().get_albums(&sort).await {
+    match library_manager
+        .get()
+        .get_albums_filtered(&sort, &filter)
+        .await
+    {
         Ok(album_list) => {
+            let groups = library_manager
+                .get()
+                .get_album_version_groups()
+                .await
+                .unwrap_or_default();
+            let (album_list, version_counts) = collapse_album_versions(album_list, &groups);
+            let cover_placeholders = library_manager
+                .get()
+                .get_album_cover_blurhashes()
+                .await
+                .unwrap_or_default();
+
             let mut artists_map = HashMap::new();
             for album in &album_list {
                 if let Ok(db_artists) = library_manager.get().get_artists_for_album(&album.id).await
@@ -2129,6 +4099,11 @@ async fn load_library(
                     artists_map.insert(album.id.clone(), artists);
                 }
             }
+            let composers = library_manager
+                .get()
+                .get_album_composers()
+                .await
+                .unwrap_or_default();
             let display_albums = album_list
                 .iter()
                 .map(|a| album_from_db_ref(a, imgs))
@@ -2138,6 +4113,9 @@ async fn load_library(
             let mut lib = lib_lens.write();
             lib.albums = display_albums;
             lib.artists_by_album = artists_map;
+            lib.composers_by_album = composers_by_album_from_db(&composers, imgs);
+            lib.version_counts = version_counts;
+            lib.cover_placeholders = cover_placeholders;
             lib.loading = false;
             lib.error = None;
         }
@@ -2148,6 +4126,61 @@ async fn load_library(
             lib.loading = false;
         }
     }
+
+    load_library_filter_options(state, library_manager).await;
+    load_recent_albums(state, library_manager).await;
+}
+
+/// Load the genres, decades and collections the library page can filter
+/// albums by
+async fn load_library_filter_options(
+    state: &Store<AppState>,
+    library_manager: &SharedLibraryManager,
+) {
+    let lm = library_manager.get();
+    match lm.get_genres().await {
+        Ok(genres) => {
+            let mut names: Vec<String> = genres.into_iter().map(|g| g.name).collect();
+            names.sort_by_key(|name| name.to_lowercase());
+            state.library().filter_genres().set(names);
+        }
+        Err(e) => tracing::warn!("Failed to load genres for filtering: {}", e),
+    }
+    match lm.get_album_decades().await {
+        Ok(decades) => state.library().filter_decades().set(decades),
+        Err(e) => tracing::warn!("Failed to load decades for filtering: {}", e),
+    }
+    match lm.get_collections().await {
+        Ok(collections) => {
+            let collections = collections
+                .into_iter()
+                .map(|c| Collection {
+                    id: c.id,
+                    name: c.name,
+                })
+                .collect();
+            state.library().collections().set(collections);
+        }
+        Err(e) => tracing::warn!("Failed to load collections: {}", e),
+    }
+    match lm.get_album_collection_ids().await {
+        Ok(ids) => state.library().collection_ids_by_album().set(ids),
+        Err(e) => tracing::warn!("Failed to load album collections: {}", e),
+    }
+}
+
+/// Load the album IDs for the library's "Recently Added" and "Recently
+/// Played" shelves
+async fn load_recent_albums(state: &Store<AppState>, library_manager: &SharedLibraryManager) {
+    let lm = library_manager.get();
+    match lm.get_recently_added_album_ids().await {
+        Ok(ids) => state.library().recently_added().set(ids),
+        Err(e) => tracing::warn!("Failed to load recently added albums: {}", e),
+    }
+    match lm.get_recently_played_album_ids().await {
+        Ok(ids) => state.library().recently_played().set(ids),
+        Err(e) => tracing::warn!("Failed to load recently played albums: {}", e),
+    }
 }
 
 /// Ensure a followed library has a local DB (bootstrap from snapshot if needed),
@@ -2159,6 +4192,9 @@ async fn load_followed_library_from_db(
     encryption_key: &[u8],
     imgs: &ImageServerHandle,
     playback_handle: &playback::PlaybackHandle,
+    download_parallelism: usize,
+    readahead: u64,
+    cache: cache::CacheManager,
 ) {
     state.library().loading().set(true);
     state.library().error().set(None);
@@ -2176,6 +4212,10 @@ async fn load_followed_library_from_db(
 
     match db.get_albums(&[]).await {
         Ok(album_list) => {
+            let groups = db.get_album_version_groups().await.unwrap_or_default();
+            let (album_list, version_counts) = collapse_album_versions(album_list, &groups);
+            let cover_placeholders = db.get_album_cover_blurhashes().await.unwrap_or_default();
+
             let mut artists_map = HashMap::new();
             for album in &album_list {
                 if let Ok(db_artists) = db.get_artists_for_album(&album.id).await {
@@ -2186,6 +4226,7 @@ async fn load_followed_library_from_db(
                     artists_map.insert(album.id.clone(), artists);
                 }
             }
+            let composers = db.get_album_composers().await.unwrap_or_default();
             let display_albums = album_list
                 .iter()
                 .map(|a| album_from_db_ref(a, imgs))
@@ -2195,6 +4236,9 @@ async fn load_followed_library_from_db(
             let mut lib = lib_lens.write();
             lib.albums = display_albums;
             lib.artists_by_album = artists_map;
+            lib.composers_by_album = composers_by_album_from_db(&composers, imgs);
+            lib.version_counts = version_counts;
+            lib.cover_placeholders = cover_placeholders;
             lib.loading = false;
             lib.error = None;
         }
@@ -2221,6 +4265,9 @@ async fn load_followed_library_from_db(
             database: db,
             cloud_storage,
             encryption,
+            download_parallelism,
+            readahead,
+            cache,
         });
     } else {
         tracing::error!(
@@ -2230,6 +4277,42 @@ async fn load_followed_library_from_db(
     }
 }
 
+/// Concurrent range requests and readahead bytes for streaming from a cloud home
+fn streaming_settings(config: &config::Config) -> (usize, u64) {
+    let download_parallelism = config
+        .download_parallelism
+        .unwrap_or(bae_core::storage::DEFAULT_DOWNLOAD_PARALLELISM);
+    let readahead = config
+        .readahead_mib
+        .map(|mib| mib as u64 * 1024 * 1024)
+        .unwrap_or(bae_core::storage::DEFAULT_READAHEAD);
+    (download_parallelism, readahead)
+}
+
+/// The library's cloud home as release file storage, if it has one. Its
+/// traffic counts toward the storage usage shown in settings.
+async fn library_cloud_storage(
+    config: &config::Config,
+    key_service: &KeyService,
+    library_manager: &LibraryManager,
+) -> Option<Arc<dyn bae_core::cloud_storage::CloudStorage>> {
+    config.cloud_provider.as_ref()?;
+    match bae_core::cloud_home::create_cloud_home(config, key_service).await {
+        Ok(home) => Some(Arc::new(MeteredCloudStorage::new(
+            Arc::new(
+                bae_core::cloud_storage::CloudHomeStorageAdapter::new(Arc::from(home))
+                    .with_upload_state(config.library_dir.uploads_dir()),
+            ),
+            library_manager.database().clone(),
+        ))),
+        Err(e) => {
+            tracing::warn!("Couldn't open the cloud home: {}", e);
+
+            None
+        }
+    }
+}
+
 /// Load album detail from a followed library's local DB into the Store.
 async fn load_followed_album_detail_from_db(
     state: &Store<AppState>,
@@ -2259,13 +4342,15 @@ async fn load_followed_album_detail_from_db(
         Ok(Some(album)) => {
             let display_album = album_from_db_ref(&album, imgs);
 
-            let artists = match db.get_artists_for_album(album_id).await {
-                Ok(db_artists) => db_artists
-                    .iter()
-                    .map(|a| artist_from_db_ref(a, imgs))
-                    .collect(),
-                Err(_) => vec![],
-            };
+            let db_artist_credit = db
+                .get_album_artist_credits(album_id)
+                .await
+                .unwrap_or_default();
+            let artists = db_artist_credit
+                .iter()
+                .map(|(a, _)| artist_from_db_ref(a, imgs))
+                .collect();
+            let artist_credit = artist_credit_from_db(&db_artist_credit, imgs);
 
             // Get the first release for this album to show tracks
             let releases = db
@@ -2275,24 +4360,75 @@ async fn load_followed_album_detail_from_db(
 
             let display_releases: Vec<Release> = releases.iter().map(release_from_db_ref).collect();
 
+            let versions = db
+                .get_album_versions(album_id)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .map(album_version_from_db_ref)
+                .collect();
+
+            let custom_tags = db
+                .get_album_custom_tags(album_id)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .map(custom_tag_from_db)
+                .collect();
+            let notes = db.get_album_notes(album_id).await.unwrap_or_default();
+            // Only the owner's side knows members' names; show keys here
+            let comments = db
+                .get_album_comments(album_id)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .map(|c| album_comment_from_db(c, &AttributionMap::new(), None))
+                .collect();
+
             let selected_release_id = releases.first().map(|r| r.id.clone());
 
-            let (tracks, files) = if let Some(release) = releases.first() {
-                let db_tracks = db
-                    .get_tracks_for_release(&release.id)
-                    .await
-                    .unwrap_or_default();
-                let db_files = db
-                    .get_files_for_release(&release.id)
-                    .await
-                    .unwrap_or_default();
-                (
-                    db_tracks.iter().map(track_from_db_ref).collect::<Vec<_>>(),
-                    db_files.iter().map(file_from_db_ref).collect::<Vec<_>>(),
-                )
-            } else {
-                (vec![], vec![])
-            };
+            let (tracks, files, track_works, track_credits, disc_titles) =
+                if let Some(release) = releases.first() {
+                    let db_tracks = db
+                        .get_tracks_for_release(&release.id)
+                        .await
+                        .unwrap_or_default();
+                    let db_files = db
+                        .get_files_for_release(&release.id)
+                        .await
+                        .unwrap_or_default();
+                    let works = db
+                        .get_track_works_for_release(&release.id)
+                        .await
+                        .unwrap_or_default();
+                    let composers = db
+                        .get_composers_for_release(&release.id)
+                        .await
+                        .unwrap_or_default();
+                    let credits = db
+                        .get_track_artist_credits_for_release(&release.id)
+                        .await
+                        .unwrap_or_default();
+                    let discs = db.get_release_discs(&release.id).await.unwrap_or_default();
+                    (
+                        db_tracks.iter().map(track_from_db_ref).collect::<Vec<_>>(),
+                        db_files.iter().map(file_from_db_ref).collect::<Vec<_>>(),
+                        track_works_from_db(&works, &composers, imgs),
+                        track_credits_from_db(&credits, imgs),
+                        discs
+                            .into_iter()
+                            .map(|d| (d.disc_number, d.title))
+                            .collect(),
+                    )
+                } else {
+                    (
+                        vec![],
+                        vec![],
+                        HashMap::new(),
+                        HashMap::new(),
+                        HashMap::new(),
+                    )
+                };
 
             let track_count = tracks.len();
             let track_ids: Vec<String> = tracks.iter().map(|t| t.id.clone()).collect();
@@ -2305,11 +4441,21 @@ async fn load_followed_album_detail_from_db(
             let mut detail = detail_lens.write();
             detail.album = Some(display_album);
             detail.artists = artists;
+            detail.artist_credit = artist_credit;
             detail.tracks = tracks;
             detail.track_count = track_count;
             detail.track_ids = track_ids;
             detail.track_disc_info = track_disc_info;
+            detail.disc_titles = disc_titles;
+            detail.track_works = track_works;
+            detail.track_credits = track_credits;
             detail.releases = display_releases;
+            detail.versions = versions;
+            detail.version_candidates = vec![];
+            detail.custom_tags = custom_tags;
+            detail.notes = notes;
+            detail.comments = comments;
+            detail.can_comment = false;
             detail.files = files;
             detail.images = vec![];
             detail.selected_release_id = selected_release_id;
@@ -2455,7 +4601,13 @@ async fn open_followed_db(
 struct AlbumDetailData {
     album: Option<Album>,
     artists: Vec<Artist>,
+    artist_credit: Vec<CreditedArtist>,
     releases: Vec<Release>,
+    versions: Vec<AlbumVersion>,
+    version_candidates: Vec<Album>,
+    custom_tags: Vec<CustomTag>,
+    notes: Option<String>,
+    comments: Vec<bae_core::db::DbAlbumComment>,
     selected_release_id: String,
     managed_locally: bool,
     managed_in_cloud: bool,
@@ -2465,6 +4617,9 @@ struct AlbumDetailData {
     track_count: usize,
     track_ids: Vec<String>,
     track_disc_info: Vec<(Option<i32>, String)>,
+    disc_titles: HashMap<i32, String>,
+    track_works: HashMap<String, TrackWork>,
+    track_credits: HashMap<String, Vec<CreditedArtist>>,
     files: Vec<File>,
     images: Vec<bae_ui::Image>,
 }
@@ -2516,14 +4671,39 @@ async fn fetch_album_detail(
 
     let releases = db_releases.iter().map(release_from_db_ref).collect();
 
-    let artists = library_manager
+    let (versions, version_candidates) = fetch_album_versions(library_manager, album_id, imgs)
+        .await
+        .map_err(|e| format!("Failed to load versions: {e}"))?;
+
+    let db_artist_credit = library_manager
         .get()
-        .get_artists_for_album(album_id)
+        .get_album_artist_credits(album_id)
         .await
-        .unwrap_or_default()
+        .unwrap_or_default();
+    let artists = db_artist_credit
+        .iter()
+        .map(|(a, _)| artist_from_db_ref(a, imgs))
+        .collect();
+    let artist_credit = artist_credit_from_db(&db_artist_credit, imgs);
+
+    let custom_tags = library_manager
+        .get()
+        .get_album_custom_tags(album_id)
+        .await
+        .map_err(|e| format!("Failed to load tags: {e}"))?
         .iter()
-        .map(|a| artist_from_db_ref(a, imgs))
+        .map(custom_tag_from_db)
         .collect();
+    let notes = library_manager
+        .get()
+        .get_album_notes(album_id)
+        .await
+        .map_err(|e| format!("Failed to load notes: {e}"))?;
+    let comments = library_manager
+        .get()
+        .get_album_comments(album_id)
+        .await
+        .map_err(|e| format!("Failed to load comments: {e}"))?;
 
     let mut tracks: Vec<Track> = library_manager
         .get()
@@ -2542,6 +4722,32 @@ async fn fetch_album_detail(
         .map(|t| (t.disc_number, t.id.clone()))
         .collect();
 
+    let works = library_manager
+        .get()
+        .get_track_works_for_release(&selected_release_id)
+        .await
+        .map_err(|e| format!("Failed to load works: {e}"))?;
+    let composers = library_manager
+        .get()
+        .get_composers_for_release(&selected_release_id)
+        .await
+        .map_err(|e| format!("Failed to load composers: {e}"))?;
+    let track_works = track_works_from_db(&works, &composers, imgs);
+    let disc_titles = library_manager
+        .get()
+        .get_release_discs(&selected_release_id)
+        .await
+        .map_err(|e| format!("Failed to load disc titles: {e}"))?
+        .into_iter()
+        .map(|d| (d.disc_number, d.title))
+        .collect();
+    let track_credits = library_manager
+        .get()
+        .get_track_artist_credits_for_release(&selected_release_id)
+        .await
+        .map_err(|e| format!("Failed to load track credits: {e}"))?;
+    let track_credits = track_credits_from_db(&track_credits, imgs);
+
     let db_files = library_manager
         .get()
         .get_files_for_release(&selected_release_id)
@@ -2562,7 +4768,13 @@ async fn fetch_album_detail(
     Ok(AlbumDetailData {
         album: Some(album),
         artists,
+        artist_credit,
         releases,
+        versions,
+        version_candidates,
+        custom_tags,
+        notes,
+        comments,
         selected_release_id,
         managed_locally,
         managed_in_cloud,
@@ -2572,11 +4784,134 @@ async fn fetch_album_detail(
         track_count,
         track_ids,
         track_disc_info,
+        disc_titles,
+        track_works,
+        track_credits,
         files,
         images,
     })
 }
 
+/// Fetch an album's linked versions and the albums that could be linked to it.
+///
+/// Candidates are other albums by the same artists that aren't already in the group.
+/// Build the sidebar row for a track: the track plus its album's title and cover
+async fn load_queue_item(
+    library_manager: &SharedLibraryManager,
+    imgs: &ImageServerHandle,
+    track_id: &str,
+) -> Option<QueueItem> {
+    let track = library_manager.get().get_track(track_id).await.ok()??;
+    let (album_title, cover_url) =
+        if let Ok(album_id) = library_manager.get().get_album_id_for_track(track_id).await {
+            if let Ok(Some(album)) = library_manager.get().get_album_by_id(&album_id).await {
+                let cover = album
+                    .cover_release_id
+                    .as_ref()
+                    .map(|rid| imgs.image_url(rid));
+                (album.title, cover)
+            } else {
+                ("Unknown Album".to_string(), None)
+            }
+        } else {
+            ("Unknown Album".to_string(), None)
+        };
+
+    Some(QueueItem {
+        track: track_from_db_ref(&track),
+        album_title,
+        cover_url,
+    })
+}
+
+async fn fetch_album_versions(
+    library_manager: &SharedLibraryManager,
+    album_id: &str,
+    imgs: &ImageServerHandle,
+) -> Result<(Vec<AlbumVersion>, Vec<Album>), bae_core::library::LibraryError> {
+    let db_versions = library_manager.get().get_album_versions(album_id).await?;
+
+    let mut excluded: HashSet<String> = db_versions.iter().map(|v| v.album_id.clone()).collect();
+    excluded.insert(album_id.to_string());
+
+    let mut candidates = Vec::new();
+    for artist in library_manager
+        .get()
+        .get_artists_for_album(album_id)
+        .await?
+    {
+        for db_album in library_manager
+            .get()
+            .database()
+            .get_albums_for_artist(&artist.id)
+            .await?
+        {
+            if excluded.insert(db_album.id.clone()) {
+                candidates.push(album_from_db_ref(&db_album, imgs));
+            }
+        }
+    }
+
+    let versions = db_versions.iter().map(album_version_from_db_ref).collect();
+    Ok((versions, candidates))
+}
+
+/// Re-read an album's versions into the Store after linking or unlinking
+async fn refresh_album_versions(
+    state: &Store<AppState>,
+    library_manager: &SharedLibraryManager,
+    album_id: &str,
+    imgs: &ImageServerHandle,
+) {
+    match fetch_album_versions(library_manager, album_id, imgs).await {
+        Ok((versions, candidates)) => {
+            state.album_detail().versions().set(versions);
+            state.album_detail().version_candidates().set(candidates);
+        }
+        Err(e) => {
+            tracing::error!("Failed to load album versions: {}", e);
+        }
+    }
+}
+
+/// Attribute comments to members by name, and work out whether the user can
+/// comment: only in a shared library, where they have a key to sign with.
+fn album_comments_for_display(
+    state: &Store<AppState>,
+    comments: &[bae_core::db::DbAlbumComment],
+) -> (Vec<bae_ui::AlbumComment>, bool) {
+    let members = state.sync().members().read().clone();
+    let user_pubkey = state.sync().user_pubkey().read().clone();
+
+    let mut names = AttributionMap::new();
+    for member in &members {
+        names.set_name(&member.pubkey, &member.display_name);
+    }
+    let display = comments
+        .iter()
+        .map(|c| album_comment_from_db(c, &names, user_pubkey.as_deref()))
+        .collect();
+    (display, user_pubkey.is_some() && !members.is_empty())
+}
+
+/// Re-read an album's comments into the Store after posting or removing one
+async fn reload_album_comments(
+    state: &Store<AppState>,
+    library_manager: &SharedLibraryManager,
+    album_id: &str,
+) {
+    match library_manager.get().get_album_comments(album_id).await {
+        Ok(comments) => {
+            let (comments, can_comment) = album_comments_for_display(state, &comments);
+            state.album_detail().comments().set(comments);
+            state.album_detail().can_comment().set(can_comment);
+        }
+        Err(e) => {
+            tracing::error!("Failed to load comments: {}", e);
+        }
+    }
+}
+
 /// Load album detail data into the Store
 async fn load_album_detail(
     state: &Store<AppState>,
@@ -2594,7 +4929,15 @@ async fn load_album_detail(
             let mut detail = detail_lens.write();
             detail.album = data.album;
             detail.artists = data.artists;
+            detail.artist_credit = data.artist_credit;
             detail.releases = data.releases;
+            detail.versions = data.versions;
+            detail.version_candidates = data.version_candidates;
+            detail.custom_tags = data.custom_tags;
+            detail.notes = data.notes;
+            let (comments, can_comment) = album_comments_for_display(state, &data.comments);
+            detail.comments = comments;
+            detail.can_comment = can_comment;
             detail.selected_release_id = Some(data.selected_release_id);
             detail.managed_locally = data.managed_locally;
             detail.managed_in_cloud = data.managed_in_cloud;
@@ -2604,6 +4947,9 @@ async fn load_album_detail(
             detail.track_count = data.track_count;
             detail.track_ids = data.track_ids;
             detail.track_disc_info = data.track_disc_info;
+            detail.disc_titles = data.disc_titles;
+            detail.track_works = data.track_works;
+            detail.track_credits = data.track_credits;
             detail.files = data.files;
             detail.images = data.images;
             detail.transfer_progress = None;
@@ -2620,6 +4966,32 @@ async fn load_album_detail(
     }
 }
 
+/// Load how much of the selected release is kept downloaded
+async fn load_offline_pin(
+    state: &Store<AppState>,
+    library_manager: &SharedLibraryManager,
+    cache: &cache::CacheManager,
+) {
+    let Some(release_id) = state.album_detail().selected_release_id().peek().clone() else {
+        return;
+    };
+    match offline::offline_status(library_manager.get(), cache, &release_id).await {
+        Ok(status) => {
+            state
+                .album_detail()
+                .offline_pin()
+                .set(status.map(|status| OfflinePin {
+                    downloaded_files: status.downloaded_files,
+                    total_files: status.total_files,
+                    downloaded_bytes: status.downloaded_bytes,
+                }));
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load offline pin of {}: {}", release_id, e);
+        }
+    }
+}
+
 /// Fetch remote cover options from MusicBrainz Cover Art Archive and Discogs
 async fn fetch_remote_covers_async(
     library_manager: &SharedLibraryManager,
@@ -2729,15 +5101,17 @@ async fn change_cover_async(
                 .map_err(|e| format!("Failed to write cover: {}", e))?;
 
             // Upsert library_images record
+            let dimensions = image_dimensions(&bytes);
             let library_image = bae_core::db::DbLibraryImage {
                 id: release_id.to_string(),
                 image_type: bae_core::db::LibraryImageType::Cover,
                 content_type,
                 file_size: bytes.len() as i64,
-                width: None,
-                height: None,
+                width: dimensions.map(|(w, _)| w as i64),
+                height: dimensions.map(|(_, h)| h as i64),
                 source: "local".to_string(),
                 source_url: Some(format!("release://{}", file.original_filename)),
+                blurhash: bae_core::image_placeholder::blurhash_blocking(bytes.clone()).await,
                 updated_at: chrono::Utc::now(),
                 created_at: chrono::Utc::now(),
             };
@@ -2772,15 +5146,17 @@ async fn change_cover_async(
                 .map_err(|e| format!("Failed to write cover: {}", e))?;
 
             // Upsert library_images record
+            let dimensions = image_dimensions(&bytes);
             let library_image = bae_core::db::DbLibraryImage {
                 id: release_id.to_string(),
                 image_type: bae_core::db::LibraryImageType::Cover,
                 content_type,
                 file_size: bytes.len() as i64,
-                width: None,
-                height: None,
+                width: dimensions.map(|(w, _)| w as i64),
+                height: dimensions.map(|(_, h)| h as i64),
                 source,
                 source_url: Some(url.clone()),
+                blurhash: bae_core::image_placeholder::blurhash_blocking(bytes.clone()).await,
                 updated_at: chrono::Utc::now(),
                 created_at: chrono::Utc::now(),
             };
@@ -2806,6 +5182,7 @@ async fn change_cover_async(
 struct ArtistDetailData {
     artist: Artist,
     albums: Vec<Album>,
+    appearances: Vec<Album>,
     artists_by_album: HashMap<String, Vec<Artist>>,
 }
 
@@ -2828,6 +5205,13 @@ async fn fetch_artist_detail(
         .get_albums_for_artist(artist_id)
         .await
         .map_err(|e| format!("Failed to load albums: {e}"))?;
+    let appearance_ids: HashSet<String> = library_manager
+        .get()
+        .get_appearance_album_ids(artist_id)
+        .await
+        .map_err(|e| format!("Failed to load albums: {e}"))?
+        .into_iter()
+        .collect();
 
     let mut artists_by_album = HashMap::new();
     for album in &db_albums {
@@ -2840,14 +5224,15 @@ async fn fetch_artist_detail(
         }
     }
 
-    let albums = db_albums
+    let (appearances, albums): (Vec<Album>, Vec<Album>) = db_albums
         .iter()
         .map(|a| album_from_db_ref(a, imgs))
-        .collect();
+        .partition(|a| appearance_ids.contains(&a.id));
 
     Ok(ArtistDetailData {
         artist,
         albums,
+        appearances,
         artists_by_album,
     })
 }
@@ -2868,6 +5253,7 @@ async fn load_artist_detail(
             let mut detail = detail_lens.write();
             detail.artist = Some(data.artist);
             detail.albums = data.albums;
+            detail.appearances = data.appearances;
             detail.artists_by_album = data.artists_by_album;
             detail.loading = false;
             detail.error = None;
@@ -2881,6 +5267,137 @@ async fn load_artist_detail(
     }
 }
 
+/// Load every genre and decade with its album count into the Store
+async fn load_browse_index(state: &Store<AppState>, library_manager: &SharedLibraryManager) {
+    state.browse().loading().set(true);
+    state.browse().error().set(None);
+
+    let lm = library_manager.get();
+    let result: Result<_, bae_core::library::LibraryError> =
+        async { Ok((lm.get_genres().await?, lm.get_decades().await?)) }.await;
+
+    let mut browse_lens = state.browse();
+    let mut browse = browse_lens.write();
+    browse.loading = false;
+    match result {
+        Ok((genres, decades)) => {
+            browse.genres = genres
+                .into_iter()
+                .filter(|g| g.album_count > 0)
+                .map(|g| BrowseGenre {
+                    name: g.name,
+                    album_count: g.album_count as usize,
+                })
+                .collect();
+            browse.decades = decades
+                .into_iter()
+                .map(|d| BrowseDecade {
+                    decade: d.decade,
+                    album_count: d.album_count as usize,
+                })
+                .collect();
+        }
+        Err(e) => browse.error = Some(format!("Failed to load genres and decades: {e}")),
+    }
+}
+
+/// Load the albums in one genre or decade, with their artists, into the Store
+async fn load_browse_albums(
+    state: &Store<AppState>,
+    library_manager: &SharedLibraryManager,
+    filter: &LibraryFilter,
+    imgs: &ImageServerHandle,
+) {
+    state.browse().loading().set(true);
+    state.browse().error().set(None);
+
+    let lm = library_manager.get();
+    let sort = ui_sort_to_core(&state.ui().library_sort().sort_criteria().read());
+    match lm
+        .get_albums_filtered(&sort, &ui_filter_to_core(filter))
+        .await
+    {
+        Ok(db_albums) => {
+            let mut artists_by_album = HashMap::new();
+            for album in &db_albums {
+                if let Ok(db_artists) = lm.get_artists_for_album(&album.id).await {
+                    let artists = db_artists
+                        .iter()
+                        .map(|a| artist_from_db_ref(a, imgs))
+                        .collect();
+                    artists_by_album.insert(album.id.clone(), artists);
+                }
+            }
+            let cover_placeholders = lm.get_album_cover_blurhashes().await.unwrap_or_default();
+
+            let mut browse_lens = state.browse();
+            let mut browse = browse_lens.write();
+            browse.albums = db_albums
+                .iter()
+                .map(|a| album_from_db_ref(a, imgs))
+                .collect();
+            browse.artists_by_album = artists_by_album;
+            browse.cover_placeholders = cover_placeholders;
+            browse.loading = false;
+        }
+        Err(e) => {
+            let mut browse_lens = state.browse();
+            let mut browse = browse_lens.write();
+            browse.error = Some(format!("Failed to load albums: {e}"));
+            browse.loading = false;
+        }
+    }
+}
+
+/// Load the playlists, and the selected playlist's tracks, into the Store.
+/// A selection that no longer exists (deleted here or on another device)
+/// is cleared.
+async fn load_playlists(
+    state: &Store<AppState>,
+    library_manager: &SharedLibraryManager,
+    imgs: &ImageServerHandle,
+) {
+    let lm = library_manager.get();
+    let result: Result<_, bae_core::library::LibraryError> = async {
+        let mut playlists = Vec::new();
+        let mut selected_entries = None;
+        let selected_id = state.playlists().selected_id().read().clone();
+        for playlist in lm.get_playlists().await? {
+            let entries = lm.get_playlist_entries(&playlist.id).await?;
+            playlists.push(playlist_from_db(&playlist, entries.len()));
+            if selected_id.as_deref() == Some(playlist.id.as_str()) {
+                selected_entries = Some(
+                    entries
+                        .iter()
+                        .map(|e| playlist_entry_from_db(e, imgs))
+                        .collect::<Vec<_>>(),
+                );
+            }
+        }
+        Ok((playlists, selected_entries))
+    }
+    .await;
+
+    let mut playlists_lens = state.playlists();
+    let mut playlists_state = playlists_lens.write();
+    playlists_state.loading = false;
+    match result {
+        Ok((playlists, selected_entries)) => {
+            if selected_entries.is_none() {
+                playlists_state.selected_id = None;
+            }
+            playlists_state.playlists = playlists;
+            playlists_state.entries = selected_entries.unwrap_or_default();
+            playlists_state.error = None;
+        }
+        Err(e) => {
+            tracing::error!("Failed to load playlists: {}", e);
+
+            playlists_state.error = Some(format!("Failed to load playlists: {e}"));
+        }
+    }
+}
+
 /// Convert bae_core ImportOperationStatus to bae_ui ImportOperationStatus
 fn convert_import_status(status: bae_core::db::ImportOperationStatus) -> ImportOperationStatus {
     match status {
@@ -2903,6 +5420,25 @@ fn convert_prepare_step(step: bae_core::import::PrepareStep) -> PrepareStep {
     }
 }
 
+/// Convert bae_core PartyStatus to bae_ui PartyInfo
+fn party_info_from_status(status: PartyStatus) -> PartyInfo {
+    PartyInfo {
+        code: status.code,
+        require_approval: status.require_approval,
+        guest_count: status.guest_count,
+        pending: status
+            .pending
+            .into_iter()
+            .map(|r| PartyRequestInfo {
+                id: r.id,
+                guest_name: r.guest_name,
+                track_title: r.track_title,
+                artist_name: r.artist_name,
+            })
+            .collect(),
+    }
+}
+
 /// Handle import progress events and update Store
 fn handle_import_progress(state: &Store<AppState>, event: ImportProgress) {
     match event {
@@ -3015,7 +5551,10 @@ fn handle_import_progress(state: &Store<AppState>, event: ImportProgress) {
 
             // Update album_detail import error
             state.album_detail().import_progress().set(None);
-            state.album_detail().import_error().set(Some(error));
+            state
+                .album_detail()
+                .import_error()
+                .set(Some(error.to_string()));
         }
     }
 }
@@ -3271,6 +5810,13 @@ async fn run_sync_loop(
     tokio::time::sleep(std::time::Duration::from_secs(3)).await;
 
     loop {
+        if !sync_handle
+            .wait_out_maintenance(trigger_rx, std::time::Duration::from_secs(30))
+            .await
+        {
+            break;
+        }
+
         // Run a sync cycle
         let result = run_sync_cycle(
             sync_handle,
@@ -3301,6 +5847,8 @@ async fn run_sync_loop(
                         device_id: d.device_id.clone(),
                         last_seq: d.last_seq,
                         last_sync: d.last_sync.clone(),
+                        revoked: sync_outcome.revoked_devices.contains(&d.device_id),
+                        pending: sync_outcome.pending_devices.contains(&d.device_id),
                     })
                     .collect();
 
@@ -3320,6 +5868,8 @@ async fn run_sync_loop(
                     Err(e) => tracing::warn!("Failed to load membership after sync: {e}"),
                 }
 
+                load_sync_conflicts(state, library_manager).await;
+
                 // Persist snapshot_seq (local_seq is persisted in run_sync_cycle after push)
                 if let Some(ss) = snapshot_seq {
                     let _ = db.set_sync_state("snapshot_seq", &ss.to_string()).await;
@@ -3382,7 +5932,7 @@ async fn run_sync_loop(
                             )
                             .await
                             {
-                                Ok(()) => {
+                                Ok(_) => {
                                     snapshot_seq = Some(local_seq);
                                     last_snapshot_time = Some(chrono::Utc::now());
 
@@ -3436,6 +5986,10 @@ async fn run_sync_loop(
 struct SyncOutcome {
     status: bae_core::sync::status::SyncStatus,
     changesets_applied: u64,
+    /// Devices revoked in the membership chain.
+    revoked_devices: Vec<String>,
+    /// Our devices waiting for this one to approve their keys.
+    pending_devices: Vec<String>,
 }
 
 /// Run a single sync cycle: grab changeset, push, pull, restart session.
@@ -3498,6 +6052,28 @@ async fn run_sync_cycle(
         .await
         .map_err(|e| format!("Failed to load sync cursors: {e}"))?;
 
+    // Pulls check changeset authors and devices against the chain. A library
+    // nobody has been invited to has none. If it fails to load, skip this
+    // cycle rather than pull without it.
+    let mut membership_chain = bae_core::sync::invite::load_membership_chain(bucket)
+        .await
+        .map_err(|e| format!("Failed to load membership chain: {e}"))?;
+
+    if let Some(chain) = membership_chain.as_mut() {
+        if let Err(e) = bae_core::sync::invite::ensure_device_enrolled(
+            bucket,
+            chain,
+            user_keypair,
+            device_id,
+            &sync_handle.device_keypair,
+            &hlc.now().to_string(),
+        )
+        .await
+        {
+            tracing::warn!("Failed to enroll this device's signing key: {e}");
+        }
+    }
+
     // Take the current session from the sync handle.
     // If no session exists (shouldn't happen, but handle gracefully), create a new one.
     let session = match sync_handle.session.lock().await.take() {
@@ -3523,7 +6099,8 @@ async fn run_sync_cycle(
                 &timestamp,
                 "background sync",
                 user_keypair,
-                None, // membership_chain: solo/legacy library for now
+                &sync_handle.device_keypair,
+                membership_chain.as_ref(),
                 library_dir,
             )
             .await
@@ -3591,6 +6168,17 @@ async fn run_sync_cycle(
         }
     }
 
+    // Keep conflicting edits for the user to review
+    for conflict in &sync_result.pull.conflicts {
+        if let Err(e) = db.upsert_sync_conflict(conflict).await {
+            tracing::warn!(
+                table = conflict.table_name.as_str(),
+                row_id = conflict.row_id.as_str(),
+                "Failed to record sync conflict: {e}"
+            );
+        }
+    }
+
     // Update HLC with max remote timestamp from pull results
     let max_remote_ts = sync_result
         .pull
@@ -3627,42 +6215,130 @@ async fn run_sync_cycle(
     let now = chrono::Utc::now().to_rfc3339();
     let status = build_sync_status(&sync_result.pull.remote_heads, device_id, Some(&now));
 
+    let (revoked_devices, pending_devices) = match membership_chain {
+        Some(chain) => {
+            // Only an enrolled device can approve others.
+            let user_pubkey = hex::encode(user_keypair.public_key);
+            let pending = match chain.device(&user_pubkey, device_id) {
+                Some(us) if !us.revoked => {
+                    bae_core::sync::invite::pending_devices(
+                        bucket,
+                        &chain,
+                        &user_pubkey,
+                        &sync_result.pull.remote_heads,
+                        device_id,
+                    )
+                    .await
+                }
+                _ => Vec::new(),
+            };
+            let revoked = chain
+                .devices()
+                .into_iter()
+                .filter(|d| d.revoked)
+                .map(|d| d.device_id)
+                .collect();
+            (revoked, pending.into_iter().map(|d| d.device_id).collect())
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+
     Ok(SyncOutcome {
         status,
         changesets_applied: sync_result.pull.changesets_applied,
+        revoked_devices,
+        pending_devices,
     })
 }
 
+/// Load unresolved sync conflicts, with the title of each conflicted album,
+/// into the store.
+async fn load_sync_conflicts(state: &Store<AppState>, library_manager: &SharedLibraryManager) {
+    let lm = library_manager.get();
+    let conflicts = match lm.get_sync_conflicts().await {
+        Ok(conflicts) => conflicts,
+        Err(e) => {
+            tracing::warn!("Failed to load sync conflicts: {e}");
+            return;
+        }
+    };
+
+    let mut infos = Vec::new();
+    for conflict in conflicts {
+        let album_title = match lm.get_album_by_id(&conflict.row_id).await {
+            Ok(Some(album)) => album.title,
+            _ => continue,
+        };
+        infos.push(SyncConflictInfo {
+            id: conflict.id,
+            album_title,
+            kept_remote: conflict.kept_remote,
+            fields: conflict
+                .fields
+                .into_iter()
+                .map(|f| SyncConflictFieldInfo {
+                    column: f.column,
+                    local: f.local,
+                    remote: f.remote,
+                })
+                .collect(),
+        });
+    }
+    state.sync().conflicts().set(infos);
+}
+
 /// Download membership entries from the bucket and build the display member list.
 ///
 /// Returns an empty Vec if no membership chain exists (solo library).
+/// Start a membership chain with this user as its founding owner, and
+/// upload the founder entry.
+async fn bootstrap_membership_chain(
+    bucket: &dyn SyncBucketClient,
+    keypair: &UserKeypair,
+    hlc: &bae_core::sync::hlc::Hlc,
+) -> Result<MembershipChain, String> {
+    let user_pubkey_hex = hex::encode(keypair.public_key);
+    let mut founder = MembershipEntry {
+        action: MembershipAction::Add,
+        user_pubkey: user_pubkey_hex.clone(),
+        role: CoreMemberRole::Owner,
+        timestamp: hlc.now().to_string(),
+        author_pubkey: String::new(),
+        signature: String::new(),
+        device_id: None,
+        device_pubkey: None,
+    };
+
+    sign_membership_entry(&mut founder, keypair);
+
+    let mut chain = MembershipChain::new();
+    chain
+        .add_entry(founder.clone())
+        .map_err(|e| format!("Failed to create founder entry: {e}"))?;
+
+    // Upload the founder entry to the bucket.
+    let founder_bytes = serde_json::to_vec(&founder)
+        .map_err(|e| format!("Failed to serialize founder entry: {e}"))?;
+    bucket
+        .put_membership_entry(&user_pubkey_hex, 1, founder_bytes)
+        .await
+        .map_err(|e| format!("Failed to upload founder entry: {e}"))?;
+
+    tracing::info!("Bootstrapped membership chain with founder entry");
+
+    Ok(chain)
+}
+
 async fn load_membership_from_bucket(
     bucket: &dyn SyncBucketClient,
     user_pubkey: Option<&str>,
 ) -> Result<Vec<Member>, String> {
-    let entry_keys = bucket
-        .list_membership_entries()
+    let Some(chain) = bae_core::sync::invite::load_membership_chain(bucket)
         .await
-        .map_err(|e| format!("Failed to list membership entries: {e}"))?;
-
-    if entry_keys.is_empty() {
+        .map_err(|e| format!("Failed to load membership chain: {e}"))?
+    else {
         return Ok(Vec::new());
-    }
-
-    let mut raw_entries = Vec::new();
-    for (author, seq) in &entry_keys {
-        let data = bucket
-            .get_membership_entry(author, *seq)
-            .await
-            .map_err(|e| format!("Failed to get membership entry {author}/{seq}: {e}"))?;
-
-        let entry: MembershipEntry = serde_json::from_slice(&data)
-            .map_err(|e| format!("Failed to parse membership entry {author}/{seq}: {e}"))?;
-        raw_entries.push(entry);
-    }
-
-    let chain = MembershipChain::from_entries(raw_entries)
-        .map_err(|e| format!("Invalid membership chain: {e}"))?;
+    };
 
     let attribution = AttributionMap::from_membership_chain(&chain);
     let current = chain.current_members();
@@ -3675,6 +6351,7 @@ async fn load_membership_from_bucket(
             let ui_role = match role {
                 CoreMemberRole::Owner => MemberRole::Owner,
                 CoreMemberRole::Member => MemberRole::Member,
+                CoreMemberRole::ReadOnly => MemberRole::ReadOnly,
             };
             Member {
                 pubkey,
@@ -3764,8 +6441,11 @@ async fn create_share_link_async(
     }
 
     // 5. Get cover image key
+    let cloud_home = cloud_home::create_cloud_home(config, key_service)
+        .await
+        .map_err(|e| format!("Cloud home error: {e}"))?;
     let cover_release_id = album.cover_release_id.as_deref().unwrap_or(release_id);
-    let cover_image_key = find_cover_image_key(db, cover_release_id).await;
+    let cover_image_key = find_cover_image_key(db, cloud_home.as_ref(), cover_release_id).await;
     if let Some(ref key) = cover_image_key {
         manifest_files.push(key.clone());
     }
@@ -3804,9 +6484,6 @@ async fn create_share_link_async(
 
     // 10. Upload to cloud home
     let share_id = uuid::Uuid::new_v4().to_string();
-    let cloud_home = cloud_home::create_cloud_home(config, key_service)
-        .await
-        .map_err(|e| format!("Cloud home error: {e}"))?;
     cloud_home
         .write(&format!("shares/{share_id}/meta.enc"), meta_encrypted)
         .await
@@ -3826,15 +6503,25 @@ async fn create_share_link_async(
 }
 
 /// Find the S3 key for a release's cover image.
-/// Returns `images/{ab}/{cd}/{release_id}` or None.
-async fn find_cover_image_key(db: &bae_core::db::Database, release_id: &str) -> Option<String> {
+/// Returns `images/{ab}/{cd}/{release_id}`, with `.plain` appended if the
+/// image was uploaded unencrypted, or None.
+async fn find_cover_image_key(
+    db: &bae_core::db::Database,
+    cloud_home: &dyn bae_core::cloud_home::CloudHome,
+    release_id: &str,
+) -> Option<String> {
     use bae_core::db::LibraryImageType;
+    use bae_core::sync::cloud_home_bucket::image_key;
     let image = db
         .get_library_image(release_id, &LibraryImageType::Cover)
         .await
         .ok()??;
-    let hex = image.id.replace('-', "");
-    Some(format!("images/{}/{}/{}", &hex[..2], &hex[2..4], &image.id))
+    let plain_key = image_key(&image.id, false);
+    if cloud_home.exists(&plain_key).await.unwrap_or(false) {
+        Some(plain_key)
+    } else {
+        Some(image_key(&image.id, true))
+    }
 }
 
 /// Detect the iCloud Drive ubiquity container for the app.
@@ -5,11 +5,13 @@ use super::utils::maybe_not_empty;
 use super::AlbumDetailView;
 use crate::ui::app_service::use_app;
 use crate::ui::Route;
-use bae_ui::display_types::{CoverChange, PlaybackDisplay};
+use bae_ui::display_types::{
+    AlbumNotesEdit, AlbumTagEdit, CoverChange, MetadataUpdate, PlaybackDisplay, TrackMapping,
+};
 use bae_ui::stores::config::LibrarySource;
 use bae_ui::stores::{
     AlbumDetailStateStoreExt, AppStateStoreExt, LibraryStateStoreExt, PlaybackStatus,
-    PlaybackUiStateStoreExt,
+    PlaybackUiStateStoreExt, PlaylistsStateStoreExt,
 };
 use bae_ui::{ErrorToast, SuccessToast};
 use dioxus::prelude::*;
@@ -104,6 +106,12 @@ pub fn AlbumDetail(album_id: ReadSignal<String>, release_id: ReadSignal<String>)
             playback.add_to_queue(vec![track_id]);
         }
     });
+    let on_track_add_to_playlist = EventHandler::new({
+        let app = app.clone();
+        move |(track_id, playlist_id): (String, String)| {
+            app.add_track_to_playlist(&playlist_id, &track_id);
+        }
+    });
     let on_track_export = EventHandler::new({
         let library_manager = library_manager.clone();
         let cache = cache.clone();
@@ -285,12 +293,30 @@ pub fn AlbumDetail(album_id: ReadSignal<String>, release_id: ReadSignal<String>)
             app.transfer_release_to_managed(&release_id);
         }
     });
+    let on_move_to_cloud = EventHandler::new({
+        let app = app.clone();
+        move |release_id: String| {
+            app.move_release_to_cloud(&release_id);
+        }
+    });
     let on_eject = EventHandler::new({
         let app = app.clone();
         move |release_id: String| {
             app.eject_release_storage(&release_id);
         }
     });
+    let on_cancel_transfer = EventHandler::new({
+        let app = app.clone();
+        move |release_id: String| {
+            app.cancel_release_transfer(&release_id);
+        }
+    });
+    let on_keep_downloaded = EventHandler::new({
+        let app = app.clone();
+        move |(release_id, keep): (String, bool)| {
+            app.set_release_kept_downloaded(&release_id, keep);
+        }
+    });
 
     // Cover picker callbacks
     let on_fetch_remote_covers = EventHandler::new({
@@ -312,9 +338,114 @@ pub fn AlbumDetail(album_id: ReadSignal<String>, release_id: ReadSignal<String>)
         }
     });
 
+    // Track mapping callbacks
+    let on_fetch_track_mapping = EventHandler::new({
+        let app = app.clone();
+        move |release_id: String| {
+            app.load_track_mapping(&release_id);
+        }
+    });
+    let on_save_track_mapping = EventHandler::new({
+        let app = app.clone();
+        move |mapping: TrackMapping| {
+            app.save_track_mapping(mapping);
+        }
+    });
+
+    // Watch track mapping errors from store and show toast
+    let track_mapping_error = state.track_mapping_error().read().clone();
+    use_effect(move || {
+        if let Some(ref e) = track_mapping_error {
+            error_toast.set(Some(e.clone()));
+            spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                error_toast.set(None);
+            });
+        }
+    });
+
+    // Tag editor callback
+    let on_save_tags = EventHandler::new({
+        let app = app.clone();
+        move |edit: AlbumTagEdit| {
+            app.save_album_tags(edit);
+        }
+    });
+
+    let on_save_notes = EventHandler::new({
+        let app = app.clone();
+        move |edit: AlbumNotesEdit| {
+            app.save_album_notes(edit);
+        }
+    });
+
+    let on_add_comment = EventHandler::new({
+        let app = app.clone();
+        move |content: String| {
+            app.add_album_comment(&album_id(), &content);
+        }
+    });
+    let on_remove_comment = EventHandler::new({
+        let app = app.clone();
+        move |comment_id: String| {
+            app.remove_album_comment(&album_id(), &comment_id);
+        }
+    });
+
+    let on_fetch_availability = EventHandler::new({
+        let app = app.clone();
+        move |release_id: String| {
+            app.load_release_availability(&release_id);
+        }
+    });
+
+    // MusicBrainz refresh callbacks
+    let on_fetch_musicbrainz_changes = EventHandler::new({
+        let app = app.clone();
+        move |release_id: String| {
+            app.load_musicbrainz_changes(&release_id);
+        }
+    });
+    let on_apply_musicbrainz_changes = EventHandler::new({
+        let app = app.clone();
+        move |updates: Vec<MetadataUpdate>| {
+            let release_id = state
+                .selected_release_id()
+                .read()
+                .clone()
+                .unwrap_or_default();
+            app.apply_musicbrainz_changes(&release_id, updates);
+        }
+    });
+
+    // Watch tag save errors from store and show toast
+    let tags_error = state.tags_error().read().clone();
+    use_effect(move || {
+        if let Some(ref e) = tags_error {
+            error_toast.set(Some(e.clone()));
+            spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                error_toast.set(None);
+            });
+        }
+    });
+
+    // Watch comment errors from store and show toast
+    let comment_error = state.comment_error().read().clone();
+    use_effect(move || {
+        if let Some(ref e) = comment_error {
+            error_toast.set(Some(e.clone()));
+            spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                error_toast.set(None);
+            });
+        }
+    });
+
     // Check if viewing a followed library (read-only mode)
     let active_source = app.state.library().active_source().read().clone();
     let is_followed = matches!(active_source, LibrarySource::Followed(_));
+    let cloud_home_configured = *app.state.sync().cloud_home_configured().read();
 
     // Release select callback - navigate to new URL which triggers data reload
     let on_release_select = {
@@ -326,10 +457,41 @@ pub fn AlbumDetail(album_id: ReadSignal<String>, release_id: ReadSignal<String>)
         }
     };
 
+    // Version picker callbacks - navigate to the picked album's page
+    let on_version_select = move |version_album_id: String| {
+        navigator().push(Route::AlbumDetail {
+            album_id: version_album_id,
+            release_id: String::new(),
+        });
+    };
+    let on_link_version = EventHandler::new({
+        let app = app.clone();
+        move |other_album_id: String| {
+            app.link_album_version(&album_id(), &other_album_id);
+        }
+    });
+    let on_unlink_version = EventHandler::new({
+        let app = app.clone();
+        move |version_album_id: String| {
+            app.unlink_album_version(&version_album_id);
+        }
+    });
+    let on_set_version_label = EventHandler::new({
+        let app = app.clone();
+        move |(version_album_id, label): (String, Option<String>)| {
+            app.set_album_version_label(&version_album_id, label);
+        }
+    });
+
     // Use lenses for routing decisions
     let loading = *state.loading().read();
     let error = state.error().read().clone();
     let has_album = state.album().read().is_some();
+    let cover_upgrade = state
+        .selected_release_id()
+        .read()
+        .as_ref()
+        .and_then(|id| app.state.library().cover_upgrades().read().get(id).cloned());
 
     rsx! {
         BackButton {}
@@ -343,7 +505,12 @@ pub fn AlbumDetail(album_id: ReadSignal<String>, release_id: ReadSignal<String>)
                 tracks,
                 playback: playback_display(),
                 read_only: is_followed,
+                cloud_home_configured,
                 on_release_select,
+                on_version_select,
+                on_link_version,
+                on_unlink_version,
+                on_set_version_label,
                 on_album_deleted,
                 on_export_release,
                 on_delete_album,
@@ -353,15 +520,30 @@ pub fn AlbumDetail(album_id: ReadSignal<String>, release_id: ReadSignal<String>)
                 on_track_resume,
                 on_track_add_next,
                 on_track_add_to_queue,
+                on_track_add_to_playlist,
                 on_track_export,
                 on_artist_click,
                 on_play_album,
                 on_add_album_to_queue,
                 on_transfer_to_managed,
+                on_move_to_cloud,
                 on_eject,
+                on_cancel_transfer,
+                on_keep_downloaded,
+                on_fetch_availability,
                 on_fetch_remote_covers,
                 on_select_cover,
+                on_fetch_track_mapping,
+                on_save_track_mapping,
+                on_save_tags,
+                on_save_notes,
+                on_add_comment,
+                on_remove_comment,
+                on_fetch_musicbrainz_changes,
+                on_apply_musicbrainz_changes,
                 on_copy_share_link,
+                cover_upgrade,
+                playlists: app.state.playlists().playlists().read().clone(),
             }
 
             if let Some(ref msg) = success_toast() {
@@ -2,12 +2,16 @@
 //!
 //! Wraps the shared AppLayoutView with desktop-specific components.
 
+use super::lyrics_panel::LyricsPanel;
 use super::now_playing_bar::NowPlayingBar;
+use super::playback_diagnostics::PlaybackDiagnosticsPanel;
 use super::queue_sidebar::QueueSidebar;
 use super::TitleBar;
+use crate::ui::app_service::use_app;
 use crate::ui::shortcuts::ShortcutsHandler;
 use crate::ui::Route;
-use bae_ui::AppLayoutView;
+use bae_ui::stores::{AppStateStoreExt, LibraryStateStoreExt};
+use bae_ui::{AppLayoutView, ErrorToast};
 use dioxus::prelude::*;
 
 /// Layout component that includes title bar, content, playback bar, and sidebar
@@ -33,8 +37,42 @@ pub fn AppLayout() -> Element {
                 queue_sidebar: rsx! {
                     QueueSidebar {}
                 },
+                extra: rsx! {
+                    LyricsPanel {}
+                    PlaybackDiagnosticsPanel {}
+                    StorageScrubAlert {}
+                },
                 Outlet::<Route> {}
             }
         }
     }
 }
+
+/// Shown when the background storage scrub finds broken files, until it's
+/// dismissed or the integrity settings are opened
+#[component]
+fn StorageScrubAlert() -> Element {
+    let app = use_app();
+    let library = app.state.library();
+    let mut scrub_alert = library.scrub_alert();
+    if !scrub_alert() {
+        return rsx! {};
+    }
+
+    let album_count = library.scrub_broken_albums().read().len();
+    let message = if album_count == 1 {
+        "1 album has files that failed the background check. See Settings > Integrity.".to_string()
+    } else {
+        format!(
+            "{album_count} albums have files that failed the background check. See Settings > Integrity."
+        )
+    };
+
+    rsx! {
+        ErrorToast {
+            title: Some("Damaged files found".to_string()),
+            message,
+            on_dismiss: move |_| scrub_alert.set(false),
+        }
+    }
+}
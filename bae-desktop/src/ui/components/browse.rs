@@ -0,0 +1,134 @@
+//! Browse page components - the library by genre and by decade
+
+use crate::ui::app_service::use_app;
+use crate::ui::components::album_detail::utils::get_album_track_ids;
+use crate::ui::Route;
+use bae_ui::display_types::LibraryFilter;
+use bae_ui::stores::AppStateStoreExt;
+use bae_ui::{decade_label, BrowseAlbumsView, BrowseView};
+use dioxus::prelude::*;
+
+use super::album_detail::back_button::BackButton;
+
+/// Browse index - lists the library's genres and decades
+#[component]
+pub fn Browse() -> Element {
+    let app = use_app();
+
+    use_effect({
+        let app = app.clone();
+        move || app.load_browse_index()
+    });
+
+    rsx! {
+        BrowseView {
+            state: app.state.browse(),
+            on_genre_click: move |genre: String| {
+                navigator().push(Route::GenreAlbums { genre });
+            },
+            on_decade_click: move |decade: i32| {
+                navigator().push(Route::DecadeAlbums { decade });
+            },
+        }
+    }
+}
+
+/// Albums tagged with one genre
+#[component]
+pub fn GenreAlbums(genre: ReadSignal<String>) -> Element {
+    let app = use_app();
+
+    use_effect({
+        let app = app.clone();
+        move || {
+            app.load_browse_albums(LibraryFilter {
+                genre: Some(genre()),
+                ..Default::default()
+            });
+        }
+    });
+
+    rsx! {
+        BrowseAlbums { title: genre() }
+    }
+}
+
+/// Albums released in one decade
+#[component]
+pub fn DecadeAlbums(decade: ReadSignal<i32>) -> Element {
+    let app = use_app();
+
+    use_effect({
+        let app = app.clone();
+        move || {
+            app.load_browse_albums(LibraryFilter {
+                decade: Some(decade()),
+                ..Default::default()
+            });
+        }
+    });
+
+    rsx! {
+        BrowseAlbums { title: decade_label(decade()) }
+    }
+}
+
+/// Album grid shared by the genre and decade pages, wired to navigation and
+/// playback
+#[component]
+fn BrowseAlbums(title: String) -> Element {
+    let app = use_app();
+    let library_manager = app.library_manager.clone();
+    let playback = app.playback_handle.clone();
+
+    let on_album_click = move |album_id: String| {
+        navigator().push(Route::AlbumDetail {
+            album_id,
+            release_id: String::new(),
+        });
+    };
+
+    let on_artist_click = move |artist_id: String| {
+        navigator().push(Route::ArtistDetail { artist_id });
+    };
+
+    let on_play_album = {
+        let library_manager = library_manager.clone();
+        let playback = playback.clone();
+        move |album_id: String| {
+            let library_manager = library_manager.clone();
+            let playback = playback.clone();
+            spawn(async move {
+                if let Ok(track_ids) = get_album_track_ids(&library_manager, &album_id).await {
+                    playback.play_album(track_ids);
+                }
+            });
+        }
+    };
+
+    let on_add_album_to_queue = {
+        let library_manager = library_manager.clone();
+        let playback = playback.clone();
+        move |album_id: String| {
+            let library_manager = library_manager.clone();
+            let playback = playback.clone();
+            spawn(async move {
+                if let Ok(track_ids) = get_album_track_ids(&library_manager, &album_id).await {
+                    playback.add_to_queue(track_ids);
+                }
+            });
+        }
+    };
+
+    rsx! {
+        BackButton {}
+        BrowseAlbumsView {
+            state: app.state.browse(),
+            title,
+            on_album_click,
+            on_artist_click,
+            on_play_album,
+            on_add_album_to_queue,
+        }
+    }
+}
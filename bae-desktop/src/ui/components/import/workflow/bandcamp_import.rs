@@ -0,0 +1,254 @@
+//! Bandcamp import wrapper - signs in, lists the collection, and downloads
+//! purchases for import, delegating UI to BandcampCollectionView
+
+use crate::ui::app_service::{use_app, AppService};
+use bae_core::import::bandcamp::{BandcampAlbum, BandcampClient};
+use bae_core::import::{ImportProgress, ImportRequest};
+use bae_ui::display_types::{BandcampAlbumItem, BandcampImportStatus};
+use bae_ui::stores::import::ImportStateStoreExt;
+use bae_ui::stores::AppStateStoreExt;
+use bae_ui::BandcampCollectionView;
+use dioxus::prelude::*;
+use std::path::Path;
+use tracing::{info, warn};
+
+#[component]
+pub fn BandcampImport() -> Element {
+    let app = use_app();
+
+    // Session cookie and full purchase details (tracklists, download links)
+    // for the listed albums. Reloaded on mount, so local to this component.
+    let identity = use_signal(|| Option::<String>::None);
+    let albums = use_signal(Vec::<BandcampAlbum>::new);
+
+    // Load the collection on mount if a session is stored
+    use_effect({
+        let app = app.clone();
+        move || {
+            if let Some(stored) = app.key_service.get_bandcamp_identity() {
+                spawn(load_collection(
+                    app.clone(),
+                    stored,
+                    false,
+                    identity,
+                    albums,
+                ));
+            }
+        }
+    });
+
+    let state = app.state.import().bandcamp().read().clone();
+
+    let on_sign_in = {
+        let app = app.clone();
+        move |cookie: String| {
+            spawn(load_collection(app.clone(), cookie, true, identity, albums));
+        }
+    };
+
+    let on_sign_out = {
+        let app = app.clone();
+        let mut identity = identity;
+        let mut albums = albums;
+        move |_| {
+            if let Err(e) = app.key_service.delete_bandcamp_identity() {
+                warn!("Failed to delete Bandcamp session: {}", e);
+            }
+            identity.set(None);
+            albums.set(Vec::new());
+            app.state.import().write().bandcamp = Default::default();
+        }
+    };
+
+    let on_refresh = {
+        let app = app.clone();
+        move |_| {
+            if let Some(cookie) = identity() {
+                spawn(load_collection(
+                    app.clone(),
+                    cookie,
+                    false,
+                    identity,
+                    albums,
+                ));
+            }
+        }
+    };
+
+    let on_import = {
+        let app = app.clone();
+        move |item_id: String| {
+            let Some(cookie) = identity() else {
+                return;
+            };
+            let Some(album) = albums.read().iter().find(|a| a.item_id == item_id).cloned() else {
+                return;
+            };
+            spawn(import_album(app.clone(), cookie, album));
+        }
+    };
+
+    rsx! {
+        BandcampCollectionView {
+            state,
+            on_sign_in,
+            on_sign_out,
+            on_refresh,
+            on_import,
+        }
+    }
+}
+
+/// Fetch the fan's collection with a session cookie.
+///
+/// With `save`, the cookie is stored in the keyring once Bandcamp accepts it.
+/// Import statuses of albums already listed are kept across reloads.
+async fn load_collection(
+    app: AppService,
+    cookie: String,
+    save: bool,
+    mut identity: Signal<Option<String>>,
+    mut albums: Signal<Vec<BandcampAlbum>>,
+) {
+    let mut import_store = app.state.import();
+    {
+        let mut state = import_store.write();
+        state.bandcamp.is_loading = true;
+        state.bandcamp.error = None;
+    }
+
+    let client = BandcampClient::new(cookie.clone());
+    let result = match client.fan_id().await {
+        Ok(fan_id) => client.collection(fan_id).await,
+        Err(e) => Err(e),
+    };
+
+    let collection = match result {
+        Ok(collection) => collection,
+        Err(e) => {
+            warn!("Failed to load Bandcamp collection: {}", e);
+
+            let mut state = import_store.write();
+            state.bandcamp.is_loading = false;
+            state.bandcamp.error = Some(e.to_string());
+            return;
+        }
+    };
+
+    if save {
+        if let Err(e) = app.key_service.set_bandcamp_identity(&cookie) {
+            warn!("Failed to save Bandcamp session: {}", e);
+        }
+    }
+
+    let mut state = import_store.write();
+    let items = collection
+        .iter()
+        .map(|album| BandcampAlbumItem {
+            item_id: album.item_id.clone(),
+            artist: album.artist.clone(),
+            title: album.title.clone(),
+            track_count: album.tracks.len(),
+            cover_url: album.art_url(),
+            status: state
+                .bandcamp
+                .albums
+                .iter()
+                .find(|existing| existing.item_id == album.item_id)
+                .map(|existing| existing.status.clone())
+                .unwrap_or_default(),
+        })
+        .collect();
+    state.bandcamp.albums = items;
+    state.bandcamp.signed_in = true;
+    state.bandcamp.is_loading = false;
+    identity.set(Some(cookie));
+    albums.set(collection);
+}
+
+/// Download a purchase, import it into managed storage, and remove the
+/// download once the import finishes either way
+async fn import_album(app: AppService, cookie: String, album: BandcampAlbum) {
+    let mut import_store = app.state.import();
+    let item_id = album.item_id.clone();
+    import_store
+        .write()
+        .bandcamp
+        .set_status(&item_id, BandcampImportStatus::Downloading);
+
+    let workspace = std::env::temp_dir().join(format!("bae_bandcamp_{}", uuid::Uuid::new_v4()));
+    let folder = match BandcampClient::new(cookie)
+        .download_flac(&album, &workspace)
+        .await
+    {
+        Ok(folder) => folder,
+        Err(e) => {
+            warn!("Failed to download '{}' from Bandcamp: {}", album.title, e);
+
+            remove_workspace(&workspace).await;
+            import_store
+                .write()
+                .bandcamp
+                .set_status(&item_id, BandcampImportStatus::Failed(e.to_string()));
+            return;
+        }
+    };
+
+    import_store
+        .write()
+        .bandcamp
+        .set_status(&item_id, BandcampImportStatus::Importing);
+
+    let import_id = uuid::Uuid::new_v4().to_string();
+    // Subscribe before sending so the completion event can't be missed
+    let mut progress_rx = app
+        .import_handle
+        .progress_handle
+        .subscribe_import(import_id.clone());
+    let request = ImportRequest::Bandcamp {
+        import_id,
+        album,
+        folder,
+        managed: true,
+    };
+
+    let status = match app.import_handle.send_request(request).await {
+        Ok(_) => loop {
+            match progress_rx.recv().await {
+                Some(ImportProgress::Complete {
+                    release_id: None, ..
+                }) => break BandcampImportStatus::Imported,
+                Some(ImportProgress::Failed { error, .. }) => {
+                    break BandcampImportStatus::Failed(error)
+                }
+                Some(_) => {}
+                None => {
+                    break BandcampImportStatus::Failed(
+                        "Import stopped reporting progress".to_string(),
+                    )
+                }
+            }
+        },
+        Err(e) => BandcampImportStatus::Failed(e),
+    };
+
+    remove_workspace(&workspace).await;
+
+    if status == BandcampImportStatus::Imported {
+        info!("Imported Bandcamp album {}", item_id);
+    }
+
+    import_store.write().bandcamp.set_status(&item_id, status);
+}
+
+async fn remove_workspace(workspace: &Path) {
+    if let Err(e) = tokio::fs::remove_dir_all(workspace).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!(
+                "Failed to remove Bandcamp download {}: {}",
+                workspace.display(),
+                e
+            );
+        }
+    }
+}
@@ -7,8 +7,8 @@ use crate::ui::import_helpers::{
 };
 use bae_core::cd::CdDrive;
 use bae_ui::components::import::CdImportView;
-use bae_ui::display_types::{CdDriveInfo, MatchCandidate, SearchSource, SearchTab};
-use bae_ui::stores::import::CandidateEvent;
+use bae_ui::display_types::{CdDriveInfo, FolderMetadata, MatchCandidate, SearchSource, SearchTab};
+use bae_ui::stores::import::{CandidateEvent, SearchField};
 use bae_ui::stores::AppStateStoreExt;
 use bae_ui::ImportSource;
 use dioxus::prelude::*;
@@ -63,15 +63,38 @@ pub fn CdImport() -> Element {
             selected_drive.set(Some(device_path.clone()));
 
             spawn(async move {
+                // Read the TOC for the disc ID and any CD-Text on the disc
+                let drive = CdDrive {
+                    device_path: device_path_clone.clone().into(),
+                    name: device_path_clone.clone(),
+                };
+                let toc = match tokio::task::spawn_blocking(move || drive.read_toc()).await {
+                    Ok(Ok(toc)) => Some(toc),
+                    Ok(Err(e)) => {
+                        warn!("Failed to read CD TOC: {}", e);
+                        None
+                    }
+                    Err(e) => {
+                        warn!("TOC read task failed: {}", e);
+                        None
+                    }
+                };
+                let cd_text = toc.as_ref().and_then(|t| t.cd_text.clone());
+                let metadata = toc
+                    .map(|toc| FolderMetadata {
+                        artist: cd_text.as_ref().and_then(|t| t.performer.clone()),
+                        album: cd_text.as_ref().and_then(|t| t.title.clone()),
+                        track_count: Some((toc.last_track - toc.first_track + 1) as u32),
+                        mb_discid: Some(toc.disc_id),
+                        ..Default::default()
+                    })
+                    .unwrap_or_default();
+
                 // Set the CD path in state
                 let mut import_store = app.state.import();
                 {
                     let mut state = import_store.write();
-                    state.init_state_machine(
-                        &device_path_clone,
-                        Default::default(),
-                        Default::default(),
-                    );
+                    state.init_state_machine(&device_path_clone, Default::default(), metadata);
                     state.switch_candidate(Some(device_path_clone.clone()));
                 }
 
@@ -82,6 +105,9 @@ pub fn CdImport() -> Element {
                     .and_then(|m| m.mb_discid.clone());
 
                 if let Some(mb_discid) = mb_discid {
+                    import_store
+                        .write()
+                        .dispatch(CandidateEvent::StartDiscIdLookup(mb_discid.clone()));
                     match lookup_discid(&mb_discid, &app.key_service).await {
                         Ok(result) => {
                             let mut matches = match result {
@@ -90,12 +116,32 @@ pub fn CdImport() -> Element {
                                 DiscIdLookupResult::MultipleMatches(cs) => cs,
                             };
                             check_candidates_for_duplicates(&app, &mut matches).await;
+                            let no_matches = matches.is_empty();
                             import_store
                                 .write()
                                 .dispatch(CandidateEvent::DiscIdLookupComplete {
                                     matches,
                                     error: None,
                                 });
+
+                            // No disc ID match: fall back to CD-Text for the manual search
+                            if let (true, Some(cd_text)) = (no_matches, cd_text) {
+                                info!("No DiscID match, prefilling search from CD-Text");
+
+                                let mut state = import_store.write();
+                                if let Some(artist) = cd_text.performer {
+                                    state.dispatch(CandidateEvent::UpdateSearchField {
+                                        field: SearchField::Artist,
+                                        value: artist,
+                                    });
+                                }
+                                if let Some(album) = cd_text.title {
+                                    state.dispatch(CandidateEvent::UpdateSearchField {
+                                        field: SearchField::Album,
+                                        value: album,
+                                    });
+                                }
+                            }
                         }
                         Err(e) => {
                             import_store
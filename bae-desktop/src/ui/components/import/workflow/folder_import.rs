@@ -2,22 +2,29 @@
 
 use crate::ui::app_service::use_app;
 use crate::ui::import_helpers::{
-    build_caa_client, check_candidates_for_duplicates, check_cover_art, confirm_and_start_import,
-    count_local_audio_files, extract_tracks_from_discogs, extract_tracks_from_mb_response,
+    build_caa_client, categorized_files_from_scanned, check_candidates_for_duplicates,
+    check_cover_art, confirm_and_start_import, count_local_audio_files,
+    extract_tracks_from_discogs, extract_tracks_from_mb_response,
     fetch_discogs_release_for_validation, fetch_mb_release_for_validation, lookup_discid,
     search_by_barcode, search_by_catalog_number, search_general, DiscIdLookupResult,
 };
 use crate::ui::Route;
 use bae_core::discogs::DiscogsRelease;
+use bae_core::import::archive::{self, ArchiveFormat};
+use bae_core::import::folder_scanner;
+use bae_core::import::network_share::NetworkShare;
+use bae_core::import::silence_split;
 use bae_ui::components::import::FolderImportView;
 use bae_ui::display_types::{
-    CandidateTrack, MatchCandidate, MatchSourceType, SearchSource, SearchTab, SelectedCover,
+    AudioContentInfo, CandidateTrack, IdentifyMode, MatchCandidate, MatchSourceType,
+    RecordingSplit, SearchSource, SearchTab, SelectedCover,
 };
 use bae_ui::stores::import::ImportStateStoreExt;
-use bae_ui::stores::import::{CandidateEvent, PrefetchState};
-use bae_ui::stores::AppStateStoreExt;
+use bae_ui::stores::import::{CandidateEvent, ImportState, PrefetchState, RecordingSplitState};
+use bae_ui::stores::{AppStateStoreExt, ConfigStateStoreExt, SyncStateStoreExt};
 use bae_ui::ImportSource;
 use dioxus::prelude::*;
+use std::path::{Path, PathBuf};
 use tracing::{info, warn};
 
 // ============================================================================
@@ -66,6 +73,146 @@ pub fn FolderImport() -> Element {
         }
     };
 
+    // Network share: mount it, then open the folder picker inside it
+    let config_store = app.state.config();
+    let network_share_url = config_store.import_network_share_url().read().clone();
+    let network_share_username = config_store.import_network_share_username().read().clone();
+    let mut is_connecting_share = use_signal(|| false);
+    let mut network_share_error = use_signal(|| Option::<String>::None);
+
+    let on_network_share = {
+        let app = app.clone();
+        let network_share_url = network_share_url.clone();
+        move |_| {
+            let Some(url) = network_share_url.clone() else {
+                return;
+            };
+            let share = match NetworkShare::parse(&url) {
+                Ok(mut share) => {
+                    if network_share_username.is_some() {
+                        share.username = network_share_username.clone();
+                    }
+                    share
+                }
+                Err(e) => {
+                    network_share_error.set(Some(e.to_string()));
+                    return;
+                }
+            };
+            let password = app.key_service.get_network_share_password();
+            let app = app.clone();
+            is_connecting_share.set(true);
+            network_share_error.set(None);
+            spawn(async move {
+                let mounted =
+                    tokio::task::spawn_blocking(move || share.mount(password.as_deref())).await;
+                is_connecting_share.set(false);
+                let root = match mounted {
+                    Ok(Ok(root)) => root,
+                    Ok(Err(e)) => {
+                        warn!("Failed to mount network share: {}", e);
+                        network_share_error.set(Some(e.to_string()));
+                        return;
+                    }
+                    Err(e) => {
+                        network_share_error.set(Some(format!("Mount task failed: {}", e)));
+                        return;
+                    }
+                };
+
+                info!("Network share available at {:?}", root);
+
+                let Some(folder) = rfd::AsyncFileDialog::new()
+                    .set_directory(&root)
+                    .pick_folder()
+                    .await
+                else {
+                    return;
+                };
+
+                {
+                    let mut import_store = app.state.import();
+                    if import_store.read().detected_candidates.is_empty() {
+                        import_store.write().reset();
+                    }
+                    import_store.write().is_scanning_candidates = true;
+                }
+
+                if let Err(e) = app
+                    .import_handle
+                    .enqueue_folder_scan(folder.path().to_path_buf())
+                {
+                    warn!("Failed to add folder to scan: {}", e);
+                }
+            });
+        }
+    };
+
+    // Archive: extract into a cache workspace, then scan the extracted folder
+    let mut is_extracting_archive = use_signal(|| false);
+    let mut archive_error = use_signal(|| Option::<String>::None);
+
+    let on_archive_select = {
+        let app = app.clone();
+        move |_| {
+            let app = app.clone();
+            spawn(async move {
+                let Some(file) = rfd::AsyncFileDialog::new()
+                    .add_filter("Archives", ArchiveFormat::EXTENSIONS)
+                    .pick_file()
+                    .await
+                else {
+                    return;
+                };
+                let archive_path = file.path().to_path_buf();
+
+                is_extracting_archive.set(true);
+                archive_error.set(None);
+                let workspace = match app.cache.create_import_workspace().await {
+                    Ok(workspace) => workspace,
+                    Err(e) => {
+                        is_extracting_archive.set(false);
+                        archive_error
+                            .set(Some(format!("Failed to create import workspace: {}", e)));
+                        return;
+                    }
+                };
+                let workspace_for_extract = workspace.clone();
+                let extracted = tokio::task::spawn_blocking(move || {
+                    archive::extract(&archive_path, &workspace_for_extract)
+                })
+                .await
+                .map_err(|e| format!("Extraction task failed: {}", e))
+                .and_then(|result| result.map_err(|e| e.to_string()));
+                is_extracting_archive.set(false);
+                let folder = match extracted {
+                    Ok(folder) => folder,
+                    Err(e) => {
+                        warn!("Failed to extract archive: {}", e);
+
+                        archive_error.set(Some(e));
+                        if let Err(e) = app.cache.remove_import_workspace(&workspace).await {
+                            warn!("Failed to remove import workspace: {}", e);
+                        }
+                        return;
+                    }
+                };
+
+                {
+                    let mut import_store = app.state.import();
+                    if import_store.read().detected_candidates.is_empty() {
+                        import_store.write().reset();
+                    }
+                    import_store.write().is_scanning_candidates = true;
+                }
+
+                if let Err(e) = app.import_handle.enqueue_folder_scan(folder) {
+                    warn!("Failed to add folder to scan: {}", e);
+                }
+            });
+        }
+    };
+
     let on_exact_match_select = {
         let app = app.clone();
         move |index: usize| {
@@ -336,6 +483,163 @@ pub fn FolderImport() -> Element {
         }
     };
 
+    // Split a single recording (a vinyl or tape side) into the release's tracks
+    let on_split_recording = {
+        let app = app.clone();
+        move |_| {
+            let app = app.clone();
+            spawn(async move {
+                let mut import_store = app.state.import();
+                let Some((recording, _, tracks)) = recording_split_source(&import_store.read())
+                else {
+                    return;
+                };
+                import_store.write().recording_split = Some(RecordingSplitState::default());
+
+                let path = recording.clone();
+                let analysis =
+                    tokio::task::spawn_blocking(move || silence_split::analyze_recording(&path))
+                        .await
+                        .map_err(|e| format!("Analysis task failed: {}", e))
+                        .and_then(|result| result.map_err(|e| e.to_string()));
+
+                // The modal may have been closed while the recording was decoding
+                let mut state = import_store.write();
+                let Some(split_state) = state.recording_split.as_mut() else {
+                    return;
+                };
+                match analysis {
+                    Ok(analysis) => {
+                        split_state.split = Some(RecordingSplit {
+                            file_name: recording
+                                .file_name()
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or_default(),
+                            duration_ms: analysis.duration_ms,
+                            split_points_ms: analysis.split_points(tracks.len()),
+                            waveform: analysis.waveform,
+                            tracks,
+                        });
+                    }
+                    Err(e) => {
+                        warn!("Failed to analyze recording: {}", e);
+
+                        split_state.error = Some(e);
+                    }
+                }
+            });
+        }
+    };
+
+    let on_split_cancel = {
+        let app = app.clone();
+        move |_| {
+            app.state.import().write().recording_split = None;
+        }
+    };
+
+    let on_split_save = {
+        let app = app.clone();
+        move |split_points: Vec<u64>| {
+            let app = app.clone();
+            spawn(async move {
+                let mut import_store = app.state.import();
+                let (source, candidate_key, mode) = {
+                    let state = import_store.read();
+                    (
+                        recording_split_source(&state),
+                        state.current_candidate_key.clone(),
+                        state.get_identify_mode(),
+                    )
+                };
+                let (Some((recording, candidate, tracks)), Some(candidate_key)) =
+                    (source, candidate_key)
+                else {
+                    return;
+                };
+                if let Some(split_state) = import_store.write().recording_split.as_mut() {
+                    split_state.is_saving = true;
+                    split_state.error = None;
+                }
+
+                let filters = app.import_handle.import_filters();
+                let folder = PathBuf::from(&candidate_key);
+                let titles: Vec<String> = tracks.iter().map(|t| t.title.clone()).collect();
+                let result = tokio::task::spawn_blocking(move || {
+                    silence_split::write_cue_sheet(
+                        &recording,
+                        &split_points,
+                        &candidate.artist,
+                        &candidate.title,
+                        &titles,
+                    )
+                    .map_err(|e| e.to_string())?;
+                    folder_scanner::collect_release_files(&folder, &filters)
+                })
+                .await
+                .map_err(|e| format!("Split task failed: {}", e))
+                .and_then(|result| result);
+
+                let files = match result {
+                    Ok(files) => files,
+                    Err(e) => {
+                        warn!("Failed to split recording: {}", e);
+
+                        if let Some(split_state) = import_store.write().recording_split.as_mut() {
+                            split_state.is_saving = false;
+                            split_state.error = Some(e);
+                        }
+                        return;
+                    }
+                };
+
+                let files = categorized_files_from_scanned(&files, &app.image_server);
+                {
+                    let mut state = import_store.write();
+                    state.dispatch_to_candidate(
+                        &candidate_key,
+                        CandidateEvent::FilesRescanned(files),
+                    );
+                    state.recording_split = None;
+                }
+
+                // The folder now holds a CUE/FLAC pair; validate the match against it
+                match mode {
+                    IdentifyMode::MultipleExactMatches(_) => {
+                        let index = import_store.read().get_selected_match_index();
+                        if let Some(index) = index {
+                            spawn_prefetch_for_exact_match(&app, index).await;
+                        }
+                    }
+                    _ => {
+                        let index = import_store
+                            .read()
+                            .get_search_state()
+                            .and_then(|s| s.current_tab_state().selected_result_index);
+                        if let Some(index) = index {
+                            spawn_prefetch_for_search_result(
+                                &app,
+                                index,
+                                &mut cached_discogs_release,
+                            )
+                            .await;
+                        }
+                    }
+                }
+            });
+        }
+    };
+
+    let on_import_from_tags = {
+        let app = app.clone();
+        move |_| {
+            app.state
+                .import()
+                .write()
+                .dispatch(CandidateEvent::ConfirmFromTags);
+        }
+    };
+
     let on_retry_discid_lookup = {
         let app = app.clone();
         move |_| {
@@ -563,10 +867,21 @@ pub fn FolderImport() -> Element {
         }
     };
 
-    // Managed flag change
+    // Managed flag change. Extracted archives are always managed, since their
+    // workspace is removed after import.
     let on_managed_change = {
         let app = app.clone();
         move |managed: bool| {
+            let from_archive = app
+                .state
+                .import()
+                .read()
+                .current_candidate_key
+                .as_ref()
+                .is_some_and(|key| app.cache.import_workspace_of(Path::new(key)).is_some());
+            if from_archive {
+                return;
+            }
             app.state
                 .import()
                 .write()
@@ -574,6 +889,17 @@ pub fn FolderImport() -> Element {
         }
     };
 
+    let on_move_to_cloud_change = {
+        let app = app.clone();
+        move |move_to_cloud: bool| {
+            app.state
+                .import()
+                .write()
+                .dispatch(CandidateEvent::SetMoveToCloud(move_to_cloud));
+        }
+    };
+    let cloud_home_configured = *app.state.sync().cloud_home_configured().read();
+
     // View album in library
     let on_view_in_library = move |album_id: String| {
         navigator.push(Route::AlbumDetail {
@@ -632,7 +958,15 @@ pub fn FolderImport() -> Element {
             viewing_index: ReadSignal::from(viewing_index),
             text_file_content,
             text_file_encoding,
+            network_share: network_share_url,
+            is_connecting_share: *is_connecting_share.read(),
+            network_share_error: network_share_error.read().clone(),
+            is_extracting_archive: *is_extracting_archive.read(),
+            archive_error: archive_error.read().clone(),
+            cloud_home_configured,
             on_folder_select_click: on_folder_select,
+            on_archive_select_click: on_archive_select,
+            on_network_share_click: on_network_share,
             on_view_change: move |idx| {
                 encoding_override.set(None);
                 viewing_index.set(idx);
@@ -655,10 +989,15 @@ pub fn FolderImport() -> Element {
             on_search: move |_| perform_search(),
             on_cancel_search: move |_| cancel_search(),
             on_manual_confirm,
+            on_import_from_tags,
+            on_split_recording,
+            on_split_cancel,
+            on_split_save,
             on_retry_cover,
             on_retry_discid_lookup,
             on_select_cover,
             on_managed_change,
+            on_move_to_cloud_change,
             on_edit,
             on_confirm,
             on_view_in_library,
@@ -720,9 +1059,11 @@ async fn spawn_prefetch_for_search_result(
         PrefetchValidationResult::TrackCountMismatch {
             release_tracks,
             local_files,
+            tracks,
         } => PrefetchState::TrackCountMismatch {
             release_tracks,
             local_files,
+            tracks,
         },
         PrefetchValidationResult::FetchFailed(err) => PrefetchState::FetchFailed(err),
     };
@@ -775,9 +1116,11 @@ async fn spawn_prefetch_for_exact_match(app: &AppService, index: usize) {
         PrefetchValidationResult::TrackCountMismatch {
             release_tracks,
             local_files,
+            tracks,
         } => PrefetchState::TrackCountMismatch {
             release_tracks,
             local_files,
+            tracks,
         },
         PrefetchValidationResult::FetchFailed(err) => PrefetchState::FetchFailed(err),
     };
@@ -797,6 +1140,7 @@ enum PrefetchValidationResult {
     TrackCountMismatch {
         release_tracks: usize,
         local_files: usize,
+        tracks: Vec<CandidateTrack>,
     },
     FetchFailed(String),
 }
@@ -823,13 +1167,14 @@ async fn prefetch_and_validate(
             .await
             {
                 Ok((release, track_count)) => {
+                    let tracks = extract_tracks_from_discogs(&release);
                     if track_count == local_file_count {
-                        let tracks = extract_tracks_from_discogs(&release);
                         PrefetchValidationResult::DiscogsValid(Box::new(release), tracks)
                     } else {
                         PrefetchValidationResult::TrackCountMismatch {
                             release_tracks: track_count,
                             local_files: local_file_count,
+                            tracks,
                         }
                     }
                 }
@@ -845,18 +1190,53 @@ async fn prefetch_and_validate(
 
             match fetch_mb_release_for_validation(release_id).await {
                 Ok((response, track_count)) => {
+                    let tracks = extract_tracks_from_mb_response(&response);
                     if track_count == local_file_count {
-                        let tracks = extract_tracks_from_mb_response(&response);
                         PrefetchValidationResult::Valid(tracks)
                     } else {
                         PrefetchValidationResult::TrackCountMismatch {
                             release_tracks: track_count,
                             local_files: local_file_count,
+                            tracks,
                         }
                     }
                 }
                 Err(e) => PrefetchValidationResult::FetchFailed(e),
             }
         }
+        MatchSourceType::Tags => PrefetchValidationResult::FetchFailed(
+            "Tag imports have no release to fetch".to_string(),
+        ),
+    }
+}
+
+/// The folder's single recording, the selected match, and the match's tracks,
+/// when the match has more tracks than the folder has files
+fn recording_split_source(
+    state: &ImportState,
+) -> Option<(PathBuf, MatchCandidate, Vec<CandidateTrack>)> {
+    let recording = match &state.current_candidate_state()?.files().audio {
+        AudioContentInfo::TrackFiles(files) if files.len() == 1 => PathBuf::from(&files[0].path),
+        _ => return None,
+    };
+    let (candidate, prefetch) = match state.get_identify_mode() {
+        IdentifyMode::MultipleExactMatches(_) => {
+            let index = state.get_selected_match_index()?;
+            let candidate = state.get_exact_match_candidates().get(index).cloned()?;
+            (candidate, state.get_exact_match_prefetch_state()?)
+        }
+        _ => {
+            let search = state.get_search_state()?;
+            let tab = search.current_tab_state();
+            let candidate = tab
+                .search_results
+                .get(tab.selected_result_index?)
+                .cloned()?;
+            (candidate, tab.prefetch_state.clone()?)
+        }
+    };
+    match prefetch {
+        PrefetchState::TrackCountMismatch { tracks, .. } => Some((recording, candidate, tracks)),
+        _ => None,
     }
 }
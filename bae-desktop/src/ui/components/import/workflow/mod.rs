@@ -1,3 +1,4 @@
+mod bandcamp_import;
 #[cfg(feature = "cd-rip")]
 mod cd_import;
 mod folder_import;
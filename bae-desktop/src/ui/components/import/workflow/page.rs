@@ -1,3 +1,4 @@
+use super::bandcamp_import::BandcampImport;
 #[cfg(feature = "cd-rip")]
 use super::cd_import::CdImport;
 use super::folder_import::FolderImport;
@@ -156,6 +157,9 @@ pub fn ImportPage() -> Element {
                 ImportSource::Cd => rsx! {
                     CdImport {}
                 },
+                ImportSource::Bandcamp => rsx! {
+                    BandcampImport {}
+                },
                 #[cfg(not(all(feature = "torrent", feature = "cd-rip")))]
                 _ => rsx! {
                     div { class: "p-4 text-red-500", "This import source is not available" }
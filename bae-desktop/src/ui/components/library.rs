@@ -20,6 +20,11 @@ pub fn LibraryPage() -> Element {
     let library_manager = app.library_manager.clone();
     let playback = app.playback_handle.clone();
 
+    use_effect({
+        let app = app.clone();
+        move || app.load_recent_albums()
+    });
+
     // Pass the state lens directly - don't read here!
     let state = app.state.library();
     let sort_state = app.state.ui().library_sort();
@@ -29,6 +34,17 @@ pub fn LibraryPage() -> Element {
         let app_service = app.clone();
         move |criteria| {
             sort_state.sort_criteria().set(criteria);
+            app_service.save_library_sort();
+            app_service.reload_library_albums();
+        }
+    };
+
+    let on_filter_change = {
+        let sort_state = app.state.ui().library_sort();
+        let app_service = app.clone();
+        move |filter| {
+            sort_state.filter().set(filter);
+            app_service.save_library_sort();
             app_service.reload_library_albums();
         }
     };
@@ -83,6 +99,27 @@ pub fn LibraryPage() -> Element {
         }
     };
 
+    let on_toggle_collection = {
+        let app_service = app.clone();
+        move |(album_id, collection_id): (String, String)| {
+            app_service.toggle_album_collection(&album_id, &collection_id);
+        }
+    };
+
+    let on_create_collection = {
+        let app_service = app.clone();
+        move |(name, album_id): (String, String)| {
+            app_service.create_collection(&name, &album_id);
+        }
+    };
+
+    let on_delete_collection = {
+        let app_service = app.clone();
+        move |collection_id: String| {
+            app_service.delete_collection(&collection_id);
+        }
+    };
+
     // Empty state action - navigate to import workflow
     let on_empty_action = move |_| {
         navigator().push(Route::ImportWorkflowManager {});
@@ -103,10 +140,14 @@ pub fn LibraryPage() -> Element {
             sort_state,
             on_sort_criteria_change,
             on_view_mode_change,
+            on_filter_change,
             on_album_click,
             on_artist_click,
             on_play_album,
             on_add_album_to_queue,
+            on_toggle_collection,
+            on_create_collection,
+            on_delete_collection,
             on_empty_action,
             read_only: is_followed,
             header_badge,
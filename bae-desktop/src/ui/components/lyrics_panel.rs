@@ -0,0 +1,89 @@
+//! Lyrics panel
+//!
+//! Wrapper that loads lyrics for the playing track while the panel is open
+//! and passes them to LyricsPanelView.
+
+use crate::ui::app_service::use_app;
+use bae_core::lyrics::Lyrics;
+use bae_ui::stores::{AppStateStoreExt, PlaybackUiStateStoreExt, UiStateStoreExt};
+use bae_ui::{LyricLine, LyricsPanelView, TrackLyrics};
+use dioxus::prelude::*;
+use tracing::warn;
+
+/// Shows the panel when toggled on from the now playing bar
+#[component]
+pub fn LyricsPanel() -> Element {
+    let app = use_app();
+    let is_open = app.state.ui().lyrics_open();
+
+    rsx! {
+        if is_open() {
+            LyricsLoader {}
+        }
+    }
+}
+
+/// Mounted only while the panel is open, so closed panels don't fetch
+#[component]
+fn LyricsLoader() -> Element {
+    let app = use_app();
+    let mut is_open = app.state.ui().lyrics_open();
+    let playback_store = app.state.playback();
+    let current_track_id = playback_store.current_track_id();
+    let mut lyrics = use_signal(|| TrackLyrics::NotFound);
+
+    // Reload whenever a new track starts
+    use_effect(move || {
+        let Some(track_id) = current_track_id.read().clone() else {
+            lyrics.set(TrackLyrics::NotFound);
+            return;
+        };
+        lyrics.set(TrackLyrics::Loading);
+        let library_manager = app.library_manager.clone();
+        spawn(async move {
+            let loaded = match library_manager.get().fetch_lyrics(&track_id).await {
+                Ok(found) => to_display(found),
+                Err(e) => {
+                    warn!("Failed to load lyrics for track {}: {}", track_id, e);
+
+                    TrackLyrics::Failed(e.to_string())
+                }
+            };
+            // The track may have changed while this was loading
+            if current_track_id.peek().as_deref() == Some(track_id.as_str()) {
+                lyrics.set(loaded);
+            }
+        });
+    });
+
+    rsx! {
+        LyricsPanelView {
+            state: playback_store,
+            lyrics: lyrics(),
+            on_close: move |_| is_open.set(false),
+        }
+    }
+}
+
+fn to_display(lyrics: Lyrics) -> TrackLyrics {
+    let lines = lyrics.synced_lines();
+    if !lines.is_empty() {
+        return TrackLyrics::Synced(
+            lines
+                .into_iter()
+                .map(|l| LyricLine {
+                    time_ms: l.time_ms,
+                    text: l.text,
+                })
+                .collect(),
+        );
+    }
+    if let Some(plain) = lyrics.plain_text() {
+        return TrackLyrics::Plain(plain);
+    }
+    if lyrics.instrumental {
+        TrackLyrics::Instrumental
+    } else {
+        TrackLyrics::NotFound
+    }
+}
@@ -6,9 +6,13 @@ pub mod album_detail;
 pub mod app;
 pub mod app_layout;
 pub mod artist_detail;
+pub mod browse;
 pub mod import;
 pub mod library;
+pub mod lyrics_panel;
 pub mod now_playing_bar;
+pub mod playback_diagnostics;
+pub mod playlists;
 pub mod queue_sidebar;
 pub mod settings;
 pub mod unlock;
@@ -18,6 +22,8 @@ pub use album_detail::AlbumDetail;
 pub use app::App;
 pub use app_layout::AppLayout;
 pub use artist_detail::ArtistDetail;
+pub use browse::{Browse, DecadeAlbums, GenreAlbums};
 pub use library::Library;
+pub use playlists::Playlists;
 pub use settings::Settings;
 pub use title_bar::TitleBar;
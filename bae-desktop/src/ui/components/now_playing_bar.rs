@@ -21,7 +21,9 @@ pub fn NowPlayingBar() -> Element {
     // Get stores - view will read via lenses
     let playback_store = app.state.playback();
     let mut playback_error_store = playback_store.playback_error();
+    let mut warming_up_store = playback_store.warming_up();
     let mut sidebar_is_open = app.state.ui().sidebar().is_open();
+    let mut lyrics_open = app.state.ui().lyrics_open();
 
     // For navigation callback, we still need to read current_release_id
     let current_release_id_store = playback_store.current_release_id();
@@ -62,6 +64,7 @@ pub fn NowPlayingBar() -> Element {
     rsx! {
         NowPlayingBarView {
             state: playback_store,
+            lyrics_available: true,
             on_previous: move |_| playback_for_prev.previous(),
             on_pause: move |_| playback_for_pause.pause(),
             on_resume: move |_| playback_for_resume.resume(),
@@ -92,11 +95,18 @@ pub fn NowPlayingBar() -> Element {
                 let current = *sidebar_is_open.read();
                 sidebar_is_open.set(!current);
             },
+            on_toggle_lyrics: move |_| {
+                let current = *lyrics_open.read();
+                lyrics_open.set(!current);
+            },
             on_track_click,
             on_artist_click: move |artist_id: String| {
                 navigator().push(Route::ArtistDetail { artist_id });
             },
-            on_dismiss_error: Some(EventHandler::new(move |_| playback_error_store.set(None))),
+            on_dismiss_error: Some(EventHandler::new(move |_| {
+                playback_error_store.set(None);
+                warming_up_store.set(None);
+            })),
         }
     }
 }
@@ -0,0 +1,74 @@
+//! Playback diagnostics overlay
+//!
+//! Wrapper that polls the playback service's quality counters while the
+//! overlay is open and passes them to PlaybackDiagnosticsOverlay.
+
+use crate::ui::app_service::use_app;
+use bae_core::playback::PlaybackMetricsSnapshot;
+use bae_ui::stores::{AppStateStoreExt, UiStateStoreExt};
+use bae_ui::{PlaybackDiagnostics, PlaybackDiagnosticsOverlay};
+use dioxus::prelude::*;
+use tracing::{error, info};
+
+/// How often the counters are re-read
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Shows the overlay when toggled on from the View menu or shortcut
+#[component]
+pub fn PlaybackDiagnosticsPanel() -> Element {
+    let app = use_app();
+    let is_open = app.state.ui().diagnostics_open();
+
+    rsx! {
+        if is_open() {
+            DiagnosticsPoller {}
+        }
+    }
+}
+
+/// Mounted only while the overlay is open, so polling stops when it closes
+#[component]
+fn DiagnosticsPoller() -> Element {
+    let app = use_app();
+    let mut is_open = app.state.ui().diagnostics_open();
+    let mut diagnostics = use_signal(|| to_display(&app.playback_handle.metrics()));
+
+    use_hook(|| {
+        let playback_handle = app.playback_handle.clone();
+        spawn(async move {
+            loop {
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+                diagnostics.set(to_display(&playback_handle.metrics()));
+            }
+        });
+    });
+
+    rsx! {
+        PlaybackDiagnosticsOverlay {
+            diagnostics: diagnostics(),
+            on_copy: move |report: String| {
+                match arboard::Clipboard::new().and_then(|mut cb| cb.set_text(&report)) {
+                    Ok(()) => info!("Copied playback diagnostics to clipboard"),
+                    Err(e) => error!("Failed to copy playback diagnostics to clipboard: {e}"),
+                }
+            },
+            on_close: move |_| is_open.set(false),
+        }
+    }
+}
+
+fn to_display(snapshot: &PlaybackMetricsSnapshot) -> PlaybackDiagnostics {
+    let ms = |d: std::time::Duration| d.as_millis() as u64;
+    PlaybackDiagnostics {
+        session_secs: snapshot.session_duration.as_secs(),
+        tracks_started: snapshot.tracks_started,
+        underruns: snapshot.underruns,
+        buffering_waits: snapshot.buffering_waits,
+        buffering_timeouts: snapshot.buffering_timeouts,
+        buffering_ms: ms(snapshot.total_buffering),
+        decode_errors: snapshot.decode_errors,
+        last_first_audio_ms: snapshot.last_time_to_first_audio.map(ms),
+        avg_first_audio_ms: snapshot.avg_time_to_first_audio.map(ms),
+        max_first_audio_ms: snapshot.max_time_to_first_audio.map(ms),
+    }
+}
@@ -0,0 +1,87 @@
+//! Playlists page component
+
+use crate::ui::app_service::use_app;
+use crate::ui::Route;
+use bae_ui::stores::config::LibrarySource;
+use bae_ui::stores::{AppStateStoreExt, LibraryStateStoreExt, PlaylistsStateStoreExt};
+use bae_ui::PlaylistsView;
+use dioxus::prelude::*;
+
+/// Playlists page - wires the playlists view to the app service and playback
+#[component]
+pub fn Playlists() -> Element {
+    let app = use_app();
+
+    use_effect({
+        let app = app.clone();
+        move || app.load_playlists()
+    });
+
+    let playback = app.playback_handle.clone();
+    let state = app.state.playlists();
+
+    // Followed libraries are read-only
+    let is_followed = matches!(
+        *app.state.library().active_source().read(),
+        LibrarySource::Followed(_)
+    );
+
+    let on_play = move |index: usize| {
+        let track_ids: Vec<String> = state
+            .entries()
+            .read()
+            .iter()
+            .skip(index)
+            .map(|e| e.track.id.clone())
+            .collect();
+        if !track_ids.is_empty() {
+            playback.play_album(track_ids);
+        }
+    };
+
+    rsx! {
+        PlaylistsView {
+            state,
+            editable: !is_followed,
+            on_select: {
+                let app = app.clone();
+                move |id: String| app.select_playlist(&id)
+            },
+            on_create: {
+                let app = app.clone();
+                move |name: String| app.create_playlist(&name)
+            },
+            on_rename: {
+                let app = app.clone();
+                move |(id, name): (String, String)| app.rename_playlist(&id, &name)
+            },
+            on_delete: {
+                let app = app.clone();
+                move |id: String| app.delete_playlist(&id)
+            },
+            on_import_m3u: {
+                let app = app.clone();
+                move |_| app.import_m3u_playlist()
+            },
+            on_export_m3u: {
+                let app = app.clone();
+                move |id: String| app.export_m3u_playlist(&id)
+            },
+            on_play,
+            on_remove_entry: {
+                let app = app.clone();
+                move |index: usize| app.remove_playlist_entry(index)
+            },
+            on_move_entry: {
+                let app = app.clone();
+                move |(from, to): (usize, usize)| app.move_playlist_entry(from, to)
+            },
+            on_album_click: move |album_id: String| {
+                navigator().push(Route::AlbumDetail {
+                    album_id,
+                    release_id: String::new(),
+                });
+            },
+        }
+    }
+}
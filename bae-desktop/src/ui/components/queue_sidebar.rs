@@ -5,7 +5,9 @@
 
 use crate::ui::app_service::use_app;
 use crate::ui::Route;
-use bae_ui::stores::{AppStateStoreExt, SidebarStateStoreExt, UiStateStoreExt};
+use bae_ui::stores::{
+    AppStateStoreExt, PlaybackUiStateStoreExt, SidebarStateStoreExt, UiStateStoreExt,
+};
 use bae_ui::QueueSidebarView;
 use dioxus::prelude::*;
 
@@ -15,10 +17,19 @@ pub fn QueueSidebar() -> Element {
     let app = use_app();
     let sidebar_store = app.state.ui().sidebar();
     let mut is_open = sidebar_store.is_open();
+    let mut show_history = sidebar_store.show_history();
     let library_manager = app.library_manager.clone();
     let playback_handle = app.playback_handle.clone();
     let playback_store = app.state.playback();
 
+    // Refresh the history while it's visible, including when a new track starts
+    use_effect(move || {
+        let _ = playback_store.current_track_id().read();
+        if *is_open.read() && *show_history.read() {
+            app.load_play_history();
+        }
+    });
+
     // Navigation callback
     let on_track_click = {
         let library_manager = library_manager.clone();
@@ -44,11 +55,16 @@ pub fn QueueSidebar() -> Element {
     let playback_for_skip = playback_handle.clone();
     let playback_for_pause = playback_handle.clone();
     let playback_for_resume = playback_handle.clone();
+    let playback_for_radio = playback_handle.clone();
+    let playback_for_history_play = playback_handle.clone();
+    let playback_for_history_queue = playback_handle.clone();
 
     rsx! {
         QueueSidebarView {
             sidebar: sidebar_store,
             playback: playback_store,
+            radio_available: true,
+            history_available: true,
             on_close: move |_| is_open.set(false),
             on_clear: move |_| playback_for_clear.clear_queue(),
             on_remove: move |idx: usize| playback_for_remove.remove_from_queue(idx),
@@ -56,6 +72,12 @@ pub fn QueueSidebar() -> Element {
             on_play_index: move |idx: usize| playback_for_skip.skip_to(idx),
             on_pause: move |_| playback_for_pause.pause(),
             on_resume: move |_| playback_for_resume.resume(),
+            on_radio_change: move |enabled: bool| playback_for_radio.set_radio_enabled(enabled),
+            on_show_history: move |show: bool| show_history.set(show),
+            on_history_play: move |track_id: String| playback_for_history_play.play(track_id),
+            on_history_add_to_queue: move |track_id: String| {
+                playback_for_history_queue.add_to_queue(vec![track_id])
+            },
         }
     }
 }
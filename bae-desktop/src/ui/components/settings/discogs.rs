@@ -48,6 +48,7 @@ pub fn DiscogsSection() -> Element {
                     }
 
                     app.save_config(|c| c.discogs_key_stored = true);
+                    app.fetch_missing_artist_images();
                 }
             }
 
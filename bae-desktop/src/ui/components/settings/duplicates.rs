@@ -0,0 +1,154 @@
+//! Duplicates wrapper - finds duplicate releases, delegates UI to DuplicatesView
+
+use crate::ui::app_service::use_app;
+use bae_core::library::duplicates::{DuplicateReason, DuplicateRelease};
+use bae_ui::{DuplicateGroupInfo, DuplicateReleaseInfo, DuplicatesView};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+/// Release name, year and format, joined for display
+fn release_details(release: &DuplicateRelease) -> String {
+    [
+        release.release_name.clone(),
+        release.year.map(|y| y.to_string()),
+        release.format.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+fn release_storage(release: &DuplicateRelease) -> String {
+    let mut storage = Vec::new();
+    if release.managed_locally {
+        storage.push("Local");
+    }
+    if release.managed_in_cloud {
+        storage.push("Cloud");
+    }
+    if release.unmanaged {
+        storage.push("Unmanaged");
+    }
+    if storage.is_empty() {
+        storage.push("No files");
+    }
+    storage.join(" + ")
+}
+
+/// The release kept unless the user picks another: the most played, or the
+/// first imported when plays are tied
+fn default_keep(releases: &[DuplicateRelease]) -> String {
+    releases
+        .iter()
+        .rev()
+        .max_by_key(|r| r.play_count)
+        .map(|r| r.release_id.clone())
+        .unwrap_or_default()
+}
+
+#[component]
+pub fn DuplicatesSection() -> Element {
+    let app = use_app();
+
+    let mut duplicates = use_resource({
+        let library_manager = app.library_manager.clone();
+        move || {
+            let library_manager = library_manager.clone();
+            async move { library_manager.get().find_duplicate_releases().await }
+        }
+    });
+    // Picked release per group, keyed by the group's first release ID
+    let mut keep = use_signal(HashMap::<String, String>::new);
+    let mut merging = use_signal(|| Option::<usize>::None);
+    let mut error = use_signal(|| Option::<String>::None);
+
+    let is_scanning = duplicates.read().is_none();
+    let (found, scan_error) = match &*duplicates.read() {
+        Some(Ok(found)) => (found.clone(), None),
+        Some(Err(e)) => (
+            Vec::new(),
+            Some(format!("Failed to look for duplicates: {}", e)),
+        ),
+        None => (Vec::new(), None),
+    };
+    let groups: Vec<DuplicateGroupInfo> = found
+        .iter()
+        .map(|group| DuplicateGroupInfo {
+            identical_audio: group.reason == DuplicateReason::IdenticalAudio,
+            releases: group
+                .releases
+                .iter()
+                .map(|release| DuplicateReleaseInfo {
+                    release_id: release.release_id.clone(),
+                    album_title: release.album_title.clone(),
+                    artist_name: release.artist_name.clone(),
+                    details: release_details(release),
+                    storage: release_storage(release),
+                    track_count: release.track_count,
+                    play_count: release.play_count,
+                })
+                .collect(),
+            keep_release_id: keep
+                .read()
+                .get(&group.releases[0].release_id)
+                .cloned()
+                .unwrap_or_else(|| default_keep(&group.releases)),
+        })
+        .collect();
+
+    let on_merge = {
+        let library_manager = app.library_manager.clone();
+        let library_dir = app.config.library_dir.clone();
+        let groups = groups.clone();
+        move |index: usize| {
+            let Some(group) = groups.get(index) else {
+                return;
+            };
+            let keep_release_id = group.keep_release_id.clone();
+            let duplicate_ids: Vec<String> = group
+                .releases
+                .iter()
+                .map(|r| r.release_id.clone())
+                .filter(|id| *id != keep_release_id)
+                .collect();
+            let library_manager = library_manager.clone();
+            let library_dir = library_dir.clone();
+            merging.set(Some(index));
+            error.set(None);
+            spawn(async move {
+                if let Err(e) = library_manager
+                    .get()
+                    .merge_duplicate_releases(&keep_release_id, &duplicate_ids, &library_dir)
+                    .await
+                {
+                    error.set(Some(format!("Failed to merge duplicates: {}", e)));
+                }
+                merging.set(None);
+                duplicates.restart();
+            });
+        }
+    };
+
+    rsx! {
+        DuplicatesView {
+            groups,
+            is_scanning,
+            merging: *merging.read(),
+            error: error.read().clone().or(scan_error),
+            on_scan: move |_| duplicates.restart(),
+            on_keep: {
+                let first_ids: Vec<String> = found
+                    .iter()
+                    .map(|group| group.releases[0].release_id.clone())
+                    .collect();
+                move |(index, release_id): (usize, String)| {
+                    if let Some(first_id) = first_ids.get(index) {
+                        keep.write().insert(first_id.clone(), release_id);
+                    }
+                }
+            },
+            on_merge,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Genre cleanup wrapper - loads genres and merge suggestions, delegates UI to GenreCleanupView
+
+use crate::ui::app_service::use_app;
+use bae_ui::{GenreCleanupView, GenreInfo, GenreMergeInfo};
+use dioxus::prelude::*;
+
+#[component]
+pub fn GenreCleanupSection() -> Element {
+    let app = use_app();
+
+    let mut genre_data = use_resource({
+        let library_manager = app.library_manager.clone();
+        move || {
+            let library_manager = library_manager.clone();
+            async move {
+                let manager = library_manager.get();
+                let genres = manager.get_genres().await?;
+                let suggestions = manager.get_genre_merge_suggestions().await?;
+                Ok::<_, bae_core::library::LibraryError>((genres, suggestions))
+            }
+        }
+    });
+    let mut selected = use_signal(Vec::<String>::new);
+    let mut merge_name = use_signal(String::new);
+    let mut is_merging = use_signal(|| false);
+    let mut error = use_signal(|| Option::<String>::None);
+
+    let (genres, suggestions): (Vec<GenreInfo>, Vec<GenreMergeInfo>) = match &*genre_data.read() {
+        Some(Ok((genres, suggestions))) => {
+            let name_of = |id: &String| {
+                genres
+                    .iter()
+                    .find(|g| &g.id == id)
+                    .map(|g| g.name.clone())
+                    .unwrap_or_else(|| id.clone())
+            };
+            (
+                genres
+                    .iter()
+                    .map(|g| GenreInfo {
+                        id: g.id.clone(),
+                        name: g.name.clone(),
+                        album_count: g.album_count as usize,
+                    })
+                    .collect(),
+                suggestions
+                    .iter()
+                    .map(|s| GenreMergeInfo {
+                        into: s.into.clone(),
+                        genre_ids: s.genre_ids.clone(),
+                        names: s.genre_ids.iter().map(name_of).collect(),
+                    })
+                    .collect(),
+            )
+        }
+        _ => (Vec::new(), Vec::new()),
+    };
+
+    let mut run_merge = {
+        let library_manager = app.library_manager.clone();
+        move |genre_ids: Vec<String>, into: String| {
+            let library_manager = library_manager.clone();
+            is_merging.set(true);
+            error.set(None);
+            spawn(async move {
+                match library_manager.get().merge_genres(&genre_ids, &into).await {
+                    Ok(_) => {
+                        selected.set(Vec::new());
+                        merge_name.set(String::new());
+                        genre_data.restart();
+                    }
+                    Err(e) => error.set(Some(format!("Failed to merge genres: {}", e))),
+                }
+                is_merging.set(false);
+            });
+        }
+    };
+
+    let on_merge_selected = {
+        let mut run_merge = run_merge.clone();
+        move |_| {
+            let into = merge_name.read().trim().to_string();
+            run_merge(selected.read().clone(), into);
+        }
+    };
+
+    rsx! {
+        GenreCleanupView {
+            genres,
+            suggestions,
+            selected: selected.read().clone(),
+            merge_name: merge_name.read().clone(),
+            is_merging: *is_merging.read(),
+            error: error.read().clone(),
+            on_toggle: move |id: String| {
+                let mut selected = selected.write();
+                if let Some(index) = selected.iter().position(|s| *s == id) {
+                    selected.remove(index);
+                } else {
+                    selected.push(id);
+                }
+            },
+            on_merge_name_change: move |name| merge_name.set(name),
+            on_merge_selected,
+            on_apply_suggestion: move |suggestion: GenreMergeInfo| {
+                run_merge(suggestion.genre_ids, suggestion.into);
+            },
+        }
+    }
+}
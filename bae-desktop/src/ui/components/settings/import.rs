@@ -0,0 +1,172 @@
+//! Import section wrapper - handles config state, delegates UI to ImportSectionView
+
+use crate::ui::app_service::use_app;
+use bae_core::import::network_share::NetworkShare;
+use bae_ui::stores::{AppStateStoreExt, ConfigStateStoreExt};
+use bae_ui::ImportSectionView;
+use dioxus::prelude::*;
+
+#[component]
+pub fn ImportSection() -> Element {
+    let app = use_app();
+
+    // Read config from Store
+    let config_store = app.state.config();
+    let store_patterns = config_store.import_ignore_patterns().read().clone();
+    let store_min_duration = *config_store.import_min_audio_duration_secs().read();
+    let store_auto_upgrade_covers = *config_store.auto_upgrade_covers().read();
+
+    let store_patterns_text = store_patterns.join("\n");
+    let store_min_duration_text = store_min_duration
+        .map(|secs| secs.to_string())
+        .unwrap_or_default();
+
+    let mut is_editing = use_signal(|| false);
+    let initial_patterns = store_patterns_text.clone();
+    let mut patterns = use_signal(move || initial_patterns.clone());
+    let initial_min_duration = store_min_duration_text.clone();
+    let mut min_duration = use_signal(move || initial_min_duration.clone());
+
+    let has_changes =
+        *patterns.read() != store_patterns_text || *min_duration.read() != store_min_duration_text;
+
+    // Network share edit state
+    let store_share_url = config_store.import_network_share_url().read().clone();
+    let store_share_username = config_store.import_network_share_username().read().clone();
+    let store_share_password_set = app.key_service.get_network_share_password().is_some();
+    let mut is_editing_share = use_signal(|| false);
+    let mut share_save_error = use_signal(|| Option::<String>::None);
+    let initial_share_url = store_share_url.clone().unwrap_or_default();
+    let mut share_url = use_signal(move || initial_share_url.clone());
+    let initial_share_username = store_share_username.clone().unwrap_or_default();
+    let mut share_username = use_signal(move || initial_share_username.clone());
+    let mut share_password = use_signal(String::new);
+
+    let store_share_url_text = store_share_url.clone().unwrap_or_default();
+    let store_share_username_text = store_share_username.clone().unwrap_or_default();
+    let has_share_changes = *share_url.read() != store_share_url_text
+        || *share_username.read() != store_share_username_text
+        || !share_password.read().is_empty();
+
+    let save_changes = {
+        let app = app.clone();
+        move |_| {
+            let new_patterns: Vec<String> = patterns
+                .read()
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect();
+            let new_min_duration = min_duration
+                .read()
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|&secs| secs > 0);
+
+            app.save_config(move |config| {
+                config.import_ignore_patterns = new_patterns;
+                config.import_min_audio_duration_secs = new_min_duration;
+            });
+
+            is_editing.set(false);
+        }
+    };
+
+    let cancel_patterns = store_patterns_text.clone();
+    let cancel_min_duration = store_min_duration_text.clone();
+    let cancel_edit = move |_| {
+        patterns.set(cancel_patterns.clone());
+        min_duration.set(cancel_min_duration.clone());
+        is_editing.set(false);
+    };
+
+    let save_share_changes = {
+        let app = app.clone();
+        move |_| {
+            let new_url = share_url.read().trim().to_string();
+            let new_username = share_username.read().trim().to_string();
+            let new_password = share_password.read().clone();
+            share_save_error.set(None);
+
+            if !new_url.is_empty() {
+                if let Err(e) = NetworkShare::parse(&new_url) {
+                    share_save_error.set(Some(e.to_string()));
+                    return;
+                }
+            }
+
+            let password_result = if new_url.is_empty() {
+                app.key_service.delete_network_share_password()
+            } else if !new_password.is_empty() {
+                app.key_service.set_network_share_password(&new_password)
+            } else {
+                Ok(())
+            };
+            if let Err(e) = password_result {
+                share_save_error.set(Some(format!("Failed to save password: {}", e)));
+                return;
+            }
+
+            app.save_config(move |config| {
+                config.import_network_share_username =
+                    if new_url.is_empty() || new_username.is_empty() {
+                        None
+                    } else {
+                        Some(new_username)
+                    };
+                config.import_network_share_url = (!new_url.is_empty()).then_some(new_url);
+            });
+
+            is_editing_share.set(false);
+            share_password.set(String::new());
+        }
+    };
+
+    let cancel_share_edit = move |_| {
+        share_url.set(store_share_url_text.clone());
+        share_username.set(store_share_username_text.clone());
+        share_password.set(String::new());
+        share_save_error.set(None);
+        is_editing_share.set(false);
+    };
+
+    rsx! {
+        ImportSectionView {
+            ignore_patterns: store_patterns,
+            min_audio_duration_secs: store_min_duration,
+            is_editing: *is_editing.read(),
+            edit_patterns: patterns.read().clone(),
+            edit_min_duration: min_duration.read().clone(),
+            has_changes,
+            on_edit_start: move |_| is_editing.set(true),
+            on_cancel: cancel_edit,
+            on_save: save_changes,
+            on_patterns_change: move |val| patterns.set(val),
+            on_min_duration_change: move |val| min_duration.set(val),
+            network_share_url: store_share_url,
+            network_share_username: store_share_username,
+            network_share_password_set: store_share_password_set,
+            is_editing_share: *is_editing_share.read(),
+            edit_share_url: share_url.read().clone(),
+            edit_share_username: share_username.read().clone(),
+            edit_share_password: share_password.read().clone(),
+            has_share_changes,
+            share_save_error: share_save_error.read().clone(),
+            on_share_edit_start: move |_| is_editing_share.set(true),
+            on_share_cancel: cancel_share_edit,
+            on_share_save: save_share_changes,
+            on_share_url_change: move |val| share_url.set(val),
+            on_share_username_change: move |val| share_username.set(val),
+            on_share_password_change: move |val| share_password.set(val),
+            auto_upgrade_covers: store_auto_upgrade_covers,
+            on_auto_upgrade_covers_change: move |enabled| {
+                app.save_config(move |config| config.auto_upgrade_covers = enabled);
+                if enabled {
+                    app.find_cover_upgrades();
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Integrity wrapper - runs the library file check, delegates UI to IntegrityView
+
+use crate::ui::app_service::use_app;
+use crate::ui::Route;
+use bae_core::cloud_storage::{CloudHomeStorageAdapter, CloudStorage};
+use bae_core::library::integrity::{
+    check_library_integrity, FileLocation, FileProblem, IntegrityReport,
+};
+use bae_ui::stores::{AppStateStoreExt, LibraryStateStoreExt};
+use bae_ui::{
+    BrokenAlbumInfo, BrokenFileInfo, BrokenReleaseInfo, ImportSource, IntegritySummary,
+    IntegrityView,
+};
+use dioxus::prelude::*;
+use std::sync::Arc;
+use tracing::warn;
+
+fn location_label(location: FileLocation) -> &'static str {
+    match location {
+        FileLocation::Local => "Local",
+        FileLocation::Cloud => "Cloud",
+        FileLocation::Unmanaged => "Unmanaged",
+    }
+}
+
+fn problem_label(problem: FileProblem) -> &'static str {
+    match problem {
+        FileProblem::Missing => "Missing",
+        FileProblem::Unreadable => "Unreadable",
+        FileProblem::Undecryptable => "Can't decrypt",
+        FileProblem::Corrupt => "Corrupt",
+    }
+}
+
+pub(crate) fn report_albums(report: &IntegrityReport) -> Vec<BrokenAlbumInfo> {
+    report
+        .albums
+        .iter()
+        .map(|album| BrokenAlbumInfo {
+            album_id: album.album_id.clone(),
+            album_title: album.album_title.clone(),
+            artist_name: album.artist_name.clone(),
+            releases: album
+                .releases
+                .iter()
+                .map(|release| BrokenReleaseInfo {
+                    release_id: release.release_id.clone(),
+                    release_name: release.release_name.clone(),
+                    file_count: release.file_count,
+                    broken_files: release
+                        .broken_files
+                        .iter()
+                        .map(|file| BrokenFileInfo {
+                            filename: file.filename.clone(),
+                            location: location_label(file.location).to_string(),
+                            problem: problem_label(file.problem).to_string(),
+                            detail: file.detail.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[component]
+pub fn IntegritySection() -> Element {
+    let app = use_app();
+
+    let mut progress = use_signal(|| Option::<(usize, usize)>::None);
+    let mut report = use_signal(|| Option::<IntegrityReport>::None);
+    let mut error = use_signal(|| Option::<String>::None);
+    let library = app.state.library();
+    let scrub_albums = library.scrub_broken_albums().read().clone();
+    let last_scrub = library.last_storage_scrub().read().and_then(|timestamp| {
+        chrono::DateTime::from_timestamp(timestamp, 0).map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%b %-d, %Y")
+                .to_string()
+        })
+    });
+
+    // The findings are on screen now
+    use_effect(move || {
+        let mut scrub_alert = library.scrub_alert();
+        if *scrub_alert.peek() {
+            scrub_alert.set(false);
+        }
+    });
+
+    let on_check = {
+        let app = app.clone();
+        move |_| {
+            let app = app.clone();
+            progress.set(Some((0, 0)));
+            error.set(None);
+            spawn(async move {
+                // Cloud releases are checked against the cloud home, when there is one
+                let cloud_storage: Option<Arc<dyn CloudStorage>> =
+                    if app.config.cloud_provider.is_some() {
+                        match bae_core::cloud_home::create_cloud_home(&app.config, &app.key_service)
+                            .await
+                        {
+                            Ok(home) => {
+                                Some(Arc::new(CloudHomeStorageAdapter::new(Arc::from(home))))
+                            }
+                            Err(e) => {
+                                warn!("Checking without the cloud home: {}", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
+                let result = check_library_integrity(
+                    app.library_manager.get(),
+                    &app.config.library_dir,
+                    cloud_storage,
+                    |done, total| progress.set(Some((done, total))),
+                )
+                .await;
+                match result {
+                    Ok(checked) => report.set(Some(checked)),
+                    Err(e) => error.set(Some(format!("Failed to check the library: {}", e))),
+                }
+                progress.set(None);
+            });
+        }
+    };
+
+    let on_reimport = {
+        let app = app.clone();
+        move |release_id: String| {
+            let folder = report.read().as_ref().and_then(|report| {
+                report
+                    .albums
+                    .iter()
+                    .flat_map(|a| &a.releases)
+                    .find(|r| r.release_id == release_id)
+                    .and_then(|r| r.import_folder.clone())
+            });
+
+            let mut import_store = app.state.import();
+            import_store.write().reset();
+            import_store.write().selected_import_source = ImportSource::Folder;
+            // Without the original folder the user picks where the files are now
+            if let Some(folder) = folder.filter(|f| std::path::Path::new(f).is_dir()) {
+                import_store.write().is_scanning_candidates = true;
+                if let Err(e) = app
+                    .import_handle
+                    .enqueue_folder_scan(std::path::PathBuf::from(folder))
+                {
+                    warn!("Failed to add folder to scan: {}", e);
+                }
+            }
+            navigator().push(Route::ImportWorkflowManager {});
+        }
+    };
+
+    let (summary, albums) = match report.read().as_ref() {
+        Some(checked) => (
+            Some(IntegritySummary {
+                releases_checked: checked.releases_checked,
+                files_checked: checked.files_checked,
+                releases_skipped: checked.releases_skipped,
+            }),
+            report_albums(checked),
+        ),
+        None => (None, Vec::new()),
+    };
+
+    rsx! {
+        IntegrityView {
+            progress: *progress.read(),
+            summary,
+            albums,
+            last_scrub,
+            scrub_albums,
+            error: error.read().clone(),
+            on_check,
+            on_reimport,
+            on_album_click: move |album_id| {
+                navigator()
+                    .push(Route::AlbumDetail {
+                        album_id,
+                        release_id: String::new(),
+                    });
+            },
+        }
+    }
+}
@@ -3,10 +3,13 @@
 use bae_core::cloud_home::s3::S3CloudHome;
 use bae_core::cloud_home::JoinInfo;
 use bae_core::config::{Config, FollowedLibrary};
-use bae_core::encryption::EncryptionService;
+use bae_core::device_link::{self, DeviceLinkTicket};
+use bae_core::encryption::{self, EncryptionService};
 use bae_core::join_code;
 use bae_core::keys::KeyService;
-use bae_core::library_dir::LibraryDir;
+use bae_core::library::bundle::{export_bundle, BUNDLE_EXTENSION};
+use bae_core::library::key_rotation::begin_key_rotation;
+use bae_core::library_dir::{LibraryDir, Manifest};
 use bae_core::sync::bucket::SyncBucketClient;
 use bae_core::sync::cloud_home_bucket::CloudHomeSyncBucket;
 use bae_core::sync::pull::pull_changes;
@@ -74,6 +77,200 @@ pub fn LibrarySection() -> Element {
     let mut follow_save_status = use_signal(|| Option::<FollowSyncStatus>::None);
     let mut follow_saving = use_signal(|| false);
 
+    // Bundle export state
+    let mut export_include_audio = use_signal(|| false);
+    let mut is_exporting = use_signal(|| false);
+    let mut exported_path = use_signal(|| Option::<String>::None);
+    let mut export_error = use_signal(|| Option::<String>::None);
+
+    let on_export = {
+        let app = app.clone();
+        move |_| {
+            let app = app.clone();
+            spawn(async move {
+                let name = app
+                    .config
+                    .library_name
+                    .clone()
+                    .unwrap_or_else(|| "library".to_string());
+                let Some(file) = rfd::AsyncFileDialog::new()
+                    .set_file_name(format!("{}.{}", name, BUNDLE_EXTENSION))
+                    .add_filter("bae library bundle", &[BUNDLE_EXTENSION])
+                    .save_file()
+                    .await
+                else {
+                    return;
+                };
+                let dest = file.path().to_path_buf();
+
+                is_exporting.set(true);
+                exported_path.set(None);
+                export_error.set(None);
+                let manifest = Manifest {
+                    library_id: app.config.library_id.clone(),
+                    library_name: app.config.library_name.clone(),
+                    encryption_key_fingerprint: app.config.encryption_key_fingerprint.clone(),
+                    passphrase_kdf: app
+                        .config
+                        .library_dir
+                        .read_manifest()
+                        .and_then(|m| m.passphrase_kdf),
+                };
+                let include_audio = *export_include_audio.read();
+                match export_bundle(
+                    app.library_manager.get().database(),
+                    &app.config.library_dir,
+                    manifest,
+                    &dest,
+                    include_audio,
+                )
+                .await
+                {
+                    Ok(_) => {
+                        info!("Exported library bundle to {}", dest.display());
+
+                        exported_path.set(Some(dest.display().to_string()));
+                    }
+                    Err(e) => {
+                        error!("Library bundle export failed: {e}");
+
+                        export_error.set(Some(e.to_string()));
+                    }
+                }
+                is_exporting.set(false);
+            });
+        }
+    };
+
+    // Synced libraries get a new key when a member is removed, and every
+    // member's copy has to change with it. A rotation also puts the new key in
+    // the keyring, so it waits until any security key is removed.
+    let can_rotate_key = app.config.encryption_key_stored
+        && app.sync_handle.is_none()
+        && app.config.security_key.is_none();
+    let key_rotation_progress = *app.state.config().key_rotation_progress().read();
+    let mut key_rotation_error = use_signal(|| Option::<String>::None);
+
+    let on_rotate_key = {
+        let app = app.clone();
+        move |_| {
+            let mut config = app.config.clone();
+            match begin_key_rotation(
+                &app.key_service,
+                &mut config,
+                encryption::generate_random_key(),
+            ) {
+                Ok(_) => super::super::welcome::relaunch(),
+                Err(e) => {
+                    error!("Key rotation failed: {e}");
+
+                    key_rotation_error.set(Some(e.to_string()));
+                }
+            }
+        }
+    };
+
+    // Dev mode keys come from the environment, which can't be emptied
+    let can_use_security_key = app.config.encryption_key_stored && !app.key_service.is_dev_mode();
+    let security_key_enabled = app.config.security_key.is_some();
+    let mut security_key_busy = use_signal(|| false);
+    let mut security_key_error = use_signal(|| Option::<String>::None);
+
+    let on_enable_security_key = {
+        let app = app.clone();
+        move |_| {
+            let Some(key_hex) = app.key_service.get_encryption_key() else {
+                security_key_error.set(Some("The encryption key isn't available".to_string()));
+                return;
+            };
+            security_key_busy.set(true);
+            security_key_error.set(None);
+            let app = app.clone();
+            spawn(async move {
+                let enrolled =
+                    tokio::task::spawn_blocking(move || crate::security_key::enroll(&key_hex))
+                        .await
+                        .map_err(|e| e.to_string())
+                        .and_then(|result| result);
+                let result = enrolled.and_then(|wrapped| {
+                    let mut config = app.config.clone();
+                    config.security_key = Some(wrapped);
+                    config.save().map_err(|e| e.to_string())?;
+                    // The wrapped copy is saved, so the keyring one can go
+                    app.key_service
+                        .delete_encryption_key()
+                        .map_err(|e| e.to_string())
+                });
+                match result {
+                    Ok(()) => {
+                        info!("Security key set up, re-launching");
+                        super::super::welcome::relaunch();
+                    }
+                    Err(e) => {
+                        error!("Security key setup failed: {e}");
+
+                        security_key_error.set(Some(e));
+                    }
+                }
+                security_key_busy.set(false);
+            });
+        }
+    };
+
+    let on_disable_security_key = {
+        let app = app.clone();
+        move |_| {
+            let result = app
+                .key_service
+                .get_encryption_key()
+                .ok_or_else(|| "The encryption key isn't available".to_string())
+                .and_then(|key_hex| {
+                    app.key_service
+                        .set_encryption_key(&key_hex)
+                        .map_err(|e| e.to_string())
+                })
+                .and_then(|()| {
+                    let mut config = app.config.clone();
+                    config.security_key = None;
+                    config.save().map_err(|e| e.to_string())
+                });
+            match result {
+                Ok(()) => {
+                    info!("Security key removed, re-launching");
+                    super::super::welcome::relaunch();
+                }
+                Err(e) => {
+                    error!("Failed to remove the security key: {e}");
+
+                    security_key_error.set(Some(e));
+                }
+            }
+        }
+    };
+
+    let can_encrypt_database = app.config.encryption_key_stored;
+    let mut database_encryption_error = use_signal(|| Option::<String>::None);
+
+    // The file is converted on launch, before anything opens it
+    let on_set_database_encryption = {
+        let app = app.clone();
+        move |encrypt: bool| {
+            let mut config = app.config.clone();
+            config.encrypt_database = encrypt;
+            match config.save() {
+                Ok(()) => {
+                    info!("Database encryption set to {encrypt}, re-launching");
+                    super::super::welcome::relaunch();
+                }
+                Err(e) => {
+                    error!("Failed to save database encryption setting: {e}");
+
+                    database_encryption_error.set(Some(e.to_string()));
+                }
+            }
+        }
+    };
+
     let on_switch = {
         let app = app.clone();
         move |path: String| {
@@ -105,7 +302,7 @@ pub fn LibrarySection() -> Element {
         let app = app.clone();
         move |_| {
             let dev_mode = app.key_service.is_dev_mode();
-            let config = match Config::create_new_library(dev_mode) {
+            let config = match Config::create_new_library(dev_mode, None) {
                 Ok(c) => c,
                 Err(e) => {
                     error!("Failed to create new library: {e}");
@@ -379,6 +576,8 @@ pub fn LibrarySection() -> Element {
 
     // Device link QR state
     let mut device_link_qr_svg = use_signal(|| Option::<String>::None);
+    let mut device_link_sas = use_signal(Vec::<(String, String)>::new);
+    let mut device_link_device_id = use_signal(|| Option::<String>::None);
 
     let show_link_device_button = matches!(
         app.config.cloud_provider,
@@ -420,20 +619,66 @@ pub fn LibrarySection() -> Element {
                 }
             };
 
-            match bae_core::device_link::generate_qr_svg(
+            let Some(bucket_client) = app.sync_handle.as_ref().map(|h| h.bucket_client.clone())
+            else {
+                error!("Sync is not set up, cannot link a device");
+                return;
+            };
+
+            let ticket = DeviceLinkTicket::generate();
+            let svg = match device_link::generate_qr_svg(
                 &proxy_url,
                 &encryption_key_bytes,
                 &user_keypair.signing_key,
                 &app.config.library_id,
+                &ticket,
             ) {
-                Ok(svg) => device_link_qr_svg.set(Some(svg)),
-                Err(e) => error!("Failed to generate QR code: {e}"),
-            }
+                Ok(svg) => svg,
+                Err(e) => {
+                    error!("Failed to generate QR code: {e}");
+                    return;
+                }
+            };
+
+            let sas = device_link::short_authentication_string(
+                &encryption_key_bytes,
+                &user_keypair.signing_key,
+                &app.config.library_id,
+                &ticket.nonce,
+            )
+            .into_iter()
+            .map(|(emoji, name)| (emoji.to_string(), name.to_string()))
+            .collect();
+
+            // The link only works once its ticket is in the cloud home
+            spawn(async move {
+                match device_link::issue_ticket(bucket_client.cloud_home(), &ticket).await {
+                    Ok(()) => {
+                        device_link_qr_svg.set(Some(svg));
+                        device_link_sas.set(sas);
+                        device_link_device_id.set(Some(ticket.device_id()));
+                    }
+                    Err(e) => error!("{e}"),
+                }
+            });
         }
     };
 
     let on_close_device_link = move |_| {
         device_link_qr_svg.set(None);
+        device_link_sas.set(Vec::new());
+        device_link_device_id.set(None);
+    };
+
+    let on_confirm_device_link = {
+        let app = app.clone();
+        move |_| {
+            if let Some(device_id) = device_link_device_id.take() {
+                app.verify_device(device_id);
+            }
+            device_link_qr_svg.set(None);
+            device_link_sas.set(Vec::new());
+        }
     };
 
     let on_rename = move |(path, new_name): (String, String)| {
@@ -507,7 +752,29 @@ pub fn LibrarySection() -> Element {
                 show_link_device_button,
                 on_link_device,
                 device_link_qr_svg: device_link_qr_svg.read().clone(),
+                device_link_sas: device_link_sas.read().clone(),
                 on_close_device_link,
+                on_confirm_device_link,
+                export_include_audio: *export_include_audio.read(),
+                is_exporting: *is_exporting.read(),
+                exported_path: exported_path.read().clone(),
+                export_error: export_error.read().clone(),
+                on_export_include_audio_change: move |val| export_include_audio.set(val),
+                on_export,
+                can_rotate_key,
+                key_rotation_progress,
+                key_rotation_error: key_rotation_error.read().clone(),
+                on_rotate_key,
+                can_use_security_key,
+                security_key_enabled,
+                security_key_busy: *security_key_busy.read(),
+                security_key_error: security_key_error.read().clone(),
+                on_enable_security_key,
+                on_disable_security_key,
+                can_encrypt_database,
+                database_encrypted: app.config.encrypt_database,
+                database_encryption_error: database_encryption_error.read().clone(),
+                on_set_database_encryption,
             }
         }
     }
@@ -750,8 +1017,12 @@ async fn bootstrap_library(
         library_name: None,
         keys_migrated: true,
         discogs_key_stored: false,
+        fanart_key_stored: false,
         encryption_key_stored: true,
         encryption_key_fingerprint: Some(encryption.fingerprint()),
+        key_rotation_in_progress: false,
+        security_key: None,
+        encrypt_database: false,
         torrent_bind_interface: None,
         torrent_listen_port: None,
         torrent_enable_upnp: true,
@@ -767,6 +1038,9 @@ async fn bootstrap_library(
         server_bind_address: "127.0.0.1".to_string(),
         server_auth_enabled: false,
         server_username: None,
+        server_snapshot_interval_hours: 24,
+        server_snapshot_retention_days: 30,
+        server_sync_interval_secs: 30,
         cloud_provider: Some(bae_core::config::CloudProvider::S3),
         cloud_home_s3_bucket: Some(bucket.to_string()),
         cloud_home_s3_region: Some(region.to_string()),
@@ -776,15 +1050,37 @@ async fn bootstrap_library(
             Some(endpoint.to_string())
         },
         cloud_home_s3_key_prefix: key_prefix.map(|s| s.to_string()),
+        cloud_home_s3_audio_storage_class: None,
         cloud_home_google_drive_folder_id: None,
         cloud_home_dropbox_folder_path: None,
         cloud_home_onedrive_drive_id: None,
         cloud_home_onedrive_folder_id: None,
         cloud_home_icloud_container_path: None,
+        cloud_home_folder_path: None,
         cloud_home_bae_cloud_url: None,
         cloud_home_bae_cloud_username: None,
         share_base_url: None,
         followed_libraries: vec![],
+        import_ignore_patterns: vec![],
+        import_min_audio_duration_secs: None,
+        import_network_share_url: None,
+        import_network_share_username: None,
+        download_parallelism: None,
+        readahead_mib: None,
+        encryption_chunk_size_kib: None,
+        encrypt_images: true,
+        offload_after_days: None,
+        cloud_prices: None,
+        export_name_template: None,
+        musicbrainz_username: None,
+        musicbrainz_collection_id: None,
+        pause_on_sleep: true,
+        pause_on_screen_lock: false,
+        pause_during_calls: true,
+        duck_for_other_audio: false,
+        auto_upgrade_covers: false,
+        library_sort: vec![],
+        library_filter: Default::default(),
     };
 
     config
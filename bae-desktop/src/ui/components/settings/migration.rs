@@ -0,0 +1,104 @@
+//! Migration section wrapper - runs a server migration, delegates UI to MigrationSectionView
+
+use crate::ui::app_service::use_app;
+use bae_core::import::server_migration::{
+    merge_remote_library, MigrationError, MigrationSummary, NavidromeClient, PlexClient,
+    RemoteLibrary,
+};
+use bae_ui::{MigrationResult, MigrationSectionView, MigrationServer, MigrationStatus};
+use dioxus::prelude::*;
+use tracing::{info, warn};
+
+/// Migrate from another server - pulls listening history from Navidrome or Plex
+#[component]
+pub fn MigrationSection() -> Element {
+    let app = use_app();
+
+    let mut server = use_signal(MigrationServer::default);
+    let mut server_url = use_signal(String::new);
+    let mut username = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut plex_token = use_signal(String::new);
+    let mut status = use_signal(MigrationStatus::default);
+
+    let on_migrate = {
+        let app = app.clone();
+        move |_| {
+            let library_manager = app.library_manager.clone();
+            let server = server();
+            let url = server_url.read().trim().to_string();
+            let username = username.read().trim().to_string();
+            let password = password.read().clone();
+            let plex_token = plex_token.read().trim().to_string();
+
+            spawn(async move {
+                status.set(MigrationStatus::Fetching);
+                let library: Result<RemoteLibrary, MigrationError> = match server {
+                    MigrationServer::Navidrome => {
+                        NavidromeClient::new(&url, username, password)
+                            .fetch_library()
+                            .await
+                    }
+                    MigrationServer::Plex => {
+                        PlexClient::new(&url, plex_token).fetch_library().await
+                    }
+                };
+                let library = match library {
+                    Ok(library) => library,
+                    Err(e) => {
+                        warn!("Failed to read library from {}: {}", url, e);
+
+                        status.set(MigrationStatus::Failed(e.to_string()));
+                        return;
+                    }
+                };
+
+                status.set(MigrationStatus::Merging);
+                let manager = library_manager.get();
+                match merge_remote_library(manager.database(), &library).await {
+                    Ok(summary) => {
+                        info!("Migration from {} finished: {:?}", url, summary);
+
+                        manager.notify_albums_changed();
+                        status.set(MigrationStatus::Done(migration_result(summary)));
+                    }
+                    Err(e) => {
+                        warn!("Failed to merge listening history: {}", e);
+
+                        status.set(MigrationStatus::Failed(e.to_string()));
+                    }
+                }
+            });
+        }
+    };
+
+    rsx! {
+        MigrationSectionView {
+            server: server(),
+            server_url: server_url(),
+            username: username(),
+            password: password(),
+            plex_token: plex_token(),
+            status: status(),
+            on_server_change: move |value| {
+                server.set(value);
+                status.set(MigrationStatus::Idle);
+            },
+            on_server_url_change: move |value| server_url.set(value),
+            on_username_change: move |value| username.set(value),
+            on_password_change: move |value| password.set(value),
+            on_plex_token_change: move |value| plex_token.set(value),
+            on_migrate,
+        }
+    }
+}
+
+fn migration_result(summary: MigrationSummary) -> MigrationResult {
+    MigrationResult {
+        albums_matched: summary.albums_matched,
+        albums_unmatched: summary.albums_unmatched,
+        tracks_updated: summary.tracks_updated,
+        playlists_imported: summary.playlists_imported,
+        playlist_entries_skipped: summary.playlist_entries_skipped,
+    }
+}
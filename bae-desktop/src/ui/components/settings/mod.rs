@@ -1,7 +1,17 @@
 mod about;
 mod bittorrent;
 mod discogs;
+mod duplicates;
+mod fanart;
+mod genres;
+mod import;
+pub(crate) mod integrity;
 mod library;
+mod migration;
+mod musicbrainz;
+mod party;
+mod playback;
+mod spotify_report;
 mod subsonic;
 mod sync;
 
@@ -20,13 +30,32 @@ pub fn Settings() -> Element {
             on_tab_change: move |tab| active_tab.set(tab),
             match *active_tab.read() {
                 SettingsTab::Library => rsx! {
-                    library::LibrarySection {}
+                    div { class: "space-y-6",
+                        library::LibrarySection {}
+                        duplicates::DuplicatesSection {}
+                        integrity::IntegritySection {}
+                    }
+                },
+                SettingsTab::Import => rsx! {
+                    div { class: "space-y-6",
+                        import::ImportSection {}
+                        migration::MigrationSection {}
+                        spotify_report::SpotifyReportSection {}
+                    }
+                },
+                SettingsTab::Playback => rsx! {
+                    playback::PlaybackSection {}
                 },
                 SettingsTab::Sync => rsx! {
                     sync::SyncSection {}
                 },
                 SettingsTab::Discogs => rsx! {
-                    discogs::DiscogsSection {}
+                    div { class: "space-y-6",
+                        discogs::DiscogsSection {}
+                        fanart::FanartSection {}
+                        musicbrainz::MusicBrainzSection {}
+                        genres::GenreCleanupSection {}
+                    }
                 },
                 SettingsTab::BitTorrent => rsx! {
                     bittorrent::BitTorrentSection {}
@@ -34,6 +63,9 @@ pub fn Settings() -> Element {
                 SettingsTab::Subsonic => rsx! {
                     subsonic::SubsonicSection {}
                 },
+                SettingsTab::Party => rsx! {
+                    party::PartySection {}
+                },
                 SettingsTab::About => rsx! {
                     about::AboutSection {}
                 },
@@ -0,0 +1,188 @@
+//! MusicBrainz section wrapper - account linking and collection choice, delegates UI to MusicBrainzSectionView
+
+use crate::ui::app_service::use_app;
+use bae_core::library::musicbrainz_collection::{
+    sync_collection, CollectionSyncError, MusicBrainzAccount,
+};
+use bae_core::musicbrainz::MbCollection;
+use bae_ui::stores::{AppStateStoreExt, ConfigStateStoreExt};
+use bae_ui::{MusicBrainzCollectionInfo, MusicBrainzSectionView};
+use dioxus::prelude::*;
+
+/// MusicBrainz section - collection sync
+#[component]
+pub fn MusicBrainzSection() -> Element {
+    let app = use_app();
+
+    let username = app.state.config().musicbrainz_username().read().clone();
+    let collection_id = app
+        .state
+        .config()
+        .musicbrainz_collection_id()
+        .read()
+        .clone();
+
+    let mut is_linking = use_signal(|| false);
+    let mut is_syncing = use_signal(|| false);
+    let mut sync_status = use_signal(|| Option::<String>::None);
+    let mut error = use_signal(|| Option::<String>::None);
+
+    // Collections are fetched whenever an account is linked
+    let collections = use_resource({
+        let key_service = app.key_service.clone();
+        let state = app.state;
+        move || {
+            let key_service = key_service.clone();
+            let username = state.config().musicbrainz_username().read().clone();
+            async move {
+                let Some(username) = username else {
+                    return Ok(Vec::new());
+                };
+                let mut account = MusicBrainzAccount::load(&key_service)?;
+                account.list_collections(&username).await
+            }
+        }
+    });
+    let collection_list: Vec<MusicBrainzCollectionInfo> = match &*collections.read() {
+        Some(Ok(list)) => list.iter().map(collection_info).collect(),
+        _ => Vec::new(),
+    };
+    let load_error = match &*collections.read() {
+        Some(Err(e)) => Some(format!("Couldn't load collections: {}", e)),
+        _ => None,
+    };
+
+    let on_link = {
+        let app = app.clone();
+        move |_| {
+            let app = app.clone();
+            is_linking.set(true);
+            error.set(None);
+            spawn(async move {
+                match MusicBrainzAccount::link(&app.key_service).await {
+                    Ok((_, username)) => {
+                        app.save_config(move |c| {
+                            c.musicbrainz_username = Some(username);
+                            c.musicbrainz_collection_id = None;
+                        });
+                    }
+                    Err(e) => error.set(Some(format!("Couldn't link account: {}", e))),
+                }
+                is_linking.set(false);
+            });
+        }
+    };
+
+    let on_unlink = {
+        let app = app.clone();
+        move |_| {
+            let app = app.clone();
+            error.set(None);
+            sync_status.set(None);
+            if let Err(e) = app.key_service.delete_musicbrainz_tokens() {
+                error.set(Some(format!("Couldn't remove account: {}", e)));
+                return;
+            }
+            app.save_config(|c| {
+                c.musicbrainz_username = None;
+                c.musicbrainz_collection_id = None;
+            });
+            spawn(async move {
+                if let Err(e) = app
+                    .library_manager
+                    .get()
+                    .clear_musicbrainz_collection()
+                    .await
+                {
+                    tracing::warn!("Failed to clear MusicBrainz collection records: {}", e);
+                }
+            });
+        }
+    };
+
+    let on_select_collection = {
+        let app = app.clone();
+        move |id: String| {
+            if id.is_empty() {
+                return;
+            }
+            let username = app.state.config().musicbrainz_username().peek().clone();
+            sync_status.set(None);
+            app.save_config(move |c| {
+                c.musicbrainz_username = username;
+                c.musicbrainz_collection_id = Some(id);
+            });
+            app.sync_musicbrainz_collection();
+        }
+    };
+
+    let on_sync_now = {
+        let app = app.clone();
+        move |_| {
+            let Some(collection_id) = app
+                .state
+                .config()
+                .musicbrainz_collection_id()
+                .peek()
+                .clone()
+            else {
+                return;
+            };
+            let app = app.clone();
+            is_syncing.set(true);
+            error.set(None);
+            spawn(async move {
+                let result = async {
+                    let mut account = MusicBrainzAccount::load(&app.key_service)?;
+                    sync_collection(app.library_manager.get(), &mut account, &collection_id).await
+                }
+                .await;
+                match result {
+                    Ok(changes) if changes.is_empty() => {
+                        sync_status.set(Some("Collection is up to date".to_string()));
+                    }
+                    Ok(changes) => sync_status.set(Some(format!(
+                        "Added {}, removed {}",
+                        changes.to_add.len(),
+                        changes.to_remove.len()
+                    ))),
+                    Err(e) => error.set(Some(sync_error_message(&e))),
+                }
+                is_syncing.set(false);
+            });
+        }
+    };
+
+    rsx! {
+        MusicBrainzSectionView {
+            username,
+            collections: collection_list,
+            collection_id,
+            is_linking: *is_linking.read(),
+            is_syncing: *is_syncing.read(),
+            sync_status: sync_status.read().clone(),
+            error: error.read().clone().or(load_error),
+            on_link,
+            on_unlink,
+            on_select_collection,
+            on_sync_now,
+        }
+    }
+}
+
+fn collection_info(collection: &MbCollection) -> MusicBrainzCollectionInfo {
+    MusicBrainzCollectionInfo {
+        id: collection.id.clone(),
+        name: collection.name.clone(),
+        release_count: collection.release_count,
+    }
+}
+
+fn sync_error_message(error: &CollectionSyncError) -> String {
+    match error {
+        CollectionSyncError::NotLinked => {
+            "MusicBrainz needs to be linked again. Unlink and link the account.".to_string()
+        }
+        e => format!("Sync failed: {}", e),
+    }
+}
@@ -0,0 +1,64 @@
+//! Party section wrapper - runs the party session, delegates UI to PartySectionView
+
+use crate::ui::app_service::use_app;
+use bae_ui::stores::{AppStateStoreExt, ConfigStateStoreExt, PartyStateStoreExt};
+use bae_ui::PartySectionView;
+use dioxus::prelude::*;
+use std::net::IpAddr;
+
+#[component]
+pub fn PartySection() -> Element {
+    let app = use_app();
+
+    let config_store = app.state.config();
+    let server_enabled = *config_store.server_enabled().read();
+    let port = *config_store.server_port().read();
+    let bind_address = config_store.server_bind_address().read().clone();
+    let server_local_only = bind_address
+        .parse::<IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback());
+
+    let party = app.state.party().active().read().clone();
+    let mut require_approval = use_signal(|| true);
+
+    // Guests need an address they can reach, not the wildcard bind address
+    let join_url = party.as_ref().and_then(|party| {
+        if server_local_only {
+            return None;
+        }
+        let host = match bind_address.parse::<IpAddr>() {
+            Ok(ip) if !ip.is_unspecified() => ip,
+            _ => bae_core::party::lan_address()?,
+        };
+        Some(format!(
+            "http://{}:{}/party?code={}",
+            host, port, party.code
+        ))
+    });
+
+    let start_party = app.party.clone();
+    let stop_party = app.party.clone();
+    let approval_party = app.party.clone();
+    let approve_party = app.party.clone();
+    let reject_party = app.party.clone();
+
+    rsx! {
+        PartySectionView {
+            server_enabled,
+            server_local_only,
+            party,
+            join_url,
+            require_approval: *require_approval.read(),
+            on_require_approval_change: move |value| {
+                require_approval.set(value);
+                approval_party.set_require_approval(value);
+            },
+            on_start: move |_| {
+                start_party.start(*require_approval.read());
+            },
+            on_stop: move |_| stop_party.stop(),
+            on_approve: move |id: String| approve_party.approve(&id),
+            on_reject: move |id: String| reject_party.reject(&id),
+        }
+    }
+}
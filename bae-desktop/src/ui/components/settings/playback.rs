@@ -0,0 +1,57 @@
+//! Playback section wrapper - saves the system event toggles, delegates UI to PlaybackSectionView
+
+use crate::ui::app_service::use_app;
+use bae_ui::stores::{AppStateStoreExt, ConfigStateStoreExt};
+use bae_ui::PlaybackSectionView;
+use dioxus::prelude::*;
+
+/// The four toggles as one value, so each save writes all of them
+#[derive(Clone, Copy)]
+struct Toggles {
+    pause_on_sleep: bool,
+    pause_on_screen_lock: bool,
+    pause_during_calls: bool,
+    duck_for_other_audio: bool,
+}
+
+#[component]
+pub fn PlaybackSection() -> Element {
+    let app = use_app();
+
+    let config_store = app.state.config();
+    let toggles = Toggles {
+        pause_on_sleep: *config_store.pause_on_sleep().read(),
+        pause_on_screen_lock: *config_store.pause_on_screen_lock().read(),
+        pause_during_calls: *config_store.pause_during_calls().read(),
+        duck_for_other_audio: *config_store.duck_for_other_audio().read(),
+    };
+
+    // save_config starts from the config loaded at launch, so every save
+    // carries the current value of all four toggles
+    let save = move |update: fn(&mut Toggles, bool)| {
+        let app = app.clone();
+        move |value: bool| {
+            let mut next = toggles;
+            update(&mut next, value);
+            app.save_config(move |config| {
+                config.pause_on_sleep = next.pause_on_sleep;
+                config.pause_on_screen_lock = next.pause_on_screen_lock;
+                config.pause_during_calls = next.pause_during_calls;
+                config.duck_for_other_audio = next.duck_for_other_audio;
+            });
+        }
+    };
+
+    rsx! {
+        PlaybackSectionView {
+            pause_on_sleep: toggles.pause_on_sleep,
+            pause_on_screen_lock: toggles.pause_on_screen_lock,
+            pause_during_calls: toggles.pause_during_calls,
+            duck_for_other_audio: toggles.duck_for_other_audio,
+            on_pause_on_sleep_change: save(|t, v| t.pause_on_sleep = v),
+            on_pause_on_screen_lock_change: save(|t, v| t.pause_on_screen_lock = v),
+            on_pause_during_calls_change: save(|t, v| t.pause_during_calls = v),
+            on_duck_for_other_audio_change: save(|t, v| t.duck_for_other_audio = v),
+        }
+    }
+}
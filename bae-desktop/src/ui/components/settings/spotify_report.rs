@@ -0,0 +1,114 @@
+//! Spotify report wrapper - runs the library comparison and metadata searches,
+//! delegates UI to SpotifyReportView
+
+use crate::ui::app_service::use_app;
+use crate::ui::import_helpers::search_general;
+use crate::ui::Route;
+use bae_core::import::spotify_library::{build_report, parse_export};
+use bae_ui::{SearchSource, SpotifyAlbumSearch, SpotifyReportAlbum, SpotifyReportView};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+/// Search results shown per missing album
+const MAX_SEARCH_RESULTS: usize = 5;
+
+#[component]
+pub fn SpotifyReportSection() -> Element {
+    let app = use_app();
+
+    let mut input = use_signal(String::new);
+    let mut is_comparing = use_signal(|| false);
+    let mut error = use_signal(|| Option::<String>::None);
+    let mut report = use_signal(|| Option::<Vec<SpotifyReportAlbum>>::None);
+    let mut searches = use_signal(HashMap::<String, SpotifyAlbumSearch>::new);
+
+    let on_compare = {
+        let library_manager = app.library_manager.clone();
+        move |_| {
+            let text = input.read().clone();
+            let library_manager = library_manager.clone();
+            is_comparing.set(true);
+            error.set(None);
+            searches.write().clear();
+            spawn(async move {
+                let result = match parse_export(&text) {
+                    Ok(albums) => build_report(library_manager.get().database(), albums).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(result) => {
+                        let owned = result.owned.into_iter().map(|o| SpotifyReportAlbum {
+                            artist: o.album.artist,
+                            title: o.album.title,
+                            entries: o.album.entries,
+                            album_id: Some(o.album_id),
+                        });
+                        let missing = result.missing.into_iter().map(|m| SpotifyReportAlbum {
+                            artist: m.artist,
+                            title: m.title,
+                            entries: m.entries,
+                            album_id: None,
+                        });
+                        report.set(Some(owned.chain(missing).collect()));
+                    }
+                    Err(e) => {
+                        report.set(None);
+                        error.set(Some(e.to_string()));
+                    }
+                }
+                is_comparing.set(false);
+            });
+        }
+    };
+
+    let on_search = {
+        let key_service = app.key_service.clone();
+        move |album: SpotifyReportAlbum| {
+            let key = album.key();
+            let key_service = key_service.clone();
+            searches
+                .write()
+                .insert(key.clone(), SpotifyAlbumSearch::Searching);
+            spawn(async move {
+                let state = match search_general(
+                    None,
+                    SearchSource::MusicBrainz,
+                    album.artist,
+                    album.title,
+                    String::new(),
+                    String::new(),
+                    &key_service,
+                )
+                .await
+                {
+                    Ok(mut candidates) => {
+                        candidates.truncate(MAX_SEARCH_RESULTS);
+                        SpotifyAlbumSearch::Results(candidates)
+                    }
+                    Err(e) => SpotifyAlbumSearch::Failed(e),
+                };
+                searches.write().insert(key, state);
+            });
+        }
+    };
+
+    rsx! {
+        SpotifyReportView {
+            input: input.read().clone(),
+            is_comparing: *is_comparing.read(),
+            error: error.read().clone(),
+            report: report.read().clone(),
+            searches: searches.read().clone(),
+            on_input_change: move |val| input.set(val),
+            on_compare,
+            on_open_album: move |album_id| {
+                navigator()
+                    .push(Route::AlbumDetail {
+                        album_id,
+                        release_id: String::new(),
+                    });
+            },
+            on_search,
+        }
+    }
+}
@@ -1,8 +1,10 @@
 //! Subsonic section wrapper - handles config state, delegates UI to SubsonicSectionView
 
 use crate::ui::app_service::use_app;
+use bae_core::db::DbAppPassword;
+use bae_core::subsonic::generate_app_password;
 use bae_ui::stores::{AppStateStoreExt, ConfigStateStoreExt};
-use bae_ui::SubsonicSectionView;
+use bae_ui::{AppPasswordInfo, SubsonicSectionView};
 use dioxus::prelude::*;
 
 #[component]
@@ -69,11 +71,15 @@ pub fn SubsonicSection() -> Element {
                 }
             }
 
-            // If auth is being disabled, clean up the password from keyring
+            // If auth is being disabled, clean up the password and any
+            // two-factor enrollment from keyring
             if !new_auth_enabled && store_auth_enabled {
                 if let Err(e) = app.key_service.delete_server_password() {
                     tracing::warn!("Failed to delete server password: {}", e);
                 }
+                if let Err(e) = app.key_service.delete_server_totp_secret() {
+                    tracing::warn!("Failed to delete server TOTP secret: {}", e);
+                }
             }
 
             app.save_config(move |config| {
@@ -137,6 +143,88 @@ pub fn SubsonicSection() -> Element {
         share_save_error.set(None);
     };
 
+    // App passwords
+    let mut app_passwords = use_resource({
+        let library_manager = app.library_manager.clone();
+        move || {
+            let library_manager = library_manager.clone();
+            async move { library_manager.get().database().get_app_passwords().await }
+        }
+    });
+    let mut new_app_password_name = use_signal(String::new);
+    let mut created_app_password = use_signal(|| Option::<String>::None);
+    let mut is_creating_app_password = use_signal(|| false);
+    let mut app_password_error = use_signal(|| Option::<String>::None);
+
+    let app_password_list: Vec<AppPasswordInfo> = match &*app_passwords.read() {
+        Some(Ok(list)) => list
+            .iter()
+            .map(|p| AppPasswordInfo {
+                id: p.id.clone(),
+                name: p.name.clone(),
+                created_at: p.created_at.to_rfc3339(),
+                last_used_at: p.last_used_at.map(|t| t.to_rfc3339()),
+                disabled: p.disabled,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let create_app_password = {
+        let library_manager = app.library_manager.clone();
+        move |_| {
+            let name = new_app_password_name.read().trim().to_string();
+            let library_manager = library_manager.clone();
+            is_creating_app_password.set(true);
+            app_password_error.set(None);
+            spawn(async move {
+                let app_password = DbAppPassword {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name,
+                    password: generate_app_password(),
+                    created_at: chrono::Utc::now(),
+                    last_used_at: None,
+                    disabled: false,
+                };
+                match library_manager
+                    .get()
+                    .database()
+                    .insert_app_password(&app_password)
+                    .await
+                {
+                    Ok(()) => {
+                        created_app_password.set(Some(app_password.password));
+                        new_app_password_name.set(String::new());
+                        app_passwords.restart();
+                    }
+                    Err(e) => app_password_error
+                        .set(Some(format!("Failed to create app password: {}", e))),
+                }
+                is_creating_app_password.set(false);
+            });
+        }
+    };
+
+    let revoke_app_password = {
+        let library_manager = app.library_manager.clone();
+        move |id: String| {
+            let library_manager = library_manager.clone();
+            app_password_error.set(None);
+            spawn(async move {
+                match library_manager
+                    .get()
+                    .database()
+                    .delete_app_password(&id)
+                    .await
+                {
+                    Ok(()) => app_passwords.restart(),
+                    Err(e) => app_password_error
+                        .set(Some(format!("Failed to revoke app password: {}", e))),
+                }
+            });
+        }
+    };
+
     let display_url = store_share_base_url.unwrap_or_default();
 
     rsx! {
@@ -176,6 +264,16 @@ pub fn SubsonicSection() -> Element {
             on_username_change: move |val| username.set(val),
             on_password_change: move |val| password.set(val),
             on_password_confirm_change: move |val| password_confirm.set(val),
+            // App password props
+            app_passwords: app_password_list,
+            new_app_password_name: new_app_password_name.read().clone(),
+            created_app_password: created_app_password.read().clone(),
+            is_creating_app_password: *is_creating_app_password.read(),
+            app_password_error: app_password_error.read().clone(),
+            on_app_password_name_change: move |val| new_app_password_name.set(val),
+            on_app_password_create: create_app_password,
+            on_app_password_revoke: revoke_app_password,
+            on_created_app_password_dismiss: move |_| created_app_password.set(None),
         }
     }
 }
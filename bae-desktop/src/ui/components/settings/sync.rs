@@ -2,9 +2,14 @@
 //! delegates config persistence to AppService
 
 use crate::ui::app_service::use_app;
+use bae_core::recovery_kit::RecoveryKit;
+use bae_core::recovery_shares::split_key;
+use bae_core::storage::usage::storage_usage;
 use bae_ui::stores::config::CloudProvider;
 use bae_ui::stores::{AppStateStoreExt, ConfigStateStoreExt, MemberRole, SyncStateStoreExt};
-use bae_ui::{BaeCloudAuthMode, CloudProviderOption, SyncBucketConfig, SyncSectionView};
+use bae_ui::{
+    BaeCloudAuthMode, CloudProviderOption, StorageUsageInfo, SyncBucketConfig, SyncSectionView,
+};
 use dioxus::prelude::*;
 
 /// Build the list of cloud provider options from current state.
@@ -91,6 +96,31 @@ fn build_cloud_options(
         description: "For Backblaze B2, Wasabi, MinIO, AWS, etc.",
         connected_account: s3_connected,
     });
+    options.push(CloudProviderOption {
+        provider: CloudProvider::Lan,
+        label: "Local network",
+        description: "Sync with devices on this network, nothing stored online",
+        connected_account: if matches!(cloud_provider, Some(CloudProvider::Lan))
+            && cloud_home_configured
+        {
+            Some("Local network".to_string())
+        } else {
+            None
+        },
+    });
+
+    options.push(CloudProviderOption {
+        provider: CloudProvider::Folder,
+        label: "Folder",
+        description: "A USB drive or network share, for syncing without the internet",
+        connected_account: if matches!(cloud_provider, Some(CloudProvider::Folder))
+            && cloud_home_configured
+        {
+            cloud_account_display.clone()
+        } else {
+            None
+        },
+    });
 
     options
 }
@@ -120,11 +150,48 @@ pub fn SyncSection() -> Element {
 
     // --- Recovery key state ---
     let mut recovery_key = use_signal(|| Option::<String>::None);
-
-    // Load membership on mount
+    let mut recovery_shares = use_signal(|| Option::<Vec<String>>::None);
+    let mut recovery_kit_path = use_signal(|| Option::<String>::None);
+    let mut recovery_kit_error = use_signal(|| Option::<String>::None);
+
+    let conflicts = app.state.sync().conflicts().read().clone();
+
+    let usage = use_resource({
+        let library_manager = app.library_manager.clone();
+        let prices = app.config.cloud_prices.clone();
+        move || {
+            let library_manager = library_manager.clone();
+            let prices = prices.clone();
+            async move {
+                match storage_usage(library_manager.get(), prices.as_ref()).await {
+                    Ok(usage) => Some(usage),
+                    Err(e) => {
+                        tracing::warn!("Failed to load storage usage: {e}");
+
+                        None
+                    }
+                }
+            }
+        }
+    });
+    let storage_usage = usage
+        .read()
+        .clone()
+        .flatten()
+        .map(|usage| StorageUsageInfo {
+            local_bytes: usage.local_bytes,
+            cloud_bytes: usage.cloud_bytes,
+            bytes_uploaded: usage.this_month.bytes_uploaded as u64,
+            bytes_downloaded: usage.this_month.bytes_downloaded as u64,
+            requests: usage.this_month.requests as u64,
+            estimated_monthly_cost: usage.estimated_monthly_cost,
+        });
+
+    // Load membership and conflicts on mount
     let app_for_membership = app.clone();
     use_effect(move || {
         app_for_membership.load_membership();
+        app_for_membership.load_sync_conflicts();
     });
 
     let copy_pubkey = {
@@ -142,7 +209,11 @@ pub fn SyncSection() -> Element {
     let cloud_home_bucket = app.state.sync().cloud_home_bucket().read().clone();
     let cloud_home_configured = *app.state.sync().cloud_home_configured().read();
     let signing_in = *app.state.sync().signing_in().read();
+    let encrypt_images = *app.state.config().encrypt_images().read();
+    let offload_after_days = *app.state.config().offload_after_days().read();
     let sign_in_error = app.state.sync().sign_in_error().read().clone();
+    let checking_connection = *app.state.sync().checking_connection().read();
+    let connection_check = app.state.sync().connection_check().read().clone();
 
     let cloud_options = build_cloud_options(
         &cloud_provider,
@@ -182,25 +253,41 @@ pub fn SyncSection() -> Element {
     let app_for_sync = app.clone();
     let app_for_edit = app.clone();
     let app_for_save = app.clone();
+    let app_for_check = app.clone();
+    let app_for_cancel = app.clone();
     let app_for_invite = app.clone();
     let app_for_dismiss = app.clone();
     let app_for_remove = app.clone();
+    let app_for_revoke = app.clone();
+    let app_for_approve = app.clone();
+    let app_for_resolve = app.clone();
     let app_for_sign_in = app.clone();
     let app_for_disconnect = app.clone();
     let app_for_select = app.clone();
     let app_for_bae_cloud_submit = app.clone();
+    let app_for_folder = app.clone();
+    let app_for_images = app.clone();
+    let app_for_offload = app.clone();
+    let app_for_recovery_kit = app.clone();
     #[cfg(target_os = "macos")]
     let app_for_icloud = app.clone();
+    let app_for_lan = app.clone();
 
     rsx! {
         SyncSectionView {
             // Status
             last_sync_time,
             other_devices,
+            on_revoke_device: move |device_id: String| app_for_revoke.revoke_device(device_id),
+            on_approve_device: move |device_id: String| app_for_approve.approve_device(device_id),
             syncing,
             error,
             user_pubkey,
             on_copy_pubkey: copy_pubkey,
+            conflicts,
+            on_resolve_conflict: move |(conflict_id, values): (String, Vec<(String, Option<String>)>)| {
+                app_for_resolve.resolve_sync_conflict(conflict_id, values);
+            },
             members,
             is_owner,
             on_remove_member: move |pubkey: String| {
@@ -230,6 +317,8 @@ pub fn SyncSection() -> Element {
             on_use_icloud: move |_| {
                 #[cfg(target_os = "macos")] app_for_icloud.use_icloud();
             },
+            on_use_lan: move |_| app_for_lan.use_lan_sync(),
+            on_choose_folder: move |_| app_for_folder.choose_sync_folder(),
 
             // S3 edit state
             is_editing: *is_editing.read(),
@@ -238,6 +327,8 @@ pub fn SyncSection() -> Element {
             edit_endpoint: edit_endpoint.read().clone(),
             edit_access_key: edit_access_key.read().clone(),
             edit_secret_key: edit_secret_key.read().clone(),
+            checking_connection,
+            connection_check,
 
             // bae cloud form state
             bae_cloud_is_editing,
@@ -264,6 +355,16 @@ pub fn SyncSection() -> Element {
                 }
             },
 
+            encrypt_images,
+            on_encrypt_images_change: move |enabled| {
+                app_for_images.save_config(move |config| config.encrypt_images = enabled);
+            },
+            offload_after_days,
+            on_offload_after_days_change: move |days| {
+                app_for_offload.save_config(move |config| config.offload_after_days = days);
+            },
+            storage_usage,
+
             // S3 callbacks
             on_edit_start: move |_| {
                 edit_bucket
@@ -304,9 +405,11 @@ pub fn SyncSection() -> Element {
                 };
                 edit_access_key.set(ak);
                 edit_secret_key.set(sk);
+                app_for_edit.state.sync().connection_check().set(None);
                 is_editing.set(true);
             },
             on_cancel_edit: move |_| {
+                app_for_cancel.state.sync().connection_check().set(None);
                 is_editing.set(false);
             },
             on_save_config: move |config: SyncBucketConfig| {
@@ -327,6 +430,7 @@ pub fn SyncSection() -> Element {
             on_endpoint_change: move |v| edit_endpoint.set(v),
             on_access_key_change: move |v| edit_access_key.set(v),
             on_secret_key_change: move |v| edit_secret_key.set(v),
+            on_check_connection: move |config| app_for_check.check_s3_connection(config),
 
             // Invite state
             show_invite_form: *show_invite_form.read(),
@@ -378,6 +482,66 @@ pub fn SyncSection() -> Element {
                     let _ = arboard::Clipboard::new().and_then(|mut cb| cb.set_text(key));
                 }
             },
+            recovery_shares: recovery_shares.read().clone(),
+            on_split_recovery_key: move |(threshold, count): (u8, u8)| {
+                let Some(key) = recovery_key
+                    .read()
+                    .as_deref()
+                    .and_then(|key| hex::decode(key).ok())
+                    .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                else {
+                    return;
+                };
+                match split_key(&key, threshold, count) {
+                    Ok(shares) => {
+                        recovery_shares.set(Some(shares.iter().map(|s| s.to_string()).collect()))
+                    }
+                    Err(e) => tracing::error!("Failed to split the recovery key: {e}"),
+                }
+            },
+            on_copy_recovery_shares: move |_| {
+                if let Some(ref shares) = *recovery_shares.read() {
+                    let _ = arboard::Clipboard::new().and_then(|mut cb| cb.set_text(shares.join("\n")));
+                }
+            },
+            recovery_kit_path: recovery_kit_path.read().clone(),
+            recovery_kit_error: recovery_kit_error.read().clone(),
+            on_save_recovery_kit: move |_| {
+                let Some(key) = recovery_key.read().clone() else {
+                    return;
+                };
+                let config = app_for_recovery_kit.config.clone();
+                spawn(async move {
+                    let name = config.library_name.clone().unwrap_or_else(|| "library".to_string());
+                    let Some(file) = rfd::AsyncFileDialog::new()
+                        .set_file_name(format!("{name} recovery kit.html"))
+                        .add_filter("Web page", &["html"])
+                        .save_file()
+                        .await
+                    else {
+                        return;
+                    };
+                    let dest = file.path().to_path_buf();
+
+                    recovery_kit_path.set(None);
+                    recovery_kit_error.set(None);
+                    let result = RecoveryKit::new(&config, &key)
+                        .and_then(|kit| kit.render_html())
+                        .and_then(|html| std::fs::write(&dest, html).map_err(|e| e.to_string()));
+                    match result {
+                        Ok(()) => {
+                            tracing::info!("Saved recovery kit to {}", dest.display());
+
+                            recovery_kit_path.set(Some(dest.display().to_string()));
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to save recovery kit: {e}");
+
+                            recovery_kit_error.set(Some(e));
+                        }
+                    }
+                });
+            },
         }
     }
 }
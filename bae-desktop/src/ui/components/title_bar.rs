@@ -36,6 +36,7 @@ pub fn TitleBar() -> Element {
     let window = use_window();
 
     let app = use_app();
+    let playback = app.playback_handle.clone();
     let current_route = use_route::<Route>();
     let search_store = app.state.ui().search();
     let mut search_query_store = search_store.query();
@@ -88,6 +89,7 @@ pub fn TitleBar() -> Element {
                                     .artists
                                     .into_iter()
                                     .map(|a| ArtistResult {
+                                        image_url: imgs.image_url_if_exists(&a.id),
                                         id: a.id,
                                         name: a.name,
                                         album_count: a.album_count as usize,
@@ -140,6 +142,19 @@ pub fn TitleBar() -> Element {
                 Route::Library {} | Route::AlbumDetail { .. } | Route::ArtistDetail { .. }
             ),
         },
+        NavItem {
+            id: "browse".to_string(),
+            label: "Browse".to_string(),
+            is_active: matches!(
+                current_route,
+                Route::Browse {} | Route::GenreAlbums { .. } | Route::DecadeAlbums { .. }
+            ),
+        },
+        NavItem {
+            id: "playlists".to_string(),
+            label: "Playlists".to_string(),
+            is_active: matches!(current_route, Route::Playlists {}),
+        },
         NavItem {
             id: "import".to_string(),
             label: "Import".to_string(),
@@ -173,6 +188,8 @@ pub fn TitleBar() -> Element {
             on_nav_click: move |id: String| {
                 let route = match id.as_str() {
                     "library" => Route::Library {},
+                    "browse" => Route::Browse {},
+                    "playlists" => Route::Playlists {},
                     "import" => Route::ImportWorkflowManager {},
                     _ => return,
                 };
@@ -194,13 +211,7 @@ pub fn TitleBar() -> Element {
                                 release_id: String::new(),
                             });
                     }
-                    SearchAction::Track { album_id } => {
-                        navigator()
-                            .push(Route::AlbumDetail {
-                                album_id,
-                                release_id: String::new(),
-                            });
-                    }
+                    SearchAction::Track(track_id) => playback.play(track_id),
                 }
             },
             on_search_focus: move |_| {
@@ -241,13 +252,13 @@ fn compute_top_artists(
     artists_by_album: &HashMap<String, Vec<bae_ui::Artist>>,
 ) -> Vec<ArtistResult> {
     // Count albums per artist
-    let mut artist_counts: HashMap<String, (String, usize)> = HashMap::new();
+    let mut artist_counts: HashMap<String, (&bae_ui::Artist, usize)> = HashMap::new();
     for artists in artists_by_album.values() {
         for artist in artists {
             artist_counts
                 .entry(artist.id.clone())
                 .and_modify(|(_, count)| *count += 1)
-                .or_insert((artist.name.clone(), 1));
+                .or_insert((artist, 1));
         }
     }
 
@@ -258,10 +269,11 @@ fn compute_top_artists(
     sorted
         .into_iter()
         .take(5)
-        .map(|(id, (name, album_count))| ArtistResult {
+        .map(|(id, (artist, album_count))| ArtistResult {
             id,
-            name,
+            name: artist.name.clone(),
             album_count,
+            image_url: artist.image_url.clone(),
         })
         .collect()
 }
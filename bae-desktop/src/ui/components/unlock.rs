@@ -2,11 +2,20 @@
 //!
 //! Shown when `config.encryption_key_stored` is true but the key is missing
 //! from the keyring (keyring wiped, new device without iCloud Keychain sync).
-//! User pastes their recovery key, we validate the fingerprint, save to keyring,
+//! User pastes their recovery key, or types the passphrase when the library's
+//! manifest has KDF parameters. We validate the fingerprint, save to keyring,
 //! and re-exec the binary.
+//!
+//! Recovery shares from a split key work too: any threshold of them, one per
+//! line, rebuild the key before the fingerprint check.
+//!
+//! Also shown on every launch of a library locked with a security key. A tap
+//! unwraps the key, which is handed to the re-exec'd process without ever
+//! touching the keyring.
 
-use bae_core::encryption::compute_key_fingerprint;
-use bae_core::keys::KeyService;
+use bae_core::encryption::{compute_key_fingerprint, resolve_unlock_input, PassphraseKdf};
+use bae_core::keys::{KeyService, WrappedEncryptionKey};
+use bae_core::recovery_shares::recover_key_hex;
 use bae_ui::components::button::{Button, ButtonSize, ButtonVariant};
 use bae_ui::components::text_input::{TextInput, TextInputSize, TextInputType};
 use dioxus::prelude::*;
@@ -19,10 +28,17 @@ use crate::ui::app::TAILWIND_CSS;
 struct UnlockContext {
     key_service: KeyService,
     expected_fingerprint: String,
+    passphrase_kdf: Option<PassphraseKdf>,
+    security_key: Option<WrappedEncryptionKey>,
 }
 
 /// Launch a minimal Dioxus app with the unlock screen.
-pub fn launch_unlock(key_service: KeyService, expected_fingerprint: String) {
+pub fn launch_unlock(
+    key_service: KeyService,
+    expected_fingerprint: String,
+    passphrase_kdf: Option<PassphraseKdf>,
+    security_key: Option<WrappedEncryptionKey>,
+) {
     let config = dioxus::desktop::Config::default()
         .with_window(
             dioxus::desktop::WindowBuilder::new()
@@ -38,6 +54,8 @@ pub fn launch_unlock(key_service: KeyService, expected_fingerprint: String) {
     let ctx = UnlockContext {
         key_service,
         expected_fingerprint,
+        passphrase_kdf,
+        security_key,
     };
 
     LaunchBuilder::desktop()
@@ -58,46 +76,109 @@ fn UnlockApp() -> Element {
 #[derive(Clone, PartialEq)]
 enum UnlockStatus {
     Idle,
+    Unlocking,
+    WaitingForTap,
     Error(String),
 }
 
 #[component]
 fn UnlockScreen() -> Element {
     let mut key_input = use_signal(String::new);
+    let mut use_shares = use_signal(|| false);
+    let mut shares_input = use_signal(String::new);
     let mut status = use_signal(|| UnlockStatus::Idle);
+    let ctx = use_context::<UnlockContext>();
+    let has_passphrase = ctx.passphrase_kdf.is_some();
+    let has_security_key = ctx.security_key.is_some();
+    let busy = matches!(
+        *status.read(),
+        UnlockStatus::Unlocking | UnlockStatus::WaitingForTap
+    );
 
-    let on_submit = move |_| {
-        let key_hex = key_input.read().trim().to_string();
+    let on_security_key = move |_| {
         let ctx = use_context::<UnlockContext>();
+        let Some(wrapped) = ctx.security_key.clone() else {
+            return;
+        };
+        status.set(UnlockStatus::WaitingForTap);
 
-        let fingerprint = match compute_key_fingerprint(&key_hex) {
-            Some(fp) => fp,
-            None => {
+        spawn(async move {
+            let unlocked =
+                tokio::task::spawn_blocking(move || crate::security_key::unlock(&wrapped))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result);
+            let key_hex = match unlocked {
+                Ok(key_hex) => key_hex,
+                Err(e) => {
+                    status.set(UnlockStatus::Error(e));
+                    return;
+                }
+            };
+            if compute_key_fingerprint(&key_hex).as_deref()
+                != Some(ctx.expected_fingerprint.as_str())
+            {
                 status.set(UnlockStatus::Error(
-                    "Invalid key. Must be 64 hex characters (32 bytes).".into(),
+                    "That security key unlocks a different library.".into(),
                 ));
                 return;
             }
-        };
 
-        if fingerprint != ctx.expected_fingerprint {
-            status.set(UnlockStatus::Error(format!(
-                "Wrong key. Fingerprint {fingerprint} does not match expected {}.",
-                ctx.expected_fingerprint,
-            )));
-            return;
-        }
+            info!("Unlocked with security key, re-launching");
+            crate::security_key::relaunch_unlocked(&key_hex);
+        });
+    };
 
-        match ctx.key_service.set_encryption_key(&key_hex) {
-            Ok(()) => {
-                info!("Encryption key restored to keyring, re-launching");
-                super::welcome::relaunch();
+    let on_submit = move |_| {
+        let input = key_input.read().trim().to_string();
+        let shares = use_shares().then(|| shares_input.read().clone());
+        let ctx = use_context::<UnlockContext>();
+        status.set(UnlockStatus::Unlocking);
+
+        spawn(async move {
+            // A passphrase goes through Argon2id, which takes about a second
+            let kdf = ctx.passphrase_kdf.clone();
+            let resolved = tokio::task::spawn_blocking(move || match shares {
+                Some(shares) => recover_key_hex(&shares).map_err(|e| e.to_string()),
+                None => resolve_unlock_input(&input, kdf.as_ref()).map_err(|e| e.to_string()),
+            })
+            .await;
+            let key_hex = match resolved {
+                Ok(Ok(key_hex)) => key_hex,
+                Ok(Err(e)) => {
+                    status.set(UnlockStatus::Error(e));
+                    return;
+                }
+                Err(e) => {
+                    status.set(UnlockStatus::Error(e.to_string()));
+                    return;
+                }
+            };
+
+            let fingerprint = compute_key_fingerprint(&key_hex).unwrap_or_default();
+            if fingerprint != ctx.expected_fingerprint {
+                status.set(UnlockStatus::Error(if has_passphrase {
+                    "Wrong passphrase or key for this library.".to_string()
+                } else {
+                    format!(
+                        "Wrong key. Fingerprint {fingerprint} does not match expected {}.",
+                        ctx.expected_fingerprint,
+                    )
+                }));
+                return;
             }
-            Err(e) => {
-                error!("Failed to save key to keyring: {e}");
-                status.set(UnlockStatus::Error(format!("Keyring error: {e}")));
+
+            match ctx.key_service.set_encryption_key(&key_hex) {
+                Ok(()) => {
+                    info!("Encryption key restored to keyring, re-launching");
+                    super::welcome::relaunch();
+                }
+                Err(e) => {
+                    error!("Failed to save key to keyring: {e}");
+                    status.set(UnlockStatus::Error(format!("Keyring error: {e}")));
+                }
             }
-        }
+        });
     };
 
     rsx! {
@@ -105,25 +186,64 @@ fn UnlockScreen() -> Element {
             div { class: "max-w-md w-full",
                 h1 { class: "text-3xl font-bold text-white text-center mb-2", "bae" }
                 p { class: "text-gray-400 text-center mb-8",
-                    "Your encryption key is missing from the keyring. Paste your recovery key to continue."
+                    if has_security_key {
+                        "This library is locked with a security key. Plug it in and unlock, or use your recovery key."
+                    } else if has_passphrase {
+                        "Your encryption key is missing from the keyring. Enter your passphrase or recovery key to continue."
+                    } else {
+                        "Your encryption key is missing from the keyring. Paste your recovery key to continue."
+                    }
                 }
                 div { class: "space-y-4",
-                    div {
-                        label { class: "block text-sm font-medium text-gray-400 mb-1",
-                            "Recovery Key"
+                    if has_security_key {
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            size: ButtonSize::Medium,
+                            disabled: busy,
+                            loading: *status.read() == UnlockStatus::WaitingForTap,
+                            onclick: on_security_key,
+                            "Unlock with Security Key"
                         }
-                        TextInput {
-                            value: key_input.read().clone(),
-                            on_input: move |v| key_input.set(v),
-                            size: TextInputSize::Medium,
-                            input_type: TextInputType::Password,
-                            placeholder: "64-character hex key",
-                            monospace: true,
-                            autofocus: true,
+                        if *status.read() == UnlockStatus::WaitingForTap {
+                            p { class: "text-sm text-gray-400 text-center", "Touch your security key..." }
                         }
                     }
+                    if use_shares() {
+                        div {
+                            label { class: "block text-sm font-medium text-gray-400 mb-1",
+                                "Recovery Shares"
+                            }
+                            RecoverySharesInput {
+                                value: shares_input.read().clone(),
+                                on_input: move |v| shares_input.set(v),
+                            }
+                        }
+                    } else {
+                        div {
+                            label { class: "block text-sm font-medium text-gray-400 mb-1",
+                                if has_passphrase {
+                                    "Passphrase or Recovery Key"
+                                } else {
+                                    "Recovery Key"
+                                }
+                            }
+                            TextInput {
+                                value: key_input.read().clone(),
+                                on_input: move |v| key_input.set(v),
+                                size: TextInputSize::Medium,
+                                input_type: TextInputType::Password,
+                                placeholder: if has_passphrase { "Library passphrase" } else { "64-character hex key" },
+                                monospace: !has_passphrase,
+                                autofocus: !has_security_key,
+                            }
+                        }
+                    }
+                    RecoverySharesToggle {
+                        use_shares: use_shares(),
+                        on_toggle: move |v| use_shares.set(v),
+                    }
                     match status.read().clone() {
-                        UnlockStatus::Idle => rsx! {},
+                        UnlockStatus::Idle | UnlockStatus::Unlocking | UnlockStatus::WaitingForTap => rsx! {},
                         UnlockStatus::Error(msg) => rsx! {
                             div { class: "p-3 bg-red-900/30 border border-red-700 rounded-lg text-sm text-red-300",
                                 "{msg}"
@@ -131,8 +251,10 @@ fn UnlockScreen() -> Element {
                         },
                     }
                     Button {
-                        variant: ButtonVariant::Primary,
+                        variant: if has_security_key { ButtonVariant::Secondary } else { ButtonVariant::Primary },
                         size: ButtonSize::Medium,
+                        disabled: busy,
+                        loading: *status.read() == UnlockStatus::Unlocking,
                         onclick: on_submit,
                         "Unlock"
                     }
@@ -141,3 +263,33 @@ fn UnlockScreen() -> Element {
         }
     }
 }
+
+/// Entry for recovery shares, one per line
+#[component]
+pub(crate) fn RecoverySharesInput(value: String, on_input: EventHandler<String>) -> Element {
+    rsx! {
+        textarea {
+            class: "w-full h-28 bg-gray-800/50 text-gray-300 text-xs font-mono rounded-lg p-3 focus:outline-none focus:ring-1 focus:ring-accent/50 resize-none placeholder-gray-500",
+            placeholder: "One share per line, e.g. bae-3-1-...",
+            value: "{value}",
+            oninput: move |e| on_input.call(e.value()),
+        }
+    }
+}
+
+/// Switch between a single key and a set of recovery shares
+#[component]
+pub(crate) fn RecoverySharesToggle(use_shares: bool, on_toggle: EventHandler<bool>) -> Element {
+    rsx! {
+        button {
+            class: "text-xs text-gray-400 hover:text-gray-200 underline",
+            r#type: "button",
+            onclick: move |_| on_toggle.call(!use_shares),
+            if use_shares {
+                "Use a single key instead"
+            } else {
+                "Use recovery shares instead"
+            }
+        }
+    }
+}
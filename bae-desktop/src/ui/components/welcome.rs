@@ -1,10 +1,17 @@
 //! Welcome screen for first-run setup
 //!
-//! Shown when no `~/.bae/active-library` pointer file exists. Offers two choices:
-//! - Create a new library (writes pointer file with UUID, re-execs binary)
+//! Shown when no `~/.bae/active-library` pointer file exists. Offers these choices:
+//! - Create a new library (writes pointer file with UUID, re-execs binary), with
+//!   a generated recovery key or one derived from a passphrase
 //! - Restore from cloud (downloads encrypted DB + covers, then re-execs)
+//! - Restore from a bundle file exported on another machine (unpacks, then re-execs)
+//!
+//! Both restores take the recovery key whole, or rebuilt from recovery shares.
 
 use bae_core::keys::KeyService;
+use bae_core::library::bundle::{self, BundleInfo, BUNDLE_EXTENSION};
+use bae_core::recovery_shares::recover_key_hex;
+use bae_core::sync::cloud_home_bucket::PLAIN_IMAGE_SUFFIX;
 use bae_ui::components::button::{Button, ButtonSize, ButtonVariant};
 use bae_ui::components::text_input::{TextInput, TextInputSize, TextInputType};
 use dioxus::prelude::*;
@@ -13,6 +20,8 @@ use tracing::{error, info};
 use crate::ui::app::MAIN_CSS;
 use crate::ui::app::TAILWIND_CSS;
 
+use super::unlock::{RecoverySharesInput, RecoverySharesToggle};
+
 #[derive(Clone)]
 struct WelcomeContext {
     dev_mode: bool,
@@ -47,10 +56,15 @@ fn WelcomeApp() -> Element {
     }
 }
 
+/// Shortest passphrase accepted for a new library
+const MIN_PASSPHRASE_LEN: usize = 12;
+
 #[derive(Clone, Copy, PartialEq)]
 enum WelcomeMode {
     Choose,
+    CreateWithPassphrase,
     Restore,
+    RestoreBundle,
 }
 
 #[derive(Clone, PartialEq)]
@@ -73,10 +87,20 @@ fn WelcomeScreen() -> Element {
     let mut access_key = use_signal(String::new);
     let mut secret_key = use_signal(String::new);
     let mut encryption_key = use_signal(String::new);
+    let mut use_shares = use_signal(|| false);
+    let mut shares_input = use_signal(String::new);
+
+    // Bundle restore fields
+    let mut bundle_path = use_signal(|| Option::<std::path::PathBuf>::None);
+    let mut bundle_info = use_signal(|| Option::<BundleInfo>::None);
+
+    // Passphrase fields
+    let mut passphrase = use_signal(String::new);
+    let mut passphrase_confirm = use_signal(String::new);
 
     let on_create_new = move |_| {
         let ctx = use_context::<WelcomeContext>();
-        let config = bae_core::config::Config::create_new_library(ctx.dev_mode)
+        let config = bae_core::config::Config::create_new_library(ctx.dev_mode, None)
             .expect("Failed to create new library");
 
         config
@@ -86,6 +110,47 @@ fn WelcomeScreen() -> Element {
         relaunch();
     };
 
+    let on_create_with_passphrase = move |_| {
+        let phrase = passphrase.read().clone();
+        if phrase.chars().count() < MIN_PASSPHRASE_LEN {
+            restore_status.set(RestoreStatus::Error(format!(
+                "Use at least {MIN_PASSPHRASE_LEN} characters."
+            )));
+            return;
+        }
+        if phrase != *passphrase_confirm.read() {
+            restore_status.set(RestoreStatus::Error("The passphrases don't match.".into()));
+            return;
+        }
+
+        restore_status.set(RestoreStatus::Restoring);
+        let ctx = use_context::<WelcomeContext>();
+
+        spawn(async move {
+            // Argon2id takes about a second
+            let created = tokio::task::spawn_blocking(move || {
+                bae_core::config::Config::create_new_library(ctx.dev_mode, Some(&phrase))
+            })
+            .await;
+            match created {
+                Ok(Ok(config)) => {
+                    config
+                        .save_active_library()
+                        .expect("Failed to write library pointer");
+                    relaunch();
+                }
+                Ok(Err(e)) => {
+                    error!("Failed to create library: {e}");
+                    restore_status.set(RestoreStatus::Error(e.to_string()));
+                }
+                Err(e) => {
+                    error!("Library creation task failed: {e}");
+                    restore_status.set(RestoreStatus::Error(e.to_string()));
+                }
+            }
+        });
+    };
+
     let on_restore = {
         move |_| {
             let lid = library_id.read().clone();
@@ -94,7 +159,13 @@ fn WelcomeScreen() -> Element {
             let ep = endpoint.read().clone();
             let ak = access_key.read().clone();
             let sk = secret_key.read().clone();
-            let ek = encryption_key.read().clone();
+            let ek = match entered_key(use_shares(), &encryption_key.read(), &shares_input.read()) {
+                Ok(ek) => ek,
+                Err(e) => {
+                    restore_status.set(RestoreStatus::Error(e));
+                    return;
+                }
+            };
 
             if lid.is_empty()
                 || b.is_empty()
@@ -128,6 +199,70 @@ fn WelcomeScreen() -> Element {
         }
     };
 
+    let on_pick_bundle = move |_| {
+        spawn(async move {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("bae library bundle", &[BUNDLE_EXTENSION])
+                .pick_file()
+                .await
+            else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            restore_status.set(RestoreStatus::Idle);
+            match bundle::read_bundle_info(&path) {
+                Ok(info) => {
+                    bundle_info.set(Some(info));
+                    bundle_path.set(Some(path));
+                }
+                Err(e) => {
+                    bundle_info.set(None);
+                    bundle_path.set(None);
+                    restore_status.set(RestoreStatus::Error(e.to_string()));
+                }
+            }
+        });
+    };
+
+    let on_restore_bundle = move |_| {
+        let Some(path) = bundle_path.read().clone() else {
+            return;
+        };
+        let Some(info) = bundle_info.read().clone() else {
+            return;
+        };
+        let ek = match entered_key(use_shares(), &encryption_key.read(), &shares_input.read()) {
+            Ok(ek) => ek,
+            Err(e) => {
+                restore_status.set(RestoreStatus::Error(e));
+                return;
+            }
+        };
+        if info.manifest.encryption_key_fingerprint.is_some() && ek.is_empty() {
+            restore_status.set(RestoreStatus::Error(
+                "This library is encrypted. Enter its passphrase or encryption key.".into(),
+            ));
+            return;
+        }
+
+        restore_status.set(RestoreStatus::Restoring);
+        let ctx = use_context::<WelcomeContext>();
+
+        spawn(async move {
+            let key = (!ek.is_empty()).then_some(ek);
+            match do_restore_bundle(ctx.dev_mode, path, info, key).await {
+                Ok(()) => {
+                    info!("Bundle restore complete, re-launching");
+                    relaunch();
+                }
+                Err(e) => {
+                    error!("Bundle restore failed: {}", e);
+                    restore_status.set(RestoreStatus::Error(e.to_string()));
+                }
+            }
+        });
+    };
+
     rsx! {
         div { class: "flex flex-col items-center justify-center min-h-screen bg-gray-900 p-8",
             div { class: "max-w-lg w-full",
@@ -143,12 +278,186 @@ fn WelcomeScreen() -> Element {
                                 h3 { class: "text-lg font-medium text-white mb-1", "Create new library" }
                                 p { class: "text-sm text-gray-400", "Start fresh with an empty music library" }
                             }
+                            button {
+                                class: "w-full p-6 bg-gray-800 hover:bg-gray-700 rounded-lg text-left transition-colors",
+                                onclick: move |_| {
+                                    restore_status.set(RestoreStatus::Idle);
+                                    mode.set(WelcomeMode::CreateWithPassphrase);
+                                },
+                                h3 { class: "text-lg font-medium text-white mb-1", "Create with a passphrase" }
+                                p { class: "text-sm text-gray-400", "Unlock on other devices with a passphrase instead of a recovery key" }
+                            }
                             button {
                                 class: "w-full p-6 bg-gray-800 hover:bg-gray-700 rounded-lg text-left transition-colors",
                                 onclick: move |_| mode.set(WelcomeMode::Restore),
                                 h3 { class: "text-lg font-medium text-white mb-1", "Restore from cloud" }
                                 p { class: "text-sm text-gray-400", "Download your library from S3 cloud backup" }
                             }
+                            button {
+                                class: "w-full p-6 bg-gray-800 hover:bg-gray-700 rounded-lg text-left transition-colors",
+                                onclick: move |_| {
+                                    restore_status.set(RestoreStatus::Idle);
+                                    mode.set(WelcomeMode::RestoreBundle);
+                                },
+                                h3 { class: "text-lg font-medium text-white mb-1", "Restore from bundle" }
+                                p { class: "text-sm text-gray-400", "Open a library bundle exported from another machine" }
+                            }
+                        }
+                    },
+                    WelcomeMode::CreateWithPassphrase => rsx! {
+                        div { class: "space-y-4",
+                            h2 { class: "text-xl font-semibold text-white", "Create with a Passphrase" }
+                            p { class: "text-sm text-gray-400",
+                                "Your encryption key is derived from this passphrase. Anyone who knows it can unlock the library, and if you forget it there is no recovery key to fall back on."
+                            }
+                            div {
+                                label { class: "block text-sm font-medium text-gray-400 mb-1", "Passphrase" }
+                                TextInput {
+                                    value: passphrase.read().clone(),
+                                    on_input: move |v| passphrase.set(v),
+                                    size: TextInputSize::Medium,
+                                    input_type: TextInputType::Password,
+                                    placeholder: "Something long you won't forget",
+                                    autofocus: true,
+                                }
+                            }
+                            div {
+                                label { class: "block text-sm font-medium text-gray-400 mb-1", "Confirm Passphrase" }
+                                TextInput {
+                                    value: passphrase_confirm.read().clone(),
+                                    on_input: move |v| passphrase_confirm.set(v),
+                                    size: TextInputSize::Medium,
+                                    input_type: TextInputType::Password,
+                                }
+                            }
+                            match restore_status.read().clone() {
+                                RestoreStatus::Idle => rsx! {},
+                                RestoreStatus::Restoring => rsx! {
+                                    div { class: "p-3 bg-indigo-900/30 border border-indigo-700 rounded-lg text-sm text-indigo-300",
+                                        "Deriving your encryption key..."
+                                    }
+                                },
+                                RestoreStatus::Error(msg) => rsx! {
+                                    div { class: "p-3 bg-red-900/30 border border-red-700 rounded-lg text-sm text-red-300",
+                                        "{msg}"
+                                    }
+                                },
+                            }
+                            div { class: "flex gap-3 pt-2",
+                                Button {
+                                    variant: ButtonVariant::Primary,
+                                    size: ButtonSize::Medium,
+                                    disabled: *restore_status.read() == RestoreStatus::Restoring,
+                                    loading: *restore_status.read() == RestoreStatus::Restoring,
+                                    onclick: on_create_with_passphrase,
+                                    "Create"
+                                }
+                                Button {
+                                    variant: ButtonVariant::Secondary,
+                                    size: ButtonSize::Medium,
+                                    disabled: *restore_status.read() == RestoreStatus::Restoring,
+                                    onclick: move |_| mode.set(WelcomeMode::Choose),
+                                    "Back"
+                                }
+                            }
+                        }
+                    },
+                    WelcomeMode::RestoreBundle => rsx! {
+                        div { class: "space-y-4",
+                            h2 { class: "text-xl font-semibold text-white", "Restore from Bundle" }
+                            div { class: "flex items-center gap-3",
+                                Button {
+                                    variant: ButtonVariant::Secondary,
+                                    size: ButtonSize::Medium,
+                                    disabled: *restore_status.read() == RestoreStatus::Restoring,
+                                    onclick: on_pick_bundle,
+                                    "Choose bundle..."
+                                }
+                                if let Some(path) = bundle_path.read().as_ref() {
+                                    span { class: "text-sm text-gray-400 truncate", "{path.display()}" }
+                                }
+                            }
+                            if let Some(info) = bundle_info.read().as_ref() {
+                                div { class: "p-4 bg-gray-800 rounded-lg text-sm space-y-1",
+                                    div { class: "text-white font-medium",
+                                        "{info.manifest.library_name.as_deref().unwrap_or(&info.manifest.library_id)}"
+                                    }
+                                    div { class: "text-gray-400",
+                                        "Exported {info.created_at.get(..10).unwrap_or(&info.created_at)}"
+                                    }
+                                    div { class: "text-gray-400",
+                                        if info.includes_audio {
+                                            "Includes audio stored in the library"
+                                        } else {
+                                            "Metadata and artwork only; audio stays where it was"
+                                        }
+                                    }
+                                }
+                                if info.manifest.encryption_key_fingerprint.is_some() {
+                                    if use_shares() {
+                                        div {
+                                            label { class: "block text-sm font-medium text-gray-400 mb-1",
+                                                "Recovery Shares"
+                                            }
+                                            RecoverySharesInput {
+                                                value: shares_input.read().clone(),
+                                                on_input: move |v| shares_input.set(v),
+                                            }
+                                        }
+                                    } else {
+                                        div {
+                                            label { class: "block text-sm font-medium text-gray-400 mb-1",
+                                                if info.manifest.passphrase_kdf.is_some() {
+                                                    "Passphrase or Encryption Key"
+                                                } else {
+                                                    "Encryption Key"
+                                                }
+                                            }
+                                            TextInput {
+                                                value: encryption_key.read().clone(),
+                                                on_input: move |v| encryption_key.set(v),
+                                                size: TextInputSize::Medium,
+                                                input_type: TextInputType::Password,
+                                                placeholder: if info.manifest.passphrase_kdf.is_some() { "Library passphrase" } else { "Hex-encoded encryption key" },
+                                            }
+                                        }
+                                    }
+                                    RecoverySharesToggle {
+                                        use_shares: use_shares(),
+                                        on_toggle: move |v| use_shares.set(v),
+                                    }
+                                }
+                            }
+                            match restore_status.read().clone() {
+                                RestoreStatus::Idle => rsx! {},
+                                RestoreStatus::Restoring => rsx! {
+                                    div { class: "p-3 bg-indigo-900/30 border border-indigo-700 rounded-lg text-sm text-indigo-300",
+                                        "Unpacking your library..."
+                                    }
+                                },
+                                RestoreStatus::Error(msg) => rsx! {
+                                    div { class: "p-3 bg-red-900/30 border border-red-700 rounded-lg text-sm text-red-300",
+                                        "{msg}"
+                                    }
+                                },
+                            }
+                            div { class: "flex gap-3 pt-2",
+                                Button {
+                                    variant: ButtonVariant::Primary,
+                                    size: ButtonSize::Medium,
+                                    disabled: bundle_info.read().is_none() || *restore_status.read() == RestoreStatus::Restoring,
+                                    loading: *restore_status.read() == RestoreStatus::Restoring,
+                                    onclick: on_restore_bundle,
+                                    "Restore"
+                                }
+                                Button {
+                                    variant: ButtonVariant::Secondary,
+                                    size: ButtonSize::Medium,
+                                    disabled: *restore_status.read() == RestoreStatus::Restoring,
+                                    onclick: move |_| mode.set(WelcomeMode::Choose),
+                                    "Back"
+                                }
+                            }
                         }
                     },
                     WelcomeMode::Restore => rsx! {
@@ -214,16 +523,30 @@ fn WelcomeScreen() -> Element {
                                     placeholder: "Secret access key",
                                 }
                             }
-                            div {
-                                label { class: "block text-sm font-medium text-gray-400 mb-1", "Encryption Key" }
-                                TextInput {
-                                    value: encryption_key.read().clone(),
-                                    on_input: move |v| encryption_key.set(v),
-                                    size: TextInputSize::Medium,
-                                    input_type: TextInputType::Password,
-                                    placeholder: "Hex-encoded encryption key",
+                            if use_shares() {
+                                div {
+                                    label { class: "block text-sm font-medium text-gray-400 mb-1", "Recovery Shares" }
+                                    RecoverySharesInput {
+                                        value: shares_input.read().clone(),
+                                        on_input: move |v| shares_input.set(v),
+                                    }
+                                }
+                            } else {
+                                div {
+                                    label { class: "block text-sm font-medium text-gray-400 mb-1", "Encryption Key" }
+                                    TextInput {
+                                        value: encryption_key.read().clone(),
+                                        on_input: move |v| encryption_key.set(v),
+                                        size: TextInputSize::Medium,
+                                        input_type: TextInputType::Password,
+                                        placeholder: "Hex-encoded encryption key",
+                                    }
                                 }
                             }
+                            RecoverySharesToggle {
+                                use_shares: use_shares(),
+                                on_toggle: move |v| use_shares.set(v),
+                            }
                             match restore_status.read().clone() {
                                 RestoreStatus::Idle => rsx! {},
                                 RestoreStatus::Restoring => rsx! {
@@ -334,13 +657,14 @@ async fn do_restore(
 
     // Download and decrypt images
     let images_dir = library_dir.images_dir();
-    download_images_encrypted(&storage, &encryption_service, &bucket, &images_dir).await?;
+    download_images(&storage, &encryption_service, &bucket, &images_dir).await?;
 
     // Write local manifest.json
     let home_manifest = bae_core::library_dir::Manifest {
         library_id: library_id.clone(),
         library_name: manifest.library_name.clone(),
         encryption_key_fingerprint: Some(fingerprint.clone()),
+        passphrase_kdf: manifest.passphrase_kdf.clone(),
     };
     let manifest_json = serde_json::to_string_pretty(&home_manifest)?;
     tokio::fs::write(library_dir.manifest_path(), manifest_json).await?;
@@ -353,8 +677,12 @@ async fn do_restore(
         library_name: manifest.library_name,
         keys_migrated: true,
         discogs_key_stored: false,
+        fanart_key_stored: false,
         encryption_key_stored: true,
         encryption_key_fingerprint: Some(fingerprint),
+        key_rotation_in_progress: false,
+        security_key: None,
+        encrypt_database: false,
         torrent_bind_interface: None,
         torrent_listen_port: None,
         torrent_enable_upnp: false,
@@ -370,20 +698,45 @@ async fn do_restore(
         server_bind_address: "127.0.0.1".to_string(),
         server_auth_enabled: false,
         server_username: None,
+        server_snapshot_interval_hours: 24,
+        server_snapshot_retention_days: 30,
+        server_sync_interval_secs: 30,
         cloud_provider: None,
         cloud_home_s3_bucket: None,
         cloud_home_s3_region: None,
         cloud_home_s3_endpoint: None,
         cloud_home_s3_key_prefix: None,
+        cloud_home_s3_audio_storage_class: None,
         cloud_home_google_drive_folder_id: None,
         cloud_home_dropbox_folder_path: None,
         cloud_home_onedrive_drive_id: None,
         cloud_home_onedrive_folder_id: None,
         cloud_home_icloud_container_path: None,
+        cloud_home_folder_path: None,
         cloud_home_bae_cloud_url: None,
         cloud_home_bae_cloud_username: None,
         share_base_url: None,
         followed_libraries: vec![],
+        import_ignore_patterns: vec![],
+        import_min_audio_duration_secs: None,
+        import_network_share_url: None,
+        import_network_share_username: None,
+        download_parallelism: None,
+        readahead_mib: None,
+        encryption_chunk_size_kib: None,
+        encrypt_images: true,
+        offload_after_days: None,
+        cloud_prices: None,
+        export_name_template: None,
+        musicbrainz_username: None,
+        musicbrainz_collection_id: None,
+        pause_on_sleep: true,
+        pause_on_screen_lock: false,
+        pause_during_calls: true,
+        duck_for_other_audio: false,
+        auto_upgrade_covers: false,
+        library_sort: vec![],
+        library_filter: Default::default(),
     };
     config.save_to_config_yaml()?;
 
@@ -411,8 +764,77 @@ async fn do_restore(
     Ok(())
 }
 
-/// Download and decrypt all images from the cloud bucket.
-async fn download_images_encrypted(
+/// The key as typed, or rebuilt from the recovery shares
+fn entered_key(use_shares: bool, key: &str, shares: &str) -> Result<String, String> {
+    if use_shares {
+        recover_key_hex(shares).map_err(|e| e.to_string())
+    } else {
+        Ok(key.trim().to_string())
+    }
+}
+
+/// Unpack a bundle into `~/.bae/libraries`, store its encryption key and point
+/// the active-library file at it. `key_input` is the recovery key, or the
+/// passphrase when the bundle's manifest has KDF parameters.
+async fn do_restore_bundle(
+    dev_mode: bool,
+    path: std::path::PathBuf,
+    info: BundleInfo,
+    key_input: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use bae_core::encryption::{resolve_unlock_input, EncryptionService};
+
+    let encryption_key_hex = match key_input {
+        Some(input) => {
+            let kdf = info.manifest.passphrase_kdf.clone();
+            Some(
+                tokio::task::spawn_blocking(move || resolve_unlock_input(&input, kdf.as_ref()))
+                    .await??,
+            )
+        }
+        None => None,
+    };
+
+    // Check the key before unpacking anything
+    if let (Some(expected_fp), Some(key_hex)) = (
+        info.manifest.encryption_key_fingerprint.as_ref(),
+        encryption_key_hex.as_ref(),
+    ) {
+        let fingerprint = EncryptionService::new(key_hex)?.fingerprint();
+        if *expected_fp != fingerprint {
+            return Err("That encryption key doesn't match this library".into());
+        }
+    }
+
+    let libraries_dir = dirs::home_dir()
+        .expect("Failed to get home directory")
+        .join(".bae")
+        .join("libraries");
+    let key_stored = encryption_key_hex.is_some();
+    let library_dir = tokio::task::spawn_blocking(move || {
+        bundle::restore_bundle(&path, &libraries_dir, key_stored)
+    })
+    .await??;
+
+    let library_id = info.manifest.library_id;
+    if let Some(key_hex) = encryption_key_hex {
+        KeyService::new(dev_mode, library_id.clone()).set_encryption_key(&key_hex)?;
+    }
+
+    // Write pointer file last (makes this idempotent on failure)
+    bae_core::config::Config::set_active_library(&library_id)?;
+
+    info!(
+        "Bundle restore complete: library at {}",
+        library_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Download all images from the cloud bucket, decrypting the ones stored
+/// encrypted.
+async fn download_images(
     storage: &bae_core::cloud_storage::S3CloudStorage,
     encryption: &bae_core::encryption::EncryptionService,
     bucket: &str,
@@ -429,16 +851,20 @@ async fn download_images_encrypted(
     for key in &keys {
         // key looks like "images/ab/cd/<id>" — strip "images/" prefix to get the relative path
         let rel = key.strip_prefix("images/").unwrap_or(key);
-        let target_path = images_dir.join(rel);
+        let plain_rel = rel.strip_suffix(PLAIN_IMAGE_SUFFIX);
+        let target_path = images_dir.join(plain_rel.unwrap_or(rel));
 
         if let Some(parent) = target_path.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
 
         let location = format!("s3://{}/{}", bucket, key);
-        let encrypted_data = storage.download(&location).await?;
-        let decrypted_data = encryption.decrypt(&encrypted_data)?;
-        tokio::fs::write(&target_path, &decrypted_data).await?;
+        let data = storage.download(&location).await?;
+        let data = match plain_rel {
+            Some(_) => data,
+            None => encryption.decrypt(&data)?,
+        };
+        tokio::fs::write(&target_path, &data).await?;
     }
 
     info!(
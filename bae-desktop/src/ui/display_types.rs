@@ -1,10 +1,22 @@
 //! Conversions from DB types to bae-ui display types
 
-use bae_core::db::{DbAlbum, DbArtist, DbFile, DbRelease, DbTrack, ImportStatus};
+use bae_core::db::{
+    DbAlbum, DbAlbumComment, DbAlbumCustomTag, DbAlbumVersion, DbArtist, DbFile, DbPlaylist,
+    DbPlaylistEntry, DbRelease, DbReleaseAvailability, DbTrack, DbTrackAudioSource, DbTrackWork,
+    ImportStatus,
+};
 use bae_core::image_server::ImageServerHandle;
+use bae_core::import::cover_upgrade;
+use bae_core::library::metadata_refresh;
+use bae_core::sync::attribution::AttributionMap;
+use std::collections::HashMap;
 
 // Re-export bae-ui types so existing code continues to work
-pub use bae_ui::{Album, Artist, File, Release, Track, TrackImportState};
+pub use bae_ui::{
+    Album, AlbumComment, AlbumVersion, Artist, CoverUpgradeOffer, CreditedArtist, CustomTag,
+    DeviceAvailability, File, MetadataChange, MetadataUpdate, Playlist, PlaylistEntry, Release,
+    Track, TrackAudioSource, TrackImportState, TrackMapping, TrackMappingEntry, TrackWork,
+};
 
 pub fn album_from_db_ref(db: &DbAlbum, imgs: &ImageServerHandle) -> Album {
     let cover = db
@@ -22,6 +34,29 @@ pub fn album_from_db_ref(db: &DbAlbum, imgs: &ImageServerHandle) -> Album {
     }
 }
 
+pub fn album_version_from_db_ref(db: &DbAlbumVersion) -> AlbumVersion {
+    AlbumVersion {
+        album_id: db.album_id.clone(),
+        title: db.title.clone(),
+        year: db.year,
+        label: db.label.clone(),
+    }
+}
+
+pub fn custom_tag_from_db(db: &DbAlbumCustomTag) -> CustomTag {
+    CustomTag {
+        key: db.key.clone(),
+        value: db.value.clone(),
+    }
+}
+
+pub fn custom_tag_to_db(tag: &CustomTag) -> DbAlbumCustomTag {
+    DbAlbumCustomTag {
+        key: tag.key.clone(),
+        value: tag.value.clone(),
+    }
+}
+
 pub fn artist_from_db_ref(db: &DbArtist, imgs: &ImageServerHandle) -> Artist {
     Artist {
         id: db.id.clone(),
@@ -30,6 +65,92 @@ pub fn artist_from_db_ref(db: &DbArtist, imgs: &ImageServerHandle) -> Artist {
     }
 }
 
+/// A comment attributed to its author by their name in the membership chain
+pub fn album_comment_from_db(
+    db: &DbAlbumComment,
+    names: &AttributionMap,
+    user_pubkey: Option<&str>,
+) -> AlbumComment {
+    AlbumComment {
+        id: db.id.clone(),
+        author_name: names.display_name(&db.author_pubkey),
+        is_own: user_pubkey == Some(db.author_pubkey.as_str()),
+        content: db.content.clone(),
+        created_at: db.created_at.to_rfc3339(),
+    }
+}
+
+/// An artist credit from (artist, join phrase) pairs in credit order
+pub fn artist_credit_from_db(
+    credit: &[(DbArtist, Option<String>)],
+    imgs: &ImageServerHandle,
+) -> Vec<CreditedArtist> {
+    credit
+        .iter()
+        .map(|(artist, join_phrase)| CreditedArtist {
+            artist: artist_from_db_ref(artist, imgs),
+            join_phrase: join_phrase.clone(),
+        })
+        .collect()
+}
+
+/// Group (track ID, artist, join phrase) credits by track, keeping their order
+pub fn track_credits_from_db(
+    credits: &[(String, DbArtist, Option<String>)],
+    imgs: &ImageServerHandle,
+) -> HashMap<String, Vec<CreditedArtist>> {
+    let mut by_track: HashMap<String, Vec<CreditedArtist>> = HashMap::new();
+    for (track_id, artist, join_phrase) in credits {
+        by_track
+            .entry(track_id.clone())
+            .or_default()
+            .push(CreditedArtist {
+                artist: artist_from_db_ref(artist, imgs),
+                join_phrase: join_phrase.clone(),
+            });
+    }
+    by_track
+}
+
+/// Works keyed by track ID, each with the composers credited on that track
+pub fn track_works_from_db(
+    works: &[DbTrackWork],
+    composers: &[(String, DbArtist)],
+    imgs: &ImageServerHandle,
+) -> HashMap<String, TrackWork> {
+    works
+        .iter()
+        .map(|work| {
+            let composers = composers
+                .iter()
+                .filter(|(track_id, _)| *track_id == work.track_id)
+                .map(|(_, artist)| artist_from_db_ref(artist, imgs))
+                .collect();
+            let display = TrackWork {
+                work_title: work.work_title.clone(),
+                movement_title: work.movement_title.clone(),
+                composers,
+            };
+            (work.track_id.clone(), display)
+        })
+        .collect()
+}
+
+/// Group (album ID, composer) pairs by album
+pub fn composers_by_album_from_db(
+    pairs: &[(String, DbArtist)],
+    imgs: &ImageServerHandle,
+) -> HashMap<String, Vec<Artist>> {
+    let mut by_album: HashMap<String, Vec<Artist>> = HashMap::new();
+    for (album_id, artist) in pairs {
+        by_album
+            .entry(album_id.clone())
+            .or_default()
+            .push(artist_from_db_ref(artist, imgs));
+    }
+    by_album
+}
+
 pub fn track_from_db_ref(db: &DbTrack) -> Track {
     let is_available = db.import_status == ImportStatus::Complete;
     Track {
@@ -47,6 +168,158 @@ pub fn track_from_db_ref(db: &DbTrack) -> Track {
     }
 }
 
+pub fn playlist_from_db(db: &DbPlaylist, track_count: usize) -> Playlist {
+    Playlist {
+        id: db.id.clone(),
+        name: db.name.clone(),
+        track_count,
+    }
+}
+
+pub fn playlist_entry_from_db(db: &DbPlaylistEntry, imgs: &ImageServerHandle) -> PlaylistEntry {
+    PlaylistEntry {
+        track: Track {
+            id: db.track_id.clone(),
+            title: db.title.clone(),
+            track_number: None,
+            disc_number: None,
+            duration_ms: db.duration_ms,
+            is_available: true,
+            import_state: TrackImportState::Complete,
+        },
+        artist_name: db.artist_name.clone(),
+        album_id: db.album_id.clone(),
+        album_title: db.album_title.clone(),
+        cover_url: db
+            .cover_release_id
+            .as_ref()
+            .map(|release_id| imgs.image_url(release_id)),
+    }
+}
+
+pub fn track_mapping_from_db(
+    release_id: &str,
+    tracks: &[DbTrack],
+    sources: &[DbTrackAudioSource],
+) -> TrackMapping {
+    let entries = tracks
+        .iter()
+        .map(|t| TrackMappingEntry {
+            track_id: t.id.clone(),
+            title: t.title.clone(),
+            disc_number: t.disc_number,
+            track_number: t.track_number,
+            source_id: sources
+                .iter()
+                .find(|s| s.track_id == t.id)
+                .map(|s| s.audio_format_id.clone()),
+        })
+        .collect();
+    let sources = sources
+        .iter()
+        .map(|s| TrackAudioSource {
+            id: s.audio_format_id.clone(),
+            filename: s
+                .original_filename
+                .clone()
+                .unwrap_or_else(|| "Unknown file".to_string()),
+            duration_ms: s.duration_ms,
+        })
+        .collect();
+
+    TrackMapping {
+        release_id: release_id.to_string(),
+        entries,
+        sources,
+    }
+}
+
+pub fn metadata_change_from_core(change: &metadata_refresh::MetadataChange) -> MetadataChange {
+    let update = match &change.update {
+        metadata_refresh::MetadataUpdate::Title(title) => MetadataUpdate::Title(title.clone()),
+        metadata_refresh::MetadataUpdate::Year(year) => MetadataUpdate::Year(*year),
+        metadata_refresh::MetadataUpdate::Artists(names) => MetadataUpdate::Artists(names.clone()),
+        metadata_refresh::MetadataUpdate::TrackTitle {
+            track_id,
+            disc_number,
+            track_number,
+            title,
+        } => MetadataUpdate::TrackTitle {
+            track_id: track_id.clone(),
+            disc_number: *disc_number,
+            track_number: *track_number,
+            title: title.clone(),
+        },
+        metadata_refresh::MetadataUpdate::DiscTitle { disc_number, title } => {
+            MetadataUpdate::DiscTitle {
+                disc_number: *disc_number,
+                title: title.clone(),
+            }
+        }
+    };
+    MetadataChange {
+        update,
+        current: change.current.clone(),
+        proposed: change.proposed.clone(),
+    }
+}
+
+pub fn cover_upgrade_offer_from_core(upgrade: &cover_upgrade::CoverUpgrade) -> CoverUpgradeOffer {
+    let label = match upgrade.source.as_str() {
+        "musicbrainz" => "Cover Art Archive",
+        "discogs" => "Discogs",
+        "itunes" => "iTunes",
+        other => other,
+    };
+    CoverUpgradeOffer {
+        release_id: upgrade.release_id.clone(),
+        url: upgrade.url.clone(),
+        source: upgrade.source.clone(),
+        label: label.to_string(),
+        width: upgrade.width,
+        height: upgrade.height,
+        current_width: upgrade.current_width,
+        current_height: upgrade.current_height,
+    }
+}
+
+pub fn metadata_update_to_core(update: &MetadataUpdate) -> metadata_refresh::MetadataUpdate {
+    match update {
+        MetadataUpdate::Title(title) => metadata_refresh::MetadataUpdate::Title(title.clone()),
+        MetadataUpdate::Year(year) => metadata_refresh::MetadataUpdate::Year(*year),
+        MetadataUpdate::Artists(names) => metadata_refresh::MetadataUpdate::Artists(names.clone()),
+        MetadataUpdate::TrackTitle {
+            track_id,
+            disc_number,
+            track_number,
+            title,
+        } => metadata_refresh::MetadataUpdate::TrackTitle {
+            track_id: track_id.clone(),
+            disc_number: *disc_number,
+            track_number: *track_number,
+            title: title.clone(),
+        },
+        MetadataUpdate::DiscTitle { disc_number, title } => {
+            metadata_refresh::MetadataUpdate::DiscTitle {
+                disc_number: *disc_number,
+                title: title.clone(),
+            }
+        }
+    }
+}
+
+pub fn device_availability_from_db(
+    db: &DbReleaseAvailability,
+    this_device_id: &str,
+) -> DeviceAvailability {
+    DeviceAvailability {
+        device_id: db.device_id.clone(),
+        is_this_device: db.device_id == this_device_id,
+        has_files: db.has_files,
+        cached_files: db.cached_files as usize,
+    }
+}
+
 pub fn file_from_db_ref(db: &DbFile) -> File {
     File {
         id: db.id.clone(),
@@ -13,7 +13,8 @@ pub mod search;
 
 // Re-export public API used by consumers outside this module
 pub use conversion::{
-    count_local_audio_files, extract_tracks_from_discogs, extract_tracks_from_mb_response,
+    categorized_files_from_scanned, count_local_audio_files, extract_tracks_from_discogs,
+    extract_tracks_from_mb_response,
 };
 pub use scan::consume_scan_events;
 pub use search::{
@@ -35,7 +36,7 @@ use bae_ui::stores::AppStateStoreExt;
 use bae_ui::ImportSource;
 use conversion::{count_discogs_release_tracks, to_display_candidate};
 use dioxus::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{error, info, warn};
 
 // ============================================================================
@@ -229,16 +230,22 @@ pub async fn confirm_and_start_import(
     let import_id = uuid::Uuid::new_v4().to_string();
 
     // Get state from store
-    let (managed, metadata, selected_cover) = {
+    let (managed, move_to_cloud, metadata, selected_cover) = {
         let state = import_store.read();
         (
             state.get_managed(),
+            state.get_move_to_cloud(),
             state.get_metadata(),
             state.get_selected_cover(),
         )
     };
     let master_year = metadata.as_ref().and_then(|m| m.year).unwrap_or(1970);
 
+    // Extracted archives live in a workspace that's removed once the import
+    // finishes, so their files always go to managed storage
+    let workspace = app.cache.import_workspace_of(Path::new(&candidate_key));
+    let managed = managed || workspace.is_some();
+
     let selected_cover = selected_cover.map(|c| match c {
         SelectedCover::Remote { url, .. } => CoverSelection::Remote(url),
         SelectedCover::Local { filename } => CoverSelection::Local(filename),
@@ -270,6 +277,7 @@ pub async fn confirm_and_start_import(
                     folder: PathBuf::from(&candidate_key),
                     master_year,
                     managed,
+                    move_to_cloud,
                     selected_cover: selected_cover.clone(),
                 }
             }
@@ -313,6 +321,18 @@ pub async fn confirm_and_start_import(
                     folder: PathBuf::from(&candidate_key),
                     master_year,
                     managed,
+                    move_to_cloud,
+                    selected_cover: selected_cover.clone(),
+                }
+            }
+            MatchSourceType::Tags => {
+                info!("Starting tag import for: {}", candidate.title);
+
+                ImportRequest::Tags {
+                    import_id: import_id.clone(),
+                    folder: PathBuf::from(&candidate_key),
+                    managed,
+                    move_to_cloud,
                     selected_cover: selected_cover.clone(),
                 }
             }
@@ -330,6 +350,7 @@ pub async fn confirm_and_start_import(
             let progress_handle = import_handle.progress_handle.clone();
             let mut import_store_clone = app.state.import();
             let album_id_for_completion = album_id.clone();
+            let app = app.clone();
             spawn(async move {
                 let mut progress_rx = progress_handle.subscribe_import(import_id.clone());
                 while let Some(event) = progress_rx.recv().await {
@@ -346,13 +367,16 @@ pub async fn confirm_and_start_import(
                             warn!("Import failed for candidate {}: {}", candidate_key, error);
                             import_store_clone.write().dispatch_to_candidate(
                                 &candidate_key,
-                                CandidateEvent::ImportFailed(error),
+                                CandidateEvent::ImportFailed(error.to_string()),
                             );
                             break;
                         }
                         _ => {}
                     }
                 }
+                if let Some(workspace) = workspace {
+                    remove_finished_workspace(&app, &workspace).await;
+                }
             });
 
             Ok(())
@@ -363,11 +387,41 @@ pub async fn confirm_and_start_import(
             import_store
                 .write()
                 .dispatch(CandidateEvent::ImportFailed(error_msg.clone()));
+            if let Some(workspace) = workspace {
+                remove_finished_workspace(app, &workspace).await;
+            }
             Err(error_msg)
         }
     }
 }
 
+/// Remove an archive's workspace once none of the candidates extracted into it
+/// is still waiting to be imported.
+async fn remove_finished_workspace(app: &AppService, workspace: &Path) {
+    let still_needed = {
+        let import_store = app.state.import();
+        let state = import_store.read();
+        state.detected_candidates.iter().any(|candidate| {
+            Path::new(&candidate.path).starts_with(workspace)
+                && !state
+                    .candidate_states
+                    .get(&candidate.path)
+                    .is_some_and(|s| s.is_import_finished())
+        })
+    };
+    if still_needed {
+        return;
+    }
+
+    if let Err(e) = app.cache.remove_import_workspace(workspace).await {
+        warn!(
+            "Failed to remove import workspace {}: {}",
+            workspace.display(),
+            e
+        );
+    }
+}
+
 // ============================================================================
 // Navigation helpers
 // ============================================================================
@@ -467,5 +521,6 @@ pub fn has_unclean_state(app: &AppService) -> bool {
         ImportSource::Folder => !state.detected_candidates.is_empty(),
         ImportSource::Torrent => false, // TODO: implement torrent state check
         ImportSource::Cd => state.current_candidate_key.is_some(),
+        ImportSource::Bandcamp => false,
     }
 }
@@ -4,7 +4,7 @@ use super::conversion::{categorized_files_from_scanned, to_display_metadata};
 use super::load_selected_release;
 use crate::ui::app_service::AppService;
 use bae_core::import::{
-    detect_folder_contents, DetectedCandidate as CoreDetectedCandidate, ScanEvent,
+    detect_folder_contents, DetectedCandidate as CoreDetectedCandidate, ImportFilters, ScanEvent,
 };
 use bae_ui::display_types::{CategorizedFileInfo, FolderMetadata as DisplayFolderMetadata};
 use bae_ui::stores::AppStateStoreExt;
@@ -17,6 +17,7 @@ use tracing::{info, warn};
 pub fn detect_candidate_locally(
     candidate: &CoreDetectedCandidate,
     imgs: &bae_core::image_server::ImageServerHandle,
+    filters: &ImportFilters,
 ) -> Result<(CategorizedFileInfo, DisplayFolderMetadata), String> {
     let files = categorized_files_from_scanned(&candidate.files, imgs);
 
@@ -25,7 +26,7 @@ pub fn detect_candidate_locally(
         candidate.name, candidate.path
     );
 
-    let folder_contents = detect_folder_contents(candidate.path.clone())
+    let folder_contents = detect_folder_contents(candidate.path.clone(), filters)
         .map_err(|e| format!("Failed to detect folder contents: {}", e))?;
     let core_metadata = folder_contents.metadata;
 
@@ -62,8 +63,9 @@ pub async fn consume_scan_events(app: AppService, mut rx: broadcast::Receiver<Sc
                         continue;
                     }
 
+                    let filters = app.import_handle.import_filters();
                     let (files, metadata) =
-                        match detect_candidate_locally(&candidate, &app.image_server) {
+                        match detect_candidate_locally(&candidate, &app.image_server, &filters) {
                             Ok(result) => result,
                             Err(e) => {
                                 warn!(
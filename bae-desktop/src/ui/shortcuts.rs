@@ -1,7 +1,8 @@
 //! App-level keyboard shortcuts
 //!
 //! Maps Cmd+N (macOS) / Ctrl+N (Windows/Linux) to navigation actions.
-//! Also provides a mechanism for native menus to request navigation.
+//! Also provides a mechanism for native menus to request navigation, and
+//! routes incoming `bae://` deep links.
 
 use crate::ui::app_service::use_app;
 use crate::ui::Route;
@@ -13,6 +14,7 @@ use bae_ui::stores::{
 use dioxus::prelude::*;
 use std::sync::{Mutex, OnceLock};
 use tokio::sync::broadcast;
+use tracing::{info, warn};
 
 /// Navigation actions that can be triggered by shortcuts or menus.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,6 +24,7 @@ pub enum NavAction {
     GoTo(NavTarget),
     GoToNowPlaying,
     ToggleQueueSidebar,
+    TogglePlaybackDiagnostics,
 }
 
 /// Navigation targets for direct routing.
@@ -42,6 +45,29 @@ impl NavTarget {
     }
 }
 
+/// Entity pages reachable through `bae://` URLs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    Album(String),
+    Track(String),
+}
+
+impl DeepLink {
+    /// Parse `bae://album/{id}` or `bae://track/{id}`.
+    pub fn parse(url: &str) -> Option<DeepLink> {
+        let path = url.strip_prefix("bae://")?.trim_end_matches('/');
+        let (kind, id) = path.split_once('/')?;
+        if id.is_empty() || id.contains('/') {
+            return None;
+        }
+        match kind {
+            "album" => Some(DeepLink::Album(id.to_string())),
+            "track" => Some(DeepLink::Track(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
 static NAV_SENDER: OnceLock<broadcast::Sender<NavAction>> = OnceLock::new();
 
 #[cfg(target_os = "macos")]
@@ -178,6 +204,9 @@ pub fn handle_shortcut(evt: &KeyboardEvent) -> Option<NavAction> {
                 Key::Character(c) if c == "S" => {
                     return Some(NavAction::ToggleQueueSidebar);
                 }
+                Key::Character(c) if c == "D" => {
+                    return Some(NavAction::TogglePlaybackDiagnostics);
+                }
                 _ => {}
             }
         }
@@ -206,7 +235,9 @@ fn execute_nav_action(action: NavAction) {
             let _ = navigator().push(target.to_route());
         }
         // Handled in ShortcutsHandler where we have access to app state
-        NavAction::GoToNowPlaying | NavAction::ToggleQueueSidebar => {}
+        NavAction::GoToNowPlaying
+        | NavAction::ToggleQueueSidebar
+        | NavAction::TogglePlaybackDiagnostics => {}
     }
 }
 
@@ -219,6 +250,7 @@ pub fn ShortcutsHandler(children: Element) -> Element {
         let library_manager = app.library_manager.clone();
         let playback = app.state.playback();
         let mut sidebar_is_open = app.state.ui().sidebar().is_open();
+        let mut diagnostics_open = app.state.ui().diagnostics_open();
         let mut rx = subscribe_nav();
         spawn(async move {
             while let Ok(action) = rx.recv().await {
@@ -231,12 +263,32 @@ pub fn ShortcutsHandler(children: Element) -> Element {
                         let current = *sidebar_is_open.read();
                         sidebar_is_open.set(!current);
                     }
+                    NavAction::TogglePlaybackDiagnostics => {
+                        let current = *diagnostics_open.read();
+                        diagnostics_open.set(!current);
+                    }
                     other => execute_nav_action(other),
                 }
             }
         });
     });
 
+    // Route URLs from Apple Events, CLI arguments and Spotlight results
+    use_hook(|| {
+        let library_manager = app.library_manager.clone();
+        let mut rx = subscribe_url();
+        // Drain any URL that arrived before this subscriber existed (cold launch)
+        let buffered = take_buffered_url();
+        spawn(async move {
+            if let Some(url) = buffered {
+                open_url(&library_manager, url).await;
+            }
+            while let Ok(url) = rx.recv().await {
+                open_url(&library_manager, url).await;
+            }
+        });
+    });
+
     // "/" shortcut: register on document so it works regardless of focus.
     // The div-level onkeydown only fires when a descendant has focus,
     // which often isn't the case (focus defaults to body).
@@ -260,6 +312,7 @@ pub fn ShortcutsHandler(children: Element) -> Element {
     });
 
     let mut sidebar_is_open = app.state.ui().sidebar().is_open();
+    let mut diagnostics_open = app.state.ui().diagnostics_open();
 
     let onkeydown = move |evt: KeyboardEvent| {
         if let Some(action) = handle_shortcut(&evt) {
@@ -273,6 +326,10 @@ pub fn ShortcutsHandler(children: Element) -> Element {
                     let current = *sidebar_is_open.read();
                     sidebar_is_open.set(!current);
                 }
+                NavAction::TogglePlaybackDiagnostics => {
+                    let current = *diagnostics_open.read();
+                    diagnostics_open.set(!current);
+                }
                 other => execute_nav_action(other),
             }
         }
@@ -304,3 +361,65 @@ fn go_to_now_playing(
         });
     }
 }
+
+/// Navigate to the page a `bae://` URL points at.
+async fn open_url(library_manager: &bae_core::library::SharedLibraryManager, url: String) {
+    info!("URL received in app: {url}");
+
+    match DeepLink::parse(&url) {
+        Some(DeepLink::Album(album_id)) => {
+            navigator().push(Route::AlbumDetail {
+                album_id,
+                release_id: String::new(),
+            });
+        }
+        Some(DeepLink::Track(track_id)) => {
+            let library = library_manager.get();
+            let track = match library.get_track(&track_id).await {
+                Ok(Some(track)) => track,
+                Ok(None) => {
+                    warn!("Deep link to unknown track {track_id}");
+                    return;
+                }
+                Err(e) => {
+                    warn!("Failed to load track {track_id}: {e}");
+                    return;
+                }
+            };
+            if let Ok(album_id) = library.get_album_id_for_release(&track.release_id).await {
+                navigator().push(Route::AlbumDetail {
+                    album_id,
+                    release_id: track.release_id,
+                });
+            }
+        }
+        None => {
+            // TODO: Parse bae://share/{token} and trigger import
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_entity_deep_links() {
+        assert_eq!(
+            DeepLink::parse("bae://album/abc-123"),
+            Some(DeepLink::Album("abc-123".to_string()))
+        );
+        assert_eq!(
+            DeepLink::parse("bae://track/t1/"),
+            Some(DeepLink::Track("t1".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_other_urls() {
+        assert_eq!(DeepLink::parse("bae://share/token"), None);
+        assert_eq!(DeepLink::parse("bae://album/"), None);
+        assert_eq!(DeepLink::parse("bae://album/a/b"), None);
+        assert_eq!(DeepLink::parse("https://album/abc"), None);
+    }
+}
@@ -146,6 +146,10 @@ unsafe fn register_menu_handler_class() {
             request_nav(NavAction::ToggleQueueSidebar);
         }
 
+        extern "C" fn toggle_playback_diagnostics(_this: &Object, _cmd: Sel, _sender: id) {
+            request_nav(NavAction::TogglePlaybackDiagnostics);
+        }
+
         extern "C" fn toggle_repeat_mode(_this: &Object, _cmd: Sel, _sender: id) {
             let current = REPEAT_MODE.load(std::sync::atomic::Ordering::SeqCst);
             let next = match current {
@@ -215,6 +219,10 @@ unsafe fn register_menu_handler_class() {
             sel!(toggleQueueSidebar:),
             toggle_queue_sidebar as extern "C" fn(&Object, Sel, id),
         );
+        decl.add_method(
+            sel!(togglePlaybackDiagnostics:),
+            toggle_playback_diagnostics as extern "C" fn(&Object, Sel, id),
+        );
         decl.add_method(
             sel!(toggleRepeatMode:),
             toggle_repeat_mode as extern "C" fn(&Object, Sel, id),
@@ -805,6 +813,16 @@ unsafe fn setup_app_menu_inner(app: id) {
     let _: () = msg_send![toggle_sidebar_item, setKeyEquivalentModifierMask: command_shift];
     view_menu.addItem_(toggle_sidebar_item);
 
+    let diagnostics_item = NSMenuItem::alloc(nil).initWithTitle_action_keyEquivalent_(
+        NSString::alloc(nil).init_str("Playback Diagnostics"),
+        selector("togglePlaybackDiagnostics:"),
+        NSString::alloc(nil).init_str("d"),
+    );
+    diagnostics_item.autorelease();
+    let _: () = msg_send![diagnostics_item, setTarget: menu_handler];
+    let _: () = msg_send![diagnostics_item, setKeyEquivalentModifierMask: command_shift];
+    view_menu.addItem_(diagnostics_item);
+
     let view_menu_item = NSMenuItem::new(nil);
     view_menu_item.autorelease();
     view_menu_item.setSubmenu_(view_menu);
@@ -4,14 +4,17 @@ use chacha20poly1305::aead::generic_array::GenericArray;
 use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305};
 use hkdf::Hkdf;
 use hmac::{Hmac, Mac};
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
 
 uniffi::setup_scaffolding!();
 
 const NONCE_SIZE: usize = 24;
 const TAG_SIZE: usize = 16;
 const CHUNK_SIZE: usize = 65536;
-const ENCRYPTED_CHUNK_SIZE: usize = CHUNK_SIZE + TAG_SIZE;
+const MAX_CHUNK_SIZE: usize = 1 << 24;
+const HEADER_SIZE: usize = 8;
+const CONTAINER_MAGIC: &[u8; 6] = b"baeenc";
+const CONTAINER_VERSION: u8 = 2;
 
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 pub enum CryptoError {
@@ -29,16 +32,24 @@ pub struct DeviceLinkPayload {
     pub encryption_key: Vec<u8>,
     pub signing_key: Vec<u8>,
     pub library_id: String,
+    /// One-time ticket to redeem before joining (hex)
+    pub nonce: String,
+    /// RFC 3339. The caller refuses the link once this has passed.
+    pub expires_at: String,
 }
 
 /// Decrypt an entire file encrypted with bae's chunked XChaCha20-Poly1305 format.
 ///
-/// Format: [24-byte base_nonce][encrypted_chunk_0][encrypted_chunk_1]...
-/// Each chunk: up to 65536 bytes plaintext + 16-byte Poly1305 auth tag.
+/// Format: [header][24-byte base_nonce][encrypted_chunk_0][encrypted_chunk_1]...
+/// The header is absent in legacy files, which always use 65536-byte chunks.
+/// Each chunk: up to chunk size bytes plaintext + 16-byte Poly1305 auth tag.
 #[uniffi::export]
 pub fn decrypt_file(ciphertext: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, CryptoError> {
     let key = validate_key(&key)?;
     let cipher = XChaCha20Poly1305::new(GenericArray::from_slice(&key));
+    let (header_len, chunk_size) = parse_header(&ciphertext)?;
+    let ciphertext = &ciphertext[header_len..];
+    let encrypted_chunk_size = chunk_size + TAG_SIZE;
 
     if ciphertext.len() < NONCE_SIZE {
         return Err(CryptoError::Decryption {
@@ -54,18 +65,18 @@ pub fn decrypt_file(ciphertext: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, Crypto
 
     let data = &ciphertext[NONCE_SIZE..];
     let total_len = data.len();
-    let num_full_chunks = total_len / ENCRYPTED_CHUNK_SIZE;
-    let remainder = total_len % ENCRYPTED_CHUNK_SIZE;
+    let num_full_chunks = total_len / encrypted_chunk_size;
+    let remainder = total_len % encrypted_chunk_size;
     let total_chunks = num_full_chunks + if remainder > 0 { 1 } else { 0 };
 
     let mut plaintext = Vec::new();
 
     for i in 0..total_chunks {
-        let chunk_start = i * ENCRYPTED_CHUNK_SIZE;
+        let chunk_start = i * encrypted_chunk_size;
         let chunk_end = if i == total_chunks - 1 && remainder > 0 {
             chunk_start + remainder
         } else {
-            chunk_start + ENCRYPTED_CHUNK_SIZE
+            chunk_start + encrypted_chunk_size
         };
 
         let chunk_data = &data[chunk_start..chunk_end];
@@ -87,7 +98,8 @@ pub fn decrypt_file(ciphertext: Vec<u8>, key: Vec<u8>) -> Result<Vec<u8>, Crypto
 
 /// Decrypt a single chunk from bae's chunked encrypted format.
 ///
-/// `ciphertext` must include the 24-byte nonce header followed by all encrypted chunks.
+/// `ciphertext` must include the container header (if any) and 24-byte nonce,
+/// followed by all encrypted chunks.
 /// `chunk_index` is the zero-based chunk to decrypt.
 #[uniffi::export]
 pub fn decrypt_chunk(
@@ -98,6 +110,9 @@ pub fn decrypt_chunk(
     let key = validate_key(&key)?;
     let cipher = XChaCha20Poly1305::new(GenericArray::from_slice(&key));
     let chunk_index = chunk_index as usize;
+    let (header_len, chunk_size) = parse_header(&ciphertext)?;
+    let ciphertext = &ciphertext[header_len..];
+    let encrypted_chunk_size = chunk_size + TAG_SIZE;
 
     if ciphertext.len() < NONCE_SIZE {
         return Err(CryptoError::Decryption {
@@ -113,8 +128,8 @@ pub fn decrypt_chunk(
 
     let data = &ciphertext[NONCE_SIZE..];
     let total_len = data.len();
-    let num_full_chunks = total_len / ENCRYPTED_CHUNK_SIZE;
-    let remainder = total_len % ENCRYPTED_CHUNK_SIZE;
+    let num_full_chunks = total_len / encrypted_chunk_size;
+    let remainder = total_len % encrypted_chunk_size;
     let total_chunks = num_full_chunks + if remainder > 0 { 1 } else { 0 };
 
     if chunk_index >= total_chunks {
@@ -126,11 +141,11 @@ pub fn decrypt_chunk(
         });
     }
 
-    let chunk_start = chunk_index * ENCRYPTED_CHUNK_SIZE;
+    let chunk_start = chunk_index * encrypted_chunk_size;
     let chunk_end = if chunk_index == total_chunks - 1 && remainder > 0 {
         chunk_start + remainder
     } else {
-        chunk_start + ENCRYPTED_CHUNK_SIZE
+        chunk_start + encrypted_chunk_size
     };
 
     let chunk_data = &data[chunk_start..chunk_end];
@@ -158,24 +173,128 @@ pub fn derive_release_key(
     release_id: String,
 ) -> Result<Vec<u8>, CryptoError> {
     let key = validate_key(&master_key)?;
+    Ok(release_key(&release_hkdf(&key), &release_id))
+}
 
-    let mut mac =
-        <Hmac<Sha256> as Mac>::new_from_slice(&key).expect("HMAC accepts any key length");
-    mac.update(b"bae-hkdf-salt-v1");
-    let salt = mac.finalize().into_bytes();
+/// Derive release keys for many releases in one call, in the order given.
+///
+/// Same keys as `derive_release_key`, but the salt is computed once and
+/// syncing thousands of releases costs one FFI round trip.
+#[uniffi::export]
+pub fn derive_release_keys(
+    master_key: Vec<u8>,
+    release_ids: Vec<String>,
+) -> Result<Vec<Vec<u8>>, CryptoError> {
+    let key = validate_key(&master_key)?;
+    let hk = release_hkdf(&key);
+    Ok(release_ids.iter().map(|id| release_key(&hk, id)).collect())
+}
 
-    let hk = Hkdf::<Sha256>::new(Some(&salt), &key);
-    let info = format!("bae-release-v1:{release_id}");
-    let mut okm = [0u8; 32];
-    hk.expand(info.as_bytes(), &mut okm)
-        .expect("32 bytes is a valid HKDF output length");
+/// Keys for decrypting library images, one per entry in `release_ids`.
+///
+/// Images that belong to a release are encrypted with that release's key;
+/// the rest (`None`) use the master key, as in bae-core's cloud home bucket.
+#[uniffi::export]
+pub fn derive_image_keys(
+    master_key: Vec<u8>,
+    release_ids: Vec<Option<String>>,
+) -> Result<Vec<Vec<u8>>, CryptoError> {
+    let key = validate_key(&master_key)?;
+    let hk = release_hkdf(&key);
+    Ok(release_ids
+        .iter()
+        .map(|id| match id {
+            Some(id) => release_key(&hk, id),
+            None => key.to_vec(),
+        })
+        .collect())
+}
 
-    Ok(okm.to_vec())
+/// One file in a bundle downloaded from the proxy's `/bundle` endpoint.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, uniffi::Record)]
+pub struct BundleEntry {
+    pub key: String,
+    /// From the start of the bundle's data section
+    pub offset: u64,
+    pub size: u64,
+    /// Hex SHA-256 of the encrypted bytes
+    pub sha256: String,
+    pub chunk_count: u64,
+}
+
+/// A bundle's manifest, plus where its data section starts.
+#[derive(Debug, uniffi::Record)]
+pub struct BundleManifest {
+    pub data_offset: u64,
+    pub files: Vec<BundleEntry>,
+}
+
+/// Read the manifest at the start of a bundle.
+///
+/// Bundle layout: `[u32 BE manifest length][manifest JSON][file 0][file 1]...`.
+/// Only the leading bytes are needed, so callers can pass the first few KB of
+/// a bundle still being written to disk.
+#[uniffi::export]
+pub fn parse_bundle_manifest(bundle_head: Vec<u8>) -> Result<BundleManifest, CryptoError> {
+    #[derive(serde::Deserialize)]
+    struct RawManifest {
+        files: Vec<BundleEntry>,
+    }
+
+    let manifest_len = bundle_head
+        .get(..4)
+        .map(|len| u32::from_be_bytes(len.try_into().unwrap()) as usize)
+        .ok_or_else(|| CryptoError::InvalidInput {
+            msg: "bundle too short for manifest length".to_string(),
+        })?;
+    let json = bundle_head
+        .get(4..4 + manifest_len)
+        .ok_or_else(|| CryptoError::InvalidInput {
+            msg: format!("bundle too short for {manifest_len}-byte manifest"),
+        })?;
+    let raw: RawManifest =
+        serde_json::from_slice(json).map_err(|e| CryptoError::InvalidInput {
+            msg: format!("invalid bundle manifest: {e}"),
+        })?;
+
+    Ok(BundleManifest {
+        data_offset: (4 + manifest_len) as u64,
+        files: raw.files,
+    })
+}
+
+/// Check that a file cut out of a bundle is complete and intact: its size,
+/// SHA-256 and encrypted chunk count must all match the manifest entry.
+#[uniffi::export]
+pub fn verify_bundle_file(data: Vec<u8>, entry: BundleEntry) -> Result<(), CryptoError> {
+    let invalid = |msg: String| Err(CryptoError::InvalidInput { msg });
+
+    if data.len() as u64 != entry.size {
+        return invalid(format!(
+            "{}: expected {} bytes, got {}",
+            entry.key,
+            entry.size,
+            data.len()
+        ));
+    }
+    if hex::encode(Sha256::digest(&data)) != entry.sha256 {
+        return invalid(format!("{}: SHA-256 mismatch", entry.key));
+    }
+    let (header_len, chunk_size) = parse_header(&data)?;
+    let body_len = data.len().saturating_sub(header_len + NONCE_SIZE);
+    let chunks = body_len.div_ceil(chunk_size + TAG_SIZE) as u64;
+    if chunks != entry.chunk_count {
+        return invalid(format!(
+            "{}: expected {} chunks, got {}",
+            entry.key, entry.chunk_count, chunks
+        ));
+    }
+    Ok(())
 }
 
 /// Parse a device link JSON payload (scanned from QR code).
 ///
-/// JSON format: {"proxy_url":"...","encryption_key":"<base64url>","signing_key":"<base64url>","library_id":"..."}
+/// JSON format: {"proxy_url":"...","encryption_key":"<base64url>","signing_key":"<base64url>","library_id":"...","nonce":"...","expires_at":"<RFC 3339>"}
 #[uniffi::export]
 pub fn parse_device_link(json: String) -> Result<DeviceLinkPayload, CryptoError> {
     #[derive(serde::Deserialize)]
@@ -184,6 +303,8 @@ pub fn parse_device_link(json: String) -> Result<DeviceLinkPayload, CryptoError>
         encryption_key: String,
         signing_key: String,
         library_id: String,
+        nonce: String,
+        expires_at: String,
     }
 
     let raw: RawPayload =
@@ -226,15 +347,56 @@ pub fn parse_device_link(json: String) -> Result<DeviceLinkPayload, CryptoError>
         encryption_key,
         signing_key,
         library_id: raw.library_id,
+        nonce: raw.nonce,
+        expires_at: raw.expires_at,
     })
 }
 
+/// Read the optional container header: returns (header length, chunk size).
+///
+/// Versioned files start with "baeenc", a version byte, and log2 of the chunk
+/// size. Anything else is a legacy file with 65536-byte chunks.
+fn parse_header(data: &[u8]) -> Result<(usize, usize), CryptoError> {
+    if data.len() < HEADER_SIZE || !data.starts_with(CONTAINER_MAGIC) {
+        return Ok((0, CHUNK_SIZE));
+    }
+    if data[6] != CONTAINER_VERSION {
+        return Err(CryptoError::Decryption {
+            msg: format!("unsupported container version {}", data[6]),
+        });
+    }
+    let chunk_size = 1usize
+        .checked_shl(data[7] as u32)
+        .filter(|size| (CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(size))
+        .ok_or_else(|| CryptoError::Decryption {
+            msg: format!("invalid chunk size 2^{}", data[7]),
+        })?;
+    Ok((HEADER_SIZE, chunk_size))
+}
+
 fn validate_key(key: &[u8]) -> Result<[u8; 32], CryptoError> {
     key.try_into().map_err(|_| CryptoError::InvalidKey {
         msg: format!("key must be 32 bytes, got {}", key.len()),
     })
 }
 
+/// HKDF over the master key with bae-core's salt, ready to expand release keys.
+fn release_hkdf(key: &[u8; 32]) -> Hkdf<Sha256> {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(b"bae-hkdf-salt-v1");
+    let salt = mac.finalize().into_bytes();
+    Hkdf::<Sha256>::new(Some(&salt), key)
+}
+
+fn release_key(hk: &Hkdf<Sha256>, release_id: &str) -> Vec<u8> {
+    let info = format!("bae-release-v1:{release_id}");
+    let mut okm = [0u8; 32];
+    hk.expand(info.as_bytes(), &mut okm)
+        .expect("32 bytes is a valid HKDF output length");
+    okm.to_vec()
+}
+
 /// Derive chunk nonce: base_nonce XOR chunk_index (little-endian in first 8 bytes).
 fn chunk_nonce(base_nonce: &[u8; NONCE_SIZE], chunk_index: u64) -> [u8; NONCE_SIZE] {
     let mut nonce = *base_nonce;
@@ -251,6 +413,8 @@ mod tests {
     use chacha20poly1305::aead::OsRng;
     use chacha20poly1305::AeadCore;
 
+    const ENCRYPTED_CHUNK_SIZE: usize = CHUNK_SIZE + TAG_SIZE;
+
     fn test_key() -> Vec<u8> {
         vec![
             0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
@@ -263,9 +427,21 @@ mod tests {
     /// This is the reference implementation for tests -- produces data that
     /// decrypt_file and decrypt_chunk must be able to handle.
     fn encrypt_chunked(key: &[u8; 32], plaintext: &[u8]) -> Vec<u8> {
+        encrypt_with_chunk_size(key, plaintext, CHUNK_SIZE)
+    }
+
+    /// Encrypt like bae-core does with a configured chunk size, adding the
+    /// container header for non-default sizes.
+    fn encrypt_with_chunk_size(key: &[u8; 32], plaintext: &[u8], chunk_size: usize) -> Vec<u8> {
         let cipher = XChaCha20Poly1305::new(GenericArray::from_slice(key));
         let base_nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
-        let mut output = base_nonce.to_vec();
+        let mut output = Vec::new();
+        if chunk_size != CHUNK_SIZE {
+            output.extend(CONTAINER_MAGIC);
+            output.push(CONTAINER_VERSION);
+            output.push(chunk_size.trailing_zeros() as u8);
+        }
+        output.extend(base_nonce);
 
         if plaintext.is_empty() {
             let nonce = chunk_nonce(base_nonce.as_slice().try_into().unwrap(), 0);
@@ -275,7 +451,7 @@ mod tests {
             return output;
         }
 
-        for (i, chunk) in plaintext.chunks(CHUNK_SIZE).enumerate() {
+        for (i, chunk) in plaintext.chunks(chunk_size).enumerate() {
             let nonce = chunk_nonce(base_nonce.as_slice().try_into().unwrap(), i as u64);
             let nonce_arr = GenericArray::from_slice(&nonce);
             let ct = cipher.encrypt(nonce_arr, chunk).unwrap();
@@ -521,6 +697,35 @@ mod tests {
         assert!(decrypt_file(ciphertext, wrong_key).is_err());
     }
 
+    #[test]
+    fn derive_release_keys_matches_single_derivation() {
+        let key = test_key();
+        let ids = vec!["rel-aaa".to_string(), "rel-bbb".to_string()];
+        let keys = derive_release_keys(key.clone(), ids.clone()).unwrap();
+        assert_eq!(keys.len(), 2);
+        for (id, derived) in ids.into_iter().zip(keys) {
+            assert_eq!(derived, derive_release_key(key.clone(), id).unwrap());
+        }
+    }
+
+    #[test]
+    fn derive_release_keys_empty_and_invalid_key() {
+        assert!(derive_release_keys(test_key(), vec![]).unwrap().is_empty());
+        assert!(derive_release_keys(vec![0u8; 16], vec!["rel-1".to_string()]).is_err());
+    }
+
+    #[test]
+    fn derive_image_keys_uses_master_key_without_release() {
+        let key = test_key();
+        let keys =
+            derive_image_keys(key.clone(), vec![Some("rel-123".to_string()), None]).unwrap();
+        assert_eq!(
+            keys[0],
+            derive_release_key(key.clone(), "rel-123".to_string()).unwrap()
+        );
+        assert_eq!(keys[1], key);
+    }
+
     // ---- device link parsing tests ----
 
     #[test]
@@ -528,7 +733,7 @@ mod tests {
         let enc_key = [0xAB_u8; 32];
         let sign_key = [0xCD_u8; 64];
         let json = format!(
-            r#"{{"proxy_url":"https://test.bae.fm","encryption_key":"{}","signing_key":"{}","library_id":"lib-abc-123"}}"#,
+            r#"{{"proxy_url":"https://test.bae.fm","encryption_key":"{}","signing_key":"{}","library_id":"lib-abc-123","nonce":"n1","expires_at":"2030-01-01T00:00:00Z"}}"#,
             URL_SAFE_NO_PAD.encode(enc_key),
             URL_SAFE_NO_PAD.encode(sign_key),
         );
@@ -538,6 +743,8 @@ mod tests {
         assert_eq!(payload.encryption_key, enc_key.to_vec());
         assert_eq!(payload.signing_key, sign_key.to_vec());
         assert_eq!(payload.library_id, "lib-abc-123");
+        assert_eq!(payload.nonce, "n1");
+        assert_eq!(payload.expires_at, "2030-01-01T00:00:00Z");
     }
 
     #[test]
@@ -555,7 +762,7 @@ mod tests {
     #[test]
     fn parse_device_link_wrong_encryption_key_length() {
         let json = format!(
-            r#"{{"proxy_url":"x","encryption_key":"{}","signing_key":"{}","library_id":"y"}}"#,
+            r#"{{"proxy_url":"x","encryption_key":"{}","signing_key":"{}","library_id":"y","nonce":"n","expires_at":"t"}}"#,
             URL_SAFE_NO_PAD.encode([0xAA_u8; 16]), // too short
             URL_SAFE_NO_PAD.encode([0xBB_u8; 64]),
         );
@@ -572,7 +779,7 @@ mod tests {
     #[test]
     fn parse_device_link_wrong_signing_key_length() {
         let json = format!(
-            r#"{{"proxy_url":"x","encryption_key":"{}","signing_key":"{}","library_id":"y"}}"#,
+            r#"{{"proxy_url":"x","encryption_key":"{}","signing_key":"{}","library_id":"y","nonce":"n","expires_at":"t"}}"#,
             URL_SAFE_NO_PAD.encode([0xAA_u8; 32]),
             URL_SAFE_NO_PAD.encode([0xBB_u8; 32]), // too short
         );
@@ -589,7 +796,7 @@ mod tests {
     #[test]
     fn parse_device_link_invalid_base64() {
         let json =
-            r#"{"proxy_url":"x","encryption_key":"!!!invalid!!!","signing_key":"AAAA","library_id":"y"}"#;
+            r#"{"proxy_url":"x","encryption_key":"!!!invalid!!!","signing_key":"AAAA","library_id":"y","nonce":"n","expires_at":"t"}"#;
         let result = parse_device_link(json.to_string());
         assert!(result.is_err());
         match result.unwrap_err() {
@@ -676,4 +883,105 @@ mod tests {
             decrypt_file(ct2, key.to_vec()).unwrap(),
         );
     }
+
+    // ---- container header tests ----
+
+    #[test]
+    fn decrypt_large_chunks_with_header() {
+        let key = test_key();
+        let key_arr: [u8; 32] = key.clone().try_into().unwrap();
+        let chunk_size = CHUNK_SIZE * 16;
+        let mut plaintext = vec![0x33u8; chunk_size];
+        plaintext.extend(vec![0x44u8; 100]);
+        let ciphertext = encrypt_with_chunk_size(&key_arr, &plaintext, chunk_size);
+        assert_eq!(&ciphertext[..6], CONTAINER_MAGIC);
+
+        assert_eq!(decrypt_file(ciphertext.clone(), key.clone()).unwrap(), plaintext);
+        assert_eq!(
+            decrypt_chunk(ciphertext.clone(), 0, key.clone()).unwrap(),
+            vec![0x33u8; chunk_size]
+        );
+        assert_eq!(decrypt_chunk(ciphertext, 1, key).unwrap(), vec![0x44u8; 100]);
+    }
+
+    // ---- bundle tests ----
+
+    /// Build a bundle the way bae-proxy does
+    fn bundle_of(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut offset = 0;
+        let entries: Vec<serde_json::Value> = files
+            .iter()
+            .map(|(key, data)| {
+                let entry = serde_json::json!({
+                    "key": key,
+                    "offset": offset,
+                    "size": data.len(),
+                    "sha256": hex::encode(Sha256::digest(data)),
+                    "chunk_count": data.len().saturating_sub(NONCE_SIZE).div_ceil(CHUNK_SIZE + TAG_SIZE),
+                });
+                offset += data.len();
+                entry
+            })
+            .collect();
+        let manifest = serde_json::to_vec(&serde_json::json!({ "files": entries })).unwrap();
+        let mut bundle = (manifest.len() as u32).to_be_bytes().to_vec();
+        bundle.extend(manifest);
+        for (_, data) in files {
+            bundle.extend(data);
+        }
+        bundle
+    }
+
+    #[test]
+    fn bundle_files_verify_and_decrypt() {
+        let key = test_key();
+        let key_arr: [u8; 32] = key.clone().try_into().unwrap();
+        let first = vec![0x55u8; CHUNK_SIZE + 10];
+        let second = b"Low Light".to_vec();
+        let bundle = bundle_of(&[
+            ("storage/ab/cd/file-1", encrypt_chunked(&key_arr, &first)),
+            ("storage/ab/cd/file-2", encrypt_chunked(&key_arr, &second)),
+        ]);
+
+        let manifest = parse_bundle_manifest(bundle.clone()).unwrap();
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(manifest.files[0].chunk_count, 2);
+
+        for (entry, plaintext) in manifest.files.iter().zip([first, second]) {
+            let start = (manifest.data_offset + entry.offset) as usize;
+            let data = bundle[start..start + entry.size as usize].to_vec();
+            verify_bundle_file(data.clone(), entry.clone()).unwrap();
+            assert_eq!(decrypt_file(data, key.clone()).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn truncated_bundle_file_fails_verification() {
+        let key_arr: [u8; 32] = test_key().try_into().unwrap();
+        let bundle = bundle_of(&[(
+            "storage/ab/cd/file-1",
+            encrypt_chunked(&key_arr, &vec![0x66u8; CHUNK_SIZE * 2]),
+        )]);
+        let manifest = parse_bundle_manifest(bundle.clone()).unwrap();
+        let entry = manifest.files[0].clone();
+        let start = manifest.data_offset as usize;
+
+        let truncated = bundle[start..start + entry.size as usize - 1].to_vec();
+        assert!(verify_bundle_file(truncated, entry.clone()).is_err());
+
+        let mut corrupted = bundle[start..].to_vec();
+        corrupted[40] ^= 0xff;
+        assert!(verify_bundle_file(corrupted, entry).is_err());
+
+        assert!(parse_bundle_manifest(bundle[..10].to_vec()).is_err());
+    }
+
+    #[test]
+    fn decrypt_unknown_container_version_fails() {
+        let key = test_key();
+        let key_arr: [u8; 32] = key.clone().try_into().unwrap();
+        let mut ciphertext = encrypt_with_chunk_size(&key_arr, b"secret", CHUNK_SIZE * 2);
+        ciphertext[6] = CONTAINER_VERSION + 1;
+        assert!(decrypt_file(ciphertext, key).is_err());
+    }
 }
@@ -3,6 +3,7 @@ import Foundation
 enum DeviceLinkError: LocalizedError {
     case invalidFormat
     case invalidKey(String)
+    case expired
 
     var errorDescription: String? {
         switch self {
@@ -10,28 +11,45 @@ enum DeviceLinkError: LocalizedError {
             "Invalid device link format"
         case .invalidKey(let msg):
             msg
+        case .expired:
+            "This QR code has expired. Show a new one on your library."
         }
     }
 }
 
+/// A scanned device link: the library credentials plus the one-time ticket
+/// that must be redeemed before joining.
+struct DeviceLink {
+    let credentials: LibraryCredentials
+    let nonce: String
+    let expiresAt: Date
+}
+
 protocol DeviceLinkParser {
-    func parse(json: String) throws -> LibraryCredentials
+    func parse(json: String) throws -> DeviceLink
 }
 
 /// Parses device link JSON directly in Swift.
 /// Will be replaced by BaeCrypto FFI once the Rust library is compiled as an xcframework.
 class DeviceLinkParserImpl: DeviceLinkParser {
-    func parse(json: String) throws -> LibraryCredentials {
+    func parse(json: String) throws -> DeviceLink {
         guard let data = json.data(using: .utf8),
               let obj = try? JSONSerialization.jsonObject(with: data) as? [String: String],
               let proxyUrl = obj["proxy_url"],
               let encKeyB64 = obj["encryption_key"],
               let signKeyB64 = obj["signing_key"],
-              let libraryId = obj["library_id"]
+              let libraryId = obj["library_id"],
+              let nonce = obj["nonce"],
+              let expiresAtString = obj["expires_at"],
+              let expiresAt = ISO8601DateFormatter().date(from: expiresAtString)
         else {
             throw DeviceLinkError.invalidFormat
         }
 
+        guard expiresAt > Date() else {
+            throw DeviceLinkError.expired
+        }
+
         guard let encKey = Data(base64URLEncoded: encKeyB64), encKey.count == 32 else {
             throw DeviceLinkError.invalidKey("encryption key must be 32 bytes")
         }
@@ -40,11 +58,15 @@ class DeviceLinkParserImpl: DeviceLinkParser {
             throw DeviceLinkError.invalidKey("signing key must be 64 bytes")
         }
 
-        return LibraryCredentials(
-            proxyUrl: proxyUrl,
-            encryptionKey: encKey,
-            signingKey: signKey,
-            libraryId: libraryId
+        return DeviceLink(
+            credentials: LibraryCredentials(
+                proxyUrl: proxyUrl,
+                encryptionKey: encKey,
+                signingKey: signKey,
+                libraryId: libraryId
+            ),
+            nonce: nonce,
+            expiresAt: expiresAt
         )
     }
 }
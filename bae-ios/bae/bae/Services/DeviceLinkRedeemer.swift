@@ -0,0 +1,61 @@
+import CryptoKit
+import Foundation
+
+enum DeviceLinkRedeemError: LocalizedError {
+    case alreadyUsed
+    case server(Int)
+
+    var errorDescription: String? {
+        switch self {
+        case .alreadyUsed:
+            "This QR code was already used or has expired. Show a new one on your library."
+        case .server(let status):
+            "Could not reach your library (HTTP \(status))"
+        }
+    }
+}
+
+/// Consumes a device link's one-time ticket on the proxy. The library wrote
+/// `device-links/{nonce}` when it showed the QR code; the proxy deletes it and
+/// answers 200 to exactly one redeem request, 410 to any other.
+class DeviceLinkRedeemer {
+    func redeem(_ link: DeviceLink) async throws {
+        let path = "/device-links/\(link.nonce)/redeem"
+        guard let url = URL(string: link.credentials.proxyUrl + path) else {
+            throw DeviceLinkError.invalidFormat
+        }
+
+        var request = URLRequest(url: url)
+        request.httpMethod = "POST"
+        try sign(&request, path: path, signingKey: link.credentials.signingKey)
+        let redeemStatus = try await status(of: request)
+        if redeemStatus == 410 {
+            throw DeviceLinkRedeemError.alreadyUsed
+        }
+        guard redeemStatus == 200 else {
+            throw DeviceLinkRedeemError.server(redeemStatus)
+        }
+    }
+
+    private func status(of request: URLRequest) async throws -> Int {
+        let (_, response) = try await URLSession.shared.data(for: request)
+        return (response as? HTTPURLResponse)?.statusCode ?? 0
+    }
+
+    /// Proxy auth: an Ed25519 signature over "METHOD\nPATH\nTIMESTAMP".
+    /// The signing key is libsodium's 64 bytes, seed then public key.
+    private func sign(_ request: inout URLRequest, path: String, signingKey: Data) throws {
+        let key = try Curve25519.Signing.PrivateKey(rawRepresentation: signingKey.prefix(32))
+        let timestamp = String(Int(Date().timeIntervalSince1970))
+        let message = "\(request.httpMethod ?? "")\n\(path)\n\(timestamp)"
+        let signature = try key.signature(for: Data(message.utf8))
+
+        request.setValue(hex(signingKey.suffix(32)), forHTTPHeaderField: "X-Bae-Pubkey")
+        request.setValue(timestamp, forHTTPHeaderField: "X-Bae-Timestamp")
+        request.setValue(hex(signature), forHTTPHeaderField: "X-Bae-Signature")
+    }
+
+    private func hex(_ data: Data) -> String {
+        data.map { String(format: "%02x", $0) }.joined()
+    }
+}
@@ -0,0 +1,42 @@
+import CryptoKit
+import Foundation
+
+/// Seven emoji derived from a device link's keys, shown on both devices so the
+/// user can check nobody swapped the QR code. Must match
+/// `short_authentication_string` in bae-core's device_link.rs.
+enum ShortAuthenticationString {
+    static func compute(for link: DeviceLink) -> [(emoji: String, name: String)] {
+        var message = Data("bae device link sas\0".utf8)
+        message.append(Data(link.credentials.libraryId.utf8))
+        message.append(0)
+        message.append(Data(link.nonce.utf8))
+        message.append(0)
+        message.append(link.credentials.encryptionKey)
+        message.append(link.credentials.signingKey.suffix(32))
+        let digest = Array(SHA256.hash(data: message))
+
+        let bits = digest.prefix(6).reduce(UInt64(0)) { ($0 << 8) | UInt64($1) }
+        return (0..<7).map { i in
+            emoji[Int((bits >> UInt64(42 - 6 * i)) & 0x3f)]
+        }
+    }
+
+    private static let emoji: [(emoji: String, name: String)] = [
+        ("🐶", "Dog"), ("🐱", "Cat"), ("🦁", "Lion"), ("🐎", "Horse"),
+        ("🦄", "Unicorn"), ("🐷", "Pig"), ("🐘", "Elephant"), ("🐰", "Rabbit"),
+        ("🐼", "Panda"), ("🐓", "Rooster"), ("🐧", "Penguin"), ("🐢", "Turtle"),
+        ("🐟", "Fish"), ("🐙", "Octopus"), ("🦋", "Butterfly"), ("🌷", "Flower"),
+        ("🌳", "Tree"), ("🌵", "Cactus"), ("🍄", "Mushroom"), ("🌏", "Globe"),
+        ("🌙", "Moon"), ("☁️", "Cloud"), ("🔥", "Fire"), ("🍌", "Banana"),
+        ("🍎", "Apple"), ("🍓", "Strawberry"), ("🌽", "Corn"), ("🍕", "Pizza"),
+        ("🎂", "Cake"), ("❤️", "Heart"), ("😀", "Smiley"), ("🤖", "Robot"),
+        ("🎩", "Hat"), ("👓", "Glasses"), ("🔧", "Spanner"), ("🎅", "Santa"),
+        ("👍", "Thumbs Up"), ("☂️", "Umbrella"), ("⌛", "Hourglass"), ("⏰", "Clock"),
+        ("🎁", "Gift"), ("💡", "Light Bulb"), ("📕", "Book"), ("✏️", "Pencil"),
+        ("📎", "Paperclip"), ("✂️", "Scissors"), ("🔒", "Lock"), ("🔑", "Key"),
+        ("🔨", "Hammer"), ("☎️", "Telephone"), ("🏁", "Flag"), ("🚂", "Train"),
+        ("🚲", "Bicycle"), ("✈️", "Aeroplane"), ("🚀", "Rocket"), ("🏆", "Trophy"),
+        ("⚽", "Ball"), ("🎸", "Guitar"), ("🎺", "Trumpet"), ("🔔", "Bell"),
+        ("⚓", "Anchor"), ("🎧", "Headphones"), ("📁", "Folder"), ("📌", "Pin"),
+    ]
+}
@@ -5,10 +5,21 @@ struct OnboardingView: View {
 
     @State private var showScanner = false
     @State private var error: String?
+    /// Redeemed link waiting for the user to compare emoji with the library
+    @State private var pendingLink: DeviceLink?
 
     private let parser: DeviceLinkParser = DeviceLinkParserImpl()
+    private let redeemer = DeviceLinkRedeemer()
 
     var body: some View {
+        if let link = pendingLink {
+            verification(link)
+        } else {
+            scan
+        }
+    }
+
+    private var scan: some View {
         VStack(spacing: 24) {
             Spacer()
             Text("bae")
@@ -45,13 +56,53 @@ struct OnboardingView: View {
         }
     }
 
+    private func verification(_ link: DeviceLink) -> some View {
+        VStack(spacing: 24) {
+            Spacer()
+            Text("Check the emoji")
+                .font(.title2.bold())
+            Text("Your library shows emoji under the QR code. They should be the same, in the same order.")
+                .font(.body)
+                .foregroundStyle(.secondary)
+                .multilineTextAlignment(.center)
+                .padding(.horizontal, 32)
+            HStack(spacing: 8) {
+                ForEach(Array(ShortAuthenticationString.compute(for: link).enumerated()), id: \.offset) { _, item in
+                    VStack(spacing: 4) {
+                        Text(item.emoji)
+                            .font(.largeTitle)
+                        Text(item.name)
+                            .font(.caption2)
+                            .foregroundStyle(.secondary)
+                    }
+                    .frame(width: 44)
+                }
+            }
+            Spacer()
+            Button("They Match") {
+                pendingLink = nil
+                onLinked(link.credentials)
+            }
+            .buttonStyle(.borderedProminent)
+            .controlSize(.large)
+            Button("They Don't Match", role: .destructive) {
+                pendingLink = nil
+                error = "The emoji didn't match, so this QR code may not be from your library. Show a new one and scan again."
+            }
+            Spacer()
+        }
+    }
+
     private func handleScanned(_ json: String) {
         showScanner = false
-        do {
-            let creds = try parser.parse(json: json)
-            onLinked(creds)
-        } catch {
-            self.error = error.localizedDescription
+        Task {
+            do {
+                let link = try parser.parse(json: json)
+                try await redeemer.redeem(link)
+                pendingLink = link
+            } catch {
+                self.error = error.localizedDescription
+            }
         }
     }
 }
@@ -438,7 +438,7 @@ struct ImportView: View {
             case .complete:
                 Label("Done", systemImage: "checkmark.circle.fill")
                     .foregroundStyle(.green)
-            case let .error(message):
+            case let .error(_, message):
                 Label(message, systemImage: "exclamationmark.triangle.fill")
                     .foregroundStyle(.red)
                     .lineLimit(1)
@@ -1037,7 +1037,7 @@ struct ImportView: View {
                     }
                     .buttonStyle(.borderedProminent)
                     .disabled(true)
-                case let .error(message):
+                case let .error(_, message):
                     Button("Back to Search") {
                         candidateStates[folderPath]?.mode = .identifying
                         candidateStates[folderPath]?.releaseDetail = nil
@@ -2,8 +2,13 @@
 
 use super::framework::{ControlRegistryBuilder, MockPage, MockPanel, Preset};
 use bae_ui::stores::{AlbumDetailState, AlbumDetailStateStoreExt};
-use bae_ui::{Album, AlbumDetailView, Artist, PlaybackDisplay, Release, Track, TrackImportState};
+use bae_ui::{
+    Album, AlbumComment, AlbumDetailView, AlbumNotesEdit, AlbumVersion, Artist, CreditedArtist,
+    CustomTag, MetadataChange, MetadataUpdate, PlaybackDisplay, Release, Track, TrackAudioSource,
+    TrackImportState, TrackMapping, TrackMappingEntry,
+};
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 #[component]
 pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
@@ -26,12 +31,26 @@ pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
             "Multiple",
             vec![("Single", "Single"), ("Multiple", "Multiple")],
         )
+        .enum_control(
+            "versions",
+            "Versions",
+            "Unlinked",
+            vec![("Unlinked", "Unlinked"), ("Linked", "Linked")],
+        )
+        .enum_control(
+            "discs",
+            "Discs",
+            "Single",
+            vec![("Single", "Single"), ("Multiple", "Multiple")],
+        )
         .with_presets(vec![
             Preset::new("Default"),
             Preset::new("Playing").set_string("playback", "Playing"),
             Preset::new("Paused").set_string("playback", "Paused"),
             Preset::new("Loading").set_string("playback", "Loading"),
             Preset::new("Single Release").set_string("releases", "Single"),
+            Preset::new("Linked Versions").set_string("versions", "Linked"),
+            Preset::new("Box Set").set_string("discs", "Multiple"),
         ])
         .build(initial_state);
 
@@ -41,10 +60,46 @@ pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
     // Local state
     let position_ms = use_signal(|| 45_000u64);
     let mut selected_release_id = use_signal(|| Some("release-1".to_string()));
+    let mut track_mapping = use_signal(|| None::<TrackMapping>);
+    let mut musicbrainz_changes = use_signal(|| None::<Vec<MetadataChange>>);
+    let mut custom_tags = use_signal(|| {
+        vec![
+            CustomTag {
+                key: "source".to_string(),
+                value: Some("vinyl rip".to_string()),
+            },
+            CustomTag {
+                key: "needs re-rip".to_string(),
+                value: None,
+            },
+        ]
+    });
+    let mut notes = use_signal(|| Some("Side B skips at 2:10.".to_string()));
+    let mut comments = use_signal(|| {
+        let hours_ago = |h: i64| (chrono::Utc::now() - chrono::Duration::hours(h)).to_rfc3339();
+        vec![
+            AlbumComment {
+                id: "comment-2".to_string(),
+                author_name: "Sam".to_string(),
+                is_own: false,
+                content: "Play track 3 loud.".to_string(),
+                created_at: hours_ago(2),
+            },
+            AlbumComment {
+                id: "comment-1".to_string(),
+                author_name: "Jordan".to_string(),
+                is_own: true,
+                content: "The CD edition sounds better than the digital one.".to_string(),
+                created_at: hours_ago(30),
+            },
+        ]
+    });
 
     // Parse state from registry
     let playback_state = registry.get_string("playback");
     let releases_mode = registry.get_string("releases");
+    let versions_mode = registry.get_string("versions");
+    let discs_mode = registry.get_string("discs");
 
     // Mock data
     let album = Album {
@@ -103,6 +158,34 @@ pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
         all_releases
     };
 
+    let versions = if versions_mode == "Linked" {
+        vec![
+            AlbumVersion {
+                album_id: "album-1".to_string(),
+                title: "Neon Frequencies".to_string(),
+                year: Some(2023),
+                label: None,
+            },
+            AlbumVersion {
+                album_id: "album-1-remaster".to_string(),
+                title: "Neon Frequencies (Remastered)".to_string(),
+                year: Some(2025),
+                label: Some("Remaster".to_string()),
+            },
+        ]
+    } else {
+        vec![]
+    };
+
+    let version_candidates = vec![Album {
+        id: "album-2".to_string(),
+        title: "Neon Frequencies (Live)".to_string(),
+        year: Some(2024),
+        cover_url: None,
+        is_compilation: false,
+        date_added: chrono::Utc::now(),
+    }];
+
     let tracks: Vec<Track> = [
         ("track-1", "Broadcast", 1, 198_000i64),
         ("track-2", "Static Dreams", 2, 245_000),
@@ -114,16 +197,71 @@ pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
         ("track-8", "Sign Off", 8, 356_000),
     ]
     .iter()
-    .map(|(id, title, num, duration)| Track {
-        id: id.to_string(),
-        title: title.to_string(),
-        track_number: Some(*num),
-        disc_number: Some(1),
-        duration_ms: Some(*duration),
-        is_available: true,
-        import_state: TrackImportState::Complete,
+    .map(|(id, title, num, duration)| {
+        // The box set splits the tracks over two discs
+        let (disc_number, track_number) = if discs_mode == "Multiple" && *num > 4 {
+            (2, num - 4)
+        } else {
+            (1, *num)
+        };
+        Track {
+            id: id.to_string(),
+            title: title.to_string(),
+            track_number: Some(track_number),
+            disc_number: Some(disc_number),
+            duration_ms: Some(*duration),
+            is_available: true,
+            import_state: TrackImportState::Complete,
+        }
     })
     .collect();
+    let disc_titles = if discs_mode == "Multiple" {
+        HashMap::from([(2, "Live at Tidewater".to_string())])
+    } else {
+        HashMap::new()
+    };
+
+    // Each track starts out playing its own file
+    let mock_mapping = TrackMapping {
+        release_id: "release-1".to_string(),
+        entries: tracks
+            .iter()
+            .map(|t| TrackMappingEntry {
+                track_id: t.id.clone(),
+                title: t.title.clone(),
+                disc_number: t.disc_number,
+                track_number: t.track_number,
+                source_id: Some(format!("audio-{}", t.id)),
+            })
+            .collect(),
+        sources: tracks
+            .iter()
+            .map(|t| TrackAudioSource {
+                id: format!("audio-{}", t.id),
+                filename: format!("{:02} {}.flac", t.track_number.unwrap_or(0), t.title),
+                duration_ms: t.duration_ms,
+            })
+            .collect(),
+    };
+
+    // What a MusicBrainz refresh would offer
+    let mock_musicbrainz_changes = vec![
+        MetadataChange {
+            update: MetadataUpdate::Year(Some(2022)),
+            current: "2023".to_string(),
+            proposed: "2022".to_string(),
+        },
+        MetadataChange {
+            update: MetadataUpdate::TrackTitle {
+                track_id: "track-6".to_string(),
+                disc_number: Some(1),
+                track_number: Some(6),
+                title: "Airwaves".to_string(),
+            },
+            current: "Airwave".to_string(),
+            proposed: "Airwaves".to_string(),
+        },
+    ];
 
     // Derive count/ids/disc_info before moving tracks
     let track_count = tracks.len();
@@ -133,17 +271,53 @@ pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
         .map(|t| (t.disc_number, t.id.clone()))
         .collect();
 
+    let artist_credit = artists
+        .iter()
+        .cloned()
+        .map(|artist| CreditedArtist {
+            artist,
+            join_phrase: None,
+        })
+        .collect();
+    let track_credits = HashMap::from([(
+        "track-4".to_string(),
+        vec![
+            CreditedArtist {
+                artist: artists[0].clone(),
+                join_phrase: Some(" feat. ".to_string()),
+            },
+            CreditedArtist {
+                artist: Artist {
+                    id: "artist-2".to_string(),
+                    name: "Glass Harbor".to_string(),
+                    image_url: None,
+                },
+                join_phrase: None,
+            },
+        ],
+    )]);
+
     // Create store once, then update when registry values change
     let mut state = use_store(AlbumDetailState::default);
 
     state.set(AlbumDetailState {
         album: Some(album),
         artists,
+        artist_credit,
         tracks,
         track_count,
         track_ids,
         track_disc_info,
+        disc_titles,
+        track_works: HashMap::new(),
+        track_credits,
         releases,
+        versions,
+        version_candidates,
+        custom_tags: custom_tags(),
+        notes: notes(),
+        comments: comments(),
+        can_comment: true,
         files: vec![],
         images: vec![],
         selected_release_id: selected_release_id(),
@@ -156,8 +330,15 @@ pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
         is_unmanaged: false,
         transfer_progress: None,
         transfer_error: None,
+        availability: None,
         remote_covers: vec![],
         loading_remote_covers: false,
+        track_mapping: track_mapping(),
+        track_mapping_error: None,
+        tags_error: None,
+        comment_error: None,
+        musicbrainz_changes: musicbrainz_changes(),
+        musicbrainz_error: None,
         share_error: None,
         share_link_copied: false,
     });
@@ -193,6 +374,10 @@ pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
                 tracks,
                 playback,
                 on_release_select: move |id| selected_release_id.set(Some(id)),
+                on_version_select: |_| {},
+                on_link_version: |_| {},
+                on_unlink_version: |_| {},
+                on_set_version_label: |_| {},
                 on_album_deleted: |_| {},
                 on_export_release: |_| {},
                 on_delete_album: |_| {},
@@ -202,14 +387,46 @@ pub fn AlbumDetailMock(initial_state: Option<String>) -> Element {
                 on_track_resume: |_| {},
                 on_track_add_next: |_| {},
                 on_track_add_to_queue: |_| {},
+                on_track_add_to_playlist: |_| {},
                 on_track_export: |_| {},
                 on_artist_click: |_| {},
                 on_play_album: |_| {},
                 on_add_album_to_queue: |_| {},
                 on_transfer_to_managed: |_| {},
+                on_move_to_cloud: |_| {},
                 on_eject: |_| {},
+                on_cancel_transfer: |_| {},
+                on_keep_downloaded: |_| {},
+                on_fetch_availability: |_| {},
                 on_fetch_remote_covers: |_| {},
                 on_select_cover: |_| {},
+                on_fetch_track_mapping: move |_| track_mapping.set(Some(mock_mapping.clone())),
+                on_save_track_mapping: move |mapping| track_mapping.set(Some(mapping)),
+                on_save_tags: |_| {},
+                on_save_notes: move |edit: AlbumNotesEdit| {
+                    custom_tags.set(edit.tags);
+                    notes.set(Some(edit.notes).filter(|n| !n.is_empty()));
+                },
+                on_add_comment: move |content: String| {
+                    let id = format!("comment-{}", comments.read().len() + 1);
+                    comments
+                        .write()
+                        .insert(
+                            0,
+                            AlbumComment {
+                                id,
+                                author_name: "Jordan".to_string(),
+                                is_own: true,
+                                content,
+                                created_at: chrono::Utc::now().to_rfc3339(),
+                            },
+                        );
+                },
+                on_remove_comment: move |id: String| comments.write().retain(|c| c.id != id),
+                on_fetch_musicbrainz_changes: move |_| {
+                    musicbrainz_changes.set(Some(mock_musicbrainz_changes.clone()))
+                },
+                on_apply_musicbrainz_changes: move |_| musicbrainz_changes.set(None),
                 on_copy_share_link: |_| {},
             }
         }
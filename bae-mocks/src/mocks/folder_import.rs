@@ -2,7 +2,7 @@
 
 use super::framework::{ControlRegistryBuilder, MockPage, MockPanel, Preset};
 use bae_ui::stores::import::{
-    CandidateState, ConfirmPhase, ConfirmingState, IdentifyingState, ImportState,
+    BandcampState, CandidateState, ConfirmPhase, ConfirmingState, IdentifyingState, ImportState,
     ManualSearchState, TabSearchState,
 };
 use bae_ui::{
@@ -543,6 +543,7 @@ pub fn FolderImportMock(initial_state: Option<String>) -> Element {
                     }),
                 selected_cover: selected_cover(),
                 managed: true,
+                move_to_cloud: false,
                 phase,
                 auto_matches: exact_match_candidates.clone(),
                 search_state: mock_search_state,
@@ -596,6 +597,8 @@ pub fn FolderImportMock(initial_state: Option<String>) -> Element {
         current_release_index: 0,
         selected_import_source: ImportSource::Folder,
         cd_toc_info: None,
+        bandcamp: BandcampState::default(),
+        recording_split: None,
     });
 
     let registry_for_search = registry.clone();
@@ -644,6 +647,8 @@ pub fn FolderImportMock(initial_state: Option<String>) -> Element {
                     },
                     text_file_encoding: Some("UTF-8".to_string()),
                     on_folder_select_click: |_| {},
+                    on_archive_select_click: |_| {},
+                    on_network_share_click: |_| {},
                     on_view_change: move |idx| viewing_index.set(idx),
                     on_encoding_change: |_| {},
                     on_skip_detection: |_| {},
@@ -661,10 +666,15 @@ pub fn FolderImportMock(initial_state: Option<String>) -> Element {
                     on_search: move |_| registry_for_search.set_string("search_phase", "Searching".to_string()),
                     on_cancel_search: move |_| registry_for_cancel.set_string("search_phase", "Empty".to_string()),
                     on_manual_confirm: |_| {},
+                    on_import_from_tags: |_| {},
+                    on_split_recording: |_| {},
+                    on_split_cancel: |_| {},
+                    on_split_save: |_| {},
                     on_retry_cover: |_| {},
                     on_retry_discid_lookup: |_| {},
                     on_select_cover: move |cover| selected_cover.set(Some(cover)),
                     on_managed_change: |_| {},
+                    on_move_to_cloud_change: |_| {},
                     on_edit: |_| {},
                     on_confirm: |_| {},
                     on_view_in_library: |_| {},
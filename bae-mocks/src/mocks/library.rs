@@ -50,12 +50,29 @@ pub fn LibraryMock(initial_state: Option<String>) -> Element {
         None
     };
 
+    let recently_played: Vec<String> = albums.iter().take(4).map(|a| a.id.clone()).collect();
+    let recently_added: Vec<String> = albums.iter().rev().take(6).map(|a| a.id.clone()).collect();
+
     // Create store once, then update when registry values change
     let mut state = use_store(LibraryState::default);
 
     state.set(LibraryState {
         albums,
         artists_by_album,
+        composers_by_album: HashMap::new(),
+        version_counts: HashMap::new(),
+        cover_placeholders: HashMap::new(),
+        recently_added,
+        recently_played,
+        filter_genres: vec![
+            "Ambient".to_string(),
+            "Dream Pop".to_string(),
+            "Shoegaze".to_string(),
+        ],
+        filter_decades: vec![1990, 2000, 2010],
+        collections: vec![],
+        collection_ids_by_album: HashMap::new(),
+        cover_upgrades: HashMap::new(),
         loading,
         error,
         active_source: bae_ui::stores::config::LibrarySource::Local,
@@ -71,6 +88,10 @@ pub fn LibraryMock(initial_state: Option<String>) -> Element {
         sort_state.view_mode().set(mode);
     };
 
+    let on_filter_change = move |filter| {
+        sort_state.filter().set(filter);
+    };
+
     let cycle_val = cycle();
 
     rsx! {
@@ -81,10 +102,14 @@ pub fn LibraryMock(initial_state: Option<String>) -> Element {
                 sort_state,
                 on_sort_criteria_change,
                 on_view_mode_change,
+                on_filter_change,
                 on_album_click: |_| {},
                 on_artist_click: |_| {},
                 on_play_album: |_| {},
                 on_add_album_to_queue: |_| {},
+                on_toggle_collection: |_| {},
+                on_create_collection: |_| {},
+                on_delete_collection: |_| {},
                 on_empty_action: |_| {},
             }
         }
@@ -2,13 +2,21 @@
 
 use super::framework::{ControlRegistryBuilder, MockPage, MockPanel};
 use bae_ui::stores::config::{CloudProvider, FollowedLibraryInfo, LibrarySource};
-use bae_ui::stores::{DeviceActivityInfo, Member, MemberRole};
+use bae_ui::stores::{
+    DeviceActivityInfo, Member, MemberRole, PartyInfo, PartyRequestInfo, SyncConflictFieldInfo,
+    SyncConflictInfo,
+};
 use bae_ui::{
-    AboutSectionView, BaeCloudAuthMode, BitTorrentSectionView, BitTorrentSettings,
-    CloudProviderOption, DiscogsSectionView, LibraryInfo, LibrarySectionView, SettingsTab,
-    SettingsView, SubsonicSectionView, SyncSectionView,
+    AboutSectionView, AppPasswordInfo, BaeCloudAuthMode, BitTorrentSectionView, BitTorrentSettings,
+    BrokenAlbumInfo, BrokenFileInfo, BrokenReleaseInfo, CloudProviderOption, DiscogsSectionView,
+    DuplicateGroupInfo, DuplicateReleaseInfo, DuplicatesView, FanartSectionView, GenreCleanupView,
+    GenreInfo, GenreMergeInfo, ImportSectionView, IntegritySummary, IntegrityView, LibraryInfo,
+    LibrarySectionView, MusicBrainzCollectionInfo, MusicBrainzSectionView, PartySectionView,
+    PlaybackSectionView, SettingsTab, SettingsView, SpotifyReportAlbum, SpotifyReportView,
+    StorageUsageInfo, SubsonicSectionView, SyncSectionView,
 };
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 #[component]
 pub fn SettingsMock(initial_state: Option<String>) -> Element {
@@ -19,6 +27,19 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
     let mut discogs_editing = use_signal(|| false);
     let mut discogs_key = use_signal(String::new);
 
+    // fanart.tv state
+    let mut fanart_editing = use_signal(|| false);
+    let mut fanart_key = use_signal(String::new);
+    let mut musicbrainz_linked = use_signal(|| true);
+    let mut musicbrainz_collection = use_signal(|| Some("collection-1".to_string()));
+
+    // Genre cleanup state
+    let mut genre_selected = use_signal(Vec::<String>::new);
+    let mut genre_merge_name = use_signal(String::new);
+
+    // Duplicates state
+    let mut duplicate_groups = use_signal(mock_duplicates);
+
     // Subsonic state
     let mut subsonic_editing = use_signal(|| false);
     let mut subsonic_edit_enabled = use_signal(|| true);
@@ -27,6 +48,18 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
     let mut subsonic_edit_username = use_signal(String::new);
     let mut subsonic_edit_password = use_signal(String::new);
     let mut subsonic_edit_password_confirm = use_signal(String::new);
+    let mut app_password_name = use_signal(String::new);
+    let mut created_app_password = use_signal(|| Option::<String>::None);
+
+    // Playback state
+    let mut pause_on_sleep = use_signal(|| true);
+    let mut pause_on_screen_lock = use_signal(|| false);
+    let mut pause_during_calls = use_signal(|| true);
+    let mut duck_for_other_audio = use_signal(|| false);
+
+    // Party state
+    let mut party = use_signal(|| Option::<PartyInfo>::None);
+    let mut party_require_approval = use_signal(|| true);
 
     rsx! {
         MockPanel {
@@ -39,23 +72,134 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
 
                 match *active_tab.read() {
                     SettingsTab::Library => rsx! {
-                        LibrarySectionView {
-                            libraries: mock_libraries(),
-                            followed_libraries: mock_followed_libraries(),
-                            active_source: LibrarySource::Local,
-                            on_switch: |_| {},
-                            on_create: |_| {},
-                            on_join: |_| {},
-                            on_follow: |_| {},
-                            on_unfollow: |_| {},
-                            on_copy_follow_code: |_| {},
-                            on_switch_source: |_| {},
-                            on_rename: |_| {},
-                            on_remove: |_| {},
-                            show_link_device_button: false,
-                            on_link_device: |_| {},
-                            device_link_qr_svg: None,
-                            on_close_device_link: |_| {},
+                        div { class: "space-y-6",
+                            LibrarySectionView {
+                                libraries: mock_libraries(),
+                                followed_libraries: mock_followed_libraries(),
+                                active_source: LibrarySource::Local,
+                                on_switch: |_| {},
+                                on_create: |_| {},
+                                on_join: |_| {},
+                                on_follow: |_| {},
+                                on_unfollow: |_| {},
+                                on_copy_follow_code: |_| {},
+                                on_switch_source: |_| {},
+                                on_rename: |_| {},
+                                on_remove: |_| {},
+                                show_link_device_button: false,
+                                on_link_device: |_| {},
+                                device_link_qr_svg: None,
+                                device_link_sas: Vec::new(),
+                                on_close_device_link: |_| {},
+                                on_confirm_device_link: |_| {},
+                                export_include_audio: false,
+                                is_exporting: false,
+                                exported_path: None,
+                                export_error: None,
+                                on_export_include_audio_change: |_| {},
+                                on_export: |_| {},
+                                can_rotate_key: true,
+                                key_rotation_progress: None,
+                                key_rotation_error: None,
+                                on_rotate_key: |_| {},
+                                can_use_security_key: true,
+                                security_key_enabled: false,
+                                security_key_busy: false,
+                                security_key_error: None,
+                                on_enable_security_key: |_| {},
+                                on_disable_security_key: |_| {},
+                                can_encrypt_database: true,
+                                database_encrypted: false,
+                                database_encryption_error: None,
+                                on_set_database_encryption: |_| {},
+                            }
+                            DuplicatesView {
+                                groups: duplicate_groups(),
+                                is_scanning: false,
+                                merging: None,
+                                error: None,
+                                on_scan: |_| {},
+                                on_keep: move |(index, release_id): (usize, String)| {
+                                    if let Some(group) = duplicate_groups.write().get_mut(index) {
+                                        group.keep_release_id = release_id;
+                                    }
+                                },
+                                on_merge: move |index: usize| {
+                                    duplicate_groups.write().remove(index);
+                                },
+                            }
+                            IntegrityView {
+                                progress: None,
+                                summary: Some(IntegritySummary {
+                                    releases_checked: 120,
+                                    files_checked: 1480,
+                                    releases_skipped: 0,
+                                }),
+                                albums: mock_broken_albums(),
+                                last_scrub: Some("Oct 12, 2026".to_string()),
+                                scrub_albums: Vec::new(),
+                                error: None,
+                                on_check: |_| {},
+                                on_reimport: |_| {},
+                                on_album_click: |_| {},
+                            }
+                        }
+                    },
+                    SettingsTab::Import => rsx! {
+                        div { class: "space-y-6",
+                            ImportSectionView {
+                                ignore_patterns: vec!["*.m3u".to_string(), "@eaDir".to_string()],
+                                min_audio_duration_secs: Some(5),
+                                is_editing: false,
+                                edit_patterns: String::new(),
+                                edit_min_duration: String::new(),
+                                has_changes: false,
+                                on_edit_start: |_| {},
+                                on_cancel: |_| {},
+                                on_save: |_| {},
+                                on_patterns_change: |_| {},
+                                on_min_duration_change: |_| {},
+                                network_share_url: Some("smb://nas.local/Music".to_string()),
+                                network_share_username: None,
+                                network_share_password_set: false,
+                                is_editing_share: false,
+                                edit_share_url: String::new(),
+                                edit_share_username: String::new(),
+                                edit_share_password: String::new(),
+                                has_share_changes: false,
+                                share_save_error: None,
+                                on_share_edit_start: |_| {},
+                                on_share_cancel: |_| {},
+                                on_share_save: |_| {},
+                                on_share_url_change: |_| {},
+                                on_share_username_change: |_| {},
+                                on_share_password_change: |_| {},
+                                auto_upgrade_covers: false,
+                                on_auto_upgrade_covers_change: |_| {},
+                            }
+                            SpotifyReportView {
+                                input: "Glass Harbor - Pacific Standard\nTidewater - Low Light".to_string(),
+                                is_comparing: false,
+                                error: None,
+                                report: Some(mock_spotify_report()),
+                                searches: HashMap::new(),
+                                on_input_change: |_| {},
+                                on_compare: |_| {},
+                                on_open_album: |_| {},
+                                on_search: |_| {},
+                            }
+                        }
+                    },
+                    SettingsTab::Playback => rsx! {
+                        PlaybackSectionView {
+                            pause_on_sleep: *pause_on_sleep.read(),
+                            pause_on_screen_lock: *pause_on_screen_lock.read(),
+                            pause_during_calls: *pause_during_calls.read(),
+                            duck_for_other_audio: *duck_for_other_audio.read(),
+                            on_pause_on_sleep_change: move |v| pause_on_sleep.set(v),
+                            on_pause_on_screen_lock_change: move |v| pause_on_screen_lock.set(v),
+                            on_pause_during_calls_change: move |v| pause_during_calls.set(v),
+                            on_duck_for_other_audio_change: move |v| duck_for_other_audio.set(v),
                         }
                     },
                     SettingsTab::Sync => rsx! {
@@ -66,12 +210,18 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
                                     device_id: "a1b2c3d4-e5f6-7890-abcd-ef1234567890".to_string(),
                                     last_seq: 42,
                                     last_sync: Some("2026-02-10T11:55:00Z".to_string()),
+                                    revoked: false,
+                                    pending: false,
                                 },
                             ],
+                            on_revoke_device: |_| {},
+                            on_approve_device: |_| {},
                             syncing: false,
                             error: None,
                             user_pubkey: Some("a1b2c3d4e5f67890abcdef1234567890a1b2c3d4e5f67890abcdef1234567890".to_string()),
                             on_copy_pubkey: |_| {},
+                            conflicts: mock_conflicts(),
+                            on_resolve_conflict: |_| {},
                             members: mock_members(),
                             is_owner: true,
                             on_remove_member: |_| {},
@@ -88,6 +238,8 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
                             on_sign_in: |_| {},
                             on_disconnect_provider: |_| {},
                             on_use_icloud: |_| {},
+                            on_use_lan: |_| {},
+                            on_choose_folder: |_| {},
                             // S3 edit state
                             is_editing: false,
                             edit_bucket: String::new(),
@@ -95,6 +247,8 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
                             edit_endpoint: String::new(),
                             edit_access_key: String::new(),
                             edit_secret_key: String::new(),
+                            checking_connection: false,
+                            connection_check: None,
                             on_edit_start: |_| {},
                             on_cancel_edit: |_| {},
                             on_save_config: |_| {},
@@ -103,6 +257,7 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
                             on_endpoint_change: |_| {},
                             on_access_key_change: |_| {},
                             on_secret_key_change: |_| {},
+                            on_check_connection: |_| {},
                             // bae cloud form state
                             bae_cloud_is_editing: false,
                             bae_cloud_mode: BaeCloudAuthMode::SignUp,
@@ -114,6 +269,18 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
                             on_bae_cloud_username_change: |_| {},
                             on_bae_cloud_password_change: |_| {},
                             on_bae_cloud_submit: |_| {},
+                            encrypt_images: true,
+                            on_encrypt_images_change: |_| {},
+                            offload_after_days: None,
+                            on_offload_after_days_change: |_| {},
+                            storage_usage: Some(StorageUsageInfo {
+                                local_bytes: 48_300_000_000,
+                                cloud_bytes: 212_700_000_000,
+                                bytes_uploaded: 3_100_000_000,
+                                bytes_downloaded: 840_000_000,
+                                requests: 4_213,
+                                estimated_monthly_cost: Some(4.96),
+                            }),
                             // Invite
                             show_invite_form: false,
                             invite_pubkey: String::new(),
@@ -130,23 +297,81 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
                             recovery_key: None,
                             on_reveal_recovery_key: |_| {},
                             on_copy_recovery_key: |_| {},
+                            recovery_shares: None,
+                            on_split_recovery_key: |_| {},
+                            on_copy_recovery_shares: |_| {},
+                            recovery_kit_path: None,
+                            recovery_kit_error: None,
+                            on_save_recovery_kit: |_| {},
                         }
                     },
                     SettingsTab::Discogs => rsx! {
-                        DiscogsSectionView {
-                            discogs_configured: true,
-                            discogs_key_value: discogs_key(),
-                            is_editing: *discogs_editing.read(),
-                            is_saving: false,
-                            has_changes: !discogs_key.read().is_empty(),
-                            save_error: None,
-                            on_edit_start: move |_| discogs_editing.set(true),
-                            on_key_change: move |v| discogs_key.set(v),
-                            on_save: move |_| discogs_editing.set(false),
-                            on_cancel: move |_| {
-                                discogs_editing.set(false);
-                                discogs_key.set(String::new());
-                            },
+                        div { class: "space-y-6",
+                            DiscogsSectionView {
+                                discogs_configured: true,
+                                discogs_key_value: discogs_key(),
+                                is_editing: *discogs_editing.read(),
+                                is_saving: false,
+                                has_changes: !discogs_key.read().is_empty(),
+                                save_error: None,
+                                on_edit_start: move |_| discogs_editing.set(true),
+                                on_key_change: move |v| discogs_key.set(v),
+                                on_save: move |_| discogs_editing.set(false),
+                                on_cancel: move |_| {
+                                    discogs_editing.set(false);
+                                    discogs_key.set(String::new());
+                                },
+                            }
+                            FanartSectionView {
+                                fanart_configured: false,
+                                fanart_key_value: fanart_key(),
+                                is_editing: *fanart_editing.read(),
+                                is_saving: false,
+                                has_changes: !fanart_key.read().is_empty(),
+                                save_error: None,
+                                on_edit_start: move |_| fanart_editing.set(true),
+                                on_key_change: move |v| fanart_key.set(v),
+                                on_save: move |_| fanart_editing.set(false),
+                                on_cancel: move |_| {
+                                    fanart_editing.set(false);
+                                    fanart_key.set(String::new());
+                                },
+                            }
+                            MusicBrainzSectionView {
+                                username: musicbrainz_linked().then(|| "glassharbor".to_string()),
+                                collections: mock_musicbrainz_collections(),
+                                collection_id: musicbrainz_collection(),
+                                is_linking: false,
+                                is_syncing: false,
+                                sync_status: None,
+                                error: None,
+                                on_link: move |_| musicbrainz_linked.set(true),
+                                on_unlink: move |_| {
+                                    musicbrainz_linked.set(false);
+                                    musicbrainz_collection.set(None);
+                                },
+                                on_select_collection: move |id| musicbrainz_collection.set(Some(id)),
+                                on_sync_now: |_| {},
+                            }
+                            GenreCleanupView {
+                                genres: mock_genres(),
+                                suggestions: mock_genre_suggestions(),
+                                selected: genre_selected(),
+                                merge_name: genre_merge_name(),
+                                is_merging: false,
+                                error: None,
+                                on_toggle: move |id: String| {
+                                    let mut selected = genre_selected.write();
+                                    if let Some(index) = selected.iter().position(|s| *s == id) {
+                                        selected.remove(index);
+                                    } else {
+                                        selected.push(id);
+                                    }
+                                },
+                                on_merge_name_change: move |v| genre_merge_name.set(v),
+                                on_merge_selected: |_| {},
+                                on_apply_suggestion: |_| {},
+                            }
                         }
                     },
                     SettingsTab::BitTorrent => rsx! {
@@ -188,9 +413,9 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
                         SubsonicSectionView {
                             enabled: true,
                             port: 4533,
-                            auth_enabled: false,
-                            auth_username: None,
-                            auth_password_set: false,
+                            auth_enabled: true,
+                            auth_username: Some("admin".to_string()),
+                            auth_password_set: true,
                             is_editing: *subsonic_editing.read(),
                             edit_enabled: *subsonic_edit_enabled.read(),
                             edit_port: subsonic_edit_port(),
@@ -220,6 +445,49 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
                             on_username_change: move |v| subsonic_edit_username.set(v),
                             on_password_change: move |v| subsonic_edit_password.set(v),
                             on_password_confirm_change: move |v| subsonic_edit_password_confirm.set(v),
+                            app_passwords: mock_app_passwords(),
+                            new_app_password_name: app_password_name(),
+                            created_app_password: created_app_password(),
+                            is_creating_app_password: false,
+                            app_password_error: None,
+                            on_app_password_name_change: move |v| app_password_name.set(v),
+                            on_app_password_create: move |_| {
+                                created_app_password.set(Some("k7mq-x2rd-p9tn-w4hc".to_string()));
+                                app_password_name.set(String::new());
+                            },
+                            on_app_password_revoke: |_| {},
+                            on_created_app_password_dismiss: move |_| created_app_password.set(None),
+                        }
+                    },
+                    SettingsTab::Party => rsx! {
+                        PartySectionView {
+                            server_enabled: true,
+                            server_local_only: false,
+                            party: party(),
+                            join_url: party().map(|p| format!("http://192.168.1.20:4533/party?code={}", p.code)),
+                            require_approval: *party_require_approval.read(),
+                            on_require_approval_change: move |v| {
+                                party_require_approval.set(v);
+                                if let Some(p) = party.write().as_mut() {
+                                    p.require_approval = v;
+                                }
+                            },
+                            on_start: move |_| {
+                                let mut info = mock_party();
+                                info.require_approval = *party_require_approval.read();
+                                party.set(Some(info));
+                            },
+                            on_stop: move |_| party.set(None),
+                            on_approve: move |id: String| {
+                                if let Some(p) = party.write().as_mut() {
+                                    p.pending.retain(|r| r.id != id);
+                                }
+                            },
+                            on_reject: move |id: String| {
+                                if let Some(p) = party.write().as_mut() {
+                                    p.pending.retain(|r| r.id != id);
+                                }
+                            },
                         }
                     },
                     SettingsTab::About => rsx! {
@@ -235,6 +503,48 @@ pub fn SettingsMock(initial_state: Option<String>) -> Element {
     }
 }
 
+fn mock_party() -> PartyInfo {
+    PartyInfo {
+        code: "K7MQ2R".to_string(),
+        require_approval: true,
+        guest_count: 4,
+        pending: vec![
+            PartyRequestInfo {
+                id: "req-1".to_string(),
+                guest_name: "Sam".to_string(),
+                track_title: "Low Light".to_string(),
+                artist_name: "Glass Harbor".to_string(),
+            },
+            PartyRequestInfo {
+                id: "req-2".to_string(),
+                guest_name: "Riley".to_string(),
+                track_title: "Pacific Standard".to_string(),
+                artist_name: "Tidewater".to_string(),
+            },
+        ],
+    }
+}
+
+fn mock_app_passwords() -> Vec<AppPasswordInfo> {
+    let now = chrono::Utc::now();
+    vec![
+        AppPasswordInfo {
+            id: "phone".to_string(),
+            name: "Symfonium on phone".to_string(),
+            created_at: (now - chrono::Duration::days(40)).to_rfc3339(),
+            last_used_at: Some((now - chrono::Duration::minutes(12)).to_rfc3339()),
+            disabled: false,
+        },
+        AppPasswordInfo {
+            id: "car".to_string(),
+            name: "Car stereo".to_string(),
+            created_at: (now - chrono::Duration::days(3)).to_rfc3339(),
+            last_used_at: None,
+            disabled: true,
+        },
+    ]
+}
+
 fn mock_libraries() -> Vec<LibraryInfo> {
     vec![
         LibraryInfo {
@@ -260,6 +570,26 @@ fn mock_followed_libraries() -> Vec<FollowedLibraryInfo> {
     }]
 }
 
+fn mock_conflicts() -> Vec<SyncConflictInfo> {
+    vec![SyncConflictInfo {
+        id: "conflict-1".to_string(),
+        album_title: "Glass Harbor".to_string(),
+        kept_remote: true,
+        fields: vec![
+            SyncConflictFieldInfo {
+                column: "title".to_string(),
+                local: Some("Glass Harbor".to_string()),
+                remote: Some("Glass Harbor (Deluxe)".to_string()),
+            },
+            SyncConflictFieldInfo {
+                column: "year".to_string(),
+                local: Some("2019".to_string()),
+                remote: None,
+            },
+        ],
+    }]
+}
+
 fn mock_members() -> Vec<Member> {
     vec![
         Member {
@@ -274,6 +604,12 @@ fn mock_members() -> Vec<Member> {
             role: MemberRole::Member,
             is_self: false,
         },
+        Member {
+            pubkey: "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef".to_string(),
+            display_name: "0123...cdef".to_string(),
+            role: MemberRole::ReadOnly,
+            is_self: false,
+        },
     ]
 }
 
@@ -315,5 +651,139 @@ fn mock_cloud_options() -> Vec<CloudProviderOption> {
             description: "For Backblaze B2, Wasabi, MinIO, AWS, etc.",
             connected_account: None,
         },
+        CloudProviderOption {
+            provider: CloudProvider::Lan,
+            label: "Local network",
+            description: "Sync with devices on this network, nothing stored online",
+            connected_account: None,
+        },
+        CloudProviderOption {
+            provider: CloudProvider::Folder,
+            label: "Folder",
+            description: "A USB drive or network share, for syncing without the internet",
+            connected_account: None,
+        },
+    ]
+}
+
+fn mock_musicbrainz_collections() -> Vec<MusicBrainzCollectionInfo> {
+    vec![
+        MusicBrainzCollectionInfo {
+            id: "collection-1".to_string(),
+            name: "My Library".to_string(),
+            release_count: 128,
+        },
+        MusicBrainzCollectionInfo {
+            id: "collection-2".to_string(),
+            name: "Wishlist".to_string(),
+            release_count: 12,
+        },
+    ]
+}
+
+fn mock_genres() -> Vec<GenreInfo> {
+    [
+        ("ambient", "Ambient", 7),
+        ("dream pop", "Dream Pop", 4),
+        ("dreampop", "Dreampop", 1),
+        ("hip hop", "Hip-Hop", 5),
+        ("rap", "Rap", 2),
+        ("shoegaze", "Shoegaze", 3),
+    ]
+    .into_iter()
+    .map(|(id, name, album_count)| GenreInfo {
+        id: id.to_string(),
+        name: name.to_string(),
+        album_count,
+    })
+    .collect()
+}
+
+fn mock_genre_suggestions() -> Vec<GenreMergeInfo> {
+    vec![
+        GenreMergeInfo {
+            into: "Dream Pop".to_string(),
+            genre_ids: vec!["dream pop".to_string(), "dreampop".to_string()],
+            names: vec!["Dream Pop".to_string(), "Dreampop".to_string()],
+        },
+        GenreMergeInfo {
+            into: "Hip-Hop".to_string(),
+            genre_ids: vec!["hip hop".to_string(), "rap".to_string()],
+            names: vec!["Hip-Hop".to_string(), "Rap".to_string()],
+        },
+    ]
+}
+
+fn mock_duplicates() -> Vec<DuplicateGroupInfo> {
+    let release = |id: &str, details: &str, storage: &str, play_count| DuplicateReleaseInfo {
+        release_id: id.to_string(),
+        album_title: "Pacific Standard".to_string(),
+        artist_name: "Glass Harbor".to_string(),
+        details: details.to_string(),
+        storage: storage.to_string(),
+        track_count: 10,
+        play_count,
+    };
+    vec![
+        DuplicateGroupInfo {
+            identical_audio: true,
+            releases: vec![
+                release("release-1", "2019 · CD", "Local", 42),
+                release("release-2", "2019 · CD", "Unmanaged", 3),
+            ],
+            keep_release_id: "release-1".to_string(),
+        },
+        DuplicateGroupInfo {
+            identical_audio: false,
+            releases: vec![
+                release("release-3", "Deluxe · 2021 · Digital", "Cloud", 0),
+                release("release-4", "2019 · Vinyl", "Local + Cloud", 7),
+            ],
+            keep_release_id: "release-4".to_string(),
+        },
+    ]
+}
+
+fn mock_broken_albums() -> Vec<BrokenAlbumInfo> {
+    vec![BrokenAlbumInfo {
+        album_id: "album-1".to_string(),
+        album_title: "Low Light".to_string(),
+        artist_name: "Tidewater".to_string(),
+        releases: vec![BrokenReleaseInfo {
+            release_id: "release-1".to_string(),
+            release_name: Some("Remaster".to_string()),
+            file_count: 12,
+            broken_files: vec![
+                BrokenFileInfo {
+                    filename: "03 Pacific Standard.flac".to_string(),
+                    location: "Local".to_string(),
+                    problem: "Missing".to_string(),
+                    detail: String::new(),
+                },
+                BrokenFileInfo {
+                    filename: "07 Glass Harbor.flac".to_string(),
+                    location: "Cloud".to_string(),
+                    problem: "Can't decrypt".to_string(),
+                    detail: "Decryption error".to_string(),
+                },
+            ],
+        }],
+    }]
+}
+
+fn mock_spotify_report() -> Vec<SpotifyReportAlbum> {
+    vec![
+        SpotifyReportAlbum {
+            artist: "Glass Harbor".to_string(),
+            title: "Pacific Standard".to_string(),
+            entries: 12,
+            album_id: Some("album-1".to_string()),
+        },
+        SpotifyReportAlbum {
+            artist: "Tidewater".to_string(),
+            title: "Low Light".to_string(),
+            entries: 3,
+            album_id: None,
+        },
     ]
 }
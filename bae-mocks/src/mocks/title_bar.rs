@@ -143,11 +143,13 @@ fn mock_search_results() -> GroupedSearchResults {
                 id: "a1".to_string(),
                 name: "Glass Harbor".to_string(),
                 album_count: 2,
+                image_url: None,
             },
             ArtistResult {
                 id: "a2".to_string(),
                 name: "Apartment Garden".to_string(),
                 album_count: 2,
+                image_url: None,
             },
         ],
         albums: vec![
@@ -3,8 +3,9 @@
 use crate::demo_data;
 use crate::Route;
 use bae_ui::stores::{AlbumDetailState, AlbumDetailStateStoreExt};
-use bae_ui::{AlbumDetailView, BackButton, ErrorDisplay, PlaybackDisplay};
+use bae_ui::{AlbumDetailView, BackButton, CreditedArtist, ErrorDisplay, PlaybackDisplay};
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 #[component]
 pub fn AlbumDetail(album_id: String) -> Element {
@@ -26,12 +27,29 @@ pub fn AlbumDetail(album_id: String) -> Element {
     // Create state store for lens support
     let state = use_store(move || AlbumDetailState {
         album,
+        artist_credit: artists
+            .iter()
+            .cloned()
+            .map(|artist| CreditedArtist {
+                artist,
+                join_phrase: None,
+            })
+            .collect(),
         artists,
         tracks,
         track_count,
         track_ids,
         track_disc_info,
+        disc_titles: HashMap::new(),
+        track_works: HashMap::new(),
+        track_credits: HashMap::new(),
         releases,
+        versions: vec![],
+        version_candidates: vec![],
+        custom_tags: vec![],
+        notes: None,
+        comments: vec![],
+        can_comment: false,
         files: vec![],
         images: vec![],
         selected_release_id,
@@ -44,8 +62,15 @@ pub fn AlbumDetail(album_id: String) -> Element {
         is_unmanaged: false,
         transfer_progress: None,
         transfer_error: None,
+        availability: None,
         remote_covers: vec![],
         loading_remote_covers: false,
+        track_mapping: None,
+        track_mapping_error: None,
+        tags_error: None,
+        comment_error: None,
+        musicbrainz_changes: None,
+        musicbrainz_error: None,
         share_error: None,
         share_link_copied: false,
     });
@@ -66,6 +91,12 @@ pub fn AlbumDetail(album_id: String) -> Element {
                 tracks,
                 playback: PlaybackDisplay::Stopped,
                 on_release_select: |_release_id: String| {},
+                on_version_select: move |album_id: String| {
+                    navigator().push(Route::AlbumDetail { album_id });
+                },
+                on_link_version: |_| {},
+                on_unlink_version: |_| {},
+                on_set_version_label: |_| {},
                 on_album_deleted: |_| {},
                 on_export_release: |_| {},
                 on_delete_album: |_| {},
@@ -75,6 +106,7 @@ pub fn AlbumDetail(album_id: String) -> Element {
                 on_track_resume: |_| {},
                 on_track_add_next: |_| {},
                 on_track_add_to_queue: |_| {},
+                on_track_add_to_playlist: |_| {},
                 on_track_export: |_| {},
                 on_artist_click: move |artist_id: String| {
                     navigator().push(Route::ArtistDetail { artist_id });
@@ -82,9 +114,21 @@ pub fn AlbumDetail(album_id: String) -> Element {
                 on_play_album: |_| {},
                 on_add_album_to_queue: |_| {},
                 on_transfer_to_managed: |_| {},
+                on_move_to_cloud: |_| {},
                 on_eject: |_| {},
+                on_cancel_transfer: |_| {},
+                on_keep_downloaded: |_| {},
+                on_fetch_availability: |_| {},
                 on_fetch_remote_covers: |_| {},
                 on_select_cover: |_| {},
+                on_fetch_track_mapping: |_| {},
+                on_save_track_mapping: |_| {},
+                on_save_tags: |_| {},
+                on_save_notes: |_| {},
+                on_add_comment: |_| {},
+                on_remove_comment: |_| {},
+                on_fetch_musicbrainz_changes: |_| {},
+                on_apply_musicbrainz_changes: |_| {},
                 on_copy_share_link: |_| {},
             }
         } else {
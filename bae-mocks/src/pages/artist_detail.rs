@@ -35,6 +35,7 @@ pub fn ArtistDetail(artist_id: ReadSignal<String>) -> Element {
     let state = use_store(|| ArtistDetailState {
         artist,
         albums: artist_albums,
+        appearances: vec![],
         artists_by_album: artists_by_album.clone(),
         loading: false,
         error: None,
@@ -1,8 +1,9 @@
 //! Import page
 
-use bae_ui::stores::import::ImportState;
+use bae_ui::stores::import::{BandcampState, ImportState};
 use bae_ui::{
-    CdDriveStatus, CdSelectorView, ImportSource, ImportView, TorrentInputMode, TorrentInputView,
+    BandcampCollectionView, CdDriveStatus, CdSelectorView, ImportSource, ImportView,
+    TorrentInputMode, TorrentInputView,
 };
 use dioxus::prelude::*;
 
@@ -33,6 +34,9 @@ pub fn Import() -> Element {
                 ImportSource::Cd => rsx! {
                     CdImportDemo {}
                 },
+                ImportSource::Bandcamp => rsx! {
+                    BandcampImportDemo {}
+                },
             }
         }
     }
@@ -68,3 +72,16 @@ pub fn CdImportDemo() -> Element {
         CdSelectorView { status: CdDriveStatus::NoDisc, on_rip_click: |_| {} }
     }
 }
+
+#[component]
+pub fn BandcampImportDemo() -> Element {
+    rsx! {
+        BandcampCollectionView {
+            state: BandcampState::default(),
+            on_sign_in: |_| {},
+            on_sign_out: |_| {},
+            on_refresh: |_| {},
+            on_import: |_| {},
+        }
+    }
+}
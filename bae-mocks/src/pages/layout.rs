@@ -2,11 +2,13 @@
 
 use crate::demo_data;
 use crate::Route;
-use bae_ui::stores::{PlaybackStatus, PlaybackUiState, SidebarState, SidebarStateStoreExt};
+use bae_ui::stores::{
+    PlaybackStatus, PlaybackUiState, PlaybackUiStateStoreExt, SidebarState, SidebarStateStoreExt,
+};
 use bae_ui::{
     ActiveImport, AlbumResult, AppLayoutView, ArtistResult, GroupedSearchResults, ImportStatus,
-    ImportsDropdownView, NavItem, NowPlayingBarView, QueueItem, QueueSidebarView, SearchAction,
-    TitleBarView, Track, TrackImportState,
+    ImportsDropdownView, NavItem, NowPlayingBarView, PlayHistoryItem, QueueItem, QueueSidebarView,
+    SearchAction, TitleBarView, Track, TrackImportState,
 };
 use dioxus::prelude::*;
 
@@ -85,6 +87,22 @@ fn mock_queue() -> Vec<QueueItem> {
     ]
 }
 
+fn mock_history() -> Vec<PlayHistoryItem> {
+    let now = chrono::Utc::now();
+    vec![
+        PlayHistoryItem {
+            id: "history-1".to_string(),
+            item: mock_queue().remove(1),
+            played_at: (now - chrono::Duration::minutes(4)).to_rfc3339(),
+        },
+        PlayHistoryItem {
+            id: "history-2".to_string(),
+            item: mock_queue().remove(0),
+            played_at: (now - chrono::Duration::hours(3)).to_rfc3339(),
+        },
+    ]
+}
+
 /// Layout component wrapping shared AppLayoutView
 #[component]
 pub fn DemoLayout() -> Element {
@@ -118,12 +136,18 @@ pub fn DemoLayout() -> Element {
         artist_id: Some("artist-1".to_string()),
         cover_url: Some("/covers/the-midnight-signal_neon-frequencies.png".to_string()),
         playback_error: None,
+        warming_up: None,
         repeat_mode: Default::default(),
+        radio_enabled: false,
         volume: 0.75,
+        history: mock_history(),
     });
 
     // Create sidebar store
-    let sidebar_store = use_store(|| SidebarState { is_open: false });
+    let sidebar_store = use_store(|| SidebarState {
+        is_open: false,
+        show_history: false,
+    });
 
     // Mock search - filter albums and artists by query
     let search_results = {
@@ -150,6 +174,7 @@ pub fn DemoLayout() -> Element {
                             id: artist.id.clone(),
                             name: artist.name.clone(),
                             album_count,
+                            image_url: artist.image_url.clone(),
                         });
                     }
                 }
@@ -231,10 +256,10 @@ pub fn DemoLayout() -> Element {
                     on_search_result_click: move |action: SearchAction| {
                         search_query.set(String::new());
                         match action {
-                            SearchAction::Album(album_id) | SearchAction::Track { album_id } => {
+                            SearchAction::Album(album_id) => {
                                 navigator().push(Route::AlbumDetail { album_id });
                             }
-                            SearchAction::Artist(_) => {}
+                            SearchAction::Artist(_) | SearchAction::Track(_) => {}
                         }
                     },
                     on_search_focus: |_| {},
@@ -262,6 +287,7 @@ pub fn DemoLayout() -> Element {
             playback_bar: rsx! {
                 NowPlayingBarView {
                     state: playback_store,
+                    lyrics_available: true,
                     on_previous: move |_| {},
                     on_pause: move |_| {},
                     on_resume: move |_| {},
@@ -274,6 +300,7 @@ pub fn DemoLayout() -> Element {
                         let current = *sidebar_is_open.read();
                         sidebar_is_open.set(!current);
                     },
+                    on_toggle_lyrics: move |_| {},
                     on_track_click: move |_track_id: String| {},
                     on_artist_click: move |_artist_id: String| {},
                 }
@@ -282,6 +309,8 @@ pub fn DemoLayout() -> Element {
                 QueueSidebarView {
                     sidebar: sidebar_store,
                     playback: playback_store,
+                    radio_available: true,
+                    history_available: true,
                     on_close: move |_| sidebar_is_open.set(false),
                     on_clear: move |_| {},
                     on_remove: move |_idx| {},
@@ -289,6 +318,12 @@ pub fn DemoLayout() -> Element {
                     on_play_index: move |_idx| {},
                     on_pause: move |_| {},
                     on_resume: move |_| {},
+                    on_radio_change: move |enabled: bool| {
+                        playback_store.radio_enabled().set(enabled);
+                    },
+                    on_show_history: move |show: bool| sidebar_store.show_history().set(show),
+                    on_history_play: move |_track_id: String| {},
+                    on_history_add_to_queue: move |_track_id: String| {},
                 }
             },
             Outlet::<Route> {}
@@ -13,10 +13,26 @@ const ALBUM_COUNT: usize = 2000;
 #[component]
 pub fn Library() -> Element {
     let (albums, artists_by_album) = generate_albums(ALBUM_COUNT);
+    let recently_played: Vec<String> = albums.iter().take(6).map(|a| a.id.clone()).collect();
+    let recently_added: Vec<String> = albums.iter().rev().take(12).map(|a| a.id.clone()).collect();
 
     let state = use_store(|| LibraryState {
         albums,
         artists_by_album,
+        composers_by_album: HashMap::new(),
+        version_counts: HashMap::new(),
+        cover_placeholders: HashMap::new(),
+        recently_added,
+        recently_played,
+        filter_genres: vec![
+            "Ambient".to_string(),
+            "Dream Pop".to_string(),
+            "Shoegaze".to_string(),
+        ],
+        filter_decades: vec![1990, 2000, 2010],
+        collections: vec![],
+        collection_ids_by_album: HashMap::new(),
+        cover_upgrades: HashMap::new(),
         loading: false,
         error: None,
         active_source: bae_ui::stores::config::LibrarySource::Local,
@@ -32,12 +48,17 @@ pub fn Library() -> Element {
         sort_state.view_mode().set(mode);
     };
 
+    let on_filter_change = move |filter| {
+        sort_state.filter().set(filter);
+    };
+
     rsx! {
         LibraryView {
             state,
             sort_state,
             on_sort_criteria_change,
             on_view_mode_change,
+            on_filter_change,
             on_album_click: move |album_id: String| {
                 navigator().push(Route::AlbumDetail { album_id });
             },
@@ -46,6 +67,9 @@ pub fn Library() -> Element {
             },
             on_play_album: |_| {},
             on_add_album_to_queue: |_| {},
+            on_toggle_collection: |_| {},
+            on_create_collection: |_| {},
+            on_delete_collection: |_| {},
             on_empty_action: |_| {},
         }
     }
@@ -50,11 +50,18 @@ pub fn MockDropdownTest() -> Element {
                         key: "{album.id}",
                         album: album.clone(),
                         artists,
+                        version_count: 0,
+                        cover_placeholder: None,
                         on_click: |_| {},
                         on_artist_click: |_| {},
                         on_play: |_| {},
                         on_add_to_queue: |_| {},
+                        on_toggle_collection: |_| {},
+                        on_new_collection: |_| {},
                         open_dropdown,
+                        anchor_scope: None,
+                        collections: None,
+                        collection_ids: Vec::new(),
                     }
                 }
             }
@@ -1,13 +1,22 @@
 //! Settings page
 
 use bae_ui::stores::config::{CloudProvider, FollowedLibraryInfo, LibrarySource};
-use bae_ui::stores::{DeviceActivityInfo, Member, MemberRole};
+use bae_ui::stores::{
+    DeviceActivityInfo, Member, MemberRole, PartyInfo, PartyRequestInfo, SyncConflictFieldInfo,
+    SyncConflictInfo,
+};
 use bae_ui::{
-    AboutSectionView, BaeCloudAuthMode, BitTorrentSectionView, BitTorrentSettings,
-    CloudProviderOption, DiscogsSectionView, LibraryInfo, LibrarySectionView, SettingsTab,
-    SettingsView, SubsonicSectionView, SyncSectionView,
+    AboutSectionView, BaeCloudAuthMode, BitTorrentSectionView, BitTorrentSettings, BrokenAlbumInfo,
+    BrokenFileInfo, BrokenReleaseInfo, CloudProviderOption, DiscogsSectionView, DuplicateGroupInfo,
+    DuplicateReleaseInfo, DuplicatesView, FanartSectionView, GenreCleanupView, GenreInfo,
+    GenreMergeInfo, ImportSectionView, IntegritySummary, IntegrityView, LibraryInfo,
+    LibrarySectionView, MigrationSectionView, MigrationServer, MigrationStatus,
+    MusicBrainzCollectionInfo, MusicBrainzSectionView, PartySectionView, PlaybackSectionView,
+    SettingsTab, SettingsView, SpotifyReportAlbum, SpotifyReportView, SubsonicSectionView,
+    SyncSectionView,
 };
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 #[component]
 pub fn Settings() -> Element {
@@ -20,23 +29,142 @@ pub fn Settings() -> Element {
 
             match *active_tab.read() {
                 SettingsTab::Library => rsx! {
-                    LibrarySectionView {
-                        libraries: mock_libraries(),
-                        followed_libraries: mock_followed_libraries(),
-                        active_source: LibrarySource::Local,
-                        on_switch: |_| {},
-                        on_create: |_| {},
-                        on_join: |_| {},
-                        on_follow: |_| {},
-                        on_unfollow: |_| {},
-                        on_copy_follow_code: |_| {},
-                        on_switch_source: |_| {},
-                        on_rename: |_| {},
-                        on_remove: |_| {},
-                        show_link_device_button: false,
-                        on_link_device: |_| {},
-                        device_link_qr_svg: None,
-                        on_close_device_link: |_| {},
+                    div { class: "space-y-6",
+                        LibrarySectionView {
+                            libraries: mock_libraries(),
+                            followed_libraries: mock_followed_libraries(),
+                            active_source: LibrarySource::Local,
+                            on_switch: |_| {},
+                            on_create: |_| {},
+                            on_join: |_| {},
+                            on_follow: |_| {},
+                            on_unfollow: |_| {},
+                            on_copy_follow_code: |_| {},
+                            on_switch_source: |_| {},
+                            on_rename: |_| {},
+                            on_remove: |_| {},
+                            show_link_device_button: false,
+                            on_link_device: |_| {},
+                            device_link_qr_svg: None,
+                            device_link_sas: Vec::new(),
+                            on_close_device_link: |_| {},
+                            on_confirm_device_link: |_| {},
+                            export_include_audio: false,
+                            is_exporting: false,
+                            exported_path: None,
+                            export_error: None,
+                            on_export_include_audio_change: |_| {},
+                            on_export: |_| {},
+                            can_rotate_key: true,
+                            key_rotation_progress: None,
+                            key_rotation_error: None,
+                            on_rotate_key: |_| {},
+                            can_use_security_key: true,
+                            security_key_enabled: false,
+                            security_key_busy: false,
+                            security_key_error: None,
+                            on_enable_security_key: |_| {},
+                            on_disable_security_key: |_| {},
+                            can_encrypt_database: true,
+                            database_encrypted: false,
+                            database_encryption_error: None,
+                            on_set_database_encryption: |_| {},
+                        }
+                        DuplicatesView {
+                            groups: mock_duplicates(),
+                            is_scanning: false,
+                            merging: None,
+                            error: None,
+                            on_scan: |_| {},
+                            on_keep: |_| {},
+                            on_merge: |_| {},
+                        }
+                        IntegrityView {
+                            progress: None,
+                            summary: Some(IntegritySummary {
+                                releases_checked: 120,
+                                files_checked: 1480,
+                                releases_skipped: 0,
+                            }),
+                            albums: mock_broken_albums(),
+                            last_scrub: Some("Oct 12, 2026".to_string()),
+                            scrub_albums: Vec::new(),
+                            error: None,
+                            on_check: |_| {},
+                            on_reimport: |_| {},
+                            on_album_click: |_| {},
+                        }
+                    }
+                },
+                SettingsTab::Import => rsx! {
+                    div { class: "space-y-6",
+                        ImportSectionView {
+                            ignore_patterns: vec!["*.m3u".to_string(), "@eaDir".to_string()],
+                            min_audio_duration_secs: Some(5),
+                            is_editing: false,
+                            edit_patterns: String::new(),
+                            edit_min_duration: String::new(),
+                            has_changes: false,
+                            on_edit_start: |_| {},
+                            on_cancel: |_| {},
+                            on_save: |_| {},
+                            on_patterns_change: |_| {},
+                            on_min_duration_change: |_| {},
+                            network_share_url: Some("smb://nas.local/Music".to_string()),
+                            network_share_username: None,
+                            network_share_password_set: false,
+                            is_editing_share: false,
+                            edit_share_url: String::new(),
+                            edit_share_username: String::new(),
+                            edit_share_password: String::new(),
+                            has_share_changes: false,
+                            share_save_error: None,
+                            on_share_edit_start: |_| {},
+                            on_share_cancel: |_| {},
+                            on_share_save: |_| {},
+                            on_share_url_change: |_| {},
+                            on_share_username_change: |_| {},
+                            on_share_password_change: |_| {},
+                            auto_upgrade_covers: false,
+                            on_auto_upgrade_covers_change: |_| {},
+                        }
+                        SpotifyReportView {
+                            input: "Glass Harbor - Pacific Standard\nTidewater - Low Light".to_string(),
+                            is_comparing: false,
+                            error: None,
+                            report: Some(mock_spotify_report()),
+                            searches: HashMap::new(),
+                            on_input_change: |_| {},
+                            on_compare: |_| {},
+                            on_open_album: |_| {},
+                            on_search: |_| {},
+                        }
+                    }
+                    MigrationSectionView {
+                        server: MigrationServer::Navidrome,
+                        server_url: "http://localhost:4533".to_string(),
+                        username: "admin".to_string(),
+                        password: String::new(),
+                        plex_token: String::new(),
+                        status: MigrationStatus::Idle,
+                        on_server_change: |_| {},
+                        on_server_url_change: |_| {},
+                        on_username_change: |_| {},
+                        on_password_change: |_| {},
+                        on_plex_token_change: |_| {},
+                        on_migrate: |_| {},
+                    }
+                },
+                SettingsTab::Playback => rsx! {
+                    PlaybackSectionView {
+                        pause_on_sleep: true,
+                        pause_on_screen_lock: false,
+                        pause_during_calls: true,
+                        duck_for_other_audio: false,
+                        on_pause_on_sleep_change: |_| {},
+                        on_pause_on_screen_lock_change: |_| {},
+                        on_pause_during_calls_change: |_| {},
+                        on_duck_for_other_audio_change: |_| {},
                     }
                 },
                 SettingsTab::Sync => rsx! {
@@ -47,12 +175,18 @@ pub fn Settings() -> Element {
                                 device_id: "a1b2c3d4-e5f6-7890-abcd-ef1234567890".to_string(),
                                 last_seq: 42,
                                 last_sync: Some("2026-02-10T11:55:00Z".to_string()),
+                                revoked: false,
+                                pending: false,
                             },
                         ],
+                        on_revoke_device: |_| {},
+                        on_approve_device: |_| {},
                         syncing: false,
                         error: None,
                         user_pubkey: Some("a1b2c3d4e5f67890abcdef1234567890a1b2c3d4e5f67890abcdef1234567890".to_string()),
                         on_copy_pubkey: |_| {},
+                        conflicts: mock_conflicts(),
+                        on_resolve_conflict: |_| {},
                         members: mock_members(),
                         is_owner: true,
                         on_remove_member: |_| {},
@@ -69,6 +203,8 @@ pub fn Settings() -> Element {
                         on_sign_in: |_| {},
                         on_disconnect_provider: |_| {},
                         on_use_icloud: |_| {},
+                        on_use_lan: |_| {},
+                        on_choose_folder: |_| {},
                         // S3 edit state
                         is_editing: false,
                         edit_bucket: String::new(),
@@ -76,6 +212,8 @@ pub fn Settings() -> Element {
                         edit_endpoint: String::new(),
                         edit_access_key: String::new(),
                         edit_secret_key: String::new(),
+                        checking_connection: false,
+                        connection_check: None,
                         on_edit_start: |_| {},
                         on_cancel_edit: |_| {},
                         on_save_config: |_| {},
@@ -84,6 +222,7 @@ pub fn Settings() -> Element {
                         on_endpoint_change: |_| {},
                         on_access_key_change: |_| {},
                         on_secret_key_change: |_| {},
+                        on_check_connection: |_| {},
                         // bae cloud form state
                         bae_cloud_is_editing: false,
                         bae_cloud_mode: BaeCloudAuthMode::SignUp,
@@ -95,6 +234,11 @@ pub fn Settings() -> Element {
                         on_bae_cloud_username_change: |_| {},
                         on_bae_cloud_password_change: |_| {},
                         on_bae_cloud_submit: |_| {},
+                        encrypt_images: true,
+                        on_encrypt_images_change: |_| {},
+                        offload_after_days: None,
+                        on_offload_after_days_change: |_| {},
+                        storage_usage: None,
                         // Invite
                         show_invite_form: false,
                         invite_pubkey: String::new(),
@@ -111,20 +255,65 @@ pub fn Settings() -> Element {
                         recovery_key: None,
                         on_reveal_recovery_key: |_| {},
                         on_copy_recovery_key: |_| {},
+                        recovery_shares: None,
+                        on_split_recovery_key: |_| {},
+                        on_copy_recovery_shares: |_| {},
+                        recovery_kit_path: None,
+                        recovery_kit_error: None,
+                        on_save_recovery_kit: |_| {},
                     }
                 },
                 SettingsTab::Discogs => rsx! {
-                    DiscogsSectionView {
-                        discogs_configured: true,
-                        discogs_key_value: String::new(),
-                        is_editing: false,
-                        is_saving: false,
-                        has_changes: false,
-                        save_error: None,
-                        on_edit_start: |_| {},
-                        on_key_change: |_| {},
-                        on_save: |_| {},
-                        on_cancel: |_| {},
+                    div { class: "space-y-6",
+                        DiscogsSectionView {
+                            discogs_configured: true,
+                            discogs_key_value: String::new(),
+                            is_editing: false,
+                            is_saving: false,
+                            has_changes: false,
+                            save_error: None,
+                            on_edit_start: |_| {},
+                            on_key_change: |_| {},
+                            on_save: |_| {},
+                            on_cancel: |_| {},
+                        }
+                        FanartSectionView {
+                            fanart_configured: false,
+                            fanart_key_value: String::new(),
+                            is_editing: false,
+                            is_saving: false,
+                            has_changes: false,
+                            save_error: None,
+                            on_edit_start: |_| {},
+                            on_key_change: |_| {},
+                            on_save: |_| {},
+                            on_cancel: |_| {},
+                        }
+                        MusicBrainzSectionView {
+                            username: Some("glassharbor".to_string()),
+                            collections: mock_musicbrainz_collections(),
+                            collection_id: Some("collection-1".to_string()),
+                            is_linking: false,
+                            is_syncing: false,
+                            sync_status: Some("Added 3, removed 1".to_string()),
+                            error: None,
+                            on_link: |_| {},
+                            on_unlink: |_| {},
+                            on_select_collection: |_| {},
+                            on_sync_now: |_| {},
+                        }
+                        GenreCleanupView {
+                            genres: mock_genres(),
+                            suggestions: mock_genre_suggestions(),
+                            selected: vec!["dream pop".to_string(), "dreampop".to_string()],
+                            merge_name: "Dream Pop".to_string(),
+                            is_merging: false,
+                            error: None,
+                            on_toggle: |_| {},
+                            on_merge_name_change: |_| {},
+                            on_merge_selected: |_| {},
+                            on_apply_suggestion: |_| {},
+                        }
                     }
                 },
                 SettingsTab::BitTorrent => rsx! {
@@ -198,6 +387,29 @@ pub fn Settings() -> Element {
                         on_username_change: |_| {},
                         on_password_change: |_| {},
                         on_password_confirm_change: |_| {},
+                        app_passwords: Vec::new(),
+                        new_app_password_name: String::new(),
+                        created_app_password: None,
+                        is_creating_app_password: false,
+                        app_password_error: None,
+                        on_app_password_name_change: |_| {},
+                        on_app_password_create: |_| {},
+                        on_app_password_revoke: |_| {},
+                        on_created_app_password_dismiss: |_| {},
+                    }
+                },
+                SettingsTab::Party => rsx! {
+                    PartySectionView {
+                        server_enabled: true,
+                        server_local_only: false,
+                        party: Some(mock_party()),
+                        join_url: Some("http://192.168.1.20:4533/party?code=K7MQ2R".to_string()),
+                        require_approval: true,
+                        on_require_approval_change: |_| {},
+                        on_start: |_| {},
+                        on_stop: |_| {},
+                        on_approve: |_| {},
+                        on_reject: |_| {},
                     }
                 },
                 SettingsTab::About => rsx! {
@@ -212,6 +424,28 @@ pub fn Settings() -> Element {
     }
 }
 
+fn mock_party() -> PartyInfo {
+    PartyInfo {
+        code: "K7MQ2R".to_string(),
+        require_approval: true,
+        guest_count: 4,
+        pending: vec![
+            PartyRequestInfo {
+                id: "req-1".to_string(),
+                guest_name: "Sam".to_string(),
+                track_title: "Low Light".to_string(),
+                artist_name: "Glass Harbor".to_string(),
+            },
+            PartyRequestInfo {
+                id: "req-2".to_string(),
+                guest_name: "Riley".to_string(),
+                track_title: "Pacific Standard".to_string(),
+                artist_name: "Tidewater".to_string(),
+            },
+        ],
+    }
+}
+
 fn mock_libraries() -> Vec<LibraryInfo> {
     vec![
         LibraryInfo {
@@ -243,6 +477,26 @@ fn mock_followed_libraries() -> Vec<FollowedLibraryInfo> {
     }]
 }
 
+fn mock_conflicts() -> Vec<SyncConflictInfo> {
+    vec![SyncConflictInfo {
+        id: "conflict-1".to_string(),
+        album_title: "Glass Harbor".to_string(),
+        kept_remote: true,
+        fields: vec![
+            SyncConflictFieldInfo {
+                column: "title".to_string(),
+                local: Some("Glass Harbor".to_string()),
+                remote: Some("Glass Harbor (Deluxe)".to_string()),
+            },
+            SyncConflictFieldInfo {
+                column: "year".to_string(),
+                local: Some("2019".to_string()),
+                remote: None,
+            },
+        ],
+    }]
+}
+
 fn mock_members() -> Vec<Member> {
     vec![
         Member {
@@ -257,6 +511,12 @@ fn mock_members() -> Vec<Member> {
             role: MemberRole::Member,
             is_self: false,
         },
+        Member {
+            pubkey: "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef".to_string(),
+            display_name: "0123...cdef".to_string(),
+            role: MemberRole::ReadOnly,
+            is_self: false,
+        },
     ]
 }
 
@@ -298,5 +558,139 @@ fn mock_cloud_options() -> Vec<CloudProviderOption> {
             description: "For Backblaze B2, Wasabi, MinIO, AWS, etc.",
             connected_account: None,
         },
+        CloudProviderOption {
+            provider: CloudProvider::Lan,
+            label: "Local network",
+            description: "Sync with devices on this network, nothing stored online",
+            connected_account: None,
+        },
+        CloudProviderOption {
+            provider: CloudProvider::Folder,
+            label: "Folder",
+            description: "A USB drive or network share, for syncing without the internet",
+            connected_account: None,
+        },
+    ]
+}
+
+fn mock_musicbrainz_collections() -> Vec<MusicBrainzCollectionInfo> {
+    vec![
+        MusicBrainzCollectionInfo {
+            id: "collection-1".to_string(),
+            name: "My Library".to_string(),
+            release_count: 128,
+        },
+        MusicBrainzCollectionInfo {
+            id: "collection-2".to_string(),
+            name: "Wishlist".to_string(),
+            release_count: 12,
+        },
+    ]
+}
+
+fn mock_genres() -> Vec<GenreInfo> {
+    [
+        ("ambient", "Ambient", 7),
+        ("dream pop", "Dream Pop", 4),
+        ("dreampop", "Dreampop", 1),
+        ("hip hop", "Hip-Hop", 5),
+        ("rap", "Rap", 2),
+        ("shoegaze", "Shoegaze", 3),
+    ]
+    .into_iter()
+    .map(|(id, name, album_count)| GenreInfo {
+        id: id.to_string(),
+        name: name.to_string(),
+        album_count,
+    })
+    .collect()
+}
+
+fn mock_genre_suggestions() -> Vec<GenreMergeInfo> {
+    vec![
+        GenreMergeInfo {
+            into: "Dream Pop".to_string(),
+            genre_ids: vec!["dream pop".to_string(), "dreampop".to_string()],
+            names: vec!["Dream Pop".to_string(), "Dreampop".to_string()],
+        },
+        GenreMergeInfo {
+            into: "Hip-Hop".to_string(),
+            genre_ids: vec!["hip hop".to_string(), "rap".to_string()],
+            names: vec!["Hip-Hop".to_string(), "Rap".to_string()],
+        },
+    ]
+}
+
+fn mock_duplicates() -> Vec<DuplicateGroupInfo> {
+    let release = |id: &str, details: &str, storage: &str, play_count| DuplicateReleaseInfo {
+        release_id: id.to_string(),
+        album_title: "Pacific Standard".to_string(),
+        artist_name: "Glass Harbor".to_string(),
+        details: details.to_string(),
+        storage: storage.to_string(),
+        track_count: 10,
+        play_count,
+    };
+    vec![
+        DuplicateGroupInfo {
+            identical_audio: true,
+            releases: vec![
+                release("release-1", "2019 · CD", "Local", 42),
+                release("release-2", "2019 · CD", "Unmanaged", 3),
+            ],
+            keep_release_id: "release-1".to_string(),
+        },
+        DuplicateGroupInfo {
+            identical_audio: false,
+            releases: vec![
+                release("release-3", "Deluxe · 2021 · Digital", "Cloud", 0),
+                release("release-4", "2019 · Vinyl", "Local + Cloud", 7),
+            ],
+            keep_release_id: "release-4".to_string(),
+        },
+    ]
+}
+
+fn mock_broken_albums() -> Vec<BrokenAlbumInfo> {
+    vec![BrokenAlbumInfo {
+        album_id: "album-1".to_string(),
+        album_title: "Low Light".to_string(),
+        artist_name: "Tidewater".to_string(),
+        releases: vec![BrokenReleaseInfo {
+            release_id: "release-1".to_string(),
+            release_name: Some("Remaster".to_string()),
+            file_count: 12,
+            broken_files: vec![
+                BrokenFileInfo {
+                    filename: "03 Pacific Standard.flac".to_string(),
+                    location: "Local".to_string(),
+                    problem: "Missing".to_string(),
+                    detail: String::new(),
+                },
+                BrokenFileInfo {
+                    filename: "07 Glass Harbor.flac".to_string(),
+                    location: "Cloud".to_string(),
+                    problem: "Can't decrypt".to_string(),
+                    detail: "Decryption error".to_string(),
+                },
+            ],
+        }],
+    }]
+}
+
+fn mock_spotify_report() -> Vec<SpotifyReportAlbum> {
+    vec![
+        SpotifyReportAlbum {
+            artist: "Glass Harbor".to_string(),
+            title: "Pacific Standard".to_string(),
+            entries: 12,
+            album_id: Some("album-1".to_string()),
+        },
+        SpotifyReportAlbum {
+            artist: "Tidewater".to_string(),
+            title: "Low Light".to_string(),
+            entries: 3,
+            album_id: None,
+        },
     ]
 }
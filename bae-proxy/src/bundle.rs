@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Most files one bundle request may ask for.
+pub const MAX_BUNDLE_FILES: usize = 500;
+
+/// Most bytes one bundle may carry.
+pub const MAX_BUNDLE_BYTES: u64 = 1 << 30;
+
+/// Body of `POST /bundle`: the S3 keys to package.
+#[derive(Deserialize)]
+pub struct BundleRequest {
+    pub files: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct BundleManifest {
+    files: Vec<BundleEntry>,
+}
+
+#[derive(Serialize)]
+struct BundleEntry {
+    key: String,
+    offset: u64,
+    size: u64,
+    sha256: String,
+    chunk_count: u64,
+}
+
+// Container layout, mirrored from bae-core's encryption module.
+const NONCE_SIZE: usize = 24;
+const TAG_SIZE: usize = 16;
+const CHUNK_SIZE: usize = 65536;
+const HEADER_SIZE: usize = 8;
+const CONTAINER_MAGIC: &[u8; 6] = b"baeenc";
+
+/// Pack files into one body: `[u32 BE manifest length][manifest JSON][file 0][file 1]...`
+///
+/// Same layout as bae-core's `sync::bundle_format`.
+pub fn encode_bundle(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut offset = 0u64;
+    let entries = files
+        .iter()
+        .map(|(key, data)| {
+            let entry = BundleEntry {
+                key: key.clone(),
+                offset,
+                size: data.len() as u64,
+                sha256: hex::encode(Sha256::digest(data)),
+                chunk_count: chunk_count(data),
+            };
+            offset += data.len() as u64;
+            entry
+        })
+        .collect();
+    let manifest = serde_json::to_vec(&BundleManifest { files: entries }).unwrap();
+
+    let mut body = Vec::with_capacity(4 + manifest.len() + offset as usize);
+    body.extend_from_slice(&(manifest.len() as u32).to_be_bytes());
+    body.extend_from_slice(&manifest);
+    for (_, data) in files {
+        body.extend_from_slice(data);
+    }
+    body
+}
+
+/// Encrypted chunks after the container header and nonce.
+fn chunk_count(data: &[u8]) -> u64 {
+    let (header_len, chunk_size) = if data.len() >= HEADER_SIZE && data.starts_with(CONTAINER_MAGIC)
+    {
+        (HEADER_SIZE, 1usize.checked_shl(data[7] as u32).unwrap_or(0))
+    } else {
+        (0, CHUNK_SIZE)
+    };
+    if chunk_size == 0 {
+        return 0;
+    }
+    let body_len = data.len().saturating_sub(header_len + NONCE_SIZE);
+    body_len.div_ceil(chunk_size + TAG_SIZE) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_count_reads_container_header() {
+        // Legacy file: nonce + one full 64KB chunk + a partial one
+        let legacy = vec![0u8; NONCE_SIZE + CHUNK_SIZE + TAG_SIZE + 10];
+        assert_eq!(chunk_count(&legacy), 2);
+
+        // Versioned file with 1MB chunks
+        let mut versioned = CONTAINER_MAGIC.to_vec();
+        versioned.extend([2, 20]);
+        versioned.extend(vec![0u8; NONCE_SIZE + (1 << 20) + TAG_SIZE]);
+        assert_eq!(chunk_count(&versioned), 1);
+
+        assert_eq!(chunk_count(&[]), 0);
+    }
+
+    #[test]
+    fn encode_bundle_prefixes_manifest() {
+        let files = vec![
+            ("storage/ab/cd/file-1".to_string(), vec![1u8; 40]),
+            ("storage/ab/cd/file-2".to_string(), vec![2u8; 60]),
+        ];
+
+        let body = encode_bundle(&files);
+
+        let manifest_len = u32::from_be_bytes(body[..4].try_into().unwrap()) as usize;
+        let manifest: serde_json::Value =
+            serde_json::from_slice(&body[4..4 + manifest_len]).unwrap();
+        assert_eq!(manifest["files"][1]["offset"], 40);
+        assert_eq!(manifest["files"][1]["size"], 60);
+        assert_eq!(body.len(), 4 + manifest_len + 100);
+    }
+}
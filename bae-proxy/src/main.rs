@@ -1,3 +1,4 @@
+mod bundle;
 mod proxy;
 mod registry;
 mod s3;
@@ -9,7 +10,7 @@ use std::sync::Arc;
 
 use clap::Parser;
 use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tower_http::services::{ServeDir, ServeFile};
 use tracing::{error, info, warn};
 
@@ -133,6 +134,7 @@ async fn main() {
     let state = Arc::new(ProxyState {
         registry,
         s3_clients,
+        redeem_lock: Mutex::new(()),
     });
 
     let router = proxy_router(state);
@@ -5,19 +5,22 @@ use axum::body::Bytes;
 use axum::extract::{Host, Path, Query, State};
 use axum::http::{HeaderMap, Method, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::routing::get;
-use axum::Router;
+use axum::routing::{get, post};
+use axum::{Json, Router};
 use ed25519_dalek::{Signature, VerifyingKey};
 use serde::Deserialize;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tracing::warn;
 
+use crate::bundle::{encode_bundle, BundleRequest, MAX_BUNDLE_BYTES, MAX_BUNDLE_FILES};
 use crate::registry::Registry;
 use crate::s3::{S3Client, S3Error};
 
 pub struct ProxyState {
     pub registry: Arc<RwLock<Registry>>,
     pub s3_clients: Arc<RwLock<HashMap<String, S3Client>>>,
+    /// Serializes device link redemptions so a ticket is handed out once.
+    pub redeem_lock: Mutex<()>,
 }
 
 #[derive(Deserialize)]
@@ -41,6 +44,8 @@ pub fn proxy_router(state: Arc<ProxyState>) -> Router {
         .route("/share/:share_id/meta", get(share_meta))
         .route("/share/:share_id/manifest", get(share_manifest))
         .route("/share/:share_id/file/*key", get(share_file))
+        .route("/device-links/:nonce/redeem", post(redeem_device_link))
+        .route("/bundle", post(bundle))
         .route("/health", get(health))
         .with_state(state)
 }
@@ -336,6 +341,84 @@ async fn delete_key(
     }
 }
 
+/// Redeem a one-time device link ticket stored at `device-links/{nonce}`.
+///
+/// The ticket is read and deleted under `redeem_lock`, so of two devices
+/// scanning the same link only one gets 200; the other gets 410. An expired
+/// ticket is deleted and refused with 410 as well.
+async fn redeem_device_link(
+    State(state): State<Arc<ProxyState>>,
+    Host(raw_host): Host,
+    headers: HeaderMap,
+    method: Method,
+    Path(nonce): Path<String>,
+) -> Response {
+    let hostname = extract_hostname(&raw_host);
+
+    let registry = state.registry.read().await;
+    let entry = match registry.find_by_hostname(hostname) {
+        Some(e) => e.clone(),
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    drop(registry);
+
+    let pubkey = match &entry.ed25519_pubkey {
+        Some(pk) => pk.clone(),
+        None => {
+            return (StatusCode::FORBIDDEN, "library not provisioned").into_response();
+        }
+    };
+
+    let request_path = format!("/device-links/{nonce}/redeem");
+    if let Err(resp) = verify_auth(&headers, &method, &request_path, &pubkey) {
+        return resp;
+    }
+
+    if nonce.is_empty() || !nonce.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return (StatusCode::BAD_REQUEST, "invalid nonce").into_response();
+    }
+
+    if let Err(resp) = get_s3_client(&state.s3_clients, &entry.library_id, &entry).await {
+        return resp;
+    }
+
+    let clients = state.s3_clients.read().await;
+    let client = clients.get(&entry.library_id).unwrap();
+
+    let key = format!("device-links/{nonce}");
+    let _guard = state.redeem_lock.lock().await;
+
+    let ticket = match client.get_object(&key).await {
+        Ok(data) => data,
+        Err(S3Error::NotFound) => {
+            return (StatusCode::GONE, "device link already used").into_response();
+        }
+        Err(err) => return s3_error_to_response(err),
+    };
+    if let Err(err) = client.delete_object(&key).await {
+        return s3_error_to_response(err);
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    if ticket_expired(&ticket, now) {
+        return (StatusCode::GONE, "device link expired").into_response();
+    }
+
+    StatusCode::OK.into_response()
+}
+
+/// A ticket holds its expiry as Unix seconds. One that can't be read counts
+/// as expired.
+fn ticket_expired(ticket: &[u8], now: u64) -> bool {
+    std::str::from_utf8(ticket)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .is_none_or(|expires_at| expires_at <= now)
+}
+
 async fn head_key(
     State(state): State<Arc<ProxyState>>,
     Host(raw_host): Host,
@@ -504,6 +587,58 @@ async fn share_file(
     }
 }
 
+/// Package several objects (typically one release's files) with a manifest of
+/// sizes, hashes and chunk counts, for offline download in one request.
+async fn bundle(
+    State(state): State<Arc<ProxyState>>,
+    Host(raw_host): Host,
+    Json(request): Json<BundleRequest>,
+) -> Response {
+    if request.files.is_empty() || request.files.len() > MAX_BUNDLE_FILES {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("bundle must list 1 to {MAX_BUNDLE_FILES} files"),
+        )
+            .into_response();
+    }
+
+    let hostname = extract_hostname(&raw_host);
+    let registry = state.registry.read().await;
+    let entry = match registry.find_by_hostname(hostname) {
+        Some(e) => e.clone(),
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    drop(registry);
+
+    if let Err(resp) = get_s3_client(&state.s3_clients, &entry.library_id, &entry).await {
+        return resp;
+    }
+
+    let clients = state.s3_clients.read().await;
+    let client = clients.get(&entry.library_id).unwrap();
+
+    let mut files = Vec::with_capacity(request.files.len());
+    let mut total = 0u64;
+    for key in request.files {
+        let data = match client.get_object(&key).await {
+            Ok(data) => data,
+            Err(err) => return s3_error_to_response(err),
+        };
+        total += data.len() as u64;
+        if total > MAX_BUNDLE_BYTES {
+            return (StatusCode::PAYLOAD_TOO_LARGE, "bundle too large").into_response();
+        }
+        files.push((key, data));
+    }
+
+    (
+        StatusCode::OK,
+        [("content-type", "application/octet-stream")],
+        encode_bundle(&files),
+    )
+        .into_response()
+}
+
 /// Parse a `Range: bytes=START-END` header.
 /// Returns (start, end) where both are inclusive, or None if unparseable.
 fn parse_range_header(header: &str) -> Option<(u64, u64)> {
@@ -707,6 +842,7 @@ mod tests {
         let state = Arc::new(ProxyState {
             registry: Arc::new(RwLock::new(registry)),
             s3_clients: Arc::new(RwLock::new(HashMap::new())),
+            redeem_lock: Mutex::new(()),
         });
         proxy_router(state)
     }
@@ -749,6 +885,31 @@ mod tests {
         assert_ne!(resp.status(), StatusCode::FORBIDDEN);
     }
 
+    #[tokio::test]
+    async fn bundle_works_without_auth() {
+        let app = test_app();
+        let req = Request::post("/bundle")
+            .header("host", "test.bae.fm")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"files":["storage/ab/cd/file-1"]}"#))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_ne!(resp.status(), StatusCode::UNAUTHORIZED);
+        assert_ne!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn bundle_rejects_empty_file_list() {
+        let app = test_app();
+        let req = Request::post("/bundle")
+            .header("host", "test.bae.fm")
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"files":[]}"#))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
     /// Write routes (PUT, DELETE) must require auth headers -- return 401 without them.
     #[tokio::test]
     async fn write_key_fails_without_auth() {
@@ -771,4 +932,23 @@ mod tests {
         let resp = app.oneshot(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
     }
+
+    #[tokio::test]
+    async fn redeem_device_link_fails_without_auth() {
+        let app = test_app();
+        let req = Request::post("/device-links/abcd/redeem")
+            .header("host", "test.bae.fm")
+            .body(Body::empty())
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn ticket_expiry() {
+        assert!(!ticket_expired(b"1000", 999));
+        assert!(ticket_expired(b"1000", 1000));
+        assert!(ticket_expired(b"1000", 1001));
+        assert!(ticket_expired(b"not a time", 0));
+    }
 }
@@ -1,9 +1,10 @@
 //! Album card component - pure view with callbacks
 
 use crate::components::helpers::Tooltip;
-use crate::components::icons::{EllipsisIcon, ImageIcon, PlayIcon, PlusIcon};
-use crate::components::{MenuDropdown, MenuItem, Placement, TextLink};
-use crate::display_types::{Album, Artist};
+use crate::components::icons::{CheckIcon, EllipsisIcon, ImageIcon, PlayIcon, PlusIcon};
+use crate::components::utils::blurhash_data_url;
+use crate::components::{MenuDivider, MenuDropdown, MenuItem, Placement, TextLink};
+use crate::display_types::{Album, Artist, Collection};
 use dioxus::prelude::*;
 
 /// Individual album card component
@@ -21,20 +22,45 @@ pub fn AlbumCard(
     // Action callbacks
     on_play: EventHandler<String>,
     on_add_to_queue: EventHandler<String>,
+    /// Called with (album ID, collection ID) to add the album to the
+    /// collection, or take it out if it's already in
+    on_toggle_collection: EventHandler<(String, String)>,
+    /// Called with the album ID to start a new collection holding it
+    on_new_collection: EventHandler<String>,
     // Which album's dropdown is open (hoisted to parent to outlive virtual scroll recycling)
     mut open_dropdown: Signal<Option<String>>,
+    /// Number of linked versions this card stands in for (0 or 1 when unlinked)
+    version_count: usize,
+    /// Blurhash shown behind the cover until it loads
+    cover_placeholder: Option<String>,
+    /// Keeps the menu anchor unique when an album appears in more than one
+    /// list on the page (a "Recently Added" shelf above the grid)
+    anchor_scope: Option<String>,
+    /// Collections offered in the menu. `None` leaves collection actions
+    /// out, e.g. for a followed library.
+    collections: Option<Vec<Collection>>,
+    /// IDs of the collections the album is in
+    collection_ids: Vec<String>,
 ) -> Element {
     let album_id = album.id.clone();
     let album_title = album.title.clone();
     let album_year = album.year;
     let cover_url = album.cover_url.clone();
+    let placeholder_style = cover_placeholder
+        .as_deref()
+        .and_then(blurhash_data_url)
+        .map(|url| format!("background-image: url({url}); background-size: cover;"))
+        .unwrap_or_default();
 
     let is_open = {
         let album_id = album_id.clone();
         use_memo(move || open_dropdown() == Some(album_id.clone()))
     };
     // Use album_id for anchor to ensure uniqueness even if component is recycled
-    let anchor_id = format!("album-card-btn-{}", album_id);
+    let anchor_id = match anchor_scope {
+        Some(scope) => format!("album-card-btn-{}-{}", scope, album_id),
+        None => format!("album-card-btn-{}", album_id),
+    };
 
     // Note: use overflow-clip (not overflow-hidden) to clip rounded corners without blocking scroll propagation
     let card_class = "bg-gray-800 rounded-lg overflow-clip shadow-lg hover:shadow-xl transition-shadow duration-300 cursor-pointer group relative";
@@ -51,7 +77,9 @@ pub fn AlbumCard(
                     }
                 }
             },
-            div { class: "aspect-square bg-gray-700 flex items-center justify-center relative",
+            div {
+                class: "aspect-square bg-gray-700 flex items-center justify-center relative",
+                style: "{placeholder_style}",
                 if let Some(url) = &cover_url {
                     img {
                         src: "{url}",
@@ -113,8 +141,18 @@ pub fn AlbumCard(
                         }
                     }
                 }
-                if let Some(year) = album_year {
-                    p { class: "text-gray-500 text-xs mt-1", "{year}" }
+                if album_year.is_some() || version_count > 1 {
+                    p { class: "text-gray-500 text-xs mt-1",
+                        if let Some(year) = album_year {
+                            "{year}"
+                        }
+                        if album_year.is_some() && version_count > 1 {
+                            " · "
+                        }
+                        if version_count > 1 {
+                            "{version_count} versions"
+                        }
+                    }
                 }
             }
 
@@ -147,6 +185,39 @@ pub fn AlbumCard(
                     PlusIcon { class: "w-4 h-4" }
                     "Add to Queue"
                 }
+                if let Some(collections) = collections {
+                    MenuDivider {}
+                    for collection in collections {
+                        MenuItem {
+                            key: "{collection.id}",
+                            onclick: {
+                                let album_id = album_id.clone();
+                                let collection_id = collection.id.clone();
+                                move |_| {
+                                    open_dropdown.set(None);
+                                    on_toggle_collection.call((album_id.clone(), collection_id.clone()));
+                                }
+                            },
+                            if collection_ids.contains(&collection.id) {
+                                CheckIcon { class: "w-4 h-4" }
+                            } else {
+                                span { class: "w-4 h-4" }
+                            }
+                            "{collection.name}"
+                        }
+                    }
+                    MenuItem {
+                        onclick: {
+                            let album_id = album_id.clone();
+                            move |_| {
+                                open_dropdown.set(None);
+                                on_new_collection.call(album_id.clone());
+                            }
+                        },
+                        PlusIcon { class: "w-4 h-4" }
+                        "New Collection..."
+                    }
+                }
             }
         }
     }
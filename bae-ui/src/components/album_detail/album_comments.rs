@@ -0,0 +1,93 @@
+//! Album comments -- notes members of a shared library leave on an album
+//! for each other ("play track 3 loud"), shown newest first
+
+use crate::components::icons::XIcon;
+use crate::components::utils::format_relative_time;
+use crate::components::{Button, ButtonSize, ButtonVariant, ChromelessButton};
+use crate::display_types::AlbumComment;
+use dioxus::prelude::*;
+
+#[component]
+pub fn AlbumComments(
+    comments: Vec<AlbumComment>,
+    can_comment: bool,
+    /// Called with the comment text
+    on_add: EventHandler<String>,
+    /// Called with the comment ID
+    on_remove: EventHandler<String>,
+) -> Element {
+    let mut draft = use_signal(String::new);
+
+    if comments.is_empty() && !can_comment {
+        return rsx! {};
+    }
+
+    let is_blank = draft.read().trim().is_empty();
+
+    rsx! {
+        div { class: "mt-8 space-y-3",
+            h3 { class: "text-sm font-semibold text-gray-400 uppercase tracking-wide",
+                "Comments"
+            }
+            if can_comment {
+                div { class: "space-y-2",
+                    textarea {
+                        class: "w-full h-16 px-2.5 py-1.5 bg-gray-800/50 rounded-lg text-sm text-gray-300 placeholder-gray-500 focus:outline-none focus:ring-1 focus:ring-accent/50",
+                        placeholder: "Leave a note for everyone in this library",
+                        maxlength: 500,
+                        value: "{draft}",
+                        oninput: move |e| draft.set(e.value()),
+                    }
+                    div { class: "flex justify-end",
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            size: ButtonSize::Small,
+                            disabled: is_blank,
+                            onclick: move |_| {
+                                let content = draft.read().trim().to_string();
+                                draft.set(String::new());
+                                on_add.call(content);
+                            },
+                            "Post"
+                        }
+                    }
+                }
+            }
+            for comment in comments.iter() {
+                div {
+                    key: "{comment.id}",
+                    class: "group flex items-start gap-3 py-2 border-b border-gray-800 last:border-b-0",
+                    div { class: "flex-1 min-w-0",
+                        div { class: "flex items-baseline gap-2",
+                            span { class: "text-sm font-medium text-gray-300",
+                                if comment.is_own {
+                                    "You"
+                                } else {
+                                    "{comment.author_name}"
+                                }
+                            }
+                            span { class: "text-xs text-gray-500",
+                                {format_relative_time(&comment.created_at)}
+                            }
+                        }
+                        p { class: "text-sm text-gray-400 whitespace-pre-wrap", "{comment.content}" }
+                    }
+                    if comment.is_own {
+                        ChromelessButton {
+                            class: Some(
+                                "p-1 text-gray-500 hover:text-red-400 opacity-0 group-hover:opacity-100 transition-opacity"
+                                    .to_string(),
+                            ),
+                            aria_label: Some("Remove comment".to_string()),
+                            onclick: {
+                                let id = comment.id.clone();
+                                move |_| on_remove.call(id.clone())
+                            },
+                            XIcon { class: "w-3.5 h-3.5" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -23,6 +23,11 @@ pub fn AlbumCoverSection(
     on_delete_album: EventHandler<String>,
     on_view_release_info: EventHandler<String>,
     on_view_storage: EventHandler<String>,
+    on_remap_tracks: EventHandler<String>,
+    /// Called with album_id to switch the view into tag editing
+    on_edit_tags: EventHandler<String>,
+    /// Called with album_id to compare the tags with MusicBrainz
+    on_refresh_metadata: EventHandler<String>,
     on_copy_share_link: EventHandler<String>,
     on_open_gallery: EventHandler<String>,
     on_change_cover: EventHandler<String>,
@@ -119,6 +124,17 @@ pub fn AlbumCoverSection(
                             },
                             "Storage"
                         }
+                        MenuItem {
+                            disabled: is_deleting || is_exporting,
+                            onclick: {
+                                let release_id = release_id.clone();
+                                move |_| {
+                                    show_dropdown.set(false);
+                                    on_remap_tracks.call(release_id.clone());
+                                }
+                            },
+                            "Track Mapping"
+                        }
                         if is_on_cloud {
                             MenuItem {
                                 disabled: is_deleting || is_exporting,
@@ -149,7 +165,29 @@ pub fn AlbumCoverSection(
                         }
                     }
                 }
-                // Change Cover - available for all albums
+                // Edit Tags, Change Cover - available for all albums
+                MenuItem {
+                    disabled: is_deleting || is_exporting,
+                    onclick: {
+                        let album_id = album.id.clone();
+                        move |_| {
+                            show_dropdown.set(false);
+                            on_edit_tags.call(album_id.clone());
+                        }
+                    },
+                    "Edit Tags"
+                }
+                MenuItem {
+                    disabled: is_deleting || is_exporting,
+                    onclick: {
+                        let album_id = album.id.clone();
+                        move |_| {
+                            show_dropdown.set(false);
+                            on_refresh_metadata.call(album_id.clone());
+                        }
+                    },
+                    "Refresh from MusicBrainz"
+                }
                 if let Some(ref release_id) = first_release_id {
                     MenuItem {
                         disabled: is_deleting || is_exporting,
@@ -1,41 +1,44 @@
 //! Album metadata display component
 
-use crate::components::TextLink;
-use crate::display_types::{Album, Artist, Release};
+use super::ArtistCredit;
+use crate::components::icons::DownloadIcon;
+use crate::components::utils::format_file_size;
+use crate::display_types::{Album, CreditedArtist, OfflinePin, Release};
 use dioxus::prelude::*;
 
 #[component]
 pub fn AlbumMetadata(
     album: Album,
-    artists: Vec<Artist>,
+    artist_credit: Vec<CreditedArtist>,
     track_count: usize,
     selected_release: Option<Release>,
+    /// Set when the selected release is kept downloaded
+    offline_pin: Option<OfflinePin>,
     on_artist_click: EventHandler<String>,
 ) -> Element {
     rsx! {
         div {
             h1 { class: "text-2xl font-bold text-white mb-2", "{album.title}" }
             p { class: "text-lg text-gray-300 mb-2",
-                if artists.is_empty() {
+                if artist_credit.is_empty() {
                     "Unknown Artist"
                 } else {
-                    for (i , artist) in artists.iter().enumerate() {
-                        if i > 0 {
-                            ", "
-                        }
-                        TextLink {
-                            onclick: {
-                                let artist_id = artist.id.clone();
-                                move |_| on_artist_click.call(artist_id.clone())
-                            },
-                            "{artist.name}"
-                        }
-                    }
+                    ArtistCredit { credit: artist_credit, on_artist_click }
                 }
                 if let Some(year) = album.year {
                     " · {year}"
                 }
             }
+            if let Some(pin) = offline_pin {
+                p { class: "flex items-center gap-1.5 text-sm text-gray-400 mb-2",
+                    DownloadIcon { class: "w-4 h-4" }
+                    if pin.downloaded_files == pin.total_files {
+                        {format!("Kept downloaded · {}", format_file_size(pin.downloaded_bytes as i64))}
+                    } else {
+                        "{pin.downloaded_files} of {pin.total_files} files downloaded"
+                    }
+                }
+            }
         }
     }
 }
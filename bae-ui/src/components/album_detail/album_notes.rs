@@ -0,0 +1,208 @@
+//! Album notes -- the user's own tags ("needs re-rip", "source: vinyl rip")
+//! and freeform notes on an album
+
+use crate::components::icons::{PencilIcon, PlusIcon, XIcon};
+use crate::components::{
+    Button, ButtonSize, ButtonVariant, ChromelessButton, TextInput, TextInputSize, TextInputType,
+};
+use crate::display_types::{AlbumNotesEdit, CustomTag};
+use dioxus::prelude::*;
+use std::collections::HashSet;
+
+/// A tag row while it's being edited
+#[derive(Clone, Debug, Default, PartialEq)]
+struct TagDraft {
+    key: String,
+    value: String,
+}
+
+#[component]
+pub fn AlbumNotes(
+    album_id: String,
+    custom_tags: Vec<CustomTag>,
+    notes: Option<String>,
+    read_only: bool,
+    on_save: EventHandler<AlbumNotesEdit>,
+) -> Element {
+    let mut is_editing = use_signal(|| false);
+
+    if is_editing() {
+        return rsx! {
+            NotesEditor {
+                album_id,
+                custom_tags,
+                notes,
+                on_cancel: move |_| is_editing.set(false),
+                on_save: move |edit| {
+                    is_editing.set(false);
+                    on_save.call(edit);
+                },
+            }
+        };
+    }
+
+    if custom_tags.is_empty() && notes.is_none() {
+        if read_only {
+            return rsx! {};
+        }
+        return rsx! {
+            div { class: "mt-4",
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    size: ButtonSize::Small,
+                    onclick: move |_| is_editing.set(true),
+                    PlusIcon { class: "w-3.5 h-3.5" }
+                    "Add tags or notes"
+                }
+            }
+        };
+    }
+
+    rsx! {
+        div { class: "mt-4 space-y-2",
+            div { class: "flex items-start justify-between gap-2",
+                div { class: "flex flex-wrap gap-1.5",
+                    for tag in custom_tags.iter() {
+                        span {
+                            key: "{tag.key}",
+                            class: "px-2 py-0.5 rounded-full bg-gray-700 text-xs text-gray-300",
+                            if let Some(ref value) = tag.value {
+                                "{tag.key}: {value}"
+                            } else {
+                                "{tag.key}"
+                            }
+                        }
+                    }
+                }
+                if !read_only {
+                    ChromelessButton {
+                        class: Some("p-1 text-gray-400 hover:text-white".to_string()),
+                        aria_label: Some("Edit tags and notes".to_string()),
+                        onclick: move |_| is_editing.set(true),
+                        PencilIcon { class: "w-3.5 h-3.5" }
+                    }
+                }
+            }
+            if let Some(ref notes) = notes {
+                p { class: "text-sm text-gray-400 whitespace-pre-wrap", "{notes}" }
+            }
+        }
+    }
+}
+
+/// Editable copy of the tags and notes, written back only on save
+#[component]
+fn NotesEditor(
+    album_id: String,
+    custom_tags: Vec<CustomTag>,
+    notes: Option<String>,
+    on_cancel: EventHandler<()>,
+    on_save: EventHandler<AlbumNotesEdit>,
+) -> Element {
+    let mut rows = use_signal(|| {
+        custom_tags
+            .iter()
+            .map(|t| TagDraft {
+                key: t.key.clone(),
+                value: t.value.clone().unwrap_or_default(),
+            })
+            .collect::<Vec<_>>()
+    });
+    let mut text = use_signal(|| notes.clone().unwrap_or_default());
+
+    let current_rows = rows.read().clone();
+    let duplicate = duplicate_key(&current_rows);
+
+    rsx! {
+        div { class: "mt-4 space-y-3",
+            div { class: "space-y-1",
+                label { class: "text-xs text-gray-500", "Tags" }
+                for (index , row) in current_rows.iter().enumerate() {
+                    div { key: "{index}", class: "flex items-center gap-2",
+                        TextInput {
+                            value: row.key.clone(),
+                            on_input: move |v| rows.write()[index].key = v,
+                            size: TextInputSize::Small,
+                            input_type: TextInputType::Text,
+                            placeholder: "Tag",
+                        }
+                        TextInput {
+                            value: row.value.clone(),
+                            on_input: move |v| rows.write()[index].value = v,
+                            size: TextInputSize::Small,
+                            input_type: TextInputType::Text,
+                            placeholder: "Value (optional)",
+                        }
+                        ChromelessButton {
+                            class: Some("p-1 text-gray-400 hover:text-red-400".to_string()),
+                            aria_label: Some("Remove tag".to_string()),
+                            onclick: move |_| {
+                                rows.write().remove(index);
+                            },
+                            XIcon { class: "w-3.5 h-3.5" }
+                        }
+                    }
+                }
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    size: ButtonSize::Small,
+                    onclick: move |_| rows.write().push(TagDraft::default()),
+                    PlusIcon { class: "w-3.5 h-3.5" }
+                    "Add tag"
+                }
+            }
+            div { class: "space-y-1",
+                label { class: "text-xs text-gray-500", "Notes" }
+                textarea {
+                    class: "w-full h-24 px-2.5 py-1.5 bg-gray-800/50 rounded-lg text-sm text-gray-300 placeholder-gray-500 focus:outline-none focus:ring-1 focus:ring-accent/50",
+                    placeholder: "Anything worth remembering about this album",
+                    value: "{text}",
+                    oninput: move |e| text.set(e.value()),
+                }
+            }
+            if let Some(ref key) = duplicate {
+                p { class: "text-sm text-red-400", "\"{key}\" is used twice." }
+            }
+            div { class: "flex justify-end gap-3",
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    size: ButtonSize::Small,
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Small,
+                    disabled: duplicate.is_some(),
+                    onclick: move |_| {
+                        let tags = rows
+                            .read()
+                            .iter()
+                            .filter(|row| !row.key.trim().is_empty())
+                            .map(|row| CustomTag {
+                                key: row.key.trim().to_string(),
+                                value: Some(row.value.trim().to_string()).filter(|v| !v.is_empty()),
+                            })
+                            .collect();
+                        on_save.call(AlbumNotesEdit {
+                            album_id: album_id.clone(),
+                            tags,
+                            notes: text.read().trim().to_string(),
+                        });
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+/// First tag key that appears more than once, ignoring case
+fn duplicate_key(rows: &[TagDraft]) -> Option<String> {
+    let mut seen = HashSet::new();
+    rows.iter()
+        .map(|row| row.key.trim())
+        .filter(|key| !key.is_empty())
+        .find(|key| !seen.insert(key.to_ascii_lowercase()))
+        .map(str::to_string)
+}
@@ -0,0 +1,39 @@
+//! Artist credit line -- each credited artist links to their page, joined
+//! the way the credit reads ("Glass Harbor feat. Tidewater")
+
+use crate::components::TextLink;
+use crate::display_types::CreditedArtist;
+use dioxus::prelude::*;
+
+#[component]
+pub fn ArtistCredit(
+    credit: Vec<CreditedArtist>,
+    /// Show names as plain text, e.g. while the track is importing
+    #[props(default)]
+    disabled: bool,
+    on_artist_click: EventHandler<String>,
+) -> Element {
+    let last = credit.len().saturating_sub(1);
+
+    rsx! {
+        for (i , credited) in credit.iter().enumerate() {
+            if disabled {
+                span { "{credited.artist.name}" }
+            } else {
+                TextLink {
+                    onclick: {
+                        let artist_id = credited.artist.id.clone();
+                        move |evt: Event<MouseData>| {
+                            evt.stop_propagation();
+                            on_artist_click.call(artist_id.clone());
+                        }
+                    },
+                    "{credited.artist.name}"
+                }
+            }
+            if i < last {
+                {credited.join_phrase.clone().unwrap_or_else(|| ", ".to_string())}
+            }
+        }
+    }
+}
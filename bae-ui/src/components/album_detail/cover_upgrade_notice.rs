@@ -0,0 +1,34 @@
+//! Offer to replace a low-resolution cover with a larger one found online
+
+use crate::components::{Button, ButtonSize, ButtonVariant};
+use crate::display_types::{CoverChange, CoverUpgradeOffer};
+use dioxus::prelude::*;
+
+#[component]
+pub fn CoverUpgradeNotice(
+    offer: CoverUpgradeOffer,
+    on_accept: EventHandler<CoverChange>,
+) -> Element {
+    rsx! {
+        div { class: "mb-6 -mt-3 flex items-center justify-between gap-3 px-3 py-2 rounded-lg bg-gray-800/50",
+            p { class: "text-xs text-gray-400",
+                "A {offer.width}×{offer.height} cover is available from {offer.label} "
+                "(this one is {offer.current_width}×{offer.current_height})"
+            }
+            Button {
+                variant: ButtonVariant::Secondary,
+                size: ButtonSize::Small,
+                onclick: {
+                    let offer = offer.clone();
+                    move |_| {
+                        on_accept.call(CoverChange::RemoteCover {
+                            url: offer.url.clone(),
+                            source: offer.source.clone(),
+                        });
+                    }
+                },
+                "Use it"
+            }
+        }
+    }
+}
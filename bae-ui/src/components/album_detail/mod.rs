@@ -1,28 +1,44 @@
 //! Album detail view components
 
 mod album_art;
+mod album_comments;
 mod album_cover_section;
 mod album_metadata;
+mod album_notes;
+mod artist_credit;
 mod cover_picker;
+mod cover_upgrade_notice;
 mod delete_album_dialog;
 mod delete_release_dialog;
 mod export_error_toast;
+mod musicbrainz_refresh_modal;
 mod play_album_button;
 mod release_info_modal;
 pub mod release_tabs_section;
 mod storage_modal;
+mod tag_editor;
+mod track_mapping_modal;
 mod track_row;
+mod version_picker;
 mod view;
 
 pub use album_art::AlbumArt;
+pub use album_comments::AlbumComments;
 pub use album_cover_section::AlbumCoverSection;
 pub use album_metadata::AlbumMetadata;
+pub use album_notes::AlbumNotes;
+pub use artist_credit::ArtistCredit;
+pub use cover_upgrade_notice::CoverUpgradeNotice;
 pub use delete_album_dialog::DeleteAlbumDialog;
 pub use delete_release_dialog::DeleteReleaseDialog;
 pub use export_error_toast::ExportErrorToast;
+pub use musicbrainz_refresh_modal::MusicBrainzRefreshModal;
 pub use play_album_button::PlayAlbumButton;
 pub use release_info_modal::ReleaseInfoModal;
 pub use release_tabs_section::ReleaseTabsSection;
 pub use storage_modal::StorageModal;
+pub use tag_editor::TagEditor;
+pub use track_mapping_modal::TrackMappingModal;
 pub use track_row::TrackRow;
+pub use version_picker::VersionPicker;
 pub use view::AlbumDetailView;
@@ -0,0 +1,156 @@
+//! MusicBrainz refresh modal -- review what MusicBrainz has changed since import
+
+use crate::components::icons::XIcon;
+use crate::components::{Button, ButtonSize, ButtonVariant, Modal};
+use crate::display_types::{MetadataChange, MetadataUpdate};
+use dioxus::prelude::*;
+
+#[component]
+pub fn MusicBrainzRefreshModal(
+    is_open: ReadSignal<bool>,
+    /// None while MusicBrainz is being fetched
+    changes: Option<Vec<MetadataChange>>,
+    error: Option<String>,
+    on_close: EventHandler<()>,
+    /// Called with the updates the user kept ticked
+    on_apply: EventHandler<Vec<MetadataUpdate>>,
+) -> Element {
+    rsx! {
+        Modal { is_open, on_close: move |_| on_close.call(()),
+            div { class: "bg-gray-800 rounded-lg shadow-xl max-w-2xl w-full mx-4 max-h-[80vh] flex flex-col",
+                div { class: "flex items-center justify-between px-6 pt-6 pb-4 border-b border-gray-700",
+                    h2 { class: "text-xl font-bold text-white", "Refresh from MusicBrainz" }
+                    button {
+                        class: "text-gray-400 hover:text-white transition-colors",
+                        onclick: move |_| on_close.call(()),
+                        XIcon { class: "w-5 h-5" }
+                    }
+                }
+                if let Some(ref error) = error {
+                    div { class: "mx-6 mt-4 p-3 bg-red-900/30 border border-red-700/50 rounded-lg text-sm text-red-300",
+                        {error.clone()}
+                    }
+                }
+                if let Some(changes) = changes {
+                    if changes.is_empty() {
+                        div { class: "p-6 text-sm text-gray-400",
+                            "Everything matches MusicBrainz."
+                        }
+                    } else {
+                        ChangeReview { changes, on_cancel: on_close, on_apply }
+                    }
+                } else if error.is_none() {
+                    div { class: "p-6 text-sm text-gray-400", "Checking MusicBrainz..." }
+                }
+            }
+        }
+    }
+}
+
+/// List of changes, all ticked to start with
+#[component]
+fn ChangeReview(
+    changes: Vec<MetadataChange>,
+    on_cancel: EventHandler<()>,
+    on_apply: EventHandler<Vec<MetadataUpdate>>,
+) -> Element {
+    let count = changes.len();
+    let mut ticked = use_signal(move || vec![true; count]);
+    let selected = ticked.read().iter().filter(|t| **t).count();
+
+    let to_apply = changes.clone();
+
+    rsx! {
+        div { class: "p-6 overflow-y-auto flex-1 space-y-1",
+            p { class: "text-sm text-gray-400 mb-4",
+                "MusicBrainz has different values for these tags. Untick anything you want to keep."
+            }
+            for (index , change) in changes.iter().enumerate() {
+                ChangeRow {
+                    key: "{index}",
+                    label: change_label(&change.update),
+                    current: change.current.clone(),
+                    proposed: change.proposed.clone(),
+                    checked: ticked.read()[index],
+                    on_toggle: move |_| {
+                        let mut ticked = ticked.write();
+                        ticked[index] = !ticked[index];
+                    },
+                }
+            }
+        }
+        div { class: "flex justify-end gap-3 px-6 py-4 border-t border-gray-700",
+            Button {
+                variant: ButtonVariant::Secondary,
+                size: ButtonSize::Medium,
+                onclick: move |_| on_cancel.call(()),
+                "Cancel"
+            }
+            Button {
+                variant: ButtonVariant::Primary,
+                size: ButtonSize::Medium,
+                disabled: selected == 0,
+                onclick: move |_| {
+                    let ticked = ticked.read();
+                    let updates = to_apply
+                        .iter()
+                        .zip(ticked.iter())
+                        .filter(|(_, ticked)| **ticked)
+                        .map(|(change, _)| change.update.clone())
+                        .collect();
+                    on_apply.call(updates);
+                },
+                "Apply {selected} changes"
+            }
+        }
+    }
+}
+
+#[component]
+fn ChangeRow(
+    label: String,
+    current: String,
+    proposed: String,
+    checked: bool,
+    on_toggle: EventHandler<()>,
+) -> Element {
+    rsx! {
+        label { class: "flex items-center gap-3 px-3 py-2 rounded-lg hover:bg-hover text-sm cursor-pointer",
+            input {
+                r#type: "checkbox",
+                class: "w-4 h-4 rounded bg-gray-700 border-gray-600 text-indigo-600 focus:ring-indigo-500",
+                checked,
+                onchange: move |_| on_toggle.call(()),
+            }
+            span { class: "w-20 shrink-0 text-gray-500", "{label}" }
+            div { class: "flex-1 min-w-0 truncate",
+                if current.is_empty() {
+                    span { class: "text-gray-500 italic", "empty" }
+                } else {
+                    span { class: "text-gray-400 line-through", "{current}" }
+                }
+                span { class: "text-gray-500 mx-2", "→" }
+                span { class: "text-white", "{proposed}" }
+            }
+        }
+    }
+}
+
+fn change_label(update: &MetadataUpdate) -> String {
+    match update {
+        MetadataUpdate::Title(_) => "Title".to_string(),
+        MetadataUpdate::Year(_) => "Year".to_string(),
+        MetadataUpdate::Artists(_) => "Artists".to_string(),
+        MetadataUpdate::TrackTitle {
+            disc_number: Some(disc),
+            track_number: Some(track),
+            ..
+        } => format!("Track {disc}-{track}"),
+        MetadataUpdate::TrackTitle {
+            track_number: Some(track),
+            ..
+        } => format!("Track {track}"),
+        MetadataUpdate::TrackTitle { .. } => "Track".to_string(),
+        MetadataUpdate::DiscTitle { disc_number, .. } => format!("Disc {disc_number}"),
+    }
+}
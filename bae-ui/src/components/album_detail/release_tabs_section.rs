@@ -25,6 +25,8 @@ pub fn ReleaseTabsSection(
     export_error: Signal<Option<String>>,
     on_view_files: EventHandler<String>,
     on_view_storage: EventHandler<String>,
+    /// Called with release_id to open the track mapping editor
+    on_remap_tracks: EventHandler<String>,
     on_delete_release: EventHandler<String>,
     on_export: EventHandler<String>,
     /// Whether the current release is on cloud storage (share requires cloud)
@@ -69,6 +71,10 @@ pub fn ReleaseTabsSection(
                                     let release_id = release_id.clone();
                                     move |_| on_view_storage.call(release_id.clone())
                                 },
+                                on_remap_tracks: {
+                                    let release_id = release_id.clone();
+                                    move |_| on_remap_tracks.call(release_id.clone())
+                                },
                                 is_on_cloud,
                                 on_copy_share_link: {
                                     let release_id = release_id.clone();
@@ -114,6 +120,7 @@ fn ReleaseTab(
     torrent: ReleaseTorrentInfo,
     on_view_files: EventHandler<()>,
     on_view_storage: EventHandler<()>,
+    on_remap_tracks: EventHandler<()>,
     is_on_cloud: bool,
     on_copy_share_link: EventHandler<()>,
     on_export: EventHandler<()>,
@@ -205,6 +212,14 @@ fn ReleaseTab(
                         },
                         "Storage"
                     }
+                    MenuItem {
+                        disabled: is_deleting() || is_exporting(),
+                        onclick: move |_| {
+                            show_release_dropdown.set(None);
+                            on_remap_tracks.call(());
+                        },
+                        "Track Mapping"
+                    }
                     if is_on_cloud {
                         MenuItem {
                             disabled: is_deleting() || is_exporting(),
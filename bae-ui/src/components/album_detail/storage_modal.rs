@@ -2,11 +2,11 @@
 
 use crate::components::icons::{
     AlertTriangleIcon, ArrowRightLeftIcon, CloudIcon, DownloadIcon, FolderIcon, HardDriveIcon,
-    LoaderIcon, XIcon,
+    ImageIcon, LoaderIcon, MonitorIcon, XIcon,
 };
 use crate::components::utils::format_file_size;
 use crate::components::Modal;
-use crate::display_types::File;
+use crate::display_types::{DeviceAvailability, File, OfflinePin, ReleaseAvailability};
 use crate::stores::album_detail::TransferProgressState;
 use dioxus::prelude::*;
 
@@ -20,8 +20,19 @@ pub fn StorageModal(
     is_unmanaged: bool,
     transfer_progress: Option<TransferProgressState>,
     transfer_error: Option<String>,
+    /// None while availability is loading
+    availability: Option<ReleaseAvailability>,
+    /// Whether the library has a cloud home to move files into
+    cloud_home_configured: bool,
+    /// How much of the release is kept downloaded (None when it isn't)
+    offline_pin: Option<OfflinePin>,
+    offline_error: Option<String>,
+    /// Called when keeping the release downloaded is switched on or off
+    on_keep_downloaded: EventHandler<bool>,
     on_transfer_to_managed: EventHandler<()>,
+    on_move_to_cloud: EventHandler<()>,
     on_eject: EventHandler<()>,
+    on_cancel_transfer: EventHandler<()>,
 ) -> Element {
     let total_size: i64 = files.iter().map(|f| f.file_size).sum();
     let is_transferring = transfer_progress.is_some();
@@ -47,9 +58,17 @@ pub fn StorageModal(
                         total_size,
                         file_count: files.len(),
                     }
+                    AvailabilitySection { availability }
+                    // Only releases that stream from the cloud home need downloading
+                    if managed_in_cloud && !managed_locally {
+                        OfflineSection { offline_pin, offline_error, on_keep_downloaded }
+                    }
                     // Transfer progress
                     if let Some(ref progress) = transfer_progress {
-                        TransferProgressSection { progress: progress.clone() }
+                        TransferProgressSection {
+                            progress: progress.clone(),
+                            on_cancel: move |_| on_cancel_transfer.call(()),
+                        }
                     }
                     // Transfer error
                     if let Some(ref error) = transfer_error {
@@ -66,7 +85,10 @@ pub fn StorageModal(
                         TransferActionsSection {
                             is_unmanaged,
                             managed_locally,
+                            managed_in_cloud,
+                            cloud_home_configured,
                             on_transfer_to_managed,
+                            on_move_to_cloud,
                             on_eject,
                         }
                     }
@@ -177,8 +199,143 @@ fn StorageStatusSection(
     }
 }
 
+/// Every place a copy of the release lives
+#[component]
+fn AvailabilitySection(availability: Option<ReleaseAvailability>) -> Element {
+    let Some(availability) = availability else {
+        return rsx! {
+            div { class: "text-sm text-gray-400", "Checking where this release is stored..." }
+        };
+    };
+    let file_count = availability.file_count;
+    let cover_detail = availability.cover_synced.map(|synced| {
+        if synced {
+            "Cover image uploaded".to_string()
+        } else {
+            "Cover image not uploaded yet".to_string()
+        }
+    });
+
+    rsx! {
+        div {
+            div { class: "text-sm font-medium text-gray-300 mb-3", "Where it lives" }
+            div { class: "space-y-2",
+                if availability.in_cloud {
+                    AvailabilityRow {
+                        label: "Cloud home",
+                        detail: "All {file_count} files",
+                        CloudIcon { class: "w-4 h-4 text-blue-400" }
+                    }
+                }
+                for device in availability.devices.iter() {
+                    AvailabilityRow {
+                        key: "{device.device_id}",
+                        label: device_label(device),
+                        detail: device_detail(device, file_count),
+                        if device.is_this_device {
+                            HardDriveIcon { class: "w-4 h-4 text-blue-400" }
+                        } else {
+                            MonitorIcon { class: "w-4 h-4 text-gray-400" }
+                        }
+                    }
+                }
+                if !availability.in_cloud && availability.devices.is_empty() {
+                    div { class: "text-sm text-gray-400",
+                        "No device or cloud storage has reported holding these files."
+                    }
+                }
+                if let Some(detail) = cover_detail {
+                    AvailabilityRow {
+                        label: "Sync bucket",
+                        detail,
+                        ImageIcon { class: "w-4 h-4 text-gray-400" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn AvailabilityRow(label: String, detail: String, children: Element) -> Element {
+    rsx! {
+        div { class: "flex items-center gap-3 py-2 px-3 bg-gray-700/50 rounded",
+            {children}
+            div { class: "flex-1 text-sm text-white", {label} }
+            div { class: "text-xs text-gray-400", {detail} }
+        }
+    }
+}
+
+fn device_label(device: &DeviceAvailability) -> String {
+    if device.is_this_device {
+        "This device".to_string()
+    } else {
+        let short_id: String = device.device_id.chars().take(8).collect();
+        format!("Device {short_id}")
+    }
+}
+
+fn device_detail(device: &DeviceAvailability, file_count: usize) -> String {
+    if device.has_files {
+        format!("All {file_count} files")
+    } else {
+        format!("{} of {file_count} files cached", device.cached_files)
+    }
+}
+
+/// Keeping a cloud-only release downloaded so it plays without a network
 #[component]
-fn TransferProgressSection(progress: TransferProgressState) -> Element {
+fn OfflineSection(
+    offline_pin: Option<OfflinePin>,
+    offline_error: Option<String>,
+    on_keep_downloaded: EventHandler<bool>,
+) -> Element {
+    let is_pinned = offline_pin.is_some();
+    let detail = match offline_pin {
+        None => "Download the files so this release plays without a network connection".to_string(),
+        Some(pin) if pin.downloaded_files == pin.total_files => format!(
+            "Downloaded • {}",
+            format_file_size(pin.downloaded_bytes as i64)
+        ),
+        Some(pin) => format!(
+            "{} of {} files downloaded • {}",
+            pin.downloaded_files,
+            pin.total_files,
+            format_file_size(pin.downloaded_bytes as i64),
+        ),
+    };
+
+    rsx! {
+        div { class: "space-y-3",
+            div { class: "text-sm font-medium text-gray-300", "Offline" }
+            label { class: "w-full flex items-center gap-3 p-3 bg-gray-700/50 hover:bg-gray-700 rounded-lg transition-colors cursor-pointer",
+                div { class: "p-1.5 rounded bg-gray-600/50",
+                    DownloadIcon { class: "w-4 h-4 text-gray-300" }
+                }
+                div { class: "flex-1",
+                    div { class: "text-sm text-white", "Keep downloaded" }
+                    div { class: "text-xs text-gray-400", {detail} }
+                }
+                input {
+                    r#type: "checkbox",
+                    checked: is_pinned,
+                    onchange: move |evt: Event<FormData>| on_keep_downloaded.call(evt.checked()),
+                    class: "w-4 h-4 rounded border-gray-600 bg-gray-700 text-indigo-500 focus:ring-indigo-500 cursor-pointer",
+                }
+            }
+            if let Some(error) = offline_error {
+                div { class: "text-xs text-red-400", {error} }
+            }
+        }
+    }
+}
+
+#[component]
+fn TransferProgressSection(
+    progress: TransferProgressState,
+    on_cancel: EventHandler<()>,
+) -> Element {
     let overall_percent = if progress.total_files > 0 {
         let file_weight = 100.0 / progress.total_files as f64;
         let completed = progress.file_index as f64 * file_weight;
@@ -192,7 +349,12 @@ fn TransferProgressSection(progress: TransferProgressState) -> Element {
         div { class: "p-4 bg-blue-900/20 border border-blue-700/30 rounded-lg space-y-3",
             div { class: "flex items-center gap-2",
                 LoaderIcon { class: "w-4 h-4 text-blue-400 animate-spin" }
-                div { class: "text-sm font-medium text-blue-300", "Transferring..." }
+                div { class: "flex-1 text-sm font-medium text-blue-300", "Transferring..." }
+                button {
+                    class: "text-xs text-gray-400 hover:text-white transition-colors",
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
             }
             div { class: "text-xs text-gray-400",
                 {
@@ -219,15 +381,21 @@ fn TransferProgressSection(progress: TransferProgressState) -> Element {
 fn TransferActionsSection(
     is_unmanaged: bool,
     managed_locally: bool,
+    managed_in_cloud: bool,
+    cloud_home_configured: bool,
     on_transfer_to_managed: EventHandler<()>,
+    on_move_to_cloud: EventHandler<()>,
     on_eject: EventHandler<()>,
 ) -> Element {
     // Show "Copy to library" for unmanaged releases
     let can_copy_to_managed = is_unmanaged;
+    // Releases only in the cloud home can come back to this device
+    let can_move_to_local = managed_in_cloud && !managed_locally;
+    let can_move_to_cloud = cloud_home_configured && !managed_in_cloud;
     // Show "Eject" for managed releases
-    let can_eject = managed_locally;
+    let can_eject = managed_locally || managed_in_cloud;
 
-    if !can_copy_to_managed && !can_eject {
+    if !can_copy_to_managed && !can_move_to_local && !can_move_to_cloud && !can_eject {
         return rsx! {};
     }
 
@@ -251,6 +419,42 @@ fn TransferActionsSection(
                     ArrowRightLeftIcon { class: "w-4 h-4 text-gray-400" }
                 }
             }
+            if can_move_to_local || can_move_to_cloud {
+                div {
+                    div { class: "text-sm font-medium text-gray-300", "Move storage" }
+                    div { class: "text-xs text-gray-500 mt-1",
+                        "Files are copied and verified before the originals are removed"
+                    }
+                }
+            }
+            if can_move_to_local {
+                button {
+                    class: "w-full flex items-center gap-3 p-3 bg-gray-700/50 hover:bg-gray-700 rounded-lg transition-colors text-left",
+                    onclick: move |_| on_transfer_to_managed.call(()),
+                    div { class: "p-1.5 rounded bg-gray-600/50",
+                        HardDriveIcon { class: "w-4 h-4 text-gray-300" }
+                    }
+                    div { class: "flex-1",
+                        div { class: "text-sm text-white", "Move to this device" }
+                        div { class: "text-xs text-gray-400", "Download files from the cloud home" }
+                    }
+                    ArrowRightLeftIcon { class: "w-4 h-4 text-gray-400" }
+                }
+            }
+            if can_move_to_cloud {
+                button {
+                    class: "w-full flex items-center gap-3 p-3 bg-gray-700/50 hover:bg-gray-700 rounded-lg transition-colors text-left",
+                    onclick: move |_| on_move_to_cloud.call(()),
+                    div { class: "p-1.5 rounded bg-gray-600/50",
+                        CloudIcon { class: "w-4 h-4 text-gray-300" }
+                    }
+                    div { class: "flex-1",
+                        div { class: "text-sm text-white", "Move to cloud home" }
+                        div { class: "text-xs text-gray-400", "Encrypt and upload files to the cloud home" }
+                    }
+                    ArrowRightLeftIcon { class: "w-4 h-4 text-gray-400" }
+                }
+            }
             if can_eject {
                 button {
                     class: "w-full flex items-center gap-3 p-3 bg-gray-700/50 hover:bg-gray-700 rounded-lg transition-colors text-left",
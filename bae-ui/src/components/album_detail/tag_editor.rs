@@ -0,0 +1,240 @@
+//! Tag editor -- fix an album's title, artists, year and tracklist in place
+
+use crate::components::icons::{PlusIcon, XIcon};
+use crate::components::{
+    Button, ButtonSize, ButtonVariant, ChromelessButton, TextInput, TextInputSize, TextInputType,
+};
+use crate::display_types::{Album, AlbumTagEdit, Artist, Track, TrackTagEdit};
+use dioxus::prelude::*;
+
+/// A track row while it's being edited. Numbers stay text until save.
+#[derive(Clone, Debug, PartialEq)]
+struct TrackDraft {
+    track_id: String,
+    title: String,
+    disc_number: String,
+    track_number: String,
+}
+
+/// Editable copy of the album's tags, written back only on save
+#[component]
+pub fn TagEditor(
+    album: Album,
+    artists: Vec<Artist>,
+    /// Tracks of the selected release
+    tracks: Vec<Track>,
+    release_id: String,
+    on_cancel: EventHandler<()>,
+    on_save: EventHandler<AlbumTagEdit>,
+) -> Element {
+    let mut title = use_signal(|| album.title.clone());
+    let mut year = use_signal(|| album.year.map(|y| y.to_string()).unwrap_or_default());
+    let mut artist_names = use_signal(|| {
+        let names: Vec<String> = artists.iter().map(|a| a.name.clone()).collect();
+        if names.is_empty() {
+            vec![String::new()]
+        } else {
+            names
+        }
+    });
+    let mut rows = use_signal(|| {
+        tracks
+            .iter()
+            .map(|t| TrackDraft {
+                track_id: t.id.clone(),
+                title: t.title.clone(),
+                disc_number: t.disc_number.map(|n| n.to_string()).unwrap_or_default(),
+                track_number: t.track_number.map(|n| n.to_string()).unwrap_or_default(),
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let edit = build_edit(
+        &album.id,
+        &release_id,
+        &title.read(),
+        &year.read(),
+        &artist_names.read(),
+        &rows.read(),
+    );
+    let problem = edit.as_ref().err().cloned();
+    let can_save = edit.is_ok();
+    let current_artists = artist_names.read().clone();
+    let current_rows = rows.read().clone();
+
+    rsx! {
+        div { class: "space-y-6",
+            div { class: "flex items-center justify-between gap-4",
+                h2 { class: "text-xl font-bold text-white", "Edit Tags" }
+                div { class: "flex gap-3",
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Small,
+                        onclick: move |_| on_cancel.call(()),
+                        "Cancel"
+                    }
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Small,
+                        disabled: !can_save,
+                        onclick: move |_| {
+                            if let Ok(ref edit) = edit {
+                                on_save.call(edit.clone());
+                            }
+                        },
+                        "Save"
+                    }
+                }
+            }
+
+            if let Some(ref problem) = problem {
+                p { class: "text-sm text-red-400", "{problem}" }
+            }
+
+            // Album
+            div { class: "grid grid-cols-[1fr_6rem] gap-3",
+                div { class: "space-y-1",
+                    label { class: "text-xs text-gray-500", "Title" }
+                    TextInput {
+                        value: title(),
+                        on_input: move |v| title.set(v),
+                        size: TextInputSize::Medium,
+                        input_type: TextInputType::Text,
+                    }
+                }
+                div { class: "space-y-1",
+                    label { class: "text-xs text-gray-500", "Year" }
+                    TextInput {
+                        value: year(),
+                        on_input: move |v| year.set(v),
+                        size: TextInputSize::Medium,
+                        input_type: TextInputType::Text,
+                    }
+                }
+            }
+            div { class: "space-y-1",
+                label { class: "text-xs text-gray-500", "Artists" }
+                for (index , name) in current_artists.iter().enumerate() {
+                    div { key: "{index}", class: "flex items-center gap-2",
+                        TextInput {
+                            value: name.clone(),
+                            on_input: move |v| artist_names.write()[index] = v,
+                            size: TextInputSize::Medium,
+                            input_type: TextInputType::Text,
+                        }
+                        if current_artists.len() > 1 {
+                            ChromelessButton {
+                                class: Some("p-1 text-gray-400 hover:text-red-400".to_string()),
+                                aria_label: Some("Remove artist".to_string()),
+                                onclick: move |_| {
+                                    artist_names.write().remove(index);
+                                },
+                                XIcon { class: "w-3.5 h-3.5" }
+                            }
+                        }
+                    }
+                }
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    size: ButtonSize::Small,
+                    onclick: move |_| artist_names.write().push(String::new()),
+                    PlusIcon { class: "w-3.5 h-3.5" }
+                    "Add artist"
+                }
+            }
+
+            // Tracks
+            div { class: "space-y-1",
+                div { class: "flex items-center gap-2 text-xs text-gray-500",
+                    span { class: "w-14", "Disc" }
+                    span { class: "w-14", "Track" }
+                    span { class: "flex-1", "Title" }
+                }
+                for (index , row) in current_rows.iter().enumerate() {
+                    div { key: "{row.track_id}", class: "flex items-center gap-2",
+                        div { class: "w-14 shrink-0",
+                            TextInput {
+                                value: row.disc_number.clone(),
+                                on_input: move |v| rows.write()[index].disc_number = v,
+                                size: TextInputSize::Small,
+                                input_type: TextInputType::Text,
+                            }
+                        }
+                        div { class: "w-14 shrink-0",
+                            TextInput {
+                                value: row.track_number.clone(),
+                                on_input: move |v| rows.write()[index].track_number = v,
+                                size: TextInputSize::Small,
+                                input_type: TextInputType::Text,
+                            }
+                        }
+                        TextInput {
+                            value: row.title.clone(),
+                            on_input: move |v| rows.write()[index].title = v,
+                            size: TextInputSize::Small,
+                            input_type: TextInputType::Text,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Turn the drafts into an edit, or say what's wrong with them
+fn build_edit(
+    album_id: &str,
+    release_id: &str,
+    title: &str,
+    year: &str,
+    artists: &[String],
+    rows: &[TrackDraft],
+) -> Result<AlbumTagEdit, String> {
+    if title.trim().is_empty() {
+        return Err("The album needs a title.".to_string());
+    }
+    let year = parse_number(year).ok_or_else(|| "Year must be a number.".to_string())?;
+    let artists: Vec<String> = artists
+        .iter()
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect();
+    if artists.is_empty() {
+        return Err("The album needs at least one artist.".to_string());
+    }
+
+    let mut tracks = Vec::with_capacity(rows.len());
+    for row in rows {
+        if row.title.trim().is_empty() {
+            return Err("Every track needs a title.".to_string());
+        }
+        let disc_number = parse_number(&row.disc_number)
+            .ok_or_else(|| "Disc numbers must be numbers.".to_string())?;
+        let track_number = parse_number(&row.track_number)
+            .ok_or_else(|| "Track numbers must be numbers.".to_string())?;
+        tracks.push(TrackTagEdit {
+            track_id: row.track_id.clone(),
+            title: row.title.trim().to_string(),
+            disc_number,
+            track_number,
+        });
+    }
+
+    Ok(AlbumTagEdit {
+        album_id: album_id.to_string(),
+        release_id: release_id.to_string(),
+        title: title.trim().to_string(),
+        year,
+        artists,
+        tracks,
+    })
+}
+
+/// Empty means no number; None means the text isn't a positive number
+fn parse_number(text: &str) -> Option<Option<i32>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(None);
+    }
+    text.parse::<i32>().ok().filter(|n| *n > 0).map(Some)
+}
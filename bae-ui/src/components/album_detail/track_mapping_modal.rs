@@ -0,0 +1,236 @@
+//! Track mapping modal -- reorder a release's tracks and reassign files to them
+
+use crate::components::icons::{ArrowDownIcon, ArrowUpIcon, XIcon};
+use crate::components::utils::format_duration;
+use crate::components::{Button, ButtonSize, ButtonVariant, Modal, Select, SelectOption};
+use crate::display_types::{TrackAudioSource, TrackMapping, TrackMappingEntry};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+/// Select value for a track with no audio
+const NO_AUDIO: &str = "__none__";
+
+#[component]
+pub fn TrackMappingModal(
+    is_open: ReadSignal<bool>,
+    /// None while the mapping is loading
+    mapping: Option<TrackMapping>,
+    error: Option<String>,
+    on_close: EventHandler<()>,
+    on_save: EventHandler<TrackMapping>,
+) -> Element {
+    rsx! {
+        Modal { is_open, on_close: move |_| on_close.call(()),
+            div { class: "bg-gray-800 rounded-lg shadow-xl max-w-2xl w-full mx-4 max-h-[80vh] flex flex-col",
+                div { class: "flex items-center justify-between px-6 pt-6 pb-4 border-b border-gray-700",
+                    h2 { class: "text-xl font-bold text-white", "Track Mapping" }
+                    button {
+                        class: "text-gray-400 hover:text-white transition-colors",
+                        onclick: move |_| on_close.call(()),
+                        XIcon { class: "w-5 h-5" }
+                    }
+                }
+                if let Some(ref error) = error {
+                    div { class: "mx-6 mt-4 p-3 bg-red-900/30 border border-red-700/50 rounded-lg text-sm text-red-300",
+                        {error.clone()}
+                    }
+                }
+                if let Some(mapping) = mapping {
+                    TrackMappingEditor {
+                        key: "{mapping.release_id}",
+                        mapping,
+                        on_cancel: on_close,
+                        on_save,
+                    }
+                } else if error.is_none() {
+                    div { class: "p-6 text-sm text-gray-400", "Loading tracks..." }
+                }
+            }
+        }
+    }
+}
+
+/// Editable copy of the mapping. Rows start from the saved mapping and are only
+/// written back on save.
+#[component]
+fn TrackMappingEditor(
+    mapping: TrackMapping,
+    on_cancel: EventHandler<()>,
+    on_save: EventHandler<TrackMapping>,
+) -> Element {
+    let mut rows = use_signal(|| mapping.entries.clone());
+
+    let current = rows.read().clone();
+    let mut use_count: HashMap<&str, usize> = HashMap::new();
+    for id in current.iter().filter_map(|r| r.source_id.as_deref()) {
+        *use_count.entry(id).or_default() += 1;
+    }
+    let has_duplicates = use_count.values().any(|&n| n > 1);
+    let unassigned = mapping
+        .sources
+        .iter()
+        .filter(|s| !use_count.contains_key(s.id.as_str()))
+        .count();
+    let changed = current != mapping.entries;
+    let can_save = changed && !has_duplicates && unassigned == 0;
+
+    let release_id = mapping.release_id.clone();
+    let sources = mapping.sources.clone();
+
+    rsx! {
+        div { class: "p-6 overflow-y-auto flex-1 space-y-1",
+            p { class: "text-sm text-gray-400 mb-4",
+                "Move tracks into the right order and pick the file each one plays."
+            }
+            for (index , row) in current.iter().enumerate() {
+                {
+                    let same_disc_above = index > 0
+                        && current[index - 1].disc_number == row.disc_number;
+                    let same_disc_below = index + 1 < current.len()
+                        && current[index + 1].disc_number == row.disc_number;
+                    let is_duplicate = row
+                        .source_id
+                        .as_deref()
+                        .is_some_and(|id| use_count.get(id).copied().unwrap_or(0) > 1);
+                    rsx! {
+                        TrackMappingRow {
+                            key: "{row.track_id}",
+                            position: disc_position(&current, index),
+                            entry: row.clone(),
+                            sources: sources.clone(),
+                            is_duplicate,
+                            can_move_up: same_disc_above,
+                            can_move_down: same_disc_below,
+                            on_move_up: move |_| rows.write().swap(index - 1, index),
+                            on_move_down: move |_| rows.write().swap(index, index + 1),
+                            on_source_change: move |value: String| {
+                                rows.write()[index].source_id = (value != NO_AUDIO).then_some(value);
+                            },
+                        }
+                    }
+                }
+            }
+        }
+        div { class: "flex items-center justify-between gap-3 px-6 py-4 border-t border-gray-700",
+            div { class: "text-sm text-red-400",
+                if has_duplicates {
+                    "A file can only be assigned to one track."
+                } else if unassigned > 0 {
+                    if unassigned == 1 {
+                        "1 file isn't assigned to a track."
+                    } else {
+                        "{unassigned} files aren't assigned to a track."
+                    }
+                }
+            }
+            div { class: "flex gap-3",
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    size: ButtonSize::Medium,
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Medium,
+                    disabled: !can_save,
+                    onclick: move |_| {
+                        let entries = renumbered(&rows.read());
+                        on_save
+                            .call(TrackMapping {
+                                release_id: release_id.clone(),
+                                entries,
+                                sources: sources.clone(),
+                            });
+                    },
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TrackMappingRow(
+    position: usize,
+    entry: TrackMappingEntry,
+    sources: Vec<TrackAudioSource>,
+    is_duplicate: bool,
+    can_move_up: bool,
+    can_move_down: bool,
+    on_move_up: EventHandler<()>,
+    on_move_down: EventHandler<()>,
+    on_source_change: EventHandler<String>,
+) -> Element {
+    let row_class = if is_duplicate {
+        "flex items-center gap-3 px-3 py-2 rounded-lg bg-red-900/20"
+    } else {
+        "flex items-center gap-3 px-3 py-2 rounded-lg hover:bg-hover"
+    };
+
+    rsx! {
+        div { class: "{row_class}",
+            div { class: "flex flex-col",
+                button {
+                    class: "text-gray-400 hover:text-white disabled:opacity-30 disabled:hover:text-gray-400",
+                    disabled: !can_move_up,
+                    onclick: move |_| on_move_up.call(()),
+                    ArrowUpIcon { class: "w-3 h-3" }
+                }
+                button {
+                    class: "text-gray-400 hover:text-white disabled:opacity-30 disabled:hover:text-gray-400",
+                    disabled: !can_move_down,
+                    onclick: move |_| on_move_down.call(()),
+                    ArrowDownIcon { class: "w-3 h-3" }
+                }
+            }
+            span { class: "w-10 text-sm text-gray-500 tabular-nums",
+                if let Some(disc) = entry.disc_number {
+                    "{disc}-{position}"
+                } else {
+                    "{position}"
+                }
+            }
+            span { class: "flex-1 min-w-0 truncate text-sm text-white", "{entry.title}" }
+            div { class: "w-64 shrink-0",
+                Select {
+                    value: entry.source_id.clone().unwrap_or_else(|| NO_AUDIO.to_string()),
+                    onchange: move |value: String| on_source_change.call(value),
+                    SelectOption { value: NO_AUDIO.to_string(), label: "No audio" }
+                    for source in sources.iter() {
+                        SelectOption {
+                            value: source.id.clone(),
+                            label: source_label(source),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn source_label(source: &TrackAudioSource) -> String {
+    match source.duration_ms {
+        Some(ms) => format!("{} ({})", source.filename, format_duration(ms)),
+        None => source.filename.clone(),
+    }
+}
+
+/// 1-based position of a row within its disc
+fn disc_position(rows: &[TrackMappingEntry], index: usize) -> usize {
+    rows[..=index]
+        .iter()
+        .filter(|r| r.disc_number == rows[index].disc_number)
+        .count()
+}
+
+/// Number tracks by their order within each disc
+fn renumbered(rows: &[TrackMappingEntry]) -> Vec<TrackMappingEntry> {
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| TrackMappingEntry {
+            track_number: Some(disc_position(rows, index) as i32),
+            ..row.clone()
+        })
+        .collect()
+}
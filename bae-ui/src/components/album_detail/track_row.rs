@@ -3,10 +3,11 @@
 //! Accepts `ReadStore<Track>` for per-track reactivity.
 //! Only this row re-renders when its track's import state changes.
 
+use super::ArtistCredit;
 use crate::components::icons::{EllipsisIcon, PauseIcon, PlayIcon};
 use crate::components::utils::format_duration;
-use crate::components::{ChromelessButton, MenuDropdown, MenuItem, Placement, TextLink};
-use crate::display_types::{Artist, TrackImportState};
+use crate::components::{ChromelessButton, MenuDivider, MenuDropdown, MenuItem, Placement};
+use crate::display_types::{Artist, CreditedArtist, Playlist, TrackImportState};
 use dioxus::prelude::*;
 
 /// Individual track row component - reads from its track store for granular reactivity
@@ -16,6 +17,12 @@ pub fn TrackRow(
     track: ReadStore<crate::display_types::Track>,
     artists: Vec<Artist>,
     release_id: String,
+    /// Shown in place of the track title when the track is a movement of a work
+    #[props(default)]
+    movement_title: Option<String>,
+    /// The track's own artist credit, when it differs from the album's
+    #[props(default)]
+    credit: Vec<CreditedArtist>,
     // Album context
     is_compilation: bool,
     // Playback state (from external playback store)
@@ -26,12 +33,17 @@ pub fn TrackRow(
     /// When true, hides export and other local-only actions
     #[props(default)]
     read_only: bool,
+    /// Playlists offered in the track menu
+    #[props(default)]
+    playlists: Vec<Playlist>,
     // Callbacks
     on_play: EventHandler<String>,
     on_pause: EventHandler<()>,
     on_resume: EventHandler<()>,
     on_add_next: EventHandler<String>,
     on_add_to_queue: EventHandler<String>,
+    /// Called with (track ID, playlist ID)
+    on_add_to_playlist: EventHandler<(String, String)>,
     on_export: EventHandler<String>,
     on_artist_click: EventHandler<String>,
 ) -> Element {
@@ -66,6 +78,19 @@ pub fn TrackRow(
     // For styling: unavailable tracks look like "importing"
     let is_importing = !is_available;
 
+    let title = movement_title.unwrap_or_else(|| track.title.clone());
+    // Compilation tracks without a credit of their own show the album's artists
+    let credit = if credit.is_empty() && is_compilation {
+        artists
+            .into_iter()
+            .map(|artist| CreditedArtist {
+                artist,
+                join_phrase: None,
+            })
+            .collect()
+    } else {
+        credit
+    };
     let track_id = track.id.clone();
     let track_id_for_play = track_id.clone();
     let track_id_for_menu = track_id.clone();
@@ -132,31 +157,13 @@ pub fn TrackRow(
                 h3 {
                     class: "font-medium transition-colors truncate",
                     class: if is_importing { "text-gray-500" } else if is_active { "text-accent-soft" } else { "text-white group-hover:text-accent-soft" },
-                    "{track.title}"
+                    "{title}"
                 }
-                if is_compilation && !artists.is_empty() {
+                if !credit.is_empty() {
                     p {
                         class: "text-sm",
                         class: if is_importing { "text-gray-600" } else { "text-gray-400" },
-                        for (i , artist) in artists.iter().enumerate() {
-                            if i > 0 {
-                                ", "
-                            }
-                            if is_importing {
-                                span { "{artist.name}" }
-                            } else {
-                                TextLink {
-                                    onclick: {
-                                        let artist_id = artist.id.clone();
-                                        move |evt: Event<MouseData>| {
-                                            evt.stop_propagation();
-                                            on_artist_click.call(artist_id.clone());
-                                        }
-                                    },
-                                    "{artist.name}"
-                                }
-                            }
-                        }
+                        ArtistCredit { credit, disabled: is_importing, on_artist_click }
                     }
                 }
             }
@@ -180,23 +187,27 @@ pub fn TrackRow(
                 TrackMenu {
                     track_id: track_id_for_menu,
                     read_only,
+                    playlists,
                     on_export,
                     on_add_next,
                     on_add_to_queue,
+                    on_add_to_playlist,
                 }
             }
         }
     }
 }
 
-/// Track context menu (export, play next, add to queue)
+/// Track context menu (export, play next, add to queue, add to a playlist)
 #[component]
 fn TrackMenu(
     track_id: String,
     read_only: bool,
+    playlists: Vec<Playlist>,
     on_export: EventHandler<String>,
     on_add_next: EventHandler<String>,
     on_add_to_queue: EventHandler<String>,
+    on_add_to_playlist: EventHandler<(String, String)>,
 ) -> Element {
     let mut show_menu = use_signal(|| false);
     let is_open: ReadSignal<bool> = show_menu.into();
@@ -257,6 +268,23 @@ fn TrackMenu(
                 },
                 "Add to Queue"
             }
+            if !read_only && !playlists.is_empty() {
+                MenuDivider {}
+                for playlist in playlists.iter() {
+                    MenuItem {
+                        key: "{playlist.id}",
+                        onclick: {
+                            let track_id = track_id.clone();
+                            let playlist_id = playlist.id.clone();
+                            move |_| {
+                                show_menu.set(false);
+                                on_add_to_playlist.call((track_id.clone(), playlist_id.clone()));
+                            }
+                        },
+                        "Add to {playlist.name}"
+                    }
+                }
+            }
         }
     }
 }
@@ -0,0 +1,163 @@
+//! Version picker for albums linked as editions of the same work
+
+use crate::components::{ChromelessButton, MenuDivider, MenuDropdown, MenuItem, Placement};
+use crate::display_types::{Album, AlbumVersion};
+use dioxus::prelude::*;
+
+/// Pill row for switching between linked versions, plus a menu to link,
+/// unlink or name them
+#[component]
+pub fn VersionPicker(
+    /// ID of the album currently shown
+    album_id: String,
+    /// All versions in the group, including the current album (empty if unlinked)
+    versions: Vec<AlbumVersion>,
+    /// Albums that can be linked as a version of this one
+    candidates: Vec<Album>,
+    /// When true, hides link/unlink actions
+    read_only: bool,
+    on_version_select: EventHandler<String>,
+    /// Called with the album_id to link to the current album
+    on_link_version: EventHandler<String>,
+    /// Called with the current album_id to remove it from its group
+    on_unlink_version: EventHandler<String>,
+    /// Called with the current album_id and its new edition label, or None
+    /// to clear it
+    on_set_version_label: EventHandler<(String, Option<String>)>,
+) -> Element {
+    let mut show_menu = use_signal(|| false);
+    let mut editing_label = use_signal(|| false);
+    let mut label_value = use_signal(String::new);
+    let is_open: ReadSignal<bool> = show_menu.into();
+    let anchor_id = format!("version-picker-{}", album_id);
+
+    let is_linked = versions.len() > 1;
+    if !is_linked && (read_only || candidates.is_empty()) {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "flex flex-wrap items-center gap-1 mb-4",
+            if is_linked {
+                span { class: "text-xs text-gray-500 mr-1", "Versions" }
+                for version in versions.iter() {
+                    {
+                        let is_current = version.album_id == album_id;
+                        let class = if is_current {
+                            "px-2.5 py-1 text-xs rounded-full bg-surface-raised text-white whitespace-nowrap"
+                        } else {
+                            "px-2.5 py-1 text-xs rounded-full text-gray-400 hover:text-white hover:bg-hover whitespace-nowrap transition-colors"
+                        };
+                        let version_id = version.album_id.clone();
+                        if is_current && editing_label() {
+                            let album_id = album_id.clone();
+                            rsx! {
+                                input {
+                                    key: "{version.album_id}",
+                                    class: "bg-gray-700 text-white text-xs rounded-full px-2.5 py-1 border border-gray-600 focus:border-indigo-500 outline-none",
+                                    value: "{label_value}",
+                                    placeholder: "Remaster, Deluxe...",
+                                    autofocus: true,
+                                    oninput: move |e| label_value.set(e.value()),
+                                    onkeydown: move |e: KeyboardEvent| {
+                                        if e.key() == Key::Enter {
+                                            editing_label.set(false);
+                                            let label = label_value.read().trim().to_string();
+                                            let label = (!label.is_empty()).then_some(label);
+                                            on_set_version_label.call((album_id.clone(), label));
+                                        } else if e.key() == Key::Escape {
+                                            editing_label.set(false);
+                                        }
+                                    },
+                                }
+                            }
+                        } else {
+                            rsx! {
+                                ChromelessButton {
+                                    key: "{version.album_id}",
+                                    class: Some(class.to_string()),
+                                    title: Some(version.title.clone()),
+                                    onclick: move |_| {
+                                        if !is_current {
+                                            on_version_select.call(version_id.clone());
+                                        }
+                                    },
+                                    {version.display_name()}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if !read_only {
+                ChromelessButton {
+                    id: Some(anchor_id.clone()),
+                    class: Some(
+                        "px-2 py-1 text-xs rounded-full text-gray-500 hover:text-white hover:bg-hover transition-colors"
+                            .to_string(),
+                    ),
+                    onclick: move |_| show_menu.toggle(),
+                    if is_linked {
+                        "⋯"
+                    } else {
+                        "Link Version"
+                    }
+                }
+                MenuDropdown {
+                    anchor_id,
+                    is_open,
+                    on_close: move |_| show_menu.set(false),
+                    placement: Placement::BottomStart,
+                    for candidate in candidates.iter() {
+                        MenuItem {
+                            key: "{candidate.id}",
+                            onclick: {
+                                let candidate_id = candidate.id.clone();
+                                move |_| {
+                                    show_menu.set(false);
+                                    on_link_version.call(candidate_id.clone());
+                                }
+                            },
+                            if let Some(year) = candidate.year {
+                                "Link {candidate.title} ({year})"
+                            } else {
+                                "Link {candidate.title}"
+                            }
+                        }
+                    }
+                    if is_linked {
+                        if !candidates.is_empty() {
+                            MenuDivider {}
+                        }
+                        MenuItem {
+                            onclick: {
+                                let label = versions
+                                    .iter()
+                                    .find(|version| version.album_id == album_id)
+                                    .and_then(|version| version.label.clone())
+                                    .unwrap_or_default();
+                                move |_| {
+                                    show_menu.set(false);
+                                    label_value.set(label.clone());
+                                    editing_label.set(true);
+                                }
+                            },
+                            "Name This Version"
+                        }
+                        MenuItem {
+                            danger: true,
+                            onclick: {
+                                let album_id = album_id.clone();
+                                move |_| {
+                                    show_menu.set(false);
+                                    on_unlink_version.call(album_id.clone());
+                                }
+                            },
+                            "Unlink This Version"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
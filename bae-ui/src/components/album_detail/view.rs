@@ -6,22 +6,33 @@
 //! - `tracks` store enables per-track reactivity via `.iter()`
 //! - Each TrackRow only re-renders when its specific track changes
 
+use super::album_comments::AlbumComments;
 use super::album_cover_section::AlbumCoverSection;
 use super::album_metadata::AlbumMetadata;
+use super::album_notes::AlbumNotes;
 use super::cover_picker::CoverPickerWrapper;
+use super::cover_upgrade_notice::CoverUpgradeNotice;
 use super::delete_album_dialog::DeleteAlbumDialog;
 use super::delete_release_dialog::DeleteReleaseDialog;
 use super::export_error_toast::ExportErrorToast;
+use super::musicbrainz_refresh_modal::MusicBrainzRefreshModal;
 use super::play_album_button::PlayAlbumButton;
 use super::release_info_modal::ReleaseInfoModal;
 use super::release_tabs_section::{ReleaseTabsSection, ReleaseTorrentInfo};
 use super::storage_modal::StorageModal;
+use super::tag_editor::TagEditor;
+use super::track_mapping_modal::TrackMappingModal;
 use super::track_row::TrackRow;
-use crate::components::{GalleryItem, GalleryItemContent, GalleryLightbox};
-use crate::display_types::{CoverChange, PlaybackDisplay, Release, Track};
+use super::version_picker::VersionPicker;
+use crate::components::icons::{ChevronDownIcon, ChevronRightIcon, PlayIcon, PlusIcon};
+use crate::components::{GalleryItem, GalleryItemContent, GalleryLightbox, TextLink};
+use crate::display_types::{
+    AlbumNotesEdit, AlbumTagEdit, CoverChange, CoverUpgradeOffer, MetadataUpdate, PlaybackDisplay,
+    Playlist, Release, Track, TrackMapping, TrackWork,
+};
 use crate::stores::album_detail::{AlbumDetailState, AlbumDetailStateStoreExt};
 use dioxus::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Album detail view component
 ///
@@ -38,6 +49,14 @@ pub fn AlbumDetailView(
     #[props(default)]
     read_only: bool,
     on_release_select: EventHandler<String>,
+    /// Called with album_id when another linked version is picked
+    on_version_select: EventHandler<String>,
+    /// Called with album_id of another album to link as a version of this one
+    on_link_version: EventHandler<String>,
+    /// Called with album_id to remove it from its version group
+    on_unlink_version: EventHandler<String>,
+    /// Called with (album_id, label) to name a version; None clears the label
+    on_set_version_label: EventHandler<(String, Option<String>)>,
     on_album_deleted: EventHandler<()>,
     on_export_release: EventHandler<String>,
     on_delete_album: EventHandler<String>,
@@ -47,17 +66,49 @@ pub fn AlbumDetailView(
     on_track_resume: EventHandler<()>,
     on_track_add_next: EventHandler<String>,
     on_track_add_to_queue: EventHandler<String>,
+    /// Called with (track ID, playlist ID)
+    on_track_add_to_playlist: EventHandler<(String, String)>,
     on_track_export: EventHandler<String>,
     on_artist_click: EventHandler<String>,
     on_play_album: EventHandler<Vec<String>>,
     on_add_album_to_queue: EventHandler<Vec<String>>,
+    /// Whether the library has a cloud home releases can move into
+    #[props(default)]
+    cloud_home_configured: bool,
     on_transfer_to_managed: EventHandler<String>,
+    /// Called with release_id to move its files into the cloud home
+    on_move_to_cloud: EventHandler<String>,
     on_eject: EventHandler<String>,
+    /// Called with release_id to stop its running transfer
+    on_cancel_transfer: EventHandler<String>,
+    /// Called with (release_id, keep) when keeping a release downloaded is switched
+    on_keep_downloaded: EventHandler<(String, bool)>,
+    /// Called with release_id when the storage modal opens
+    on_fetch_availability: EventHandler<String>,
     on_fetch_remote_covers: EventHandler<()>,
     on_select_cover: EventHandler<CoverChange>,
+    /// Called with release_id when the track mapping editor opens
+    on_fetch_track_mapping: EventHandler<String>,
+    on_save_track_mapping: EventHandler<TrackMapping>,
+    on_save_tags: EventHandler<AlbumTagEdit>,
+    on_save_notes: EventHandler<AlbumNotesEdit>,
+    /// Called with the text of a new comment on the album
+    on_add_comment: EventHandler<String>,
+    /// Called with the ID of a comment to remove
+    on_remove_comment: EventHandler<String>,
+    /// Called with release_id when the MusicBrainz refresh opens
+    on_fetch_musicbrainz_changes: EventHandler<String>,
+    /// Called with the MusicBrainz changes picked for the selected release
+    on_apply_musicbrainz_changes: EventHandler<Vec<MetadataUpdate>>,
     /// Called with release_id to create a cloud share link and copy to clipboard
     on_copy_share_link: EventHandler<String>,
     #[props(default)] torrent_info: std::collections::HashMap<String, ReleaseTorrentInfo>,
+    /// Larger cover found for the selected release, accepted through `on_select_cover`
+    #[props(default)]
+    cover_upgrade: Option<CoverUpgradeOffer>,
+    /// Playlists offered in the track menus
+    #[props(default)]
+    playlists: Vec<Playlist>,
     #[props(default)] on_start_seeding: Option<EventHandler<String>>,
     #[props(default)] on_stop_seeding: Option<EventHandler<String>>,
 ) -> Element {
@@ -71,6 +122,13 @@ pub fn AlbumDetailView(
     let mut show_storage_modal = use_signal(|| None::<String>);
     let mut show_gallery = use_signal(|| false);
     let mut show_cover_picker = use_signal(|| false);
+    let mut show_track_mapping = use_signal(|| false);
+    let mut is_editing_tags = use_signal(|| false);
+    let mut show_musicbrainz_refresh = use_signal(|| false);
+    let mut open_storage = move |release_id: String| {
+        on_fetch_availability.call(release_id.clone());
+        show_storage_modal.set(Some(release_id));
+    };
 
     // Check if album exists - only subscribe to this field via lens
     if state.album().read().is_none() {
@@ -99,8 +157,19 @@ pub fn AlbumDetailView(
                         on_view_release_info: EventHandler::new(move |id: String| {
                             show_release_info_modal.set(Some(id));
                         }),
-                        on_view_storage: EventHandler::new(move |id: String| {
-                            show_storage_modal.set(Some(id));
+                        on_view_storage: EventHandler::new(move |id: String| open_storage(id)),
+                        on_remap_tracks: EventHandler::new(move |id: String| {
+                            show_track_mapping.set(true);
+                            on_fetch_track_mapping.call(id);
+                        }),
+                        on_edit_tags: EventHandler::new(move |_: String| {
+                            is_editing_tags.set(true);
+                        }),
+                        on_refresh_metadata: EventHandler::new(move |_: String| {
+                            if let Some(release_id) = state.selected_release_id().read().clone() {
+                                show_musicbrainz_refresh.set(true);
+                                on_fetch_musicbrainz_changes.call(release_id);
+                            }
                         }),
                         on_copy_share_link,
                         on_open_gallery: EventHandler::new(move |_: String| {
@@ -110,43 +179,76 @@ pub fn AlbumDetailView(
                             show_cover_picker.set(true);
                             on_fetch_remote_covers.call(());
                         }),
+                        cover_upgrade: cover_upgrade.clone(),
+                        on_select_cover,
                         on_artist_click,
                         on_play_album,
                         on_add_to_queue: on_add_album_to_queue,
+                        on_save_notes,
                     }
                 }
 
-                // Right column - release tabs + tracklist
+                // Right column - version picker + release tabs + tracklist, or the tag editor
                 div { class: "flex-1 min-w-0",
-                    ReleaseTabsSectionWrapper {
-                        state,
-                        read_only,
-                        is_deleting,
-                        is_exporting,
-                        export_error,
-                        torrent_info: torrent_info.clone(),
-                        on_release_select,
-                        on_view_files: move |id| show_release_info_modal.set(Some(id)),
-                        on_view_storage: move |id| show_storage_modal.set(Some(id)),
-                        on_delete_release: move |id| show_release_delete_confirm.set(Some(id)),
-                        on_export: on_export_release,
-                        on_copy_share_link,
-                        on_start_seeding,
-                        on_stop_seeding,
-                    }
+                    if is_editing_tags() {
+                        TagEditorWrapper {
+                            state,
+                            on_cancel: move |_| is_editing_tags.set(false),
+                            on_save: move |edit: AlbumTagEdit| {
+                                is_editing_tags.set(false);
+                                on_save_tags.call(edit);
+                            },
+                        }
+                    } else {
+                        VersionPickerWrapper {
+                            state,
+                            read_only,
+                            on_version_select,
+                            on_link_version,
+                            on_unlink_version,
+                            on_set_version_label,
+                        }
 
-                    TrackListSection {
-                        state,
-                        tracks,
-                        playback,
-                        read_only,
-                        on_track_play,
-                        on_track_pause,
-                        on_track_resume,
-                        on_track_add_next,
-                        on_track_add_to_queue,
-                        on_track_export,
-                        on_artist_click,
+                        ReleaseTabsSectionWrapper {
+                            state,
+                            read_only,
+                            is_deleting,
+                            is_exporting,
+                            export_error,
+                            torrent_info: torrent_info.clone(),
+                            on_release_select,
+                            on_view_files: move |id| show_release_info_modal.set(Some(id)),
+                            on_view_storage: move |id| open_storage(id),
+                            on_remap_tracks: move |id| {
+                                show_track_mapping.set(true);
+                                on_fetch_track_mapping.call(id);
+                            },
+                            on_delete_release: move |id| show_release_delete_confirm.set(Some(id)),
+                            on_export: on_export_release,
+                            on_copy_share_link,
+                            on_start_seeding,
+                            on_stop_seeding,
+                        }
+
+                        TrackListSection {
+                            state,
+                            tracks,
+                            playback,
+                            read_only,
+                            playlists,
+                            on_track_play,
+                            on_track_pause,
+                            on_track_resume,
+                            on_track_add_next,
+                            on_track_add_to_queue,
+                            on_track_add_to_playlist,
+                            on_track_export,
+                            on_artist_click,
+                            on_play_disc: on_play_album,
+                            on_add_disc_to_queue: on_add_album_to_queue,
+                        }
+
+                        AlbumCommentsWrapper { state, on_add_comment, on_remove_comment }
                     }
                 }
             }
@@ -174,14 +276,26 @@ pub fn AlbumDetailView(
         StorageModalWrapper {
             state,
             show: show_storage_modal,
+            cloud_home_configured,
             on_transfer_to_managed,
+            on_move_to_cloud,
             on_eject,
+            on_cancel_transfer,
+            on_keep_downloaded,
         }
 
         GalleryLightboxWrapper { state, show: show_gallery }
 
         CoverPickerWrapper { state, show: show_cover_picker, on_select: on_select_cover }
 
+        TrackMappingModalWrapper { state, show: show_track_mapping, on_save: on_save_track_mapping }
+
+        MusicBrainzRefreshModalWrapper {
+            state,
+            show: show_musicbrainz_refresh,
+            on_apply: on_apply_musicbrainz_changes,
+        }
+
         if let Some(ref error) = export_error() {
             ExportErrorToast {
                 error: error.clone(),
@@ -206,12 +320,18 @@ fn AlbumInfoSection(
     on_delete_album: EventHandler<String>,
     on_view_release_info: EventHandler<String>,
     on_view_storage: EventHandler<String>,
+    on_remap_tracks: EventHandler<String>,
+    on_edit_tags: EventHandler<String>,
+    on_refresh_metadata: EventHandler<String>,
     on_copy_share_link: EventHandler<String>,
     on_open_gallery: EventHandler<String>,
     on_change_cover: EventHandler<String>,
+    cover_upgrade: Option<CoverUpgradeOffer>,
+    on_select_cover: EventHandler<CoverChange>,
     on_artist_click: EventHandler<String>,
     on_play_album: EventHandler<Vec<String>>,
     on_add_to_queue: EventHandler<Vec<String>>,
+    on_save_notes: EventHandler<AlbumNotesEdit>,
 ) -> Element {
     // Use lenses to read individual fields - avoids subscribing to track changes
     let album = state.album().read().clone();
@@ -219,11 +339,14 @@ fn AlbumInfoSection(
         return rsx! {};
     };
     let releases = state.releases().read().clone();
-    let artists = state.artists().read().clone();
+    let artist_credit = state.artist_credit().read().clone();
     let import_progress = *state.import_progress().read();
     let import_error = state.import_error().read().clone();
     let selected_release_id = state.selected_release_id().read().clone();
     let is_on_cloud = *state.managed_in_cloud().read();
+    let custom_tags = state.custom_tags().read().clone();
+    let notes = state.notes().read().clone();
+    let offline_pin = state.offline_pin().read().clone();
 
     // Use derived fields - these don't change during import progress updates
     let track_count = *state.track_count().read();
@@ -242,16 +365,25 @@ fn AlbumInfoSection(
             on_delete_album,
             on_view_release_info,
             on_view_storage,
+            on_remap_tracks,
+            on_edit_tags,
+            on_refresh_metadata,
             on_copy_share_link,
             on_open_gallery,
             on_change_cover,
             is_on_cloud,
         }
+        if !read_only {
+            if let Some(offer) = cover_upgrade {
+                CoverUpgradeNotice { offer, on_accept: on_select_cover }
+            }
+        }
         AlbumMetadata {
             album: album.clone(),
-            artists,
+            artist_credit,
             track_count,
             selected_release: releases.iter().find(|r| Some(r.id.clone()) == selected_release_id).cloned(),
+            offline_pin,
             on_artist_click,
         }
         PlayAlbumButton {
@@ -262,6 +394,46 @@ fn AlbumInfoSection(
             on_play_album,
             on_add_to_queue,
         }
+        AlbumNotes {
+            album_id: album.id.clone(),
+            custom_tags,
+            notes,
+            read_only,
+            on_save: on_save_notes,
+        }
+    }
+}
+
+/// Version picker wrapper - uses lenses
+#[component]
+fn VersionPickerWrapper(
+    state: ReadStore<AlbumDetailState>,
+    read_only: bool,
+    on_version_select: EventHandler<String>,
+    on_link_version: EventHandler<String>,
+    on_unlink_version: EventHandler<String>,
+    on_set_version_label: EventHandler<(String, Option<String>)>,
+) -> Element {
+    let album_id = state
+        .album()
+        .read()
+        .as_ref()
+        .map(|a| a.id.clone())
+        .unwrap_or_default();
+    let versions = state.versions().read().clone();
+    let candidates = state.version_candidates().read().clone();
+
+    rsx! {
+        VersionPicker {
+            album_id,
+            versions,
+            candidates,
+            read_only,
+            on_version_select,
+            on_link_version,
+            on_unlink_version,
+            on_set_version_label,
+        }
     }
 }
 
@@ -277,6 +449,7 @@ fn ReleaseTabsSectionWrapper(
     on_release_select: EventHandler<String>,
     on_view_files: EventHandler<String>,
     on_view_storage: EventHandler<String>,
+    on_remap_tracks: EventHandler<String>,
     on_delete_release: EventHandler<String>,
     on_export: EventHandler<String>,
     on_copy_share_link: EventHandler<String>,
@@ -303,6 +476,7 @@ fn ReleaseTabsSectionWrapper(
             export_error,
             on_view_files,
             on_view_storage,
+            on_remap_tracks,
             on_delete_release,
             on_export,
             is_on_cloud,
@@ -321,14 +495,22 @@ fn TrackListSection(
     tracks: ReadStore<Vec<Track>>,
     playback: PlaybackDisplay,
     read_only: bool,
+    playlists: Vec<Playlist>,
     on_track_play: EventHandler<String>,
     on_track_pause: EventHandler<()>,
     on_track_resume: EventHandler<()>,
     on_track_add_next: EventHandler<String>,
     on_track_add_to_queue: EventHandler<String>,
+    on_track_add_to_playlist: EventHandler<(String, String)>,
     on_track_export: EventHandler<String>,
     on_artist_click: EventHandler<String>,
+    /// Called with the disc's track IDs
+    on_play_disc: EventHandler<Vec<String>>,
+    /// Called with the disc's track IDs
+    on_add_disc_to_queue: EventHandler<Vec<String>>,
 ) -> Element {
+    let mut collapsed_discs = use_signal(HashSet::<i32>::new);
+
     // Use lenses for individual fields - avoids subscribing to track import_state changes
     let artists = state.artists().read().clone();
     let is_compilation = state
@@ -374,6 +556,19 @@ fn TrackListSection(
 
     // Track which disc we're on for headers
     let mut current_disc: Option<i32> = None;
+    let disc_titles = state.disc_titles().read().clone();
+    let mut disc_track_ids: HashMap<i32, Vec<String>> = HashMap::new();
+    for (disc_number, track_id) in &disc_info {
+        disc_track_ids
+            .entry(disc_number.unwrap_or(1))
+            .or_default()
+            .push(track_id.clone());
+    }
+
+    // Consecutive movements of a work go under one header
+    let track_works = state.track_works().read().clone();
+    let mut current_work: Option<TrackWork> = None;
+    let track_credits = state.track_credits().read().clone();
 
     rsx! {
         div { class: "space-y-1",
@@ -385,9 +580,15 @@ fn TrackListSection(
                     if show_disc_header {
                         current_disc = disc_number;
                     }
-                    let disc_label = disc_number
-                        .map(|d| format!("Disc {}", d))
-                        .unwrap_or_else(|| "Disc 1".to_string());
+                    let disc = disc_number.unwrap_or(1);
+                    let is_collapsed = has_multiple_discs && collapsed_discs.read().contains(&disc);
+                    let disc_tracks = disc_track_ids.get(&disc).cloned().unwrap_or_default();
+                    let work = track_works.get(&track_id);
+                    let show_work_header = work.is_some()
+                        && (show_disc_header || work != current_work.as_ref());
+                    current_work = work.cloned();
+                    let movement_title = work.and_then(|w| w.movement_title.clone());
+                    let credit = track_credits.get(&track_id).cloned().unwrap_or_default();
 
                     // Playback state for this track
                     let is_this_track = current_track_id.as_ref() == Some(&track_id);
@@ -402,27 +603,51 @@ fn TrackListSection(
                     rsx! {
                         div { key: "track-{track_id}",
                             if show_disc_header {
-                                h3 { class: "text-sm font-semibold text-gray-400 uppercase tracking-wide pt-4 pb-2 first:pt-0",
-                                    "{disc_label}"
+                                DiscHeader {
+                                    disc_number: disc,
+                                    title: disc_titles.get(&disc).cloned(),
+                                    is_collapsed,
+                                    on_toggle: move |_| {
+                                        let mut collapsed = collapsed_discs.write();
+                                        if !collapsed.remove(&disc) {
+                                            collapsed.insert(disc);
+                                        }
+                                    },
+                                    on_play: {
+                                        let disc_tracks = disc_tracks.clone();
+                                        move |_| on_play_disc.call(disc_tracks.clone())
+                                    },
+                                    on_add_to_queue: move |_| on_add_disc_to_queue.call(disc_tracks.clone()),
                                 }
                             }
-                            TrackRow {
-                                track: track_store,
-                                artists: artists.clone(),
-                                release_id: release_id.clone(),
-                                is_compilation,
-                                is_playing,
-                                is_paused,
-                                is_loading,
-                                show_spinner: is_loading,
-                                read_only,
-                                on_play: on_track_play,
-                                on_pause: on_track_pause,
-                                on_resume: on_track_resume,
-                                on_add_next: on_track_add_next,
-                                on_add_to_queue: on_track_add_to_queue,
-                                on_export: on_track_export,
-                                on_artist_click,
+                            if show_work_header && !is_collapsed {
+                                if let Some(work) = work {
+                                    WorkHeader { work: work.clone(), on_artist_click }
+                                }
+                            }
+                            if !is_collapsed {
+                                TrackRow {
+                                    track: track_store,
+                                    artists: artists.clone(),
+                                    release_id: release_id.clone(),
+                                    movement_title,
+                                    credit,
+                                    is_compilation,
+                                    is_playing,
+                                    is_paused,
+                                    is_loading,
+                                    show_spinner: is_loading,
+                                    read_only,
+                                    playlists: playlists.clone(),
+                                    on_play: on_track_play,
+                                    on_pause: on_track_pause,
+                                    on_resume: on_track_resume,
+                                    on_add_next: on_track_add_next,
+                                    on_add_to_queue: on_track_add_to_queue,
+                                    on_add_to_playlist: on_track_add_to_playlist,
+                                    on_export: on_track_export,
+                                    on_artist_click,
+                                }
                             }
                         }
                     }
@@ -432,6 +657,130 @@ fn TrackListSection(
     }
 }
 
+/// Header above a disc's tracks, with buttons to play or queue just that disc
+#[component]
+fn DiscHeader(
+    disc_number: i32,
+    title: Option<String>,
+    is_collapsed: bool,
+    on_toggle: EventHandler<()>,
+    on_play: EventHandler<()>,
+    on_add_to_queue: EventHandler<()>,
+) -> Element {
+    rsx! {
+        div { class: "group flex items-center gap-2 pt-4 pb-2",
+            button {
+                class: "flex items-center gap-1.5 min-w-0 text-sm text-gray-400 hover:text-white transition-colors",
+                title: if is_collapsed { "Show tracks" } else { "Hide tracks" },
+                onclick: move |_| on_toggle.call(()),
+                if is_collapsed {
+                    ChevronRightIcon { class: "w-4 h-4 flex-shrink-0" }
+                } else {
+                    ChevronDownIcon { class: "w-4 h-4 flex-shrink-0" }
+                }
+                h3 { class: "truncate",
+                    span { class: "font-semibold uppercase tracking-wide", "Disc {disc_number}" }
+                    if let Some(ref title) = title {
+                        ": {title}"
+                    }
+                }
+            }
+            div { class: "ml-auto flex items-center gap-1 opacity-0 group-hover:opacity-100 transition-opacity",
+                button {
+                    class: "p-1.5 rounded text-gray-400 hover:text-white hover:bg-gray-700 transition-colors",
+                    title: "Play disc",
+                    onclick: move |_| on_play.call(()),
+                    PlayIcon { class: "w-3.5 h-3.5" }
+                }
+                button {
+                    class: "p-1.5 rounded text-gray-400 hover:text-white hover:bg-gray-700 transition-colors",
+                    title: "Add disc to queue",
+                    onclick: move |_| on_add_to_queue.call(()),
+                    PlusIcon { class: "w-3.5 h-3.5" }
+                }
+            }
+        }
+    }
+}
+
+/// Comments wrapper - reads only the comment fields
+#[component]
+fn AlbumCommentsWrapper(
+    state: ReadStore<AlbumDetailState>,
+    on_add_comment: EventHandler<String>,
+    on_remove_comment: EventHandler<String>,
+) -> Element {
+    let comments = state.comments().read().clone();
+    let can_comment = *state.can_comment().read();
+
+    rsx! {
+        AlbumComments {
+            comments,
+            can_comment,
+            on_add: on_add_comment,
+            on_remove: on_remove_comment,
+        }
+    }
+}
+
+/// Work title and composers above its movements in the track list
+#[component]
+fn WorkHeader(work: TrackWork, on_artist_click: EventHandler<String>) -> Element {
+    rsx! {
+        div { class: "px-4 pt-3 pb-1",
+            h4 { class: "text-sm font-medium text-gray-300", "{work.work_title}" }
+            if !work.composers.is_empty() {
+                p { class: "text-xs text-gray-500",
+                    for (i , composer) in work.composers.iter().enumerate() {
+                        if i > 0 {
+                            ", "
+                        }
+                        TextLink {
+                            onclick: {
+                                let artist_id = composer.id.clone();
+                                move |_| on_artist_click.call(artist_id.clone())
+                            },
+                            "{composer.name}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tag editor wrapper - seeds the editor from the album and selected release
+#[component]
+fn TagEditorWrapper(
+    state: ReadStore<AlbumDetailState>,
+    on_cancel: EventHandler<()>,
+    on_save: EventHandler<AlbumTagEdit>,
+) -> Element {
+    let album = state.album().read().clone();
+    let Some(album) = album else {
+        return rsx! {};
+    };
+    let artists = state.artists().read().clone();
+    let tracks = state.tracks().read().clone();
+    let release_id = state
+        .selected_release_id()
+        .read()
+        .clone()
+        .unwrap_or_default();
+
+    rsx! {
+        TagEditor {
+            key: "{release_id}",
+            album,
+            artists,
+            tracks,
+            release_id,
+            on_cancel,
+            on_save,
+        }
+    }
+}
+
 // ============================================================================
 // Dialog wrappers - only read state when shown
 // ============================================================================
@@ -570,8 +919,12 @@ fn ReleaseInfoModalWrapper(
 fn StorageModalWrapper(
     state: ReadStore<AlbumDetailState>,
     show: Signal<Option<String>>,
+    cloud_home_configured: bool,
     on_transfer_to_managed: EventHandler<String>,
+    on_move_to_cloud: EventHandler<String>,
     on_eject: EventHandler<String>,
+    on_cancel_transfer: EventHandler<String>,
+    on_keep_downloaded: EventHandler<(String, bool)>,
 ) -> Element {
     let is_open_memo = use_memo(move || show().is_some());
     let is_open: ReadSignal<bool> = is_open_memo.into();
@@ -582,9 +935,15 @@ fn StorageModalWrapper(
     let is_unmanaged = *state.is_unmanaged().read();
     let transfer_progress = state.transfer_progress().read().clone();
     let transfer_error = state.transfer_error().read().clone();
+    let availability = state.availability().read().clone();
+    let offline_pin = state.offline_pin().read().clone();
+    let offline_error = state.offline_error().read().clone();
 
     let release_id_for_transfer = show().unwrap_or_default();
+    let release_id_for_cloud = release_id_for_transfer.clone();
     let release_id_for_eject = release_id_for_transfer.clone();
+    let release_id_for_cancel = release_id_for_transfer.clone();
+    let release_id_for_offline = release_id_for_transfer.clone();
 
     rsx! {
         StorageModal {
@@ -596,12 +955,73 @@ fn StorageModalWrapper(
             is_unmanaged,
             transfer_progress,
             transfer_error,
+            availability,
+            cloud_home_configured,
+            offline_pin,
+            offline_error,
+            on_keep_downloaded: move |keep: bool| {
+                on_keep_downloaded.call((release_id_for_offline.clone(), keep));
+            },
             on_transfer_to_managed: move |_| {
                 on_transfer_to_managed.call(release_id_for_transfer.clone());
             },
+            on_move_to_cloud: move |_| {
+                on_move_to_cloud.call(release_id_for_cloud.clone());
+            },
             on_eject: move |_| {
                 on_eject.call(release_id_for_eject.clone());
             },
+            on_cancel_transfer: move |_| {
+                on_cancel_transfer.call(release_id_for_cancel.clone());
+            },
+        }
+    }
+}
+
+#[component]
+fn TrackMappingModalWrapper(
+    state: ReadStore<AlbumDetailState>,
+    show: Signal<bool>,
+    on_save: EventHandler<TrackMapping>,
+) -> Element {
+    let mapping = state.track_mapping().read().clone();
+    let error = state.track_mapping_error().read().clone();
+    let is_open: ReadSignal<bool> = show.into();
+
+    rsx! {
+        TrackMappingModal {
+            is_open,
+            mapping,
+            error,
+            on_close: move |_| show.set(false),
+            on_save: move |mapping: TrackMapping| {
+                show.set(false);
+                on_save.call(mapping);
+            },
+        }
+    }
+}
+
+#[component]
+fn MusicBrainzRefreshModalWrapper(
+    state: ReadStore<AlbumDetailState>,
+    show: Signal<bool>,
+    on_apply: EventHandler<Vec<MetadataUpdate>>,
+) -> Element {
+    let changes = state.musicbrainz_changes().read().clone();
+    let error = state.musicbrainz_error().read().clone();
+    let is_open: ReadSignal<bool> = show.into();
+
+    rsx! {
+        MusicBrainzRefreshModal {
+            is_open,
+            changes,
+            error,
+            on_close: move |_| show.set(false),
+            on_apply: move |updates: Vec<MetadataUpdate>| {
+                show.set(false);
+                on_apply.call(updates);
+            },
         }
     }
 }
@@ -32,9 +32,11 @@ pub fn ArtistDetailView(
     let error = state.error().read().clone();
     let artist = state.artist().read().clone();
     let albums = state.albums().read().clone();
+    let appearances = state.appearances().read().clone();
     let artists_by_album = state.artists_by_album().read().clone();
 
     let mut scroll_target: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
+    let appearance_dropdown: Signal<Option<String>> = use_signal(|| None);
 
     rsx! {
         div {
@@ -70,7 +72,7 @@ pub fn ArtistDetailView(
 
                         ArtistAlbumGrid {
                             albums,
-                            artists_by_album,
+                            artists_by_album: artists_by_album.clone(),
                             on_album_click,
                             on_artist_click,
                             on_play_album,
@@ -78,6 +80,32 @@ pub fn ArtistDetailView(
                             scroll_target: ScrollTarget::Element(scroll_target.into()),
                         }
                     }
+
+                    // Usually a handful of compilations, so no virtual scrolling
+                    if !appearances.is_empty() {
+                        h2 { class: "text-xl font-semibold text-white mt-10 mb-4", "Appears on" }
+                        div { class: "grid grid-cols-[repeat(auto-fill,200px)] gap-6",
+                            for album in appearances {
+                                AlbumCard {
+                                    key: "{album.id}",
+                                    artists: artists_by_album.get(&album.id).cloned().unwrap_or_default(),
+                                    version_count: 0,
+                                    cover_placeholder: None,
+                                    album,
+                                    on_click: on_album_click,
+                                    on_artist_click,
+                                    on_play: on_play_album,
+                                    on_add_to_queue: on_add_album_to_queue,
+                                    on_toggle_collection: |_| {},
+                                    on_new_collection: |_| {},
+                                    open_dropdown: appearance_dropdown,
+                                    anchor_scope: None,
+                                    collections: None,
+                                    collection_ids: Vec::new(),
+                                }
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -118,11 +146,18 @@ fn ArtistAlbumGrid(
                 key: "{item.album.id}",
                 album: item.album,
                 artists: item.artists,
+                version_count: 0,
+                cover_placeholder: None,
                 on_click: on_album_click,
                 on_artist_click,
                 on_play: on_play_album,
                 on_add_to_queue: on_add_album_to_queue,
+                on_toggle_collection: |_| {},
+                on_new_collection: |_| {},
                 open_dropdown,
+                anchor_scope: None,
+                collections: None,
+                collection_ids: Vec::new(),
             }
         }
     }));
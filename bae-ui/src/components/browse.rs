@@ -0,0 +1,158 @@
+//! Browse views - the library by genre and by decade
+
+use crate::components::helpers::{ErrorDisplay, LoadingSpinner};
+use crate::components::library::AlbumGrid;
+use crate::components::ChromelessButton;
+use crate::stores::browse::{BrowseState, BrowseStateStoreExt};
+use dioxus::prelude::*;
+use dioxus_virtual_scroll::ScrollTarget;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// "1990s" for 1990
+pub fn decade_label(decade: i32) -> String {
+    format!("{decade}s")
+}
+
+fn album_count_label(count: usize) -> String {
+    if count == 1 {
+        "1 album".to_string()
+    } else {
+        format!("{count} albums")
+    }
+}
+
+/// Browse index - every decade and genre in the library with its album count
+#[component]
+pub fn BrowseView(
+    state: ReadStore<BrowseState>,
+    on_genre_click: EventHandler<String>,
+    on_decade_click: EventHandler<i32>,
+) -> Element {
+    let loading = *state.loading().read();
+    let error = state.error().read().clone();
+    let genres = state.genres().read().clone();
+    let decades = state.decades().read().clone();
+
+    rsx! {
+        div { class: "flex-grow overflow-y-auto flex flex-col py-10",
+            div { class: "container mx-auto flex flex-col flex-1",
+                h1 { class: "text-3xl font-bold text-white mb-6", "Browse" }
+
+                if loading {
+                    LoadingSpinner { message: "Loading genres and decades...".to_string() }
+                } else if let Some(err) = error {
+                    ErrorDisplay { message: err }
+                } else if genres.is_empty() && decades.is_empty() {
+                    div { class: "flex-1 flex flex-col items-center justify-center",
+                        p { class: "text-gray-500", "Albums with a genre or year show up here" }
+                    }
+                } else {
+                    if !decades.is_empty() {
+                        h2 { class: "text-xl font-semibold text-white mb-4", "Decades" }
+                        div { class: "grid grid-cols-[repeat(auto-fill,minmax(160px,1fr))] gap-3 mb-10",
+                            for decade in decades {
+                                BrowseTile {
+                                    key: "{decade.decade}",
+                                    label: decade_label(decade.decade),
+                                    album_count: decade.album_count,
+                                    on_click: move |_| on_decade_click.call(decade.decade),
+                                }
+                            }
+                        }
+                    }
+
+                    if !genres.is_empty() {
+                        h2 { class: "text-xl font-semibold text-white mb-4", "Genres" }
+                        div { class: "grid grid-cols-[repeat(auto-fill,minmax(160px,1fr))] gap-3",
+                            for genre in genres {
+                                BrowseTile {
+                                    key: "{genre.name}",
+                                    label: genre.name.clone(),
+                                    album_count: genre.album_count,
+                                    on_click: {
+                                        let name = genre.name.clone();
+                                        move |_| on_genre_click.call(name.clone())
+                                    },
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A genre or decade on the browse index
+#[component]
+fn BrowseTile(label: String, album_count: usize, on_click: EventHandler<()>) -> Element {
+    rsx! {
+        ChromelessButton {
+            class: Some(
+                "flex flex-col items-start gap-1 px-4 py-3 rounded-lg bg-surface-raised hover:bg-hover transition-colors text-left"
+                    .to_string(),
+            ),
+            onclick: move |_| on_click.call(()),
+            span { class: "text-sm font-medium text-white truncate w-full", "{label}" }
+            span { class: "text-xs text-gray-400", {album_count_label(album_count)} }
+        }
+    }
+}
+
+/// The albums in one genre or decade, in the library's virtual album grid
+#[component]
+pub fn BrowseAlbumsView(
+    state: ReadStore<BrowseState>,
+    /// Genre name or decade label shown as the heading
+    title: String,
+    on_album_click: EventHandler<String>,
+    on_artist_click: EventHandler<String>,
+    on_play_album: EventHandler<String>,
+    on_add_album_to_queue: EventHandler<String>,
+) -> Element {
+    let loading = *state.loading().read();
+    let error = state.error().read().clone();
+    let albums = state.albums().read().clone();
+    let artists_by_album = state.artists_by_album().read().clone();
+    let cover_placeholders = state.cover_placeholders().read().clone();
+
+    let mut scroll_target: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
+
+    rsx! {
+        div {
+            class: "flex-grow overflow-y-auto flex flex-col py-10",
+            onmounted: move |evt| scroll_target.set(Some(evt.data())),
+            div { class: "container mx-auto flex flex-col flex-1",
+                h1 { class: "text-3xl font-bold text-white mb-2", "{title}" }
+
+                if loading {
+                    LoadingSpinner { message: "Loading albums...".to_string() }
+                } else if let Some(err) = error {
+                    ErrorDisplay { message: err }
+                } else if albums.is_empty() {
+                    div { class: "flex-1 flex flex-col items-center justify-center",
+                        p { class: "text-gray-500", "No albums here" }
+                    }
+                } else {
+                    p { class: "text-sm text-gray-400 mb-6", {album_count_label(albums.len())} }
+                    AlbumGrid {
+                        albums,
+                        artists_by_album,
+                        version_counts: HashMap::new(),
+                        cover_placeholders,
+                        collections: None,
+                        collection_ids_by_album: HashMap::new(),
+                        on_album_click,
+                        on_artist_click,
+                        on_play_album,
+                        on_add_album_to_queue,
+                        on_toggle_collection: |_| {},
+                        on_new_collection: |_| {},
+                        scroll_target: ScrollTarget::Element(scroll_target.into()),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -857,6 +857,48 @@ pub fn Repeat1Icon(#[props(default = "w-4 h-4")] class: &'static str) -> Element
     }
 }
 
+/// Radio icon (broadcast waves - for radio auto-continuation)
+#[component]
+pub fn RadioIcon(#[props(default = "w-4 h-4")] class: &'static str) -> Element {
+    rsx! {
+        svg {
+            class: "{class}",
+            xmlns: "http://www.w3.org/2000/svg",
+            view_box: "0 0 24 24",
+            fill: "none",
+            stroke: "currentColor",
+            stroke_width: "2",
+            stroke_linecap: "round",
+            stroke_linejoin: "round",
+            path { d: "M16.247 7.761a6 6 0 0 1 0 8.478" }
+            path { d: "M19.075 4.933a10 10 0 0 1 0 14.134" }
+            path { d: "M4.925 19.067a10 10 0 0 1 0-14.134" }
+            path { d: "M7.753 16.239a6 6 0 0 1 0-8.478" }
+            circle { cx: "12", cy: "12", r: "2" }
+        }
+    }
+}
+
+/// Mic vocal icon (microphone - for lyrics)
+#[component]
+pub fn MicVocalIcon(#[props(default = "w-4 h-4")] class: &'static str) -> Element {
+    rsx! {
+        svg {
+            class: "{class}",
+            xmlns: "http://www.w3.org/2000/svg",
+            view_box: "0 0 24 24",
+            fill: "none",
+            stroke: "currentColor",
+            stroke_width: "2",
+            stroke_linecap: "round",
+            stroke_linejoin: "round",
+            path { d: "m11 7.601-5.994 8.19a1 1 0 0 0 .1 1.298l.817.818a1 1 0 0 0 1.314.087L15.09 12" }
+            path { d: "M16.5 21.174C15.5 20.5 14.372 20 13 20c-2.058 0-3.928 2.356-6 2-2.072-.356-2.775-3.369-1.5-4.5" }
+            circle { cx: "16", cy: "7", r: "5" }
+        }
+    }
+}
+
 /// Volume 2 icon (speaker with two arcs - high volume)
 #[component]
 pub fn Volume2Icon(#[props(default = "w-4 h-4")] class: &'static str) -> Element {
@@ -0,0 +1,200 @@
+//! Bandcamp collection view component
+
+use crate::components::{
+    Button, ButtonSize, ButtonVariant, TextInput, TextInputSize, TextInputType,
+};
+use crate::display_types::{BandcampAlbumItem, BandcampImportStatus};
+use crate::stores::import::BandcampState;
+use dioxus::prelude::*;
+
+/// Bandcamp import source - sign-in form, then the purchased albums with an
+/// import button each
+#[component]
+pub fn BandcampCollectionView(
+    state: BandcampState,
+    /// Called with the value of the bandcamp.com `identity` cookie
+    on_sign_in: EventHandler<String>,
+    on_sign_out: EventHandler<()>,
+    /// Called to reload the collection from Bandcamp
+    on_refresh: EventHandler<()>,
+    /// Called with the Bandcamp item ID of the album to download and import
+    on_import: EventHandler<String>,
+) -> Element {
+    rsx! {
+        div { class: "flex-1 min-h-0 overflow-y-auto px-5 pb-5",
+            if state.signed_in {
+                CollectionList {
+                    albums: state.albums.clone(),
+                    is_loading: state.is_loading,
+                    error: state.error.clone(),
+                    on_sign_out,
+                    on_refresh,
+                    on_import,
+                }
+            } else {
+                SignInForm {
+                    is_loading: state.is_loading,
+                    error: state.error.clone(),
+                    on_sign_in,
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SignInForm(
+    is_loading: bool,
+    error: Option<String>,
+    on_sign_in: EventHandler<String>,
+) -> Element {
+    let mut identity = use_signal(String::new);
+
+    rsx! {
+        div { class: "bg-gray-800 rounded-lg p-6 space-y-4 max-w-xl",
+            div {
+                h3 { class: "text-lg font-medium text-white", "Sign in to Bandcamp" }
+                p { class: "text-sm text-gray-400 mt-1",
+                    "bae reads your purchases using the session of a browser signed in to bandcamp.com. "
+                    "Copy the value of its "
+                    code { class: "text-gray-300", "identity" }
+                    " cookie and paste it here."
+                }
+            }
+            TextInput {
+                value: identity(),
+                on_input: move |v| identity.set(v),
+                size: TextInputSize::Medium,
+                input_type: TextInputType::Password,
+                placeholder: "identity cookie",
+                monospace: true,
+            }
+            if let Some(error) = error {
+                div { class: "p-3 bg-red-900/30 border border-red-700 rounded-lg text-sm text-red-300",
+                    "{error}"
+                }
+            }
+            Button {
+                variant: ButtonVariant::Primary,
+                size: ButtonSize::Medium,
+                disabled: identity.read().trim().is_empty() || is_loading,
+                loading: is_loading,
+                onclick: move |_| on_sign_in.call(identity.read().trim().to_string()),
+                if is_loading {
+                    "Signing in..."
+                } else {
+                    "Sign In"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CollectionList(
+    albums: Vec<BandcampAlbumItem>,
+    is_loading: bool,
+    error: Option<String>,
+    on_sign_out: EventHandler<()>,
+    on_refresh: EventHandler<()>,
+    on_import: EventHandler<String>,
+) -> Element {
+    let count = albums.len();
+
+    rsx! {
+        div { class: "space-y-4",
+            div { class: "flex items-center justify-between",
+                p { class: "text-sm text-gray-400",
+                    if is_loading {
+                        "Loading collection..."
+                    } else if count == 1 {
+                        "1 album in your collection"
+                    } else {
+                        "{count} albums in your collection"
+                    }
+                }
+                div { class: "flex gap-2",
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Small,
+                        disabled: is_loading,
+                        onclick: move |_| on_refresh.call(()),
+                        "Refresh"
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Small,
+                        onclick: move |_| on_sign_out.call(()),
+                        "Sign Out"
+                    }
+                }
+            }
+            if let Some(error) = error {
+                div { class: "p-3 bg-red-900/30 border border-red-700 rounded-lg text-sm text-red-300",
+                    "{error}"
+                }
+            }
+            div { class: "bg-gray-800 rounded-lg divide-y divide-gray-700",
+                for album in albums {
+                    CollectionRow { key: "{album.item_id}", album, on_import }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CollectionRow(album: BandcampAlbumItem, on_import: EventHandler<String>) -> Element {
+    let track_count = album.track_count;
+    let item_id = album.item_id.clone();
+
+    rsx! {
+        div { class: "flex items-center gap-3 p-3",
+            div { class: "w-12 h-12 flex-shrink-0 bg-gray-700 rounded overflow-hidden",
+                if let Some(url) = &album.cover_url {
+                    img {
+                        class: "w-full h-full object-cover",
+                        src: "{url}",
+                        alt: "{album.title}",
+                    }
+                }
+            }
+            div { class: "flex-1 min-w-0",
+                p { class: "text-white font-medium truncate", "{album.title}" }
+                p { class: "text-sm text-gray-400 truncate",
+                    "{album.artist} · {track_count} tracks"
+                }
+            }
+            match album.status {
+                BandcampImportStatus::NotImported => rsx! {
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Small,
+                        onclick: move |_| on_import.call(item_id.clone()),
+                        "Import"
+                    }
+                },
+                BandcampImportStatus::Downloading => rsx! {
+                    span { class: "text-sm text-gray-400", "Downloading..." }
+                },
+                BandcampImportStatus::Importing => rsx! {
+                    span { class: "text-sm text-gray-400", "Importing..." }
+                },
+                BandcampImportStatus::Imported => rsx! {
+                    span { class: "text-sm text-green-400", "Imported" }
+                },
+                BandcampImportStatus::Failed(error) => rsx! {
+                    div { class: "flex items-center gap-3 min-w-0",
+                        span { class: "text-sm text-red-400 truncate", title: "{error}", "{error}" }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Small,
+                            onclick: move |_| on_import.call(item_id.clone()),
+                            "Retry"
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
@@ -2,12 +2,14 @@
 //!
 //! Pure, props-based components for the import UI.
 
+mod bandcamp_collection;
 mod cd_selector;
 mod source_selector;
 mod torrent_input;
 mod view;
 pub mod workflow;
 
+pub use bandcamp_collection::BandcampCollectionView;
 pub use cd_selector::{CdDriveStatus, CdSelectorView};
 pub use source_selector::{ImportSource, ImportSourceSelectorView};
 pub use torrent_input::{TorrentInputMode, TorrentInputView};
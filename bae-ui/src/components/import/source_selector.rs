@@ -11,6 +11,7 @@ pub enum ImportSource {
     Folder,
     Torrent,
     Cd,
+    Bandcamp,
 }
 
 impl ImportSource {
@@ -19,6 +20,7 @@ impl ImportSource {
             ImportSource::Folder => "Folder",
             ImportSource::Torrent => "Torrent",
             ImportSource::Cd => "CD",
+            ImportSource::Bandcamp => "Bandcamp",
         }
     }
 
@@ -27,6 +29,7 @@ impl ImportSource {
             ImportSource::Folder => "folder",
             ImportSource::Torrent => "torrent",
             ImportSource::Cd => "cd",
+            ImportSource::Bandcamp => "bandcamp",
         }
     }
 
@@ -37,6 +40,7 @@ impl ImportSource {
             ImportSource::Torrent,
             #[cfg(feature = "cd-rip")]
             ImportSource::Cd,
+            ImportSource::Bandcamp,
         ]
     }
 
@@ -44,6 +48,7 @@ impl ImportSource {
         match value {
             "torrent" => ImportSource::Torrent,
             "cd" => ImportSource::Cd,
+            "bandcamp" => ImportSource::Bandcamp,
             _ => ImportSource::Folder,
         }
     }
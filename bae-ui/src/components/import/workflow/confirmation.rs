@@ -23,6 +23,10 @@ pub fn ConfirmationView(
     remote_cover_url: Option<String>,
     /// Whether to copy files into managed storage
     managed: bool,
+    /// Whether the library has a cloud home to keep the files in
+    cloud_home_configured: bool,
+    /// Whether managed files move on to the cloud home once imported
+    move_to_cloud: bool,
     /// Whether import is in progress
     is_importing: bool,
     /// Whether import has completed
@@ -35,6 +39,8 @@ pub fn ConfirmationView(
     on_select_cover: EventHandler<SelectedCover>,
     /// Called when user toggles managed storage
     on_managed_change: EventHandler<bool>,
+    /// Called when user toggles keeping the files in the cloud home
+    on_move_to_cloud_change: EventHandler<bool>,
     /// Called when user clicks Edit to go back
     on_edit: EventHandler<()>,
     /// Called when user confirms import
@@ -68,6 +74,7 @@ pub fn ConfirmationView(
         let source_label = match candidate.source_type {
             MatchSourceType::MusicBrainz => "MusicBrainz",
             MatchSourceType::Discogs => "Discogs",
+            MatchSourceType::Tags => "Embedded",
         };
         // Use full-size CAA image for the lightbox (the thumbnail URL is only 250px)
         let lightbox_url = match (&candidate.source_type, &candidate.musicbrainz_release_id) {
@@ -165,6 +172,9 @@ pub fn ConfirmationView(
                             if let Some(ref country) = country_text {
                                 span { "{country}" }
                             }
+                            if candidate.source_type == MatchSourceType::Tags {
+                                span { class: "text-amber-400/80", "From tags, unverified" }
+                            }
                         }
                         if label_text.is_some() || original_year.is_some() {
                             div { class: "text-xs text-gray-500 flex flex-wrap gap-x-3",
@@ -232,6 +242,20 @@ pub fn ConfirmationView(
                         }
                         "Copy to library"
                     }
+                    if cloud_home_configured {
+                        label { class: "flex items-center gap-2 text-sm text-gray-400 cursor-pointer",
+                            input {
+                                r#type: "checkbox",
+                                checked: managed && move_to_cloud,
+                                disabled: disabled || !managed,
+                                onchange: move |evt: Event<FormData>| {
+                                    on_move_to_cloud_change.call(evt.checked());
+                                },
+                                class: "w-4 h-4 rounded border-gray-600 bg-gray-700 text-indigo-500 focus:ring-indigo-500 cursor-pointer",
+                            }
+                            "Keep in cloud home"
+                        }
+                    }
 
                     // Import status area: three visual states
                     if is_completed {
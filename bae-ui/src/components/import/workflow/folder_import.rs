@@ -20,17 +20,20 @@
 
 use super::{
     ConfirmationView, DiscIdPill, DiscIdSource, LoadingIndicator, ManualSearchPanelView,
-    MultipleExactMatchesView, SmartFileDisplayView,
+    MultipleExactMatchesView, RecordingSplitModal, SmartFileDisplayView,
 };
 use crate::components::helpers::Tooltip;
 use crate::components::icons::{CloudOffIcon, LoaderIcon};
 use crate::components::{Button, ButtonSize, ButtonVariant};
 use crate::components::{PanelPosition, ResizablePanel, ResizeDirection};
 use crate::display_types::{
-    IdentifyMode, ImportStep, MatchCandidate, SearchSource, SearchTab, SelectedCover,
+    AudioContentInfo, IdentifyMode, ImportStep, MatchCandidate, SearchSource, SearchTab,
+    SelectedCover,
 };
 use crate::floating_ui::Placement;
-use crate::stores::import::{CandidateState, ConfirmPhase, ImportState, ImportStateStoreExt};
+use crate::stores::import::{
+    CandidateState, ConfirmPhase, ImportState, ImportStateStoreExt, PrefetchState,
+};
 use dioxus::prelude::*;
 
 // ============================================================================
@@ -55,9 +58,27 @@ pub struct FolderImportViewProps {
     /// Detected encoding of the text file (for encoding selector)
     #[props(default)]
     pub text_file_encoding: Option<String>,
+    /// Configured network share address, offered next to the folder picker
+    #[props(default)]
+    pub network_share: Option<String>,
+    /// Whether the network share is being mounted
+    #[props(default)]
+    pub is_connecting_share: bool,
+    #[props(default)]
+    pub network_share_error: Option<String>,
+    /// Whether a picked archive is being extracted
+    #[props(default)]
+    pub is_extracting_archive: bool,
+    #[props(default)]
+    pub archive_error: Option<String>,
+    /// Whether the library has a cloud home, offered as a place for the files
+    #[props(default)]
+    pub cloud_home_configured: bool,
 
     // === Callbacks ===
     pub on_folder_select_click: EventHandler<()>,
+    pub on_archive_select_click: EventHandler<()>,
+    pub on_network_share_click: EventHandler<()>,
     pub on_view_change: EventHandler<Option<usize>>,
     pub on_encoding_change: EventHandler<(usize, String)>,
     pub on_skip_detection: EventHandler<()>,
@@ -75,10 +96,17 @@ pub struct FolderImportViewProps {
     pub on_search: EventHandler<()>,
     pub on_cancel_search: EventHandler<()>,
     pub on_manual_confirm: EventHandler<MatchCandidate>,
+    pub on_import_from_tags: EventHandler<()>,
+    /// Open the split editor for the candidate's single recording
+    pub on_split_recording: EventHandler<()>,
+    pub on_split_cancel: EventHandler<()>,
+    /// Save split points (ms) as a CUE sheet for the recording
+    pub on_split_save: EventHandler<Vec<u64>>,
     pub on_retry_cover: EventHandler<usize>,
     pub on_retry_discid_lookup: EventHandler<()>,
     pub on_select_cover: EventHandler<SelectedCover>,
     pub on_managed_change: EventHandler<bool>,
+    pub on_move_to_cloud_change: EventHandler<bool>,
     pub on_edit: EventHandler<()>,
     pub on_confirm: EventHandler<()>,
     pub on_view_in_library: EventHandler<String>,
@@ -103,7 +131,14 @@ pub fn FolderImportView(props: FolderImportViewProps) -> Element {
             div { class: "flex-1 flex flex-col",
                 EmptyView {
                     is_scanning,
+                    network_share: props.network_share.clone(),
+                    is_connecting_share: props.is_connecting_share,
+                    network_share_error: props.network_share_error.clone(),
+                    is_extracting_archive: props.is_extracting_archive,
+                    archive_error: props.archive_error.clone(),
                     on_folder_select: props.on_folder_select_click,
+                    on_archive_select: props.on_archive_select_click,
+                    on_network_share: props.on_network_share_click,
                 }
             }
         } else if let Some(key) = candidate_key {
@@ -145,16 +180,26 @@ pub fn FolderImportView(props: FolderImportViewProps) -> Element {
                             on_search: props.on_search,
                             on_cancel_search: props.on_cancel_search,
                             on_manual_confirm: props.on_manual_confirm,
+                            on_import_from_tags: props.on_import_from_tags,
+                            on_split_recording: props.on_split_recording,
                             on_retry_cover: props.on_retry_cover,
                             on_retry_discid_lookup: props.on_retry_discid_lookup,
                             on_select_cover: props.on_select_cover,
+                            cloud_home_configured: props.cloud_home_configured,
                             on_managed_change: props.on_managed_change,
+                            on_move_to_cloud_change: props.on_move_to_cloud_change,
                             on_edit: props.on_edit,
                             on_confirm: props.on_confirm,
                             on_view_in_library: props.on_view_in_library,
                         }
                     }
                 }
+
+                RecordingSplitModalWrapper {
+                    state,
+                    on_close: props.on_split_cancel,
+                    on_save: props.on_split_save,
+                }
             }
         }
     }
@@ -166,21 +211,64 @@ pub fn FolderImportView(props: FolderImportViewProps) -> Element {
 
 /// Empty state shown when no candidates are detected yet
 #[component]
-fn EmptyView(is_scanning: bool, on_folder_select: EventHandler<()>) -> Element {
+fn EmptyView(
+    is_scanning: bool,
+    network_share: Option<String>,
+    is_connecting_share: bool,
+    network_share_error: Option<String>,
+    is_extracting_archive: bool,
+    archive_error: Option<String>,
+    on_folder_select: EventHandler<()>,
+    on_archive_select: EventHandler<()>,
+    on_network_share: EventHandler<()>,
+) -> Element {
     rsx! {
         div { class: "flex-1 flex items-center justify-center px-6 py-4",
             div { class: "w-full max-w-3xl text-center space-y-3",
                 if is_scanning {
                     LoaderIcon { class: "w-5 h-5 text-gray-400 animate-spin mx-auto" }
                     p { class: "text-sm text-gray-400", "Scanning folder for releases..." }
+                } else if is_connecting_share {
+                    LoaderIcon { class: "w-5 h-5 text-gray-400 animate-spin mx-auto" }
+                    p { class: "text-sm text-gray-400", "Connecting to network share..." }
+                } else if is_extracting_archive {
+                    LoaderIcon { class: "w-5 h-5 text-gray-400 animate-spin mx-auto" }
+                    p { class: "text-sm text-gray-400", "Extracting archive..." }
                 } else {
-                    Button {
-                        variant: ButtonVariant::Primary,
-                        size: ButtonSize::Medium,
-                        onclick: move |_| on_folder_select.call(()),
-                        "Select folder"
+                    div { class: "flex items-center justify-center gap-3",
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            size: ButtonSize::Medium,
+                            onclick: move |_| on_folder_select.call(()),
+                            "Select folder"
+                        }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Medium,
+                            onclick: move |_| on_archive_select.call(()),
+                            "Open archive"
+                        }
+                        if network_share.is_some() {
+                            Button {
+                                variant: ButtonVariant::Secondary,
+                                size: ButtonSize::Medium,
+                                onclick: move |_| on_network_share.call(()),
+                                "Browse network share"
+                            }
+                        }
+                    }
+                    p { class: "text-sm text-gray-400",
+                        "Scans for folders with music files, or extracts a zip, 7z or rar download"
+                    }
+                    if let Some(ref url) = network_share {
+                        p { class: "text-xs text-gray-500 font-mono", "{url}" }
+                    }
+                    if let Some(ref error) = network_share_error {
+                        p { class: "text-sm text-red-400", "{error}" }
+                    }
+                    if let Some(ref error) = archive_error {
+                        p { class: "text-sm text-red-400", "{error}" }
                     }
-                    p { class: "text-sm text-gray-400", "Scans for folders with music files" }
                 }
             }
         }
@@ -206,10 +294,14 @@ fn WorkflowContent(
     on_search: EventHandler<()>,
     on_cancel_search: EventHandler<()>,
     on_manual_confirm: EventHandler<MatchCandidate>,
+    on_import_from_tags: EventHandler<()>,
+    on_split_recording: EventHandler<()>,
     on_retry_cover: EventHandler<usize>,
     on_retry_discid_lookup: EventHandler<()>,
     on_select_cover: EventHandler<SelectedCover>,
+    cloud_home_configured: bool,
     on_managed_change: EventHandler<bool>,
+    on_move_to_cloud_change: EventHandler<bool>,
     on_edit: EventHandler<()>,
     on_confirm: EventHandler<()>,
     on_view_in_library: EventHandler<String>,
@@ -247,6 +339,8 @@ fn WorkflowContent(
                         on_search,
                         on_cancel_search,
                         on_manual_confirm,
+                        on_import_from_tags,
+                        on_split_recording,
                         on_retry_cover,
                         on_retry_discid_lookup,
                         on_view_in_library,
@@ -256,7 +350,9 @@ fn WorkflowContent(
                     ConfirmStep {
                         state,
                         on_select_cover,
+                        cloud_home_configured,
                         on_managed_change,
+                        on_move_to_cloud_change,
                         on_edit,
                         on_confirm,
                         on_view_in_library,
@@ -290,6 +386,8 @@ fn IdentifyStep(
     on_search: EventHandler<()>,
     on_cancel_search: EventHandler<()>,
     on_manual_confirm: EventHandler<MatchCandidate>,
+    on_import_from_tags: EventHandler<()>,
+    on_split_recording: EventHandler<()>,
     on_retry_cover: EventHandler<usize>,
     on_retry_discid_lookup: EventHandler<()>,
     on_view_in_library: EventHandler<String>,
@@ -329,6 +427,7 @@ fn IdentifyStep(
                     on_switch_to_manual_search,
                     on_view_in_library,
                 }
+                SplitRecordingBar { state, on_split: on_split_recording }
             },
             IdentifyMode::ManualSearch => rsx! {
                 ManualSearchPanelView {
@@ -347,11 +446,114 @@ fn IdentifyStep(
                     on_view_in_library,
                     on_switch_to_exact_matches,
                 }
+                SplitRecordingBar { state, on_split: on_split_recording }
+                ImportFromTagsBar { state, on_import: on_import_from_tags }
             },
         }
     }
 }
 
+/// Offers a tag-only import when the folder's tags name an artist and album
+#[component]
+fn ImportFromTagsBar(state: ReadStore<ImportState>, on_import: EventHandler<()>) -> Element {
+    let metadata = state
+        .current_candidate_key()
+        .read()
+        .as_ref()
+        .and_then(|k| {
+            state
+                .candidate_states()
+                .read()
+                .get(k)
+                .map(|s| s.metadata().clone())
+        })
+        .unwrap_or_default();
+
+    let (Some(artist), Some(album)) = (metadata.artist, metadata.album) else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div { class: "mx-5 mb-5 bg-gray-800/20 rounded-lg p-3 flex items-center justify-between gap-4",
+            p { class: "text-sm text-gray-400 min-w-0 truncate",
+                "Not on MusicBrainz or Discogs? Import "
+                span { class: "text-gray-300", "{artist} – {album}" }
+                " from its tags"
+            }
+            Button {
+                variant: ButtonVariant::Outline,
+                size: ButtonSize::Small,
+                onclick: move |_| on_import.call(()),
+                "Use tags"
+            }
+        }
+    }
+}
+
+/// Offers to split the folder's single recording when the selected release
+/// has more tracks than the folder has files
+#[component]
+fn SplitRecordingBar(state: ReadStore<ImportState>, on_split: EventHandler<()>) -> Element {
+    let release_tracks = state.current_candidate_key().read().as_ref().and_then(|k| {
+        match state.candidate_states().read().get(k) {
+            Some(CandidateState::Identifying(is)) => {
+                let prefetch = match is.mode {
+                    IdentifyMode::MultipleExactMatches(_) => is.exact_match_prefetch.clone(),
+                    _ => is.search_state.current_tab_state().prefetch_state.clone(),
+                };
+                match (&is.files.audio, prefetch) {
+                    (
+                        AudioContentInfo::TrackFiles(files),
+                        Some(PrefetchState::TrackCountMismatch { release_tracks, .. }),
+                    ) if files.len() == 1 && files[0].format == "FLAC" && release_tracks > 1 => {
+                        Some(release_tracks)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    });
+
+    let Some(release_tracks) = release_tracks else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div { class: "mx-5 mb-5 bg-gray-800/20 rounded-lg p-3 flex items-center justify-between gap-4",
+            p { class: "text-sm text-gray-400 min-w-0",
+                "This folder has one recording. Split it into {release_tracks} tracks at the silences between them?"
+            }
+            Button {
+                variant: ButtonVariant::Outline,
+                size: ButtonSize::Small,
+                onclick: move |_| on_split.call(()),
+                "Split recording"
+            }
+        }
+    }
+}
+
+#[component]
+fn RecordingSplitModalWrapper(
+    state: ReadStore<ImportState>,
+    on_close: EventHandler<()>,
+    on_save: EventHandler<Vec<u64>>,
+) -> Element {
+    let split_state = state.recording_split().read().clone();
+    let is_open_memo = use_memo(move || state.recording_split().read().is_some());
+    let is_open: ReadSignal<bool> = is_open_memo.into();
+
+    rsx! {
+        RecordingSplitModal {
+            is_open,
+            state: split_state.unwrap_or_default(),
+            on_close,
+            on_save,
+        }
+    }
+}
+
 // ============================================================================
 // Step 3: Confirm
 // ============================================================================
@@ -361,7 +563,9 @@ fn IdentifyStep(
 fn ConfirmStep(
     state: ReadStore<ImportState>,
     on_select_cover: EventHandler<SelectedCover>,
+    cloud_home_configured: bool,
     on_managed_change: EventHandler<bool>,
+    on_move_to_cloud_change: EventHandler<bool>,
     on_edit: EventHandler<()>,
     on_confirm: EventHandler<()>,
     on_view_in_library: EventHandler<String>,
@@ -376,6 +580,7 @@ fn ConfirmStep(
         display_cover_url,
         artwork_files,
         managed,
+        move_to_cloud,
         is_importing,
         is_completed,
         completed_album_id,
@@ -406,6 +611,7 @@ fn ConfirmStep(
                 cover_url,
                 cs.files.artwork.clone(),
                 cs.managed,
+                cs.move_to_cloud,
                 importing,
                 completed,
                 album_id,
@@ -421,6 +627,7 @@ fn ConfirmStep(
             true,
             false,
             false,
+            false,
             None,
             None,
             None,
@@ -439,12 +646,15 @@ fn ConfirmStep(
             artwork_files,
             remote_cover_url: candidate.cover_url.clone(),
             managed,
+            cloud_home_configured,
+            move_to_cloud,
             is_importing,
             is_completed,
             completed_album_id,
             preparing_step_text,
             on_select_cover,
             on_managed_change,
+            on_move_to_cloud_change,
             on_edit,
             on_confirm,
             on_view_in_library,
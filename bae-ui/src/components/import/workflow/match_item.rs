@@ -150,7 +150,7 @@ pub fn MatchItemView(
                     // Error message for track count mismatch or fetch failure
                     if is_selected {
                         match &prefetch_state {
-                            Some(PrefetchState::TrackCountMismatch { release_tracks, local_files }) => {
+                            Some(PrefetchState::TrackCountMismatch { release_tracks, local_files, .. }) => {
                                 rsx! {
                                     p { class: "text-xs text-red-400 mt-1",
                                         "Release has {release_tracks} tracks but folder has {local_files} audio files"
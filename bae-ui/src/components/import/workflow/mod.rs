@@ -14,6 +14,7 @@ mod match_item;
 mod match_results_panel;
 mod metadata_display;
 mod multiple_exact_matches;
+mod recording_split;
 mod release_selector;
 mod release_sidebar;
 mod search_source_selector;
@@ -34,6 +35,7 @@ pub use match_item::{MatchItemView, TrackListingCompact};
 pub use match_results_panel::MatchResultsPanel;
 pub use metadata_display::MetadataDisplayView;
 pub use multiple_exact_matches::MultipleExactMatchesView;
+pub use recording_split::RecordingSplitModal;
 pub use release_selector::ReleaseSelectorView;
 pub use release_sidebar::{
     ReleaseSidebarView, DEFAULT_SIDEBAR_WIDTH, MAX_SIDEBAR_WIDTH, MIN_SIDEBAR_WIDTH,
@@ -0,0 +1,253 @@
+//! Recording split modal -- cut one long recording into the release's tracks
+//!
+//! Split points start at the detected silences. Clicking the waveform adds a
+//! split there; each split can be nudged or removed from the segment list.
+
+use crate::components::icons::{ChevronLeftIcon, ChevronRightIcon, LoaderIcon, XIcon};
+use crate::components::utils::format_duration;
+use crate::components::{Button, ButtonSize, ButtonVariant, ChromelessButton, Modal};
+use crate::display_types::RecordingSplit;
+use crate::stores::import::RecordingSplitState;
+use dioxus::prelude::*;
+
+/// How far one nudge moves a split point
+const NUDGE_MS: u64 = 500;
+
+#[component]
+pub fn RecordingSplitModal(
+    is_open: ReadSignal<bool>,
+    state: RecordingSplitState,
+    on_close: EventHandler<()>,
+    /// Called with the final split points, in ms
+    on_save: EventHandler<Vec<u64>>,
+) -> Element {
+    rsx! {
+        Modal { is_open, on_close: move |_| on_close.call(()),
+            div { class: "bg-gray-800 rounded-lg shadow-xl max-w-3xl w-full mx-4 max-h-[85vh] flex flex-col",
+                div { class: "flex items-center justify-between px-6 pt-6 pb-4 border-b border-gray-700",
+                    h2 { class: "text-xl font-bold text-white", "Split Recording" }
+                    button {
+                        class: "text-gray-400 hover:text-white transition-colors",
+                        onclick: move |_| on_close.call(()),
+                        XIcon { class: "w-5 h-5" }
+                    }
+                }
+                if let Some(ref error) = state.error {
+                    div { class: "mx-6 mt-4 p-3 bg-red-900/30 border border-red-700/50 rounded-lg text-sm text-red-300",
+                        {error.clone()}
+                    }
+                }
+                if let Some(split) = state.split {
+                    RecordingSplitEditor {
+                        key: "{split.file_name}",
+                        split,
+                        is_saving: state.is_saving,
+                        on_cancel: on_close,
+                        on_save,
+                    }
+                } else if state.error.is_none() {
+                    div { class: "p-6 flex items-center gap-3 text-sm text-gray-400",
+                        LoaderIcon { class: "w-4 h-4 animate-spin" }
+                        "Finding the gaps between tracks..."
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Editable copy of the split points, written back only on save
+#[component]
+fn RecordingSplitEditor(
+    split: RecordingSplit,
+    is_saving: bool,
+    on_cancel: EventHandler<()>,
+    on_save: EventHandler<Vec<u64>>,
+) -> Element {
+    let mut points = use_signal(|| split.split_points_ms.clone());
+
+    let current = points.read().clone();
+    let duration_ms = split.duration_ms.max(1);
+    let segment_count = current.len() + 1;
+    let track_count = split.tracks.len();
+    let can_save = segment_count == track_count && !is_saving;
+
+    let bucket_count = split.waveform.len().max(1);
+    let bucket_ms = duration_ms / bucket_count as u64;
+
+    let mut add_point = move |at_ms: u64| {
+        let mut points = points.write();
+        if let Err(pos) = points.binary_search(&at_ms) {
+            points.insert(pos, at_ms);
+        }
+    };
+
+    rsx! {
+        div { class: "p-6 overflow-y-auto flex-1 space-y-4",
+            p { class: "text-sm text-gray-400",
+                "{split.file_name} · {format_duration(split.duration_ms as i64)}. Click the waveform to add a split, and nudge splits until each segment lines up with its track."
+            }
+
+            // Waveform with split markers
+            div { class: "relative h-24 bg-gray-900/60 rounded-lg px-1",
+                div { class: "flex items-center h-full gap-px",
+                    for (index , level) in split.waveform.iter().enumerate() {
+                        {
+                            let height = (level * 100.0).max(2.0);
+                            rsx! {
+                                div {
+                                    key: "{index}",
+                                    class: "flex-1 h-full flex items-center cursor-pointer group",
+                                    onclick: move |_| add_point(index as u64 * bucket_ms + bucket_ms / 2),
+                                    div {
+                                        class: "w-full rounded-sm bg-gray-500 group-hover:bg-accent-soft",
+                                        style: "height: {height}%",
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                for point in current.iter() {
+                    {
+                        let left = *point as f64 / duration_ms as f64 * 100.0;
+                        rsx! {
+                            div {
+                                key: "{point}",
+                                class: "absolute inset-y-0 w-0.5 bg-accent pointer-events-none",
+                                style: "left: {left}%",
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Segments matched to the release's tracks
+            div { class: "space-y-1",
+                for index in 0..segment_count.max(track_count) {
+                    {
+                        let start_ms = if index == 0 { Some(0) } else { current.get(index - 1).copied() };
+                        let end_ms = current.get(index).copied().unwrap_or(split.duration_ms);
+                        let track = split.tracks.get(index).cloned();
+                        rsx! {
+                            SegmentRow {
+                                key: "{index}",
+                                number: index + 1,
+                                start_ms,
+                                length_ms: start_ms.map(|start| end_ms.saturating_sub(start)),
+                                track_title: track.as_ref().map(|t| t.title.clone()),
+                                track_duration: track.and_then(|t| t.duration),
+                                can_edit: index > 0 && start_ms.is_some(),
+                                on_nudge: move |forward: bool| {
+                                    let mut points = points.write();
+                                    let i = index - 1;
+                                    let lower = if i == 0 { 0 } else { points[i - 1] };
+                                    let upper = points.get(i + 1).copied().unwrap_or(duration_ms);
+                                    let moved = if forward {
+                                        points[i] + NUDGE_MS
+                                    } else {
+                                        points[i].saturating_sub(NUDGE_MS)
+                                    };
+                                    if moved > lower && moved < upper {
+                                        points[i] = moved;
+                                    }
+                                },
+                                on_remove: move |_| {
+                                    points.write().remove(index - 1);
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        div { class: "flex items-center justify-between gap-3 px-6 py-4 border-t border-gray-700",
+            div { class: "text-sm",
+                if segment_count == track_count {
+                    span { class: "text-gray-400", "{segment_count} segments for {track_count} tracks" }
+                } else {
+                    span { class: "text-red-400",
+                        "{segment_count} segments, but the release has {track_count} tracks"
+                    }
+                }
+            }
+            div { class: "flex gap-3",
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    size: ButtonSize::Medium,
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Medium,
+                    disabled: !can_save,
+                    loading: is_saving,
+                    onclick: move |_| on_save.call(points.read().clone()),
+                    "Split"
+                }
+            }
+        }
+    }
+}
+
+/// One segment: where it starts, how long it runs, and the track it becomes
+#[component]
+fn SegmentRow(
+    number: usize,
+    /// None for tracks that have no segment yet
+    start_ms: Option<u64>,
+    length_ms: Option<u64>,
+    track_title: Option<String>,
+    track_duration: Option<String>,
+    can_edit: bool,
+    on_nudge: EventHandler<bool>,
+    on_remove: EventHandler<()>,
+) -> Element {
+    rsx! {
+        div { class: "flex items-center gap-3 px-3 py-2 rounded-lg hover:bg-hover",
+            span { class: "w-6 text-sm text-gray-500 tabular-nums", "{number}" }
+            div { class: "flex-1 min-w-0",
+                div { class: "text-sm text-white truncate",
+                    if let Some(ref title) = track_title {
+                        "{title}"
+                    } else {
+                        span { class: "text-red-400", "No track for this segment" }
+                    }
+                }
+                if let Some(ref duration) = track_duration {
+                    div { class: "text-xs text-gray-500", "Release: {duration}" }
+                }
+            }
+            if let (Some(start), Some(length)) = (start_ms, length_ms) {
+                span { class: "w-28 text-sm text-gray-400 tabular-nums text-right",
+                    "{format_duration(start as i64)} +{format_duration(length as i64)}"
+                }
+            } else {
+                span { class: "w-28 text-sm text-gray-500 text-right", "Not split yet" }
+            }
+            div { class: "w-20 flex items-center justify-end gap-1",
+                if can_edit {
+                    ChromelessButton {
+                        class: Some("p-1 text-gray-400 hover:text-white".to_string()),
+                        aria_label: Some("Move split earlier".to_string()),
+                        onclick: move |_| on_nudge.call(false),
+                        ChevronLeftIcon { class: "w-3.5 h-3.5" }
+                    }
+                    ChromelessButton {
+                        class: Some("p-1 text-gray-400 hover:text-white".to_string()),
+                        aria_label: Some("Move split later".to_string()),
+                        onclick: move |_| on_nudge.call(true),
+                        ChevronRightIcon { class: "w-3.5 h-3.5" }
+                    }
+                    ChromelessButton {
+                        class: Some("p-1 text-gray-400 hover:text-red-400".to_string()),
+                        aria_label: Some("Remove split".to_string()),
+                        onclick: move |_| on_remove.call(()),
+                        XIcon { class: "w-3.5 h-3.5" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -440,12 +440,15 @@ fn TorrentConfirmContent(
                 remote_cover_url: candidate.cover_url.clone(),
 
                 managed,
+                cloud_home_configured: false,
+                move_to_cloud: false,
                 is_importing,
                 is_completed,
                 completed_album_id,
                 preparing_step_text,
                 on_select_cover,
                 on_managed_change,
+                on_move_to_cloud_change: |_| {},
                 on_edit,
                 on_confirm,
 
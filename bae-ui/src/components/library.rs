@@ -9,10 +9,12 @@ use crate::components::helpers::{ErrorDisplay, LoadingSpinner};
 use crate::components::icons::{
     ArrowDownIcon, ArrowUpIcon, ChevronDownIcon, PlusIcon, UserIcon, XIcon,
 };
-use crate::components::{Button, ButtonSize, ButtonVariant, ChromelessButton};
+use crate::components::{Button, ButtonSize, ButtonVariant, ChromelessButton, Modal};
 use crate::components::{MenuDropdown, MenuItem, Placement};
+use crate::components::{TextInput, TextInputSize, TextInputType};
 use crate::display_types::{
-    Album, Artist, LibrarySortField, LibraryViewMode, SortCriterion, SortDirection,
+    Album, Artist, Collection, LibraryFilter, LibraryQualityFilter, LibrarySortField,
+    LibraryStorageFilter, LibraryViewMode, SortCriterion, SortDirection,
 };
 use crate::stores::library::{LibraryState, LibraryStateStoreExt};
 use crate::stores::ui::{LibrarySortState, LibrarySortStateStoreExt};
@@ -26,6 +28,9 @@ use std::rc::Rc;
 struct AlbumGridItem {
     album: Album,
     artists: Vec<Artist>,
+    version_count: usize,
+    cover_placeholder: Option<String>,
+    collection_ids: Vec<String>,
 }
 
 fn sort_field_label(field: LibrarySortField) -> &'static str {
@@ -34,6 +39,22 @@ fn sort_field_label(field: LibrarySortField) -> &'static str {
         LibrarySortField::Artist => "Artist",
         LibrarySortField::Year => "Year",
         LibrarySortField::DateAdded => "Date Added",
+        LibrarySortField::PlayCount => "Plays",
+    }
+}
+
+fn storage_filter_label(storage: LibraryStorageFilter) -> &'static str {
+    match storage {
+        LibraryStorageFilter::Local => "On this device",
+        LibraryStorageFilter::Cloud => "In the cloud",
+        LibraryStorageFilter::Unmanaged => "Unmanaged files",
+    }
+}
+
+fn quality_filter_label(quality: LibraryQualityFilter) -> &'static str {
+    match quality {
+        LibraryQualityFilter::Lossless => "Lossless",
+        LibraryQualityFilter::Lossy => "Lossy",
     }
 }
 
@@ -41,6 +62,7 @@ fn view_mode_label(mode: LibraryViewMode) -> &'static str {
     match mode {
         LibraryViewMode::Albums => "Albums",
         LibraryViewMode::Artists => "Artists",
+        LibraryViewMode::Composers => "Composers",
     }
 }
 
@@ -53,6 +75,7 @@ pub fn LibraryView(
     sort_state: ReadStore<LibrarySortState>,
     on_sort_criteria_change: EventHandler<Vec<SortCriterion>>,
     on_view_mode_change: EventHandler<LibraryViewMode>,
+    on_filter_change: EventHandler<LibraryFilter>,
     // Navigation callback - called with album_id when an album is clicked
     on_album_click: EventHandler<String>,
     // Navigation callback - called with artist_id when an artist name is clicked
@@ -60,6 +83,14 @@ pub fn LibraryView(
     // Action callbacks
     on_play_album: EventHandler<String>,
     on_add_album_to_queue: EventHandler<String>,
+    /// Called with (album ID, collection ID) to add the album to the
+    /// collection or take it out
+    on_toggle_collection: EventHandler<(String, String)>,
+    /// Called with (collection name, album ID) to create a collection
+    /// holding the album
+    on_create_collection: EventHandler<(String, String)>,
+    /// Called with the collection ID
+    on_delete_collection: EventHandler<String>,
     // Empty state action (e.g., navigate to import)
     on_empty_action: EventHandler<()>,
     /// When true, hides import/edit actions (used when viewing a followed library)
@@ -74,12 +105,33 @@ pub fn LibraryView(
     let error = state.error().read().clone();
     let albums = state.albums().read().clone();
     let artists_by_album = state.artists_by_album().read().clone();
+    let composers_by_album = state.composers_by_album().read().clone();
+    let version_counts = state.version_counts().read().clone();
+    let cover_placeholders = state.cover_placeholders().read().clone();
+    let recently_added = state.recently_added().read().clone();
+    let recently_played = state.recently_played().read().clone();
+    let filter_genres = state.filter_genres().read().clone();
+    let filter_decades = state.filter_decades().read().clone();
+    let collections = state.collections().read().clone();
+    let collection_ids_by_album = state.collection_ids_by_album().read().clone();
+    // A followed library is someone else's to organize
+    let offered_collections = if read_only {
+        None
+    } else {
+        Some(collections.clone())
+    };
 
     let sort_criteria = sort_state.sort_criteria().read().clone();
     let view_mode = *sort_state.view_mode().read();
+    let filter = sort_state.filter().read().clone();
+    let is_filtered = !filter.is_empty();
     let mut scroll_target: Signal<Option<Rc<MountedData>>> = use_signal(|| None);
+    // Album the "New Collection" dialog will add to the collection
+    let mut new_collection_album: Signal<Option<String>> = use_signal(|| None);
+    let on_new_collection = move |album_id: String| new_collection_album.set(Some(album_id));
 
     rsx! {
+        NewCollectionDialog { album_id: new_collection_album, on_create: on_create_collection }
         div {
             class: "flex-grow overflow-y-auto flex flex-col py-10",
             onmounted: move |evt| scroll_target.set(Some(evt.data())),
@@ -95,7 +147,7 @@ pub fn LibraryView(
                         }
                     }
 
-                    if !loading && error.is_none() && !albums.is_empty() {
+                    if !loading && error.is_none() && (!albums.is_empty() || is_filtered) {
                         SortToolbar {
                             sort_criteria: sort_criteria.clone(),
                             view_mode,
@@ -112,6 +164,25 @@ pub fn LibraryView(
                     p { class: "text-sm mt-2 text-gray-400",
                         "An error occurred while loading your music library."
                     }
+                } else if albums.is_empty() && is_filtered {
+                    FilterToolbar {
+                        filter: filter.clone(),
+                        genres: filter_genres,
+                        decades: filter_decades,
+                        collections: collections.clone(),
+                        read_only,
+                        on_filter_change,
+                        on_delete_collection,
+                    }
+                    div { class: "flex-1 flex flex-col items-center justify-center",
+                        p { class: "text-gray-500 mb-4", "No albums match these filters" }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Medium,
+                            onclick: move |_| on_filter_change.call(LibraryFilter::default()),
+                            "Clear Filters"
+                        }
+                    }
                 } else if albums.is_empty() {
                     div { class: "flex-1 flex flex-col items-center justify-center",
                         if read_only {
@@ -129,18 +200,90 @@ pub fn LibraryView(
                 } else {
                     match view_mode {
                         LibraryViewMode::Albums => rsx! {
+                            FilterToolbar {
+                                filter: filter.clone(),
+                                genres: filter_genres,
+                                decades: filter_decades,
+                                collections: collections.clone(),
+                                read_only,
+                                on_filter_change,
+                                on_delete_collection,
+                            }
+                            // The shelves are for browsing the whole library
+                            if !is_filtered {
+                                RecentAlbumsShelf {
+                                    title: "Recently Played",
+                                    scope: "recently-played",
+                                    album_ids: recently_played,
+                                    albums: albums.clone(),
+                                    artists_by_album: artists_by_album.clone(),
+                                    version_counts: version_counts.clone(),
+                                    cover_placeholders: cover_placeholders.clone(),
+                                    on_album_click,
+                                    on_artist_click,
+                                    on_play_album,
+                                    on_add_album_to_queue,
+                                    collections: offered_collections.clone(),
+                                    collection_ids_by_album: collection_ids_by_album.clone(),
+                                    on_toggle_collection,
+                                    on_new_collection,
+                                }
+                                RecentAlbumsShelf {
+                                    title: "Recently Added",
+                                    scope: "recently-added",
+                                    album_ids: recently_added,
+                                    albums: albums.clone(),
+                                    artists_by_album: artists_by_album.clone(),
+                                    version_counts: version_counts.clone(),
+                                    cover_placeholders: cover_placeholders.clone(),
+                                    on_album_click,
+                                    on_artist_click,
+                                    on_play_album,
+                                    on_add_album_to_queue,
+                                    collections: offered_collections.clone(),
+                                    collection_ids_by_album: collection_ids_by_album.clone(),
+                                    on_toggle_collection,
+                                    on_new_collection,
+                                }
+                            }
                             AlbumGrid {
                                 albums: albums.clone(),
                                 artists_by_album,
+                                version_counts,
+                                cover_placeholders,
+                                collections: offered_collections,
+                                collection_ids_by_album,
                                 on_album_click,
                                 on_artist_click,
                                 on_play_album,
                                 on_add_album_to_queue,
+                                on_toggle_collection,
+                                on_new_collection,
                                 scroll_target: ScrollTarget::Element(scroll_target.into()),
                             }
                         },
                         LibraryViewMode::Artists => rsx! {
-                            ArtistListView { albums, artists_by_album, on_artist_click }
+                            ArtistListView {
+                                albums,
+                                artists_by_album,
+                                include_compilations: false,
+                                on_artist_click,
+                            }
+                        },
+                        LibraryViewMode::Composers if composers_by_album.is_empty() => rsx! {
+                            p { class: "text-gray-500",
+                                "No composers yet. They're added for albums matched on MusicBrainz that list the works performed."
+                            }
+                        },
+                        // Compilations count here: a composer is credited per
+                        // track, not as the album artist
+                        LibraryViewMode::Composers => rsx! {
+                            ArtistListView {
+                                albums,
+                                artists_by_album: composers_by_album,
+                                include_compilations: true,
+                                on_artist_click,
+                            }
                         },
                     }
                 }
@@ -210,7 +353,7 @@ fn SortToolbar(
     }
 }
 
-/// View mode dropdown (Albums / Artists)
+/// View mode dropdown (Albums / Artists / Composers)
 #[component]
 fn ViewModeDropdown(
     view_mode: LibraryViewMode,
@@ -239,7 +382,7 @@ fn ViewModeDropdown(
             on_close: move |_| show_menu.set(false),
             placement: Placement::BottomEnd,
 
-            for mode in [LibraryViewMode::Albums, LibraryViewMode::Artists] {
+            for mode in LibraryViewMode::ALL {
                 MenuItem {
                     onclick: move |_| {
                         show_menu.set(false);
@@ -370,6 +513,266 @@ fn SortCriterionItem(
     }
 }
 
+/// Genre, decade, storage, quality and collection filters above the album grid
+#[component]
+fn FilterToolbar(
+    filter: LibraryFilter,
+    genres: Vec<String>,
+    decades: Vec<i32>,
+    collections: Vec<Collection>,
+    read_only: bool,
+    on_filter_change: EventHandler<LibraryFilter>,
+    on_delete_collection: EventHandler<String>,
+) -> Element {
+    let decade_options: Vec<String> = decades.iter().map(|d| format!("{d}s")).collect();
+    let storage_options: Vec<String> = LibraryStorageFilter::ALL
+        .iter()
+        .map(|s| storage_filter_label(*s).to_string())
+        .collect();
+    let quality_options: Vec<String> = LibraryQualityFilter::ALL
+        .iter()
+        .map(|q| quality_filter_label(*q).to_string())
+        .collect();
+
+    let selected_genre = filter
+        .genre
+        .as_ref()
+        .and_then(|g| genres.iter().position(|name| name == g));
+    let selected_decade = filter
+        .decade
+        .and_then(|d| decades.iter().position(|decade| *decade == d));
+    let selected_storage = filter
+        .storage
+        .and_then(|s| LibraryStorageFilter::ALL.iter().position(|o| *o == s));
+    let selected_quality = filter
+        .quality
+        .and_then(|q| LibraryQualityFilter::ALL.iter().position(|o| *o == q));
+    let collection_options: Vec<String> = collections.iter().map(|c| c.name.clone()).collect();
+    let selected_collection = filter
+        .collection
+        .as_ref()
+        .and_then(|id| collections.iter().position(|c| &c.id == id));
+    let deletable_collection = selected_collection
+        .filter(|_| !read_only)
+        .map(|i| collections[i].id.clone());
+
+    rsx! {
+        div { class: "flex items-center gap-1 mb-6 -mt-2",
+            if !genres.is_empty() {
+                FilterDropdown {
+                    id: "filter-genre-btn",
+                    label: "Genre",
+                    options: genres.clone(),
+                    selected: selected_genre,
+                    on_select: {
+                        let filter = filter.clone();
+                        let genres = genres.clone();
+                        move |index: Option<usize>| {
+                            let mut next = filter.clone();
+                            next.genre = index.map(|i| genres[i].clone());
+                            on_filter_change.call(next);
+                        }
+                    },
+                }
+            }
+            if !decades.is_empty() {
+                FilterDropdown {
+                    id: "filter-decade-btn",
+                    label: "Decade",
+                    options: decade_options,
+                    selected: selected_decade,
+                    on_select: {
+                        let filter = filter.clone();
+                        let decades = decades.clone();
+                        move |index: Option<usize>| {
+                            let mut next = filter.clone();
+                            next.decade = index.map(|i| decades[i]);
+                            on_filter_change.call(next);
+                        }
+                    },
+                }
+            }
+            FilterDropdown {
+                id: "filter-storage-btn",
+                label: "Storage",
+                options: storage_options,
+                selected: selected_storage,
+                on_select: {
+                    let filter = filter.clone();
+                    move |index: Option<usize>| {
+                        let mut next = filter.clone();
+                        next.storage = index.map(|i| LibraryStorageFilter::ALL[i]);
+                        on_filter_change.call(next);
+                    }
+                },
+            }
+            FilterDropdown {
+                id: "filter-quality-btn",
+                label: "Quality",
+                options: quality_options,
+                selected: selected_quality,
+                on_select: {
+                    let filter = filter.clone();
+                    move |index: Option<usize>| {
+                        let mut next = filter.clone();
+                        next.quality = index.map(|i| LibraryQualityFilter::ALL[i]);
+                        on_filter_change.call(next);
+                    }
+                },
+            }
+            if !collections.is_empty() {
+                FilterDropdown {
+                    id: "filter-collection-btn",
+                    label: "Collection",
+                    options: collection_options,
+                    selected: selected_collection,
+                    on_select: {
+                        let filter = filter.clone();
+                        let collections = collections.clone();
+                        move |index: Option<usize>| {
+                            let mut next = filter.clone();
+                            next.collection = index.map(|i| collections[i].id.clone());
+                            on_filter_change.call(next);
+                        }
+                    },
+                }
+            }
+            if let Some(collection_id) = deletable_collection {
+                ChromelessButton {
+                    class: Some(
+                        "px-2 py-1 rounded-md text-sm text-gray-500 hover:text-red-400 hover:bg-hover transition-all"
+                            .to_string(),
+                    ),
+                    onclick: move |_| on_delete_collection.call(collection_id.clone()),
+                    "Delete Collection"
+                }
+            }
+            if !filter.is_empty() {
+                ChromelessButton {
+                    class: Some(
+                        "px-2 py-1 rounded-md text-sm text-gray-500 hover:text-white hover:bg-hover transition-all"
+                            .to_string(),
+                    ),
+                    onclick: move |_| on_filter_change.call(LibraryFilter::default()),
+                    "Clear"
+                }
+            }
+        }
+    }
+}
+
+/// One filter: shows its label, or the selected option, with a menu of the
+/// options and "Any"
+#[component]
+fn FilterDropdown(
+    id: &'static str,
+    label: &'static str,
+    options: Vec<String>,
+    selected: Option<usize>,
+    /// Called with the option index, or None for "Any"
+    on_select: EventHandler<Option<usize>>,
+) -> Element {
+    let mut show_menu = use_signal(|| false);
+    let is_open: ReadSignal<bool> = show_menu.into();
+    let current = selected.and_then(|i| options.get(i)).cloned();
+
+    rsx! {
+        ChromelessButton {
+            id: Some(id.to_string()),
+            class: Some(
+                "flex items-center gap-1 px-2 py-1 rounded-md text-sm hover:text-white hover:bg-hover transition-all"
+                    .to_string(),
+            ),
+            aria_label: Some(format!("Filter by {}", label.to_lowercase())),
+            onclick: move |_| show_menu.set(!show_menu()),
+            if let Some(ref current) = current {
+                span { class: "text-accent-soft", "{current}" }
+            } else {
+                span { class: "text-gray-400", "{label}" }
+            }
+            ChevronDownIcon { class: "w-3 h-3 text-gray-400" }
+        }
+
+        MenuDropdown {
+            anchor_id: id.to_string(),
+            is_open,
+            on_close: move |_| show_menu.set(false),
+            placement: Placement::BottomStart,
+
+            MenuItem {
+                onclick: move |_| {
+                    show_menu.set(false);
+                    on_select.call(None);
+                },
+                span { class: if selected.is_none() { "text-accent-soft" } else { "" }, "Any" }
+            }
+            for (index , option) in options.iter().enumerate() {
+                MenuItem {
+                    onclick: move |_| {
+                        show_menu.set(false);
+                        on_select.call(Some(index));
+                    },
+                    span { class: if selected == Some(index) { "text-accent-soft" } else { "" },
+                        "{option}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Names a new collection for the album in `album_id`, open while it's set
+#[component]
+fn NewCollectionDialog(
+    mut album_id: Signal<Option<String>>,
+    /// Called with (collection name, album ID)
+    on_create: EventHandler<(String, String)>,
+) -> Element {
+    let is_open = use_memo(move || album_id().is_some());
+    let mut name = use_signal(String::new);
+    let is_blank = name.read().trim().is_empty();
+    let mut close = move || {
+        album_id.set(None);
+        name.set(String::new());
+    };
+
+    rsx! {
+        Modal { is_open, on_close: move |_| close(),
+            div { class: "bg-gray-800 rounded-lg p-6 max-w-md w-full mx-4",
+                h2 { class: "text-xl font-bold text-white mb-4", "New Collection" }
+                TextInput {
+                    value: name(),
+                    on_input: move |v| name.set(v),
+                    size: TextInputSize::Medium,
+                    input_type: TextInputType::Text,
+                    placeholder: "Collection name",
+                    autofocus: true,
+                }
+                div { class: "flex gap-3 justify-end mt-4",
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Medium,
+                        onclick: move |_| close(),
+                        "Cancel"
+                    }
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Medium,
+                        disabled: is_blank,
+                        onclick: move |_| {
+                            if let Some(album_id) = album_id() {
+                                on_create.call((name.read().trim().to_string(), album_id));
+                            }
+                            close();
+                        },
+                        "Create"
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// An artist with their first non-compilation album cover
 struct ArtistListItem {
     artist: Artist,
@@ -387,12 +790,13 @@ struct ArtistGroup {
 fn derive_artist_list(
     albums: &[Album],
     artists_by_album: &HashMap<String, Vec<Artist>>,
+    include_compilations: bool,
 ) -> Vec<ArtistListItem> {
     // Invert the map: artist_id -> (Artist, first cover_url)
     let mut artist_map: HashMap<String, ArtistListItem> = HashMap::new();
 
     for album in albums {
-        if album.is_compilation {
+        if album.is_compilation && !include_compilations {
             continue;
         }
 
@@ -478,9 +882,10 @@ fn group_artists_by_letter(items: Vec<ArtistListItem>) -> Vec<ArtistGroup> {
 fn ArtistListView(
     albums: Vec<Album>,
     artists_by_album: HashMap<String, Vec<Artist>>,
+    include_compilations: bool,
     on_artist_click: EventHandler<String>,
 ) -> Element {
-    let items = derive_artist_list(&albums, &artists_by_album);
+    let items = derive_artist_list(&albums, &artists_by_album, include_compilations);
     let groups = group_artists_by_letter(items);
 
     rsx! {
@@ -536,15 +941,85 @@ fn ArtistRow(artist: Artist, cover_url: Option<String>, on_click: EventHandler<S
     }
 }
 
+/// A row of albums above the grid, e.g. the ones played most recently.
+/// Renders nothing when none of `album_ids` is in `albums`.
+#[component]
+fn RecentAlbumsShelf(
+    title: &'static str,
+    /// Prefix for the cards' menu anchors, which would otherwise clash
+    /// with the same album's card in the grid
+    scope: &'static str,
+    album_ids: Vec<String>,
+    albums: Vec<Album>,
+    artists_by_album: HashMap<String, Vec<Artist>>,
+    version_counts: HashMap<String, usize>,
+    cover_placeholders: HashMap<String, String>,
+    on_album_click: EventHandler<String>,
+    on_artist_click: EventHandler<String>,
+    on_play_album: EventHandler<String>,
+    on_add_album_to_queue: EventHandler<String>,
+    collections: Option<Vec<Collection>>,
+    collection_ids_by_album: HashMap<String, Vec<String>>,
+    on_toggle_collection: EventHandler<(String, String)>,
+    on_new_collection: EventHandler<String>,
+) -> Element {
+    let open_dropdown: Signal<Option<String>> = use_signal(|| None);
+
+    let shelf_albums: Vec<Album> = album_ids
+        .iter()
+        .filter_map(|id| albums.iter().find(|a| &a.id == id).cloned())
+        .collect();
+    if shelf_albums.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "mb-8",
+            h2 { class: "text-sm font-semibold text-gray-400 uppercase tracking-wide mb-3",
+                "{title}"
+            }
+            div { class: "flex gap-6 overflow-x-auto pb-2",
+                for album in shelf_albums {
+                    div { key: "{album.id}", class: "w-[200px] flex-shrink-0",
+                        AlbumCard {
+                            artists: artists_by_album.get(&album.id).cloned().unwrap_or_default(),
+                            version_count: version_counts.get(&album.id).copied().unwrap_or(0),
+                            cover_placeholder: cover_placeholders.get(&album.id).cloned(),
+                            collection_ids: collection_ids_by_album.get(&album.id).cloned().unwrap_or_default(),
+                            album,
+                            on_click: on_album_click,
+                            on_artist_click,
+                            on_play: on_play_album,
+                            on_add_to_queue: on_add_album_to_queue,
+                            on_toggle_collection,
+                            on_new_collection,
+                            open_dropdown,
+                            anchor_scope: Some(scope.to_string()),
+                            collections: collections.clone(),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Grid component to display albums with virtual scrolling
 #[component]
-fn AlbumGrid(
+pub(crate) fn AlbumGrid(
     albums: Vec<Album>,
     artists_by_album: HashMap<String, Vec<Artist>>,
+    version_counts: HashMap<String, usize>,
+    cover_placeholders: HashMap<String, String>,
+    /// Collections offered in each card's menu, if any
+    collections: Option<Vec<Collection>>,
+    collection_ids_by_album: HashMap<String, Vec<String>>,
     on_album_click: EventHandler<String>,
     on_artist_click: EventHandler<String>,
     on_play_album: EventHandler<String>,
     on_add_album_to_queue: EventHandler<String>,
+    on_toggle_collection: EventHandler<(String, String)>,
+    on_new_collection: EventHandler<String>,
     scroll_target: ScrollTarget,
 ) -> Element {
     // Prepare items by joining albums with their artists
@@ -552,7 +1027,19 @@ fn AlbumGrid(
         .into_iter()
         .map(|album| {
             let artists = artists_by_album.get(&album.id).cloned().unwrap_or_default();
-            AlbumGridItem { album, artists }
+            let version_count = version_counts.get(&album.id).copied().unwrap_or(0);
+            let cover_placeholder = cover_placeholders.get(&album.id).cloned();
+            let collection_ids = collection_ids_by_album
+                .get(&album.id)
+                .cloned()
+                .unwrap_or_default();
+            AlbumGridItem {
+                album,
+                artists,
+                version_count,
+                cover_placeholder,
+                collection_ids,
+            }
         })
         .collect();
 
@@ -574,11 +1061,18 @@ fn AlbumGrid(
                 key: "{item.album.id}",
                 album: item.album,
                 artists: item.artists,
+                version_count: item.version_count,
+                cover_placeholder: item.cover_placeholder,
+                collections: collections.clone(),
+                collection_ids: item.collection_ids,
                 on_click: on_album_click,
                 on_artist_click,
                 on_play: on_play_album,
                 on_add_to_queue: on_add_album_to_queue,
+                on_toggle_collection,
+                on_new_collection,
                 open_dropdown,
+                anchor_scope: None,
             }
         }
     }));
@@ -4,6 +4,7 @@ pub mod album_card;
 pub mod album_detail;
 pub mod app_layout;
 pub mod artist_detail;
+pub mod browse;
 pub mod button;
 pub mod dropdown;
 pub mod error_banner;
@@ -17,6 +18,7 @@ pub mod menu;
 pub mod modal;
 pub mod pill;
 pub mod playback;
+pub mod playlists;
 pub mod resizable_panel;
 pub mod segmented_control;
 pub mod select;
@@ -32,10 +34,11 @@ pub use album_detail::release_tabs_section::ReleaseTorrentInfo;
 pub use album_detail::{
     AlbumArt, AlbumCoverSection, AlbumDetailView, AlbumMetadata, DeleteAlbumDialog,
     DeleteReleaseDialog, ExportErrorToast, PlayAlbumButton, ReleaseInfoModal, ReleaseTabsSection,
-    TrackRow,
+    TrackRow, VersionPicker,
 };
 pub use app_layout::AppLayoutView;
 pub use artist_detail::ArtistDetailView;
+pub use browse::{decade_label, BrowseAlbumsView, BrowseView};
 pub use button::{Button, ButtonSize, ButtonVariant, ChromelessButton};
 pub use dioxus_virtual_scroll::{
     GridLayout, KeyFn, RenderFn, ScrollTarget, VirtualGrid, VirtualGridConfig,
@@ -55,9 +58,9 @@ pub use icons::{
     SettingsIcon, SkipBackIcon, SkipForwardIcon, StarIcon, TrashIcon, UploadIcon, UserIcon, XIcon,
 };
 pub use import::{
-    CdDriveStatus, CdSelectorView, ConfirmationView, DiscIdLookupErrorView, FileListView,
-    FolderImportView, FolderImportViewProps, GalleryItem, GalleryItemContent, GalleryLightbox,
-    ImportErrorDisplayView, ImportSource, ImportSourceSelectorView, ImportView,
+    BandcampCollectionView, CdDriveStatus, CdSelectorView, ConfirmationView, DiscIdLookupErrorView,
+    FileListView, FolderImportView, FolderImportViewProps, GalleryItem, GalleryItemContent,
+    GalleryLightbox, ImportErrorDisplayView, ImportSource, ImportSourceSelectorView, ImportView,
     ManualSearchPanelView, MatchItemView, MetadataDetectionPromptView, MultipleExactMatchesView,
     ReleaseSelectorView, ReleaseSidebarView, SearchSourceSelectorView, SelectedSourceView,
     TorrentFilesDisplayView, TorrentInfoDisplayView, TorrentInputMode, TorrentInputView,
@@ -68,16 +71,25 @@ pub use library::LibraryView;
 pub use menu::{MenuDivider, MenuDropdown, MenuItem};
 pub use modal::Modal;
 pub use pill::{Pill, PillVariant};
-pub use playback::{NowPlayingBarView, QueueSidebarState, QueueSidebarView};
+pub use playback::{
+    LyricsPanelView, NowPlayingBarView, PlaybackDiagnosticsOverlay, QueueSidebarState,
+    QueueSidebarView,
+};
+pub use playlists::PlaylistsView;
 pub use resizable_panel::{GrabBar, PanelPosition, ResizablePanel, ResizeDirection};
 pub use segmented_control::{Segment, SegmentedControl};
 pub use select::{Select, SelectOption};
 pub use settings::{
-    AboutSectionView, BaeCloudAuthMode, BitTorrentSectionView, BitTorrentSettings,
-    CloudProviderOption, CloudProviderPicker, DiscogsSectionView, FollowLibraryView,
-    FollowSyncStatus, JoinLibraryView, JoinStatus, LibraryInfo, LibrarySectionView, SettingsCard,
-    SettingsSection, SettingsTab, SettingsView, SubsonicSectionView, SyncBucketConfig,
-    SyncSectionView,
+    AboutSectionView, AppPasswordInfo, BaeCloudAuthMode, BitTorrentSectionView, BitTorrentSettings,
+    BrokenAlbumInfo, BrokenFileInfo, BrokenReleaseInfo, CloudProviderOption, CloudProviderPicker,
+    DiscogsSectionView, DuplicateGroupInfo, DuplicateReleaseInfo, DuplicatesView,
+    FanartSectionView, FollowLibraryView, FollowSyncStatus, GenreCleanupView, GenreInfo,
+    GenreMergeInfo, ImportSectionView, IntegritySummary, IntegrityView, JoinLibraryView,
+    JoinStatus, LibraryInfo, LibrarySectionView, MigrationResult, MigrationSectionView,
+    MigrationServer, MigrationStatus, MusicBrainzCollectionInfo, MusicBrainzSectionView,
+    PartySectionView, PlaybackSectionView, SettingsCard, SettingsSection, SettingsTab,
+    SettingsView, SpotifyAlbumSearch, SpotifyReportAlbum, SpotifyReportView, SubsonicSectionView,
+    SyncBucketConfig, SyncSectionView,
 };
 pub use success_toast::SuccessToast;
 pub use text_input::{TextInput, TextInputSize, TextInputType};
@@ -86,4 +98,4 @@ pub use title_bar::{
     AlbumResult, ArtistResult, GroupedSearchResults, NavItem, SearchAction, TitleBarView,
     TrackResult, SEARCH_INPUT_ID,
 };
-pub use utils::{format_duration, format_file_size};
+pub use utils::{blurhash_data_url, format_duration, format_file_size};
@@ -0,0 +1,106 @@
+//! Playback diagnostics overlay -- session quality counters for chasing stutter
+
+use crate::components::icons::XIcon;
+use crate::components::{Button, ButtonSize, ButtonVariant, ChromelessButton};
+use crate::display_types::PlaybackDiagnostics;
+use dioxus::prelude::*;
+
+#[component]
+pub fn PlaybackDiagnosticsOverlay(
+    diagnostics: PlaybackDiagnostics,
+    /// Copy the counters as text, for pasting into a bug report. Carries the text.
+    on_copy: EventHandler<String>,
+    on_close: EventHandler<()>,
+) -> Element {
+    let mut copied = use_signal(|| false);
+    let rows = diagnostics_rows(&diagnostics);
+    let report = diagnostics_report(&rows);
+
+    rsx! {
+        div { class: "fixed right-4 bottom-24 z-50 w-72 bg-gray-900/95 border border-gray-700 rounded-lg shadow-xl",
+            div { class: "flex items-center justify-between px-4 pt-3 pb-2 border-b border-gray-700",
+                h3 { class: "text-sm font-semibold text-white", "Playback Diagnostics" }
+                ChromelessButton {
+                    class: Some("p-1 text-gray-400 hover:text-white".to_string()),
+                    aria_label: Some("Close diagnostics".to_string()),
+                    onclick: move |_| on_close.call(()),
+                    XIcon { class: "w-3.5 h-3.5" }
+                }
+            }
+            div { class: "px-4 py-3 space-y-1",
+                for (label , value , is_problem) in rows.iter() {
+                    div { key: "{label}", class: "flex justify-between gap-3 text-xs",
+                        span { class: "text-gray-400", "{label}" }
+                        span { class: if *is_problem { "tabular-nums text-amber-400" } else { "tabular-nums text-gray-200" },
+                            "{value}"
+                        }
+                    }
+                }
+            }
+            div { class: "px-4 pb-3",
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    size: ButtonSize::Small,
+                    onclick: move |_| {
+                        on_copy.call(report.clone());
+                        copied.set(true);
+                    },
+                    if copied() {
+                        "Copied"
+                    } else {
+                        "Copy"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// (label, value, whether it points at a problem)
+fn diagnostics_rows(d: &PlaybackDiagnostics) -> Vec<(&'static str, String, bool)> {
+    let ms = |v: Option<u64>| {
+        v.map(|ms| format!("{ms} ms"))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    vec![
+        ("Session", format_session(d.session_secs), false),
+        ("Tracks started", d.tracks_started.to_string(), false),
+        ("Underruns", d.underruns.to_string(), d.underruns > 0),
+        (
+            "Buffering waits",
+            format!("{} ({} ms)", d.buffering_waits, d.buffering_ms),
+            false,
+        ),
+        (
+            "Buffering timeouts",
+            d.buffering_timeouts.to_string(),
+            d.buffering_timeouts > 0,
+        ),
+        (
+            "Decode errors",
+            d.decode_errors.to_string(),
+            d.decode_errors > 0,
+        ),
+        ("First audio (last)", ms(d.last_first_audio_ms), false),
+        ("First audio (avg)", ms(d.avg_first_audio_ms), false),
+        ("First audio (max)", ms(d.max_first_audio_ms), false),
+    ]
+}
+
+fn diagnostics_report(rows: &[(&'static str, String, bool)]) -> String {
+    let mut report = String::from("bae playback diagnostics\n");
+    for (label, value, _) in rows {
+        report.push_str(&format!("{label}: {value}\n"));
+    }
+    report
+}
+
+fn format_session(secs: u64) -> String {
+    let hours = secs / 3600;
+    let minutes = secs % 3600 / 60;
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else {
+        format!("{minutes}m {:02}s", secs % 60)
+    }
+}
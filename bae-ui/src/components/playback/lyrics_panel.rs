@@ -0,0 +1,113 @@
+//! Lyrics panel -- follows along with the playing track
+//!
+//! Synced lyrics highlight the line being sung and keep it scrolled into view.
+//! Reads only position_ms and current_track from the playback store.
+
+use crate::components::icons::XIcon;
+use crate::components::ChromelessButton;
+use crate::display_types::{LyricLine, TrackLyrics};
+use crate::stores::playback::{PlaybackUiState, PlaybackUiStateStoreExt};
+use dioxus::prelude::*;
+
+const ACTIVE_LINE_ID: &str = "lyrics-active-line";
+
+#[component]
+pub fn LyricsPanelView(
+    state: ReadStore<PlaybackUiState>,
+    lyrics: TrackLyrics,
+    on_close: EventHandler<()>,
+) -> Element {
+    let title = state
+        .current_track()
+        .read()
+        .as_ref()
+        .map(|qi| qi.track.title.clone());
+
+    rsx! {
+        div { class: "fixed inset-x-0 mx-auto bottom-24 z-40 w-[32rem] max-h-[60vh] flex flex-col bg-gray-900/95 border border-gray-700 rounded-lg shadow-xl",
+            div { class: "flex items-center justify-between px-4 pt-3 pb-2 border-b border-gray-700",
+                h3 { class: "text-sm font-semibold text-white truncate",
+                    if let Some(ref title) = title {
+                        "Lyrics · {title}"
+                    } else {
+                        "Lyrics"
+                    }
+                }
+                ChromelessButton {
+                    class: Some("p-1 text-gray-400 hover:text-white".to_string()),
+                    aria_label: Some("Close lyrics".to_string()),
+                    onclick: move |_| on_close.call(()),
+                    XIcon { class: "w-3.5 h-3.5" }
+                }
+            }
+            div { class: "flex-1 overflow-y-auto px-6 py-4",
+                match lyrics {
+                    TrackLyrics::Loading => rsx! {
+                        p { class: "text-sm text-gray-400", "Looking for lyrics..." }
+                    },
+                    TrackLyrics::Synced(lines) => rsx! {
+                        SyncedLyrics { state, lines }
+                    },
+                    TrackLyrics::Plain(text) => rsx! {
+                        p { class: "text-base text-gray-200 whitespace-pre-line leading-relaxed", "{text}" }
+                    },
+                    TrackLyrics::Instrumental => rsx! {
+                        p { class: "text-sm text-gray-400", "Instrumental" }
+                    },
+                    TrackLyrics::NotFound => rsx! {
+                        p { class: "text-sm text-gray-400", "No lyrics found for this track." }
+                    },
+                    TrackLyrics::Failed(message) => rsx! {
+                        p { class: "text-sm text-red-400", "Couldn't load lyrics: {message}" }
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Timed lines - re-renders on position changes, scrolls when the line changes
+#[component]
+fn SyncedLyrics(state: ReadStore<PlaybackUiState>, lines: Vec<LyricLine>) -> Element {
+    let position_ms = *state.position_ms().read();
+    let active = active_line(&lines, position_ms);
+
+    // Scroll once per line change, not on every position tick
+    let mut scrolled_to = use_signal(|| None::<usize>);
+    if active != *scrolled_to.peek() {
+        scrolled_to.set(active);
+        if active.is_some() {
+            spawn(async move {
+                let js = format!(
+                    "document.getElementById('{}')?.scrollIntoView({{block: 'center', behavior: 'smooth'}})",
+                    ACTIVE_LINE_ID,
+                );
+                dioxus::document::eval(&js);
+            });
+        }
+    }
+
+    rsx! {
+        div { class: "space-y-2",
+            for (index , line) in lines.iter().enumerate() {
+                p {
+                    key: "{index}",
+                    id: if active == Some(index) { ACTIVE_LINE_ID } else { "" },
+                    class: if active == Some(index) { "text-lg font-semibold text-white transition-colors" } else { "text-lg text-gray-500 transition-colors" },
+                    if line.text.is_empty() {
+                        "♪"
+                    } else {
+                        "{line.text}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Index of the last line that has started
+fn active_line(lines: &[LyricLine], position_ms: u64) -> Option<usize> {
+    lines
+        .partition_point(|l| l.time_ms <= position_ms)
+        .checked_sub(1)
+}
@@ -1,7 +1,11 @@
 //! Playback UI components
 
+mod diagnostics_overlay;
+mod lyrics_panel;
 mod now_playing_bar;
 mod queue_sidebar;
 
+pub use diagnostics_overlay::PlaybackDiagnosticsOverlay;
+pub use lyrics_panel::LyricsPanelView;
 pub use now_playing_bar::NowPlayingBarView;
 pub use queue_sidebar::{QueueSidebarState, QueueSidebarView};
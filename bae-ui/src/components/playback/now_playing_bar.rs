@@ -6,8 +6,8 @@
 
 use crate::components::error_toast::ErrorToast;
 use crate::components::icons::{
-    MenuIcon, PauseIcon, PlayIcon, Repeat1Icon, RepeatIcon, SkipBackIcon, SkipForwardIcon,
-    Volume1Icon, Volume2Icon, VolumeXIcon,
+    MenuIcon, MicVocalIcon, PauseIcon, PlayIcon, Repeat1Icon, RepeatIcon, SkipBackIcon,
+    SkipForwardIcon, Volume1Icon, Volume2Icon, VolumeXIcon, XIcon,
 };
 use crate::components::{Button, ButtonSize, ButtonVariant, ChromelessButton, TextLink};
 use crate::stores::playback::{
@@ -20,6 +20,8 @@ use dioxus::prelude::*;
 pub fn NowPlayingBarView(
     /// Playback state store - sub-components read only what they need
     state: ReadStore<PlaybackUiState>,
+    /// Whether this client can show lyrics
+    lyrics_available: bool,
     // Callbacks - all required
     on_previous: EventHandler<()>,
     on_pause: EventHandler<()>,
@@ -30,6 +32,7 @@ pub fn NowPlayingBarView(
     on_volume_change: EventHandler<f32>,
     on_toggle_mute: EventHandler<()>,
     on_toggle_queue: EventHandler<()>,
+    on_toggle_lyrics: EventHandler<()>,
     on_track_click: EventHandler<String>,
     on_artist_click: EventHandler<String>,
     #[props(default)] on_dismiss_error: Option<EventHandler<()>>,
@@ -55,6 +58,15 @@ pub fn NowPlayingBarView(
 
                 VolumeControl { state, on_volume_change, on_toggle_mute }
 
+                if lyrics_available {
+                    ChromelessButton {
+                        class: Some("p-1 rounded-md text-gray-400 hover:text-white transition-all".to_string()),
+                        aria_label: Some("Lyrics".to_string()),
+                        onclick: move |_| on_toggle_lyrics.call(()),
+                        MicVocalIcon { class: "w-5 h-5" }
+                    }
+                }
+
                 Button {
                     variant: ButtonVariant::Secondary,
                     size: ButtonSize::Medium,
@@ -65,6 +77,8 @@ pub fn NowPlayingBarView(
         }
 
         PlaybackErrorSection { state, on_dismiss_error }
+
+        WarmingUpSection { state, on_dismiss_error }
     }
 }
 
@@ -383,6 +397,38 @@ fn PlaybackErrorSection(
     }
 }
 
+/// Archive restore notice - reads only warming_up
+#[component]
+fn WarmingUpSection(
+    state: ReadStore<PlaybackUiState>,
+    on_dismiss_error: Option<EventHandler<()>>,
+) -> Element {
+    let Some(message) = state.warming_up().read().clone() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div { class: "fixed bottom-20 right-4 bg-amber-600 text-white px-6 py-4 rounded-lg shadow-lg z-50 max-w-md",
+            div { class: "flex items-center justify-between gap-4",
+                div { class: "flex-1",
+                    p { class: "font-medium", "Warming up" }
+                    span { class: "text-sm text-amber-100", "{message}" }
+                }
+                ChromelessButton {
+                    class: Some("text-white hover:text-gray-200".to_string()),
+                    aria_label: Some("Dismiss".to_string()),
+                    onclick: move |_| {
+                        if let Some(handler) = on_dismiss_error {
+                            handler.call(());
+                        }
+                    },
+                    XIcon { class: "w-4 h-4" }
+                }
+            }
+        }
+    }
+}
+
 /// Volume control - speaker icon + slider
 #[component]
 fn VolumeControl(
@@ -3,12 +3,14 @@
 //! ## Reactive State Pattern
 //! Accepts `ReadStore<PlaybackUiState>` and reads fields via lenses.
 //! Each section only re-renders when its specific data changes.
+//!
+//! A History tab lists recently played tracks in place of the queue.
 
-use crate::components::icons::{EllipsisIcon, ImageIcon, PauseIcon, PlayIcon, XIcon};
-use crate::components::utils::format_duration;
+use crate::components::icons::{EllipsisIcon, ImageIcon, PauseIcon, PlayIcon, RadioIcon, XIcon};
+use crate::components::utils::{format_duration, format_relative_time};
 use crate::components::{Button, ButtonSize, ButtonVariant, ChromelessButton};
 use crate::components::{MenuDropdown, MenuItem, Placement};
-use crate::display_types::QueueItem;
+use crate::display_types::{PlayHistoryItem, QueueItem};
 use crate::stores::playback::{PlaybackStatus, PlaybackUiState, PlaybackUiStateStoreExt};
 use crate::stores::ui::{SidebarState, SidebarStateStoreExt};
 use dioxus::prelude::*;
@@ -26,6 +28,10 @@ pub fn QueueSidebarView(
     sidebar: ReadStore<SidebarState>,
     /// Playback state store
     playback: ReadStore<PlaybackUiState>,
+    /// Whether this client can continue the queue with radio picks
+    radio_available: bool,
+    /// Whether this client keeps a play history
+    history_available: bool,
     // Callbacks
     on_close: EventHandler<()>,
     on_clear: EventHandler<()>,
@@ -34,6 +40,13 @@ pub fn QueueSidebarView(
     on_play_index: EventHandler<usize>,
     on_pause: EventHandler<()>,
     on_resume: EventHandler<()>,
+    on_radio_change: EventHandler<bool>,
+    /// Called with true when the History tab is selected, false for the queue
+    on_show_history: EventHandler<bool>,
+    /// Play a track from the history
+    on_history_play: EventHandler<String>,
+    /// Add a track from the history to the end of the queue
+    on_history_add_to_queue: EventHandler<String>,
 ) -> Element {
     // Read is_open via lens - only this check re-runs when visibility changes
     let is_open = *sidebar.is_open().read();
@@ -42,19 +55,41 @@ pub fn QueueSidebarView(
         return rsx! {};
     }
 
+    let show_history = history_available && *sidebar.show_history().read();
+
     rsx! {
         div { class: "w-80 flex-shrink-0 bg-gray-900 border-l border-gray-700 flex flex-col",
             // Header with controls
             div { class: "flex items-center justify-between px-4 py-3 border-b border-gray-700",
-                h2 { class: "text-sm font-semibold text-gray-300 uppercase tracking-wide",
-                    "Queue"
+                if history_available {
+                    div { class: "flex items-center gap-3",
+                        SidebarTab {
+                            label: "Queue",
+                            is_active: !show_history,
+                            onclick: move |_| on_show_history.call(false),
+                        }
+                        SidebarTab {
+                            label: "History",
+                            is_active: show_history,
+                            onclick: move |_| on_show_history.call(true),
+                        }
+                    }
+                } else {
+                    h2 { class: "text-sm font-semibold text-gray-300 uppercase tracking-wide",
+                        "Queue"
+                    }
                 }
                 div { class: "flex items-center gap-2",
-                    Button {
-                        variant: ButtonVariant::Secondary,
-                        size: ButtonSize::Small,
-                        onclick: move |_| on_clear.call(()),
-                        "Clear"
+                    if radio_available && !show_history {
+                        RadioToggle { playback, on_radio_change }
+                    }
+                    if !show_history {
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Small,
+                            onclick: move |_| on_clear.call(()),
+                            "Clear"
+                        }
                     }
                     ChromelessButton {
                         class: Some("text-gray-400 hover:text-white transition-colors".to_string()),
@@ -66,24 +101,78 @@ pub fn QueueSidebarView(
             }
 
             div { class: "flex-1 overflow-y-auto",
-                NowPlayingSection {
-                    playback,
-                    on_track_click,
-                    on_pause,
-                    on_resume,
-                }
+                if show_history {
+                    HistorySection {
+                        playback,
+                        on_track_click,
+                        on_play: on_history_play,
+                        on_add_to_queue: on_history_add_to_queue,
+                    }
+                } else {
+                    NowPlayingSection {
+                        playback,
+                        on_track_click,
+                        on_pause,
+                        on_resume,
+                    }
 
-                UpNextSection {
-                    playback,
-                    on_track_click,
-                    on_remove,
-                    on_play_index,
+                    UpNextSection {
+                        playback,
+                        on_track_click,
+                        on_remove,
+                        on_play_index,
+                    }
                 }
             }
         }
     }
 }
 
+/// Header tab switching between the queue and history
+#[component]
+fn SidebarTab(label: &'static str, is_active: bool, onclick: EventHandler<()>) -> Element {
+    let color = if is_active {
+        "text-gray-200"
+    } else {
+        "text-gray-500 hover:text-gray-300"
+    };
+
+    rsx! {
+        ChromelessButton {
+            class: Some(format!("text-sm font-semibold uppercase tracking-wide transition-colors {color}")),
+            onclick: move |_| onclick.call(()),
+            "{label}"
+        }
+    }
+}
+
+/// Radio toggle - reads only radio_enabled
+#[component]
+fn RadioToggle(
+    playback: ReadStore<PlaybackUiState>,
+    on_radio_change: EventHandler<bool>,
+) -> Element {
+    let enabled = *playback.radio_enabled().read();
+    let (aria_label, color) = if enabled {
+        ("Turn off radio", "text-blue-400 hover:text-blue-300")
+    } else {
+        (
+            "Keep playing similar tracks when the queue ends",
+            "text-gray-500 hover:text-white",
+        )
+    };
+
+    rsx! {
+        ChromelessButton {
+            class: Some(format!("{color} transition-colors")),
+            aria_label: Some(aria_label.to_string()),
+            title: Some(aria_label.to_string()),
+            onclick: move |_| on_radio_change.call(!enabled),
+            RadioIcon { class: "w-4 h-4" }
+        }
+    }
+}
+
 /// Now playing section - reads current_track and status
 #[component]
 fn NowPlayingSection(
@@ -191,7 +280,7 @@ fn NowPlayingItem(
     }
 }
 
-/// Up next section - reads only queue_items
+/// Up next section - reads queue_items and radio_enabled
 #[component]
 fn UpNextSection(
     playback: ReadStore<PlaybackUiState>,
@@ -200,6 +289,7 @@ fn UpNextSection(
     on_play_index: EventHandler<usize>,
 ) -> Element {
     let queue = playback.queue_items().read().clone();
+    let radio_enabled = *playback.radio_enabled().read();
 
     rsx! {
         div {
@@ -219,6 +309,10 @@ fn UpNextSection(
                         on_play_index,
                     }
                 }
+            } else if radio_enabled {
+                div { class: "px-4 py-3 text-gray-500 text-sm",
+                    "Radio will pick similar tracks from your library"
+                }
             } else {
                 div { class: "px-4 py-3 text-gray-500 text-sm", "No tracks queued" }
             }
@@ -320,3 +414,127 @@ fn QueueItemView(
         }
     }
 }
+
+/// History section - reads only history
+#[component]
+fn HistorySection(
+    playback: ReadStore<PlaybackUiState>,
+    on_track_click: EventHandler<String>,
+    on_play: EventHandler<String>,
+    on_add_to_queue: EventHandler<String>,
+) -> Element {
+    let history = playback.history().read().clone();
+
+    rsx! {
+        div { class: "pt-2",
+            if history.is_empty() {
+                div { class: "px-4 py-3 text-gray-500 text-sm", "Nothing played yet" }
+            } else {
+                for entry in history.iter() {
+                    HistoryItemView {
+                        key: "{entry.id}",
+                        entry: entry.clone(),
+                        on_track_click,
+                        on_play,
+                        on_add_to_queue,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// History row - play again or send the track back to the queue
+#[component]
+fn HistoryItemView(
+    entry: PlayHistoryItem,
+    on_track_click: EventHandler<String>,
+    on_play: EventHandler<String>,
+    on_add_to_queue: EventHandler<String>,
+) -> Element {
+    let mut show_menu = use_signal(|| false);
+    let is_open: ReadSignal<bool> = show_menu.into();
+    let anchor_id = format!("history-menu-{}", entry.id);
+    let menu_is_open = is_open();
+    let item = entry.item.clone();
+    let track_id = item.track.id.clone();
+
+    rsx! {
+        div { class: "flex items-center gap-3 py-2 px-3 mx-2 rounded-lg hover:bg-hover transition-colors group",
+            ChromelessButton {
+                class: Some(
+                    "w-6 h-6 rounded-full border border-blue-400 opacity-0 group-hover:opacity-100 transition-opacity flex items-center justify-center text-blue-400 hover:text-blue-300 hover:bg-blue-400/10"
+                        .to_string(),
+                ),
+                aria_label: Some("Play".to_string()),
+                onclick: {
+                    let track_id = track_id.clone();
+                    move |_| on_play.call(track_id.clone())
+                },
+                PlayIcon { class: "w-3 h-3" }
+            }
+
+            div { class: "w-10 h-10 flex-shrink-0 bg-gray-700 rounded overflow-clip",
+                if let Some(ref url) = item.cover_url {
+                    img {
+                        src: "{url}",
+                        alt: "Album cover",
+                        class: "w-full h-full object-cover",
+                    }
+                } else {
+                    div { class: "w-full h-full flex items-center justify-center text-gray-500",
+                        ImageIcon { class: "w-5 h-5" }
+                    }
+                }
+            }
+
+            div { class: "flex-1 min-w-0",
+                ChromelessButton {
+                    class: Some(
+                        "block w-full font-medium text-white group-hover:text-accent-soft transition-colors truncate text-left"
+                            .to_string(),
+                    ),
+                    onclick: {
+                        let track_id = track_id.clone();
+                        move |_| on_track_click.call(track_id.clone())
+                    },
+                    "{item.track.title}"
+                }
+                div { class: "text-sm text-gray-400 truncate",
+                    "{item.album_title} · {format_relative_time(&entry.played_at)}"
+                }
+            }
+
+            ChromelessButton {
+                id: Some(anchor_id.clone()),
+                class: Some(
+                    if menu_is_open {
+                        "px-2 py-1 rounded-md text-gray-400 hover:text-white hover:bg-hover transition-all"
+                            .to_string()
+                    } else {
+                        "px-2 py-1 rounded-md text-gray-400 hover:text-white hover:bg-hover opacity-0 group-hover:opacity-100 transition-all"
+                            .to_string()
+                    },
+                ),
+                aria_label: Some("Track menu".to_string()),
+                onclick: move |_| show_menu.set(!show_menu()),
+                EllipsisIcon { class: "w-4 h-4" }
+            }
+
+            MenuDropdown {
+                anchor_id: anchor_id.clone(),
+                is_open,
+                on_close: move |_| show_menu.set(false),
+                placement: Placement::BottomEnd,
+
+                MenuItem {
+                    onclick: move |_| {
+                        show_menu.set(false);
+                        on_add_to_queue.call(track_id.clone());
+                    },
+                    "Add to Queue"
+                }
+            }
+        }
+    }
+}
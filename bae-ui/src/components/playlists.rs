@@ -0,0 +1,350 @@
+//! Playlists view - the playlist list beside the selected playlist's tracks
+
+use crate::components::helpers::{ConfirmDialogView, ErrorDisplay, LoadingSpinner};
+use crate::components::icons::{
+    ArrowDownIcon, ArrowUpIcon, PencilIcon, PlayIcon, PlusIcon, TrashIcon, XIcon,
+};
+use crate::components::utils::format_duration;
+use crate::components::{
+    Button, ButtonSize, ButtonVariant, ChromelessButton, TextInput, TextInputSize, TextInputType,
+};
+use crate::display_types::PlaylistEntry;
+use crate::stores::playlists::{PlaylistsState, PlaylistsStateStoreExt};
+use dioxus::prelude::*;
+
+/// Playlists view component
+///
+/// Followed libraries and the web UI show playlists without `editable`, which
+/// hides creating, importing and every edit.
+#[component]
+pub fn PlaylistsView(
+    state: ReadStore<PlaylistsState>,
+    editable: bool,
+    /// Called with the playlist ID
+    on_select: EventHandler<String>,
+    /// Called with the new playlist's name
+    on_create: EventHandler<String>,
+    /// Called with (playlist ID, new name)
+    on_rename: EventHandler<(String, String)>,
+    on_delete: EventHandler<String>,
+    on_import_m3u: EventHandler<()>,
+    on_export_m3u: EventHandler<String>,
+    /// Called with the index of the entry to start playing from
+    on_play: EventHandler<usize>,
+    /// Called with the entry index
+    on_remove_entry: EventHandler<usize>,
+    /// Called with (from index, to index)
+    on_move_entry: EventHandler<(usize, usize)>,
+    on_album_click: EventHandler<String>,
+) -> Element {
+    let loading = *state.loading().read();
+    let error = state.error().read().clone();
+    let playlists = state.playlists().read().clone();
+    let selected_id = state.selected_id().read().clone();
+    let import_summary = state.import_summary().read().clone();
+    let selected = selected_id
+        .as_ref()
+        .and_then(|id| playlists.iter().find(|p| &p.id == id))
+        .cloned();
+
+    let mut new_name = use_signal(String::new);
+    let is_new_name_blank = new_name.read().trim().is_empty();
+
+    rsx! {
+        div { class: "flex-grow flex min-h-0",
+            div { class: "w-64 flex-shrink-0 border-r border-gray-800 overflow-y-auto p-4 space-y-1",
+                h2 { class: "text-sm font-semibold text-gray-400 uppercase tracking-wide mb-3",
+                    "Playlists"
+                }
+                if editable {
+                    div { class: "flex gap-2 mb-3",
+                        TextInput {
+                            value: new_name(),
+                            on_input: move |v| new_name.set(v),
+                            size: TextInputSize::Small,
+                            input_type: TextInputType::Text,
+                            placeholder: "New playlist",
+                        }
+                        ChromelessButton {
+                            class: Some("p-1.5 text-gray-400 hover:text-white disabled:opacity-50".to_string()),
+                            aria_label: Some("Create playlist".to_string()),
+                            disabled: is_new_name_blank,
+                            onclick: move |_| {
+                                let name = new_name.read().trim().to_string();
+                                new_name.set(String::new());
+                                on_create.call(name);
+                            },
+                            PlusIcon { class: "w-4 h-4" }
+                        }
+                    }
+                }
+                for playlist in playlists.iter() {
+                    {
+                        let is_selected = selected_id.as_deref() == Some(playlist.id.as_str());
+                        let id = playlist.id.clone();
+                        rsx! {
+                            button {
+                                key: "{playlist.id}",
+                                class: "w-full flex items-center justify-between px-3 py-2 rounded-lg text-left text-sm transition-colors",
+                                class: if is_selected { "bg-hover text-white" } else { "text-gray-300 hover:bg-hover" },
+                                onclick: move |_| on_select.call(id.clone()),
+                                span { class: "truncate", "{playlist.name}" }
+                                span { class: "text-xs text-gray-500 ml-2", "{playlist.track_count}" }
+                            }
+                        }
+                    }
+                }
+                if editable {
+                    div { class: "pt-3",
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            size: ButtonSize::Small,
+                            onclick: move |_| on_import_m3u.call(()),
+                            "Import M3U…"
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex-1 min-w-0 overflow-y-auto p-6",
+                if let Some(summary) = import_summary {
+                    p { class: "mb-4 text-sm text-gray-400", "{summary}" }
+                }
+                if let Some(err) = error {
+                    ErrorDisplay { message: err }
+                } else if loading {
+                    LoadingSpinner { message: "Loading playlists...".to_string() }
+                } else if let Some(playlist) = selected {
+                    PlaylistHeader {
+                        id: playlist.id.clone(),
+                        name: playlist.name.clone(),
+                        has_entries: playlist.track_count > 0,
+                        editable,
+                        on_rename,
+                        on_delete,
+                        on_export_m3u,
+                        on_play,
+                    }
+                    PlaylistEntries {
+                        entries: state.entries().read().clone(),
+                        editable,
+                        on_play,
+                        on_remove_entry,
+                        on_move_entry,
+                        on_album_click,
+                    }
+                } else if playlists.is_empty() {
+                    div { class: "text-center py-16 text-gray-400",
+                        if editable {
+                            "Create a playlist or import an M3U file to get started."
+                        } else {
+                            "No playlists yet."
+                        }
+                    }
+                } else {
+                    div { class: "text-center py-16 text-gray-400", "Select a playlist." }
+                }
+            }
+        }
+    }
+}
+
+/// Name, rename and the playlist-wide actions
+#[component]
+fn PlaylistHeader(
+    id: String,
+    name: String,
+    has_entries: bool,
+    editable: bool,
+    on_rename: EventHandler<(String, String)>,
+    on_delete: EventHandler<String>,
+    on_export_m3u: EventHandler<String>,
+    on_play: EventHandler<usize>,
+) -> Element {
+    let mut renaming = use_signal(|| None::<String>);
+    let mut show_delete_confirm = use_signal(|| false);
+    let is_delete_confirm_open: ReadSignal<bool> = show_delete_confirm.into();
+
+    rsx! {
+        div { class: "flex items-center gap-3 mb-6",
+            if let Some(draft) = renaming() {
+                div { class: "flex-1 flex items-center gap-2",
+                    TextInput {
+                        value: draft.clone(),
+                        on_input: move |v| renaming.set(Some(v)),
+                        size: TextInputSize::Medium,
+                        input_type: TextInputType::Text,
+                        autofocus: true,
+                    }
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Small,
+                        disabled: draft.trim().is_empty(),
+                        onclick: {
+                            let id = id.clone();
+                            move |_| {
+                                if let Some(draft) = renaming() {
+                                    on_rename.call((id.clone(), draft.trim().to_string()));
+                                }
+                                renaming.set(None);
+                            }
+                        },
+                        "Save"
+                    }
+                    ChromelessButton {
+                        class: Some("p-1.5 text-gray-400 hover:text-white".to_string()),
+                        aria_label: Some("Cancel rename".to_string()),
+                        onclick: move |_| renaming.set(None),
+                        XIcon { class: "w-4 h-4" }
+                    }
+                }
+            } else {
+                h1 { class: "flex-1 text-3xl font-bold text-white truncate", "{name}" }
+                if editable {
+                    ChromelessButton {
+                        class: Some("p-1.5 text-gray-400 hover:text-white".to_string()),
+                        aria_label: Some("Rename playlist".to_string()),
+                        onclick: {
+                            let name = name.clone();
+                            move |_| renaming.set(Some(name.clone()))
+                        },
+                        PencilIcon { class: "w-4 h-4" }
+                    }
+                }
+            }
+            Button {
+                variant: ButtonVariant::Primary,
+                size: ButtonSize::Small,
+                disabled: !has_entries,
+                onclick: move |_| on_play.call(0),
+                PlayIcon { class: "w-3 h-3" }
+                "Play"
+            }
+            if editable {
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    size: ButtonSize::Small,
+                    onclick: {
+                        let id = id.clone();
+                        move |_| on_export_m3u.call(id.clone())
+                    },
+                    "Export M3U…"
+                }
+                ChromelessButton {
+                    class: Some("p-1.5 text-gray-400 hover:text-red-400".to_string()),
+                    aria_label: Some("Delete playlist".to_string()),
+                    onclick: move |_| show_delete_confirm.set(true),
+                    TrashIcon { class: "w-4 h-4" }
+                }
+            }
+        }
+
+        ConfirmDialogView {
+            is_open: is_delete_confirm_open,
+            title: "Delete playlist?".to_string(),
+            message: format!("\"{}\" will be removed. Its tracks stay in the library.", name),
+            confirm_label: "Delete".to_string(),
+            on_confirm: move |_| {
+                show_delete_confirm.set(false);
+                on_delete.call(id.clone());
+            },
+            on_cancel: move |_| show_delete_confirm.set(false),
+        }
+    }
+}
+
+/// The playlist's tracks, with move and remove controls when editable
+#[component]
+fn PlaylistEntries(
+    entries: Vec<PlaylistEntry>,
+    editable: bool,
+    on_play: EventHandler<usize>,
+    on_remove_entry: EventHandler<usize>,
+    on_move_entry: EventHandler<(usize, usize)>,
+    on_album_click: EventHandler<String>,
+) -> Element {
+    if entries.is_empty() {
+        return rsx! {
+            div { class: "text-center py-8 text-gray-400",
+                "This playlist is empty. Add tracks from an album's track menu."
+            }
+        };
+    }
+
+    let last = entries.len() - 1;
+
+    rsx! {
+        div { class: "space-y-1",
+            for (index , entry) in entries.iter().enumerate() {
+                div {
+                    // Tracks can appear more than once, so the position is the key
+                    key: "{index}-{entry.track.id}",
+                    class: "flex items-center py-2 px-4 rounded-lg group hover:bg-hover transition-colors",
+                    ChromelessButton {
+                        class: Some(
+                            "w-6 h-6 rounded-full border border-blue-400 opacity-0 group-hover:opacity-100 transition-opacity flex items-center justify-center text-blue-400 hover:text-blue-300 hover:bg-blue-400/10"
+                                .to_string(),
+                        ),
+                        aria_label: Some("Play".to_string()),
+                        onclick: move |_| on_play.call(index),
+                        PlayIcon { class: "w-3 h-3" }
+                    }
+                    div { class: "w-10 text-right text-sm font-mono text-gray-500", "{index + 1}." }
+                    if let Some(ref cover_url) = entry.cover_url {
+                        img {
+                            class: "w-8 h-8 rounded object-cover ml-4",
+                            src: "{cover_url}",
+                        }
+                    } else {
+                        div { class: "w-8 h-8 rounded bg-gray-800 ml-4" }
+                    }
+                    div { class: "flex-1 min-w-0 ml-3",
+                        h3 { class: "font-medium text-white truncate", "{entry.track.title}" }
+                        p { class: "text-sm text-gray-400 truncate",
+                            "{entry.artist_name} · "
+                            span {
+                                class: "hover:text-white hover:underline cursor-pointer",
+                                onclick: {
+                                    let album_id = entry.album_id.clone();
+                                    move |_| on_album_click.call(album_id.clone())
+                                },
+                                "{entry.album_title}"
+                            }
+                        }
+                    }
+                    if editable {
+                        div { class: "flex items-center gap-1 ml-4 opacity-0 group-hover:opacity-100 transition-opacity",
+                            ChromelessButton {
+                                class: Some("p-1 text-gray-400 hover:text-white disabled:opacity-30".to_string()),
+                                aria_label: Some("Move up".to_string()),
+                                disabled: index == 0,
+                                onclick: move |_| on_move_entry.call((index, index.saturating_sub(1))),
+                                ArrowUpIcon { class: "w-3.5 h-3.5" }
+                            }
+                            ChromelessButton {
+                                class: Some("p-1 text-gray-400 hover:text-white disabled:opacity-30".to_string()),
+                                aria_label: Some("Move down".to_string()),
+                                disabled: index == last,
+                                onclick: move |_| on_move_entry.call((index, index + 1)),
+                                ArrowDownIcon { class: "w-3.5 h-3.5" }
+                            }
+                            ChromelessButton {
+                                class: Some("p-1 text-gray-400 hover:text-red-400".to_string()),
+                                aria_label: Some("Remove from playlist".to_string()),
+                                onclick: move |_| on_remove_entry.call(index),
+                                XIcon { class: "w-3.5 h-3.5" }
+                            }
+                        }
+                    }
+                    div { class: "text-sm font-mono text-gray-400 ml-4",
+                        if let Some(duration_ms) = entry.track.duration_ms {
+                            {format_duration(duration_ms)}
+                        } else {
+                            "—:—"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::components::{
     TextInputType,
 };
 use crate::stores::config::CloudProvider;
+use crate::stores::ConnectionCheckStep;
 use dioxus::prelude::*;
 
 use super::sync::SyncBucketConfig;
@@ -44,6 +45,10 @@ pub fn CloudProviderPicker(
     s3_endpoint: String,
     s3_access_key: String,
     s3_secret_key: String,
+    /// Whether the edited S3 settings are being tried.
+    s3_checking_connection: bool,
+    /// Result of the last connection check. None until one has run.
+    s3_connection_check: Option<Vec<ConnectionCheckStep>>,
 
     // --- bae cloud form state ---
     bae_cloud_is_editing: bool,
@@ -57,6 +62,8 @@ pub fn CloudProviderPicker(
     on_sign_in: EventHandler<CloudProvider>,
     on_disconnect: EventHandler<()>,
     on_use_icloud: EventHandler<()>,
+    on_use_lan: EventHandler<()>,
+    on_choose_folder: EventHandler<()>,
     // S3 callbacks
     on_s3_edit_start: EventHandler<()>,
     on_s3_cancel: EventHandler<()>,
@@ -66,6 +73,7 @@ pub fn CloudProviderPicker(
     on_s3_endpoint_change: EventHandler<String>,
     on_s3_access_key_change: EventHandler<String>,
     on_s3_secret_key_change: EventHandler<String>,
+    on_s3_check_connection: EventHandler<SyncBucketConfig>,
     // bae cloud callbacks
     on_bae_cloud_mode_change: EventHandler<BaeCloudAuthMode>,
     on_bae_cloud_email_change: EventHandler<String>,
@@ -78,6 +86,14 @@ pub fn CloudProviderPicker(
         && !s3_access_key.is_empty()
         && !s3_secret_key.is_empty();
 
+    let s3_config = SyncBucketConfig {
+        bucket: s3_bucket.to_string(),
+        region: s3_region.to_string(),
+        endpoint: s3_endpoint.to_string(),
+        access_key: s3_access_key.to_string(),
+        secret_key: s3_secret_key.to_string(),
+    };
+
     let bae_cloud_has_required = !bae_cloud_email.is_empty()
         && !bae_cloud_password.is_empty()
         && (bae_cloud_mode == BaeCloudAuthMode::LogIn || !bae_cloud_username.is_empty());
@@ -104,6 +120,8 @@ pub fn CloudProviderPicker(
                         let connected = option.connected_account.clone();
                         let is_s3 = option.provider == CloudProvider::S3;
                         let is_icloud = option.provider == CloudProvider::ICloud;
+                        let is_lan = option.provider == CloudProvider::Lan;
+                        let is_folder = option.provider == CloudProvider::Folder;
                         let is_bae_cloud = option.provider == CloudProvider::BaeCloud;
                         let needs_oauth = matches!(
                             option.provider,
@@ -166,6 +184,26 @@ pub fn CloudProviderPicker(
                                                         },
                                                         "Use iCloud Drive"
                                                     }
+                                                } else if is_lan {
+                                                    Button {
+                                                        variant: ButtonVariant::Primary,
+                                                        size: ButtonSize::Small,
+                                                        onclick: move |evt: Event<MouseData>| {
+                                                            evt.stop_propagation();
+                                                            on_use_lan.call(());
+                                                        },
+                                                        "Use Local Network"
+                                                    }
+                                                } else if is_folder {
+                                                    Button {
+                                                        variant: ButtonVariant::Primary,
+                                                        size: ButtonSize::Small,
+                                                        onclick: move |evt: Event<MouseData>| {
+                                                            evt.stop_propagation();
+                                                            on_choose_folder.call(());
+                                                        },
+                                                        "Choose Folder"
+                                                    }
                                                 } else if needs_oauth {
                                                     Button {
                                                         variant: ButtonVariant::Primary,
@@ -338,13 +376,7 @@ pub fn CloudProviderPicker(
                                                         size: ButtonSize::Small,
                                                         disabled: !s3_has_required,
                                                         onclick: {
-                                                            let config = SyncBucketConfig {
-                                                                bucket: s3_bucket.to_string(),
-                                                                region: s3_region.to_string(),
-                                                                endpoint: s3_endpoint.to_string(),
-                                                                access_key: s3_access_key.to_string(),
-                                                                secret_key: s3_secret_key.to_string(),
-                                                            };
+                                                            let config = s3_config.clone();
                                                             move |evt: Event<MouseData>| {
                                                                 evt.stop_propagation();
                                                                 on_s3_save.call(config.clone());
@@ -352,6 +384,19 @@ pub fn CloudProviderPicker(
                                                         },
                                                         "Save"
                                                     }
+                                                    Button {
+                                                        variant: ButtonVariant::Secondary,
+                                                        size: ButtonSize::Small,
+                                                        disabled: !s3_has_required || s3_checking_connection,
+                                                        onclick: {
+                                                            let config = s3_config.clone();
+                                                            move |evt: Event<MouseData>| {
+                                                                evt.stop_propagation();
+                                                                on_s3_check_connection.call(config.clone());
+                                                            }
+                                                        },
+                                                        "Test connection"
+                                                    }
                                                     Button {
                                                         variant: ButtonVariant::Secondary,
                                                         size: ButtonSize::Small,
@@ -362,6 +407,26 @@ pub fn CloudProviderPicker(
                                                         "Cancel"
                                                     }
                                                 }
+                                                if s3_checking_connection {
+                                                    div { class: "flex items-center gap-2 text-sm text-gray-400",
+                                                        LoadingSpinner {}
+                                                        "Testing connection..."
+                                                    }
+                                                } else if let Some(ref steps) = s3_connection_check {
+                                                    div { class: "text-xs space-y-0.5",
+                                                        for step in steps.iter() {
+                                                            div {
+                                                                key: "{step.label}",
+                                                                class: if step.error.is_some() { "text-red-400" } else { "text-green-400" },
+                                                                if let Some(ref err) = step.error {
+                                                                    "{step.label} failed after {step.latency_ms} ms: {err}"
+                                                                } else {
+                                                                    "{step.label} OK in {step.latency_ms} ms"
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                }
                                             }
                                         }
                                         if is_selected && is_s3 && !s3_is_editing && connected.is_some() {
@@ -0,0 +1,200 @@
+//! Duplicate releases view
+//!
+//! Lists releases that were imported more than once. For each group the user
+//! picks the release to keep, and merging deletes the others after moving
+//! their listening history over.
+
+use crate::components::helpers::ConfirmDialogView;
+use crate::components::{Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection};
+use dioxus::prelude::*;
+
+/// A release of a duplicate group
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateReleaseInfo {
+    pub release_id: String,
+    pub album_title: String,
+    pub artist_name: String,
+    /// Release name, year and format, as far as they're known
+    pub details: String,
+    /// "Local", "Cloud", "Unmanaged" or a combination
+    pub storage: String,
+    pub track_count: usize,
+    pub play_count: i64,
+}
+
+/// Releases that look like copies of each other
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateGroupInfo {
+    /// True when the audio files are identical, false when the albums only
+    /// share a MusicBrainz release group
+    pub identical_audio: bool,
+    pub releases: Vec<DuplicateReleaseInfo>,
+    /// Release ID picked to keep
+    pub keep_release_id: String,
+}
+
+/// Duplicate releases section
+#[component]
+pub fn DuplicatesView(
+    groups: Vec<DuplicateGroupInfo>,
+    is_scanning: bool,
+    /// Index of the group being merged
+    merging: Option<usize>,
+    error: Option<String>,
+    on_scan: EventHandler<()>,
+    /// Called with (group index, release ID to keep)
+    on_keep: EventHandler<(usize, String)>,
+    /// Called with the group index, once confirmed
+    on_merge: EventHandler<usize>,
+) -> Element {
+    let mut confirm_group = use_signal(|| None::<usize>);
+    let is_confirm_open_memo = use_memo(move || confirm_group().is_some());
+    let is_confirm_open: ReadSignal<bool> = is_confirm_open_memo.into();
+    let confirm_message = confirm_group()
+        .and_then(|index| groups.get(index))
+        .map(|group| {
+            let deleted = group.releases.len() - 1;
+            let plays: i64 = group
+                .releases
+                .iter()
+                .filter(|r| r.release_id != group.keep_release_id)
+                .map(|r| r.play_count)
+                .sum();
+            format!(
+                "{} other {} and {} files will be deleted. Their {} plays, ratings and playlist entries move to the release you keep.",
+                deleted,
+                if deleted == 1 { "release" } else { "releases" },
+                if deleted == 1 { "its" } else { "their" },
+                plays,
+            )
+        })
+        .unwrap_or_default();
+
+    rsx! {
+        SettingsSection {
+            SettingsCard {
+                div { class: "flex items-center justify-between mb-2",
+                    h3 { class: "text-lg font-medium text-white", "Duplicates" }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Small,
+                        disabled: is_scanning || merging.is_some(),
+                        loading: is_scanning,
+                        onclick: move |_| on_scan.call(()),
+                        "Scan again"
+                    }
+                }
+                p { class: "text-sm text-gray-400 mb-4",
+                    "Releases imported more than once, matched by MusicBrainz release group or identical audio. Albums linked as versions aren't listed."
+                }
+
+                if let Some(ref error) = error {
+                    div { class: "mb-3 text-sm text-red-400", "{error}" }
+                }
+
+                if groups.is_empty() {
+                    p { class: "text-sm text-gray-500 italic",
+                        if is_scanning {
+                            "Looking for duplicates..."
+                        } else {
+                            "No duplicates found"
+                        }
+                    }
+                } else {
+                    div { class: "space-y-4",
+                        for (index , group) in groups.iter().enumerate() {
+                            DuplicateGroupRow {
+                                key: "{group.releases[0].release_id}",
+                                index,
+                                group: group.clone(),
+                                disabled: merging.is_some(),
+                                is_merging: merging == Some(index),
+                                on_keep,
+                                on_merge: move |index| confirm_group.set(Some(index)),
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        ConfirmDialogView {
+            is_open: is_confirm_open,
+            title: "Merge duplicates?".to_string(),
+            message: confirm_message,
+            confirm_label: "Merge".to_string(),
+            on_confirm: move |_| {
+                if let Some(index) = confirm_group() {
+                    on_merge.call(index);
+                }
+                confirm_group.set(None);
+            },
+            on_cancel: move |_| confirm_group.set(None),
+        }
+    }
+}
+
+#[component]
+fn DuplicateGroupRow(
+    index: usize,
+    group: DuplicateGroupInfo,
+    disabled: bool,
+    is_merging: bool,
+    on_keep: EventHandler<(usize, String)>,
+    on_merge: EventHandler<usize>,
+) -> Element {
+    let reason = if group.identical_audio {
+        "Identical audio"
+    } else {
+        "Same release group"
+    };
+    let group_name = format!("duplicate-group-{}", index);
+
+    rsx! {
+        div { class: "border border-gray-700 rounded-lg p-3",
+            div { class: "flex items-center justify-between mb-2",
+                span { class: "text-xs text-gray-500 uppercase tracking-wide", "{reason}" }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Small,
+                    disabled,
+                    loading: is_merging,
+                    onclick: move |_| on_merge.call(index),
+                    "Keep selected, delete others"
+                }
+            }
+            div { class: "space-y-1",
+                for release in group.releases.iter() {
+                    {
+                        let release_id = release.release_id.clone();
+                        rsx! {
+                            label {
+                                key: "{release.release_id}",
+                                class: "flex items-center gap-3 py-1 text-sm cursor-pointer",
+                                input {
+                                    r#type: "radio",
+                                    name: "{group_name}",
+                                    class: "w-4 h-4 bg-gray-700 border-gray-600 text-indigo-600 focus:ring-indigo-500",
+                                    checked: release.release_id == group.keep_release_id,
+                                    disabled,
+                                    onchange: move |_| on_keep.call((index, release_id.clone())),
+                                }
+                                div { class: "min-w-0 flex-1",
+                                    div { class: "text-white truncate",
+                                        "{release.artist_name} – {release.album_title}"
+                                    }
+                                    div { class: "text-gray-500 truncate",
+                                        if !release.details.is_empty() {
+                                            "{release.details} · "
+                                        }
+                                        "{release.storage} · {release.track_count} tracks · {release.play_count} plays"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Genre cleanup view
+//!
+//! Lists the library's genres with suggested merges for spelling variants,
+//! and lets the user tick any genres and merge them under one name.
+
+use crate::components::{
+    Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection, TextInput, TextInputSize,
+    TextInputType,
+};
+use dioxus::prelude::*;
+
+/// A genre in the library
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenreInfo {
+    pub id: String,
+    pub name: String,
+    pub album_count: usize,
+}
+
+/// Genres that look like the same one
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenreMergeInfo {
+    /// Name the genres would be merged into
+    pub into: String,
+    pub genre_ids: Vec<String>,
+    /// Current names of the genres, for display
+    pub names: Vec<String>,
+}
+
+/// Genre cleanup section
+#[component]
+pub fn GenreCleanupView(
+    /// All genres, alphabetically
+    genres: Vec<GenreInfo>,
+    suggestions: Vec<GenreMergeInfo>,
+    /// IDs of the genres ticked for a manual merge
+    selected: Vec<String>,
+    /// Name the ticked genres merge into
+    merge_name: String,
+    is_merging: bool,
+    error: Option<String>,
+    on_toggle: EventHandler<String>,
+    on_merge_name_change: EventHandler<String>,
+    on_merge_selected: EventHandler<()>,
+    on_apply_suggestion: EventHandler<GenreMergeInfo>,
+) -> Element {
+    let can_merge = selected.len() >= 2 && !merge_name.trim().is_empty() && !is_merging;
+
+    rsx! {
+        SettingsSection {
+            SettingsCard {
+                h3 { class: "text-lg font-medium text-white mb-2", "Genres" }
+                p { class: "text-sm text-gray-400 mb-4",
+                    "Genres are tidied up on import. Merge the ones that still overlap so browsing by genre finds everything."
+                }
+
+                if let Some(ref error) = error {
+                    div { class: "mb-3 text-sm text-red-400", "{error}" }
+                }
+
+                if !suggestions.is_empty() {
+                    h4 { class: "text-sm font-medium text-gray-300 mb-2", "Suggested merges" }
+                    div { class: "space-y-2 mb-6",
+                        for suggestion in suggestions {
+                            SuggestionRow {
+                                key: "{suggestion.into}",
+                                suggestion,
+                                disabled: is_merging,
+                                on_apply: on_apply_suggestion,
+                            }
+                        }
+                    }
+                }
+
+                if genres.is_empty() {
+                    p { class: "text-sm text-gray-500 italic", "No genres in the library yet" }
+                } else {
+                    div { class: "max-h-80 overflow-y-auto space-y-1 mb-4",
+                        for genre in genres {
+                            GenreRow {
+                                key: "{genre.id}",
+                                checked: selected.contains(&genre.id),
+                                genre,
+                                on_toggle,
+                            }
+                        }
+                    }
+                    div { class: "flex items-center gap-3",
+                        div { class: "flex-1",
+                            TextInput {
+                                value: merge_name.clone(),
+                                on_input: move |v| on_merge_name_change.call(v),
+                                size: TextInputSize::Medium,
+                                input_type: TextInputType::Text,
+                                placeholder: "Merge into...",
+                            }
+                        }
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            size: ButtonSize::Medium,
+                            disabled: !can_merge,
+                            loading: is_merging,
+                            onclick: move |_| on_merge_selected.call(()),
+                            "Merge {selected.len()} genres"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SuggestionRow(
+    suggestion: GenreMergeInfo,
+    disabled: bool,
+    on_apply: EventHandler<GenreMergeInfo>,
+) -> Element {
+    let names = suggestion.names.join(", ");
+    let to_apply = suggestion.clone();
+
+    rsx! {
+        div { class: "flex items-center justify-between gap-4 text-sm",
+            div { class: "min-w-0 truncate",
+                span { class: "text-gray-400", "{names}" }
+                span { class: "text-gray-500 mx-2", "→" }
+                span { class: "text-white", "{suggestion.into}" }
+            }
+            Button {
+                variant: ButtonVariant::Secondary,
+                size: ButtonSize::Small,
+                disabled,
+                onclick: move |_| on_apply.call(to_apply.clone()),
+                "Merge"
+            }
+        }
+    }
+}
+
+#[component]
+fn GenreRow(genre: GenreInfo, checked: bool, on_toggle: EventHandler<String>) -> Element {
+    let id = genre.id.clone();
+
+    rsx! {
+        label { class: "flex items-center gap-3 py-1 text-sm cursor-pointer",
+            input {
+                r#type: "checkbox",
+                class: "w-4 h-4 rounded bg-gray-700 border-gray-600 text-indigo-600 focus:ring-indigo-500",
+                checked,
+                onchange: move |_| on_toggle.call(id.clone()),
+            }
+            span { class: "text-white", "{genre.name}" }
+            span { class: "text-gray-500", "{genre.album_count} albums" }
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Import section view
+
+use crate::components::{Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection};
+use dioxus::prelude::*;
+
+/// Import section view
+#[component]
+pub fn ImportSectionView(
+    /// Saved ignore patterns (display mode)
+    ignore_patterns: Vec<String>,
+    /// Saved minimum audio duration in seconds (display mode)
+    min_audio_duration_secs: Option<u32>,
+    /// Whether currently in edit mode
+    is_editing: bool,
+    /// Temporary values while editing (patterns are one per line)
+    edit_patterns: String,
+    edit_min_duration: String,
+    has_changes: bool,
+    /// Callbacks
+    on_edit_start: EventHandler<()>,
+    on_cancel: EventHandler<()>,
+    on_save: EventHandler<()>,
+    on_patterns_change: EventHandler<String>,
+    on_min_duration_change: EventHandler<String>,
+    // Network share settings
+    /// Saved share address (display mode)
+    network_share_url: Option<String>,
+    network_share_username: Option<String>,
+    network_share_password_set: bool,
+    is_editing_share: bool,
+    edit_share_url: String,
+    edit_share_username: String,
+    edit_share_password: String,
+    has_share_changes: bool,
+    share_save_error: Option<String>,
+    on_share_edit_start: EventHandler<()>,
+    on_share_cancel: EventHandler<()>,
+    on_share_save: EventHandler<()>,
+    on_share_url_change: EventHandler<String>,
+    on_share_username_change: EventHandler<String>,
+    on_share_password_change: EventHandler<String>,
+    /// Replace low-resolution covers without asking
+    auto_upgrade_covers: bool,
+    on_auto_upgrade_covers_change: EventHandler<bool>,
+) -> Element {
+    let invalid_duration =
+        !edit_min_duration.trim().is_empty() && edit_min_duration.trim().parse::<u32>().is_err();
+
+    rsx! {
+        SettingsSection {
+            h2 { class: "text-xl font-semibold text-white mb-6", "Import" }
+
+            SettingsCard {
+                div { class: "flex items-center justify-between mb-4",
+                    h3 { class: "text-lg font-medium text-white", "Folder Scanning" }
+                    if !is_editing {
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Small,
+                            onclick: move |_| on_edit_start.call(()),
+                            "Edit"
+                        }
+                    }
+                }
+
+                if is_editing {
+                    div { class: "space-y-4",
+                        div {
+                            label { class: "block text-sm text-gray-400 mb-1", "Ignore patterns" }
+                            textarea {
+                                class: "w-full h-32 px-3 py-2 bg-gray-700 border border-gray-600 rounded-lg text-white font-mono text-sm focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                                placeholder: "*.m3u\n@eaDir\nsample*",
+                                value: "{edit_patterns}",
+                                oninput: move |e| on_patterns_change.call(e.value()),
+                            }
+                            p { class: "text-xs text-gray-500 mt-1",
+                                "One pattern per line. Matched against file and folder names; "
+                                "* matches any run of characters and ? matches one."
+                            }
+                        }
+                        div { class: "flex items-center gap-4",
+                            label { class: "text-sm text-gray-400 w-48", "Skip audio shorter than:" }
+                            input {
+                                r#type: "number",
+                                class: "w-24 px-3 py-2 bg-gray-700 border border-gray-600 rounded-lg text-white focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                                min: "0",
+                                placeholder: "Off",
+                                value: "{edit_min_duration}",
+                                oninput: move |e| on_min_duration_change.call(e.value()),
+                            }
+                            span { class: "text-sm text-gray-400", "seconds" }
+                        }
+                        if invalid_duration {
+                            div { class: "text-sm text-red-400", "Duration must be a whole number of seconds" }
+                        }
+                    }
+
+                    div { class: "flex gap-3 mt-4",
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            size: ButtonSize::Medium,
+                            disabled: !has_changes || invalid_duration,
+                            onclick: move |_| on_save.call(()),
+                            "Save Changes"
+                        }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Medium,
+                            onclick: move |_| on_cancel.call(()),
+                            "Cancel"
+                        }
+                    }
+                } else {
+                    div { class: "space-y-2 text-sm",
+                        div { class: "flex items-start gap-2",
+                            span { class: "text-gray-400", "Ignore patterns:" }
+                            if ignore_patterns.is_empty() {
+                                span { class: "text-gray-500 italic", "None" }
+                            } else {
+                                span { class: "text-white font-mono", {ignore_patterns.join(", ")} }
+                            }
+                        }
+                        div { class: "flex items-center gap-2",
+                            span { class: "text-gray-400", "Minimum audio length:" }
+                            if let Some(secs) = min_audio_duration_secs {
+                                span { class: "text-white", "{secs} seconds" }
+                            } else {
+                                span { class: "text-gray-500 italic", "Off" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            SettingsCard {
+                div { class: "flex items-center justify-between mb-4",
+                    h3 { class: "text-lg font-medium text-white", "Network Share" }
+                    if !is_editing_share {
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Small,
+                            onclick: move |_| on_share_edit_start.call(()),
+                            "Edit"
+                        }
+                    }
+                }
+
+                if is_editing_share {
+                    div { class: "space-y-4",
+                        div {
+                            label { class: "block text-sm text-gray-400 mb-1", "Address" }
+                            input {
+                                r#type: "text",
+                                class: "w-full px-3 py-2 bg-gray-700 border border-gray-600 rounded-lg text-white font-mono text-sm focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                                placeholder: "smb://nas.local/Music",
+                                value: "{edit_share_url}",
+                                oninput: move |e| on_share_url_change.call(e.value()),
+                            }
+                            p { class: "text-xs text-gray-500 mt-1",
+                                "An smb:// or nfs:// folder to import from. Leave empty to remove."
+                            }
+                        }
+                        div { class: "flex items-center gap-4",
+                            label { class: "text-sm text-gray-400 w-32", "Username:" }
+                            input {
+                                r#type: "text",
+                                class: "flex-1 px-3 py-2 bg-gray-700 border border-gray-600 rounded-lg text-white focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                                placeholder: "Guest",
+                                value: "{edit_share_username}",
+                                oninput: move |e| on_share_username_change.call(e.value()),
+                            }
+                        }
+                        div { class: "flex items-center gap-4",
+                            label { class: "text-sm text-gray-400 w-32", "Password:" }
+                            input {
+                                r#type: "password",
+                                class: "flex-1 px-3 py-2 bg-gray-700 border border-gray-600 rounded-lg text-white focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                                placeholder: if network_share_password_set { "Leave blank to keep current" } else { "None" },
+                                value: "{edit_share_password}",
+                                oninput: move |e| on_share_password_change.call(e.value()),
+                            }
+                        }
+                    }
+
+                    if let Some(error) = share_save_error {
+                        div { class: "p-3 bg-red-900/30 border border-red-700 rounded-lg text-sm text-red-300 mt-4",
+                            "{error}"
+                        }
+                    }
+
+                    div { class: "flex gap-3 mt-4",
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            size: ButtonSize::Medium,
+                            disabled: !has_share_changes,
+                            onclick: move |_| on_share_save.call(()),
+                            "Save Changes"
+                        }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Medium,
+                            onclick: move |_| on_share_cancel.call(()),
+                            "Cancel"
+                        }
+                    }
+                } else {
+                    div { class: "space-y-2 text-sm",
+                        div { class: "flex items-center gap-2",
+                            span { class: "text-gray-400", "Address:" }
+                            if let Some(ref url) = network_share_url {
+                                span { class: "text-white font-mono", "{url}" }
+                            } else {
+                                span { class: "text-gray-500 italic", "Not configured" }
+                            }
+                        }
+                        if network_share_url.is_some() {
+                            div { class: "flex items-center gap-2",
+                                span { class: "text-gray-400", "Username:" }
+                                if let Some(ref username) = network_share_username {
+                                    span { class: "text-white", "{username}" }
+                                } else {
+                                    span { class: "text-gray-500 italic", "Guest" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            SettingsCard {
+                h3 { class: "text-lg font-medium text-white mb-4", "Cover Art" }
+                div { class: "flex items-center gap-3",
+                    input {
+                        r#type: "checkbox",
+                        class: "w-4 h-4 rounded bg-gray-700 border-gray-600 text-indigo-600 focus:ring-indigo-500",
+                        checked: auto_upgrade_covers,
+                        onchange: move |e| on_auto_upgrade_covers_change.call(e.checked()),
+                    }
+                    label { class: "text-sm text-gray-300",
+                        "Replace low-resolution covers automatically"
+                    }
+                }
+                p { class: "text-xs text-gray-500 mt-3",
+                    "Covers smaller than 600 pixels are looked up on Cover Art Archive, "
+                    "Discogs and iTunes. When this is off, larger covers are offered on "
+                    "the album page instead."
+                }
+            }
+        }
+    }
+}
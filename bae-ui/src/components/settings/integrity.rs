@@ -0,0 +1,159 @@
+//! Library integrity view
+//!
+//! Runs the check that reads back every file of the library, and lists the
+//! albums whose files are missing or damaged, including those the background
+//! storage scrub found.
+
+use crate::components::{Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection};
+use crate::display_types::BrokenAlbumInfo;
+use dioxus::prelude::*;
+
+/// Counts from the last finished check
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntegritySummary {
+    pub releases_checked: usize,
+    pub files_checked: usize,
+    /// Cloud releases left out because no cloud home is configured
+    pub releases_skipped: usize,
+}
+
+/// Library integrity section
+#[component]
+pub fn IntegrityView(
+    /// (releases done, release count) while a check runs
+    progress: Option<(usize, usize)>,
+    summary: Option<IntegritySummary>,
+    albums: Vec<BrokenAlbumInfo>,
+    /// When the background storage scrub last verified files, for display
+    last_scrub: Option<String>,
+    /// Albums the background storage scrub found broken files in
+    scrub_albums: Vec<BrokenAlbumInfo>,
+    error: Option<String>,
+    on_check: EventHandler<()>,
+    /// Called with the release ID
+    on_reimport: EventHandler<String>,
+    /// Called with the album ID
+    on_album_click: EventHandler<String>,
+) -> Element {
+    let is_checking = progress.is_some();
+
+    rsx! {
+        SettingsSection {
+            SettingsCard {
+                div { class: "flex items-center justify-between mb-2",
+                    h3 { class: "text-lg font-medium text-white", "Integrity" }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Small,
+                        disabled: is_checking,
+                        loading: is_checking,
+                        onclick: move |_| on_check.call(()),
+                        "Check files"
+                    }
+                }
+                p { class: "text-sm text-gray-400 mb-4",
+                    "Reads back every file from local storage, the cloud home and unmanaged folders, and makes sure it decrypts and matches what was imported. This can take a while for a large library."
+                }
+                if let Some(ref last_scrub) = last_scrub {
+                    p { class: "text-sm text-gray-500 mb-4",
+                        "A few files of every release are also checked in the background each month, most recently on {last_scrub}."
+                    }
+                }
+
+                if let Some(ref error) = error {
+                    div { class: "mb-3 text-sm text-red-400", "{error}" }
+                }
+
+                if let Some((done, total)) = progress {
+                    p { class: "text-sm text-gray-400", "Checked {done} of {total} releases..." }
+                } else if let Some(ref summary) = summary {
+                    p { class: "text-sm text-gray-400 mb-3",
+                        "Checked {summary.files_checked} files in {summary.releases_checked} releases."
+                        if summary.releases_skipped > 0 {
+                            " {summary.releases_skipped} cloud releases were skipped because no cloud home is configured."
+                        }
+                    }
+                    if albums.is_empty() {
+                        p { class: "text-sm text-gray-500 italic", "All files are intact" }
+                    } else {
+                        div { class: "space-y-4",
+                            for album in albums {
+                                BrokenAlbumRow {
+                                    key: "{album.album_id}",
+                                    album,
+                                    on_reimport,
+                                    on_album_click,
+                                }
+                            }
+                        }
+                    }
+                } else if !scrub_albums.is_empty() {
+                    p { class: "text-sm text-red-400 mb-3",
+                        "The background check found broken files. Check files to look at the whole library."
+                    }
+                    div { class: "space-y-4",
+                        for album in scrub_albums {
+                            BrokenAlbumRow {
+                                key: "{album.album_id}",
+                                album,
+                                on_reimport,
+                                on_album_click,
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn BrokenAlbumRow(
+    album: BrokenAlbumInfo,
+    on_reimport: EventHandler<String>,
+    on_album_click: EventHandler<String>,
+) -> Element {
+    let album_id = album.album_id.clone();
+
+    rsx! {
+        div { class: "border border-gray-700 rounded-lg p-3",
+            button {
+                class: "text-sm text-white hover:underline text-left",
+                onclick: move |_| on_album_click.call(album_id.clone()),
+                "{album.artist_name} – {album.album_title}"
+            }
+            for release in album.releases {
+                div { key: "{release.release_id}", class: "mt-2",
+                    div { class: "flex items-center justify-between gap-4",
+                        span { class: "text-xs text-gray-500",
+                            if let Some(ref name) = release.release_name {
+                                "{name} · "
+                            }
+                            "{release.broken_files.len()} of {release.file_count} files broken"
+                        }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Small,
+                            onclick: {
+                                let release_id = release.release_id.clone();
+                                move |_| on_reimport.call(release_id.clone())
+                            },
+                            "Re-import"
+                        }
+                    }
+                    ul { class: "mt-1 space-y-0.5",
+                        for (index , file) in release.broken_files.iter().enumerate() {
+                            li {
+                                key: "{index}",
+                                class: "text-xs text-gray-400 truncate",
+                                title: "{file.detail}",
+                                span { class: "text-red-400", "{file.problem}" }
+                                " · {file.location} · {file.filename}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -31,7 +31,38 @@ pub fn LibrarySectionView(
     show_link_device_button: bool,
     on_link_device: EventHandler<()>,
     device_link_qr_svg: Option<String>,
+    /// Short authentication string of the shown link, as (emoji, name)
+    device_link_sas: Vec<(String, String)>,
     on_close_device_link: EventHandler<()>,
+    /// The phone showed the same emoji
+    on_confirm_device_link: EventHandler<()>,
+    // Bundle export of the active library
+    export_include_audio: bool,
+    is_exporting: bool,
+    /// Where the last export was saved
+    exported_path: Option<String>,
+    export_error: Option<String>,
+    on_export_include_audio_change: EventHandler<bool>,
+    on_export: EventHandler<()>,
+    // Master key rotation of the active library
+    can_rotate_key: bool,
+    /// Re-encryption after a rotation, as (releases done, release count)
+    key_rotation_progress: Option<(usize, usize)>,
+    key_rotation_error: Option<String>,
+    on_rotate_key: EventHandler<()>,
+    // Security key unlock of the active library on this device
+    can_use_security_key: bool,
+    security_key_enabled: bool,
+    /// Waiting on taps to set the security key up
+    security_key_busy: bool,
+    security_key_error: Option<String>,
+    on_enable_security_key: EventHandler<()>,
+    on_disable_security_key: EventHandler<()>,
+    // Encryption of library.db on this device
+    can_encrypt_database: bool,
+    database_encrypted: bool,
+    database_encryption_error: Option<String>,
+    on_set_database_encryption: EventHandler<bool>,
 ) -> Element {
     let mut renaming_path = use_signal(|| None::<String>);
     let mut rename_value = use_signal(String::new);
@@ -39,6 +70,9 @@ pub fn LibrarySectionView(
     let mut confirming_switch = use_signal(|| None::<String>);
     let mut confirming_delete = use_signal(|| None::<String>);
     let mut confirming_unfollow = use_signal(|| None::<String>);
+    let mut confirming_rotate = use_signal(|| false);
+    let mut confirming_security_key = use_signal(|| false);
+    let mut confirming_database_encryption = use_signal(|| false);
 
     let mut start_rename = move |lib: &LibraryInfo| {
         renaming_path.set(Some(lib.path.clone()));
@@ -70,10 +104,36 @@ pub fn LibrarySectionView(
                     p { class: "text-sm text-gray-700 text-center",
                         "Scan with bae on your phone to link this library"
                     }
-                    button {
-                        class: "px-4 py-2 text-sm bg-gray-200 hover:bg-gray-300 text-gray-800 rounded-md transition-colors",
-                        onclick: move |_| on_close_device_link.call(()),
-                        "Close"
+                    if !device_link_sas.is_empty() {
+                        div { class: "flex flex-col items-center gap-2 pt-2 border-t border-gray-200 w-full",
+                            p { class: "text-xs text-gray-500 text-center",
+                                "Once it's scanned, check that your phone shows the same emoji in the same order."
+                            }
+                            div { class: "flex gap-2",
+                                for (i , (emoji , name)) in device_link_sas.iter().enumerate() {
+                                    div {
+                                        key: "{i}",
+                                        class: "flex flex-col items-center w-10",
+                                        span { class: "text-2xl", "{emoji}" }
+                                        span { class: "text-[10px] text-gray-500 text-center", "{name}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    div { class: "flex gap-2",
+                        if !device_link_sas.is_empty() {
+                            button {
+                                class: "px-4 py-2 text-sm bg-indigo-600 hover:bg-indigo-500 text-white rounded-md transition-colors",
+                                onclick: move |_| on_confirm_device_link.call(()),
+                                "They Match"
+                            }
+                        }
+                        button {
+                            class: "px-4 py-2 text-sm bg-gray-200 hover:bg-gray-300 text-gray-800 rounded-md transition-colors",
+                            onclick: move |_| on_close_device_link.call(()),
+                            "Close"
+                        }
                     }
                 }
             }
@@ -246,6 +306,195 @@ pub fn LibrarySectionView(
                     }
                 }
             }
+            // Bundle export
+            div { class: "mt-6 p-4 rounded-lg border border-border-subtle",
+                div { class: "flex items-center justify-between gap-4",
+                    div {
+                        h3 { class: "text-sm font-medium text-white", "Export bundle" }
+                        p { class: "text-xs text-gray-500 mt-1",
+                            "Save the active library as a single file, then restore it from the welcome screen on another machine."
+                        }
+                    }
+                    button {
+                        class: "px-3 py-1.5 text-sm bg-gray-700 hover:bg-gray-600 text-white rounded-md transition-colors flex-shrink-0 disabled:opacity-50",
+                        disabled: is_exporting,
+                        onclick: move |_| on_export.call(()),
+                        if is_exporting {
+                            "Exporting..."
+                        } else {
+                            "Export..."
+                        }
+                    }
+                }
+                label { class: "flex items-center gap-2 mt-3 text-sm text-gray-300",
+                    input {
+                        r#type: "checkbox",
+                        class: "w-4 h-4 rounded bg-gray-700 border-gray-600 text-indigo-600 focus:ring-indigo-500",
+                        checked: export_include_audio,
+                        disabled: is_exporting,
+                        onchange: move |e| on_export_include_audio_change.call(e.checked()),
+                    }
+                    "Include audio stored in the library"
+                }
+                if let Some(path) = &exported_path {
+                    p { class: "text-xs text-green-400 mt-2 truncate", "Saved to {path}" }
+                }
+                if let Some(error) = &export_error {
+                    p { class: "text-xs text-red-400 mt-2", "{error}" }
+                }
+            }
+            // Master key rotation
+            if can_rotate_key || key_rotation_progress.is_some() {
+                div { class: "mt-6 p-4 rounded-lg border border-border-subtle",
+                    div { class: "flex items-center justify-between gap-4",
+                        div {
+                            h3 { class: "text-sm font-medium text-white", "Encryption key" }
+                            p { class: "text-xs text-gray-500 mt-1",
+                                "Switch to a new key and re-encrypt the library's files with it. Everything stays playable while that runs in the background."
+                            }
+                        }
+                        if let Some((done, total)) = key_rotation_progress {
+                            span { class: "text-xs text-gray-400 flex-shrink-0",
+                                if total == 0 {
+                                    "Re-encrypting..."
+                                } else {
+                                    "Re-encrypting {done} of {total} releases..."
+                                }
+                            }
+                        } else if confirming_rotate() {
+                            div { class: "flex items-center gap-2 flex-shrink-0",
+                                span { class: "text-xs text-gray-400", "App will restart. Rotate?" }
+                                button {
+                                    class: "px-2 py-1 text-xs bg-indigo-600 hover:bg-indigo-500 text-white rounded transition-colors",
+                                    onclick: move |_| {
+                                        confirming_rotate.set(false);
+                                        on_rotate_key.call(());
+                                    },
+                                    "Yes"
+                                }
+                                button {
+                                    class: "px-2 py-1 text-xs text-gray-400 hover:text-white transition-colors",
+                                    onclick: move |_| confirming_rotate.set(false),
+                                    "No"
+                                }
+                            }
+                        } else {
+                            button {
+                                class: "px-3 py-1.5 text-sm bg-gray-700 hover:bg-gray-600 text-white rounded-md transition-colors flex-shrink-0",
+                                onclick: move |_| confirming_rotate.set(true),
+                                "Rotate Key"
+                            }
+                        }
+                    }
+                    if let Some(error) = &key_rotation_error {
+                        p { class: "text-xs text-red-400 mt-2", "{error}" }
+                    }
+                }
+            }
+            // Security key unlock
+            if can_use_security_key {
+                div { class: "mt-6 p-4 rounded-lg border border-border-subtle",
+                    div { class: "flex items-center justify-between gap-4",
+                        div {
+                            h3 { class: "text-sm font-medium text-white", "Security key" }
+                            p { class: "text-xs text-gray-500 mt-1",
+                                if security_key_enabled {
+                                    "Unlocking the library on this device takes a tap on your security key. Its key is not kept in the keyring."
+                                } else {
+                                    "Require a tap on a FIDO2 security key, such as a YubiKey, to unlock the library on this device instead of keeping its key in the keyring."
+                                }
+                            }
+                        }
+                        if security_key_busy {
+                            span { class: "text-xs text-gray-400 flex-shrink-0",
+                                "Touch your security key..."
+                            }
+                        } else if confirming_security_key() {
+                            div { class: "flex items-center gap-2 flex-shrink-0",
+                                span { class: "text-xs text-gray-400", "App will restart. Continue?" }
+                                button {
+                                    class: "px-2 py-1 text-xs bg-indigo-600 hover:bg-indigo-500 text-white rounded transition-colors",
+                                    onclick: move |_| {
+                                        confirming_security_key.set(false);
+                                        if security_key_enabled {
+                                            on_disable_security_key.call(());
+                                        } else {
+                                            on_enable_security_key.call(());
+                                        }
+                                    },
+                                    "Yes"
+                                }
+                                button {
+                                    class: "px-2 py-1 text-xs text-gray-400 hover:text-white transition-colors",
+                                    onclick: move |_| confirming_security_key.set(false),
+                                    "No"
+                                }
+                            }
+                        } else {
+                            button {
+                                class: "px-3 py-1.5 text-sm bg-gray-700 hover:bg-gray-600 text-white rounded-md transition-colors flex-shrink-0",
+                                onclick: move |_| confirming_security_key.set(true),
+                                if security_key_enabled {
+                                    "Remove"
+                                } else {
+                                    "Set Up"
+                                }
+                            }
+                        }
+                    }
+                    if let Some(error) = &security_key_error {
+                        p { class: "text-xs text-red-400 mt-2", "{error}" }
+                    }
+                }
+            }
+            // Database encryption at rest
+            if can_encrypt_database {
+                div { class: "mt-6 p-4 rounded-lg border border-border-subtle",
+                    div { class: "flex items-center justify-between gap-4",
+                        div {
+                            h3 { class: "text-sm font-medium text-white", "Encrypt database" }
+                            p { class: "text-xs text-gray-500 mt-1",
+                                if database_encrypted {
+                                    "The library database on this device is encrypted with the library's key."
+                                } else {
+                                    "Encrypt the library database on this device, so artists, albums and listening history aren't readable without the library's key."
+                                }
+                            }
+                        }
+                        if confirming_database_encryption() {
+                            div { class: "flex items-center gap-2 flex-shrink-0",
+                                span { class: "text-xs text-gray-400", "App will restart. Continue?" }
+                                button {
+                                    class: "px-2 py-1 text-xs bg-indigo-600 hover:bg-indigo-500 text-white rounded transition-colors",
+                                    onclick: move |_| {
+                                        confirming_database_encryption.set(false);
+                                        on_set_database_encryption.call(!database_encrypted);
+                                    },
+                                    "Yes"
+                                }
+                                button {
+                                    class: "px-2 py-1 text-xs text-gray-400 hover:text-white transition-colors",
+                                    onclick: move |_| confirming_database_encryption.set(false),
+                                    "No"
+                                }
+                            }
+                        } else {
+                            button {
+                                class: "px-3 py-1.5 text-sm bg-gray-700 hover:bg-gray-600 text-white rounded-md transition-colors flex-shrink-0",
+                                onclick: move |_| confirming_database_encryption.set(true),
+                                if database_encrypted {
+                                    "Decrypt"
+                                } else {
+                                    "Encrypt"
+                                }
+                            }
+                        }
+                    }
+                    if let Some(error) = &database_encryption_error {
+                        p { class: "text-xs text-red-400 mt-2", "{error}" }
+                    }
+                }
+            }
             // Followed libraries section
             if !followed_libraries.is_empty() {
                 div { class: "mt-6",
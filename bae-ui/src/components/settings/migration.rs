@@ -0,0 +1,210 @@
+//! Server migration view
+
+use crate::components::button::ButtonVariant;
+use crate::components::segmented_control::{Segment, SegmentedControl};
+use crate::components::{
+    Button, ButtonSize, SettingsCard, SettingsSection, TextInput, TextInputSize, TextInputType,
+};
+use dioxus::prelude::*;
+
+/// Music server to migrate listening history from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MigrationServer {
+    #[default]
+    Navidrome,
+    Plex,
+}
+
+impl MigrationServer {
+    fn value(&self) -> &'static str {
+        match self {
+            MigrationServer::Navidrome => "navidrome",
+            MigrationServer::Plex => "plex",
+        }
+    }
+
+    fn from_value(value: &str) -> MigrationServer {
+        match value {
+            "plex" => MigrationServer::Plex,
+            _ => MigrationServer::Navidrome,
+        }
+    }
+}
+
+/// What a finished migration changed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MigrationResult {
+    pub albums_matched: usize,
+    pub albums_unmatched: usize,
+    pub tracks_updated: usize,
+    pub playlists_imported: usize,
+    pub playlist_entries_skipped: usize,
+}
+
+/// Progress of a migration run
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum MigrationStatus {
+    #[default]
+    Idle,
+    /// Reading the remote server's library
+    Fetching,
+    /// Writing play counts, ratings and playlists into the library
+    Merging,
+    Done(MigrationResult),
+    Failed(String),
+}
+
+/// Form for pulling play counts, ratings and playlists from a Navidrome or
+/// Plex server
+#[component]
+pub fn MigrationSectionView(
+    server: MigrationServer,
+    server_url: String,
+    /// Navidrome credentials
+    username: String,
+    password: String,
+    /// Plex authentication token
+    plex_token: String,
+    status: MigrationStatus,
+    on_server_change: EventHandler<MigrationServer>,
+    on_server_url_change: EventHandler<String>,
+    on_username_change: EventHandler<String>,
+    on_password_change: EventHandler<String>,
+    on_plex_token_change: EventHandler<String>,
+    on_migrate: EventHandler<()>,
+) -> Element {
+    let is_running = matches!(status, MigrationStatus::Fetching | MigrationStatus::Merging);
+    let has_credentials = match server {
+        MigrationServer::Navidrome => !username.trim().is_empty() && !password.is_empty(),
+        MigrationServer::Plex => !plex_token.trim().is_empty(),
+    };
+    let can_migrate = !server_url.trim().is_empty() && has_credentials && !is_running;
+
+    let segments = vec![
+        Segment::new("Navidrome", "navidrome"),
+        Segment::new("Plex", "plex"),
+    ];
+
+    rsx! {
+        SettingsSection {
+            SettingsCard {
+                div { class: "space-y-4",
+                    div {
+                        h3 { class: "text-lg font-medium text-white", "Migrate From Another Server" }
+                        p { class: "text-sm text-gray-400 mt-1",
+                            "Copies play counts, ratings and playlists onto albums already in your library. "
+                            "Music files aren't transferred; import them first."
+                        }
+                    }
+
+                    SegmentedControl {
+                        segments,
+                        selected: server.value().to_string(),
+                        selected_variant: ButtonVariant::Secondary,
+                        on_select: move |value: &str| on_server_change.call(MigrationServer::from_value(value)),
+                    }
+
+                    div {
+                        label { class: "block text-sm font-medium text-gray-400 mb-2", "Server URL" }
+                        TextInput {
+                            value: server_url,
+                            on_input: move |v| on_server_url_change.call(v),
+                            size: TextInputSize::Medium,
+                            input_type: TextInputType::Text,
+                            placeholder: match server {
+                                MigrationServer::Navidrome => "http://localhost:4533",
+                                MigrationServer::Plex => "http://localhost:32400",
+                            },
+                            disabled: is_running,
+                        }
+                    }
+
+                    match server {
+                        MigrationServer::Navidrome => rsx! {
+                            div { class: "grid grid-cols-2 gap-3",
+                                div {
+                                    label { class: "block text-sm font-medium text-gray-400 mb-2", "Username" }
+                                    TextInput {
+                                        value: username,
+                                        on_input: move |v| on_username_change.call(v),
+                                        size: TextInputSize::Medium,
+                                        input_type: TextInputType::Text,
+                                        disabled: is_running,
+                                    }
+                                }
+                                div {
+                                    label { class: "block text-sm font-medium text-gray-400 mb-2", "Password" }
+                                    TextInput {
+                                        value: password,
+                                        on_input: move |v| on_password_change.call(v),
+                                        size: TextInputSize::Medium,
+                                        input_type: TextInputType::Password,
+                                        disabled: is_running,
+                                    }
+                                }
+                            }
+                        },
+                        MigrationServer::Plex => rsx! {
+                            div {
+                                label { class: "block text-sm font-medium text-gray-400 mb-2", "Plex Token" }
+                                TextInput {
+                                    value: plex_token,
+                                    on_input: move |v| on_plex_token_change.call(v),
+                                    size: TextInputSize::Medium,
+                                    input_type: TextInputType::Password,
+                                    monospace: true,
+                                    disabled: is_running,
+                                }
+                            }
+                        },
+                    }
+
+                    match status {
+                        MigrationStatus::Idle => rsx! {},
+                        MigrationStatus::Fetching => rsx! {
+                            p { class: "text-sm text-gray-400", "Reading library from server..." }
+                        },
+                        MigrationStatus::Merging => rsx! {
+                            p { class: "text-sm text-gray-400", "Merging listening history..." }
+                        },
+                        MigrationStatus::Done(result) => rsx! {
+                            div { class: "p-3 bg-green-900/30 border border-green-700 rounded-lg text-sm text-green-300 space-y-1",
+                                p {
+                                    "Matched {result.albums_matched} albums and updated {result.tracks_updated} tracks."
+                                }
+                                if result.albums_unmatched > 0 {
+                                    p { "{result.albums_unmatched} albums weren't found in your library." }
+                                }
+                                p {
+                                    "Imported {result.playlists_imported} playlists"
+                                    if result.playlist_entries_skipped > 0 {
+                                        " ({result.playlist_entries_skipped} entries skipped)"
+                                    }
+                                    "."
+                                }
+                            }
+                        },
+                        MigrationStatus::Failed(error) => rsx! {
+                            div { class: "p-3 bg-red-900/30 border border-red-700 rounded-lg text-sm text-red-300",
+                                "{error}"
+                            }
+                        },
+                    }
+
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Medium,
+                        disabled: !can_migrate,
+                        loading: is_running,
+                        onclick: move |_| on_migrate.call(()),
+                        if is_running {
+                            "Migrating..."
+                        } else {
+                            "Migrate"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -7,11 +7,22 @@ mod bittorrent;
 mod card;
 mod cloud_provider;
 mod discogs;
+mod duplicates;
+mod fanart;
 mod follow_library;
+mod genres;
+mod import;
+mod integrity;
 mod join_library;
 mod library;
+mod migration;
+mod musicbrainz;
+mod party;
+mod playback;
+mod spotify_report;
 mod subsonic;
 mod sync;
+mod sync_conflicts;
 mod view;
 
 pub use about::AboutSectionView;
@@ -19,9 +30,19 @@ pub use bittorrent::{BitTorrentSectionView, BitTorrentSettings};
 pub use card::{SettingsCard, SettingsSection};
 pub use cloud_provider::{BaeCloudAuthMode, CloudProviderOption, CloudProviderPicker};
 pub use discogs::DiscogsSectionView;
+pub use duplicates::{DuplicateGroupInfo, DuplicateReleaseInfo, DuplicatesView};
+pub use fanart::FanartSectionView;
 pub use follow_library::{FollowLibraryView, FollowSyncStatus};
+pub use genres::{GenreCleanupView, GenreInfo, GenreMergeInfo};
+pub use import::ImportSectionView;
+pub use integrity::{IntegritySummary, IntegrityView};
 pub use join_library::{JoinLibraryView, JoinStatus};
 pub use library::{LibraryInfo, LibrarySectionView};
-pub use subsonic::SubsonicSectionView;
+pub use migration::{MigrationResult, MigrationSectionView, MigrationServer, MigrationStatus};
+pub use musicbrainz::{MusicBrainzCollectionInfo, MusicBrainzSectionView};
+pub use party::PartySectionView;
+pub use playback::PlaybackSectionView;
+pub use spotify_report::{SpotifyAlbumSearch, SpotifyReportAlbum, SpotifyReportView};
+pub use subsonic::{AppPasswordInfo, SubsonicSectionView};
 pub use sync::{SyncBucketConfig, SyncSectionView};
 pub use view::{SettingsTab, SettingsView};
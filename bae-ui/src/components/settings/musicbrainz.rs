@@ -0,0 +1,139 @@
+//! MusicBrainz account section view
+//!
+//! Links a MusicBrainz account and picks one of its release collections to
+//! keep in sync with the library.
+
+use crate::components::{
+    Button, ButtonSize, ButtonVariant, Select, SelectOption, SettingsCard, SettingsSection,
+};
+use dioxus::prelude::*;
+
+/// A release collection on the linked account
+#[derive(Clone, Debug, PartialEq)]
+pub struct MusicBrainzCollectionInfo {
+    pub id: String,
+    pub name: String,
+    pub release_count: u64,
+}
+
+/// MusicBrainz account and collection sync
+#[component]
+pub fn MusicBrainzSectionView(
+    /// Linked account, None when not linked
+    username: Option<String>,
+    /// Collections on the linked account (empty until loaded)
+    collections: Vec<MusicBrainzCollectionInfo>,
+    /// Collection kept in sync, if one is chosen
+    collection_id: Option<String>,
+    /// Whether the browser authorization is in progress
+    is_linking: bool,
+    is_syncing: bool,
+    /// Outcome of the last sync (e.g. "Added 3, removed 1")
+    sync_status: Option<String>,
+    error: Option<String>,
+    on_link: EventHandler<()>,
+    on_unlink: EventHandler<()>,
+    on_select_collection: EventHandler<String>,
+    on_sync_now: EventHandler<()>,
+) -> Element {
+    rsx! {
+        SettingsSection {
+            h2 { class: "text-xl font-semibold text-white mb-6", "MusicBrainz" }
+            SettingsCard {
+                div { class: "space-y-4",
+                    if let Some(ref username) = username {
+                        div { class: "flex items-center justify-between",
+                            div {
+                                h3 { class: "text-lg font-medium text-white", "Account" }
+                                p { class: "text-sm text-gray-400 mt-1", "Linked as {username}" }
+                            }
+                            Button {
+                                variant: ButtonVariant::Secondary,
+                                size: ButtonSize::Small,
+                                onclick: move |_| on_unlink.call(()),
+                                "Unlink"
+                            }
+                        }
+
+                        div {
+                            label { class: "block text-sm font-medium text-gray-400 mb-2",
+                                "Collection"
+                            }
+                            if collections.is_empty() {
+                                p { class: "text-sm text-gray-500",
+                                    "No release collections on this account yet. Create one on musicbrainz.org."
+                                }
+                            } else {
+                                Select {
+                                    value: collection_id.clone().unwrap_or_default(),
+                                    onchange: move |id| on_select_collection.call(id),
+                                    if collection_id.is_none() {
+                                        SelectOption { value: "", label: "Choose a collection" }
+                                    }
+                                    for collection in collections.iter() {
+                                        SelectOption {
+                                            key: "{collection.id}",
+                                            value: collection.id.clone(),
+                                            label: format!("{} ({} releases)", collection.name, collection.release_count),
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if collection_id.is_some() {
+                            div { class: "flex items-center justify-between",
+                                p { class: "text-sm text-gray-400",
+                                    if let Some(ref status) = sync_status {
+                                        "{status}"
+                                    } else {
+                                        "Imports are added and deletions removed automatically."
+                                    }
+                                }
+                                Button {
+                                    variant: ButtonVariant::Secondary,
+                                    size: ButtonSize::Small,
+                                    disabled: is_syncing,
+                                    loading: is_syncing,
+                                    onclick: move |_| on_sync_now.call(()),
+                                    if is_syncing {
+                                        "Syncing..."
+                                    } else {
+                                        "Sync now"
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        div { class: "flex items-center justify-between",
+                            div {
+                                h3 { class: "text-lg font-medium text-white", "Collection sync" }
+                                p { class: "text-sm text-gray-400 mt-1",
+                                    "Link your MusicBrainz account to keep one of your collections in step with your library."
+                                }
+                            }
+                            Button {
+                                variant: ButtonVariant::Primary,
+                                size: ButtonSize::Small,
+                                disabled: is_linking,
+                                loading: is_linking,
+                                onclick: move |_| on_link.call(()),
+                                if is_linking {
+                                    "Waiting for browser..."
+                                } else {
+                                    "Link account"
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some(ref error) = error {
+                        div { class: "p-3 bg-red-900/30 border border-red-700 rounded-lg text-sm text-red-300",
+                            "{error}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
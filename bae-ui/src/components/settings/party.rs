@@ -0,0 +1,158 @@
+//! Party section view
+
+use crate::components::{Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection};
+use crate::stores::{PartyInfo, PartyRequestInfo};
+use dioxus::prelude::*;
+
+/// Party mode: let guests request tracks from their phones
+#[component]
+pub fn PartySectionView(
+    /// Party routes are served by the Subsonic server
+    server_enabled: bool,
+    /// Whether the server only listens on this machine
+    server_local_only: bool,
+    /// The running party, if any
+    party: Option<PartyInfo>,
+    /// Link guests open to join, when it can be determined
+    join_url: Option<String>,
+    /// Approval setting used when starting a party
+    require_approval: bool,
+    on_require_approval_change: EventHandler<bool>,
+    on_start: EventHandler<()>,
+    on_stop: EventHandler<()>,
+    on_approve: EventHandler<String>,
+    on_reject: EventHandler<String>,
+) -> Element {
+    let approval_checked = party
+        .as_ref()
+        .map(|p| p.require_approval)
+        .unwrap_or(require_approval);
+
+    rsx! {
+        SettingsSection {
+            h2 { class: "text-xl font-semibold text-white mb-6", "Party Mode" }
+
+            SettingsCard {
+                div { class: "flex items-center justify-between mb-4",
+                    h3 { class: "text-lg font-medium text-white", "Guest Requests" }
+                    if party.is_some() {
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Small,
+                            onclick: move |_| on_stop.call(()),
+                            "End Party"
+                        }
+                    } else {
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            size: ButtonSize::Small,
+                            disabled: !server_enabled,
+                            onclick: move |_| on_start.call(()),
+                            "Start Party"
+                        }
+                    }
+                }
+
+                p { class: "text-sm text-gray-400 mb-4",
+                    "Guests join with a code, search your library, and add tracks to the queue. They can't see or change anything else."
+                }
+
+                if !server_enabled {
+                    div { class: "p-3 bg-yellow-900/20 border border-yellow-700/50 rounded-lg text-sm text-yellow-200/80",
+                        "Enable the Subsonic server to host a party."
+                    }
+                } else if server_local_only {
+                    div { class: "p-3 mb-4 bg-yellow-900/20 border border-yellow-700/50 rounded-lg text-sm text-yellow-200/80",
+                        "The server only listens on this computer. Set its bind address to 0.0.0.0 so phones on your network can reach it."
+                    }
+                }
+
+                div { class: "flex items-center gap-3",
+                    input {
+                        r#type: "checkbox",
+                        class: "w-4 h-4 rounded bg-gray-700 border-gray-600 text-indigo-600 focus:ring-indigo-500",
+                        checked: approval_checked,
+                        onchange: move |e| on_require_approval_change.call(e.checked()),
+                    }
+                    label { class: "text-sm text-gray-300", "Approve each request before it's queued" }
+                }
+
+                if let Some(ref party) = party {
+                    div { class: "mt-4 space-y-2 text-sm",
+                        div { class: "flex items-center gap-2",
+                            span { class: "text-gray-400", "Code:" }
+                            span { class: "text-white font-mono text-lg tracking-widest", "{party.code}" }
+                        }
+                        if let Some(ref url) = join_url {
+                            div { class: "flex items-center gap-2",
+                                span { class: "text-gray-400", "Link:" }
+                                span { class: "text-indigo-400 font-mono select-all", "{url}" }
+                            }
+                        }
+                        div { class: "flex items-center gap-2",
+                            span { class: "text-gray-400", "Guests:" }
+                            span { class: "text-white", "{party.guest_count}" }
+                        }
+                    }
+                }
+            }
+
+            if let Some(party) = party {
+                if party.require_approval {
+                    SettingsCard {
+                        h3 { class: "text-lg font-medium text-white mb-4", "Pending Requests" }
+                        if party.pending.is_empty() {
+                            p { class: "text-sm text-gray-500", "No requests yet." }
+                        } else {
+                            div { class: "space-y-2",
+                                for request in party.pending {
+                                    PendingRequestRow {
+                                        key: "{request.id}",
+                                        request,
+                                        on_approve,
+                                        on_reject,
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn PendingRequestRow(
+    request: PartyRequestInfo,
+    on_approve: EventHandler<String>,
+    on_reject: EventHandler<String>,
+) -> Element {
+    let approve_id = request.id.clone();
+    let reject_id = request.id.clone();
+
+    rsx! {
+        div { class: "flex items-center justify-between gap-4 py-2",
+            div { class: "min-w-0",
+                div { class: "text-sm text-white truncate", "{request.track_title}" }
+                div { class: "text-xs text-gray-400 truncate",
+                    "{request.artist_name} · requested by {request.guest_name}"
+                }
+            }
+            div { class: "flex gap-2 flex-shrink-0",
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Small,
+                    onclick: move |_| on_approve.call(approve_id.clone()),
+                    "Queue"
+                }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    size: ButtonSize::Small,
+                    onclick: move |_| on_reject.call(reject_id.clone()),
+                    "Decline"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Playback section view
+
+use crate::components::{SettingsCard, SettingsSection};
+use dioxus::prelude::*;
+
+/// Playback section view - what happens to playback on system events
+#[component]
+pub fn PlaybackSectionView(
+    pause_on_sleep: bool,
+    pause_on_screen_lock: bool,
+    pause_during_calls: bool,
+    duck_for_other_audio: bool,
+    on_pause_on_sleep_change: EventHandler<bool>,
+    on_pause_on_screen_lock_change: EventHandler<bool>,
+    on_pause_during_calls_change: EventHandler<bool>,
+    on_duck_for_other_audio_change: EventHandler<bool>,
+) -> Element {
+    rsx! {
+        SettingsSection {
+            h2 { class: "text-xl font-semibold text-white mb-6", "Playback" }
+
+            SettingsCard {
+                h3 { class: "text-lg font-medium text-white mb-4", "Pause Automatically" }
+                div { class: "space-y-3",
+                    SettingsCheckbox {
+                        checked: pause_on_sleep,
+                        label: "When the computer goes to sleep",
+                        on_change: on_pause_on_sleep_change,
+                    }
+                    SettingsCheckbox {
+                        checked: pause_on_screen_lock,
+                        label: "When the screen locks",
+                        on_change: on_pause_on_screen_lock_change,
+                    }
+                    SettingsCheckbox {
+                        checked: pause_during_calls,
+                        label: "During calls, resuming when the call ends",
+                        on_change: on_pause_during_calls_change,
+                    }
+                }
+                p { class: "text-xs text-gray-500 mt-3",
+                    "Calls are detected by another app using the microphone. "
+                    "Call detection needs macOS 14.2 or later."
+                }
+            }
+
+            SettingsCard {
+                h3 { class: "text-lg font-medium text-white mb-4", "Other Audio" }
+                SettingsCheckbox {
+                    checked: duck_for_other_audio,
+                    label: "Lower the volume while other apps play sound",
+                    on_change: on_duck_for_other_audio_change,
+                }
+                p { class: "text-xs text-gray-500 mt-3",
+                    "Covers notification sounds and alerts. Apps that keep their audio "
+                    "open while silent will hold the volume down."
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SettingsCheckbox(checked: bool, label: &'static str, on_change: EventHandler<bool>) -> Element {
+    rsx! {
+        div { class: "flex items-center gap-3",
+            input {
+                r#type: "checkbox",
+                class: "w-4 h-4 rounded bg-gray-700 border-gray-600 text-indigo-600 focus:ring-indigo-500",
+                checked,
+                onchange: move |e| on_change.call(e.checked()),
+            }
+            label { class: "text-sm text-gray-300", "{label}" }
+        }
+    }
+}
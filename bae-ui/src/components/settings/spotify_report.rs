@@ -0,0 +1,194 @@
+//! Spotify library report view
+//!
+//! Compares a pasted Spotify export against the library and lists what's
+//! owned and what's missing, with a metadata search for each missing album.
+
+use crate::components::{Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection};
+use crate::display_types::MatchCandidate;
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+/// One album referenced by a Spotify export
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpotifyReportAlbum {
+    pub artist: String,
+    pub title: String,
+    /// Number of saved tracks or playlist items from this album
+    pub entries: usize,
+    /// Library album ID, if the album is already owned
+    pub album_id: Option<String>,
+}
+
+impl SpotifyReportAlbum {
+    /// Key for looking up this album's search state
+    pub fn key(&self) -> String {
+        format!("{}\u{1f}{}", self.artist, self.title)
+    }
+}
+
+/// Metadata search state for a missing album
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpotifyAlbumSearch {
+    Searching,
+    Results(Vec<MatchCandidate>),
+    Failed(String),
+}
+
+/// Spotify library report section
+#[component]
+pub fn SpotifyReportView(
+    /// Pasted export text
+    input: String,
+    is_comparing: bool,
+    error: Option<String>,
+    /// Albums from the last comparison, in export order
+    report: Option<Vec<SpotifyReportAlbum>>,
+    /// Search state keyed by `SpotifyReportAlbum::key`
+    searches: HashMap<String, SpotifyAlbumSearch>,
+    on_input_change: EventHandler<String>,
+    on_compare: EventHandler<()>,
+    on_open_album: EventHandler<String>,
+    on_search: EventHandler<SpotifyReportAlbum>,
+) -> Element {
+    let (owned, missing): (Vec<SpotifyReportAlbum>, Vec<SpotifyReportAlbum>) = report
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|a| a.album_id.is_some());
+
+    rsx! {
+        SettingsSection {
+            SettingsCard {
+                h3 { class: "text-lg font-medium text-white mb-2", "Spotify Library Report" }
+                p { class: "text-sm text-gray-400 mb-4",
+                    "Paste YourLibrary.json or a playlist file from your Spotify data export, "
+                    "a playlist CSV, or \"Artist - Album\" lines to see which albums you already own."
+                }
+                textarea {
+                    class: "w-full h-32 px-3 py-2 bg-gray-700 border border-gray-600 rounded-lg text-white font-mono text-sm focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                    placeholder: "Artist - Album",
+                    value: "{input}",
+                    oninput: move |e| on_input_change.call(e.value()),
+                }
+                div { class: "flex items-center gap-3 mt-3",
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        size: ButtonSize::Medium,
+                        disabled: is_comparing || input.trim().is_empty(),
+                        loading: is_comparing,
+                        onclick: move |_| on_compare.call(()),
+                        if is_comparing {
+                            "Comparing..."
+                        } else {
+                            "Compare"
+                        }
+                    }
+                    if let Some(ref report) = report {
+                        span { class: "text-sm text-gray-400",
+                            "{owned.len()} of {report.len()} albums owned"
+                        }
+                    }
+                }
+                if let Some(ref error) = error {
+                    div { class: "mt-3 text-sm text-red-400", "{error}" }
+                }
+            }
+
+            if report.is_some() {
+                SettingsCard {
+                    h3 { class: "text-lg font-medium text-white mb-3", "Missing ({missing.len()})" }
+                    if missing.is_empty() {
+                        p { class: "text-sm text-gray-500 italic", "Everything is already in your library" }
+                    }
+                    div { class: "space-y-3",
+                        for album in missing {
+                            MissingAlbumRow {
+                                key: "{album.key()}",
+                                search: searches.get(&album.key()).cloned(),
+                                album,
+                                on_search,
+                            }
+                        }
+                    }
+                }
+
+                SettingsCard {
+                    h3 { class: "text-lg font-medium text-white mb-3", "Owned ({owned.len()})" }
+                    div { class: "space-y-1",
+                        for album in owned {
+                            div {
+                                key: "{album.key()}",
+                                class: "flex items-center justify-between text-sm",
+                                span { class: "text-white truncate", "{album.artist} – {album.title}" }
+                                if let Some(album_id) = album.album_id.clone() {
+                                    Button {
+                                        variant: ButtonVariant::Ghost,
+                                        size: ButtonSize::Small,
+                                        onclick: move |_| on_open_album.call(album_id.clone()),
+                                        "Open"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MissingAlbumRow(
+    album: SpotifyReportAlbum,
+    search: Option<SpotifyAlbumSearch>,
+    on_search: EventHandler<SpotifyReportAlbum>,
+) -> Element {
+    let searching = matches!(search, Some(SpotifyAlbumSearch::Searching));
+    let album_for_search = album.clone();
+
+    rsx! {
+        div {
+            div { class: "flex items-center justify-between text-sm",
+                div { class: "min-w-0",
+                    span { class: "text-white", "{album.artist} – {album.title}" }
+                    span { class: "text-gray-500 ml-2", "{album.entries} in export" }
+                }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    size: ButtonSize::Small,
+                    disabled: searching,
+                    loading: searching,
+                    onclick: move |_| on_search.call(album_for_search.clone()),
+                    "Search"
+                }
+            }
+            match search {
+                Some(SpotifyAlbumSearch::Results(candidates)) => rsx! {
+                    if candidates.is_empty() {
+                        p { class: "mt-1 ml-4 text-xs text-gray-500 italic", "No releases found" }
+                    }
+                    div { class: "mt-1 ml-4 space-y-0.5",
+                        for (i , candidate) in candidates.into_iter().enumerate() {
+                            div { key: "{i}", class: "text-xs text-gray-400",
+                                "{candidate.artist} – {candidate.title}"
+                                if let Some(year) = candidate.year {
+                                    " ({year})"
+                                }
+                                if let Some(format) = candidate.format {
+                                    " · {format}"
+                                }
+                                if let Some(label) = candidate.label {
+                                    " · {label}"
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(SpotifyAlbumSearch::Failed(error)) => rsx! {
+                    p { class: "mt-1 ml-4 text-xs text-red-400", "{error}" }
+                },
+                _ => rsx! {},
+            }
+        }
+    }
+}
@@ -1,8 +1,22 @@
 //! Subsonic section view
 
+use crate::components::utils::format_relative_time;
 use crate::components::{Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection};
 use dioxus::prelude::*;
 
+/// A per-client app password, without the secret itself
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppPasswordInfo {
+    pub id: String,
+    pub name: String,
+    /// RFC 3339
+    pub created_at: String,
+    /// RFC 3339; `None` until a client signs in with it
+    pub last_used_at: Option<String>,
+    /// Turned off from the server admin page
+    pub disabled: bool,
+}
+
 /// Subsonic section view
 #[component]
 pub fn SubsonicSectionView(
@@ -50,6 +64,17 @@ pub fn SubsonicSectionView(
     on_username_change: EventHandler<String>,
     on_password_change: EventHandler<String>,
     on_password_confirm_change: EventHandler<String>,
+    // App passwords
+    app_passwords: Vec<AppPasswordInfo>,
+    new_app_password_name: String,
+    /// Password just generated, shown once so it can be copied into the client
+    created_app_password: Option<String>,
+    is_creating_app_password: bool,
+    app_password_error: Option<String>,
+    on_app_password_name_change: EventHandler<String>,
+    on_app_password_create: EventHandler<()>,
+    on_app_password_revoke: EventHandler<String>,
+    on_created_app_password_dismiss: EventHandler<()>,
 ) -> Element {
     let passwords_mismatch = !edit_password.is_empty() && edit_password != edit_password_confirm;
     let needs_password = edit_auth_enabled && !auth_password_set && edit_password.is_empty();
@@ -240,6 +265,88 @@ pub fn SubsonicSectionView(
                 }
             }
 
+            if auth_enabled {
+                SettingsCard {
+                    h3 { class: "text-lg font-medium text-white mb-2", "App Passwords" }
+                    p { class: "text-sm text-gray-400 mb-4",
+                        "Give each Subsonic app its own password, used with your server username. Apps that support OpenSubsonic API keys can take it as the key instead, with no username. Revoking one signs out that app without affecting the others."
+                    }
+
+                    if let Some(password) = created_app_password {
+                        div { class: "p-3 mb-4 bg-indigo-900/30 border border-indigo-700 rounded-lg",
+                            p { class: "text-sm text-indigo-200 mb-2",
+                                "Enter this password or API key in the app now. It won't be shown again."
+                            }
+                            div { class: "flex items-center justify-between gap-3",
+                                span { class: "font-mono text-white select-all", "{password}" }
+                                Button {
+                                    variant: ButtonVariant::Secondary,
+                                    size: ButtonSize::Small,
+                                    onclick: move |_| on_created_app_password_dismiss.call(()),
+                                    "Done"
+                                }
+                            }
+                        }
+                    }
+
+                    if !app_passwords.is_empty() {
+                        div { class: "divide-y divide-gray-700 mb-4",
+                            for app_password in app_passwords {
+                                div {
+                                    key: "{app_password.id}",
+                                    class: "flex items-center justify-between py-2",
+                                    div {
+                                        div { class: "text-sm text-white", "{app_password.name}" }
+                                        div { class: "text-xs text-gray-500",
+                                            "Created {format_relative_time(&app_password.created_at)} · "
+                                            if let Some(ref used) = app_password.last_used_at {
+                                                "Last used {format_relative_time(used)}"
+                                            } else {
+                                                "Never used"
+                                            }
+                                            if app_password.disabled {
+                                                " · Disabled"
+                                            }
+                                        }
+                                    }
+                                    Button {
+                                        variant: ButtonVariant::Danger,
+                                        size: ButtonSize::Small,
+                                        onclick: {
+                                            let id = app_password.id.clone();
+                                            move |_| on_app_password_revoke.call(id.clone())
+                                        },
+                                        "Revoke"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div { class: "flex items-center gap-3",
+                        input {
+                            r#type: "text",
+                            class: "flex-1 px-3 py-2 bg-gray-700 border border-gray-600 rounded-lg text-white focus:outline-none focus:ring-2 focus:ring-indigo-500",
+                            placeholder: "App name, e.g. Symfonium on phone",
+                            value: "{new_app_password_name}",
+                            oninput: move |e| on_app_password_name_change.call(e.value()),
+                        }
+                        Button {
+                            variant: ButtonVariant::Primary,
+                            size: ButtonSize::Medium,
+                            disabled: new_app_password_name.trim().is_empty() || is_creating_app_password,
+                            loading: is_creating_app_password,
+                            onclick: move |_| on_app_password_create.call(()),
+                            "Create"
+                        }
+                    }
+
+                    if let Some(error) = app_password_error {
+                        p { class: "mt-3 text-sm text-red-400", "{error}" }
+                    }
+                }
+            }
+
             SettingsCard {
                 div { class: "flex items-center justify-between mb-4",
                     h3 { class: "text-lg font-medium text-white", "Share Links" }
@@ -5,15 +5,24 @@ use crate::components::icons::{CheckIcon, CopyIcon};
 use crate::components::settings::cloud_provider::{
     BaeCloudAuthMode, CloudProviderOption, CloudProviderPicker,
 };
+use crate::components::settings::sync_conflicts::SyncConflictsCard;
+use crate::components::utils::{format_file_size, format_relative_time};
 use crate::components::{
-    Button, ButtonSize, ButtonVariant, ChromelessButton, SettingsCard, SettingsSection, TextInput,
-    TextInputSize, TextInputType,
+    Button, ButtonSize, ButtonVariant, ChromelessButton, Segment, SegmentedControl, Select,
+    SelectOption, SettingsCard, SettingsSection, TextInput, TextInputSize, TextInputType,
 };
+use crate::display_types::StorageUsageInfo;
 use crate::floating_ui::Placement;
 use crate::stores::config::CloudProvider;
-use crate::stores::{DeviceActivityInfo, InviteStatus, Member, MemberRole, ShareInfo};
+use crate::stores::{
+    ConnectionCheckStep, DeviceActivityInfo, InviteStatus, Member, MemberRole, ShareInfo,
+    SyncConflictInfo,
+};
 use dioxus::prelude::*;
 
+/// Choices for how long an unplayed album stays on this device, in days
+const OFFLOAD_PERIODS: [u32; 4] = [30, 90, 180, 365];
+
 /// Data bundle for sync bucket configuration fields (avoids 5 separate EventHandler props for save).
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct SyncBucketConfig {
@@ -32,6 +41,10 @@ pub fn SyncSectionView(
     last_sync_time: Option<String>,
     /// Other devices' sync activity.
     other_devices: Vec<DeviceActivityInfo>,
+    /// Called when the user confirms revoking a device. Carries the device ID.
+    on_revoke_device: EventHandler<String>,
+    /// Called when the user approves a pending device's key. Carries the device ID.
+    on_approve_device: EventHandler<String>,
     /// Whether a sync is currently in progress.
     syncing: bool,
     /// Last sync error, if any.
@@ -40,6 +53,10 @@ pub fn SyncSectionView(
     user_pubkey: Option<String>,
     /// Called when the user clicks the copy button on their public key.
     on_copy_pubkey: EventHandler<()>,
+    /// Conflicting edits waiting for review.
+    conflicts: Vec<SyncConflictInfo>,
+    /// Called with (conflict ID, chosen value per column) when a conflict is resolved.
+    on_resolve_conflict: EventHandler<(String, Vec<(String, Option<String>)>)>,
 
     // --- Config display props ---
     /// Whether sync is fully configured (cloud provider + credentials).
@@ -62,6 +79,10 @@ pub fn SyncSectionView(
     on_disconnect_provider: EventHandler<()>,
     /// Callback when user selects iCloud Drive.
     on_use_icloud: EventHandler<()>,
+    /// Callback when user selects the local network.
+    on_use_lan: EventHandler<()>,
+    /// Callback when user asks to pick a sync folder.
+    on_choose_folder: EventHandler<()>,
 
     // --- S3 edit state props (passed through to CloudProviderPicker) ---
     /// Whether currently editing the S3 config.
@@ -76,6 +97,10 @@ pub fn SyncSectionView(
     edit_access_key: String,
     /// Edit field: secret key.
     edit_secret_key: String,
+    /// Whether the edited S3 settings are being tried.
+    checking_connection: bool,
+    /// Result of the last connection check. None until one has run.
+    connection_check: Option<Vec<ConnectionCheckStep>>,
 
     // --- bae cloud edit state props (passed through to CloudProviderPicker) ---
     /// Whether the bae cloud form is shown.
@@ -98,6 +123,14 @@ pub fn SyncSectionView(
     on_bae_cloud_password_change: EventHandler<String>,
     /// Called when the submit button is clicked.
     on_bae_cloud_submit: EventHandler<()>,
+    /// Whether images uploaded to the cloud home are encrypted
+    encrypt_images: bool,
+    on_encrypt_images_change: EventHandler<bool>,
+    /// Days without a play before an album moves to the cloud home, None when off
+    offload_after_days: Option<u32>,
+    on_offload_after_days_change: EventHandler<Option<u32>>,
+    /// Bytes stored in each place and this month's cloud home traffic. None while loading.
+    storage_usage: Option<StorageUsageInfo>,
 
     // --- Members props ---
     /// Current library members from membership chain. Empty if solo/not syncing.
@@ -133,6 +166,8 @@ pub fn SyncSectionView(
     on_endpoint_change: EventHandler<String>,
     on_access_key_change: EventHandler<String>,
     on_secret_key_change: EventHandler<String>,
+    /// Try the edited S3 settings before saving them.
+    on_check_connection: EventHandler<SyncBucketConfig>,
 
     // --- Invite callbacks ---
     /// Toggle the invite form open/closed.
@@ -155,11 +190,25 @@ pub fn SyncSectionView(
     on_reveal_recovery_key: EventHandler<()>,
     /// Called when the user clicks "Copy" on the recovery key.
     on_copy_recovery_key: EventHandler<()>,
+    /// Shares from the last split of the recovery key, one per entry
+    recovery_shares: Option<Vec<String>>,
+    /// Split the recovery key. Carries (threshold, share count).
+    on_split_recovery_key: EventHandler<(u8, u8)>,
+    /// Copy the shares to the clipboard, one per line
+    on_copy_recovery_shares: EventHandler<()>,
+    /// Where the recovery kit was last saved
+    recovery_kit_path: Option<String>,
+    recovery_kit_error: Option<String>,
+    /// Save a printable page with the key, its QR code and the cloud home
+    on_save_recovery_kit: EventHandler<()>,
 ) -> Element {
     let mut copied = use_signal(|| false);
     let mut share_copied = use_signal(|| false);
     let mut recovery_copied = use_signal(|| false);
+    let mut shares_copied = use_signal(|| false);
+    let mut share_scheme = use_signal(|| "3-5");
     let mut confirming_remove_pubkey = use_signal(|| Option::<String>::None);
+    let mut confirming_revoke_device = use_signal(|| Option::<String>::None);
 
     let handle_copy = move |_| {
         on_copy_pubkey.call(());
@@ -259,23 +308,96 @@ pub fn SyncSectionView(
                 }
             }
 
+            if !conflicts.is_empty() {
+                SyncConflictsCard { conflicts, on_resolve: on_resolve_conflict }
+            }
+
             // Other devices
             if !other_devices.is_empty() {
                 SettingsCard {
                     h3 { class: "text-lg font-medium text-white mb-4", "Other devices" }
                     div { class: "space-y-2",
                         for device in other_devices.iter() {
-                            div {
-                                key: "{device.device_id}",
-                                class: "flex justify-between items-center py-1",
-                                span { class: "text-gray-400 font-mono text-sm truncate mr-4",
-                                    {short_device_id(&device.device_id)}
-                                }
-                                span { class: "text-gray-300 text-sm flex-shrink-0",
-                                    if let Some(ref ts) = device.last_sync {
-                                        {format_relative_time(ts).as_str()}
-                                    } else {
-                                        "Unknown"
+                            {
+                                let device_id = device.device_id.clone();
+                                let short_id = short_device_id(&device.device_id);
+                                let is_confirming = confirming_revoke_device.read().as_deref()
+                                    == Some(device.device_id.as_str());
+                                rsx! {
+                                    div { key: "{device.device_id}",
+                                        div { class: "flex justify-between items-center py-1",
+                                            span { class: "text-gray-400 font-mono text-sm truncate mr-4",
+                                                "{short_id}"
+                                            }
+                                            div { class: "flex items-center gap-3 flex-shrink-0",
+                                                span { class: "text-gray-300 text-sm",
+                                                    if let Some(ref ts) = device.last_sync {
+                                                        {format_relative_time(ts).as_str()}
+                                                    } else {
+                                                        "Unknown"
+                                                    }
+                                                }
+                                                if device.revoked {
+                                                    span { class: "px-2 py-0.5 bg-red-900/40 text-red-300 rounded-full text-xs",
+                                                        "Revoked"
+                                                    }
+                                                } else if device.pending && !is_confirming {
+                                                    {
+                                                        let approve_device_id = device.device_id.clone();
+                                                        rsx! {
+                                                            Button {
+                                                                variant: ButtonVariant::Primary,
+                                                                size: ButtonSize::Small,
+                                                                onclick: move |_| on_approve_device.call(approve_device_id.clone()),
+                                                                "Approve"
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                if !device.revoked && !is_confirming {
+                                                    Button {
+                                                        variant: ButtonVariant::Secondary,
+                                                        size: ButtonSize::Small,
+                                                        onclick: move |_| confirming_revoke_device.set(Some(device_id.clone())),
+                                                        "Revoke"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        if device.pending && !is_confirming {
+                                            p { class: "text-xs text-gray-500 mt-1",
+                                                "Its changes are on hold until you approve it. Only approve a device you recognize."
+                                            }
+                                        }
+                                        if is_confirming {
+                                            div { class: "mt-2 p-3 bg-red-900/20 border border-red-800 rounded-lg",
+                                                p { class: "text-sm text-gray-300 mb-3",
+                                                    "Revoke {short_id}? Changes it makes will be ignored from now on. The encryption key stays the same, so it can still read what it has."
+                                                }
+                                                div { class: "flex gap-2",
+                                                    {
+                                                        let confirm_device_id = device.device_id.clone();
+                                                        rsx! {
+                                                            Button {
+                                                                variant: ButtonVariant::Danger,
+                                                                size: ButtonSize::Small,
+                                                                onclick: move |_| {
+                                                                    confirming_revoke_device.set(None);
+                                                                    on_revoke_device.call(confirm_device_id.clone());
+                                                                },
+                                                                "Confirm"
+                                                            }
+                                                        }
+                                                    }
+                                                    Button {
+                                                        variant: ButtonVariant::Secondary,
+                                                        size: ButtonSize::Small,
+                                                        onclick: move |_| confirming_revoke_device.set(None),
+                                                        "Cancel"
+                                                    }
+                                                }
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -333,6 +455,11 @@ pub fn SyncSectionView(
                                                                         "Member"
                                                                     }
                                                                 },
+                                                                MemberRole::ReadOnly => rsx! {
+                                                                    span { class: "px-2 py-0.5 bg-gray-700 text-gray-400 rounded text-xs font-medium flex-shrink-0",
+                                                                        "Read-only"
+                                                                    }
+                                                                },
                                                             }
                                                         }
                                                         if can_remove && !is_confirming {
@@ -424,6 +551,12 @@ pub fn SyncSectionView(
                                             onclick: move |_| on_invite_role_change.call(MemberRole::Member),
                                             "Member"
                                         }
+                                        Button {
+                                            variant: if invite_role == MemberRole::ReadOnly { ButtonVariant::Primary } else { ButtonVariant::Secondary },
+                                            size: ButtonSize::Small,
+                                            onclick: move |_| on_invite_role_change.call(MemberRole::ReadOnly),
+                                            "Read-only"
+                                        }
                                         Button {
                                             variant: if invite_role == MemberRole::Owner { ButtonVariant::Primary } else { ButtonVariant::Secondary },
                                             size: ButtonSize::Small,
@@ -541,6 +674,8 @@ pub fn SyncSectionView(
                 s3_endpoint: edit_endpoint,
                 s3_access_key: edit_access_key,
                 s3_secret_key: edit_secret_key,
+                s3_checking_connection: checking_connection,
+                s3_connection_check: connection_check,
                 bae_cloud_is_editing,
                 bae_cloud_mode,
                 bae_cloud_email,
@@ -550,6 +685,8 @@ pub fn SyncSectionView(
                 on_sign_in: move |p| on_sign_in.call(p),
                 on_disconnect: move |_| on_disconnect_provider.call(()),
                 on_use_icloud: move |_| on_use_icloud.call(()),
+                on_use_lan: move |_| on_use_lan.call(()),
+                on_choose_folder: move |_| on_choose_folder.call(()),
                 on_s3_edit_start: move |_| on_edit_start.call(()),
                 on_s3_cancel: move |_| on_cancel_edit.call(()),
                 on_s3_save: move |config| on_save_config.call(config),
@@ -558,6 +695,7 @@ pub fn SyncSectionView(
                 on_s3_endpoint_change: move |v| on_endpoint_change.call(v),
                 on_s3_access_key_change: move |v| on_access_key_change.call(v),
                 on_s3_secret_key_change: move |v| on_secret_key_change.call(v),
+                on_s3_check_connection: move |config| on_check_connection.call(config),
                 on_bae_cloud_mode_change: move |m| on_bae_cloud_mode_change.call(m),
                 on_bae_cloud_email_change: move |v| on_bae_cloud_email_change.call(v),
                 on_bae_cloud_username_change: move |v| on_bae_cloud_username_change.call(v),
@@ -565,6 +703,93 @@ pub fn SyncSectionView(
                 on_bae_cloud_submit: move |_| on_bae_cloud_submit.call(()),
             }
 
+            // Image encryption policy
+            if cloud_home_configured {
+                SettingsCard {
+                    h3 { class: "text-lg font-medium text-white mb-4", "Images" }
+                    div { class: "flex items-center gap-3",
+                        input {
+                            r#type: "checkbox",
+                            class: "w-4 h-4 rounded bg-gray-700 border-gray-600 text-indigo-600 focus:ring-indigo-500",
+                            checked: encrypt_images,
+                            onchange: move |e| on_encrypt_images_change.call(e.checked()),
+                        }
+                        label { class: "text-sm text-gray-300",
+                            "Encrypt cover art and artist photos in the cloud home"
+                        }
+                    }
+                    p { class: "text-xs text-gray-500 mt-3",
+                        "When this is off, images are uploaded as plain image files. "
+                        "Images already in the cloud home change over the next time they're uploaded."
+                    }
+                }
+
+                SettingsCard {
+                    h3 { class: "text-lg font-medium text-white mb-4", "Storage on this device" }
+                    div { class: "flex items-center gap-4",
+                        label { class: "text-sm text-gray-300 flex-1", "Keep albums on this device" }
+                        div { class: "w-64",
+                            Select {
+                                value: offload_after_days.map(|days| days.to_string()).unwrap_or_default(),
+                                onchange: move |value: String| on_offload_after_days_change.call(value.parse().ok()),
+                                SelectOption { value: "", label: "Always" }
+                                for days in OFFLOAD_PERIODS {
+                                    SelectOption {
+                                        key: "{days}",
+                                        value: days.to_string(),
+                                        label: format!("Played in the last {days} days"),
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    p { class: "text-xs text-gray-500 mt-3",
+                        "Albums that haven't been played or changed in that long move to the cloud home. "
+                        "Playing one streams it from the cloud home and brings it back to this device."
+                    }
+                }
+
+                if let Some(usage) = storage_usage {
+                    SettingsCard {
+                        h3 { class: "text-lg font-medium text-white mb-4", "Storage usage" }
+                        div { class: "space-y-2 text-sm",
+                            UsageRow {
+                                label: "On this device",
+                                value: format_file_size(usage.local_bytes as i64),
+                            }
+                            UsageRow {
+                                label: "In the cloud home",
+                                value: format_file_size(usage.cloud_bytes as i64),
+                            }
+                            UsageRow {
+                                label: "Sent this month",
+                                value: format_file_size(usage.bytes_uploaded as i64),
+                            }
+                            UsageRow {
+                                label: "Downloaded this month",
+                                value: format_file_size(usage.bytes_downloaded as i64),
+                            }
+                            UsageRow {
+                                label: "Requests this month",
+                                value: usage.requests.to_string(),
+                            }
+                            if let Some(cost) = usage.estimated_monthly_cost {
+                                UsageRow {
+                                    label: "Estimated cost this month",
+                                    value: format!("{cost:.2}"),
+                                }
+                            }
+                        }
+                        p { class: "text-xs text-gray-500 mt-3",
+                            "Counts album files this device sent to or read from the cloud home. "
+                            if usage.estimated_monthly_cost.is_none() {
+                                "Add your provider's prices as cloud_prices in config.yaml to estimate the cost."
+                            }
+                        }
+                    }
+                }
+            }
+
             // Recovery key
             SettingsCard {
                 h3 { class: "text-lg font-medium text-white mb-2", "Recovery key" }
@@ -600,6 +825,86 @@ pub fn SyncSectionView(
                     p { class: "text-xs text-gray-500 mt-3",
                         "Keep this key safe. Anyone with it can decrypt your library."
                     }
+                    // Recovery kit
+                    div { class: "mt-4 pt-4 border-t border-gray-700",
+                        h4 { class: "text-sm font-medium text-gray-300 mb-1", "Recovery kit" }
+                        p { class: "text-xs text-gray-500 mb-3",
+                            "A page with the key, its fingerprint, a QR code and where your cloud home is, to print or keep offline. It's made on this device and never uploaded."
+                        }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Small,
+                            onclick: move |_| on_save_recovery_kit.call(()),
+                            "Save Recovery Kit"
+                        }
+                        if let Some(path) = &recovery_kit_path {
+                            p { class: "text-xs text-green-400 mt-2 truncate", "Saved to {path}" }
+                        }
+                        if let Some(error) = &recovery_kit_error {
+                            p { class: "text-xs text-red-400 mt-2", "{error}" }
+                        }
+                    }
+                    // Shamir split
+                    div { class: "mt-4 pt-4 border-t border-gray-700",
+                        h4 { class: "text-sm font-medium text-gray-300 mb-1", "Split into shares" }
+                        p { class: "text-xs text-gray-500 mb-3",
+                            "Print the shares or give them to people you trust. Any threshold of them rebuilds the key on the unlock or welcome screen; fewer reveal nothing about it."
+                        }
+                        div { class: "flex items-center gap-3",
+                            SegmentedControl {
+                                segments: vec![
+                                    Segment::new("2 of 3", "2-3"),
+                                    Segment::new("3 of 5", "3-5"),
+                                    Segment::new("4 of 7", "4-7"),
+                                ],
+                                selected: share_scheme().to_string(),
+                                selected_variant: ButtonVariant::Secondary,
+                                on_select: move |value: &'static str| share_scheme.set(value),
+                            }
+                            Button {
+                                variant: ButtonVariant::Secondary,
+                                size: ButtonSize::Small,
+                                onclick: move |_| {
+                                    let scheme = match share_scheme() {
+                                        "2-3" => (2, 3),
+                                        "4-7" => (4, 7),
+                                        _ => (3, 5),
+                                    };
+                                    on_split_recovery_key.call(scheme);
+                                },
+                                "Split"
+                            }
+                        }
+                        if let Some(ref shares) = recovery_shares {
+                            textarea {
+                                class: "w-full h-32 mt-3 bg-gray-700 text-white text-xs font-mono rounded-lg p-3 border border-gray-600 focus:outline-none resize-none",
+                                readonly: true,
+                                value: shares.join("\n"),
+                            }
+                            div { class: "flex items-center gap-3 mt-2",
+                                Button {
+                                    variant: ButtonVariant::Secondary,
+                                    size: ButtonSize::Small,
+                                    onclick: move |_| {
+                                        on_copy_recovery_shares.call(());
+                                        shares_copied.set(true);
+                                        spawn(async move {
+                                            sleep_ms(2000).await;
+                                            shares_copied.set(false);
+                                        });
+                                    },
+                                    if *shares_copied.read() {
+                                        "Copied"
+                                    } else {
+                                        "Copy Shares"
+                                    }
+                                }
+                                span { class: "text-xs text-gray-500",
+                                    "Shares from different splits can't be combined."
+                                }
+                            }
+                        }
+                    }
                 } else {
                     Button {
                         variant: ButtonVariant::Secondary,
@@ -613,6 +918,16 @@ pub fn SyncSectionView(
     }
 }
 
+#[component]
+fn UsageRow(label: &'static str, value: String) -> Element {
+    rsx! {
+        div { class: "flex justify-between items-center",
+            span { class: "text-gray-400", "{label}" }
+            span { class: "text-white", "{value}" }
+        }
+    }
+}
+
 /// Truncate a hex-encoded public key for display: first 8 and last 8 characters.
 fn truncate_pubkey(key: &str) -> String {
     if key.len() > 20 {
@@ -632,47 +947,6 @@ fn short_device_id(id: &str) -> String {
     }
 }
 
-/// Format an RFC 3339 timestamp as a relative time string.
-///
-/// Falls back to the raw timestamp if parsing fails.
-fn format_relative_time(rfc3339: &str) -> String {
-    let Ok(dt) = chrono::DateTime::parse_from_rfc3339(rfc3339) else {
-        return rfc3339.to_string();
-    };
-
-    let now = chrono::Utc::now();
-    let duration = now.signed_duration_since(dt);
-
-    if duration.num_seconds() < 60 {
-        return "Just now".to_string();
-    }
-
-    if duration.num_minutes() < 60 {
-        let mins = duration.num_minutes();
-        return if mins == 1 {
-            "1 minute ago".to_string()
-        } else {
-            format!("{mins} minutes ago")
-        };
-    }
-
-    if duration.num_hours() < 24 {
-        let hours = duration.num_hours();
-        return if hours == 1 {
-            "1 hour ago".to_string()
-        } else {
-            format!("{hours} hours ago")
-        };
-    }
-
-    let days = duration.num_days();
-    if days == 1 {
-        "1 day ago".to_string()
-    } else {
-        format!("{days} days ago")
-    }
-}
-
 #[cfg(target_arch = "wasm32")]
 async fn sleep_ms(ms: u64) {
     gloo_timers::future::TimeoutFuture::new(ms as u32).await;
@@ -0,0 +1,227 @@
+//! Sync conflict review
+//!
+//! When an album is edited here and on another device before either syncs,
+//! sync keeps the newer edit. The conflicts card lists those albums; the
+//! dialog lets the user keep this device's value, the other device's, or type
+//! a merged one for each field.
+
+use crate::components::{
+    Button, ButtonSize, ButtonVariant, Modal, Segment, SegmentedControl, SettingsCard, TextInput,
+    TextInputSize, TextInputType,
+};
+use crate::stores::{SyncConflictFieldInfo, SyncConflictInfo};
+use dioxus::prelude::*;
+
+const LOCAL: &str = "local";
+const REMOTE: &str = "remote";
+const CUSTOM: &str = "custom";
+
+/// Albums with conflicting edits, each opening the resolution dialog
+#[component]
+pub fn SyncConflictsCard(
+    conflicts: Vec<SyncConflictInfo>,
+    /// Called with (conflict ID, chosen value per column)
+    on_resolve: EventHandler<(String, Vec<(String, Option<String>)>)>,
+) -> Element {
+    let mut reviewing = use_signal(|| None::<SyncConflictInfo>);
+    let is_open_memo = use_memo(move || reviewing().is_some());
+    let is_open: ReadSignal<bool> = is_open_memo.into();
+
+    rsx! {
+        SettingsCard {
+            h3 { class: "text-lg font-medium text-white mb-2", "Conflicts" }
+            p { class: "text-sm text-gray-400 mb-4",
+                "These albums were edited on this device and another one at the same time. Sync kept the newer edit until you review them."
+            }
+            div { class: "space-y-2",
+                for conflict in conflicts.iter() {
+                    div {
+                        key: "{conflict.id}",
+                        class: "flex justify-between items-center py-1",
+                        div { class: "min-w-0 mr-4",
+                            div { class: "text-white text-sm truncate", "{conflict.album_title}" }
+                            div { class: "text-gray-400 text-xs truncate",
+                                {fields_summary(&conflict.fields)}
+                            }
+                        }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Small,
+                            onclick: {
+                                let conflict = conflict.clone();
+                                move |_| reviewing.set(Some(conflict.clone()))
+                            },
+                            "Review"
+                        }
+                    }
+                }
+            }
+        }
+
+        Modal { is_open, on_close: move |_| reviewing.set(None),
+            if let Some(conflict) = reviewing() {
+                ConflictResolution {
+                    key: "{conflict.id}",
+                    conflict,
+                    on_cancel: move |_| reviewing.set(None),
+                    on_resolve: move |resolution| {
+                        on_resolve.call(resolution);
+                        reviewing.set(None);
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Dialog body for one conflict. Each field starts on the value sync kept.
+#[component]
+fn ConflictResolution(
+    conflict: SyncConflictInfo,
+    on_cancel: EventHandler<()>,
+    on_resolve: EventHandler<(String, Vec<(String, Option<String>)>)>,
+) -> Element {
+    let kept = if conflict.kept_remote { REMOTE } else { LOCAL };
+    let count = conflict.fields.len();
+    let mut choices = use_signal(move || vec![kept; count]);
+    let mut custom = use_signal({
+        let fields = conflict.fields.clone();
+        let kept_remote = conflict.kept_remote;
+        move || {
+            fields
+                .iter()
+                .map(|f| {
+                    let kept = if kept_remote { &f.remote } else { &f.local };
+                    kept.clone().unwrap_or_default()
+                })
+                .collect::<Vec<_>>()
+        }
+    });
+
+    // None while a custom value is left empty
+    let values: Option<Vec<(String, Option<String>)>> = conflict
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let value = match choices.read()[index] {
+                LOCAL => Some(field.local.clone()),
+                REMOTE => Some(field.remote.clone()),
+                _ => {
+                    let value = custom.read()[index].trim().to_string();
+                    (!value.is_empty()).then_some(Some(value))
+                }
+            };
+            value.map(|value| (field.column.clone(), value))
+        })
+        .collect();
+    let conflict_id = conflict.id.clone();
+
+    rsx! {
+        div { class: "bg-gray-800 rounded-lg shadow-xl max-w-2xl w-full mx-4 max-h-[80vh] flex flex-col",
+            div { class: "px-6 pt-6 pb-4 border-b border-gray-700",
+                h2 { class: "text-xl font-bold text-white", "Resolve conflict" }
+                p { class: "text-sm text-gray-400 mt-1", "{conflict.album_title}" }
+            }
+            div { class: "p-6 overflow-y-auto flex-1 space-y-6",
+                for (index , field) in conflict.fields.iter().enumerate() {
+                    div { key: "{field.column}", class: "space-y-2",
+                        div { class: "flex items-center justify-between gap-4",
+                            span { class: "text-sm font-medium text-white",
+                                {column_label(&field.column)}
+                            }
+                            SegmentedControl {
+                                segments: vec![
+                                    Segment::new("This device", LOCAL),
+                                    Segment::new("Other device", REMOTE),
+                                    Segment::new("Custom", CUSTOM),
+                                ],
+                                selected: choices.read()[index].to_string(),
+                                selected_variant: ButtonVariant::Secondary,
+                                on_select: move |value: &'static str| choices.write()[index] = value,
+                            }
+                        }
+                        div { class: "grid grid-cols-2 gap-3 text-sm",
+                            div {
+                                div { class: "text-xs text-gray-500", "This device" }
+                                div { class: "text-gray-300 break-words", {display_value(&field.local)} }
+                            }
+                            div {
+                                div { class: "text-xs text-gray-500", "Other device" }
+                                div { class: "text-gray-300 break-words", {display_value(&field.remote)} }
+                            }
+                        }
+                        if choices.read()[index] == CUSTOM {
+                            TextInput {
+                                value: custom.read()[index].clone(),
+                                on_input: move |value| custom.write()[index] = value,
+                                size: TextInputSize::Medium,
+                                input_type: TextInputType::Text,
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "flex justify-end gap-3 px-6 py-4 border-t border-gray-700",
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    size: ButtonSize::Medium,
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Medium,
+                    disabled: values.is_none(),
+                    onclick: move |_| {
+                        if let Some(ref values) = values {
+                            on_resolve.call((conflict_id.clone(), values.clone()));
+                        }
+                    },
+                    "Resolve"
+                }
+            }
+        }
+    }
+}
+
+/// "title" -> "Title", "musicbrainz_release_id" -> "Musicbrainz release id"
+fn column_label(column: &str) -> String {
+    let label = column.replace('_', " ");
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => label,
+    }
+}
+
+fn display_value(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "(empty)".to_string())
+}
+
+/// "Title and year differ"
+fn fields_summary(fields: &[SyncConflictFieldInfo]) -> String {
+    let labels: Vec<String> = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let label = column_label(&field.column);
+            if index == 0 {
+                label
+            } else {
+                label.to_lowercase()
+            }
+        })
+        .collect();
+    let verb = if labels.len() == 1 {
+        "differs"
+    } else {
+        "differ"
+    };
+    match labels.split_last() {
+        Some((last, rest)) if !rest.is_empty() => {
+            format!("{} and {} {}", rest.join(", "), last, verb)
+        }
+        _ => format!("{} {}", labels.join(""), verb),
+    }
+}
@@ -7,10 +7,13 @@ use dioxus::prelude::*;
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum SettingsTab {
     Library,
+    Import,
+    Playback,
     Sync,
     Discogs,
     BitTorrent,
     Subsonic,
+    Party,
     About,
 }
 
@@ -18,10 +21,13 @@ impl SettingsTab {
     pub fn label(&self) -> &'static str {
         match self {
             SettingsTab::Library => "Library",
+            SettingsTab::Import => "Import",
+            SettingsTab::Playback => "Playback",
             SettingsTab::Sync => "Sync",
-            SettingsTab::Discogs => "Discogs",
+            SettingsTab::Discogs => "Metadata",
             SettingsTab::BitTorrent => "BitTorrent",
             SettingsTab::Subsonic => "Subsonic",
+            SettingsTab::Party => "Party",
             SettingsTab::About => "About",
         }
     }
@@ -29,11 +35,14 @@ impl SettingsTab {
     pub fn all() -> &'static [SettingsTab] {
         &[
             SettingsTab::Library,
+            SettingsTab::Import,
+            SettingsTab::Playback,
             SettingsTab::Sync,
             SettingsTab::Discogs,
             #[cfg(feature = "torrent")]
             SettingsTab::BitTorrent,
             SettingsTab::Subsonic,
+            SettingsTab::Party,
             SettingsTab::About,
         ]
     }
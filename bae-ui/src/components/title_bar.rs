@@ -5,7 +5,7 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::components::icons::{
-    ChevronDownIcon, DiscIcon, ImageIcon, SettingsIcon, UserIcon, XIcon,
+    ChevronDownIcon, DiscIcon, ImageIcon, PlayIcon, SettingsIcon, UserIcon, XIcon,
 };
 use crate::components::utils::format_duration;
 use crate::components::{ChromelessButton, Dropdown, Placement};
@@ -45,6 +45,7 @@ pub struct ArtistResult {
     pub id: String,
     pub name: String,
     pub album_count: usize,
+    pub image_url: Option<String>,
 }
 
 /// Album search result
@@ -73,7 +74,8 @@ pub struct TrackResult {
 pub enum SearchAction {
     Artist(String),
     Album(String),
-    Track { album_id: String },
+    /// Play the track with this ID
+    Track(String),
 }
 
 /// Title bar view (pure, props-based)
@@ -119,9 +121,7 @@ pub fn TitleBarView(
             list.push(SearchAction::Album(a.id.clone()));
         }
         for t in &search_results.tracks {
-            list.push(SearchAction::Track {
-                album_id: t.album_id.clone(),
-            });
+            list.push(SearchAction::Track(t.id.clone()));
         }
         list
     };
@@ -466,8 +466,16 @@ fn ArtistResultItem(
                 evt.stop_propagation();
                 on_click.call(SearchAction::Artist(id.clone()));
             },
-            div { class: "w-8 h-8 bg-gray-700 rounded-full flex items-center justify-center flex-shrink-0",
-                UserIcon { class: "w-4 h-4 text-gray-400" }
+            if let Some(url) = &artist.image_url {
+                img {
+                    src: "{url}",
+                    class: "w-8 h-8 rounded-full object-cover flex-shrink-0",
+                    alt: "{artist.name}",
+                }
+            } else {
+                div { class: "w-8 h-8 bg-gray-700 rounded-full flex items-center justify-center flex-shrink-0",
+                    UserIcon { class: "w-4 h-4 text-gray-400" }
+                }
             }
             div { class: "flex-1 min-w-0",
                 div { class: "text-white text-xs font-medium truncate", "{artist.name}" }
@@ -518,34 +526,43 @@ fn AlbumResultItem(
     }
 }
 
-/// Track result item
+/// Track result item - clicking the row plays the track, clicking the album
+/// title opens the album
 #[component]
 fn TrackResultItem(
     track: TrackResult,
     is_selected: bool,
     on_click: EventHandler<SearchAction>,
 ) -> Element {
+    let track_id = track.id.clone();
     let album_id = track.album_id.clone();
-    let subtitle = format!("{} \u{2022} {}", track.album_title, track.artist_name);
     let duration = track.duration_ms.map(format_duration).unwrap_or_default();
     let selected_class = if is_selected { "bg-hover" } else { "" };
 
     rsx! {
         div {
-            class: "flex items-center gap-3 px-3 py-2 hover:bg-hover cursor-pointer {selected_class}",
+            class: "group flex items-center gap-3 px-3 py-2 hover:bg-hover cursor-pointer {selected_class}",
             onclick: move |evt| {
                 evt.stop_propagation();
-                on_click
-                    .call(SearchAction::Track {
-                        album_id: album_id.clone(),
-                    });
+                on_click.call(SearchAction::Track(track_id.clone()));
             },
             div { class: "w-8 h-8 bg-gray-700 rounded flex items-center justify-center flex-shrink-0",
-                DiscIcon { class: "w-4 h-4 text-gray-500" }
+                DiscIcon { class: "w-4 h-4 text-gray-500 group-hover:hidden" }
+                PlayIcon { class: "w-4 h-4 text-white hidden group-hover:block" }
             }
             div { class: "flex-1 min-w-0",
                 div { class: "text-white text-xs font-medium truncate", "{track.title}" }
-                div { class: "text-gray-400 text-[10px] truncate", "{subtitle}" }
+                div { class: "text-gray-400 text-[10px] truncate",
+                    span {
+                        class: "hover:text-white hover:underline",
+                        onclick: move |evt| {
+                            evt.stop_propagation();
+                            on_click.call(SearchAction::Album(album_id.clone()));
+                        },
+                        "{track.album_title}"
+                    }
+                    " \u{2022} {track.artist_name}"
+                }
             }
             if !duration.is_empty() {
                 span { class: "text-gray-500 text-[10px] flex-shrink-0", "{duration}" }
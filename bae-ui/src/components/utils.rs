@@ -8,6 +8,47 @@ pub fn format_duration(duration_ms: i64) -> String {
     format!("{}:{:02}", minutes, seconds)
 }
 
+/// Format an RFC 3339 timestamp as a relative time string.
+///
+/// Falls back to the raw timestamp if parsing fails.
+pub fn format_relative_time(rfc3339: &str) -> String {
+    let Ok(dt) = chrono::DateTime::parse_from_rfc3339(rfc3339) else {
+        return rfc3339.to_string();
+    };
+
+    let now = chrono::Utc::now();
+    let duration = now.signed_duration_since(dt);
+
+    if duration.num_seconds() < 60 {
+        return "Just now".to_string();
+    }
+
+    if duration.num_minutes() < 60 {
+        let mins = duration.num_minutes();
+        return if mins == 1 {
+            "1 minute ago".to_string()
+        } else {
+            format!("{mins} minutes ago")
+        };
+    }
+
+    if duration.num_hours() < 24 {
+        let hours = duration.num_hours();
+        return if hours == 1 {
+            "1 hour ago".to_string()
+        } else {
+            format!("{hours} hours ago")
+        };
+    }
+
+    let days = duration.num_days();
+    if days == 1 {
+        "1 day ago".to_string()
+    } else {
+        format!("{days} days ago")
+    }
+}
+
 /// Format file size in human-readable form
 pub fn format_file_size(bytes: i64) -> String {
     if bytes < 1024 {
@@ -20,3 +61,40 @@ pub fn format_file_size(bytes: i64) -> String {
         format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
     }
 }
+
+/// Decode a blurhash into a tiny BMP data URL for use as a placeholder image.
+///
+/// The image is only a few pixels wide; browsers smooth it when scaling up,
+/// which gives the blurred look without any CSS filters.
+pub fn blurhash_data_url(hash: &str) -> Option<String> {
+    use base64::Engine;
+
+    const SIZE: u32 = 8;
+    const HEADER_LEN: u32 = 54;
+
+    let rgba = blurhash::decode(hash, SIZE, SIZE, 1.0).ok()?;
+    // 24-bit rows are already 4-byte aligned at this width, so no padding
+    let pixel_len = SIZE * SIZE * 3;
+
+    let mut bmp = Vec::with_capacity((HEADER_LEN + pixel_len) as usize);
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(HEADER_LEN + pixel_len).to_le_bytes());
+    bmp.extend_from_slice(&0u32.to_le_bytes());
+    bmp.extend_from_slice(&HEADER_LEN.to_le_bytes());
+    bmp.extend_from_slice(&40u32.to_le_bytes());
+    bmp.extend_from_slice(&(SIZE as i32).to_le_bytes());
+    // Negative height means rows are stored top-down
+    bmp.extend_from_slice(&(-(SIZE as i32)).to_le_bytes());
+    bmp.extend_from_slice(&1u16.to_le_bytes());
+    bmp.extend_from_slice(&24u16.to_le_bytes());
+    // Uncompressed, default resolution, no palette
+    bmp.extend_from_slice(&[0u8; 24]);
+    for px in rgba.chunks_exact(4) {
+        bmp.extend_from_slice(&[px[2], px[1], px[0]]);
+    }
+
+    Some(format!(
+        "data:image/bmp;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(&bmp)
+    ))
+}
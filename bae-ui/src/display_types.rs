@@ -13,14 +13,16 @@ pub enum LibrarySortField {
     Artist,
     Year,
     DateAdded,
+    PlayCount,
 }
 
 impl LibrarySortField {
-    pub const ALL: [LibrarySortField; 4] = [
+    pub const ALL: [LibrarySortField; 5] = [
         LibrarySortField::Title,
         LibrarySortField::Artist,
         LibrarySortField::Year,
         LibrarySortField::DateAdded,
+        LibrarySortField::PlayCount,
     ];
 }
 
@@ -37,11 +39,88 @@ pub struct SortCriterion {
     pub direction: SortDirection,
 }
 
+/// Where an album's files are kept, for filtering the library
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LibraryStorageFilter {
+    Local,
+    Cloud,
+    Unmanaged,
+}
+
+impl LibraryStorageFilter {
+    pub const ALL: [LibraryStorageFilter; 3] = [
+        LibraryStorageFilter::Local,
+        LibraryStorageFilter::Cloud,
+        LibraryStorageFilter::Unmanaged,
+    ];
+}
+
+/// Lossless or lossy audio, for filtering the library
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LibraryQualityFilter {
+    Lossless,
+    Lossy,
+}
+
+impl LibraryQualityFilter {
+    pub const ALL: [LibraryQualityFilter; 2] =
+        [LibraryQualityFilter::Lossless, LibraryQualityFilter::Lossy];
+}
+
+/// Filters on the library's albums. Unset fields match every album.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LibraryFilter {
+    pub genre: Option<String>,
+    /// First year of the decade, e.g. 1990
+    pub decade: Option<i32>,
+    pub storage: Option<LibraryStorageFilter>,
+    pub quality: Option<LibraryQualityFilter>,
+    /// Collection ID
+    pub collection: Option<String>,
+}
+
+impl LibraryFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A named set of albums the user keeps, e.g. the ones also owned on vinyl
+#[derive(Clone, Debug, PartialEq)]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+}
+
+/// A genre on the browse page, with how many albums carry it
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrowseGenre {
+    pub name: String,
+    pub album_count: usize,
+}
+
+/// A decade on the browse page, with how many albums are from it
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrowseDecade {
+    /// First year of the decade, e.g. 1990
+    pub decade: i32,
+    pub album_count: usize,
+}
+
 /// View mode for the library page
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum LibraryViewMode {
     Albums,
     Artists,
+    Composers,
+}
+
+impl LibraryViewMode {
+    pub const ALL: [LibraryViewMode; 3] = [
+        LibraryViewMode::Albums,
+        LibraryViewMode::Artists,
+        LibraryViewMode::Composers,
+    ];
 }
 
 /// Album display info
@@ -55,6 +134,27 @@ pub struct Album {
     pub date_added: chrono::DateTime<chrono::Utc>,
 }
 
+/// One edition of a work in an album's version group (original, remaster, deluxe)
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlbumVersion {
+    pub album_id: String,
+    pub title: String,
+    pub year: Option<i32>,
+    /// User-facing edition name (e.g., "Remaster")
+    pub label: Option<String>,
+}
+
+impl AlbumVersion {
+    /// Short name for the version picker: label, then year, then title
+    pub fn display_name(&self) -> String {
+        match (&self.label, self.year) {
+            (Some(label), _) => label.clone(),
+            (None, Some(year)) => year.to_string(),
+            (None, None) => self.title.clone(),
+        }
+    }
+}
+
 /// Artist display info
 #[derive(Clone, Debug, PartialEq)]
 pub struct Artist {
@@ -63,6 +163,14 @@ pub struct Artist {
     pub image_url: Option<String>,
 }
 
+/// One artist in an artist credit ("Glass Harbor feat. Tidewater")
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreditedArtist {
+    pub artist: Artist,
+    /// Text between this artist and the next; a comma when not given
+    pub join_phrase: Option<String>,
+}
+
 /// Track import state for UI display
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TrackImportState {
@@ -87,6 +195,16 @@ pub struct Track {
     pub import_state: TrackImportState,
 }
 
+/// The classical work a track performs. Consecutive tracks of the same
+/// work are shown as its movements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackWork {
+    pub work_title: String,
+    /// Title of this track's movement, shown instead of the track title
+    pub movement_title: Option<String>,
+    pub composers: Vec<Artist>,
+}
+
 /// Playback display state
 #[derive(Clone, Debug, PartialEq)]
 pub enum PlaybackDisplay {
@@ -114,6 +232,67 @@ pub struct QueueItem {
     pub cover_url: Option<String>,
 }
 
+/// A play from the history panel
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayHistoryItem {
+    pub id: String,
+    pub item: QueueItem,
+    /// RFC 3339
+    pub played_at: String,
+}
+
+/// Playlist summary for the playlists sidebar
+#[derive(Clone, Debug, PartialEq)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+    pub track_count: usize,
+}
+
+/// One track of a playlist, in playlist order
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaylistEntry {
+    pub track: Track,
+    pub artist_name: String,
+    pub album_id: String,
+    pub album_title: String,
+    pub cover_url: Option<String>,
+}
+
+/// Playback quality counters for the current session
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaybackDiagnostics {
+    pub session_secs: u64,
+    pub tracks_started: u64,
+    pub underruns: u64,
+    pub buffering_waits: u64,
+    pub buffering_timeouts: u64,
+    pub buffering_ms: u64,
+    pub decode_errors: u64,
+    pub last_first_audio_ms: Option<u64>,
+    pub avg_first_audio_ms: Option<u64>,
+    pub max_first_audio_ms: Option<u64>,
+}
+
+/// One timed line of synced lyrics
+#[derive(Clone, Debug, PartialEq)]
+pub struct LyricLine {
+    pub time_ms: u64,
+    pub text: String,
+}
+
+/// Lyrics for the playing track
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrackLyrics {
+    Loading,
+    /// Timed lines, highlighted as the track plays
+    Synced(Vec<LyricLine>),
+    Plain(String),
+    Instrumental,
+    NotFound,
+    Failed(String),
+}
+
 /// Release display info
 #[derive(Clone, Debug, PartialEq)]
 pub struct Release {
@@ -166,6 +345,200 @@ pub enum CoverChange {
     RemoteCover { url: String, source: String },
 }
 
+/// A larger cover the background cover upgrade scan found for a release
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoverUpgradeOffer {
+    pub release_id: String,
+    pub url: String,
+    /// Source recorded with the cover, as in `CoverChange::RemoteCover`
+    pub source: String,
+    /// Where it was found, for display ("Cover Art Archive")
+    pub label: String,
+    pub width: u32,
+    pub height: u32,
+    pub current_width: u32,
+    pub current_height: u32,
+}
+
+/// A file that failed the integrity check or the storage scrub
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrokenFileInfo {
+    pub filename: String,
+    /// "Local", "Cloud" or "Unmanaged"
+    pub location: String,
+    /// "Missing", "Unreadable", "Can't decrypt" or "Corrupt"
+    pub problem: String,
+    pub detail: String,
+}
+
+/// A release with broken files
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrokenReleaseInfo {
+    pub release_id: String,
+    pub release_name: Option<String>,
+    pub file_count: usize,
+    pub broken_files: Vec<BrokenFileInfo>,
+}
+
+/// An album with at least one broken release
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrokenAlbumInfo {
+    pub album_id: String,
+    pub album_title: String,
+    pub artist_name: String,
+    pub releases: Vec<BrokenReleaseInfo>,
+}
+
+/// What's kept on this device and in the cloud home, and this month's
+/// traffic to the cloud home
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StorageUsageInfo {
+    pub local_bytes: u64,
+    pub cloud_bytes: u64,
+    pub bytes_uploaded: u64,
+    pub bytes_downloaded: u64,
+    pub requests: u64,
+    /// None without a price table in the config
+    pub estimated_monthly_cost: Option<f64>,
+}
+
+/// Audio that can be assigned to a track in the track mapping editor
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackAudioSource {
+    pub id: String,
+    pub filename: String,
+    pub duration_ms: Option<i64>,
+}
+
+/// One track's row in the track mapping editor
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackMappingEntry {
+    pub track_id: String,
+    pub title: String,
+    pub disc_number: Option<i32>,
+    pub track_number: Option<i32>,
+    /// ID of the `TrackAudioSource` assigned to this track
+    pub source_id: Option<String>,
+}
+
+/// A release's tracks and the audio they can be mapped to
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackMapping {
+    pub release_id: String,
+    /// Tracks in display order
+    pub entries: Vec<TrackMappingEntry>,
+    pub sources: Vec<TrackAudioSource>,
+}
+
+/// One track's tags as edited in the tag editor
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrackTagEdit {
+    pub track_id: String,
+    pub title: String,
+    pub disc_number: Option<i32>,
+    pub track_number: Option<i32>,
+}
+
+/// Album and track tags saved from the tag editor
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlbumTagEdit {
+    pub album_id: String,
+    /// Release whose tracks were edited
+    pub release_id: String,
+    pub title: String,
+    pub year: Option<i32>,
+    /// Album artist names, in credit order
+    pub artists: Vec<String>,
+    pub tracks: Vec<TrackTagEdit>,
+}
+
+/// A tag the user put on an album
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomTag {
+    pub key: String,
+    /// None for a plain label
+    pub value: Option<String>,
+}
+
+/// Custom tags and notes saved from the album page
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlbumNotesEdit {
+    pub album_id: String,
+    pub tags: Vec<CustomTag>,
+    pub notes: String,
+}
+
+/// A note a library member left on an album
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlbumComment {
+    pub id: String,
+    /// Member's name, or a shortened key when they haven't been named
+    pub author_name: String,
+    /// Written by the current user, who can remove it
+    pub is_own: bool,
+    pub content: String,
+    /// RFC 3339
+    pub created_at: String,
+}
+
+/// Where a release's files live, across cloud storage and devices
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReleaseAvailability {
+    pub release_id: String,
+    pub file_count: usize,
+    pub in_cloud: bool,
+    /// Whether the cover has reached the sync bucket (None when not syncing or no cover)
+    pub cover_synced: Option<bool>,
+    pub devices: Vec<DeviceAvailability>,
+}
+
+/// A release this device keeps downloaded for playing offline
+#[derive(Clone, Debug, PartialEq)]
+pub struct OfflinePin {
+    pub downloaded_files: usize,
+    pub total_files: usize,
+    /// Size of the downloaded files as stored
+    pub downloaded_bytes: u64,
+}
+
+/// What one device holds of a release
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeviceAvailability {
+    pub device_id: String,
+    pub is_this_device: bool,
+    /// All files are on the device
+    pub has_files: bool,
+    /// Files cached from the cloud
+    pub cached_files: usize,
+}
+
+/// A new value MusicBrainz has for one album, track or disc field
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetadataUpdate {
+    Title(String),
+    Year(Option<i32>),
+    /// Album artist names, in credit order
+    Artists(Vec<String>),
+    TrackTitle {
+        track_id: String,
+        disc_number: Option<i32>,
+        track_number: Option<i32>,
+        title: String,
+    },
+    DiscTitle {
+        disc_number: i32,
+        title: String,
+    },
+}
+
+/// A difference between the local tags and MusicBrainz, for review
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetadataChange {
+    pub update: MetadataUpdate,
+    pub current: String,
+    pub proposed: String,
+}
+
 /// Import operation status for UI display
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ImportStatus {
@@ -250,11 +623,26 @@ pub struct CandidateTrack {
     pub duration: Option<String>,
 }
 
+/// A single-file recording (e.g. a vinyl side) being split into tracks
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordingSplit {
+    pub file_name: String,
+    pub duration_ms: u64,
+    /// Peak level per bucket (0.0 to 1.0), evenly spaced over the recording
+    pub waveform: Vec<f32>,
+    /// Where each track after the first starts, in ms
+    pub split_points_ms: Vec<u64>,
+    /// Tracklist of the selected release, matched to segments in order
+    pub tracks: Vec<CandidateTrack>,
+}
+
 /// Match candidate source type
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MatchSourceType {
     MusicBrainz,
     Discogs,
+    /// Built from the folder's embedded tags (no external match)
+    Tags,
 }
 
 /// Match candidate for UI display
@@ -448,3 +836,26 @@ pub struct CdDriveInfo {
     pub device_path: String,
     pub name: String,
 }
+
+/// A purchased album in the user's Bandcamp collection
+#[derive(Clone, Debug, PartialEq)]
+pub struct BandcampAlbumItem {
+    /// Bandcamp album ID
+    pub item_id: String,
+    pub artist: String,
+    pub title: String,
+    pub track_count: usize,
+    pub cover_url: Option<String>,
+    pub status: BandcampImportStatus,
+}
+
+/// Where a Bandcamp purchase is in the download-and-import flow
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum BandcampImportStatus {
+    #[default]
+    NotImported,
+    Downloading,
+    Importing,
+    Imported,
+    Failed(String),
+}
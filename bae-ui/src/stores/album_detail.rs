@@ -1,7 +1,12 @@
 //! Album detail state store
 
-use crate::display_types::{Album, Artist, File, Image, Release, RemoteCoverOption, Track};
+use crate::display_types::{
+    Album, AlbumComment, AlbumVersion, Artist, CreditedArtist, CustomTag, File, Image,
+    MetadataChange, OfflinePin, Release, ReleaseAvailability, RemoteCoverOption, Track,
+    TrackMapping, TrackWork,
+};
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 /// Transfer progress state
 #[derive(Clone, Debug, PartialEq)]
@@ -19,6 +24,8 @@ pub struct AlbumDetailState {
     pub album: Option<Album>,
     /// Artists for this album
     pub artists: Vec<Artist>,
+    /// The album's artist credit, with the text joining each artist to the next
+    pub artist_credit: Vec<CreditedArtist>,
     /// Tracks for this album (with per-track reactive import_state)
     pub tracks: Vec<Track>,
     /// Track count - set when tracks are loaded, avoids subscribing to track changes
@@ -27,8 +34,26 @@ pub struct AlbumDetailState {
     pub track_ids: Vec<String>,
     /// Track disc info (disc_number, track_id) - for disc headers without subscribing to tracks
     pub track_disc_info: Vec<(Option<i32>, String)>,
+    /// Disc subtitles keyed by disc number (only titled discs)
+    pub disc_titles: HashMap<i32, String>,
+    /// Works performed by the tracks, keyed by track ID (only tracks with one)
+    pub track_works: HashMap<String, TrackWork>,
+    /// Credits of tracks credited differently from the album, keyed by track ID
+    pub track_credits: HashMap<String, Vec<CreditedArtist>>,
     /// Releases (editions) for this album
     pub releases: Vec<Release>,
+    /// Other albums linked as versions of this one (including itself; empty if unlinked)
+    pub versions: Vec<AlbumVersion>,
+    /// Albums that can be linked as a version of this one
+    pub version_candidates: Vec<Album>,
+    /// Tags the user put on this album
+    pub custom_tags: Vec<CustomTag>,
+    /// The user's notes on this album
+    pub notes: Option<String>,
+    /// Comments members of a shared library left on this album, newest first
+    pub comments: Vec<AlbumComment>,
+    /// Whether the user can comment: the library is shared and they have a key
+    pub can_comment: bool,
     /// Files for the current release
     pub files: Vec<File>,
     /// Images for this album
@@ -53,10 +78,28 @@ pub struct AlbumDetailState {
     pub transfer_progress: Option<TransferProgressState>,
     /// Transfer error message
     pub transfer_error: Option<String>,
+    /// Where the release in the storage modal lives (None until loaded)
+    pub availability: Option<ReleaseAvailability>,
+    /// How much of the current release is kept downloaded (None when it isn't)
+    pub offline_pin: Option<OfflinePin>,
+    /// Error from keeping the current release downloaded
+    pub offline_error: Option<String>,
     /// Remote cover options fetched from MusicBrainz/Discogs
     pub remote_covers: Vec<RemoteCoverOption>,
     /// Whether remote covers are currently loading
     pub loading_remote_covers: bool,
+    /// Tracks and audio of the release being remapped (None until loaded)
+    pub track_mapping: Option<TrackMapping>,
+    /// Error from loading or saving a track mapping
+    pub track_mapping_error: Option<String>,
+    /// Error from saving tag edits, custom tags or notes
+    pub tags_error: Option<String>,
+    /// Error from posting or removing a comment
+    pub comment_error: Option<String>,
+    /// Differences from MusicBrainz for the release being refreshed (None until fetched)
+    pub musicbrainz_changes: Option<Vec<MetadataChange>>,
+    /// Error from fetching or applying MusicBrainz changes
+    pub musicbrainz_error: Option<String>,
     /// Share error message (e.g., share link creation failure)
     pub share_error: Option<String>,
     /// Set to true when a share link has been copied to clipboard
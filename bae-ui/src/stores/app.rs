@@ -6,10 +6,13 @@
 use super::active_imports::ActiveImportsUiState;
 use super::album_detail::AlbumDetailState;
 use super::artist_detail::ArtistDetailState;
+use super::browse::BrowseState;
 use super::config::ConfigState;
 use super::import::ImportState;
 use super::library::LibraryState;
+use super::party::PartyState;
 use super::playback::PlaybackUiState;
+use super::playlists::PlaylistsState;
 use super::sync::SyncState;
 use super::ui::UiState;
 use dioxus::prelude::*;
@@ -25,8 +28,12 @@ pub struct AppState {
     pub album_detail: AlbumDetailState,
     /// Artist detail view state
     pub artist_detail: ArtistDetailState,
+    /// Genre and decade browse state
+    pub browse: BrowseState,
     /// Active imports shown in toolbar dropdown
     pub active_imports: ActiveImportsUiState,
+    /// Playlists view state
+    pub playlists: PlaylistsState,
     /// Playback state (playing/paused, queue)
     pub playback: PlaybackUiState,
     /// General UI state (overlays, sidebar, search)
@@ -35,4 +42,6 @@ pub struct AppState {
     pub config: ConfigState,
     /// Sync status
     pub sync: SyncState,
+    /// Party mode (guest requests)
+    pub party: PartyState,
 }
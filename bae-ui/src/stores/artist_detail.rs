@@ -9,8 +9,10 @@ use std::collections::HashMap;
 pub struct ArtistDetailState {
     /// The artist being viewed
     pub artist: Option<Artist>,
-    /// Albums by this artist
+    /// Albums credited to this artist
     pub albums: Vec<Album>,
+    /// Albums the artist is only credited on for some tracks, like compilations
+    pub appearances: Vec<Album>,
     /// Artists keyed by album ID (for compilations showing other artists)
     pub artists_by_album: HashMap<String, Vec<Artist>>,
     /// Whether data is loading
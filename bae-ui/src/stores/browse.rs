@@ -0,0 +1,24 @@
+//! Browse state store
+
+use crate::display_types::{Album, Artist, BrowseDecade, BrowseGenre};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+/// State for the genre and decade browse pages
+#[derive(Clone, Debug, Default, PartialEq, Store)]
+pub struct BrowseState {
+    /// Genres with at least one album, alphabetically
+    pub genres: Vec<BrowseGenre>,
+    /// Decades with at least one album, earliest first
+    pub decades: Vec<BrowseDecade>,
+    /// Albums in the genre or decade being browsed
+    pub albums: Vec<Album>,
+    /// Artists keyed by album ID
+    pub artists_by_album: HashMap<String, Vec<Artist>>,
+    /// Cover blurhashes keyed by album ID, painted while covers load
+    pub cover_placeholders: HashMap<String, String>,
+    /// Whether data is loading
+    pub loading: bool,
+    /// Error message if loading failed
+    pub error: Option<String>,
+}
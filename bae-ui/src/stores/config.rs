@@ -11,6 +11,8 @@ pub enum CloudProvider {
     Dropbox,
     OneDrive,
     BaeCloud,
+    Lan,
+    Folder,
 }
 
 /// Application configuration state
@@ -25,10 +27,15 @@ pub struct ConfigState {
     pub cloud_account_display: Option<String>,
     /// Whether a Discogs API key is stored (hint flag, avoids keyring read)
     pub discogs_key_stored: bool,
+    /// Whether a fanart.tv API key is stored (hint flag, avoids keyring read)
+    pub fanart_key_stored: bool,
     /// Whether an encryption key is stored (hint flag, avoids keyring read)
     pub encryption_key_stored: bool,
     /// SHA-256 fingerprint of the encryption key (for display and validation)
     pub encryption_key_fingerprint: Option<String>,
+    /// Re-encryption progress after a key rotation, as (releases done,
+    /// release count). None when no rotation is running.
+    pub key_rotation_progress: Option<(usize, usize)>,
 
     // Server settings
     /// Whether the Subsonic API server is enabled
@@ -60,6 +67,38 @@ pub struct ConfigState {
 
     /// Base URL for share links (e.g. "https://listen.example.com")
     pub share_base_url: Option<String>,
+
+    // Import settings
+    /// File and folder name patterns skipped when scanning imports
+    pub import_ignore_patterns: Vec<String>,
+    /// Audio files shorter than this many seconds are skipped when scanning imports
+    pub import_min_audio_duration_secs: Option<u32>,
+    /// SMB or NFS share offered as a folder import source
+    pub import_network_share_url: Option<String>,
+    pub import_network_share_username: Option<String>,
+
+    // MusicBrainz collection sync
+    /// Linked MusicBrainz account (None = not linked)
+    pub musicbrainz_username: Option<String>,
+    /// Collection kept in sync with the library
+    pub musicbrainz_collection_id: Option<String>,
+
+    // Playback on system events
+    /// Pause when the computer goes to sleep
+    pub pause_on_sleep: bool,
+    /// Pause when the screen locks
+    pub pause_on_screen_lock: bool,
+    /// Pause while the microphone is in use
+    pub pause_during_calls: bool,
+    /// Lower the volume while another app is playing sound
+    pub duck_for_other_audio: bool,
+    /// Replace low-resolution covers without asking
+    pub auto_upgrade_covers: bool,
+    /// Encrypt images uploaded to the cloud home
+    pub encrypt_images: bool,
+    /// Days without a play before an album moves to the cloud home, None when off
+    pub offload_after_days: Option<u32>,
+
     /// Followed remote libraries
     pub followed_libraries: Vec<FollowedLibraryInfo>,
 }
@@ -4,8 +4,9 @@
 //! bae-desktop (real import) and bae-mocks (design tool).
 
 use crate::display_types::{
-    CandidateTrack, CategorizedFileInfo, DetectedCandidate, FolderMetadata, IdentifyMode,
-    MatchCandidate, SearchSource, SearchTab, SelectedCover,
+    BandcampAlbumItem, BandcampImportStatus, CandidateTrack, CategorizedFileInfo,
+    DetectedCandidate, FolderMetadata, IdentifyMode, MatchCandidate, MatchSourceType,
+    RecordingSplit, SearchSource, SearchTab, SelectedCover,
 };
 use dioxus::prelude::*;
 
@@ -62,11 +63,22 @@ pub enum PrefetchState {
     TrackCountMismatch {
         release_tracks: usize,
         local_files: usize,
+        /// The release's tracklist, for splitting a single recording to match
+        tracks: Vec<CandidateTrack>,
     },
     /// Fetch failed with error message
     FetchFailed(String),
 }
 
+/// Splitting a candidate's single recording into tracks at its silences
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecordingSplitState {
+    /// None while the recording is being analyzed
+    pub split: Option<RecordingSplit>,
+    pub is_saving: bool,
+    pub error: Option<String>,
+}
+
 /// Per-tab search results and status
 #[derive(Clone, Debug, Default, PartialEq, Store)]
 pub struct TabSearchState {
@@ -146,6 +158,8 @@ pub struct ConfirmingState {
     pub selected_cover: Option<SelectedCover>,
     /// Whether to copy files into managed local storage
     pub managed: bool,
+    /// Whether managed files move on to the cloud home once imported
+    pub move_to_cloud: bool,
     /// Current phase within Confirm step
     pub phase: ConfirmPhase,
     /// Cached auto-match results (for returning to Identify)
@@ -172,6 +186,29 @@ fn default_cover(candidate: &MatchCandidate, files: &CategorizedFileInfo) -> Opt
     None
 }
 
+/// Candidate for a tag-only import. Needs a detected artist and album.
+fn tags_candidate(metadata: &FolderMetadata) -> Option<MatchCandidate> {
+    Some(MatchCandidate {
+        title: metadata.album.clone()?,
+        artist: metadata.artist.clone()?,
+        year: metadata.year.map(|y| y.to_string()),
+        cover_url: None,
+        cover_fetch_failed: false,
+        format: None,
+        country: None,
+        label: None,
+        catalog_number: None,
+        source_type: MatchSourceType::Tags,
+        original_year: None,
+        musicbrainz_release_id: None,
+        musicbrainz_release_group_id: None,
+        discogs_release_id: None,
+        discogs_master_id: None,
+        existing_album_id: None,
+        tracks: vec![],
+    })
+}
+
 /// Phase within the Confirm step
 #[derive(Clone, Debug, Default, PartialEq, Store)]
 pub enum ConfirmPhase {
@@ -238,12 +275,16 @@ pub enum CandidateEvent {
     SelectSearchResult(usize),
     /// User confirms the selected search result
     ConfirmSearchResult,
+    /// User imports from the detected tags instead of a MusicBrainz/Discogs match
+    ConfirmFromTags,
     /// Prefetch started for a search result (sets Fetching state)
     PrefetchStarted(usize),
     /// Prefetch completed for a search result
     PrefetchComplete { index: usize, result: PrefetchState },
     /// Set confirm_pending flag (user clicked Select while prefetch in-flight)
     SetConfirmPending,
+    /// The candidate's files changed on disk (e.g. a CUE sheet was written for it)
+    FilesRescanned(CategorizedFileInfo),
     /// Update cover art for a search result (after retry or initial check)
     UpdateSearchResultCover {
         index: usize,
@@ -258,6 +299,8 @@ pub enum CandidateEvent {
     SelectCover(Option<SelectedCover>),
     /// User selects storage profile
     SetManaged(bool),
+    /// User picks the cloud home for this import's files
+    SetMoveToCloud(bool),
     /// User clicks "Import" button
     StartImport,
     /// Import is preparing (from async operation)
@@ -326,6 +369,14 @@ impl CandidateState {
         )
     }
 
+    /// Check if an import ran to the end, whether it completed or failed
+    pub fn is_import_finished(&self) -> bool {
+        matches!(
+            self,
+            CandidateState::Confirming(s) if matches!(s.phase, ConfirmPhase::Completed(_) | ConfirmPhase::Failed(_))
+        )
+    }
+
     /// Apply an event and return the new state.
     /// This is the core state machine transition function.
     pub fn transition(self, event: CandidateEvent) -> CandidateState {
@@ -369,6 +420,7 @@ impl IdentifyingState {
                             confirmed_candidate: candidate,
                             selected_cover,
                             managed: true,
+                            move_to_cloud: false,
                             phase: ConfirmPhase::Ready,
                             auto_matches: self.auto_matches,
                             search_state: self.search_state,
@@ -410,6 +462,7 @@ impl IdentifyingState {
                                 confirmed_candidate: candidate,
                                 selected_cover,
                                 managed: true,
+                                move_to_cloud: false,
                                 phase: ConfirmPhase::Ready,
                                 auto_matches: state.auto_matches,
                                 search_state: state.search_state,
@@ -476,6 +529,7 @@ impl IdentifyingState {
                         confirmed_candidate: candidate,
                         selected_cover,
                         managed: true,
+                        move_to_cloud: false,
                         phase: ConfirmPhase::Ready,
                         auto_matches: matches,
                         search_state: state.search_state,
@@ -594,6 +648,7 @@ impl IdentifyingState {
                                 confirmed_candidate: candidate,
                                 selected_cover,
                                 managed: true,
+                                move_to_cloud: false,
                                 phase: ConfirmPhase::Ready,
                                 auto_matches: state.auto_matches,
                                 search_state: state.search_state,
@@ -628,6 +683,7 @@ impl IdentifyingState {
                             confirmed_candidate: candidate,
                             selected_cover,
                             managed: true,
+                            move_to_cloud: false,
                             phase: ConfirmPhase::Ready,
                             auto_matches: state.auto_matches,
                             search_state: state.search_state,
@@ -637,9 +693,34 @@ impl IdentifyingState {
                 }
                 CandidateState::Identifying(state)
             }
+            CandidateEvent::FilesRescanned(files) => {
+                let mut state = self;
+                state.files = files;
+                CandidateState::Identifying(state)
+            }
+            CandidateEvent::ConfirmFromTags => {
+                let state = self;
+                let Some(candidate) = tags_candidate(&state.metadata) else {
+                    return CandidateState::Identifying(state);
+                };
+                let selected_cover = default_cover(&candidate, &state.files);
+                CandidateState::Confirming(Box::new(ConfirmingState {
+                    files: state.files,
+                    metadata: state.metadata,
+                    confirmed_candidate: candidate,
+                    selected_cover,
+                    managed: true,
+                    move_to_cloud: false,
+                    phase: ConfirmPhase::Ready,
+                    auto_matches: state.auto_matches,
+                    search_state: state.search_state,
+                    source_disc_id: None,
+                }))
+            }
             CandidateEvent::GoBackToIdentify
             | CandidateEvent::SelectCover(_)
             | CandidateEvent::SetManaged(_)
+            | CandidateEvent::SetMoveToCloud(_)
             | CandidateEvent::StartImport
             | CandidateEvent::ImportPreparing(_)
             | CandidateEvent::ImportStarted
@@ -681,6 +762,11 @@ impl ConfirmingState {
                 state.managed = managed;
                 CandidateState::Confirming(Box::new(state))
             }
+            CandidateEvent::SetMoveToCloud(move_to_cloud) => {
+                let mut state = self;
+                state.move_to_cloud = move_to_cloud;
+                CandidateState::Confirming(Box::new(state))
+            }
             CandidateEvent::StartImport => {
                 let mut state = self;
                 state.phase = ConfirmPhase::Preparing("Starting...".to_string());
@@ -723,9 +809,11 @@ impl ConfirmingState {
             | CandidateEvent::SearchComplete { .. }
             | CandidateEvent::SelectSearchResult(_)
             | CandidateEvent::ConfirmSearchResult
+            | CandidateEvent::ConfirmFromTags
             | CandidateEvent::PrefetchStarted(_)
             | CandidateEvent::PrefetchComplete { .. }
             | CandidateEvent::SetConfirmPending
+            | CandidateEvent::FilesRescanned(_)
             | CandidateEvent::UpdateSearchResultCover { .. } => {
                 CandidateState::Confirming(Box::new(self))
             }
@@ -762,6 +850,32 @@ pub struct ImportState {
     pub selected_import_source: crate::ImportSource,
     /// CD TOC info: (disc_id, first_track, last_track)
     pub cd_toc_info: Option<(String, u8, u8)>,
+    /// Bandcamp sign-in and collection (kept across source switches)
+    pub bandcamp: BandcampState,
+    /// Split editor for the current candidate's recording (None = closed)
+    pub recording_split: Option<RecordingSplitState>,
+}
+
+/// Bandcamp import source state
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BandcampState {
+    /// True once a session cookie has been accepted by Bandcamp
+    pub signed_in: bool,
+    /// True while signing in or loading the collection
+    pub is_loading: bool,
+    /// Sign-in or collection loading error
+    pub error: Option<String>,
+    /// Purchased albums, newest first
+    pub albums: Vec<BandcampAlbumItem>,
+}
+
+impl BandcampState {
+    /// Update the import status of one album in the collection
+    pub fn set_status(&mut self, item_id: &str, status: BandcampImportStatus) {
+        if let Some(album) = self.albums.iter_mut().find(|a| a.item_id == item_id) {
+            album.status = status;
+        }
+    }
 }
 
 impl ImportState {
@@ -776,6 +890,7 @@ impl ImportState {
         self.discid_lookup_attempted.clear();
         self.selected_release_indices = Vec::new();
         self.current_release_index = 0;
+        self.recording_split = None;
     }
 
     /// Get the current candidate's state (if any)
@@ -787,6 +902,7 @@ impl ImportState {
     /// Switch to a different candidate by key
     pub fn switch_candidate(&mut self, new_key: Option<String>) {
         self.current_candidate_key = new_key.clone();
+        self.recording_split = None;
         if let Some(key) = new_key {
             if let Some(state) = self.candidate_states.get(&key) {
                 self.folder_files = state.files().clone();
@@ -1013,6 +1129,12 @@ impl ImportState {
             .unwrap_or(true)
     }
 
+    /// Get whether managed files should move on to the cloud home
+    pub fn get_move_to_cloud(&self) -> bool {
+        self.current_candidate_state()
+            .is_some_and(|s| matches!(s, CandidateState::Confirming(cs) if cs.move_to_cloud))
+    }
+
     /// Get selected candidate index from current candidate key
     pub fn get_selected_candidate_index(&self) -> Option<usize> {
         self.current_candidate_key
@@ -1,6 +1,6 @@
 //! Library state store
 
-use crate::display_types::{Album, Artist};
+use crate::display_types::{Album, Artist, BrokenAlbumInfo, Collection, CoverUpgradeOffer};
 use crate::stores::config::LibrarySource;
 use dioxus::prelude::*;
 use std::collections::HashMap;
@@ -12,6 +12,32 @@ pub struct LibraryState {
     pub albums: Vec<Album>,
     /// Artists keyed by album ID
     pub artists_by_album: HashMap<String, Vec<Artist>>,
+    /// Composers credited on each album's tracks, keyed by album ID
+    pub composers_by_album: HashMap<String, Vec<Artist>>,
+    /// Number of linked versions keyed by album ID (only albums with more than one)
+    pub version_counts: HashMap<String, usize>,
+    /// Cover blurhashes keyed by album ID, painted while covers load
+    pub cover_placeholders: HashMap<String, String>,
+    /// IDs of the most recently added albums, newest first
+    pub recently_added: Vec<String>,
+    /// IDs of the most recently played albums, latest play first
+    pub recently_played: Vec<String>,
+    /// Genre names the albums can be filtered by
+    pub filter_genres: Vec<String>,
+    /// Decades the albums can be filtered by, as their first years
+    pub filter_decades: Vec<i32>,
+    /// The user's album collections, alphabetically
+    pub collections: Vec<Collection>,
+    /// IDs of the collections each album is in, keyed by album ID
+    pub collection_ids_by_album: HashMap<String, Vec<String>>,
+    /// Larger covers waiting to be accepted, keyed by release ID
+    pub cover_upgrades: HashMap<String, CoverUpgradeOffer>,
+    /// Albums the background storage scrub found broken files in
+    pub scrub_broken_albums: Vec<BrokenAlbumInfo>,
+    /// When the storage scrub last verified a release, as a Unix timestamp
+    pub last_storage_scrub: Option<i64>,
+    /// Whether the alert for newly found broken files is showing
+    pub scrub_alert: bool,
     /// Whether the library is loading
     pub loading: bool,
     /// Error message if loading failed
@@ -8,10 +8,13 @@ pub mod active_imports;
 pub mod album_detail;
 pub mod app;
 pub mod artist_detail;
+pub mod browse;
 pub mod config;
 pub mod import;
 pub mod library;
+pub mod party;
 pub mod playback;
+pub mod playlists;
 pub mod sync;
 pub mod ui;
 
@@ -19,9 +22,12 @@ pub use active_imports::*;
 pub use album_detail::*;
 pub use app::*;
 pub use artist_detail::*;
+pub use browse::*;
 pub use config::*;
 pub use import::*;
 pub use library::*;
+pub use party::*;
 pub use playback::*;
+pub use playlists::*;
 pub use sync::*;
 pub use ui::*;
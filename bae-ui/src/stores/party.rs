@@ -0,0 +1,29 @@
+//! Party mode state store
+
+use dioxus::prelude::*;
+
+/// A guest's track request waiting for the host
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartyRequestInfo {
+    pub id: String,
+    pub guest_name: String,
+    pub track_title: String,
+    pub artist_name: String,
+}
+
+/// The running party, as shown to the host
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartyInfo {
+    /// Code guests enter to join
+    pub code: String,
+    pub require_approval: bool,
+    pub guest_count: usize,
+    pub pending: Vec<PartyRequestInfo>,
+}
+
+/// Party mode state for the UI
+#[derive(Clone, Debug, Default, PartialEq, Store)]
+pub struct PartyState {
+    /// None when no party is running
+    pub active: Option<PartyInfo>,
+}
@@ -1,6 +1,6 @@
 //! Playback UI state store
 
-use crate::display_types::QueueItem;
+use crate::display_types::{PlayHistoryItem, QueueItem};
 use dioxus::prelude::*;
 
 /// Playback state enum matching bae-core's PlaybackState
@@ -30,6 +30,8 @@ pub struct PlaybackUiState {
     pub current_track: Option<QueueItem>,
     /// Queue items with full display info (track + album title + cover)
     pub queue_items: Vec<QueueItem>,
+    /// Recently played tracks, newest first
+    pub history: Vec<PlayHistoryItem>,
     /// Current playback position in milliseconds
     pub position_ms: u64,
     /// Track duration in milliseconds (0 if unknown)
@@ -44,8 +46,13 @@ pub struct PlaybackUiState {
     pub cover_url: Option<String>,
     /// Transient playback error message
     pub playback_error: Option<String>,
+    /// Set while the track that failed to start is being restored from
+    /// archive storage; cleared once something plays
+    pub warming_up: Option<String>,
     /// Repeat mode
     pub repeat_mode: RepeatMode,
+    /// Keep playing similar tracks when the queue runs out
+    pub radio_enabled: bool,
     /// Volume level (0.0 to 1.0)
     pub volume: f32,
 }
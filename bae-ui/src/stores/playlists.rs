@@ -0,0 +1,21 @@
+//! Playlists state store
+
+use crate::display_types::{Playlist, PlaylistEntry};
+use dioxus::prelude::*;
+
+/// State for the playlists view
+#[derive(Clone, Debug, Default, PartialEq, Store)]
+pub struct PlaylistsState {
+    /// All playlists, alphabetically
+    pub playlists: Vec<Playlist>,
+    /// The playlist being viewed
+    pub selected_id: Option<String>,
+    /// Tracks of the selected playlist, in order
+    pub entries: Vec<PlaylistEntry>,
+    /// Outcome of the last M3U import ("Matched 12 of 15 tracks")
+    pub import_summary: Option<String>,
+    /// Whether data is loading
+    pub loading: bool,
+    /// Error message if loading or editing failed
+    pub error: Option<String>,
+}
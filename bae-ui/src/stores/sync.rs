@@ -9,6 +9,10 @@ pub struct DeviceActivityInfo {
     pub last_seq: u64,
     /// RFC 3339 timestamp of when the device last synced.
     pub last_sync: Option<String>,
+    /// Whether it has been revoked in the membership chain.
+    pub revoked: bool,
+    /// Whether its key is waiting for this device to approve it.
+    pub pending: bool,
 }
 
 /// Role of a library member (display-only, shadows bae-core's MemberRole).
@@ -16,6 +20,7 @@ pub struct DeviceActivityInfo {
 pub enum MemberRole {
     Owner,
     Member,
+    ReadOnly,
 }
 
 /// A library member for display in the sync settings.
@@ -46,6 +51,36 @@ pub struct ShareInfo {
     pub invitee_display: String,
 }
 
+/// An album edited on this device and another at the same time, waiting for
+/// the user to pick the values to keep (display-only).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncConflictInfo {
+    pub id: String,
+    pub album_title: String,
+    /// Whether sync kept the other device's edit.
+    pub kept_remote: bool,
+    pub fields: Vec<SyncConflictFieldInfo>,
+}
+
+/// One conflicted album field. None means the field was cleared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncConflictFieldInfo {
+    /// Database column name, e.g. "title".
+    pub column: String,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+}
+
+/// One operation of a cloud home connection check (display-only).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionCheckStep {
+    /// What was tried, e.g. "Range read".
+    pub label: String,
+    pub latency_ms: u64,
+    /// Why it failed. None if it worked.
+    pub error: Option<String>,
+}
+
 /// Sync status state for the UI.
 #[derive(Clone, Debug, Default, PartialEq, Store)]
 pub struct SyncState {
@@ -61,6 +96,8 @@ pub struct SyncState {
     pub user_pubkey: Option<String>,
     /// Current library members (from membership chain). Empty if solo or not syncing.
     pub members: Vec<Member>,
+    /// Conflicting edits waiting for review.
+    pub conflicts: Vec<SyncConflictInfo>,
 
     // Cloud home configuration (mirrors Config, for UI display)
     /// S3 bucket name for cloud home.
@@ -75,6 +112,10 @@ pub struct SyncState {
     pub signing_in: bool,
     /// Error from a sign-in attempt.
     pub sign_in_error: Option<String>,
+    /// Whether S3 settings are being tried before they're saved.
+    pub checking_connection: bool,
+    /// Result of the last connection check. None until one has run.
+    pub connection_check: Option<Vec<ConnectionCheckStep>>,
 
     // Invite flow state
     /// Current invite operation status.
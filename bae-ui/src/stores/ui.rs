@@ -1,12 +1,16 @@
 //! General UI state store (sidebar, search, library sort)
 
-use crate::display_types::{LibrarySortField, LibraryViewMode, SortCriterion, SortDirection};
+use crate::display_types::{
+    LibraryFilter, LibrarySortField, LibraryViewMode, SortCriterion, SortDirection,
+};
 use dioxus::prelude::*;
 
 /// State for the queue sidebar
 #[derive(Clone, Debug, Default, PartialEq, Store)]
 pub struct SidebarState {
     pub is_open: bool,
+    /// Showing play history instead of the queue
+    pub show_history: bool,
 }
 
 /// State for library search
@@ -20,6 +24,8 @@ pub struct SearchState {
 pub struct LibrarySortState {
     pub sort_criteria: Vec<SortCriterion>,
     pub view_mode: LibraryViewMode,
+    /// Filters on the album grid
+    pub filter: LibraryFilter,
 }
 
 impl Default for LibrarySortState {
@@ -30,6 +36,7 @@ impl Default for LibrarySortState {
                 direction: SortDirection::Descending,
             }],
             view_mode: LibraryViewMode::Albums,
+            filter: LibraryFilter::default(),
         }
     }
 }
@@ -43,4 +50,8 @@ pub struct UiState {
     pub search: SearchState,
     /// Library sort/view state (persisted across tab switches)
     pub library_sort: LibrarySortState,
+    /// Playback diagnostics overlay is showing
+    pub diagnostics_open: bool,
+    /// Lyrics panel is showing
+    pub lyrics_open: bool,
 }
@@ -1,6 +1,9 @@
-use bae_ui::display_types::{Album, Artist, Release, Track, TrackImportState};
+use bae_ui::display_types::{
+    Album, Artist, BrowseDecade, BrowseGenre, CreditedArtist, Playlist, PlaylistEntry, Release,
+    Track, TrackImportState,
+};
 use bae_ui::stores::AlbumDetailState;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Subsonic API response envelope
@@ -15,6 +18,9 @@ struct SubsonicInner {
     #[serde(rename = "albumList")]
     album_list: Option<AlbumListData>,
     album: Option<AlbumWithSongs>,
+    playlists: Option<PlaylistsData>,
+    playlist: Option<PlaylistWithSongs>,
+    genres: Option<GenresData>,
 }
 
 #[derive(Deserialize)]
@@ -32,6 +38,8 @@ struct SubsonicAlbum {
     year: Option<i32>,
     #[serde(rename = "coverArt")]
     cover_art: Option<String>,
+    #[serde(rename = "coverBlurhash")]
+    cover_blurhash: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -53,6 +61,42 @@ struct SubsonicSong {
     title: String,
     track: Option<i32>,
     duration: Option<i32>,
+    artist: Option<String>,
+    album: Option<String>,
+    #[serde(rename = "albumId")]
+    album_id: Option<String>,
+    #[serde(rename = "coverArt")]
+    cover_art: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GenresData {
+    genre: Vec<SubsonicGenre>,
+}
+
+#[derive(Deserialize)]
+struct SubsonicGenre {
+    value: String,
+    #[serde(rename = "albumCount")]
+    album_count: usize,
+}
+
+#[derive(Deserialize)]
+struct PlaylistsData {
+    playlist: Vec<SubsonicPlaylist>,
+}
+
+#[derive(Deserialize)]
+struct SubsonicPlaylist {
+    id: String,
+    name: String,
+    #[serde(rename = "songCount")]
+    song_count: usize,
+}
+
+#[derive(Deserialize)]
+struct PlaylistWithSongs {
+    entry: Option<Vec<SubsonicSong>>,
 }
 
 // -- Cloud share types (decrypted from meta.enc) --
@@ -82,9 +126,72 @@ fn cover_url_for(cover_art: &Option<String>) -> Option<String> {
         .map(|id| format!("/rest/getCoverArt?id={}", id))
 }
 
+/// Albums, artists keyed by album ID, and cover blurhashes keyed by album ID
+pub type AlbumList = (
+    Vec<Album>,
+    HashMap<String, Vec<Artist>>,
+    HashMap<String, String>,
+);
+
 /// Fetch all albums from the subsonic API
-pub async fn fetch_albums() -> Result<(Vec<Album>, HashMap<String, Vec<Artist>>), String> {
-    let resp = reqwest::get("/rest/getAlbumList")
+pub async fn fetch_albums() -> Result<AlbumList, String> {
+    fetch_album_list("/rest/getAlbumList").await
+}
+
+/// Fetch the albums tagged with a genre
+pub async fn fetch_albums_by_genre(genre: &str) -> Result<AlbumList, String> {
+    let genre = String::from(js_sys_x::encode_uri_component(genre));
+    fetch_album_list(&format!("/rest/getAlbumList?type=byGenre&genre={genre}")).await
+}
+
+/// Fetch the albums released in a decade, earliest first
+pub async fn fetch_albums_by_decade(decade: i32) -> Result<AlbumList, String> {
+    let url = format!(
+        "/rest/getAlbumList?type=byYear&fromYear={decade}&toYear={}",
+        decade + 9
+    );
+    fetch_album_list(&url).await
+}
+
+/// Fetch every genre that has albums, alphabetically
+pub async fn fetch_genres() -> Result<Vec<BrowseGenre>, String> {
+    let resp = reqwest::get("/rest/getGenres")
+        .await
+        .map_err(|e| format!("Network error: {e}"))?;
+
+    let envelope: SubsonicEnvelope = resp.json().await.map_err(|e| format!("Parse error: {e}"))?;
+
+    Ok(envelope
+        .subsonic_response
+        .genres
+        .map(|g| g.genre)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|g| BrowseGenre {
+            name: g.value,
+            album_count: g.album_count,
+        })
+        .collect())
+}
+
+/// Count albums per decade, earliest first. Subsonic has no decade list, so
+/// this is worked out from the albums' years.
+pub fn decades_of(albums: &[Album]) -> Vec<BrowseDecade> {
+    let mut counts = std::collections::BTreeMap::new();
+    for year in albums.iter().filter_map(|a| a.year) {
+        *counts.entry(year / 10 * 10).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(decade, album_count)| BrowseDecade {
+            decade,
+            album_count,
+        })
+        .collect()
+}
+
+async fn fetch_album_list(url: &str) -> Result<AlbumList, String> {
+    let resp = reqwest::get(url)
         .await
         .map_err(|e| format!("Network error: {e}"))?;
 
@@ -98,6 +205,7 @@ pub async fn fetch_albums() -> Result<(Vec<Album>, HashMap<String, Vec<Artist>>)
 
     let mut albums = Vec::with_capacity(subsonic_albums.len());
     let mut artists_by_album = HashMap::new();
+    let mut cover_placeholders = HashMap::new();
 
     for sa in subsonic_albums {
         let artist_name = sa
@@ -118,6 +226,10 @@ pub async fn fetch_albums() -> Result<(Vec<Album>, HashMap<String, Vec<Artist>>)
             }],
         );
 
+        if let Some(hash) = sa.cover_blurhash {
+            cover_placeholders.insert(sa.id.clone(), hash);
+        }
+
         albums.push(Album {
             id: sa.id,
             title: sa.name,
@@ -128,7 +240,24 @@ pub async fn fetch_albums() -> Result<(Vec<Album>, HashMap<String, Vec<Artist>>)
         });
     }
 
-    Ok((albums, artists_by_album))
+    Ok((albums, artists_by_album, cover_placeholders))
+}
+
+/// Fetch the IDs of the albums in one of the subsonic list types, such as
+/// "newest" or "recent", in list order
+pub async fn fetch_album_ids(list_type: &str, size: usize) -> Result<Vec<String>, String> {
+    let url = format!("/rest/getAlbumList?type={}&size={}", list_type, size);
+    let resp = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("Network error: {e}"))?;
+
+    let envelope: SubsonicEnvelope = resp.json().await.map_err(|e| format!("Parse error: {e}"))?;
+
+    Ok(envelope
+        .subsonic_response
+        .album_list
+        .map(|al| al.album.into_iter().map(|a| a.id).collect())
+        .unwrap_or_default())
 }
 
 /// Fetch a single album with tracks from the subsonic API
@@ -211,12 +340,29 @@ pub async fn fetch_album(album_id: &str) -> Result<AlbumDetailState, String> {
 
     Ok(AlbumDetailState {
         album: Some(album),
+        artist_credit: artists
+            .iter()
+            .cloned()
+            .map(|artist| CreditedArtist {
+                artist,
+                join_phrase: None,
+            })
+            .collect(),
         artists,
         tracks,
         track_count,
         track_ids,
         track_disc_info,
+        disc_titles: HashMap::new(),
+        track_works: HashMap::new(),
+        track_credits: HashMap::new(),
         releases,
+        versions: vec![],
+        version_candidates: vec![],
+        custom_tags: vec![],
+        notes: None,
+        comments: vec![],
+        can_comment: false,
         files: vec![],
         images: vec![],
         selected_release_id: Some(release_id),
@@ -229,13 +375,359 @@ pub async fn fetch_album(album_id: &str) -> Result<AlbumDetailState, String> {
         is_unmanaged: false,
         transfer_progress: None,
         transfer_error: None,
+        availability: None,
         remote_covers: vec![],
         loading_remote_covers: false,
+        track_mapping: None,
+        track_mapping_error: None,
+        tags_error: None,
+        comment_error: None,
+        musicbrainz_changes: None,
+        musicbrainz_error: None,
         share_error: None,
         share_link_copied: false,
     })
 }
 
+/// Fetch all playlists from the subsonic API
+pub async fn fetch_playlists() -> Result<Vec<Playlist>, String> {
+    let resp = reqwest::get("/rest/getPlaylists")
+        .await
+        .map_err(|e| format!("Network error: {e}"))?;
+
+    let envelope: SubsonicEnvelope = resp.json().await.map_err(|e| format!("Parse error: {e}"))?;
+
+    Ok(envelope
+        .subsonic_response
+        .playlists
+        .map(|p| p.playlist)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| Playlist {
+            id: p.id,
+            name: p.name,
+            track_count: p.song_count,
+        })
+        .collect())
+}
+
+/// Fetch a playlist's tracks, in order, from the subsonic API
+pub async fn fetch_playlist(playlist_id: &str) -> Result<Vec<PlaylistEntry>, String> {
+    let url = format!("/rest/getPlaylist?id={}", playlist_id);
+    let resp = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("Network error: {e}"))?;
+
+    let envelope: SubsonicEnvelope = resp.json().await.map_err(|e| format!("Parse error: {e}"))?;
+
+    let playlist = envelope
+        .subsonic_response
+        .playlist
+        .ok_or_else(|| "No playlist in response".to_string())?;
+
+    Ok(playlist
+        .entry
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| PlaylistEntry {
+            cover_url: cover_url_for(&s.cover_art),
+            track: Track {
+                id: s.id,
+                title: s.title,
+                track_number: s.track,
+                disc_number: None,
+                duration_ms: s.duration.map(|d| d as i64 * 1000),
+                is_available: true,
+                import_state: TrackImportState::Complete,
+            },
+            artist_name: s.artist.unwrap_or_else(|| "Unknown Artist".to_string()),
+            album_id: s.album_id.unwrap_or_default(),
+            album_title: s.album.unwrap_or_default(),
+        })
+        .collect())
+}
+
+// -- Server sign-in --
+
+/// Sign-in state reported by the server
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct SessionStatus {
+    /// Whether the server requires sign-in at all
+    pub auth_enabled: bool,
+    pub authenticated: bool,
+    pub totp_enabled: bool,
+}
+
+/// A two-factor secret waiting to be confirmed
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct TotpEnrollment {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    pub uri: String,
+    pub qr_svg: String,
+}
+
+/// A rejected sign-in or settings change
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct AuthFailure {
+    #[serde(rename = "error")]
+    pub message: String,
+    /// Set when the server wants an authenticator code
+    #[serde(default)]
+    pub totp_required: bool,
+}
+
+#[derive(Serialize)]
+struct LoginBody<'a> {
+    username: &'a str,
+    password: &'a str,
+    code: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct CodeBody<'a> {
+    code: &'a str,
+}
+
+async fn auth_failure(resp: reqwest::Response) -> AuthFailure {
+    let status = resp.status();
+    resp.json().await.unwrap_or_else(|_| AuthFailure {
+        message: format!("Server error: {status}"),
+        totp_required: false,
+    })
+}
+
+fn network_failure(e: reqwest::Error) -> AuthFailure {
+    AuthFailure {
+        message: format!("Network error: {e}"),
+        totp_required: false,
+    }
+}
+
+/// Fetch whether this browser is signed in.
+pub async fn fetch_session() -> Result<SessionStatus, String> {
+    let resp = reqwest::get("/auth/session")
+        .await
+        .map_err(|e| format!("Network error: {e}"))?;
+
+    resp.json().await.map_err(|e| format!("Parse error: {e}"))
+}
+
+/// Sign in with the server credentials and, once enrolled, an authenticator code.
+pub async fn login(
+    username: &str,
+    password: &str,
+    code: Option<&str>,
+) -> Result<SessionStatus, AuthFailure> {
+    let resp = reqwest::Client::new()
+        .post("/auth/login")
+        .json(&LoginBody {
+            username,
+            password,
+            code,
+        })
+        .send()
+        .await
+        .map_err(network_failure)?;
+
+    if !resp.status().is_success() {
+        return Err(auth_failure(resp).await);
+    }
+    resp.json().await.map_err(|e| AuthFailure {
+        message: format!("Parse error: {e}"),
+        totp_required: false,
+    })
+}
+
+/// End this browser's session.
+pub async fn logout() -> Result<(), String> {
+    reqwest::Client::new()
+        .post("/auth/logout")
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {e}"))?;
+    Ok(())
+}
+
+/// Generate a two-factor secret to scan into an authenticator app.
+pub async fn enroll_totp() -> Result<TotpEnrollment, AuthFailure> {
+    let resp = reqwest::Client::new()
+        .post("/auth/totp/enroll")
+        .send()
+        .await
+        .map_err(network_failure)?;
+
+    if !resp.status().is_success() {
+        return Err(auth_failure(resp).await);
+    }
+    resp.json().await.map_err(|e| AuthFailure {
+        message: format!("Parse error: {e}"),
+        totp_required: false,
+    })
+}
+
+/// Turn two-factor on by proving the pending secret was scanned.
+pub async fn confirm_totp(code: &str) -> Result<(), AuthFailure> {
+    post_code("/auth/totp/confirm", code).await
+}
+
+/// Turn two-factor off. Requires a current code.
+pub async fn disable_totp(code: &str) -> Result<(), AuthFailure> {
+    post_code("/auth/totp/disable", code).await
+}
+
+async fn post_code(url: &str, code: &str) -> Result<(), AuthFailure> {
+    let resp = reqwest::Client::new()
+        .post(url)
+        .json(&CodeBody { code })
+        .send()
+        .await
+        .map_err(network_failure)?;
+
+    if !resp.status().is_success() {
+        return Err(auth_failure(resp).await);
+    }
+    Ok(())
+}
+
+// -- Server admin --
+
+/// Health summary of the server
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct ServerHealth {
+    pub version: String,
+    pub uptime_secs: u64,
+    pub albums: usize,
+    pub local_bytes: u64,
+    pub cloud_bytes: u64,
+    pub sync_enabled: bool,
+    pub maintenance: bool,
+    pub last_sync: Option<String>,
+    pub sync_failures: u64,
+    pub s3_errors: u64,
+}
+
+/// An app password clients sign in with
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct ServerUser {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub disabled: bool,
+}
+
+/// A user just created, with the password only shown once
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct CreatedUser {
+    pub id: String,
+    pub name: String,
+    pub password: String,
+}
+
+/// A device the server syncs with
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct SyncDevice {
+    pub device_id: String,
+    pub last_seq: u64,
+    pub this_device: bool,
+}
+
+#[derive(Serialize)]
+struct CreateUserBody<'a> {
+    name: &'a str,
+}
+
+/// Fetch the server's health summary.
+pub async fn fetch_health() -> Result<ServerHealth, AuthFailure> {
+    get_admin("/admin/health").await
+}
+
+/// Fetch the server's users.
+pub async fn fetch_users() -> Result<Vec<ServerUser>, AuthFailure> {
+    get_admin("/admin/users").await
+}
+
+/// Fetch the devices the server syncs with.
+pub async fn fetch_devices() -> Result<Vec<SyncDevice>, AuthFailure> {
+    get_admin("/admin/devices").await
+}
+
+/// Create a user with a generated password.
+pub async fn create_user(name: &str) -> Result<CreatedUser, AuthFailure> {
+    let resp = reqwest::Client::new()
+        .post("/admin/users")
+        .json(&CreateUserBody { name })
+        .send()
+        .await
+        .map_err(network_failure)?;
+
+    if !resp.status().is_success() {
+        return Err(auth_failure(resp).await);
+    }
+    resp.json().await.map_err(|e| AuthFailure {
+        message: format!("Parse error: {e}"),
+        totp_required: false,
+    })
+}
+
+/// Stop or allow a user signing in.
+pub async fn set_user_disabled(id: &str, disabled: bool) -> Result<(), AuthFailure> {
+    let action = if disabled { "disable" } else { "enable" };
+    send_admin(reqwest::Client::new().post(format!("/admin/users/{id}/{action}"))).await
+}
+
+/// Delete a user.
+pub async fn delete_user(id: &str) -> Result<(), AuthFailure> {
+    send_admin(reqwest::Client::new().delete(format!("/admin/users/{id}"))).await
+}
+
+/// Ask the server to sync now.
+pub async fn force_sync() -> Result<(), AuthFailure> {
+    send_admin(reqwest::Client::new().post("/admin/sync")).await
+}
+
+#[derive(Serialize)]
+struct MaintenanceBody {
+    enabled: bool,
+}
+
+/// Turn the server's maintenance mode on or off.
+pub async fn set_maintenance(enabled: bool) -> Result<(), AuthFailure> {
+    send_admin(
+        reqwest::Client::new()
+            .post("/admin/maintenance")
+            .json(&MaintenanceBody { enabled }),
+    )
+    .await
+}
+
+/// Ask the server to reload its settings from config.yaml.
+pub async fn reload_settings() -> Result<(), AuthFailure> {
+    send_admin(reqwest::Client::new().post("/admin/reload")).await
+}
+
+async fn get_admin<T: serde::de::DeserializeOwned>(url: &str) -> Result<T, AuthFailure> {
+    let resp = reqwest::get(url).await.map_err(network_failure)?;
+
+    if !resp.status().is_success() {
+        return Err(auth_failure(resp).await);
+    }
+    resp.json().await.map_err(|e| AuthFailure {
+        message: format!("Parse error: {e}"),
+        totp_required: false,
+    })
+}
+
+async fn send_admin(request: reqwest::RequestBuilder) -> Result<(), AuthFailure> {
+    let resp = request.send().await.map_err(network_failure)?;
+
+    if !resp.status().is_success() {
+        return Err(auth_failure(resp).await);
+    }
+    Ok(())
+}
+
 /// Fetch encrypted share metadata from bae-proxy.
 pub async fn fetch_share_meta_encrypted(share_id: &str) -> Result<Vec<u8>, String> {
     let url = format!("/share/{share_id}/meta");
@@ -278,3 +770,44 @@ pub async fn fetch_share_file(share_id: &str, file_key: &str) -> Result<Vec<u8>,
         .map(|b| b.to_vec())
         .map_err(|e| format!("Read error: {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn album(year: Option<i32>) -> Album {
+        Album {
+            id: String::new(),
+            title: String::new(),
+            year,
+            cover_url: None,
+            is_compilation: false,
+            date_added: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn decades_are_counted_from_album_years() {
+        let albums = [
+            album(Some(2003)),
+            album(Some(1994)),
+            album(None),
+            album(Some(1999)),
+            album(Some(1990)),
+        ];
+
+        assert_eq!(
+            decades_of(&albums),
+            vec![
+                BrowseDecade {
+                    decade: 1990,
+                    album_count: 3
+                },
+                BrowseDecade {
+                    decade: 2000,
+                    album_count: 1
+                },
+            ]
+        );
+    }
+}
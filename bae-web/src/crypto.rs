@@ -4,14 +4,23 @@ use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305};
 const NONCE_SIZE: usize = 24;
 const TAG_SIZE: usize = 16;
 const CHUNK_SIZE: usize = 65536;
-const ENCRYPTED_CHUNK_SIZE: usize = CHUNK_SIZE + TAG_SIZE;
+const MAX_CHUNK_SIZE: usize = 1 << 24;
+const HEADER_SIZE: usize = 8;
+const CONTAINER_MAGIC: &[u8; 6] = b"baeenc";
+const CONTAINER_VERSION: u8 = 2;
 
 /// Decrypt data encrypted with bae-core's chunked XChaCha20-Poly1305 format.
 ///
-/// Format: [24-byte base_nonce][encrypted_chunk_0][encrypted_chunk_1]...
-/// Each chunk: up to 65536 bytes plaintext + 16-byte auth tag.
+/// Format: [header][24-byte base_nonce][encrypted_chunk_0][encrypted_chunk_1]...
+/// The header is absent in legacy files, which always use 65536-byte chunks.
+/// Otherwise it is "baeenc", a version byte, and log2 of the chunk size.
+/// Each chunk: up to chunk size bytes plaintext + 16-byte auth tag.
 /// Chunk nonce: base_nonce XOR chunk_index (little-endian).
 pub fn decrypt(key: &[u8; 32], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    let (header_len, chunk_size) = parse_header(ciphertext)?;
+    let ciphertext = &ciphertext[header_len..];
+    let encrypted_chunk_size = chunk_size + TAG_SIZE;
+
     if ciphertext.len() < NONCE_SIZE {
         return Err("ciphertext too short for nonce".to_string());
     }
@@ -25,18 +34,18 @@ pub fn decrypt(key: &[u8; 32], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
     let data = &ciphertext[NONCE_SIZE..];
     let total_len = data.len();
 
-    let num_full_chunks = total_len / ENCRYPTED_CHUNK_SIZE;
-    let remainder = total_len % ENCRYPTED_CHUNK_SIZE;
+    let num_full_chunks = total_len / encrypted_chunk_size;
+    let remainder = total_len % encrypted_chunk_size;
     let total_chunks = num_full_chunks + if remainder > 0 { 1 } else { 0 };
 
     let mut plaintext = Vec::new();
 
     for i in 0..total_chunks {
-        let chunk_start = i * ENCRYPTED_CHUNK_SIZE;
+        let chunk_start = i * encrypted_chunk_size;
         let chunk_end = if i == total_chunks - 1 && remainder > 0 {
             chunk_start + remainder
         } else {
-            chunk_start + ENCRYPTED_CHUNK_SIZE
+            chunk_start + encrypted_chunk_size
         };
 
         let chunk_data = &data[chunk_start..chunk_end];
@@ -53,6 +62,21 @@ pub fn decrypt(key: &[u8; 32], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
     Ok(plaintext)
 }
 
+/// Read the optional container header: returns (header length, chunk size).
+fn parse_header(data: &[u8]) -> Result<(usize, usize), String> {
+    if data.len() < HEADER_SIZE || !data.starts_with(CONTAINER_MAGIC) {
+        return Ok((0, CHUNK_SIZE));
+    }
+    if data[6] != CONTAINER_VERSION {
+        return Err(format!("unsupported container version {}", data[6]));
+    }
+    let chunk_size = 1usize
+        .checked_shl(data[7] as u32)
+        .filter(|size| (CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(size))
+        .ok_or_else(|| format!("invalid chunk size 2^{}", data[7]))?;
+    Ok((HEADER_SIZE, chunk_size))
+}
+
 /// Derive chunk nonce: base_nonce XOR chunk_index (little-endian).
 fn chunk_nonce(base_nonce: &[u8; NONCE_SIZE], chunk_index: u64) -> [u8; NONCE_SIZE] {
     let mut nonce = *base_nonce;
@@ -71,9 +95,21 @@ mod tests {
 
     /// Encrypt using the same chunked format as bae-core.
     fn encrypt_chunked(key: &[u8; 32], plaintext: &[u8]) -> Vec<u8> {
+        encrypt_with_chunk_size(key, plaintext, CHUNK_SIZE)
+    }
+
+    /// Encrypt like bae-core does with a configured chunk size, adding the
+    /// container header for non-default sizes.
+    fn encrypt_with_chunk_size(key: &[u8; 32], plaintext: &[u8], chunk_size: usize) -> Vec<u8> {
         let cipher = XChaCha20Poly1305::new(GenericArray::from_slice(key));
         let base_nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
-        let mut output = base_nonce.to_vec();
+        let mut output = Vec::new();
+        if chunk_size != CHUNK_SIZE {
+            output.extend(CONTAINER_MAGIC);
+            output.push(CONTAINER_VERSION);
+            output.push(chunk_size.trailing_zeros() as u8);
+        }
+        output.extend(base_nonce);
 
         if plaintext.is_empty() {
             let nonce = chunk_nonce(base_nonce.as_slice().try_into().unwrap(), 0);
@@ -83,7 +119,7 @@ mod tests {
             return output;
         }
 
-        for (i, chunk) in plaintext.chunks(CHUNK_SIZE).enumerate() {
+        for (i, chunk) in plaintext.chunks(chunk_size).enumerate() {
             let nonce = chunk_nonce(base_nonce.as_slice().try_into().unwrap(), i as u64);
             let nonce_arr = GenericArray::from_slice(&nonce);
             let ct = cipher.encrypt(nonce_arr, chunk).unwrap();
@@ -153,4 +189,23 @@ mod tests {
         let key = [0x42u8; 32];
         assert!(decrypt(&key, &[0u8; 10]).is_err());
     }
+
+    #[test]
+    fn decrypt_large_chunks_with_header() {
+        let key = [0x42u8; 32];
+        let chunk_size = CHUNK_SIZE * 16;
+        let plaintext: Vec<u8> = (0..chunk_size + 100).map(|i| (i % 251) as u8).collect();
+        let ciphertext = encrypt_with_chunk_size(&key, &plaintext, chunk_size);
+        assert_eq!(&ciphertext[..6], CONTAINER_MAGIC);
+        let decrypted = decrypt(&key, &ciphertext).unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn decrypt_unknown_version_fails() {
+        let key = [0x42u8; 32];
+        let mut ciphertext = encrypt_with_chunk_size(&key, b"secret", CHUNK_SIZE * 2);
+        ciphertext[6] = CONTAINER_VERSION + 1;
+        assert!(decrypt(&key, &ciphertext).is_err());
+    }
 }
@@ -4,7 +4,10 @@ pub mod pages;
 pub mod playback;
 
 use dioxus::prelude::*;
-use pages::{AlbumDetail, AppLayout, Library, ShareView};
+use pages::{
+    Admin, AlbumDetail, AppLayout, Browse, DecadeAlbums, GenreAlbums, Library, Login, Playlists,
+    Settings, ShareView,
+};
 
 pub const FAVICON: Asset = asset!("/assets/favicon.ico");
 pub const MAIN_CSS: Asset = asset!("/assets/main.css");
@@ -20,6 +23,21 @@ pub enum Route {
         Library {},
         #[route("/album/:album_id")]
         AlbumDetail { album_id: String },
+        #[route("/browse")]
+        Browse {},
+        #[route("/browse/genre?:genre")]
+        GenreAlbums { genre: String },
+        #[route("/browse/decade/:decade")]
+        DecadeAlbums { decade: i32 },
+        #[route("/playlists")]
+        Playlists {},
+        #[route("/settings")]
+        Settings {},
+        #[route("/admin")]
+        Admin {},
+    #[end_layout]
+    #[route("/login")]
+    Login {},
     // ShareView is outside AppLayout — standalone page with no nav/sidebar/queue
     #[route("/share/:token")]
     ShareView { token: String },
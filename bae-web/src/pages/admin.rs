@@ -0,0 +1,350 @@
+use crate::api;
+use bae_ui::components::utils::format_relative_time;
+use bae_ui::{
+    format_file_size, Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection, TextInput,
+    TextInputSize, TextInputType,
+};
+use dioxus::prelude::*;
+
+/// Server administration: health, the users Subsonic apps sign in as, and the
+/// devices the server syncs with
+#[component]
+pub fn Admin() -> Element {
+    rsx! {
+        div { class: "p-6 overflow-y-auto h-full",
+            h1 { class: "text-2xl font-bold text-white mb-6", "Server" }
+            SettingsSection {
+                HealthCard {}
+                UsersCard {}
+                DevicesCard {}
+            }
+        }
+    }
+}
+
+#[component]
+fn HealthCard() -> Element {
+    let mut health = use_resource(api::fetch_health);
+    let mut is_syncing = use_signal(|| false);
+    let mut is_reloading = use_signal(|| false);
+    let mut action_error = use_signal(|| Option::<String>::None);
+
+    let sync_now = move |_| {
+        is_syncing.set(true);
+        action_error.set(None);
+        spawn(async move {
+            if let Err(failure) = api::force_sync().await {
+                action_error.set(Some(failure.message));
+            }
+            is_syncing.set(false);
+            health.restart();
+        });
+    };
+
+    let mut toggle_maintenance = move |enabled: bool| {
+        action_error.set(None);
+        spawn(async move {
+            if let Err(failure) = api::set_maintenance(enabled).await {
+                action_error.set(Some(failure.message));
+            }
+            health.restart();
+        });
+    };
+
+    let reload = move |_| {
+        is_reloading.set(true);
+        action_error.set(None);
+        spawn(async move {
+            if let Err(failure) = api::reload_settings().await {
+                action_error.set(Some(failure.message));
+            }
+            is_reloading.set(false);
+        });
+    };
+
+    let content = match &*health.read() {
+        None => rsx! {
+            div { class: "text-gray-400", "Loading..." }
+        },
+        Some(Err(failure)) => rsx! {
+            div { class: "text-red-400", "{failure.message}" }
+        },
+        Some(Ok(h)) => {
+            let last_sync = match (&h.last_sync, h.sync_enabled) {
+                (_, false) => "Off".to_string(),
+                (Some(t), true) => format_relative_time(t),
+                (None, true) => "Not since startup".to_string(),
+            };
+            let maintenance = if h.maintenance {
+                "On, sync and cloud writes paused"
+            } else {
+                "Off"
+            };
+            rsx! {
+                dl { class: "grid grid-cols-2 gap-x-6 gap-y-2 text-sm",
+                    HealthRow { label: "Version", value: h.version.clone() }
+                    HealthRow { label: "Uptime", value: format_uptime(h.uptime_secs) }
+                    HealthRow { label: "Albums", value: h.albums.to_string() }
+                    HealthRow { label: "Stored locally", value: format_file_size(h.local_bytes as i64) }
+                    HealthRow { label: "Stored in the cloud", value: format_file_size(h.cloud_bytes as i64) }
+                    HealthRow { label: "Last sync", value: last_sync }
+                    HealthRow { label: "Maintenance mode", value: maintenance.to_string() }
+                    HealthRow { label: "Failed syncs", value: h.sync_failures.to_string() }
+                    HealthRow { label: "S3 errors", value: h.s3_errors.to_string() }
+                }
+                div { class: "flex gap-2 mt-4",
+                    if h.sync_enabled {
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            size: ButtonSize::Medium,
+                            disabled: *is_syncing.read() || h.maintenance,
+                            loading: *is_syncing.read(),
+                            onclick: sync_now,
+                            "Sync now"
+                        }
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Medium,
+                        onclick: {
+                            let enabled = !h.maintenance;
+                            move |_| toggle_maintenance(enabled)
+                        },
+                        if h.maintenance {
+                            "Leave maintenance mode"
+                        } else {
+                            "Enter maintenance mode"
+                        }
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Medium,
+                        disabled: *is_reloading.read(),
+                        loading: *is_reloading.read(),
+                        onclick: reload,
+                        "Reload settings"
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        SettingsCard {
+            h3 { class: "text-lg font-medium text-white mb-4", "Health" }
+            {content}
+            if let Some(ref error) = *action_error.read() {
+                p { class: "mt-3 text-sm text-red-400", "{error}" }
+            }
+        }
+    }
+}
+
+#[component]
+fn HealthRow(label: &'static str, value: String) -> Element {
+    rsx! {
+        dt { class: "text-gray-400", "{label}" }
+        dd { class: "text-white", "{value}" }
+    }
+}
+
+#[component]
+fn UsersCard() -> Element {
+    let mut users = use_resource(api::fetch_users);
+    let mut name = use_signal(String::new);
+    let mut created = use_signal(|| Option::<api::CreatedUser>::None);
+    let mut is_busy = use_signal(|| false);
+    let mut error = use_signal(|| Option::<String>::None);
+
+    let create = move |_| {
+        let value = name.read().trim().to_string();
+        is_busy.set(true);
+        error.set(None);
+        spawn(async move {
+            match api::create_user(&value).await {
+                Ok(user) => {
+                    created.set(Some(user));
+                    name.set(String::new());
+                    users.restart();
+                }
+                Err(failure) => error.set(Some(failure.message)),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let mut set_disabled = move |id: String, disabled: bool| {
+        error.set(None);
+        spawn(async move {
+            match api::set_user_disabled(&id, disabled).await {
+                Ok(()) => users.restart(),
+                Err(failure) => error.set(Some(failure.message)),
+            }
+        });
+    };
+
+    let mut delete = move |id: String| {
+        error.set(None);
+        spawn(async move {
+            match api::delete_user(&id).await {
+                Ok(()) => users.restart(),
+                Err(failure) => error.set(Some(failure.message)),
+            }
+        });
+    };
+
+    let busy = *is_busy.read();
+    let has_name = !name.read().trim().is_empty();
+
+    let list = match &*users.read() {
+        None => rsx! {
+            div { class: "text-gray-400", "Loading..." }
+        },
+        Some(Err(failure)) => rsx! {
+            div { class: "text-red-400", "{failure.message}" }
+        },
+        Some(Ok(list)) if list.is_empty() => rsx! {
+            p { class: "text-sm text-gray-500", "No users yet." }
+        },
+        Some(Ok(list)) => rsx! {
+            div { class: "divide-y divide-gray-700",
+                for user in list.iter().cloned() {
+                    div { key: "{user.id}", class: "flex items-center justify-between py-3",
+                        div {
+                            div { class: "text-white", "{user.name}" }
+                            div { class: "text-xs text-gray-500",
+                                "Created {format_relative_time(&user.created_at)}"
+                                if let Some(ref used) = user.last_used_at {
+                                    " · Last used {format_relative_time(used)}"
+                                }
+                                if user.disabled {
+                                    " · Disabled"
+                                }
+                            }
+                        }
+                        div { class: "flex gap-2",
+                            Button {
+                                variant: ButtonVariant::Secondary,
+                                size: ButtonSize::Small,
+                                onclick: {
+                                    let id = user.id.clone();
+                                    let disabled = user.disabled;
+                                    move |_| set_disabled(id.clone(), !disabled)
+                                },
+                                if user.disabled {
+                                    "Enable"
+                                } else {
+                                    "Disable"
+                                }
+                            }
+                            Button {
+                                variant: ButtonVariant::Danger,
+                                size: ButtonSize::Small,
+                                onclick: {
+                                    let id = user.id.clone();
+                                    move |_| delete(id.clone())
+                                },
+                                "Delete"
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        SettingsCard {
+            h3 { class: "text-lg font-medium text-white mb-2", "Users" }
+            p { class: "text-sm text-gray-400 mb-4",
+                "Each user gets its own password to sign in to Subsonic apps with. Disable one to lock it out without losing it."
+            }
+            {list}
+
+            if let Some(ref user) = *created.read() {
+                div { class: "mt-4 p-3 rounded-lg bg-gray-900",
+                    p { class: "text-sm text-gray-400 mb-1",
+                        "Password for {user.name}. It won't be shown again."
+                    }
+                    span { class: "font-mono text-white select-all", "{user.password}" }
+                }
+            }
+
+            div { class: "flex items-center gap-3 mt-4",
+                div { class: "w-56",
+                    TextInput {
+                        value: name.read().clone(),
+                        on_input: move |val| name.set(val),
+                        size: TextInputSize::Medium,
+                        input_type: TextInputType::Text,
+                        placeholder: "Name, e.g. Phone",
+                    }
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Medium,
+                    disabled: busy || !has_name,
+                    loading: busy,
+                    onclick: create,
+                    "Add user"
+                }
+            }
+
+            if let Some(ref error) = *error.read() {
+                p { class: "mt-3 text-sm text-red-400", "{error}" }
+            }
+        }
+    }
+}
+
+#[component]
+fn DevicesCard() -> Element {
+    let devices = use_resource(api::fetch_devices);
+
+    let content = match &*devices.read() {
+        None => rsx! {
+            div { class: "text-gray-400", "Loading..." }
+        },
+        Some(Err(failure)) => rsx! {
+            div { class: "text-red-400", "{failure.message}" }
+        },
+        Some(Ok(list)) if list.is_empty() => rsx! {
+            p { class: "text-sm text-gray-500", "Sync isn't set up on this server." }
+        },
+        Some(Ok(list)) => rsx! {
+            div { class: "divide-y divide-gray-700",
+                for device in list.iter().cloned() {
+                    div {
+                        key: "{device.device_id}",
+                        class: "flex items-center justify-between py-3 text-sm",
+                        span { class: "font-mono text-gray-300",
+                            "{device.device_id}"
+                            if device.this_device {
+                                span { class: "ml-2 font-sans text-gray-500", "(this server)" }
+                            }
+                        }
+                        span { class: "text-gray-400", "Changeset {device.last_seq}" }
+                    }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        SettingsCard {
+            h3 { class: "text-lg font-medium text-white mb-4", "Devices" }
+            {content}
+        }
+    }
+}
+
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
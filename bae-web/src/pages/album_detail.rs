@@ -97,6 +97,12 @@ pub fn AlbumDetail(album_id: String) -> Element {
                     tracks,
                     playback: playback_display(),
                     on_release_select: |_| {},
+                    on_version_select: move |album_id: String| {
+                        navigator().push(Route::AlbumDetail { album_id });
+                    },
+                    on_link_version: |_| {},
+                    on_unlink_version: |_| {},
+                    on_set_version_label: |_| {},
                     on_album_deleted: |_| {},
                     on_export_release: |_| {},
                     on_delete_album: |_| {},
@@ -125,6 +131,7 @@ pub fn AlbumDetail(album_id: String) -> Element {
                             service.write().add_to_queue_with_info(vec![info]);
                         }
                     },
+                    on_track_add_to_playlist: |_| {},
                     on_track_export: |_| {},
                     on_artist_click: |_| {},
                     on_play_album: move |track_ids: Vec<String>| {
@@ -138,9 +145,21 @@ pub fn AlbumDetail(album_id: String) -> Element {
                         service.write().add_to_queue_with_info(infos);
                     },
                     on_transfer_to_managed: |_| {},
+                    on_move_to_cloud: |_| {},
                     on_eject: |_| {},
+                    on_cancel_transfer: |_| {},
+                    on_keep_downloaded: |_| {},
+                    on_fetch_availability: |_| {},
                     on_fetch_remote_covers: |_| {},
                     on_select_cover: |_| {},
+                    on_fetch_track_mapping: |_| {},
+                    on_save_track_mapping: |_| {},
+                    on_save_tags: |_| {},
+                    on_save_notes: |_| {},
+                    on_add_comment: |_| {},
+                    on_remove_comment: |_| {},
+                    on_fetch_musicbrainz_changes: |_| {},
+                    on_apply_musicbrainz_changes: |_| {},
                     on_copy_share_link: |_| {},
                 }
             }
@@ -0,0 +1,128 @@
+use super::library::build_track_infos_from_detail;
+use crate::api;
+use crate::playback::WebPlaybackService;
+use crate::Route;
+use bae_ui::stores::{BrowseState, BrowseStateStoreExt};
+use bae_ui::{decade_label, BrowseAlbumsView, BrowseView};
+use dioxus::prelude::*;
+
+#[component]
+pub fn Browse() -> Element {
+    let state = use_store(|| BrowseState {
+        loading: true,
+        ..BrowseState::default()
+    });
+
+    use_effect(move || {
+        spawn(async move {
+            let result = async {
+                let genres = api::fetch_genres().await?;
+                let (albums, _, _) = api::fetch_albums().await?;
+                Ok::<_, String>((genres, api::decades_of(&albums)))
+            }
+            .await;
+            match result {
+                Ok((genres, decades)) => {
+                    state.genres().set(genres);
+                    state.decades().set(decades);
+                }
+                Err(e) => state.error().set(Some(e)),
+            }
+            state.loading().set(false);
+        });
+    });
+
+    rsx! {
+        BrowseView {
+            state,
+            on_genre_click: move |genre: String| {
+                navigator().push(Route::GenreAlbums { genre });
+            },
+            on_decade_click: move |decade: i32| {
+                navigator().push(Route::DecadeAlbums { decade });
+            },
+        }
+    }
+}
+
+#[component]
+pub fn GenreAlbums(genre: String) -> Element {
+    let state = use_store(|| BrowseState {
+        loading: true,
+        ..BrowseState::default()
+    });
+    let title = genre.clone();
+
+    use_effect(move || {
+        let genre = genre.clone();
+        spawn(async move {
+            set_albums(state, api::fetch_albums_by_genre(&genre).await);
+        });
+    });
+
+    rsx! {
+        BrowseAlbums { state, title }
+    }
+}
+
+#[component]
+pub fn DecadeAlbums(decade: i32) -> Element {
+    let state = use_store(|| BrowseState {
+        loading: true,
+        ..BrowseState::default()
+    });
+
+    use_effect(move || {
+        spawn(async move {
+            set_albums(state, api::fetch_albums_by_decade(decade).await);
+        });
+    });
+
+    rsx! {
+        BrowseAlbums { state, title: decade_label(decade) }
+    }
+}
+
+fn set_albums(state: Store<BrowseState>, result: Result<api::AlbumList, String>) {
+    match result {
+        Ok((albums, artists_by_album, cover_placeholders)) => {
+            state.albums().set(albums);
+            state.artists_by_album().set(artists_by_album);
+            state.cover_placeholders().set(cover_placeholders);
+        }
+        Err(e) => state.error().set(Some(e)),
+    }
+    state.loading().set(false);
+}
+
+#[component]
+fn BrowseAlbums(state: Store<BrowseState>, title: String) -> Element {
+    let mut service: Signal<WebPlaybackService> = use_context();
+
+    rsx! {
+        BrowseAlbumsView {
+            state,
+            title,
+            on_album_click: move |album_id: String| {
+                navigator().push(Route::AlbumDetail { album_id });
+            },
+            on_artist_click: |_| {},
+            on_play_album: move |album_id: String| {
+                spawn(async move {
+                    if let Ok(detail) = api::fetch_album(&album_id).await {
+                        let infos = build_track_infos_from_detail(&detail);
+                        service.write().play_album(infos);
+                    }
+                });
+            },
+            on_add_album_to_queue: move |album_id: String| {
+                spawn(async move {
+                    if let Ok(detail) = api::fetch_album(&album_id).await {
+                        let infos = build_track_infos_from_detail(&detail);
+                        service.write().add_to_queue_with_info(infos);
+                    }
+                });
+            },
+        }
+    }
+}
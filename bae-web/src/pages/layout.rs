@@ -1,3 +1,4 @@
+use crate::api;
 use crate::playback::WebPlaybackService;
 use crate::Route;
 use bae_ui::stores::playback::PlaybackUiState;
@@ -13,6 +14,16 @@ pub fn AppLayout() -> Element {
     let current_route = use_route::<Route>();
     let mut search_query = use_signal(String::new);
 
+    // Servers with sign-in enabled need a session before the API answers
+    let session = use_resource(api::fetch_session);
+    use_effect(move || {
+        if let Some(Ok(status)) = &*session.read() {
+            if !status.authenticated {
+                navigator().replace(Route::Login {});
+            }
+        }
+    });
+
     let playback_store = use_context_provider(|| {
         use_store(|| PlaybackUiState {
             volume: 1.0,
@@ -22,11 +33,26 @@ pub fn AppLayout() -> Element {
     let sidebar_store = use_store(SidebarState::default);
     let mut service = use_context_provider(|| Signal::new(WebPlaybackService::new(playback_store)));
 
-    let nav_items = vec![NavItem {
-        id: "library".to_string(),
-        label: "Library".to_string(),
-        is_active: matches!(current_route, Route::Library {} | Route::AlbumDetail { .. }),
-    }];
+    let nav_items = vec![
+        NavItem {
+            id: "library".to_string(),
+            label: "Library".to_string(),
+            is_active: matches!(current_route, Route::Library {} | Route::AlbumDetail { .. }),
+        },
+        NavItem {
+            id: "browse".to_string(),
+            label: "Browse".to_string(),
+            is_active: matches!(
+                current_route,
+                Route::Browse {} | Route::GenreAlbums { .. } | Route::DecadeAlbums { .. }
+            ),
+        },
+        NavItem {
+            id: "playlists".to_string(),
+            label: "Playlists".to_string(),
+            is_active: matches!(current_route, Route::Playlists {}),
+        },
+    ];
 
     rsx! {
         // Hidden audio element — persists across route changes
@@ -53,8 +79,17 @@ pub fn AppLayout() -> Element {
                 TitleBarView {
                     nav_items,
                     on_nav_click: move |id: String| {
-                        if id == "library" {
-                            navigator().push(Route::Library {});
+                        match id.as_str() {
+                            "library" => {
+                                navigator().push(Route::Library {});
+                            }
+                            "browse" => {
+                                navigator().push(Route::Browse {});
+                            }
+                            "playlists" => {
+                                navigator().push(Route::Playlists {});
+                            }
+                            _ => {}
                         }
                     },
                     search_value: search_query(),
@@ -65,13 +100,16 @@ pub fn AppLayout() -> Element {
                     on_search_result_click: |_| {},
                     on_search_focus: |_| {},
                     on_search_blur: |_| {},
-                    on_settings_click: |_| {},
+                    on_settings_click: move |_| {
+                        navigator().push(Route::Settings {});
+                    },
                     left_padding: 16,
                 }
             },
             playback_bar: rsx! {
                 NowPlayingBarView {
                     state: playback_store,
+                    lyrics_available: false,
                     on_previous: move |_| service.write().previous(),
                     on_pause: move |_| service.write().pause(),
                     on_resume: move |_| service.write().resume(),
@@ -84,6 +122,7 @@ pub fn AppLayout() -> Element {
                         let current = *sidebar_store.is_open().read();
                         sidebar_store.is_open().set(!current);
                     },
+                    on_toggle_lyrics: |_| {},
                     on_track_click: |_| {},
                     on_artist_click: |_| {},
                     on_dismiss_error: move |_| service.write().dismiss_error(),
@@ -93,6 +132,8 @@ pub fn AppLayout() -> Element {
                 QueueSidebarView {
                     sidebar: sidebar_store,
                     playback: playback_store,
+                    radio_available: false,
+                    history_available: false,
                     on_close: move |_| sidebar_store.is_open().set(false),
                     on_clear: move |_| service.write().clear_queue(),
                     on_remove: move |idx: usize| service.write().remove_from_queue(idx),
@@ -100,6 +141,10 @@ pub fn AppLayout() -> Element {
                     on_play_index: move |idx: usize| service.write().skip_to(idx),
                     on_pause: move |_| service.write().pause(),
                     on_resume: move |_| service.write().resume(),
+                    on_radio_change: |_| {},
+                    on_show_history: |_| {},
+                    on_history_play: |_| {},
+                    on_history_add_to_queue: |_| {},
                 }
             },
             Outlet::<Route> {}
@@ -1,11 +1,18 @@
 use crate::api;
 use crate::playback::{TrackInfo, WebPlaybackService};
 use crate::Route;
-use bae_ui::stores::{AlbumDetailState, LibrarySortState, LibrarySortStateStoreExt, LibraryState};
+use bae_ui::stores::{
+    AlbumDetailState, LibrarySortState, LibrarySortStateStoreExt, LibraryState,
+    LibraryStateStoreExt,
+};
 use bae_ui::LibraryView;
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
-fn build_track_infos_from_detail(detail: &AlbumDetailState) -> Vec<TrackInfo> {
+/// Albums in each of the "Recently Played" and "Recently Added" shelves
+const RECENT_ALBUMS_LEN: usize = 12;
+
+pub(super) fn build_track_infos_from_detail(detail: &AlbumDetailState) -> Vec<TrackInfo> {
     let album = match detail.album.as_ref() {
         Some(a) => a,
         None => return vec![],
@@ -34,7 +41,7 @@ pub fn Library() -> Element {
     let read = data.read();
 
     let result = match &*read {
-        Some(Ok((albums, artists_by_album))) => Ok((albums.clone(), artists_by_album.clone())),
+        Some(Ok(album_list)) => Ok(album_list.clone()),
         Some(Err(e)) => Err(e.clone()),
         None => {
             return rsx! {
@@ -47,15 +54,36 @@ pub fn Library() -> Element {
     drop(read);
 
     match result {
-        Ok((albums, artists_by_album)) => {
+        Ok((albums, artists_by_album, cover_placeholders)) => {
             let state = use_store(move || LibraryState {
                 albums,
                 artists_by_album,
+                composers_by_album: HashMap::new(),
+                version_counts: HashMap::new(),
+                cover_placeholders,
+                recently_added: vec![],
+                recently_played: vec![],
+                filter_genres: vec![],
+                filter_decades: vec![],
+                collections: vec![],
+                collection_ids_by_album: HashMap::new(),
+                cover_upgrades: HashMap::new(),
                 loading: false,
                 error: None,
                 active_source: Default::default(),
             });
 
+            use_effect(move || {
+                spawn(async move {
+                    if let Ok(ids) = api::fetch_album_ids("recent", RECENT_ALBUMS_LEN).await {
+                        state.recently_played().set(ids);
+                    }
+                    if let Ok(ids) = api::fetch_album_ids("newest", RECENT_ALBUMS_LEN).await {
+                        state.recently_added().set(ids);
+                    }
+                });
+            });
+
             let sort_state = use_store(LibrarySortState::default);
             let mut service: Signal<WebPlaybackService> = use_context();
 
@@ -69,6 +97,9 @@ pub fn Library() -> Element {
                     on_view_mode_change: move |mode| {
                         sort_state.view_mode().set(mode);
                     },
+                    on_filter_change: move |filter| {
+                        sort_state.filter().set(filter);
+                    },
                     on_album_click: move |album_id: String| {
                         navigator().push(Route::AlbumDetail { album_id });
                     },
@@ -89,7 +120,12 @@ pub fn Library() -> Element {
                             }
                         });
                     },
+                    on_toggle_collection: |_| {},
+                    on_create_collection: |_| {},
+                    on_delete_collection: |_| {},
                     on_empty_action: |_| {},
+                    // Collections aren't served over Subsonic, and there's no import on web
+                    read_only: true,
                 }
             }
         }
@@ -0,0 +1,106 @@
+use crate::api;
+use crate::Route;
+use bae_ui::{Button, ButtonSize, ButtonVariant, TextInput, TextInputSize, TextInputType};
+use dioxus::prelude::*;
+
+/// Sign-in page, shown when the server requires authentication
+#[component]
+pub fn Login() -> Element {
+    let mut username = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut code = use_signal(String::new);
+    let mut needs_code = use_signal(|| false);
+    let mut is_signing_in = use_signal(|| false);
+    let mut error = use_signal(|| Option::<String>::None);
+
+    let mut sign_in = move || {
+        is_signing_in.set(true);
+        error.set(None);
+        spawn(async move {
+            let user = username.read().clone();
+            let pass = password.read().clone();
+            let code_value = code.read().clone();
+            let code_param = (*needs_code.read()).then_some(code_value.as_str());
+            match api::login(&user, &pass, code_param).await {
+                Ok(_) => {
+                    navigator().replace(Route::Library {});
+                }
+                Err(failure) => {
+                    // The first code prompt isn't an error, just the second step
+                    if failure.totp_required && !*needs_code.read() {
+                        needs_code.set(true);
+                    } else {
+                        error.set(Some(failure.message));
+                    }
+                    code.set(String::new());
+                }
+            }
+            is_signing_in.set(false);
+        });
+    };
+
+    let can_submit = !username.read().is_empty()
+        && !password.read().is_empty()
+        && (!*needs_code.read() || !code.read().trim().is_empty());
+
+    rsx! {
+        div { class: "min-h-screen bg-[var(--color-surface-base)] flex items-center justify-center p-4",
+            form {
+                class: "bg-[var(--color-surface-raised)] rounded-xl shadow-2xl max-w-sm w-full p-6 space-y-4",
+                onsubmit: move |e| {
+                    e.prevent_default();
+                    if can_submit {
+                        sign_in();
+                    }
+                },
+                h1 { class: "text-xl font-semibold text-white", "Sign in to bae" }
+                if *needs_code.read() {
+                    p { class: "text-sm text-gray-400",
+                        "Enter the 6-digit code from your authenticator app."
+                    }
+                    TextInput {
+                        value: code.read().clone(),
+                        on_input: move |val| code.set(val),
+                        size: TextInputSize::Medium,
+                        input_type: TextInputType::Text,
+                        placeholder: "123456",
+                        monospace: true,
+                        autofocus: true,
+                    }
+                } else {
+                    TextInput {
+                        value: username.read().clone(),
+                        on_input: move |val| username.set(val),
+                        size: TextInputSize::Medium,
+                        input_type: TextInputType::Text,
+                        placeholder: "Username",
+                        autofocus: true,
+                    }
+                    TextInput {
+                        value: password.read().clone(),
+                        on_input: move |val| password.set(val),
+                        size: TextInputSize::Medium,
+                        input_type: TextInputType::Password,
+                        placeholder: "Password",
+                    }
+                }
+                if let Some(ref error) = *error.read() {
+                    p { class: "text-sm text-red-400", "{error}" }
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Medium,
+                    disabled: !can_submit || *is_signing_in.read(),
+                    loading: *is_signing_in.read(),
+                    // Submits the form, which handles the click
+                    onclick: |_| {},
+                    if *needs_code.read() {
+                        "Verify"
+                    } else {
+                        "Sign in"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,19 @@
+mod admin;
 mod album_detail;
+mod browse;
 mod layout;
 mod library;
+mod login;
+mod playlists;
+mod settings;
 mod share;
 
+pub use admin::Admin;
 pub use album_detail::AlbumDetail;
+pub use browse::{Browse, DecadeAlbums, GenreAlbums};
 pub use layout::AppLayout;
 pub use library::Library;
+pub use login::Login;
+pub use playlists::Playlists;
+pub use settings::Settings;
 pub use share::ShareView;
@@ -0,0 +1,70 @@
+use crate::api;
+use crate::playback::{TrackInfo, WebPlaybackService};
+use crate::Route;
+use bae_ui::stores::{PlaylistsState, PlaylistsStateStoreExt};
+use bae_ui::PlaylistsView;
+use dioxus::prelude::*;
+
+#[component]
+pub fn Playlists() -> Element {
+    let state = use_store(|| PlaylistsState {
+        loading: true,
+        ..PlaylistsState::default()
+    });
+    let mut service: Signal<WebPlaybackService> = use_context();
+
+    use_effect(move || {
+        spawn(async move {
+            match api::fetch_playlists().await {
+                Ok(playlists) => state.playlists().set(playlists),
+                Err(e) => state.error().set(Some(e)),
+            }
+            state.loading().set(false);
+        });
+    });
+
+    // Playlists are edited in the desktop app; the web UI only plays them
+    rsx! {
+        PlaylistsView {
+            state,
+            editable: false,
+            on_select: move |playlist_id: String| {
+                state.selected_id().set(Some(playlist_id.clone()));
+                state.entries().set(Vec::new());
+                spawn(async move {
+                    match api::fetch_playlist(&playlist_id).await {
+                        Ok(entries) => state.entries().set(entries),
+                        Err(e) => state.error().set(Some(e)),
+                    }
+                });
+            },
+            on_create: |_| {},
+            on_rename: |_| {},
+            on_delete: |_| {},
+            on_import_m3u: |_| {},
+            on_export_m3u: |_| {},
+            on_play: move |index: usize| {
+                let infos: Vec<TrackInfo> = state
+                    .entries()
+                    .read()
+                    .iter()
+                    .skip(index)
+                    .map(|e| TrackInfo {
+                        track_id: e.track.id.clone(),
+                        track: e.track.clone(),
+                        album_title: e.album_title.clone(),
+                        cover_url: e.cover_url.clone(),
+                        artist_name: e.artist_name.clone(),
+                        artist_id: None,
+                    })
+                    .collect();
+                service.write().play_album(infos);
+            },
+            on_remove_entry: |_| {},
+            on_move_entry: |_| {},
+            on_album_click: move |album_id: String| {
+                navigator().push(Route::AlbumDetail { album_id });
+            },
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::api;
+use crate::Route;
+use bae_ui::{
+    Button, ButtonSize, ButtonVariant, SettingsCard, SettingsSection, TextInput, TextInputSize,
+    TextInputType,
+};
+use dioxus::prelude::*;
+
+/// Server settings reachable from the web UI: two-factor enrollment, a link to
+/// server administration and sign-out
+#[component]
+pub fn Settings() -> Element {
+    let mut session = use_resource(api::fetch_session);
+
+    let content = match &*session.read() {
+        None => rsx! {
+            div { class: "text-gray-400", "Loading..." }
+        },
+        Some(Err(e)) => rsx! {
+            div { class: "text-red-400", "{e}" }
+        },
+        Some(Ok(status)) if !status.auth_enabled => rsx! {
+            SettingsCard {
+                h3 { class: "text-lg font-medium text-white mb-2", "Two-Factor Authentication" }
+                p { class: "text-sm text-gray-400",
+                    "Sign-in is off for this server. Turn on authentication in the Subsonic settings of the bae app first."
+                }
+            }
+        },
+        Some(Ok(status)) => {
+            let totp_enabled = status.totp_enabled;
+            rsx! {
+                TwoFactorCard { totp_enabled, on_changed: move |_| session.restart() }
+                SettingsCard {
+                    h3 { class: "text-lg font-medium text-white mb-2", "Server" }
+                    p { class: "text-sm text-gray-400 mb-4",
+                        "Check the server's health, manage the users Subsonic apps sign in as and see the devices it syncs with."
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Medium,
+                        onclick: move |_| {
+                            navigator().push(Route::Admin {});
+                        },
+                        "Manage server"
+                    }
+                }
+                SettingsCard {
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        size: ButtonSize::Medium,
+                        onclick: move |_| {
+                            spawn(async move {
+                                if let Err(e) = api::logout().await {
+                                    tracing::warn!("Sign out failed: {e}");
+                                }
+                                navigator().replace(Route::Login {});
+                            });
+                        },
+                        "Sign out"
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        div { class: "p-6 overflow-y-auto h-full",
+            h1 { class: "text-2xl font-bold text-white mb-6", "Settings" }
+            SettingsSection { {content} }
+        }
+    }
+}
+
+#[component]
+fn TwoFactorCard(totp_enabled: bool, on_changed: EventHandler<()>) -> Element {
+    let mut enrollment = use_signal(|| Option::<api::TotpEnrollment>::None);
+    let mut code = use_signal(String::new);
+    let mut is_busy = use_signal(|| false);
+    let mut error = use_signal(|| Option::<String>::None);
+
+    let start_enrollment = move |_| {
+        is_busy.set(true);
+        error.set(None);
+        spawn(async move {
+            match api::enroll_totp().await {
+                Ok(e) => enrollment.set(Some(e)),
+                Err(failure) => error.set(Some(failure.message)),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let submit_code = move |_| {
+        let value = code.read().trim().to_string();
+        is_busy.set(true);
+        error.set(None);
+        spawn(async move {
+            let result = if totp_enabled {
+                api::disable_totp(&value).await
+            } else {
+                api::confirm_totp(&value).await
+            };
+            match result {
+                Ok(()) => {
+                    enrollment.set(None);
+                    code.set(String::new());
+                    on_changed.call(());
+                }
+                Err(failure) => error.set(Some(failure.message)),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let busy = *is_busy.read();
+    let has_code = !code.read().trim().is_empty();
+
+    rsx! {
+        SettingsCard {
+            h3 { class: "text-lg font-medium text-white mb-2", "Two-Factor Authentication" }
+            if totp_enabled {
+                p { class: "text-sm text-gray-400 mb-4",
+                    "On. Signing in asks for a code from your authenticator app. Enter a current code to turn it off."
+                }
+            } else if let Some(ref pending) = *enrollment.read() {
+                p { class: "text-sm text-gray-400 mb-4",
+                    "Scan this code with your authenticator app, then enter the 6-digit code it shows."
+                }
+                div {
+                    class: "w-48 h-48 mb-3 bg-white rounded-lg p-2",
+                    dangerous_inner_html: "{pending.qr_svg}",
+                }
+                p { class: "text-xs text-gray-500 mb-4",
+                    "Or enter this key manually: "
+                    span { class: "font-mono text-gray-300 select-all", "{pending.secret}" }
+                }
+            } else {
+                p { class: "text-sm text-gray-400 mb-4",
+                    "Off. Require a code from an authenticator app in addition to your password when signing in from a browser. Subsonic apps will then need an app password, created in the Subsonic settings of the bae app."
+                }
+            }
+
+            if totp_enabled || enrollment.read().is_some() {
+                div { class: "flex items-center gap-3",
+                    div { class: "w-40",
+                        TextInput {
+                            value: code.read().clone(),
+                            on_input: move |val| code.set(val),
+                            size: TextInputSize::Medium,
+                            input_type: TextInputType::Text,
+                            placeholder: "123456",
+                            monospace: true,
+                        }
+                    }
+                    Button {
+                        variant: if totp_enabled { ButtonVariant::Danger } else { ButtonVariant::Primary },
+                        size: ButtonSize::Medium,
+                        disabled: busy || !has_code,
+                        loading: busy,
+                        onclick: submit_code,
+                        if totp_enabled {
+                            "Turn off"
+                        } else {
+                            "Confirm"
+                        }
+                    }
+                }
+            } else {
+                Button {
+                    variant: ButtonVariant::Primary,
+                    size: ButtonSize::Medium,
+                    disabled: busy,
+                    loading: busy,
+                    onclick: start_enrollment,
+                    "Set up"
+                }
+            }
+
+            if let Some(ref error) = *error.read() {
+                p { class: "mt-3 text-sm text-red-400", "{error}" }
+            }
+        }
+    }
+}
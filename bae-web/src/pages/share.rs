@@ -202,9 +202,15 @@ fn CloudAlbumView(share_id: String, meta: api::CloudShareMeta) -> Element {
         spawn(async move {
             if let Some(key) = cover_key {
                 if let Ok(release_key) = decode_release_key(&rk_b64) {
-                    if let Ok(encrypted) = api::fetch_share_file(&sid, &key).await {
-                        if let Ok(decrypted) = crate::crypto::decrypt(&release_key, &encrypted) {
-                            if let Ok(url) = create_blob_url(&decrypted, "image/jpeg") {
+                    if let Ok(data) = api::fetch_share_file(&sid, &key).await {
+                        // Covers uploaded with image encryption off are stored as is
+                        let cover = if key.ends_with(".plain") {
+                            Ok(data)
+                        } else {
+                            crate::crypto::decrypt(&release_key, &data)
+                        };
+                        if let Ok(cover) = cover {
+                            if let Ok(url) = create_blob_url(&cover, "image/jpeg") {
                                 cover_blob_url.set(Some(url));
                             }
                         }